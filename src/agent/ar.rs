@@ -0,0 +1,219 @@
+//! AR scan review queue for the edge agent.
+//!
+//! The field app converts a scan into pending equipment (see
+//! [`crate::ingest::ar_scan`]) and queues it with `ar.pending.submit`;
+//! reviewers page through `ar.pending.list` and decide with
+//! `ar.pending.approve` (adds the equipment to `building.yaml`) or
+//! `ar.pending.reject`. Records stay under `.arx/ar-pending/` as the review
+//! history. Submissions are first triaged by `.arx/ar-policy.yaml`, which can
+//! accept confident detections outright or flag probable duplicates.
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::core::access::acting_user;
+use crate::core::clock;
+use crate::core::provenance::{ProvenanceGuard, ProvenanceSource};
+use crate::ingest::ar_scan::{
+    place_pending, ArReviewPolicy, PendingEquipment, PendingState, Triage,
+};
+use crate::persistence::ar_pending::{list_pending, load_pending, save_pending};
+use crate::persistence::{load_building_at, save_building_at, BUILDING_YAML};
+
+/// `reviewed_by` on records the policy accepted.
+pub const POLICY_REVIEWER: &str = "ar-policy";
+
+/// Queue scanned items, triaged by the repository's [`ArReviewPolicy`]:
+/// auto-accepted items are added to the building in one save, probable
+/// matches are flagged, and items already reviewed are left as they are.
+pub fn submit(repo_root: &Path, records: Vec<PendingEquipment>) -> Result<Vec<PendingEquipment>> {
+    let policy = ArReviewPolicy::load_at(repo_root).map_err(|e| anyhow!(e))?;
+    let mut building = load_building_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    let mut queued = Vec::new();
+    let mut accepted = 0;
+    for mut record in records {
+        if record.name.trim().is_empty() {
+            bail!("{}: name is required", record.id);
+        }
+        if let Some(existing) = load_pending(repo_root, &record.id)? {
+            if existing.state != PendingState::Pending {
+                continue;
+            }
+        }
+        record.state = PendingState::Pending;
+        record.submitted_at = Some(clock::now());
+        record.reviewed_by = None;
+        record.reviewed_at = None;
+        record.equipment_id = None;
+        record.probable_match = None;
+        match policy.triage(&building, &record) {
+            Triage::AutoAccept => {
+                let id = place_pending(&mut building, &record)
+                    .map_err(|e| anyhow!("{}: {}", record.id, e))?;
+                record.equipment_id = Some(id);
+                record.state = PendingState::Approved;
+                record.reviewed_by = Some(POLICY_REVIEWER.to_string());
+                record.reviewed_at = record.submitted_at;
+                record.note = record
+                    .confidence
+                    .map(|c| format!("auto-accepted at confidence {:.2}", c));
+                accepted += 1;
+            }
+            Triage::ProbableMatch(id) => record.probable_match = Some(id),
+            Triage::Pending => {}
+        }
+        queued.push(record);
+    }
+    if accepted > 0 {
+        let _provenance = ProvenanceGuard::source(ProvenanceSource::ArScan);
+        save_building_at(repo_root, &building)
+            .map_err(|e| anyhow!("Failed to write {}: {}", BUILDING_YAML, e))?;
+    }
+    for record in &queued {
+        save_pending(repo_root, record)?;
+    }
+    let waiting: Vec<String> = queued
+        .iter()
+        .filter(|r| r.state == PendingState::Pending)
+        .map(|r| r.name.clone())
+        .collect();
+    if !waiting.is_empty() {
+        crate::notifications::notify(
+            repo_root,
+            &[crate::notifications::Event::ArPending {
+                count: waiting.len(),
+                items: waiting,
+            }],
+        );
+    }
+    tracing::info!(
+        count = queued.len(),
+        accepted,
+        "AR scan items queued for review"
+    );
+    Ok(queued)
+}
+
+/// Queued items (including reviewed ones when `all`).
+pub fn list(repo_root: &Path, all: bool) -> Result<Vec<PendingEquipment>> {
+    Ok(list_pending(repo_root, all)?)
+}
+
+fn pending(repo_root: &Path, ids: &[String]) -> Result<Vec<PendingEquipment>> {
+    if ids.is_empty() {
+        bail!("No ids given");
+    }
+    ids.iter()
+        .map(|id| {
+            let record = load_pending(repo_root, id)?
+                .ok_or_else(|| anyhow!("No pending equipment '{}'", id))?;
+            if record.state != PendingState::Pending {
+                bail!("'{}' was already reviewed", id);
+            }
+            Ok(record)
+        })
+        .collect()
+}
+
+/// Add the items to the building in one save; nothing is written if any
+/// item cannot be placed.
+pub fn approve(repo_root: &Path, ids: &[String]) -> Result<Vec<PendingEquipment>> {
+    let mut records = pending(repo_root, ids)?;
+    let mut building = load_building_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    for record in &mut records {
+        let id =
+            place_pending(&mut building, record).map_err(|e| anyhow!("{}: {}", record.id, e))?;
+        record.equipment_id = Some(id);
+    }
+    {
+        let _provenance = ProvenanceGuard::source(ProvenanceSource::ArScan);
+        save_building_at(repo_root, &building)
+            .map_err(|e| anyhow!("Failed to write {}: {}", BUILDING_YAML, e))?;
+    }
+    review(repo_root, &mut records, PendingState::Approved, None)?;
+    Ok(records)
+}
+
+/// Mark the items rejected, with an optional reason.
+pub fn reject(
+    repo_root: &Path,
+    ids: &[String],
+    note: Option<String>,
+) -> Result<Vec<PendingEquipment>> {
+    let mut records = pending(repo_root, ids)?;
+    review(repo_root, &mut records, PendingState::Rejected, note)?;
+    Ok(records)
+}
+
+fn review(
+    repo_root: &Path,
+    records: &mut [PendingEquipment],
+    state: PendingState,
+    note: Option<String>,
+) -> Result<()> {
+    let reviewer = acting_user();
+    for record in records {
+        record.state = state;
+        record.reviewed_by = reviewer.clone();
+        record.reviewed_at = Some(clock::now());
+        record.note = note.clone();
+        save_pending(repo_root, record)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Floor};
+    use crate::ingest::ar_scan::{pending_from_scan, ArScan, ScannedEquipment};
+
+    #[test]
+    fn test_submit_approve_and_reject() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut building = Building::new("HQ".into(), String::new());
+        building.add_floor(Floor::new("Ground".into(), 0));
+        save_building_at(dir.path(), &building).unwrap();
+
+        let scan = ArScan {
+            scan_id: "scan-1".into(),
+            floor_level: Some(0),
+            equipment: ["AHU-1", "Pump-1"]
+                .map(|name| ScannedEquipment {
+                    name: name.into(),
+                    ..Default::default()
+                })
+                .to_vec(),
+            ..Default::default()
+        };
+        submit(dir.path(), pending_from_scan(&scan).unwrap()).unwrap();
+        assert_eq!(list(dir.path(), false).unwrap().len(), 2);
+
+        let approved = approve(dir.path(), &["scan-1-1".to_string()]).unwrap();
+        let equipment_id = approved[0].equipment_id.clone().unwrap();
+        let building = load_building_at(dir.path()).unwrap();
+        assert!(building
+            .get_all_equipment()
+            .iter()
+            .any(|e| e.id == equipment_id));
+
+        reject(
+            dir.path(),
+            &["scan-1-2".to_string()],
+            Some("duplicate".into()),
+        )
+        .unwrap();
+        assert!(approve(dir.path(), &["scan-1-2".to_string()]).is_err());
+        assert!(list(dir.path(), false).unwrap().is_empty());
+        let all = list(dir.path(), true).unwrap();
+        assert_eq!(all.len(), 2);
+
+        // Resubmitting the scan does not reopen reviewed items
+        assert!(submit(dir.path(), pending_from_scan(&scan).unwrap())
+            .unwrap()
+            .is_empty());
+    }
+}
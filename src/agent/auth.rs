@@ -1,9 +1,23 @@
 use std::collections::HashSet;
+use std::path::Path;
 
-use anyhow::{anyhow, Result};
-use chrono::{DateTime, Utc};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+use crate::core::clock;
+
+/// Repo-relative store of hashed API keys (`arx agent keys`).
+pub const API_KEYS_FILE: &str = ".arx/agent/api-keys.yaml";
+
+/// Prefix that marks a secret as an API key rather than a DID token.
+pub const API_KEY_PREFIX: &str = "arxk_";
+
+/// Actions open to any credential, even without a mapped capability.
+const OPEN_ACTIONS: &[&str] = &["issue.submit"];
+
 #[derive(Debug, Clone)]
 pub struct TokenState {
     value: String,
@@ -16,7 +30,7 @@ impl TokenState {
         Self {
             value,
             capabilities,
-            last_rotated: Utc::now(),
+            last_rotated: clock::now(),
         }
     }
 
@@ -35,7 +49,7 @@ impl TokenState {
     pub fn rotate(&mut self, new_value: String, capabilities: Vec<String>) {
         self.value = new_value;
         self.capabilities = capabilities;
-        self.last_rotated = Utc::now();
+        self.last_rotated = clock::now();
     }
 
     pub fn update_capabilities(&mut self, capabilities: Vec<String>) {
@@ -47,6 +61,29 @@ pub fn generate_did_key() -> String {
     format!("did:key:z{}", Uuid::new_v4().to_string().replace('-', ""))
 }
 
+/// Every capability the agent grants to its root token.
+pub fn all_capabilities() -> Vec<String> {
+    [
+        "git.status",
+        "git.diff",
+        "git.commit",
+        "files.read",
+        "building.get",
+        "building.edit",
+        "ifc.import",
+        "ifc.export",
+        "collab.sync",
+        "auth.manage",
+        "issue.read",
+        "sensor.write",
+        "ar.review",
+        "command.run",
+    ]
+    .iter()
+    .map(|c| c.to_string())
+    .collect()
+}
+
 pub fn filter_capabilities(default: &[String], requested: &[String]) -> (Vec<String>, Vec<String>) {
     let available: HashSet<&String> = default.iter().collect();
     let mut granted = Vec::new();
@@ -82,19 +119,197 @@ pub fn ensure_capability(action: &str, capabilities: &[String]) -> Result<()> {
 fn action_required_capability(action: &str) -> Option<&'static str> {
     match action {
         "git.status" => Some("git.status"),
-        "git.diff" => Some("git.diff"),
+        "git.diff" | "git.log" => Some("git.diff"),
         "git.commit" => Some("git.commit"),
         "files.read" => Some("files.read"),
-        "building.get" => Some("building.get"),
-        "ifc.import" => Some("ifc.import"),
-        "ifc.export" => Some("ifc.export"),
-        "auth.rotate" | "auth.negotiate" => Some("auth.manage"),
+        "building.get" | "building.list" | "equipment.list" | "room.list" | "twin.snapshot"
+        | "command.catalog" | "ar.pending.list" | "room.get" | "equipment.get"
+        | "spatial.query" | "job.status" | "job.list" => Some("building.get"),
+        "room.create" | "room.update" | "room.delete" | "equipment.create"
+        | "equipment.update" | "equipment.delete" => Some("building.edit"),
+        "ifc.import" | "transfer.upload" | "transfer.chunk" | "transfer.finish"
+        | "ar.pending.submit" | "job.cancel" => Some("ifc.import"),
+        "ar.pending.approve" | "ar.pending.reject" => Some("ar.review"),
+        "transfer.download" | "transfer.fetch" | "transfer.status" => Some("files.read"),
+        "ifc.export" | "export.run" | "export.status" => Some("ifc.export"),
+        "auth.rotate" | "auth.negotiate" | "session.list" => Some("auth.manage"),
         "collab.sync" => Some("collab.sync"),
+        // Any CLI command, mutating ones included
+        "command.run" | "command.status" => Some("command.run"),
         "collab.config.get" | "collab.config.set" => Some("collab.config"),
+        // issue.submit stays open: occupant intake needs no building capabilities
+        "issue.list" => Some("issue.read"),
+        "sensor.report" | "energy.record" => Some("sensor.write"),
         _ => None,
     }
 }
 
+/// Who sent a request: the holder of the agent token or a scoped API key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Caller {
+    Token,
+    ApiKey {
+        id: String,
+        capabilities: Vec<String>,
+    },
+}
+
+/// API keys may only call actions their capabilities name explicitly;
+/// actions without a mapped capability stay with the agent token.
+pub fn ensure_key_capability(action: &str, capabilities: &[String]) -> Result<()> {
+    if OPEN_ACTIONS.contains(&action) {
+        return Ok(());
+    }
+    match action_required_capability(action) {
+        Some(required) if capabilities.iter().any(|cap| cap == required) => Ok(()),
+        Some(required) => Err(anyhow!(
+            "Capability '{}' required for action '{}'",
+            required,
+            action
+        )),
+        None => Err(anyhow!(
+            "Action '{}' requires the agent token, not an API key",
+            action
+        )),
+    }
+}
+
+/// One issued API key; only the SHA-256 of the secret is stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub hash: String,
+    pub capabilities: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// Contents of [`API_KEYS_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiKeyStore {
+    #[serde(default)]
+    pub keys: Vec<ApiKey>,
+}
+
+fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+impl ApiKeyStore {
+    pub fn load(base: &Path) -> Result<Self> {
+        let path = base.join(API_KEYS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, base: &Path) -> Result<()> {
+        let path = base.join(API_KEYS_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_yaml::to_string(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Issue a key for `capabilities` (each one the agent grants); returns the
+    /// secret, which is not stored and cannot be shown again.
+    pub fn create(
+        &mut self,
+        name: Option<String>,
+        capabilities: Vec<String>,
+        ttl: Option<Duration>,
+    ) -> Result<(String, ApiKey)> {
+        let known = all_capabilities();
+        if let Some(unknown) = capabilities.iter().find(|c| !known.contains(c)) {
+            bail!(
+                "Unknown capability '{}'. Use: {}",
+                unknown,
+                known.join(", ")
+            );
+        }
+        if capabilities.is_empty() {
+            bail!("An API key needs at least one capability");
+        }
+        let secret = format!(
+            "{}{}{}",
+            API_KEY_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let now = clock::now();
+        let expires_at = match ttl {
+            Some(ttl) => Some(
+                now.checked_add_signed(ttl)
+                    .ok_or_else(|| anyhow!("Key lifetime is too long"))?,
+            ),
+            None => None,
+        };
+        let key = ApiKey {
+            id: clock::new_id().replace('-', "")[..8].to_string(),
+            name,
+            hash: hash_secret(&secret),
+            capabilities,
+            created_at: now,
+            expires_at,
+        };
+        self.keys.push(key.clone());
+        Ok((secret, key))
+    }
+
+    /// Remove key `id`; false when there was none.
+    pub fn revoke(&mut self, id: &str) -> bool {
+        let before = self.keys.len();
+        self.keys.retain(|k| k.id != id);
+        self.keys.len() != before
+    }
+
+    /// The unexpired key whose secret is `secret`.
+    pub fn authenticate(&self, secret: &str, now: DateTime<Utc>) -> Option<&ApiKey> {
+        if !secret.starts_with(API_KEY_PREFIX) {
+            return None;
+        }
+        let hash = hash_secret(secret);
+        self.keys
+            .iter()
+            .find(|k| k.hash == hash && !k.is_expired(now))
+    }
+}
+
+/// Parse a key lifetime such as `90d`, `12h`, `30m`, or `2w`.
+pub fn parse_ttl(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let invalid = || anyhow!("Invalid lifetime '{}' (expected e.g. 90d, 12h, 2w)", s);
+    let (split, unit) = s.char_indices().last().ok_or_else(invalid)?;
+    let n: i64 = s[..split]
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(invalid)?;
+    let ttl = match unit {
+        'm' => Duration::try_minutes(n),
+        'h' => Duration::try_hours(n),
+        'd' => Duration::try_days(n),
+        'w' => Duration::try_weeks(n),
+        _ => None,
+    };
+    ttl.ok_or_else(invalid)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +332,8 @@ mod tests {
         assert!(ensure_capability("ifc.export", &capabilities).is_ok());
         assert!(ensure_capability("ping", &capabilities).is_ok());
         assert!(ensure_capability("git.commit", &capabilities).is_err());
+        assert!(ensure_capability("command.run", &capabilities).is_err());
+        assert!(ensure_capability("command.status", &["command.run".into()]).is_ok());
     }
 
     #[test]
@@ -128,4 +345,54 @@ mod tests {
         assert_eq!(granted, vec!["git.status"]);
         assert_eq!(denied, vec!["files.read"]);
     }
+
+    #[test]
+    fn api_keys_authenticate_until_expiry_and_stay_scoped() {
+        let mut store = ApiKeyStore::default();
+        let (secret, key) = store
+            .create(
+                Some("ci".into()),
+                vec!["git.status".into(), "building.get".into()],
+                Some(parse_ttl("90d").unwrap()),
+            )
+            .unwrap();
+        assert!(secret.starts_with(API_KEY_PREFIX));
+        assert!(!store.keys[0].hash.contains(&secret[API_KEY_PREFIX.len()..]));
+
+        let now = clock::now();
+        assert_eq!(
+            store.authenticate(&secret, now).map(|k| &k.id),
+            Some(&key.id)
+        );
+        assert!(store
+            .authenticate(&secret, now + Duration::days(91))
+            .is_none());
+        assert!(store.authenticate("arxk_wrong", now).is_none());
+
+        assert!(ensure_key_capability("room.list", &key.capabilities).is_ok());
+        assert!(ensure_key_capability("git.commit", &key.capabilities).is_err());
+        assert!(ensure_key_capability("claim.review", &key.capabilities).is_err());
+        assert!(store
+            .create(None, vec!["building.read".into()], None)
+            .is_err());
+        assert!(store.revoke(&key.id));
+        assert!(store.authenticate(&secret, now).is_none());
+    }
+
+    #[test]
+    fn parse_ttl_rejects_bad_units_and_overflow() {
+        assert_eq!(parse_ttl("2w").unwrap(), Duration::days(14));
+        assert!(parse_ttl("5д").is_err());
+        assert!(parse_ttl("д").is_err());
+        assert!(parse_ttl("").is_err());
+        assert!(parse_ttl("999999999999999d").is_err());
+
+        // Fits in a Duration but not past today's date.
+        let mut store = ApiKeyStore::default();
+        let ttl = parse_ttl("99999999999d").unwrap();
+        assert!(store
+            .create(None, vec!["git.status".into()], Some(ttl))
+            .is_err());
+        assert!(store.keys.is_empty());
+    }
 }
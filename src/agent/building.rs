@@ -4,12 +4,17 @@
 
 use std::path::Path;
 
-use anyhow::{anyhow, Result};
-use serde::Serialize;
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
 
+use crate::core::clock;
 use crate::core::review::{equipment_review_status, room_review_status, ReviewStatus};
-use crate::core::{summarize_review, Building};
-use crate::persistence::{load_building_at, BUILDING_YAML};
+use crate::core::spatial::plan::{building_overview, BuildingOverview};
+use crate::core::{summarize_review, Building, Equipment};
+use crate::persistence::{
+    load_building_at, load_building_shared_at, PersistenceManager, BUILDING_YAML,
+};
+use crate::yaml::BuildingYamlSerializer;
 
 /// JSON result for `building.get`.
 #[derive(Debug, Serialize)]
@@ -23,12 +28,48 @@ pub struct BuildingGetResult {
     pub floors: usize,
     pub rooms: usize,
     pub equipment: usize,
+    /// `building.yaml` text (only `floor` when filtered), when `include_yaml` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yaml: Option<String>,
+}
+
+/// `building.get` parameters.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BuildingGetParams {
+    /// Keep only the floor at this level
+    #[serde(default)]
+    pub floor: Option<i32>,
+    /// Also return the YAML document
+    #[serde(default)]
+    pub include_yaml: bool,
 }
 
 /// Load durable `building.yaml` and attach review summary for the phone Review UI.
 pub fn get_building(repo_root: &Path) -> Result<BuildingGetResult> {
-    let building = load_building_at(repo_root)
+    get_building_with(repo_root, &BuildingGetParams::default())
+}
+
+/// [`get_building`] narrowed to one floor and/or carrying the YAML text.
+pub fn get_building_with(
+    repo_root: &Path,
+    params: &BuildingGetParams,
+) -> Result<BuildingGetResult> {
+    let mut building = load_building_at(repo_root)
         .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    if let Some(level) = params.floor {
+        building.floors.retain(|f| f.level == level);
+        if building.floors.is_empty() {
+            bail!("No floor at level {}", level);
+        }
+    }
+    let yaml = match (params.include_yaml, params.floor) {
+        (false, _) => None,
+        (true, None) => PersistenceManager::at(repo_root).read_building_yaml()?,
+        (true, Some(_)) => Some(
+            BuildingYamlSerializer::serialize_building(&building)
+                .map_err(|e| anyhow!("Failed to serialize building: {}", e))?,
+        ),
+    };
 
     let summary = summarize_review(&building);
     let proposed_rooms = building
@@ -55,9 +96,122 @@ pub fn get_building(repo_root: &Path) -> Result<BuildingGetResult> {
         floors,
         rooms,
         equipment,
+        yaml,
     })
 }
 
+/// Buildings this agent serves (one per repository) with their floors.
+pub fn list_buildings(repo_root: &Path) -> Result<Vec<BuildingOverview>> {
+    let building = load_building_shared_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    Ok(vec![building_overview(&building)])
+}
+
+/// Row of `equipment.list`.
+#[derive(Debug, Serialize)]
+pub struct EquipmentRow {
+    pub id: String,
+    pub name: String,
+    pub equipment_type: String,
+    pub status: String,
+    pub floor_level: i32,
+    pub wing_name: Option<String>,
+    pub room_name: Option<String>,
+    pub address: Option<String>,
+    /// Accumulated run hours, when tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_hours: Option<f64>,
+    /// `[x, y, z]` in the building's coordinate system
+    pub position: [f64; 3],
+}
+
+/// Row of `room.list`.
+#[derive(Debug, Serialize)]
+pub struct RoomRow {
+    pub id: String,
+    pub name: String,
+    pub room_type: String,
+    pub floor_level: i32,
+    pub wing_name: String,
+    pub equipment: usize,
+    pub address: Option<String>,
+    /// `[x, y, z]` in the building's coordinate system
+    pub position: [f64; 3],
+}
+
+/// Every piece of equipment with its location, in model order.
+pub fn list_equipment(repo_root: &Path) -> Result<Vec<EquipmentRow>> {
+    let building = load_building_shared_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    Ok(equipment_rows(&building))
+}
+
+/// [`list_equipment`] for a loaded building.
+pub fn equipment_rows(building: &Building) -> Vec<EquipmentRow> {
+    let now = clock::now();
+    let mut rows = Vec::new();
+    let mut push = |eq: &Equipment, level: i32, wing: Option<&str>, room: Option<&str>| {
+        rows.push(EquipmentRow {
+            id: eq.id.clone(),
+            name: eq.name.clone(),
+            equipment_type: eq.equipment_type.to_string(),
+            status: eq.status.to_string(),
+            floor_level: level,
+            wing_name: wing.map(str::to_string),
+            room_name: room.map(str::to_string),
+            address: eq.address.as_ref().map(|a| a.to_string()),
+            runtime_hours: eq.runtime.as_ref().map(|r| r.hours_at(now)),
+            position: [eq.position.x, eq.position.y, eq.position.z],
+        })
+    };
+    for floor in &building.floors {
+        for eq in &floor.equipment {
+            push(eq, floor.level, None, None);
+        }
+        for wing in &floor.wings {
+            for eq in &wing.equipment {
+                push(eq, floor.level, Some(&wing.name), None);
+            }
+            for room in &wing.rooms {
+                for eq in &room.equipment {
+                    push(eq, floor.level, Some(&wing.name), Some(&room.name));
+                }
+            }
+        }
+    }
+    rows
+}
+
+/// Every room with its location and equipment count, in model order.
+pub fn list_rooms(repo_root: &Path) -> Result<Vec<RoomRow>> {
+    let building = load_building_shared_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    Ok(room_rows(&building))
+}
+
+/// [`list_rooms`] for a loaded building.
+pub fn room_rows(building: &Building) -> Vec<RoomRow> {
+    let mut rows = Vec::new();
+    for floor in &building.floors {
+        for wing in &floor.wings {
+            for room in &wing.rooms {
+                let position = &room.spatial_properties.position;
+                rows.push(RoomRow {
+                    id: room.id.clone(),
+                    name: room.name.clone(),
+                    room_type: room.room_type.to_string(),
+                    floor_level: floor.level,
+                    wing_name: wing.name.clone(),
+                    equipment: room.equipment.len(),
+                    address: room.address.as_ref().map(|a| a.to_string()),
+                    position: [position.x, position.y, position.z],
+                });
+            }
+        }
+    }
+    rows
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,4 +238,58 @@ mod tests {
         assert_eq!(got.building.name, "Pilot");
         assert!(!got.review_warnings.is_empty());
     }
+
+    #[test]
+    fn get_building_filters_floor_and_lists() {
+        let dir = tempdir().unwrap();
+        let mut b = Building::new("Pilot".into(), "/pilot".into());
+        for (name, level) in [("L1", 0), ("L2", 1)] {
+            let mut floor = Floor::new(name.into(), level);
+            let mut wing = Wing::new("A".into());
+            wing.add_room(Room::new(format!("Room {}", level), RoomType::Office));
+            floor.add_wing(wing);
+            b.add_floor(floor);
+        }
+        save_building_at(dir.path(), &b).unwrap();
+
+        let params = BuildingGetParams {
+            floor: Some(1),
+            include_yaml: true,
+        };
+        let got = get_building_with(dir.path(), &params).unwrap();
+        assert_eq!(got.floors, 1);
+        assert_eq!(got.building.floors[0].name, "L2");
+        let yaml = got.yaml.unwrap();
+        assert!(yaml.contains("Room 1") && !yaml.contains("Room 0"));
+        assert!(get_building(dir.path()).unwrap().yaml.is_none());
+        let missing = BuildingGetParams {
+            floor: Some(9),
+            ..Default::default()
+        };
+        assert!(get_building_with(dir.path(), &missing).is_err());
+
+        let list = list_buildings(dir.path()).unwrap();
+        assert_eq!(list[0].name, "Pilot");
+        assert_eq!(list[0].floors.len(), 2);
+    }
+    #[test]
+    fn get_building_reads_yaml_from_configured_storage() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        std::fs::write(
+            dir.path()
+                .join(crate::persistence::storage::STORAGE_CONFIG_FILE),
+            "store: data\n",
+        )
+        .unwrap();
+        save_building_at(dir.path(), &Building::new("Stored".into(), "/s".into())).unwrap();
+        assert!(!dir.path().join(BUILDING_YAML).exists());
+
+        let params = BuildingGetParams {
+            include_yaml: true,
+            ..Default::default()
+        };
+        let got = get_building_with(dir.path(), &params).unwrap();
+        assert!(got.yaml.unwrap().contains("Stored"));
+    }
 }
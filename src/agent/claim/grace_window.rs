@@ -6,6 +6,7 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use crate::core::clock;
 use crate::yaml::BuildingYamlSerializer;
 use super::rewards::RewardReleaser;
 
@@ -32,7 +33,7 @@ impl GraceWindowManager {
 
     /// Register a newly claimed building, setting the grace window duration threshold.
     pub fn register_active_claim(&mut self, building_id: String, duration_days: u32) {
-        let now = chrono::Utc::now().timestamp() as u64;
+        let now = clock::now().timestamp() as u64;
         let expiration = now + (duration_days as u64 * 24 * 60 * 60);
         self.active_claims.insert(building_id, expiration);
     }
@@ -40,7 +41,7 @@ impl GraceWindowManager {
     /// Return true if the building's grace window has not expired.
     pub fn is_in_grace_window(&self, building_id: &str) -> bool {
         if let Some(&expiration) = self.active_claims.get(building_id) {
-            let now = chrono::Utc::now().timestamp() as u64;
+            let now = clock::now().timestamp() as u64;
             now < expiration
         } else {
             false
@@ -6,6 +6,8 @@ use dirs;
 use octocrab::Octocrab;
 use serde::{Deserialize, Serialize};
 
+use crate::core::clock;
+
 const CONFIG_ENV: &str = "ARXOS_AGENT_CONFIG_DIR";
 pub const GITHUB_TOKEN_ENV: &str = "ARXOS_GITHUB_TOKEN";
 const CONFIG_FILENAME: &str = "collab.toml";
@@ -277,7 +279,7 @@ async fn post_comment(
 
 fn format_comment(message: &CollabMessage) -> String {
     let submitted_at =
-        DateTime::<Utc>::from_timestamp_millis(message.timestamp).unwrap_or_else(Utc::now);
+        DateTime::<Utc>::from_timestamp_millis(message.timestamp).unwrap_or_else(clock::now);
 
     format!(
         "### ArxOS Update for `{}`\n\n{}\n\n— _{}_ @ {} UTC_",
@@ -1,4 +1,245 @@
 //! Agent command handlers
 //!
+//! `command.catalog` lists the CLI's scriptable commands and `command.run`
+//! executes one through the same dispatcher as the terminal (see
+//! [`crate::cli::catalog`]). A run is a background job in
+//! [`crate::persistence::jobs`]: its output is appended line by line to the
+//! job's log, and `command.status` returns the lines after a client's offset,
+//! so clients can stream it by polling, also after a reconnect or an agent
+//! restart. `job.status` / `job.list` see the same jobs.
+//!
 //! Hardware/sensor SSH commands were removed with the open-source hardware stack.
-//! Agent focuses on git + IFC import/export RPC.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::catalog::{parse_command, run_command, CommandOutput};
+use crate::persistence::jobs::{self, JobGuard, JobRecord, JobState};
+
+/// Output lines kept per job; later lines are dropped and `truncated` set.
+pub const MAX_OUTPUT_LINES: usize = 10_000;
+
+/// Job description prefix, followed by the command line.
+const JOB_PREFIX: &str = "command.run ";
+
+/// A command run, as reported by `command.run` and `command.status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandJob {
+    pub id: String,
+    pub args: Vec<String>,
+    pub state: JobState,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Lines from `since` on (all lines while stored)
+    pub output: Vec<CommandOutput>,
+    /// Offset to pass as `since` on the next `command.status`
+    pub next: usize,
+    pub truncated: bool,
+}
+
+/// `command.run` parameters.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CommandRequest {
+    /// Arguments after `arx`, e.g. `["energy", "report", "--period", "month"]`
+    pub args: Vec<String>,
+    /// Run with `arx --dry-run`
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// One JSON line of a command job's log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum LogLine {
+    Start { args: Vec<String> },
+    Output(CommandOutput),
+    Exit { exit_code: i32, truncated: bool },
+}
+
+fn write_line(file: &mut File, line: &LogLine) -> std::io::Result<()> {
+    let mut text = serde_json::to_string(line)?;
+    text.push('\n');
+    file.write_all(text.as_bytes())
+}
+
+/// Validate `request` against the CLI schema and start it in `repo_root`.
+///
+/// The worker thread does not inherit the dispatcher's thread-local state, so
+/// the acting user and an active dry run are captured here and handed to the
+/// child `arx`.
+pub fn start(repo_root: &Path, request: CommandRequest) -> Result<CommandJob> {
+    let mut args = request.args;
+    let dry_run = request.dry_run || crate::core::operations::is_dry_run();
+    if dry_run && !args.iter().any(|a| a == "--dry-run") {
+        args.insert(0, "--dry-run".to_string());
+    }
+    parse_command(&args).map_err(|e| anyhow!("{}", e.trim_end()))?;
+
+    let description = format!("{}arx {}", JOB_PREFIX, args.join(" "));
+    let record = jobs::create(repo_root, &description)?;
+    let log = jobs::log_path(&record.id);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(repo_root.join(&log))?;
+    write_line(&mut file, &LogLine::Start { args: args.clone() })?;
+    let record = jobs::set_log(repo_root, &record.id, &log)?;
+
+    let (root, id) = (repo_root.to_path_buf(), record.id.clone());
+    let acting = crate::core::access::acting_user();
+    std::thread::spawn(move || {
+        let _acting = acting.map(crate::core::access::ActingUserGuard::act_as);
+        let guard = match JobGuard::attach(&root, &id) {
+            Ok(guard) => guard,
+            Err(e) => {
+                log::warn!("Failed to start command job {}: {}", id, e);
+                return;
+            }
+        };
+        let (mut lines, mut truncated) = (0, false);
+        let outcome = run_command(&args, &root, |line| {
+            if lines < MAX_OUTPUT_LINES {
+                lines += 1;
+                if let Err(e) = write_line(&mut file, &LogLine::Output(line)) {
+                    log::warn!("Failed to record output of command job {}: {}", id, e);
+                }
+            } else {
+                truncated = true;
+            }
+        });
+        let outcome = match outcome {
+            Ok(exit_code) => {
+                let exit = LogLine::Exit {
+                    exit_code,
+                    truncated,
+                };
+                if let Err(e) = write_line(&mut file, &exit) {
+                    log::warn!("Failed to record exit of command job {}: {}", id, e);
+                }
+                if exit_code == 0 {
+                    Ok(Some(serde_json::json!({ "exit_code": exit_code })))
+                } else {
+                    Err(format!("exited with status {}", exit_code))
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = guard.finish(outcome) {
+            log::warn!("Failed to record outcome of command job {}: {}", id, e);
+        }
+    });
+    from_record(repo_root, record, 0)?.ok_or_else(|| anyhow!("command job was not recorded"))
+}
+
+/// The command view of job `record` with output from `since` on, or `None`
+/// for other kinds of job.
+fn from_record(repo_root: &Path, record: JobRecord, since: usize) -> Result<Option<CommandJob>> {
+    let (Some(_), Some(log)) = (record.description.strip_prefix(JOB_PREFIX), &record.log) else {
+        return Ok(None);
+    };
+    let text = fs::read_to_string(repo_root.join(log)).unwrap_or_default();
+    let mut job = CommandJob {
+        id: record.id,
+        args: Vec::new(),
+        state: record.state,
+        created_at: record.created_at,
+        exit_code: None,
+        error: record.error,
+        output: Vec::new(),
+        next: 0,
+        truncated: false,
+    };
+    // A line still being written fails to parse and is picked up next time
+    for line in text.lines() {
+        match serde_json::from_str(line) {
+            Ok(LogLine::Start { args }) => job.args = args,
+            Ok(LogLine::Output(output)) => job.output.push(output),
+            Ok(LogLine::Exit {
+                exit_code,
+                truncated,
+            }) => {
+                job.exit_code = Some(exit_code);
+                job.truncated = truncated;
+            }
+            Err(_) => {}
+        }
+    }
+    job.truncated |= job.output.len() >= MAX_OUTPUT_LINES;
+    job.next = job.output.len();
+    job.output = job.output.split_off(since.min(job.output.len()));
+    Ok(Some(job))
+}
+
+/// Job `id` with the output lines from `since` on.
+pub fn status(repo_root: &Path, id: &str, since: usize) -> Result<Option<CommandJob>> {
+    match jobs::load(repo_root, id)? {
+        Some(record) => from_record(repo_root, record, since),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_rejects_invalid_and_status_pages_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let bad = CommandRequest {
+            args: vec!["energy".into(), "report".into(), "--bogus".into()],
+            dry_run: false,
+        };
+        assert!(start(dir.path(), bad).is_err());
+        let tui = CommandRequest {
+            args: vec!["spreadsheet".into(), "rooms".into()],
+            dry_run: true,
+        };
+        assert!(start(dir.path(), tui).is_err());
+
+        let record = jobs::create(dir.path(), "command.run arx validate").unwrap();
+        let log = jobs::log_path(&record.id);
+        let mut file = File::create(dir.path().join(&log)).unwrap();
+        for line in [
+            LogLine::Start {
+                args: vec!["validate".into()],
+            },
+            LogLine::Output(CommandOutput::Stdout("one".into())),
+            LogLine::Output(CommandOutput::Stderr("two".into())),
+            LogLine::Output(CommandOutput::Stdout("three".into())),
+            LogLine::Exit {
+                exit_code: 0,
+                truncated: false,
+            },
+        ] {
+            write_line(&mut file, &line).unwrap();
+        }
+        jobs::set_log(dir.path(), &record.id, &log).unwrap();
+        let job = status(dir.path(), &record.id, 0).unwrap().unwrap();
+        assert_eq!(job.args, ["validate"]);
+        assert_eq!(job.exit_code, Some(0));
+        let page = status(dir.path(), &job.id, 1).unwrap().unwrap();
+        assert_eq!(page.output.len(), 2);
+        assert_eq!(page.next, 3);
+        assert_eq!(
+            serde_json::to_value(&page.output[0]).unwrap(),
+            serde_json::json!({"stream": "stderr", "line": "two"})
+        );
+        assert!(status(dir.path(), &job.id, 9)
+            .unwrap()
+            .unwrap()
+            .output
+            .is_empty());
+        assert!(status(dir.path(), "missing", 0).unwrap().is_none());
+        // Other kinds of job are not command runs
+        let other = jobs::create(dir.path(), "ifc.import").unwrap();
+        assert!(status(dir.path(), &other.id, 0).unwrap().is_none());
+    }
+}
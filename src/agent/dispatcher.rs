@@ -4,57 +4,206 @@ use std::sync::{Arc, Mutex};
 use anyhow::Result;
 use serde_json::Value;
 
-use crate::agent::auth::{ensure_capability, TokenState};
+use crate::agent::auth::{ensure_capability, ensure_key_capability, Caller, TokenState};
+use crate::core::access::ActingUserGuard;
+use crate::core::clock;
+use crate::core::operations::DryRunGuard;
+use crate::agent::pagination::{list_action, paginate, PageRequest};
 use crate::agent::protocol::{
-    JsonRpcRequest, JsonRpcResponse, AUTH_ERROR, INTERNAL_ERROR,
-    METHOD_NOT_FOUND,
+    JsonRpcRequest, JsonRpcResponse, AUTH_ERROR, INTERNAL_ERROR, INVALID_PARAMS,
+    METHOD_NOT_FOUND, READ_ONLY_ERROR,
 };
-use crate::agent::{building, collab, files, git, ifc};
+use crate::agent::{ar, building, collab, commands, exports, files, git, ifc, issues, model, replica, transfer};
+use crate::persistence::audit::{record_quietly, AuditEntry, AuditOutcome};
+use crate::persistence::jobs;
+
+/// Mutating actions whose writes all go through dry-run aware layers.
+/// Read-only actions run unchanged under a dry run.
+const SIMULATABLE_ACTIONS: &[&str] = &[
+    "git.status",
+    "git.diff",
+    "git.log",
+    "git.commit",
+    "files.read",
+    "building.get",
+    "building.list",
+    "equipment.list",
+    "room.list",
+    "ifc.import",
+    "issue.submit",
+    "issue.list",
+    "sensor.report",
+    "energy.record",
+    "command.catalog",
+    "command.run",
+    "command.status",
+    "ar.pending.submit",
+    "ar.pending.list",
+    "ar.pending.approve",
+    "ar.pending.reject",
+    "room.get",
+    "room.create",
+    "room.update",
+    "room.delete",
+    "equipment.get",
+    "equipment.create",
+    "equipment.update",
+    "equipment.delete",
+    "spatial.query",
+    "job.status",
+    "job.list",
+];
+
+/// Commits returned by `git.log` when no `limit` is given, and the cap on it.
+const DEFAULT_LOG_LIMIT: usize = 20;
+const MAX_LOG_LIMIT: u64 = 500;
 
 pub struct AgentState {
     pub repo_root: PathBuf,
     pub token: Arc<Mutex<TokenState>>,
     pub metrics: Arc<crate::agent::observability::AgentMetrics>,
+    pub sessions: Arc<crate::agent::sessions::SessionRegistry>,
     pub reload_handle: Option<tracing_subscriber::reload::Handle<tracing_subscriber::EnvFilter, tracing_subscriber::Registry>>,
 }
 
 pub async fn dispatch(state: Arc<AgentState>, request: JsonRpcRequest) -> JsonRpcResponse {
+    dispatch_as(state, request, &Caller::Token).await
+}
+
+/// Dispatch on behalf of `caller`, checking its capabilities. Every request
+/// is recorded in the audit log, denied and failed ones included.
+pub async fn dispatch_as(
+    state: Arc<AgentState>,
+    request: JsonRpcRequest,
+    caller: &Caller,
+) -> JsonRpcResponse {
+    let method = request.method.clone();
+    let params = request.params.as_ref();
+    let actor = acting_user(caller, params.unwrap_or(&Value::Null))
+        .unwrap_or_else(crate::persistence::PersistenceManager::acting_user);
+    let target = params.and_then(audit_target);
+
+    let response = dispatch_request(state.clone(), request, caller).await;
+    let outcome = match &response.error {
+        None => AuditOutcome::Success,
+        Some(e) if e.code == AUTH_ERROR => AuditOutcome::Denied,
+        Some(_) => AuditOutcome::Failure,
+    };
+    let entry = AuditEntry {
+        detail: response.error.as_ref().map(|e| e.message.clone()),
+        ..AuditEntry::new(format!("agent.{}", method), target, outcome).with_actor(actor)
+    };
+    record_quietly(&state.repo_root, &entry);
+    response
+}
+
+/// The entity or file a request names, for the audit log.
+fn audit_target(params: &Value) -> Option<String> {
+    ["path", "id", "room", "equipment", "name", "sensor_id"]
+        .iter()
+        .find_map(|key| params.get(key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+}
+
+async fn dispatch_request(
+    state: Arc<AgentState>,
+    request: JsonRpcRequest,
+    caller: &Caller,
+) -> JsonRpcResponse {
     let id = request.id.clone();
     let method = request.method.as_str();
     let params = request.params.unwrap_or(Value::Null);
 
     // 1. Check capabilities
-    let capabilities = {
-        let token_guard = state.token.lock().unwrap();
-        token_guard.capabilities().to_vec()
+    let allowed = match caller {
+        Caller::Token => {
+            let token_guard = state.token.lock().unwrap();
+            ensure_capability(method, token_guard.capabilities())
+        }
+        Caller::ApiKey { capabilities, .. } => ensure_key_capability(method, capabilities),
     };
-
-    if let Err(e) = ensure_capability(method, &capabilities) {
+    if let Err(e) = allowed {
         return JsonRpcResponse::error(id, AUTH_ERROR, format!("Permission denied: {}", e), None);
     }
 
-    // 2. Dispatch to handler
-    let result = match method {
-        "git.status" => handle_git_status(&state.repo_root),
-        "git.diff" => handle_git_diff(&state.repo_root, params),
-        "git.commit" => handle_git_commit(&state, params),
-        "files.read" => handle_files_read(&state.repo_root, params),
-        "building.get" => handle_building_get(&state.repo_root),
-        "ifc.import" => handle_ifc_import(&state.repo_root, params),
-        "ifc.export" => handle_ifc_export(&state.repo_root, params),
-        "collab.sync" => handle_collab_sync(params).await,
-        "claim.list_pending" => handle_claim_list_pending(&state.repo_root),
-        "claim.review" => handle_claim_review(&state.repo_root, params),
-        "claim.get_status" => handle_claim_get_status(&state.repo_root, params),
-        _ => Err(anyhow::anyhow!("Method not found")),
+    // Standby replicas only serve reads until promoted
+    if let Err(e) = replica::ensure_writable(method) {
+        return JsonRpcResponse::error(id, READ_ONLY_ERROR, e.to_string(), None);
+    }
+
+    // 2. Optional dry run: simulate writes and Git effects, return the change set
+    let dry_run = params
+        .get("dry_run")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if dry_run && !SIMULATABLE_ACTIONS.contains(&method) {
+        return JsonRpcResponse::error(
+            id,
+            INTERNAL_ERROR,
+            format!("dry_run is not supported for '{}'", method),
+            None,
+        );
+    }
+    // 3. List actions share one paging contract; bad parameters fail before the handler runs
+    let page = match list_action(method).map(|a| PageRequest::from_params(a, &params)) {
+        Some(Ok(page)) => Some(page),
+        Some(Err(e)) => return JsonRpcResponse::error(id, INVALID_PARAMS, e.to_string(), None),
+        None => None,
     };
 
+    // 4. Dispatch to handler. The dry-run, acting-user, and held-approval
+    // state is thread-local, so it only spans the synchronous handlers; an
+    // await could resume the request on another worker thread.
+    let (result, changes, held) = match method {
+        "collab.sync" => (handle_collab_sync(params).await, None, Vec::new()),
+        _ => {
+            let guard = dry_run.then(DryRunGuard::begin);
+            // Building edits are authorized against the caller's role (.arx/roles.yaml)
+            let acting = acting_user(caller, &params).map(ActingUserGuard::act_as);
+            let result = dispatch_handler(&state, method, params, caller);
+            drop(acting);
+            // Critical equipment changes held for sign-off by this request's saves
+            let held = crate::persistence::approvals::take_held();
+            (result, guard.map(DryRunGuard::finish), held)
+        }
+    };
+    let result = match page {
+        Some(page) => result
+            .and_then(|value| paginate(value, &page))
+            .and_then(|page| Ok(serde_json::to_value(page)?)),
+        None => result,
+    };
+    let result = match changes {
+        Some(changes) => result.map(|value| {
+            serde_json::json!({ "dry_run": true, "result": value, "changes": changes })
+        }),
+        None => result,
+    };
+
+    let result = if held.is_empty() {
+        result
+    } else {
+        result.map(|value| {
+            let pending: Vec<Value> = held
+                .iter()
+                .map(|r| serde_json::json!({ "id": r.id, "summary": r.summary() }))
+                .collect();
+            serde_json::json!({ "result": value, "pending_approvals": pending })
+        })
+    };
+
+    if result.is_ok() && !dry_run {
+        state.sessions.notify_action(method, caller);
+    }
+
     match result {
         Ok(value) => JsonRpcResponse::success(id, value),
         Err(e) => {
             let msg = e.to_string();
             if msg == "Method not found" {
                 JsonRpcResponse::error(id, METHOD_NOT_FOUND, msg, None)
+            } else if msg.contains("Permission denied") {
+                JsonRpcResponse::error(id, AUTH_ERROR, msg, None)
             } else {
                 JsonRpcResponse::error(id, INTERNAL_ERROR, msg, None)
             }
@@ -62,6 +211,90 @@ pub async fn dispatch(state: Arc<AgentState>, request: JsonRpcRequest) -> JsonRp
     }
 }
 
+/// Run a synchronous handler for `method`.
+fn dispatch_handler(
+    state: &AgentState,
+    method: &str,
+    params: Value,
+    caller: &Caller,
+) -> Result<Value> {
+    match method {
+        "git.status" => handle_git_status(&state.repo_root),
+        "git.diff" => handle_git_diff(&state.repo_root, params),
+        "git.log" => handle_git_log(&state.repo_root, params),
+        "git.commit" => handle_git_commit(state, caller, params),
+        "files.read" => handle_files_read(&state.repo_root, params),
+        "building.get" => handle_building_get(&state.repo_root, params),
+        "building.list" => handle_building_list(&state.repo_root),
+        "equipment.list" => handle_equipment_list(&state.repo_root),
+        "room.list" => handle_room_list(&state.repo_root),
+        "twin.snapshot" => handle_twin_snapshot(&state.repo_root, params),
+        "ifc.import" => handle_ifc_import(&state.repo_root, params),
+        "ifc.export" => handle_ifc_export(&state.repo_root, params),
+        "export.run" => handle_export_run(&state.repo_root, params),
+        "export.status" => handle_export_status(&state.repo_root, params),
+        "session.list" => to_value(Ok(state.sessions.list())),
+        "command.catalog" => to_value(Ok(crate::cli::catalog::command_catalog())),
+        "command.run" => handle_command_run(&state.repo_root, params),
+        "command.status" => handle_command_status(&state.repo_root, params),
+        "transfer.upload" => handle_transfer_upload(&state.repo_root, params),
+        "transfer.download" => handle_transfer_download(&state.repo_root, params),
+        "transfer.fetch" => handle_transfer_fetch(&state.repo_root, params),
+        "transfer.status" => {
+            transfer_id(&params).and_then(|id| to_value(transfer::status(&state.repo_root, id)))
+        }
+        "transfer.finish" => {
+            transfer_id(&params).and_then(|id| to_value(transfer::finish(&state.repo_root, id)))
+        }
+        "claim.list_pending" => handle_claim_list_pending(&state.repo_root),
+        "claim.review" => handle_claim_review(&state.repo_root, params),
+        "claim.get_status" => handle_claim_get_status(&state.repo_root, params),
+        "issue.submit" => handle_issue_submit(&state.repo_root, params),
+        "issue.list" => handle_issue_list(&state.repo_root, params),
+        "sensor.report" => handle_sensor_report(&state.repo_root, params),
+        "energy.record" => handle_energy_record(&state.repo_root, params),
+        "job.status" => handle_job_status(&state.repo_root, params),
+        "job.list" => to_value(jobs::list(&state.repo_root).map_err(anyhow::Error::from)),
+        "job.cancel" => handle_job_cancel(&state.repo_root, params),
+        "ar.pending.submit" => handle_ar_pending_submit(&state.repo_root, params),
+        "ar.pending.list" => handle_ar_pending_list(&state.repo_root, params),
+        "ar.pending.approve" => handle_ar_pending_approve(&state.repo_root, params),
+        "ar.pending.reject" => handle_ar_pending_reject(&state.repo_root, params),
+        "room.get" => model_key(&params, "room")
+            .and_then(|key| to_value(model::get_room(&state.repo_root, key))),
+        "room.create" => model_params(params)
+            .and_then(|p| to_value(model::create_room(&state.repo_root, p))),
+        "room.update" => model_params(params)
+            .and_then(|p| to_value(model::update_room(&state.repo_root, p))),
+        "room.delete" => model_key(&params, "room")
+            .and_then(|key| to_value(model::delete_room(&state.repo_root, key))),
+        "equipment.get" => model_key(&params, "equipment")
+            .and_then(|key| to_value(model::get_equipment(&state.repo_root, key))),
+        "equipment.create" => model_params(params)
+            .and_then(|p| to_value(model::create_equipment(&state.repo_root, p))),
+        "equipment.update" => model_params(params)
+            .and_then(|p| to_value(model::update_equipment(&state.repo_root, p))),
+        "equipment.delete" => model_key(&params, "equipment")
+            .and_then(|key| to_value(model::delete_equipment(&state.repo_root, key))),
+        "spatial.query" => model_params(params)
+            .and_then(|q| to_value(model::spatial(&state.repo_root, &q))),
+        _ => Err(anyhow::anyhow!("Method not found")),
+    }
+}
+
+/// User the request acts for: `api-key:<id>` for API keys; the agent token may
+/// name the user it relays for (the field app) in `acting_user`.
+fn acting_user(caller: &Caller, params: &Value) -> Option<String> {
+    match caller {
+        Caller::Token => params
+            .get("acting_user")
+            .and_then(|v| v.as_str())
+            .filter(|u| !u.is_empty())
+            .map(str::to_string),
+        Caller::ApiKey { id, .. } => Some(format!("api-key:{}", id)),
+    }
+}
+
 fn handle_git_status(root: &std::path::Path) -> Result<Value> {
     let summary = git::status(root)?;
     Ok(serde_json::to_value(summary)?)
@@ -75,7 +308,21 @@ fn handle_git_diff(root: &std::path::Path, params: Value) -> Result<Value> {
     Ok(serde_json::to_value(diff)?)
 }
 
-fn handle_git_commit(state: &AgentState, params: Value) -> Result<Value> {
+fn handle_git_log(root: &std::path::Path, params: Value) -> Result<Value> {
+    let limit = match params.get("limit") {
+        None | Some(Value::Null) => DEFAULT_LOG_LIMIT,
+        Some(v) => v
+            .as_u64()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| anyhow::anyhow!("'limit' must be a positive integer"))?
+            .min(MAX_LOG_LIMIT) as usize,
+    };
+
+    let commits = git::log(root, limit)?;
+    Ok(serde_json::to_value(commits)?)
+}
+
+fn handle_git_commit(state: &AgentState, caller: &Caller, params: Value) -> Result<Value> {
     let message = params
         .get("message")
         .and_then(|v| v.as_str())
@@ -86,10 +333,10 @@ fn handle_git_commit(state: &AgentState, params: Value) -> Result<Value> {
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    // Get the DID key from the current token state
-    let did_key = {
-        let token = state.token.lock().unwrap();
-        token.value().to_string()
+    // Commits carry the DID key of the token, or the id of the API key
+    let did_key = match caller {
+        Caller::Token => state.token.lock().unwrap().value().to_string(),
+        Caller::ApiKey { id, .. } => format!("api-key:{}", id),
     };
 
     let result = git::commit(&state.repo_root, message, stage_all, &did_key)?;
@@ -106,12 +353,61 @@ fn handle_files_read(root: &std::path::Path, params: Value) -> Result<Value> {
     Ok(serde_json::to_value(content)?)
 }
 
-fn handle_building_get(root: &std::path::Path) -> Result<Value> {
-    let result = building::get_building(root)?;
+fn handle_building_get(root: &std::path::Path, params: Value) -> Result<Value> {
+    let params: building::BuildingGetParams = if params.is_null() {
+        Default::default()
+    } else {
+        serde_json::from_value(params)
+            .map_err(|e| anyhow::anyhow!("Invalid building parameters: {}", e))?
+    };
+    let result = building::get_building_with(root, &params)?;
     Ok(serde_json::to_value(result)?)
 }
 
+fn handle_building_list(root: &std::path::Path) -> Result<Value> {
+    Ok(serde_json::to_value(building::list_buildings(root)?)?)
+}
+
+fn handle_equipment_list(root: &std::path::Path) -> Result<Value> {
+    Ok(serde_json::to_value(building::list_equipment(root)?)?)
+}
+
+fn handle_room_list(root: &std::path::Path) -> Result<Value> {
+    Ok(serde_json::to_value(building::list_rooms(root)?)?)
+}
+
+fn handle_twin_snapshot(root: &std::path::Path, params: Value) -> Result<Value> {
+    let params: crate::agent::twin::TwinSnapshotParams = if params.is_null() {
+        Default::default()
+    } else {
+        serde_json::from_value(params)
+            .map_err(|e| anyhow::anyhow!("Invalid twin parameters: {}", e))?
+    };
+    Ok(serde_json::to_value(crate::agent::twin::snapshot_since(root, &params)?)?)
+}
+
 fn handle_ifc_import(root: &std::path::Path, params: Value) -> Result<Value> {
+    // `async: true` returns a job straight away; poll it with `job.status`
+    if params.get("async").and_then(|v| v.as_bool()).unwrap_or(false) {
+        if crate::core::operations::is_dry_run() {
+            anyhow::bail!("dry_run cannot be combined with async");
+        }
+        let (repo_root, mut params) = (root.to_path_buf(), params);
+        params["async"] = Value::Bool(false);
+        let job = jobs::spawn(root, "ifc.import", move || {
+            handle_ifc_import(&repo_root, params).map_err(|e| e.to_string())
+        })?;
+        return Ok(serde_json::to_value(job)?);
+    }
+
+    // A file already in the repo (e.g. sent with transfer.upload)
+    if let Some(path) = params.get("path").and_then(|v| v.as_str()) {
+        crate::utils::path_safety::PathSafety::detect_path_traversal(path)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let result = ifc::import_ifc_local(root, &root.join(path))?;
+        return Ok(serde_json::to_value(result)?);
+    }
+
     let filename = params
         .get("filename")
         .and_then(|v| v.as_str())
@@ -145,6 +441,78 @@ fn handle_ifc_export(root: &std::path::Path, params: Value) -> Result<Value> {
     Ok(serde_json::to_value(result)?)
 }
 
+fn handle_export_run(root: &std::path::Path, params: Value) -> Result<Value> {
+    let request: exports::ExportRequest = serde_json::from_value(params)
+        .map_err(|e| anyhow::anyhow!("Invalid export parameters: {}", e))?;
+    let job = exports::start(root, request)?;
+    Ok(serde_json::to_value(job)?)
+}
+
+fn handle_command_run(root: &std::path::Path, params: Value) -> Result<Value> {
+    let request: commands::CommandRequest = serde_json::from_value(params)
+        .map_err(|e| anyhow::anyhow!("Invalid command parameters: {}", e))?;
+    let job = commands::start(root, request)?;
+    Ok(serde_json::to_value(job)?)
+}
+
+fn handle_command_status(root: &std::path::Path, params: Value) -> Result<Value> {
+    let id = params
+        .get("job_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'job_id' parameter"))?;
+    let since = params.get("since").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    let job = commands::status(root, id, since)?
+        .ok_or_else(|| anyhow::anyhow!("Unknown command job '{}'", id))?;
+    Ok(serde_json::to_value(job)?)
+}
+
+fn to_value<T: serde::Serialize>(result: Result<T>) -> Result<Value> {
+    Ok(serde_json::to_value(result?)?)
+}
+
+fn transfer_id(params: &Value) -> Result<&str> {
+    params
+        .get("transfer_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'transfer_id' parameter"))
+}
+
+fn handle_transfer_upload(root: &std::path::Path, params: Value) -> Result<Value> {
+    let request: transfer::UploadRequest = serde_json::from_value(params)
+        .map_err(|e| anyhow::anyhow!("Invalid transfer parameters: {}", e))?;
+    to_value(transfer::begin_upload(root, request))
+}
+
+fn handle_transfer_download(root: &std::path::Path, params: Value) -> Result<Value> {
+    let path = params
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+    let chunk_size = params
+        .get("chunk_size")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize);
+    to_value(transfer::begin_download(root, path, chunk_size))
+}
+
+/// Chunk metadata; the WebSocket server follows it with the binary frame.
+fn handle_transfer_fetch(root: &std::path::Path, params: Value) -> Result<Value> {
+    let id = transfer_id(&params)?;
+    let offset = params.get("offset").and_then(|v| v.as_u64()).unwrap_or(0);
+    let (header, _) = transfer::read_chunk(root, id, offset)?;
+    Ok(serde_json::to_value(header)?)
+}
+
+fn handle_export_status(root: &std::path::Path, params: Value) -> Result<Value> {
+    let id = params
+        .get("job_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'job_id' parameter"))?;
+    let job = exports::status(root, id)?
+        .ok_or_else(|| anyhow::anyhow!("Unknown export job '{}'", id))?;
+    Ok(serde_json::to_value(job)?)
+}
+
 async fn handle_collab_sync(params: Value) -> Result<Value> {
     let messages_val = params
         .get("messages")
@@ -185,7 +553,7 @@ fn handle_claim_list_pending(root: &std::path::Path) -> Result<Value> {
                 (building_id, address, contributor, summary, timestamp)
             }
             Err(_) => {
-                ("unknown".to_string(), "unknown".to_string(), "unknown".to_string(), "Invalid YAML contribution".to_string(), chrono::Utc::now().timestamp() as u64)
+                ("unknown".to_string(), "unknown".to_string(), "unknown".to_string(), "Invalid YAML contribution".to_string(), clock::now().timestamp() as u64)
             }
         };
 
@@ -262,6 +630,136 @@ fn handle_claim_get_status(_root: &std::path::Path, params: Value) -> Result<Val
     }))
 }
 
+fn handle_issue_submit(root: &std::path::Path, params: Value) -> Result<Value> {
+    let mut report: crate::core::domain::issue::IssueReport = serde_json::from_value(params)
+        .map_err(|e| anyhow::anyhow!("Invalid issue report: {}", e))?;
+    if report.source.is_none() {
+        report.source = Some("agent".to_string());
+    }
+    let issue = issues::submit(root, &report)?;
+    Ok(serde_json::to_value(issue)?)
+}
+
+fn handle_issue_list(root: &std::path::Path, params: Value) -> Result<Value> {
+    let open_only = params
+        .get("open_only")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let list = issues::list(root, open_only)?;
+    Ok(serde_json::to_value(list)?)
+}
+
+fn handle_sensor_report(root: &std::path::Path, params: Value) -> Result<Value> {
+    let sensor_id = params
+        .get("sensor_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'sensor_id' parameter"))?;
+    let sensor_type = params.get("sensor_type").and_then(|v| v.as_str());
+    let value = params.get("value").and_then(|v| v.as_f64());
+    let seen = crate::persistence::sensors::record_sensor_reading(root, sensor_id, sensor_type, value)?;
+    Ok(serde_json::to_value(seen)?)
+}
+
+fn handle_energy_record(root: &std::path::Path, params: Value) -> Result<Value> {
+    let meter_id = params
+        .get("meter_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'meter_id' parameter"))?;
+    let value = params
+        .get("value")
+        .and_then(|v| v.as_f64())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'value' parameter"))?;
+    let time = |key: &str| -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        params
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|v| {
+                chrono::DateTime::parse_from_rfc3339(v)
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .map_err(|e| anyhow::anyhow!("Invalid '{}': {}", key, e))
+            })
+            .transpose()
+    };
+    let interval = crate::persistence::energy::record_interval(
+        root,
+        meter_id,
+        time("start")?,
+        time("end")?,
+        value,
+    )?;
+    Ok(serde_json::to_value(interval)?)
+}
+
+fn job_id(params: &Value) -> Result<&str> {
+    params
+        .get("job_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'job_id' parameter"))
+}
+
+fn handle_job_status(root: &std::path::Path, params: Value) -> Result<Value> {
+    let id = job_id(&params)?;
+    let job = jobs::load(root, id)?.ok_or_else(|| anyhow::anyhow!("job '{}' not found", id))?;
+    Ok(serde_json::to_value(job)?)
+}
+
+fn handle_job_cancel(root: &std::path::Path, params: Value) -> Result<Value> {
+    Ok(serde_json::to_value(jobs::cancel(root, job_id(&params)?)?)?)
+}
+
+fn handle_ar_pending_submit(root: &std::path::Path, params: Value) -> Result<Value> {
+    let records = params
+        .get("equipment")
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Missing 'equipment' parameter"))?;
+    let records = serde_json::from_value(records)
+        .map_err(|e| anyhow::anyhow!("Invalid pending equipment: {}", e))?;
+    Ok(serde_json::to_value(ar::submit(root, records)?)?)
+}
+
+fn handle_ar_pending_list(root: &std::path::Path, params: Value) -> Result<Value> {
+    let all = params.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+    Ok(serde_json::to_value(ar::list(root, all)?)?)
+}
+
+fn ar_pending_ids(params: &Value) -> Result<Vec<String>> {
+    let ids = params
+        .get("ids")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'ids' parameter"))?;
+    ids.iter()
+        .map(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("'ids' must be strings"))
+        })
+        .collect()
+}
+
+fn handle_ar_pending_approve(root: &std::path::Path, params: Value) -> Result<Value> {
+    Ok(serde_json::to_value(ar::approve(root, &ar_pending_ids(&params)?)?)?)
+}
+
+fn handle_ar_pending_reject(root: &std::path::Path, params: Value) -> Result<Value> {
+    let note = params
+        .get("reason")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    Ok(serde_json::to_value(ar::reject(root, &ar_pending_ids(&params)?, note)?)?)
+}
+
+/// Room or equipment id, name, or alias from `params[field]`.
+fn model_key<'a>(params: &'a Value, field: &str) -> Result<&'a str> {
+    params
+        .get(field)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing '{}' parameter", field))
+}
+
+fn model_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T> {
+    serde_json::from_value(params).map_err(|e| anyhow::anyhow!("Invalid parameters: {}", e))
+}
+
 fn map_grace_error(e: String) -> anyhow::Error {
     anyhow::anyhow!(e)
 }
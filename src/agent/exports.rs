@@ -0,0 +1,349 @@
+//! `export.run`: building exports produced in the background for agent clients.
+//!
+//! A run is a background job in [`crate::persistence::jobs`] that writes the
+//! artifact under [`EXPORTS_DIR`]; `export.status` (or `job.status`) reports
+//! its stage and progress, also after a reconnect or an agent restart, and
+//! `job.cancel` stops it. Once done, the artifact is served exactly once from
+//! `GET /api/exports/:id` (agent token required) and deleted. Artifacts never
+//! downloaded expire after [`ARTIFACT_TTL_SECS`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::materials::MaterialSet;
+use crate::export::csv::equipment_to_csv;
+use crate::export::gltf::export_gltf;
+use crate::export::ifc::IFCExporter;
+use crate::export::png::{render_floor_png, FloorOverlay, FloorPngOptions};
+use crate::export::svg::render_floor_svg;
+use crate::persistence::jobs::{self, JobRecord, JobState};
+use crate::persistence::meshes::load_building_meshes;
+use crate::persistence::{load_building_at, PersistenceManager, BUILDING_YAML};
+
+/// Repo-relative directory holding artifacts until they are downloaded.
+pub const EXPORTS_DIR: &str = ".arx/agent/exports";
+
+/// Formats `export.run` accepts (the built-in `arx export` formats).
+pub const FORMATS: &[&str] = &["ifc", "gltf", "csv", "json", "yaml", "png", "svg"];
+
+/// Artifacts never fetched are removed after this long.
+pub const ARTIFACT_TTL_SECS: u64 = 3600;
+
+/// Job description prefix, followed by the format.
+const JOB_PREFIX: &str = "export.run ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportState {
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+    /// The agent exited before the export finished
+    Interrupted,
+    /// Artifact served (or expired) and removed
+    Downloaded,
+}
+
+/// Progress report returned by `export.run` and `export.status`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub id: String,
+    pub format: String,
+    pub state: ExportState,
+    /// 0–100
+    pub progress: u8,
+    pub stage: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    /// One-time download path, set once the artifact is ready
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// `export.run` parameters.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExportRequest {
+    pub format: String,
+    /// Drop proposed / rejected LiDAR auto entities (ifc, gltf, csv, png, svg)
+    #[serde(default)]
+    pub approved_only: bool,
+    /// Floor level for png / svg (default: lowest floor)
+    #[serde(default)]
+    pub floor: Option<i32>,
+    /// png overlay
+    #[serde(default)]
+    pub overlay: FloorOverlay,
+}
+
+/// What a finished export job stores as its result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportResult {
+    filename: String,
+    size_bytes: u64,
+}
+
+fn artifact_path(repo_root: &Path, id: &str) -> PathBuf {
+    repo_root.join(EXPORTS_DIR).join(id)
+}
+
+/// Remove artifacts nobody fetched within [`ARTIFACT_TTL_SECS`].
+fn prune(repo_root: &Path) {
+    let Ok(entries) = fs::read_dir(repo_root.join(EXPORTS_DIR)) else {
+        return;
+    };
+    let Some(cutoff) = SystemTime::now().checked_sub(Duration::from_secs(ARTIFACT_TTL_SECS)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn progress(percent: u8, stage: &str) -> Result<()> {
+    jobs::report(percent, stage).map_err(|e| anyhow!(e))
+}
+
+/// The export view of job `record`, or `None` for other kinds of job.
+fn from_record(repo_root: &Path, record: JobRecord) -> Option<ExportJob> {
+    let format = record.description.strip_prefix(JOB_PREFIX)?.to_string();
+    let result: Option<ExportResult> = record
+        .result
+        .and_then(|value| serde_json::from_value(value).ok());
+    let state = match record.state {
+        JobState::Running => ExportState::Running,
+        JobState::Done if !artifact_path(repo_root, &record.id).exists() => ExportState::Downloaded,
+        JobState::Done => ExportState::Done,
+        JobState::Failed => ExportState::Failed,
+        JobState::Cancelled => ExportState::Cancelled,
+        JobState::Interrupted => ExportState::Interrupted,
+    };
+    Some(ExportJob {
+        download_url: (state == ExportState::Done).then(|| format!("/api/exports/{}", record.id)),
+        id: record.id,
+        format,
+        state,
+        progress: record.progress,
+        stage: record.stage,
+        created_at: record.created_at,
+        filename: result.as_ref().map(|r| r.filename.clone()),
+        size_bytes: result.map(|r| r.size_bytes),
+        error: record.error,
+    })
+}
+
+/// Start an export job; the artifact is written on a worker thread.
+pub fn start(repo_root: &Path, request: ExportRequest) -> Result<ExportJob> {
+    if !FORMATS.contains(&request.format.as_str()) {
+        bail!(
+            "Unsupported export format '{}'. Use: {}",
+            request.format,
+            FORMATS.join(", ")
+        );
+    }
+    prune(repo_root);
+    let root = repo_root.to_path_buf();
+    let description = format!("{}{}", JOB_PREFIX, request.format);
+    let record = jobs::spawn(repo_root, &description, move || {
+        let id = jobs::current().ok_or("export job is not bound to its thread")?;
+        let (filename, size_bytes) =
+            write_artifact(&root, &id, &request).map_err(|e| e.to_string())?;
+        serde_json::to_value(ExportResult {
+            filename,
+            size_bytes,
+        })
+        .map_err(|e| e.to_string())
+    })?;
+    from_record(repo_root, record).ok_or_else(|| anyhow!("export job was not recorded"))
+}
+
+/// Build the artifact for `request`; returns its download filename and size.
+fn write_artifact(repo_root: &Path, id: &str, request: &ExportRequest) -> Result<(String, u64)> {
+    progress(10, "loading building")?;
+    let mut building = load_building_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    let dir = repo_root.join(EXPORTS_DIR);
+    fs::create_dir_all(&dir)?;
+    let path = artifact_path(repo_root, id);
+
+    progress(40, &format!("exporting {}", request.format))?;
+    let filename = match request.format.as_str() {
+        "ifc" => {
+            crate::ifc::mapping::assign_missing_global_ids(&mut building);
+            let export_building =
+                crate::core::filter_building_for_export(&building, request.approved_only);
+            IFCExporter::new(export_building).export(&path)?;
+            format!("{}.ifc", building.name)
+        }
+        "gltf" => {
+            let export_building =
+                crate::core::filter_building_for_export(&building, request.approved_only);
+            let keep: std::collections::HashSet<String> = export_building
+                .get_all_rooms()
+                .iter()
+                .map(|r| r.id.clone())
+                .chain(
+                    export_building
+                        .get_all_equipment()
+                        .iter()
+                        .map(|e| e.id.clone()),
+                )
+                .collect();
+            let mut meshes = load_building_meshes(repo_root, &building)?;
+            meshes.rooms.retain(|m| keep.contains(&m.room_id));
+            meshes.equipment.retain(|m| keep.contains(&m.equipment_id));
+            let materials = MaterialSet::load_at(repo_root).map_err(|e| anyhow!(e.to_string()))?;
+            export_gltf(&building.name, &meshes, &materials, &path)?;
+            format!("{}.gltf", building.name)
+        }
+        "csv" => {
+            let export_building =
+                crate::core::filter_building_for_export(&building, request.approved_only);
+            fs::write(&path, equipment_to_csv(&export_building))?;
+            format!("{}-equipment.csv", building.name)
+        }
+        "png" | "svg" => {
+            let export_building =
+                crate::core::filter_building_for_export(&building, request.approved_only);
+            let level = request
+                .floor
+                .or_else(|| export_building.floors.iter().map(|f| f.level).min())
+                .ok_or_else(|| anyhow!("Building has no floors to draw"))?;
+            let options = FloorPngOptions {
+                overlay: request.overlay,
+                ..Default::default()
+            };
+            if request.format == "svg" {
+                fs::write(&path, render_floor_svg(&export_building, level, &options)?)?;
+            } else {
+                fs::write(&path, render_floor_png(&export_building, level, &options)?)?;
+            }
+            format!("{}-floor-{}.{}", building.name, level, request.format)
+        }
+        // Same as `arx export`: the stored model, not the filtered one
+        "yaml" => {
+            fs::write(&path, stored_yaml(repo_root)?)?;
+            BUILDING_YAML.to_string()
+        }
+        "json" => {
+            let yaml = stored_yaml(repo_root)?;
+            let value: serde_yaml::Value = serde_yaml::from_str(&yaml)?;
+            fs::write(&path, serde_json::to_string_pretty(&value)?)?;
+            "building.json".to_string()
+        }
+        other => bail!("Unsupported export format '{}'", other),
+    };
+
+    progress(90, "finalizing")?;
+    Ok((filename, fs::metadata(&path)?.len()))
+}
+
+/// The stored `building.yaml` text, from whichever backend holds it.
+fn stored_yaml(repo_root: &Path) -> Result<String> {
+    PersistenceManager::at(repo_root)
+        .read_building_yaml()?
+        .ok_or_else(|| anyhow!("No {} to export", BUILDING_YAML))
+}
+
+/// Current state of export job `id`.
+pub fn status(repo_root: &Path, id: &str) -> Result<Option<ExportJob>> {
+    let record = jobs::load(repo_root, id)?;
+    Ok(record.and_then(|record| from_record(repo_root, record)))
+}
+
+/// Hand out a finished artifact once: its job and bytes, after which the file
+/// is removed. Fails for unknown, unfinished, or already downloaded jobs.
+pub fn take_artifact(repo_root: &Path, id: &str) -> Result<(ExportJob, Vec<u8>)> {
+    let mut job = status(repo_root, id)?.ok_or_else(|| anyhow!("Unknown export job '{}'", id))?;
+    match job.state {
+        ExportState::Done => {}
+        ExportState::Downloaded => bail!("Export '{}' was already downloaded", id),
+        ExportState::Running => bail!("Export '{}' is not finished", id),
+        ExportState::Failed | ExportState::Cancelled | ExportState::Interrupted => {
+            bail!("Export '{}' {}", id, job.stage)
+        }
+    }
+    // Claim the artifact by renaming it, so concurrent downloads get it once
+    let path = artifact_path(repo_root, id);
+    let claimed = path.with_extension("taken");
+    fs::rename(&path, &claimed).map_err(|_| anyhow!("Export '{}' was already downloaded", id))?;
+    let bytes = fs::read(&claimed);
+    let _ = fs::remove_file(&claimed);
+    job.state = ExportState::Downloaded;
+    job.download_url = None;
+    Ok((job, bytes?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn run_reports_progress_and_serves_artifact_once() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        crate::persistence::PersistenceManager::at(tmp.path())
+            .save_building_unchecked(&building)
+            .unwrap();
+
+        let request = ExportRequest {
+            format: "csv".into(),
+            ..Default::default()
+        };
+        let job = start(tmp.path(), request).unwrap();
+        assert_eq!(job.state, ExportState::Running);
+        let mut done = None;
+        for _ in 0..200 {
+            let current = status(tmp.path(), &job.id).unwrap().unwrap();
+            if current.state != ExportState::Running {
+                done = Some(current);
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let done = done.expect("export did not finish");
+        assert_eq!(done.state, ExportState::Done);
+        assert_eq!(done.progress, 100);
+        assert_eq!(done.filename.as_deref(), Some("HQ-equipment.csv"));
+
+        let (_, bytes) = take_artifact(tmp.path(), &job.id).unwrap();
+        assert!(String::from_utf8(bytes).unwrap().contains("AHU-1"));
+        assert!(take_artifact(tmp.path(), &job.id).is_err());
+        // The job is on record in the shared registry
+        let record = jobs::load(tmp.path(), &job.id).unwrap().unwrap();
+        assert_eq!(record.state, JobState::Done);
+        assert_eq!(
+            status(tmp.path(), &job.id).unwrap().unwrap().state,
+            ExportState::Downloaded
+        );
+        assert!(start(tmp.path(), ExportRequest::default()).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use crate::core::clock;
 use crate::git::diff::DiffLineType;
 use crate::git::{BuildingGitManager, CommitMetadata, GitConfigManager};
 use anyhow::{anyhow, Context, Result};
@@ -47,7 +48,7 @@ impl SyncState {
     }
 
     pub fn update_after_export(&mut self, equipment: Vec<String>, rooms: Vec<String>) {
-        self.last_export_timestamp = chrono::Utc::now();
+        self.last_export_timestamp = clock::now();
         self.equipment_paths = equipment;
         self.rooms_paths = rooms;
     }
@@ -90,6 +91,14 @@ pub struct GitFileDiff {
     pub content: String,
 }
 
+#[derive(Serialize)]
+pub struct GitLogEntry {
+    pub commit_id: String,
+    pub message: String,
+    pub author: String,
+    pub time: i64,
+}
+
 #[derive(Serialize)]
 pub struct GitCommitResult {
     pub commit_id: String,
@@ -165,6 +174,30 @@ pub fn diff(repo_root: &Path, commit: Option<&str>, file: Option<&str>) -> Resul
     })
 }
 
+/// Newest-first commit history from HEAD, at most `limit` entries.
+pub fn log(repo_root: &Path, limit: usize) -> Result<Vec<GitLogEntry>> {
+    let config = GitConfigManager::default_config();
+    let repo_root_str = repo_root
+        .to_str()
+        .ok_or_else(|| anyhow!("Repository path is not valid UTF-8"))?;
+
+    let manager = BuildingGitManager::new(repo_root_str, "Workspace", config)
+        .context("Failed to open Git repository")?;
+    let commits = manager
+        .list_commits(limit)
+        .context("Failed to read commit history")?;
+
+    Ok(commits
+        .into_iter()
+        .map(|c| GitLogEntry {
+            commit_id: c.id,
+            message: c.message.trim_end().to_string(),
+            author: c.author,
+            time: c.time,
+        })
+        .collect())
+}
+
 pub fn commit(
     repo_root: &Path,
     message: &str,
@@ -320,4 +353,21 @@ mod tests {
         let payload = diff(&root, None, None).unwrap();
         assert!(payload.files_changed >= 1);
     }
+
+    #[test]
+    fn log_lists_newest_first_within_limit() {
+        let (_tmp, root, _guard) = setup_repo();
+        let file = root.join("notes.txt");
+        for (i, message) in ["First", "Second", "Third"].iter().enumerate() {
+            fs::write(&file, format!("rev {}", i)).unwrap();
+            commit(&root, message, true, DID_KEY).unwrap();
+        }
+
+        let entries = log(&root, 2).unwrap();
+        let messages: Vec<&str> = entries
+            .iter()
+            .filter_map(|e| e.message.lines().next())
+            .collect();
+        assert_eq!(messages, vec!["Third", "Second"]);
+    }
 }
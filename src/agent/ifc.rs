@@ -2,9 +2,11 @@ use std::fs;
 use std::path::Path;
 
 use crate::agent::git::SyncState;
+use crate::core::provenance::{ProvenanceGuard, ProvenanceSource};
 use crate::export::ifc::IFCExporter;
-use crate::ingest::import_ifc_path;
-use crate::persistence::{load_building_at, save_building_at, BUILDING_YAML};
+use crate::ingest::import_ifc_onto;
+use crate::persistence::jobs::report;
+use crate::persistence::{load_building_at, save_building_at, PersistenceManager, BUILDING_YAML};
 use crate::utils::path_safety::PathSafety;
 use anyhow::{anyhow, bail, Result};
 use base64::{engine::general_purpose, Engine as _};
@@ -40,6 +42,18 @@ pub fn import_ifc(repo_root: &Path, filename: &str, data_base64: &str) -> Result
 
     let sanitized_name = ensure_extension(&sanitize_filename(filename, "upload.ifc"), ".ifc");
     let imports_dir = repo_root.join("imports");
+
+    if crate::core::operations::is_dry_run() {
+        // Parse from a scratch copy so the simulated import leaves imports/ untouched
+        crate::core::operations::dry_run::record_file_write(
+            &format!("imports/{}", sanitized_name),
+            imports_dir.join(&sanitized_name).exists(),
+        );
+        let scratch = tempfile::Builder::new().suffix(".ifc").tempfile()?;
+        fs::write(scratch.path(), &bytes)?;
+        return finish_import(repo_root, scratch.path());
+    }
+
     fs::create_dir_all(&imports_dir)?;
 
     let import_path = imports_dir.join(&sanitized_name);
@@ -61,15 +75,14 @@ pub fn import_ifc_local(repo_root: &Path, ifc_path: &Path) -> Result<IfcImportRe
 
 /// Shared import pipeline via `ingest` (parse → merge → validate → write YAML).
 fn finish_import(repo_root: &Path, ifc_path: &Path) -> Result<IfcImportResult> {
-    // Prefer merging with an existing YAML named after the eventual building, if present
-    let building_yaml = repo_root.join("building.yaml");
-    let existing = if building_yaml.exists() {
-        Some(building_yaml.as_path())
-    } else {
-        None
-    };
+    // Merge onto the stored building, if there is one
+    let existing = PersistenceManager::at(repo_root)
+        .load_existing()
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
 
-    let result = import_ifc_path(ifc_path, existing, false, true)
+    report(10, "parsing IFC")?;
+    let ifc_path = &crate::git::lfs::resolve_path(ifc_path)?;
+    let result = import_ifc_onto(ifc_path, existing, false, true)
         .map_err(|e| anyhow!("IFC import failed: {}", e))?;
 
     if result.validation.has_errors() {
@@ -90,6 +103,8 @@ fn finish_import(repo_root: &Path, ifc_path: &Path) -> Result<IfcImportResult> {
         .sum();
     let equipment = building.get_all_equipment().len();
 
+    report(80, "writing building.yaml")?;
+    let _provenance = ProvenanceGuard::source(ProvenanceSource::IfcImport);
     save_building_at(repo_root, &building)
         .map_err(|e| anyhow!("Failed to write {}: {}", BUILDING_YAML, e))?;
 
@@ -186,6 +201,7 @@ pub fn export_ifc_with_options(
         .map_err(|e| anyhow!("Failed to save IFC sync state: {}", e))?;
 
     let bytes = fs::read(&ifc_path)?;
+    crate::persistence::artifacts::enforce(repo_root, "exports")?;
     let encoded = general_purpose::STANDARD.encode(&bytes);
 
     Ok(IfcExportResult {
@@ -280,6 +296,7 @@ mod tests {
         let room = Room {
             id: uuid::Uuid::new_v4().to_string(),
             name: "Room 101".to_string(),
+            aliases: Vec::new(),
             room_type: RoomType::Office,
             equipment: Vec::new(),
             spatial_properties: SpatialProperties::default(),
@@ -292,6 +309,9 @@ mod tests {
             address: None,
             anchors: Vec::new(),
             pending_anchor_ids: Vec::new(),
+            provenance: Default::default(),
+            vendor_extensions: Default::default(),
+            attachments: Vec::new(),
         };
         wing.rooms.push(room);
         floor.wings.push(wing);
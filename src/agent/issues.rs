@@ -0,0 +1,83 @@
+//! Occupant issue intake for the edge agent.
+//!
+//! `POST /api/issues` and the `issue.submit` action accept "problem in room X"
+//! reports from room pages or external forms; records land in `.arx/issues/`
+//! via the persistence layer. `GET /issues/new` serves a minimal report form.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+use crate::core::domain::issue::{Issue, IssueReport};
+use crate::persistence::issues::{load_issues, open_issues, submit_issue};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+
+/// Triage and store a report against the current building.
+pub fn submit(repo_root: &Path, report: &IssueReport) -> Result<Issue> {
+    let building = load_building_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    let issue = submit_issue(repo_root, &building, report)?;
+    tracing::info!(id = %issue.id, priority = %issue.priority, "Issue reported");
+    Ok(issue)
+}
+
+/// Stored issues (open only when `open_only`).
+pub fn list(repo_root: &Path, open_only: bool) -> Result<Vec<Issue>> {
+    Ok(if open_only {
+        open_issues(repo_root)?
+    } else {
+        load_issues(repo_root)?
+    })
+}
+
+/// Report form; `room` pre-fills the location (from a room page link).
+pub fn intake_form_html(room: Option<&str>) -> String {
+    let room = crate::export::site::escape_html(room.unwrap_or(""));
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Report a problem</title>
+<style>
+body {{ font-family: system-ui, sans-serif; margin: 1.5rem; max-width: 32rem; }}
+input, textarea {{ width: 100%; margin: 0.3rem 0 1rem; padding: 0.5rem; box-sizing: border-box; }}
+button {{ padding: 0.7rem 1.2rem; }}
+</style>
+</head>
+<body>
+<h1>Report a problem</h1>
+<form id="f">
+<label>Room<input name="room" value="{room}" required></label>
+<label>Equipment (optional)<input name="equipment"></label>
+<label>What's wrong?<textarea name="description" rows="5" required></textarea></label>
+<label>Your name / contact (optional)<input name="reporter"></label>
+<button type="submit">Send</button>
+</form>
+<p id="out"></p>
+<script>
+document.getElementById('f').addEventListener('submit', async (e) => {{
+  e.preventDefault();
+  const d = Object.fromEntries(new FormData(e.target));
+  d.source = 'room-page';
+  const r = await fetch('/api/issues', {{ method: 'POST', headers: {{ 'Content-Type': 'application/json' }}, body: JSON.stringify(d) }});
+  document.getElementById('out').textContent = r.ok ? 'Thanks — reported as ' + (await r.json()).id : 'Could not send: ' + await r.text();
+}});
+</script>
+</body>
+</html>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_escapes_prefilled_room() {
+        let html = intake_form_html(Some("\"><script>"));
+        assert!(html.contains("value=\"&quot;&gt;&lt;script&gt;\""));
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Provides a local WebSocket server for PWA integration when agent feature is enabled.
 
+#[cfg(feature = "agent")]
+pub mod ar;
 #[cfg(feature = "agent")]
 pub mod auth;
 #[cfg(feature = "agent")]
@@ -15,16 +17,36 @@ pub mod commands;
 #[cfg(feature = "agent")]
 pub mod discovery;
 #[cfg(feature = "agent")]
+pub mod exports;
+#[cfg(feature = "agent")]
 pub mod files;
 #[cfg(feature = "agent")]
 pub mod git;
 #[cfg(feature = "agent")]
 pub mod ifc;
 #[cfg(feature = "agent")]
+pub mod issues;
+#[cfg(feature = "agent")]
+pub mod model;
+#[cfg(feature = "agent")]
+pub mod rest;
+#[cfg(feature = "agent")]
+pub mod pagination;
+#[cfg(feature = "agent")]
+pub mod recorder;
+#[cfg(feature = "agent")]
+pub mod replica;
+#[cfg(feature = "agent")]
+pub mod sessions;
+#[cfg(feature = "agent")]
 pub mod ssh_auth;
 #[cfg(feature = "agent")]
 pub mod ssh_server;
 #[cfg(feature = "agent")]
+pub mod transfer;
+#[cfg(feature = "agent")]
+pub mod twin;
+#[cfg(feature = "agent")]
 pub mod watcher;
 #[cfg(feature = "agent")]
 pub mod workspace;
@@ -0,0 +1,417 @@
+//! Room and equipment edits and spatial queries (`room.*`, `equipment.*`,
+//! `spatial.query`).
+//!
+//! Rooms and equipment are addressed by id, name, or alias. Each edit is one
+//! validated save of `building.yaml`, so roles, approvals, provenance, and
+//! dry runs apply as they do for every other writer; nothing is committed.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::building::{equipment_rows, room_rows, EquipmentRow, RoomRow};
+use crate::core::operations::spatial_query;
+use crate::core::{Building, Equipment, EquipmentHealthStatus, Floor, Room, RoomType, Wing};
+use crate::ingest::equipment_csv::resolve_room;
+use crate::ingest::text::{parse_eq_type, parse_status};
+use crate::persistence::{load_building_at, save_building_at, BUILDING_YAML};
+
+/// `room.create` parameters.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoomCreate {
+    pub name: String,
+    pub floor_level: i32,
+    /// Wing name; created when missing (default `Default`)
+    #[serde(default)]
+    pub wing: Option<String>,
+    #[serde(default)]
+    pub room_type: Option<String>,
+    #[serde(default)]
+    pub position: Option<[f64; 3]>,
+}
+
+/// `room.update` parameters; unset fields are left alone.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoomUpdate {
+    /// Room id, name, or alias
+    pub room: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub room_type: Option<String>,
+    #[serde(default)]
+    pub position: Option<[f64; 3]>,
+    /// Merged into the room's properties
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+}
+
+/// `equipment.create` parameters.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EquipmentCreate {
+    pub name: String,
+    pub equipment_type: String,
+    /// Room id, name, or alias; without one the equipment sits on `floor_level`
+    #[serde(default)]
+    pub room: Option<String>,
+    #[serde(default)]
+    pub floor_level: Option<i32>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub position: Option<[f64; 3]>,
+}
+
+/// `equipment.update` parameters; unset fields are left alone.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EquipmentUpdate {
+    /// Equipment id, name, or alias
+    pub equipment: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub health_status: Option<String>,
+    #[serde(default)]
+    pub position: Option<[f64; 3]>,
+    /// Merged into the equipment's properties
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+}
+
+/// `spatial.query` parameters (see `core::operations::spatial_query`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpatialQuery {
+    /// `within_radius`, `nearest`, `within_bounds`, or `all`
+    pub query_type: String,
+    /// `room`, `equipment`, or empty for both
+    #[serde(default)]
+    pub entity: String,
+    #[serde(default)]
+    pub params: Vec<String>,
+}
+
+/// One `spatial.query` result.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpatialHit {
+    pub name: String,
+    pub entity_type: String,
+    pub position: [f64; 3],
+    pub distance: f64,
+}
+
+fn load(repo_root: &Path) -> Result<Building> {
+    load_building_at(repo_root).map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))
+}
+
+fn save(repo_root: &Path, building: &Building) -> Result<()> {
+    save_building_at(repo_root, building)
+        .map_err(|e| anyhow!("Failed to write {}: {}", BUILDING_YAML, e))
+}
+
+fn room_id(building: &Building, key: &str) -> Result<String> {
+    resolve_room(building, key, None)
+        .map(|(_, id, _)| id)
+        .map_err(|e| anyhow!("{}", e))
+}
+
+fn equipment_id(building: &Building, key: &str) -> Result<String> {
+    building
+        .find_equipment(key)
+        .map(|eq| eq.id.clone())
+        .ok_or_else(|| anyhow!("equipment '{}' not found", key))
+}
+
+fn room_row(building: &Building, id: &str) -> Result<RoomRow> {
+    room_rows(building)
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| anyhow!("room '{}' not found", id))
+}
+
+fn equipment_row(building: &Building, id: &str) -> Result<EquipmentRow> {
+    equipment_rows(building)
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| anyhow!("equipment '{}' not found", id))
+}
+
+fn parse_health(s: &str) -> Result<EquipmentHealthStatus> {
+    Ok(match s.trim().to_ascii_lowercase().as_str() {
+        "healthy" => EquipmentHealthStatus::Healthy,
+        "warning" => EquipmentHealthStatus::Warning,
+        "critical" => EquipmentHealthStatus::Critical,
+        "unknown" => EquipmentHealthStatus::Unknown,
+        other => bail!("unknown health status '{}'", other),
+    })
+}
+
+pub fn get_room(repo_root: &Path, key: &str) -> Result<RoomRow> {
+    let building = load(repo_root)?;
+    room_row(&building, &room_id(&building, key)?)
+}
+
+pub fn create_room(repo_root: &Path, params: RoomCreate) -> Result<RoomRow> {
+    if params.name.trim().is_empty() {
+        bail!("Room name is required");
+    }
+    let mut building = load(repo_root)?;
+    let room_type = match params.room_type.as_deref() {
+        Some(t) => t.parse::<RoomType>().map_err(|e| anyhow!("{}", e))?,
+        None => RoomType::Other("Unknown".into()),
+    };
+    let mut room = Room::new(params.name.trim().to_string(), room_type);
+    if let Some([x, y, z]) = params.position {
+        let position = &mut room.spatial_properties.position;
+        (position.x, position.y, position.z) = (x, y, z);
+    }
+    let id = room.id.clone();
+
+    if building.find_floor_mut(params.floor_level).is_none() {
+        let name = format!("Floor {}", params.floor_level);
+        building.add_floor(Floor::new(name, params.floor_level));
+    }
+    let floor = building
+        .find_floor_mut(params.floor_level)
+        .ok_or_else(|| anyhow!("floor {} not found", params.floor_level))?;
+    let wing_name = params.wing.as_deref().unwrap_or("Default");
+    if !floor.wings.iter().any(|w| w.name == wing_name) {
+        floor.add_wing(Wing::new(wing_name.to_string()));
+    }
+    floor
+        .wings
+        .iter_mut()
+        .find(|w| w.name == wing_name)
+        .ok_or_else(|| anyhow!("wing '{}' not found", wing_name))?
+        .add_room(room);
+    save(repo_root, &building)?;
+    room_row(&building, &id)
+}
+
+pub fn update_room(repo_root: &Path, params: RoomUpdate) -> Result<RoomRow> {
+    let mut building = load(repo_root)?;
+    let id = room_id(&building, &params.room)?;
+    let room = building
+        .find_room_mut(&id)
+        .ok_or_else(|| anyhow!("room '{}' not found", params.room))?;
+    if let Some(name) = params.name.filter(|n| !n.trim().is_empty()) {
+        room.name = name.trim().to_string();
+    }
+    if let Some(t) = params.room_type {
+        room.room_type = t.parse::<RoomType>().map_err(|e| anyhow!("{}", e))?;
+    }
+    if let Some([x, y, z]) = params.position {
+        let position = &mut room.spatial_properties.position;
+        (position.x, position.y, position.z) = (x, y, z);
+    }
+    room.properties.extend(params.properties);
+    save(repo_root, &building)?;
+    room_row(&building, &id)
+}
+
+/// Remove an empty room; returns it as it was.
+pub fn delete_room(repo_root: &Path, key: &str) -> Result<RoomRow> {
+    let mut building = load(repo_root)?;
+    let id = room_id(&building, key)?;
+    let row = room_row(&building, &id)?;
+    if row.equipment > 0 {
+        bail!(
+            "Room '{}' still has {} equipment item(s); move or delete them first",
+            row.name,
+            row.equipment
+        );
+    }
+    for floor in &mut building.floors {
+        for wing in &mut floor.wings {
+            wing.rooms.retain(|r| r.id != id);
+        }
+    }
+    save(repo_root, &building)?;
+    Ok(row)
+}
+
+pub fn get_equipment(repo_root: &Path, key: &str) -> Result<EquipmentRow> {
+    let building = load(repo_root)?;
+    equipment_row(&building, &equipment_id(&building, key)?)
+}
+
+pub fn create_equipment(repo_root: &Path, params: EquipmentCreate) -> Result<EquipmentRow> {
+    if params.name.trim().is_empty() {
+        bail!("Equipment name is required");
+    }
+    let mut building = load(repo_root)?;
+    let mut equipment = Equipment::new(
+        params.name.trim().to_string(),
+        String::new(),
+        parse_eq_type(&params.equipment_type)?,
+    );
+    if let Some(status) = &params.status {
+        equipment.status = parse_status(status)?;
+    }
+    if let Some([x, y, z]) = params.position {
+        let position = &mut equipment.position;
+        (position.x, position.y, position.z) = (x, y, z);
+    }
+    let id = equipment.id.clone();
+
+    match &params.room {
+        Some(room) => {
+            let (_, room_id, _) =
+                resolve_room(&building, room, params.floor_level).map_err(|e| anyhow!("{}", e))?;
+            equipment.room_id = Some(room_id.clone());
+            building
+                .find_room_mut(&room_id)
+                .ok_or_else(|| anyhow!("room '{}' not found", room))?
+                .add_equipment(equipment);
+        }
+        None => {
+            let level = params
+                .floor_level
+                .ok_or_else(|| anyhow!("Give a room or a floor_level"))?;
+            building
+                .find_floor_mut(level)
+                .ok_or_else(|| anyhow!("floor {} not found", level))?
+                .equipment
+                .push(equipment);
+        }
+    }
+    save(repo_root, &building)?;
+    equipment_row(&building, &id)
+}
+
+pub fn update_equipment(repo_root: &Path, params: EquipmentUpdate) -> Result<EquipmentRow> {
+    let mut building = load(repo_root)?;
+    let id = equipment_id(&building, &params.equipment)?;
+    let equipment = building
+        .find_equipment_mut(&id)
+        .ok_or_else(|| anyhow!("equipment '{}' not found", params.equipment))?;
+    if let Some(name) = params.name.filter(|n| !n.trim().is_empty()) {
+        equipment.name = name.trim().to_string();
+    }
+    if let Some(status) = &params.status {
+        equipment.status = parse_status(status)?;
+    }
+    if let Some(health) = &params.health_status {
+        equipment.health_status = Some(parse_health(health)?);
+    }
+    if let Some([x, y, z]) = params.position {
+        let position = &mut equipment.position;
+        (position.x, position.y, position.z) = (x, y, z);
+    }
+    equipment.properties.extend(params.properties);
+    save(repo_root, &building)?;
+    equipment_row(&building, &id)
+}
+
+/// Remove equipment; returns it as it was.
+pub fn delete_equipment(repo_root: &Path, key: &str) -> Result<EquipmentRow> {
+    let mut building = load(repo_root)?;
+    let id = equipment_id(&building, key)?;
+    let row = equipment_row(&building, &id)?;
+    for floor in &mut building.floors {
+        floor.equipment.retain(|e| e.id != id);
+        for wing in &mut floor.wings {
+            wing.equipment.retain(|e| e.id != id);
+            for room in &mut wing.rooms {
+                room.equipment.retain(|e| e.id != id);
+            }
+        }
+    }
+    save(repo_root, &building)?;
+    Ok(row)
+}
+
+pub fn spatial(repo_root: &Path, query: &SpatialQuery) -> Result<Vec<SpatialHit>> {
+    let building = load(repo_root)?;
+    let results = spatial_query(
+        &building,
+        &query.query_type,
+        &query.entity,
+        query.params.clone(),
+    )
+    .map_err(|e| anyhow!("{}", e))?;
+    Ok(results
+        .into_iter()
+        .map(|r| SpatialHit {
+            name: r.entity_name,
+            entity_type: r.entity_type,
+            position: [r.position.x, r.position.y, r.position.z],
+            distance: r.distance,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn room_and_equipment_crud_round_trip() {
+        let dir = tempdir().unwrap();
+        let mut b = Building::new("Pilot".into(), "/pilot".into());
+        b.add_floor(Floor::new("L1".into(), 0));
+        save_building_at(dir.path(), &b).unwrap();
+
+        let room = create_room(
+            dir.path(),
+            RoomCreate {
+                name: "Lab 1".into(),
+                floor_level: 0,
+                wing: None,
+                room_type: Some("laboratory".into()),
+                position: Some([4.0, 2.0, 0.0]),
+            },
+        )
+        .unwrap();
+        assert_eq!((room.wing_name.as_str(), room.position), ("Default", [4.0, 2.0, 0.0]));
+        let eq = create_equipment(
+            dir.path(),
+            EquipmentCreate {
+                name: "Fume Hood".into(),
+                equipment_type: "safety".into(),
+                room: Some("Lab 1".into()),
+                floor_level: None,
+                status: Some("active".into()),
+                position: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(eq.room_name.as_deref(), Some("Lab 1"));
+        assert!(delete_room(dir.path(), "Lab 1").is_err());
+
+        let updated = update_equipment(
+            dir.path(),
+            EquipmentUpdate {
+                equipment: "Fume Hood".into(),
+                name: None,
+                status: Some("maintenance".into()),
+                health_status: Some("warning".into()),
+                position: None,
+                properties: HashMap::from([("serial".into(), "FH-9".into())]),
+            },
+        )
+        .unwrap();
+        assert_eq!(updated.status, "Maintenance");
+        let stored = load(dir.path()).unwrap();
+        assert_eq!(stored.find_equipment(&eq.id).unwrap().properties["serial"], "FH-9");
+
+        let hits = spatial(
+            dir.path(),
+            &SpatialQuery {
+                query_type: "all".into(),
+                entity: "room".into(),
+                params: vec![],
+            },
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        delete_equipment(dir.path(), &eq.id).unwrap();
+        assert_eq!(delete_room(dir.path(), &room.id).unwrap().name, "Lab 1");
+        assert!(get_room(dir.path(), "Lab 1").is_err());
+    }
+}
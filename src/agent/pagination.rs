@@ -0,0 +1,360 @@
+//! Pagination and sorting shared by every agent list action.
+//!
+//! List handlers return their full result as a JSON array; the dispatcher
+//! sorts and slices it here and wraps the page in a [`Page`] envelope, so no
+//! list action can send an unbounded message and every one accepts the same
+//! parameters:
+//!
+//! - `page_size` — items per page (default [`DEFAULT_PAGE_SIZE`], capped at
+//!   [`MAX_PAGE_SIZE`])
+//! - `cursor` — `next_cursor` from the previous page
+//! - `sort` — one of the action's sort fields (see [`LIST_ACTIONS`])
+//! - `order` — `asc` (default) or `desc`
+//!
+//! Cursors are opaque offsets tied to the sort they were issued for; items
+//! added or removed between pages can shift the window by that many rows.
+
+use std::cmp::Ordering;
+
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Items per page when `page_size` is not given.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page a client can ask for.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+const CURSOR_VERSION: &str = "v1";
+
+/// A paginated action and the item fields it can be sorted by.
+#[derive(Debug, Clone, Copy)]
+pub struct ListAction {
+    pub method: &'static str,
+    /// Allowed `sort` values; the first is the default
+    pub sort_fields: &'static [&'static str],
+}
+
+/// Every list action; the dispatcher paginates exactly these.
+pub const LIST_ACTIONS: &[ListAction] = &[
+    ListAction {
+        method: "ar.pending.list",
+        sort_fields: &["submitted_at", "scan_id", "name", "state"],
+    },
+    ListAction {
+        method: "building.list",
+        sort_fields: &["name", "path"],
+    },
+    ListAction {
+        method: "claim.list_pending",
+        sort_fields: &["index", "timestamp", "building_id", "contributor"],
+    },
+    ListAction {
+        method: "equipment.list",
+        sort_fields: &[
+            "name",
+            "equipment_type",
+            "floor_level",
+            "room_name",
+            "status",
+            "runtime_hours",
+        ],
+    },
+    ListAction {
+        method: "issue.list",
+        sort_fields: &["created_at", "status", "floor_level", "room_name"],
+    },
+    ListAction {
+        method: "job.list",
+        sort_fields: &["created_at", "state", "description"],
+    },
+    ListAction {
+        method: "room.list",
+        sort_fields: &["name", "room_type", "floor_level", "wing_name"],
+    },
+];
+
+/// The list action registered for `method`, if any.
+pub fn list_action(method: &str) -> Option<&'static ListAction> {
+    LIST_ACTIONS.iter().find(|a| a.method == method)
+}
+
+/// Sort direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn as_str(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+/// Validated paging parameters for one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRequest {
+    pub page_size: usize,
+    pub offset: usize,
+    pub sort: &'static str,
+    pub order: SortOrder,
+}
+
+impl PageRequest {
+    /// Read `page_size`, `cursor`, `sort`, and `order` from `params`.
+    pub fn from_params(action: &ListAction, params: &Value) -> Result<Self> {
+        let page_size = match params.get("page_size") {
+            None | Some(Value::Null) => DEFAULT_PAGE_SIZE,
+            Some(v) => match v.as_u64() {
+                Some(n) if n > 0 => (n as usize).min(MAX_PAGE_SIZE),
+                _ => bail!("'page_size' must be a positive integer"),
+            },
+        };
+        let sort = match params.get("sort").and_then(|v| v.as_str()) {
+            None => action.sort_fields[0],
+            Some(s) => action
+                .sort_fields
+                .iter()
+                .copied()
+                .find(|f| *f == s)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Cannot sort '{}' by '{}' (expected one of: {})",
+                        action.method,
+                        s,
+                        action.sort_fields.join(", ")
+                    )
+                })?,
+        };
+        let order = match params.get("order").and_then(|v| v.as_str()) {
+            None | Some("asc") => SortOrder::Asc,
+            Some("desc") => SortOrder::Desc,
+            Some(o) => bail!("'order' must be 'asc' or 'desc', got '{}'", o),
+        };
+        let offset = match params.get("cursor").and_then(|v| v.as_str()) {
+            None => 0,
+            Some(cursor) => decode_cursor(cursor, sort, order)?,
+        };
+        Ok(Self {
+            page_size,
+            offset,
+            sort,
+            order,
+        })
+    }
+}
+
+fn encode_cursor(offset: usize, sort: &str, order: SortOrder) -> String {
+    URL_SAFE_NO_PAD.encode(format!(
+        "{}:{}:{}:{}",
+        CURSOR_VERSION,
+        offset,
+        sort,
+        order.as_str()
+    ))
+}
+
+fn decode_cursor(cursor: &str, sort: &str, order: SortOrder) -> Result<usize> {
+    let invalid = || anyhow!("Invalid 'cursor'");
+    let raw = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+    let mut parts = raw.splitn(4, ':');
+    if parts.next() != Some(CURSOR_VERSION) {
+        return Err(invalid());
+    }
+    let offset = parts
+        .next()
+        .and_then(|o| o.parse().ok())
+        .ok_or_else(invalid)?;
+    if parts.next() != Some(sort) || parts.next() != Some(order.as_str()) {
+        bail!("'cursor' was issued for a different sort; start again without it");
+    }
+    Ok(offset)
+}
+
+/// One page of a list result.
+#[derive(Debug, Clone, Serialize)]
+pub struct Page {
+    pub items: Vec<Value>,
+    /// Items across all pages
+    pub total: usize,
+    pub page_size: usize,
+    /// Pass as `cursor` for the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    pub sort: &'static str,
+    pub order: SortOrder,
+}
+
+/// Missing fields and nulls sort last in ascending order.
+fn compare_field(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (None | Some(Value::Null), None | Some(Value::Null)) => Ordering::Equal,
+        (None | Some(Value::Null), _) => Ordering::Greater,
+        (_, None | Some(Value::Null)) => Ordering::Less,
+        (Some(Value::Number(x)), Some(Value::Number(y))) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(x)), Some(Value::String(y))) => x.to_lowercase().cmp(&y.to_lowercase()),
+        (Some(Value::Bool(x)), Some(Value::Bool(y))) => x.cmp(y),
+        (Some(x), Some(y)) => x.to_string().cmp(&y.to_string()),
+    }
+}
+
+/// Sort `result` (a JSON array) and cut out the requested page.
+pub fn paginate(result: Value, request: &PageRequest) -> Result<Page> {
+    let Value::Array(mut items) = result else {
+        bail!("list action returned a non-array result");
+    };
+    // Stable sort: equal keys keep the handler's order
+    items.sort_by(|a, b| {
+        let ord = compare_field(a.get(request.sort), b.get(request.sort));
+        match request.order {
+            SortOrder::Asc => ord,
+            SortOrder::Desc => ord.reverse(),
+        }
+    });
+    let total = items.len();
+    let end = request.offset.saturating_add(request.page_size).min(total);
+    let page: Vec<Value> = items
+        .into_iter()
+        .skip(request.offset)
+        .take(end.saturating_sub(request.offset))
+        .collect();
+    Ok(Page {
+        items: page,
+        total,
+        page_size: request.page_size,
+        next_cursor: (end < total).then(|| encode_cursor(end, request.sort, request.order)),
+        sort: request.sort,
+        order: request.order,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pages_follow_cursor_and_sort() {
+        let action = list_action("room.list").unwrap();
+        let rooms: Vec<Value> = (0..7)
+            .map(|i| json!({ "name": format!("R{}", 6 - i), "floor_level": i % 3 }))
+            .collect();
+
+        let params = json!({ "page_size": 3, "sort": "name" });
+        let first = PageRequest::from_params(action, &params).unwrap();
+        let page = paginate(Value::Array(rooms.clone()), &first).unwrap();
+        assert_eq!((page.total, page.items.len()), (7, 3));
+        assert_eq!(page.items[0]["name"], "R0");
+
+        let mut names = Vec::new();
+        let mut cursor = None;
+        loop {
+            let mut params = json!({ "page_size": 3, "sort": "name" });
+            if let Some(c) = cursor {
+                params["cursor"] = json!(c);
+            }
+            let request = PageRequest::from_params(action, &params).unwrap();
+            let page = paginate(Value::Array(rooms.clone()), &request).unwrap();
+            names.extend(
+                page.items
+                    .iter()
+                    .map(|r| r["name"].as_str().unwrap().to_string()),
+            );
+            match page.next_cursor {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+        assert_eq!(names, ["R0", "R1", "R2", "R3", "R4", "R5", "R6"]);
+
+        let desc = json!({ "sort": "floor_level", "order": "desc" });
+        let request = PageRequest::from_params(action, &desc).unwrap();
+        let page = paginate(Value::Array(rooms), &request).unwrap();
+        assert_eq!(page.items[0]["floor_level"], 2);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn rejects_bad_parameters_and_caps_page_size() {
+        let action = list_action("issue.list").unwrap();
+        let big = PageRequest::from_params(action, &json!({ "page_size": 100_000 })).unwrap();
+        assert_eq!(big.page_size, MAX_PAGE_SIZE);
+        assert_eq!(big.sort, "created_at");
+        assert!(PageRequest::from_params(action, &json!({ "page_size": 0 })).is_err());
+        assert!(PageRequest::from_params(action, &json!({ "sort": "description" })).is_err());
+        assert!(PageRequest::from_params(action, &json!({ "order": "up" })).is_err());
+        assert!(PageRequest::from_params(action, &json!({ "cursor": "nope" })).is_err());
+
+        let cursor = encode_cursor(50, "created_at", SortOrder::Asc);
+        let other_sort = json!({ "cursor": cursor, "sort": "status" });
+        assert!(PageRequest::from_params(action, &other_sort).is_err());
+        let same = json!({ "cursor": cursor });
+        assert_eq!(PageRequest::from_params(action, &same).unwrap().offset, 50);
+    }
+
+    #[test]
+    fn dispatcher_pages_list_actions() {
+        use crate::agent::auth::TokenState;
+        use crate::agent::dispatcher::{dispatch, AgentState};
+        use crate::agent::protocol::{JsonRpcRequest, INVALID_PARAMS};
+        use crate::core::{Building, Floor, Room, RoomType, Wing};
+        use std::sync::{Arc, Mutex};
+
+        let project = tempfile::tempdir().unwrap();
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("L1".into(), 1);
+        let mut wing = Wing::new("A".into());
+        for name in ["C", "A", "B"] {
+            wing.add_room(Room::new(name.into(), RoomType::Office));
+        }
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        crate::persistence::save_building_at(project.path(), &building).unwrap();
+
+        let state = Arc::new(AgentState {
+            repo_root: project.path().to_path_buf(),
+            token: Arc::new(Mutex::new(TokenState::new(
+                "t".into(),
+                vec!["building.get".into()],
+            ))),
+            metrics: Arc::new(crate::agent::observability::AgentMetrics::new()),
+            reload_handle: None,
+            sessions: Default::default(),
+        });
+        let call = |params: Value| {
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".into(),
+                method: "room.list".into(),
+                params: Some(params),
+                id: Some(json!(1)),
+            };
+            tokio::runtime::Runtime::new()
+                .unwrap()
+                .block_on(dispatch(state.clone(), request))
+        };
+
+        let page = call(json!({ "page_size": 2 })).result.unwrap();
+        assert_eq!(page["total"], 3);
+        assert_eq!(page["items"][0]["name"], "A");
+        assert_eq!(page["items"].as_array().unwrap().len(), 2);
+        let rest = call(json!({ "page_size": 2, "cursor": page["next_cursor"] }))
+            .result
+            .unwrap();
+        assert_eq!(rest["items"][0]["name"], "C");
+        assert!(rest.get("next_cursor").is_none());
+
+        let error = call(json!({ "sort": "area" })).error.unwrap();
+        assert_eq!(error.code, INVALID_PARAMS);
+    }
+}
@@ -48,6 +48,20 @@ impl JsonRpcResponse {
     }
 }
 
+impl JsonRpcError {
+    /// The action named something that does not exist (`room 'X' not found`).
+    pub fn is_not_found(&self) -> bool {
+        self.code != METHOD_NOT_FOUND && self.message.contains("not found")
+    }
+
+    /// Missing or malformed parameters, whether caught before or by the handler.
+    pub fn is_invalid_params(&self) -> bool {
+        self.code == INVALID_PARAMS
+            || self.message.starts_with("Missing")
+            || self.message.starts_with("Invalid")
+    }
+}
+
 // Error codes
 pub const PARSE_ERROR: i32 = -32700;
 pub const INVALID_REQUEST: i32 = -32600;
@@ -55,3 +69,5 @@ pub const METHOD_NOT_FOUND: i32 = -32601;
 pub const INVALID_PARAMS: i32 = -32602;
 pub const INTERNAL_ERROR: i32 = -32603;
 pub const AUTH_ERROR: i32 = -32001;
+/// Mutating action sent to a standby replica
+pub const READ_ONLY_ERROR: i32 = -32002;
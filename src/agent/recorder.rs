@@ -0,0 +1,472 @@
+//! Opt-in session recording and replay for agent JSON-RPC traffic.
+//!
+//! Set `ARX_AGENT_RECORD=<file>` when starting the agent to append every
+//! request/response pair (WebSocket and `/rpc`) to a JSON-lines session file.
+//! Payloads pass through [`RedactionRules`] before they hit disk: the default
+//! rules blank credential-like keys, and `.arx/agent/redaction.yaml` can add
+//! more keys (e.g. `data` to drop uploaded IFC bodies).
+//!
+//! `arx agent replay <file> --workspace <dir>` seeds a scratch workspace from
+//! the building snapshot taken at session start, re-dispatches each request,
+//! and reports where the responses diverge from the recording. Each request
+//! runs with the clock frozen at its recorded time and sequential ids, so
+//! replaying the same session twice writes the same timestamps and ids.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agent::auth::Caller;
+use crate::agent::dispatcher::{dispatch, dispatch_as, AgentState};
+use crate::agent::observability::redact_secrets;
+use crate::agent::protocol::{JsonRpcRequest, JsonRpcResponse};
+use crate::core::clock::{self, ClockGuard};
+use crate::persistence::{PersistenceManager, BUILDING_YAML};
+
+/// Environment variable that enables recording.
+pub const RECORD_ENV: &str = "ARX_AGENT_RECORD";
+
+/// Extra redaction rules under the repo root.
+pub const REDACTION_RULES_FILE: &str = ".arx/agent/redaction.yaml";
+
+/// Placeholder written in place of redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Session file format version.
+const SESSION_VERSION: u32 = 1;
+
+/// Keys whose values legitimately differ between a recording and its replay.
+const VOLATILE_KEYS: &[&str] = &[
+    "id",
+    "commit_id",
+    "commit_hash",
+    "compare_hash",
+    "last_commit",
+    "last_commit_time",
+    "created_at",
+    "updated_at",
+    "first_seen",
+    "last_seen",
+    "timestamp",
+];
+
+static RECORDER: OnceLock<SessionRecorder> = OnceLock::new();
+
+/// Which payload fields are blanked before recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRules {
+    /// Object keys (case-insensitive) whose values are replaced anywhere in a payload
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// Truncate longer string values (bytes); unset keeps strings whole
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_string_len: Option<usize>,
+}
+
+impl Default for RedactionRules {
+    fn default() -> Self {
+        Self {
+            keys: [
+                "token",
+                "authorization",
+                "password",
+                "secret",
+                "private_key",
+                "signature",
+                "did_key",
+            ]
+            .iter()
+            .map(|k| k.to_string())
+            .collect(),
+            max_string_len: None,
+        }
+    }
+}
+
+impl RedactionRules {
+    /// Default rules plus any keys from `.arx/agent/redaction.yaml`.
+    pub fn load(repo_root: &Path) -> Result<Self> {
+        let mut rules = Self::default();
+        let path = repo_root.join(REDACTION_RULES_FILE);
+        if path.exists() {
+            let extra: RedactionRules = serde_yaml::from_str(&std::fs::read_to_string(&path)?)
+                .with_context(|| format!("invalid {}", REDACTION_RULES_FILE))?;
+            rules.keys.extend(extra.keys);
+            rules.max_string_len = extra.max_string_len.or(rules.max_string_len);
+        }
+        Ok(rules)
+    }
+
+    fn is_sensitive(&self, key: &str) -> bool {
+        self.keys.iter().any(|k| k.eq_ignore_ascii_case(key))
+    }
+
+    /// Copy of `value` with sensitive keys blanked and secrets scrubbed from strings.
+    pub fn apply(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| {
+                        let v = if self.is_sensitive(k) {
+                            Value::String(REDACTED.to_string())
+                        } else {
+                            self.apply(v)
+                        };
+                        (k.clone(), v)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.apply(v)).collect()),
+            Value::String(s) => {
+                let mut s = redact_secrets(s);
+                if let Some(max) = self.max_string_len.filter(|max| s.len() > *max) {
+                    let mut cut = max;
+                    while !s.is_char_boundary(cut) {
+                        cut -= 1;
+                    }
+                    s.truncate(cut);
+                    s.push_str("…[TRUNCATED]");
+                }
+                Value::String(s)
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+/// One line of a session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEntry {
+    /// First line: format version and the building the session started from.
+    Start {
+        version: u32,
+        started_at: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        building_yaml: Option<String>,
+    },
+    Exchange(Box<RecordedExchange>),
+}
+
+/// A recorded request/response pair (after redaction).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    /// `ws` or `rpc`
+    pub transport: String,
+    pub duration_ms: u64,
+    pub request: JsonRpcRequest,
+    pub response: JsonRpcResponse,
+}
+
+/// Appends exchanges to a session file.
+pub struct SessionRecorder {
+    path: PathBuf,
+    rules: RedactionRules,
+    inner: Mutex<(File, u64)>,
+}
+
+impl SessionRecorder {
+    /// Create (truncate) `path` and write the start entry with a snapshot of `repo_root`'s building.
+    pub fn create(path: &Path, repo_root: &Path, rules: RedactionRules) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .with_context(|| format!("creating session file {}", path.display()))?;
+        let start = SessionEntry::Start {
+            version: SESSION_VERSION,
+            started_at: clock::now(),
+            building_yaml: PersistenceManager::at(repo_root)
+                .read_building_yaml()
+                .ok()
+                .flatten(),
+        };
+        writeln!(file, "{}", serde_json::to_string(&start)?)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            rules,
+            inner: Mutex::new((file, 0)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Redact and append one exchange.
+    pub fn record(
+        &self,
+        transport: &str,
+        request: &JsonRpcRequest,
+        response: &JsonRpcResponse,
+        duration_ms: u64,
+    ) -> Result<()> {
+        let mut request = request.clone();
+        request.params = request.params.as_ref().map(|p| self.rules.apply(p));
+        let response: JsonRpcResponse =
+            serde_json::from_value(self.rules.apply(&serde_json::to_value(response)?))?;
+
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| anyhow!("recorder poisoned"))?;
+        inner.1 += 1;
+        let entry = SessionEntry::Exchange(Box::new(RecordedExchange {
+            seq: inner.1,
+            at: clock::now(),
+            transport: transport.to_string(),
+            duration_ms,
+            request,
+            response,
+        }));
+        writeln!(inner.0, "{}", serde_json::to_string(&entry)?)?;
+        inner.0.flush()?;
+        Ok(())
+    }
+}
+
+/// Enable recording for this process when [`RECORD_ENV`] is set.
+pub fn install_from_env(repo_root: &Path) -> Result<Option<&'static SessionRecorder>> {
+    let Some(path) = std::env::var_os(RECORD_ENV).filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    let recorder = SessionRecorder::create(
+        Path::new(&path),
+        repo_root,
+        RedactionRules::load(repo_root)?,
+    )?;
+    RECORDER
+        .set(recorder)
+        .map_err(|_| anyhow!("session recorder already installed"))?;
+    Ok(RECORDER.get())
+}
+
+/// Dispatch `request` and record the exchange when recording is enabled.
+pub async fn dispatch_recorded(
+    state: Arc<AgentState>,
+    request: JsonRpcRequest,
+    transport: &str,
+    caller: &Caller,
+) -> JsonRpcResponse {
+    let Some(recorder) = RECORDER.get() else {
+        return dispatch_as(state, request, caller).await;
+    };
+    let started = Instant::now();
+    let response = dispatch_as(state, request.clone(), caller).await;
+    let elapsed = started.elapsed().as_millis() as u64;
+    if let Err(e) = recorder.record(transport, &request, &response, elapsed) {
+        tracing::warn!(error = %e, "Failed to record agent exchange");
+    }
+    response
+}
+
+/// A parsed session file.
+#[derive(Debug, Clone)]
+pub struct RecordedSession {
+    pub started_at: DateTime<Utc>,
+    pub building_yaml: Option<String>,
+    pub exchanges: Vec<RecordedExchange>,
+}
+
+/// Read a session file written by [`SessionRecorder`].
+pub fn load_session(path: &Path) -> Result<RecordedSession> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut session: Option<RecordedSession> = None;
+    for (n, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: SessionEntry = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: invalid session entry", path.display(), n + 1))?;
+        match (entry, session.as_mut()) {
+            (
+                SessionEntry::Start {
+                    version,
+                    started_at,
+                    building_yaml,
+                },
+                None,
+            ) => {
+                if version > SESSION_VERSION {
+                    bail!("session format v{} is newer than this build", version);
+                }
+                session = Some(RecordedSession {
+                    started_at,
+                    building_yaml,
+                    exchanges: Vec::new(),
+                });
+            }
+            (SessionEntry::Exchange(exchange), Some(s)) => s.exchanges.push(*exchange),
+            _ => bail!("{}:{}: unexpected session entry", path.display(), n + 1),
+        }
+    }
+    session.ok_or_else(|| anyhow!("{} is empty", path.display()))
+}
+
+/// Seed `workspace` with the session's starting building and a Git repo.
+pub fn prepare_workspace(workspace: &Path, session: &RecordedSession) -> Result<()> {
+    std::fs::create_dir_all(workspace)?;
+    let yaml = workspace.join(BUILDING_YAML);
+    if let (false, Some(snapshot)) = (yaml.exists(), &session.building_yaml) {
+        std::fs::write(&yaml, snapshot)?;
+    }
+    if git2::Repository::open(workspace).is_err() {
+        git2::Repository::init(workspace)?;
+    }
+    Ok(())
+}
+
+/// Result of replaying one exchange.
+#[derive(Debug, Clone)]
+pub struct ReplayStep {
+    pub seq: u64,
+    pub method: String,
+    /// `None` when the replayed response matches the recording
+    pub divergence: Option<(Value, Value)>,
+}
+
+/// Re-dispatch every recorded request against `state` (a scratch workspace).
+pub async fn replay_session(state: Arc<AgentState>, session: &RecordedSession) -> Vec<ReplayStep> {
+    let mut steps = Vec::with_capacity(session.exchanges.len());
+    for exchange in &session.exchanges {
+        // Pin time and ids to the recording so reruns produce identical output
+        let _clock = ClockGuard::deterministic(exchange.at, exchange.seq);
+        let actual = dispatch(state.clone(), exchange.request.clone()).await;
+        let expected = serde_json::to_value(&exchange.response).unwrap_or(Value::Null);
+        let actual = serde_json::to_value(&actual).unwrap_or(Value::Null);
+        steps.push(ReplayStep {
+            seq: exchange.seq,
+            method: exchange.request.method.clone(),
+            divergence: (!responses_match(&expected, &actual)).then_some((expected, actual)),
+        });
+    }
+    steps
+}
+
+/// Structural match where redacted expectations and volatile keys match anything.
+pub fn responses_match(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::String(s), _) if s == REDACTED => true,
+        (Value::Object(e), Value::Object(a)) => {
+            let keys = e.keys().chain(a.keys());
+            keys.filter(|k| !VOLATILE_KEYS.contains(&k.as_str()))
+                .all(|k| match (e.get(k), a.get(k)) {
+                    (Some(ev), Some(av)) => responses_match(ev, av),
+                    (None, None) => true,
+                    _ => false,
+                })
+        }
+        (Value::Array(e), Value::Array(a)) => {
+            e.len() == a.len() && e.iter().zip(a).all(|(ev, av)| responses_match(ev, av))
+        }
+        _ => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::auth::TokenState;
+    use serde_json::json;
+
+    fn request(method: &str, params: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".into(),
+            method: method.into(),
+            params: Some(params),
+            id: Some(json!(1)),
+        }
+    }
+
+    #[test]
+    fn redaction_blanks_keys_and_secrets() {
+        let rules = RedactionRules {
+            max_string_len: Some(10),
+            ..RedactionRules::default()
+        };
+        let redacted = rules.apply(&json!({
+            "Token": "abc",
+            "nested": [{"private_key": "k", "note": "hello world"}],
+            "hash": "a".repeat(64),
+        }));
+        assert_eq!(redacted["Token"], REDACTED);
+        assert_eq!(redacted["nested"][0]["private_key"], REDACTED);
+        assert_eq!(redacted["nested"][0]["note"], "hello worl…[TRUNCATED]");
+        assert_eq!(redacted["hash"], REDACTED);
+    }
+
+    #[test]
+    fn matching_ignores_volatile_and_redacted_values() {
+        let expected =
+            json!({"result": {"commit_id": "abc", "staged_files": 1, "token": REDACTED}});
+        let same = json!({"result": {"commit_id": "def", "staged_files": 1, "token": "x"}});
+        let other = json!({"result": {"commit_id": "def", "staged_files": 2, "token": "x"}});
+        assert!(responses_match(&expected, &same));
+        assert!(!responses_match(&expected, &other));
+    }
+
+    #[test]
+    fn recorded_session_replays_against_scratch_workspace() {
+        let project = tempfile::tempdir().unwrap();
+        let building = crate::core::Building::new("HQ".into(), "/hq".into());
+        crate::persistence::save_building_at(project.path(), &building).unwrap();
+        let session_path = project.path().join("session.jsonl");
+
+        let recorder =
+            SessionRecorder::create(&session_path, project.path(), RedactionRules::default())
+                .unwrap();
+        let state = |root: &Path| {
+            Arc::new(AgentState {
+                repo_root: root.to_path_buf(),
+                token: Arc::new(Mutex::new(TokenState::new(
+                    "t".into(),
+                    vec!["sensor.write".into(), "building.get".into()],
+                ))),
+                metrics: Arc::new(crate::agent::observability::AgentMetrics::new()),
+                reload_handle: None,
+                sessions: Default::default(),
+            })
+        };
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        for req in [
+            request(
+                "sensor.report",
+                json!({"sensor_id": "zb-1", "token": "s3cret"}),
+            ),
+            request("building.get", json!({})),
+        ] {
+            let response = rt.block_on(dispatch(state(project.path()), req.clone()));
+            recorder.record("ws", &req, &response, 0).unwrap();
+        }
+        let raw = std::fs::read_to_string(&session_path).unwrap();
+        assert!(!raw.contains("s3cret"));
+
+        let session = load_session(&session_path).unwrap();
+        assert_eq!(session.exchanges.len(), 2);
+        let scratch = tempfile::tempdir().unwrap();
+        prepare_workspace(scratch.path(), &session).unwrap();
+        let steps = rt.block_on(replay_session(state(scratch.path()), &session));
+        assert_eq!(steps.len(), 2);
+        assert!(steps.iter().all(|s| s.divergence.is_none()), "{:?}", steps);
+        assert!(scratch
+            .path()
+            .join(crate::persistence::sensors::SENSOR_INBOX)
+            .exists());
+    }
+}
@@ -0,0 +1,479 @@
+//! Standby read replica.
+//!
+//! A second agent can run as a hot spare of the building data service. While
+//! `.arx/agent/replica.yaml` marks the workspace as a replica, the agent pulls
+//! the configured Git remote on an interval, fast-forwards its working tree,
+//! and only serves read-only actions. `arx agent promote` stamps the file as
+//! promoted; the running agent notices, stops pulling, and accepts writes.
+//!
+//! ```yaml
+//! remote: origin
+//! branch: main          # default: the checked-out branch
+//! interval_secs: 15
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use git2::build::CheckoutBuilder;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::core::clock;
+
+/// Repo-relative replica role file.
+pub const REPLICA_FILE: &str = ".arx/agent/replica.yaml";
+
+/// Actions a standby replica serves; everything else is refused until promotion.
+pub const READ_ONLY_ACTIONS: &[&str] = &[
+    "git.status",
+    "git.diff",
+    "git.log",
+    "files.read",
+    "building.get",
+    "building.list",
+    "equipment.list",
+    "room.list",
+    "twin.snapshot",
+    "claim.list_pending",
+    "claim.get_status",
+    "issue.list",
+    "collab.config.get",
+    "transfer.download",
+    "transfer.fetch",
+    "transfer.status",
+    "session.list",
+    "command.catalog",
+    "command.status",
+    "ar.pending.list",
+    "room.get",
+    "equipment.get",
+    "spatial.query",
+    "job.status",
+    "job.list",
+];
+
+const DEFAULT_REMOTE: &str = "origin";
+const DEFAULT_SYNC_INTERVAL_SECS: u64 = 15;
+
+/// Health reports `degraded` once the last good pull is this many intervals old.
+const STALE_INTERVALS: i64 = 3;
+
+static REPLICA: OnceLock<Replica> = OnceLock::new();
+
+/// Contents of [`REPLICA_FILE`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplicaConfig {
+    #[serde(default = "default_remote")]
+    pub remote: String,
+    /// Branch to follow (default: the checked-out branch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default = "default_interval")]
+    pub interval_secs: u64,
+    /// Set by `arx agent promote`; the workspace is read-write from then on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promoted_at: Option<DateTime<Utc>>,
+}
+
+fn default_remote() -> String {
+    DEFAULT_REMOTE.to_string()
+}
+
+fn default_interval() -> u64 {
+    DEFAULT_SYNC_INTERVAL_SECS
+}
+
+impl ReplicaConfig {
+    pub fn new(remote: &str, branch: Option<String>, interval_secs: u64) -> Self {
+        Self {
+            remote: remote.to_string(),
+            branch,
+            interval_secs: interval_secs.max(1),
+            promoted_at: None,
+        }
+    }
+
+    /// The replica file under `base`, if any.
+    pub fn load(base: &Path) -> Result<Option<Self>> {
+        let path = base.join(REPLICA_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, base: &Path) -> Result<()> {
+        let path = base.join(REPLICA_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_yaml::to_string(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    pub fn is_standby(&self) -> bool {
+        self.promoted_at.is_none()
+    }
+}
+
+/// Mark the replica under `base` as promoted (read-write).
+pub fn promote(base: &Path) -> Result<ReplicaConfig> {
+    let mut config = ReplicaConfig::load(base)?.ok_or_else(|| {
+        anyhow!(
+            "{} not found: this workspace is not a replica",
+            REPLICA_FILE
+        )
+    })?;
+    if !config.is_standby() {
+        bail!("replica was already promoted");
+    }
+    config.promoted_at = Some(clock::now());
+    config.save(base)?;
+    Ok(config)
+}
+
+/// Result of one pull.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncOutcome {
+    /// Commit checked out after the pull
+    pub head: String,
+    /// Commits the replica was behind before fast-forwarding
+    pub behind: usize,
+}
+
+/// Fetch `branch` from `remote` and fast-forward the working tree to it.
+///
+/// Fetching shells out to `git` so SSH agents and credential helpers work as
+/// they do for the operator. Local commits or conflicting local edits stop the
+/// pull rather than being overwritten.
+pub fn sync_from_remote(base: &Path, remote: &str, branch: Option<&str>) -> Result<SyncOutcome> {
+    let repo = Repository::open(base).context("replica workspace is not a Git repository")?;
+    let branch = match branch {
+        Some(b) => b.to_string(),
+        None => repo
+            .head()
+            .ok()
+            .and_then(|h| h.shorthand().map(str::to_string))
+            .ok_or_else(|| anyhow!("no branch checked out; set `branch` in {}", REPLICA_FILE))?,
+    };
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(base)
+        .args(["fetch", "--quiet", remote, &branch])
+        .output()
+        .context("running git fetch")?;
+    if !output.status.success() {
+        bail!(
+            "git fetch {} {} failed: {}",
+            remote,
+            branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let fetched = repo.refname_to_id("FETCH_HEAD")?;
+    let local = repo.head().ok().and_then(|h| h.target());
+    let behind = match local {
+        Some(local) if local == fetched => {
+            return Ok(SyncOutcome {
+                head: fetched.to_string(),
+                behind: 0,
+            })
+        }
+        Some(local) => {
+            let (ahead, behind) = repo.graph_ahead_behind(local, fetched)?;
+            if ahead > 0 {
+                bail!(
+                    "replica has {} local commit(s) not on {}/{}; refusing to overwrite",
+                    ahead,
+                    remote,
+                    branch
+                );
+            }
+            behind
+        }
+        None => 0,
+    };
+
+    let commit = repo.find_commit(fetched)?;
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))
+        .context("local edits conflict with incoming changes")?;
+    let message = format!("replica: fast-forward to {}/{}", remote, branch);
+    if repo.head_detached().unwrap_or(false) || local.is_none() {
+        repo.reference(&format!("refs/heads/{}", branch), fetched, true, &message)?;
+        repo.set_head(&format!("refs/heads/{}", branch))?;
+    } else {
+        repo.head()?.set_target(fetched, &message)?;
+    }
+    Ok(SyncOutcome {
+        head: fetched.to_string(),
+        behind,
+    })
+}
+
+#[derive(Debug, Default)]
+struct SyncProgress {
+    head: Option<String>,
+    last_attempt_at: Option<DateTime<Utc>>,
+    last_sync_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    behind: usize,
+}
+
+/// Replication state reported by `/health`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplicationStatus {
+    /// `replica` or `primary` (promoted)
+    pub role: String,
+    pub remote: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub interval_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_sync_at: Option<DateTime<Utc>>,
+    /// Seconds since the last successful pull
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lag_seconds: Option<i64>,
+    /// Commits pulled by the last successful sync
+    pub last_pull_commits: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promoted_at: Option<DateTime<Utc>>,
+    /// False while in standby and pulls are stale or failing
+    pub healthy: bool,
+}
+
+/// A workspace running as (or promoted from) a standby replica.
+pub struct Replica {
+    base: PathBuf,
+    config: Mutex<ReplicaConfig>,
+    read_only: AtomicBool,
+    progress: Mutex<SyncProgress>,
+}
+
+impl Replica {
+    pub fn new(base: &Path, config: ReplicaConfig) -> Self {
+        Self {
+            base: base.to_path_buf(),
+            read_only: AtomicBool::new(config.is_standby()),
+            config: Mutex::new(config),
+            progress: Mutex::new(SyncProgress::default()),
+        }
+    }
+
+    /// True until promoted; re-reads the role file so a promotion takes
+    /// effect on the next write even between sync ticks.
+    pub fn is_read_only(&self) -> bool {
+        if !self.read_only.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.refresh_role();
+        self.read_only.load(Ordering::SeqCst)
+    }
+
+    fn refresh_role(&self) {
+        if let Ok(Some(config)) = ReplicaConfig::load(&self.base) {
+            if !config.is_standby() {
+                self.read_only.store(false, Ordering::SeqCst);
+                tracing::info!("Replica promoted to primary; accepting writes");
+            }
+            *self.config.lock().unwrap() = config;
+        }
+    }
+
+    /// Refuse `action` while in standby unless it is read-only.
+    pub fn check_action(&self, action: &str) -> Result<()> {
+        if READ_ONLY_ACTIONS.contains(&action) || !self.is_read_only() {
+            return Ok(());
+        }
+        bail!(
+            "'{}' is unavailable: this agent is a read-only replica (run `arx agent promote` to accept writes)",
+            action
+        )
+    }
+
+    /// Pull once and record the outcome.
+    pub fn sync_once(&self) -> Result<SyncOutcome> {
+        let (remote, branch) = {
+            let config = self.config.lock().unwrap();
+            (config.remote.clone(), config.branch.clone())
+        };
+        let result = sync_from_remote(&self.base, &remote, branch.as_deref());
+        let mut progress = self.progress.lock().unwrap();
+        let now = clock::now();
+        progress.last_attempt_at = Some(now);
+        match &result {
+            Ok(outcome) => {
+                progress.head = Some(outcome.head.clone());
+                progress.last_sync_at = Some(now);
+                progress.behind = outcome.behind;
+                progress.last_error = None;
+            }
+            Err(e) => progress.last_error = Some(format!("{:#}", e)),
+        }
+        result
+    }
+
+    pub fn status(&self) -> ReplicationStatus {
+        let config = self.config.lock().unwrap().clone();
+        let progress = self.progress.lock().unwrap();
+        let standby = self.read_only.load(Ordering::SeqCst);
+        let lag_seconds = progress
+            .last_sync_at
+            .map(|at| (clock::now() - at).num_seconds().max(0));
+        let fresh =
+            lag_seconds.is_some_and(|lag| lag <= STALE_INTERVALS * config.interval_secs as i64);
+        ReplicationStatus {
+            role: if standby { "replica" } else { "primary" }.to_string(),
+            remote: config.remote,
+            branch: config.branch,
+            interval_secs: config.interval_secs,
+            head: progress.head.clone(),
+            last_attempt_at: progress.last_attempt_at,
+            last_sync_at: progress.last_sync_at,
+            lag_seconds,
+            last_pull_commits: progress.behind,
+            last_error: progress.last_error.clone(),
+            promoted_at: config.promoted_at,
+            healthy: !standby || (fresh && progress.last_error.is_none()),
+        }
+    }
+
+    /// Pull on the configured interval until promoted.
+    pub async fn run(&'static self) {
+        tracing::info!(base = %self.base.display(), "Replica sync started");
+        loop {
+            match tokio::task::spawn_blocking(move || self.sync_once()).await {
+                Ok(Ok(outcome)) if outcome.behind > 0 => {
+                    tracing::info!(head = %outcome.head, commits = outcome.behind, "Replica pulled")
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => tracing::warn!(error = %format!("{:#}", e), "Replica pull failed"),
+                Err(e) => tracing::error!(error = %e, "Replica pull task panicked"),
+            }
+            let interval = self.config.lock().unwrap().interval_secs.max(1);
+            // Poll the role file each second so promotion is picked up promptly
+            for _ in 0..interval {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                if !self.is_read_only() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Install the replica for this process when `repo_root` has a replica file.
+pub fn install(repo_root: &Path) -> Result<Option<&'static Replica>> {
+    let Some(config) = ReplicaConfig::load(repo_root)? else {
+        return Ok(None);
+    };
+    REPLICA
+        .set(Replica::new(repo_root, config))
+        .map_err(|_| anyhow!("replica already installed"))?;
+    Ok(REPLICA.get())
+}
+
+/// The installed replica, if this agent runs as one.
+pub fn current() -> Option<&'static Replica> {
+    REPLICA.get()
+}
+
+/// Refuse mutating actions while this process is a standby replica.
+pub fn ensure_writable(action: &str) -> Result<()> {
+    match current() {
+        Some(replica) => replica.check_action(action),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(status.status.success(), "git {:?}: {:?}", args, status);
+    }
+
+    #[test]
+    fn pulls_fast_forwards_and_refuses_divergence() {
+        let primary = tempfile::tempdir().unwrap();
+        git(primary.path(), &["init", "-q", "-b", "main"]);
+        std::fs::write(primary.path().join("building.yaml"), "name: A\n").unwrap();
+        git(primary.path(), &["add", "-A"]);
+        git(primary.path(), &["commit", "-qm", "one"]);
+
+        let spare = tempfile::tempdir().unwrap();
+        let spare_path = spare.path().join("spare");
+        let output = Command::new("git")
+            .args(["clone", "-q"])
+            .arg(primary.path())
+            .arg(&spare_path)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        std::fs::write(primary.path().join("building.yaml"), "name: B\n").unwrap();
+        git(primary.path(), &["commit", "-qam", "two"]);
+
+        let outcome = sync_from_remote(&spare_path, "origin", None).unwrap();
+        assert_eq!(outcome.behind, 1);
+        assert_eq!(
+            std::fs::read_to_string(spare_path.join("building.yaml")).unwrap(),
+            "name: B\n"
+        );
+        assert_eq!(
+            sync_from_remote(&spare_path, "origin", None)
+                .unwrap()
+                .behind,
+            0
+        );
+
+        std::fs::write(spare_path.join("notes.txt"), "local").unwrap();
+        git(&spare_path, &["add", "-A"]);
+        git(&spare_path, &["commit", "-qm", "local"]);
+        let err = sync_from_remote(&spare_path, "origin", None).unwrap_err();
+        assert!(err.to_string().contains("local commit"), "{}", err);
+    }
+
+    #[test]
+    fn standby_refuses_writes_until_promoted() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ReplicaConfig::new("origin", None, 5);
+        config.save(dir.path()).unwrap();
+        let replica = Replica::new(dir.path(), config);
+
+        assert!(replica.check_action("building.get").is_ok());
+        assert!(replica.check_action("git.commit").is_err());
+        let status = replica.status();
+        assert_eq!(status.role, "replica");
+        assert!(!status.healthy, "never synced");
+
+        promote(dir.path()).unwrap();
+        assert!(replica.check_action("git.commit").is_ok());
+        assert_eq!(replica.status().role, "primary");
+        assert!(promote(dir.path()).is_err());
+    }
+}
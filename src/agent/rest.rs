@@ -0,0 +1,306 @@
+//! Plain HTTP JSON API next to the WebSocket and `/rpc`.
+//!
+//! Each route runs one agent action for the caller in the `Authorization:
+//! Bearer` header (or `?token=` for the agent token), so capabilities, roles,
+//! approvals, dry runs, and read-only replicas apply as they do over
+//! JSON-RPC. Successful calls return the action's result as the body; errors
+//! return `{"error": …, "code": …}` with a matching HTTP status.
+//!
+//! | Route | Action |
+//! |---|---|
+//! | `GET /api/building` | `building.get` |
+//! | `GET/POST /api/rooms` | `room.list` / `room.create` |
+//! | `GET/PATCH/DELETE /api/rooms/:room` | `room.get` / `room.update` / `room.delete` |
+//! | `GET/POST /api/equipment` | `equipment.list` / `equipment.create` |
+//! | `GET/PATCH/DELETE /api/equipment/:equipment` | `equipment.get` / `equipment.update` / `equipment.delete` |
+//! | `GET /api/commits` | `git.log` |
+//!
+//! List routes take `page_size`, `cursor`, `sort`, and `order`; `/api/commits`
+//! takes `limit`; edits take `dry_run=true`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::agent::dispatcher::AgentState;
+use crate::agent::protocol::{JsonRpcError, JsonRpcRequest, AUTH_ERROR, READ_ONLY_ERROR};
+use crate::agent::recorder::dispatch_recorded;
+use crate::agent::server::authenticate;
+
+/// Query string shared by the routes; set fields become action params.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RestQuery {
+    #[serde(skip_serializing)]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+}
+
+/// REST routes, merged into the agent router.
+pub fn routes() -> Router<Arc<AgentState>> {
+    Router::new()
+        .route("/api/building", get(building_get))
+        .route("/api/rooms", get(rooms_list).post(room_create))
+        .route(
+            "/api/rooms/:room",
+            get(room_get).patch(room_update).delete(room_delete),
+        )
+        .route("/api/equipment", get(equipment_list).post(equipment_create))
+        .route(
+            "/api/equipment/:equipment",
+            get(equipment_get)
+                .patch(equipment_update)
+                .delete(equipment_delete),
+        )
+        .route("/api/commits", get(commits_list))
+}
+
+async fn building_get(
+    headers: HeaderMap,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    call(state, &headers, &query, "building.get", json!({})).await
+}
+
+async fn rooms_list(
+    headers: HeaderMap,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    call(state, &headers, &query, "room.list", json!({})).await
+}
+
+async fn room_create(
+    headers: HeaderMap,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+    Json(body): Json<Value>,
+) -> Response {
+    call(state, &headers, &query, "room.create", body).await
+}
+
+async fn room_get(
+    headers: HeaderMap,
+    Path(room): Path<String>,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    let params = json!({ "room": room });
+    call(state, &headers, &query, "room.get", params).await
+}
+
+async fn room_update(
+    headers: HeaderMap,
+    Path(room): Path<String>,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+    Json(body): Json<Value>,
+) -> Response {
+    let params = with_key(body, "room", room);
+    call(state, &headers, &query, "room.update", params).await
+}
+
+async fn room_delete(
+    headers: HeaderMap,
+    Path(room): Path<String>,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    let params = json!({ "room": room });
+    call(state, &headers, &query, "room.delete", params).await
+}
+
+async fn equipment_list(
+    headers: HeaderMap,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    call(state, &headers, &query, "equipment.list", json!({})).await
+}
+
+async fn equipment_create(
+    headers: HeaderMap,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+    Json(body): Json<Value>,
+) -> Response {
+    call(state, &headers, &query, "equipment.create", body).await
+}
+
+async fn equipment_get(
+    headers: HeaderMap,
+    Path(equipment): Path<String>,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    let params = json!({ "equipment": equipment });
+    call(state, &headers, &query, "equipment.get", params).await
+}
+
+async fn equipment_update(
+    headers: HeaderMap,
+    Path(equipment): Path<String>,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+    Json(body): Json<Value>,
+) -> Response {
+    let params = with_key(body, "equipment", equipment);
+    call(state, &headers, &query, "equipment.update", params).await
+}
+
+async fn equipment_delete(
+    headers: HeaderMap,
+    Path(equipment): Path<String>,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    let params = json!({ "equipment": equipment });
+    call(state, &headers, &query, "equipment.delete", params).await
+}
+
+async fn commits_list(
+    headers: HeaderMap,
+    Query(query): Query<RestQuery>,
+    State(state): State<Arc<AgentState>>,
+) -> Response {
+    call(state, &headers, &query, "git.log", json!({})).await
+}
+
+/// `body` with the path segment set as `field`.
+fn with_key(body: Value, field: &str, key: String) -> Value {
+    let mut params = match body {
+        Value::Object(map) => map,
+        _ => Default::default(),
+    };
+    params.insert(field.to_string(), Value::String(key));
+    Value::Object(params)
+}
+
+/// Run `method` for the request's caller with `params` plus the query string.
+async fn call(
+    state: Arc<AgentState>,
+    headers: &HeaderMap,
+    query: &RestQuery,
+    method: &str,
+    params: Value,
+) -> Response {
+    let Some(caller) = authenticate(headers, query.token.as_deref(), &state) else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Invalid or missing token" })),
+        )
+            .into_response();
+    };
+    let mut params = params;
+    if let (Value::Object(map), Ok(Value::Object(extra))) =
+        (&mut params, serde_json::to_value(query))
+    {
+        map.extend(extra);
+    }
+    let request = JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: Some(params),
+        id: None,
+    };
+    let response = dispatch_recorded(state, request, "rest", &caller).await;
+    match response.error {
+        None => Json(response.result.unwrap_or(Value::Null)).into_response(),
+        Some(error) => {
+            let body = json!({ "error": error.message, "code": error.code });
+            (status(&error), Json(body)).into_response()
+        }
+    }
+}
+
+/// HTTP status for an agent error.
+fn status(error: &JsonRpcError) -> StatusCode {
+    match error.code {
+        AUTH_ERROR => StatusCode::FORBIDDEN,
+        READ_ONLY_ERROR => StatusCode::SERVICE_UNAVAILABLE,
+        _ if error.is_not_found() => StatusCode::NOT_FOUND,
+        _ if error.is_invalid_params() => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::auth::{all_capabilities, TokenState};
+    use crate::core::{Building, Floor};
+    use crate::persistence::save_building_at;
+    use axum::body::to_bytes;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_rooms_round_trip_over_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut building = Building::new("Pilot".into(), "/pilot".into());
+        building.add_floor(Floor::new("L1".into(), 0));
+        save_building_at(dir.path(), &building).unwrap();
+        let state = Arc::new(AgentState {
+            repo_root: dir.path().to_path_buf(),
+            token: Arc::new(Mutex::new(TokenState::new(
+                "secret".to_string(),
+                all_capabilities(),
+            ))),
+            metrics: Arc::new(crate::agent::observability::AgentMetrics::new()),
+            reload_handle: None,
+            sessions: Default::default(),
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert("Authorization", "Bearer secret".parse().unwrap());
+        let query = RestQuery::default();
+
+        let unauthorized = room_get(
+            HeaderMap::new(),
+            Path("Lab".into()),
+            Query(RestQuery::default()),
+            State(state.clone()),
+        )
+        .await;
+        assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+        let body = json!({ "name": "Lab", "floor_level": 0 });
+        let created = call(state.clone(), &headers, &query, "room.create", body).await;
+        assert_eq!(created.status(), StatusCode::OK);
+
+        let listed = rooms_list(headers.clone(), Query(query), State(state.clone())).await;
+        let page: Value =
+            serde_json::from_slice(&to_bytes(listed.into_body(), usize::MAX).await.unwrap())
+                .unwrap();
+        assert_eq!(
+            (page["total"].as_u64(), &page["items"][0]["name"]),
+            (Some(1), &json!("Lab"))
+        );
+
+        let missing = room_get(
+            headers,
+            Path("Gym".into()),
+            Query(RestQuery::default()),
+            State(state),
+        )
+        .await;
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -9,12 +9,20 @@ use std::{
 #[cfg(feature = "agent")]
 #[cfg(feature = "agent")]
 use crate::agent::{
-    auth::{generate_did_key, TokenState},
-    dispatcher::{dispatch, AgentState},
-    protocol::{JsonRpcRequest, JsonRpcResponse, PARSE_ERROR},
+    auth::{
+        all_capabilities, ensure_capability, ensure_key_capability, generate_did_key, ApiKeyStore,
+        Caller, TokenState,
+    },
+    dispatcher::AgentState,
+    protocol::{JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, PARSE_ERROR},
+    recorder::dispatch_recorded,
     workspace::detect_repo_root,
 };
 #[cfg(feature = "agent")]
+use crate::core::clock;
+#[cfg(feature = "agent")]
+use crate::persistence::audit::{record_quietly, AuditEntry, AuditOutcome};
+#[cfg(feature = "agent")]
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
@@ -66,17 +74,7 @@ pub async fn start_agent() -> Result<(), Box<dyn std::error::Error>> {
 
     // 2. Generate Root Token
     let root_token = generate_did_key();
-    let all_capabilities = vec![
-        "git.status".to_string(),
-        "git.diff".to_string(),
-        "git.commit".to_string(),
-        "files.read".to_string(),
-        "building.get".to_string(),
-        "ifc.import".to_string(),
-        "ifc.export".to_string(),
-        "collab.sync".to_string(),
-        "auth.manage".to_string(),
-    ];
+    let all_capabilities = all_capabilities();
 
     let token_state = TokenState::new(root_token.clone(), all_capabilities);
     let metrics = Arc::new(crate::agent::observability::AgentMetrics::new());
@@ -85,42 +83,91 @@ pub async fn start_agent() -> Result<(), Box<dyn std::error::Error>> {
         token: Arc::new(Mutex::new(token_state)),
         metrics: metrics.clone(),
         reload_handle: Some(reload_handle.clone()),
+        sessions: Default::default(),
     });
 
+    // Opt-in session recording (ARX_AGENT_RECORD=<file>) for `arx agent replay`
+    if let Some(recorder) = crate::agent::recorder::install_from_env(&repo_root)? {
+        tracing::info!(file = %recorder.path().display(), "Recording agent session");
+    }
+
+    // Standby replica (`.arx/agent/replica.yaml`): pull from the remote, serve reads only
+    let replica = crate::agent::replica::install(&repo_root)?;
+    if let Some(replica) = replica.filter(|r| r.is_read_only()) {
+        let status = replica.status();
+        tracing::info!(remote = %status.remote, "Running as read-only replica");
+    }
+
     // Spawn log watcher
     crate::agent::observability::spawn_log_level_watcher(repo_root.clone(), reload_handle);
 
     println!("\n🔑 ROOT TOKEN: {}\n", root_token);
     tracing::info!("⚠️  Keep this token secret! You will need it to connect.");
-    tracing::info!("ℹ️  Hardware/BACnet drivers not included in this build (revisit later).");
+    #[cfg(not(feature = "bacnet"))]
+    tracing::info!("ℹ️  BACnet driver not included in this build (--features bacnet).");
 
     // 3. Setup Router
-    let app = Router::new()
-        .route("/ws", get(ws_handler))
-        .route("/rpc", post(rpc_handler))
-        .route("/api/status", get(http_agent_status))
-        .route("/api/claims/status", get(http_claims_status))
-        .route("/metrics", get(http_prometheus_metrics))
-        .route("/api/claims/staging", get(http_claims_staging))
-        .route("/api/claims/:id/approve", post(http_claim_approve))
-        .route("/api/claims/:id/reject", post(http_claim_reject))
-        .with_state(state.clone());
+    let app = agent_router(state.clone());
 
-    // 4. Start File Watchers
-    let export_state = state.clone();
+    // 4. Start File Watchers (a replica starts them once promoted)
+    let watcher_state = state.clone();
     tokio::spawn(async move {
-        if let Err(e) = run_auto_export_watcher(export_state).await {
-            eprintln!("❌ Auto-export watcher error: {}", e);
+        if let Some(replica) = replica.filter(|r| r.is_read_only()) {
+            replica.run().await;
         }
-    });
-
-    let import_state = state.clone();
-    tokio::spawn(async move {
-        if let Err(e) = run_auto_import_watcher(import_state).await {
+        let export_state = watcher_state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_auto_export_watcher(export_state).await {
+                eprintln!("❌ Auto-export watcher error: {}", e);
+            }
+        });
+        if let Err(e) = run_auto_import_watcher(watcher_state).await {
             eprintln!("❌ Auto-import watcher error: {}", e);
         }
     });
 
+    // Scheduled backups (`.arx/backup.yaml` with `schedule.interval_hours`)
+    match crate::persistence::backup::BackupConfig::load(&repo_root) {
+        Ok(config) if config.schedule.interval_hours.is_some() => {
+            tokio::spawn(run_backup_scheduler(repo_root.clone(), config));
+        }
+        _ => {}
+    }
+
+    // BACnet/IP polling (`.arx/bacnet.yaml`); a read-only replica leaves it to the primary
+    #[cfg(feature = "bacnet")]
+    if !replica.is_some_and(|r| r.is_read_only()) {
+        match crate::hardware::bacnet::BacnetConfig::load_at(&repo_root) {
+            Ok(config) if config.point_count() > 0 => {
+                tokio::spawn(run_bacnet_poller(state.clone(), config));
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "BACnet polling disabled"),
+        }
+    }
+
+    // Live file changes (building.yaml, sensor data, issues, …) → `files.changed`
+    tokio::spawn(run_change_notifier(state.clone()));
+
+    // gRPC façade over the same dispatcher (ARX_GRPC_ADDR, default 0.0.0.0:50051)
+    #[cfg(feature = "grpc")]
+    {
+        let addr = std::env::var(crate::grpc::GRPC_ADDR_ENV)
+            .unwrap_or_else(|_| crate::grpc::DEFAULT_GRPC_ADDR.to_string());
+        match addr.parse::<SocketAddr>() {
+            Ok(addr) => {
+                println!("🔌 gRPC listening on {}", addr);
+                let grpc_state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = crate::grpc::serve(grpc_state, addr).await {
+                        tracing::error!(error = %e, "gRPC server stopped");
+                    }
+                });
+            }
+            Err(e) => tracing::warn!(addr = %addr, error = %e, "gRPC disabled: bad address"),
+        }
+    }
+
     // 5. Start P2P Local Discovery
     crate::agent::discovery::start_discovery(root_token.clone(), 8787);
 
@@ -143,6 +190,28 @@ pub async fn start_agent() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// HTTP / WebSocket routes served by the agent (also used by `arx devtools hil`).
+#[cfg(feature = "agent")]
+pub fn agent_router(state: Arc<AgentState>) -> Router {
+    Router::new()
+        .route("/health", get(http_health))
+        .route("/ws", get(ws_handler))
+        .route("/rpc", post(rpc_handler))
+        .route("/api/status", get(http_agent_status))
+        .route("/api/claims/status", get(http_claims_status))
+        .route("/metrics", get(http_prometheus_metrics))
+        .route("/api/claims/staging", get(http_claims_staging))
+        .route("/api/claims/:id/approve", post(http_claim_approve))
+        .route("/api/claims/:id/reject", post(http_claim_reject))
+        .route("/issues/new", get(http_issue_form))
+        .route("/api/issues", post(http_issue_submit).get(http_issues_list))
+        .route("/api/maintenance.ics", get(http_maintenance_ical))
+        .route("/api/embed/floors/:level", get(http_embed_floor))
+        .route("/api/exports/:id", get(http_export_download))
+        .merge(crate::agent::rest::routes())
+        .with_state(state)
+}
+
 /// Field-facing connect card for iPhone PWA on the same LAN/hotspot (Batch A P0.2).
 #[cfg(feature = "agent")]
 fn print_iphone_connect_hints(token: &str, port: u16) {
@@ -186,23 +255,69 @@ fn guess_lan_ips() -> Vec<String> {
     out
 }
 
+/// Who holds the request's credential: the agent token (bearer header or
+/// `?token=`) or an unexpired API key (bearer header only, so keys stay out
+/// of URLs and access logs).
 #[cfg(feature = "agent")]
-fn check_auth(headers: &HeaderMap, query_token: Option<&str>, state: &AgentState) -> bool {
-    let token_str = if let Some(bearer) = headers
+pub(crate) fn authenticate(
+    headers: &HeaderMap,
+    query_token: Option<&str>,
+    state: &AgentState,
+) -> Option<Caller> {
+    let bearer = headers
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .and_then(|s| s.strip_prefix("Bearer "))
-    {
-        Some(bearer.to_string())
-    } else {
-        query_token.map(|s| s.to_string())
-    };
+        .and_then(|s| s.strip_prefix("Bearer "));
+    let token = bearer.or(query_token)?;
+    if state.token.lock().unwrap().value() == token {
+        return Some(Caller::Token);
+    }
+    let caller = bearer.and_then(|secret| match ApiKeyStore::load(&state.repo_root) {
+        Ok(keys) => keys
+            .authenticate(secret, clock::now())
+            .map(|key| Caller::ApiKey {
+                id: key.id.clone(),
+                capabilities: key.capabilities.clone(),
+            }),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read API keys");
+            None
+        }
+    });
+    if caller.is_none() {
+        let entry = AuditEntry {
+            actor: "unknown".to_string(),
+            detail: Some("Unauthorized: invalid or expired credential".to_string()),
+            ..AuditEntry::new("agent.auth", None, AuditOutcome::Denied)
+        };
+        record_quietly(&state.repo_root, &entry);
+    }
+    caller
+}
 
-    if let Some(token) = token_str {
-        let guard = state.token.lock().unwrap();
-        guard.value() == token
-    } else {
-        false
+/// Agent token only.
+#[cfg(feature = "agent")]
+fn check_auth(headers: &HeaderMap, query_token: Option<&str>, state: &AgentState) -> bool {
+    matches!(
+        authenticate(headers, query_token, state),
+        Some(Caller::Token)
+    )
+}
+
+/// Agent token, or an API key whose capabilities cover `action`.
+#[cfg(feature = "agent")]
+fn check_access(
+    headers: &HeaderMap,
+    query_token: Option<&str>,
+    state: &AgentState,
+    action: &str,
+) -> bool {
+    match authenticate(headers, query_token, state) {
+        Some(Caller::Token) => true,
+        Some(Caller::ApiKey { capabilities, .. }) => {
+            ensure_key_capability(action, &capabilities).is_ok()
+        }
+        None => false,
     }
 }
 
@@ -269,7 +384,7 @@ pub async fn http_claims_staging(
                 (building_id, address, contributor, summary, timestamp)
             }
             Err(_) => {
-                ("unknown".to_string(), "unknown".to_string(), "unknown".to_string(), "Invalid YAML contribution".to_string(), chrono::Utc::now().timestamp() as u64)
+                ("unknown".to_string(), "unknown".to_string(), "unknown".to_string(), "Invalid YAML contribution".to_string(), clock::now().timestamp() as u64)
             }
         };
 
@@ -302,6 +417,10 @@ pub async fn http_claim_approve(
         return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
     }
 
+    if let Some(refusal) = replica_refusal("claim.review") {
+        return refusal;
+    }
+
     use crate::agent::claim::GraceWindowManager;
     let mut manager = GraceWindowManager::new();
 
@@ -359,6 +478,10 @@ pub async fn http_claim_reject(
         return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
     }
 
+    if let Some(refusal) = replica_refusal("claim.review") {
+        return refusal;
+    }
+
     use crate::agent::claim::GraceWindowManager;
     let mut manager = GraceWindowManager::new();
 
@@ -409,15 +532,15 @@ async fn ws_handler(
     Query(params): Query<AuthParams>,
     State(state): State<Arc<AgentState>>,
 ) -> impl IntoResponse {
-    if !check_auth(&headers, params.token.as_deref(), &state) {
+    let Some(caller) = authenticate(&headers, params.token.as_deref(), &state) else {
         return (
             StatusCode::UNAUTHORIZED,
             "Unauthorized: Invalid or missing token",
         )
             .into_response();
-    }
+    };
 
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+    ws.on_upgrade(|socket| handle_socket(socket, state, caller))
 }
 
 #[cfg(feature = "agent")]
@@ -427,32 +550,116 @@ async fn rpc_handler(
     State(state): State<Arc<AgentState>>,
     Json(request): Json<JsonRpcRequest>,
 ) -> impl IntoResponse {
-    if !check_auth(&headers, params.token.as_deref(), &state) {
+    let Some(caller) = authenticate(&headers, params.token.as_deref(), &state) else {
         return (
             StatusCode::UNAUTHORIZED,
             "Unauthorized: Invalid or missing token",
         )
             .into_response();
-    }
+    };
 
-    let response = dispatch(state, request).await;
+    let response = dispatch_recorded(state, request, "rpc", &caller).await;
     Json(response).into_response()
 }
 
+/// Run one WebSocket request on its own task, forwarding the progress it
+/// reports (see [`crate::utils::progress`]) as `progress` notifications tied
+/// to the request id ahead of the response. `None` once the socket is gone.
 #[cfg(feature = "agent")]
-async fn handle_socket(mut socket: WebSocket, state: Arc<AgentState>) {
-    struct WsGuard(Arc<AgentState>);
+async fn dispatch_with_progress(
+    socket: &mut WebSocket,
+    state: Arc<AgentState>,
+    request: JsonRpcRequest,
+    caller: &Caller,
+    session: &str,
+) -> Option<JsonRpcResponse> {
+    let (tx, mut notes) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let id = request.id.clone();
+    let listener: crate::utils::progress::ProgressListener = match id.clone() {
+        Some(id) => Arc::new(move |progress, stage| {
+            let note = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "progress",
+                "params": { "id": id, "progress": progress, "stage": stage },
+            });
+            let _ = tx.send(note.to_string());
+        }),
+        None => Arc::new(|_, _| {}),
+    };
+    let caller = caller.clone();
+    let dispatch = async move { dispatch_recorded(state, request, "ws", &caller).await };
+    let mut task = tokio::spawn(crate::agent::sessions::in_session(
+        session.to_string(),
+        crate::utils::progress::reported(listener, dispatch),
+    ));
+
+    let joined = loop {
+        tokio::select! {
+            biased;
+            Some(note) = notes.recv() => {
+                if let Err(e) = socket.send(Message::Text(note)).await {
+                    tracing::error!(error = %e, "Failed to send progress notification");
+                    return None;
+                }
+            }
+            joined = &mut task => break joined,
+        }
+    };
+    while let Ok(note) = notes.try_recv() {
+        if socket.send(Message::Text(note)).await.is_err() {
+            return None;
+        }
+    }
+    Some(joined.unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Agent request task failed");
+        JsonRpcResponse::error(id, INTERNAL_ERROR, "Request failed".to_string(), None)
+    }))
+}
+
+#[cfg(feature = "agent")]
+async fn handle_socket(mut socket: WebSocket, state: Arc<AgentState>, caller: Caller) {
+    struct WsGuard(Arc<AgentState>, String);
     impl Drop for WsGuard {
         fn drop(&mut self) {
+            self.0.sessions.close(&self.1);
             self.0.metrics.active_ws_clients.fetch_sub(1, Ordering::SeqCst);
             tracing::info!(active = self.0.metrics.active_ws_clients.load(Ordering::SeqCst), "WebSocket client disconnected");
         }
     }
+    let capabilities = match &caller {
+        Caller::Token => state.token.lock().unwrap().capabilities().to_vec(),
+        Caller::ApiKey { capabilities, .. } => capabilities.clone(),
+    };
+    let session = state.sessions.open("ws", &caller, capabilities);
+    let mut events = state.sessions.subscribe();
     state.metrics.active_ws_clients.fetch_add(1, Ordering::SeqCst);
-    let _guard = WsGuard(state.clone());
-    tracing::info!(active = state.metrics.active_ws_clients.load(Ordering::SeqCst), "WebSocket client connected");
+    let _guard = WsGuard(state.clone(), session.id.clone());
+    tracing::info!(active = state.metrics.active_ws_clients.load(Ordering::SeqCst), session = %session.id, "WebSocket client connected");
 
-    while let Some(msg) = socket.recv().await {
+    loop {
+        let msg = tokio::select! {
+            msg = socket.recv() => msg,
+            event = events.recv() => {
+                match event {
+                    Ok(event) if crate::agent::sessions::should_deliver(&session, &event) => {
+                        let note = event.to_notification().to_string();
+                        if let Err(e) = socket.send(Message::Text(note)).await {
+                            tracing::error!(error = %e, "Failed to send WebSocket event");
+                            return;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::warn!(session = %session.id, missed, "WebSocket client missed events");
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+                }
+                continue;
+            }
+        };
+        let Some(msg) = msg else {
+            return;
+        };
         let msg = match msg {
             Ok(msg) => msg,
             Err(e) => {
@@ -464,8 +671,19 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AgentState>) {
         match msg {
             Message::Text(text) => {
                 // Parse JSON-RPC Request
+                let mut fetch = None;
                 let response = match serde_json::from_str::<JsonRpcRequest>(&text) {
-                    Ok(request) => dispatch(state.clone(), request).await,
+                    Ok(request) => {
+                        if request.method == "transfer.fetch" {
+                            fetch = request.params.clone();
+                        }
+                        let dispatch =
+                            dispatch_with_progress(&mut socket, state.clone(), request, &caller, &session.id);
+                        match dispatch.await {
+                            Some(response) => response,
+                            None => return,
+                        }
+                    }
                     Err(e) => JsonRpcResponse::error(
                         None,
                         PARSE_ERROR,
@@ -481,6 +699,51 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AgentState>) {
                         return;
                     }
                 }
+
+                // A granted transfer.fetch is followed by its chunk as a binary frame
+                if let (Some(params), Some(header)) = (fetch, response.result.as_ref()) {
+                    let id = params
+                        .get("transfer_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    let offset = header.get("offset").and_then(|v| v.as_u64()).unwrap_or(0);
+                    match crate::agent::transfer::read_chunk(&state.repo_root, id, offset) {
+                        Ok((_, frame)) => {
+                            if let Err(e) = socket.send(Message::Binary(frame)).await {
+                                tracing::error!(error = %e, "Failed to send transfer chunk");
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Transfer chunk vanished after fetch")
+                        }
+                    }
+                }
+            }
+            Message::Binary(frame) => {
+                let accepted = binary_chunk_allowed(&state, &caller)
+                    .and_then(|()| crate::agent::transfer::accept_chunk(&state.repo_root, &frame));
+                let reply = match accepted {
+                    Ok(status) => serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "method": "transfer.ack",
+                        "params": status
+                    }),
+                    Err(e) => {
+                        let transfer_id = crate::agent::transfer::decode_frame(&frame)
+                            .map(|(header, _)| header.transfer_id)
+                            .ok();
+                        serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "method": "transfer.error",
+                            "params": {"transfer_id": transfer_id, "message": e.to_string()}
+                        })
+                    }
+                };
+                if let Err(e) = socket.send(Message::Text(reply.to_string())).await {
+                    tracing::error!(error = %e, "Failed to send transfer ack");
+                    return;
+                }
             }
             Message::Close(_) => {
                 return;
@@ -490,6 +753,21 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AgentState>) {
     }
 }
 
+/// Binary frames are upload chunks: same capability and replica rules as `transfer.chunk`.
+#[cfg(feature = "agent")]
+fn binary_chunk_allowed(state: &AgentState, caller: &Caller) -> anyhow::Result<()> {
+    match caller {
+        Caller::Token => {
+            let token = state.token.lock().unwrap();
+            ensure_capability("transfer.chunk", token.capabilities())?;
+        }
+        Caller::ApiKey { capabilities, .. } => {
+            ensure_key_capability("transfer.chunk", capabilities)?
+        }
+    }
+    crate::agent::replica::ensure_writable("transfer.chunk")
+}
+
 #[cfg(feature = "agent")]
 #[derive(serde::Serialize)]
 struct AgentStatusDto {
@@ -531,6 +809,27 @@ pub async fn http_agent_status(
     Json(status).into_response()
 }
 
+/// Unauthenticated liveness / replication probe for load balancers and failover tooling.
+#[cfg(feature = "agent")]
+pub async fn http_health(State(state): State<Arc<AgentState>>) -> impl IntoResponse {
+    let replication = crate::agent::replica::current().map(|r| r.status());
+    let healthy = replication.as_ref().is_none_or(|r| r.healthy);
+    Json(serde_json::json!({
+        "status": if healthy { "ok" } else { "degraded" },
+        "role": replication.as_ref().map_or("primary", |r| r.role.as_str()),
+        "uptime_seconds": state.metrics.start_time.elapsed().as_secs(),
+        "replication": replication,
+    }))
+}
+
+/// 503 for mutating HTTP endpoints while this agent is a standby replica.
+#[cfg(feature = "agent")]
+fn replica_refusal(action: &str) -> Option<axum::response::Response> {
+    crate::agent::replica::ensure_writable(action)
+        .err()
+        .map(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response())
+}
+
 #[cfg(feature = "agent")]
 #[derive(serde::Serialize)]
 struct ClaimsStatusDto {
@@ -660,6 +959,109 @@ async fn run_auto_export_watcher(state: Arc<AgentState>) -> Result<(), Box<dyn s
     }
 }
 
+/// Publish `files.changed` for each batch of changes the repository watcher sees.
+#[cfg(feature = "agent")]
+async fn run_change_notifier(state: Arc<AgentState>) {
+    use std::time::Duration;
+
+    let watcher = match crate::agent::watcher::RepoWatcher::new(&state.repo_root) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!(error = %e, "File change notifications disabled");
+            return;
+        }
+    };
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let changes = watcher.drain();
+        if !changes.is_empty() {
+            tracing::debug!(count = changes.len(), "Files changed");
+            state.sessions.notify_files(changes);
+        }
+    }
+}
+
+/// Poll mapped BACnet points every `interval_secs`; other clients hear about
+/// new readings as a `sensor.report` change.
+#[cfg(all(feature = "agent", feature = "bacnet"))]
+async fn run_bacnet_poller(state: Arc<AgentState>, config: crate::hardware::bacnet::BacnetConfig) {
+    use crate::hardware::bacnet::{poll_once, BacnetClient};
+    use std::time::Duration;
+
+    let mut client = match BacnetClient::from_config(&config) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!(error = %e, "BACnet polling disabled");
+            return;
+        }
+    };
+    tracing::info!(points = config.point_count(), "BACnet poller started");
+    loop {
+        let (root, cfg) = (state.repo_root.clone(), config.clone());
+        let result = tokio::task::spawn_blocking(move || {
+            let report = poll_once(&root, &cfg, &mut client);
+            (client, report)
+        })
+        .await;
+        match result {
+            Ok((returned, report)) => {
+                client = returned;
+                match report {
+                    Ok(report) => {
+                        for failure in &report.failures {
+                            tracing::warn!(point = %failure, "BACnet read failed");
+                        }
+                        if report.recorded > 0 {
+                            state
+                                .sessions
+                                .notify_action("sensor.report", &Caller::Token);
+                        }
+                    }
+                    Err(e) => tracing::warn!(error = %e, "BACnet poll failed"),
+                }
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "BACnet poll task panicked");
+                return;
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(config.interval_secs)).await;
+    }
+}
+
+/// Create a backup whenever the configured interval has passed, then prune.
+#[cfg(feature = "agent")]
+async fn run_backup_scheduler(
+    repo_root: std::path::PathBuf,
+    config: crate::persistence::backup::BackupConfig,
+) {
+    use crate::persistence::backup::{create_backup, is_due, list_backups, prune_backups};
+    use std::time::Duration;
+
+    tracing::info!(target = %config.target, "Backup scheduler started");
+    loop {
+        let (root, cfg) = (repo_root.clone(), config.clone());
+        let result = tokio::task::spawn_blocking(move || -> anyhow::Result<Option<String>> {
+            let target = cfg.open_target(&root)?;
+            let latest = list_backups(target.as_ref())?.last().map(|m| m.created_at);
+            if !is_due(&cfg, latest, clock::now()) {
+                return Ok(None);
+            }
+            let manifest = create_backup(&root, &cfg)?;
+            prune_backups(&cfg, target.as_ref())?;
+            Ok(Some(manifest.id))
+        })
+        .await;
+        match result {
+            Ok(Ok(Some(id))) => tracing::info!(backup = %id, "Scheduled backup created"),
+            Ok(Ok(None)) => {}
+            Ok(Err(e)) => tracing::warn!(error = %format!("{:#}", e), "Scheduled backup failed"),
+            Err(e) => tracing::error!(error = %e, "Backup task panicked"),
+        }
+        tokio::time::sleep(Duration::from_secs(600)).await;
+    }
+}
+
 #[cfg(feature = "agent")]
 async fn run_auto_import_watcher(state: Arc<AgentState>) -> Result<(), Box<dyn std::error::Error>> {
     use std::time::Duration;
@@ -692,3 +1094,230 @@ async fn run_auto_import_watcher(state: Arc<AgentState>) -> Result<(), Box<dyn s
         }
     }
 }
+
+#[cfg(feature = "agent")]
+#[derive(Deserialize)]
+pub struct IssueFormParams {
+    pub room: Option<String>,
+}
+
+/// Public report form linked from room pages (`arx doc room-pages`).
+#[cfg(feature = "agent")]
+pub async fn http_issue_form(Query(params): Query<IssueFormParams>) -> impl IntoResponse {
+    axum::response::Html(crate::agent::issues::intake_form_html(params.room.as_deref()))
+}
+
+/// Public intake: occupants / external forms submit without a token.
+#[cfg(feature = "agent")]
+pub async fn http_issue_submit(
+    State(state): State<Arc<AgentState>>,
+    Json(mut report): Json<crate::core::domain::issue::IssueReport>,
+) -> impl IntoResponse {
+    if let Some(refusal) = replica_refusal("issue.submit") {
+        return refusal;
+    }
+    if report.source.is_none() {
+        report.source = Some("http".to_string());
+    }
+    match crate::agent::issues::submit(&state.repo_root, &report) {
+        Ok(issue) => (StatusCode::CREATED, Json(issue)).into_response(),
+        Err(e) => {
+            state.metrics.record_error();
+            (StatusCode::BAD_REQUEST, e.to_string()).into_response()
+        }
+    }
+}
+
+#[cfg(feature = "agent")]
+pub async fn http_issues_list(
+    headers: HeaderMap,
+    Query(params): Query<AuthParams>,
+    State(state): State<Arc<AgentState>>,
+) -> impl IntoResponse {
+    if !check_access(&headers, params.token.as_deref(), &state, "issue.list") {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+    match crate::agent::issues::list(&state.repo_root, false) {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(feature = "agent")]
+#[derive(Deserialize)]
+pub struct CalendarParams {
+    pub token: Option<String>,
+    pub assignee: Option<String>,
+    pub days: Option<i64>,
+}
+
+/// Subscribable iCalendar feed (`arx maintenance ical` over HTTP); calendar
+/// clients pass the token as `?token=`.
+#[cfg(feature = "agent")]
+pub async fn http_maintenance_ical(
+    headers: HeaderMap,
+    Query(params): Query<CalendarParams>,
+    State(state): State<Arc<AgentState>>,
+) -> impl IntoResponse {
+    if !check_access(&headers, params.token.as_deref(), &state, "building.get") {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+    let mut building = match crate::persistence::load_building_at(&state.repo_root) {
+        Ok(b) => b,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    let schedules =
+        match crate::core::runtime::MaintenanceSchedules::load_at(&state.repo_root) {
+            Ok(s) => s,
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        };
+    if let Err(e) = crate::persistence::sensors::sync_runtime(&state.repo_root, &mut building) {
+        tracing::warn!(error = %e, "Runtime sync failed; using stored counters");
+    }
+    let events = crate::export::ical::upcoming_events(
+        &building,
+        &schedules,
+        crate::core::clock::now(),
+        params.days.unwrap_or(365).max(1),
+        params.assignee.as_deref(),
+    );
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        crate::export::ical::to_ical(&building.name, &events),
+    )
+        .into_response()
+}
+
+/// Embed requests allowed per token per minute.
+#[cfg(feature = "agent")]
+const EMBED_REQUESTS_PER_MINUTE: u32 = 30;
+
+/// How long browsers and intranet proxies may reuse an embed response.
+#[cfg(feature = "agent")]
+const EMBED_MAX_AGE_SECS: u64 = 30;
+
+/// Fixed one-minute windows per embed token: (window start, requests).
+#[cfg(feature = "agent")]
+static EMBED_RATE: std::sync::OnceLock<
+    Mutex<std::collections::HashMap<String, (std::time::Instant, u32)>>,
+> = std::sync::OnceLock::new();
+
+/// `Err(seconds until the window resets)` once `token` is over its budget.
+#[cfg(feature = "agent")]
+fn embed_rate_check(token: &str) -> Result<(), u64> {
+    let window = std::time::Duration::from_secs(60);
+    let now = std::time::Instant::now();
+    let mut hits = EMBED_RATE.get_or_init(Default::default).lock().unwrap();
+    hits.retain(|_, (start, _)| now.duration_since(*start) < window);
+    let (start, count) = hits.entry(token.to_string()).or_insert((now, 0));
+    if *count >= EMBED_REQUESTS_PER_MINUTE {
+        return Err((window - now.duration_since(*start)).as_secs().max(1));
+    }
+    *count += 1;
+    Ok(())
+}
+
+/// Public floor status widget data. Read-only embed tokens from
+/// `.arx/embed.yaml` (scoped to floors) or the agent token.
+#[cfg(feature = "agent")]
+pub async fn http_embed_floor(
+    headers: HeaderMap,
+    axum::extract::Path(level): axum::extract::Path<i32>,
+    Query(params): Query<AuthParams>,
+    State(state): State<Arc<AgentState>>,
+) -> impl IntoResponse {
+    use axum::http::header;
+    let token = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .or(params.token.as_deref())
+        .unwrap_or_default()
+        .to_string();
+    let embed = match crate::export::embed::EmbedConfig::load_at(&state.repo_root) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    if embed.authorize(&token, level).is_none()
+        && !check_access(&headers, Some(&token), &state, "building.get")
+    {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+    if let Err(retry_after) = embed_rate_check(&token) {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            "Rate limit exceeded",
+        )
+            .into_response();
+    }
+
+    let building = match crate::persistence::load_building_at(&state.repo_root) {
+        Ok(b) => b,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    let mut open_issues = std::collections::HashMap::new();
+    if let Ok(issues) = crate::persistence::issues::open_issues(&state.repo_root) {
+        for room_id in issues.into_iter().filter_map(|i| i.room_id) {
+            *open_issues.entry(room_id).or_insert(0) += 1;
+        }
+    }
+    let Some(summary) = crate::export::embed::floor_summary(&building, level, &open_issues) else {
+        return (StatusCode::NOT_FOUND, format!("No floor at level {}", level)).into_response();
+    };
+    let body = match serde_json::to_string(&summary) {
+        Ok(b) => b,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    let etag = crate::export::embed::etag(&body);
+    let cache_control = format!("public, max-age={}", EMBED_MAX_AGE_SECS);
+    let common = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, cache_control),
+        (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*".to_string()),
+    ];
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|t| t.trim() == etag));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, common).into_response();
+    }
+    (
+        common,
+        [(header::CONTENT_TYPE, "application/json")],
+        body,
+    )
+        .into_response()
+}
+
+/// One-time download of a finished `export.run` artifact.
+#[cfg(feature = "agent")]
+pub async fn http_export_download(
+    headers: HeaderMap,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Query(params): Query<AuthParams>,
+    State(state): State<Arc<AgentState>>,
+) -> impl IntoResponse {
+    use axum::http::header;
+    if !check_access(&headers, params.token.as_deref(), &state, "export.status") {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+    match crate::agent::exports::take_artifact(&state.repo_root, &id) {
+        Ok((job, bytes)) => {
+            let filename = job.filename.unwrap_or_else(|| id.clone()).replace('"', "");
+            (
+                [
+                    (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{}\"", filename),
+                    ),
+                ],
+                bytes,
+            )
+                .into_response()
+        }
+        Err(e) => (StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    }
+}
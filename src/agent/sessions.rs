@@ -0,0 +1,266 @@
+//! Connected clients and change notifications between them.
+//!
+//! Each WebSocket connection opens a session with its own id and the
+//! capabilities it authenticated with. When an action changes the repository
+//! (a commit, an import, a finished upload, …) the dispatcher publishes a
+//! [`REPO_UPDATED`] event; every other session allowed to read the building
+//! receives it as a JSON-RPC notification:
+//!
+//! ```json
+//! {"jsonrpc": "2.0", "method": "repo.updated",
+//!  "params": {"action": "git.commit", "origin": "<session id>", "by": "token", "at": "…"}}
+//! ```
+//!
+//! Changes on disk, including edits made outside the agent, arrive as
+//! [`FILES_CHANGED`] with the files from the repository watcher:
+//!
+//! ```json
+//! {"jsonrpc": "2.0", "method": "files.changed",
+//!  "params": {"action": "watch", "by": "filesystem", "at": "…",
+//!             "changes": [{"path": "building.yaml", "area": "building", "kind": "modified", "at": "…"}]}}
+//! ```
+//!
+//! `session.list` reports the open sessions.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::agent::auth::Caller;
+use crate::agent::watcher::FileChange;
+use crate::core::clock;
+
+/// Event sent after an action changes the repository.
+pub const REPO_UPDATED: &str = "repo.updated";
+
+/// Event sent when the repository watcher sees files change.
+pub const FILES_CHANGED: &str = "files.changed";
+
+/// Events buffered per subscriber before a slow client starts missing some.
+pub const EVENT_CAPACITY: usize = 64;
+
+/// Actions that change the repository when they succeed.
+pub const MUTATING_ACTIONS: &[&str] = &[
+    "git.commit",
+    "ifc.import",
+    "transfer.finish",
+    "claim.review",
+    "issue.submit",
+    "sensor.report",
+    "energy.record",
+    "collab.sync",
+    "collab.config.set",
+    "ar.pending.submit",
+    "ar.pending.approve",
+    "ar.pending.reject",
+    "room.create",
+    "room.update",
+    "room.delete",
+    "equipment.create",
+    "equipment.update",
+    "equipment.delete",
+];
+
+/// A session hears events if it holds any of these.
+const EVENT_READ_CAPABILITIES: &[&str] = &["building.get", "git.status"];
+
+tokio::task_local! {
+    static CURRENT_SESSION: String;
+}
+
+/// One connected client.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub transport: String,
+    /// `token` or `api-key:<id>`
+    pub caller: String,
+    pub capabilities: Vec<String>,
+    pub connected_at: DateTime<Utc>,
+}
+
+/// Change notification fanned out to sessions.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentEvent {
+    pub event: String,
+    pub action: String,
+    /// Session whose request caused the change (none for HTTP / SSH)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    pub by: String,
+    pub at: DateTime<Utc>,
+    /// Files behind a [`FILES_CHANGED`] event
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<FileChange>,
+}
+
+impl AgentEvent {
+    /// The JSON-RPC notification a client receives.
+    pub fn to_notification(&self) -> Value {
+        let mut note = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": self.event,
+            "params": {
+                "action": self.action,
+                "origin": self.origin,
+                "by": self.by,
+                "at": self.at,
+            }
+        });
+        if !self.changes.is_empty() {
+            note["params"]["changes"] = serde_json::json!(self.changes);
+        }
+        note
+    }
+}
+
+/// Open sessions plus the broadcast channel they listen on.
+pub struct SessionRegistry {
+    sessions: Mutex<HashMap<String, SessionInfo>>,
+    events: broadcast::Sender<AgentEvent>,
+}
+
+impl Default for SessionRegistry {
+    fn default() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+}
+
+fn caller_label(caller: &Caller) -> String {
+    match caller {
+        Caller::Token => "token".to_string(),
+        Caller::ApiKey { id, .. } => format!("api-key:{}", id),
+    }
+}
+
+impl SessionRegistry {
+    /// Register a connection; `capabilities` is what it may call.
+    pub fn open(&self, transport: &str, caller: &Caller, capabilities: Vec<String>) -> SessionInfo {
+        let session = SessionInfo {
+            id: clock::new_id(),
+            transport: transport.to_string(),
+            caller: caller_label(caller),
+            capabilities,
+            connected_at: clock::now(),
+        };
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session.id.clone(), session.clone());
+        session
+    }
+
+    pub fn close(&self, id: &str) {
+        self.sessions.lock().unwrap().remove(id);
+    }
+
+    /// Open sessions, oldest first.
+    pub fn list(&self) -> Vec<SessionInfo> {
+        let mut sessions: Vec<SessionInfo> =
+            self.sessions.lock().unwrap().values().cloned().collect();
+        sessions.sort_by(|a, b| a.connected_at.cmp(&b.connected_at).then(a.id.cmp(&b.id)));
+        sessions
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AgentEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish [`REPO_UPDATED`] if `action` changes the repository.
+    pub fn notify_action(&self, action: &str, caller: &Caller) {
+        if !MUTATING_ACTIONS.contains(&action) {
+            return;
+        }
+        let event = AgentEvent {
+            event: REPO_UPDATED.to_string(),
+            action: action.to_string(),
+            origin: CURRENT_SESSION.try_with(|id| id.clone()).ok(),
+            by: caller_label(caller),
+            at: clock::now(),
+            changes: Vec::new(),
+        };
+        // No subscribers is fine
+        let _ = self.events.send(event);
+    }
+
+    /// Publish [`FILES_CHANGED`] for `changes` seen on disk.
+    pub fn notify_files(&self, changes: Vec<FileChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        let event = AgentEvent {
+            event: FILES_CHANGED.to_string(),
+            action: "watch".to_string(),
+            origin: None,
+            by: "filesystem".to_string(),
+            at: clock::now(),
+            changes,
+        };
+        let _ = self.events.send(event);
+    }
+}
+
+/// Whether `session` should be told about `event`: not its own change, and it
+/// may read the building.
+pub fn should_deliver(session: &SessionInfo, event: &AgentEvent) -> bool {
+    event.origin.as_deref() != Some(session.id.as_str())
+        && session
+            .capabilities
+            .iter()
+            .any(|c| EVENT_READ_CAPABILITIES.contains(&c.as_str()))
+}
+
+/// Run `future` (a dispatch) on behalf of session `id`, so events it causes
+/// carry that origin.
+pub async fn in_session<F: Future>(id: String, future: F) -> F::Output {
+    CURRENT_SESSION.scope(id, future).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn commits_reach_other_readers_only() {
+        let registry = SessionRegistry::default();
+        let committer = registry.open("ws", &Caller::Token, vec!["building.get".into()]);
+        let viewer = registry.open("ws", &Caller::Token, vec!["git.status".into()]);
+        let intake = registry.open(
+            "ws",
+            &Caller::ApiKey {
+                id: "k1".into(),
+                capabilities: vec!["issue.submit".into()],
+            },
+            vec!["issue.submit".into()],
+        );
+        assert_eq!(registry.list().len(), 3);
+
+        let mut rx = registry.subscribe();
+        registry.notify_action("git.status", &Caller::Token);
+        in_session(committer.id.clone(), async {
+            registry.notify_action("git.commit", &Caller::Token)
+        })
+        .await;
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.event, REPO_UPDATED);
+        assert_eq!(event.action, "git.commit");
+        assert_eq!(event.origin.as_deref(), Some(committer.id.as_str()));
+        assert!(rx.try_recv().is_err());
+        assert!(!should_deliver(&committer, &event));
+        assert!(should_deliver(&viewer, &event));
+        assert!(!should_deliver(&intake, &event));
+        assert_eq!(event.to_notification()["method"], REPO_UPDATED);
+
+        registry.close(&viewer.id);
+        assert_eq!(registry.list().len(), 2);
+    }
+}
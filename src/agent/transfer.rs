@@ -0,0 +1,561 @@
+//! Chunked binary file transfer over the agent WebSocket.
+//!
+//! Large IFC files and point clouds move as raw binary frames instead of
+//! base64 inside JSON. Control messages are ordinary JSON-RPC actions:
+//!
+//! - `transfer.upload` `{path, size, sha256, chunk_size?}` starts (or resumes)
+//!   an upload to a repo-relative `path` under one of [`UPLOAD_DIRS`]; the
+//!   reply's `received` is the offset to send next. Uploads never replace a
+//!   file tracked in Git, so building data only changes through the actions
+//!   that check roles and validate it.
+//! - Binary frames carry the chunks; the agent answers each with a
+//!   `transfer.ack` (or `transfer.error`) text message.
+//! - `transfer.finish` `{transfer_id}` verifies the whole-file SHA-256 and moves
+//!   the upload into place.
+//! - `transfer.download` `{path, chunk_size?}` snapshots a repo file;
+//!   `transfer.fetch` `{transfer_id, offset}` answers with chunk metadata and
+//!   then the chunk as a binary frame.
+//!
+//! Frame layout: `ARXT`, a big-endian `u32` header length, the JSON
+//! [`ChunkHeader`], then the payload. Partial uploads live under
+//! [`TRANSFERS_DIR`], so a client that reconnects can resume from `received`.
+//! Transfers untouched for [`TRANSFER_TTL_SECS`] are removed.
+
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::clock;
+use crate::utils::path_safety::PathSafety;
+
+/// Repo-relative directory holding manifests and partial data.
+pub const TRANSFERS_DIR: &str = ".arx/agent/transfers";
+
+/// Repo-relative directories uploads may land in: IFC originals and scans.
+pub const UPLOAD_DIRS: &[&str] = &["imports", "scans"];
+
+/// Magic prefix of every binary frame.
+pub const FRAME_MAGIC: &[u8; 4] = b"ARXT";
+
+/// Chunk size when the client does not ask for one.
+pub const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Largest chunk either side may send.
+pub const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Unfinished transfers are dropped after this long without activity.
+pub const TRANSFER_TTL_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Upload,
+    Download,
+}
+
+/// Stored state of one transfer (`<id>.yaml` beside `<id>.part`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransferManifest {
+    pub id: String,
+    pub direction: Direction,
+    /// Repo-relative destination (upload) or source (download)
+    pub path: String,
+    pub size: u64,
+    /// Hex SHA-256 of the whole file
+    pub sha256: String,
+    pub chunk_size: usize,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Reply to the transfer actions and binary frames.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransferStatus {
+    pub transfer_id: String,
+    pub direction: Direction,
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    pub chunk_size: usize,
+    /// Bytes the agent holds: next upload offset; `size` for downloads
+    pub received: u64,
+    pub complete: bool,
+}
+
+/// JSON header of a binary frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkHeader {
+    pub transfer_id: String,
+    pub offset: u64,
+    /// Hex SHA-256 of this chunk's payload
+    pub sha256: String,
+    /// True on the final chunk of a download
+    #[serde(default)]
+    pub last: bool,
+}
+
+/// `transfer.upload` parameters.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadRequest {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn dir(repo_root: &Path) -> PathBuf {
+    repo_root.join(TRANSFERS_DIR)
+}
+
+fn manifest_path(repo_root: &Path, id: &str) -> PathBuf {
+    dir(repo_root).join(format!("{}.yaml", id))
+}
+
+fn part_path(repo_root: &Path, id: &str) -> PathBuf {
+    dir(repo_root).join(format!("{}.part", id))
+}
+
+fn chunk_size(requested: Option<usize>) -> Result<usize> {
+    match requested {
+        None => Ok(DEFAULT_CHUNK_SIZE),
+        Some(0) => bail!("chunk_size must be positive"),
+        Some(n) if n > MAX_CHUNK_SIZE => bail!("chunk_size exceeds {} bytes", MAX_CHUNK_SIZE),
+        Some(n) => Ok(n),
+    }
+}
+
+/// Resolve a client path inside the repo, keeping agent state and `.git` off limits.
+fn repo_path(repo_root: &Path, relative: &str) -> Result<PathBuf> {
+    PathSafety::validate_path_format(relative).map_err(|e| anyhow!(e))?;
+    PathSafety::detect_path_traversal(relative).map_err(|e| anyhow!(e))?;
+    let normalized = relative.replace('\\', "/");
+    let normalized = normalized.trim_start_matches("./");
+    if normalized.starts_with(".git/") || normalized.starts_with(".arx/") {
+        bail!("Transfers cannot touch '{}'", relative);
+    }
+    Ok(repo_root.join(relative))
+}
+
+/// Resolve an upload destination: inside [`UPLOAD_DIRS`] and not a tracked file.
+fn upload_path(repo_root: &Path, relative: &str) -> Result<PathBuf> {
+    let dest = repo_path(repo_root, relative)?;
+    let normalized = relative.replace('\\', "/");
+    let normalized = normalized.trim_start_matches("./");
+    let allowed = UPLOAD_DIRS.iter().any(|dir| {
+        normalized
+            .strip_prefix(dir)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .is_some_and(|name| !name.is_empty())
+    });
+    if !allowed {
+        bail!(
+            "Uploads must go under {}/ (got '{}')",
+            UPLOAD_DIRS.join("/ or "),
+            relative
+        );
+    }
+    if is_tracked(repo_root, normalized) {
+        bail!("Uploads cannot replace tracked file '{}'", relative);
+    }
+    Ok(dest)
+}
+
+/// Whether `relative` is in the Git index (false outside a repository).
+fn is_tracked(repo_root: &Path, relative: &str) -> bool {
+    git2::Repository::open(repo_root)
+        .and_then(|repo| repo.index())
+        .is_ok_and(|index| index.get_path(Path::new(relative), 0).is_some())
+}
+
+fn load(repo_root: &Path, id: &str) -> Result<TransferManifest> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        bail!("Invalid transfer id '{}'", id);
+    }
+    let text = fs::read_to_string(manifest_path(repo_root, id))
+        .map_err(|_| anyhow!("Unknown transfer '{}'", id))?;
+    Ok(serde_yaml::from_str(&text)?)
+}
+
+fn save(repo_root: &Path, manifest: &TransferManifest) -> Result<()> {
+    fs::create_dir_all(dir(repo_root))?;
+    fs::write(
+        manifest_path(repo_root, &manifest.id),
+        serde_yaml::to_string(manifest)?,
+    )?;
+    Ok(())
+}
+
+fn remove(repo_root: &Path, id: &str) {
+    let _ = fs::remove_file(manifest_path(repo_root, id));
+    let _ = fs::remove_file(part_path(repo_root, id));
+}
+
+fn manifests(repo_root: &Path) -> Vec<TransferManifest> {
+    let Ok(entries) = fs::read_dir(dir(repo_root)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "yaml"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|text| serde_yaml::from_str(&text).ok())
+        .collect()
+}
+
+/// Drop transfers idle for longer than [`TRANSFER_TTL_SECS`].
+fn prune(repo_root: &Path) {
+    let cutoff = clock::now() - chrono::Duration::seconds(TRANSFER_TTL_SECS);
+    for manifest in manifests(repo_root) {
+        if manifest.updated_at < cutoff {
+            remove(repo_root, &manifest.id);
+        }
+    }
+}
+
+fn status_of(repo_root: &Path, manifest: &TransferManifest) -> TransferStatus {
+    let received = match manifest.direction {
+        Direction::Upload => fs::metadata(part_path(repo_root, &manifest.id))
+            .map(|m| m.len())
+            .unwrap_or(0),
+        Direction::Download => manifest.size,
+    };
+    TransferStatus {
+        transfer_id: manifest.id.clone(),
+        direction: manifest.direction,
+        path: manifest.path.clone(),
+        size: manifest.size,
+        sha256: manifest.sha256.clone(),
+        chunk_size: manifest.chunk_size,
+        received,
+        complete: received == manifest.size,
+    }
+}
+
+/// Start an upload, or resume the unfinished one for the same path and checksum.
+pub fn begin_upload(repo_root: &Path, request: UploadRequest) -> Result<TransferStatus> {
+    upload_path(repo_root, &request.path)?;
+    let limit =
+        crate::resource_limits::max_ifc_bytes().max(crate::resource_limits::max_lidar_bytes());
+    if request.size > limit {
+        bail!(
+            "Upload of {} bytes exceeds the {} byte limit (ARX_MAX_IFC_BYTES / ARX_MAX_LIDAR_BYTES)",
+            request.size,
+            limit
+        );
+    }
+    let sha256 = request.sha256.to_ascii_lowercase();
+    if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("sha256 must be 64 hex characters");
+    }
+    prune(repo_root);
+    if let Some(existing) = manifests(repo_root)
+        .into_iter()
+        .find(|m| m.direction == Direction::Upload && m.path == request.path && m.sha256 == sha256)
+    {
+        return Ok(status_of(repo_root, &existing));
+    }
+    let manifest = TransferManifest {
+        id: clock::new_id(),
+        direction: Direction::Upload,
+        path: request.path,
+        size: request.size,
+        sha256,
+        chunk_size: chunk_size(request.chunk_size)?,
+        updated_at: clock::now(),
+    };
+    save(repo_root, &manifest)?;
+    fs::write(part_path(repo_root, &manifest.id), [])?;
+    Ok(status_of(repo_root, &manifest))
+}
+
+/// Snapshot a repo file for download.
+pub fn begin_download(
+    repo_root: &Path,
+    path: &str,
+    requested_chunk: Option<usize>,
+) -> Result<TransferStatus> {
+    let source = repo_path(repo_root, path)?;
+    let bytes = fs::read(&source).map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+    prune(repo_root);
+    let manifest = TransferManifest {
+        id: clock::new_id(),
+        direction: Direction::Download,
+        path: path.to_string(),
+        size: bytes.len() as u64,
+        sha256: sha256_hex(&bytes),
+        chunk_size: chunk_size(requested_chunk)?,
+        updated_at: clock::now(),
+    };
+    save(repo_root, &manifest)?;
+    fs::write(part_path(repo_root, &manifest.id), &bytes)?;
+    Ok(status_of(repo_root, &manifest))
+}
+
+/// Progress of transfer `id`.
+pub fn status(repo_root: &Path, id: &str) -> Result<TransferStatus> {
+    Ok(status_of(repo_root, &load(repo_root, id)?))
+}
+
+/// Split a binary frame into its header and payload.
+pub fn decode_frame(frame: &[u8]) -> Result<(ChunkHeader, &[u8])> {
+    if frame.len() < 8 || &frame[..4] != FRAME_MAGIC {
+        bail!("Not a transfer frame");
+    }
+    let header_len = u32::from_be_bytes([frame[4], frame[5], frame[6], frame[7]]) as usize;
+    let body = &frame[8..];
+    if header_len > body.len() {
+        bail!("Truncated transfer frame");
+    }
+    let header: ChunkHeader = serde_json::from_slice(&body[..header_len])
+        .map_err(|e| anyhow!("Invalid frame header: {}", e))?;
+    Ok((header, &body[header_len..]))
+}
+
+/// Build a binary frame for `payload`.
+pub fn encode_frame(header: &ChunkHeader, payload: &[u8]) -> Result<Vec<u8>> {
+    let header = serde_json::to_vec(header)?;
+    let mut frame = Vec::with_capacity(8 + header.len() + payload.len());
+    frame.extend_from_slice(FRAME_MAGIC);
+    frame.extend_from_slice(&(header.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&header);
+    frame.extend_from_slice(payload);
+    Ok(frame)
+}
+
+/// Store one upload chunk. Chunks must arrive in order: an offset other than
+/// `received` is refused so the client can resume from the right place.
+pub fn accept_chunk(repo_root: &Path, frame: &[u8]) -> Result<TransferStatus> {
+    let (header, payload) = decode_frame(frame)?;
+    let mut manifest = load(repo_root, &header.transfer_id)?;
+    if manifest.direction != Direction::Upload {
+        bail!("Transfer '{}' is a download", manifest.id);
+    }
+    if payload.is_empty() || payload.len() > manifest.chunk_size {
+        bail!(
+            "Chunk must be 1..={} bytes (got {})",
+            manifest.chunk_size,
+            payload.len()
+        );
+    }
+    if sha256_hex(payload) != header.sha256.to_ascii_lowercase() {
+        bail!("Chunk checksum mismatch at offset {}", header.offset);
+    }
+    let received = status_of(repo_root, &manifest).received;
+    if header.offset != received {
+        bail!("Expected offset {} but got {}", received, header.offset);
+    }
+    if received + payload.len() as u64 > manifest.size {
+        bail!(
+            "Chunk runs past the declared size of {} bytes",
+            manifest.size
+        );
+    }
+    let mut part = OpenOptions::new()
+        .append(true)
+        .open(part_path(repo_root, &manifest.id))?;
+    part.write_all(payload)?;
+    manifest.updated_at = clock::now();
+    save(repo_root, &manifest)?;
+    Ok(status_of(repo_root, &manifest))
+}
+
+/// Complete transfer `id`. Uploads are checked against their SHA-256 and moved
+/// to their destination (a mismatch discards the data); downloads are released.
+pub fn finish(repo_root: &Path, id: &str) -> Result<TransferStatus> {
+    let manifest = load(repo_root, id)?;
+    let status = status_of(repo_root, &manifest);
+    if manifest.direction == Direction::Download {
+        remove(repo_root, id);
+        return Ok(status);
+    }
+    if !status.complete {
+        bail!(
+            "Upload '{}' has {} of {} bytes",
+            id,
+            status.received,
+            status.size
+        );
+    }
+    let part = part_path(repo_root, id);
+    let bytes = fs::read(&part)?;
+    if sha256_hex(&bytes) != manifest.sha256 {
+        remove(repo_root, id);
+        bail!("Upload '{}' failed its SHA-256 check and was discarded", id);
+    }
+    let dest = upload_path(repo_root, &manifest.path)?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&part, &dest).or_else(|_| fs::write(&dest, &bytes))?;
+    remove(repo_root, id);
+    Ok(status)
+}
+
+/// Binary frame carrying the download chunk of `id` that starts at `offset`.
+pub fn read_chunk(repo_root: &Path, id: &str, offset: u64) -> Result<(ChunkHeader, Vec<u8>)> {
+    let mut manifest = load(repo_root, id)?;
+    if manifest.direction != Direction::Download {
+        bail!("Transfer '{}' is an upload", id);
+    }
+    // Only an empty file has a chunk at its end offset (the empty one at 0)
+    if offset > manifest.size || (offset == manifest.size && offset > 0) {
+        bail!(
+            "Offset {} is past the end ({} bytes)",
+            offset,
+            manifest.size
+        );
+    }
+    let length = (manifest.size - offset).min(manifest.chunk_size as u64) as usize;
+    let mut file = fs::File::open(part_path(repo_root, id))?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut payload = vec![0; length];
+    file.read_exact(&mut payload)?;
+    manifest.updated_at = clock::now();
+    save(repo_root, &manifest)?;
+    let header = ChunkHeader {
+        transfer_id: id.to_string(),
+        offset,
+        sha256: sha256_hex(&payload),
+        last: offset + length as u64 >= manifest.size,
+    };
+    let frame = encode_frame(&header, &payload)?;
+    Ok((header, frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &str, offset: u64, payload: &[u8]) -> Vec<u8> {
+        let header = ChunkHeader {
+            transfer_id: id.to_string(),
+            offset,
+            sha256: sha256_hex(payload),
+            last: false,
+        };
+        encode_frame(&header, payload).unwrap()
+    }
+
+    #[test]
+    fn upload_resumes_and_verifies_then_downloads() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let data = b"ISO-10303-21; a small ifc body".to_vec();
+        let request = || UploadRequest {
+            path: "imports/site.ifc".into(),
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+            chunk_size: Some(8),
+        };
+        let started = begin_upload(tmp.path(), request()).unwrap();
+        let id = started.transfer_id.clone();
+        assert_eq!(started.received, 0);
+
+        accept_chunk(tmp.path(), &chunk(&id, 0, &data[..8])).unwrap();
+        // Out-of-order and corrupted chunks are refused
+        assert!(accept_chunk(tmp.path(), &chunk(&id, 16, &data[16..24])).is_err());
+        let mut bad = chunk(&id, 8, &data[8..16]);
+        let last = bad.len() - 1;
+        bad[last] ^= 1;
+        assert!(accept_chunk(tmp.path(), &bad).is_err());
+        assert!(finish(tmp.path(), &id).is_err());
+
+        // Reconnect: same path and checksum resumes at the stored offset
+        let resumed = begin_upload(tmp.path(), request()).unwrap();
+        assert_eq!(resumed.transfer_id, id);
+        let mut offset = resumed.received;
+        for piece in data[offset as usize..].chunks(8) {
+            offset = accept_chunk(tmp.path(), &chunk(&id, offset, piece))
+                .unwrap()
+                .received;
+        }
+        assert!(finish(tmp.path(), &id).unwrap().complete);
+        assert_eq!(fs::read(tmp.path().join("imports/site.ifc")).unwrap(), data);
+        assert!(status(tmp.path(), &id).is_err());
+
+        let down = begin_download(tmp.path(), "imports/site.ifc", Some(16)).unwrap();
+        let mut rebuilt = Vec::new();
+        loop {
+            let (header, frame) =
+                read_chunk(tmp.path(), &down.transfer_id, rebuilt.len() as u64).unwrap();
+            let (_, payload) = decode_frame(&frame).unwrap();
+            rebuilt.extend_from_slice(payload);
+            if header.last {
+                break;
+            }
+        }
+        assert_eq!(rebuilt, data);
+        assert!(begin_download(tmp.path(), "../etc/passwd", None).is_err());
+        assert!(begin_download(tmp.path(), ".arx/agent/api-keys.yaml", None).is_err());
+    }
+
+    #[test]
+    fn fetch_past_the_end_is_refused() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("imports")).unwrap();
+        fs::write(tmp.path().join("imports/empty.ifc"), b"").unwrap();
+        fs::write(tmp.path().join("imports/site.ifc"), b"0123456789").unwrap();
+
+        let empty = begin_download(tmp.path(), "imports/empty.ifc", None).unwrap();
+        let (header, _) = read_chunk(tmp.path(), &empty.transfer_id, 0).unwrap();
+        assert!(header.last);
+        assert!(read_chunk(tmp.path(), &empty.transfer_id, 1).is_err());
+
+        let site = begin_download(tmp.path(), "imports/site.ifc", None).unwrap();
+        assert!(read_chunk(tmp.path(), &site.transfer_id, 9).is_ok());
+        assert!(read_chunk(tmp.path(), &site.transfer_id, 10).is_err());
+        assert!(read_chunk(tmp.path(), &site.transfer_id, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn uploads_stay_in_upload_dirs_and_off_tracked_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+        fs::create_dir_all(tmp.path().join("imports")).unwrap();
+        fs::write(tmp.path().join("imports/site.ifc"), b"committed").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("imports/site.ifc")).unwrap();
+        index.write().unwrap();
+
+        let data = b"replacement".to_vec();
+        let request = |path: &str| UploadRequest {
+            path: path.into(),
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+            chunk_size: None,
+        };
+        for path in [
+            "building.yaml",
+            "imports",
+            "imports/",
+            "importsx/a.ifc",
+            "imports/site.ifc",
+        ] {
+            assert!(begin_upload(tmp.path(), request(path)).is_err(), "{}", path);
+        }
+        assert!(begin_upload(tmp.path(), request("scans/site.las")).is_ok());
+
+        // A file tracked after the upload started is still protected at finish
+        let started = begin_upload(tmp.path(), request("imports/new.ifc")).unwrap();
+        accept_chunk(tmp.path(), &chunk(&started.transfer_id, 0, &data)).unwrap();
+        fs::write(tmp.path().join("imports/new.ifc"), b"committed").unwrap();
+        index.add_path(Path::new("imports/new.ifc")).unwrap();
+        index.write().unwrap();
+        assert!(finish(tmp.path(), &started.transfer_id).is_err());
+        assert_eq!(
+            fs::read(tmp.path().join("imports/new.ifc")).unwrap(),
+            b"committed"
+        );
+    }
+}
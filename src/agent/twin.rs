@@ -0,0 +1,120 @@
+//! `twin.snapshot`: canonical building state for PWA sync.
+//!
+//! The agent keeps the last few snapshots it handed out. A client that sends
+//! the hash of the snapshot it holds gets only the diff to the current state
+//! (or `unchanged`); any other client gets the full snapshot.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::core::twin::{TwinDiff, TwinSnapshot};
+use crate::persistence::sensors::latest_sensor_values;
+use crate::persistence::{load_building_at, BUILDING_YAML};
+
+/// Snapshots remembered as diff bases.
+const CACHED_SNAPSHOTS: usize = 8;
+
+static SNAPSHOTS: OnceLock<Mutex<VecDeque<TwinSnapshot>>> = OnceLock::new();
+
+fn snapshots() -> &'static Mutex<VecDeque<TwinSnapshot>> {
+    SNAPSHOTS.get_or_init(Default::default)
+}
+
+/// `twin.snapshot` parameters.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TwinSnapshotParams {
+    /// Hash of the snapshot the client already holds
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+/// `twin.snapshot` result: exactly one of the three shapes.
+#[derive(Debug, Serialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum TwinSnapshotResult {
+    /// The client is current
+    Unchanged { hash: String },
+    /// Changes from the client's snapshot
+    Diff { diff: TwinDiff },
+    /// No usable base; the whole state
+    Full { snapshot: TwinSnapshot },
+}
+
+/// Current snapshot of the repository at `repo_root`.
+pub fn capture(repo_root: &Path) -> Result<TwinSnapshot> {
+    let building = load_building_at(repo_root)
+        .map_err(|e| anyhow!("Failed to load {}: {}", BUILDING_YAML, e))?;
+    let latest = latest_sensor_values(repo_root)?;
+    Ok(TwinSnapshot::capture(&building, &latest))
+}
+
+/// Answer `twin.snapshot` and remember the current snapshot as a diff base.
+pub fn snapshot_since(repo_root: &Path, params: &TwinSnapshotParams) -> Result<TwinSnapshotResult> {
+    let current = capture(repo_root)?;
+    let mut cache = snapshots().lock().unwrap();
+    let result = match params.since.as_deref() {
+        Some(since) if since == current.hash => TwinSnapshotResult::Unchanged {
+            hash: current.hash.clone(),
+        },
+        Some(since) => match cache.iter().find(|s| s.hash == since) {
+            Some(base) => TwinSnapshotResult::Diff {
+                diff: base.diff(&current),
+            },
+            None => TwinSnapshotResult::Full {
+                snapshot: current.clone(),
+            },
+        },
+        None => TwinSnapshotResult::Full {
+            snapshot: current.clone(),
+        },
+    };
+    if !cache.iter().any(|s| s.hash == current.hash) {
+        cache.push_back(current);
+        while cache.len() > CACHED_SNAPSHOTS {
+            cache.pop_front();
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Floor};
+
+    #[test]
+    fn test_client_gets_full_then_diff_then_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        crate::persistence::save_building_unchecked_at(dir.path(), &building).unwrap();
+
+        let TwinSnapshotResult::Full { snapshot } =
+            snapshot_since(dir.path(), &TwinSnapshotParams::default()).unwrap()
+        else {
+            panic!("first sync is a full snapshot");
+        };
+
+        building.add_floor(Floor::new("Ground".into(), 0));
+        crate::persistence::save_building_unchecked_at(dir.path(), &building).unwrap();
+        let params = TwinSnapshotParams {
+            since: Some(snapshot.hash.clone()),
+        };
+        let TwinSnapshotResult::Diff { diff } = snapshot_since(dir.path(), &params).unwrap() else {
+            panic!("known base yields a diff");
+        };
+        assert_eq!(diff.added.len(), 1);
+        let updated = snapshot.apply(&diff).unwrap();
+
+        let params = TwinSnapshotParams {
+            since: Some(updated.hash),
+        };
+        assert!(matches!(
+            snapshot_since(dir.path(), &params).unwrap(),
+            TwinSnapshotResult::Unchanged { .. }
+        ));
+    }
+}
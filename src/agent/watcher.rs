@@ -1,9 +1,34 @@
-use std::path::{Path, PathBuf};
+//! File-system watchers over the repository.
+//!
+//! [`FileWatcher`] drives the agent's auto-import / auto-export conveniences
+//! (root-level files by extension). [`RepoWatcher`] follows the whole project,
+//! `building.yaml` plus the `.arx/` record folders (sensor data, issues, work
+//! orders, …), and reports each change as a [`FileChange`] for the dashboard
+//! and for agent sessions (`files.changed`).
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver};
 use std::time::Duration;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use notify::event::{ModifyKind, RenameMode};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+
+use crate::core::clock;
+use crate::persistence::issues::ISSUES_DIR;
+use crate::persistence::sensors::SENSOR_DATA_DIR;
+use crate::persistence::workorders::WORKORDERS_DIR;
+use crate::persistence::BUILDING_YAML;
+
+/// Paths under the root that never produce events (Git internals, caches, build
+/// output, and the audit log and job records every request appends to).
+const IGNORED_PREFIXES: &[&str] = &[".git", ".arx/cache", ".arx/audit", ".arx/jobs", "target"];
+
+/// Sensor inbox and trace folder.
+const SENSORS_DIR: &str = ".arx/sensors";
 
 /// File watcher for YAML building data files
 pub struct FileWatcher {
@@ -74,6 +99,205 @@ impl FileWatcher {
     }
 }
 
+/// Part of the project a change touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeArea {
+    /// `building.yaml`
+    Building,
+    /// Per-sensor history under `.arx/sensor-data/`
+    SensorData,
+    /// Sensor inbox and trace log under `.arx/sensors/`
+    Sensors,
+    Issues,
+    WorkOrders,
+    Other,
+}
+
+impl std::fmt::Display for ChangeArea {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ChangeArea::Building => "building",
+            ChangeArea::SensorData => "sensor data",
+            ChangeArea::Sensors => "sensors",
+            ChangeArea::Issues => "issues",
+            ChangeArea::WorkOrders => "work orders",
+            ChangeArea::Other => "other",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Removed => "removed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// One file that changed, relative to the repository root.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub area: ChangeArea,
+    pub kind: ChangeKind,
+    pub at: DateTime<Utc>,
+}
+
+/// Area of a repo-relative path, or `None` for paths that are not watched.
+pub fn classify(relative: &Path) -> Option<ChangeArea> {
+    let parts: Vec<&str> = relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    let path = parts.join("/");
+    let file = parts.last()?;
+    // Editor swap / backup files and atomic-write temporaries
+    if file.ends_with('~') || file.ends_with(".swp") || file.ends_with(".tmp") {
+        return None;
+    }
+    let under = |dir: &str| path == dir || path.starts_with(&format!("{}/", dir));
+    if IGNORED_PREFIXES.iter().any(|p| under(p)) {
+        return None;
+    }
+    Some(if path == BUILDING_YAML {
+        ChangeArea::Building
+    } else if under(SENSOR_DATA_DIR) {
+        ChangeArea::SensorData
+    } else if under(SENSORS_DIR) {
+        ChangeArea::Sensors
+    } else if under(ISSUES_DIR) {
+        ChangeArea::Issues
+    } else if under(WORKORDERS_DIR) {
+        ChangeArea::WorkOrders
+    } else {
+        ChangeArea::Other
+    })
+}
+
+/// Recursive watcher over a project, reporting [`FileChange`]s.
+pub struct RepoWatcher {
+    _watcher: RecommendedWatcher,
+    receiver: Receiver<Result<Event, notify::Error>>,
+    root: PathBuf,
+}
+
+impl RepoWatcher {
+    pub fn new(repo_root: &Path) -> Result<Self> {
+        let (tx, rx) = channel();
+        let mut watcher = RecommendedWatcher::new(
+            move |res| {
+                let _ = tx.send(res);
+            },
+            Config::default().with_poll_interval(Duration::from_millis(500)),
+        )?;
+        // Recursive from the root, so `.arx/` folders created later are covered too
+        watcher.watch(repo_root, RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            receiver: rx,
+            // Events carry canonical paths on some platforms
+            root: repo_root
+                .canonicalize()
+                .unwrap_or_else(|_| repo_root.to_path_buf()),
+        })
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.strip_prefix(&self.root).ok()
+    }
+
+    /// Changes since the last call (non-blocking), one per path in path order.
+    /// A file created and then written counts as created; one created and
+    /// removed again is dropped.
+    pub fn drain(&self) -> Vec<FileChange> {
+        let mut changes: BTreeMap<String, FileChange> = BTreeMap::new();
+        while let Ok(Ok(event)) = self.receiver.try_recv() {
+            let kind = match event.kind {
+                EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                    ChangeKind::Created
+                }
+                EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                    ChangeKind::Removed
+                }
+                EventKind::Modify(ModifyKind::Metadata(_)) => continue,
+                EventKind::Modify(_) => ChangeKind::Modified,
+                _ => continue,
+            };
+            let mut paths = Vec::new();
+            for path in event.paths {
+                if kind != ChangeKind::Removed && path.is_dir() {
+                    // Directories only count for the files already inside:
+                    // those can be written before the new folder is watched
+                    let watched = self.relative(&path).and_then(classify).is_some();
+                    if kind == ChangeKind::Created && watched {
+                        files_under(&path, &mut paths);
+                    }
+                } else {
+                    paths.push(path);
+                }
+            }
+            for path in &paths {
+                let Some(relative) = self.relative(path) else {
+                    continue;
+                };
+                let Some(area) = classify(relative) else {
+                    continue;
+                };
+                let key = relative.to_string_lossy().replace('\\', "/");
+                let kind = match (changes.get(&key).map(|c| c.kind), kind) {
+                    (Some(ChangeKind::Created), ChangeKind::Removed) => {
+                        changes.remove(&key);
+                        continue;
+                    }
+                    (Some(ChangeKind::Created), _) => ChangeKind::Created,
+                    (_, kind) => kind,
+                };
+                changes.insert(
+                    key.clone(),
+                    FileChange {
+                        path: key,
+                        area,
+                        kind,
+                        at: clock::now(),
+                    },
+                );
+            }
+        }
+        changes.into_values().collect()
+    }
+}
+
+/// Files below `dir`, recursively.
+fn files_under(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files_under(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,4 +346,59 @@ mod tests {
         let changed = watcher.check_for_changes();
         assert!(changed.is_none());
     }
+
+    #[test]
+    fn repo_watcher_reports_structured_changes() {
+        assert_eq!(classify(Path::new(".git/index")), None);
+        assert_eq!(classify(Path::new(".arx/cache/meshes/a.json")), None);
+        assert_eq!(
+            classify(Path::new(".arx/sensor-data/t1.csv")),
+            Some(ChangeArea::SensorData)
+        );
+        assert_eq!(
+            classify(Path::new(".arx/sensors/seen.yaml")),
+            Some(ChangeArea::Sensors)
+        );
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("building.yaml"), "name: A").unwrap();
+        let watcher = RepoWatcher::new(root).unwrap();
+        fs::write(root.join("building.yaml"), "name: B").unwrap();
+        fs::create_dir_all(root.join(".arx/sensor-data")).unwrap();
+        fs::write(root.join(".arx/sensor-data/t1.csv"), "x\n").unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join(".git/HEAD"), "ref").unwrap();
+        std::thread::sleep(Duration::from_millis(600));
+
+        let changes = watcher.drain();
+        let find = |path: &str| changes.iter().find(|c| c.path == path);
+        assert_eq!(find("building.yaml").unwrap().area, ChangeArea::Building);
+        let data = find(".arx/sensor-data/t1.csv").unwrap();
+        assert_eq!(
+            (data.area, data.kind),
+            (ChangeArea::SensorData, ChangeKind::Created)
+        );
+        assert!(changes.iter().all(|c| !c.path.starts_with(".git")));
+        assert!(watcher.drain().is_empty());
+    }
+
+    #[test]
+    fn repo_watcher_ignores_audit_log_appends() {
+        use crate::persistence::audit::{self, AuditEntry, AuditOutcome};
+
+        assert_eq!(classify(Path::new(".arx/audit/audit.jsonl")), None);
+        assert_eq!(classify(Path::new(".arx/jobs/abc.yaml")), None);
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join(".arx")).unwrap();
+        let watcher = RepoWatcher::new(root).unwrap();
+        for outcome in [AuditOutcome::Denied, AuditOutcome::Success] {
+            let entry = AuditEntry::new("agent.auth", None, outcome);
+            audit::record(root, &entry).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(600));
+        assert_eq!(watcher.drain(), Vec::new());
+    }
 }
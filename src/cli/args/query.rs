@@ -48,26 +48,46 @@ pub struct SearchArgs {
 
 /// Arguments for the Query command
 ///
-/// Query equipment by ArxAddress glob pattern.
+/// Query entities by hierarchical ArxAddress glob pattern.
 /// Supports hierarchical path queries: /country/state/city/building/floor/room/fixture
+/// where `*` matches within one segment, `**` spans any number of segments,
+/// and `!glob` negates a segment.
 ///
 /// # Examples
 ///
 /// ```bash
 /// # Find all boilers in mech rooms on any floor
-/// arx query "/usa/ny/*/floor-*/mech/boiler-*"
+/// arx query "/usa/ny/*/*/floor-*/mech/boiler-*"
 ///
 /// # Find all equipment in kitchen on floor 02
 /// arx query "/usa/ny/brooklyn/ps-118/floor-02/kitchen/*"
 ///
 /// # Find all HVAC equipment in any city
 /// arx query "/usa/ny/*/ps-118/floor-*/hvac/*"
+///
+/// # Everything on floor 02 outside the kitchen, at any depth
+/// arx query "/usa/**/floor-02/!kitchen/**" --kind all
+///
+/// # Rooms in PS-118 except mechanical spaces
+/// arx query "/**/ps-118/**" --kind room --not "/**/mech*"
+///
+/// # Match equipment by name or alias
+/// arx query "rooftop*"
 /// ```
 #[derive(Debug, Clone, Args)]
 pub struct QueryArgs {
-    /// ArxAddress glob pattern with wildcards (e.g., "/usa/ny/*/floor-*/mech/boiler-*")
+    /// ArxAddress glob pattern with wildcards (e.g., "/usa/**/mech/boiler-*"),
+    /// or a name / alias glob when it does not start with '/' (e.g., "rooftop*")
     pub pattern: String,
 
+    /// Drop matches of this address pattern (repeatable)
+    #[arg(long = "not")]
+    pub exclude: Vec<String>,
+
+    /// Entity kind to return (equipment, room, wing, floor, building, anchor, all)
+    #[arg(long, default_value = "equipment")]
+    pub kind: String,
+
     /// Output format (table, json, yaml)
     #[arg(long, default_value = "table")]
     pub format: String,
@@ -0,0 +1,440 @@
+//! Command catalog and programmatic execution.
+//!
+//! The catalog is read from the clap schema in [`super::spec`], so every
+//! surface that lists commands (agent, web, scripts) sees exactly the CLI's
+//! command set. [`run_command`] executes one catalog command through the real
+//! `arx` dispatcher in a child process and streams its output line by line.
+//! Each argument carries enough metadata (value type, choices, default,
+//! completion source) for a palette to prompt for it, and [`search_catalog`]
+//! ranks commands against a fuzzy query.
+
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command as Process, Stdio};
+use std::sync::mpsc;
+
+use clap::CommandFactory;
+use serde::{Deserialize, Serialize};
+
+use super::Cli;
+
+/// Top-level commands that need a terminal, would recurse into a server, or
+/// manage access and recovery (roles, sign-offs, backups), which stay with
+/// an operator at the terminal.
+pub const NON_SCRIPTABLE: &[&str] = &[
+    "agent",
+    "devtools",
+    "remote",
+    "spreadsheet",
+    "help",
+    "completions",
+    "wizard",
+    "role",
+    "approve",
+    "backup",
+];
+
+/// Kind of value an argument takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgType {
+    /// A switch; present or not
+    Flag,
+    String,
+    Integer,
+    Number,
+    /// One of `choices`
+    Choice,
+}
+
+/// Where a prompt can offer values for an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionSource {
+    Building,
+    Floor,
+    Wing,
+    Room,
+    Equipment,
+    /// A room or equipment item
+    Entity,
+    Sensor,
+    Commit,
+    Branch,
+    /// A file or directory
+    Path,
+}
+
+/// Arguments whose name says where their values come from.
+const COMPLETIONS: &[(&str, CompletionSource)] = &[
+    ("building", CompletionSource::Building),
+    ("building_id", CompletionSource::Building),
+    ("floor", CompletionSource::Floor),
+    ("wing", CompletionSource::Wing),
+    ("room", CompletionSource::Room),
+    ("room_id", CompletionSource::Room),
+    ("equipment", CompletionSource::Equipment),
+    ("equipment_id", CompletionSource::Equipment),
+    ("entity", CompletionSource::Entity),
+    ("sensor_id", CompletionSource::Sensor),
+    ("commit", CompletionSource::Commit),
+    ("branch", CompletionSource::Branch),
+    ("path", CompletionSource::Path),
+    ("file", CompletionSource::Path),
+    ("output", CompletionSource::Path),
+    ("workspace", CompletionSource::Path),
+    ("script", CompletionSource::Path),
+];
+
+/// One argument of a catalog command.
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogArg {
+    pub name: String,
+    /// `--long` form; `None` for positionals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long: Option<String>,
+    pub required: bool,
+    /// Takes a value (false for switches)
+    pub takes_value: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub help: String,
+    pub value_type: ArgType,
+    /// Accepted values, for `choice` arguments
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion: Option<CompletionSource>,
+}
+
+/// One runnable command, e.g. `energy report`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogEntry {
+    /// Words before the arguments, e.g. `["energy", "report"]`
+    pub command: Vec<String>,
+    pub about: String,
+    pub args: Vec<CatalogArg>,
+}
+
+/// A line of output from [`run_command`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "stream", content = "line", rename_all = "lowercase")]
+pub enum CommandOutput {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// A catalog entry ranked against a search query.
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogMatch {
+    /// Higher is better
+    pub score: i64,
+    #[serde(flatten)]
+    pub entry: CatalogEntry,
+}
+
+/// Values listed at the end of `help`, e.g. `Output format (table, json)`.
+fn listed_choices(help: &str) -> Vec<String> {
+    let Some(list) = help
+        .trim_end()
+        .strip_suffix(')')
+        .and_then(|h| h.rsplit_once('('))
+        .map(|(_, list)| list)
+    else {
+        return Vec::new();
+    };
+    let items: Vec<&str> = list.split(", ").collect();
+    let is_word = |w: &str| {
+        w.starts_with(|c: char| c.is_ascii_lowercase())
+            && w.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    };
+    if items.len() < 2 || !items.iter().all(|w| is_word(w)) {
+        return Vec::new();
+    }
+    items.into_iter().map(str::to_string).collect()
+}
+
+fn catalog_arg(arg: &clap::Arg) -> CatalogArg {
+    use std::any::TypeId;
+
+    let name = arg.get_id().to_string();
+    let takes_value = arg.get_action().takes_values();
+    let help = arg.get_help().map(|h| h.to_string()).unwrap_or_default();
+    let completion = match arg.get_value_hint() {
+        _ if !takes_value => None,
+        clap::ValueHint::AnyPath | clap::ValueHint::FilePath | clap::ValueHint::DirPath => {
+            Some(CompletionSource::Path)
+        }
+        _ => COMPLETIONS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, source)| *source),
+    };
+    let mut choices: Vec<String> = Vec::new();
+    if takes_value {
+        choices = arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        if choices.is_empty() && completion != Some(CompletionSource::Path) {
+            choices = listed_choices(&help);
+        }
+    }
+    let parser = arg.get_value_parser().type_id();
+    let value_type = if !takes_value {
+        ArgType::Flag
+    } else if !choices.is_empty() {
+        ArgType::Choice
+    } else if [
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<usize>(),
+    ]
+    .iter()
+    .any(|t| parser == *t)
+    {
+        ArgType::Integer
+    } else if parser == TypeId::of::<f64>() || parser == TypeId::of::<f32>() {
+        ArgType::Number
+    } else {
+        ArgType::String
+    };
+    let default = arg
+        .get_default_values()
+        .first()
+        .filter(|_| takes_value)
+        .map(|v| v.to_string_lossy().into_owned());
+
+    CatalogArg {
+        name,
+        long: arg.get_long().map(str::to_string),
+        required: arg.is_required_set(),
+        takes_value,
+        help,
+        value_type,
+        choices,
+        default,
+        completion,
+    }
+}
+
+fn collect(command: &clap::Command, prefix: &[String], out: &mut Vec<CatalogEntry>) {
+    for sub in command.get_subcommands().filter(|c| !c.is_hide_set()) {
+        let mut path = prefix.to_vec();
+        path.push(sub.get_name().to_string());
+        if path.len() == 1 && NON_SCRIPTABLE.contains(&sub.get_name()) {
+            continue;
+        }
+        if sub.has_subcommands() {
+            collect(sub, &path, out);
+            continue;
+        }
+        let args = sub
+            .get_arguments()
+            .filter(|a| !a.is_hide_set() && !a.is_global_set())
+            .filter(|a| !matches!(a.get_id().as_str(), "help" | "version"))
+            .map(catalog_arg)
+            .collect();
+        out.push(CatalogEntry {
+            command: path,
+            about: sub.get_about().map(|h| h.to_string()).unwrap_or_default(),
+            args,
+        });
+    }
+}
+
+/// Every scriptable command, in `--help` order.
+pub fn command_catalog() -> Vec<CatalogEntry> {
+    let mut entries = Vec::new();
+    collect(&Cli::command(), &[], &mut entries);
+    entries
+}
+
+/// Fuzzy score of `query` against `text`, ignoring case: every query
+/// character must appear in order. Consecutive characters and characters at
+/// the start of a word score higher, longer texts slightly lower.
+fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut at = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let offset = text[at..].iter().position(|&c| c == q)?;
+        let i = at + offset;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 10;
+        }
+        previous = Some(i);
+        at = i + 1;
+    }
+    Some(score * 10 - text.len() as i64 / 10)
+}
+
+/// Catalog commands matching `query`, best first (all of them, in catalog
+/// order, for an empty query). Command words weigh more than descriptions.
+pub fn search_catalog(query: &str, limit: usize) -> Vec<CatalogMatch> {
+    let catalog = command_catalog();
+    if query.trim().is_empty() {
+        return catalog
+            .into_iter()
+            .take(limit)
+            .map(|entry| CatalogMatch { score: 0, entry })
+            .collect();
+    }
+    let mut matches: Vec<CatalogMatch> = catalog
+        .into_iter()
+        .filter_map(|entry| {
+            let on_name = fuzzy_score(query, &entry.command.join(" ")).map(|s| s * 2);
+            let on_about = fuzzy_score(query, &entry.about);
+            let score = on_name.max(on_about)?;
+            Some(CatalogMatch { score, entry })
+        })
+        .collect();
+    // Stable: ties keep catalog order
+    matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+    matches.truncate(limit);
+    matches
+}
+
+/// Check `args` (without the leading `arx`) against the CLI schema.
+///
+/// Returns clap's message on failure, so callers can show the same usage
+/// error the terminal would.
+pub fn parse_command(args: &[String]) -> Result<Cli, String> {
+    use clap::Parser;
+
+    let first = args.iter().find(|a| !a.starts_with('-'));
+    if let Some(name) = first.filter(|n| NON_SCRIPTABLE.contains(&n.as_str())) {
+        return Err(format!("'{}' cannot be run programmatically", name));
+    }
+    Cli::try_parse_from(std::iter::once("arx".to_string()).chain(args.iter().cloned()))
+        .map_err(|e| e.render().to_string())
+}
+
+/// Run `arx <args>` in `cwd`, calling `on_output` for each line as it is
+/// printed; returns the exit code.
+///
+/// Arguments are validated with [`parse_command`] before anything starts.
+/// The child acts for the current thread's acting user (see
+/// [`crate::core::access::ACTING_USER_ENV`]) and runs with `--dry-run` while
+/// a dry run is active.
+pub fn run_command(
+    args: &[String],
+    cwd: &Path,
+    mut on_output: impl FnMut(CommandOutput),
+) -> Result<i32, Box<dyn Error>> {
+    let mut args = args.to_vec();
+    if crate::core::operations::is_dry_run() && !args.iter().any(|a| a == "--dry-run") {
+        args.insert(0, "--dry-run".to_string());
+    }
+    parse_command(&args)?;
+
+    // Same binary: the agent and the CLI are one `arx` executable
+    let mut process = Process::new(std::env::current_exe()?);
+    process
+        .args(&args)
+        .current_dir(cwd)
+        .env("NO_COLOR", "1")
+        .env_remove(crate::core::access::ACTING_USER_ENV);
+    if let Some(user) = crate::core::access::acting_user() {
+        process.env(crate::core::access::ACTING_USER_ENV, user);
+    }
+    let mut child = process
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let (tx, rx) = mpsc::channel();
+    let stdout = child.stdout.take().map(|out| {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(out).lines().map_while(Result::ok) {
+                let _ = tx.send(CommandOutput::Stdout(line));
+            }
+        })
+    });
+    let stderr = child.stderr.take().map(|err| {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(err).lines().map_while(Result::ok) {
+                let _ = tx.send(CommandOutput::Stderr(line));
+            }
+        })
+    });
+    drop(tx);
+    for line in rx {
+        on_output(line);
+    }
+    for reader in [stdout, stderr].into_iter().flatten() {
+        let _ = reader.join();
+    }
+    Ok(child.wait()?.code().unwrap_or(-1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_lists_leaf_commands_and_parse_rejects_bad_args() {
+        let catalog = command_catalog();
+        let report = catalog
+            .iter()
+            .find(|e| e.command == ["energy", "report"])
+            .expect("energy report in catalog");
+        assert!(report
+            .args
+            .iter()
+            .any(|a| a.long.as_deref() == Some("period") && a.takes_value));
+        assert!(catalog.iter().any(|e| e.command == ["validate"]));
+        assert!(!catalog.iter().any(|e| e.command[0] == "spreadsheet"));
+        assert!(!catalog.iter().any(|e| e.command[0] == "merge-driver"));
+
+        assert!(parse_command(&["validate".to_string()]).is_ok());
+        let Err(err) = parse_command(&["energy".into(), "report".into(), "--bogus".into()]) else {
+            panic!("unknown flag accepted");
+        };
+        assert!(err.contains("--bogus"));
+        assert!(parse_command(&["spreadsheet".into(), "rooms".into()]).is_err());
+        for admin in ["role", "approve", "backup"] {
+            assert!(parse_command(&[admin.into(), "--help".into()]).is_err());
+        }
+    }
+
+    #[test]
+    fn search_ranks_commands_and_describes_their_arguments() {
+        let matches = search_catalog("rm schd", 5);
+        assert_eq!(matches[0].entry.command, ["room", "schedule"]);
+        assert!(search_catalog("zzzz", 5).is_empty());
+        assert_eq!(search_catalog("", 3).len(), 3);
+
+        let report = command_catalog()
+            .into_iter()
+            .find(|e| e.command == ["completeness"])
+            .unwrap();
+        let arg = |name: &str| report.args.iter().find(|a| a.name == name).unwrap();
+        assert_eq!(arg("format").value_type, ArgType::Choice);
+        assert_eq!(arg("format").choices, ["table", "json", "markdown"]);
+        assert_eq!(arg("format").default.as_deref(), Some("table"));
+        assert_eq!(arg("min_score").value_type, ArgType::Number);
+        assert_eq!(arg("path").completion, Some(CompletionSource::Path));
+        assert!(arg("output").choices.is_empty());
+        let create = command_catalog()
+            .into_iter()
+            .find(|e| e.command == ["room", "create"])
+            .unwrap();
+        assert!(create.args.iter().all(|a| a.choices.is_empty()));
+    }
+}
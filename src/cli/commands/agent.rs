@@ -0,0 +1,235 @@
+//! Agent server, standby replica, and session tools - requires agent feature
+#![cfg(feature = "agent")]
+
+use super::Command;
+use crate::agent::auth::{parse_ttl, ApiKeyStore, TokenState, API_KEYS_FILE};
+use crate::agent::dispatcher::AgentState;
+use crate::agent::recorder::{load_session, prepare_workspace, replay_session};
+use crate::agent::replica::{self, ReplicaConfig, REPLICA_FILE};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub struct AgentCommand {
+    pub action: AgentAction,
+}
+
+pub enum AgentAction {
+    /// Run the agent; `replica` names the remote a standby pulls from.
+    Serve {
+        replica: Option<String>,
+        branch: Option<String>,
+        interval: u64,
+    },
+    /// Promote a standby replica to read-write.
+    Promote { path: Option<PathBuf> },
+    /// Re-dispatch a recorded session against a scratch workspace.
+    Replay {
+        file: PathBuf,
+        workspace: PathBuf,
+        verbose: bool,
+    },
+    /// Issue an API key for headless automation.
+    KeysCreate {
+        capabilities: Vec<String>,
+        expires: Option<String>,
+        name: Option<String>,
+    },
+    KeysList,
+    KeysRevoke { id: String },
+}
+
+impl AgentCommand {
+    fn serve(
+        replica_remote: Option<&str>,
+        branch: Option<&str>,
+        interval: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(remote) = replica_remote {
+            let repo_root = crate::agent::workspace::detect_repo_root()?;
+            let config = ReplicaConfig::new(remote, branch.map(str::to_string), interval);
+            config.save(&repo_root)?;
+            println!(
+                "🪞 Standby replica of '{}' (pull every {}s); promote with `arx agent promote`",
+                remote, config.interval_secs
+            );
+        }
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(crate::agent::start_agent())
+    }
+
+    fn promote(path: Option<&Path>) -> Result<(), Box<dyn Error>> {
+        let base = path.unwrap_or_else(|| Path::new("."));
+        let config = replica::promote(base)?;
+        println!(
+            "✅ Promoted to primary at {} ({} updated)",
+            config
+                .promoted_at
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                .unwrap_or_default(),
+            REPLICA_FILE
+        );
+        println!(
+            "💡 A running agent stops pulling from '{}' and accepts writes",
+            config.remote
+        );
+        Ok(())
+    }
+
+    fn keys_create(
+        capabilities: &[String],
+        expires: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let repo_root = crate::agent::workspace::detect_repo_root()?;
+        let ttl = expires.map(parse_ttl).transpose()?;
+        let mut store = ApiKeyStore::load(&repo_root)?;
+        let (secret, key) = store.create(name.map(str::to_string), capabilities.to_vec(), ttl)?;
+        store.save(&repo_root)?;
+        println!("🔑 API key {} created", key.id);
+        println!("   capabilities: {}", key.capabilities.join(", "));
+        match key.expires_at {
+            Some(at) => println!("   expires: {}", at.format("%Y-%m-%d %H:%M UTC")),
+            None => println!("   expires: never"),
+        }
+        println!("\n   {}\n", secret);
+        println!(
+            "⚠️  Store this secret now; only its hash is kept in {}",
+            API_KEYS_FILE
+        );
+        println!("💡 Send it as `Authorization: Bearer <key>` to /ws, /rpc, or the HTTP API");
+        Ok(())
+    }
+
+    fn keys_list() -> Result<(), Box<dyn Error>> {
+        let repo_root = crate::agent::workspace::detect_repo_root()?;
+        let store = ApiKeyStore::load(&repo_root)?;
+        if store.keys.is_empty() {
+            println!("No API keys (create one with `arx agent keys create`)");
+            return Ok(());
+        }
+        let now = crate::core::clock::now();
+        for key in &store.keys {
+            let expiry = match key.expires_at {
+                Some(_) if key.is_expired(now) => "expired".to_string(),
+                Some(at) => format!("expires {}", at.format("%Y-%m-%d")),
+                None => "no expiry".to_string(),
+            };
+            println!(
+                "  {}  {}  [{}]  {}",
+                key.id,
+                key.name.as_deref().unwrap_or("-"),
+                key.capabilities.join(", "),
+                expiry
+            );
+        }
+        Ok(())
+    }
+
+    fn keys_revoke(id: &str) -> Result<(), Box<dyn Error>> {
+        let repo_root = crate::agent::workspace::detect_repo_root()?;
+        let mut store = ApiKeyStore::load(&repo_root)?;
+        if !store.revoke(id) {
+            return Err(format!("No API key with id '{}'", id).into());
+        }
+        store.save(&repo_root)?;
+        println!("✅ Revoked API key {}", id);
+        Ok(())
+    }
+
+    fn replay(file: &Path, workspace: &Path, verbose: bool) -> Result<(), Box<dyn Error>> {
+        // Replay mutates its workspace; never point it at the live project
+        let cwd = std::env::current_dir()?.canonicalize()?;
+        if workspace.canonicalize().ok().as_ref() == Some(&cwd) {
+            return Err(
+                "Replay workspace must be a scratch directory, not the current project".into(),
+            );
+        }
+
+        let session = load_session(file)?;
+        prepare_workspace(workspace, &session)?;
+        println!(
+            "🎬 Replaying {} request(s) recorded {} into {}",
+            session.exchanges.len(),
+            session.started_at.format("%Y-%m-%d %H:%M"),
+            workspace.display()
+        );
+
+        let capabilities = crate::agent::auth::all_capabilities();
+        let state = Arc::new(AgentState {
+            repo_root: workspace.to_path_buf(),
+            token: Arc::new(Mutex::new(TokenState::new(
+                crate::agent::auth::generate_did_key(),
+                capabilities,
+            ))),
+            metrics: Arc::new(crate::agent::observability::AgentMetrics::new()),
+            reload_handle: None,
+            sessions: Default::default(),
+        });
+        let rt = tokio::runtime::Runtime::new()?;
+        let steps = rt.block_on(replay_session(state, &session));
+
+        let mut diverged = 0;
+        for step in &steps {
+            match &step.divergence {
+                None => println!("  ✅ #{} {}", step.seq, step.method),
+                Some((expected, actual)) => {
+                    diverged += 1;
+                    println!("  ❌ #{} {} diverged", step.seq, step.method);
+                    if verbose {
+                        println!("     expected: {}", expected);
+                        println!("     actual:   {}", actual);
+                    }
+                }
+            }
+        }
+        if diverged == 0 {
+            println!(
+                "✅ Session reproduced: all {} response(s) match",
+                steps.len()
+            );
+        } else {
+            println!(
+                "⚠️  {} of {} response(s) diverged{}",
+                diverged,
+                steps.len(),
+                if verbose {
+                    ""
+                } else {
+                    " (use --verbose for details)"
+                }
+            );
+        }
+        println!("💡 Workspace kept at {}", workspace.display());
+        Ok(())
+    }
+}
+
+impl Command for AgentCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        match &self.action {
+            AgentAction::Serve {
+                replica,
+                branch,
+                interval,
+            } => Self::serve(replica.as_deref(), branch.as_deref(), *interval),
+            AgentAction::Promote { path } => Self::promote(path.as_deref()),
+            AgentAction::Replay {
+                file,
+                workspace,
+                verbose,
+            } => Self::replay(file, workspace, *verbose),
+            AgentAction::KeysCreate {
+                capabilities,
+                expires,
+                name,
+            } => Self::keys_create(capabilities, expires.as_deref(), name.as_deref()),
+            AgentAction::KeysList => Self::keys_list(),
+            AgentAction::KeysRevoke { id } => Self::keys_revoke(id),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "agent"
+    }
+}
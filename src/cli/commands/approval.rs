@@ -0,0 +1,136 @@
+//! Approve and approvals commands: sign off held critical equipment changes.
+
+use super::Command;
+use crate::persistence::approvals::{
+    decide, load_record, load_records, verify_signature, ApprovalRecord, ApprovalStatus,
+    APPROVALS_CONFIG_FILE,
+};
+use crate::persistence::PersistenceManager;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct ApproveCommand {
+    pub change_id: String,
+    pub reject: bool,
+    pub note: Option<String>,
+    /// Sign the decision with GPG
+    pub sign: bool,
+    pub dry_run: bool,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for ApproveCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let approver = PersistenceManager::acting_user();
+        let record = decide(
+            &base,
+            &self.change_id,
+            &approver,
+            !self.reject,
+            self.note.clone(),
+            self.sign && !self.dry_run,
+        )?;
+        let verb = match (self.dry_run, self.reject) {
+            (true, false) => "Would approve",
+            (true, true) => "Would reject",
+            (false, false) => "Approved",
+            (false, true) => "Rejected",
+        };
+        println!(
+            "✅ {} {} ({}) requested by {}",
+            verb,
+            record.id,
+            record.summary(),
+            record.requested_by
+        );
+        if record.signature.is_some() {
+            println!("🔏 Signed by {}", approver);
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "approve"
+    }
+}
+
+pub struct ApprovalsCommand {
+    pub change_id: Option<String>,
+    /// Include decided requests
+    pub all: bool,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+fn status_marker(status: ApprovalStatus) -> &'static str {
+    match status {
+        ApprovalStatus::Pending => "⏳",
+        ApprovalStatus::Approved => "✅",
+        ApprovalStatus::Rejected => "❌",
+    }
+}
+
+fn print_record(record: &ApprovalRecord) {
+    println!(
+        "{} {}  {}  by {} at {}",
+        status_marker(record.status),
+        record.id,
+        record.summary(),
+        record.requested_by,
+        record.requested_at.format("%Y-%m-%d %H:%M")
+    );
+    if let (Some(by), Some(at)) = (&record.decided_by, record.decided_at) {
+        println!(
+            "   {:?} by {} at {}",
+            record.status,
+            by,
+            at.format("%Y-%m-%d %H:%M")
+        );
+    }
+}
+
+impl Command for ApprovalsCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+
+        if let Some(id) = &self.change_id {
+            let record = load_record(&base, id)?;
+            print_record(&record);
+            if let Some(note) = &record.note {
+                println!("   Note: {}", note);
+            }
+            for change in &record.changes {
+                println!("   • {}", change);
+            }
+            match verify_signature(&record)? {
+                Some(report) => println!("🔏 {}", report),
+                None if record.status == ApprovalStatus::Pending => {}
+                None => println!("   (unsigned)"),
+            }
+            return Ok(());
+        }
+
+        let records: Vec<ApprovalRecord> = load_records(&base)?
+            .into_iter()
+            .filter(|r| self.all || r.status == ApprovalStatus::Pending)
+            .collect();
+        if records.is_empty() && !base.join(APPROVALS_CONFIG_FILE).exists() {
+            println!(
+                "No sign-off rules: list critical equipment types or ids in {}",
+                APPROVALS_CONFIG_FILE
+            );
+            return Ok(());
+        }
+        for record in &records {
+            print_record(record);
+        }
+        println!("{} request(s)", records.len());
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "approvals"
+    }
+}
@@ -0,0 +1,67 @@
+//! Ask command: natural-language questions over building data.
+
+use super::Command;
+use crate::core::ask::{parse_question, run_query, AskBackend, AskConfig, AskContext};
+use crate::core::runtime::MaintenanceSchedules;
+use crate::persistence::{load_building_at, sensors::latest_sensor_values, BUILDING_YAML};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct AskCommand {
+    pub question: String,
+    /// Output format: table, json, yaml
+    pub format: String,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for AskCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+        let config = AskConfig::load(&base)?;
+        let context = AskContext {
+            schedules: MaintenanceSchedules::load_at(&base)?,
+            latest: latest_sensor_values(&base)?,
+        };
+
+        let llm = match (&config.backend, &config.llm) {
+            (AskBackend::Llm, Some(llm)) => {
+                match crate::core::ask::parse_with_llm(llm, &self.question) {
+                    Ok(query) => Some(query),
+                    Err(e) => {
+                        eprintln!("⚠️  LLM backend failed ({}); using the built-in grammar", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let query = match llm {
+            Some(query) => query,
+            None => parse_question(&self.question, &building, &context.schedules)?,
+        };
+
+        let matches = run_query(&building, &query, &context, chrono::Utc::now());
+        if self.format == "table" {
+            println!("🔍 Interpreted as: {}", query.describe());
+            println!();
+            if matches.is_empty() {
+                println!("❌ No equipment matches");
+                return Ok(());
+            }
+        }
+        super::query::print_matches(&matches, &self.format, false)
+    }
+
+    fn name(&self) -> &'static str {
+        "ask"
+    }
+}
@@ -0,0 +1,264 @@
+//! Attachment command: attach photos and documents to rooms and equipment, move
+//! large attachments to the object store, and fetch them back.
+
+use super::Command;
+use crate::core::Attachment;
+use crate::ingest::persist_building_at;
+use crate::persistence::attachments::{
+    referenced_paths, resolve, AttachmentStore, Pointer, ATTACHMENTS_CONFIG_FILE,
+};
+use crate::persistence::entity_attachments::{
+    add_attachment, attachments_of, remove_attachment, resolve_owner,
+};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct AttachmentCommand {
+    pub action: AttachmentAction,
+    /// Report what would move without uploading or rewriting files
+    pub dry_run: bool,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum AttachmentAction {
+    /// Copy a file into the directory of a room or equipment item.
+    Add {
+        entity: String,
+        file: PathBuf,
+        caption: Option<String>,
+        commit: bool,
+    },
+    /// Print attachments of one entity, or of every entity that has some.
+    List { entity: Option<String> },
+    /// Drop an attachment and delete its file.
+    Remove {
+        entity: String,
+        file: String,
+        commit: bool,
+    },
+    /// Replace referenced attachments at least this large with pointers.
+    Migrate { min_size_kb: Option<u64> },
+    /// Print the local path of one reference, or warm the cache for all of them.
+    Fetch { reference: Option<String> },
+}
+
+fn print_attachment(attachment: &Attachment) {
+    let caption = attachment
+        .caption
+        .as_deref()
+        .map(|c| format!(" — {}", c))
+        .unwrap_or_default();
+    println!(
+        "   {} {} ({} KiB){} · {}, {}",
+        if attachment.is_photo() {
+            "🖼️ "
+        } else {
+            "📄"
+        },
+        attachment.path,
+        attachment.size.div_ceil(1024),
+        caption,
+        attachment.uploaded_by,
+        attachment.uploaded_at.format("%Y-%m-%d %H:%M")
+    );
+}
+
+/// Stage `file` so the building.yaml commit that follows includes it.
+fn stage_with_building(base: &Path, file: &str) -> Result<(), Box<dyn Error>> {
+    use crate::git::manager::{BuildingGitManager, GitConfigManager};
+    let base_str = base.to_str().ok_or("base path is not valid UTF-8")?;
+    let mut git = BuildingGitManager::new(
+        base_str,
+        "building",
+        GitConfigManager::load_from_arx_config_or_env(),
+    )?;
+    git.stage_file(file)?;
+    Ok(())
+}
+
+fn open_store(base: &Path) -> Result<AttachmentStore, Box<dyn Error>> {
+    AttachmentStore::open(base)?.ok_or_else(|| {
+        format!(
+            "No attachment store: create {} with a `store` (directory or s3://bucket/prefix)",
+            ATTACHMENTS_CONFIG_FILE
+        )
+        .into()
+    })
+}
+
+impl Command for AttachmentCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let load = || {
+            load_building_at(&base).map_err(|e| {
+                format!(
+                    "Failed to load {} under {}: {}",
+                    BUILDING_YAML,
+                    base.display(),
+                    e
+                )
+            })
+        };
+
+        match &self.action {
+            AttachmentAction::Add {
+                entity,
+                file,
+                caption,
+                commit,
+            } => {
+                let mut building = load()?;
+                let uploader = crate::git::manager::GitConfigManager::load_from_arx_config_or_env()
+                    .author_name;
+                let added = add_attachment(
+                    &base,
+                    &mut building,
+                    entity,
+                    file,
+                    caption.clone(),
+                    &uploader,
+                )?;
+                let name = added.attachment.file_name().to_string();
+                if self.dry_run {
+                    println!(
+                        "🔍 Dry run: would attach {} to {} '{}'",
+                        added.attachment.path, added.owner.kind, added.owner.name
+                    );
+                    return Ok(());
+                }
+                let commit = *commit && base.join(".git").exists();
+                if commit {
+                    stage_with_building(&base, &added.attachment.path)?;
+                }
+                let message = format!(
+                    "Attach {} to {} '{}'",
+                    name, added.owner.kind, added.owner.name
+                );
+                persist_building_at(&base, building, commit, Some(&message))?;
+                println!(
+                    "✅ Attached {} to {} '{}'",
+                    added.attachment.path, added.owner.kind, added.owner.name
+                );
+                if added.external {
+                    println!("📦 Uploaded to the attachment store; the repository keeps a pointer");
+                }
+            }
+            AttachmentAction::List {
+                entity: Some(entity),
+            } => {
+                let building = load()?;
+                let owner = resolve_owner(&building, entity)?;
+                let attachments = attachments_of(&building, &owner);
+                println!(
+                    "📎 {} '{}': {} attachment(s)",
+                    owner.kind,
+                    owner.name,
+                    attachments.len()
+                );
+                attachments.iter().for_each(print_attachment);
+            }
+            AttachmentAction::List { entity: None } => {
+                let building = load()?;
+                let mut total = 0;
+                let rooms = building
+                    .get_all_rooms()
+                    .into_iter()
+                    .map(|r| ("room", r.name.as_str(), r.attachments.as_slice()));
+                let equipment = building
+                    .get_all_equipment()
+                    .into_iter()
+                    .map(|e| ("equipment", e.name.as_str(), e.attachments.as_slice()));
+                for (kind, name, attachments) in rooms.chain(equipment) {
+                    if attachments.is_empty() {
+                        continue;
+                    }
+                    println!("📎 {} '{}'", kind, name);
+                    attachments.iter().for_each(print_attachment);
+                    total += attachments.len();
+                }
+                println!("{} attachment(s)", total);
+            }
+            AttachmentAction::Remove {
+                entity,
+                file,
+                commit,
+            } => {
+                let mut building = load()?;
+                let (owner, attachment) = remove_attachment(&base, &mut building, entity, file)?;
+                if self.dry_run {
+                    println!(
+                        "🔍 Dry run: would remove {} from {} '{}'",
+                        attachment.path, owner.kind, owner.name
+                    );
+                    return Ok(());
+                }
+                let commit = *commit && base.join(".git").exists();
+                if commit {
+                    stage_with_building(&base, &attachment.path)?;
+                }
+                let message = format!(
+                    "Remove attachment {} from {} '{}'",
+                    attachment.file_name(),
+                    owner.kind,
+                    owner.name
+                );
+                persist_building_at(&base, building, commit, Some(&message))?;
+                println!(
+                    "🗑️  Removed {} from {} '{}'",
+                    attachment.path, owner.kind, owner.name
+                );
+            }
+            AttachmentAction::Migrate { min_size_kb } => {
+                let store = open_store(&base)?;
+                let building = load()?;
+                let min_kb = min_size_kb.unwrap_or(store.config.migrate_min_kb);
+                let report = store.migrate(&building, min_kb * 1024, self.dry_run)?;
+                let verb = if self.dry_run { "Would move" } else { "Moved" };
+                for (rel, size) in &report.moved {
+                    println!("📦 {} ({} KiB)", rel.display(), size.div_ceil(1024));
+                }
+                println!(
+                    "✅ {} {} attachment(s), {} KiB, to {}; {} already external, {} under {} KiB",
+                    verb,
+                    report.moved.len(),
+                    report.bytes_moved().div_ceil(1024),
+                    store.describe(),
+                    report.already_migrated,
+                    report.below_threshold,
+                    min_kb
+                );
+                if !self.dry_run && !report.moved.is_empty() {
+                    println!("💡 Commit the pointer files to drop the blobs from future history");
+                }
+            }
+            AttachmentAction::Fetch {
+                reference: Some(reference),
+            } => {
+                println!("{}", resolve(&base, reference)?.display());
+            }
+            AttachmentAction::Fetch { reference: None } => {
+                let store = open_store(&base)?;
+                let building = load()?;
+                let mut fetched = 0;
+                for rel in referenced_paths(&base, &building) {
+                    if let Some(pointer) = Pointer::read(&base.join(&rel))? {
+                        store.fetch(&pointer)?;
+                        fetched += 1;
+                    }
+                }
+                println!(
+                    "✅ {} external attachment(s) available in {}",
+                    fetched,
+                    store.cache_dir().display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "attachment"
+    }
+}
@@ -0,0 +1,104 @@
+//! Audit command: read the append-only audit log.
+
+use super::dataset::parse_time;
+use super::Command;
+use crate::persistence::audit::{load_entries, AuditEntry, AuditFilter, AuditOutcome};
+use std::error::Error;
+use std::path::Path;
+
+pub struct AuditCommand {
+    pub action: AuditAction,
+}
+
+pub enum AuditAction {
+    Tail {
+        lines: usize,
+        format: String,
+    },
+    Search {
+        actor: Option<String>,
+        action: Option<String>,
+        target: Option<String>,
+        outcome: Option<String>,
+        since: Option<String>,
+        limit: usize,
+        format: String,
+    },
+}
+
+fn parse_outcome(value: &str) -> Result<AuditOutcome, Box<dyn Error>> {
+    match value.to_lowercase().as_str() {
+        "success" => Ok(AuditOutcome::Success),
+        "failure" => Ok(AuditOutcome::Failure),
+        "denied" => Ok(AuditOutcome::Denied),
+        other => Err(format!("--outcome {}: expected success, failure, or denied", other).into()),
+    }
+}
+
+/// Print the last `limit` of `entries`.
+fn print_entries(entries: &[AuditEntry], limit: usize, format: &str) -> Result<(), Box<dyn Error>> {
+    let shown = &entries[entries.len().saturating_sub(limit)..];
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(shown)?);
+        return Ok(());
+    }
+    if shown.is_empty() {
+        println!("📭 No audit entries");
+        return Ok(());
+    }
+    for entry in shown {
+        let marker = match entry.outcome {
+            AuditOutcome::Success => "✓",
+            AuditOutcome::Failure => "✗",
+            AuditOutcome::Denied => "⛔",
+        };
+        println!(
+            "{} {} {:<16} {:<28} {}",
+            entry.at.format("%Y-%m-%d %H:%M:%S"),
+            marker,
+            entry.actor,
+            entry.action,
+            entry.target.as_deref().unwrap_or("")
+        );
+        if let Some(detail) = &entry.detail {
+            println!("      {}", detail);
+        }
+    }
+    if shown.len() < entries.len() {
+        println!("({} of {} entries)", shown.len(), entries.len());
+    }
+    Ok(())
+}
+
+impl Command for AuditCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let entries = load_entries(Path::new("."))?;
+        match &self.action {
+            AuditAction::Tail { lines, format } => print_entries(&entries, *lines, format),
+            AuditAction::Search {
+                actor,
+                action,
+                target,
+                outcome,
+                since,
+                limit,
+                format,
+            } => {
+                let filter = AuditFilter {
+                    actor: actor.clone(),
+                    action: action.clone(),
+                    target: target.clone(),
+                    outcome: outcome.as_deref().map(parse_outcome).transpose()?,
+                    since: parse_time("since", since.as_deref())?,
+                };
+                let matching: Vec<AuditEntry> =
+                    entries.into_iter().filter(|e| filter.matches(e)).collect();
+                print_entries(&matching, *limit, format)
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "audit"
+    }
+}
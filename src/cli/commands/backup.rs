@@ -0,0 +1,142 @@
+//! Backup command: create, list, prune, and verify backups configured in `.arx/backup.yaml`.
+
+use super::Command;
+use crate::persistence::backup::{
+    create_backup, is_due, list_backups, prune_backups, verify_backup, BackupConfig,
+};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct BackupCommand {
+    pub action: BackupAction,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum BackupAction {
+    /// Create a backup now (or only when the schedule says one is due).
+    Run { if_due: bool },
+    /// Print stored backups, oldest first.
+    List,
+    /// Apply the retention policy.
+    Prune,
+    /// Restore a backup into a scratch directory and validate it (default: newest).
+    Verify { id: Option<String>, all: bool },
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GiB", b as f64 / (1u64 << 30) as f64),
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1u64 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / 1024.0),
+        b => format!("{} B", b),
+    }
+}
+
+impl Command for BackupCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let config = BackupConfig::load(&base)?;
+        let target = config.open_target(&base)?;
+
+        match &self.action {
+            BackupAction::Run { if_due } => {
+                if *if_due {
+                    let latest = list_backups(target.as_ref())?.last().map(|m| m.created_at);
+                    if !is_due(&config, latest, chrono::Utc::now()) {
+                        println!("✅ No backup due (latest is within the schedule interval)");
+                        return Ok(());
+                    }
+                }
+                println!("💾 Backing up to {}...", target.describe());
+                let manifest = create_backup(&base, &config)?;
+                println!(
+                    "✅ {}: {} file(s), {}",
+                    manifest.id,
+                    manifest.files.len(),
+                    format_size(manifest.total_size())
+                );
+                if manifest.uncommitted_changes {
+                    println!("⚠️  building.yaml has uncommitted changes; only committed history is in the bundle");
+                }
+                let pruned = prune_backups(&config, target.as_ref())?;
+                if !pruned.is_empty() {
+                    println!("🧹 Pruned {} backup(s) past retention", pruned.len());
+                }
+            }
+            BackupAction::List => {
+                let backups = list_backups(target.as_ref())?;
+                if backups.is_empty() {
+                    println!("No backups in {}", target.describe());
+                    return Ok(());
+                }
+                println!("💾 {} backup(s) in {}", backups.len(), target.describe());
+                for m in &backups {
+                    println!(
+                        "  {}  {}  {:>9}  {}",
+                        m.id,
+                        m.created_at.format("%Y-%m-%d %H:%M"),
+                        format_size(m.total_size()),
+                        &m.head[..m.head.len().min(8)]
+                    );
+                }
+            }
+            BackupAction::Prune => {
+                let pruned = prune_backups(&config, target.as_ref())?;
+                if pruned.is_empty() {
+                    println!("✅ Nothing to prune");
+                }
+                for id in &pruned {
+                    println!("🧹 Removed {}", id);
+                }
+            }
+            BackupAction::Verify { id, all } => {
+                let ids: Vec<String> = match (id, all) {
+                    (Some(id), _) => vec![id.clone()],
+                    (None, true) => target.list()?,
+                    (None, false) => target.list()?.into_iter().last().into_iter().collect(),
+                };
+                if ids.is_empty() {
+                    return Err(format!("No backups in {}", target.describe()).into());
+                }
+                let mut failed = 0;
+                for id in &ids {
+                    let report = verify_backup(target.as_ref(), id)?;
+                    if report.is_ok() {
+                        println!(
+                            "✅ {}: {} file(s), {} verified; restored {} and validation matches ({} error(s))",
+                            report.id,
+                            report.files_checked,
+                            format_size(report.bytes_checked),
+                            report
+                                .restored_head
+                                .as_deref()
+                                .map(|h| &h[..h.len().min(8)])
+                                .unwrap_or("-"),
+                            report.validation_errors.unwrap_or(0)
+                        );
+                    } else {
+                        failed += 1;
+                        println!("❌ {} is not restorable:", report.id);
+                        for problem in &report.problems {
+                            println!("   {}", problem);
+                        }
+                    }
+                }
+                if failed > 0 {
+                    return Err(format!(
+                        "{} of {} backup(s) failed verification",
+                        failed,
+                        ids.len()
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "backup"
+    }
+}
@@ -0,0 +1,117 @@
+//! BACnet command: discover devices, poll mapped points into the sensor
+//! inbox, and bind points to equipment (`.arx/bacnet.yaml`).
+
+use super::Command;
+use crate::hardware::bacnet::client::parse_address;
+use crate::hardware::bacnet::{bind_points, poll_once, BacnetClient, BacnetConfig, BACNET_CONFIG};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub struct BacnetCommand {
+    pub action: BacnetAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum BacnetAction {
+    /// Broadcast Who-Is and list the devices that answer.
+    Discover {
+        target: Option<String>,
+        wait_ms: u64,
+    },
+    /// Read mapped points into sensor ingestion, once or every `interval_secs`.
+    Poll { once: bool },
+    /// Turn the mapping's `equipment` entries into sensor mappings.
+    Bind { commit: bool },
+}
+
+impl Command for BacnetCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let config = BacnetConfig::load_at(&base)?;
+
+        match &self.action {
+            BacnetAction::Discover { target, wait_ms } => {
+                let client = BacnetClient::from_config(&config)?;
+                let target = parse_address(target.as_deref().unwrap_or(&config.broadcast))?;
+                let devices = client.discover(target, None, Duration::from_millis(*wait_ms))?;
+                if devices.is_empty() {
+                    println!("📡 No BACnet devices answered Who-Is at {}", target);
+                    return Ok(());
+                }
+                println!("📡 {} BACnet device(s)", devices.len());
+                for d in &devices {
+                    let mapped = config
+                        .devices
+                        .iter()
+                        .find(|m| m.instance == d.instance)
+                        .map(|m| format!("  {} point(s) mapped", m.points.len()))
+                        .unwrap_or_default();
+                    println!(
+                        "  device {}  {}  vendor {}{}",
+                        d.instance, d.address, d.vendor_id, mapped
+                    );
+                }
+            }
+            BacnetAction::Poll { once } => {
+                if config.point_count() == 0 {
+                    return Err(format!("No BACnet points mapped in {}", BACNET_CONFIG).into());
+                }
+                let mut client = BacnetClient::from_config(&config)?;
+                loop {
+                    let report = poll_once(&base, &config, &mut client)?;
+                    println!(
+                        "📡 Recorded {} of {} point(s)",
+                        report.recorded,
+                        config.point_count()
+                    );
+                    for failure in &report.failures {
+                        println!("  ⚠️  {}", failure);
+                    }
+                    if *once {
+                        break;
+                    }
+                    std::thread::sleep(Duration::from_secs(config.interval_secs));
+                }
+            }
+            BacnetAction::Bind { commit } => {
+                let mut building = load_building_at(&base).map_err(|e| {
+                    format!(
+                        "Failed to load {} under {}: {}",
+                        BUILDING_YAML,
+                        base.display(),
+                        e
+                    )
+                })?;
+                let bound = bind_points(&mut building, &config)?;
+                if bound.is_empty() {
+                    println!("✅ All BACnet points with equipment are already mapped");
+                    return Ok(());
+                }
+                let message = format!("Map {} BACnet point(s)", bound.len());
+                crate::ingest::persist_building_at(&base, building, *commit, Some(&message))?;
+                for (sensor_id, equipment) in &bound {
+                    println!("📡 {} → {}", sensor_id, equipment);
+                }
+                println!("✅ Saved {} sensor mapping(s)", bound.len());
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "bacnet"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let BacnetAction::Discover { wait_ms, .. } = &self.action {
+            if *wait_ms == 0 {
+                return Err("--wait-ms must be greater than 0".into());
+            }
+        }
+        Ok(())
+    }
+}
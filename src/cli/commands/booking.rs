@@ -0,0 +1,173 @@
+//! Booking command: import room reservations from calendars and compare them
+//! with occupancy sensors.
+
+use super::Command;
+use crate::core::clock;
+use crate::core::domain::booking::{booking_report, resolve_booking_rooms};
+use crate::ingest::bookings::{build_source, BookingSource, BookingSources, IcsFileSource};
+use crate::persistence::bookings::{all_bookings, room_occupancy_samples, store_bookings};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use chrono::Duration;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct BookingCommand {
+    pub action: BookingAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum BookingAction {
+    /// Pull bookings from `.arx/bookings.yaml` sources (and/or one `.ics` file).
+    Import {
+        ics: Option<PathBuf>,
+        room: Option<String>,
+        days: i64,
+        ahead: i64,
+    },
+    /// Utilization vs bookings over the last `days`.
+    Report { days: i64, format: String },
+}
+
+impl Command for BookingCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+        let now = clock::now();
+
+        match &self.action {
+            BookingAction::Import {
+                ics,
+                room,
+                days,
+                ahead,
+            } => {
+                let mut sources: Vec<Box<dyn BookingSource>> = Vec::new();
+                for config in BookingSources::load_at(&base)?.sources {
+                    sources.push(build_source(&base, &config)?);
+                }
+                if let Some(path) = ics {
+                    sources.push(Box::new(IcsFileSource {
+                        path: path.clone(),
+                        room: room.clone(),
+                    }));
+                }
+                if sources.is_empty() {
+                    return Err(
+                        "No calendar sources: add .arx/bookings.yaml or pass --ics <file>".into(),
+                    );
+                }
+
+                let (from, to) = (now - Duration::days(*days), now + Duration::days(*ahead));
+                let mut fetched = Vec::new();
+                for source in &sources {
+                    let bookings = source.fetch(from, to)?;
+                    println!("📅 {}: {} booking(s)", source.kind(), bookings.len());
+                    fetched.extend(bookings);
+                }
+                let unresolved = resolve_booking_rooms(&building, &mut fetched);
+                let total = fetched.len();
+                let changed = store_bookings(&base, fetched)?;
+                println!(
+                    "✅ Imported {} booking(s), {} new or changed",
+                    total, changed
+                );
+                if unresolved > 0 {
+                    println!(
+                        "⚠️  {} booking(s) name a room not in the building (check room names / aliases)",
+                        unresolved
+                    );
+                }
+            }
+            BookingAction::Report { days, format } => {
+                let bookings = all_bookings(&base)?;
+                let samples = room_occupancy_samples(&base, &building)?;
+                let report = booking_report(
+                    &building,
+                    &bookings,
+                    &samples,
+                    now - Duration::days(*days),
+                    now,
+                );
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
+
+                println!("📊 Room utilization vs bookings (last {} day(s))", days);
+                if report.rooms.is_empty() {
+                    println!("   No bookings or occupancy readings in this period");
+                }
+                for room in &report.rooms {
+                    let utilization = room
+                        .utilization()
+                        .map(|u| format!("{:.0}%", u * 100.0))
+                        .unwrap_or_else(|| "-".to_string());
+                    println!(
+                        "   {:<24} booked {:>6.1}h  occupied {:>6.1}h  utilization {:>5}  ghosts {} ({:.1}h)",
+                        room.room,
+                        room.booked_hours,
+                        room.occupied_hours,
+                        utilization,
+                        room.ghost_meetings,
+                        room.ghost_hours
+                    );
+                }
+                if !report.ghosts.is_empty() {
+                    println!("👻 Ghost meetings (booked, sensors reporting, nobody seen):");
+                    for ghost in &report.ghosts {
+                        let b = &ghost.booking;
+                        println!(
+                            "   {}  {}  {}{}",
+                            b.start.format("%Y-%m-%d %H:%M"),
+                            b.room,
+                            b.title.as_deref().unwrap_or("(untitled)"),
+                            b.organizer
+                                .as_deref()
+                                .map(|o| format!(" — {}", o))
+                                .unwrap_or_default()
+                        );
+                    }
+                }
+                if report.unresolved_bookings > 0 {
+                    println!(
+                        "⚠️  {} booking(s) skipped: room not in the building",
+                        report.unresolved_bookings
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "booking"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        match &self.action {
+            BookingAction::Import { days, ahead, .. } => {
+                if *days < 0 || *ahead < 0 {
+                    return Err("--days and --ahead must not be negative".into());
+                }
+            }
+            BookingAction::Report { days, format } => {
+                if *days <= 0 {
+                    return Err("--days must be greater than 0".into());
+                }
+                if format != "table" && format != "json" {
+                    return Err(format!("Unknown format '{}' (use table or json)", format).into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
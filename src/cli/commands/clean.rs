@@ -0,0 +1,90 @@
+//! Clean command: evict artifacts down to their retention limits.
+
+use super::Command;
+use crate::persistence::artifacts::{clean, ArtifactsConfig, ARTIFACTS_CONFIG_FILE};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+pub struct CleanCommand {
+    /// Categories to clean; empty means all
+    pub categories: Vec<String>,
+    /// Remove every untracked file, ignoring limits
+    pub purge: bool,
+    /// Report evictions without removing files
+    pub dry_run: bool,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+fn mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+impl Command for CleanCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let all = ArtifactsConfig::load(&base)?.categories(&base)?;
+        for name in &self.categories {
+            if !all.iter().any(|c| &c.name == name) {
+                return Err(format!(
+                    "Unknown category '{}' (known: {}; add more in {})",
+                    name,
+                    all.iter()
+                        .map(|c| c.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    ARTIFACTS_CONFIG_FILE
+                )
+                .into());
+            }
+        }
+
+        let now = SystemTime::now();
+        let mut freed = 0;
+        let mut removed = 0;
+        for category in all
+            .iter()
+            .filter(|c| self.categories.is_empty() || self.categories.contains(&c.name))
+        {
+            let report = clean(&base, category, now, self.purge)?;
+            if report.files == 0 {
+                continue;
+            }
+            println!(
+                "🧹 {} ({}): {} file(s), {} → {}{}{}",
+                report.category,
+                report.dir.display(),
+                report.files,
+                mib(report.bytes_before),
+                mib(report.bytes_after()),
+                report
+                    .max_bytes
+                    .map(|m| format!(" (cap {})", mib(m)))
+                    .unwrap_or_default(),
+                if report.tracked > 0 {
+                    format!(", {} tracked by Git kept", report.tracked)
+                } else {
+                    String::new()
+                }
+            );
+            for e in &report.evicted {
+                println!("   - {} ({}, {})", e.path.display(), mib(e.size), e.reason);
+            }
+            freed += report.bytes_freed();
+            removed += report.evicted.len();
+        }
+
+        let verb = if self.dry_run {
+            "Would remove"
+        } else {
+            "Removed"
+        };
+        println!("✅ {} {} file(s), {}", verb, removed, mib(freed));
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "clean"
+    }
+}
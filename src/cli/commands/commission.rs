@@ -0,0 +1,352 @@
+//! Commission command: enroll equipment, sign off stages, report progress.
+
+use super::Command;
+use crate::core::alias::matches_name;
+use crate::core::commissioning::{
+    advance, commissioning_report, enroll, CommissioningReport, CommissioningStage, Evidence,
+    SignOff,
+};
+use crate::core::Building;
+use crate::ingest::persist_building_at;
+use crate::persistence::attachments::contained;
+use crate::persistence::sensors::load_sensor_readings;
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use chrono::Utc;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct CommissionCommand {
+    pub action: CommissionAction,
+    /// Check the sign-off without saving
+    pub dry_run: bool,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum CommissionAction {
+    /// Put equipment into a commissioning project.
+    Enroll {
+        equipment: Vec<String>,
+        project: String,
+        commit: bool,
+    },
+    /// Sign off the next stage (or reopen an earlier one).
+    Advance {
+        equipment: String,
+        to: Option<String>,
+        by: Option<String>,
+        photos: Vec<String>,
+        reading: Option<String>,
+        note: Option<String>,
+        commit: bool,
+    },
+    /// Current stage and history of one piece of equipment.
+    Status { equipment: String },
+    /// Progress per project.
+    Report {
+        project: Option<String>,
+        format: String,
+        output: Option<PathBuf>,
+    },
+}
+
+/// Equipment id for an id, name, or alias; errors when ambiguous.
+fn resolve_equipment(building: &Building, query: &str) -> Result<String, Box<dyn Error>> {
+    let all = building.get_all_equipment();
+    if let Some(eq) = all.iter().find(|e| e.id == query) {
+        return Ok(eq.id.clone());
+    }
+    let matches: Vec<_> = all
+        .iter()
+        .filter(|e| matches_name(&e.name, &e.aliases, query))
+        .collect();
+    match matches.as_slice() {
+        [eq] => Ok(eq.id.clone()),
+        [] => Err(format!("No equipment named '{}'", query).into()),
+        _ => Err(format!(
+            "'{}' matches {} equipment; use the id ({})",
+            query,
+            matches.len(),
+            matches
+                .iter()
+                .map(|e| e.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .into()),
+    }
+}
+
+/// Parse `sensor_id=value`.
+fn parse_reading(spec: &str) -> Result<Evidence, Box<dyn Error>> {
+    let (sensor_id, value) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("--reading '{}' must look like sensor_id=value", spec))?;
+    let value = value
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| format!("--reading '{}': {}", spec, e))?;
+    Ok(Evidence::TestReading {
+        sensor_id: sensor_id.trim().to_string(),
+        value,
+        at: Utc::now(),
+    })
+}
+
+/// Latest logged reading from any sensor mapped to the equipment.
+fn latest_trace_reading(
+    base: &Path,
+    sensor_ids: &[String],
+) -> Result<Option<Evidence>, Box<dyn Error>> {
+    Ok(load_sensor_readings(base)?
+        .into_iter()
+        .filter(|r| sensor_ids.contains(&r.sensor_id))
+        .max_by_key(|r| r.at)
+        .map(|r| Evidence::TestReading {
+            sensor_id: r.sensor_id,
+            value: r.value,
+            at: r.at,
+        }))
+}
+
+impl CommissionCommand {
+    fn render_table(report: &CommissioningReport) -> String {
+        let mut out = String::new();
+        if report.projects.is_empty() {
+            out.push_str("No equipment enrolled (see `arx commission enroll`)\n");
+        }
+        for p in &report.projects {
+            out.push_str(&format!(
+                "🏗️  {}: {} equipment, {:.0}% complete\n",
+                p.project,
+                p.equipment.len(),
+                p.completion * 100.0
+            ));
+            for e in &p.equipment {
+                out.push_str(&format!(
+                    "  {:<24} {:<14} {}\n",
+                    e.name,
+                    e.stage.map_or("not started", |s| s.as_str()),
+                    e.next
+                        .as_deref()
+                        .map(|n| format!("next: {}", n))
+                        .unwrap_or_else(|| "✅".into())
+                ));
+            }
+        }
+        out
+    }
+}
+
+impl Command for CommissionCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let mut building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+
+        match &self.action {
+            CommissionAction::Enroll {
+                equipment,
+                project,
+                commit,
+            } => {
+                let ids = equipment
+                    .iter()
+                    .map(|q| resolve_equipment(&building, q))
+                    .collect::<Result<Vec<_>, _>>()?;
+                for id in &ids {
+                    let eq = building.find_equipment_mut(id).expect("resolved above");
+                    enroll(eq, project)?;
+                    println!("🏗️  {} → {}", eq.name, project);
+                }
+                if self.dry_run {
+                    println!("🔍 Dry run: {} equipment would be enrolled", ids.len());
+                    return Ok(());
+                }
+                let message = format!("Commissioning: enroll {} in {}", ids.len(), project);
+                persist_building_at(&base, building, *commit, Some(&message))?;
+                println!("✅ Enrolled {} equipment in {}", ids.len(), project);
+            }
+            CommissionAction::Advance {
+                equipment,
+                to,
+                by,
+                photos,
+                reading,
+                note,
+                commit,
+            } => {
+                let id = resolve_equipment(&building, equipment)?;
+                let eq = building.find_equipment_mut(&id).expect("resolved above");
+                let state = eq.commissioning.as_ref().ok_or_else(|| {
+                    format!(
+                        "'{}' is not enrolled (run `arx commission enroll {} --project <name>`)",
+                        eq.name, equipment
+                    )
+                })?;
+                let stage = match to {
+                    Some(s) => CommissioningStage::parse(s)
+                        .ok_or_else(|| format!("Unknown stage '{}'", s))?,
+                    None => state
+                        .next_stage()
+                        .ok_or_else(|| format!("'{}' is already accepted", eq.name))?,
+                };
+
+                let mut evidence = Vec::new();
+                for photo in photos {
+                    let rel = Path::new(photo);
+                    if !contained(rel) || !base.join(rel).is_file() {
+                        return Err(
+                            format!("Photo '{}' must be a file inside the project", photo).into(),
+                        );
+                    }
+                    evidence.push(Evidence::Photo {
+                        path: photo.clone(),
+                    });
+                }
+                match reading {
+                    Some(spec) => evidence.push(parse_reading(spec)?),
+                    None if stage == CommissioningStage::Tested => {
+                        let sensor_ids: Vec<String> = eq
+                            .sensor_mappings
+                            .iter()
+                            .flatten()
+                            .map(|m| m.sensor_id.clone())
+                            .collect();
+                        if let Some(latest) = latest_trace_reading(&base, &sensor_ids)? {
+                            evidence.push(latest);
+                        }
+                    }
+                    None => {}
+                }
+                for e in &evidence {
+                    if let Evidence::TestReading {
+                        sensor_id,
+                        value,
+                        at,
+                    } = e
+                    {
+                        println!(
+                            "📡 {} = {} ({})",
+                            sensor_id,
+                            value,
+                            at.format("%Y-%m-%d %H:%M")
+                        );
+                    }
+                }
+
+                let by = match by {
+                    Some(by) => by.clone(),
+                    None => {
+                        crate::git::manager::GitConfigManager::load_from_arx_config_or_env()
+                            .author_name
+                    }
+                };
+                let name = eq.name.clone();
+                advance(
+                    eq,
+                    SignOff {
+                        stage,
+                        by: by.clone(),
+                        at: Utc::now(),
+                        evidence,
+                        note: note.clone(),
+                        reopened: false,
+                    },
+                )?;
+                let reopened = eq
+                    .commissioning
+                    .as_ref()
+                    .and_then(|c| c.history.last())
+                    .is_some_and(|s| s.reopened);
+                let verb = if reopened {
+                    "reopened at"
+                } else {
+                    "signed off"
+                };
+                if self.dry_run {
+                    println!("🔍 Dry run: {} would be {} {}", name, verb, stage);
+                    return Ok(());
+                }
+                let message = format!("Commissioning: {} {} {}", name, verb, stage);
+                persist_building_at(&base, building, *commit, Some(&message))?;
+                println!("✅ {} {} {} by {}", name, verb, stage, by);
+            }
+            CommissionAction::Status { equipment } => {
+                let id = resolve_equipment(&building, equipment)?;
+                let eq = building.find_equipment(&id).expect("resolved above");
+                let Some(state) = &eq.commissioning else {
+                    println!("{}: not enrolled in commissioning", eq.name);
+                    return Ok(());
+                };
+                println!(
+                    "🏗️  {} ({}): {}",
+                    eq.name,
+                    state.project,
+                    state.stage.map_or("not started", |s| s.as_str())
+                );
+                for s in &state.history {
+                    println!(
+                        "  {} {:<14} {}{}{}",
+                        s.at.format("%Y-%m-%d %H:%M"),
+                        s.stage,
+                        s.by,
+                        if s.reopened { " (reopened)" } else { "" },
+                        s.note
+                            .as_deref()
+                            .map(|n| format!(" — {}", n))
+                            .unwrap_or_default()
+                    );
+                }
+                match state.next_stage() {
+                    Some(next) => println!("💡 Next: {} (needs {})", next, next.requirement()),
+                    None => println!("✅ Accepted"),
+                }
+            }
+            CommissionAction::Report {
+                project,
+                format,
+                output,
+            } => {
+                let report = commissioning_report(&building, project.as_deref());
+                let rendered = match format.as_str() {
+                    "json" => serde_json::to_string_pretty(&report)?,
+                    "markdown" | "md" => report.to_markdown(),
+                    _ => Self::render_table(&report),
+                };
+                match output {
+                    Some(out) => {
+                        std::fs::write(out, rendered)?;
+                        println!("📝 Commissioning report: {}", out.display());
+                    }
+                    None => print!("{}", rendered),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "commission"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let CommissionAction::Report { format, .. } = &self.action {
+            if !matches!(format.as_str(), "table" | "json" | "markdown" | "md") {
+                return Err(format!(
+                    "Unknown format '{}' (expected table, json, markdown)",
+                    format
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+//! Compare command: entity-level differences between two building states.
+
+use super::Command;
+use crate::core::compare::{compare_buildings, BuildingComparison};
+use crate::core::Building;
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use crate::yaml::BuildingYamlSerializer;
+use serde::Serialize;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Diff two buildings, each a Git revision, a project directory, or a
+/// building.yaml / IFC file.
+pub struct CompareCommand {
+    /// The earlier (or reference) state
+    pub from: String,
+    /// The later state (default: the working building.yaml)
+    pub to: Option<String>,
+    /// Output format: text, json
+    pub format: String,
+    /// Also write the JSON report here
+    pub output: Option<PathBuf>,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct CompareReport<'a> {
+    from: &'a str,
+    to: &'a str,
+    #[serde(flatten)]
+    comparison: &'a BuildingComparison,
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(12)]
+}
+
+/// Building named by `source` and a label for it. Existing paths are read
+/// as files or project directories; anything else is a revision of the
+/// repository at `base`.
+fn load_source(base: &Path, source: &str) -> Result<(Building, String), Box<dyn Error>> {
+    let path = Path::new(source);
+    if path.is_dir() {
+        let building = load_building_at(path).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                path.display(),
+                e
+            )
+        })?;
+        return Ok((building, path.display().to_string()));
+    }
+    if path.is_file() {
+        let is_ifc = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ifc"));
+        let building = if is_ifc {
+            let resolved = crate::git::lfs::resolve_path(path)?;
+            crate::ifc::IFCProcessor::new().extract_hierarchy(&resolved.to_string_lossy())?
+        } else {
+            BuildingYamlSerializer::deserialize_building(&std::fs::read_to_string(path)?)
+                .map_err(|e| format!("{} does not parse: {}", path.display(), e))?
+        };
+        return Ok((building, path.display().to_string()));
+    }
+
+    if !base.join(".git").exists() {
+        return Err(format!(
+            "'{}' is not a file or directory, and {} is not a Git repository to look it up in",
+            source,
+            base.display()
+        )
+        .into());
+    }
+    let git = BuildingGitManager::new(
+        base.to_str().ok_or("base path is not valid UTF-8")?,
+        "building",
+        GitConfigManager::load_from_arx_config_or_env(),
+    )?;
+    let commit = git
+        .resolve_commit(source)
+        .map_err(|e| format!("Unknown revision '{}': {}", source, e))?;
+    let yaml = git
+        .read_file_at(&commit.id, BUILDING_YAML)?
+        .ok_or_else(|| format!("{} does not exist at {}", BUILDING_YAML, source))?;
+    let building = BuildingYamlSerializer::deserialize_building(&yaml)
+        .map_err(|e| format!("{} at {} does not parse: {}", BUILDING_YAML, source, e))?;
+    Ok((building, format!("{} ({})", source, short_id(&commit.id))))
+}
+
+impl Command for CompareCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let (before, from) = load_source(&base, &self.from)?;
+        let (after, to) = match &self.to {
+            Some(source) => load_source(&base, source)?,
+            None => {
+                let building = load_building_at(&base).map_err(|e| {
+                    format!(
+                        "Failed to load {} under {}: {}",
+                        BUILDING_YAML,
+                        base.display(),
+                        e
+                    )
+                })?;
+                (building, "working tree".to_string())
+            }
+        };
+
+        let comparison = compare_buildings(&before, &after);
+        let report = CompareReport {
+            from: &from,
+            to: &to,
+            comparison: &comparison,
+        };
+        let json = serde_json::to_string_pretty(&report)?;
+        if let Some(ref out) = self.output {
+            std::fs::write(out, &json)?;
+        }
+
+        if self.format == "json" {
+            println!("{}", json);
+        } else {
+            println!("🔀 Comparing {} → {}", from, to);
+            if comparison.is_empty() {
+                println!("✅ No differences");
+            } else {
+                for line in comparison.summary_lines() {
+                    println!("   {}", line);
+                }
+            }
+            if let Some(ref out) = self.output {
+                println!("📝 JSON report: {}", out.display());
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "compare"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn compares_a_revision_with_the_working_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let mut wing = Wing::new("East".into());
+        wing.add_room(Room::new("Lobby".into(), RoomType::Office));
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        let pm = crate::persistence::PersistenceManager::at(dir.path());
+        pm.save_and_commit(&building, Some("Survey")).unwrap();
+
+        building.floors[0].wings[0].add_room(Room::new("Lab".into(), RoomType::Laboratory));
+        crate::persistence::save_building_at(dir.path(), &building).unwrap();
+
+        let output = dir.path().join("compare.json");
+        CompareCommand {
+            from: "HEAD".into(),
+            to: None,
+            format: "text".into(),
+            output: Some(output.clone()),
+            path: Some(dir.path().to_path_buf()),
+        }
+        .execute()
+        .unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(output).unwrap()).unwrap();
+        assert_eq!(report["to"], "working tree");
+        assert_eq!(report["added"][0]["name"], "Lab");
+        assert_eq!(report["removed"].as_array().unwrap().len(), 0);
+    }
+}
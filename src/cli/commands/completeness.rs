@@ -0,0 +1,99 @@
+//! Completeness command: LOD / completeness score per room, floor, and building.
+
+use super::Command;
+use crate::core::completeness::{assess_completeness, CompletenessReport};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Report where the digital twin is thin.
+pub struct CompletenessCommand {
+    /// Output format: table, json, markdown
+    pub format: String,
+    /// Write the report here instead of stdout
+    pub output: Option<PathBuf>,
+    /// Minimum rollup score for rooms without a declared LOD
+    pub min_score: f64,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl CompletenessCommand {
+    fn render_table(&self, report: &CompletenessReport) -> String {
+        let mut out = report.summary_lines().join("\n");
+        out.push('\n');
+        let thin = report.thin_rooms(self.min_score);
+        if thin.is_empty() {
+            out.push_str("✅ No rooms below target\n");
+        } else {
+            out.push_str(&format!("⚠️  {} room(s) below target:\n", thin.len()));
+            for (floor, room) in thin {
+                out.push_str(&format!(
+                    "  {} / {}: {:.0}% (LOD {}{}) missing: {}\n",
+                    floor.name,
+                    room.room.name,
+                    room.rollup_score * 100.0,
+                    room.rollup_lod,
+                    room.room
+                        .declared_lod
+                        .map(|l| format!(", target {}", l))
+                        .unwrap_or_default(),
+                    room.room.checks.missing().join(", ")
+                ));
+            }
+        }
+        out
+    }
+}
+
+impl Command for CompletenessCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+
+        let report = assess_completeness(&building);
+        let rendered = match self.format.as_str() {
+            "json" => serde_json::to_string_pretty(&report)?,
+            "markdown" | "md" => report.to_markdown(),
+            _ => self.render_table(&report),
+        };
+
+        match self.output {
+            Some(ref out) => {
+                std::fs::write(out, rendered)?;
+                println!("📝 Completeness report: {}", out.display());
+            }
+            None => print!("{}", rendered),
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "completeness"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if !matches!(
+            self.format.as_str(),
+            "table" | "json" | "markdown" | "md"
+        ) {
+            return Err(format!(
+                "Unknown format '{}' (expected table, json, markdown)",
+                self.format
+            )
+            .into());
+        }
+        if !(0.0..=1.0).contains(&self.min_score) {
+            return Err("--min-score must be between 0 and 1".into());
+        }
+        Ok(())
+    }
+}
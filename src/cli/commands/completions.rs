@@ -0,0 +1,51 @@
+//! Completions command: shell completion scripts generated from the CLI schema.
+
+use super::Command;
+use crate::cli::Cli;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::error::Error;
+use std::io::Write;
+
+/// Print a completion script for `shell` to stdout.
+pub struct CompletionsCommand {
+    pub shell: Shell,
+}
+
+impl CompletionsCommand {
+    /// Completion script for `shell`, written to `out`.
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        // Rendered in memory first: `generate` panics on write errors such as
+        // a closed pipe
+        let mut script = Vec::new();
+        clap_complete::generate(self.shell, &mut Cli::command(), "arx", &mut script);
+        out.write_all(&script)
+    }
+}
+
+impl Command for CompletionsCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.write_to(&mut std::io::stdout())?;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "completions"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bash_script_completes_subcommands() {
+        let mut script = Vec::new();
+        CompletionsCommand { shell: Shell::Bash }
+            .write_to(&mut script)
+            .unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("complete -F _arx"));
+        assert!(script.contains("compare"));
+    }
+}
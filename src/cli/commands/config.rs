@@ -0,0 +1,67 @@
+//! Config command: report configuration sources, unknown keys, and errors.
+
+use super::Command;
+use crate::config::ConfigManager;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct ConfigDoctorCommand {
+    /// Project root holding arx.toml / .arxos/config.toml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for ConfigDoctorCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let doctor = ConfigManager::doctor(&base);
+
+        println!("🩺 Configuration sources (lowest to highest precedence):");
+        println!("   ·  defaults");
+        for (source, exists) in &doctor.files {
+            if *exists {
+                println!("   ✅ {}", source);
+            } else {
+                println!("   ·  {} (not found)", source);
+            }
+        }
+        if doctor.env_vars.is_empty() {
+            println!("   ·  environment (no ARX_* overrides set)");
+        } else {
+            println!("   ✅ environment ({})", doctor.env_vars.join(", "));
+        }
+
+        if !doctor.unknown_keys.is_empty() {
+            println!();
+            println!("⚠️  Unknown keys (ignored):");
+            for unknown in &doctor.unknown_keys {
+                match &unknown.suggestion {
+                    Some(s) => println!(
+                        "   {}: {} — did you mean {}?",
+                        unknown.source, unknown.key, s
+                    ),
+                    None => println!("   {}: {}", unknown.source, unknown.key),
+                }
+            }
+        }
+
+        println!();
+        match &doctor.error {
+            Some(e) => println!("❌ {}", e),
+            None => println!("✅ Configuration is valid"),
+        }
+
+        if doctor.is_healthy() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Configuration has {} problem(s)",
+                doctor.unknown_keys.len() + usize::from(doctor.error.is_some())
+            )
+            .into())
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "config doctor"
+    }
+}
@@ -0,0 +1,74 @@
+//! Cost command: budget rollups over work order and equipment costs.
+
+use super::Command;
+use crate::core::domain::cost::{rollup, CostGrouping, CostLine};
+use crate::persistence::load_building_at;
+use crate::persistence::workorders::load_work_orders;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct CostCommand {
+    pub action: CostAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum CostAction {
+    Report { by: CostGrouping, format: String },
+}
+
+fn print_line(line: &CostLine, acquisition: bool) {
+    print!(
+        "  {:<20} {:>6} {:>16} {:>16}",
+        line.key,
+        line.work_orders,
+        line.labor.to_string(),
+        line.parts.to_string()
+    );
+    if acquisition {
+        print!(" {:>16}", line.acquisition.to_string());
+    }
+    println!(" {:>16}", line.total.to_string());
+}
+
+impl Command for CostCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        match &self.action {
+            CostAction::Report { by, format } => {
+                let building = load_building_at(&base)?;
+                let orders = load_work_orders(&base)?;
+                let (lines, total) = rollup(&building, &orders, *by)?;
+                if format == "json" {
+                    let report = serde_json::json!({ "lines": lines, "total": total });
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
+                if lines.is_empty() {
+                    println!(
+                        "💰 No recorded costs (close work orders with --labor-cost or --part)"
+                    );
+                    return Ok(());
+                }
+                let acquisition = *by == CostGrouping::Equipment;
+                print!(
+                    "  {:<20} {:>6} {:>16} {:>16}",
+                    "Group", "Orders", "Labor", "Parts"
+                );
+                if acquisition {
+                    print!(" {:>16}", "Acquisition");
+                }
+                println!(" {:>16}", "Total");
+                for line in &lines {
+                    print_line(line, acquisition);
+                }
+                print_line(&total, acquisition);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "cost"
+    }
+}
@@ -4,18 +4,31 @@
 //! room management, equipment management, and spatial operations.
 
 use super::Command;
+use super::dataset::parse_time;
 use crate::cli::subcommands::{EquipmentCommands, RoomCommands, SpatialCommands};
+use crate::core::clock;
+use crate::core::domain::booking::{find_booking_room, room_schedule, RoomBooking, RoomSchedule};
 use crate::core::domain::ArxAddress;
+use crate::core::provenance;
+use crate::core::runtime::{
+    maintenance_status, record_service, set_status, MaintenanceSchedules, RuntimeCounter,
+    MAINTENANCE_FILE,
+};
+use crate::core::templates::find_template;
+use crate::core::uptime::uptime_report;
 use crate::core::{Dimensions, Position, SpatialProperties};
 use crate::core::{
     Equipment, EquipmentHealthStatus, EquipmentStatus, EquipmentType, Room, RoomType,
 };
+use crate::persistence::bookings::{all_bookings, reserve, room_occupancy_samples};
+use crate::persistence::sensors::sync_runtime;
+use crate::persistence::status_history::load_status_history;
+use crate::persistence::PersistenceManager;
 use std::collections::HashMap;
 use std::error::Error;
 use std::path::{Path, PathBuf};
 
 fn load_building_from_dir() -> Result<(PathBuf, crate::core::Building), Box<dyn Error>> {
-    use crate::persistence::PersistenceManager;
     let pm = PersistenceManager::from_cwd()?;
     let path = pm.building_yaml_path();
     let building = pm.load_building_data()?;
@@ -31,14 +44,40 @@ fn save_building_to_path(
     commit: bool,
     message: &str,
 ) -> Result<(), Box<dyn Error>> {
-    let base = path
-        .parent()
-        .filter(|p| !p.as_os_str().is_empty())
-        .unwrap_or_else(|| Path::new("."));
-    crate::ingest::persist_building_at(base, building, commit, Some(message))?;
+    crate::ingest::persist_building_at(project_root(path), building, commit, Some(message))?;
     Ok(())
 }
 
+/// Project root of a `building.yaml` path.
+fn project_root(path: &Path) -> &Path {
+    path.parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+}
+
+fn print_registered_schedules(schedules: &[String]) {
+    if !schedules.is_empty() {
+        println!(
+            "🔧 Added template schedules to {}: {}",
+            MAINTENANCE_FILE,
+            schedules.join(", ")
+        );
+    }
+}
+
+/// `12.3 h (running)`
+fn runtime_label(runtime: &RuntimeCounter, now: chrono::DateTime<chrono::Utc>) -> String {
+    format!(
+        "{:.1} h{}",
+        runtime.hours_at(now),
+        if runtime.is_running() {
+            " (running)"
+        } else {
+            ""
+        }
+    )
+}
+
 fn parse_dimensions(input: &str) -> Result<Dimensions, Box<dyn Error>> {
     let cleaned = input.replace('X', "x");
     let parts: Vec<&str> = cleaned.split('x').collect();
@@ -91,18 +130,7 @@ fn parse_properties(props: &[String]) -> Result<HashMap<String, String>, Box<dyn
 }
 
 fn parse_equipment_type(input: &str) -> Result<EquipmentType, Box<dyn Error>> {
-    let normalized = input.trim().to_lowercase();
-    let eq_type = match normalized.as_str() {
-        "hvac" => EquipmentType::HVAC,
-        "electrical" => EquipmentType::Electrical,
-        "av" => EquipmentType::AV,
-        "furniture" => EquipmentType::Furniture,
-        "safety" => EquipmentType::Safety,
-        "plumbing" => EquipmentType::Plumbing,
-        "network" => EquipmentType::Network,
-        _ => EquipmentType::Other(input.to_string()),
-    };
-    Ok(eq_type)
+    Ok(input.parse()?)
 }
 
 fn parse_equipment_status(input: &str) -> Result<EquipmentStatus, Box<dyn Error>> {
@@ -178,7 +206,10 @@ fn apply_equipment_updates(
         match key.trim().to_lowercase().as_str() {
             "name" => equipment.name = value.trim().to_string(),
             "equipment_type" => equipment.equipment_type = parse_equipment_type(value)?,
-            "status" => equipment.status = parse_equipment_status(value)?,
+            "status" => {
+                let status = parse_equipment_status(value)?;
+                set_status(equipment, status, clock::now());
+            }
             "health_status" => equipment.health_status = Some(parse_health_status(value)?),
             "room" | "room_id" => equipment.room_id = Some(value.trim().to_string()),
             "address" => {
@@ -223,6 +254,7 @@ impl Command for RoomCommand {
                 wing,
                 name,
                 room_type,
+                template,
                 dimensions,
                 position,
                 commit,
@@ -235,7 +267,16 @@ impl Command for RoomCommand {
                     )
                     .into());
                 }
-
+                let base = project_root(&path);
+                let template = template
+                    .as_deref()
+                    .map(|t| find_template(base, t))
+                    .transpose()?;
+
+                let room_type = room_type
+                    .as_deref()
+                    .or_else(|| template.as_ref().and_then(|(t, _)| t.room_type.as_deref()))
+                    .ok_or("--room-type is required without --template")?;
                 let parsed_room_type: RoomType = room_type.parse()?;
                 let mut room = Room::new(name.clone(), parsed_room_type);
 
@@ -255,11 +296,17 @@ impl Command for RoomCommand {
                 if let Some(pos_str) = position.as_deref() {
                     pos = parse_position(pos_str, &coordinate_system)?;
                 }
-                if let Some(dim_str) = dimensions.as_deref() {
+                if let Some(dim_str) = dimensions
+                    .as_deref()
+                    .or_else(|| template.as_ref().and_then(|(t, _)| t.dimensions.as_deref()))
+                {
                     dims = parse_dimensions(dim_str)?;
                 }
 
                 room.spatial_properties = SpatialProperties::new(pos, dims, coordinate_system);
+                if let Some((template, templates)) = &template {
+                    template.instantiate_room_equipment(&mut room, templates)?;
+                }
 
                 let floor_ref = if let Some(floor_ref) = model.find_floor_mut(*floor) {
                     floor_ref
@@ -286,7 +333,19 @@ impl Command for RoomCommand {
 
                 save_building_to_path(&path, model, *commit, &format!("Add room: {}", room.name))?;
 
+                // Schedules follow the save so a failed or refused create leaves none behind
+                let mut schedules = Vec::new();
+                if let Some((template, templates)) = &template {
+                    for used in template.equipment_templates(templates) {
+                        schedules.extend(used.register_schedules(base)?);
+                    }
+                }
+
                 println!("✅ Created room: {}", room.name);
+                for eq in &room.equipment {
+                    println!("   + {} ({})", eq.name, eq.equipment_type);
+                }
+                print_registered_schedules(&schedules);
                 Ok(())
             }
             RoomCommands::List {
@@ -354,7 +413,7 @@ impl Command for RoomCommand {
                 for floor_ref in &model.floors {
                     for wing_ref in &floor_ref.wings {
                         if let Some(r) = wing_ref.rooms.iter().find(|r| {
-                            r.name.eq_ignore_ascii_case(room) || r.id.eq_ignore_ascii_case(room)
+                            r.matches_name(room) || r.id.eq_ignore_ascii_case(room)
                         }) {
                             found_room = Some(r);
                             break;
@@ -370,6 +429,9 @@ impl Command for RoomCommand {
                 println!("   ID: {}", room_ref.id);
                 println!("   Type: {}", room_ref.room_type);
                 println!("   Equipment count: {}", room_ref.equipment.len());
+                if !room_ref.provenance.is_empty() {
+                    println!("   Provenance: {}", provenance::summarize(&room_ref.provenance));
+                }
 
                 if *equipment {
                     for eq in &room_ref.equipment {
@@ -390,7 +452,7 @@ impl Command for RoomCommand {
                 for floor_ref in &mut model.floors {
                     for wing_ref in &mut floor_ref.wings {
                         if let Some(room_ref) = wing_ref.rooms.iter_mut().find(|r| {
-                            r.name.eq_ignore_ascii_case(room) || r.id.eq_ignore_ascii_case(room)
+                            r.matches_name(room) || r.id.eq_ignore_ascii_case(room)
                         }) {
                             apply_room_updates(room_ref, property)?;
                             updated_room = Some(room_ref.clone());
@@ -453,6 +515,140 @@ impl Command for RoomCommand {
                     Err("Invalid room delete arguments".into())
                 }
             }
+            RoomCommands::Book {
+                room,
+                start,
+                end,
+                minutes,
+                title,
+                organizer,
+            } => {
+                let (path, model) = load_building_from_dir()?;
+                let base = path.parent().unwrap_or(Path::new("."));
+                let target = find_booking_room(&model, room)
+                    .ok_or_else(|| format!("Room '{}' not found", room))?;
+                let start = parse_time("start", Some(start))?.ok_or("--start is required")?;
+                let end = match (end, minutes) {
+                    (Some(end), _) => parse_time("end", Some(end))?.ok_or("--end is required")?,
+                    (None, Some(m)) if *m > 0 => start + chrono::Duration::minutes(*m),
+                    (None, Some(_)) => return Err("--minutes must be greater than 0".into()),
+                    (None, None) => return Err("Pass --end or --minutes".into()),
+                };
+                let booking = reserve(
+                    base,
+                    RoomBooking {
+                        id: String::new(),
+                        source: String::new(),
+                        room: target.name.clone(),
+                        room_id: Some(target.id.clone()),
+                        start,
+                        end,
+                        title: title.clone(),
+                        organizer: Some(
+                            organizer
+                                .clone()
+                                .unwrap_or_else(PersistenceManager::acting_user),
+                        ),
+                    },
+                )?;
+
+                println!(
+                    "📅 Booked {} {} – {} ({})",
+                    booking.room,
+                    booking.start.format("%Y-%m-%d %H:%M"),
+                    booking.end.format("%H:%M"),
+                    booking.id
+                );
+                Ok(())
+            }
+            RoomCommands::Schedule {
+                room,
+                days,
+                format,
+                interactive,
+            } => {
+                if *days <= 0 {
+                    return Err("--days must be greater than 0".into());
+                }
+                if format != "table" && format != "json" {
+                    return Err(format!("Unknown format '{}' (use table or json)", format).into());
+                }
+                let (path, model) = load_building_from_dir()?;
+                let base = path.parent().unwrap_or(Path::new("."));
+                let bookings = all_bookings(base)?;
+                let samples = room_occupancy_samples(base, &model)?;
+                let now = clock::now();
+                let rooms: Vec<&Room> = match room {
+                    Some(needle) => vec![find_booking_room(&model, needle)
+                        .ok_or_else(|| format!("Room '{}' not found", needle))?],
+                    None => model.get_all_rooms(),
+                };
+
+                if *interactive {
+                    #[cfg(feature = "tui")]
+                    {
+                        let selected = rooms
+                            .first()
+                            .filter(|_| room.is_some())
+                            .map(|r| r.id.clone());
+                        return run_calendar(model.clone(), bookings, samples, now, selected);
+                    }
+                    #[cfg(not(feature = "tui"))]
+                    return Err("Calendar view requires --features tui".into());
+                }
+
+                let to = now + chrono::Duration::days(*days);
+                let schedules: Vec<RoomSchedule> = rooms
+                    .into_iter()
+                    .map(|r| room_schedule(r, &bookings, &samples, now, to, now))
+                    .filter(|s| room.is_some() || !s.bookings.is_empty() || s.occupancy.is_some())
+                    .collect();
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&schedules)?);
+                    return Ok(());
+                }
+
+                if schedules.is_empty() {
+                    println!("📭 No bookings in the next {} day(s)", days);
+                }
+                for schedule in &schedules {
+                    println!(
+                        "📅 {}  capacity {}  occupancy now {}",
+                        schedule.room,
+                        schedule
+                            .capacity
+                            .map(|c| c.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        schedule
+                            .occupancy
+                            .map(|o| format!("{:.0}", o))
+                            .unwrap_or_else(|| "-".to_string())
+                    );
+                    if schedule.bookings.is_empty() {
+                        println!("   No bookings in the next {} day(s)", days);
+                    }
+                    for scheduled in &schedule.bookings {
+                        let b = &scheduled.booking;
+                        println!(
+                            "   {} – {}  {}{}",
+                            b.start.format("%Y-%m-%d %H:%M"),
+                            b.end.format("%H:%M"),
+                            b.title.as_deref().unwrap_or("(untitled)"),
+                            b.organizer
+                                .as_deref()
+                                .map(|o| format!(" — {}", o))
+                                .unwrap_or_default()
+                        );
+                        if !scheduled.conflicts.is_empty() {
+                            println!(
+                                "     ⚠️  double-booked with {}",
+                                scheduled.conflicts.join(", ")
+                            );
+                        }
+                    }
+                }
+                Ok(())
+            }
         }
     }
 
@@ -461,6 +657,42 @@ impl Command for RoomCommand {
     }
 }
 
+#[cfg(feature = "tui")]
+fn run_calendar(
+    building: crate::core::Building,
+    bookings: Vec<RoomBooking>,
+    samples: Vec<crate::core::domain::booking::OccupancySample>,
+    now: chrono::DateTime<chrono::Utc>,
+    room_id: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    use crate::tui::booking_calendar::{CalendarAction, CalendarView};
+    use crate::tui::TerminalManager;
+    use crossterm::event::{self, Event};
+    use std::time::Duration;
+
+    let mut view = CalendarView::new(building, bookings, samples, now);
+    if view.room_count() == 0 {
+        println!("No rooms in the building");
+        return Ok(());
+    }
+    if let Some(id) = room_id {
+        view.select_room(&id);
+    }
+    let mut terminal_manager = TerminalManager::new()?;
+    loop {
+        terminal_manager.terminal().draw(|frame| {
+            view.render(frame, frame.size());
+        })?;
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if view.handle_key(key) == CalendarAction::Exit {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
 /// Equipment management command dispatcher
 pub struct EquipmentCommand {
     pub subcommand: EquipmentCommands,
@@ -473,19 +705,41 @@ impl Command for EquipmentCommand {
                 room,
                 name,
                 equipment_type,
+                template,
                 position,
                 at,
                 property,
                 commit,
             } => {
                 let (path, mut model) = load_building_from_dir()?;
-
-                let eq_type = parse_equipment_type(equipment_type)?;
-                let mut equipment = Equipment::new(
-                    name.clone(),
-                    at.clone().unwrap_or_else(|| "/".to_string()),
-                    eq_type,
-                );
+                let base = project_root(&path);
+
+                let template = template
+                    .as_deref()
+                    .map(|t| find_template(base, t))
+                    .transpose()?
+                    .map(|(template, _)| template);
+                let mut equipment = match &template {
+                    Some(template) => {
+                        let mut equipment = template.instantiate_equipment(name)?;
+                        if let Some(t) = equipment_type {
+                            equipment.equipment_type = parse_equipment_type(t)?;
+                        }
+                        equipment
+                    }
+                    None => Equipment::new(
+                        name.clone(),
+                        "/".to_string(),
+                        parse_equipment_type(
+                            equipment_type
+                                .as_deref()
+                                .ok_or("--equipment-type is required without --template")?,
+                        )?,
+                    ),
+                };
+                if let Some(addr) = at {
+                    equipment.path = addr.clone();
+                }
 
                 if let Some(addr) = at {
                     let parsed = ArxAddress::from_path(addr)?;
@@ -499,7 +753,7 @@ impl Command for EquipmentCommand {
                     equipment.set_position(pos);
                 }
 
-                equipment.properties = parse_properties(property)?;
+                equipment.properties.extend(parse_properties(property)?);
 
                 let mut added = false;
                 for floor_ref in &mut model.floors {
@@ -507,7 +761,7 @@ impl Command for EquipmentCommand {
                         if let Some(room_ref) = wing_ref
                             .rooms
                             .iter_mut()
-                            .find(|r| r.name == *room || r.id == *room)
+                            .find(|r| r.matches_name(room) || r.id == *room)
                         {
                             equipment.room_id = Some(room_ref.id.clone());
                             room_ref.add_equipment(equipment.clone());
@@ -531,7 +785,14 @@ impl Command for EquipmentCommand {
                     &format!("Add equipment: {}", equipment.name),
                 )?;
 
+                // Schedules follow the save so a failed or refused add leaves none behind
+                let schedules = match &template {
+                    Some(template) => template.register_schedules(base)?,
+                    None => Vec::new(),
+                };
+
                 println!("✅ Added equipment: {}", equipment.name);
+                print_registered_schedules(&schedules);
                 Ok(())
             }
             EquipmentCommands::List {
@@ -554,8 +815,7 @@ impl Command for EquipmentCommand {
                             for floor in &model.floors {
                                 for wing in &floor.wings {
                                     for rm in &wing.rooms {
-                                        if (rm.name.eq_ignore_ascii_case(r)
-                                            || rm.id.eq_ignore_ascii_case(r))
+                                        if (rm.matches_name(r) || rm.id.eq_ignore_ascii_case(r))
                                             && rm.equipment.iter().any(|e| e.id == eq.id)
                                         {
                                             in_room = true;
@@ -595,6 +855,12 @@ impl Command for EquipmentCommand {
                         if let Some(addr) = &eq.address {
                             println!("  address: {}", addr.path);
                         }
+                        if let Some(runtime) = &eq.runtime {
+                            println!("  runtime: {}", runtime_label(runtime, clock::now()));
+                        }
+                        if !eq.provenance.is_empty() {
+                            println!("  provenance: {}", provenance::summarize(&eq.provenance));
+                        }
                     } else {
                         println!("- {}", eq.name);
                     }
@@ -679,6 +945,206 @@ impl Command for EquipmentCommand {
                     Err("Invalid equipment remove arguments".into())
                 }
             }
+            EquipmentCommands::Runtime {
+                equipment,
+                serviced,
+                commit,
+            } => {
+                let (path, mut model) = load_building_from_dir()?;
+                let base = path
+                    .parent()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .unwrap_or_else(|| Path::new("."));
+                let schedules = MaintenanceSchedules::load_at(base)?;
+                let now = clock::now();
+
+                let synced = sync_runtime(base, &mut model)?;
+                let target = match equipment {
+                    Some(query) => Some(
+                        model
+                            .find_equipment(query)
+                            .map(|eq| eq.id.clone())
+                            .ok_or_else(|| format!("Equipment '{}' not found", query))?,
+                    ),
+                    None => None,
+                };
+                if let Some(name) = serviced {
+                    let id = target
+                        .as_deref()
+                        .ok_or("--serviced needs the equipment that was serviced")?;
+                    let schedule = schedules.get(name).ok_or_else(|| {
+                        format!(
+                            "Unknown maintenance schedule '{}' in {}",
+                            name, MAINTENANCE_FILE
+                        )
+                    })?;
+                    let eq = model.find_equipment_mut(id).expect("resolved above");
+                    if !schedule.selector.matches(eq) {
+                        return Err(
+                            format!("Schedule '{}' does not apply to {}", name, eq.name).into()
+                        );
+                    }
+                    record_service(eq, name, now);
+                    println!("🔧 {}: {} serviced", eq.name, name);
+                }
+
+                let mut tracked: Vec<&Equipment> = model
+                    .get_all_equipment()
+                    .into_iter()
+                    .filter(|eq| target.as_ref().is_none_or(|id| &eq.id == id))
+                    .filter(|eq| eq.runtime.is_some())
+                    .collect();
+                tracked.sort_by(|a, b| a.name.cmp(&b.name));
+                if tracked.is_empty() {
+                    println!(
+                        "⏱️  No runtime tracked yet (status changes and run_status / on_off sensors start it)"
+                    );
+                } else {
+                    println!("⏱️  Runtime ({} equipment)", tracked.len());
+                    for eq in &tracked {
+                        let runtime = eq.runtime.as_ref().expect("filtered above");
+                        println!("- {}: {}", eq.name, runtime_label(runtime, now));
+                    }
+                }
+                for status in maintenance_status(&model, &schedules, now)
+                    .iter()
+                    .filter(|s| target.as_ref().is_none_or(|id| &s.equipment_id == id))
+                {
+                    let state = if status.is_due() {
+                        format!("⚠️  due ({:.1} h over)", -status.remaining_hours())
+                    } else {
+                        format!("in {:.1} h", status.remaining_hours())
+                    };
+                    println!(
+                        "   🔧 {} / {}: {:.1} of {} h since service, {}",
+                        status.equipment,
+                        status.schedule,
+                        status.since_service_hours,
+                        status.every_runtime_hours,
+                        state
+                    );
+                }
+
+                if synced > 0 || serviced.is_some() {
+                    let message = match serviced {
+                        Some(name) => format!("Maintenance: {} serviced", name),
+                        None => format!("Runtime: sync {} equipment", synced),
+                    };
+                    save_building_to_path(&path, model, *commit, &message)?;
+                }
+                Ok(())
+            }
+            EquipmentCommands::Uptime {
+                equipment,
+                days,
+                since,
+                until,
+                format,
+            } => {
+                let (path, model) = load_building_from_dir()?;
+                let base = path
+                    .parent()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .unwrap_or_else(|| Path::new("."));
+                let eq = model
+                    .find_equipment(equipment)
+                    .ok_or_else(|| format!("Equipment '{}' not found", equipment))?;
+                let end = parse_time("until", until.as_deref())?.unwrap_or_else(clock::now);
+                let start = parse_time("since", since.as_deref())?
+                    .unwrap_or_else(|| end - chrono::Duration::days(*days));
+                if start >= end {
+                    return Err(
+                        "The uptime window is empty (--since must be before --until)".into(),
+                    );
+                }
+                let history = load_status_history(base, Some(&eq.id))?;
+                let report = uptime_report(&eq.id, &eq.name, &history, start, end);
+
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
+                println!(
+                    "📈 {}: {} → {}",
+                    report.equipment,
+                    start.format("%Y-%m-%d %H:%M"),
+                    end.format("%Y-%m-%d %H:%M")
+                );
+                let Some(availability) = report.availability_percent else {
+                    println!(
+                        "   No status history in this window (changes are recorded from the next save)"
+                    );
+                    return Ok(());
+                };
+                let hours = |h: Option<f64>| h.map_or("—".to_string(), |h| format!("{:.1} h", h));
+                println!(
+                    "   Availability: {:.2}% of {:.1} h tracked",
+                    availability, report.tracked_hours
+                );
+                println!(
+                    "   Up {:.1} h, maintenance {:.1} h, out of order {:.1} h",
+                    report.up_hours, report.planned_downtime_hours, report.unplanned_downtime_hours
+                );
+                println!(
+                    "   Failures: {}  MTBF: {}  MTTR: {}",
+                    report.failures,
+                    hours(report.mtbf_hours),
+                    hours(report.mttr_hours)
+                );
+                for t in &report.transitions {
+                    let from = t.from.map_or("(added)".to_string(), |s| format!("{:?}", s));
+                    println!(
+                        "   {}  {} → {:?}  [{}{}]",
+                        t.at.format("%Y-%m-%d %H:%M"),
+                        from,
+                        t.to,
+                        t.cause,
+                        t.actor
+                            .as_deref()
+                            .map(|a| format!(", {}", a))
+                            .unwrap_or_default()
+                    );
+                }
+                Ok(())
+            }
+            EquipmentCommands::Labels { output, room } => {
+                use crate::export::labels::{
+                    equipment_labels, render_labels_pdf, render_labels_png, LabelFormat,
+                };
+
+                let (_, model) = load_building_from_dir()?;
+                let output = Path::new(output);
+                let format = LabelFormat::from_path(output)?;
+                let labels = equipment_labels(&model, room.as_deref())?;
+                if labels.is_empty() {
+                    return Err("No equipment to label".into());
+                }
+                if crate::core::operations::is_dry_run() {
+                    crate::core::operations::dry_run::record_file_write(
+                        &output.display().to_string(),
+                        output.exists(),
+                    );
+                    return Ok(());
+                }
+                let bytes = match format {
+                    LabelFormat::Pdf => render_labels_pdf(&labels)?,
+                    LabelFormat::Png => render_labels_png(&labels)?,
+                };
+                std::fs::write(output, bytes)?;
+                println!(
+                    "🏷️  Wrote {} equipment label(s) to {}",
+                    labels.len(),
+                    output.display()
+                );
+                let by_id = labels.iter().filter(|l| l.code == l.equipment_id).count();
+                if by_id > 0 {
+                    println!(
+                        "ℹ️  {} label(s) encode the equipment id; run `arx migrate` to backfill ArxAddresses",
+                        by_id
+                    );
+                }
+                Ok(())
+            }
         }
     }
 
@@ -695,7 +1161,7 @@ pub struct SpatialCommand {
 impl Command for SpatialCommand {
     fn execute(&self) -> Result<(), Box<dyn Error>> {
         use crate::core::operations::spatial::{
-            spatial_query, transform_coordinates, validate_spatial,
+            apply_transform, spatial_query, transform_coordinates, validate_spatial,
         };
         use crate::persistence::load_building_at;
         use std::path::Path;
@@ -727,9 +1193,41 @@ impl Command for SpatialCommand {
                 }
                 Ok(())
             }
-            SpatialCommands::Transform { from, to, entity } => {
-                let building = load_building_at(Path::new("."))
+            SpatialCommands::Transform {
+                from,
+                to,
+                entity,
+                apply,
+            } => {
+                let mut building = load_building_at(Path::new("."))
                     .map_err(|e| format!("load building.yaml: {}", e))?;
+                if let Some(spec) = apply {
+                    let report = apply_transform(&mut building, entity, spec)?;
+                    let message = format!(
+                        "Transform {} {}: {}",
+                        report.entity_type,
+                        report.entity_name,
+                        report.operations.join(" ")
+                    );
+                    crate::ingest::persist_building_at(".", building, true, Some(&message))?;
+                    println!(
+                        "Transformed {} '{}': (x={:.2}, y={:.2}, z={:.2}) -> (x={:.2}, y={:.2}, z={:.2})",
+                        report.entity_type,
+                        report.entity_name,
+                        report.before.x,
+                        report.before.y,
+                        report.before.z,
+                        report.after.x,
+                        report.after.y,
+                        report.after.z
+                    );
+                    if report.equipment_moved > 0 {
+                        println!("Moved {} contained equipment", report.equipment_moved);
+                    }
+                    return Ok(());
+                }
+                let from = from.as_deref().unwrap_or_default();
+                let to = to.as_deref().unwrap_or_default();
                 let msg = transform_coordinates(&building, from, to, entity)?;
                 println!("{}", msg);
                 Ok(())
@@ -794,7 +1292,8 @@ mod tests {
                 floor: 1,
                 wing: "north".to_string(),
                 name: "conference-room".to_string(),
-                room_type: "Office".to_string(),
+                room_type: Some("Office".to_string()),
+                template: None,
                 dimensions: Some("10x12x3".to_string()),
                 position: Some("0,0,0".to_string()),
                 commit: false,
@@ -838,7 +1337,8 @@ mod tests {
                 floor: 1,
                 wing: "north".to_string(),
                 name: "conference-room".to_string(),
-                room_type: "Office".to_string(),
+                room_type: Some("Office".to_string()),
+                template: None,
                 dimensions: Some("10x12x3".to_string()),
                 position: Some("0,0,0".to_string()),
                 commit: false,
@@ -850,7 +1350,8 @@ mod tests {
             subcommand: EquipmentCommands::Add {
                 room: "conference-room".to_string(),
                 name: "projector-01".to_string(),
-                equipment_type: "AV".to_string(),
+                equipment_type: Some("AV".to_string()),
+                template: None,
                 position: Some("5,6,2.5".to_string()),
                 at: Some("/usa/ny/brooklyn/ps-118/floor-02/conference/projector-01".to_string()),
                 property: vec!["brand=Epson".to_string()],
@@ -869,6 +1370,57 @@ mod tests {
         std::env::set_current_dir(&original_dir).expect("restore current_dir");
     }
 
+    #[test]
+    #[serial]
+    fn test_equipment_template_schedules_follow_the_save() {
+        use crate::core::operations::DryRunGuard;
+        use crate::core::runtime::MAINTENANCE_FILE;
+
+        let tmp = tempdir().expect("tempdir");
+        let dir = tmp.path();
+        let mut building =
+            crate::core::Building::new("My Building".to_string(), "/building".to_string());
+        let mut floor = crate::core::Floor::new("Floor 1".to_string(), 1);
+        let mut wing = crate::core::Wing::new("north".to_string());
+        wing.rooms
+            .push(Room::new("mech-room".to_string(), RoomType::Mechanical));
+        floor.wings.push(wing);
+        building.add_floor(floor);
+        crate::persistence::save_building_at(dir, &building).expect("write building.yaml");
+
+        let original_dir = std::env::current_dir().expect("current_dir");
+        std::env::set_current_dir(dir).expect("set_current_dir");
+        let add = |room: &str| EquipmentCommand {
+            subcommand: EquipmentCommands::Add {
+                room: room.to_string(),
+                name: "AHU-1".to_string(),
+                equipment_type: None,
+                template: Some("ahu-standard".to_string()),
+                position: None,
+                at: None,
+                property: Vec::new(),
+                commit: false,
+            },
+        };
+
+        let missing = add("NoSuchRoom").execute();
+        let guard = DryRunGuard::begin();
+        let simulated = add("mech-room").execute();
+        let changes = guard.finish();
+        let no_schedules = !dir.join(MAINTENANCE_FILE).exists();
+        let real = add("mech-room").execute();
+        std::env::set_current_dir(&original_dir).expect("restore current_dir");
+
+        assert!(missing.is_err());
+        assert!(simulated.is_ok());
+        assert!(no_schedules);
+        assert!(changes
+            .iter()
+            .any(|c| c.target == format!("file {}", MAINTENANCE_FILE)));
+        assert!(real.is_ok());
+        assert!(dir.join(MAINTENANCE_FILE).exists());
+    }
+
     #[test]
     fn test_spatial_command_name() {
         let cmd = SpatialCommand {
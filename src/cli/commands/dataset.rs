@@ -0,0 +1,123 @@
+//! Dataset command: package the sensor trace log for the data market.
+
+use super::Command;
+use crate::contribution::{
+    build_sensor_dataset, write_sensor_dataset, DatasetOptions, PrivacyPolicy,
+};
+use crate::persistence::sensors::load_sensor_readings;
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use chrono::{DateTime, Utc};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct DatasetCommand {
+    pub action: DatasetAction,
+    /// Print the manifest without writing the package
+    pub dry_run: bool,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum DatasetAction {
+    Package {
+        output: PathBuf,
+        bucket_minutes: Option<u32>,
+        k_min: Option<u64>,
+        epsilon: Option<f64>,
+        no_noise: bool,
+        raw: bool,
+        since: Option<String>,
+        until: Option<String>,
+    },
+}
+
+pub(crate) fn parse_time(flag: &str, value: Option<&str>) -> Result<Option<DateTime<Utc>>, Box<dyn Error>> {
+    value
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| format!("--{} {}: {}", flag, v, e).into())
+        })
+        .transpose()
+}
+
+fn head_commit(base: &Path) -> Option<String> {
+    let repo = git2::Repository::open(base).ok()?;
+    let oid = repo.head().ok()?.peel_to_commit().ok()?.id();
+    Some(oid.to_string())
+}
+
+impl Command for DatasetCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        match &self.action {
+            DatasetAction::Package {
+                output,
+                bucket_minutes,
+                k_min,
+                epsilon,
+                no_noise,
+                raw,
+                since,
+                until,
+            } => {
+                let building = load_building_at(&base)
+                    .map_err(|e| format!("load {}: {}", BUILDING_YAML, e))?;
+                let privacy = if *raw {
+                    None
+                } else {
+                    let mut policy = PrivacyPolicy::load_at(&base)?;
+                    if let Some(m) = bucket_minutes {
+                        policy.bucket_minutes = *m;
+                    }
+                    if let Some(k) = k_min {
+                        policy.k_min = *k;
+                    }
+                    if *no_noise {
+                        policy.epsilon = None;
+                    } else if epsilon.is_some() {
+                        policy.epsilon = *epsilon;
+                    }
+                    Some(policy)
+                };
+                let opts = DatasetOptions {
+                    privacy,
+                    git_commit: head_commit(&base),
+                    since: parse_time("since", since.as_deref())?,
+                    until: parse_time("until", until.as_deref())?,
+                };
+                let readings = load_sensor_readings(&base)?;
+                let (manifest, csv) =
+                    build_sensor_dataset(&building, &readings, &opts, &mut rand::thread_rng())?;
+
+                println!("📊 Sensor dataset {}", manifest.dataset_id);
+                println!("  {}", manifest.summary);
+                if let Some(p) = &manifest.privacy {
+                    println!(
+                        "  cells: {} published, {} suppressed (k < {})",
+                        p.published_cells, p.suppressed_cells, p.k_min
+                    );
+                } else {
+                    println!("⚠️  Raw traces can reveal individual schedules; prefer the default privacy layer for external buyers");
+                }
+                if manifest.unmapped_readings > 0 {
+                    println!(
+                        "  skipped {} reading(s) from unmapped sensors (see `arx sensor unmapped`)",
+                        manifest.unmapped_readings
+                    );
+                }
+                if self.dry_run {
+                    println!("{}", serde_json::to_string_pretty(&manifest)?);
+                    return Ok(());
+                }
+                write_sensor_dataset(output, &manifest, &csv)?;
+                println!("✅ Wrote {} row(s) to {}", manifest.rows, output.display());
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "dataset"
+    }
+}
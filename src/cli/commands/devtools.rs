@@ -0,0 +1,64 @@
+//! Developer harnesses - requires agent feature
+#![cfg(feature = "agent")]
+
+use super::Command;
+use crate::devtools::hil::{self, HilConfig};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct DevtoolsCommand {
+    pub action: DevtoolsAction,
+}
+
+pub enum DevtoolsAction {
+    /// Run the ingestion HIL harness; `workspace` keeps the scratch repo.
+    Hil {
+        config: HilConfig,
+        workspace: Option<PathBuf>,
+    },
+}
+
+impl Command for DevtoolsCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        match &self.action {
+            DevtoolsAction::Hil { config, workspace } => {
+                let scratch = tempfile::tempdir()?;
+                let base = match workspace {
+                    Some(dir) => {
+                        if dir.exists() && std::fs::read_dir(dir)?.next().is_some() {
+                            return Err(format!("{} is not empty", dir.display()).into());
+                        }
+                        std::fs::create_dir_all(dir)?;
+                        dir.clone()
+                    }
+                    None => scratch.path().to_path_buf(),
+                };
+                println!(
+                    "🔌 HIL: {} device(s) x {} report(s) over {:?} (seed {})",
+                    config.devices, config.readings_per_device, config.transport, config.seed
+                );
+                let rt = tokio::runtime::Runtime::new()?;
+                let report = rt.block_on(hil::run(&base, config))?;
+                println!(
+                    "  sent {}, accepted {}, rejected {} in {:.2?}",
+                    report.sent, report.accepted, report.rejected, report.elapsed
+                );
+                if workspace.is_some() {
+                    println!("  workspace: {}", report.workspace.display());
+                }
+                if report.passed() {
+                    println!("✅ Ingestion checks passed");
+                    return Ok(());
+                }
+                for failure in &report.failures {
+                    println!("❌ {}", failure);
+                }
+                Err(format!("{} ingestion check(s) failed", report.failures.len()).into())
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "devtools"
+    }
+}
@@ -0,0 +1,71 @@
+//! Doc command: static site output generated from the Building SSOT.
+
+use super::Command;
+use crate::export::site::{write_room_pages, RoomPageOptions};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct DocCommand {
+    pub action: DocAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum DocAction {
+    /// Guest-facing room info pages + QR codes.
+    RoomPages {
+        base_url: String,
+        output: PathBuf,
+        report_url: Option<String>,
+    },
+}
+
+impl Command for DocCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+
+        match &self.action {
+            DocAction::RoomPages {
+                base_url,
+                output,
+                report_url,
+            } => {
+                let mut options = RoomPageOptions::with_templates_from(&base, base_url.clone())?;
+                options.report_url = report_url.clone();
+                let pages = write_room_pages(&building, output, &options)?;
+                println!(
+                    "✅ Wrote {} room page(s) + QR codes to {}",
+                    pages.len(),
+                    output.display()
+                );
+                println!("📋 Index: {}", output.join("index.html").display());
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "doc"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        match &self.action {
+            DocAction::RoomPages { base_url, .. } => {
+                if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+                    return Err("--base-url must start with http:// or https://".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::cli::commands::Command;
 use crate::ingest::ingest_text_script;
-use crate::persistence::{load_building_at, save_building_at, BUILDING_YAML};
+use crate::persistence::{load_building_at, save_building_at, PersistenceManager, BUILDING_YAML};
 use anyhow::anyhow;
 use std::error::Error;
 use std::fs;
@@ -29,13 +29,12 @@ impl Command for EditCommand {
         };
 
         let (base, yaml_path) = resolve_building_base(self.building.as_deref())?;
-        let building =
-            load_building_at(&base).map_err(|e| format!("load {}: {}", yaml_path.display(), e))?;
+        let building = load_building_at(&base).map_err(|e| format!("load {}: {}", yaml_path, e))?;
 
         let result = ingest_text_script(building, &script_body, true)
             .map_err(|e| format!("text edit failed: {}", e))?;
 
-        println!("Text edits applied to {}", yaml_path.display());
+        println!("Text edits applied to {}", yaml_path);
         for line in result.summary_lines() {
             println!("  {}", line);
         }
@@ -50,8 +49,8 @@ impl Command for EditCommand {
         }
 
         save_building_at(&base, &result.building)
-            .map_err(|e| anyhow!("save {}: {}", yaml_path.display(), e))?;
-        println!("Saved {}", yaml_path.display());
+            .map_err(|e| anyhow!("save {}: {}", yaml_path, e))?;
+        println!("Saved {}", yaml_path);
         Ok(())
     }
 
@@ -60,16 +59,15 @@ impl Command for EditCommand {
     }
 }
 
-/// Resolve project base directory and expected `building.yaml` path.
-fn resolve_building_base(explicit: Option<&str>) -> Result<(PathBuf, PathBuf), Box<dyn Error>> {
+/// Resolve project base directory and where its `building.yaml` is stored
+/// (a path, or the storage backend's description).
+fn resolve_building_base(explicit: Option<&str>) -> Result<(PathBuf, String), Box<dyn Error>> {
     if let Some(p) = explicit {
         let path = Path::new(p);
         if path.is_dir() {
-            let yaml = path.join(BUILDING_YAML);
-            if yaml.exists() {
-                return Ok((path.to_path_buf(), yaml));
-            }
-            return Err(format!("no {} in {}", BUILDING_YAML, path.display()).into());
+            return stored_building(path)?
+                .map(|location| (path.to_path_buf(), location))
+                .ok_or_else(|| format!("no {} in {}", BUILDING_YAML, path.display()).into());
         }
         if path.exists()
             && path
@@ -82,15 +80,25 @@ fn resolve_building_base(explicit: Option<&str>) -> Result<(PathBuf, PathBuf), B
                 .parent()
                 .map(|p| p.to_path_buf())
                 .unwrap_or_else(|| PathBuf::from("."));
-            return Ok((base, path.to_path_buf()));
+            return Ok((base, path.display().to_string()));
         }
         return Err(format!("building SSOT must be {} (got {})", BUILDING_YAML, p).into());
     }
 
     let base = PathBuf::from(".");
-    let yaml = base.join(BUILDING_YAML);
-    if !yaml.exists() {
-        return Err(format!("no {} found in current directory", BUILDING_YAML).into());
+    let location = stored_building(&base)?
+        .ok_or_else(|| format!("no {} found in current directory", BUILDING_YAML))?;
+    Ok((base, location))
+}
+
+/// Where `base`'s storage backend keeps `building.yaml`, if it has one.
+fn stored_building(base: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    let storage = PersistenceManager::at(base).storage()?;
+    if !storage.exists(BUILDING_YAML)? {
+        return Ok(None);
     }
-    Ok((base, yaml))
+    Ok(Some(match storage.local_path(BUILDING_YAML) {
+        Some(path) => path.display().to_string(),
+        None => format!("{} in {}", BUILDING_YAML, storage.describe()),
+    }))
 }
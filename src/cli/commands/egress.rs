@@ -0,0 +1,92 @@
+//! Egress command: travel distance to nearest exit + compliance report / overlay.
+
+use super::Command;
+use crate::core::operations::egress::{analyze_egress, render_egress_svg, EgressOptions};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Fire-safety egress analysis over the Building SSOT.
+pub struct EgressCommand {
+    /// Maximum allowed travel distance to an exit (m)
+    pub max_distance: f64,
+    /// Write Markdown compliance report here
+    pub report: Option<PathBuf>,
+    /// Write SVG egress overlay here
+    pub svg: Option<PathBuf>,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for EgressCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+
+        let options = EgressOptions {
+            max_travel_distance_m: self.max_distance,
+            ..Default::default()
+        };
+        let report = analyze_egress(&building, &options);
+
+        println!(
+            "🚪 Egress: {} room(s), {} exit(s), max travel {:.1} m",
+            report.rooms.len(),
+            report.exits,
+            report.max_travel_distance_m
+        );
+        if report.exits == 0 {
+            println!("⚠️  No exits found — mark rooms with egress_exit=true");
+        }
+        for r in report.violations() {
+            match r.travel_distance_m {
+                Some(d) => println!(
+                    "  ❌ floor {} / {}: {:.1} m to {}",
+                    r.floor_level,
+                    r.room_name,
+                    d,
+                    r.nearest_exit.as_deref().unwrap_or("?")
+                ),
+                None => println!(
+                    "  ❌ floor {} / {}: no path to an exit",
+                    r.floor_level, r.room_name
+                ),
+            }
+        }
+
+        if let Some(ref out) = self.report {
+            std::fs::write(out, report.to_markdown())?;
+            println!("📝 Compliance report: {}", out.display());
+        }
+        if let Some(ref out) = self.svg {
+            std::fs::write(out, render_egress_svg(&building, &report))?;
+            println!("🗺️  Egress overlay: {}", out.display());
+        }
+
+        if report.is_compliant() {
+            println!("✅ All rooms within {:.1} m of an exit", report.max_travel_distance_m);
+            Ok(())
+        } else {
+            Err("Egress analysis found non-compliant rooms".into())
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "egress"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.max_distance.is_nan() || self.max_distance <= 0.0 {
+            return Err("--max-distance must be positive".into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! Energy command: register meters, record consumption, and report rollups.
+
+use super::dataset::parse_time;
+use super::Command;
+use crate::core::energy::{Period, Utility};
+use crate::persistence::energy::{
+    add_meter, energy_report, import_intervals, load_meters, meter_target, record_interval,
+};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct EnergyCommand {
+    pub action: EnergyAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum EnergyAction {
+    /// Register a meter on an equipment item or floor.
+    Meter {
+        id: String,
+        utility: String,
+        equipment: Option<String>,
+        floor: Option<String>,
+    },
+    /// Print registered meters.
+    Meters,
+    /// Record consumption for one interval.
+    Record {
+        meter: String,
+        value: f64,
+        start: Option<String>,
+        end: Option<String>,
+    },
+    /// Append a `start,end,value` CSV of intervals.
+    Import { meter: String, file: PathBuf },
+    /// Rollups per utility with baselines.
+    Report {
+        building: Option<String>,
+        period: String,
+        since: Option<String>,
+        format: String,
+    },
+}
+
+impl EnergyCommand {
+    fn load(base: &Path) -> Result<crate::core::Building, Box<dyn Error>> {
+        Ok(load_building_at(base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?)
+    }
+}
+
+/// `+12%` / `-3%` against the baseline, or blank without one.
+fn baseline_delta(change: Option<f64>) -> String {
+    change
+        .map(|c| format!("{:+.0}% vs baseline", c))
+        .unwrap_or_default()
+}
+
+impl Command for EnergyCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+
+        match &self.action {
+            EnergyAction::Meter {
+                id,
+                utility,
+                equipment,
+                floor,
+            } => {
+                let utility: Utility = utility.parse()?;
+                let building = Self::load(&base)?;
+                let meter = add_meter(
+                    &base,
+                    &building,
+                    id,
+                    utility,
+                    equipment.as_deref(),
+                    floor.as_deref(),
+                )?;
+                println!(
+                    "⚡ Meter {} ({}) on {}",
+                    meter.id,
+                    meter.utility,
+                    meter_target(&building, &meter)
+                );
+            }
+            EnergyAction::Meters => {
+                let building = Self::load(&base)?;
+                let meters = load_meters(&base)?;
+                for meter in &meters {
+                    println!(
+                        "  {}  {:<8} {}",
+                        meter.id,
+                        meter.utility,
+                        meter_target(&building, meter)
+                    );
+                }
+                println!("{} meter(s)", meters.len());
+            }
+            EnergyAction::Record {
+                meter,
+                value,
+                start,
+                end,
+            } => {
+                let start = parse_time("start", start.as_deref())?;
+                let end = parse_time("end", end.as_deref())?;
+                let interval = record_interval(&base, meter, start, end, *value)?;
+                println!(
+                    "⚡ {}: {} from {} to {}",
+                    meter,
+                    interval.value,
+                    interval.start.format("%Y-%m-%d %H:%M"),
+                    interval.end.format("%Y-%m-%d %H:%M")
+                );
+            }
+            EnergyAction::Import { meter, file } => {
+                let csv = std::fs::read_to_string(file)
+                    .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+                let count = import_intervals(&base, meter, &csv)?;
+                println!("⚡ Imported {} interval(s) into {}", count, meter);
+            }
+            EnergyAction::Report {
+                building: requested,
+                period,
+                since,
+                format,
+            } => {
+                let period: Period = period.parse()?;
+                let since = parse_time("since", since.as_deref())?;
+                let building = Self::load(&base)?;
+                if let Some(requested) = requested {
+                    if building.name != *requested && building.id != *requested {
+                        return Err(format!(
+                            "Building '{}' not found (this repository holds '{}')",
+                            requested, building.name
+                        )
+                        .into());
+                    }
+                }
+                let reports = energy_report(&base, &building, period, since)?;
+                match format.as_str() {
+                    "json" => println!("{}", serde_json::to_string_pretty(&reports)?),
+                    "csv" => {
+                        println!("utility,unit,start,end,total,baseline");
+                        for report in &reports {
+                            for r in &report.rollups {
+                                println!(
+                                    "{},{},{},{},{},{}",
+                                    report.utility,
+                                    report.unit,
+                                    r.start.to_rfc3339(),
+                                    r.end.to_rfc3339(),
+                                    r.total,
+                                    r.baseline.map(|b| b.to_string()).unwrap_or_default()
+                                );
+                            }
+                        }
+                    }
+                    _ => {
+                        if reports.is_empty() {
+                            println!("No meter readings (arx energy record / import)");
+                            return Ok(());
+                        }
+                        println!("⚡ {}: consumption per {}", building.name, period);
+                        for report in &reports {
+                            println!("{} ({})", report.utility, report.unit);
+                            for r in &report.rollups {
+                                let line = format!(
+                                    "  {}  {:>12.1}  {}",
+                                    r.start.format("%Y-%m-%d"),
+                                    r.total,
+                                    baseline_delta(r.change_vs_baseline())
+                                );
+                                println!("{}", line.trim_end());
+                            }
+                            for usage in &report.meters {
+                                println!(
+                                    "    {} {}: {:.1}",
+                                    usage.meter_id, usage.target, usage.total
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "energy"
+    }
+}
@@ -1,10 +1,25 @@
 use crate::cli::commands::Command;
+use crate::core::materials::MaterialSet;
+use crate::core::spatial::BuildingMeshes;
+use crate::core::Building;
 use crate::core::{filter_building_for_export, summarize_review};
+use crate::export::csv::equipment_to_csv;
+use crate::export::gltf::export_gltf;
 use crate::export::ifc::IFCExporter;
+use crate::export::plugin::{discover_plugins, export_with_plugin, find_plugin};
+use crate::export::png::{render_floor_png, FloorOverlay, FloorPngOptions};
+use crate::export::pwa_bundle::{BundleCommit, PwaBundle, HISTORY_LIMIT};
+use crate::export::svg::render_floor_svg;
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
 use crate::ifc::mapping::report_export_losses;
-use crate::persistence::{load_building_at, BUILDING_YAML};
+use crate::persistence::jobs::report;
+use crate::persistence::meshes::load_building_meshes;
+use crate::persistence::sensors::latest_sensor_values;
+use crate::persistence::{load_building_at, PersistenceManager, BUILDING_YAML};
 use crate::utils::path_safety::PathSafety;
 use anyhow::anyhow;
+use chrono::DateTime;
+use std::collections::HashSet;
 use std::error::Error;
 use std::path::{Path, PathBuf};
 
@@ -20,6 +35,128 @@ pub struct ExportCommand {
     pub commercial: bool,
     /// Path to access receipt (default: access-receipt.json).
     pub access_receipt: Option<String>,
+    /// Floor level for png / svg export (default: lowest floor).
+    pub floor: Option<i32>,
+    /// png / svg overlay name (`none` or `status`).
+    pub overlay: String,
+}
+
+/// Meshes of `building` for the rooms and equipment kept in `export_building`.
+/// Cached per commit; rooms lacking 3D geometry are extruded from their outlines.
+fn export_meshes(
+    repo_root: &Path,
+    building: &Building,
+    export_building: &Building,
+) -> Result<BuildingMeshes, Box<dyn Error>> {
+    let keep_rooms: HashSet<&str> = export_building
+        .get_all_rooms()
+        .iter()
+        .map(|r| r.id.as_str())
+        .collect();
+    let keep_equipment: HashSet<&str> = export_building
+        .get_all_equipment()
+        .iter()
+        .map(|e| e.id.as_str())
+        .collect();
+    let mut meshes = load_building_meshes(repo_root, building)?;
+    meshes
+        .rooms
+        .retain(|m| keep_rooms.contains(m.room_id.as_str()));
+    meshes
+        .equipment
+        .retain(|m| keep_equipment.contains(m.equipment_id.as_str()));
+    Ok(meshes)
+}
+
+/// Recent `building.yaml` history and the HEAD commit, when `repo_root` is a
+/// Git repository.
+fn bundle_history(repo_root: &Path) -> (Vec<BundleCommit>, Option<String>) {
+    if !repo_root.join(".git").exists() {
+        return (Vec::new(), None);
+    }
+    let Some(git) = repo_root.to_str().and_then(|root| {
+        BuildingGitManager::new(
+            root,
+            "building",
+            GitConfigManager::load_from_arx_config_or_env(),
+        )
+        .ok()
+    }) else {
+        return (Vec::new(), None);
+    };
+    let history = git
+        .get_file_history(BUILDING_YAML)
+        .unwrap_or_default()
+        .into_iter()
+        .take(HISTORY_LIMIT)
+        .map(|c| BundleCommit {
+            summary: c.message.lines().next().unwrap_or("").trim().to_string(),
+            time: DateTime::from_timestamp(c.time, 0).unwrap_or_default(),
+            id: c.id,
+            author: c.author,
+        })
+        .collect();
+    (history, git.resolve_commit("HEAD").ok().map(|c| c.id))
+}
+
+impl ExportCommand {
+    /// Export through a WASM format plugin from `.arx/plugins/formats/`.
+    fn export_with_plugin(&self, repo_root: &Path, format: &str) -> Result<(), Box<dyn Error>> {
+        let plugin = match find_plugin(repo_root, format)? {
+            Some(plugin) => plugin,
+            None => {
+                let plugins: Vec<String> = discover_plugins(repo_root)?
+                    .into_iter()
+                    .map(|p| p.name)
+                    .collect();
+                let mut formats = vec![
+                    "ifc",
+                    "yaml",
+                    "json",
+                    "gltf",
+                    "pwa-bundle",
+                    "csv",
+                    "png",
+                    "svg",
+                ];
+                formats.extend(plugins.iter().map(String::as_str));
+                return Err(format!(
+                    "Unsupported export format: '{}'. Use: {}",
+                    format,
+                    formats.join(", ")
+                )
+                .into());
+            }
+        };
+
+        println!("📤 Exporting with plugin '{}'...", plugin.name);
+        let building = load_building_at(repo_root)
+            .map_err(|e| format!("No {} under {}: {}", BUILDING_YAML, repo_root.display(), e))?;
+        let export_building = filter_building_for_export(&building, self.approved_only);
+        let bytes = export_with_plugin(&plugin, &export_building)?;
+
+        let output_file = self
+            .output
+            .clone()
+            .unwrap_or_else(|| plugin.default_output(&building));
+        let output_path = {
+            let p = Path::new(&output_file);
+            if p.is_absolute() {
+                p.to_path_buf()
+            } else {
+                repo_root.join(p)
+            }
+        };
+        PathSafety::validate_path_for_write(&output_path).map_err(|e| anyhow!(e))?;
+        if let Some(parent) = output_path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::write(&output_path, bytes)?;
+        println!("✅ Export successful: {}", output_path.display());
+        Ok(())
+    }
 }
 
 impl Command for ExportCommand {
@@ -108,6 +245,7 @@ impl Command for ExportCommand {
                 }
 
                 let export_notes = report_export_losses(&export_building);
+                report(50, "writing IFC")?;
                 let exporter = IFCExporter::new(export_building);
                 exporter.export(&output_path).map_err(|e| anyhow!(e))?;
 
@@ -152,18 +290,12 @@ impl Command for ExportCommand {
                         repo_root.join(p)
                     }
                 };
-                let source_path = repo_root.join(BUILDING_YAML);
-
-                if !source_path.exists() {
-                    return Err(format!(
-                        "No {} found under {}",
-                        BUILDING_YAML,
-                        repo_root.display()
-                    )
-                    .into());
-                }
+                let (yaml_content, source_path) = stored_building_yaml(&repo_root)?;
 
-                if output_path.canonicalize().ok() == source_path.canonicalize().ok() {
+                let same_file = source_path
+                    .and_then(|p| p.canonicalize().ok())
+                    .is_some_and(|p| output_path.canonicalize().ok() == Some(p));
+                if same_file {
                     println!("⚠️  Source and destination are the same file");
                 } else {
                     if let Some(parent) = output_path.parent() {
@@ -171,7 +303,7 @@ impl Command for ExportCommand {
                             std::fs::create_dir_all(parent)?;
                         }
                     }
-                    std::fs::copy(&source_path, &output_path)?;
+                    std::fs::write(&output_path, yaml_content)?;
                     println!("✅ Export successful: {}", output_path.display());
                 }
                 Ok(())
@@ -190,18 +322,7 @@ impl Command for ExportCommand {
                         repo_root.join(p)
                     }
                 };
-                let source_path = repo_root.join(BUILDING_YAML);
-
-                if !source_path.exists() {
-                    return Err(format!(
-                        "No {} found under {}",
-                        BUILDING_YAML,
-                        repo_root.display()
-                    )
-                    .into());
-                }
-
-                let yaml_content = std::fs::read_to_string(&source_path)?;
+                let (yaml_content, _) = stored_building_yaml(&repo_root)?;
                 let yaml_value: serde_yaml::Value = serde_yaml::from_str(&yaml_content)?;
                 let json_content = serde_json::to_string_pretty(&yaml_value)?;
 
@@ -214,11 +335,177 @@ impl Command for ExportCommand {
                 println!("✅ Export successful: {}", output_path.display());
                 Ok(())
             }
-            _ => Err(format!(
-                "Unsupported export format: '{}'. Use: ifc, yaml, json",
-                self.format
-            )
-            .into()),
+            "gltf" => {
+                println!("📤 Exporting building geometry to glTF...");
+                let building = load_building_at(&repo_root).map_err(|e| {
+                    format!("No {} under {}: {}", BUILDING_YAML, repo_root.display(), e)
+                })?;
+                let export_building = filter_building_for_export(&building, self.approved_only);
+                report(20, "building meshes")?;
+                let meshes = export_meshes(&repo_root, &building, &export_building)?;
+                if meshes.rooms.is_empty() {
+                    println!("⚠️  No room has a mesh or a 2D outline to extrude");
+                }
+                let materials = MaterialSet::load_at(&repo_root)?;
+
+                let output_file = self
+                    .output
+                    .clone()
+                    .unwrap_or_else(|| format!("{}.gltf", building.name));
+                let output_path = {
+                    let p = Path::new(&output_file);
+                    if p.is_absolute() {
+                        p.to_path_buf()
+                    } else {
+                        repo_root.join(p)
+                    }
+                };
+                PathSafety::validate_path_for_write(&output_path).map_err(|e| anyhow!(e))?;
+                if let Some(parent) = output_path.parent() {
+                    if !parent.as_os_str().is_empty() && !parent.exists() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                }
+                report(70, "writing glTF")?;
+                export_gltf(&building.name, &meshes, &materials, &output_path)?;
+
+                let derived = meshes.rooms.iter().filter(|m| m.derived).count();
+                println!("✅ Export successful: {}", output_path.display());
+                println!(
+                    "  {} floor(s); {} room mesh(es), {} derived from 2D outlines; {} equipment mesh(es)",
+                    meshes.floors.len(),
+                    meshes.rooms.len(),
+                    derived,
+                    meshes.equipment.len()
+                );
+                Ok(())
+            }
+            "pwa-bundle" => {
+                println!("📤 Packaging offline PWA bundle...");
+                let building = load_building_at(&repo_root).map_err(|e| {
+                    format!("No {} under {}: {}", BUILDING_YAML, repo_root.display(), e)
+                })?;
+                let export_building = filter_building_for_export(&building, self.approved_only);
+                report(20, "building meshes")?;
+                let meshes = export_meshes(&repo_root, &building, &export_building)?;
+                let mut bundle = PwaBundle::new(export_building, meshes);
+                bundle.sensors = latest_sensor_values(&repo_root)?;
+                (bundle.history, bundle.commit) = bundle_history(&repo_root);
+
+                let output_file = self
+                    .output
+                    .clone()
+                    .unwrap_or_else(|| format!("{}.arxbundle", building.name));
+                let output_path = {
+                    let p = Path::new(&output_file);
+                    if p.is_absolute() {
+                        p.to_path_buf()
+                    } else {
+                        repo_root.join(p)
+                    }
+                };
+                PathSafety::validate_path_for_write(&output_path).map_err(|e| anyhow!(e))?;
+                if let Some(parent) = output_path.parent() {
+                    if !parent.as_os_str().is_empty() && !parent.exists() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                }
+                report(70, "writing bundle")?;
+                let bytes = bundle.to_bytes()?;
+                std::fs::write(&output_path, &bytes)?;
+                println!("✅ Export successful: {}", output_path.display());
+                println!(
+                    "  {} floor plan(s), {} room mesh(es), {} sensor reading(s), {} commit(s) of history; {} KiB",
+                    bundle.plans.len(),
+                    bundle.meshes.rooms.len(),
+                    bundle.sensors.len(),
+                    bundle.history.len(),
+                    bytes.len().div_ceil(1024)
+                );
+                Ok(())
+            }
+            "csv" => {
+                println!("📤 Exporting equipment register to CSV...");
+                let building = load_building_at(&repo_root).map_err(|e| {
+                    format!("No {} under {}: {}", BUILDING_YAML, repo_root.display(), e)
+                })?;
+                let export_building = filter_building_for_export(&building, self.approved_only);
+                let output_file = self
+                    .output
+                    .clone()
+                    .unwrap_or_else(|| format!("{}-equipment.csv", building.name));
+                let output_path = {
+                    let p = Path::new(&output_file);
+                    if p.is_absolute() {
+                        p.to_path_buf()
+                    } else {
+                        repo_root.join(p)
+                    }
+                };
+                PathSafety::validate_path_for_write(&output_path).map_err(|e| anyhow!(e))?;
+                if let Some(parent) = output_path.parent() {
+                    if !parent.as_os_str().is_empty() && !parent.exists() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                }
+                std::fs::write(&output_path, equipment_to_csv(&export_building))?;
+                println!("✅ Export successful: {}", output_path.display());
+                Ok(())
+            }
+            "png" | "svg" => {
+                let building = load_building_at(&repo_root).map_err(|e| {
+                    format!("No {} under {}: {}", BUILDING_YAML, repo_root.display(), e)
+                })?;
+                let export_building = filter_building_for_export(&building, self.approved_only);
+                let level = self
+                    .floor
+                    .or_else(|| export_building.floors.iter().map(|f| f.level).min())
+                    .ok_or("Building has no floors to draw")?;
+                let overlay = match self.overlay.as_str() {
+                    "none" => FloorOverlay::None,
+                    "status" => FloorOverlay::Status,
+                    other => {
+                        return Err(format!("Unknown overlay '{}'. Use: none, status", other).into())
+                    }
+                };
+                println!(
+                    "📤 Rendering floor {} to {}...",
+                    level,
+                    self.format.to_uppercase()
+                );
+                let options = FloorPngOptions {
+                    overlay,
+                    ..Default::default()
+                };
+                let bytes = if self.format == "svg" {
+                    render_floor_svg(&export_building, level, &options)?.into_bytes()
+                } else {
+                    render_floor_png(&export_building, level, &options)?
+                };
+
+                let output_file = self
+                    .output
+                    .clone()
+                    .unwrap_or_else(|| format!("{}-floor-{}.{}", building.name, level, self.format));
+                let output_path = {
+                    let p = Path::new(&output_file);
+                    if p.is_absolute() {
+                        p.to_path_buf()
+                    } else {
+                        repo_root.join(p)
+                    }
+                };
+                PathSafety::validate_path_for_write(&output_path).map_err(|e| anyhow!(e))?;
+                if let Some(parent) = output_path.parent() {
+                    if !parent.as_os_str().is_empty() && !parent.exists() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                }
+                std::fs::write(&output_path, bytes)?;
+                println!("✅ Export successful: {}", output_path.display());
+                Ok(())
+            }
+            other => self.export_with_plugin(&repo_root, other),
         }
     }
 
@@ -226,3 +513,14 @@ impl Command for ExportCommand {
         "export"
     }
 }
+
+/// The stored `building.yaml` text from the storage backend, and its local
+/// path when the backend keeps one.
+fn stored_building_yaml(repo_root: &Path) -> Result<(String, Option<PathBuf>), Box<dyn Error>> {
+    let manager = PersistenceManager::at(repo_root);
+    let storage = manager.storage()?;
+    let yaml = manager
+        .read_building_yaml()?
+        .ok_or_else(|| format!("No {} found in {}", BUILDING_YAML, storage.describe()))?;
+    Ok((yaml, storage.local_path(BUILDING_YAML)))
+}
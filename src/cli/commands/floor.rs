@@ -0,0 +1,109 @@
+//! Floor command: bulk operations on whole floors.
+
+use super::Command;
+use crate::core::operations::{clone_floor, parse_levels, CloneFloorOptions};
+use crate::ingest::persist_building_at;
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct FloorCommand {
+    pub action: FloorAction,
+    /// Preview the entities to be created without saving
+    pub dry_run: bool,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum FloorAction {
+    Clone {
+        from: i32,
+        /// Target levels: `4`, `4..10`, or a comma list
+        to: String,
+        rename_pattern: Option<String>,
+        floor_height: Option<f64>,
+        commit: bool,
+    },
+}
+
+impl Command for FloorCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let mut building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+        match &self.action {
+            FloorAction::Clone {
+                from,
+                to,
+                rename_pattern,
+                floor_height,
+                commit,
+            } => {
+                let options = CloneFloorOptions {
+                    from: *from,
+                    to: parse_levels(to)?,
+                    rename_pattern: rename_pattern.clone(),
+                    floor_height: *floor_height,
+                };
+                let report = clone_floor(&mut building, &options)?;
+                println!(
+                    "🏢 Cloning '{}' (level {}) onto {} floor(s), {:.2} m apart",
+                    report.source,
+                    from,
+                    report.floors.len(),
+                    report.floor_height
+                );
+                let (mut rooms, mut equipment) = (0, 0);
+                for floor in &report.floors {
+                    println!(
+                        "   + floor {} '{}' (z {:+.2} m)",
+                        floor.level, floor.name, floor.z_offset
+                    );
+                    for (old, new) in &floor.rooms {
+                        println!("     + room {} (from {})", new, old);
+                    }
+                    for (old, new) in &floor.equipment {
+                        println!("     + equipment {} (from {})", new, old);
+                    }
+                    rooms += floor.rooms.len();
+                    equipment += floor.equipment.len();
+                }
+                for sensor in &report.dropped_sensors {
+                    println!(
+                        "   ⚠️  sensor not copied (bound to the source floor): {}",
+                        sensor
+                    );
+                }
+
+                if self.dry_run {
+                    println!(
+                        "🔍 Dry run: would create {} floor(s), {} room(s), {} equipment",
+                        report.floors.len(),
+                        rooms,
+                        equipment
+                    );
+                    return Ok(());
+                }
+                let message = format!("Clone floor {} to {}", from, to);
+                persist_building_at(&base, building, *commit, Some(&message))?;
+                println!(
+                    "✅ Created {} floor(s), {} room(s), {} equipment",
+                    report.floors.len(),
+                    rooms,
+                    equipment
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "floor"
+    }
+}
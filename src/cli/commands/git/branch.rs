@@ -0,0 +1,66 @@
+use crate::cli::commands::Command;
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
+use std::error::Error;
+
+pub enum BranchAction {
+    Create {
+        name: String,
+        from: Option<String>,
+        switch: bool,
+    },
+    List,
+    Switch {
+        name: String,
+    },
+}
+
+/// Scenario branches over the building repository in the current directory.
+pub struct BranchCommand {
+    pub action: BranchAction,
+}
+
+impl Command for BranchCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let config = GitConfigManager::load_from_arx_config_or_env();
+        let manager = BuildingGitManager::new(".", "current", config)?;
+
+        match &self.action {
+            BranchAction::Create { name, from, switch } => {
+                let commit = manager.create_branch(name, from.as_deref())?;
+                println!(
+                    "🌿 Created branch {} at {}",
+                    name,
+                    &commit[..8.min(commit.len())]
+                );
+                if *switch {
+                    manager.switch_branch(name)?;
+                    println!("🔀 Switched to {}", name);
+                }
+            }
+            BranchAction::List => {
+                let branches = manager.list_branches()?;
+                if branches.is_empty() {
+                    println!("No branches yet (commit building.yaml first)");
+                }
+                for branch in branches {
+                    println!(
+                        "{} {:<24} {}  {}",
+                        if branch.current { "*" } else { " " },
+                        branch.name,
+                        &branch.commit[..8.min(branch.commit.len())],
+                        branch.summary
+                    );
+                }
+            }
+            BranchAction::Switch { name } => {
+                manager.switch_branch(name)?;
+                println!("🔀 Switched to {}", name);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "git branch"
+    }
+}
@@ -1,9 +1,11 @@
+pub mod branch;
 pub mod commit;
 pub mod diff;
 pub mod stage;
 pub mod status;
 pub mod unstage;
 
+pub use branch::{BranchAction, BranchCommand};
 pub use commit::CommitCommand;
 pub use diff::DiffCommand;
 pub use stage::StageCommand;
@@ -21,6 +21,7 @@ impl Command for StatusCommand {
                     token: std::sync::Arc::new(std::sync::Mutex::new(token_state)),
                     metrics: std::sync::Arc::new(crate::agent::observability::AgentMetrics::new()),
                     reload_handle: None,
+                    sessions: Default::default(),
                 });
 
                 let rt = tokio::runtime::Runtime::new()?;
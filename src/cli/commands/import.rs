@@ -1,6 +1,8 @@
 use crate::cli::commands::Command;
-use crate::ingest::import_ifc_path;
-use crate::persistence::{save_building_at, BUILDING_YAML};
+use crate::core::provenance::{ProvenanceGuard, ProvenanceSource};
+use crate::ingest::import_ifc_onto;
+use crate::persistence::jobs::report;
+use crate::persistence::{save_building_at, PersistenceManager, BUILDING_YAML};
 use anyhow::anyhow;
 use std::error::Error;
 use std::path::Path;
@@ -25,16 +27,12 @@ impl Command for ImportCommand {
         }
 
         let repo_root = Path::new(".");
-        let ifc_path = Path::new(&self.ifc_file);
+        let ifc_path = &crate::git::lfs::resolve_path(Path::new(&self.ifc_file))?;
 
-        let building_yaml = repo_root.join(BUILDING_YAML);
-        let existing = if building_yaml.exists() {
-            Some(building_yaml.as_path())
-        } else {
-            None
-        };
+        let existing = PersistenceManager::at(repo_root).load_existing()?;
 
-        let result = import_ifc_path(ifc_path, existing, self.strict, true)
+        report(10, "parsing IFC")?;
+        let result = import_ifc_onto(ifc_path, existing, self.strict, true)
             .map_err(|e| format!("IFC import failed: {}", e))?;
 
         if result.validation.has_errors() {
@@ -54,6 +52,8 @@ impl Command for ImportCommand {
             return Ok(());
         }
 
+        report(80, "writing building.yaml")?;
+        let _provenance = ProvenanceGuard::source(ProvenanceSource::IfcImport);
         save_building_at(repo_root, &result.building)
             .map_err(|e| anyhow!("Failed to write {}: {}", BUILDING_YAML, e))?;
 
@@ -0,0 +1,120 @@
+//! Import command for equipment inventories kept in spreadsheets (CSV).
+
+use super::Command;
+use crate::ingest::equipment_csv::{
+    apply_equipment_import, plan_equipment_import, EquipmentCsvMapping, RowIssueKind,
+    EQUIPMENT_CSV_MAPPING,
+};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct ImportCsvCommand {
+    /// CSV file with one row per piece of equipment
+    pub file: String,
+    /// Column mapping (default: `.arx/import/equipment-csv.yaml`, else export headers)
+    pub mapping: Option<PathBuf>,
+    /// Refuse to write when any row is skipped
+    pub strict: bool,
+    pub dry_run: bool,
+    pub commit: bool,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for ImportCsvCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let mapping = match &self.mapping {
+            Some(path) => EquipmentCsvMapping::load(path)?,
+            None => EquipmentCsvMapping::load_at(&base)?,
+        };
+        let mut building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+        let text = std::fs::read_to_string(&self.file)
+            .map_err(|e| format!("Failed to read {}: {}", self.file, e))?;
+
+        println!("📥 Importing equipment from {}...", self.file);
+        let plan = plan_equipment_import(&building, &text, &mapping)
+            .map_err(|e| format!("{}: {}", self.file, e))?;
+
+        if self.dry_run {
+            for row in &plan.create {
+                println!(
+                    "  + line {:<4} {} ({})  → {}",
+                    row.line,
+                    row.equipment.name,
+                    row.equipment.equipment_type,
+                    row.room_name
+                        .clone()
+                        .unwrap_or_else(|| format!("floor {}", row.floor_level))
+                );
+            }
+        }
+        for issue in &plan.issues {
+            println!(
+                "  ⚠️  line {} ({}): {}",
+                issue.line, issue.kind, issue.message
+            );
+        }
+        println!(
+            "  {} to create; skipped {} duplicate(s), {} already in model, {} unknown room(s), {} invalid",
+            plan.create.len(),
+            plan.count(RowIssueKind::Duplicate),
+            plan.count(RowIssueKind::Exists),
+            plan.count(RowIssueKind::UnknownRoom),
+            plan.count(RowIssueKind::Invalid)
+        );
+
+        if self.strict && !plan.issues.is_empty() {
+            return Err(format!(
+                "{} row(s) failed validation; refusing to write {} (--strict)",
+                plan.issues.len(),
+                BUILDING_YAML
+            )
+            .into());
+        }
+        if plan.create.is_empty() {
+            println!("✅ Nothing to import");
+            return Ok(());
+        }
+
+        let added = apply_equipment_import(&mut building, &plan);
+        let message = format!(
+            "Import {} equipment from {}",
+            added,
+            Path::new(&self.file)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| self.file.clone())
+        );
+        crate::ingest::persist_building_at(&base, building, self.commit, Some(&message))?;
+        println!("✅ Imported {} piece(s) of equipment", added);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "import-csv"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        let ext = Path::new(&self.file)
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase());
+        if matches!(ext.as_deref(), Some("xlsx" | "xls" | "ods")) {
+            return Err(format!(
+                "{} is a workbook; save the equipment sheet as CSV and import that (column mapping: {})",
+                self.file, EQUIPMENT_CSV_MAPPING
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
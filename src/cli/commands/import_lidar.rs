@@ -1,6 +1,8 @@
 use crate::cli::commands::Command;
+use crate::core::provenance::{ProvenanceGuard, ProvenanceSource};
 use crate::ingest::import_lidar_path;
 use crate::persistence::{save_building_at, BUILDING_YAML};
+use crate::spatial::lidar::downsampler::Decimation;
 use anyhow::anyhow;
 use std::error::Error;
 use std::path::Path;
@@ -9,6 +11,9 @@ pub struct ImportLidarCommand {
     pub file_path: String,
     pub voxel_size: f64,
     pub light: bool,
+    /// Keep every Nth input point
+    pub decimate: usize,
+    pub max_points: Option<usize>,
     pub dry_run: bool,
     pub merge: bool,
     pub building: Option<String>,
@@ -21,12 +26,18 @@ impl Command for ImportLidarCommand {
         if self.light {
             println!("  Mode: Light (optimized for resource limits)");
         }
+        if self.decimate > 1 {
+            println!("  Decimation: every {} point(s)", self.decimate);
+        }
+        if let Some(max) = self.max_points {
+            println!("  Max points: {}", max);
+        }
         if self.dry_run {
             println!("Dry run mode enabled - no changes will be written");
         }
 
         let repo_root = Path::new(".");
-        let lidar_path = Path::new(&self.file_path);
+        let lidar_path = &crate::git::lfs::resolve_path(Path::new(&self.file_path))?;
 
         let existing = if self.merge {
             let building_yaml = repo_root.join(BUILDING_YAML);
@@ -44,6 +55,10 @@ impl Command for ImportLidarCommand {
             existing.as_deref(),
             self.voxel_size,
             self.light,
+            Decimation {
+                every: self.decimate,
+                max_points: self.max_points,
+            },
             true,
         )
         .map_err(|e| format!("LiDAR import failed: {}", e))?;
@@ -69,6 +84,7 @@ impl Command for ImportLidarCommand {
             return Ok(());
         }
 
+        let _provenance = ProvenanceGuard::source(ProvenanceSource::ArScan);
         save_building_at(repo_root, &result.building)
             .map_err(|e| anyhow!("Failed to write {}: {}", BUILDING_YAML, e))?;
 
@@ -0,0 +1,61 @@
+//! Import command for custom formats handled by WASM plugins.
+
+use super::Command;
+use crate::export::plugin::{discover_plugins, find_plugin, import_with_plugin};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct ImportPluginCommand {
+    /// Plugin format name
+    pub format: String,
+    /// Input file handed to the plugin
+    pub file: String,
+    pub commit: bool,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for ImportPluginCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let plugin = match find_plugin(&base, &self.format)? {
+            Some(plugin) => plugin,
+            None => {
+                let available: Vec<String> = discover_plugins(&base)?
+                    .into_iter()
+                    .map(|p| p.name)
+                    .collect();
+                return Err(format!(
+                    "No format plugin '{}' in {} (available: {})",
+                    self.format,
+                    crate::export::plugin::PLUGIN_DIR,
+                    if available.is_empty() {
+                        "none".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                )
+                .into());
+            }
+        };
+
+        println!(
+            "📥 Importing {} with plugin '{}'...",
+            self.file, plugin.name
+        );
+        let input = std::fs::read(&self.file)
+            .map_err(|e| format!("Failed to read {}: {}", self.file, e))?;
+        let building = import_with_plugin(&plugin, &input)?;
+        let floors = building.floors.len();
+        let name = building.name.clone();
+
+        let message = format!("Import {} via plugin '{}'", self.file, plugin.name);
+        crate::ingest::persist_building_at(&base, building, self.commit, Some(&message))?;
+        println!("✅ Imported '{}' ({} floor(s))", name, floors);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "import-plugin"
+    }
+}
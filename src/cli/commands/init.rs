@@ -14,7 +14,7 @@ pub struct InitCommand {
     #[arg(short, long)]
     pub name: Option<String>,
 
-    /// Install Git hooks for auto-export
+    /// Install Git hooks for auto-export and the building.yaml merge driver
     #[arg(long, default_value = "true")]
     pub install_hooks: bool,
 
@@ -162,21 +162,40 @@ impl InitCommand {
             anyhow::bail!("Git hooks directory not found. Initialize Git first.");
         }
 
-        let template = include_str!("../../../templates/post-merge");
-        let hook_path = hooks_dir.join("post-merge");
+        let hooks = [
+            ("post-merge", include_str!("../../../templates/post-merge")),
+            ("pre-commit", include_str!("../../../templates/pre-commit")),
+        ];
+        for (name, template) in hooks {
+            let hook_path = hooks_dir.join(name);
+            fs::write(&hook_path, template)
+                .with_context(|| format!("Failed to write {} hook", name))?;
+
+            // Make executable (Unix only)
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mut perms = fs::metadata(&hook_path)?.permissions();
+                perms.set_mode(0o755);
+                fs::set_permissions(&hook_path, perms)?;
+            }
+        }
 
-        fs::write(&hook_path, template).context("Failed to write post-merge hook")?;
+        crate::git::merge_driver::install_merge_driver(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to install merge driver: {}", e))?;
+        let lfs_patterns = crate::git::lfs::install_lfs(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to set up Git LFS: {}", e))?;
 
-        // Make executable (Unix only)
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&hook_path)?.permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&hook_path, perms)?;
+        println!(
+            "🪝 Installed Git hooks (post-merge, pre-commit) and the building.yaml merge driver"
+        );
+        if lfs_patterns > 0 {
+            println!(
+                "📦 Tracking {} large-file pattern(s) with Git LFS (patterns: {})",
+                lfs_patterns,
+                crate::git::lfs::LFS_CONFIG_FILE
+            );
         }
-
-        println!("🪝 Installed Git hooks (post-merge)");
         Ok(())
     }
 }
@@ -0,0 +1,126 @@
+//! Inventory command: spare parts stock and the reorder report.
+
+use super::Command;
+use crate::core::inventory::SparePart;
+use crate::core::EquipmentType;
+use crate::persistence::inventory::{load_inventory, update_inventory, INVENTORY_FILE};
+use crate::persistence::load_building_at;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct InventoryCommand {
+    pub action: InventoryAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum InventoryAction {
+    List {
+        equipment_type: Option<String>,
+    },
+    Add {
+        part: SparePart,
+    },
+    /// Restock (positive) or correct a count (negative).
+    Adjust {
+        part_number: String,
+        delta: i64,
+    },
+    LowStock {
+        format: String,
+    },
+}
+
+impl Command for InventoryCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        match &self.action {
+            InventoryAction::List { equipment_type } => {
+                let inventory = load_inventory(&base)?;
+                let wanted = equipment_type
+                    .as_deref()
+                    .map(str::parse::<EquipmentType>)
+                    .transpose()?;
+                let parts: Vec<&SparePart> = inventory
+                    .parts
+                    .iter()
+                    .filter(|p| wanted.as_ref().is_none_or(|t| p.fits(t)))
+                    .collect();
+                if parts.is_empty() {
+                    println!("📦 No parts (add with `arx inventory add`)");
+                    return Ok(());
+                }
+                println!(
+                    "  {:<16} {:>5} {:>5}  {:<24} {:<14} Description",
+                    "Part", "Qty", "Min", "Location", "Fits"
+                );
+                for p in parts {
+                    println!(
+                        "{} {:<16} {:>5} {:>5}  {:<24} {:<14} {}",
+                        if p.is_low() { "!" } else { " " },
+                        p.part_number,
+                        p.quantity,
+                        p.min_stock,
+                        p.location.as_deref().unwrap_or("-"),
+                        if p.equipment_types.is_empty() {
+                            "any".to_string()
+                        } else {
+                            p.equipment_types.join(",")
+                        },
+                        p.description.as_deref().unwrap_or("")
+                    );
+                }
+            }
+            InventoryAction::Add { part } => {
+                update_inventory(&base, |inventory| inventory.add(part.clone()))?;
+                println!(
+                    "✅ Added {} ({} in stock, min {}) to {}",
+                    part.part_number, part.quantity, part.min_stock, INVENTORY_FILE
+                );
+            }
+            InventoryAction::Adjust { part_number, delta } => {
+                let quantity =
+                    update_inventory(&base, |inventory| inventory.adjust(part_number, *delta))?;
+                println!("✅ {}: {:+} → {} in stock", part_number, delta, quantity);
+            }
+            InventoryAction::LowStock { format } => {
+                let inventory = load_inventory(&base)?;
+                let building = load_building_at(&base).ok();
+                let report = inventory.low_stock(building.as_ref());
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
+                if report.is_empty() {
+                    println!(
+                        "✅ All {} part(s) at or above minimum stock",
+                        inventory.parts.len()
+                    );
+                    return Ok(());
+                }
+                println!("📉 {} part(s) to reorder", report.len());
+                println!(
+                    "  {:<16} {:>5} {:>5} {:>6}  {:<24} {:>9}  Description",
+                    "Part", "Qty", "Min", "Order", "Location", "Equipment"
+                );
+                for line in &report {
+                    println!(
+                        "  {:<16} {:>5} {:>5} {:>6}  {:<24} {:>9}  {}",
+                        line.part_number,
+                        line.quantity,
+                        line.min_stock,
+                        line.order,
+                        line.location.as_deref().unwrap_or("-"),
+                        line.equipment,
+                        line.description.as_deref().unwrap_or("")
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "inventory"
+    }
+}
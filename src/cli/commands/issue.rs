@@ -0,0 +1,83 @@
+//! Issue command: list / export occupant issue reports stored under `.arx/issues/`.
+
+use super::Command;
+use crate::core::domain::issue::issues_to_csv;
+use crate::persistence::issues::{load_issues, open_issues};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct IssueCommand {
+    pub action: IssueAction,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum IssueAction {
+    /// Print issues, most urgent first.
+    List { all: bool },
+    /// Write CSV / JSON for CMMS import.
+    Export {
+        format: String,
+        output: PathBuf,
+        all: bool,
+    },
+}
+
+impl Command for IssueCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let load = |all: bool| {
+            if all {
+                load_issues(&base)
+            } else {
+                open_issues(&base)
+            }
+        };
+
+        match &self.action {
+            IssueAction::List { all } => {
+                let issues = load(*all)?;
+                if issues.is_empty() {
+                    println!("✅ No {}issues", if *all { "" } else { "open " });
+                    return Ok(());
+                }
+                println!("📋 {} issue(s)", issues.len());
+                for issue in &issues {
+                    println!("  {} ({})", issue.summary(), issue.status);
+                }
+            }
+            IssueAction::Export {
+                format,
+                output,
+                all,
+            } => {
+                let issues = load(*all)?;
+                let content = match format.as_str() {
+                    "json" => serde_json::to_string_pretty(&issues)?,
+                    _ => issues_to_csv(&issues),
+                };
+                std::fs::write(output, content)?;
+                println!(
+                    "✅ Exported {} issue(s) to {}",
+                    issues.len(),
+                    output.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "issue"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let IssueAction::Export { format, .. } = &self.action {
+            if !matches!(format.as_str(), "csv" | "json") {
+                return Err(format!("Unknown format '{}' (expected csv, json)", format).into());
+            }
+        }
+        Ok(())
+    }
+}
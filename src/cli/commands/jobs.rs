@@ -0,0 +1,106 @@
+//! Jobs command: follow and cancel background jobs (`arx --async`, agent).
+
+use super::Command;
+use crate::persistence::jobs::{self, JobRecord, JobState, JOB_ID_ENV};
+use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+pub enum JobsAction {
+    List { all: bool },
+    Status { id: String },
+    Cancel { id: String },
+}
+
+pub struct JobsCommand {
+    pub action: JobsAction,
+    /// Project root holding .arx/jobs (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+fn print_job(job: &JobRecord) {
+    println!(
+        "{}  {:<11} {:>3}%  {}  {}",
+        job.id,
+        job.state.to_string(),
+        job.progress,
+        job.stage,
+        job.description
+    );
+}
+
+impl Command for JobsCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        match &self.action {
+            JobsAction::List { all } => {
+                let records: Vec<JobRecord> = jobs::list(&base)?
+                    .into_iter()
+                    .filter(|j| *all || j.state == JobState::Running)
+                    .collect();
+                if records.is_empty() {
+                    println!("📭 No {}jobs", if *all { "" } else { "running " });
+                }
+                for job in &records {
+                    print_job(job);
+                }
+            }
+            JobsAction::Status { id } => {
+                let job = jobs::load(&base, id)?.ok_or_else(|| format!("Unknown job '{}'", id))?;
+                print_job(&job);
+                println!(
+                    "   started {}, updated {}",
+                    job.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    job.updated_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+                if let Some(error) = &job.error {
+                    println!("   error: {}", error);
+                }
+                if let Some(log) = &job.log {
+                    println!("   output: {}", log);
+                }
+                if job.state == JobState::Running && jobs::is_cancel_requested(&base, id) {
+                    println!("   cancel requested");
+                }
+            }
+            JobsAction::Cancel { id } => {
+                let job = jobs::cancel(&base, id)?;
+                println!(
+                    "🛑 Cancel requested for {} ({}); it stops at its next progress step",
+                    job.id, job.description
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "jobs"
+    }
+}
+
+/// `arx --async …`: run the same command line (without `--async`) in a
+/// detached `arx` process bound to a new job, and return straight away.
+pub fn start_detached(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let base = Path::new(".");
+    let job = jobs::create(base, &format!("arx {}", args.join(" ")))?;
+    let log = jobs::log_path(&job.id);
+    let output = File::create(base.join(&log))?;
+    // The child records its own pid when it attaches to the job
+    jobs::set_log(base, &job.id, &log)?;
+    std::process::Command::new(std::env::current_exe()?)
+        .args(args)
+        .env(JOB_ID_ENV, &job.id)
+        .stdin(Stdio::null())
+        .stdout(output.try_clone()?)
+        .stderr(output)
+        .spawn()?;
+
+    println!("🧵 Job {} started in the background", job.id);
+    println!(
+        "   Follow with `arx jobs status {}`; output in {}",
+        job.id, log
+    );
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Maintenance command: calendar feeds of upcoming maintenance, inspections,
+//! and warranty expirations.
+
+use super::Command;
+use crate::core::clock;
+use crate::core::runtime::MaintenanceSchedules;
+use crate::export::ical::{to_ical, upcoming_events};
+use crate::persistence::sensors::sync_runtime;
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct MaintenanceCommand {
+    pub action: MaintenanceAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum MaintenanceAction {
+    /// iCalendar feed written to `output`.
+    Ical {
+        output: PathBuf,
+        assignee: Option<String>,
+        days: i64,
+    },
+}
+
+impl Command for MaintenanceCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let mut building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+        let schedules = MaintenanceSchedules::load_at(&base)?;
+
+        match &self.action {
+            MaintenanceAction::Ical {
+                output,
+                assignee,
+                days,
+            } => {
+                // Forecast from the latest run signals; nothing is saved
+                sync_runtime(&base, &mut building)?;
+                let events = upcoming_events(
+                    &building,
+                    &schedules,
+                    clock::now(),
+                    *days,
+                    assignee.as_deref(),
+                );
+                std::fs::write(output, to_ical(&building.name, &events))
+                    .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+                println!(
+                    "✅ Wrote {} event(s) for the next {} day(s) to {}",
+                    events.len(),
+                    days,
+                    output.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "maintenance"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        match &self.action {
+            MaintenanceAction::Ical { days, .. } => {
+                if *days <= 0 {
+                    return Err("--days must be greater than 0".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
-//! Merge conflict resolution command
+//! Merge command: building-aware merge of a scenario branch, or interactive
+//! resolution of merge conflicts left by Git.
 
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
+use crate::git::merge::MergePreference;
 #[cfg(feature = "tui")]
 use crate::tui::merge_tool::MergeTool;
 use clap::Args;
@@ -7,21 +10,90 @@ use clap::Args;
 use std::path::Path;
 use std::path::PathBuf;
 
-/// Resolve merge conflicts interactively
+/// Merge a branch, or resolve merge conflicts interactively
 #[derive(Debug, Args)]
 pub struct MergeCommand {
-    /// File to resolve conflicts in (if not specified, finds all conflicted files)
-    #[arg(value_name = "FILE")]
+    /// Branch to merge into the current one, or a conflicted file to resolve
+    /// (if not specified, finds all conflicted files)
+    #[arg(value_name = "BRANCH|FILE")]
     file: Option<PathBuf>,
 
     /// List conflicted files without launching viewer
     #[arg(short, long)]
     list: bool,
+
+    /// Side that wins when both branches changed the same entity (ours, theirs)
+    #[arg(long)]
+    prefer: Option<MergePreference>,
 }
 
 impl MergeCommand {
-    #[cfg(feature = "tui")]
     pub fn execute(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(branch) = self.branch_target() {
+            return self.merge_branch(&branch);
+        }
+        self.resolve_conflicts()
+    }
+
+    /// The positional argument when it names a local branch rather than a file.
+    fn branch_target(&self) -> Option<String> {
+        let target = self.file.as_ref().filter(|p| !p.exists())?.to_str()?;
+        let repo = git2::Repository::open(".").ok()?;
+        repo.find_branch(target, git2::BranchType::Local).ok()?;
+        Some(target.to_string())
+    }
+
+    fn merge_branch(&self, branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+        use crate::git::branch::MergeKind;
+
+        let config = GitConfigManager::load_from_arx_config_or_env();
+        let manager = BuildingGitManager::new(".", "current", config)?;
+        let report = manager.merge_branch(branch, self.prefer)?;
+        if let Some(merged) = &report.building {
+            for change in &merged.changes {
+                println!("   {}", change);
+            }
+            for conflict in &merged.resolved {
+                println!(
+                    "   ⚖️  {} (kept {})",
+                    conflict,
+                    self.prefer.map(|p| p.to_string()).unwrap_or_default()
+                );
+            }
+            for conflict in &merged.conflicts {
+                println!("   ⚠️  {}", conflict);
+            }
+        }
+        for file in &report.file_conflicts {
+            println!("   ⚠️  {}: conflicting edits", file);
+        }
+        if report.has_conflicts() {
+            return Err(format!(
+                "Merge of '{}' stopped with conflicts; nothing was changed. \
+                 Re-run with --prefer ours|theirs to settle entity conflicts",
+                branch
+            )
+            .into());
+        }
+        let short = |id: &Option<String>| {
+            id.as_deref()
+                .map(|id| id[..8.min(id.len())].to_string())
+                .unwrap_or_default()
+        };
+        match report.kind {
+            MergeKind::UpToDate => println!("✅ Already up to date with {}", branch),
+            MergeKind::FastForward => println!(
+                "⏩ Fast-forwarded to {} ({})",
+                branch,
+                short(&report.commit)
+            ),
+            MergeKind::Merged => println!("🔀 Merged {} ({})", branch, short(&report.commit)),
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "tui")]
+    fn resolve_conflicts(&self) -> Result<(), Box<dyn std::error::Error>> {
         if self.list {
             return self.list_conflicts();
         }
@@ -38,7 +110,7 @@ impl MergeCommand {
     }
 
     #[cfg(not(feature = "tui"))]
-    pub fn execute(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn resolve_conflicts(&self) -> Result<(), Box<dyn std::error::Error>> {
         Err("TUI feature not enabled".into())
     }
 
@@ -54,6 +126,12 @@ impl MergeCommand {
         println!("Files with merge conflicts:");
         for file in files {
             println!("  {}", file.display());
+            for record in MergeTool::recorded_conflicts(&file)? {
+                println!(
+                    "    ⚠️  {} '{}': {}",
+                    record.kind, record.name, record.reason
+                );
+            }
         }
         println!("\nRun 'arx merge <file>' to resolve conflicts.");
 
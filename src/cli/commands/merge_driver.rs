@@ -0,0 +1,52 @@
+//! `arx merge-driver`: the Git merge driver `arx init` registers for building.yaml.
+
+use super::Command;
+use crate::git::merge_driver::{run_merge_driver, MERGE_CONFLICTS_FILE};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Invoked by Git as `arx merge-driver %O %A %B %P`; fails (so Git marks the
+/// file conflicted) when entities need a decision.
+pub struct MergeDriverCommand {
+    pub base: PathBuf,
+    /// Our version; replaced with the merge result
+    pub ours: PathBuf,
+    pub theirs: PathBuf,
+    /// Path of the file in the repository
+    pub path: String,
+}
+
+impl Command for MergeDriverCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        // Git runs merge drivers from the top of the working tree
+        let records = run_merge_driver(
+            Path::new("."),
+            &self.base,
+            &self.ours,
+            &self.theirs,
+            &self.path,
+        )?;
+        if records.is_empty() {
+            println!("🔀 Merged {} entity by entity", self.path);
+            return Ok(());
+        }
+        for record in &records {
+            eprintln!(
+                "   ⚠️  {} '{}': {}",
+                record.kind, record.name, record.reason
+            );
+        }
+        Err(format!(
+            "{} entity conflict(s) in {} (recorded in {}); run 'arx merge {}' to resolve",
+            records.len(),
+            self.path,
+            MERGE_CONFLICTS_FILE,
+            self.path
+        )
+        .into())
+    }
+
+    fn name(&self) -> &'static str {
+        "merge-driver"
+    }
+}
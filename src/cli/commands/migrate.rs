@@ -1,9 +1,11 @@
-//! One-shot migration: fill missing durable `ArxAddress` on equipment.
+//! One-shot migrations: fill missing durable `ArxAddress` on equipment, and
+//! upgrade persisted documents to the current `schema_version`.
 
 use super::Command;
 use crate::core::operations::backfill_equipment_addresses;
+use crate::core::provenance::{ProvenanceGuard, ProvenanceSource};
 use crate::ingest::persist_building_at;
-use crate::persistence::{load_building_at, BUILDING_YAML};
+use crate::persistence::{load_building_at, schema, BUILDING_YAML};
 use std::error::Error;
 use std::path::PathBuf;
 
@@ -46,6 +48,7 @@ impl Command for MigrateCommand {
         }
 
         // Path-aware persist — does not mutate process cwd (Track I10).
+        let _provenance = ProvenanceGuard::source(ProvenanceSource::Migration);
         persist_building_at(
             &base,
             building,
@@ -60,3 +63,47 @@ impl Command for MigrateCommand {
         "migrate"
     }
 }
+
+/// Rewrite older documents at their current schema version.
+pub struct SchemaMigrateCommand {
+    pub dry_run: bool,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for SchemaMigrateCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let migrated = schema::migrate_repo(&base, self.dry_run)?;
+        if migrated.is_empty() {
+            println!("✅ All documents are at their current schema version");
+            return Ok(());
+        }
+        let verb = if self.dry_run {
+            "Would upgrade"
+        } else {
+            "Upgraded"
+        };
+        for file in &migrated {
+            let from = match file.upgrade.from {
+                Some(v) => format!("v{}", v),
+                None => "unversioned".to_string(),
+            };
+            println!(
+                "🔄 {} {} ({}): {} → v{}",
+                verb, file.path, file.document, from, file.upgrade.to
+            );
+        }
+        if !self.dry_run {
+            println!(
+                "✅ {} document(s) migrated; review and commit",
+                migrated.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "migrate schema"
+    }
+}
@@ -1,35 +1,128 @@
 //! CLI command implementations for the Building compiler surface.
 
 pub mod access;
+pub mod approval;
+pub mod attachment;
+pub mod audit;
+pub mod backup;
+pub mod booking;
+pub mod clean;
 pub mod command_trait;
+pub mod commission;
+pub mod compare;
+pub mod completions;
+pub mod config;
 pub mod contribute;
 pub mod data;
+pub mod dataset;
 pub mod edit;
+pub mod completeness;
+pub mod doc;
+pub mod egress;
+pub mod energy;
+pub mod floor;
+pub mod issue;
+pub mod maintenance;
+pub mod workorder;
 pub mod export;
 pub mod git;
 pub mod import;
+pub mod import_csv;
 pub mod import_lidar;
+pub mod import_plugin;
 pub mod init;
+pub mod inventory;
+pub mod cost;
+pub mod jobs;
 pub mod merge;
+pub mod merge_driver;
 pub mod migrate;
+pub mod notify;
+pub mod provenance;
 pub mod query;
+pub mod rename;
+pub mod role;
+pub mod rollback;
+pub mod sensor;
+pub mod system;
+pub mod template;
+pub mod wizard;
+pub mod verify;
+pub mod workspace;
 
 #[cfg(feature = "tui")]
 pub mod search;
 
 pub use access::AccessCommand;
+pub use approval::{ApprovalsCommand, ApproveCommand};
+pub use attachment::AttachmentCommand;
+pub use audit::AuditCommand;
+pub use backup::BackupCommand;
+pub use booking::BookingCommand;
+pub use clean::CleanCommand;
 pub use command_trait::Command;
+pub use commission::CommissionCommand;
+pub use compare::CompareCommand;
+pub use completions::CompletionsCommand;
+pub use config::ConfigDoctorCommand;
 pub use contribute::ContributeCommand;
+pub use dataset::DatasetCommand;
+pub use completeness::CompletenessCommand;
+pub use doc::DocCommand;
+pub use egress::EgressCommand;
+pub use energy::EnergyCommand;
+pub use floor::FloorCommand;
+pub use issue::IssueCommand;
+pub use maintenance::MaintenanceCommand;
+pub use workorder::WorkOrderCommand;
 pub use export::ExportCommand;
 pub use import::ImportCommand;
 pub use init::InitCommand;
+pub use inventory::InventoryCommand;
+pub use cost::CostCommand;
+pub use jobs::JobsCommand;
 pub use merge::MergeCommand;
-pub use migrate::MigrateCommand;
+pub use merge_driver::MergeDriverCommand;
+pub use migrate::{MigrateCommand, SchemaMigrateCommand};
+pub use notify::NotifyTestCommand;
+pub use provenance::ProvenanceCommand;
+pub use rename::RenameCommand;
+pub use role::RoleCommand;
+pub use rollback::RollbackCommand;
+pub use sensor::SensorCommand;
+pub use system::SystemCommand;
+pub use template::TemplateCommand;
+pub use wizard::WizardCommand;
+pub use verify::VerifyCommand;
+pub use workspace::WorkspaceCommand;
 
 #[cfg(feature = "tui")]
 pub use search::SearchCommand;
 
+#[cfg(feature = "bacnet")]
+pub mod bacnet;
+#[cfg(feature = "bacnet")]
+pub use bacnet::BacnetCommand;
+
+#[cfg(feature = "ask")]
+pub mod ask;
+#[cfg(feature = "ask")]
+pub use ask::AskCommand;
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttCommand;
+
+#[cfg(feature = "agent")]
+pub mod agent;
 #[cfg(feature = "agent")]
 pub mod remote;
 #[cfg(feature = "agent")]
+pub use agent::AgentCommand;
+#[cfg(feature = "agent")]
 pub use remote::RemoteCommand;
+#[cfg(feature = "agent")]
+pub mod devtools;
+#[cfg(feature = "agent")]
+pub use devtools::DevtoolsCommand;
@@ -0,0 +1,120 @@
+//! MQTT command: publish building state changes to a broker
+//! (`.arx/mqtt.yaml`).
+
+use super::Command;
+use crate::hardware::mqtt::bridge::{enqueue, publish_queued, recent_commits};
+use crate::hardware::mqtt::{Bridge, MqttClient, MqttConfig};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub struct MqttCommand {
+    pub action: MqttAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum MqttAction {
+    /// Publish state changes, once or every `interval_secs`.
+    Bridge { once: bool },
+    /// Publish one message (to check broker settings).
+    Publish {
+        topic: String,
+        payload: String,
+        retain: bool,
+    },
+}
+
+impl MqttCommand {
+    fn load_building(base: &Path) -> Result<crate::core::Building, Box<dyn Error>> {
+        load_building_at(base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+            .into()
+        })
+    }
+
+    fn run_bridge(base: &Path, config: &MqttConfig, once: bool) -> Result<(), Box<dyn Error>> {
+        let mut bridge = Bridge::new(config.clone());
+        let mut queue = VecDeque::new();
+        let mut client: Option<MqttClient> = None;
+        loop {
+            let building = Self::load_building(base)?;
+            let commits = recent_commits(base)?;
+            enqueue(&mut queue, bridge.changes(&building, &commits));
+
+            if client.is_none() && !queue.is_empty() {
+                match MqttClient::connect(config) {
+                    Ok(c) => {
+                        println!("📡 Connected to {}", c.broker());
+                        client = Some(c);
+                    }
+                    Err(e) if once => return Err(e.into()),
+                    Err(e) => println!("  ⚠️  {} ({} message(s) queued)", e, queue.len()),
+                }
+            }
+            if let Some(c) = client.as_mut() {
+                let result = publish_queued(c, &mut queue).and_then(|sent| {
+                    c.keep_alive()?;
+                    Ok(sent)
+                });
+                match result {
+                    Ok(0) => {}
+                    Ok(sent) => println!("📡 Published {} message(s)", sent),
+                    Err(e) if once => return Err(e.into()),
+                    Err(e) => {
+                        println!("  ⚠️  {} ({} message(s) queued)", e, queue.len());
+                        client = None;
+                    }
+                }
+            }
+            if once {
+                if let Some(c) = client {
+                    c.disconnect();
+                }
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_secs(config.interval_secs));
+        }
+    }
+}
+
+impl Command for MqttCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let config = MqttConfig::load_at(&base)?;
+
+        match &self.action {
+            MqttAction::Bridge { once } => Self::run_bridge(&base, &config, *once)?,
+            MqttAction::Publish {
+                topic,
+                payload,
+                retain,
+            } => {
+                let mut client = MqttClient::connect(&config)?;
+                client.publish(topic, payload.as_bytes(), *retain)?;
+                client.disconnect();
+                println!("📡 Published to {} on {}", topic, config.socket_address());
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "mqtt"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let MqttAction::Publish { topic, .. } = &self.action {
+            crate::hardware::mqtt::codec::validate_topic(topic)?;
+        }
+        Ok(())
+    }
+}
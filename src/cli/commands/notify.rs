@@ -0,0 +1,55 @@
+//! Notify command: send a test message to configured webhooks.
+
+use super::Command;
+use crate::notifications::{send, Event, NotificationConfig, NOTIFICATIONS_FILE};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct NotifyTestCommand {
+    /// Only this webhook (by name)
+    pub webhook: Option<String>,
+    /// Project root holding .arx/notifications.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl Command for NotifyTestCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let config = NotificationConfig::load_at(&base)?
+            .ok_or_else(|| format!("No webhooks configured ({} not found)", NOTIFICATIONS_FILE))?;
+        if let Some(name) = &self.webhook {
+            if !config.webhooks.iter().any(|w| &w.name == name) {
+                return Err(
+                    format!("No webhook named '{}' in {}", name, NOTIFICATIONS_FILE).into(),
+                );
+            }
+        }
+
+        let deliveries = send(&base, &config, &Event::Test, self.webhook.as_deref());
+        if deliveries.is_empty() {
+            println!("📭 No webhooks in {}", NOTIFICATIONS_FILE);
+            return Ok(());
+        }
+        let mut failed = 0;
+        for delivery in &deliveries {
+            match &delivery.error {
+                None => println!("✅ {} delivered", delivery.webhook),
+                Some(e) => {
+                    failed += 1;
+                    println!(
+                        "❌ {} failed after {} attempt(s): {}",
+                        delivery.webhook, delivery.attempts, e
+                    );
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(format!("{} of {} webhook(s) failed", failed, deliveries.len()).into());
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "notify"
+    }
+}
@@ -0,0 +1,184 @@
+//! Provenance command: where a room or equipment field value came from.
+
+use super::Command;
+use crate::core::provenance::{lookup, FieldProvenance, ProvenanceMap};
+use crate::core::Building;
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use crate::yaml::BuildingYamlSerializer;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Show the provenance of `field` (or every tracked field) on `entity`.
+pub struct ProvenanceCommand {
+    /// Room or equipment id, name, or alias
+    pub entity: String,
+    pub field: Option<String>,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+fn entity_provenance<'a>(
+    building: &'a Building,
+    query: &str,
+) -> Option<(String, &'a ProvenanceMap)> {
+    if let Some(room) = building
+        .get_all_rooms()
+        .into_iter()
+        .find(|r| r.id.eq_ignore_ascii_case(query) || r.matches_name(query))
+    {
+        return Some((format!("room '{}'", room.name), &room.provenance));
+    }
+    building
+        .find_equipment(query)
+        .map(|eq| (format!("equipment '{}'", eq.name), &eq.provenance))
+}
+
+/// Oldest commit in the unbroken run of history (newest first) whose
+/// building.yaml already held `entry` for `field` on `entity`.
+fn recording_commit(
+    base: &Path,
+    entity: &str,
+    field: &str,
+    entry: &FieldProvenance,
+) -> Option<String> {
+    if !base.join(".git").exists() {
+        return None;
+    }
+    let git = BuildingGitManager::new(
+        base.to_str()?,
+        "building",
+        GitConfigManager::load_from_arx_config_or_env(),
+    )
+    .ok()?;
+    let mut found = None;
+    for commit in git.get_file_history(BUILDING_YAML).ok()? {
+        let stored = git
+            .read_file_at(&commit.id, BUILDING_YAML)
+            .ok()
+            .flatten()
+            .and_then(|yaml| BuildingYamlSerializer::deserialize_building(&yaml).ok());
+        let same = stored.as_ref().is_some_and(|b| {
+            entity_provenance(b, entity)
+                .and_then(|(_, map)| map.get(field))
+                .is_some_and(|p| p == entry)
+        });
+        if !same {
+            break;
+        }
+        found = Some(commit.id);
+    }
+    found
+}
+
+fn print_entry(field: &str, entry: &FieldProvenance, commit: Option<&str>) {
+    println!("   {}", field);
+    println!("     source: {}", entry.source);
+    println!("     at: {}", entry.at.to_rfc3339());
+    if let Some(actor) = &entry.actor {
+        println!("     actor: {}", actor);
+    }
+    match commit {
+        Some(id) => println!("     commit: {}", &id[..id.len().min(12)]),
+        None => println!("     commit: (not committed)"),
+    }
+}
+
+impl Command for ProvenanceCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+        let (label, map) = entity_provenance(&building, &self.entity)
+            .ok_or_else(|| format!("No room or equipment matches '{}'", self.entity))?;
+
+        let entries: Vec<(&str, &FieldProvenance)> = match &self.field {
+            Some(field) => vec![lookup(map, field).ok_or_else(|| {
+                format!(
+                    "No provenance recorded for '{}' on {} (unchanged since tracking began)",
+                    field, label
+                )
+            })?],
+            None => map.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+        };
+        println!("🔎 Provenance of {}", label);
+        if entries.is_empty() {
+            println!("   (no fields changed since tracking began)");
+        }
+        for (field, entry) in entries {
+            let commit = entry
+                .commit
+                .clone()
+                .or_else(|| recording_commit(&base, &self.entity, field, entry));
+            print_entry(field, entry, commit.as_deref());
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "provenance"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn test_recording_commit_is_first_commit_with_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let pm = crate::persistence::PersistenceManager::at(dir.path());
+        pm.save_and_commit(&building, Some("Add plant")).unwrap();
+        let first = load_building_at(dir.path()).unwrap();
+        let mut edited = first.clone();
+        edited
+            .find_equipment_mut("AHU-1")
+            .unwrap()
+            .properties
+            .insert("serial".into(), "SN-42".into());
+        pm.save_and_commit(&edited, Some("Record serial")).unwrap();
+
+        let stored = load_building_at(dir.path()).unwrap();
+        let (_, map) = entity_provenance(&stored, "AHU-1").unwrap();
+        let git = BuildingGitManager::new(
+            dir.path().to_str().unwrap(),
+            "building",
+            GitConfigManager::default_config(),
+        )
+        .unwrap();
+        let history = git.get_file_history(BUILDING_YAML).unwrap();
+        assert_eq!(history.len(), 2);
+
+        let (_, name) = lookup(map, "name").unwrap();
+        let (field, serial) = lookup(map, "serial").unwrap();
+        assert_eq!(
+            recording_commit(dir.path(), "AHU-1", "name", name),
+            Some(history[1].id.clone())
+        );
+        assert_eq!(
+            recording_commit(dir.path(), "AHU-1", field, serial),
+            Some(history[0].id.clone())
+        );
+    }
+}
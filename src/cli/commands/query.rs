@@ -1,8 +1,13 @@
-//! Query command: match equipment by durable `ArxAddress` glob patterns.
+//! Query command: match entities by hierarchical `ArxAddress` glob patterns
+//! (`*` per segment, `**` across segments, `!` negation), or equipment by
+//! name / alias glob when the pattern is not an address.
 
 use super::Command;
 use crate::cli::args::{QueryArgs, SearchArgs};
-use crate::core::Equipment;
+use crate::core::alias::display_names;
+use crate::core::domain::AddressPattern;
+use crate::core::operations::{resolve_addresses, AddressKind, ResolvedAddress};
+use crate::core::{Building, Equipment};
 use crate::persistence::load_building_data_from_dir;
 use std::error::Error;
 
@@ -32,7 +37,26 @@ pub struct QueryCommand {
 impl Command for QueryCommand {
     fn execute(&self) -> Result<(), Box<dyn Error>> {
         self.validate()?;
-        run_address_query(&self.args.pattern, &self.args.format, self.args.verbose)
+        if self.args.pattern.starts_with('/') {
+            let kind = self.args.kind.parse::<KindFilter>()?;
+            run_address_query(
+                &self.args.pattern,
+                &self.args.exclude,
+                kind,
+                &self.args.format,
+                self.args.verbose,
+            )
+        } else {
+            let building = load_building_data_from_dir()?;
+            let matches = query_equipment_by_name(&building, &self.args.pattern)?;
+            println!("🔍 Query name / alias: {}", self.args.pattern);
+            println!();
+            if matches.is_empty() {
+                println!("❌ No equipment found matching name or alias");
+                return Ok(());
+            }
+            print_matches(&matches, &self.args.format, self.args.verbose)
+        }
     }
 
     fn name(&self) -> &'static str {
@@ -40,8 +64,8 @@ impl Command for QueryCommand {
     }
 
     fn validate(&self) -> Result<(), Box<dyn Error>> {
-        if !self.args.pattern.starts_with('/') {
-            return Err("ArxAddress pattern must start with '/'".into());
+        if self.args.pattern.trim().is_empty() {
+            return Err("Query pattern must not be empty".into());
         }
         match self.args.format.as_str() {
             "table" | "json" | "yaml" => Ok(()),
@@ -54,46 +78,147 @@ impl Command for QueryCommand {
     }
 }
 
-/// Load Building and return equipment whose durable `address` matches `pattern`.
-pub fn query_equipment_by_address(pattern: &str) -> Result<Vec<Equipment>, Box<dyn Error>> {
-    let building = load_building_data_from_dir()?;
-    let mut matches = Vec::new();
+/// Which entity kinds an address query returns (`--kind`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KindFilter {
+    All,
+    Only(AddressKind),
+}
 
-    for item in building.get_all_equipment() {
-        if let Some(addr) = &item.address {
-            if addr.matches_glob(pattern) {
-                matches.push(item.clone());
-            }
+impl std::str::FromStr for KindFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            Ok(KindFilter::All)
+        } else {
+            s.parse().map(KindFilter::Only)
         }
     }
+}
 
-    Ok(matches)
+impl KindFilter {
+    fn includes(&self, kind: AddressKind) -> bool {
+        match self {
+            KindFilter::All => true,
+            KindFilter::Only(only) => *only == kind,
+        }
+    }
 }
 
-/// Run address query and print results.
-pub fn run_address_query(pattern: &str, format: &str, verbose: bool) -> Result<(), Box<dyn Error>> {
-    println!("🔍 Query pattern: {}", pattern);
-    println!();
+fn compile(pattern: &str) -> Result<AddressPattern, Box<dyn Error>> {
+    AddressPattern::parse(pattern).map_err(|e| format!("Invalid ArxAddress pattern: {}", e).into())
+}
 
-    // Segment count check (must contain at least one segment)
-    let parts: Vec<&str> = pattern.trim_start_matches('/').split('/').filter(|s| !s.is_empty()).collect();
-    if parts.is_empty() {
-        return Err("Invalid ArxAddress pattern. Must contain at least one segment".into());
-    }
+/// Entities whose full address (durable or derived) matches `pattern` and
+/// none of the `exclude` patterns.
+pub fn query_addresses(
+    building: &Building,
+    pattern: &str,
+    exclude: &[String],
+    kind: KindFilter,
+) -> Result<Vec<ResolvedAddress>, Box<dyn Error>> {
+    let pattern = compile(pattern)?;
+    let exclude = exclude
+        .iter()
+        .map(|p| compile(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(resolve_addresses(building)
+        .into_iter()
+        .filter(|r| kind.includes(r.kind))
+        .filter(|r| pattern.matches(&r.address) && !exclude.iter().any(|x| x.matches(&r.address)))
+        .collect())
+}
 
-    // Validate glob syntax
-    if glob::Pattern::new(pattern).is_err() {
-        return Err(format!("Invalid glob pattern: {}", pattern).into());
-    }
+/// Equipment in `building` matching the address query, carrying its full
+/// address.
+pub fn equipment_by_address(
+    building: &Building,
+    pattern: &str,
+    exclude: &[String],
+) -> Result<Vec<Equipment>, Box<dyn Error>> {
+    let equipment = KindFilter::Only(AddressKind::Equipment);
+    Ok(query_addresses(building, pattern, exclude, equipment)?
+        .into_iter()
+        .filter_map(|r| {
+            let mut eq = building.find_equipment(&r.id)?.clone();
+            eq.address = Some(r.address);
+            Some(eq)
+        })
+        .collect())
+}
+
+/// Load Building and return equipment whose address matches `pattern`.
+pub fn query_equipment_by_address(pattern: &str) -> Result<Vec<Equipment>, Box<dyn Error>> {
+    let building = load_building_data_from_dir()?;
+    equipment_by_address(&building, pattern, &[])
+}
+
+/// Equipment whose name or any alias matches `pattern` (case-insensitive glob).
+pub fn query_equipment_by_name(
+    building: &Building,
+    pattern: &str,
+) -> Result<Vec<Equipment>, Box<dyn Error>> {
+    let glob = glob::Pattern::new(&pattern.to_lowercase())
+        .map_err(|_| format!("Invalid glob pattern: {}", pattern))?;
+    Ok(building
+        .get_all_equipment()
+        .into_iter()
+        .filter(|e| display_names(&e.name, &e.aliases).any(|n| glob.matches(&n.to_lowercase())))
+        .cloned()
+        .collect())
+}
 
-    let matches = query_equipment_by_address(pattern)?;
+/// Run address query and print results: equipment in the usual layout, any
+/// other kind as kind / name / address rows.
+pub fn run_address_query(
+    pattern: &str,
+    exclude: &[String],
+    kind: KindFilter,
+    format: &str,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let building = load_building_data_from_dir()?;
+    if kind == KindFilter::Only(AddressKind::Equipment) {
+        let matches = equipment_by_address(&building, pattern, exclude)?;
+        println!("🔍 Query pattern: {}", pattern);
+        println!();
+        if matches.is_empty() {
+            println!("❌ No equipment found matching pattern");
+            return Ok(());
+        }
+        return print_matches(&matches, format, verbose);
+    }
 
-    if matches.is_empty() {
-        println!("❌ No equipment found matching pattern");
-        println!("   (equipment must have a durable `address` field in building.yaml)");
-        return Ok(());
+    let matches = query_addresses(&building, pattern, exclude, kind)?;
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&matches)?),
+        "yaml" => println!("{}", serde_yaml::to_string(&matches)?),
+        _ => {
+            println!("🔍 Query pattern: {}", pattern);
+            println!();
+            if matches.is_empty() {
+                println!("❌ No entities found matching pattern");
+                return Ok(());
+            }
+            println!("  {:<10} {:<28} Address", "Kind", "Name");
+            for r in &matches {
+                println!(
+                    "  {:<10} {:<28} {}{}",
+                    r.kind.to_string(),
+                    truncate(&r.name, 26),
+                    r.address,
+                    if r.durable { "" } else { "  (derived)" }
+                );
+            }
+            println!();
+            println!("✅ Total: {} result(s)", matches.len());
+        }
     }
+    Ok(())
+}
 
+pub fn print_matches(matches: &[Equipment], format: &str, verbose: bool) -> Result<(), Box<dyn Error>> {
     match format {
         "json" => {
             println!("{}", serde_json::to_string_pretty(&matches)?);
@@ -107,7 +232,7 @@ pub fn run_address_query(pattern: &str, format: &str, verbose: bool) -> Result<(
             println!("  {:<28} {:<12} {:<50}", "Name", "Type", "Address");
             println!("  {}", "-".repeat(92));
 
-            for item in &matches {
+            for item in matches {
                 let eq_type = format!("{:?}", item.equipment_type);
                 let name = truncate(&item.name, 26);
                 let addr = item
@@ -125,6 +250,9 @@ pub fn run_address_query(pattern: &str, format: &str, verbose: bool) -> Result<(
 
                 if verbose {
                     println!("     id: {}", item.id);
+                    if !item.aliases.is_empty() {
+                        println!("     aka: {}", item.aliases.join(", "));
+                    }
                     if !item.properties.is_empty() {
                         for (key, value) in item.properties.iter().take(5) {
                             println!("       {}: {}", key, value);
@@ -193,14 +321,85 @@ mod tests {
         let original = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
         env::set_current_dir(dir).unwrap();
 
-        let matches = query_equipment_by_address("/usa/ny/*/floor-*/mech/boiler-*").expect("query");
+        let matches =
+            query_equipment_by_address("/usa/ny/*/*/floor-*/mech/boiler-*").expect("query");
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].name, "Boiler-01");
 
         let none = query_equipment_by_address("/usa/ca/*/floor-*/mech/*").expect("query");
         assert!(none.is_empty());
+        let deep = query_equipment_by_address("/usa/**/boiler-*").expect("query");
+        assert_eq!(deep.len(), 1);
 
         assert!(dir.join(BUILDING_YAML).exists());
         env::set_current_dir(original).unwrap();
     }
+
+    #[test]
+    fn test_query_matches_name_or_alias_glob() {
+        let mut building = Building::new("Alias HQ".into(), "/a".into());
+        let mut eq = Equipment::new("AHU-3".into(), String::new(), EquipmentType::HVAC);
+        eq.add_alias("Rooftop Unit East");
+        let mut room = Room::new("R-2.113".into(), RoomType::Mechanical);
+        room.add_equipment(eq);
+        let mut wing = Wing::new("Main".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Roof".into(), 9);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        assert_eq!(query_equipment_by_name(&building, "ahu-*").unwrap().len(), 1);
+        assert_eq!(query_equipment_by_name(&building, "rooftop*").unwrap().len(), 1);
+        assert!(query_equipment_by_name(&building, "boiler*").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_addresses_all_kinds_with_exclusions() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Floor 1".into(), 1);
+        let mut wing = Wing::new("East".into());
+        for (room_name, eq_name) in [("Mech", "AHU 1"), ("Kitchen", "Fridge")] {
+            let mut room = Room::new(room_name.into(), RoomType::Mechanical);
+            room.add_equipment(Equipment::new(
+                eq_name.into(),
+                String::new(),
+                EquipmentType::Other("x".into()),
+            ));
+            wing.add_room(room);
+        }
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let names = |matches: Vec<ResolvedAddress>| -> Vec<String> {
+            matches
+                .into_iter()
+                .map(|r| format!("{}:{}", r.kind, r.name))
+                .collect()
+        };
+        let everything = query_addresses(&building, "/local/**", &[], KindFilter::All).unwrap();
+        assert_eq!(
+            everything.len(),
+            7,
+            "building, floor, wing, rooms, equipment"
+        );
+
+        let outside_kitchen = query_addresses(
+            &building,
+            "/**/floor-1/!kitchen/*",
+            &[],
+            KindFilter::Only(AddressKind::Equipment),
+        )
+        .unwrap();
+        assert_eq!(names(outside_kitchen), ["equipment:AHU 1"]);
+
+        let rooms = query_addresses(
+            &building,
+            "/local/**",
+            &["/**/mech".to_string()],
+            "room".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(names(rooms), ["room:Kitchen"]);
+        assert!(query_addresses(&building, "/", &[], KindFilter::All).is_err());
+    }
 }
@@ -0,0 +1,124 @@
+//! Rename command: rename a floor, wing, room, or equipment and rewrite references.
+
+use super::Command;
+use crate::core::operations::rename_path;
+use crate::ingest::persist_building_at;
+use crate::persistence::issues::{load_issues, save_issue, ISSUES_DIR};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Rename `from` to `to` (paths differing only in their last segment).
+pub struct RenameCommand {
+    pub from: String,
+    pub to: String,
+    /// Commit building.yaml and touched issue records in one commit
+    pub commit: bool,
+    /// Show the rewrites without saving
+    pub dry_run: bool,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl RenameCommand {
+    /// Point stored issues for a renamed room at its new name; returns the
+    /// repo-relative paths of rewritten issue files.
+    fn rewrite_issues(
+        base: &Path,
+        room_id: &str,
+        new_name: &str,
+        dry_run: bool,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut touched = Vec::new();
+        for mut issue in load_issues(base)? {
+            if issue.room_id.as_deref() != Some(room_id)
+                || issue.room_name.as_deref() == Some(new_name)
+            {
+                continue;
+            }
+            issue.room_name = Some(new_name.to_string());
+            if !dry_run {
+                save_issue(base, &issue)?;
+            }
+            touched.push(format!("{}/{}.yaml", ISSUES_DIR, issue.id));
+        }
+        Ok(touched)
+    }
+}
+
+impl Command for RenameCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let mut building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+
+        let report = rename_path(&mut building, &self.from, &self.to)?;
+        println!(
+            "✏️  Renaming {} '{}' → '{}'",
+            report.kind, report.old_name, report.new_name
+        );
+        for change in &report.changes {
+            println!("   {}", change);
+        }
+
+        let issue_files = if report.kind == "room" {
+            Self::rewrite_issues(&base, &report.entity_id, &report.new_name, self.dry_run)?
+        } else {
+            Vec::new()
+        };
+        for file in &issue_files {
+            println!("   issue record: {}", file);
+        }
+
+        if self.dry_run {
+            println!(
+                "🔍 Dry run: {} reference(s) would change",
+                report.changes.len() + issue_files.len()
+            );
+            return Ok(());
+        }
+
+        // Stage issue records first so save_and_commit folds them into the same commit
+        if self.commit && !issue_files.is_empty() && base.join(".git").exists() {
+            use crate::git::manager::{BuildingGitManager, GitConfigManager};
+            let base_str = base.to_str().ok_or("base path is not valid UTF-8")?;
+            let mut git = BuildingGitManager::new(
+                base_str,
+                "building",
+                GitConfigManager::load_from_arx_config_or_env(),
+            )?;
+            for file in &issue_files {
+                git.stage_file(file)?;
+            }
+        }
+
+        let message = format!("Rename {} → {}", self.from, self.to);
+        persist_building_at(&base, building, self.commit, Some(&message))?;
+        println!(
+            "✅ Renamed {} '{}' → '{}' ({} reference(s) updated)",
+            report.kind,
+            report.old_name,
+            report.new_name,
+            report.changes.len() + issue_files.len()
+        );
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "rename"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.from.trim().is_empty() || self.to.trim().is_empty() {
+            return Err("--from and --to are required".into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+//! Role command: assign the roles enforced on building edits.
+
+use super::Command;
+use crate::core::access::{AccessPolicy, Role, ROLES_FILE};
+use crate::persistence::PersistenceManager;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct RoleCommand {
+    pub action: RoleAction,
+    /// Show the change without writing the roles file
+    pub dry_run: bool,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum RoleAction {
+    List,
+    Set { user: String, role: String },
+    Remove { user: String },
+}
+
+impl RoleCommand {
+    /// Policy to edit; the first assignment makes the current user admin so
+    /// nobody is locked out. Only admins may edit an existing policy.
+    fn editable_policy(base: &Path, me: &str) -> Result<AccessPolicy, Box<dyn Error>> {
+        match AccessPolicy::load(base)? {
+            Some(policy) if policy.role_of(me) == Role::Admin => Ok(policy),
+            Some(policy) => Err(format!(
+                "Permission denied: {} ({}) may not change roles",
+                me,
+                policy.role_of(me)
+            )
+            .into()),
+            None => {
+                let mut policy = AccessPolicy::default();
+                policy.users.insert(me.to_string(), Role::Admin);
+                println!("🔐 Enabling roles: {} becomes admin", me);
+                Ok(policy)
+            }
+        }
+    }
+
+    fn save(&self, base: &Path, policy: &AccessPolicy) -> Result<(), Box<dyn Error>> {
+        if !policy.users.values().any(|r| *r == Role::Admin) {
+            return Err("At least one user must stay admin".into());
+        }
+        if self.dry_run {
+            crate::core::operations::dry_run::record_file_write(
+                ROLES_FILE,
+                base.join(ROLES_FILE).exists(),
+            );
+            return Ok(());
+        }
+        policy.save(base)?;
+        Ok(())
+    }
+}
+
+impl Command for RoleCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let me = PersistenceManager::acting_user();
+
+        match &self.action {
+            RoleAction::List => {
+                let Some(policy) = AccessPolicy::load(&base)? else {
+                    println!(
+                        "No {}: every user may edit. Assign one with `arx role set <user> <role>`",
+                        ROLES_FILE
+                    );
+                    return Ok(());
+                };
+                for (user, role) in &policy.users {
+                    println!("   {:<24} {}", user, role);
+                }
+                println!("   {:<24} {}", "(everyone else)", policy.default_role);
+                println!("👤 You are {} ({})", me, policy.role_of(&me));
+            }
+            RoleAction::Set { user, role } => {
+                let role: Role = role.parse()?;
+                let mut policy = Self::editable_policy(&base, &me)?;
+                policy.users.insert(user.clone(), role);
+                self.save(&base, &policy)?;
+                println!("✅ {} is now {}", user, role);
+            }
+            RoleAction::Remove { user } => {
+                let mut policy = Self::editable_policy(&base, &me)?;
+                if policy.users.remove(user).is_none() {
+                    return Err(format!("'{}' has no role assignment", user).into());
+                }
+                self.save(&base, &policy)?;
+                println!(
+                    "✅ {} now has the default role ({})",
+                    user, policy.default_role
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "role"
+    }
+}
@@ -0,0 +1,208 @@
+//! Rollback command: restore a room, equipment, or the whole building from Git history.
+
+use super::Command;
+use crate::core::operations::{rollback_building, rollback_entity};
+use crate::core::Building;
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
+use crate::git::CommitInfo;
+use crate::ingest::persist_building_at;
+use crate::persistence::{load_building_at, PersistenceManager, BUILDING_YAML};
+use crate::yaml::BuildingYamlSerializer;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Restore `entity` (or everything) to its state in `commit` and commit the result.
+pub struct RollbackCommand {
+    /// Room or equipment path, id, or name; `None` restores the whole building
+    pub entity: Option<String>,
+    /// Revision to restore from (default: the last committed building.yaml
+    /// that differs from the current one)
+    pub commit: Option<String>,
+    /// Show what would be restored without saving
+    pub dry_run: bool,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(12)]
+}
+
+/// Most recent commit whose building.yaml differs from the stored one.
+fn previous_version(git: &BuildingGitManager, base: &Path) -> Result<CommitInfo, Box<dyn Error>> {
+    let stored = PersistenceManager::at(base)
+        .read_building_yaml()?
+        .unwrap_or_default();
+    for commit in git.get_file_history(BUILDING_YAML)? {
+        if git.read_file_at(&commit.id, BUILDING_YAML)?.as_deref() != Some(stored.as_str()) {
+            return Ok(commit);
+        }
+    }
+    Err(format!("No earlier version of {} in history", BUILDING_YAML).into())
+}
+
+fn building_at(git: &BuildingGitManager, commit: &CommitInfo) -> Result<Building, Box<dyn Error>> {
+    let yaml = git
+        .read_file_at(&commit.id, BUILDING_YAML)?
+        .ok_or_else(|| {
+            format!(
+                "{} does not exist at commit {}",
+                BUILDING_YAML,
+                short_id(&commit.id)
+            )
+        })?;
+    BuildingYamlSerializer::deserialize_building(&yaml).map_err(|e| {
+        format!(
+            "{} at commit {} does not parse: {}",
+            BUILDING_YAML,
+            short_id(&commit.id),
+            e
+        )
+        .into()
+    })
+}
+
+impl Command for RollbackCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        if !base.join(".git").exists() {
+            return Err(format!(
+                "{} is not a Git repository; rollback restores from commit history",
+                base.display()
+            )
+            .into());
+        }
+        let git = BuildingGitManager::new(
+            base.to_str().ok_or("base path is not valid UTF-8")?,
+            "building",
+            GitConfigManager::load_from_arx_config_or_env(),
+        )?;
+        let commit = match &self.commit {
+            Some(revision) => git
+                .resolve_commit(revision)
+                .map_err(|e| format!("Unknown commit '{}': {}", revision, e))?,
+            None => previous_version(&git, &base)?,
+        };
+        let earlier = building_at(&git, &commit)?;
+        let mut building = load_building_at(&base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?;
+
+        let report = match &self.entity {
+            Some(entity) => rollback_entity(&mut building, &earlier, entity)?,
+            None => {
+                let report = rollback_building(&building, &earlier);
+                building = earlier;
+                report
+            }
+        };
+        println!(
+            "⏪ Rolling back {} '{}' to {} ({})",
+            report.kind,
+            report.name,
+            short_id(&commit.id),
+            commit.message.lines().next().unwrap_or("").trim()
+        );
+        for change in &report.changes {
+            println!("   {}", change);
+        }
+        if report.changes.is_empty() {
+            println!(
+                "✅ Already matches {}; nothing to restore",
+                short_id(&commit.id)
+            );
+            return Ok(());
+        }
+        if self.dry_run {
+            println!(
+                "🔍 Dry run: {} change(s) would be restored",
+                report.changes.len()
+            );
+            return Ok(());
+        }
+
+        let message = format!(
+            "Roll back {} '{}' to {}\n\nArxOS-Rollback-Of: {}\nArxOS-Rollback-Entity: {}",
+            report.kind,
+            report.name,
+            short_id(&commit.id),
+            commit.id,
+            self.entity.as_deref().unwrap_or("(building)")
+        );
+        persist_building_at(&base, building, true, Some(&message))?;
+        println!(
+            "✅ Restored {} '{}' from {} ({} change(s), committed)",
+            report.kind,
+            report.name,
+            short_id(&commit.id),
+            report.changes.len()
+        );
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "rollback"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentStatus, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn test_rollback_equipment_creates_revert_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let pm = crate::persistence::PersistenceManager::at(dir.path());
+        pm.save_and_commit(&building, Some("Add plant")).unwrap();
+        let mut edited = load_building_at(dir.path()).unwrap();
+        edited.find_equipment_mut("AHU-1").unwrap().status = EquipmentStatus::Maintenance;
+        pm.save_and_commit(&edited, Some("AHU-1 down")).unwrap();
+
+        RollbackCommand {
+            entity: Some("AHU-1".into()),
+            commit: None,
+            dry_run: false,
+            path: Some(dir.path().to_path_buf()),
+        }
+        .execute()
+        .unwrap();
+
+        let restored = load_building_at(dir.path()).unwrap();
+        assert_eq!(
+            restored.find_equipment("AHU-1").unwrap().status,
+            EquipmentStatus::Active
+        );
+        let git = BuildingGitManager::new(
+            dir.path().to_str().unwrap(),
+            "building",
+            GitConfigManager::default_config(),
+        )
+        .unwrap();
+        let head = git.resolve_commit("HEAD").unwrap();
+        let first = git.resolve_commit("HEAD~2").unwrap();
+        assert!(head.message.starts_with("Roll back equipment 'AHU-1'"));
+        assert!(head
+            .message
+            .contains(&format!("ArxOS-Rollback-Of: {}", first.id)));
+    }
+}
@@ -0,0 +1,371 @@
+//! Sensor command: record incoming sensor ids, map them onto the model, and
+//! print their history.
+
+use super::dataset::parse_time;
+use super::Command;
+use crate::core::timeseries::downsample;
+use crate::persistence::sensors::{record_sensor_reading, sensor_history, unmapped_sensors};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct SensorCommand {
+    pub action: SensorAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum SensorAction {
+    /// Note a reading in the sensor inbox (for gateways without the agent).
+    Record {
+        sensor_id: String,
+        sensor_type: Option<String>,
+        value: Option<f64>,
+    },
+    /// Print inbox sensors that have no mapping.
+    Unmapped,
+    /// Interactive mapping editor (requires `tui`).
+    Map { commit: bool },
+    /// Readings of one sensor in a time range, optionally downsampled.
+    History {
+        sensor_id: String,
+        since: Option<String>,
+        until: Option<String>,
+        bucket_minutes: Option<u32>,
+        format: String,
+    },
+    /// Feed synthetic readings from a profile through ingestion: `hours` of
+    /// backfilled history ending now, or live samples when `realtime`.
+    Simulate {
+        profile: PathBuf,
+        hours: Option<f64>,
+        realtime: bool,
+    },
+    /// Poll Modbus registers into sensor ingestion, once or every `interval_secs`.
+    #[cfg(feature = "modbus")]
+    Modbus {
+        /// Mapping file (default: `.arx/modbus.yaml` under the project root)
+        config: Option<PathBuf>,
+        once: bool,
+    },
+}
+
+impl SensorCommand {
+    fn load(base: &Path) -> Result<crate::core::Building, Box<dyn Error>> {
+        Ok(load_building_at(base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?)
+    }
+
+    #[cfg(feature = "tui")]
+    fn run_editor(base: &Path, commit: bool) -> Result<(), Box<dyn Error>> {
+        use crate::tui::sensor_mapping::{SensorMappingAction, SensorMappingEditor};
+        use crate::tui::TerminalManager;
+        use crossterm::event::{self, Event};
+        use std::time::Duration;
+
+        let building = Self::load(base)?;
+        let unmapped = unmapped_sensors(base, &building)?;
+        if unmapped.is_empty() {
+            println!(
+                "✅ No unmapped sensors in {}",
+                crate::persistence::sensors::SENSOR_INBOX
+            );
+            return Ok(());
+        }
+
+        let mut editor = SensorMappingEditor::new(building, unmapped);
+        let action = {
+            let mut terminal_manager = TerminalManager::new()?;
+            loop {
+                terminal_manager.terminal().draw(|frame| {
+                    editor.render(frame, frame.size());
+                })?;
+                if event::poll(Duration::from_millis(100))? {
+                    if let Event::Key(key) = event::read()? {
+                        match editor.handle_key(key) {
+                            SensorMappingAction::Continue => {}
+                            action => break action,
+                        }
+                    }
+                }
+            }
+            // TerminalManager dropped here, restoring the terminal before printing
+        };
+
+        if action == SensorMappingAction::Exit {
+            if !editor.bound().is_empty() {
+                println!("↩️  Discarded {} binding(s)", editor.bound().len());
+            }
+            return Ok(());
+        }
+
+        let bound = editor.bound().to_vec();
+        let message = format!("Map {} sensor(s)", bound.len());
+        crate::ingest::persist_building_at(base, editor.into_building(), commit, Some(&message))?;
+        for (sensor_id, target) in &bound {
+            println!("📡 {} → {}", sensor_id, target);
+        }
+        println!("✅ Saved {} sensor mapping(s)", bound.len());
+        Ok(())
+    }
+}
+
+impl SensorCommand {
+    fn run_simulation(
+        base: &Path,
+        profile: &Path,
+        hours: Option<f64>,
+        realtime: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        use crate::core::clock::{self, ClockGuard, FixedClock, RandomIds};
+        use crate::core::sensor_sim::{SensorSimulator, SimulatedReading, SimulationProfile};
+        use std::sync::Arc;
+
+        let profile = SimulationProfile::load(profile)?;
+        let interval = chrono::Duration::seconds(profile.interval_secs as i64);
+        let mut simulator = SensorSimulator::new(profile);
+        let (mut recorded, mut faults) = (0usize, 0usize);
+        let mut feed = |readings: Vec<SimulatedReading>| -> Result<(), Box<dyn Error>> {
+            for r in readings {
+                // Stamp each reading with its simulated time
+                let _clock =
+                    ClockGuard::install(Arc::new(FixedClock::at(r.at)), Arc::new(RandomIds));
+                record_sensor_reading(base, &r.sensor_id, Some(&r.sensor_type), r.value)?;
+                recorded += 1;
+                faults += usize::from(r.fault.is_some());
+            }
+            Ok(())
+        };
+
+        let sensors = simulator.profile().sensors.len();
+        if realtime {
+            let until =
+                hours.map(|h| clock::now() + chrono::Duration::seconds((h * 3600.0) as i64));
+            println!(
+                "📡 Simulating {} sensor(s) every {}s (Ctrl+C to stop)",
+                sensors,
+                interval.num_seconds()
+            );
+            loop {
+                let now = clock::now();
+                if until.is_some_and(|u| now >= u) {
+                    break;
+                }
+                feed(simulator.sample(now))?;
+                std::thread::sleep(interval.to_std()?);
+            }
+        } else {
+            let end = clock::now();
+            let mut at = end - chrono::Duration::seconds((hours.unwrap_or(24.0) * 3600.0) as i64);
+            while at <= end {
+                feed(simulator.sample(at))?;
+                at += interval;
+            }
+        }
+        println!(
+            "✅ Recorded {} simulated reading(s) from {} sensor(s), {} with injected faults",
+            recorded, sensors, faults
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "modbus")]
+impl SensorCommand {
+    fn run_modbus(base: &Path, config: Option<&Path>, once: bool) -> Result<(), Box<dyn Error>> {
+        use crate::hardware::modbus::{poll_once, ModbusConfig, ModbusPoller, MODBUS_CONFIG};
+        use std::time::Duration;
+
+        let config = match config {
+            Some(path) => ModbusConfig::load(path)?,
+            None => ModbusConfig::load_at(base)?,
+        };
+        if config.point_count() == 0 {
+            return Err(format!("No Modbus points mapped in {}", MODBUS_CONFIG).into());
+        }
+        let mut poller = ModbusPoller::new(&config);
+        loop {
+            let report = poll_once(base, &config, &mut poller)?;
+            println!(
+                "📡 Recorded {} of {} Modbus point(s)",
+                report.recorded,
+                config.point_count()
+            );
+            for failure in &report.failures {
+                println!("  ⚠️  {}", failure);
+            }
+            if once {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_secs(config.interval_secs));
+        }
+    }
+}
+
+impl Command for SensorCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+
+        match &self.action {
+            SensorAction::Record {
+                sensor_id,
+                sensor_type,
+                value,
+            } => {
+                let seen = record_sensor_reading(&base, sensor_id, sensor_type.as_deref(), *value)?;
+                println!(
+                    "📡 {} recorded ({} reading(s))",
+                    seen.sensor_id, seen.readings
+                );
+            }
+            SensorAction::Unmapped => {
+                let building = Self::load(&base)?;
+                let unmapped = unmapped_sensors(&base, &building)?;
+                if unmapped.is_empty() {
+                    println!("✅ No unmapped sensors");
+                    return Ok(());
+                }
+                println!("📡 {} unmapped sensor(s)", unmapped.len());
+                for s in &unmapped {
+                    println!(
+                        "  {}{}  last seen {}",
+                        s.sensor_id,
+                        s.sensor_type
+                            .as_ref()
+                            .map(|t| format!(" ({})", t))
+                            .unwrap_or_default(),
+                        s.last_seen.format("%Y-%m-%d %H:%M")
+                    );
+                }
+                println!("💡 Run `arx sensor map` to bind them");
+            }
+            SensorAction::Map { commit } => {
+                #[cfg(feature = "tui")]
+                {
+                    Self::run_editor(&base, *commit)?;
+                }
+                #[cfg(not(feature = "tui"))]
+                {
+                    let _ = commit;
+                    println!("⚠️  Sensor mapping editor requires --features tui");
+                }
+            }
+            SensorAction::History {
+                sensor_id,
+                since,
+                until,
+                bucket_minutes,
+                format,
+            } => {
+                let from = parse_time("since", since.as_deref())?;
+                let to = parse_time("until", until.as_deref())?;
+                let samples = sensor_history(&base, sensor_id, from, to)?;
+                match bucket_minutes {
+                    Some(minutes) => {
+                        let buckets =
+                            downsample(&samples, chrono::Duration::minutes(i64::from(*minutes)));
+                        match format.as_str() {
+                            "json" => println!("{}", serde_json::to_string_pretty(&buckets)?),
+                            "csv" => {
+                                println!("start,count,min,max,mean,last");
+                                for b in &buckets {
+                                    println!(
+                                        "{},{},{},{},{},{}",
+                                        b.start.to_rfc3339(),
+                                        b.count,
+                                        b.min,
+                                        b.max,
+                                        b.mean,
+                                        b.last
+                                    );
+                                }
+                            }
+                            _ => {
+                                println!(
+                                    "📈 {}: {} reading(s) in {} bucket(s) of {} min",
+                                    sensor_id,
+                                    samples.len(),
+                                    buckets.len(),
+                                    minutes
+                                );
+                                for b in &buckets {
+                                    println!(
+                                        "  {}  n={:<4} min {:<8.2} mean {:<8.2} max {:.2}",
+                                        b.start.format("%Y-%m-%d %H:%M"),
+                                        b.count,
+                                        b.min,
+                                        b.mean,
+                                        b.max
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    None => match format.as_str() {
+                        "json" => println!("{}", serde_json::to_string_pretty(&samples)?),
+                        "csv" => {
+                            println!("at,value");
+                            for s in &samples {
+                                println!("{},{}", s.at.to_rfc3339(), s.value);
+                            }
+                        }
+                        _ => {
+                            println!("📈 {}: {} reading(s)", sensor_id, samples.len());
+                            for s in &samples {
+                                println!("  {}  {}", s.at.format("%Y-%m-%d %H:%M:%S"), s.value);
+                            }
+                        }
+                    },
+                }
+            }
+            SensorAction::Simulate {
+                profile,
+                hours,
+                realtime,
+            } => Self::run_simulation(&base, profile, *hours, *realtime)?,
+            #[cfg(feature = "modbus")]
+            SensorAction::Modbus { config, once } => {
+                Self::run_modbus(&base, config.as_deref(), *once)?
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "sensor"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let SensorAction::Simulate {
+            hours: Some(hours), ..
+        } = &self.action
+        {
+            if !hours.is_finite() || *hours <= 0.0 {
+                return Err("--hours must be positive".into());
+            }
+        }
+        if let SensorAction::History {
+            bucket_minutes,
+            format,
+            ..
+        } = &self.action
+        {
+            if *bucket_minutes == Some(0) {
+                return Err("--bucket-minutes must be at least 1".into());
+            }
+            if !matches!(format.as_str(), "table" | "csv" | "json") {
+                return Err(
+                    format!("Unknown format '{}' (expected table, csv, json)", format).into(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+//! System command: connect equipment into systems (feeds, powers, controls,
+//! serves) and trace upstream / downstream impact.
+
+use super::Command;
+use crate::core::systems::{connect, disconnect, ImpactNode, Relation, SystemGraph};
+use crate::ingest::persist_building_at;
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct SystemCommand {
+    pub action: SystemAction,
+    /// Show the edge change without saving
+    pub dry_run: bool,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum SystemAction {
+    /// Add the edge `from --relation--> to`.
+    Connect {
+        from: String,
+        relation: String,
+        to: String,
+        commit: bool,
+    },
+    /// Remove the edges from `from` to `to`.
+    Disconnect {
+        from: String,
+        to: String,
+        commit: bool,
+    },
+    /// Equipment affected by (or, with `upstream`, feeding) one item.
+    Impact { equipment: String, upstream: bool },
+    /// Print every edge.
+    List,
+    /// Interactive topology view (requires `tui`).
+    View,
+}
+
+impl SystemCommand {
+    fn load(base: &Path) -> Result<crate::core::Building, Box<dyn Error>> {
+        Ok(load_building_at(base).map_err(|e| {
+            format!(
+                "Failed to load {} under {}: {}",
+                BUILDING_YAML,
+                base.display(),
+                e
+            )
+        })?)
+    }
+
+    fn print_tree(nodes: &[ImpactNode], arrow: &str) {
+        for node in nodes {
+            println!(
+                "  {}{} {} {}",
+                "  ".repeat(node.depth - 1),
+                arrow,
+                node.relation,
+                node.name
+            );
+        }
+    }
+
+    #[cfg(feature = "tui")]
+    fn run_view(base: &Path) -> Result<(), Box<dyn Error>> {
+        use crate::tui::topology::{TopologyAction, TopologyView};
+        use crate::tui::TerminalManager;
+        use crossterm::event::{self, Event};
+        use std::time::Duration;
+
+        let mut view = TopologyView::new(Self::load(base)?);
+        if view.node_count() == 0 {
+            println!("No connected equipment. Add edges with `arx system connect`");
+            return Ok(());
+        }
+        let mut terminal_manager = TerminalManager::new()?;
+        loop {
+            terminal_manager.terminal().draw(|frame| {
+                view.render(frame, frame.size());
+            })?;
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if view.handle_key(key) == TopologyAction::Exit {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Command for SystemCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+
+        match &self.action {
+            SystemAction::Connect {
+                from,
+                relation,
+                to,
+                commit,
+            } => {
+                let relation: Relation = relation.parse()?;
+                let mut building = Self::load(&base)?;
+                let (from, to) = connect(&mut building, from, relation, to)?;
+                if self.dry_run {
+                    println!("🔍 Dry run: would record {} {} {}", from, relation, to);
+                    return Ok(());
+                }
+                let message = format!("Connect {} {} {}", from, relation, to);
+                persist_building_at(&base, building, *commit, Some(&message))?;
+                println!("🔗 {} {} {}", from, relation, to);
+            }
+            SystemAction::Disconnect { from, to, commit } => {
+                let mut building = Self::load(&base)?;
+                let removed = disconnect(&mut building, from, to)?;
+                if removed == 0 {
+                    println!("No edge from {} to {}", from, to);
+                    return Ok(());
+                }
+                if self.dry_run {
+                    println!(
+                        "🔍 Dry run: would remove {} edge(s) from {} to {}",
+                        removed, from, to
+                    );
+                    return Ok(());
+                }
+                let message = format!("Disconnect {} from {}", to, from);
+                persist_building_at(&base, building, *commit, Some(&message))?;
+                println!("✂️  Removed {} edge(s) from {} to {}", removed, from, to);
+            }
+            SystemAction::Impact {
+                equipment,
+                upstream,
+            } => {
+                let building = Self::load(&base)?;
+                let eq = building
+                    .find_equipment(equipment)
+                    .ok_or_else(|| format!("Equipment '{}' not found", equipment))?;
+                let graph = SystemGraph::new(&building);
+                if *upstream {
+                    let nodes = graph.upstream(&eq.id);
+                    println!("⬆️  {} depends on {} item(s)", eq.name, nodes.len());
+                    Self::print_tree(&nodes, "←");
+                } else {
+                    let nodes = graph.downstream(&eq.id);
+                    println!("⬇️  {} affects {} item(s)", eq.name, nodes.len());
+                    Self::print_tree(&nodes, "→");
+                }
+            }
+            SystemAction::List => {
+                let building = Self::load(&base)?;
+                let graph = SystemGraph::new(&building);
+                let name = |id: &str| graph.equipment(id).map(|eq| eq.name.clone());
+                for (from, relation, to) in graph.edges() {
+                    if let (Some(from), Some(to)) = (name(from), name(to)) {
+                        println!("  {} {} {}", from, relation, to);
+                    }
+                }
+                println!("{} edge(s)", graph.edge_count());
+            }
+            SystemAction::View => {
+                #[cfg(feature = "tui")]
+                Self::run_view(&base)?;
+                #[cfg(not(feature = "tui"))]
+                println!("⚠️  Topology view requires --features tui");
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "system"
+    }
+}
@@ -0,0 +1,64 @@
+//! Template command: standard room and equipment types.
+
+use super::Command;
+use crate::core::templates::{find_template, load_templates, TemplateKind, TEMPLATES_DIR};
+use std::error::Error;
+use std::path::Path;
+
+pub struct TemplateCommand {
+    pub action: TemplateAction,
+}
+
+pub enum TemplateAction {
+    List { format: String },
+    Show { name: String },
+}
+
+impl Command for TemplateCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = Path::new(".");
+        match &self.action {
+            TemplateAction::List { format } => {
+                let templates = load_templates(base)?;
+                if format == "json" {
+                    let summaries: Vec<serde_json::Value> = templates
+                        .iter()
+                        .map(|t| {
+                            serde_json::json!({
+                                "name": t.name,
+                                "kind": t.kind,
+                                "description": t.description,
+                                "type": t.equipment_type.as_ref().or(t.room_type.as_ref()),
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&summaries)?);
+                    return Ok(());
+                }
+                for t in &templates {
+                    let (kind, type_name) = match t.kind {
+                        TemplateKind::Equipment => ("equipment", t.equipment_type.as_deref()),
+                        TemplateKind::Room => ("room", t.room_type.as_deref()),
+                    };
+                    println!(
+                        "{:<20} {:<9} {:<12} {}",
+                        t.name,
+                        kind,
+                        type_name.unwrap_or(""),
+                        t.description.as_deref().unwrap_or("")
+                    );
+                }
+                println!("(project templates: {}/<name>.yaml)", TEMPLATES_DIR);
+            }
+            TemplateAction::Show { name } => {
+                let (template, _) = find_template(base, name)?;
+                print!("{}", serde_yaml::to_string(&template)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "template"
+    }
+}
@@ -0,0 +1,95 @@
+//! Verify command: check commits against the signed-commit policy.
+
+use super::Command;
+use crate::git::signing::{compliance_report, unsigned_staged_changes, SigningPolicy};
+use git2::Repository;
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Check that commits touching `git.signed_paths` are GPG-signed.
+pub struct VerifyCommand {
+    /// Every commit reachable from HEAD, not just HEAD
+    pub all: bool,
+    /// Check the staged changes instead (the pre-commit hook)
+    pub staged: bool,
+    /// Output format: text, json
+    pub format: String,
+    /// Project root (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+impl VerifyCommand {
+    /// Refuse a `git commit` that would record protected paths unsigned.
+    fn verify_staged(
+        &self,
+        repo: &Repository,
+        policy: &SigningPolicy,
+    ) -> Result<(), Box<dyn Error>> {
+        let unsigned = unsigned_staged_changes(repo, policy)?;
+        if unsigned.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "Commits changing {} must be GPG-signed (git.signed_paths). Run `git config commit.gpgsign true` (and set user.signingkey), then commit again.",
+            unsigned.join(", ")
+        )
+        .into())
+    }
+}
+
+impl Command for VerifyCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let repo = Repository::discover(&base)
+            .map_err(|_| format!("{} is not in a Git repository", base.display()))?;
+        let root = repo.workdir().map(PathBuf::from).unwrap_or(base);
+        let policy = SigningPolicy::load(&root)?;
+        if self.staged {
+            return self.verify_staged(&repo, &policy);
+        }
+
+        let report = compliance_report(&repo, &policy, self.all)?;
+        if self.format == "json" {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            if policy.protected_paths.is_empty() {
+                println!(
+                    "ℹ️  No signed paths configured (git.signed_paths); every commit complies"
+                );
+            } else {
+                println!("🔏 Signed paths: {}", policy.protected_paths.join(", "));
+            }
+            for commit in &report.commits {
+                let mark = if commit.compliant { "✅" } else { "❌" };
+                let signer = commit
+                    .signer
+                    .as_deref()
+                    .map(|s| format!(" by {}", s))
+                    .unwrap_or_default();
+                println!(
+                    "{} {} {} ({}{})",
+                    mark,
+                    &commit.commit[..commit.commit.len().min(12)],
+                    commit.summary,
+                    commit.signature.as_str(),
+                    signer
+                );
+                if !commit.compliant {
+                    println!("   changes {}", commit.protected_changes.join(", "));
+                }
+            }
+            println!(
+                "{} commit(s) checked, {} violation(s)",
+                report.commits_checked, report.violations
+            );
+        }
+        if !report.compliant {
+            return Err(format!(
+                "{} commit(s) change signed paths without a valid signature",
+                report.violations
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
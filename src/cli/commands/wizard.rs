@@ -0,0 +1,373 @@
+//! Wizard command: build a room or equipment command step by step.
+//!
+//! The wizard walks the command catalog ([`crate::cli::catalog`]): pick a
+//! `room` or `equipment` command by fuzzy search, then answer one prompt per
+//! argument. Answers are checked against the argument's type and choices as
+//! they are typed, and existing floors, wings, rooms, and equipment are
+//! offered where an argument names one. The finished command line is checked
+//! by the CLI parser, shown, and run (or dry-run) once confirmed.
+
+use super::Command;
+use crate::cli::catalog::{
+    parse_command, run_command, search_catalog, ArgType, CatalogArg, CatalogEntry, CommandOutput,
+    CompletionSource,
+};
+use crate::core::Building;
+use crate::persistence::load_building_at;
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Command groups the wizard builds commands for.
+pub const WIZARD_GROUPS: &[&str] = &["room", "equipment"];
+
+/// Commands listed per search.
+const MAX_LISTED: usize = 9;
+
+/// Existing values offered per argument.
+const MAX_HINTS: usize = 9;
+
+/// Interactive builder for room and equipment commands.
+pub struct WizardCommand {
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+/// What to do with a finished command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    Run,
+    DryRun,
+    Cancel,
+}
+
+/// `arx` arguments (without `arx`) and what to do with them.
+pub type BuiltCommand = (Vec<String>, Confirmation);
+
+type Io<'a> = (&'a mut dyn BufRead, &'a mut dyn Write);
+
+/// Prompt and read one trimmed line; `None` at end of input.
+fn ask(io: &mut Io, prompt: &str) -> std::io::Result<Option<String>> {
+    write!(io.1, "{}", prompt)?;
+    io.1.flush()?;
+    let mut line = String::new();
+    if io.0.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Names of existing entities an argument can take.
+fn completion_values(building: &Building, source: CompletionSource) -> Vec<String> {
+    let rooms = || {
+        building
+            .floors
+            .iter()
+            .flat_map(|f| f.wings.iter().flat_map(|w| &w.rooms))
+            .map(|r| r.name.clone())
+    };
+    let equipment = || {
+        building
+            .get_all_equipment()
+            .into_iter()
+            .map(|e| e.name.clone())
+    };
+    let mut values: Vec<String> = match source {
+        CompletionSource::Building => vec![building.name.clone()],
+        CompletionSource::Floor => building
+            .floors
+            .iter()
+            .map(|f| f.level.to_string())
+            .collect(),
+        CompletionSource::Wing => building
+            .floors
+            .iter()
+            .flat_map(|f| f.wings.iter().map(|w| w.name.clone()))
+            .collect(),
+        CompletionSource::Room => rooms().collect(),
+        CompletionSource::Equipment => equipment().collect(),
+        CompletionSource::Entity => rooms().chain(equipment()).collect(),
+        _ => Vec::new(),
+    };
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// Pick a room or equipment command; `None` when input ends.
+fn choose_command(io: &mut Io) -> std::io::Result<Option<CatalogEntry>> {
+    loop {
+        let Some(query) = ask(io, "🔎 Search room / equipment commands: ")? else {
+            return Ok(None);
+        };
+        let listed: Vec<CatalogEntry> = search_catalog(&query, usize::MAX)
+            .into_iter()
+            .map(|m| m.entry)
+            .filter(|e| WIZARD_GROUPS.contains(&e.command[0].as_str()))
+            .take(MAX_LISTED)
+            .collect();
+        if listed.is_empty() {
+            writeln!(io.1, "   No matching command")?;
+            continue;
+        }
+        for (i, entry) in listed.iter().enumerate() {
+            writeln!(
+                io.1,
+                "   {}) arx {} — {}",
+                i + 1,
+                entry.command.join(" "),
+                entry.about
+            )?;
+        }
+        let Some(pick) = ask(io, "   Pick a number (Enter to search again): ")? else {
+            return Ok(None);
+        };
+        if let Some(entry) = pick
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| listed.get(n.wrapping_sub(1)))
+        {
+            return Ok(Some(entry.clone()));
+        }
+    }
+}
+
+/// `answer` checked against `arg`; the value to pass, or why it is rejected.
+fn check_answer(arg: &CatalogArg, answer: &str, hints: &[String]) -> Result<String, String> {
+    let numbered = arg.value_type == ArgType::String && !hints.is_empty();
+    if let Some(hint) = numbered
+        .then(|| answer.parse::<usize>().ok())
+        .flatten()
+        .and_then(|n| hints.get(n.wrapping_sub(1)))
+    {
+        return Ok(hint.clone());
+    }
+    match arg.value_type {
+        ArgType::Integer if answer.parse::<i64>().is_err() => {
+            Err(format!("'{}' is not a whole number", answer))
+        }
+        ArgType::Number if answer.parse::<f64>().is_err() => {
+            Err(format!("'{}' is not a number", answer))
+        }
+        ArgType::Choice => arg
+            .choices
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(answer))
+            .cloned()
+            .ok_or_else(|| format!("Use one of: {}", arg.choices.join(", "))),
+        _ => Ok(answer.to_string()),
+    }
+}
+
+/// Ask for one argument; `Some(None)` leaves it out, `None` when input ends.
+fn prompt_arg(
+    io: &mut Io,
+    arg: &CatalogArg,
+    building: Option<&Building>,
+) -> std::io::Result<Option<Option<String>>> {
+    let label = arg.long.as_deref().unwrap_or(&arg.name);
+    if arg.value_type == ArgType::Flag {
+        let Some(answer) = ask(io, &format!("   --{}: {} [y/N] ", label, arg.help))? else {
+            return Ok(None);
+        };
+        let yes = matches!(answer.to_lowercase().as_str(), "y" | "yes");
+        return Ok(Some(yes.then(|| "true".to_string())));
+    }
+
+    let hints: Vec<String> = match (building, arg.completion) {
+        (Some(b), Some(source)) => completion_values(b, source)
+            .into_iter()
+            .take(MAX_HINTS)
+            .collect(),
+        _ => Vec::new(),
+    };
+    let required = arg.required && arg.default.is_none();
+    writeln!(
+        io.1,
+        "   {}{}: {}",
+        label,
+        if required { " (required)" } else { "" },
+        arg.help
+    )?;
+    if !arg.choices.is_empty() {
+        writeln!(io.1, "     choices: {}", arg.choices.join(", "))?;
+    }
+    if !hints.is_empty() {
+        let listed: Vec<String> = if arg.value_type == ArgType::String {
+            hints
+                .iter()
+                .enumerate()
+                .map(|(i, h)| format!("{}) {}", i + 1, h))
+                .collect()
+        } else {
+            hints.clone()
+        };
+        writeln!(io.1, "     existing: {}", listed.join("  "))?;
+    }
+    let prompt = match &arg.default {
+        Some(default) => format!("   > [{}] ", default),
+        None => "   > ".to_string(),
+    };
+    loop {
+        let Some(answer) = ask(io, &prompt)? else {
+            return Ok(None);
+        };
+        if answer.is_empty() {
+            if required {
+                writeln!(io.1, "     ⚠️  {} is required", label)?;
+                continue;
+            }
+            return Ok(Some(None));
+        }
+        match check_answer(arg, &answer, &hints) {
+            Ok(value) => return Ok(Some(Some(value))),
+            Err(message) => writeln!(io.1, "     ⚠️  {}", message)?,
+        }
+    }
+}
+
+/// `arg` as it would be typed in a shell.
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=,:@".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Walk the user through one command; `None` when input ended first.
+pub fn build_command(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    building: Option<&Building>,
+) -> Result<Option<BuiltCommand>, Box<dyn Error>> {
+    let mut io: Io = (input, output);
+    loop {
+        let Some(entry) = choose_command(&mut io)? else {
+            return Ok(None);
+        };
+        writeln!(io.1, "🧭 arx {}: {}", entry.command.join(" "), entry.about)?;
+        let mut args = entry.command.clone();
+        // The wizard already runs in the project root
+        for arg in entry.args.iter().filter(|a| a.name != "path") {
+            let Some(value) = prompt_arg(&mut io, arg, building)? else {
+                return Ok(None);
+            };
+            match (value, &arg.long) {
+                (None, _) => {}
+                (Some(_), Some(long)) if arg.value_type == ArgType::Flag => {
+                    args.push(format!("--{}", long))
+                }
+                (Some(value), Some(long)) => args.extend([format!("--{}", long), value]),
+                (Some(value), None) => args.push(value),
+            }
+        }
+
+        if let Err(usage) = parse_command(&args) {
+            writeln!(io.1, "❌ {}", usage.trim_end())?;
+            writeln!(io.1, "   Starting over")?;
+            continue;
+        }
+        let line: Vec<String> = args.iter().map(|a| shell_quote(a)).collect();
+        writeln!(io.1, "📋 arx {}", line.join(" "))?;
+        let Some(answer) = ask(&mut io, "   [r]un, [d]ry run, or [c]ancel? [r] ")? else {
+            return Ok(None);
+        };
+        let confirmation = match answer.to_lowercase().as_str() {
+            "" | "r" | "run" => Confirmation::Run,
+            "d" | "dry" | "dry run" => Confirmation::DryRun,
+            _ => Confirmation::Cancel,
+        };
+        return Ok(Some((args, confirmation)));
+    }
+}
+
+impl Command for WizardCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let building = load_building_at(&base).ok();
+        if building.is_none() {
+            println!(
+                "⚠️  No building.yaml under {}; no names to suggest",
+                base.display()
+            );
+        }
+
+        let stdin = std::io::stdin();
+        let built = build_command(&mut stdin.lock(), &mut std::io::stdout(), building.as_ref())?;
+        let Some((mut args, confirmation)) = built else {
+            return Ok(());
+        };
+        match confirmation {
+            Confirmation::Cancel => {
+                println!("✋ Cancelled; nothing was run");
+                return Ok(());
+            }
+            Confirmation::DryRun => args.insert(0, "--dry-run".to_string()),
+            Confirmation::Run => {}
+        }
+        let code = run_command(&args, &base, |line| match line {
+            CommandOutput::Stdout(line) => println!("{}", line),
+            CommandOutput::Stderr(line) => eprintln!("{}", line),
+        })?;
+        if code != 0 {
+            return Err(format!("arx {} exited with status {}", args.join(" "), code).into());
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "wizard"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, Wing};
+
+    #[test]
+    fn builds_a_validated_room_command_from_answers() {
+        let mut floor = Floor::new("Ground".into(), 2);
+        floor.wings.push(Wing::new("East".into()));
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        // building: pick the suggestion; floor: "two" is rejected, then 2; no template
+        let answers = "room create\n1\n1\ntwo\n2\n1\n\nLab\nlaboratory\n\n\n1,2,0\ny\n\n";
+        let mut input = answers.as_bytes();
+        let mut output = Vec::new();
+        let (args, confirmation) = build_command(&mut input, &mut output, Some(&building))
+            .unwrap()
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("'two' is not a whole number"));
+        assert!(output.contains("name (required)"));
+        assert_eq!(confirmation, Confirmation::Run);
+        assert_eq!(
+            args,
+            [
+                "room",
+                "create",
+                "--building",
+                "HQ",
+                "--floor",
+                "2",
+                "--wing",
+                "East",
+                "--name",
+                "Lab",
+                "--room-type",
+                "laboratory",
+                "--position",
+                "1,2,0",
+                "--commit"
+            ]
+        );
+    }
+}
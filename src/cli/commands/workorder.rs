@@ -0,0 +1,316 @@
+//! Work order command: create / assign / comment / close work orders stored
+//! under `.arx/workorders/`, one Git commit per change.
+
+use super::Command;
+use crate::core::domain::economy::Money;
+use crate::core::domain::issue::IssuePriority;
+use crate::core::domain::workorder::{WorkOrder, WorkOrderDraft};
+use crate::core::inventory::PartUsage;
+use crate::git::manager::GitConfigManager;
+use crate::persistence::inventory::{update_inventory, INVENTORY_FILE};
+use crate::persistence::workorders::{
+    commit_work_order, commit_work_order_with, load_work_order, load_work_orders, save_work_order,
+    submit_work_order,
+};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct WorkOrderCommand {
+    pub action: WorkOrderAction,
+    /// Project root containing building.yaml (default: cwd)
+    pub path: Option<PathBuf>,
+}
+
+pub enum WorkOrderAction {
+    Create {
+        title: String,
+        description: Option<String>,
+        room: Option<String>,
+        equipment: Option<String>,
+        priority: String,
+        assignee: Option<String>,
+        issue: Option<String>,
+    },
+    /// Print work orders (open only unless `all`).
+    List {
+        all: bool,
+        assignee: Option<String>,
+    },
+    Show {
+        id: String,
+    },
+    Assign {
+        id: String,
+        assignee: String,
+    },
+    Comment {
+        id: String,
+        text: String,
+        author: Option<String>,
+    },
+    Close {
+        id: String,
+        resolution: Option<String>,
+        /// Spare parts used, `PART` or `PART:QTY`; taken out of inventory
+        parts: Vec<String>,
+        /// Labor cost, e.g. `240` or `240 EUR`
+        labor_cost: Option<String>,
+    },
+}
+
+fn parse_priority(value: &str) -> Option<IssuePriority> {
+    match value {
+        "low" => Some(IssuePriority::Low),
+        "normal" => Some(IssuePriority::Normal),
+        "high" => Some(IssuePriority::High),
+        "urgent" => Some(IssuePriority::Urgent),
+        _ => None,
+    }
+}
+
+/// Comment author when none is given: the configured Git author.
+fn default_author() -> String {
+    GitConfigManager::load_from_arx_config_or_env().author_name
+}
+
+/// Save `order` and record the change as its own commit.
+fn record(base: &Path, order: &WorkOrder, change: &str) -> Result<(), Box<dyn Error>> {
+    save_work_order(base, order)?;
+    commit_work_order(
+        base,
+        order,
+        &format!("Work order {}: {} — {}", order.id, change, order.title),
+    )?;
+    Ok(())
+}
+
+/// ` — cost 312.80 USD` when the order has costs recorded.
+fn cost_note(order: &WorkOrder) -> Result<String, String> {
+    Ok(order
+        .total_cost()?
+        .map(|total| format!(" — cost {}", total))
+        .unwrap_or_default())
+}
+
+fn print_details(order: &WorkOrder) {
+    println!("🔧 {} — {}", order.id, order.title);
+    println!("   Status:   {}", order.status);
+    println!("   Priority: {}", order.priority);
+    if let Some(room) = &order.room_name {
+        let floor = order
+            .floor_level
+            .map(|l| format!(" (floor {})", l))
+            .unwrap_or_default();
+        println!("   Room:     {}{}", room, floor);
+    }
+    if let Some(equipment) = &order.equipment_name {
+        println!("   Equipment: {}", equipment);
+    }
+    if let Some(assignee) = &order.assignee {
+        println!("   Assignee: {}", assignee);
+    }
+    if let Some(issue) = &order.issue_id {
+        println!("   Issue:    {}", issue);
+    }
+    println!("   Created:  {}", order.created_at.to_rfc3339());
+    if let Some(description) = &order.description {
+        println!("   {}", description);
+    }
+    for comment in &order.comments {
+        println!(
+            "   💬 {} {}: {}",
+            comment.at.format("%Y-%m-%d %H:%M"),
+            comment.author,
+            comment.text
+        );
+    }
+    if !order.parts_used.is_empty() {
+        let parts: Vec<String> = order.parts_used.iter().map(|p| p.to_string()).collect();
+        println!("   Parts:    {}", parts.join(", "));
+    }
+    if let Ok(Some(total)) = order.total_cost() {
+        let part =
+            |label: &str, cost: &Option<Money>| cost.as_ref().map(|c| format!(", {} {}", label, c));
+        println!(
+            "   Cost:     {}{}{}",
+            total,
+            part("labor", &order.labor_cost).unwrap_or_default(),
+            part("parts", &order.parts_cost).unwrap_or_default()
+        );
+    }
+    if let Some(closed_at) = order.closed_at {
+        println!(
+            "   Closed:   {} {}",
+            closed_at.to_rfc3339(),
+            order.resolution.as_deref().unwrap_or("")
+        );
+    }
+}
+
+impl Command for WorkOrderCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        self.validate()?;
+        let base = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
+
+        match &self.action {
+            WorkOrderAction::Create {
+                title,
+                description,
+                room,
+                equipment,
+                priority,
+                assignee,
+                issue,
+            } => {
+                let building = load_building_at(&base).map_err(|e| {
+                    format!(
+                        "Failed to load {} under {}: {}",
+                        BUILDING_YAML,
+                        base.display(),
+                        e
+                    )
+                })?;
+                let draft = WorkOrderDraft {
+                    title: title.clone(),
+                    description: description.clone(),
+                    room: room.clone(),
+                    equipment: equipment.clone(),
+                    priority: parse_priority(priority).unwrap_or_default(),
+                    assignee: assignee.clone(),
+                    created_by: Some(default_author()),
+                    issue_id: issue.clone(),
+                };
+                let order = submit_work_order(&base, &building, &draft)?;
+                commit_work_order(
+                    &base,
+                    &order,
+                    &format!("Work order {}: created — {}", order.id, order.title),
+                )?;
+                println!("✅ Created {}", order.summary());
+            }
+            WorkOrderAction::List { all, assignee } => {
+                let orders: Vec<WorkOrder> = load_work_orders(&base)?
+                    .into_iter()
+                    .filter(|o| *all || o.status.is_open())
+                    .filter(|o| assignee.is_none() || o.assignee.as_deref() == assignee.as_deref())
+                    .collect();
+                if orders.is_empty() {
+                    println!("✅ No {}work orders", if *all { "" } else { "open " });
+                    return Ok(());
+                }
+                println!("📋 {} work order(s)", orders.len());
+                for order in &orders {
+                    println!("  {} ({})", order.summary(), order.status);
+                }
+            }
+            WorkOrderAction::Show { id } => {
+                print_details(&load_work_order(&base, id)?);
+            }
+            WorkOrderAction::Assign { id, assignee } => {
+                let mut order = load_work_order(&base, id)?;
+                order.assign(assignee)?;
+                record(&base, &order, &format!("assigned to {}", assignee.trim()))?;
+                println!("✅ Assigned {} to {}", order.id, assignee.trim());
+            }
+            WorkOrderAction::Comment { id, text, author } => {
+                let mut order = load_work_order(&base, id)?;
+                let author = author.clone().unwrap_or_else(default_author);
+                order.comment(&author, text)?;
+                record(&base, &order, &format!("comment by {}", author))?;
+                println!("✅ Commented on {}", order.id);
+            }
+            WorkOrderAction::Close {
+                id,
+                resolution,
+                parts,
+                labor_cost,
+            } => {
+                let mut order = load_work_order(&base, id)?;
+                order.close(resolution.as_deref())?;
+                order.labor_cost = labor_cost.as_deref().map(str::parse::<Money>).transpose()?;
+                if parts.is_empty() {
+                    record(&base, &order, "closed")?;
+                    println!("✅ Closed {}{}", order.id, cost_note(&order)?);
+                    return Ok(());
+                }
+
+                let used = parts
+                    .iter()
+                    .map(|p| p.parse::<PartUsage>())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let equipment_type = order.equipment_id.as_deref().and_then(|id| {
+                    load_building_at(&base)
+                        .ok()?
+                        .find_equipment(id)
+                        .map(|eq| eq.equipment_type.clone())
+                });
+                let (parts_cost, low) = update_inventory(&base, |inventory| {
+                    inventory.consume(&used)?;
+                    let mut low = Vec::new();
+                    for usage in &used {
+                        let Some(part) = inventory.part(&usage.part_number) else {
+                            continue;
+                        };
+                        if let Some(t) = equipment_type.as_ref().filter(|t| !part.fits(t)) {
+                            println!("⚠️  {} is not listed for {} equipment", part.part_number, t);
+                        }
+                        if part.is_low() {
+                            low.push(format!(
+                                "{} ({} left, min {})",
+                                part.part_number, part.quantity, part.min_stock
+                            ));
+                        }
+                    }
+                    Ok((inventory.cost_of(&used)?, low))
+                })?;
+                order.parts_used = used;
+                order.parts_cost = parts_cost;
+                save_work_order(&base, &order)?;
+                commit_work_order_with(
+                    &base,
+                    &order,
+                    &[INVENTORY_FILE],
+                    &format!("Work order {}: closed — {}", order.id, order.title),
+                )?;
+                let parts: Vec<String> = order.parts_used.iter().map(|p| p.to_string()).collect();
+                println!(
+                    "✅ Closed {} (used {}){}",
+                    order.id,
+                    parts.join(", "),
+                    cost_note(&order)?
+                );
+                for line in low {
+                    println!("📉 Low stock: {}", line);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "workorder"
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let WorkOrderAction::Create {
+            room,
+            equipment,
+            priority,
+            ..
+        } = &self.action
+        {
+            if room.is_none() && equipment.is_none() {
+                return Err("--room or --equipment is required".into());
+            }
+            if parse_priority(priority).is_none() {
+                return Err(format!(
+                    "Unknown priority '{}' (expected low, normal, high, urgent)",
+                    priority
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+//! Workspace command: the registry of building repositories.
+
+use super::Command;
+use crate::persistence::workspace::{BuildingHandle, WorkspaceRegistry};
+use serde::Serialize;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub struct WorkspaceCommand {
+    pub action: WorkspaceAction,
+    /// Show the change without writing the registry
+    pub dry_run: bool,
+}
+
+pub enum WorkspaceAction {
+    Add { path: PathBuf, name: Option<String> },
+    List { format: String },
+    Switch { name: String },
+    Remove { name: String },
+}
+
+/// One row of `arx workspace list`.
+#[derive(Serialize)]
+struct WorkspaceSummary<'a> {
+    name: &'a str,
+    root: &'a std::path::Path,
+    active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    building: Option<String>,
+    floors: usize,
+    rooms: usize,
+    equipment: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn summarize(handle: &BuildingHandle, active: bool) -> WorkspaceSummary<'_> {
+    let mut summary = WorkspaceSummary {
+        name: &handle.name,
+        root: &handle.root,
+        active,
+        building: None,
+        floors: 0,
+        rooms: 0,
+        equipment: 0,
+        error: None,
+    };
+    match handle.load_shared() {
+        Ok(building) => {
+            summary.building = Some(building.name.clone());
+            summary.floors = building.floors.len();
+            summary.rooms = building.get_all_rooms().len();
+            summary.equipment = building.get_all_equipment().len();
+        }
+        Err(e) => summary.error = Some(e.to_string()),
+    }
+    summary
+}
+
+impl WorkspaceCommand {
+    fn save(&self, registry: &WorkspaceRegistry) -> Result<(), Box<dyn Error>> {
+        let path = WorkspaceRegistry::default_path()?;
+        if self.dry_run {
+            crate::core::operations::dry_run::record_file_write(
+                &path.display().to_string(),
+                path.exists(),
+            );
+            return Ok(());
+        }
+        registry.save_to(&path)?;
+        Ok(())
+    }
+}
+
+impl Command for WorkspaceCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let mut registry = WorkspaceRegistry::load()?;
+        match &self.action {
+            WorkspaceAction::Add { path, name } => {
+                let handle = registry.add(path, name.as_deref())?.clone();
+                if registry.active.is_none() {
+                    registry.active = Some(handle.name.clone());
+                }
+                self.save(&registry)?;
+                println!("🏢 Added '{}' ({})", handle.name, handle.root.display());
+            }
+            WorkspaceAction::List { format } => {
+                let active = registry.active().map(|h| h.name.clone());
+                let summaries: Vec<WorkspaceSummary> = registry
+                    .buildings
+                    .iter()
+                    .map(|h| summarize(h, active.as_deref() == Some(h.name.as_str())))
+                    .collect();
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&summaries)?);
+                    return Ok(());
+                }
+                if summaries.is_empty() {
+                    println!(
+                        "No buildings in the workspace. Add one with `arx workspace add <path>`"
+                    );
+                    return Ok(());
+                }
+                for s in &summaries {
+                    let mark = if s.active { "*" } else { " " };
+                    match &s.error {
+                        Some(error) => println!(
+                            "{} {:<20} {}  ⚠️  {}",
+                            mark,
+                            s.name,
+                            s.root.display(),
+                            error
+                        ),
+                        None => println!(
+                            "{} {:<20} {}  {} floor(s), {} room(s), {} equipment",
+                            mark,
+                            s.name,
+                            s.root.display(),
+                            s.floors,
+                            s.rooms,
+                            s.equipment
+                        ),
+                    }
+                }
+            }
+            WorkspaceAction::Switch { name } => {
+                let handle = registry.switch(name)?.clone();
+                self.save(&registry)?;
+                println!(
+                    "🏢 Active building: '{}' ({})",
+                    handle.name,
+                    handle.root.display()
+                );
+            }
+            WorkspaceAction::Remove { name } => {
+                let handle = registry.remove(name)?;
+                self.save(&registry)?;
+                println!(
+                    "🗑️  Removed '{}' from the workspace ({} is unchanged)",
+                    handle.name,
+                    handle.root.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "workspace"
+    }
+}
@@ -4,10 +4,11 @@
 use clap::Parser;
 
 pub mod args;
+pub mod catalog;
 pub mod commands;
 pub mod spec;
 
-pub use spec::{AccessSubcommand, Commands, ImportSubcommand};
+pub use spec::{AccessSubcommand, AttachmentSubcommand, AuditSubcommand, BackupSubcommand, BookingSubcommand, BranchSubcommand, CommissionSubcommand, Commands, ConfigSubcommand, CostSubcommand, DatasetSubcommand, DocSubcommand, EnergySubcommand, FloorSubcommand, ImportSubcommand, InventorySubcommand, IssueSubcommand, JobsSubcommand, MaintenanceSubcommand, MigrateSubcommand, NotifySubcommand, RoleSubcommand, SensorSubcommand, SystemSubcommand, TemplateSubcommand, WorkOrderSubcommand, WorkspaceSubcommand};
 
 // Sub-command definitions (room / equipment / spatial clap trees)
 pub mod subcommands;
@@ -15,27 +16,232 @@ pub use subcommands::*;
 
 use commands::{
     access::AccessAction,
+    attachment::AttachmentAction,
+    audit::AuditAction,
+    backup::BackupAction,
+    booking::BookingAction,
+    commission::CommissionAction,
+    cost::CostAction,
+    dataset::DatasetAction,
+    doc::DocAction,
+    energy::EnergyAction,
+    floor::FloorAction,
+    inventory::InventoryAction,
+    issue::IssueAction,
+    jobs::JobsAction,
+    maintenance::MaintenanceAction,
+    role::RoleAction,
+    workorder::WorkOrderAction,
+    template::TemplateAction,
+    workspace::WorkspaceAction,
+    sensor::SensorAction,
+    system::SystemAction,
     data::{EquipmentCommand, RoomCommand, SpatialCommand},
-    git::{CommitCommand, DiffCommand, StageCommand, StatusCommand, UnstageCommand},
-    AccessCommand, Command, ContributeCommand, ExportCommand, ImportCommand, InitCommand,
-    MigrateCommand,
+    git::{BranchAction, BranchCommand, CommitCommand, DiffCommand, StageCommand, StatusCommand, UnstageCommand},
+    AccessCommand, ApprovalsCommand, ApproveCommand, AttachmentCommand, AuditCommand, BackupCommand, BookingCommand, CleanCommand, Command, CommissionCommand, CompareCommand, CompletenessCommand, CompletionsCommand, ConfigDoctorCommand, ContributeCommand, CostCommand, DatasetCommand, DocCommand, EgressCommand, EnergyCommand, FloorCommand, InventoryCommand, IssueCommand, ExportCommand, ImportCommand,
+    InitCommand, JobsCommand, MaintenanceCommand, MergeDriverCommand, MigrateCommand, NotifyTestCommand, ProvenanceCommand, RenameCommand, RoleCommand, RollbackCommand, SchemaMigrateCommand, SensorCommand, SystemCommand, TemplateCommand, VerifyCommand, WizardCommand, WorkOrderCommand, WorkspaceCommand,
 };
+use crate::persistence::jobs::{JobGuard, JOB_ID_ENV};
 
 #[derive(Parser)]
 #[command(name = "arx")]
 #[command(about = "ArxOS building compiler — Git for Buildings")]
 #[command(
-    long_about = "Local-first building compiler: IFC / LiDAR / text → building.yaml → Git → IFC export.\n\nDefault features: compiler spine + TUI (primary UI). Optional: --features agent | web | blockchain | full.\n\nL1 pilot loop: init → import → edit/review → validate → git → export --format ifc\n(see docs/l1-supported-workflow.md). Lab contribute/access are optional."
+    long_about = "Local-first building compiler: IFC / LiDAR / text → building.yaml → Git → IFC export.\n\nDefault features: compiler spine + TUI (primary UI). Optional: --features agent | web | blockchain | bacnet | modbus | mqtt | full.\n\nL1 pilot loop: init → import → edit/review → validate → git → export --format ifc\n(see docs/l1-supported-workflow.md). Lab contribute/access are optional."
 )]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 pub struct Cli {
+    /// Simulate the command: print the would-be change set (model, files, Git) without writing
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Run the command as a background job and return its id (`arx jobs status <id>`)
+    #[arg(long = "async", global = true, conflicts_with = "dry_run")]
+    pub run_async: bool,
+
+    /// Run in this workspace building (`arx workspace list`) instead of the current directory
+    #[arg(long = "in", value_name = "BUILDING", global = true)]
+    pub in_building: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Flags whose values never reach the audit log.
+const SECRET_FLAGS: &[&str] = &["--token", "--password", "--secret", "--api-key"];
+
+/// `floor.clone` for `arx floor clone …`: the subcommands clap matched.
+pub fn command_path(matches: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    names.join(".")
+}
+
+/// Append an invocation (`args` without the leading `arx`) to the audit log
+/// of the building it ran in; outside a building project nothing is written.
+pub fn audit_invocation(
+    action: &str,
+    args: &[String],
+    result: &Result<(), Box<dyn std::error::Error>>,
+) {
+    use crate::persistence::audit::{record_quietly, AuditEntry};
+    use std::path::Path;
+
+    let base = Path::new(".");
+    if !base.join(crate::persistence::BUILDING_YAML).exists() && !base.join(".arx").is_dir() {
+        return;
+    }
+    let mut path_words: Vec<&str> = action.split('.').rev().collect();
+    let mut redact_next = false;
+    let mut target = Vec::new();
+    for arg in args {
+        if redact_next {
+            target.push("***".to_string());
+            redact_next = false;
+        } else if path_words.last() == Some(&arg.as_str()) {
+            path_words.pop();
+        } else if let Some(flag) = SECRET_FLAGS.iter().find(|f| arg.starts_with(*f)) {
+            redact_next = arg == flag;
+            target.push(if redact_next { arg.clone() } else { format!("{}=***", flag) });
+        } else {
+            target.push(arg.clone());
+        }
+    }
+    let target = (!target.is_empty()).then(|| target.join(" "));
+    let entry = AuditEntry::for_result(format!("cli.{}", action), target, result);
+    record_quietly(base, &entry);
+}
+
 impl Cli {
+    /// Whether stdout is machine-readable output (a completion script, a
+    /// JSON compliance report) that must not get the closing status line.
+    pub fn raw_output(&self) -> bool {
+        match &self.command {
+            Commands::Completions { .. } => true,
+            Commands::Verify { format, .. } => format == "json",
+            Commands::Inventory {
+                subcommand: InventorySubcommand::LowStock { format, .. },
+            } => format == "json",
+            Commands::Cost {
+                subcommand: CostSubcommand::Report { format, .. },
+            } => format == "json",
+            Commands::Audit { subcommand } => match subcommand {
+                AuditSubcommand::Tail { format, .. } | AuditSubcommand::Search { format, .. } => {
+                    format == "json"
+                }
+            },
+            #[cfg(feature = "ask")]
+            Commands::Ask { format, .. } => format != "table",
+            Commands::Template { subcommand } => match subcommand {
+                TemplateSubcommand::List { format } => format == "json",
+                TemplateSubcommand::Show { .. } => true,
+            },
+            _ => false,
+        }
+    }
+
     pub fn execute(self) -> Result<(), Box<dyn std::error::Error>> {
-        match self.command {
+        self.enter_building()?;
+        if self.run_async {
+            let args: Vec<String> = std::env::args()
+                .skip(1)
+                .filter(|a| a != "--async")
+                .collect();
+            return commands::jobs::start_detached(&args);
+        }
+        // Started by `arx --async`: progress and the outcome go to its job record
+        let Ok(job_id) = std::env::var(JOB_ID_ENV) else {
+            return self.run();
+        };
+        let job = JobGuard::attach(std::path::Path::new("."), &job_id)?;
+        let result = self.run();
+        job.finish(result.as_ref().map(|_| None).map_err(|e| e.to_string()))?;
+        result
+    }
+
+    /// Move into the building the command works on: `--in <name>`, else the
+    /// active workspace building when the current directory is not a
+    /// building project.
+    fn enter_building(&self) -> Result<(), Box<dyn std::error::Error>> {
+        use crate::persistence::storage::STORAGE_CONFIG_FILE;
+        use crate::persistence::workspace::WorkspaceRegistry;
+        use crate::persistence::BUILDING_YAML;
+        use std::path::Path;
+
+        let handle = match &self.in_building {
+            Some(name) => WorkspaceRegistry::load()?.handle(name)?.clone(),
+            None => {
+                // Commands that create a project, or manage the registry, stay put
+                let creates_project = matches!(
+                    self.command,
+                    Commands::Init { .. }
+                        | Commands::Import { .. }
+                        | Commands::Workspace { .. }
+                        | Commands::Completions { .. }
+                );
+                if creates_project
+                    || Path::new(BUILDING_YAML).exists()
+                    || Path::new(STORAGE_CONFIG_FILE).exists()
+                {
+                    return Ok(());
+                }
+                let Some(handle) = WorkspaceRegistry::load()
+                    .ok()
+                    .and_then(|r| r.active().cloned())
+                else {
+                    return Ok(());
+                };
+                eprintln!(
+                    "🏢 Using workspace building '{}' ({})",
+                    handle.name,
+                    handle.root.display()
+                );
+                handle
+            }
+        };
+        std::env::set_current_dir(&handle.root).map_err(|e| {
+            format!(
+                "Cannot enter '{}' at {}: {}",
+                handle.name,
+                handle.root.display(),
+                e
+            )
+        })?;
+        Ok(())
+    }
+
+    fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.dry_run {
+            let result = Self::dispatch(self.command, false);
+            Self::report_held_approvals(false);
+            return result;
+        }
+        let (result, changes) =
+            crate::core::operations::simulate(|| Self::dispatch(self.command, true));
+        Self::report_held_approvals(true);
+        for line in crate::core::operations::dry_run::render_changes(&changes) {
+            println!("{}", line);
+        }
+        result
+    }
+
+    /// Critical equipment changes the command's saves held for sign-off.
+    fn report_held_approvals(dry_run: bool) {
+        for record in crate::persistence::approvals::take_held() {
+            let verb = if dry_run { "would need" } else { "needs" };
+            println!("⏳ {} {} sign-off: {}", record.id, verb, record.summary());
+            if !dry_run {
+                println!("   Another user applies it with `arx approve {}`", record.id);
+            }
+        }
+    }
+
+    fn dispatch(command: Commands, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+        match command {
             Commands::Init {
                 name,
                 no_git,
@@ -54,7 +260,6 @@ impl Cli {
                 ImportSubcommand::Ifc {
                     ifc_file,
                     repo,
-                    dry_run,
                     strict,
                     strict_addresses,
                 } => {
@@ -73,7 +278,8 @@ impl Cli {
                     file_path,
                     voxel_size,
                     light,
-                    dry_run,
+                    decimate,
+                    max_points,
                     merge,
                     building,
                 } => {
@@ -81,6 +287,8 @@ impl Cli {
                         file_path,
                         voxel_size,
                         light,
+                        decimate,
+                        max_points,
                         dry_run,
                         merge,
                         building,
@@ -90,7 +298,6 @@ impl Cli {
                 ImportSubcommand::Text {
                     script,
                     building,
-                    dry_run,
                 } => {
                     let cmd = commands::edit::EditCommand {
                         script,
@@ -99,11 +306,41 @@ impl Cli {
                     };
                     Ok(cmd.execute()?)
                 }
+                ImportSubcommand::Csv {
+                    file,
+                    mapping,
+                    strict,
+                    commit,
+                    path,
+                } => {
+                    let cmd = commands::import_csv::ImportCsvCommand {
+                        file,
+                        mapping: mapping.map(std::path::PathBuf::from),
+                        strict,
+                        dry_run,
+                        commit,
+                        path: path.map(std::path::PathBuf::from),
+                    };
+                    Ok(cmd.execute()?)
+                }
+                ImportSubcommand::Plugin {
+                    format,
+                    file,
+                    commit,
+                    path,
+                } => {
+                    let cmd = commands::import_plugin::ImportPluginCommand {
+                        format,
+                        file,
+                        commit,
+                        path: path.map(std::path::PathBuf::from),
+                    };
+                    Ok(cmd.execute()?)
+                }
             },
             Commands::Edit {
                 script,
                 building,
-                dry_run,
             } => {
                 let cmd = commands::edit::EditCommand {
                     script,
@@ -120,6 +357,8 @@ impl Cli {
                 approved_only,
                 commercial,
                 access_receipt,
+                floor,
+                overlay,
             } => {
                 let cmd = ExportCommand {
                     format,
@@ -129,6 +368,8 @@ impl Cli {
                     approved_only,
                     commercial,
                     access_receipt,
+                    floor,
+                    overlay,
                 };
                 Ok(cmd.execute()?)
             }
@@ -138,7 +379,6 @@ impl Cli {
                 longitude,
                 git_commit,
                 allow_invalid,
-                dry_run,
                 sign,
                 private_key,
                 oracle,
@@ -166,6 +406,39 @@ impl Cli {
                 };
                 Ok(cmd.execute()?)
             }
+            Commands::Dataset { subcommand } => {
+                let (action, path) = match subcommand {
+                    DatasetSubcommand::Package {
+                        output,
+                        bucket_minutes,
+                        k_min,
+                        epsilon,
+                        no_noise,
+                        raw,
+                        since,
+                        until,
+                        path,
+                    } => (
+                        DatasetAction::Package {
+                            output: std::path::PathBuf::from(output),
+                            bucket_minutes,
+                            k_min,
+                            epsilon,
+                            no_noise,
+                            raw,
+                            since,
+                            until,
+                        },
+                        path,
+                    ),
+                };
+                let cmd = DatasetCommand {
+                    action,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
             Commands::Access { subcommand } => {
                 let action = match subcommand {
                     AccessSubcommand::Quote {
@@ -211,13 +484,37 @@ impl Cli {
                 Ok(AccessCommand { action }.execute()?)
             }
             #[cfg(feature = "tui")]
-            Commands::Render { building, .. } => {
-                // Hierarchy tree only — LiDAR point-cloud / Bevy viz deferred.
-                crate::tui::render_building(&building)?;
+            Commands::Render {
+                building,
+                interactive,
+                floor,
+            } => {
+                if interactive {
+                    crate::tui::inspect_building(&building, floor)?;
+                } else {
+                    // Hierarchy tree only — LiDAR point-cloud / Bevy viz deferred.
+                    crate::tui::render_building(&building)?;
+                }
                 Ok(())
             }
             #[cfg(feature = "tui")]
+            Commands::Spreadsheet { target, commit } => {
+                crate::tui::edit_spreadsheet(&target, commit)?;
+                Ok(())
+            }
             Commands::Merge(cmd) => Ok(cmd.execute()?),
+            Commands::MergeDriver {
+                base,
+                ours,
+                theirs,
+                path,
+            } => MergeDriverCommand {
+                base: base.into(),
+                ours: ours.into(),
+                theirs: theirs.into(),
+                path,
+            }
+            .execute(),
             Commands::Status {
                 verbose,
                 interactive,
@@ -267,6 +564,16 @@ impl Cli {
                 verbose,
                 file,
             } => Self::handle_history(limit, verbose, file),
+            Commands::Branch { subcommand } => {
+                let action = match subcommand {
+                    BranchSubcommand::Create { name, from, switch } => {
+                        BranchAction::Create { name, from, switch }
+                    }
+                    BranchSubcommand::List => BranchAction::List,
+                    BranchSubcommand::Switch { name } => BranchAction::Switch { name },
+                };
+                BranchCommand { action }.execute()
+            }
             Commands::Search {
                 query,
                 equipment,
@@ -290,16 +597,861 @@ impl Cli {
             ),
             Commands::Query {
                 pattern,
+                exclude,
+                kind,
                 format,
                 verbose,
-            } => commands::query::run_address_query(&pattern, &format, verbose),
-            Commands::Migrate { dry_run } => {
+            } => commands::query::run_address_query(
+                &pattern,
+                &exclude,
+                kind.parse()?,
+                &format,
+                verbose,
+            ),
+            #[cfg(feature = "ask")]
+            Commands::Ask {
+                question,
+                format,
+                path,
+            } => commands::ask::AskCommand {
+                question: question.join(" "),
+                format,
+                path: path.map(std::path::PathBuf::from),
+            }
+            .execute(),
+            Commands::Egress {
+                max_distance,
+                report,
+                svg,
+                path,
+            } => {
+                let cmd = EgressCommand {
+                    max_distance,
+                    report: report.map(std::path::PathBuf::from),
+                    svg: svg.map(std::path::PathBuf::from),
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Completeness {
+                format,
+                output,
+                min_score,
+                path,
+            } => {
+                let cmd = CompletenessCommand {
+                    format,
+                    output: output.map(std::path::PathBuf::from),
+                    min_score,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Doc { subcommand } => match subcommand {
+                DocSubcommand::RoomPages {
+                    base_url,
+                    output,
+                    report_url,
+                    path,
+                } => {
+                    let cmd = DocCommand {
+                        action: DocAction::RoomPages {
+                            base_url,
+                            output: std::path::PathBuf::from(output),
+                            report_url,
+                        },
+                        path: path.map(std::path::PathBuf::from),
+                    };
+                    cmd.execute()
+                }
+            },
+            Commands::Maintenance { subcommand } => match subcommand {
+                MaintenanceSubcommand::Ical {
+                    output,
+                    assignee,
+                    days,
+                    path,
+                } => {
+                    let cmd = MaintenanceCommand {
+                        action: MaintenanceAction::Ical {
+                            output: std::path::PathBuf::from(output),
+                            assignee,
+                            days,
+                        },
+                        path: path.map(std::path::PathBuf::from),
+                    };
+                    cmd.execute()
+                }
+            },
+            Commands::Booking { subcommand } => {
+                let (action, path) = match subcommand {
+                    BookingSubcommand::Import {
+                        ics,
+                        room,
+                        days,
+                        ahead,
+                        path,
+                    } => (
+                        BookingAction::Import {
+                            ics: ics.map(std::path::PathBuf::from),
+                            room,
+                            days,
+                            ahead,
+                        },
+                        path,
+                    ),
+                    BookingSubcommand::Report { days, format, path } => {
+                        (BookingAction::Report { days, format }, path)
+                    }
+                };
+                let cmd = BookingCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Issue { subcommand } => {
+                let (action, path) = match subcommand {
+                    IssueSubcommand::List { all, path } => (IssueAction::List { all }, path),
+                    IssueSubcommand::Export {
+                        format,
+                        output,
+                        all,
+                        path,
+                    } => (
+                        IssueAction::Export {
+                            format,
+                            output: std::path::PathBuf::from(output),
+                            all,
+                        },
+                        path,
+                    ),
+                };
+                let cmd = IssueCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Inventory { subcommand } => {
+                let (action, path) = match subcommand {
+                    InventorySubcommand::List {
+                        equipment_type,
+                        path,
+                    } => (InventoryAction::List { equipment_type }, path),
+                    InventorySubcommand::Add {
+                        part_number,
+                        description,
+                        quantity,
+                        min_stock,
+                        unit_cost,
+                        reorder_quantity,
+                        location,
+                        equipment_types,
+                        path,
+                    } => (
+                        InventoryAction::Add {
+                            part: crate::core::inventory::SparePart {
+                                part_number,
+                                description,
+                                quantity,
+                                min_stock,
+                                unit_cost: unit_cost.map(|c| c.parse()).transpose()?,
+                                reorder_quantity,
+                                location,
+                                equipment_types,
+                            },
+                        },
+                        path,
+                    ),
+                    InventorySubcommand::Adjust {
+                        part_number,
+                        delta,
+                        path,
+                    } => (InventoryAction::Adjust { part_number, delta }, path),
+                    InventorySubcommand::LowStock { format, path } => {
+                        (InventoryAction::LowStock { format }, path)
+                    }
+                };
+                InventoryCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                }
+                .execute()
+            }
+            Commands::Cost { subcommand } => match subcommand {
+                CostSubcommand::Report { by, format, path } => CostCommand {
+                    action: CostAction::Report {
+                        by: by.parse()?,
+                        format,
+                    },
+                    path: path.map(std::path::PathBuf::from),
+                }
+                .execute(),
+            },
+            Commands::WorkOrder { subcommand } => {
+                let (action, path) = match subcommand {
+                    WorkOrderSubcommand::Create {
+                        title,
+                        description,
+                        room,
+                        equipment,
+                        priority,
+                        assignee,
+                        issue,
+                        path,
+                    } => (
+                        WorkOrderAction::Create {
+                            title,
+                            description,
+                            room,
+                            equipment,
+                            priority,
+                            assignee,
+                            issue,
+                        },
+                        path,
+                    ),
+                    WorkOrderSubcommand::List {
+                        all,
+                        assignee,
+                        path,
+                    } => (WorkOrderAction::List { all, assignee }, path),
+                    WorkOrderSubcommand::Show { id, path } => (WorkOrderAction::Show { id }, path),
+                    WorkOrderSubcommand::Assign { id, to, path } => {
+                        (WorkOrderAction::Assign { id, assignee: to }, path)
+                    }
+                    WorkOrderSubcommand::Comment {
+                        id,
+                        message,
+                        author,
+                        path,
+                    } => (
+                        WorkOrderAction::Comment {
+                            id,
+                            text: message,
+                            author,
+                        },
+                        path,
+                    ),
+                    WorkOrderSubcommand::Close {
+                        id,
+                        resolution,
+                        parts,
+                        labor_cost,
+                        path,
+                    } => (
+                        WorkOrderAction::Close {
+                            id,
+                            resolution,
+                            parts,
+                            labor_cost,
+                        },
+                        path,
+                    ),
+                };
+                let cmd = WorkOrderCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Attachment { subcommand } => {
+                let (action, path) = match subcommand {
+                    AttachmentSubcommand::Add {
+                        entity,
+                        file,
+                        caption,
+                        commit,
+                        path,
+                    } => (
+                        AttachmentAction::Add {
+                            entity,
+                            file: std::path::PathBuf::from(file),
+                            caption,
+                            commit,
+                        },
+                        path,
+                    ),
+                    AttachmentSubcommand::List { entity, path } => {
+                        (AttachmentAction::List { entity }, path)
+                    }
+                    AttachmentSubcommand::Remove {
+                        entity,
+                        file,
+                        commit,
+                        path,
+                    } => (
+                        AttachmentAction::Remove {
+                            entity,
+                            file,
+                            commit,
+                        },
+                        path,
+                    ),
+                    AttachmentSubcommand::Migrate { min_size_kb, path } => {
+                        (AttachmentAction::Migrate { min_size_kb }, path)
+                    }
+                    AttachmentSubcommand::Fetch { reference, path } => {
+                        (AttachmentAction::Fetch { reference }, path)
+                    }
+                };
+                let cmd = AttachmentCommand {
+                    action,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Floor { subcommand } => {
+                let (action, path) = match subcommand {
+                    FloorSubcommand::Clone {
+                        from,
+                        to,
+                        rename_pattern,
+                        floor_height,
+                        commit,
+                        path,
+                    } => (
+                        FloorAction::Clone {
+                            from,
+                            to,
+                            rename_pattern,
+                            floor_height,
+                            commit,
+                        },
+                        path,
+                    ),
+                };
+                FloorCommand {
+                    action,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                }
+                .execute()
+            }
+            Commands::Template { subcommand } => {
+                let action = match subcommand {
+                    TemplateSubcommand::List { format } => TemplateAction::List { format },
+                    TemplateSubcommand::Show { name } => TemplateAction::Show { name },
+                };
+                TemplateCommand { action }.execute()
+            }
+            Commands::Audit { subcommand } => {
+                let action = match subcommand {
+                    AuditSubcommand::Tail { lines, format } => AuditAction::Tail { lines, format },
+                    AuditSubcommand::Search {
+                        actor,
+                        action,
+                        target,
+                        outcome,
+                        since,
+                        limit,
+                        format,
+                    } => AuditAction::Search {
+                        actor,
+                        action,
+                        target,
+                        outcome,
+                        since,
+                        limit,
+                        format,
+                    },
+                };
+                AuditCommand { action }.execute()
+            }
+            Commands::Workspace { subcommand } => {
+                let action = match subcommand {
+                    WorkspaceSubcommand::Add { path, name } => WorkspaceAction::Add {
+                        path: path.into(),
+                        name,
+                    },
+                    WorkspaceSubcommand::List { format } => WorkspaceAction::List { format },
+                    WorkspaceSubcommand::Switch { name } => WorkspaceAction::Switch { name },
+                    WorkspaceSubcommand::Remove { name } => WorkspaceAction::Remove { name },
+                    #[cfg(feature = "tui")]
+                    WorkspaceSubcommand::Dashboard => {
+                        crate::tui::portfolio_dashboard()?;
+                        return Ok(());
+                    }
+                };
+                let cmd = WorkspaceCommand { action, dry_run };
+                cmd.execute()
+            }
+            Commands::Role { subcommand } => {
+                let (action, path) = match subcommand {
+                    RoleSubcommand::List { path } => (RoleAction::List, path),
+                    RoleSubcommand::Set { user, role, path } => {
+                        (RoleAction::Set { user, role }, path)
+                    }
+                    RoleSubcommand::Remove { user, path } => (RoleAction::Remove { user }, path),
+                };
+                let cmd = RoleCommand {
+                    action,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Backup { subcommand } => {
+                let (action, path) = match subcommand {
+                    BackupSubcommand::Run { if_due, path } => (BackupAction::Run { if_due }, path),
+                    BackupSubcommand::List { path } => (BackupAction::List, path),
+                    BackupSubcommand::Prune { path } => (BackupAction::Prune, path),
+                    BackupSubcommand::Verify { id, all, path } => {
+                        (BackupAction::Verify { id, all }, path)
+                    }
+                };
+                let cmd = BackupCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Provenance {
+                entity,
+                field,
+                path,
+            } => {
+                let cmd = ProvenanceCommand {
+                    entity,
+                    field,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Rename {
+                from,
+                to,
+                commit,
+                path,
+            } => {
+                let cmd = RenameCommand {
+                    from,
+                    to,
+                    commit,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Approve {
+                change_id,
+                reject,
+                note,
+                no_sign,
+                path,
+            } => {
+                let cmd = ApproveCommand {
+                    change_id,
+                    reject,
+                    note,
+                    sign: !no_sign,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Approvals {
+                change_id,
+                all,
+                path,
+            } => {
+                let cmd = ApprovalsCommand {
+                    change_id,
+                    all,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Compare {
+                from,
+                to,
+                format,
+                output,
+                path,
+            } => {
+                let cmd = CompareCommand {
+                    from,
+                    to,
+                    format,
+                    output: output.map(std::path::PathBuf::from),
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Completions { shell } => {
+                let cmd = CompletionsCommand { shell };
+                cmd.execute()
+            }
+            Commands::Wizard { path } => {
+                let cmd = WizardCommand {
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Verify {
+                all,
+                staged,
+                format,
+                path,
+            } => {
+                let cmd = VerifyCommand {
+                    all,
+                    staged,
+                    format,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Rollback {
+                entity,
+                commit,
+                path,
+            } => {
+                let cmd = RollbackCommand {
+                    entity,
+                    commit,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            #[cfg(feature = "bacnet")]
+            Commands::Bacnet { subcommand } => {
+                use commands::bacnet::BacnetAction;
+                use spec::BacnetSubcommand;
+
+                let (action, path) = match subcommand {
+                    BacnetSubcommand::Discover {
+                        target,
+                        wait_ms,
+                        path,
+                    } => (BacnetAction::Discover { target, wait_ms }, path),
+                    BacnetSubcommand::Poll { once, path } => (BacnetAction::Poll { once }, path),
+                    BacnetSubcommand::Bind { commit, path } => {
+                        (BacnetAction::Bind { commit }, path)
+                    }
+                };
+                let cmd = commands::BacnetCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            #[cfg(feature = "mqtt")]
+            Commands::Mqtt { subcommand } => {
+                use commands::mqtt::MqttAction;
+                use spec::MqttSubcommand;
+
+                let (action, path) = match subcommand {
+                    MqttSubcommand::Bridge { once, path } => (MqttAction::Bridge { once }, path),
+                    MqttSubcommand::Publish {
+                        topic,
+                        payload,
+                        retain,
+                        path,
+                    } => (
+                        MqttAction::Publish {
+                            topic,
+                            payload,
+                            retain,
+                        },
+                        path,
+                    ),
+                };
+                let cmd = commands::MqttCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Sensor { subcommand } => {
+                let (action, path) = match subcommand {
+                    SensorSubcommand::Record {
+                        sensor_id,
+                        sensor_type,
+                        value,
+                        path,
+                    } => (
+                        SensorAction::Record {
+                            sensor_id,
+                            sensor_type,
+                            value,
+                        },
+                        path,
+                    ),
+                    SensorSubcommand::Unmapped { path } => (SensorAction::Unmapped, path),
+                    SensorSubcommand::Map { commit, path } => (SensorAction::Map { commit }, path),
+                    SensorSubcommand::History {
+                        sensor_id,
+                        since,
+                        until,
+                        bucket_minutes,
+                        format,
+                        path,
+                    } => (
+                        SensorAction::History {
+                            sensor_id,
+                            since,
+                            until,
+                            bucket_minutes,
+                            format,
+                        },
+                        path,
+                    ),
+                    SensorSubcommand::Simulate {
+                        profile,
+                        hours,
+                        realtime,
+                        path,
+                    } => (
+                        SensorAction::Simulate {
+                            profile: std::path::PathBuf::from(profile),
+                            hours,
+                            realtime,
+                        },
+                        path,
+                    ),
+                    #[cfg(feature = "modbus")]
+                    SensorSubcommand::Modbus { config, once, path } => (
+                        SensorAction::Modbus {
+                            config: config.map(std::path::PathBuf::from),
+                            once,
+                        },
+                        path,
+                    ),
+                };
+                let cmd = SensorCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Energy { subcommand } => {
+                let (action, path) = match subcommand {
+                    EnergySubcommand::Meter {
+                        id,
+                        utility,
+                        equipment,
+                        floor,
+                        path,
+                    } => (
+                        EnergyAction::Meter {
+                            id,
+                            utility,
+                            equipment,
+                            floor,
+                        },
+                        path,
+                    ),
+                    EnergySubcommand::Meters { path } => (EnergyAction::Meters, path),
+                    EnergySubcommand::Record {
+                        meter,
+                        value,
+                        start,
+                        end,
+                        path,
+                    } => (
+                        EnergyAction::Record {
+                            meter,
+                            value,
+                            start,
+                            end,
+                        },
+                        path,
+                    ),
+                    EnergySubcommand::Import { meter, file, path } => (
+                        EnergyAction::Import {
+                            meter,
+                            file: std::path::PathBuf::from(file),
+                        },
+                        path,
+                    ),
+                    EnergySubcommand::Report {
+                        building,
+                        period,
+                        since,
+                        format,
+                        path,
+                    } => (
+                        EnergyAction::Report {
+                            building,
+                            period,
+                            since,
+                            format,
+                        },
+                        path,
+                    ),
+                };
+                let cmd = EnergyCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::System { subcommand } => {
+                let (action, path) = match subcommand {
+                    SystemSubcommand::Connect {
+                        from,
+                        relation,
+                        to,
+                        commit,
+                        path,
+                    } => (
+                        SystemAction::Connect {
+                            from,
+                            relation,
+                            to,
+                            commit,
+                        },
+                        path,
+                    ),
+                    SystemSubcommand::Disconnect {
+                        from,
+                        to,
+                        commit,
+                        path,
+                    } => (SystemAction::Disconnect { from, to, commit }, path),
+                    SystemSubcommand::Impact {
+                        equipment,
+                        upstream,
+                        path,
+                    } => (
+                        SystemAction::Impact {
+                            equipment,
+                            upstream,
+                        },
+                        path,
+                    ),
+                    SystemSubcommand::List { path } => (SystemAction::List, path),
+                    SystemSubcommand::View { path } => (SystemAction::View, path),
+                };
+                let cmd = SystemCommand {
+                    action,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Clean {
+                categories,
+                purge,
+                path,
+            } => {
+                let cmd = CleanCommand {
+                    categories,
+                    purge,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Commission { subcommand } => {
+                let (action, path) = match subcommand {
+                    CommissionSubcommand::Enroll {
+                        equipment,
+                        project,
+                        commit,
+                        path,
+                    } => (
+                        CommissionAction::Enroll {
+                            equipment,
+                            project,
+                            commit,
+                        },
+                        path,
+                    ),
+                    CommissionSubcommand::Advance {
+                        equipment,
+                        to,
+                        by,
+                        photos,
+                        reading,
+                        note,
+                        commit,
+                        path,
+                    } => (
+                        CommissionAction::Advance {
+                            equipment,
+                            to,
+                            by,
+                            photos,
+                            reading,
+                            note,
+                            commit,
+                        },
+                        path,
+                    ),
+                    CommissionSubcommand::Status { equipment, path } => {
+                        (CommissionAction::Status { equipment }, path)
+                    }
+                    CommissionSubcommand::Report {
+                        project,
+                        format,
+                        output,
+                        path,
+                    } => (
+                        CommissionAction::Report {
+                            project,
+                            format,
+                            output: output.map(std::path::PathBuf::from),
+                        },
+                        path,
+                    ),
+                };
+                let cmd = CommissionCommand {
+                    action,
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                cmd.execute()
+            }
+            Commands::Config { subcommand } => match subcommand {
+                ConfigSubcommand::Doctor { path } => {
+                    let cmd = ConfigDoctorCommand {
+                        path: path.map(std::path::PathBuf::from),
+                    };
+                    Ok(cmd.execute()?)
+                }
+            },
+            Commands::Notify { subcommand } => match subcommand {
+                NotifySubcommand::Test { webhook, path } => {
+                    let cmd = NotifyTestCommand {
+                        webhook,
+                        path: path.map(std::path::PathBuf::from),
+                    };
+                    Ok(cmd.execute()?)
+                }
+            },
+            Commands::Jobs { subcommand } => {
+                let (action, path) = match subcommand {
+                    JobsSubcommand::List { all, path } => (JobsAction::List { all }, path),
+                    JobsSubcommand::Status { id, path } => (JobsAction::Status { id }, path),
+                    JobsSubcommand::Cancel { id, path } => (JobsAction::Cancel { id }, path),
+                };
+                let cmd = JobsCommand {
+                    action,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                Ok(cmd.execute()?)
+            }
+            Commands::Migrate { subcommand: None } => {
                 let cmd = MigrateCommand {
                     dry_run,
                     path: None,
                 };
                 Ok(cmd.execute()?)
             }
+            Commands::Migrate {
+                subcommand: Some(MigrateSubcommand::Schema { path }),
+            } => {
+                let cmd = SchemaMigrateCommand {
+                    dry_run,
+                    path: path.map(std::path::PathBuf::from),
+                };
+                Ok(cmd.execute()?)
+            }
             Commands::Room { command } => {
                 let cmd = RoomCommand {
                     subcommand: command,
@@ -362,6 +1514,75 @@ impl Cli {
                 }
                 Ok(())
             }
+            #[cfg(feature = "agent")]
+            Commands::Agent { subcommand } => {
+                let action = match subcommand {
+                    spec::AgentSubcommand::Serve {
+                        replica,
+                        branch,
+                        interval,
+                    } => commands::agent::AgentAction::Serve {
+                        replica,
+                        branch,
+                        interval,
+                    },
+                    spec::AgentSubcommand::Promote { path } => {
+                        commands::agent::AgentAction::Promote {
+                            path: path.map(std::path::PathBuf::from),
+                        }
+                    }
+                    spec::AgentSubcommand::Replay {
+                        file,
+                        workspace,
+                        verbose,
+                    } => commands::agent::AgentAction::Replay {
+                        file: file.into(),
+                        workspace: workspace.into(),
+                        verbose,
+                    },
+                    spec::AgentSubcommand::Keys { subcommand } => match subcommand {
+                        spec::AgentKeysSubcommand::Create {
+                            capabilities,
+                            expires,
+                            name,
+                        } => commands::agent::AgentAction::KeysCreate {
+                            capabilities,
+                            expires,
+                            name,
+                        },
+                        spec::AgentKeysSubcommand::List => commands::agent::AgentAction::KeysList,
+                        spec::AgentKeysSubcommand::Revoke { id } => {
+                            commands::agent::AgentAction::KeysRevoke { id }
+                        }
+                    },
+                };
+                Ok(commands::AgentCommand { action }.execute()?)
+            }
+            #[cfg(feature = "agent")]
+            Commands::Devtools { subcommand } => {
+                let action = match subcommand {
+                    spec::DevtoolsSubcommand::Hil {
+                        devices,
+                        readings,
+                        transport,
+                        defect_every,
+                        concurrency,
+                        seed,
+                        workspace,
+                    } => commands::devtools::DevtoolsAction::Hil {
+                        config: crate::devtools::hil::HilConfig {
+                            devices,
+                            readings_per_device: readings,
+                            transport: transport.parse()?,
+                            defect_every,
+                            concurrency,
+                            seed,
+                        },
+                        workspace: workspace.map(std::path::PathBuf::from),
+                    },
+                };
+                Ok(commands::DevtoolsCommand { action }.execute()?)
+            }
             #[cfg(all(feature = "tui", feature = "agent"))]
             Commands::Dashboard => {
                 use crate::agent::auth::TokenState;
@@ -376,20 +1597,33 @@ impl Cli {
                         ))),
                         metrics: std::sync::Arc::new(crate::agent::observability::AgentMetrics::new()),
                         reload_handle: None,
+                        sessions: Default::default(),
                     });
 
                     crate::tui::dashboard::run_dashboard(state).await
                 })?;
                 Ok(())
             }
-            Commands::Validate { path, strict_addresses } => {
+            Commands::Validate {
+                path,
+                strict_addresses,
+                format,
+            } => {
                 use crate::persistence::{load_building_at, BUILDING_YAML};
-                use crate::validation::{validate_building, STRICT_ADDRESSES};
+                use crate::validation::{
+                    capacity_summary_lines, evaluate_constraints, evaluate_rules,
+                    validate_building, ConstraintSet, RuleConfig, STRICT_ADDRESSES,
+                };
                 use std::sync::atomic::Ordering;
 
                 if strict_addresses {
                     STRICT_ADDRESSES.store(true, Ordering::Relaxed);
                 }
+                let json = match format.as_str() {
+                    "text" => false,
+                    "json" => true,
+                    other => return Err(format!("Unknown format '{}' (text, json)", other).into()),
+                };
 
                 let base = path
                     .as_deref()
@@ -403,14 +1637,50 @@ impl Cli {
                         e
                     )
                 })?;
-                let report = validate_building(&building);
-                for line in report.summary_lines() {
-                    println!("{}", line);
+                let mut report = validate_building(&building);
+                let rules = RuleConfig::load_at(&base)?;
+                report.results.extend(evaluate_rules(&building, &rules).results);
+                let constraints = ConstraintSet::load_at(&base)?;
+                let capacity = evaluate_constraints(&building, &constraints);
+                report.results.extend(capacity.iter().cloned());
+                if json {
+                    let body = serde_json::json!({
+                        "ok": !report.has_errors(),
+                        "errors": report.errors().count(),
+                        "warnings": report.warnings().count(),
+                        "results": report.results,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&body)?);
+                } else {
+                    for line in report.summary_lines() {
+                        println!("{}", line);
+                    }
+                    if !constraints.is_empty() {
+                        for line in capacity_summary_lines(&capacity) {
+                            println!("{}", line);
+                        }
+                    }
                 }
                 if report.has_errors() {
+                    let errors = report
+                        .errors()
+                        .map(|e| match &e.field {
+                            Some(f) => format!("{}: {}", f, e.message),
+                            None => e.message.clone(),
+                        })
+                        .collect();
+                    crate::notifications::notify(
+                        &base,
+                        &[crate::notifications::Event::ValidationFailed {
+                            source: "arx validate".to_string(),
+                            errors,
+                        }],
+                    );
                     Err("Building validation failed".into())
                 } else {
-                    println!("✅ Validation completed successfully");
+                    if !json {
+                        println!("✅ Validation completed successfully");
+                    }
                     Ok(())
                 }
             }
@@ -571,20 +1841,36 @@ impl Cli {
 
         let mut total_results = 0;
 
+        // Names and aliases are both searchable
+        use crate::core::alias::display_names;
+        let text_matches = |text: &str| -> Result<bool, Box<dyn std::error::Error>> {
+            Ok(if regex {
+                regex::Regex::new(&query)?.is_match(text)
+            } else if case_sensitive {
+                text.contains(&query)
+            } else {
+                text.to_lowercase().contains(&query.to_lowercase())
+            })
+        };
+
+        let aka = |aliases: &[String]| {
+            if aliases.is_empty() {
+                String::new()
+            } else {
+                format!(" (aka {})", aliases.join(", "))
+            }
+        };
+
         // Search equipment
         if search_equipment {
             let equipment_list = eq_ops::list_equipment(None)?;
             let mut matches = Vec::new();
 
             for item in equipment_list {
-                let matches_name = if regex {
-                    let re = regex::Regex::new(&query)?;
-                    re.is_match(&item.name)
-                } else if case_sensitive {
-                    item.name.contains(&query)
-                } else {
-                    item.name.to_lowercase().contains(&query.to_lowercase())
-                };
+                let mut matches_name = false;
+                for name in display_names(&item.name, &item.aliases) {
+                    matches_name |= text_matches(name)?;
+                }
 
                 if matches_name {
                     matches.push(item);
@@ -602,7 +1888,7 @@ impl Cli {
                         }
                         println!();
                     } else {
-                        println!("  - {}", item.name);
+                        println!("  - {}{}", item.name, aka(&item.aliases));
                     }
                 }
                 if matches.len() > limit {
@@ -622,14 +1908,10 @@ impl Cli {
             let mut matches = Vec::new();
 
             for room in room_list {
-                let matches_name = if regex {
-                    let re = regex::Regex::new(&query)?;
-                    re.is_match(&room.name)
-                } else if case_sensitive {
-                    room.name.contains(&query)
-                } else {
-                    room.name.to_lowercase().contains(&query.to_lowercase())
-                };
+                let mut matches_name = false;
+                for name in display_names(&room.name, &room.aliases) {
+                    matches_name |= text_matches(name)?;
+                }
 
                 if matches_name {
                     matches.push(room);
@@ -645,7 +1927,7 @@ impl Cli {
                         println!("     Equipment: {} items", room.equipment.len());
                         println!();
                     } else {
-                        println!("  - {}", room.name);
+                        println!("  - {}{}", room.name, aka(&room.aliases));
                     }
                 }
                 if matches.len() > limit {
@@ -52,9 +52,6 @@ pub enum Commands {
         /// Building YAML path or name
         #[arg(long)]
         building: Option<String>,
-        /// Show result without writing
-        #[arg(long)]
-        dry_run: bool,
     },
     /// Validate building.yaml
     Validate {
@@ -64,6 +61,9 @@ pub enum Commands {
         /// Enable strict address prefix checking
         #[arg(long)]
         strict_addresses: bool,
+        /// Output format (text, json)
+        #[arg(long, default_value = "text")]
+        format: String,
     },
     /// Export building SSOT (IFC is the compiler interchange spine)
     ///
@@ -82,7 +82,7 @@ export clean IFC for import.
 Official pilot handoffs: `arx export --format ifc` (not agent auto-export).
 Use --path to select a project root without changing cwd.")]
     Export {
-        /// Export format: ifc (recommended), yaml, json
+        /// Export format: ifc (recommended), yaml, json, gltf, pwa-bundle, csv, png, svg, or a plugin in .arx/plugins/formats
         #[arg(long, default_value = "ifc")]
         format: String,
         /// Output file path
@@ -103,15 +103,32 @@ Use --path to select a project root without changing cwd.")]
         /// Path to access receipt JSON (default: access-receipt.json)
         #[arg(long)]
         access_receipt: Option<String>,
+        /// Floor level drawn by png / svg export (default: lowest floor)
+        #[arg(long, allow_hyphen_values = true)]
+        floor: Option<i32>,
+        /// png / svg overlay: none or status (rooms filled by worst equipment state)
+        #[arg(long, default_value = "none")]
+        overlay: String,
     },
-    /// Query equipment by durable ArxAddress glob
+    /// Query entities by hierarchical ArxAddress glob
+    ///
+    /// `*` matches within one segment, `**` any number of segments, and
+    /// `!glob` negates a segment. Entities without a durable address are
+    /// matched on the address `arx migrate` would assign.
     ///
     /// Examples:
-    ///   arx query "/usa/ny/*/floor-*/mech/boiler-*"
-    ///   arx query "/local/local/local/*/*/*/*"
+    ///   arx query "/usa/ny/*/*/floor-*/mech/boiler-*"
+    ///   arx query "/local/**/!kitchen/*"
+    ///   arx query "/**/floor-2/**" --kind room --not "/**/mech*"
     Query {
         /// ArxAddress glob pattern with wildcards
         pattern: String,
+        /// Drop matches of this address pattern (repeatable)
+        #[arg(long = "not")]
+        exclude: Vec<String>,
+        /// Entity kind to return (equipment, room, wing, floor, building, anchor, all)
+        #[arg(long, default_value = "equipment")]
+        kind: String,
         /// Output format (table, json, yaml)
         #[arg(long, default_value = "table")]
         format: String,
@@ -119,11 +136,47 @@ Use --path to select a project root without changing cwd.")]
         #[arg(long)]
         verbose: bool,
     },
-    /// Backfill missing ArxAddress fields on equipment
-    Migrate {
-        /// Preview changes without writing
+    /// Ask a question about the building in plain language
+    ///
+    /// Parsed into the same filters as `arx query` plus maintenance, sensor,
+    /// and alert conditions; `.arx/ask.yaml` can select an LLM backend.
+    ///
+    /// Examples:
+    ///   arx ask "which AHUs on floor 3 have filters overdue?"
+    ///   arx ask pumps out of order in room 101
+    #[cfg(feature = "ask")]
+    Ask {
+        /// The question (quoting is optional)
+        #[arg(required = true)]
+        question: Vec<String>,
+        /// Output format (table, json, yaml)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Project root containing building.yaml (default: cwd)
         #[arg(long)]
-        dry_run: bool,
+        path: Option<String>,
+    },
+    /// Backfill missing ArxAddress fields on equipment, or upgrade document schemas
+    ///
+    /// Preview with `arx --dry-run migrate` / `arx --dry-run migrate schema`.
+    Migrate {
+        #[command(subcommand)]
+        subcommand: Option<MigrateSubcommand>,
+    },
+    /// Inspect layered configuration (defaults, system, user, arx.toml, ARX_* env)
+    Config {
+        #[command(subcommand)]
+        subcommand: ConfigSubcommand,
+    },
+    /// Webhook notifications for commits, validation failures, alerts, and AR review (.arx/notifications.yaml)
+    Notify {
+        #[command(subcommand)]
+        subcommand: NotifySubcommand,
+    },
+    /// Background jobs started with `arx --async` or by the agent (.arx/jobs)
+    Jobs {
+        #[command(subcommand)]
+        subcommand: JobsSubcommand,
     },
 
     // ── Model CRUD ──────────────────────────────────────────────────────
@@ -142,6 +195,333 @@ Use --path to select a project root without changing cwd.")]
         #[command(subcommand)]
         command: SpatialCommands,
     },
+    /// Fire-safety egress analysis: travel distance from each room to the nearest exit
+    ///
+    /// Exits are rooms with `egress_exit=true` (or named *exit* / *stair*).
+    Egress {
+        /// Maximum travel distance to an exit in metres
+        #[arg(long, default_value = "45")]
+        max_distance: f64,
+        /// Write Markdown compliance report to this path
+        #[arg(long)]
+        report: Option<String>,
+        /// Write SVG egress overlay to this path
+        #[arg(long)]
+        svg: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Completeness / LOD report: geometry, properties, sensors, photos per room and floor
+    ///
+    /// Declare a target with the `lod` property (e.g. `lod=300`) on rooms or equipment.
+    Completeness {
+        /// Output format (table, json, markdown)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Write the report to this path instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+        /// Minimum rollup score (0-1) for rooms without a declared LOD
+        #[arg(long, default_value = "0.5")]
+        min_score: f64,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Documentation / static site output (guest room pages)
+    Doc {
+        #[command(subcommand)]
+        subcommand: DocSubcommand,
+    },
+
+    /// Occupant issue reports (from the agent intake endpoint / room pages)
+    Issue {
+        #[command(subcommand)]
+        subcommand: IssueSubcommand,
+    },
+
+    /// Maintenance schedules (.arx/maintenance.yaml) as calendar feeds
+    Maintenance {
+        #[command(subcommand)]
+        subcommand: MaintenanceSubcommand,
+    },
+
+    /// Work orders on rooms / equipment (.arx/workorders/, one Git commit per change)
+    #[command(name = "workorder")]
+    WorkOrder {
+        #[command(subcommand)]
+        subcommand: WorkOrderSubcommand,
+    },
+
+    /// Spare parts stock (.arx/inventory.yaml); work orders deduct the parts they use
+    Inventory {
+        #[command(subcommand)]
+        subcommand: InventorySubcommand,
+    },
+
+    /// Budget rollups of work order labor/parts and equipment acquisition costs
+    Cost {
+        #[command(subcommand)]
+        subcommand: CostSubcommand,
+    },
+
+    /// Room bookings from calendars (.arx/bookings.yaml) vs occupancy sensors
+    Booking {
+        #[command(subcommand)]
+        subcommand: BookingSubcommand,
+    },
+
+    /// Rename a floor, wing, room, or equipment and rewrite every reference
+    ///
+    /// Paths are addresses or `/<building>/<floor>/<wing>[/<room>[/<equipment>]]`
+    /// and may differ only in their last segment, e.g. `--from /B1/3/A --to /B1/3/B`.
+    Rename {
+        /// Current path of the entity
+        #[arg(long)]
+        from: String,
+        /// New path (same parent, new last segment)
+        #[arg(long)]
+        to: String,
+        /// Commit the rename as one Git commit
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Whole-floor operations, e.g. `arx floor clone --from 3 --to 4..10`
+    Floor {
+        #[command(subcommand)]
+        subcommand: FloorSubcommand,
+    },
+
+    /// Restore a room, equipment, or the whole building from Git history
+    ///
+    /// Commits the restored building.yaml with the source commit recorded in the
+    /// message, e.g. `arx rollback --entity /HQ/1/East/Plant/AHU-1 --commit HEAD~3`.
+    Rollback {
+        /// Room or equipment path, id, or name (default: the whole building)
+        #[arg(long)]
+        entity: Option<String>,
+        /// Commit to restore from (default: the last committed building.yaml
+        /// that differs from the current one)
+        #[arg(long)]
+        commit: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Compare two building states: rooms and equipment added, removed, moved, or changed
+    ///
+    /// Each side is a Git revision (branch, tag, commit) of this project, a
+    /// project directory, or a building.yaml / IFC file, e.g.
+    /// `arx compare main` on a renovation branch, or
+    /// `arx compare design.ifc --to scans/` for as-built vs design.
+    Compare {
+        /// Earlier or reference state
+        from: String,
+        /// Later state (default: the working building.yaml)
+        #[arg(long)]
+        to: Option<String>,
+        /// Output format (text, json)
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// Also write the JSON report to this path
+        #[arg(long)]
+        output: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// e.g. `arx completions bash > /etc/bash_completion.d/arx`, or
+    /// `arx completions zsh > "${fpath[1]}/_arx"`.
+    Completions {
+        /// Shell to generate for (bash, zsh, fish, powershell, elvish)
+        shell: clap_complete::Shell,
+    },
+
+    /// Build a room or equipment command step by step, then run it
+    ///
+    /// Prompts for each argument, checks types and choices as you answer,
+    /// suggests existing floors, wings, rooms, and equipment, and shows the
+    /// finished command before running (or dry-running) it.
+    Wizard {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Check that commits changing signed paths are GPG-signed
+    ///
+    /// Paths are set with `git.signed_paths` in the arx configuration (e.g.
+    /// `arx.toml`). Checks HEAD by default; `--all --format json` prints a
+    /// compliance report for the whole history. Exits non-zero on violations.
+    Verify {
+        /// Check every commit reachable from HEAD
+        #[arg(long, conflicts_with = "staged")]
+        all: bool,
+        /// Check the staged changes instead (run by the pre-commit hook)
+        #[arg(long)]
+        staged: bool,
+        /// Output format (text, json)
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// Project root (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Sign off a held change to critical equipment (rules in .arx/approvals.yaml)
+    ///
+    /// Applies the change, signs the record with GPG, and commits both. The
+    /// approver must be a different user from the requester.
+    Approve {
+        /// Approval request id (APR-...)
+        change_id: String,
+        /// Reject instead of approving; the change is discarded
+        #[arg(long)]
+        reject: bool,
+        /// Note kept with the decision
+        #[arg(long)]
+        note: Option<String>,
+        /// Record the decision without a GPG signature
+        #[arg(long)]
+        no_sign: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// List approval requests (pending unless --all), or show one with its signature check
+    Approvals {
+        /// Approval request id to show
+        change_id: Option<String>,
+        /// Include approved and rejected requests
+        #[arg(long)]
+        all: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Show where a room or equipment field value came from (source, actor, time, commit)
+    Provenance {
+        /// Room or equipment id, name, or alias
+        entity: String,
+        /// Field (`name`, `position`, a property key, ...); omit for every tracked field
+        field: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Photos and documents on rooms and equipment; large files in a directory or
+    /// S3-compatible bucket (configured in .arx/attachments.yaml)
+    Attachment {
+        #[command(subcommand)]
+        subcommand: AttachmentSubcommand,
+    },
+
+    /// User roles (viewer, technician, manager, admin) enforced on building edits
+    ///
+    /// Assignments live in .arx/roles.yaml; without it every edit is allowed.
+    Role {
+        #[command(subcommand)]
+        subcommand: RoleSubcommand,
+    },
+
+    /// Templates for standard room and equipment types (built in, or .arx/templates/*.yaml)
+    ///
+    /// Use them with `arx equipment add --template` and `arx room create --template`.
+    Template {
+        #[command(subcommand)]
+        subcommand: TemplateSubcommand,
+    },
+
+    /// Append-only log of CLI and agent actions, including views, exports, and denials
+    ///
+    /// Kept in .arx/audit/ (not committed); rotation is set in .arx/audit.yaml.
+    Audit {
+        #[command(subcommand)]
+        subcommand: AuditSubcommand,
+    },
+
+    /// Registry of building repositories for working across a portfolio
+    ///
+    /// Outside a building project, commands use the active building; `arx
+    /// --in <name> …` runs one command in another registered building.
+    Workspace {
+        #[command(subcommand)]
+        subcommand: WorkspaceSubcommand,
+    },
+
+    /// Backups to a directory or S3-compatible bucket (configured in .arx/backup.yaml)
+    Backup {
+        #[command(subcommand)]
+        subcommand: BackupSubcommand,
+    },
+
+    /// Evict old exports, renders, sessions, and cached attachments down to their limits
+    ///
+    /// Least-recently-used files go first; limits live in .arx/artifacts.yaml.
+    /// Files tracked by Git and the newest file of each category are kept.
+    Clean {
+        /// Categories to clean (default: all)
+        categories: Vec<String>,
+        /// Remove every untracked file in the categories, ignoring limits
+        #[arg(long)]
+        purge: bool,
+        /// Project root (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+
+    /// Sensor inbox, mapping (bind incoming sensor ids to equipment / rooms), and history
+    #[command(alias = "sensors")]
+    Sensor {
+        #[command(subcommand)]
+        subcommand: SensorSubcommand,
+    },
+
+    /// Energy meters (electric, gas, water) on equipment or floors, with period rollups
+    Energy {
+        #[command(subcommand)]
+        subcommand: EnergySubcommand,
+    },
+
+    /// Equipment systems: typed edges (feeds, powers, controls, serves) and impact analysis
+    System {
+        #[command(subcommand)]
+        subcommand: SystemSubcommand,
+    },
+
+    /// BACnet/IP devices: discover, poll mapped points into the sensor inbox (.arx/bacnet.yaml)
+    #[cfg(feature = "bacnet")]
+    Bacnet {
+        #[command(subcommand)]
+        subcommand: BacnetSubcommand,
+    },
+
+    /// Publish equipment status, alerts, and commits to an MQTT broker (.arx/mqtt.yaml)
+    #[cfg(feature = "mqtt")]
+    Mqtt {
+        #[command(subcommand)]
+        subcommand: MqttSubcommand,
+    },
+
+    /// Equipment commissioning: installed → wired → sensor_mapped → tested → accepted
+    Commission {
+        #[command(subcommand)]
+        subcommand: CommissionSubcommand,
+    },
 
     // ── Git ─────────────────────────────────────────────────────────────
     /// Show repository status
@@ -212,6 +592,11 @@ Use --path to select a project root without changing cwd.")]
         #[arg(long)]
         file: Option<String>,
     },
+    /// Scenario branches ("what-if" renovations); merge them back with `arx merge <branch>`
+    Branch {
+        #[command(subcommand)]
+        subcommand: BranchSubcommand,
+    },
 
     // ── UI (default feature `tui`) ──────────────────────────────────────
     /// Search building data by name
@@ -250,16 +635,42 @@ Use --path to select a project root without changing cwd.")]
         #[arg(long)]
         interactive: bool,
     },
-    /// Print building hierarchy as text (not LiDAR point-cloud viz)
+    /// Print building hierarchy as text, or inspect floor plans interactively
     #[cfg(feature = "tui")]
     Render {
         /// Building name or id (loaded from cwd building.yaml)
         #[arg(long)]
         building: String,
+        /// Open the floor-plan inspector (pick rooms / equipment with keys or mouse)
+        #[arg(long)]
+        interactive: bool,
+        /// Floor level to open the inspector on (default: lowest)
+        #[arg(long, allow_hyphen_values = true)]
+        floor: Option<i32>,
     },
-    /// Resolve merge conflicts interactively
+    /// Bulk-edit equipment or rooms in a spreadsheet, reviewing changes before they are written
     #[cfg(feature = "tui")]
+    Spreadsheet {
+        /// What to edit: equipment or rooms
+        target: String,
+        /// Commit the written changes to Git (toggle with `c` in the review)
+        #[arg(long)]
+        commit: bool,
+    },
+    /// Merge a scenario branch entity by entity, or resolve merge conflicts interactively
     Merge(crate::cli::commands::MergeCommand),
+    /// Git merge driver for building.yaml (registered by `arx init`)
+    #[command(hide = true)]
+    MergeDriver {
+        /// Common ancestor version (%O)
+        base: String,
+        /// Current branch version, overwritten with the result (%A)
+        ours: String,
+        /// Other branch version (%B)
+        theirs: String,
+        /// Path of the file in the repository (%P)
+        path: String,
+    },
     /// Launch agent dashboard
     #[cfg(all(feature = "tui", feature = "agent"))]
     Dashboard,
@@ -282,9 +693,6 @@ Use --path to select a project root without changing cwd.")]
         /// Allow packaging even if validation has errors (not for mint path)
         #[arg(long)]
         allow_invalid: bool,
-        /// Print package summary without writing a file
-        #[arg(long)]
-        dry_run: bool,
         /// EIP-712 sign package (requires --features blockchain)
         #[arg(long)]
         sign: bool,
@@ -315,6 +723,11 @@ Use --path to select a project root without changing cwd.")]
         #[command(subcommand)]
         subcommand: AccessSubcommand,
     },
+    /// Package sensor datasets for publication with privacy protection (lab; not L1-required)
+    Dataset {
+        #[command(subcommand)]
+        subcommand: DatasetSubcommand,
+    },
 
     // ── Agent ring ──────────────────────────────────────────────────────
     /// Manage remote building connections via SSH
@@ -336,6 +749,161 @@ Use --path to select a project root without changing cwd.")]
         #[arg(long)]
         live: bool,
     },
+    /// Run the agent, manage standby replicas, replay recorded sessions
+    #[cfg(feature = "agent")]
+    Agent {
+        #[command(subcommand)]
+        subcommand: AgentSubcommand,
+    },
+    /// Developer harnesses (hardware-in-the-loop ingestion tests)
+    #[cfg(feature = "agent")]
+    Devtools {
+        #[command(subcommand)]
+        subcommand: DevtoolsSubcommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BranchSubcommand {
+    /// Create a branch (default: at the current commit)
+    Create {
+        name: String,
+        /// Start from this commit or branch instead
+        #[arg(long)]
+        from: Option<String>,
+        /// Switch to the new branch
+        #[arg(long)]
+        switch: bool,
+    },
+    /// List branches (* = checked out)
+    List,
+    /// Check out another branch (building.yaml and tracked files)
+    Switch { name: String },
+}
+
+#[cfg(feature = "agent")]
+#[derive(Subcommand)]
+pub enum DevtoolsSubcommand {
+    /// Simulate a device fleet posting sensor reports and check inbox, trace log, and Git
+    Hil {
+        /// Number of simulated devices
+        #[arg(long, default_value = "12")]
+        devices: usize,
+        /// Reports per device
+        #[arg(long, default_value = "10")]
+        readings: usize,
+        /// http (agent router on loopback) or dispatcher (no socket)
+        #[arg(long, default_value = "http")]
+        transport: String,
+        /// Every n-th report is malformed (0: none)
+        #[arg(long, default_value = "7")]
+        defect_every: usize,
+        /// Reports in flight at once
+        #[arg(long, default_value = "8")]
+        concurrency: usize,
+        /// RNG seed for payloads
+        #[arg(long, default_value = "1")]
+        seed: u64,
+        /// Keep the scratch workspace at this path instead of a temp dir
+        #[arg(long)]
+        workspace: Option<String>,
+    },
+}
+
+#[cfg(feature = "agent")]
+#[derive(Subcommand)]
+pub enum AgentSubcommand {
+    /// Start the agent (WebSocket / RPC server) for this repository
+    Serve {
+        /// Run as a read-only standby that pulls from this Git remote
+        #[arg(long, value_name = "REMOTE")]
+        replica: Option<String>,
+        /// Branch the replica follows (default: the checked-out branch)
+        #[arg(long, requires = "replica")]
+        branch: Option<String>,
+        /// Seconds between replica pulls
+        #[arg(long, default_value = "15", requires = "replica")]
+        interval: u64,
+    },
+    /// Promote a standby replica to read-write (takes effect in the running agent)
+    Promote {
+        /// Replica workspace (default: current directory)
+        path: Option<String>,
+    },
+    /// Re-execute a recorded session against a scratch workspace
+    Replay {
+        /// Session file written by the agent (ARX_AGENT_RECORD=<file>)
+        file: String,
+        /// Scratch workspace (seeded from the session's starting building.yaml)
+        #[arg(long)]
+        workspace: String,
+        /// Print expected vs actual responses for divergent requests
+        #[arg(long)]
+        verbose: bool,
+    },
+    /// Issue, list, and revoke capability-scoped API keys for automation
+    Keys {
+        #[command(subcommand)]
+        subcommand: AgentKeysSubcommand,
+    },
+}
+
+#[cfg(feature = "agent")]
+#[derive(Subcommand)]
+pub enum AgentKeysSubcommand {
+    /// Issue a key; the secret is printed once and only its hash is stored
+    Create {
+        /// Comma-separated capabilities (e.g. git.status,building.get)
+        #[arg(long, value_delimiter = ',', required = true)]
+        capabilities: Vec<String>,
+        /// Lifetime such as 90d, 12h, or 2w (default: never expires)
+        #[arg(long)]
+        expires: Option<String>,
+        /// Label shown by `arx agent keys list`
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List issued keys (never their secrets)
+    List,
+    /// Revoke a key by id
+    Revoke {
+        /// Key id from `arx agent keys list`
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DatasetSubcommand {
+    /// Aggregate the sensor trace log into sensors.csv + manifest.json
+    Package {
+        /// Output directory
+        #[arg(long, default_value = "dataset")]
+        output: String,
+        /// Time bucket width in minutes (default: .arx/privacy.yaml or 60)
+        #[arg(long)]
+        bucket_minutes: Option<u32>,
+        /// Suppress cells with fewer readings than this (default: .arx/privacy.yaml or 5)
+        #[arg(long)]
+        k_min: Option<u64>,
+        /// Laplace privacy budget (default: .arx/privacy.yaml or 1.0)
+        #[arg(long, conflicts_with = "no_noise")]
+        epsilon: Option<f64>,
+        /// Bucket and suppress without adding noise
+        #[arg(long)]
+        no_noise: bool,
+        /// Publish raw per-reading traces (no privacy layer)
+        #[arg(long, conflicts_with_all = ["bucket_minutes", "k_min", "epsilon", "no_noise"])]
+        raw: bool,
+        /// Only readings at or after this RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+        /// Only readings before this RFC 3339 time
+        #[arg(long)]
+        until: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -397,27 +965,802 @@ pub enum AccessSubcommand {
 }
 
 #[derive(Subcommand)]
-pub enum ImportSubcommand {
-    /// Import IFC (vendor BIM → clean IFC export → arx)
-    Ifc {
-        /// Path to IFC file
-        ifc_file: String,
-        /// Git repository URL
-        #[arg(long)]
-        repo: Option<String>,
-        /// Dry run - show what would be done without making changes
+pub enum DocSubcommand {
+    /// Per-room info pages (temperature, equipment, report-a-problem) + QR codes
+    ///
+    /// Override layout with `.arx/templates/room-page.html` / `room-index.html`.
+    RoomPages {
+        /// Public URL the pages will be served from (encoded in the QR codes)
         #[arg(long)]
-        dry_run: bool,
-        /// Enable strict validation (fail on missing spatial entities)
+        base_url: String,
+        /// Output directory
+        #[arg(long, default_value = "site")]
+        output: String,
+        /// Report-a-problem link ({room_id} / {room_name} substituted)
         #[arg(long)]
-        strict: bool,
-        /// Enable strict address prefix checking
+        report_url: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
         #[arg(long)]
-        strict_addresses: bool,
+        path: Option<String>,
     },
-    /// Import LiDAR point cloud (assistive structure; review proposed entities)
-    Lidar {
-        /// Path to PLY, LAS, or CSV/XYZ file
+}
+
+#[derive(Subcommand)]
+pub enum IssueSubcommand {
+    /// List issues (open only unless --all)
+    List {
+        /// Include resolved / closed issues
+        #[arg(long)]
+        all: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Export issues for CMMS work-request import
+    Export {
+        /// Output format (csv, json)
+        #[arg(long, default_value = "csv")]
+        format: String,
+        /// Output path
+        #[arg(long, default_value = "issues.csv")]
+        output: String,
+        /// Include resolved / closed issues
+        #[arg(long)]
+        all: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorkOrderSubcommand {
+    /// Open a work order on a room and / or piece of equipment
+    Create {
+        /// Short title, e.g. "Replace filter"
+        #[arg(long)]
+        title: String,
+        /// Longer description
+        #[arg(long)]
+        description: Option<String>,
+        /// Room id, name / alias, or address
+        #[arg(long)]
+        room: Option<String>,
+        /// Equipment id, name / alias, or address
+        #[arg(long)]
+        equipment: Option<String>,
+        /// Priority (low, normal, high, urgent)
+        #[arg(long, default_value = "normal")]
+        priority: String,
+        /// Assign on creation
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Issue id this work order addresses
+        #[arg(long)]
+        issue: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// List work orders (open only unless --all)
+    List {
+        /// Include closed work orders
+        #[arg(long)]
+        all: bool,
+        /// Only work orders assigned to this person
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Show one work order with its comments
+    Show {
+        /// Work order id (WO-…)
+        id: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Assign a work order
+    Assign {
+        /// Work order id (WO-…)
+        id: String,
+        /// Person or team
+        #[arg(long)]
+        to: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Add a comment
+    Comment {
+        /// Work order id (WO-…)
+        id: String,
+        /// Comment text
+        #[arg(short, long)]
+        message: String,
+        /// Author (default: configured Git author)
+        #[arg(long)]
+        author: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Close a work order
+    Close {
+        /// Work order id (WO-…)
+        id: String,
+        /// What was done
+        #[arg(long)]
+        resolution: Option<String>,
+        /// Spare part used, `PART` or `PART:QTY` (repeatable); deducted from inventory
+        #[arg(long = "part", value_name = "PART[:QTY]")]
+        parts: Vec<String>,
+        /// Labor cost of the job, e.g. `240` or `240 EUR`
+        #[arg(long)]
+        labor_cost: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MaintenanceSubcommand {
+    /// iCalendar feed of upcoming maintenance, inspections, and warranty expirations
+    Ical {
+        /// Output path
+        #[arg(short, long, default_value = "schedule.ics")]
+        output: String,
+        /// Only events for this assignee (schedule `assignee`, else equipment property)
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Look-ahead window in days
+        #[arg(long, default_value_t = 365)]
+        days: i64,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BookingSubcommand {
+    /// Import bookings from configured calendars (ICS / CalDAV / Microsoft Graph)
+    Import {
+        /// Also read this .ics file
+        #[arg(long)]
+        ics: Option<String>,
+        /// Room booked by every event in --ics (default: each event's LOCATION)
+        #[arg(long)]
+        room: Option<String>,
+        /// Days of history to import
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+        /// Days of upcoming bookings to import
+        #[arg(long, default_value_t = 7)]
+        ahead: i64,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Booked vs occupied hours per room, with ghost meetings
+    Report {
+        /// Days to look back
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AttachmentSubcommand {
+    /// Attach a photo or document to a room or equipment item
+    Add {
+        /// Room or equipment path, id, or name
+        entity: String,
+        /// File to copy into the entity's attachment directory
+        file: String,
+        /// Short description shown in listings
+        #[arg(long)]
+        caption: Option<String>,
+        /// Commit building.yaml and the file as one Git commit
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// List attachments of one room or equipment item (all when omitted)
+    List {
+        /// Room or equipment path, id, or name
+        entity: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Remove an attachment and delete its file
+    Remove {
+        /// Room or equipment path, id, or name
+        entity: String,
+        /// Attachment file name or path
+        file: String,
+        /// Commit building.yaml and the removal as one Git commit
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Upload large attachments to the store and replace them with pointer files
+    Migrate {
+        /// Only move attachments at least this many KiB (default: migrate_min_kb)
+        #[arg(long)]
+        min_size_kb: Option<u64>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Print the local path of an attachment reference, fetching it if needed (all when omitted)
+    Fetch {
+        /// Attachment reference as stored in a property (e.g. photos/lab.jpg)
+        reference: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RoleSubcommand {
+    /// Show role assignments and the role of the current user
+    List {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Assign a role to a user (Git author name, or api-key:<id>); admins only
+    Set {
+        user: String,
+        /// viewer, technician, manager, or admin
+        role: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Drop a user's assignment so the default role applies; admins only
+    Remove {
+        user: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FloorSubcommand {
+    /// Duplicate a floor's rooms and equipment onto other levels (repeated hotel / school floors)
+    ///
+    /// Copies get new ids, rewritten addresses, and a z-offset of one floor
+    /// height per level. Use --dry-run to preview the entities to be created.
+    Clone {
+        /// Source floor level
+        #[arg(long, allow_hyphen_values = true)]
+        from: i32,
+        /// Target levels: `4`, `4..10` (inclusive), or a comma list
+        #[arg(long, allow_hyphen_values = true)]
+        to: String,
+        /// Room names: {room} source name, {floor} target level, {suffix} name
+        /// after the source level (default: `301` → `401`, else "{room}-{floor}")
+        #[arg(long)]
+        rename_pattern: Option<String>,
+        /// Meters between levels (default: from floor elevations or room heights)
+        #[arg(long)]
+        floor_height: Option<f64>,
+        /// Commit the new floors to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TemplateSubcommand {
+    /// Available templates (project templates override built-in ones)
+    List {
+        /// Output format (text, json)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// Print a template's YAML (redirect into .arx/templates/ to customize it)
+    Show { name: String },
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceSubcommand {
+    /// Register a building project (the first one added becomes active)
+    Add {
+        /// Project root containing building.yaml
+        path: String,
+        /// Name in the workspace (default: the building's name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Registered buildings with floor, room, and equipment counts (* = active)
+    List {
+        /// Output format (text, json)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// Make a registered building the active one
+    Switch { name: String },
+    /// Unregister a building (its project is left alone)
+    Remove { name: String },
+    /// Portfolio dashboard: equipment, open alerts, last commit, and validation per building
+    #[cfg(feature = "tui")]
+    Dashboard,
+}
+
+#[derive(Subcommand)]
+pub enum BackupSubcommand {
+    /// Bundle the repository, attachments, and indexes to the backup target
+    Run {
+        /// Skip unless the schedule interval has passed since the newest backup (for cron)
+        #[arg(long)]
+        if_due: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// List stored backups
+    List {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Delete backups past the retention policy
+    Prune {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Restore a backup into a temp dir and validate it (default: newest)
+    Verify {
+        /// Backup id (see `arx backup list`)
+        id: Option<String>,
+        /// Verify every stored backup
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SensorSubcommand {
+    /// Record a reading from a sensor id (adds it to the inbox)
+    Record {
+        /// Sensor id as reported by the gateway
+        sensor_id: String,
+        /// Sensor type (temperature, humidity, co2, ...)
+        #[arg(long = "type")]
+        sensor_type: Option<String>,
+        /// Reading value
+        #[arg(long)]
+        value: Option<f64>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// List inbox sensors without a mapping
+    Unmapped {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Interactive editor: bind unmapped sensors, set thresholds
+    Map {
+        /// Commit the new mappings to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Readings of one sensor, optionally downsampled (min / mean / max per bucket)
+    History {
+        /// Sensor id as reported by the gateway
+        sensor_id: String,
+        /// Only readings at or after this time (RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only readings before this time (RFC 3339)
+        #[arg(long)]
+        until: Option<String>,
+        /// Downsample into buckets of this many minutes
+        #[arg(long)]
+        bucket_minutes: Option<u32>,
+        /// Output format (table, csv, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Feed synthetic sensor streams (daily curves, occupancy, faults) through ingestion
+    Simulate {
+        /// Simulation profile (YAML: interval_secs, seed, sensors with patterns)
+        #[arg(long)]
+        profile: String,
+        /// Hours of history to backfill ending now (default 24); with
+        /// --realtime, how long to run (default: until interrupted)
+        #[arg(long)]
+        hours: Option<f64>,
+        /// Sample live at the profile interval instead of backfilling
+        #[arg(long)]
+        realtime: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Poll Modbus TCP/RTU registers into the sensor inbox and history
+    #[cfg(feature = "modbus")]
+    Modbus {
+        /// Point mapping (default: .arx/modbus.yaml under the project root)
+        #[arg(long)]
+        config: Option<String>,
+        /// One pass instead of polling every `interval_secs`
+        #[arg(long)]
+        once: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long, alias = "building")]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SystemSubcommand {
+    /// Record that one equipment item feeds / powers / controls / serves another
+    Connect {
+        /// Upstream equipment (id, name, or alias)
+        from: String,
+        /// feeds, powers, controls, or serves
+        relation: String,
+        /// Downstream equipment (id, name, or alias)
+        to: String,
+        /// Commit the change to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Remove the edges between two equipment items
+    Disconnect {
+        from: String,
+        to: String,
+        /// Commit the change to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Equipment that loses service if this item fails (or, with --upstream, that it depends on)
+    Impact {
+        equipment: String,
+        /// Trace what the equipment depends on instead
+        #[arg(long)]
+        upstream: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// List every edge
+    List {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Interactive topology view
+    View {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigSubcommand {
+    /// List configuration sources, flag unknown keys, and validate the merged result
+    Doctor {
+        /// Project root holding arx.toml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NotifySubcommand {
+    /// Send a test message to every configured webhook and report the result
+    Test {
+        /// Only this webhook (by name)
+        #[arg(long)]
+        webhook: Option<String>,
+        /// Project root holding .arx/notifications.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MigrateSubcommand {
+    /// Upgrade building.yaml and .arx documents to the current schema_version
+    Schema {
+        /// Project root (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum JobsSubcommand {
+    /// List running jobs
+    List {
+        /// Include finished, failed, cancelled, and interrupted jobs
+        #[arg(long)]
+        all: bool,
+        /// Project root holding .arx/jobs (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Show a job's state, progress, and output log
+    Status {
+        /// Job id
+        id: String,
+        /// Project root holding .arx/jobs (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Ask a running job to stop at its next progress step
+    Cancel {
+        /// Job id
+        id: String,
+        /// Project root holding .arx/jobs (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EnergySubcommand {
+    /// Register a meter on an equipment item or a floor
+    Meter {
+        /// Meter id (e.g. the utility's meter number)
+        id: String,
+        /// electric, gas, or water
+        #[arg(long)]
+        utility: String,
+        /// Metered equipment (id, name, or alias)
+        #[arg(long, conflicts_with = "floor")]
+        equipment: Option<String>,
+        /// Metered floor (id, name, or level)
+        #[arg(long)]
+        floor: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// List registered meters
+    Meters {
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Record consumption for one interval (kWh, therm, or m³)
+    Record {
+        meter: String,
+        value: f64,
+        /// Interval start (RFC 3339; default: end of the previous interval)
+        #[arg(long)]
+        start: Option<String>,
+        /// Interval end (RFC 3339; default: now)
+        #[arg(long)]
+        end: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Import a start,end,value CSV of intervals (e.g. a utility export)
+    Import {
+        meter: String,
+        file: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Consumption per day / week / month with a baseline of the preceding periods
+    Report {
+        /// Building name or id (default: the repository's building)
+        #[arg(long)]
+        building: Option<String>,
+        /// day, week, or month
+        #[arg(long, default_value = "month")]
+        period: String,
+        /// Only intervals starting at or after this time (RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Output format (table, csv, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[cfg(feature = "bacnet")]
+#[derive(Subcommand)]
+pub enum BacnetSubcommand {
+    /// Broadcast Who-Is and list the devices that answer
+    Discover {
+        /// Address to send Who-Is to (default: `broadcast` from .arx/bacnet.yaml)
+        #[arg(long)]
+        target: Option<String>,
+        /// How long to collect I-Am replies
+        #[arg(long, default_value_t = 2000)]
+        wait_ms: u64,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Read mapped points' present values into the sensor inbox and history
+    Poll {
+        /// One pass instead of polling every `interval_secs`
+        #[arg(long)]
+        once: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Add sensor mappings for points that name their equipment
+    Bind {
+        /// Commit the new mappings to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[cfg(feature = "mqtt")]
+#[derive(Subcommand)]
+pub enum MqttSubcommand {
+    /// Publish state changes as building.yaml and Git history change
+    Bridge {
+        /// Publish current state and pending changes once, then exit
+        #[arg(long)]
+        once: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Publish one message to check the broker settings
+    Publish {
+        /// Topic name (no wildcards)
+        topic: String,
+        /// Message payload
+        payload: String,
+        /// Ask the broker to retain the message
+        #[arg(long)]
+        retain: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CommissionSubcommand {
+    /// Put equipment (ids, names, or aliases) into a commissioning project
+    Enroll {
+        /// Equipment to enroll
+        #[arg(required = true)]
+        equipment: Vec<String>,
+        /// Commissioning project name
+        #[arg(long)]
+        project: String,
+        /// Commit the change to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Sign off the next stage, with its evidence
+    ///
+    /// installed / wired need --photo; sensor_mapped needs a sensor mapping;
+    /// tested needs --reading (default: latest logged reading of a mapped sensor).
+    /// An earlier --to stage reopens the equipment and needs --note.
+    Advance {
+        /// Equipment id, name, or alias
+        equipment: String,
+        /// Stage to enter (default: the next one)
+        #[arg(long)]
+        to: Option<String>,
+        /// Who signs off (default: Git author)
+        #[arg(long)]
+        by: Option<String>,
+        /// Repo-relative photo path (repeatable)
+        #[arg(long = "photo")]
+        photos: Vec<String>,
+        /// Test reading as sensor_id=value
+        #[arg(long)]
+        reading: Option<String>,
+        /// Sign-off note
+        #[arg(long)]
+        note: Option<String>,
+        /// Commit the sign-off to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Show the stage and sign-off history of one piece of equipment
+    Status {
+        /// Equipment id, name, or alias
+        equipment: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Progress report per commissioning project
+    Report {
+        /// Only this project
+        #[arg(long)]
+        project: Option<String>,
+        /// Output format (table, json, markdown)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Write the report to this path instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportSubcommand {
+    /// Import IFC (vendor BIM → clean IFC export → arx)
+    Ifc {
+        /// Path to IFC file
+        ifc_file: String,
+        /// Git repository URL
+        #[arg(long)]
+        repo: Option<String>,
+        /// Enable strict validation (fail on missing spatial entities)
+        #[arg(long)]
+        strict: bool,
+        /// Enable strict address prefix checking
+        #[arg(long)]
+        strict_addresses: bool,
+    },
+    /// Import LiDAR point cloud (assistive structure; review proposed entities)
+    Lidar {
+        /// Path to PLY, LAS, E57, or CSV/XYZ file
         file_path: String,
         /// Voxel size in meters for downsampling
         #[arg(long, default_value = "0.05")]
@@ -425,9 +1768,12 @@ pub enum ImportSubcommand {
         /// Enable light mode (aggressive downsampling & lower memory limits)
         #[arg(long)]
         light: bool,
-        /// Dry run - parse and downsample without writing to disk
+        /// Keep every Nth scan point before voxel downsampling
+        #[arg(long, default_value = "1")]
+        decimate: usize,
+        /// Thin the downsampled cloud to at most this many points
         #[arg(long)]
-        dry_run: bool,
+        max_points: Option<usize>,
         /// Merge into an existing building instead of creating new
         #[arg(long)]
         merge: bool,
@@ -442,8 +1788,147 @@ pub enum ImportSubcommand {
         /// Building YAML path or name
         #[arg(long)]
         building: Option<String>,
-        /// Show result without writing
+    },
+    /// Import an equipment inventory from CSV (preview with `arx --dry-run import csv`)
+    Csv {
+        /// CSV file, one row per piece of equipment (save XLSX sheets as CSV)
+        file: String,
+        /// Column mapping YAML (default: .arx/import/equipment-csv.yaml, else export headers)
+        #[arg(long)]
+        mapping: Option<String>,
+        /// Refuse to write if any row is a duplicate, unknown room, or invalid
+        #[arg(long)]
+        strict: bool,
+        /// Commit the imported equipment to Git
+        #[arg(long)]
+        commit: bool,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Import a custom format through a WASM plugin in .arx/plugins/formats
+    Plugin {
+        /// Plugin format name (manifest name or .wasm file stem)
+        format: String,
+        /// Input file handed to the plugin
+        file: String,
+        /// Commit the imported building to Git
         #[arg(long)]
-        dry_run: bool,
+        commit: bool,
+        /// Project root (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuditSubcommand {
+    /// Most recent entries
+    Tail {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value = "20")]
+        lines: usize,
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// Entries matching every given filter, oldest first
+    Search {
+        /// Acting user (exact, case-insensitive)
+        #[arg(long)]
+        actor: Option<String>,
+        /// Action prefix, e.g. `agent.` or `cli.export`
+        #[arg(long)]
+        action: Option<String>,
+        /// Text contained in the target
+        #[arg(long)]
+        target: Option<String>,
+        /// success, failure, or denied
+        #[arg(long)]
+        outcome: Option<String>,
+        /// Only entries at or after this time (RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Show at most this many (the most recent)
+        #[arg(long, default_value = "100")]
+        limit: usize,
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum InventorySubcommand {
+    /// List stocked parts (`!` marks parts below minimum)
+    List {
+        /// Only parts that fit this equipment type
+        #[arg(long = "type")]
+        equipment_type: Option<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Add a spare part
+    Add {
+        /// Part number
+        part_number: String,
+        #[arg(long)]
+        description: Option<String>,
+        /// Quantity in stock
+        #[arg(long, default_value = "0")]
+        quantity: u32,
+        /// Reorder once stock falls below this
+        #[arg(long, default_value = "0")]
+        min_stock: u32,
+        /// Price of one, e.g. `18.40` or `18.40 EUR`
+        #[arg(long)]
+        unit_cost: Option<String>,
+        /// Usual order size
+        #[arg(long)]
+        reorder_quantity: Option<u32>,
+        /// Storage location (room, shelf, bin)
+        #[arg(long)]
+        location: Option<String>,
+        /// Equipment type the part fits (repeatable; default: any)
+        #[arg(long = "equipment-type")]
+        equipment_types: Vec<String>,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Restock (`+10`) or correct the count (`-2`) of a part
+    Adjust {
+        part_number: String,
+        #[arg(allow_hyphen_values = true)]
+        delta: i64,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Reorder report: parts below minimum stock with suggested order quantities
+    LowStock {
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CostSubcommand {
+    /// Cost report grouped by floor, system, month, or equipment (lifetime)
+    Report {
+        /// Grouping (floor, system, month, equipment)
+        #[arg(long, default_value = "floor")]
+        by: String,
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Project root containing building.yaml (default: cwd)
+        #[arg(long)]
+        path: Option<String>,
     },
 }
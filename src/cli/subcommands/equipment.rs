@@ -12,9 +12,12 @@ pub enum EquipmentCommands {
         /// Equipment name
         #[arg(long)]
         name: String,
-        /// Equipment type
+        /// Equipment type (required unless --template gives one)
         #[arg(long)]
-        equipment_type: String,
+        equipment_type: Option<String>,
+        /// Start from a template (`arx template list`); other flags override it
+        #[arg(long)]
+        template: Option<String>,
         /// Equipment position (x,y,z)
         #[arg(long)]
         position: Option<String>,
@@ -70,4 +73,41 @@ pub enum EquipmentCommands {
         #[arg(long)]
         commit: bool,
     },
+    /// Run hours and runtime-based maintenance (.arx/maintenance.yaml)
+    Runtime {
+        /// Equipment ID or name (default: all tracked equipment)
+        equipment: Option<String>,
+        /// Record a service for this maintenance schedule (needs equipment)
+        #[arg(long)]
+        serviced: Option<String>,
+        /// Commit changes to Git
+        #[arg(long)]
+        commit: bool,
+    },
+    /// Availability, MTBF, and MTTR from the equipment's status history
+    Uptime {
+        /// Equipment ID or name
+        equipment: String,
+        /// Days to look back
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+        /// Window start (RFC 3339; overrides --days)
+        #[arg(long)]
+        since: Option<String>,
+        /// Window end (RFC 3339; default: now)
+        #[arg(long)]
+        until: Option<String>,
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// Print QR labels encoding each item's ArxAddress (.pdf sheets or .png)
+    Labels {
+        /// Output file; the extension picks the format (pdf, png)
+        #[arg(long, default_value = "labels.pdf")]
+        output: String,
+        /// Only equipment in this room (ID or name)
+        #[arg(long)]
+        room: Option<String>,
+    },
 }
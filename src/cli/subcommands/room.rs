@@ -18,9 +18,12 @@ pub enum RoomCommands {
         /// Room name
         #[arg(long)]
         name: String,
-        /// Room type
+        /// Room type (required unless --template gives one)
         #[arg(long)]
-        room_type: String,
+        room_type: Option<String>,
+        /// Start from a room template (`arx template list`); other flags override it
+        #[arg(long)]
+        template: Option<String>,
         /// Room dimensions (width x depth x height)
         #[arg(long)]
         dimensions: Option<String>,
@@ -79,4 +82,38 @@ pub enum RoomCommands {
         #[arg(long)]
         commit: bool,
     },
+    /// Reserve a room; refused when it is already booked
+    Book {
+        /// Room ID or name
+        room: String,
+        /// Start time (RFC 3339, e.g. 2026-03-04T09:00:00Z)
+        #[arg(long)]
+        start: String,
+        /// End time (RFC 3339)
+        #[arg(long, conflicts_with = "minutes")]
+        end: Option<String>,
+        /// Length in minutes (instead of --end)
+        #[arg(long)]
+        minutes: Option<i64>,
+        /// Meeting title
+        #[arg(long)]
+        title: Option<String>,
+        /// Organizer (default: acting user)
+        #[arg(long)]
+        organizer: Option<String>,
+    },
+    /// Show bookings, capacity, and current occupancy
+    Schedule {
+        /// Room ID or name (default: every booked room)
+        room: Option<String>,
+        /// Days ahead to show
+        #[arg(long, default_value_t = 7)]
+        days: i64,
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Open the calendar view
+        #[arg(long)]
+        interactive: bool,
+    },
 }
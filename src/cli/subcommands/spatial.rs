@@ -19,17 +19,21 @@ pub enum SpatialCommands {
         #[arg(long)]
         params: Vec<String>,
     },
-    /// Transform coordinates for an entity between systems
+    /// Transform coordinates for an entity between systems, or move its
+    /// stored geometry with `--apply "translate(2,0) rotate(90) scale(1.5)"`
     Transform {
         /// Source coordinate system
-        #[arg(long)]
-        from: String,
+        #[arg(long, required_unless_present = "apply")]
+        from: Option<String>,
         /// Target coordinate system
-        #[arg(long)]
-        to: String,
+        #[arg(long, required_unless_present = "apply")]
+        to: Option<String>,
         /// Entity to transform
         #[arg(long)]
         entity: String,
+        /// Operations to apply to the stored geometry and commit to Git
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        apply: Option<String>,
     },
     /// Validate spatial data
     Validate {
@@ -5,7 +5,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Configuration error types
 #[derive(Debug, thiserror::Error)]
@@ -18,6 +18,33 @@ pub enum ConfigError {
     #[error("Failed to parse config file: {0}")]
     ParseError(#[from] toml::de::Error),
 
+    /// A configuration file is not valid TOML
+    #[error("Failed to parse {}: {message}", path.display())]
+    InvalidFile {
+        /// The file
+        path: PathBuf,
+        /// Parser message, with the offending line
+        message: String,
+    },
+
+    /// A layer set a key to a value of the wrong type
+    #[error("Invalid configuration in {origin}: {message}")]
+    InvalidLayer {
+        /// The file, or "environment"
+        origin: String,
+        /// Deserializer message naming the key
+        message: String,
+    },
+
+    /// An `ARX_*` override does not parse
+    #[error("Invalid {var}: {message}")]
+    InvalidEnv {
+        /// The variable
+        var: String,
+        /// What was expected
+        message: String,
+    },
+
     /// Configuration validation failed
     #[error("Configuration validation failed for '{field}': {message}")]
     ValidationFailed {
@@ -74,6 +101,9 @@ pub struct GitConfig {
     /// Enable GPG signing
     #[serde(default)]
     pub gpg_sign: bool,
+    /// Paths (globs) whose commits must be GPG-signed, e.g. `["building.yaml"]`
+    #[serde(default)]
+    pub signed_paths: Vec<String>,
 }
 
 /// Path configuration
@@ -261,6 +291,7 @@ impl Default for GitConfig {
         Self {
             default_branch: default_branch(),
             gpg_sign: false,
+            signed_paths: Vec::new(),
         }
     }
 }
@@ -312,6 +343,181 @@ impl Default for UiConfig {
     }
 }
 
+/// System-wide configuration file (lowest-precedence file layer)
+pub const SYSTEM_CONFIG: &str = "/etc/arxos/config.toml";
+
+/// Repository-local configuration file, next to building.yaml
+pub const REPO_CONFIG: &str = "arx.toml";
+
+/// Environment overrides and the keys they set
+const ENV_KEYS: &[(&str, &str)] = &[
+    ("ARX_USER_NAME", "user.name"),
+    ("ARX_USER_EMAIL", "user.email"),
+    ("ARX_USER_ORGANIZATION", "user.organization"),
+    ("ARX_GIT_BRANCH", "git.default_branch"),
+    ("ARX_GPG_SIGN", "git.gpg_sign"),
+    (
+        "ARX_COORDINATE_SYSTEM",
+        "building.default_coordinate_system",
+    ),
+    ("ARX_AUTO_COMMIT", "building.auto_commit"),
+    ("ARX_MAX_THREADS", "performance.max_parallel_threads"),
+    ("ARX_MEMORY_LIMIT", "performance.memory_limit_mb"),
+    ("ARX_CACHE_ENABLED", "performance.cache_enabled"),
+    ("ARX_USE_EMOJI", "ui.use_emoji"),
+    ("ARX_VERBOSITY", "ui.verbosity"),
+    ("ARX_COLOR_SCHEME", "ui.color_scheme"),
+];
+
+/// Keys that are valid but absent from the serialized defaults
+const OPTIONAL_KEYS: &[&str] = &["user.organization"];
+
+/// Where a configuration layer comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Built-in defaults
+    Defaults,
+    /// [`SYSTEM_CONFIG`]
+    System(PathBuf),
+    /// `~/.arxos/config.toml` (`%APPDATA%\arxos\config.toml` on Windows)
+    User(PathBuf),
+    /// `.arxos/config.toml` in the project
+    Project(PathBuf),
+    /// [`REPO_CONFIG`] in the project
+    Repo(PathBuf),
+    /// `ARX_*` environment variables
+    Env,
+}
+
+impl ConfigSource {
+    /// File backing this source, if any
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ConfigSource::System(p)
+            | ConfigSource::User(p)
+            | ConfigSource::Project(p)
+            | ConfigSource::Repo(p) => Some(p),
+            ConfigSource::Defaults | ConfigSource::Env => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Defaults => f.write_str("defaults"),
+            ConfigSource::Env => f.write_str("environment"),
+            other => write!(f, "{}", other.path().unwrap_or(Path::new("")).display()),
+        }
+    }
+}
+
+/// One source's values, as written (not yet merged)
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    pub source: ConfigSource,
+    pub values: toml::Table,
+}
+
+/// A key no configuration field reads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    pub source: ConfigSource,
+    /// Dotted path, e.g. `performance.max_threads`
+    pub key: String,
+    /// Closest known key, when one is near
+    pub suggestion: Option<String>,
+}
+
+/// `arx config doctor` findings
+#[derive(Debug)]
+pub struct ConfigDoctor {
+    /// File sources in precedence order and whether each exists
+    pub files: Vec<(ConfigSource, bool)>,
+    /// Environment overrides that are set
+    pub env_vars: Vec<&'static str>,
+    pub unknown_keys: Vec<UnknownKey>,
+    /// Parse, type, or validation failure of the merged configuration
+    pub error: Option<ConfigError>,
+}
+
+impl ConfigDoctor {
+    /// True when nothing needs attention
+    pub fn is_healthy(&self) -> bool {
+        self.unknown_keys.is_empty() && self.error.is_none()
+    }
+}
+
+fn defaults_table() -> toml::Table {
+    match toml::Value::try_from(ArxConfig::default()) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => toml::Table::new(),
+    }
+}
+
+/// Overlay `layer` onto `base`, descending into tables
+fn merge_table(base: &mut toml::Table, layer: &toml::Table) {
+    for (key, value) in layer {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(layer)) => merge_table(base, layer),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Dotted paths of every leaf key in `table`
+fn key_paths(table: &toml::Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            toml::Value::Table(inner) => key_paths(inner, &path, out),
+            _ => out.push(path),
+        }
+    }
+}
+
+fn lookup<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    let (section, rest) = key.split_once('.')?;
+    table.get(section)?.as_table()?.get(rest)
+}
+
+fn set_key(table: &mut toml::Table, key: &str, value: toml::Value) {
+    if let Some((section, rest)) = key.split_once('.') {
+        let section = table
+            .entry(section)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if let toml::Value::Table(section) = section {
+            section.insert(rest.to_string(), value);
+        }
+    }
+}
+
+/// Levenshtein distance, for "did you mean" suggestions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(cur).min(row[j])
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
 /// Configuration manager for loading and saving config
 #[derive(Default)]
 pub struct ConfigManager {
@@ -323,142 +529,208 @@ impl ConfigManager {
     ///
     /// Precedence (highest to lowest):
     /// 1. Environment variables (ARX_* prefix)
-    /// 2. Project config (.arxos/config.toml in current directory)
-    /// 3. User config (~/.arxos/config.toml)
-    /// 4. Defaults
+    /// 2. Repository config (arx.toml in current directory)
+    /// 3. Project config (.arxos/config.toml in current directory)
+    /// 4. User config (~/.arxos/config.toml)
+    /// 5. System config (/etc/arxos/config.toml)
+    /// 6. Defaults
+    ///
+    /// Layers merge key by key, so a file only needs the keys it changes.
     pub fn new() -> Result<Self, ConfigError> {
-        // Start with defaults
-        let mut config = ArxConfig::default();
-
-        // Load user config if exists
-        if let Ok(user_config) = Self::load_user_config() {
-            Self::merge_config(&mut config, user_config);
-        }
-
-        // Load project config if exists (overrides user config)
-        if let Ok(project_config) = Self::load_project_config() {
-            Self::merge_config(&mut config, project_config);
-        }
-
-        // Apply environment variable overrides (highest priority)
-        Self::apply_env_overrides(&mut config);
+        Self::load_layered(Path::new("."))
+    }
 
-        // Validate the final configuration
+    /// [`ConfigManager::new`] with project files looked up under `root`
+    pub fn load_layered(root: &Path) -> Result<Self, ConfigError> {
+        let config = Self::merge_layers(&Self::layers(root)?)?;
         Self::validate_config(&config)?;
-
         Ok(Self { config })
     }
 
-    /// Load user config from ~/.arxos/config.toml
-    fn load_user_config() -> Result<ArxConfig, ConfigError> {
-        let config_path = if cfg!(windows) {
+    /// Candidate configuration files under `root`, lowest precedence first
+    pub fn config_files(root: &Path) -> Vec<ConfigSource> {
+        let user = if cfg!(windows) {
             // Windows: %APPDATA%\arxos\config.toml or fallback to HOME
-            if let Ok(appdata) = env::var("APPDATA") {
-                PathBuf::from(appdata).join("arxos").join("config.toml")
-            } else {
-                default_data_dir().join("config.toml")
+            match env::var("APPDATA") {
+                Ok(appdata) => PathBuf::from(appdata).join("arxos").join("config.toml"),
+                Err(_) => default_data_dir().join("config.toml"),
             }
         } else {
-            // Unix: ~/.arxos/config.toml
             default_data_dir().join("config.toml")
         };
-
-        if config_path.exists() {
-            let contents = std::fs::read_to_string(&config_path)?;
-            let config = toml::from_str(&contents)?;
-            Ok(config)
-        } else {
-            Err(ConfigError::IoError(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "User config not found",
-            )))
-        }
+        vec![
+            ConfigSource::System(PathBuf::from(SYSTEM_CONFIG)),
+            ConfigSource::User(user),
+            ConfigSource::Project(root.join(".arxos").join("config.toml")),
+            ConfigSource::Repo(root.join(REPO_CONFIG)),
+        ]
     }
 
-    /// Load project config from .arxos/config.toml in current directory
-    fn load_project_config() -> Result<ArxConfig, ConfigError> {
-        let config_path = PathBuf::from(".arxos").join("config.toml");
-
-        if config_path.exists() {
-            let contents = std::fs::read_to_string(&config_path)?;
-            let config = toml::from_str(&contents)?;
-            Ok(config)
-        } else {
-            Err(ConfigError::IoError(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Project config not found",
-            )))
+    /// Read every present layer under `root`, environment last
+    pub fn layers(root: &Path) -> Result<Vec<ConfigLayer>, ConfigError> {
+        let mut layers = Vec::new();
+        for source in Self::config_files(root) {
+            let Some(path) = source.path().filter(|p| p.exists()) else {
+                continue;
+            };
+            let contents = std::fs::read_to_string(path)?;
+            let values = contents
+                .parse::<toml::Table>()
+                .map_err(|e| ConfigError::InvalidFile {
+                    path: path.to_path_buf(),
+                    message: e.to_string(),
+                })?;
+            layers.push(ConfigLayer { source, values });
         }
+        layers.push(ConfigLayer {
+            source: ConfigSource::Env,
+            values: Self::env_layer()?,
+        });
+        Ok(layers)
     }
 
-    /// Merge source config into target config (non-default values only)
-    fn merge_config(target: &mut ArxConfig, source: ArxConfig) {
-        // For simplicity, we'll replace entire sections if they differ from defaults
-        // A more sophisticated merge would check individual fields
-        target.user = source.user;
-        target.git = source.git;
-        target.paths = source.paths;
-        target.building = source.building;
-        target.performance = source.performance;
-        target.ui = source.ui;
-    }
-
-    /// Apply environment variable overrides (ARX_* prefix)
-    fn apply_env_overrides(config: &mut ArxConfig) {
-        // User overrides
-        if let Ok(val) = env::var("ARX_USER_NAME") {
-            config.user.name = val;
-        }
-        if let Ok(val) = env::var("ARX_USER_EMAIL") {
-            config.user.email = val;
-        }
-        if let Ok(val) = env::var("ARX_USER_ORGANIZATION") {
-            config.user.organization = Some(val);
+    /// Values set by `ARX_*` variables, typed like the keys they override
+    pub fn env_layer() -> Result<toml::Table, ConfigError> {
+        let defaults = defaults_table();
+        let mut table = toml::Table::new();
+        for (var, key) in ENV_KEYS {
+            let Ok(raw) = env::var(var) else {
+                continue;
+            };
+            let value = match lookup(&defaults, key) {
+                Some(toml::Value::Boolean(_)) => {
+                    raw.trim().parse().map(toml::Value::Boolean).map_err(|_| {
+                        ConfigError::InvalidEnv {
+                            var: var.to_string(),
+                            message: format!("expected true or false, got '{}'", raw),
+                        }
+                    })?
+                }
+                Some(toml::Value::Integer(_)) => {
+                    raw.trim().parse().map(toml::Value::Integer).map_err(|_| {
+                        ConfigError::InvalidEnv {
+                            var: var.to_string(),
+                            message: format!("expected a whole number, got '{}'", raw),
+                        }
+                    })?
+                }
+                _ => toml::Value::String(raw),
+            };
+            set_key(&mut table, key, value);
         }
+        Ok(table)
+    }
 
-        // Git overrides
-        if let Ok(val) = env::var("ARX_GIT_BRANCH") {
-            config.git.default_branch = val;
-        }
-        if let Ok(val) = env::var("ARX_GPG_SIGN") {
-            config.git.gpg_sign = val.parse().unwrap_or(false);
+    /// Merge layers over the defaults, in order
+    ///
+    /// The merged values are checked after each layer, so a type error names
+    /// the file (or the environment) that introduced it.
+    pub fn merge_layers(layers: &[ConfigLayer]) -> Result<ArxConfig, ConfigError> {
+        let mut merged = defaults_table();
+        let mut config = ArxConfig::default();
+        for layer in layers {
+            merge_table(&mut merged, &layer.values);
+            config =
+                toml::Value::Table(merged.clone())
+                    .try_into()
+                    .map_err(|e: toml::de::Error| ConfigError::InvalidLayer {
+                        origin: layer.source.to_string(),
+                        message: e.message().to_string(),
+                    })?;
         }
+        Ok(config)
+    }
 
-        // Building overrides
-        if let Ok(val) = env::var("ARX_COORDINATE_SYSTEM") {
-            config.building.default_coordinate_system = val;
-        }
-        if let Ok(val) = env::var("ARX_AUTO_COMMIT") {
-            config.building.auto_commit = val.parse().unwrap_or(true);
-        }
+    /// Keys in `layer` that no configuration field reads
+    pub fn unknown_keys(layer: &ConfigLayer) -> Vec<UnknownKey> {
+        let mut known = Vec::new();
+        key_paths(&defaults_table(), "", &mut known);
+        known.extend(OPTIONAL_KEYS.iter().map(|k| k.to_string()));
+
+        let mut keys = Vec::new();
+        key_paths(&layer.values, "", &mut keys);
+        keys.into_iter()
+            .filter(|key| !known.contains(key))
+            .map(|key| {
+                // A typo anywhere, or a loosely remembered name in the same section
+                let (section, leaf) = key.split_once('.').unwrap_or(("", key.as_str()));
+                let suggestion = known
+                    .iter()
+                    .filter_map(|k| {
+                        let distance = edit_distance(&key, k);
+                        let (known_section, known_leaf) = k.split_once('.')?;
+                        let near = distance <= 3
+                            || (known_section == section
+                                && distance <= leaf.len().max(known_leaf.len()) / 2);
+                        near.then_some((distance, k))
+                    })
+                    .min_by_key(|(d, _)| *d)
+                    .map(|(_, k)| k.clone());
+                UnknownKey {
+                    source: layer.source.clone(),
+                    key,
+                    suggestion,
+                }
+            })
+            .collect()
+    }
 
-        // Performance overrides
-        if let Ok(val) = env::var("ARX_MAX_THREADS") {
-            if let Ok(num) = val.parse() {
-                config.performance.max_parallel_threads = num;
-            }
-        }
-        if let Ok(val) = env::var("ARX_MEMORY_LIMIT") {
-            if let Ok(num) = val.parse() {
-                config.performance.memory_limit_mb = num;
+    /// Inspect the configuration under `root` without failing fast
+    pub fn doctor(root: &Path) -> ConfigDoctor {
+        let files = Self::config_files(root)
+            .into_iter()
+            .map(|source| {
+                let exists = source.path().is_some_and(|p| p.exists());
+                (source, exists)
+            })
+            .collect();
+        let env_vars = ENV_KEYS
+            .iter()
+            .filter(|(var, _)| env::var(var).is_ok())
+            .map(|(var, _)| *var)
+            .collect();
+
+        let (unknown_keys, error) = match Self::layers(root) {
+            Ok(layers) => {
+                let unknown = layers.iter().flat_map(Self::unknown_keys).collect();
+                let error = Self::merge_layers(&layers)
+                    .and_then(|config| Self::validate_config(&config))
+                    .err();
+                (unknown, error)
             }
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        ConfigDoctor {
+            files,
+            env_vars,
+            unknown_keys,
+            error,
         }
-        if let Ok(val) = env::var("ARX_CACHE_ENABLED") {
-            config.performance.cache_enabled = val.parse().unwrap_or(true);
-        }
+    }
 
-        // UI overrides
-        if let Ok(val) = env::var("ARX_USE_EMOJI") {
-            config.ui.use_emoji = val.parse().unwrap_or(true);
-        }
-        if let Ok(val) = env::var("ARX_VERBOSITY") {
-            config.ui.verbosity = val;
-        }
-        if let Ok(val) = env::var("ARX_COLOR_SCHEME") {
-            config.ui.color_scheme = val;
+    /// Apply environment variable overrides (ARX_* prefix)
+    ///
+    /// Best effort: if any variable does not parse, none are applied; the
+    /// loader reports the bad variable instead.
+    pub fn apply_env_overrides(config: &mut ArxConfig) {
+        let base = ConfigLayer {
+            source: ConfigSource::Defaults,
+            values: match toml::Value::try_from(&*config) {
+                Ok(toml::Value::Table(table)) => table,
+                _ => return,
+            },
+        };
+        let Ok(values) = Self::env_layer() else {
+            return;
+        };
+        let env = ConfigLayer {
+            source: ConfigSource::Env,
+            values,
+        };
+        if let Ok(merged) = Self::merge_layers(&[base, env]) {
+            *config = merged;
         }
     }
-
     /// Validate configuration
     pub fn validate_config(config: &ArxConfig) -> Result<(), ConfigError> {
         // Validate user email
@@ -582,10 +854,14 @@ impl ConfigManager {
         Ok(())
     }
 
-    /// Load config from specific file
+    /// Load config from specific file (over the defaults)
     pub fn load(path: &PathBuf) -> Result<Self, ConfigError> {
         let contents = std::fs::read_to_string(path)?;
-        let config = toml::from_str(&contents)?;
+        let layer = ConfigLayer {
+            source: ConfigSource::Project(path.clone()),
+            values: contents.parse::<toml::Table>()?,
+        };
+        let config = Self::merge_layers(&[layer])?;
         Self::validate_config(&config)?;
         Ok(Self { config })
     }
@@ -687,6 +963,71 @@ mod tests {
         env::remove_var("ARX_MAX_THREADS");
     }
 
+    #[test]
+    fn test_layers_merge_key_by_key_and_name_bad_source() {
+        let layer = |source, text: &str| ConfigLayer {
+            source,
+            values: text.parse().unwrap(),
+        };
+        let user = layer(
+            ConfigSource::User("user.toml".into()),
+            "[user]\nname = \"Ada\"\nemail = \"ada@example.com\"\n[ui]\nuse_emoji = false",
+        );
+        let repo = layer(
+            ConfigSource::Repo("arx.toml".into()),
+            "[performance]\nmax_parallel_threads = 12",
+        );
+        let config = ConfigManager::merge_layers(&[user.clone(), repo]).unwrap();
+        assert_eq!(config.user.name, "Ada");
+        assert!(!config.ui.use_emoji);
+        assert_eq!(config.ui.verbosity, "Normal");
+        assert_eq!(config.performance.max_parallel_threads, 12);
+
+        let bad = layer(
+            ConfigSource::Repo("arx.toml".into()),
+            "[performance]\nmax_parallel_threads = \"many\"",
+        );
+        match ConfigManager::merge_layers(&[user, bad]) {
+            Err(ConfigError::InvalidLayer { origin, .. }) => assert_eq!(origin, "arx.toml"),
+            other => panic!("expected InvalidLayer, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_doctor_reports_unknown_keys_with_suggestions() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(REPO_CONFIG),
+            "[performance]\nmax_threads = 8\n[user]\norganization = \"ACME\"\n[plugins]\nenabled = true\n",
+        )
+        .unwrap();
+
+        let doctor = ConfigManager::doctor(dir.path());
+        assert!(doctor
+            .files
+            .contains(&(ConfigSource::Repo(dir.path().join(REPO_CONFIG)), true)));
+        let repo: Vec<_> = doctor
+            .unknown_keys
+            .iter()
+            .filter(|k| k.source == ConfigSource::Repo(dir.path().join(REPO_CONFIG)))
+            .collect();
+        assert_eq!(repo.len(), 2);
+        assert_eq!(repo[0].key, "performance.max_threads");
+        assert_eq!(
+            repo[0].suggestion.as_deref(),
+            Some("performance.max_parallel_threads")
+        );
+        assert_eq!(repo[1].key, "plugins.enabled");
+        assert_eq!(repo[1].suggestion, None);
+        assert!(!doctor.is_healthy());
+
+        std::fs::write(dir.path().join(REPO_CONFIG), "[ui\n").unwrap();
+        assert!(matches!(
+            ConfigManager::doctor(dir.path()).error,
+            Some(ConfigError::InvalidFile { .. })
+        ));
+    }
+
     #[test]
     fn test_default_manager() {
         let manager = ConfigManager::default();
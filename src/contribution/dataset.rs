@@ -0,0 +1,317 @@
+//! Sensor dataset packages for the data economy.
+//!
+//! A package is a CSV of sensor data plus `manifest.json` describing it. Only
+//! readings from sensors mapped into the building are published, labelled by
+//! zone (`floor/wing/room`) rather than sensor id when aggregated. The privacy
+//! layer ([`super::privacy`]) runs during packaging and its parameters are
+//! recorded in the manifest; raw packages say so with `privacy: null`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::Building;
+use crate::persistence::sensors::SensorReading;
+
+use super::privacy::{privatize, PrivacyParameters, PrivacyPolicy, TraceReading};
+
+/// Manifest file written next to the data.
+pub const DATASET_MANIFEST_FILE: &str = "manifest.json";
+
+/// Data file written into the package directory.
+pub const DATASET_DATA_FILE: &str = "sensors.csv";
+
+/// Where a mapped sensor sits in the building.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensorPlacement {
+    pub zone: String,
+    pub sensor_type: String,
+}
+
+/// Options for packaging a sensor dataset.
+#[derive(Debug, Clone, Default)]
+pub struct DatasetOptions {
+    /// Privacy policy to apply; `None` publishes raw readings
+    pub privacy: Option<PrivacyPolicy>,
+    pub git_commit: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Serializable description of a packaged dataset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    pub package_version: u32,
+    /// Dataset identifier used in `ContributionRecord::dataset_id`.
+    pub dataset_id: String,
+    pub building_id: String,
+    pub building_name: String,
+    pub git_commit: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Covered period (first / last published reading).
+    pub period_start: Option<DateTime<Utc>>,
+    pub period_end: Option<DateTime<Utc>>,
+    /// `aggregated` or `raw`.
+    pub kind: String,
+    pub data_file: String,
+    pub rows: usize,
+    /// SHA-256 (hex) of the data file.
+    pub data_sha256: String,
+    /// Readings skipped because their sensor is not mapped in the building.
+    pub unmapped_readings: usize,
+    /// Privacy parameters applied, or `None` for a raw release.
+    pub privacy: Option<PrivacyParameters>,
+    pub summary: String,
+}
+
+/// Zone and type of every sensor mapped in `building`.
+pub fn sensor_placements(building: &Building) -> HashMap<String, SensorPlacement> {
+    let mut out = HashMap::new();
+    for floor in &building.floors {
+        let mut place = |zone: String, equipment: &[crate::core::Equipment]| {
+            for eq in equipment {
+                for m in eq.sensor_mappings.iter().flatten() {
+                    out.insert(
+                        m.sensor_id.clone(),
+                        SensorPlacement {
+                            zone: zone.clone(),
+                            sensor_type: m.sensor_type.clone(),
+                        },
+                    );
+                }
+            }
+        };
+        place(floor.name.clone(), &floor.equipment);
+        for wing in &floor.wings {
+            place(format!("{}/{}", floor.name, wing.name), &wing.equipment);
+            for room in &wing.rooms {
+                place(
+                    format!("{}/{}/{}", floor.name, wing.name, room.name),
+                    &room.equipment,
+                );
+            }
+        }
+    }
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn opt_number(v: Option<f64>) -> String {
+    v.map(|x| format!("{:.3}", x)).unwrap_or_default()
+}
+
+/// Build the CSV and manifest for `readings` (the trace log) under `opts`.
+pub fn build_sensor_dataset<R: Rng + ?Sized>(
+    building: &Building,
+    readings: &[SensorReading],
+    opts: &DatasetOptions,
+    rng: &mut R,
+) -> Result<(DatasetManifest, String), String> {
+    let placements = sensor_placements(building);
+    let mut unmapped = 0;
+    let mut placed = Vec::new();
+    for r in readings {
+        if opts.since.is_some_and(|t| r.at < t) || opts.until.is_some_and(|t| r.at >= t) {
+            continue;
+        }
+        match placements.get(&r.sensor_id) {
+            Some(p) => placed.push((r, p)),
+            None => unmapped += 1,
+        }
+    }
+
+    let mut csv = String::new();
+    let (rows, privacy, period) = match &opts.privacy {
+        Some(policy) => {
+            let trace: Vec<TraceReading> = placed
+                .iter()
+                .map(|(r, p)| TraceReading {
+                    at: r.at,
+                    zone: p.zone.clone(),
+                    sensor_type: p.sensor_type.clone(),
+                    value: r.value,
+                })
+                .collect();
+            let (rows, params) = privatize(&trace, policy, rng)?;
+            csv.push_str("bucket_start,zone,sensor_type,readings,total,mean\n");
+            for row in &rows {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    row.bucket_start.to_rfc3339(),
+                    csv_field(&row.zone),
+                    csv_field(&row.sensor_type),
+                    row.readings,
+                    opt_number(row.total),
+                    opt_number(row.mean)
+                ));
+            }
+            let width = chrono::Duration::minutes(i64::from(policy.bucket_minutes));
+            let period = (
+                rows.iter().map(|r| r.bucket_start).min(),
+                rows.iter().map(|r| r.bucket_start + width).max(),
+            );
+            (rows.len(), Some(params), period)
+        }
+        None => {
+            csv.push_str("at,sensor_id,zone,sensor_type,value\n");
+            for (r, p) in &placed {
+                csv.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    r.at.to_rfc3339(),
+                    csv_field(&r.sensor_id),
+                    csv_field(&p.zone),
+                    csv_field(&p.sensor_type),
+                    r.value
+                ));
+            }
+            let period = (
+                placed.iter().map(|(r, _)| r.at).min(),
+                placed.iter().map(|(r, _)| r.at).max(),
+            );
+            (placed.len(), None, period)
+        }
+    };
+
+    let created_at = Utc::now();
+    let kind = if privacy.is_some() {
+        "aggregated"
+    } else {
+        "raw"
+    };
+    let data_sha256 = format!("{:x}", Sha256::digest(csv.as_bytes()));
+    let summary = format!(
+        "dataset kind={} rows={} privacy={}",
+        kind,
+        rows,
+        privacy
+            .as_ref()
+            .map(|p| format!(
+                "{} epsilon={} k={} bucket={}m",
+                p.mechanism,
+                p.epsilon.map_or("-".into(), |e| e.to_string()),
+                p.k_min,
+                p.bucket_minutes
+            ))
+            .unwrap_or_else(|| "none".into())
+    );
+    let manifest = DatasetManifest {
+        package_version: 1,
+        dataset_id: format!(
+            "sensors-{}-{}",
+            &building.id[..8.min(building.id.len())],
+            created_at.format("%Y%m%dT%H%M%SZ")
+        ),
+        building_id: building.id.clone(),
+        building_name: building.name.clone(),
+        git_commit: opts.git_commit.clone(),
+        created_at,
+        period_start: period.0,
+        period_end: period.1,
+        kind: kind.to_string(),
+        data_file: DATASET_DATA_FILE.to_string(),
+        rows,
+        data_sha256,
+        unmapped_readings: unmapped,
+        privacy,
+        summary,
+    };
+    Ok((manifest, csv))
+}
+
+/// Write `sensors.csv` and `manifest.json` into `dir`.
+pub fn write_sensor_dataset(
+    dir: &Path,
+    manifest: &DatasetManifest,
+    csv: &str,
+) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("create {}: {}", dir.display(), e))?;
+    std::fs::write(dir.join(&manifest.data_file), csv).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(DATASET_MANIFEST_FILE), json).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::operations::{bind_sensor, SensorBindTarget};
+    use crate::core::{Floor, Room, RoomType, Wing};
+    use chrono::TimeZone;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn packages_only_mapped_sensors_and_records_privacy() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let room = Room::new("Lab, North".into(), RoomType::Laboratory);
+        let room_id = room.id.clone();
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        bind_sensor(
+            &mut building,
+            "pir-1",
+            "occupancy",
+            &SensorBindTarget::Room(room_id),
+            None,
+        )
+        .unwrap();
+
+        let start = Utc.timestamp_opt(1_699_999_200, 0).unwrap();
+        let mut readings: Vec<SensorReading> = (0..8)
+            .map(|m| SensorReading {
+                sensor_id: "pir-1".into(),
+                sensor_type: None,
+                at: start + chrono::Duration::minutes(m),
+                value: 1.0,
+            })
+            .collect();
+        readings.push(SensorReading {
+            sensor_id: "stray".into(),
+            sensor_type: Some("occupancy".into()),
+            at: start,
+            value: 4.0,
+        });
+
+        let opts = DatasetOptions {
+            privacy: Some(PrivacyPolicy {
+                epsilon: None,
+                ..PrivacyPolicy::default()
+            }),
+            ..DatasetOptions::default()
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        let (manifest, csv) = build_sensor_dataset(&building, &readings, &opts, &mut rng).unwrap();
+        assert_eq!(manifest.kind, "aggregated");
+        assert_eq!(manifest.unmapped_readings, 1);
+        assert_eq!(manifest.rows, 1);
+        assert_eq!(manifest.privacy.as_ref().unwrap().k_min, 5);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "2023-11-14T22:00:00+00:00,\"Ground/East/Lab, North\",occupancy,8,8.000,1.000"
+        );
+        assert!(!csv.contains("pir-1"));
+
+        let raw = DatasetOptions::default();
+        let (manifest, csv) = build_sensor_dataset(&building, &readings, &raw, &mut rng).unwrap();
+        assert_eq!((manifest.kind.as_str(), manifest.rows), ("raw", 8));
+        assert!(manifest.privacy.is_none());
+        assert_eq!(
+            manifest.data_sha256,
+            format!("{:x}", Sha256::digest(csv.as_bytes()))
+        );
+    }
+}
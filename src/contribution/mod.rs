@@ -8,14 +8,21 @@
 //! Sensor-based hashing remains in `blockchain::contribution` as a secondary path only.
 
 mod commitment;
+mod dataset;
 mod package;
+pub mod privacy;
 mod quality;
 
 pub use commitment::{
     building_content_hash, building_entity_merkle_root, commit_building, BuildingCommitment,
     HASH_ALG_LABEL,
 };
+pub use dataset::{
+    build_sensor_dataset, sensor_placements, write_sensor_dataset, DatasetManifest,
+    DatasetOptions, SensorPlacement, DATASET_DATA_FILE, DATASET_MANIFEST_FILE,
+};
 pub use package::{
     build_contribution_package, parse_hex32, ContributionPackage, PackageOptions,
 };
+pub use privacy::{PrivacyParameters, PrivacyPolicy};
 pub use quality::{quality_from_building, QualityScores};
@@ -0,0 +1,285 @@
+//! Role-based authorization for building edits.
+//!
+//! Roles are assigned per user in [`ROLES_FILE`]; without that file nothing is
+//! enforced. When it exists, every save of building.yaml diffs the building
+//! against the stored copy and checks each created, updated, or deleted entity
+//! against the role of the acting user: the user installed by an
+//! [`ActingUserGuard`] (agent callers, the field app), else the user in
+//! [`ACTING_USER_ENV`] (CLI commands the agent runs for a caller), else the
+//! configured Git author.
+//!
+//! | role       | may change                                                  |
+//! |------------|-------------------------------------------------------------|
+//! | viewer     | nothing                                                     |
+//! | technician | update rooms and equipment, add equipment                   |
+//! | manager    | create, update, and delete floors, wings, rooms, equipment  |
+//! | admin      | everything, including building fields and role assignments  |
+
+use super::operations::dry_run::{Change, ChangeKind};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Repo-relative role assignments.
+pub const ROLES_FILE: &str = ".arx/roles.yaml";
+
+/// Environment variable carrying the acting user into a child `arx` process.
+pub const ACTING_USER_ENV: &str = "ARX_ACTING_USER";
+
+/// What a user may do to the building.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    Viewer,
+    Technician,
+    Manager,
+    Admin,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Viewer, Role::Technician, Role::Manager, Role::Admin];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Technician => "technician",
+            Role::Manager => "manager",
+            Role::Admin => "admin",
+        }
+    }
+
+    /// Whether this role may make a change of `kind` to an entity of type
+    /// `entity` (`building`, `floor`, `wing`, `room`, `equipment`).
+    pub fn allows(self, kind: ChangeKind, entity: &str) -> bool {
+        match self {
+            Role::Viewer => false,
+            Role::Technician => match kind {
+                ChangeKind::Modify => matches!(entity, "room" | "equipment"),
+                ChangeKind::Add => entity == "equipment",
+                _ => false,
+            },
+            Role::Manager => entity != "building",
+            Role::Admin => true,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Role::ALL
+            .into_iter()
+            .find(|r| r.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown role '{}'. Use: viewer, technician, manager, admin",
+                    s
+                )
+            })
+    }
+}
+
+/// Contents of [`ROLES_FILE`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessPolicy {
+    /// Role of users not listed
+    #[serde(default)]
+    pub default_role: Role,
+    /// User (Git author name, or `api-key:<id>` for agent keys) → role
+    #[serde(default)]
+    pub users: BTreeMap<String, Role>,
+}
+
+impl AccessPolicy {
+    /// The policy under `base`, or `None` when roles are not configured.
+    pub fn load(base: &Path) -> Result<Option<Self>> {
+        let path = base.join(ROLES_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, base: &Path) -> Result<()> {
+        let path = base.join(ROLES_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, crate::yaml::to_canonical_yaml(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    pub fn role_of(&self, user: &str) -> Role {
+        self.users.get(user).copied().unwrap_or(self.default_role)
+    }
+
+    /// Check every entity change in `changes` against the role of `user`.
+    pub fn authorize(&self, user: &str, changes: &[Change]) -> Result<(), AccessDenied> {
+        authorize_role(user, self.role_of(user), changes)
+    }
+}
+
+/// A change the acting user's role does not permit.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Permission denied: {user} ({role}) may not {action} {target}")]
+pub struct AccessDenied {
+    pub user: String,
+    pub role: Role,
+    pub action: &'static str,
+    pub target: String,
+}
+
+/// Check `changes` against `role`; Git changes are not entity edits and pass.
+pub fn authorize_role(user: &str, role: Role, changes: &[Change]) -> Result<(), AccessDenied> {
+    for change in changes {
+        let action = match change.kind {
+            ChangeKind::Add => "create",
+            ChangeKind::Modify => "update",
+            ChangeKind::Remove => "delete",
+            ChangeKind::Git => continue,
+        };
+        let entity = change.target.split(' ').next().unwrap_or_default();
+        if !role.allows(change.kind, entity) {
+            return Err(AccessDenied {
+                user: user.to_string(),
+                role,
+                action,
+                target: change.target.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+thread_local! {
+    static ACTING_USER: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Sets the user edits on the current thread are made on behalf of until
+/// dropped; guards nest and restore whatever was set before.
+pub struct ActingUserGuard {
+    previous: Option<String>,
+}
+
+impl ActingUserGuard {
+    pub fn act_as(user: impl Into<String>) -> Self {
+        Self {
+            previous: ACTING_USER.with(|u| u.replace(Some(user.into()))),
+        }
+    }
+}
+
+impl Drop for ActingUserGuard {
+    fn drop(&mut self) {
+        ACTING_USER.with(|u| *u.borrow_mut() = self.previous.take());
+    }
+}
+
+/// User installed by the current guard, else the one in [`ACTING_USER_ENV`].
+pub fn acting_user() -> Option<String> {
+    ACTING_USER.with(|u| u.borrow().clone()).or_else(|| {
+        std::env::var(ACTING_USER_ENV)
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(kind: ChangeKind, target: &str) -> Change {
+        Change::new(kind, target, None)
+    }
+
+    #[test]
+    fn test_role_matrix() {
+        let policy: AccessPolicy = serde_yaml::from_str(
+            "users:\n  dana: technician\n  lee: manager\n  api-key:ab12: admin\n",
+        )
+        .unwrap();
+        assert_eq!(policy.role_of("someone"), Role::Viewer);
+
+        let update_eq = [change(ChangeKind::Modify, "equipment 'AHU-1' (1 / East)")];
+        let delete_room = [change(ChangeKind::Remove, "room 'Lab' (1 / East)")];
+        let rename_building = [change(ChangeKind::Modify, "building 'HQ'")];
+        assert!(policy.authorize("someone", &update_eq).is_err());
+        assert!(policy.authorize("dana", &update_eq).is_ok());
+        let denied = policy.authorize("dana", &delete_room).unwrap_err();
+        assert_eq!(
+            denied.to_string(),
+            "Permission denied: dana (technician) may not delete room 'Lab' (1 / East)"
+        );
+        assert!(policy.authorize("lee", &delete_room).is_ok());
+        assert!(policy.authorize("lee", &rename_building).is_err());
+        assert!(policy.authorize("api-key:ab12", &rename_building).is_ok());
+        assert!(policy
+            .authorize("someone", &[change(ChangeKind::Git, "commit")])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_saves_checked_against_acting_user() {
+        use crate::core::{Building, Floor};
+        use crate::persistence::{PersistenceError, PersistenceManager};
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistenceManager::at(dir.path());
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        store.save_building_data(&building).unwrap();
+
+        let mut policy = AccessPolicy::default();
+        policy.users.insert("lee".into(), Role::Manager);
+        policy.save(dir.path()).unwrap();
+        building.add_floor(Floor::new("Ground".into(), 0));
+
+        let _viewer = ActingUserGuard::act_as("dana");
+        assert!(matches!(
+            store.save_building_data(&building),
+            Err(PersistenceError::PermissionDenied(_))
+        ));
+        let _manager = ActingUserGuard::act_as("lee");
+        store.save_building_data(&building).unwrap();
+        assert_eq!(store.load_building_data().unwrap().floors.len(), 1);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_acting_user_guard_nests() {
+        assert_eq!(acting_user(), None);
+        let _outer = ActingUserGuard::act_as("dana");
+        {
+            let _inner = ActingUserGuard::act_as("lee");
+            assert_eq!(acting_user().as_deref(), Some("lee"));
+        }
+        assert_eq!(acting_user().as_deref(), Some("dana"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_acting_user_from_environment() {
+        std::env::set_var(ACTING_USER_ENV, "dana");
+        assert_eq!(acting_user().as_deref(), Some("dana"));
+        {
+            let _guard = ActingUserGuard::act_as("lee");
+            assert_eq!(acting_user().as_deref(), Some("lee"));
+        }
+        std::env::remove_var(ACTING_USER_ENV);
+        assert_eq!(acting_user(), None);
+    }
+}
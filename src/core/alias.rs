@@ -0,0 +1,54 @@
+//! Alias-aware name matching for rooms and equipment.
+//!
+//! Rooms often carry an official number (`R-2.113`) and a colloquial name
+//! ("the fishbowl"). Aliases are extra display names only — the `id` remains
+//! the stable key — but every lookup by name should accept them.
+
+/// Case-insensitive exact match against the primary name or any alias.
+pub fn matches_name(name: &str, aliases: &[String], query: &str) -> bool {
+    let query = query.trim();
+    name.eq_ignore_ascii_case(query) || aliases.iter().any(|a| a.eq_ignore_ascii_case(query))
+}
+
+/// Primary name followed by aliases (for search indexing / display).
+pub fn display_names<'a>(name: &'a str, aliases: &'a [String]) -> impl Iterator<Item = &'a str> {
+    std::iter::once(name).chain(aliases.iter().map(String::as_str))
+}
+
+/// Add `alias` unless it is blank or already a name / alias (case-insensitive).
+/// Returns whether it was added.
+pub fn push_alias(name: &str, aliases: &mut Vec<String>, alias: &str) -> bool {
+    let alias = alias.trim();
+    if alias.is_empty() || matches_name(name, aliases, alias) {
+        return false;
+    }
+    aliases.push(alias.to_string());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_primary_or_alias_case_insensitively() {
+        let aliases = vec!["The Fishbowl".to_string()];
+        assert!(matches_name("R-2.113", &aliases, "r-2.113"));
+        assert!(matches_name("R-2.113", &aliases, " the fishbowl "));
+        assert!(!matches_name("R-2.113", &aliases, "fishbowl"));
+        assert_eq!(
+            display_names("R-2.113", &aliases).collect::<Vec<_>>(),
+            vec!["R-2.113", "The Fishbowl"]
+        );
+    }
+
+    #[test]
+    fn push_alias_skips_duplicates() {
+        let mut aliases = Vec::new();
+        assert!(push_alias("R-2.113", &mut aliases, "fishbowl"));
+        assert!(!push_alias("R-2.113", &mut aliases, "FISHBOWL"));
+        assert!(!push_alias("R-2.113", &mut aliases, "r-2.113"));
+        assert!(!push_alias("R-2.113", &mut aliases, "  "));
+        assert_eq!(aliases, vec!["fishbowl"]);
+    }
+}
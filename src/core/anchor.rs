@@ -3,6 +3,7 @@
 //! Anchors represent physical or digital reference points dropped by field workers via AR/PWA.
 //! They support recalibration over time, data saturation analysis, and relative poses to other anchors or geometry.
 
+use super::clock;
 use super::domain::ArxAddress;
 use super::types::Position;
 use chrono::{DateTime, Utc};
@@ -145,7 +146,7 @@ impl Anchor {
     /// Create a new anchor with default values and generated UUID.
     pub fn new(name: String, position: Position, confidence: f64) -> Self {
         Self {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name,
             address: None,
             position,
@@ -185,7 +186,7 @@ impl Anchor {
         self.position = new_pos;
         self.confidence = confidence;
         self.recalibration_count += 1;
-        self.last_recalibrated_at = Some(Utc::now());
+        self.last_recalibrated_at = Some(clock::now());
     }
 }
 
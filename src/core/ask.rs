@@ -0,0 +1,610 @@
+//! Natural-language questions over building data (`arx ask`).
+//!
+//! A question is turned into an [`AskQuery`] — the same filters `arx query`
+//! and the equipment list apply, plus maintenance, sensor, and alert
+//! conditions — and run against the building. Parsing is rule based by
+//! default: the grammar knows floor / room phrases, status and condition
+//! words, and the building's own vocabulary (equipment types, name prefixes,
+//! template names, maintenance schedule words), so
+//! "which AHUs on floor 3 have filters overdue?" becomes
+//! `kind: ahu, floor: 3, maintenance_due: filter`.
+//!
+//! `.arx/ask.yaml` can hand parsing to an LLM instead (any OpenAI-compatible
+//! chat completions endpoint, called with `curl`); it must answer with the
+//! query as JSON, and arx falls back to the rules when it does not:
+//!
+//! ```yaml
+//! backend: llm
+//! llm:
+//!   url: https://api.openai.com/v1/chat/completions
+//!   model: gpt-4o-mini
+//!   api_key_env: OPENAI_API_KEY
+//! ```
+
+use super::runtime::{maintenance_status, MaintenanceSchedules};
+use super::twin::SensorValue;
+use super::{Building, Equipment, EquipmentStatus, EquipmentType};
+use crate::export::embed::{equipment_state, RoomState};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::path::Path;
+
+/// Backend configuration, next to building.yaml.
+pub const ASK_CONFIG_FILE: &str = ".arx/ask.yaml";
+
+/// Equipment kinds the grammar knows without seeing them in the building.
+const KNOWN_KINDS: &[&str] = &[
+    "ahu",
+    "rtu",
+    "vav",
+    "fcu",
+    "boiler",
+    "chiller",
+    "pump",
+    "fan",
+    "panel",
+    "thermostat",
+    "meter",
+    "valve",
+    "damper",
+    "heater",
+    "compressor",
+    "extinguisher",
+    "projector",
+    "camera",
+    "elevator",
+    "generator",
+    "transformer",
+    "ups",
+];
+
+/// Multi-word names folded into one token before parsing.
+const PHRASES: &[(&str, &str)] = &[
+    ("air handling units", "ahu"),
+    ("air handling unit", "ahu"),
+    ("air handlers", "ahu"),
+    ("air handler", "ahu"),
+    ("rooftop units", "rtu"),
+    ("rooftop unit", "rtu"),
+    ("fan coil units", "fcu"),
+    ("fan coils", "fcu"),
+    ("out of order", "out_of_order"),
+    ("out of range", "out_of_range"),
+    ("outside thresholds", "out_of_range"),
+    ("outside their thresholds", "out_of_range"),
+    ("in maintenance", "in_maintenance"),
+    ("under maintenance", "in_maintenance"),
+    ("no sensors", "without_sensors"),
+    ("without sensors", "without_sensors"),
+];
+
+/// Structured form of a question.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AskQuery {
+    /// Equipment kind: matched against type, name, aliases, and template
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub floor: Option<i32>,
+    /// Room name or alias
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<EquipmentStatus>,
+    /// Due (or overdue) maintenance; the value narrows to schedule names
+    /// containing it, empty for any schedule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance_due: Option<String>,
+    /// A mapped sensor's latest value is outside its thresholds
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub out_of_range: bool,
+    /// In warning or critical state
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub alerting: bool,
+    /// No sensor mapped
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub without_sensors: bool,
+}
+
+impl AskQuery {
+    fn is_empty(&self) -> bool {
+        *self == AskQuery::default()
+    }
+
+    /// `kind ahu, floor 3, maintenance due (filter)`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(kind) = &self.kind {
+            parts.push(format!("kind {}", kind));
+        }
+        if let Some(floor) = self.floor {
+            parts.push(format!("floor {}", floor));
+        }
+        if let Some(room) = &self.room {
+            parts.push(format!("room {}", room));
+        }
+        if let Some(status) = self.status {
+            parts.push(format!("status {}", status));
+        }
+        match self.maintenance_due.as_deref() {
+            Some("") => parts.push("maintenance due".to_string()),
+            Some(schedule) => parts.push(format!("maintenance due ({})", schedule)),
+            None => {}
+        }
+        if self.out_of_range {
+            parts.push("sensor out of range".to_string());
+        }
+        if self.alerting {
+            parts.push("alerting".to_string());
+        }
+        if self.without_sensors {
+            parts.push("no sensors".to_string());
+        }
+        if parts.is_empty() {
+            "all equipment".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Data a query runs against besides the building.
+#[derive(Debug, Clone, Default)]
+pub struct AskContext {
+    pub schedules: MaintenanceSchedules,
+    /// Latest value per sensor id
+    pub latest: BTreeMap<String, SensorValue>,
+}
+
+/// Lower-case without a plural `s` / `es` (`AHUs` → `ahu`, `boxes` → `box`).
+fn singular(word: &str) -> String {
+    let word = word.to_lowercase();
+    if word.len() > 3 && word.ends_with("es") && !word.ends_with("ses") {
+        word[..word.len() - 2].to_string()
+    } else if word.len() > 2 && word.ends_with('s') && !word.ends_with("ss") {
+        word[..word.len() - 1].to_string()
+    } else {
+        word
+    }
+}
+
+/// Leading letters of a name (`AHU-3` → `ahu`, `Boiler01` → `boiler`).
+fn name_prefix(name: &str) -> String {
+    name.chars()
+        .take_while(|c| c.is_alphabetic())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Kinds the question may name: known kinds, plus the building's equipment
+/// types, name prefixes, and template names.
+fn vocabulary(building: &Building) -> BTreeSet<String> {
+    let mut words: BTreeSet<String> = KNOWN_KINDS.iter().map(|k| k.to_string()).collect();
+    for eq in building.get_all_equipment() {
+        words.insert(eq.equipment_type.to_string().to_lowercase());
+        let prefix = name_prefix(&eq.name);
+        if prefix.len() >= 2 {
+            words.insert(prefix);
+        }
+        if let Some(template) = eq.properties.get(super::templates::TEMPLATE_PROPERTY) {
+            words.insert(name_prefix(template));
+        }
+    }
+    words.retain(|w| !w.is_empty());
+    words
+}
+
+fn ordinal(word: &str) -> Option<i32> {
+    match word {
+        "ground" | "first" => Some(1),
+        "second" => Some(2),
+        "third" => Some(3),
+        "fourth" => Some(4),
+        "fifth" => Some(5),
+        "basement" => Some(-1),
+        _ => word
+            .trim_end_matches(|c: char| c.is_alphabetic())
+            .parse()
+            .ok()
+            .filter(|_| word.ends_with(|c: char| c.is_alphabetic())),
+    }
+}
+
+/// Parse `question` with the rule-based grammar.
+pub fn parse_question(
+    question: &str,
+    building: &Building,
+    schedules: &MaintenanceSchedules,
+) -> Result<AskQuery, String> {
+    let mut text = format!(
+        " {} ",
+        question.to_lowercase().replace(
+            |c: char| !(c.is_alphanumeric() || c == '-' || c == '.'),
+            " "
+        )
+    );
+    for (phrase, token) in PHRASES {
+        text = text.replace(&format!(" {} ", phrase), &format!(" {} ", token));
+    }
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let vocabulary = vocabulary(building);
+    let schedule_words: BTreeSet<String> = schedules
+        .schedules
+        .iter()
+        .flat_map(|s| s.name.split(['-', '_', ' ']).map(singular))
+        .filter(|w| w.len() > 2)
+        .collect();
+
+    let mut query = AskQuery::default();
+    let mut due_subject: Option<String> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let next = tokens.get(i + 1).copied();
+        let word = singular(token);
+        match *token {
+            "floor" | "level" => {
+                if let Some(level) = next.and_then(|n| n.parse().ok()) {
+                    query.floor = Some(level);
+                }
+                continue;
+            }
+            "room" => {
+                if let Some(room) = next {
+                    query.room = Some(room.to_string());
+                }
+                continue;
+            }
+            "due" | "overdue" => {
+                query.maintenance_due = Some(due_subject.take().unwrap_or_default());
+                continue;
+            }
+            "out_of_range" | "abnormal" | "alarm" | "alarms" => {
+                query.out_of_range = true;
+                continue;
+            }
+            "alert" | "alerts" | "alerting" | "warning" | "warnings" | "critical" | "failing"
+            | "unhealthy" => {
+                query.alerting = true;
+                continue;
+            }
+            "out_of_order" | "broken" | "down" => {
+                query.status = Some(EquipmentStatus::OutOfOrder);
+                continue;
+            }
+            "in_maintenance" => {
+                query.status = Some(EquipmentStatus::Maintenance);
+                continue;
+            }
+            "inactive" | "off" => {
+                query.status = Some(EquipmentStatus::Inactive);
+                continue;
+            }
+            "running" | "active" => {
+                query.status = Some(EquipmentStatus::Active);
+                continue;
+            }
+            "without_sensors" | "unmapped" => {
+                query.without_sensors = true;
+                continue;
+            }
+            _ => {}
+        }
+        if next.is_some_and(|n| n == "floor" || n == "level") {
+            if let Some(level) = ordinal(token) {
+                query.floor = Some(level);
+                continue;
+            }
+        }
+        // Kinds win over schedule words (`ahu-filter-change` names both)
+        if query.kind.is_none() && vocabulary.contains(&word) {
+            query.kind = Some(word);
+        } else if schedule_words.contains(&word) || word == "filter" {
+            due_subject = Some(word);
+        }
+    }
+    if query.maintenance_due.is_none() && due_subject.is_some() && text.contains(" service") {
+        query.maintenance_due = due_subject;
+    }
+    if query.is_empty() {
+        return Err(format!(
+            "Could not understand \"{}\". Try e.g. \"which AHUs on floor 3 have filters overdue?\" or \"equipment out of order in room 101\"",
+            question.trim()
+        ));
+    }
+    Ok(query)
+}
+
+fn out_of_range(eq: &Equipment, latest: &BTreeMap<String, SensorValue>) -> bool {
+    eq.sensor_mappings.iter().flatten().any(|m| {
+        latest.get(&m.sensor_id).is_some_and(|reading| {
+            let v = reading.value;
+            m.thresholds
+                .values()
+                .any(|t| t.min.is_some_and(|min| v < min) || t.max.is_some_and(|max| v > max))
+        })
+    })
+}
+
+fn kind_matches(eq: &Equipment, kind: &str) -> bool {
+    let kind = kind.to_lowercase();
+    let type_matches = match &eq.equipment_type {
+        EquipmentType::Other(name) => name.to_lowercase() == kind,
+        other => other.to_string().to_lowercase() == kind,
+    };
+    type_matches
+        || std::iter::once(&eq.name)
+            .chain(&eq.aliases)
+            .any(|n| name_prefix(n) == kind || n.to_lowercase().contains(&kind))
+        || eq
+            .properties
+            .get(super::templates::TEMPLATE_PROPERTY)
+            .is_some_and(|t| name_prefix(t) == kind)
+}
+
+/// Equipment matching `query`, in building order.
+pub fn run_query(
+    building: &Building,
+    query: &AskQuery,
+    context: &AskContext,
+    now: DateTime<Utc>,
+) -> Vec<Equipment> {
+    let due: Option<BTreeSet<String>> = query.maintenance_due.as_ref().map(|schedule| {
+        maintenance_status(building, &context.schedules, now)
+            .into_iter()
+            .filter(|s| s.is_due() && s.schedule.to_lowercase().contains(schedule.as_str()))
+            .map(|s| s.equipment_id)
+            .collect()
+    });
+    let mut out = Vec::new();
+    for floor in &building.floors {
+        if query.floor.is_some_and(|level| level != floor.level) {
+            continue;
+        }
+        let rooms = floor.wings.iter().flat_map(|w| &w.rooms);
+        let in_rooms = rooms
+            .filter(|r| query.room.as_deref().is_none_or(|q| r.matches_name(q)))
+            .flat_map(|r| &r.equipment);
+        let common = floor
+            .equipment
+            .iter()
+            .chain(floor.wings.iter().flat_map(|w| &w.equipment))
+            .filter(|_| query.room.is_none());
+        for eq in in_rooms.chain(common) {
+            let keep = query.kind.as_deref().is_none_or(|k| kind_matches(eq, k))
+                && query.status.is_none_or(|s| s == eq.status)
+                && due.as_ref().is_none_or(|ids| ids.contains(&eq.id))
+                && (!query.out_of_range || out_of_range(eq, &context.latest))
+                && (!query.alerting || equipment_state(eq) <= RoomState::Warning)
+                && (!query.without_sensors
+                    || eq.sensor_mappings.as_ref().is_none_or(|m| m.is_empty()));
+            if keep {
+                out.push(eq.clone());
+            }
+        }
+    }
+    out
+}
+
+/// Contents of [`ASK_CONFIG_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AskConfig {
+    /// `rules` (default) or `llm`
+    #[serde(default)]
+    pub backend: AskBackend,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm: Option<LlmConfig>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AskBackend {
+    #[default]
+    Rules,
+    Llm,
+}
+
+/// OpenAI-compatible chat completions endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    pub url: String,
+    pub model: String,
+    /// Environment variable holding the bearer token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    #[serde(default = "default_llm_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_llm_timeout() -> u64 {
+    30
+}
+
+impl AskConfig {
+    /// Load `{base}/.arx/ask.yaml`; absent means rule-based parsing.
+    pub fn load(base: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = base.join(ASK_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let config: Self = serde_yaml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        if config.backend == AskBackend::Llm && config.llm.is_none() {
+            return Err(format!("{}: backend llm needs an llm: section", path.display()).into());
+        }
+        Ok(config)
+    }
+}
+
+/// Instructions sent with every question to the LLM backend.
+fn llm_prompt() -> String {
+    format!(
+        "Translate building-operations questions into a JSON query object. \
+         Fields (all optional): kind (equipment kind such as ahu, boiler, pump, or a type \
+         such as hvac, electrical), floor (integer level), room (room name), status (one of {}), \
+         maintenance_due (schedule name fragment such as \"filter\", or \"\" for any due \
+         maintenance), out_of_range (bool: sensor reading outside thresholds), alerting (bool: \
+         warning or critical state), without_sensors (bool). Answer with the JSON object only.",
+        ["Active", "Inactive", "Maintenance", "OutOfOrder", "Unknown"].join(", ")
+    )
+}
+
+/// Query from a chat completions response body.
+pub fn parse_llm_response(body: &str) -> Result<AskQuery, String> {
+    let response: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("LLM response is not JSON: {}", e))?;
+    let content = response["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or("LLM response has no choices[0].message.content")?;
+    // Models like to wrap JSON in a fenced block
+    let json = content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    serde_json::from_str(json).map_err(|e| format!("LLM answer is not a query: {}", e))
+}
+
+/// Ask the configured LLM to parse `question`.
+pub fn parse_with_llm(config: &LlmConfig, question: &str) -> Result<AskQuery, String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let body = serde_json::json!({
+        "model": config.model,
+        "temperature": 0,
+        "messages": [
+            {"role": "system", "content": llm_prompt()},
+            {"role": "user", "content": question},
+        ],
+    })
+    .to_string();
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--config", "-"])
+        .args(["--max-time", &config.timeout_secs.to_string()])
+        .args(["--header", "Content-Type: application/json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("running curl (required for the llm backend): {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let quote = crate::notifications::webhook::config_string;
+        let mut config_lines = vec![
+            format!("url = {}", quote(&config.url)),
+            format!("data-binary = {}", quote(&body)),
+        ];
+        if let Some(key) = config
+            .api_key_env
+            .as_deref()
+            .and_then(|var| std::env::var(var).ok())
+        {
+            config_lines.push(format!(
+                "header = {}",
+                quote(&format!("Authorization: Bearer {}", key))
+            ));
+        }
+        writeln!(stdin, "{}", config_lines.join("\n")).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    parse_llm_response(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runtime::{MaintenanceSchedule, RuntimeCounter};
+    use crate::core::{Floor, Room, RoomType, Wing};
+    use crate::validation::EquipmentSelector;
+
+    fn building() -> (Building, MaintenanceSchedules) {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        for level in [2, 3] {
+            let mut room = Room::new(format!("Mech-{}", level), RoomType::Mechanical);
+            for (name, hours) in [("AHU-1", 2500.0), ("AHU-2", 100.0), ("Boiler-1", 5000.0)] {
+                let mut eq = Equipment::new(name.into(), String::new(), EquipmentType::HVAC);
+                eq.runtime = Some(RuntimeCounter {
+                    hours,
+                    ..Default::default()
+                });
+                eq.status = EquipmentStatus::Active;
+                room.equipment.push(eq);
+            }
+            let mut wing = Wing::new("Main".into());
+            wing.rooms.push(room);
+            let mut floor = Floor::new(format!("Floor {}", level), level);
+            floor.wings.push(wing);
+            building.add_floor(floor);
+        }
+        let schedules = MaintenanceSchedules {
+            schedules: vec![MaintenanceSchedule {
+                name: "ahu-filter-change".into(),
+                description: None,
+                selector: EquipmentSelector {
+                    name: Some("AHU*".into()),
+                    ..Default::default()
+                },
+                every_runtime_hours: 2000.0,
+                assignee: None,
+            }],
+            inspections: Vec::new(),
+        };
+        (building, schedules)
+    }
+
+    #[test]
+    fn parses_and_answers_overdue_filters_on_a_floor() {
+        let (building, schedules) = building();
+        let query = parse_question(
+            "Which AHUs on floor 3 have filters overdue?",
+            &building,
+            &schedules,
+        )
+        .unwrap();
+        assert_eq!(
+            query,
+            AskQuery {
+                kind: Some("ahu".into()),
+                floor: Some(3),
+                maintenance_due: Some("filter".into()),
+                ..Default::default()
+            }
+        );
+        let context = AskContext {
+            schedules,
+            ..Default::default()
+        };
+        let answer = run_query(&building, &query, &context, Utc::now());
+        let names: Vec<&str> = answer.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["AHU-1"]);
+
+        let query = parse_question(
+            "boilers out of order in room mech-2",
+            &building,
+            &context.schedules,
+        );
+        let query = query.unwrap();
+        assert_eq!(query.kind.as_deref(), Some("boiler"));
+        assert_eq!(query.status, Some(EquipmentStatus::OutOfOrder));
+        assert!(run_query(&building, &query, &context, Utc::now()).is_empty());
+        assert!(parse_question("hello there", &building, &context.schedules).is_err());
+    }
+
+    #[test]
+    fn reads_queries_from_llm_answers() {
+        let body = serde_json::json!({
+            "choices": [{"message": {"content": "```json\n{\"kind\": \"pump\", \"floor\": -1, \"alerting\": true}\n```"}}]
+        })
+        .to_string();
+        let query = parse_llm_response(&body).unwrap();
+        assert_eq!(query.kind.as_deref(), Some("pump"));
+        assert_eq!(query.floor, Some(-1));
+        assert!(query.alerting);
+        assert_eq!(query.describe(), "kind pump, floor -1, alerting");
+        assert!(parse_llm_response("{\"choices\": []}").is_err());
+    }
+}
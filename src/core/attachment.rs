@@ -0,0 +1,48 @@
+//! Files attached to rooms and equipment: photos, manuals, warranty documents.
+//!
+//! The file itself lives in the repository under
+//! [`attachment_dir`] (`attachments/<rooms|equipment>/<id>/`), or as a pointer
+//! to the attachment store; the entity keeps who added it, when, and a caption.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Repo-relative root of entity attachment directories.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// File extensions shown as photos rather than documents.
+const PHOTO_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "heic", "webp", "gif", "tif", "tiff"];
+
+/// A file attached to a room or equipment item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// Repo-relative path of the file (or its store pointer)
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    pub uploaded_by: String,
+    pub uploaded_at: DateTime<Utc>,
+    /// Size of the original file in bytes
+    pub size: u64,
+}
+
+impl Attachment {
+    /// File name without the directory.
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// Whether the file is an image (by extension).
+    pub fn is_photo(&self) -> bool {
+        let name = self.file_name().to_ascii_lowercase();
+        name.rsplit_once('.')
+            .is_some_and(|(_, ext)| PHOTO_EXTENSIONS.contains(&ext))
+    }
+}
+
+/// Repo-relative directory holding the attachments of one entity;
+/// `kind` is `room` or `equipment`.
+pub fn attachment_dir(kind: &str, id: &str) -> String {
+    let group = if kind == "room" { "rooms" } else { "equipment" };
+    format!("{}/{}/{}", ATTACHMENTS_DIR, group, id)
+}
@@ -1,11 +1,11 @@
 //! Building data structure and implementation
 
+use super::clock;
 use super::{BoundingBox, Floor, Room, Anchor};
 use super::domain::ArxAddress;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 /// Building metadata (YAML-only field)
 ///
@@ -188,9 +188,9 @@ impl Building {
     ///
     /// A new `Building` instance with empty floors collection
     pub fn new(name: String, path: String) -> Self {
-        let now = Utc::now();
+        let now = clock::now();
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name,
             path,
             description: None,
@@ -238,7 +238,7 @@ impl Building {
             floor.level
         );
         self.floors.push(floor);
-        self.updated_at = Utc::now();
+        self.updated_at = clock::now();
     }
 
     /// Add a floor to the building with validation
@@ -271,7 +271,7 @@ impl Building {
             return Err(format!("Floor with level {} already exists", floor.level));
         }
         self.floors.push(floor);
-        self.updated_at = Utc::now();
+        self.updated_at = clock::now();
         Ok(())
     }
 
@@ -470,18 +470,18 @@ impl Building {
         equipment
     }
 
-    /// Find an equipment item by its unique ID
+    /// Find an equipment item by its unique ID, name, or alias
     pub fn find_equipment(&self, id: &str) -> Option<&super::Equipment> {
         self.get_all_equipment()
             .into_iter()
-            .find(|eq| eq.id.eq_ignore_ascii_case(id) || eq.name.eq_ignore_ascii_case(id))
+            .find(|eq| eq.id.eq_ignore_ascii_case(id) || eq.matches_name(id))
     }
 
-    /// Find an equipment item by its unique ID or name (mutable reference)
+    /// Find an equipment item by its unique ID, name, or alias (mutable reference)
     pub fn find_equipment_mut(&mut self, id: &str) -> Option<&mut super::Equipment> {
         self.get_all_equipment_mut()
             .into_iter()
-            .find(|eq| eq.id.eq_ignore_ascii_case(id) || eq.name.eq_ignore_ascii_case(id))
+            .find(|eq| eq.id.eq_ignore_ascii_case(id) || eq.matches_name(id))
     }
 
     /// Find equipment within a given radius of a 3D point
@@ -687,9 +687,9 @@ impl Building {
 
 impl Default for Building {
     fn default() -> Self {
-        let now = Utc::now();
+        let now = clock::now();
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name: "Default Building".to_string(),
             path: "/default".to_string(),
             description: None,
@@ -0,0 +1,212 @@
+//! Injectable time and id sources.
+//!
+//! Core, persistence, and agent code read the current time through [`now`]
+//! and mint ids through [`new_uuid`] / [`new_id`] instead of calling
+//! `Utc::now()` and `Uuid::new_v4()` directly. By default these are the system
+//! clock and random v4 UUIDs. A [`ClockGuard`] swaps in other sources on the
+//! current thread (same scoping as the dry-run guard), so tests and agent
+//! replay get reproducible timestamps and ids without threading a context
+//! through every constructor.
+//!
+//! Credentials (agent tokens) deliberately keep using random UUIDs.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Source of fresh ids.
+pub trait IdGenerator: Send + Sync {
+    fn next_uuid(&self) -> Uuid;
+}
+
+/// Wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Deterministic clock: starts at a fixed instant and advances by `step` on
+/// every read (zero step keeps it frozen).
+#[derive(Debug)]
+pub struct FixedClock {
+    start: DateTime<Utc>,
+    step: Duration,
+    reads: AtomicU64,
+}
+
+impl FixedClock {
+    /// Frozen at `at`.
+    pub fn at(at: DateTime<Utc>) -> Self {
+        Self::stepping(at, Duration::zero())
+    }
+
+    /// Starts at `start`, moving forward `step` per read.
+    pub fn stepping(start: DateTime<Utc>, step: Duration) -> Self {
+        Self {
+            start,
+            step,
+            reads: AtomicU64::new(0),
+        }
+    }
+
+    /// 2024-01-01T00:00:00Z, frozen; a convenient default for tests.
+    pub fn epoch() -> Self {
+        Self::at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        let n = self.reads.fetch_add(1, Ordering::Relaxed);
+        self.start + self.step * n as i32
+    }
+}
+
+/// Random v4 UUIDs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIds;
+
+impl IdGenerator for RandomIds {
+    fn next_uuid(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// Deterministic ids: `seed` in the high 64 bits, a counter in the low 64,
+/// e.g. `00000000-0000-0000-0000-000000000001` for seed 0.
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+    seed: u64,
+    next: AtomicU64,
+}
+
+impl SequentialIds {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            next: AtomicU64::new(1),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_uuid(&self) -> Uuid {
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        Uuid::from_u64_pair(self.seed, n)
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Option<Arc<dyn Clock>>> = const { RefCell::new(None) };
+    static IDS: RefCell<Option<Arc<dyn IdGenerator>>> = const { RefCell::new(None) };
+}
+
+/// Current time from the installed clock (system clock by default).
+pub fn now() -> DateTime<Utc> {
+    CLOCK
+        .with(|c| c.borrow().as_ref().map(|c| c.now()))
+        .unwrap_or_else(Utc::now)
+}
+
+/// Fresh UUID from the installed generator (random v4 by default).
+pub fn new_uuid() -> Uuid {
+    IDS.with(|g| g.borrow().as_ref().map(|g| g.next_uuid()))
+        .unwrap_or_else(Uuid::new_v4)
+}
+
+/// Fresh id string (hyphenated UUID).
+pub fn new_id() -> String {
+    new_uuid().to_string()
+}
+
+/// Installs a clock and id generator on the current thread until dropped;
+/// guards nest and restore whatever was installed before.
+pub struct ClockGuard {
+    previous_clock: Option<Arc<dyn Clock>>,
+    previous_ids: Option<Arc<dyn IdGenerator>>,
+}
+
+impl ClockGuard {
+    pub fn install(clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Self {
+        Self {
+            previous_clock: CLOCK.with(|c| c.replace(Some(clock))),
+            previous_ids: IDS.with(|g| g.replace(Some(ids))),
+        }
+    }
+
+    /// Frozen clock at `at` with sequential ids from `seed`.
+    pub fn deterministic(at: DateTime<Utc>, seed: u64) -> Self {
+        Self::install(Arc::new(FixedClock::at(at)), Arc::new(SequentialIds::new(seed)))
+    }
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        CLOCK.with(|c| *c.borrow_mut() = self.previous_clock.take());
+        IDS.with(|g| *g.borrow_mut() = self.previous_ids.take());
+    }
+}
+
+/// Run `f` with a frozen clock at `at` and sequential ids from `seed`.
+pub fn with_deterministic<R>(at: DateTime<Utc>, seed: u64, f: impl FnOnce() -> R) -> R {
+    let _guard = ClockGuard::deterministic(at, seed);
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Room, RoomType};
+
+    #[test]
+    fn test_guard_makes_entities_reproducible_and_restores() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let build = || {
+            with_deterministic(at, 7, || {
+                let mut building = Building::new("HQ".into(), "/hq".into());
+                building.add_floor(crate::core::Floor::new("Ground".into(), 0));
+                (building, Room::new("Lab".into(), RoomType::Laboratory))
+            })
+        };
+        let (a, room_a) = build();
+        let (b, room_b) = build();
+        assert_eq!(a.id, b.id);
+        assert_eq!(a.floors[0].id, b.floors[0].id);
+        assert_eq!(room_a.id, room_b.id);
+        assert_eq!(a.id, "00000000-0000-0007-0000-000000000001");
+        assert_eq!(a.created_at, at);
+        assert_eq!(room_a.created_at, Some(at));
+
+        // Outside the guard: system clock and random ids again
+        assert_ne!(new_id(), new_id());
+        assert!(now() > at);
+    }
+
+    #[test]
+    fn test_stepping_clock_and_nested_guards() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let _outer = ClockGuard::install(
+            Arc::new(FixedClock::stepping(start, Duration::seconds(5))),
+            Arc::new(SequentialIds::new(0)),
+        );
+        assert_eq!(now(), start);
+        assert_eq!(now(), start + Duration::seconds(5));
+        {
+            let _inner = ClockGuard::deterministic(start - Duration::days(1), 1);
+            assert_eq!(now(), start - Duration::days(1));
+        }
+        assert_eq!(now(), start + Duration::seconds(10));
+        assert_eq!(new_id(), "00000000-0000-0000-0000-000000000001");
+    }
+}
@@ -0,0 +1,501 @@
+//! Commissioning workflow for new equipment.
+//!
+//! Equipment enrolled in a commissioning project moves through
+//! `installed → wired → sensor_mapped → tested → accepted`, one stage at a
+//! time. Every step is a sign-off (who, when, evidence) kept in the
+//! equipment's history, and each stage has its own evidence requirement:
+//!
+//! | Stage | Evidence |
+//! | --- | --- |
+//! | `installed`, `wired` | a photo |
+//! | `sensor_mapped` | at least one sensor mapping on the equipment |
+//! | `tested` | a reading from one of its mapped sensors, inside its thresholds |
+//! | `accepted` | sign-off only |
+//!
+//! Going back to an earlier stage (a failed test, rewiring) is allowed with a
+//! note and needs no evidence; the equipment then has to re-earn the later
+//! stages.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Building, Equipment};
+
+/// Commissioning stages in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommissioningStage {
+    Installed,
+    Wired,
+    SensorMapped,
+    Tested,
+    Accepted,
+}
+
+impl CommissioningStage {
+    pub const ALL: [CommissioningStage; 5] = [
+        CommissioningStage::Installed,
+        CommissioningStage::Wired,
+        CommissioningStage::SensorMapped,
+        CommissioningStage::Tested,
+        CommissioningStage::Accepted,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommissioningStage::Installed => "installed",
+            CommissioningStage::Wired => "wired",
+            CommissioningStage::SensorMapped => "sensor_mapped",
+            CommissioningStage::Tested => "tested",
+            CommissioningStage::Accepted => "accepted",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "installed" | "install" => Some(CommissioningStage::Installed),
+            "wired" | "wiring" => Some(CommissioningStage::Wired),
+            "sensor_mapped" | "mapped" => Some(CommissioningStage::SensorMapped),
+            "tested" | "test" => Some(CommissioningStage::Tested),
+            "accepted" | "accept" => Some(CommissioningStage::Accepted),
+            _ => None,
+        }
+    }
+
+    /// Stage after this one (`None` once accepted).
+    pub fn next(self) -> Option<Self> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+
+    /// Position in [`Self::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// What a sign-off into this stage must carry.
+    pub fn requirement(self) -> &'static str {
+        match self {
+            CommissioningStage::Installed | CommissioningStage::Wired => "a photo",
+            CommissioningStage::SensorMapped => "a sensor mapping on the equipment",
+            CommissioningStage::Tested => "a reading from a mapped sensor within its thresholds",
+            CommissioningStage::Accepted => "a sign-off",
+        }
+    }
+}
+
+impl fmt::Display for CommissioningStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Evidence attached to a sign-off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Evidence {
+    /// Repo-relative photo path (may be an attachment pointer)
+    Photo { path: String },
+    /// Reading taken from a mapped sensor during the test
+    TestReading {
+        sensor_id: String,
+        value: f64,
+        at: DateTime<Utc>,
+    },
+}
+
+/// One transition in the commissioning history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignOff {
+    /// Stage entered
+    pub stage: CommissioningStage,
+    pub by: String,
+    pub at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<Evidence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Moved back to an earlier stage
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reopened: bool,
+}
+
+/// Commissioning state stored on equipment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Commissioning {
+    pub project: String,
+    /// Current stage; `None` until the first sign-off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<CommissioningStage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<SignOff>,
+}
+
+impl Commissioning {
+    /// Stage the next forward sign-off must enter.
+    pub fn next_stage(&self) -> Option<CommissioningStage> {
+        match self.stage {
+            None => Some(CommissioningStage::Installed),
+            Some(s) => s.next(),
+        }
+    }
+}
+
+/// Put `eq` into `project` (keeps its stage and history if already enrolled).
+pub fn enroll(eq: &mut Equipment, project: &str) -> Result<(), String> {
+    let project = project.trim();
+    if project.is_empty() {
+        return Err("Commissioning project name is empty".into());
+    }
+    eq.commissioning
+        .get_or_insert_with(Commissioning::default)
+        .project = project.to_string();
+    Ok(())
+}
+
+/// Check that `evidence` satisfies the requirement for entering `stage` on `eq`.
+fn check_evidence(
+    eq: &Equipment,
+    stage: CommissioningStage,
+    evidence: &[Evidence],
+) -> Result<(), String> {
+    match stage {
+        CommissioningStage::Installed | CommissioningStage::Wired => {
+            if evidence.iter().any(|e| matches!(e, Evidence::Photo { .. })) {
+                Ok(())
+            } else {
+                Err(format!("'{}' → {} needs a photo", eq.name, stage))
+            }
+        }
+        CommissioningStage::SensorMapped => {
+            if eq.sensor_mappings.iter().flatten().next().is_some() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "'{}' has no sensor mapping (bind one with `arx sensor map`)",
+                    eq.name
+                ))
+            }
+        }
+        CommissioningStage::Tested => {
+            let mut rejected = Vec::new();
+            for e in evidence {
+                let Evidence::TestReading {
+                    sensor_id, value, ..
+                } = e
+                else {
+                    continue;
+                };
+                let Some(mapping) = eq
+                    .sensor_mappings
+                    .iter()
+                    .flatten()
+                    .find(|m| &m.sensor_id == sensor_id)
+                else {
+                    rejected.push(format!("{} is not mapped to '{}'", sensor_id, eq.name));
+                    continue;
+                };
+                let in_range = mapping.thresholds.values().all(|t| {
+                    t.min.is_none_or(|min| *value >= min) && t.max.is_none_or(|max| *value <= max)
+                });
+                if value.is_finite() && in_range {
+                    return Ok(());
+                }
+                rejected.push(format!(
+                    "{} = {} is outside its thresholds",
+                    sensor_id, value
+                ));
+            }
+            if rejected.is_empty() {
+                Err(format!(
+                    "'{}' → tested needs a reading from a mapped sensor",
+                    eq.name
+                ))
+            } else {
+                Err(rejected.join("; "))
+            }
+        }
+        CommissioningStage::Accepted => Ok(()),
+    }
+}
+
+/// Apply `sign_off` to enrolled equipment.
+///
+/// Forward moves must enter the next stage and carry its evidence; moving
+/// back to an earlier (or the current) stage reopens it and needs a note.
+pub fn advance(eq: &mut Equipment, mut sign_off: SignOff) -> Result<(), String> {
+    if sign_off.by.trim().is_empty() {
+        return Err("Sign-off needs a name (--by)".into());
+    }
+    let state = eq
+        .commissioning
+        .as_ref()
+        .ok_or_else(|| format!("'{}' is not enrolled in a commissioning project", eq.name))?;
+    let target = sign_off.stage;
+
+    if state.stage.is_some_and(|current| target <= current) {
+        if sign_off.note.as_deref().is_none_or(|n| n.trim().is_empty()) {
+            return Err(format!(
+                "Reopening '{}' at {} needs a note explaining why",
+                eq.name, target
+            ));
+        }
+        sign_off.reopened = true;
+    } else {
+        match state.next_stage() {
+            Some(next) if next == target => {}
+            Some(next) => {
+                return Err(format!(
+                    "'{}' cannot go from {} to {}; next stage is {}",
+                    eq.name,
+                    state.stage.map_or("not started", |s| s.as_str()),
+                    target,
+                    next
+                ))
+            }
+            None => return Err(format!("'{}' is already accepted", eq.name)),
+        }
+        check_evidence(eq, target, &sign_off.evidence)?;
+        sign_off.reopened = false;
+    }
+
+    let state = eq.commissioning.as_mut().expect("checked above");
+    state.stage = Some(target);
+    state.history.push(sign_off);
+    Ok(())
+}
+
+/// One equipment row in the progress report.
+#[derive(Debug, Clone, Serialize)]
+pub struct EquipmentProgress {
+    pub equipment_id: String,
+    pub name: String,
+    pub stage: Option<CommissioningStage>,
+    /// Stage still to be signed off next, with its evidence requirement
+    pub next: Option<String>,
+    pub last_sign_off: Option<DateTime<Utc>>,
+    pub last_signed_by: Option<String>,
+}
+
+/// Progress of one commissioning project.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectProgress {
+    pub project: String,
+    pub equipment: Vec<EquipmentProgress>,
+    /// Equipment count per current stage (`not_started` included)
+    pub stages: BTreeMap<String, usize>,
+    /// Signed-off stages over all stages of all equipment (0-1)
+    pub completion: f64,
+}
+
+/// Commissioning progress per project.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommissioningReport {
+    pub building: String,
+    pub projects: Vec<ProjectProgress>,
+}
+
+/// Collect enrolled equipment by project, optionally only `project`.
+pub fn commissioning_report(building: &Building, project: Option<&str>) -> CommissioningReport {
+    let mut by_project: BTreeMap<&str, Vec<&Equipment>> = BTreeMap::new();
+    for eq in building.get_all_equipment() {
+        if let Some(c) = &eq.commissioning {
+            if project.is_none_or(|p| p == c.project) {
+                by_project.entry(c.project.as_str()).or_default().push(eq);
+            }
+        }
+    }
+
+    let total = CommissioningStage::ALL.len();
+    let projects = by_project
+        .into_iter()
+        .map(|(name, mut equipment)| {
+            equipment.sort_by(|a, b| a.name.cmp(&b.name));
+            let mut stages: BTreeMap<String, usize> = BTreeMap::new();
+            let mut done = 0;
+            let rows: Vec<EquipmentProgress> = equipment
+                .iter()
+                .map(|eq| {
+                    let c = eq.commissioning.as_ref().expect("filtered above");
+                    let label = c.stage.map_or("not_started", |s| s.as_str());
+                    *stages.entry(label.to_string()).or_default() += 1;
+                    done += c.stage.map_or(0, |s| s.index() + 1);
+                    let last = c.history.last();
+                    EquipmentProgress {
+                        equipment_id: eq.id.clone(),
+                        name: eq.name.clone(),
+                        stage: c.stage,
+                        next: c
+                            .next_stage()
+                            .map(|s| format!("{} (needs {})", s, s.requirement())),
+                        last_sign_off: last.map(|s| s.at),
+                        last_signed_by: last.map(|s| s.by.clone()),
+                    }
+                })
+                .collect();
+            ProjectProgress {
+                project: name.to_string(),
+                completion: done as f64 / (rows.len() * total).max(1) as f64,
+                equipment: rows,
+                stages,
+            }
+        })
+        .collect();
+
+    CommissioningReport {
+        building: building.name.clone(),
+        projects,
+    }
+}
+
+impl CommissioningReport {
+    /// Markdown progress report for project handoff.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Commissioning progress — {}", self.building);
+        for p in &self.projects {
+            let accepted = p.stages.get("accepted").copied().unwrap_or(0);
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {}", p.project);
+            let _ = writeln!(out);
+            let _ = writeln!(
+                out,
+                "- Equipment: {} ({} accepted)",
+                p.equipment.len(),
+                accepted
+            );
+            let _ = writeln!(out, "- Completion: {:.0}%", p.completion * 100.0);
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| Equipment | Stage | Next | Last sign-off |");
+            let _ = writeln!(out, "| --- | --- | --- | --- |");
+            for e in &p.equipment {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} |",
+                    e.name,
+                    e.stage.map_or("not started", |s| s.as_str()),
+                    e.next.as_deref().unwrap_or("—"),
+                    match (&e.last_signed_by, e.last_sign_off) {
+                        (Some(by), Some(at)) => format!("{} ({})", by, at.format("%Y-%m-%d")),
+                        _ => "—".into(),
+                    }
+                );
+            }
+        }
+        if self.projects.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "_No equipment enrolled in commissioning._");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::operations::sensor::parse_threshold_range;
+    use crate::core::operations::{bind_sensor, SensorBindTarget};
+    use crate::core::{EquipmentType, Floor};
+
+    fn sign(stage: CommissioningStage, evidence: Vec<Evidence>) -> SignOff {
+        SignOff {
+            stage,
+            by: "pat".into(),
+            at: Utc::now(),
+            evidence,
+            note: None,
+            reopened: false,
+        }
+    }
+
+    fn photo() -> Vec<Evidence> {
+        vec![Evidence::Photo {
+            path: "photos/ahu.jpg".into(),
+        }]
+    }
+
+    fn reading(sensor_id: &str, value: f64) -> Vec<Evidence> {
+        vec![Evidence::TestReading {
+            sensor_id: sensor_id.into(),
+            value,
+            at: Utc::now(),
+        }]
+    }
+
+    #[test]
+    fn stages_advance_in_order_with_evidence() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Roof".into(), 5);
+        let eq = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        let id = eq.id.clone();
+        floor.equipment.push(eq);
+        building.add_floor(floor);
+
+        let eq = building.find_equipment_mut(&id).unwrap();
+        assert!(advance(eq, sign(CommissioningStage::Installed, photo())).is_err());
+        enroll(eq, "Phase 2").unwrap();
+        assert!(advance(eq, sign(CommissioningStage::Installed, vec![])).is_err());
+        assert!(advance(eq, sign(CommissioningStage::Wired, photo())).is_err());
+        advance(eq, sign(CommissioningStage::Installed, photo())).unwrap();
+        advance(eq, sign(CommissioningStage::Wired, photo())).unwrap();
+        assert!(advance(eq, sign(CommissioningStage::SensorMapped, vec![])).is_err());
+
+        bind_sensor(
+            &mut building,
+            "sat-1",
+            "temperature",
+            &SensorBindTarget::Equipment(id.clone()),
+            Some(parse_threshold_range("5..40").unwrap()),
+        )
+        .unwrap();
+        let eq = building.find_equipment_mut(&id).unwrap();
+        advance(eq, sign(CommissioningStage::SensorMapped, vec![])).unwrap();
+        assert!(advance(eq, sign(CommissioningStage::Tested, reading("other", 20.0))).is_err());
+        assert!(advance(eq, sign(CommissioningStage::Tested, reading("sat-1", 90.0))).is_err());
+        advance(eq, sign(CommissioningStage::Tested, reading("sat-1", 18.5))).unwrap();
+
+        // Failed acceptance walk-down: back to wired, needs a note
+        assert!(advance(eq, sign(CommissioningStage::Wired, vec![])).is_err());
+        let mut reopen = sign(CommissioningStage::Wired, vec![]);
+        reopen.note = Some("damper actuator miswired".into());
+        advance(eq, reopen).unwrap();
+        let state = eq.commissioning.as_ref().unwrap();
+        assert_eq!(state.stage, Some(CommissioningStage::Wired));
+        assert!(state.history.last().unwrap().reopened);
+        assert_eq!(state.history.len(), 5);
+
+        let report = commissioning_report(&building, None);
+        assert_eq!(report.projects.len(), 1);
+        let p = &report.projects[0];
+        assert_eq!(p.project, "Phase 2");
+        assert_eq!(p.stages.get("wired"), Some(&1));
+        assert!((p.completion - 0.4).abs() < 1e-9);
+        assert!(report
+            .to_markdown()
+            .contains("| AHU-1 | wired | sensor_mapped"));
+        assert!(commissioning_report(&building, Some("Phase 3"))
+            .projects
+            .is_empty());
+    }
+
+    #[test]
+    fn state_round_trips_through_yaml() {
+        let mut eq = Equipment::new("VAV-3".into(), String::new(), EquipmentType::HVAC);
+        assert!(!serde_yaml::to_string(&eq)
+            .unwrap()
+            .contains("commissioning"));
+        enroll(&mut eq, "Fit-out").unwrap();
+        advance(&mut eq, sign(CommissioningStage::Installed, photo())).unwrap();
+        let yaml = serde_yaml::to_string(&eq).unwrap();
+        assert!(yaml.contains("stage: installed"));
+        assert!(yaml.contains("kind: photo"));
+        let back: Equipment = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(back.commissioning, eq.commissioning);
+        assert_eq!(
+            CommissioningStage::parse("sensor-mapped"),
+            Some(CommissioningStage::SensorMapped)
+        );
+    }
+}
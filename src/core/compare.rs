@@ -0,0 +1,440 @@
+//! Entity-level comparison of two building states.
+//!
+//! [`compare_buildings`] lines up the floors, wings, rooms, and equipment of
+//! two buildings — by id first, then by kind and name for entities whose ids
+//! differ (e.g. a scan-derived model against the design model) — and reports
+//! what was added, removed, moved to another container or position, and which
+//! fields changed. Properties are compared key by key; bookkeeping fields
+//! (timestamps, provenance) and fields derived from the location (addresses,
+//! paths) are left out.
+
+use super::{Building, Equipment, Position};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Equipment closer than this (m) to its old position has not moved.
+pub const MOVE_TOLERANCE: f64 = 0.01;
+
+/// Fields never compared: bookkeeping and values that follow the location.
+const IGNORED_FIELDS: &[&str] = &[
+    "created_at",
+    "updated_at",
+    "provenance",
+    "address",
+    "path",
+    "room_id",
+    "position",
+];
+
+/// One floor, wing, room, or equipment item, as it appears in a comparison.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntityRef {
+    /// `floor`, `wing`, `room`, or `equipment`
+    pub kind: &'static str,
+    pub id: String,
+    pub name: String,
+    /// Containing floor / wing / room names, e.g. `Ground / East / Lobby`
+    pub location: String,
+}
+
+/// An entity now in another container or position.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MovedEntity {
+    #[serde(flatten)]
+    pub entity: EntityRef,
+    /// Location in the first building
+    pub from: String,
+    /// Distance (m) between the two positions, for equipment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+}
+
+/// One field that differs; properties appear as `properties.<key>`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// An entity present in both buildings with different fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangedEntity {
+    #[serde(flatten)]
+    pub entity: EntityRef,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences from one building state to another.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BuildingComparison {
+    /// Building-level fields that differ
+    pub building: Vec<FieldChange>,
+    pub added: Vec<EntityRef>,
+    pub removed: Vec<EntityRef>,
+    pub moved: Vec<MovedEntity>,
+    pub changed: Vec<ChangedEntity>,
+    /// Entities paired by kind and name because their ids differ
+    pub matched_by_name: usize,
+}
+
+impl BuildingComparison {
+    pub fn is_empty(&self) -> bool {
+        self.building.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.changed.is_empty()
+    }
+
+    /// Human-readable listing, one line per entity, then a count summary.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let label = |e: &EntityRef| {
+            if e.location.is_empty() {
+                format!("{} '{}'", e.kind, e.name)
+            } else {
+                format!("{} '{}' ({})", e.kind, e.name, e.location)
+            }
+        };
+        let fields = |changes: &[FieldChange]| {
+            changes
+                .iter()
+                .map(|c| format!("{}: {} → {}", c.field, short(&c.before), short(&c.after)))
+                .collect::<Vec<_>>()
+                .join("; ")
+        };
+
+        let mut lines = Vec::new();
+        if !self.building.is_empty() {
+            lines.push(format!("~ building: {}", fields(&self.building)));
+        }
+        lines.extend(self.added.iter().map(|e| format!("+ {}", label(e))));
+        lines.extend(self.removed.iter().map(|e| format!("- {}", label(e))));
+        for m in &self.moved {
+            let mut line = format!(
+                "→ {} '{}': {} → {}",
+                m.entity.kind, m.entity.name, m.from, m.entity.location
+            );
+            if let Some(d) = m.distance {
+                line.push_str(&format!(" ({:.2} m)", d));
+            }
+            lines.push(line);
+        }
+        lines.extend(
+            self.changed
+                .iter()
+                .map(|c| format!("~ {}: {}", label(&c.entity), fields(&c.changes))),
+        );
+        let mut summary = format!(
+            "{} added, {} removed, {} moved, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.moved.len(),
+            self.changed.len() + usize::from(!self.building.is_empty())
+        );
+        if self.matched_by_name > 0 {
+            summary.push_str(&format!(" ({} matched by name)", self.matched_by_name));
+        }
+        lines.push(summary);
+        lines
+    }
+}
+
+fn short(value: &Value) -> String {
+    match value {
+        Value::Null => "(none)".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => format!("{} item(s)", items.len()),
+        Value::Object(_) => "{…}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Own fields of `entity` without `children` or ignored fields, with each
+/// property as its own `properties.<key>` field.
+fn flatten<T: Serialize>(entity: &T, children: &[&str]) -> BTreeMap<String, Value> {
+    let Ok(Value::Object(map)) = serde_json::to_value(entity) else {
+        return BTreeMap::new();
+    };
+    let mut out = BTreeMap::new();
+    for (key, value) in map {
+        if children.contains(&key.as_str()) || IGNORED_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        match value {
+            Value::Object(props) if key == "properties" => {
+                for (k, v) in props {
+                    out.insert(format!("properties.{}", k), v);
+                }
+            }
+            other => {
+                out.insert(key, other);
+            }
+        }
+    }
+    out
+}
+
+fn field_changes(
+    before: &BTreeMap<String, Value>,
+    after: &BTreeMap<String, Value>,
+) -> Vec<FieldChange> {
+    let keys: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|k| {
+            let (old, new) = (
+                before.get(k).unwrap_or(&Value::Null),
+                after.get(k).unwrap_or(&Value::Null),
+            );
+            (old != new).then(|| FieldChange {
+                field: k.clone(),
+                before: old.clone(),
+                after: new.clone(),
+            })
+        })
+        .collect()
+}
+
+struct Entry {
+    entity: EntityRef,
+    fields: BTreeMap<String, Value>,
+    position: Option<(f64, f64, f64)>,
+}
+
+fn entries(building: &Building) -> Vec<Entry> {
+    let mut out = Vec::new();
+    let mut add = |kind, id: &str, name: &str, location: &str, fields, position| {
+        out.push(Entry {
+            entity: EntityRef {
+                kind,
+                id: id.to_string(),
+                name: name.to_string(),
+                location: location.to_string(),
+            },
+            fields,
+            position,
+        });
+    };
+    let at = |p: &Position| Some((p.x, p.y, p.z));
+    let equipment = |eq: &Equipment| flatten(eq, &[]);
+
+    for floor in &building.floors {
+        let fields = flatten(floor, &["wings", "equipment"]);
+        add("floor", &floor.id, &floor.name, "", fields, None);
+        for eq in &floor.equipment {
+            add(
+                "equipment",
+                &eq.id,
+                &eq.name,
+                &floor.name,
+                equipment(eq),
+                at(&eq.position),
+            );
+        }
+        for wing in &floor.wings {
+            let fields = flatten(wing, &["rooms", "equipment"]);
+            add("wing", &wing.id, &wing.name, &floor.name, fields, None);
+            let in_wing = format!("{} / {}", floor.name, wing.name);
+            for eq in &wing.equipment {
+                add(
+                    "equipment",
+                    &eq.id,
+                    &eq.name,
+                    &in_wing,
+                    equipment(eq),
+                    at(&eq.position),
+                );
+            }
+            for room in &wing.rooms {
+                let fields = flatten(room, &["equipment"]);
+                add("room", &room.id, &room.name, &in_wing, fields, None);
+                let in_room = format!("{} / {}", in_wing, room.name);
+                for eq in &room.equipment {
+                    add(
+                        "equipment",
+                        &eq.id,
+                        &eq.name,
+                        &in_room,
+                        equipment(eq),
+                        at(&eq.position),
+                    );
+                }
+            }
+        }
+    }
+    out
+}
+
+fn name_key(entry: &Entry) -> (&'static str, String) {
+    (entry.entity.kind, entry.entity.name.trim().to_lowercase())
+}
+
+/// Pairs of (before, after) indices: same id, then same kind and name where
+/// exactly one unpaired entity on each side has it.
+fn pair(before: &[Entry], after: &[Entry]) -> (Vec<(usize, usize)>, usize) {
+    let by_id: HashMap<(&str, &str), usize> = before
+        .iter()
+        .enumerate()
+        .map(|(i, e)| ((e.entity.kind, e.entity.id.as_str()), i))
+        .collect();
+    let mut pairs = Vec::new();
+    let (mut used_before, mut used_after) = (HashSet::new(), HashSet::new());
+    for (j, e) in after.iter().enumerate() {
+        if let Some(&i) = by_id.get(&(e.entity.kind, e.entity.id.as_str())) {
+            if used_before.insert(i) {
+                used_after.insert(j);
+                pairs.push((i, j));
+            }
+        }
+    }
+
+    let unique = |entries: &[Entry], used: &HashSet<usize>| {
+        let mut seen: HashMap<(&'static str, String), Option<usize>> = HashMap::new();
+        for (i, e) in entries
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !used.contains(i))
+        {
+            seen.entry(name_key(e))
+                .and_modify(|slot| *slot = None)
+                .or_insert(Some(i));
+        }
+        seen
+    };
+    let old_names = unique(before, &used_before);
+    let mut by_name = 0;
+    for (key, j) in unique(after, &used_after) {
+        if let (Some(j), Some(Some(i))) = (j, old_names.get(&key)) {
+            pairs.push((*i, j));
+            by_name += 1;
+        }
+    }
+    pairs.sort_unstable_by_key(|&(_, j)| j);
+    (pairs, by_name)
+}
+
+/// Differences that turn `before` into `after`.
+pub fn compare_buildings(before: &Building, after: &Building) -> BuildingComparison {
+    let (old, new) = (entries(before), entries(after));
+    let (pairs, matched_by_name) = pair(&old, &new);
+    let mut comparison = BuildingComparison {
+        building: field_changes(&flatten(before, &["floors"]), &flatten(after, &["floors"])),
+        matched_by_name,
+        ..Default::default()
+    };
+    comparison.building.retain(|c| c.field != "id");
+
+    let paired_before: HashSet<usize> = pairs.iter().map(|&(i, _)| i).collect();
+    let paired_after: HashSet<usize> = pairs.iter().map(|&(_, j)| j).collect();
+    for (j, e) in new.iter().enumerate() {
+        if !paired_after.contains(&j) {
+            comparison.added.push(e.entity.clone());
+        }
+    }
+    for (i, e) in old.iter().enumerate() {
+        if !paired_before.contains(&i) {
+            comparison.removed.push(e.entity.clone());
+        }
+    }
+
+    for (i, j) in pairs {
+        let (a, b) = (&old[i], &new[j]);
+        let distance = match (a.position, b.position) {
+            (Some(p), Some(q)) => {
+                let d = ((q.0 - p.0).powi(2) + (q.1 - p.1).powi(2) + (q.2 - p.2).powi(2)).sqrt();
+                Some(d).filter(|d| *d > MOVE_TOLERANCE)
+            }
+            _ => None,
+        };
+        let relocated = !a.entity.location.eq_ignore_ascii_case(&b.entity.location);
+        if relocated || distance.is_some() {
+            comparison.moved.push(MovedEntity {
+                entity: b.entity.clone(),
+                from: a.entity.location.clone(),
+                distance,
+            });
+        }
+        let changes = field_changes(&a.fields, &b.fields);
+        if !changes.is_empty() {
+            comparison.changed.push(ChangedEntity {
+                entity: b.entity.clone(),
+                changes,
+            });
+        }
+    }
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        for name in ["101", "102"] {
+            let mut room = Room::new(name.into(), RoomType::Office);
+            room.properties.insert("capacity".into(), "8".into());
+            wing.add_room(room);
+        }
+        wing.rooms[0].add_equipment(Equipment::new(
+            "VAV-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn reports_added_removed_moved_and_changed_entities() {
+        let before = building();
+        let mut after = before.clone();
+        let wing = &mut after.floors[0].wings[0];
+        let mut vav = wing.rooms[0].equipment.remove(0);
+        vav.position.x += 3.0;
+        wing.rooms[1].add_equipment(vav);
+        wing.rooms[1]
+            .properties
+            .insert("capacity".into(), "12".into());
+        wing.rooms.remove(0);
+        wing.add_room(Room::new("Lab".into(), RoomType::Laboratory));
+
+        let cmp = compare_buildings(&before, &after);
+        let names = |list: &[EntityRef]| list.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&cmp.added), ["Lab"]);
+        assert_eq!(names(&cmp.removed), ["101"]);
+        assert_eq!(cmp.moved.len(), 1);
+        assert_eq!(cmp.moved[0].from, "Ground / East / 101");
+        assert_eq!(cmp.moved[0].entity.location, "Ground / East / 102");
+        assert!((cmp.moved[0].distance.unwrap() - 3.0).abs() < 1e-9);
+        assert_eq!(cmp.changed.len(), 1);
+        assert_eq!(cmp.changed[0].changes[0].field, "properties.capacity");
+        assert_eq!(
+            cmp.summary_lines().last().unwrap(),
+            "1 added, 1 removed, 1 moved, 1 changed"
+        );
+        assert!(compare_buildings(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn pairs_entities_by_name_when_ids_differ() {
+        let design = building();
+        let mut scan = design.clone();
+        for room in &mut scan.floors[0].wings[0].rooms {
+            room.id = format!("scan-{}", room.name);
+        }
+
+        let cmp = compare_buildings(&design, &scan);
+        assert!(cmp.added.is_empty() && cmp.removed.is_empty());
+        assert_eq!(cmp.matched_by_name, 2);
+        assert!(cmp
+            .changed
+            .iter()
+            .all(|c| c.changes.len() == 1 && c.changes[0].field == "id"));
+    }
+}
@@ -0,0 +1,388 @@
+//! Level-of-development (LOD) tagging and completeness scoring.
+//!
+//! Each room and equipment item is checked for the facts a usable digital twin
+//! needs — geometry, filled-in properties, mapped sensors, attached photos —
+//! and the per-entity scores roll up to room, floor, and building. Teams can
+//! declare a target LOD per entity with the `lod` property; the report shows
+//! declared vs achieved so thin areas of the model are easy to spot.
+
+use crate::core::{Building, Equipment, Room, PROP_REVIEW_STATUS};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Property holding the declared (target) LOD, e.g. `lod=300`.
+pub const PROP_LOD: &str = "lod";
+
+/// Property key prefixes that count as an attached photo / document reference.
+pub const PHOTO_PROPERTY_PREFIXES: &[&str] = &["photo", "attachment"];
+
+/// Properties that are bookkeeping, not descriptive data.
+const NON_DATA_PROPERTIES: &[&str] = &[PROP_REVIEW_STATUS, PROP_LOD];
+
+/// Individual completeness checks for one entity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CompletenessChecks {
+    pub geometry: bool,
+    pub properties: bool,
+    pub sensors: bool,
+    pub photos: bool,
+}
+
+impl CompletenessChecks {
+    /// Fraction of checks passed (0.0 – 1.0).
+    pub fn score(&self) -> f64 {
+        let passed = [self.geometry, self.properties, self.sensors, self.photos]
+            .iter()
+            .filter(|b| **b)
+            .count();
+        passed as f64 / 4.0
+    }
+
+    /// Names of the checks that failed.
+    pub fn missing(&self) -> Vec<&'static str> {
+        let mut out = Vec::new();
+        if !self.geometry {
+            out.push("geometry");
+        }
+        if !self.properties {
+            out.push("properties");
+        }
+        if !self.sensors {
+            out.push("sensors");
+        }
+        if !self.photos {
+            out.push("photos");
+        }
+        out
+    }
+}
+
+/// Map a completeness score onto the usual BIM LOD ladder.
+pub fn lod_for_score(score: f64) -> u16 {
+    if score >= 1.0 {
+        400
+    } else if score >= 0.75 {
+        350
+    } else if score >= 0.5 {
+        300
+    } else if score >= 0.25 {
+        200
+    } else {
+        100
+    }
+}
+
+/// Completeness for one equipment item.
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityCompleteness {
+    pub id: String,
+    pub name: String,
+    pub checks: CompletenessChecks,
+    pub score: f64,
+    pub achieved_lod: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared_lod: Option<u16>,
+}
+
+/// Room rollup (room checks + its equipment).
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomCompleteness {
+    #[serde(flatten)]
+    pub room: EntityCompleteness,
+    pub equipment: Vec<EntityCompleteness>,
+    /// Mean of the room's own score and its equipment scores
+    pub rollup_score: f64,
+    pub rollup_lod: u16,
+}
+
+/// Floor rollup (mean over rooms).
+#[derive(Debug, Clone, Serialize)]
+pub struct FloorCompleteness {
+    pub name: String,
+    pub level: i32,
+    pub rooms: Vec<RoomCompleteness>,
+    pub score: f64,
+    pub lod: u16,
+}
+
+/// Building-wide completeness report.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletenessReport {
+    pub building: String,
+    pub floors: Vec<FloorCompleteness>,
+    pub score: f64,
+    pub lod: u16,
+}
+
+impl CompletenessReport {
+    /// Rooms below their declared LOD (or below `min_score` when undeclared).
+    pub fn thin_rooms(&self, min_score: f64) -> Vec<(&FloorCompleteness, &RoomCompleteness)> {
+        let mut out = Vec::new();
+        for floor in &self.floors {
+            for room in &floor.rooms {
+                let below = match room.room.declared_lod {
+                    Some(target) => room.rollup_lod < target,
+                    None => room.rollup_score < min_score,
+                };
+                if below {
+                    out.push((floor, room));
+                }
+            }
+        }
+        out
+    }
+
+    /// Short lines for dashboards.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Completeness: {:.0}% (LOD {})",
+            self.score * 100.0,
+            self.lod
+        )];
+        for floor in &self.floors {
+            lines.push(format!(
+                "  {}: {:.0}% (LOD {}, {} room(s))",
+                floor.name,
+                floor.score * 100.0,
+                floor.lod,
+                floor.rooms.len()
+            ));
+        }
+        lines
+    }
+
+    /// Markdown report for handoff packets.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Completeness report — {}", self.building);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Building: **{:.0}%** (LOD {})",
+            self.score * 100.0,
+            self.lod
+        );
+        let _ = writeln!(out);
+        for floor in &self.floors {
+            let _ = writeln!(
+                out,
+                "## {} — {:.0}% (LOD {})",
+                floor.name,
+                floor.score * 100.0,
+                floor.lod
+            );
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| Room | Score | LOD | Declared | Missing (room) | Equipment |");
+            let _ = writeln!(out, "| --- | ---: | ---: | ---: | --- | ---: |");
+            for room in &floor.rooms {
+                let _ = writeln!(
+                    out,
+                    "| {} | {:.0}% | {} | {} | {} | {} |",
+                    room.room.name,
+                    room.rollup_score * 100.0,
+                    room.rollup_lod,
+                    room.room
+                        .declared_lod
+                        .map(|l| l.to_string())
+                        .unwrap_or_else(|| "—".into()),
+                    room.room.checks.missing().join(", "),
+                    room.equipment.len()
+                );
+            }
+            let _ = writeln!(out);
+        }
+        out
+    }
+}
+
+fn has_data_properties(props: &std::collections::HashMap<String, String>) -> bool {
+    props.iter().any(|(k, v)| {
+        !v.trim().is_empty()
+            && !NON_DATA_PROPERTIES.contains(&k.as_str())
+            && !is_photo_key(k)
+    })
+}
+
+fn is_photo_key(key: &str) -> bool {
+    let k = key.to_ascii_lowercase();
+    PHOTO_PROPERTY_PREFIXES.iter().any(|p| k.starts_with(p))
+}
+
+fn has_photos(props: &std::collections::HashMap<String, String>) -> bool {
+    props
+        .iter()
+        .any(|(k, v)| is_photo_key(k) && !v.trim().is_empty())
+}
+
+fn declared_lod(props: &std::collections::HashMap<String, String>) -> Option<u16> {
+    props
+        .get(PROP_LOD)
+        .and_then(|v| v.trim().trim_start_matches("LOD").trim().parse().ok())
+}
+
+/// Checks for one equipment item.
+pub fn equipment_checks(eq: &Equipment) -> CompletenessChecks {
+    let p = &eq.position;
+    CompletenessChecks {
+        geometry: eq.mesh.is_some() || p.x != 0.0 || p.y != 0.0 || p.z != 0.0,
+        properties: has_data_properties(&eq.properties),
+        sensors: eq
+            .sensor_mappings
+            .as_ref()
+            .map(|m| !m.is_empty())
+            .unwrap_or(false),
+        photos: has_photos(&eq.properties) || !eq.attachments.is_empty(),
+    }
+}
+
+/// Checks for one room. A room counts as sensor-mapped when any of its
+/// equipment carries a sensor mapping.
+pub fn room_checks(room: &Room) -> CompletenessChecks {
+    let d = &room.spatial_properties.dimensions;
+    CompletenessChecks {
+        geometry: room.spatial_properties.mesh.is_some()
+            || (d.width > 0.0 && d.depth > 0.0 && d.height > 0.0),
+        properties: has_data_properties(&room.properties),
+        sensors: room.equipment.iter().any(|e| equipment_checks(e).sensors),
+        photos: has_photos(&room.properties) || !room.attachments.is_empty(),
+    }
+}
+
+fn entity(
+    id: &str,
+    name: &str,
+    checks: CompletenessChecks,
+    props: &std::collections::HashMap<String, String>,
+) -> EntityCompleteness {
+    let score = checks.score();
+    EntityCompleteness {
+        id: id.to_string(),
+        name: name.to_string(),
+        checks,
+        score,
+        achieved_lod: lod_for_score(score),
+        declared_lod: declared_lod(props),
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f64
+    }
+}
+
+/// Score every room / floor and roll up to the building.
+pub fn assess_completeness(building: &Building) -> CompletenessReport {
+    let mut floors = Vec::new();
+    for floor in &building.floors {
+        let mut rooms = Vec::new();
+        for room in floor.wings.iter().flat_map(|w| w.rooms.iter()) {
+            let room_entity = entity(&room.id, &room.name, room_checks(room), &room.properties);
+            let equipment: Vec<EntityCompleteness> = room
+                .equipment
+                .iter()
+                .map(|e| entity(&e.id, &e.name, equipment_checks(e), &e.properties))
+                .collect();
+            let rollup_score = mean(
+                std::iter::once(room_entity.score).chain(equipment.iter().map(|e| e.score)),
+            );
+            rooms.push(RoomCompleteness {
+                room: room_entity,
+                equipment,
+                rollup_score,
+                rollup_lod: lod_for_score(rollup_score),
+            });
+        }
+        let score = mean(rooms.iter().map(|r| r.rollup_score));
+        floors.push(FloorCompleteness {
+            name: floor.name.clone(),
+            level: floor.level,
+            rooms,
+            score,
+            lod: lod_for_score(score),
+        });
+    }
+    floors.sort_by_key(|f| f.level);
+    let score = mean(floors.iter().filter(|f| !f.rooms.is_empty()).map(|f| f.score));
+    CompletenessReport {
+        building: building.name.clone(),
+        floors,
+        score,
+        lod: lod_for_score(score),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::equipment::SensorMapping;
+    use crate::core::{EquipmentType, Floor, Position, RoomType, Wing};
+    use std::collections::HashMap;
+
+    fn sample() -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("A".into());
+
+        let mut room = Room::new("Lab".into(), RoomType::Laboratory);
+        room.properties.insert("use".into(), "wet lab".into());
+        room.properties.insert(PROP_LOD.into(), "LOD 400".into());
+
+        let mut eq = Equipment::new("Fume Hood".into(), String::new(), EquipmentType::HVAC);
+        eq.set_position(Position {
+            x: 1.0,
+            y: 2.0,
+            z: 0.0,
+            coordinate_system: "building_local".into(),
+        });
+        eq.add_property("manufacturer".into(), "Labconco".into());
+        eq.add_property("photo_front".into(), "photos/hood.jpg".into());
+        eq.sensor_mappings = Some(vec![SensorMapping {
+            sensor_id: "s-1".into(),
+            sensor_type: "airflow".into(),
+            thresholds: HashMap::new(),
+        }]);
+        room.add_equipment(eq);
+
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    #[test]
+    fn scores_entities_and_rolls_up() {
+        let report = assess_completeness(&sample());
+        let room = &report.floors[0].rooms[0];
+        // Room: geometry + properties + sensors (via equipment), no photos
+        assert_eq!(room.room.checks.missing(), vec!["photos"]);
+        assert_eq!(room.room.achieved_lod, 350);
+        assert_eq!(room.room.declared_lod, Some(400));
+        // Equipment fully complete
+        assert_eq!(room.equipment[0].score, 1.0);
+        assert!((room.rollup_score - 0.875).abs() < 1e-9);
+        assert_eq!(report.lod, 350);
+        assert!((report.score - 0.875).abs() < 1e-9);
+    }
+
+    #[test]
+    fn thin_rooms_respect_declared_lod() {
+        let report = assess_completeness(&sample());
+        // Declared LOD 400 but achieved 350 → thin regardless of min score
+        assert_eq!(report.thin_rooms(0.1).len(), 1);
+
+        let md = report.to_markdown();
+        assert!(md.contains("| Lab | 88% | 350 | 400 | photos | 1 |"));
+        assert_eq!(report.summary_lines()[0], "Completeness: 88% (LOD 350)");
+    }
+
+    #[test]
+    fn review_status_is_not_descriptive_data() {
+        let mut room = Room::new("Scan".into(), RoomType::Office);
+        crate::core::mark_proposed(&mut room.properties);
+        assert!(!room_checks(&room).properties);
+    }
+}
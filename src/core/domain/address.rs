@@ -339,20 +339,11 @@ impl ArxAddress {
         }
     }
 
-    /// Whether this address matches a glob pattern against the full path.
-    ///
-    /// Patterns use standard glob wildcards (`*`, `?`) on the full path string,
-    /// e.g. `/usa/ny/*/floor-*/mech/boiler-*`.
+    /// Whether this address matches a hierarchical glob pattern (see
+    /// [`AddressPattern`]), e.g. `/usa/ny/*/*/floor-*/mech/boiler-*` or
+    /// `/usa/**/!kitchen/*`. Invalid patterns match nothing.
     pub fn matches_glob(&self, pattern: &str) -> bool {
-        let pat = if pattern.starts_with('/') {
-            pattern.to_string()
-        } else {
-            format!("/{}", pattern)
-        };
-        match glob::Pattern::new(&pat) {
-            Ok(p) => p.matches(&self.path),
-            Err(_) => false,
-        }
+        AddressPattern::parse(pattern).is_ok_and(|p| p.matches(self))
     }
 
     /// Sanitize a path part for use in addresses
@@ -405,6 +396,125 @@ impl fmt::Display for ArxAddress {
     }
 }
 
+/// One segment of an [`AddressPattern`].
+#[derive(Clone, Debug)]
+enum PatternSegment {
+    /// `**`: zero or more segments
+    AnyDepth,
+    /// Glob on exactly one segment (`*`, `?`, `[a-c]`)
+    Glob(glob::Pattern),
+    /// `!glob`: exactly one segment that does not match
+    Not(glob::Pattern),
+}
+
+/// Compiled hierarchical glob over [`ArxAddress`] paths.
+///
+/// Wildcards apply per segment: `*` and `?` never cross a `/`, `**` spans any
+/// number of segments (including none), and a `!` prefix negates a segment.
+/// Matching is case-insensitive.
+///
+/// ```text
+/// /usa/ny/*/ps-118/floor-*/mech/boiler-*   boilers in any mech room
+/// /usa/**/hvac/*                           HVAC fixtures at any depth
+/// /usa/ny/brooklyn/ps-118/*/!kitchen/*     everything outside kitchens
+/// ```
+#[derive(Clone, Debug)]
+pub struct AddressPattern {
+    raw: String,
+    segments: Vec<PatternSegment>,
+}
+
+impl AddressPattern {
+    /// Compile `pattern`; the leading `/` is optional.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        for part in pattern.trim().split('/').filter(|s| !s.is_empty()) {
+            let part = part.to_lowercase();
+            let segment = if part == "**" {
+                // `**/**` is the same as `**`
+                if matches!(segments.last(), Some(PatternSegment::AnyDepth)) {
+                    continue;
+                }
+                PatternSegment::AnyDepth
+            } else if let Some(negated) = part.strip_prefix('!') {
+                PatternSegment::Not(Self::compile(pattern, negated)?)
+            } else {
+                PatternSegment::Glob(Self::compile(pattern, &part)?)
+            };
+            segments.push(segment);
+        }
+        if segments.is_empty() {
+            return Err(ArxError::path_invalid(
+                pattern,
+                "Pattern must contain at least one segment",
+            )
+            .into());
+        }
+        Ok(Self {
+            raw: pattern.trim().to_string(),
+            segments,
+        })
+    }
+
+    fn compile(pattern: &str, segment: &str) -> Result<glob::Pattern> {
+        if segment.is_empty() || segment.contains("**") {
+            return Err(ArxError::path_invalid(
+                pattern,
+                "`**` must be a whole segment and `!` needs a pattern",
+            )
+            .into());
+        }
+        glob::Pattern::new(segment).map_err(|e| {
+            ArxError::path_invalid(
+                pattern.to_string(),
+                format!("Invalid glob segment '{}': {}", segment, e),
+            )
+            .into()
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn matches(&self, address: &ArxAddress) -> bool {
+        self.matches_path(&address.path)
+    }
+
+    /// Match a raw `/`-separated path.
+    pub fn matches_path(&self, path: &str) -> bool {
+        let path = path.to_lowercase();
+        let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        Self::match_segments(&self.segments, &parts)
+    }
+
+    fn match_segments(pattern: &[PatternSegment], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((PatternSegment::AnyDepth, rest)) => {
+                (0..=path.len()).any(|skip| Self::match_segments(rest, &path[skip..]))
+            }
+            Some((segment, rest)) => match path.split_first() {
+                None => false,
+                Some((part, path)) => {
+                    let hit = match segment {
+                        PatternSegment::Glob(glob) => glob.matches(part),
+                        PatternSegment::Not(glob) => !glob.matches(part),
+                        PatternSegment::AnyDepth => unreachable!(),
+                    };
+                    hit && Self::match_segments(rest, path)
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for AddressPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -420,10 +530,40 @@ mod tests {
             "mech",
             "boiler-01",
         );
-        assert!(addr.matches_glob("/usa/ny/*/floor-*/mech/boiler-*"));
+        assert!(addr.matches_glob("/usa/ny/*/*/floor-*/mech/boiler-*"));
         assert!(addr.matches_glob("/usa/ny/brooklyn/ps-118/floor-02/mech/*"));
         assert!(!addr.matches_glob("/usa/ny/brooklyn/ps-118/floor-02/kitchen/*"));
         assert!(!addr.matches_glob("/usa/ca/*/floor-*/mech/*"));
+        // `*` stays within one segment
+        assert!(!addr.matches_glob("/usa/ny/*/floor-*/mech/boiler-*"));
+    }
+
+    #[test]
+    fn test_pattern_any_depth_and_negation() {
+        let boiler =
+            ArxAddress::from_path("/usa/ny/brooklyn/ps-118/floor-02/mech/boiler-01").unwrap();
+        let fridge =
+            ArxAddress::from_path("/usa/ny/brooklyn/ps-118/floor-02/kitchen/fridge/pbj-sandwich")
+                .unwrap();
+
+        let any_depth = AddressPattern::parse("/usa/**").unwrap();
+        assert!(any_depth.matches(&boiler) && any_depth.matches(&fridge));
+        assert!(boiler.matches_glob("/**/boiler-*"));
+        assert!(
+            boiler.matches_glob("/usa/**/**/mech/**/boiler-01"),
+            "`**` may match nothing"
+        );
+        assert!(!fridge.matches_glob("/usa/**/mech/*"));
+        assert!(fridge.matches_glob("/usa/**/kitchen/**"));
+
+        let outside_kitchen = AddressPattern::parse("/usa/ny/*/ps-118/*/!kitchen/**").unwrap();
+        assert!(outside_kitchen.matches(&boiler));
+        assert!(!outside_kitchen.matches(&fridge));
+        assert!(boiler.matches_glob("/USA/NY/**/BOILER-0[0-9]"));
+
+        assert!(AddressPattern::parse("/").is_err());
+        assert!(AddressPattern::parse("/usa/a**b").is_err());
+        assert!(AddressPattern::parse("/usa/!").is_err());
     }
 
     #[test]
@@ -0,0 +1,472 @@
+//! Room bookings (calendar reservations) compared with observed occupancy.
+//!
+//! Bookings arrive from calendar adapters (`crate::ingest::bookings`) and are
+//! stored next to the sensor readings log. The utilization report lines up
+//! what was reserved with what a room's occupancy sensors saw. A *ghost
+//! meeting* is a booking during which the sensors kept reporting but never
+//! saw anyone; bookings with no readings at all are left undecided rather
+//! than counted against the organizer.
+
+use crate::core::{Building, Room};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Sensor types read as room occupancy (count or presence; > 0 = occupied).
+pub const OCCUPANCY_SENSOR_TYPES: &[&str] = &["occupancy", "people_count", "presence", "motion"];
+
+/// Bookings shorter than this are not judged as ghosts.
+pub const GHOST_MIN_MINUTES: i64 = 15;
+
+/// Room property holding its seat count (`arx room update <room> --property capacity=12`).
+pub const CAPACITY_PROPERTY: &str = "capacity";
+
+/// `source` of reservations made in ArxOS (`arx room book`).
+pub const LOCAL_BOOKING_SOURCE: &str = "arx";
+
+/// Occupancy readings older than this are not reported as current.
+pub const CURRENT_OCCUPANCY_MINUTES: i64 = 30;
+
+/// One reservation of a room.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomBooking {
+    /// Event id from the calendar (UID / Graph id)
+    pub id: String,
+    /// Adapter that produced it (`ics`, `caldav`, `graph`)
+    pub source: String,
+    /// Room as the calendar names it
+    pub room: String,
+    /// Building room the booking resolved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organizer: Option<String>,
+}
+
+impl RoomBooking {
+    pub fn hours(&self) -> f64 {
+        (self.end - self.start).num_seconds().max(0) as f64 / 3600.0
+    }
+
+    pub fn overlaps(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+        self.start < to && self.end > from
+    }
+}
+
+/// One occupancy reading attributed to a room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OccupancySample {
+    pub room_id: String,
+    pub at: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// What the sensors saw during a booking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Attendance {
+    Attended,
+    Ghost,
+    /// No readings during the booking (or too short to judge)
+    Unknown,
+}
+
+/// A booking with its attendance verdict.
+#[derive(Debug, Clone, Serialize)]
+pub struct BookingCheck {
+    #[serde(flatten)]
+    pub booking: RoomBooking,
+    pub attendance: Attendance,
+    /// Readings seen during the booking
+    pub readings: usize,
+}
+
+/// Booked vs observed use of one room.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RoomUtilization {
+    pub room_id: String,
+    pub room: String,
+    pub bookings: usize,
+    pub booked_hours: f64,
+    /// Clock hours in which any occupancy reading was > 0
+    pub occupied_hours: f64,
+    pub ghost_meetings: usize,
+    pub ghost_hours: f64,
+}
+
+impl RoomUtilization {
+    /// Occupied hours per booked hour (`None` when nothing was booked).
+    pub fn utilization(&self) -> Option<f64> {
+        (self.booked_hours > 0.0).then(|| self.occupied_hours / self.booked_hours)
+    }
+}
+
+/// Utilization-vs-booking report over `[from, to)`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BookingReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Rooms with bookings or occupancy readings, most ghost hours first
+    pub rooms: Vec<RoomUtilization>,
+    pub ghosts: Vec<BookingCheck>,
+    /// Bookings whose room did not match any room in the building
+    pub unresolved_bookings: usize,
+}
+
+fn matches_room(room: &Room, needle: &str) -> bool {
+    room.id == needle || room.matches_name(needle)
+}
+
+/// Resolve each booking's calendar room name to a building room id; returns
+/// how many stayed unresolved.
+pub fn resolve_booking_rooms(building: &Building, bookings: &mut [RoomBooking]) -> usize {
+    let rooms = building.get_all_rooms();
+    let mut unresolved = 0;
+    for booking in bookings.iter_mut() {
+        let needle = booking.room.trim();
+        booking.room_id = rooms
+            .iter()
+            .find(|r| matches_room(r, needle))
+            .map(|r| r.id.clone());
+        if booking.room_id.is_none() {
+            unresolved += 1;
+        }
+    }
+    unresolved
+}
+
+/// The room a booking or command names, by id, name, or alias.
+pub fn find_booking_room<'a>(building: &'a Building, needle: &str) -> Option<&'a Room> {
+    let needle = needle.trim();
+    building
+        .get_all_rooms()
+        .into_iter()
+        .find(|r| matches_room(r, needle))
+}
+
+/// Seat count from the room's `capacity` property.
+pub fn room_capacity(room: &Room) -> Option<u32> {
+    room.properties
+        .get(CAPACITY_PROPERTY)
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Other bookings of the same room that overlap `booking`.
+pub fn booking_conflicts<'a>(
+    booking: &RoomBooking,
+    bookings: &'a [RoomBooking],
+) -> Vec<&'a RoomBooking> {
+    bookings
+        .iter()
+        .filter(|b| b.room_id.is_some() && b.room_id == booking.room_id)
+        .filter(|b| (&b.source, &b.id) != (&booking.source, &booking.id))
+        .filter(|b| b.overlaps(booking.start, booking.end))
+        .collect()
+}
+
+/// A booking on a room's schedule.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledBooking {
+    #[serde(flatten)]
+    pub booking: RoomBooking,
+    /// Ids of overlapping bookings of the same room (double-bookings)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+}
+
+/// One room's bookings over `[from, to)` with its capacity and occupancy.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomSchedule {
+    pub room_id: String,
+    pub room: String,
+    pub capacity: Option<u32>,
+    /// Latest occupancy reading, when one arrived in the last
+    /// [`CURRENT_OCCUPANCY_MINUTES`]
+    pub occupancy: Option<f64>,
+    pub occupancy_at: Option<DateTime<Utc>>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub bookings: Vec<ScheduledBooking>,
+}
+
+impl RoomSchedule {
+    pub fn conflicts(&self) -> usize {
+        self.bookings
+            .iter()
+            .filter(|b| !b.conflicts.is_empty())
+            .count()
+    }
+}
+
+/// `room`'s schedule over `[from, to)`; occupancy is judged at `now`.
+pub fn room_schedule(
+    room: &Room,
+    bookings: &[RoomBooking],
+    samples: &[OccupancySample],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> RoomSchedule {
+    let own: Vec<RoomBooking> = bookings
+        .iter()
+        .filter(|b| b.room_id.as_deref() == Some(room.id.as_str()))
+        .cloned()
+        .collect();
+    let mut scheduled: Vec<ScheduledBooking> = own
+        .iter()
+        .filter(|b| b.overlaps(from, to))
+        .map(|b| ScheduledBooking {
+            booking: b.clone(),
+            conflicts: booking_conflicts(b, &own)
+                .into_iter()
+                .map(|c| c.id.clone())
+                .collect(),
+        })
+        .collect();
+    scheduled.sort_by(|a, b| {
+        a.booking
+            .start
+            .cmp(&b.booking.start)
+            .then_with(|| a.booking.id.cmp(&b.booking.id))
+    });
+
+    let latest = samples
+        .iter()
+        .filter(|s| s.room_id == room.id && s.at <= now)
+        .filter(|s| now - s.at <= Duration::minutes(CURRENT_OCCUPANCY_MINUTES))
+        .max_by_key(|s| s.at);
+    RoomSchedule {
+        room_id: room.id.clone(),
+        room: room.name.clone(),
+        capacity: room_capacity(room),
+        occupancy: latest.map(|s| s.value),
+        occupancy_at: latest.map(|s| s.at),
+        from,
+        to,
+        bookings: scheduled,
+    }
+}
+
+/// Attendance for `booking` given the room's samples.
+pub fn check_booking(booking: &RoomBooking, samples: &[OccupancySample]) -> BookingCheck {
+    let during: Vec<&OccupancySample> = samples
+        .iter()
+        .filter(|s| Some(&s.room_id) == booking.room_id.as_ref())
+        .filter(|s| s.at >= booking.start && s.at < booking.end)
+        .collect();
+    let attendance = if during.iter().any(|s| s.value > 0.0) {
+        Attendance::Attended
+    } else if during.is_empty()
+        || booking.end - booking.start < Duration::minutes(GHOST_MIN_MINUTES)
+    {
+        Attendance::Unknown
+    } else {
+        Attendance::Ghost
+    };
+    BookingCheck {
+        booking: booking.clone(),
+        attendance,
+        readings: during.len(),
+    }
+}
+
+fn room_entry<'a>(
+    rooms: &'a mut BTreeMap<String, RoomUtilization>,
+    names: &BTreeMap<String, String>,
+    room_id: &str,
+) -> Option<&'a mut RoomUtilization> {
+    let name = names.get(room_id)?;
+    Some(
+        rooms
+            .entry(room_id.to_string())
+            .or_insert_with(|| RoomUtilization {
+                room_id: room_id.to_string(),
+                room: name.clone(),
+                ..Default::default()
+            }),
+    )
+}
+
+/// Compare bookings with occupancy samples over `[from, to)`.
+pub fn booking_report(
+    building: &Building,
+    bookings: &[RoomBooking],
+    samples: &[OccupancySample],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> BookingReport {
+    let room_names: BTreeMap<String, String> = building
+        .get_all_rooms()
+        .into_iter()
+        .map(|r| (r.id.clone(), r.name.clone()))
+        .collect();
+    let mut rooms: BTreeMap<String, RoomUtilization> = BTreeMap::new();
+
+    let mut ghosts = Vec::new();
+    let mut unresolved_bookings = 0;
+    for booking in bookings.iter().filter(|b| b.overlaps(from, to)) {
+        let Some(room) = booking
+            .room_id
+            .as_deref()
+            .and_then(|id| room_entry(&mut rooms, &room_names, id))
+        else {
+            unresolved_bookings += 1;
+            continue;
+        };
+        room.bookings += 1;
+        room.booked_hours += booking.hours();
+        let check = check_booking(booking, samples);
+        if check.attendance == Attendance::Ghost {
+            room.ghost_meetings += 1;
+            room.ghost_hours += booking.hours();
+            ghosts.push(check);
+        }
+    }
+
+    let mut occupied: BTreeMap<&str, BTreeSet<DateTime<Utc>>> = BTreeMap::new();
+    for sample in samples.iter().filter(|s| s.at >= from && s.at < to) {
+        if room_entry(&mut rooms, &room_names, &sample.room_id).is_none() {
+            continue;
+        }
+        let hours = occupied.entry(&sample.room_id).or_default();
+        if sample.value > 0.0 {
+            hours.insert(
+                sample
+                    .at
+                    .duration_trunc(Duration::hours(1))
+                    .unwrap_or(sample.at),
+            );
+        }
+    }
+    for (room_id, hours) in occupied {
+        if let Some(room) = rooms.get_mut(room_id) {
+            room.occupied_hours = hours.len() as f64;
+        }
+    }
+
+    let mut rooms: Vec<RoomUtilization> = rooms.into_values().collect();
+    rooms.sort_by(|a, b| {
+        b.ghost_hours
+            .total_cmp(&a.ghost_hours)
+            .then_with(|| a.room.cmp(&b.room))
+    });
+    ghosts.sort_by_key(|c| c.booking.start);
+    BookingReport {
+        from,
+        to,
+        rooms,
+        ghosts,
+        unresolved_bookings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, RoomType, Wing};
+    use chrono::TimeZone;
+
+    fn at(hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 4, hour, min, 0).unwrap()
+    }
+
+    fn booking(id: &str, room: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> RoomBooking {
+        RoomBooking {
+            id: id.into(),
+            source: "ics".into(),
+            room: room.into(),
+            room_id: None,
+            start,
+            end,
+            title: None,
+            organizer: None,
+        }
+    }
+
+    #[test]
+    fn test_report_flags_ghost_meetings() {
+        let mut room = Room::new("Conference A".into(), RoomType::Office);
+        room.id = "room-a".into();
+        let mut wing = Wing::new("East".into());
+        wing.rooms.push(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.wings.push(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let mut bookings = vec![
+            booking("standup", "conference a", at(9, 0), at(10, 0)),
+            booking("ghost", "Conference A", at(11, 0), at(12, 0)),
+            booking("no-data", "Conference A", at(14, 0), at(15, 0)),
+            booking("elsewhere", "Atrium", at(9, 0), at(10, 0)),
+        ];
+        assert_eq!(resolve_booking_rooms(&building, &mut bookings), 1);
+
+        let sample = |h, m, value| OccupancySample {
+            room_id: "room-a".into(),
+            at: at(h, m),
+            value,
+        };
+        let samples = vec![
+            sample(9, 5, 4.0),
+            sample(9, 40, 3.0),
+            sample(11, 10, 0.0),
+            sample(11, 40, 0.0),
+            sample(16, 30, 2.0),
+        ];
+        let report = booking_report(&building, &bookings, &samples, at(0, 0), at(23, 0));
+        assert_eq!(report.unresolved_bookings, 1);
+        assert_eq!(report.rooms.len(), 1);
+        let room = &report.rooms[0];
+        assert_eq!((room.bookings, room.ghost_meetings), (3, 1));
+        assert_eq!((room.booked_hours, room.occupied_hours), (3.0, 2.0));
+        assert_eq!(report.ghosts.len(), 1);
+        assert_eq!(report.ghosts[0].booking.id, "ghost");
+        assert_eq!(
+            check_booking(&bookings[2], &samples).attendance,
+            Attendance::Unknown
+        );
+    }
+
+    #[test]
+    fn test_schedule_flags_double_bookings() {
+        let mut room = Room::new("Huddle".into(), RoomType::Office);
+        room.id = "huddle".into();
+        room.properties.insert(CAPACITY_PROPERTY.into(), "6".into());
+        let resolved = |id: &str, start, end| RoomBooking {
+            room_id: Some("huddle".into()),
+            ..booking(id, "Huddle", start, end)
+        };
+        let bookings = vec![
+            resolved("a", at(9, 0), at(10, 0)),
+            resolved("b", at(9, 30), at(11, 0)),
+            resolved("c", at(11, 0), at(12, 0)),
+        ];
+        assert_eq!(booking_conflicts(&bookings[2], &bookings).len(), 0);
+
+        let samples = vec![
+            OccupancySample {
+                room_id: "huddle".into(),
+                at: at(9, 40),
+                value: 5.0,
+            },
+            OccupancySample {
+                room_id: "huddle".into(),
+                at: at(8, 0),
+                value: 1.0,
+            },
+        ];
+        let schedule = room_schedule(&room, &bookings, &samples, at(0, 0), at(23, 0), at(9, 45));
+        assert_eq!(schedule.capacity, Some(6));
+        assert_eq!(schedule.occupancy, Some(5.0));
+        assert_eq!(schedule.conflicts(), 2);
+        assert_eq!(schedule.bookings[0].conflicts, ["b"]);
+
+        let later = room_schedule(&room, &bookings, &samples, at(0, 0), at(23, 0), at(12, 0));
+        assert_eq!(later.occupancy, None);
+    }
+}
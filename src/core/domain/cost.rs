@@ -0,0 +1,256 @@
+//! Cost rollups over work orders and equipment.
+//!
+//! Work orders carry the labor and parts cost recorded when they were closed;
+//! equipment may carry its acquisition cost in the `purchase_cost` property.
+//! [`rollup`] groups those into budget lines by floor, system, month, or
+//! equipment (lifetime cost: acquisition plus every work order on it).
+
+use super::economy::Money;
+use super::workorder::WorkOrder;
+use crate::core::Building;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Equipment property holding its acquisition cost (`12500`, `12500 EUR`).
+pub const PURCHASE_COST_PROPERTY: &str = "purchase_cost";
+
+/// What a cost report groups by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostGrouping {
+    Floor,
+    System,
+    Month,
+    Equipment,
+}
+
+impl FromStr for CostGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "floor" => Ok(CostGrouping::Floor),
+            "system" => Ok(CostGrouping::System),
+            "month" => Ok(CostGrouping::Month),
+            "equipment" => Ok(CostGrouping::Equipment),
+            _ => Err(format!(
+                "Unknown grouping '{}'. Use: floor, system, month, equipment",
+                s
+            )),
+        }
+    }
+}
+
+/// One budget line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostLine {
+    pub key: String,
+    /// Work orders with a recorded cost
+    pub work_orders: usize,
+    pub labor: Money,
+    pub parts: Money,
+    /// Equipment acquisition (equipment grouping only)
+    pub acquisition: Money,
+    pub total: Money,
+}
+
+impl CostLine {
+    fn new(key: String) -> Self {
+        Self {
+            key,
+            work_orders: 0,
+            labor: Money::default(),
+            parts: Money::default(),
+            acquisition: Money::default(),
+            total: Money::default(),
+        }
+    }
+
+    fn add_order(&mut self, order: &WorkOrder) -> Result<(), String> {
+        self.work_orders += 1;
+        if let Some(labor) = &order.labor_cost {
+            self.labor = self.labor.checked_add(labor)?;
+        }
+        if let Some(parts) = &order.parts_cost {
+            self.parts = self.parts.checked_add(parts)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.total = self
+            .labor
+            .checked_add(&self.parts)?
+            .checked_add(&self.acquisition)?;
+        Ok(())
+    }
+}
+
+fn acquisition_cost(eq: &crate::core::Equipment) -> Result<Option<Money>, String> {
+    eq.properties
+        .get(PURCHASE_COST_PROPERTY)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|e| format!("{} of {}: {}", PURCHASE_COST_PROPERTY, eq.name, e))
+        })
+        .transpose()
+}
+
+/// Budget lines of `orders` (those with a recorded cost) grouped by `by`,
+/// sorted by key, plus the grand total line (`key: "total"`).
+pub fn rollup(
+    building: &Building,
+    orders: &[WorkOrder],
+    by: CostGrouping,
+) -> Result<(Vec<CostLine>, CostLine), String> {
+    let mut lines: BTreeMap<String, CostLine> = BTreeMap::new();
+    if by == CostGrouping::Equipment {
+        for eq in building.get_all_equipment() {
+            if let Some(cost) = acquisition_cost(eq)? {
+                lines
+                    .entry(eq.name.clone())
+                    .or_insert_with(|| CostLine::new(eq.name.clone()))
+                    .acquisition = cost;
+            }
+        }
+    }
+    for order in orders {
+        if order.labor_cost.is_none() && order.parts_cost.is_none() {
+            continue;
+        }
+        let equipment = order
+            .equipment_id
+            .as_deref()
+            .and_then(|id| building.find_equipment(id));
+        let key = match by {
+            CostGrouping::Floor => order
+                .floor_level
+                .map(|level| format!("floor {}", level))
+                .unwrap_or_else(|| "unlocated".to_string()),
+            CostGrouping::System => equipment
+                .map(|eq| eq.system_type())
+                .unwrap_or_else(|| "ROOM".to_string()),
+            CostGrouping::Month => order
+                .closed_at
+                .unwrap_or(order.created_at)
+                .format("%Y-%m")
+                .to_string(),
+            CostGrouping::Equipment => match (equipment, &order.equipment_name) {
+                (Some(eq), _) => eq.name.clone(),
+                (None, Some(name)) => name.clone(),
+                (None, None) => "(rooms)".to_string(),
+            },
+        };
+        lines
+            .entry(key.clone())
+            .or_insert_with(|| CostLine::new(key))
+            .add_order(order)?;
+    }
+
+    let mut total = CostLine::new("total".to_string());
+    let mut out = Vec::with_capacity(lines.len());
+    for (_, mut line) in lines {
+        line.finish()?;
+        total.work_orders += line.work_orders;
+        total.labor = total.labor.checked_add(&line.labor)?;
+        total.parts = total.parts.checked_add(&line.parts)?;
+        total.acquisition = total.acquisition.checked_add(&line.acquisition)?;
+        out.push(line);
+    }
+    total.finish()?;
+    Ok((out, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::workorder::{create_work_order, WorkOrderDraft};
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn money_parses_adds_and_refuses_mixed_currencies() {
+        let a: Money = "125.5".parse().unwrap();
+        assert_eq!(a.to_string(), "125.50 USD");
+        assert_eq!("EUR 3".parse::<Money>().unwrap().to_string(), "3.00 EUR");
+        assert!("-4".parse::<Money>().is_err());
+        assert_eq!(Money::default().checked_add(&a).unwrap(), a);
+        assert_eq!(a.checked_add(&Money::default()).unwrap(), a);
+        assert_eq!(
+            a.times(2).checked_add(&a).unwrap().to_string(),
+            "376.50 USD"
+        );
+        assert!(a.checked_add(&"1 EUR".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn rolls_up_work_order_costs_and_equipment_lifetime() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        for level in [1, 2] {
+            let mut room = Room::new(format!("Mech {}", level), RoomType::Mechanical);
+            let mut ahu =
+                Equipment::new(format!("AHU-{}", level), String::new(), EquipmentType::HVAC);
+            ahu.properties
+                .insert(PURCHASE_COST_PROPERTY.into(), "10000".into());
+            room.equipment.push(ahu);
+            room.equipment.push(Equipment::new(
+                format!("Pump-{}", level),
+                String::new(),
+                EquipmentType::Plumbing,
+            ));
+            let mut wing = Wing::new("Main".into());
+            wing.rooms.push(room);
+            let mut floor = Floor::new(format!("Floor {}", level), level);
+            floor.wings.push(wing);
+            building.add_floor(floor);
+        }
+        let order = |equipment: &str, labor: Option<&str>, parts: Option<&str>| {
+            let draft = WorkOrderDraft {
+                title: "Service".into(),
+                equipment: Some(equipment.into()),
+                ..Default::default()
+            };
+            let mut order =
+                create_work_order(&building, &draft, format!("WO-{}", equipment)).unwrap();
+            order.labor_cost = labor.map(|c| c.parse().unwrap());
+            order.parts_cost = parts.map(|c| c.parse().unwrap());
+            order
+        };
+        let orders = vec![
+            order("AHU-1", Some("200"), Some("50.25")),
+            order("Pump-1", Some("80"), None),
+            order("AHU-2", None, Some("19.75")),
+            order("Pump-2", None, None),
+        ];
+
+        let (lines, total) = rollup(&building, &orders, CostGrouping::System).unwrap();
+        let summary: Vec<(&str, usize, String)> = lines
+            .iter()
+            .map(|l| (l.key.as_str(), l.work_orders, l.total.to_string()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("HVAC", 2, "270.00 USD".to_string()),
+                ("PLUMBING", 1, "80.00 USD".to_string())
+            ]
+        );
+        assert_eq!(total.total.to_string(), "350.00 USD");
+
+        let (lines, _) = rollup(&building, &orders, CostGrouping::Floor).unwrap();
+        assert_eq!(lines[0].key, "floor 1");
+        assert_eq!(lines[0].total.to_string(), "330.25 USD");
+
+        let (lines, total) = rollup(&building, &orders, CostGrouping::Equipment).unwrap();
+        assert_eq!(lines[0].key, "AHU-1");
+        assert_eq!(lines[0].total.to_string(), "10250.25 USD");
+        assert_eq!(total.acquisition.to_string(), "20000.00 USD");
+        assert!(rollup(
+            &building,
+            &orders,
+            "room".parse().unwrap_or(CostGrouping::Month)
+        )
+        .is_ok());
+        assert!("room".parse::<CostGrouping>().is_err());
+    }
+}
@@ -32,6 +32,85 @@ impl Money {
     pub fn zero() -> Self {
         Self::usd_cents(0)
     }
+
+    /// `amount` in major units of `currency` (e.g. 12.5 EUR).
+    pub fn from_amount(currency: &str, amount: f64) -> Self {
+        Self {
+            currency: Cow::Owned(currency.to_uppercase()),
+            amount_cents: (amount * 100.0).round() as u128,
+        }
+    }
+
+    /// `self × quantity`.
+    pub fn times(&self, quantity: u32) -> Self {
+        Self {
+            currency: self.currency.clone(),
+            amount_cents: self.amount_cents * u128::from(quantity),
+        }
+    }
+
+    /// Sum of two amounts; a zero amount takes the other's currency, and
+    /// other currency mismatches are an error.
+    pub fn checked_add(&self, other: &Money) -> Result<Money, String> {
+        if other.amount_cents == 0 && !self.currency.is_empty() {
+            return Ok(self.clone());
+        }
+        if self.amount_cents == 0 {
+            return Ok(other.clone());
+        }
+        if other.amount_cents == 0 {
+            return Ok(self.clone());
+        }
+        if self.currency != other.currency {
+            return Err(format!(
+                "cannot add {} and {} amounts",
+                self.currency, other.currency
+            ));
+        }
+        Ok(Self {
+            currency: self.currency.clone(),
+            amount_cents: self.amount_cents + other.amount_cents,
+        })
+    }
+}
+
+/// `1234.50 USD`.
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{:02} {}",
+            self.amount_cents / 100,
+            self.amount_cents % 100,
+            self.currency
+        )
+    }
+}
+
+/// `125.50` (USD), `125.50 EUR`, or `EUR 125.50`.
+impl std::str::FromStr for Money {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let (amount, currency) = match parts.as_slice() {
+            [amount] => (*amount, "USD"),
+            [a, b] if a.parse::<f64>().is_ok() => (*a, *b),
+            [a, b] => (*b, *a),
+            _ => return Err(format!("'{}': expected an amount like 125.50 or 125.50 EUR", s)),
+        };
+        let value: f64 = amount
+            .trim_start_matches('$')
+            .parse()
+            .map_err(|_| format!("'{}': amount is not a number", s))?;
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("'{}': amount must be ≥ 0", s));
+        }
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("'{}': currency must be an ISO 4217 code", s));
+        }
+        Ok(Self::from_amount(currency, value))
+    }
 }
 
 /// Tax assessment data captured for a building.
@@ -0,0 +1,409 @@
+//! Occupant issue reports ("problem in room X") and their triage.
+//!
+//! An [`IssueReport`] is what an intake form or agent action submits; triage
+//! resolves the room / equipment it refers to against the Building and assigns
+//! a priority, producing an [`Issue`] record the store persists.
+
+use crate::core::clock;
+use crate::core::{Building, Equipment, Floor, Room};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Longest description accepted from intake (characters).
+pub const MAX_ISSUE_DESCRIPTION_LEN: usize = 2000;
+
+/// Keywords that mark a report urgent (life safety / water / gas).
+const URGENT_KEYWORDS: &[&str] = &["fire", "smoke", "gas", "leak", "flood", "sparks", "injur"];
+/// Keywords that mark a report high priority (loss of service).
+const HIGH_KEYWORDS: &[&str] = &[
+    "no heat",
+    "no power",
+    "not working",
+    "broken",
+    "outage",
+    "too hot",
+    "too cold",
+];
+
+/// What an occupant or external form submits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueReport {
+    /// Room id, name / alias, or address path
+    #[serde(default)]
+    pub room: Option<String>,
+    /// Equipment id or name / alias (optional)
+    #[serde(default)]
+    pub equipment: Option<String>,
+    pub description: String,
+    #[serde(default)]
+    pub reporter: Option<String>,
+    /// Where the report came from (e.g. "room-page", "form", "agent")
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssuePriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl fmt::Display for IssuePriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssuePriority::Low => write!(f, "low"),
+            IssuePriority::Normal => write!(f, "normal"),
+            IssuePriority::High => write!(f, "high"),
+            IssuePriority::Urgent => write!(f, "urgent"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueStatus {
+    /// Received but the room could not be resolved
+    #[default]
+    New,
+    /// Linked to a room / equipment and prioritised
+    Triaged,
+    InProgress,
+    Resolved,
+    Closed,
+}
+
+impl IssueStatus {
+    pub fn is_open(&self) -> bool {
+        !matches!(self, IssueStatus::Resolved | IssueStatus::Closed)
+    }
+}
+
+impl fmt::Display for IssueStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueStatus::New => write!(f, "new"),
+            IssueStatus::Triaged => write!(f, "triaged"),
+            IssueStatus::InProgress => write!(f, "in_progress"),
+            IssueStatus::Resolved => write!(f, "resolved"),
+            IssueStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// A triaged issue record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Issue {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub status: IssueStatus,
+    pub priority: IssuePriority,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub floor_level: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reporter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// The room / equipment text as submitted (kept when it could not be resolved)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reported_location: Option<String>,
+}
+
+impl Issue {
+    /// One-line summary for dashboards / notifications.
+    pub fn summary(&self) -> String {
+        let location = self
+            .room_name
+            .clone()
+            .or_else(|| self.reported_location.clone())
+            .unwrap_or_else(|| "unknown location".into());
+        let mut text: String = self.description.chars().take(60).collect();
+        if self.description.chars().count() > 60 {
+            text.push('…');
+        }
+        format!("[{}] {} — {}: {}", self.priority, self.id, location, text)
+    }
+}
+
+fn matches_room(room: &Room, needle: &str) -> bool {
+    room.id == needle
+        || room.matches_name(needle)
+        || room
+            .address
+            .as_ref()
+            .map(|a| a.path.eq_ignore_ascii_case(needle))
+            .unwrap_or(false)
+}
+
+fn matches_equipment(eq: &Equipment, needle: &str) -> bool {
+    eq.id == needle
+        || eq.matches_name(needle)
+        || eq
+            .address
+            .as_ref()
+            .map(|a| a.path.eq_ignore_ascii_case(needle))
+            .unwrap_or(false)
+}
+
+fn rooms_with_floor(building: &Building) -> impl Iterator<Item = (&Floor, &Room)> {
+    building.floors.iter().flat_map(|f| {
+        f.wings
+            .iter()
+            .flat_map(move |w| w.rooms.iter().map(move |r| (f, r)))
+    })
+}
+
+/// Find the room and equipment a report refers to.
+///
+/// Equipment is looked up inside the resolved room first, then building-wide
+/// (in which case its room becomes the located room).
+pub(crate) fn locate<'a>(
+    building: &'a Building,
+    room: Option<&str>,
+    equipment: Option<&str>,
+) -> (Option<(&'a Floor, &'a Room)>, Option<&'a Equipment>) {
+    let mut located: Option<(&Floor, &Room)> =
+        room.and_then(|n| rooms_with_floor(building).find(|(_, r)| matches_room(r, n)));
+    let mut found: Option<&Equipment> = None;
+    if let Some(needle) = equipment {
+        found =
+            located.and_then(|(_, r)| r.equipment.iter().find(|e| matches_equipment(e, needle)));
+        if found.is_none() {
+            for (floor, room) in rooms_with_floor(building) {
+                if let Some(eq) = room.equipment.iter().find(|e| matches_equipment(e, needle)) {
+                    found = Some(eq);
+                    if located.is_none() {
+                        located = Some((floor, room));
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    (located, found)
+}
+
+/// Keyword-based priority for a free-text description.
+pub fn classify_priority(description: &str) -> IssuePriority {
+    let text = description.to_lowercase();
+    if URGENT_KEYWORDS.iter().any(|k| text.contains(k)) {
+        IssuePriority::Urgent
+    } else if HIGH_KEYWORDS.iter().any(|k| text.contains(k)) {
+        IssuePriority::High
+    } else {
+        IssuePriority::Normal
+    }
+}
+
+/// Check a report is acceptable for intake.
+pub fn validate_report(report: &IssueReport) -> Result<(), String> {
+    let description = report.description.trim();
+    if description.is_empty() {
+        return Err("description is required".into());
+    }
+    if description.chars().count() > MAX_ISSUE_DESCRIPTION_LEN {
+        return Err(format!(
+            "description exceeds {} characters",
+            MAX_ISSUE_DESCRIPTION_LEN
+        ));
+    }
+    if report
+        .room
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or("")
+        .is_empty()
+        && report
+            .equipment
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or("")
+            .is_empty()
+    {
+        return Err("room or equipment is required".into());
+    }
+    Ok(())
+}
+
+/// Resolve the report against the building and build an [`Issue`].
+///
+/// See `locate` for how room and equipment are matched. Unresolved reports
+/// are kept with status `new` so nothing submitted is lost.
+pub fn triage_report(building: &Building, report: &IssueReport, id: String) -> Issue {
+    let room_needle = report
+        .room
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let eq_needle = report
+        .equipment
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let (located, equipment) = locate(building, room_needle, eq_needle);
+
+    let reported_location = match (room_needle, eq_needle) {
+        (Some(r), Some(e)) => Some(format!("{} / {}", r, e)),
+        (Some(r), None) => Some(r.to_string()),
+        (None, Some(e)) => Some(e.to_string()),
+        (None, None) => None,
+    };
+
+    Issue {
+        id,
+        created_at: clock::now(),
+        status: if located.is_some() {
+            IssueStatus::Triaged
+        } else {
+            IssueStatus::New
+        },
+        priority: classify_priority(&report.description),
+        description: report.description.trim().to_string(),
+        room_id: located.map(|(_, r)| r.id.clone()),
+        room_name: located.map(|(_, r)| r.name.clone()),
+        floor_level: located.map(|(f, _)| f.level),
+        equipment_id: equipment.map(|e| e.id.clone()),
+        equipment_name: equipment.map(|e| e.name.clone()),
+        reporter: report.reporter.clone(),
+        source: report.source.clone(),
+        reported_location: if located.is_some() {
+            None
+        } else {
+            reported_location
+        },
+    }
+}
+
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Flat CSV for CMMS work-request import.
+pub fn issues_to_csv(issues: &[Issue]) -> String {
+    let mut out = String::from(
+        "id,created_at,status,priority,floor,room_id,room,equipment_id,equipment,description,reporter,source\n",
+    );
+    for i in issues {
+        let row = [
+            i.id.clone(),
+            i.created_at.to_rfc3339(),
+            i.status.to_string(),
+            i.priority.to_string(),
+            i.floor_level.map(|l| l.to_string()).unwrap_or_default(),
+            i.room_id.clone().unwrap_or_default(),
+            i.room_name
+                .clone()
+                .or_else(|| i.reported_location.clone())
+                .unwrap_or_default(),
+            i.equipment_id.clone().unwrap_or_default(),
+            i.equipment_name.clone().unwrap_or_default(),
+            i.description.clone(),
+            i.reporter.clone().unwrap_or_default(),
+            i.source.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentType, RoomType, Wing};
+
+    fn sample() -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Second".into(), 2);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("R-2.113".into(), RoomType::Office);
+        room.add_equipment(Equipment::new(
+            "VAV-7".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    #[test]
+    fn triage_resolves_room_by_name_and_prioritises() {
+        let b = sample();
+        let report = IssueReport {
+            room: Some("r-2.113".into()),
+            description: "Water leak under the window".into(),
+            ..Default::default()
+        };
+        let issue = triage_report(&b, &report, "ISS-1".into());
+        assert_eq!(issue.status, IssueStatus::Triaged);
+        assert_eq!(issue.priority, IssuePriority::Urgent);
+        assert_eq!(issue.room_name.as_deref(), Some("R-2.113"));
+        assert_eq!(issue.floor_level, Some(2));
+        assert!(issue.reported_location.is_none());
+    }
+
+    #[test]
+    fn equipment_only_report_finds_its_room() {
+        let b = sample();
+        let report = IssueReport {
+            equipment: Some("VAV-7".into()),
+            description: "Too cold all afternoon".into(),
+            ..Default::default()
+        };
+        let issue = triage_report(&b, &report, "ISS-2".into());
+        assert_eq!(issue.equipment_name.as_deref(), Some("VAV-7"));
+        assert_eq!(issue.room_name.as_deref(), Some("R-2.113"));
+        assert_eq!(issue.priority, IssuePriority::High);
+    }
+
+    #[test]
+    fn unresolved_reports_are_kept_as_new() {
+        let b = sample();
+        let report = IssueReport {
+            room: Some("the fishbowl".into()),
+            description: "Flickering light, \"again\"".into(),
+            ..Default::default()
+        };
+        assert!(validate_report(&report).is_ok());
+        let issue = triage_report(&b, &report, "ISS-3".into());
+        assert_eq!(issue.status, IssueStatus::New);
+        assert_eq!(issue.reported_location.as_deref(), Some("the fishbowl"));
+
+        let csv = issues_to_csv(&[issue]);
+        assert!(csv.contains(",the fishbowl,,,\"Flickering light, \"\"again\"\"\","));
+    }
+
+    #[test]
+    fn validate_rejects_empty_or_unlocated_reports() {
+        let mut report = IssueReport {
+            description: "  ".into(),
+            room: Some("R-2.113".into()),
+            ..Default::default()
+        };
+        assert!(validate_report(&report).is_err());
+        report.description = "Door sticks".into();
+        report.room = None;
+        assert!(validate_report(&report).is_err());
+    }
+}
@@ -4,7 +4,13 @@
 //! building-related entities.
 
 pub mod address;
+pub mod booking;
+pub mod cost;
 pub mod economy;
+pub mod issue;
+pub mod workorder;
 
-pub use address::{ArxAddress, RESERVED_SYSTEMS};
+pub use address::{AddressPattern, ArxAddress, RESERVED_SYSTEMS};
 pub use economy::{BuildingValuation, ContributionRecord, EconomySnapshot, Money, RevenuePayout};
+pub use issue::{Issue, IssuePriority, IssueReport, IssueStatus};
+pub use workorder::{WorkOrder, WorkOrderComment, WorkOrderDraft, WorkOrderStatus};
@@ -0,0 +1,331 @@
+//! Work orders: maintenance jobs tied to a room or piece of equipment.
+//!
+//! A [`WorkOrderDraft`] names its target by id, name / alias, or address;
+//! [`create_work_order`] resolves it against the Building. The record then
+//! moves through `open → assigned → in_progress → closed`, collecting
+//! comments along the way.
+
+use super::economy::Money;
+use super::issue::{locate, IssuePriority};
+use crate::core::clock;
+use crate::core::inventory::PartUsage;
+use crate::core::Building;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkOrderStatus {
+    #[default]
+    Open,
+    Assigned,
+    InProgress,
+    Closed,
+}
+
+impl WorkOrderStatus {
+    pub fn is_open(&self) -> bool {
+        !matches!(self, WorkOrderStatus::Closed)
+    }
+}
+
+impl fmt::Display for WorkOrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkOrderStatus::Open => write!(f, "open"),
+            WorkOrderStatus::Assigned => write!(f, "assigned"),
+            WorkOrderStatus::InProgress => write!(f, "in_progress"),
+            WorkOrderStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// What `arx workorder create` (or an integration) supplies.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkOrderDraft {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Room id, name / alias, or address path
+    #[serde(default)]
+    pub room: Option<String>,
+    /// Equipment id, name / alias, or address path
+    #[serde(default)]
+    pub equipment: Option<String>,
+    #[serde(default)]
+    pub priority: IssuePriority,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Issue this work order addresses
+    #[serde(default)]
+    pub issue_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkOrderComment {
+    pub at: DateTime<Utc>,
+    pub author: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOrder {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub status: WorkOrderStatus,
+    pub priority: IssuePriority,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub floor_level: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<WorkOrderComment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    /// Spare parts taken out of inventory for this job
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts_used: Vec<PartUsage>,
+    /// Labor cost recorded at close
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labor_cost: Option<Money>,
+    /// Parts cost at the inventory's unit costs when the parts were used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parts_cost: Option<Money>,
+}
+
+impl WorkOrder {
+    /// Labor plus parts; `None` when no cost was recorded.
+    pub fn total_cost(&self) -> Result<Option<Money>, String> {
+        match (&self.labor_cost, &self.parts_cost) {
+            (None, None) => Ok(None),
+            (labor, parts) => labor
+                .clone()
+                .unwrap_or_default()
+                .checked_add(&parts.clone().unwrap_or_default())
+                .map(Some),
+        }
+    }
+
+    /// One-line summary for listings.
+    pub fn summary(&self) -> String {
+        let target = match (&self.room_name, &self.equipment_name) {
+            (Some(room), Some(eq)) => format!("{} / {}", room, eq),
+            (Some(room), None) => room.clone(),
+            (None, Some(eq)) => eq.clone(),
+            (None, None) => "unlocated".to_string(),
+        };
+        let assignee = self
+            .assignee
+            .as_deref()
+            .map(|a| format!(" → {}", a))
+            .unwrap_or_default();
+        format!(
+            "[{}] {} — {}: {}{}",
+            self.priority, self.id, target, self.title, assignee
+        )
+    }
+
+    /// Hand the work order to `assignee`; an open order becomes `assigned`.
+    pub fn assign(&mut self, assignee: &str) -> Result<(), String> {
+        self.ensure_open()?;
+        let assignee = assignee.trim();
+        if assignee.is_empty() {
+            return Err("assignee is required".into());
+        }
+        self.assignee = Some(assignee.to_string());
+        if self.status == WorkOrderStatus::Open {
+            self.status = WorkOrderStatus::Assigned;
+        }
+        Ok(())
+    }
+
+    /// Mark work as started.
+    pub fn start(&mut self) -> Result<(), String> {
+        self.ensure_open()?;
+        self.status = WorkOrderStatus::InProgress;
+        Ok(())
+    }
+
+    pub fn comment(&mut self, author: &str, text: &str) -> Result<(), String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("comment text is required".into());
+        }
+        self.comments.push(WorkOrderComment {
+            at: clock::now(),
+            author: author.to_string(),
+            text: text.to_string(),
+        });
+        Ok(())
+    }
+
+    pub fn close(&mut self, resolution: Option<&str>) -> Result<(), String> {
+        self.ensure_open()?;
+        self.status = WorkOrderStatus::Closed;
+        self.closed_at = Some(clock::now());
+        self.resolution = resolution
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string);
+        Ok(())
+    }
+
+    fn ensure_open(&self) -> Result<(), String> {
+        if self.status.is_open() {
+            Ok(())
+        } else {
+            Err(format!("work order {} is closed", self.id))
+        }
+    }
+}
+
+/// Resolve `draft` against the building and build a [`WorkOrder`].
+///
+/// Unlike issue intake, the room or equipment must exist: a work order is
+/// always tied to something in the model.
+pub fn create_work_order(
+    building: &Building,
+    draft: &WorkOrderDraft,
+    id: String,
+) -> Result<WorkOrder, String> {
+    let title = draft.title.trim();
+    if title.is_empty() {
+        return Err("title is required".into());
+    }
+    let room_needle = draft
+        .room
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let eq_needle = draft
+        .equipment
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    if room_needle.is_none() && eq_needle.is_none() {
+        return Err("room or equipment is required".into());
+    }
+
+    let (located, equipment) = locate(building, room_needle, eq_needle);
+    if let Some(room) = room_needle {
+        if located.is_none() {
+            return Err(format!("room '{}' not found", room));
+        }
+    }
+    if let Some(eq) = eq_needle {
+        if equipment.is_none() {
+            return Err(format!("equipment '{}' not found", eq));
+        }
+    }
+
+    let mut order = WorkOrder {
+        id,
+        created_at: clock::now(),
+        status: WorkOrderStatus::Open,
+        priority: draft.priority,
+        title: title.to_string(),
+        description: draft
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(str::to_string),
+        room_id: located.map(|(_, r)| r.id.clone()),
+        room_name: located.map(|(_, r)| r.name.clone()),
+        floor_level: located.map(|(f, _)| f.level),
+        equipment_id: equipment.map(|e| e.id.clone()),
+        equipment_name: equipment.map(|e| e.name.clone()),
+        assignee: None,
+        created_by: draft.created_by.clone(),
+        issue_id: draft.issue_id.clone(),
+        comments: Vec::new(),
+        closed_at: None,
+        resolution: None,
+        parts_used: Vec::new(),
+        labor_cost: None,
+        parts_cost: None,
+    };
+    if let Some(assignee) = draft.assignee.as_deref() {
+        order.assign(assignee)?;
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn sample() -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Second".into(), 2);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("R-2.113".into(), RoomType::Office);
+        room.add_equipment(Equipment::new(
+            "VAV-7".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    #[test]
+    fn test_work_order_lifecycle() {
+        let building = sample();
+        let draft = WorkOrderDraft {
+            title: "Replace filter".into(),
+            equipment: Some("VAV-7".into()),
+            ..Default::default()
+        };
+        let mut order = create_work_order(&building, &draft, "WO-1".into()).unwrap();
+        assert_eq!(order.room_name.as_deref(), Some("R-2.113"));
+        assert_eq!(order.floor_level, Some(2));
+        assert_eq!(order.status, WorkOrderStatus::Open);
+
+        order.assign("pat").unwrap();
+        assert_eq!(order.status, WorkOrderStatus::Assigned);
+        order.start().unwrap();
+        order.comment("pat", "Filter on order").unwrap();
+        order.close(Some("Filter replaced")).unwrap();
+        assert_eq!(order.status, WorkOrderStatus::Closed);
+        assert_eq!(order.comments.len(), 1);
+        assert!(order.closed_at.is_some());
+        assert!(order.close(None).is_err());
+        assert!(order.assign("sam").is_err());
+
+        let missing = WorkOrderDraft {
+            title: "Paint".into(),
+            room: Some("Nowhere".into()),
+            ..Default::default()
+        };
+        assert!(create_work_order(&building, &missing, "WO-2".into()).is_err());
+        let untitled = WorkOrderDraft {
+            room: Some("R-2.113".into()),
+            ..Default::default()
+        };
+        assert!(create_work_order(&building, &untitled, "WO-3".into()).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+//! Energy metering: meters, consumption intervals, and period rollups.
+//!
+//! A [`Meter`] measures one utility for one equipment item or floor. Its
+//! readings are stored as [`Interval`]s of consumption (by
+//! `persistence::energy`); this module only rolls loaded intervals up into
+//! calendar periods and compares each period with a baseline of the ones
+//! before it.
+
+use chrono::{DateTime, Datelike, Duration, Months, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Periods averaged into a rollup's baseline.
+pub const BASELINE_PERIODS: usize = 4;
+
+/// What a meter measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Utility {
+    Electric,
+    Gas,
+    Water,
+}
+
+impl Utility {
+    pub const ALL: [Utility; 3] = [Utility::Electric, Utility::Gas, Utility::Water];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Utility::Electric => "electric",
+            Utility::Gas => "gas",
+            Utility::Water => "water",
+        }
+    }
+
+    /// Unit interval values are recorded in.
+    pub fn unit(self) -> &'static str {
+        match self {
+            Utility::Electric => "kWh",
+            Utility::Gas => "therm",
+            Utility::Water => "m³",
+        }
+    }
+}
+
+impl fmt::Display for Utility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Utility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "electric" | "electricity" | "power" => Ok(Utility::Electric),
+            "gas" => Ok(Utility::Gas),
+            "water" => Ok(Utility::Water),
+            _ => Err(format!(
+                "Unknown utility '{}'. Use: electric, gas, water",
+                s
+            )),
+        }
+    }
+}
+
+/// A utility meter tied to an equipment item or a floor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Meter {
+    pub id: String,
+    pub utility: Utility,
+    /// Metered equipment id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment: Option<String>,
+    /// Metered floor id (sub-meter of a whole floor)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub floor: Option<String>,
+}
+
+/// Consumption between `start` (inclusive) and `end` (exclusive).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Interval {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// Calendar period intervals are rolled up into (UTC; weeks start Monday).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+        }
+    }
+
+    /// Start of the period containing `at`.
+    pub fn start_of(self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let date = at.date_naive();
+        let date = match self {
+            Period::Day => date,
+            Period::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        };
+        Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+    }
+
+    /// Start of the period after the one starting at `start`.
+    pub fn next(self, start: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Period::Day => start + Duration::days(1),
+            Period::Week => start + Duration::weeks(1),
+            Period::Month => start
+                .checked_add_months(Months::new(1))
+                .unwrap_or(start + Duration::days(31)),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "day" | "daily" => Ok(Period::Day),
+            "week" | "weekly" => Ok(Period::Week),
+            "month" | "monthly" => Ok(Period::Month),
+            _ => Err(format!("Unknown period '{}'. Use: day, week, month", s)),
+        }
+    }
+}
+
+/// Total consumption in one period.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rollup {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub total: f64,
+    /// Intervals summed into `total`
+    pub intervals: usize,
+    /// Mean total of up to [`BASELINE_PERIODS`] earlier periods with data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<f64>,
+}
+
+impl Rollup {
+    /// Percent above (positive) or below the baseline.
+    pub fn change_vs_baseline(&self) -> Option<f64> {
+        self.baseline
+            .filter(|b| *b > 0.0)
+            .map(|b| (self.total - b) / b * 100.0)
+    }
+}
+
+/// Sum `intervals` into `period`s, oldest first. Each interval counts toward
+/// the period its start falls in; periods without data are omitted.
+pub fn rollup(intervals: &[Interval], period: Period) -> Vec<Rollup> {
+    let mut sorted: Vec<&Interval> = intervals.iter().collect();
+    sorted.sort_by_key(|i| i.start);
+    let mut rollups: Vec<Rollup> = Vec::new();
+    for interval in sorted {
+        let start = period.start_of(interval.start);
+        match rollups.last_mut() {
+            Some(r) if r.start == start => {
+                r.total += interval.value;
+                r.intervals += 1;
+            }
+            _ => rollups.push(Rollup {
+                start,
+                end: period.next(start),
+                total: interval.value,
+                intervals: 1,
+                baseline: None,
+            }),
+        }
+    }
+    for i in 1..rollups.len() {
+        let earlier = &rollups[i.saturating_sub(BASELINE_PERIODS)..i];
+        rollups[i].baseline =
+            Some(earlier.iter().map(|r| r.total).sum::<f64>() / earlier.len() as f64);
+    }
+    rollups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(day: u32, hour: u32, value: f64) -> Interval {
+        let start = Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+        Interval {
+            start,
+            end: start + Duration::hours(1),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_daily_weekly_monthly_rollups() {
+        // 2026-03-01 is a Sunday; the 2nd starts a new week
+        let intervals = [
+            interval(2, 9, 30.0),
+            interval(1, 10, 10.0),
+            interval(1, 11, 20.0),
+            interval(3, 9, 60.0),
+            interval(9, 9, 45.0),
+        ];
+
+        let daily = rollup(&intervals, Period::Day);
+        let totals: Vec<f64> = daily.iter().map(|r| r.total).collect();
+        assert_eq!(totals, [30.0, 30.0, 60.0, 45.0]);
+        assert_eq!(daily[0].intervals, 2);
+        assert_eq!(daily[0].baseline, None);
+        assert_eq!(daily[2].baseline, Some(30.0));
+        assert_eq!(daily[2].change_vs_baseline(), Some(100.0));
+
+        let weekly = rollup(&intervals, Period::Week);
+        let totals: Vec<f64> = weekly.iter().map(|r| r.total).collect();
+        assert_eq!(totals, [30.0, 90.0, 45.0]);
+        assert_eq!(
+            weekly[1].start,
+            Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap()
+        );
+        assert_eq!(weekly[2].baseline, Some(60.0));
+
+        let monthly = rollup(&intervals, Period::Month);
+        assert_eq!(monthly.len(), 1);
+        assert_eq!(monthly[0].total, 165.0);
+        assert_eq!(
+            monthly[0].end,
+            Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap()
+        );
+    }
+}
@@ -1,11 +1,11 @@
 //! Equipment data structure and implementation
 
+use super::clock;
 use super::types::Position;
 use crate::core::domain::ArxAddress;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
-use uuid::Uuid;
 
 /// Sensor mapping structure for equipment
 ///
@@ -18,6 +18,7 @@ pub struct SensorMapping {
     /// Type of sensor (e.g., "temperature", "humidity", "pressure")
     pub sensor_type: String,
     /// Threshold configurations for this sensor
+    #[serde(with = "crate::utils::sorted_map")]
     pub thresholds: HashMap<String, ThresholdConfig>,
 }
 
@@ -62,6 +63,9 @@ pub struct Equipment {
     pub id: String,
     /// Human-readable equipment name
     pub name: String,
+    /// Alternate display names (tag numbers, nicknames); `id` stays the stable key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     /// Universal path identifier (legacy string form; prefer `address` when set)
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub path: String,
@@ -100,6 +104,24 @@ pub struct Equipment {
     /// IFC product GlobalId when known (stable interchange identity)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ifc_global_id: Option<String>,
+    /// Commissioning project, stage, and sign-off history (new equipment only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commissioning: Option<super::commissioning::Commissioning>,
+    /// Accumulated run hours (tracking starts at the first status transition or run signal)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<super::runtime::RuntimeCounter>,
+    /// Where key field values came from (maintained on save)
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub provenance: super::provenance::ProvenanceMap,
+    /// Unmapped vendor IFC property sets, re-emitted on export
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub vendor_extensions: super::vendor::VendorExtensions,
+    /// Photos, manuals, and warranty documents attached to the item
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<super::Attachment>,
+    /// Typed edges to downstream equipment ("feeds", "powers", ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<super::systems::Connection>,
 }
 
 /// Types of equipment
@@ -152,8 +174,9 @@ pub enum EquipmentHealthStatus {
 impl Default for Equipment {
     fn default() -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name: "Unnamed Equipment".to_string(),
+            aliases: Vec::new(),
             path: "/".to_string(),
             address: None,
             equipment_type: EquipmentType::Other("Unknown".to_string()),
@@ -171,6 +194,12 @@ impl Default for Equipment {
             sensor_mappings: None,
             lidar_enrichment: None,
             ifc_global_id: None,
+            commissioning: None,
+            runtime: None,
+            provenance: Default::default(),
+            vendor_extensions: Default::default(),
+            attachments: Vec::new(),
+            connections: Vec::new(),
         }
     }
 }
@@ -209,8 +238,9 @@ impl Equipment {
     /// ```
     pub fn new(name: String, path: String, equipment_type: EquipmentType) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name,
+            aliases: Vec::new(),
             path,
             address: None,
             equipment_type,
@@ -228,6 +258,12 @@ impl Equipment {
             sensor_mappings: None,
             lidar_enrichment: None,
             ifc_global_id: None,
+            commissioning: None,
+            runtime: None,
+            provenance: Default::default(),
+            vendor_extensions: Default::default(),
+            attachments: Vec::new(),
+            connections: Vec::new(),
         }
     }
 
@@ -307,6 +343,16 @@ impl Equipment {
         self.properties.insert(key, value);
     }
 
+    /// Add an alternate display name (ignored when blank or already known)
+    pub fn add_alias(&mut self, alias: &str) {
+        super::alias::push_alias(&self.name, &mut self.aliases, alias);
+    }
+
+    /// Case-insensitive match against the equipment name or any alias
+    pub fn matches_name(&self, query: &str) -> bool {
+        super::alias::matches_name(&self.name, &self.aliases, query)
+    }
+
     /// Get system type from equipment type
     ///
     /// This computes the system_type string from the equipment_type enum.
@@ -360,6 +406,24 @@ impl fmt::Display for EquipmentType {
     }
 }
 
+/// Case-insensitive; unrecognized names become [`EquipmentType::Other`].
+impl std::str::FromStr for EquipmentType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "hvac" => EquipmentType::HVAC,
+            "electrical" => EquipmentType::Electrical,
+            "av" => EquipmentType::AV,
+            "furniture" => EquipmentType::Furniture,
+            "safety" => EquipmentType::Safety,
+            "plumbing" => EquipmentType::Plumbing,
+            "network" => EquipmentType::Network,
+            _ => EquipmentType::Other(s.to_string()),
+        })
+    }
+}
+
 impl fmt::Display for EquipmentStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
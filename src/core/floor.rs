@@ -1,10 +1,10 @@
 //! Floor data structure and implementation
 
+use super::clock;
 use super::{Equipment, Wing, Anchor};
 use super::domain::ArxAddress;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 /// Represents a floor in a building
 #[derive(Debug, Clone)]
@@ -133,7 +133,7 @@ impl Floor {
     /// ```
     pub fn new(name: String, level: i32) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name,
             level,
             elevation: None,
@@ -0,0 +1,251 @@
+//! Spare parts inventory.
+//!
+//! A [`SparePart`] is stocked in one storage location and may be linked to
+//! the equipment types it fits. Closing a work order with the parts it used
+//! ([`Inventory::consume`]) takes them out of stock; parts that fall below
+//! their minimum show up in the reorder report ([`Inventory::low_stock`]).
+
+use super::domain::Money;
+use super::{Building, EquipmentType};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One stocked part.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SparePart {
+    pub part_number: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub quantity: u32,
+    /// Reorder once stock falls below this
+    #[serde(default)]
+    pub min_stock: u32,
+    /// Price of one; work orders record parts cost from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit_cost: Option<Money>,
+    /// Usual order size; the report suggests at least the shortfall
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reorder_quantity: Option<u32>,
+    /// Where it is kept (room, shelf, bin)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Equipment types the part fits (empty: any)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equipment_types: Vec<String>,
+}
+
+impl SparePart {
+    pub fn is_low(&self) -> bool {
+        self.quantity < self.min_stock
+    }
+
+    /// Whether the part is linked to `equipment_type` (unlinked parts fit any).
+    pub fn fits(&self, equipment_type: &EquipmentType) -> bool {
+        self.equipment_types.is_empty()
+            || self.equipment_types.iter().any(|t| {
+                t.parse::<EquipmentType>()
+                    .is_ok_and(|t| &t == equipment_type)
+            })
+    }
+}
+
+/// Parts taken out of stock for a job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartUsage {
+    pub part_number: String,
+    pub quantity: u32,
+}
+
+impl fmt::Display for PartUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} × {}", self.quantity, self.part_number)
+    }
+}
+
+impl std::str::FromStr for PartUsage {
+    type Err = String;
+
+    /// `PN` (one) or `PN:QTY`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (part_number, quantity) = match s.rsplit_once(':') {
+            Some((part, qty)) => (
+                part,
+                qty.trim()
+                    .parse()
+                    .map_err(|_| format!("'{}': quantity must be a positive number", s))?,
+            ),
+            None => (s, 1),
+        };
+        let part_number = part_number.trim();
+        if part_number.is_empty() || quantity == 0 {
+            return Err(format!("'{}': expected PART or PART:QTY", s));
+        }
+        Ok(PartUsage {
+            part_number: part_number.to_string(),
+            quantity,
+        })
+    }
+}
+
+/// A part below its minimum, with the suggested order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReorderLine {
+    pub part_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub quantity: u32,
+    pub min_stock: u32,
+    pub order: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Equipment in the building the part fits
+    pub equipment: usize,
+}
+
+/// Contents of the inventory file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    #[serde(default)]
+    pub parts: Vec<SparePart>,
+}
+
+impl Inventory {
+    pub fn part(&self, part_number: &str) -> Option<&SparePart> {
+        self.parts
+            .iter()
+            .find(|p| p.part_number.eq_ignore_ascii_case(part_number))
+    }
+
+    fn part_mut(&mut self, part_number: &str) -> Result<&mut SparePart, String> {
+        self.parts
+            .iter_mut()
+            .find(|p| p.part_number.eq_ignore_ascii_case(part_number))
+            .ok_or_else(|| format!("Part '{}' is not in the inventory", part_number))
+    }
+
+    /// Add a new part; part numbers are unique (case-insensitive).
+    pub fn add(&mut self, part: SparePart) -> Result<(), String> {
+        if part.part_number.trim().is_empty() {
+            return Err("part number is required".into());
+        }
+        if self.part(&part.part_number).is_some() {
+            return Err(format!("Part '{}' already exists", part.part_number));
+        }
+        self.parts.push(part);
+        self.parts.sort_by(|a, b| a.part_number.cmp(&b.part_number));
+        Ok(())
+    }
+
+    /// Change stock by `delta` (restock or count correction); returns the
+    /// new quantity.
+    pub fn adjust(&mut self, part_number: &str, delta: i64) -> Result<u32, String> {
+        let part = self.part_mut(part_number)?;
+        let quantity = i64::from(part.quantity) + delta;
+        part.quantity = u32::try_from(quantity).map_err(|_| {
+            format!(
+                "Part '{}' has {} in stock; cannot remove {}",
+                part.part_number, part.quantity, -delta
+            )
+        })?;
+        Ok(part.quantity)
+    }
+
+    /// Take `used` out of stock, all or nothing.
+    pub fn consume(&mut self, used: &[PartUsage]) -> Result<(), String> {
+        let mut updated = self.clone();
+        for usage in used {
+            updated.adjust(&usage.part_number, -i64::from(usage.quantity))?;
+        }
+        *self = updated;
+        Ok(())
+    }
+
+    /// Cost of `used` at current unit costs; `None` when none of the parts
+    /// has one.
+    pub fn cost_of(&self, used: &[PartUsage]) -> Result<Option<Money>, String> {
+        let mut total: Option<Money> = None;
+        for usage in used {
+            let Some(unit) = self
+                .part(&usage.part_number)
+                .and_then(|p| p.unit_cost.as_ref())
+            else {
+                continue;
+            };
+            total = Some(
+                total
+                    .unwrap_or_default()
+                    .checked_add(&unit.times(usage.quantity))?,
+            );
+        }
+        Ok(total)
+    }
+
+    /// Parts below their minimum, by part number.
+    pub fn low_stock(&self, building: Option<&Building>) -> Vec<ReorderLine> {
+        let equipment = building.map(|b| b.get_all_equipment()).unwrap_or_default();
+        self.parts
+            .iter()
+            .filter(|p| p.is_low())
+            .map(|p| {
+                let shortfall = p.min_stock - p.quantity;
+                ReorderLine {
+                    part_number: p.part_number.clone(),
+                    description: p.description.clone(),
+                    quantity: p.quantity,
+                    min_stock: p.min_stock,
+                    order: p.reorder_quantity.unwrap_or(0).max(shortfall),
+                    location: p.location.clone(),
+                    equipment: equipment
+                        .iter()
+                        .filter(|eq| p.fits(&eq.equipment_type))
+                        .count(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(number: &str, quantity: u32, min_stock: u32) -> SparePart {
+        SparePart {
+            part_number: number.into(),
+            description: None,
+            quantity,
+            min_stock,
+            unit_cost: None,
+            reorder_quantity: None,
+            location: Some("Mech 101 shelf B".into()),
+            equipment_types: vec!["hvac".into()],
+        }
+    }
+
+    #[test]
+    fn consumes_all_or_nothing_and_reports_low_stock() {
+        let mut inventory = Inventory::default();
+        inventory.add(part("FLT-2424", 8, 6)).unwrap();
+        inventory.add(part("BELT-A42", 1, 2)).unwrap();
+        assert!(inventory.add(part("flt-2424", 1, 1)).is_err());
+
+        let used: Vec<PartUsage> = ["FLT-2424:4", "belt-a42:2"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert!(inventory.consume(&used).is_err());
+        assert_eq!(inventory.part("FLT-2424").unwrap().quantity, 8);
+
+        inventory.consume(&used[..1]).unwrap();
+        inventory.parts[0].reorder_quantity = Some(3);
+        let report = inventory.low_stock(None);
+        let lines: Vec<(&str, u32)> = report
+            .iter()
+            .map(|l| (l.part_number.as_str(), l.order))
+            .collect();
+        assert_eq!(lines, [("BELT-A42", 3), ("FLT-2424", 2)]);
+        assert!("FLT:0".parse::<PartUsage>().is_err());
+        assert!(part("X", 0, 0).fits(&EquipmentType::HVAC));
+        assert!(!part("X", 0, 0).fits(&EquipmentType::Plumbing));
+    }
+}
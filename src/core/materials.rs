@@ -0,0 +1,263 @@
+//! Display materials per room type and equipment type.
+//!
+//! Mesh exports and the terminal views colour geometry by type so the same
+//! room reads the same everywhere. A built-in palette covers every
+//! [`RoomType`] and [`EquipmentType`]; teams override or extend it in
+//! `.arx/materials.yaml`, keyed by the type as displayed (case-insensitive,
+//! custom `Other` names included):
+//!
+//! ```yaml
+//! room_types:
+//!   Laboratory: { color: "#6fb3d2", roughness: 0.4 }
+//!   Clean Room: { color: "#ffffff" }
+//! equipment_types:
+//!   HVAC: { color: "#00c8c8", metallic: 0.6 }
+//! floor_slab: { color: "#b0a898" }
+//! ```
+
+use super::{EquipmentType, RoomType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Repo-relative location of material overrides.
+pub const MATERIALS_FILE: &str = ".arx/materials.yaml";
+
+/// PBR-style material: base colour plus the factors glTF understands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    /// Base colour as `#rrggbb`
+    pub color: String,
+    #[serde(default = "default_opacity")]
+    pub opacity: f64,
+    #[serde(default)]
+    pub metallic: f64,
+    #[serde(default = "default_roughness")]
+    pub roughness: f64,
+}
+
+fn default_opacity() -> f64 {
+    1.0
+}
+
+fn default_roughness() -> f64 {
+    0.9
+}
+
+impl Material {
+    pub fn new(color: &str) -> Self {
+        Self {
+            color: color.to_string(),
+            opacity: default_opacity(),
+            metallic: 0.0,
+            roughness: default_roughness(),
+        }
+    }
+
+    /// Base colour as 8-bit RGB; unparseable colours fall back to mid grey.
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        parse_hex_color(&self.color).unwrap_or((128, 128, 128))
+    }
+
+    /// `[r, g, b, a]` in 0..1 for glTF `baseColorFactor` (sRGB values used as-is).
+    pub fn base_color_factor(&self) -> [f64; 4] {
+        let (r, g, b) = self.rgb();
+        [
+            r as f64 / 255.0,
+            g as f64 / 255.0,
+            b as f64 / 255.0,
+            self.opacity.clamp(0.0, 1.0),
+        ]
+    }
+}
+
+/// Parse `#rrggbb` / `rrggbb`.
+pub fn parse_hex_color(s: &str) -> Option<(u8, u8, u8)> {
+    let hex = s.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Materials keyed by room / equipment type name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialSet {
+    #[serde(default)]
+    pub room_types: BTreeMap<String, Material>,
+    #[serde(default)]
+    pub equipment_types: BTreeMap<String, Material>,
+    /// Used for room types without an entry
+    #[serde(default = "default_room_material")]
+    pub default_room: Material,
+    /// Used for equipment types without an entry
+    #[serde(default = "default_equipment_material")]
+    pub default_equipment: Material,
+    /// Storey slabs under each floor's rooms
+    #[serde(default = "default_floor_slab_material")]
+    pub floor_slab: Material,
+}
+
+fn default_room_material() -> Material {
+    Material::new("#cccccc")
+}
+
+fn default_equipment_material() -> Material {
+    Material::new("#888888")
+}
+
+fn default_floor_slab_material() -> Material {
+    Material::new("#9e9e9e")
+}
+
+impl Default for MaterialSet {
+    fn default() -> Self {
+        let rooms = [
+            ("Classroom", "#f2c879"),
+            ("Laboratory", "#6fb3d2"),
+            ("Office", "#a7c7a0"),
+            ("Gymnasium", "#d98c5f"),
+            ("Cafeteria", "#e6a157"),
+            ("Library", "#b08fc7"),
+            ("Auditorium", "#c76f6f"),
+            ("Hallway", "#d9d9d9"),
+            ("Restroom", "#8fd0c7"),
+            ("Storage", "#a89f91"),
+            ("Mechanical", "#7f8c99"),
+            ("Electrical", "#e8d45c"),
+        ];
+        let equipment = [
+            ("HVAC", "#00c8c8"),
+            ("Electrical", "#ffaa00"),
+            ("AV", "#9b6bd6"),
+            ("Furniture", "#b5835a"),
+            ("Safety", "#e53935"),
+            ("Plumbing", "#1e88e5"),
+            ("Network", "#43a047"),
+        ];
+        Self {
+            room_types: rooms
+                .iter()
+                .map(|(k, c)| (k.to_string(), Material::new(c)))
+                .collect(),
+            equipment_types: equipment
+                .iter()
+                .map(|(k, c)| (k.to_string(), Material::new(c)))
+                .collect(),
+            default_room: default_room_material(),
+            default_equipment: default_equipment_material(),
+            floor_slab: default_floor_slab_material(),
+        }
+    }
+}
+
+impl MaterialSet {
+    /// Built-in palette with `base/.arx/materials.yaml` layered on top.
+    pub fn load_at(base: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = base.join(MATERIALS_FILE);
+        let mut set = Self::default();
+        if !path.exists() {
+            return Ok(set);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let overrides: MaterialOverrides = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        overrides.apply(&mut set);
+        Ok(set)
+    }
+
+    /// Material for a room type name as displayed.
+    pub fn for_room_type_name(&self, name: &str) -> &Material {
+        lookup(&self.room_types, name).unwrap_or(&self.default_room)
+    }
+
+    /// Material for an equipment type name as displayed.
+    pub fn for_equipment_type_name(&self, name: &str) -> &Material {
+        lookup(&self.equipment_types, name).unwrap_or(&self.default_equipment)
+    }
+
+    pub fn for_room_type(&self, room_type: &RoomType) -> &Material {
+        self.for_room_type_name(&room_type.to_string())
+    }
+
+    pub fn for_equipment_type(&self, equipment_type: &EquipmentType) -> &Material {
+        self.for_equipment_type_name(&equipment_type.to_string())
+    }
+}
+
+fn lookup<'a>(map: &'a BTreeMap<String, Material>, name: &str) -> Option<&'a Material> {
+    map.get(name).or_else(|| {
+        map.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, m)| m)
+    })
+}
+
+/// File shape: every section optional, entries replace built-ins per key.
+#[derive(Debug, Default, Deserialize)]
+struct MaterialOverrides {
+    #[serde(default)]
+    room_types: BTreeMap<String, Material>,
+    #[serde(default)]
+    equipment_types: BTreeMap<String, Material>,
+    default_room: Option<Material>,
+    default_equipment: Option<Material>,
+    floor_slab: Option<Material>,
+}
+
+impl MaterialOverrides {
+    fn apply(self, set: &mut MaterialSet) {
+        fn merge(into: &mut BTreeMap<String, Material>, from: BTreeMap<String, Material>) {
+            for (name, material) in from {
+                into.retain(|k, _| !k.eq_ignore_ascii_case(&name));
+                into.insert(name, material);
+            }
+        }
+        merge(&mut set.room_types, self.room_types);
+        merge(&mut set.equipment_types, self.equipment_types);
+        if let Some(m) = self.default_room {
+            set.default_room = m;
+        }
+        if let Some(m) = self.default_equipment {
+            set.default_equipment = m;
+        }
+        if let Some(m) = self.floor_slab {
+            set.floor_slab = m;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_layer_over_builtin_palette() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        std::fs::write(
+            dir.path().join(MATERIALS_FILE),
+            "room_types:\n  laboratory: { color: \"#102030\", opacity: 0.5 }\n  Clean Room: { color: \"#ffffff\" }\n",
+        )
+        .unwrap();
+
+        let set = MaterialSet::load_at(dir.path()).unwrap();
+        let lab = set.for_room_type(&RoomType::Laboratory);
+        assert_eq!(lab.rgb(), (0x10, 0x20, 0x30));
+        assert_eq!(lab.base_color_factor()[3], 0.5);
+        assert_eq!(lab.roughness, 0.9);
+        assert_eq!(
+            set.for_room_type(&RoomType::Other("clean room".into()))
+                .color,
+            "#ffffff"
+        );
+        // Untouched built-ins and fallbacks survive
+        assert_eq!(set.for_room_type(&RoomType::Office).color, "#a7c7a0");
+        assert_eq!(
+            set.for_equipment_type(&EquipmentType::Other("Kiln".into())),
+            &set.default_equipment
+        );
+        assert_eq!(parse_hex_color("nope"), None);
+    }
+}
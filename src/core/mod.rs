@@ -4,23 +4,46 @@
 //! for representing buildings, floors, rooms, equipment, and their spatial relationships.
 
 // Core modules
+pub mod access;
+pub mod alias;
 mod anchor;
+#[cfg(feature = "ask")]
+pub mod ask;
+pub mod attachment;
 mod building;
+pub mod clock;
+pub mod commissioning;
+pub mod compare;
+pub mod completeness;
 pub mod domain;
+pub mod energy;
 mod equipment;
 mod floor;
 pub mod identity;
+pub mod inventory;
+pub mod materials;
 pub mod operations;
+pub mod provenance;
 pub mod review;
+pub mod runtime;
 mod room;
+pub mod sensor_sim;
 mod serde_helpers;
 pub mod spatial;
+pub mod systems;
+pub mod templates;
+pub mod timeseries;
+pub mod twin;
+pub mod uptime;
 mod types;
+pub mod vendor;
 mod wing;
 
 // Re-export all public types and functions
 pub use anchor::{Anchor, RelativePose, PoseType, MapRef};
+pub use attachment::Attachment;
 pub use building::{Building, BuildingMetadata, CoordinateSystemInfo};
+pub use completeness::{assess_completeness, CompletenessReport, PROP_LOD};
 pub use equipment::{Equipment, EquipmentHealthStatus, EquipmentStatus, EquipmentType};
 pub use floor::Floor;
 pub use identity::ArxId;
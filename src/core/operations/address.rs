@@ -1,7 +1,12 @@
-//! Backfill durable `ArxAddress` values on Building equipment.
+//! Backfill durable `ArxAddress` values on Building equipment, and resolve
+//! the full address of every entity for `arx query`.
 
 use crate::core::domain::ArxAddress;
 use crate::core::Building;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
 /// Assign `address` (and `path`) to equipment that lack one, using hierarchy context.
 ///
@@ -144,6 +149,160 @@ pub fn backfill_equipment_addresses(building: &mut Building) -> usize {
     count
 }
 
+/// Kind of entity an address belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressKind {
+    Building,
+    Floor,
+    Wing,
+    Room,
+    Equipment,
+    Anchor,
+}
+
+impl fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AddressKind::Building => "building",
+            AddressKind::Floor => "floor",
+            AddressKind::Wing => "wing",
+            AddressKind::Room => "room",
+            AddressKind::Equipment => "equipment",
+            AddressKind::Anchor => "anchor",
+        })
+    }
+}
+
+impl FromStr for AddressKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "building" => Ok(AddressKind::Building),
+            "floor" => Ok(AddressKind::Floor),
+            "wing" => Ok(AddressKind::Wing),
+            "room" => Ok(AddressKind::Room),
+            "equipment" => Ok(AddressKind::Equipment),
+            "anchor" => Ok(AddressKind::Anchor),
+            _ => Err(format!(
+                "Unknown kind '{}'. Use: building, floor, wing, room, equipment, anchor",
+                s
+            )),
+        }
+    }
+}
+
+/// An entity with its full address.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResolvedAddress {
+    pub kind: AddressKind,
+    pub id: String,
+    pub name: String,
+    pub address: ArxAddress,
+    /// Stored in building.yaml; otherwise derived the way
+    /// [`backfill_equipment_addresses`] would assign it
+    pub durable: bool,
+}
+
+/// Visit every addressable entity: building, floors, wings, rooms,
+/// equipment, then anchors.
+fn visit_entities(
+    building: &Building,
+    mut visit: impl FnMut(AddressKind, &str, &str, Option<&ArxAddress>),
+) {
+    visit(
+        AddressKind::Building,
+        &building.id,
+        &building.name,
+        building.address.as_ref(),
+    );
+    for floor in &building.floors {
+        visit(
+            AddressKind::Floor,
+            &floor.id,
+            &floor.name,
+            floor.address.as_ref(),
+        );
+        for eq in &floor.equipment {
+            visit(
+                AddressKind::Equipment,
+                &eq.id,
+                &eq.name,
+                eq.address.as_ref(),
+            );
+        }
+        for wing in &floor.wings {
+            visit(
+                AddressKind::Wing,
+                &wing.id,
+                &wing.name,
+                wing.address.as_ref(),
+            );
+            for eq in &wing.equipment {
+                visit(
+                    AddressKind::Equipment,
+                    &eq.id,
+                    &eq.name,
+                    eq.address.as_ref(),
+                );
+            }
+            for room in &wing.rooms {
+                visit(
+                    AddressKind::Room,
+                    &room.id,
+                    &room.name,
+                    room.address.as_ref(),
+                );
+                for eq in &room.equipment {
+                    visit(
+                        AddressKind::Equipment,
+                        &eq.id,
+                        &eq.name,
+                        eq.address.as_ref(),
+                    );
+                }
+            }
+        }
+    }
+    for anchor in building.get_all_anchors() {
+        visit(
+            AddressKind::Anchor,
+            &anchor.id,
+            &anchor.name,
+            anchor.address.as_ref(),
+        );
+    }
+}
+
+/// Full address of every entity in `building`. Entities without a durable
+/// address get the one the backfill would assign, so queries see the whole
+/// tree before `arx migrate` has run.
+pub fn resolve_addresses(building: &Building) -> Vec<ResolvedAddress> {
+    let mut durable = HashSet::new();
+    visit_entities(building, |kind, id, _, address| {
+        if address.is_some() {
+            durable.insert((kind, id.to_string()));
+        }
+    });
+
+    let mut filled = building.clone();
+    backfill_equipment_addresses(&mut filled);
+    let mut resolved = Vec::new();
+    visit_entities(&filled, |kind, id, name, address| {
+        if let Some(address) = address {
+            resolved.push(ResolvedAddress {
+                kind,
+                id: id.to_string(),
+                name: name.to_string(),
+                address: address.clone(),
+                durable: durable.contains(&(kind, id.to_string())),
+            });
+        }
+    });
+    resolved
+}
+
 fn slug(s: &str) -> String {
     let s = s.trim().to_lowercase();
     if s.is_empty() {
@@ -223,4 +382,67 @@ mod tests {
             "/usa/ny/brooklyn/ps-118/floor-02/elec/panel-01"
         );
     }
+
+    #[test]
+    fn resolves_durable_and_derived_addresses_for_every_entity() {
+        let mut b = Building::new("PS 118".into(), "/ps".into());
+        let mut floor = Floor::new("Floor 2".into(), 2);
+        let mut wing = Wing::new("Main".into());
+        let mut room = Room::new("Mech".into(), RoomType::Mechanical);
+        let mut panel = Equipment::new("Panel".into(), String::new(), EquipmentType::Electrical);
+        panel.address = Some(ArxAddress::new(
+            "usa", "ny", "brooklyn", "ps-118", "floor-02", "elec", "panel-01",
+        ));
+        room.add_equipment(panel);
+        room.add_equipment(Equipment::new(
+            "Boiler 01".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+
+        let resolved = resolve_addresses(&b);
+        let paths: Vec<(AddressKind, &str, bool)> = resolved
+            .iter()
+            .map(|r| (r.kind, r.address.path.as_str(), r.durable))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (AddressKind::Building, "/local/local/local/ps-118", false),
+                (
+                    AddressKind::Floor,
+                    "/local/local/local/ps-118/floor-2",
+                    false
+                ),
+                (
+                    AddressKind::Wing,
+                    "/local/local/local/ps-118/floor-2/main",
+                    false
+                ),
+                (
+                    AddressKind::Room,
+                    "/local/local/local/ps-118/floor-2/main/mech",
+                    false
+                ),
+                (
+                    AddressKind::Equipment,
+                    "/usa/ny/brooklyn/ps-118/floor-02/elec/panel-01",
+                    true
+                ),
+                (
+                    AddressKind::Equipment,
+                    "/local/local/local/ps-118/floor-2/mech/boiler-01",
+                    false
+                ),
+            ]
+        );
+        assert!(
+            b.get_all_equipment().iter().any(|e| e.address.is_none()),
+            "input untouched"
+        );
+        assert_eq!("ROOM".parse::<AddressKind>(), Ok(AddressKind::Room));
+    }
 }
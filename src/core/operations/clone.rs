@@ -0,0 +1,541 @@
+//! Clone a floor onto other levels (hotels and schools repeat floors).
+//!
+//! Every wing, room, and equipment item of the source floor is copied with a
+//! fresh id and moved up by `(target - source) × floor height`. Names follow
+//! a rename pattern with `{room}` (source room name), `{floor}` (target
+//! level), and `{suffix}` (the room name after its leading source level, so
+//! `301` on floor 3 has suffix `01`). Without a pattern, rooms numbered by
+//! floor are renumbered (`301` → `401`) and others become `{room}-{floor}`.
+//!
+//! Addresses are rewritten segment by segment (floor, room, and fixture
+//! segments), and sensor ids that embed the equipment name follow the new
+//! name; other sensor ids belong to the source floor's hardware and are
+//! dropped. Identity tied to the physical source — IFC GlobalIds, aliases,
+//! AR anchors, LiDAR enrichment, attachments, run hours, commissioning —
+//! is not copied.
+
+use super::rename::path_slug;
+use crate::core::clock;
+use crate::core::domain::ArxAddress;
+use crate::core::spatial::mesh::Mesh;
+use crate::core::{Building, Equipment, Floor, Room};
+
+/// Floor height when nothing in the building gives one, in meters.
+pub const DEFAULT_FLOOR_HEIGHT: f64 = 3.0;
+
+/// What to clone where.
+#[derive(Debug, Clone)]
+pub struct CloneFloorOptions {
+    /// Source floor level
+    pub from: i32,
+    /// Target levels (none may exist yet)
+    pub to: Vec<i32>,
+    /// Room name pattern (`{room}`, `{floor}`, `{suffix}`)
+    pub rename_pattern: Option<String>,
+    /// Vertical distance between levels (default: inferred)
+    pub floor_height: Option<f64>,
+}
+
+/// One floor created by [`clone_floor`].
+#[derive(Debug, Clone)]
+pub struct ClonedFloor {
+    pub level: i32,
+    pub name: String,
+    pub z_offset: f64,
+    /// Source → new room names
+    pub rooms: Vec<(String, String)>,
+    /// Source → new equipment names (rooms, wings, and common areas)
+    pub equipment: Vec<(String, String)>,
+}
+
+/// Outcome of [`clone_floor`].
+#[derive(Debug, Clone)]
+pub struct CloneFloorReport {
+    pub source: String,
+    pub floor_height: f64,
+    pub floors: Vec<ClonedFloor>,
+    /// `equipment: sensor id` mappings not copied
+    pub dropped_sensors: Vec<String>,
+}
+
+/// Levels from `4`, `4..10` (inclusive), `4..=10`, or comma lists of those.
+pub fn parse_levels(input: &str) -> Result<Vec<i32>, String> {
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<i32>()
+            .map_err(|_| format!("'{}' is not a floor level", s.trim()))
+    };
+    let mut levels = Vec::new();
+    for part in input.split(',').filter(|p| !p.trim().is_empty()) {
+        match part.split_once("..") {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end.trim_start_matches('='))?);
+                if start > end {
+                    return Err(format!("Empty level range '{}'", part.trim()));
+                }
+                levels.extend(start..=end);
+            }
+            None => levels.push(parse(part)?),
+        }
+    }
+    levels.sort_unstable();
+    levels.dedup();
+    if levels.is_empty() {
+        return Err("No target levels given".to_string());
+    }
+    Ok(levels)
+}
+
+/// Replace whole digit runs equal to `from` with `to`, keeping zero padding
+/// (`Floor 03` → `Floor 04`).
+fn renumber(s: &str, from: i32, to: i32) -> String {
+    let mut out = String::new();
+    let mut digits = String::new();
+    let flush = |digits: &mut String, out: &mut String| {
+        if digits.parse::<i32>().ok() == Some(from) && from >= 0 && to >= 0 {
+            out.push_str(&format!("{:0width$}", to, width = digits.len()));
+        } else {
+            out.push_str(digits);
+        }
+        digits.clear();
+    };
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+        } else {
+            flush(&mut digits, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut digits, &mut out);
+    out
+}
+
+fn room_name(pattern: Option<&str>, room: &str, from: i32, to: i32) -> String {
+    let suffix = room
+        .strip_prefix(&from.to_string())
+        .filter(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()));
+    let pattern = match (pattern, suffix) {
+        (Some(pattern), _) => pattern,
+        (None, Some(_)) => "{floor}{suffix}",
+        (None, None) => "{room}-{floor}",
+    };
+    pattern
+        .replace("{room}", room)
+        .replace("{floor}", &to.to_string())
+        .replace("{suffix}", suffix.unwrap_or(room))
+}
+
+/// Vertical spacing from floor elevations nearest the source, else the
+/// tallest source room, else [`DEFAULT_FLOOR_HEIGHT`].
+fn infer_floor_height(building: &Building, source: &Floor) -> f64 {
+    if let Some(elevation) = source.elevation {
+        let nearest = building
+            .floors
+            .iter()
+            .filter(|f| f.level != source.level)
+            .filter_map(|f| f.elevation.map(|e| (f.level, e)))
+            .min_by_key(|(level, _)| (level - source.level).abs());
+        if let Some((level, other)) = nearest {
+            let height = (other - elevation) / f64::from(level - source.level);
+            if height > 0.0 {
+                return height;
+            }
+        }
+    }
+    source
+        .wings
+        .iter()
+        .flat_map(|w| &w.rooms)
+        .map(|r| r.spatial_properties.dimensions.height)
+        .fold(None, |max: Option<f64>, h| {
+            Some(max.map_or(h, |m| m.max(h)))
+        })
+        .filter(|h| *h > 0.0)
+        .unwrap_or(DEFAULT_FLOOR_HEIGHT)
+}
+
+fn last_segment(address: &Option<ArxAddress>) -> Option<String> {
+    address
+        .as_ref()
+        .and_then(|a| a.path.rsplit('/').next().map(str::to_string))
+        .filter(|s| !s.is_empty())
+}
+
+/// Rewrite address segments from the floor segment on.
+struct Rewrites {
+    floor: (String, String),
+    segments: Vec<(String, String)>,
+}
+
+impl Rewrites {
+    fn with(&self, old: Option<String>, new: &str) -> Rewrites {
+        let mut segments = self.segments.clone();
+        if let Some(old) = old {
+            segments.push((old, path_slug(new)));
+        }
+        Rewrites {
+            floor: self.floor.clone(),
+            segments,
+        }
+    }
+
+    fn path(&self, path: &str) -> String {
+        let mut in_floor = false;
+        path.split('/')
+            .map(|seg| {
+                if !in_floor && !seg.is_empty() && seg.eq_ignore_ascii_case(&self.floor.0) {
+                    in_floor = true;
+                    return self.floor.1.clone();
+                }
+                if in_floor {
+                    if let Some((_, new)) = self
+                        .segments
+                        .iter()
+                        .find(|(old, _)| seg.eq_ignore_ascii_case(old))
+                    {
+                        return new.clone();
+                    }
+                }
+                seg.to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn address(&self, address: &mut Option<ArxAddress>) {
+        if let Some(addr) = address {
+            addr.path = self.path(&addr.path);
+        }
+    }
+}
+
+fn offset_mesh(mesh: &mut Option<Mesh>, dz: f64) {
+    if let Some(mesh) = mesh {
+        for v in &mut mesh.vertices {
+            v.z += dz;
+        }
+    }
+}
+
+struct FloorClone<'a> {
+    from: i32,
+    to: i32,
+    dz: f64,
+    pattern: Option<&'a str>,
+    equipment: Vec<(String, String)>,
+    dropped_sensors: Vec<String>,
+}
+
+impl FloorClone<'_> {
+    fn equipment(&mut self, eq: &mut Equipment, rename: Option<(&str, &str)>, rewrites: &Rewrites) {
+        let old_name = eq.name.clone();
+        let new_name = match rename {
+            Some((old_room, new_room)) if old_name.contains(old_room) => {
+                old_name.replace(old_room, new_room)
+            }
+            _ => renumber(&old_name, self.from, self.to),
+        };
+        let rewrites = if new_name != old_name {
+            rewrites.with(last_segment(&eq.address), &new_name)
+        } else {
+            rewrites.with(None, "")
+        };
+        eq.id = clock::new_id();
+        eq.name = new_name.clone();
+        eq.aliases.clear();
+        rewrites.address(&mut eq.address);
+        if !eq.path.is_empty() {
+            eq.path = rewrites.path(&eq.path);
+        }
+        eq.position.z += self.dz;
+        offset_mesh(&mut eq.mesh, self.dz);
+        if let Some(mappings) = eq.sensor_mappings.as_mut() {
+            mappings.retain_mut(|m| {
+                let renamed = if m.sensor_id.contains(&old_name) && new_name != old_name {
+                    m.sensor_id.replace(&old_name, &new_name)
+                } else {
+                    renumber(&m.sensor_id, self.from, self.to)
+                };
+                if renamed == m.sensor_id {
+                    self.dropped_sensors
+                        .push(format!("{}: {}", new_name, m.sensor_id));
+                    return false;
+                }
+                m.sensor_id = renamed;
+                true
+            });
+            if mappings.is_empty() {
+                eq.sensor_mappings = None;
+            }
+        }
+        eq.ifc_global_id = None;
+        eq.lidar_enrichment = None;
+        eq.commissioning = None;
+        eq.runtime = None;
+        eq.attachments.clear();
+        self.equipment.push((old_name, new_name));
+    }
+
+    fn room(&mut self, room: &mut Room, rewrites: &Rewrites) -> (String, String) {
+        let old_name = room.name.clone();
+        let new_name = room_name(self.pattern, &old_name, self.from, self.to);
+        let rewrites = rewrites.with(last_segment(&room.address), &new_name);
+        let now = Some(clock::now());
+        room.id = clock::new_id();
+        room.name = new_name.clone();
+        room.aliases.clear();
+        rewrites.address(&mut room.address);
+        let spatial = &mut room.spatial_properties;
+        spatial.position.z += self.dz;
+        spatial.bounding_box.min.z += self.dz;
+        spatial.bounding_box.max.z += self.dz;
+        offset_mesh(&mut spatial.mesh, self.dz);
+        room.ifc_global_id = None;
+        room.lidar_enrichment = None;
+        room.anchors.clear();
+        room.pending_anchor_ids.clear();
+        room.attachments.clear();
+        room.created_at = now;
+        room.updated_at = now;
+        for eq in &mut room.equipment {
+            self.equipment(eq, Some((&old_name, &new_name)), &rewrites);
+            eq.room_id = Some(room.id.clone());
+        }
+        (old_name, new_name)
+    }
+}
+
+/// Copy floor `options.from` onto each of `options.to`.
+pub fn clone_floor(
+    building: &mut Building,
+    options: &CloneFloorOptions,
+) -> Result<CloneFloorReport, String> {
+    let source = building
+        .floors
+        .iter()
+        .find(|f| f.level == options.from)
+        .cloned()
+        .ok_or_else(|| format!("No floor at level {}", options.from))?;
+    if options.to.is_empty() {
+        return Err("No target levels given".to_string());
+    }
+    if let Some(level) = options
+        .to
+        .iter()
+        .find(|l| building.floors.iter().any(|f| f.level == **l))
+    {
+        return Err(format!("Floor level {} already exists", level));
+    }
+    let floor_height = match options.floor_height {
+        Some(h) if h > 0.0 => h,
+        Some(h) => return Err(format!("Floor height must be positive, got {}", h)),
+        None => infer_floor_height(building, &source),
+    };
+
+    let mut report = CloneFloorReport {
+        source: source.name.clone(),
+        floor_height,
+        floors: Vec::new(),
+        dropped_sensors: Vec::new(),
+    };
+    for &level in &options.to {
+        let mut floor = source.clone();
+        let mut run = FloorClone {
+            from: source.level,
+            to: level,
+            dz: f64::from(level - source.level) * floor_height,
+            pattern: options.rename_pattern.as_deref(),
+            equipment: Vec::new(),
+            dropped_sensors: Vec::new(),
+        };
+
+        let renumbered = renumber(&source.name, source.level, level);
+        floor.name = if renumbered != source.name {
+            renumbered
+        } else {
+            format!("Floor {}", level)
+        };
+        // Same fallback as the address backfill when the floor has no address
+        let old_segment = last_segment(&source.address).unwrap_or_else(|| {
+            if source.name.trim().is_empty() {
+                format!("floor-{}", source.level)
+            } else {
+                path_slug(&source.name)
+            }
+        });
+        let new_segment = match renumber(&old_segment, source.level, level) {
+            same if same == old_segment => path_slug(&floor.name),
+            renumbered => renumbered,
+        };
+        let rewrites = Rewrites {
+            floor: (old_segment, new_segment),
+            segments: Vec::new(),
+        };
+
+        floor.id = clock::new_id();
+        floor.level = level;
+        floor.elevation = source.elevation.map(|e| e + run.dz);
+        if let Some(bbox) = floor.bounding_box.as_mut() {
+            bbox.min.z += run.dz;
+            bbox.max.z += run.dz;
+        }
+        rewrites.address(&mut floor.address);
+        floor.ifc_global_id = None;
+        floor.anchors.clear();
+        floor.pending_anchor_ids.clear();
+        for eq in &mut floor.equipment {
+            run.equipment(eq, None, &rewrites);
+        }
+
+        let mut rooms = Vec::new();
+        for wing in &mut floor.wings {
+            wing.id = clock::new_id();
+            rewrites.address(&mut wing.address);
+            wing.anchors.clear();
+            wing.pending_anchor_ids.clear();
+            for eq in &mut wing.equipment {
+                run.equipment(eq, None, &rewrites);
+            }
+            for room in &mut wing.rooms {
+                rooms.push(run.room(room, &rewrites));
+            }
+        }
+
+        report.floors.push(ClonedFloor {
+            level,
+            name: floor.name.clone(),
+            z_offset: run.dz,
+            rooms,
+            equipment: run.equipment,
+        });
+        report.dropped_sensors.extend(run.dropped_sensors);
+        building.add_floor(floor);
+    }
+    building.floors.sort_by_key(|f| f.level);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::equipment::SensorMapping;
+    use crate::core::{EquipmentType, RoomType, Wing};
+
+    fn hotel() -> Building {
+        let mut building = Building::new("Inn".into(), "/inn".into());
+        let mut floor = Floor::new("Floor 3".into(), 3);
+        floor.elevation = Some(9.0);
+        floor.address = ArxAddress::from_path("/usa/ny/nyc/inn/floor-3").ok();
+        let mut wing = Wing::new("East".into());
+        for name in ["301", "Linen"] {
+            let mut room = Room::new(name.into(), RoomType::Storage);
+            room.address =
+                ArxAddress::from_path(&format!("/usa/ny/nyc/inn/floor-3/{}", path_slug(name))).ok();
+            room.spatial_properties.position.z = 9.0;
+            let mut eq =
+                Equipment::new(format!("{}-fcu", name), String::new(), EquipmentType::HVAC);
+            eq.address = ArxAddress::from_path(&format!(
+                "/usa/ny/nyc/inn/floor-3/{}/{}-fcu",
+                path_slug(name),
+                path_slug(name)
+            ))
+            .ok();
+            eq.position.z = 10.0;
+            eq.ifc_global_id = Some("2O2Fr$t4X7Zf8NOew3FLOH".into());
+            eq.sensor_mappings = Some(vec![
+                SensorMapping {
+                    sensor_id: format!("{}-fcu-temp", name),
+                    sensor_type: "temperature".into(),
+                    thresholds: Default::default(),
+                },
+                SensorMapping {
+                    sensor_id: "bacnet:77".into(),
+                    sensor_type: "humidity".into(),
+                    thresholds: Default::default(),
+                },
+            ]);
+            eq.room_id = Some(room.id.clone());
+            room.equipment.push(eq);
+            wing.rooms.push(room);
+        }
+        floor.wings.push(wing);
+        building.add_floor(floor);
+        let mut floor = Floor::new("Floor 2".into(), 2);
+        floor.elevation = Some(5.5);
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn levels_parse_ranges_and_lists() {
+        assert_eq!(parse_levels("4..6").unwrap(), [4, 5, 6]);
+        assert_eq!(parse_levels("8,4..=5,5").unwrap(), [4, 5, 8]);
+        assert!(parse_levels("6..4").is_err());
+        assert!(parse_levels("four").is_err());
+        assert_eq!(renumber("floor-03", 3, 10), "floor-10");
+        assert_eq!(renumber("B3-13", 3, 4), "B4-13");
+    }
+
+    #[test]
+    fn clones_rooms_equipment_addresses_and_offsets() {
+        let mut building = hotel();
+        let report = clone_floor(
+            &mut building,
+            &CloneFloorOptions {
+                from: 3,
+                to: vec![4, 5],
+                rename_pattern: None,
+                floor_height: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(report.floor_height, 3.5);
+        assert_eq!(report.floors[1].z_offset, 7.0);
+        assert_eq!(
+            report.floors[0].rooms,
+            [
+                ("301".to_string(), "401".to_string()),
+                ("Linen".to_string(), "Linen-4".to_string())
+            ]
+        );
+        assert_eq!(report.dropped_sensors.len(), 4);
+
+        let levels: Vec<i32> = building.floors.iter().map(|f| f.level).collect();
+        assert_eq!(levels, [2, 3, 4, 5]);
+        let floor = building.find_floor(5).unwrap();
+        assert_eq!(floor.name, "Floor 5");
+        assert_eq!(floor.elevation, Some(16.0));
+        assert_eq!(
+            floor.address.as_ref().unwrap().path,
+            "/usa/ny/nyc/inn/floor-5"
+        );
+        let room = &floor.wings[0].rooms[0];
+        assert_eq!(room.name, "501");
+        assert_eq!(room.spatial_properties.position.z, 16.0);
+        let eq = &room.equipment[0];
+        assert_eq!(eq.name, "501-fcu");
+        assert_eq!(
+            eq.address.as_ref().unwrap().path,
+            "/usa/ny/nyc/inn/floor-5/501/501-fcu"
+        );
+        assert_eq!(eq.position.z, 17.0);
+        assert_eq!(eq.room_id.as_deref(), Some(room.id.as_str()));
+        assert_eq!(eq.ifc_global_id, None);
+        let sensors = eq.sensor_mappings.as_ref().unwrap();
+        assert_eq!(sensors.len(), 1);
+        assert_eq!(sensors[0].sensor_id, "501-fcu-temp");
+
+        // The source floor is untouched and levels cannot be cloned twice
+        let source = building.find_floor(3).unwrap();
+        assert_eq!(source.wings[0].rooms[0].name, "301");
+        assert_ne!(source.wings[0].rooms[0].id, room.id);
+        let again = CloneFloorOptions {
+            from: 3,
+            to: vec![5],
+            rename_pattern: Some("{room}-{floor}".into()),
+            floor_height: None,
+        };
+        assert!(clone_floor(&mut building, &again).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! Dry-run simulation shared by every mutating path.
+//!
+//! While a [`DryRunGuard`] is active on the current thread, the persistence
+//! layer, Git manager, and side-file writers (issues, sensor inbox) skip their
+//! writes and record what they would have done as [`Change`]s instead. The CLI
+//! (`arx --dry-run <command>`) and agent actions (`"dry_run": true`) wrap the
+//! normal code path in a guard, so a simulation exercises exactly the same
+//! logic — validation included — as a real run.
+
+use crate::core::Building;
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+
+thread_local! {
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static CHANGES: RefCell<Vec<Change>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Fields that change on every save (or with every real change) and would
+/// drown out real differences
+const VOLATILE_FIELDS: &[&str] = &["updated_at", "provenance"];
+
+/// Kind of simulated change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Add,
+    Modify,
+    Remove,
+    Git,
+}
+
+impl ChangeKind {
+    fn marker(&self) -> &'static str {
+        match self {
+            ChangeKind::Add => "+",
+            ChangeKind::Modify => "~",
+            ChangeKind::Remove => "-",
+            ChangeKind::Git => "⎇",
+        }
+    }
+}
+
+/// One would-be mutation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// What is affected, e.g. `room 'Lab'`, `file building.yaml`, `commit`
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl Change {
+    pub fn new(kind: ChangeKind, target: impl Into<String>, detail: Option<String>) -> Self {
+        Self {
+            kind,
+            target: target.into(),
+            detail,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind.marker(), self.target)?;
+        if let Some(ref detail) = self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether mutations on this thread should be simulated.
+pub fn is_dry_run() -> bool {
+    ACTIVE.with(|a| a.get())
+}
+
+/// Record a simulated change (no-op outside a dry run).
+pub fn record(change: Change) {
+    if is_dry_run() {
+        CHANGES.with(|c| c.borrow_mut().push(change));
+    }
+}
+
+/// Record a simulated file write under the repo root.
+pub fn record_file_write(path: &str, existed: bool) {
+    let kind = if existed {
+        ChangeKind::Modify
+    } else {
+        ChangeKind::Add
+    };
+    record(Change::new(kind, format!("file {}", path), None));
+}
+
+/// Activates dry-run mode on the current thread until finished or dropped.
+pub struct DryRunGuard {
+    previous: bool,
+}
+
+impl DryRunGuard {
+    pub fn begin() -> Self {
+        let previous = ACTIVE.with(|a| a.replace(true));
+        CHANGES.with(|c| c.borrow_mut().clear());
+        Self { previous }
+    }
+
+    /// End the simulation and return everything recorded.
+    pub fn finish(self) -> Vec<Change> {
+        CHANGES.with(|c| std::mem::take(&mut *c.borrow_mut()))
+    }
+}
+
+impl Drop for DryRunGuard {
+    fn drop(&mut self) {
+        ACTIVE.with(|a| a.set(self.previous));
+    }
+}
+
+/// Run `f` as a dry run and return its result with the recorded change set.
+pub fn simulate<R>(f: impl FnOnce() -> R) -> (R, Vec<Change>) {
+    let guard = DryRunGuard::begin();
+    let result = f();
+    (result, guard.finish())
+}
+
+/// Uniform human-readable rendering of a change set.
+pub fn render_changes(changes: &[Change]) -> Vec<String> {
+    let mut lines = vec!["🔍 Dry run — nothing was written and no Git history changed".to_string()];
+    if changes.is_empty() {
+        lines.push("   (no changes)".to_string());
+    } else {
+        lines.push(format!("   {} change(s):", changes.len()));
+        lines.extend(changes.iter().map(|c| format!("   {}", c)));
+    }
+    lines
+}
+
+/// Top-level fields of `value` other than `children`, for change detection.
+fn own_fields<T: Serialize>(value: &T, children: &[&str]) -> serde_json::Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(mut map)) => {
+            for key in children.iter().chain(VOLATILE_FIELDS) {
+                map.remove(*key);
+            }
+            map
+        }
+        _ => serde_json::Map::new(),
+    }
+}
+
+fn changed_fields<T: Serialize>(before: &T, after: &T, children: &[&str]) -> Vec<String> {
+    let (a, b) = (own_fields(before, children), own_fields(after, children));
+    let mut keys: Vec<String> = a
+        .keys()
+        .chain(b.keys())
+        .filter(|k| a.get(*k) != b.get(*k))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+fn diff_level<T: Serialize>(
+    changes: &mut Vec<Change>,
+    kind: &str,
+    before: &[(String, String, &T)],
+    after: &[(String, String, &T)],
+    children: &[&str],
+) {
+    let old: HashMap<&str, (&str, &T)> = before
+        .iter()
+        .map(|(id, label, v)| (id.as_str(), (label.as_str(), *v)))
+        .collect();
+    let new_ids: std::collections::HashSet<&str> =
+        after.iter().map(|(id, _, _)| id.as_str()).collect();
+    for (id, label, entity) in after {
+        match old.get(id.as_str()) {
+            None => changes.push(Change::new(
+                ChangeKind::Add,
+                format!("{} {}", kind, label),
+                None,
+            )),
+            Some((_, previous)) => {
+                let fields = changed_fields(*previous, *entity, children);
+                if !fields.is_empty() {
+                    changes.push(Change::new(
+                        ChangeKind::Modify,
+                        format!("{} {}", kind, label),
+                        Some(fields.join(", ")),
+                    ));
+                }
+            }
+        }
+    }
+    for (id, label, _) in before {
+        if !new_ids.contains(id.as_str()) {
+            changes.push(Change::new(
+                ChangeKind::Remove,
+                format!("{} {}", kind, label),
+                None,
+            ));
+        }
+    }
+}
+
+type Indexed<'a, T> = Vec<(String, String, &'a T)>;
+
+#[allow(clippy::type_complexity)]
+fn index(
+    building: &Building,
+) -> (
+    Indexed<'_, crate::core::Floor>,
+    Indexed<'_, crate::core::Wing>,
+    Indexed<'_, crate::core::Room>,
+    Indexed<'_, crate::core::Equipment>,
+) {
+    let (mut floors, mut wings, mut rooms, mut equipment) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for floor in &building.floors {
+        floors.push((floor.id.clone(), format!("'{}'", floor.name), floor));
+        for eq in &floor.equipment {
+            equipment.push((eq.id.clone(), format!("'{}' ({})", eq.name, floor.name), eq));
+        }
+        for wing in &floor.wings {
+            let at = format!("{} / {}", floor.name, wing.name);
+            wings.push((wing.id.clone(), format!("'{}'", at), wing));
+            for eq in &wing.equipment {
+                equipment.push((eq.id.clone(), format!("'{}' ({})", eq.name, at), eq));
+            }
+            for room in &wing.rooms {
+                rooms.push((room.id.clone(), format!("'{}' ({})", room.name, at), room));
+                for eq in &room.equipment {
+                    equipment.push((
+                        eq.id.clone(),
+                        format!("'{}' ({} / {})", eq.name, at, room.name),
+                        eq,
+                    ));
+                }
+            }
+        }
+    }
+    (floors, wings, rooms, equipment)
+}
+
+/// Entity-level differences between a stored building and a would-be save.
+pub fn diff_buildings(before: Option<&Building>, after: &Building) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let empty = Building::default();
+    let before = before.unwrap_or(&empty);
+
+    let building_fields = changed_fields(before, after, &["floors"]);
+    if !building_fields.is_empty() {
+        changes.push(Change::new(
+            ChangeKind::Modify,
+            format!("building '{}'", after.name),
+            Some(building_fields.join(", ")),
+        ));
+    }
+
+    let (bf, bw, br, be) = index(before);
+    let (af, aw, ar, ae) = index(after);
+    diff_level(&mut changes, "floor", &bf, &af, &["wings", "equipment"]);
+    diff_level(&mut changes, "wing", &bw, &aw, &["rooms", "equipment"]);
+    diff_level(&mut changes, "room", &br, &ar, &["equipment"]);
+    diff_level(&mut changes, "equipment", &be, &ae, &[]);
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn sample() -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Lobby".into(), RoomType::Office);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    #[test]
+    fn diff_reports_added_modified_removed_entities() {
+        let before = sample();
+        let mut after = before.clone();
+        let wing = &mut after.floors[0].wings[0];
+        wing.rooms[0].name = "Main Lobby".into();
+        wing.rooms[0].equipment.clear();
+        wing.add_room(Room::new("Lab".into(), RoomType::Laboratory));
+
+        let changes = diff_buildings(Some(&before), &after);
+        let rendered: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert!(rendered
+            .iter()
+            .any(|l| l.starts_with("~ room 'Main Lobby'") && l.ends_with(": name")));
+        assert!(rendered.iter().any(|l| l.starts_with("+ room 'Lab'")));
+        assert!(rendered
+            .iter()
+            .any(|l| l.starts_with("- equipment 'AHU-1'")));
+        assert!(diff_buildings(Some(&before), &before).is_empty());
+    }
+
+    #[test]
+    fn simulated_persist_leaves_yaml_and_history_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let building = sample();
+        crate::persistence::save_building_at(dir.path(), &building).unwrap();
+        let yaml = dir.path().join(crate::persistence::BUILDING_YAML);
+        let before = std::fs::read_to_string(&yaml).unwrap();
+
+        let mut edited = building.clone();
+        edited.floors[0].wings[0].rooms[0].name = "Atrium".into();
+        let (result, changes) = simulate(|| {
+            crate::ingest::persist_building_at(dir.path(), edited, true, Some("Rename lobby"))
+        });
+        result.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&yaml).unwrap(), before);
+        let rendered: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert!(rendered.iter().any(|l| l.starts_with("~ room 'Atrium'")));
+        assert!(rendered.contains(&"~ file building.yaml".to_string()));
+        assert!(rendered.contains(&"⎇ git commit: Rename lobby".to_string()));
+        assert!(git2::Repository::open(dir.path()).unwrap().head().is_err());
+    }
+
+    #[test]
+    fn guard_scopes_recording_to_the_simulation() {
+        record(Change::new(ChangeKind::Git, "commit", None));
+        assert!(!is_dry_run());
+        let (value, changes) = simulate(|| {
+            assert!(is_dry_run());
+            record_file_write("building.yaml", true);
+            42
+        });
+        assert_eq!(value, 42);
+        assert!(!is_dry_run());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].to_string(), "~ file building.yaml");
+        assert!(render_changes(&[])[1].contains("no changes"));
+    }
+}
@@ -0,0 +1,401 @@
+//! Fire-safety egress analysis.
+//!
+//! Walks the room [`NavigationGraph`] from every room to the nearest exit and
+//! flags rooms whose travel distance exceeds the configured maximum. Output is a
+//! compliance report (Markdown) and an SVG plan overlay for safety audits.
+//!
+//! Exits are rooms with `egress_exit=true` in their properties, or whose name
+//! contains "exit" / "stair". Travel distance = half the start room's plan
+//! diagonal (farthest occupant) + centre-to-centre path to the exit room.
+//! This is an audit aid, not a code-compliance certificate.
+
+use crate::core::spatial::navigation::{NavigationGraph, DEFAULT_ADJACENCY_TOLERANCE};
+use crate::core::{Building, Room};
+use crate::export::site::escape_html;
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Room property marking an exit / protected stair.
+pub const PROP_EGRESS_EXIT: &str = "egress_exit";
+
+/// Default maximum travel distance (m) to an exit.
+pub const DEFAULT_MAX_TRAVEL_DISTANCE_M: f64 = 45.0;
+
+/// Egress analysis options.
+#[derive(Debug, Clone)]
+pub struct EgressOptions {
+    pub max_travel_distance_m: f64,
+    pub adjacency_tolerance_m: f64,
+}
+
+impl Default for EgressOptions {
+    fn default() -> Self {
+        Self {
+            max_travel_distance_m: DEFAULT_MAX_TRAVEL_DISTANCE_M,
+            adjacency_tolerance_m: DEFAULT_ADJACENCY_TOLERANCE,
+        }
+    }
+}
+
+/// Compliance outcome for one room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EgressStatus {
+    Exit,
+    Compliant,
+    ExceedsMaximum,
+    NoPathToExit,
+}
+
+impl EgressStatus {
+    fn label(self) -> &'static str {
+        match self {
+            EgressStatus::Exit => "exit",
+            EgressStatus::Compliant => "ok",
+            EgressStatus::ExceedsMaximum => "EXCEEDS",
+            EgressStatus::NoPathToExit => "NO PATH",
+        }
+    }
+
+    fn fill(self) -> &'static str {
+        match self {
+            EgressStatus::Exit => "#4a90d9",
+            EgressStatus::Compliant => "#7bc67b",
+            EgressStatus::ExceedsMaximum => "#e05555",
+            EgressStatus::NoPathToExit => "#9e9e9e",
+        }
+    }
+}
+
+/// Egress result for one room.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomEgress {
+    pub room_id: String,
+    pub room_name: String,
+    pub floor_level: i32,
+    pub travel_distance_m: Option<f64>,
+    pub nearest_exit: Option<String>,
+    pub status: EgressStatus,
+}
+
+/// Building-wide egress report.
+#[derive(Debug, Clone, Serialize)]
+pub struct EgressReport {
+    pub building: String,
+    pub max_travel_distance_m: f64,
+    pub exits: usize,
+    pub rooms: Vec<RoomEgress>,
+}
+
+impl EgressReport {
+    /// Rooms that fail the audit (too far or unreachable).
+    pub fn violations(&self) -> impl Iterator<Item = &RoomEgress> {
+        self.rooms.iter().filter(|r| {
+            matches!(
+                r.status,
+                EgressStatus::ExceedsMaximum | EgressStatus::NoPathToExit
+            )
+        })
+    }
+
+    pub fn is_compliant(&self) -> bool {
+        self.exits > 0 && self.violations().next().is_none()
+    }
+
+    /// Markdown compliance report for audit handoff.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Egress compliance report — {}", self.building);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "- Maximum travel distance: {:.1} m",
+            self.max_travel_distance_m
+        );
+        let _ = writeln!(out, "- Exits identified: {}", self.exits);
+        let _ = writeln!(out, "- Rooms analysed: {}", self.rooms.len());
+        let _ = writeln!(out, "- Violations: {}", self.violations().count());
+        let _ = writeln!(
+            out,
+            "- Result: {}",
+            if self.is_compliant() { "PASS" } else { "FAIL" }
+        );
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Floor | Room | Distance (m) | Nearest exit | Status |");
+        let _ = writeln!(out, "| ---: | --- | ---: | --- | --- |");
+        for r in &self.rooms {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                r.floor_level,
+                r.room_name,
+                r.travel_distance_m
+                    .map(|d| format!("{:.1}", d))
+                    .unwrap_or_else(|| "—".into()),
+                r.nearest_exit.as_deref().unwrap_or("—"),
+                r.status.label()
+            );
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "_Derived from room envelopes and adjacency; verify against licensed egress drawings._"
+        );
+        out
+    }
+}
+
+/// Whether a room is treated as an exit.
+pub fn is_exit(room: &Room) -> bool {
+    if let Some(v) = room.properties.get(PROP_EGRESS_EXIT) {
+        return matches!(v.trim().to_ascii_lowercase().as_str(), "true" | "yes" | "1");
+    }
+    let name = room.name.to_ascii_lowercase();
+    name.contains("exit") || name.contains("stair")
+}
+
+/// Compute travel distance from every room to its nearest exit.
+pub fn analyze_egress(building: &Building, options: &EgressOptions) -> EgressReport {
+    let graph = NavigationGraph::from_building(building, options.adjacency_tolerance_m);
+    let index = graph.index_map();
+
+    let rooms: Vec<&Room> = building.get_all_rooms();
+    let exits: Vec<usize> = rooms
+        .iter()
+        .filter(|r| is_exit(r))
+        .filter_map(|r| index.get(r.id.as_str()).copied())
+        .collect();
+    let distances = graph.distances_from(&exits);
+
+    let mut results = Vec::new();
+    for room in rooms {
+        let Some(&i) = index.get(room.id.as_str()) else {
+            continue;
+        };
+        let node = &graph.nodes[i];
+        let (travel, exit, status) = if exits.contains(&i) {
+            (Some(0.0), Some(room.name.clone()), EgressStatus::Exit)
+        } else {
+            match distances[i] {
+                Some((path, origin)) => {
+                    let d = &room.spatial_properties.dimensions;
+                    let travel = path + (d.width.powi(2) + d.depth.powi(2)).sqrt() / 2.0;
+                    let status = if travel > options.max_travel_distance_m {
+                        EgressStatus::ExceedsMaximum
+                    } else {
+                        EgressStatus::Compliant
+                    };
+                    (
+                        Some(travel),
+                        Some(graph.nodes[origin].room_name.clone()),
+                        status,
+                    )
+                }
+                None => (None, None, EgressStatus::NoPathToExit),
+            }
+        };
+        results.push(RoomEgress {
+            room_id: room.id.clone(),
+            room_name: room.name.clone(),
+            floor_level: node.floor_level,
+            travel_distance_m: travel,
+            nearest_exit: exit,
+            status,
+        });
+    }
+    results.sort_by(|a, b| {
+        a.floor_level
+            .cmp(&b.floor_level)
+            .then_with(|| a.room_name.cmp(&b.room_name))
+    });
+
+    EgressReport {
+        building: building.name.clone(),
+        max_travel_distance_m: options.max_travel_distance_m,
+        exits: exits.len(),
+        rooms: results,
+    }
+}
+
+/// Render an SVG plan overlay: one panel per floor, rooms coloured by status.
+pub fn render_egress_svg(building: &Building, report: &EgressReport) -> String {
+    const SCALE: f64 = 10.0; // px per metre
+    const MARGIN: f64 = 20.0;
+    const TITLE: f64 = 24.0;
+
+    let status_of = |id: &str| {
+        report
+            .rooms
+            .iter()
+            .find(|r| r.room_id == id)
+    };
+
+    let mut floors = building.floors.iter().collect::<Vec<_>>();
+    floors.sort_by_key(|f| f.level);
+
+    let mut body = String::new();
+    let mut offset_y = MARGIN;
+    let mut width: f64 = 200.0;
+    for floor in floors {
+        let rooms: Vec<&Room> = floor.wings.iter().flat_map(|w| w.rooms.iter()).collect();
+        if rooms.is_empty() {
+            continue;
+        }
+        let min_x = rooms
+            .iter()
+            .map(|r| r.spatial_properties.bounding_box.min.x)
+            .fold(f64::INFINITY, f64::min);
+        let min_y = rooms
+            .iter()
+            .map(|r| r.spatial_properties.bounding_box.min.y)
+            .fold(f64::INFINITY, f64::min);
+        let max_x = rooms
+            .iter()
+            .map(|r| r.spatial_properties.bounding_box.max.x)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let max_y = rooms
+            .iter()
+            .map(|r| r.spatial_properties.bounding_box.max.y)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let _ = writeln!(
+            body,
+            r#"  <text x="{:.1}" y="{:.1}" font-size="14" font-weight="bold">{}</text>"#,
+            MARGIN,
+            offset_y + 14.0,
+            escape_html(&floor.name)
+        );
+        let top = offset_y + TITLE;
+        for room in rooms {
+            let bb = &room.spatial_properties.bounding_box;
+            let x = MARGIN + (bb.min.x - min_x) * SCALE;
+            // SVG y grows downward; flip so plan north is up
+            let y = top + (max_y - bb.max.y) * SCALE;
+            let w = (bb.max.x - bb.min.x) * SCALE;
+            let h = (bb.max.y - bb.min.y) * SCALE;
+            let (fill, label) = match status_of(&room.id) {
+                Some(r) => (
+                    r.status.fill(),
+                    match r.travel_distance_m {
+                        Some(d) if r.status != EgressStatus::Exit => format!("{:.1} m", d),
+                        _ => r.status.label().to_string(),
+                    },
+                ),
+                None => ("#ffffff", String::new()),
+            };
+            let _ = writeln!(
+                body,
+                r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" stroke="#333" stroke-width="1"/>"##,
+                x, y, w, h, fill
+            );
+            let _ = writeln!(
+                body,
+                r#"  <text x="{:.1}" y="{:.1}" font-size="10" text-anchor="middle">{} ({})</text>"#,
+                x + w / 2.0,
+                y + h / 2.0,
+                escape_html(&room.name),
+                escape_html(&label)
+            );
+        }
+        width = width.max(MARGIN * 2.0 + (max_x - min_x) * SCALE);
+        offset_y = top + (max_y - min_y) * SCALE + MARGIN;
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" font-family=\"sans-serif\">\n{}</svg>\n",
+        width,
+        offset_y.max(100.0),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Dimensions, Floor, Position, RoomType, SpatialProperties, Wing};
+
+    fn room_at(name: &str, x: f64, y: f64, w: f64, d: f64) -> Room {
+        let mut room = Room::new(name.into(), RoomType::Office);
+        room.spatial_properties = SpatialProperties::new(
+            Position {
+                x,
+                y,
+                z: 0.0,
+                coordinate_system: "building_local".into(),
+            },
+            Dimensions {
+                width: w,
+                height: 3.0,
+                depth: d,
+            },
+            "building_local".into(),
+        );
+        room
+    }
+
+    /// Corridor of 10 m rooms along X: Exit Stair | A | B | C, plus an island room.
+    fn corridor_building() -> Building {
+        let mut b = Building::new("Egress HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("Main".into());
+        wing.add_room(room_at("Exit Stair", 5.0, 5.0, 10.0, 10.0));
+        wing.add_room(room_at("A", 15.0, 5.0, 10.0, 10.0));
+        wing.add_room(room_at("B", 25.0, 5.0, 10.0, 10.0));
+        wing.add_room(room_at("C", 35.0, 5.0, 10.0, 10.0));
+        wing.add_room(room_at("Island", 100.0, 100.0, 5.0, 5.0));
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    #[test]
+    fn computes_travel_distance_to_nearest_exit() {
+        let b = corridor_building();
+        let report = analyze_egress(&b, &EgressOptions::default());
+        assert_eq!(report.exits, 1);
+
+        let c = report.rooms.iter().find(|r| r.room_name == "C").unwrap();
+        // 30 m centre path + half the 10×10 diagonal
+        let expected = 30.0 + (200.0f64).sqrt() / 2.0;
+        assert!((c.travel_distance_m.unwrap() - expected).abs() < 1e-9);
+        assert_eq!(c.nearest_exit.as_deref(), Some("Exit Stair"));
+        assert_eq!(c.status, EgressStatus::Compliant);
+
+        let island = report.rooms.iter().find(|r| r.room_name == "Island").unwrap();
+        assert_eq!(island.status, EgressStatus::NoPathToExit);
+        assert!(!report.is_compliant());
+    }
+
+    #[test]
+    fn flags_rooms_beyond_maximum() {
+        let b = corridor_building();
+        let options = EgressOptions {
+            max_travel_distance_m: 20.0,
+            ..Default::default()
+        };
+        let report = analyze_egress(&b, &options);
+        let failing: Vec<&str> = report
+            .violations()
+            .map(|r| r.room_name.as_str())
+            .collect();
+        assert_eq!(failing, vec!["B", "C", "Island"]);
+
+        let md = report.to_markdown();
+        assert!(md.contains("Result: FAIL"));
+        assert!(md.contains("| 0 | C |"));
+
+        let svg = render_egress_svg(&b, &report);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("#e05555"));
+    }
+
+    #[test]
+    fn explicit_exit_property_wins_over_name() {
+        let mut room = room_at("Stair Core", 0.0, 0.0, 4.0, 4.0);
+        room.properties
+            .insert(PROP_EGRESS_EXIT.into(), "false".into());
+        assert!(!is_exit(&room));
+        room.properties.insert(PROP_EGRESS_EXIT.into(), "yes".into());
+        assert!(is_exit(&room));
+    }
+}
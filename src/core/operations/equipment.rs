@@ -27,7 +27,7 @@ pub fn add_equipment(
                 if let Some(room) = wing
                     .rooms
                     .iter_mut()
-                    .find(|r| r.name == room_name || r.id == room_name)
+                    .find(|r| r.matches_name(room_name) || r.id == room_name)
                 {
                     room.equipment.push(equipment.clone());
                     added = true;
@@ -8,6 +8,12 @@
 //! - `room` - Room CRUD operations
 //! - `equipment` - Equipment CRUD operations
 //! - `spatial` - Spatial queries and validation
+//! - `dry_run` - Simulated mutations and uniform change sets
+//! - `egress` - Fire-safety egress analysis
+//! - `rename` - Bulk rename with reference rewriting
+//! - `clone` - Clone a floor onto other levels
+//! - `rollback` - Restore a room, equipment, or building from an earlier version
+//! - `sensor` - Bind incoming sensor ids to equipment or rooms
 //!
 //! # Usage
 //!
@@ -25,13 +31,25 @@
 //! ```
 
 pub mod address;
+pub mod clone;
+pub mod dry_run;
+pub mod egress;
 pub mod equipment;
+pub mod rename;
+pub mod rollback;
 pub mod room;
+pub mod sensor;
 pub mod spatial;
 #[cfg(test)]
 mod spatial_tests;
 
-pub use address::backfill_equipment_addresses;
+pub use address::{backfill_equipment_addresses, resolve_addresses, AddressKind, ResolvedAddress};
+pub use clone::{clone_floor, parse_levels, CloneFloorOptions, CloneFloorReport, ClonedFloor};
+pub use dry_run::{is_dry_run, simulate, Change, ChangeKind, DryRunGuard};
+pub use egress::{analyze_egress, render_egress_svg, EgressOptions, EgressReport};
+pub use rename::{rename_path, RenameReport, RenameTarget};
+pub use rollback::{rollback_building, rollback_entity, RollbackReport};
+pub use sensor::{bind_sensor, mapped_sensor_ids, SensorBindTarget};
 
 // Re-export room operations
 pub use room::{
@@ -46,6 +64,6 @@ pub use equipment::{
 
 // Re-export spatial operations and types
 pub use spatial::{
-    set_spatial_relationship, spatial_query, transform_coordinates, validate_spatial,
-    SpatialValidationIssue, SpatialValidationResult,
+    apply_transform, set_spatial_relationship, spatial_query, transform_coordinates,
+    validate_spatial, SpatialValidationIssue, SpatialValidationResult, TransformReport,
 };
@@ -0,0 +1,606 @@
+//! Bulk rename of floors, wings, rooms, or equipment with reference rewriting.
+//!
+//! `from` / `to` are slash paths that differ only in their last segment. `from`
+//! resolves either as a durable address (`floor.address`, `room.address`, …) or
+//! as a hierarchy path `/<building>/<floor>/<wing>[/<room>[/<equipment>]]`
+//! where each segment matches a name (or slug, alias, floor level).
+//!
+//! Renaming changes the entity name and rewrites every path that starts with
+//! the entity's old address — descendant addresses, legacy equipment `path`,
+//! property values such as `canonical_path` / attachment references, and
+//! path-like sensor ids — so nothing is left pointing at the old location.
+
+use crate::core::clock;
+use crate::core::domain::ArxAddress;
+use crate::core::{Building, Equipment, Floor, Room, Wing};
+use std::collections::HashMap;
+
+/// What a rename path resolved to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameTarget {
+    Floor {
+        floor: usize,
+    },
+    Wing {
+        floor: usize,
+        wing: usize,
+    },
+    Room {
+        floor: usize,
+        wing: usize,
+        room: usize,
+    },
+    Equipment {
+        floor: usize,
+        wing: usize,
+        room: usize,
+        equipment: usize,
+    },
+}
+
+impl RenameTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RenameTarget::Floor { .. } => "floor",
+            RenameTarget::Wing { .. } => "wing",
+            RenameTarget::Room { .. } => "room",
+            RenameTarget::Equipment { .. } => "equipment",
+        }
+    }
+}
+
+/// Outcome of [`rename_path`].
+#[derive(Debug, Clone)]
+pub struct RenameReport {
+    pub kind: &'static str,
+    pub entity_id: String,
+    pub old_name: String,
+    pub new_name: String,
+    /// Prefix rewrites applied (old → new)
+    pub rewrites: Vec<(String, String)>,
+    /// Human-readable list of changed references
+    pub changes: Vec<String>,
+}
+
+/// Lower-case, dash-separated slug (same shape the address backfill uses).
+pub fn path_slug(s: &str) -> String {
+    s.trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+fn segment_matches(name: &str, segment: &str) -> bool {
+    name.eq_ignore_ascii_case(segment) || path_slug(name) == path_slug(segment)
+}
+
+/// Replace `old` prefix with `new` on a segment boundary.
+pub fn rewrite_prefix(value: &str, old: &str, new: &str) -> Option<String> {
+    if old.is_empty() || old == new {
+        return None;
+    }
+    if value == old {
+        return Some(new.to_string());
+    }
+    value
+        .strip_prefix(old)
+        .filter(|rest| rest.starts_with('/'))
+        .map(|rest| format!("{}{}", new, rest))
+}
+
+fn address_is(addr: &Option<ArxAddress>, path: &str) -> bool {
+    addr.as_ref()
+        .map(|a| a.path.eq_ignore_ascii_case(path.trim_end_matches('/')))
+        .unwrap_or(false)
+}
+
+fn resolve_by_address(building: &Building, path: &str) -> Option<RenameTarget> {
+    for (fi, floor) in building.floors.iter().enumerate() {
+        if address_is(&floor.address, path) {
+            return Some(RenameTarget::Floor { floor: fi });
+        }
+        for (wi, wing) in floor.wings.iter().enumerate() {
+            if address_is(&wing.address, path) {
+                return Some(RenameTarget::Wing {
+                    floor: fi,
+                    wing: wi,
+                });
+            }
+            for (ri, room) in wing.rooms.iter().enumerate() {
+                if address_is(&room.address, path) {
+                    return Some(RenameTarget::Room {
+                        floor: fi,
+                        wing: wi,
+                        room: ri,
+                    });
+                }
+                for (ei, eq) in room.equipment.iter().enumerate() {
+                    if address_is(&eq.address, path) {
+                        return Some(RenameTarget::Equipment {
+                            floor: fi,
+                            wing: wi,
+                            room: ri,
+                            equipment: ei,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_floor(building: &Building, segment: &str) -> Option<usize> {
+    let by_level = segment.parse::<i32>().ok();
+    building
+        .floors
+        .iter()
+        .position(|f| segment_matches(&f.name, segment))
+        .or_else(|| by_level.and_then(|l| building.floors.iter().position(|f| f.level == l)))
+}
+
+fn resolve_by_hierarchy(building: &Building, path: &str) -> Option<RenameTarget> {
+    let mut segs = segments(path);
+    let building_names = [building.name.as_str(), building.path.trim_matches('/')];
+    if segs.len() > 1
+        && building_names
+            .iter()
+            .any(|n| !n.is_empty() && segment_matches(n, segs[0]))
+    {
+        segs.remove(0);
+    }
+    let fi = find_floor(building, segs.first()?)?;
+    if segs.len() == 1 {
+        return Some(RenameTarget::Floor { floor: fi });
+    }
+    let floor = &building.floors[fi];
+    let wi = floor
+        .wings
+        .iter()
+        .position(|w| segment_matches(&w.name, segs[1]))?;
+    if segs.len() == 2 {
+        return Some(RenameTarget::Wing {
+            floor: fi,
+            wing: wi,
+        });
+    }
+    let wing = &floor.wings[wi];
+    let ri = wing
+        .rooms
+        .iter()
+        .position(|r| r.matches_name(segs[2]) || segment_matches(&r.name, segs[2]))?;
+    if segs.len() == 3 {
+        return Some(RenameTarget::Room {
+            floor: fi,
+            wing: wi,
+            room: ri,
+        });
+    }
+    let room = &wing.rooms[ri];
+    let ei = room
+        .equipment
+        .iter()
+        .position(|e| e.matches_name(segs[3]) || segment_matches(&e.name, segs[3]))?;
+    if segs.len() == 4 {
+        return Some(RenameTarget::Equipment {
+            floor: fi,
+            wing: wi,
+            room: ri,
+            equipment: ei,
+        });
+    }
+    None
+}
+
+/// Resolve `path` to the entity it names.
+pub fn resolve_rename_target(building: &Building, path: &str) -> Option<RenameTarget> {
+    resolve_by_address(building, path).or_else(|| resolve_by_hierarchy(building, path))
+}
+
+/// Equipment addresses are 7-part (`…/building/floor/room/fixture`); the index
+/// of the segment that carries the renamed entity's slug.
+fn equipment_segment_index(target: &RenameTarget) -> usize {
+    match target {
+        RenameTarget::Floor { .. } => 4,
+        RenameTarget::Wing { .. } | RenameTarget::Room { .. } => 5,
+        RenameTarget::Equipment { .. } => 6,
+    }
+}
+
+fn equipment_under<'a>(building: &'a Building, target: &RenameTarget) -> Vec<&'a Equipment> {
+    match *target {
+        RenameTarget::Floor { floor } => {
+            let f = &building.floors[floor];
+            f.equipment
+                .iter()
+                .chain(f.wings.iter().flat_map(|w| {
+                    w.equipment
+                        .iter()
+                        .chain(w.rooms.iter().flat_map(|r| r.equipment.iter()))
+                }))
+                .collect()
+        }
+        // Room equipment addresses skip the wing, so only wing-level equipment carries its slug
+        RenameTarget::Wing { floor, wing } => building.floors[floor].wings[wing]
+            .equipment
+            .iter()
+            .collect(),
+        RenameTarget::Room { floor, wing, room } => building.floors[floor].wings[wing].rooms[room]
+            .equipment
+            .iter()
+            .collect(),
+        RenameTarget::Equipment {
+            floor,
+            wing,
+            room,
+            equipment,
+        } => vec![&building.floors[floor].wings[wing].rooms[room].equipment[equipment]],
+    }
+}
+
+fn target_state(
+    building: &Building,
+    target: &RenameTarget,
+) -> (String, String, Option<ArxAddress>) {
+    match *target {
+        RenameTarget::Floor { floor } => {
+            let f: &Floor = &building.floors[floor];
+            (f.id.clone(), f.name.clone(), f.address.clone())
+        }
+        RenameTarget::Wing { floor, wing } => {
+            let w: &Wing = &building.floors[floor].wings[wing];
+            (w.id.clone(), w.name.clone(), w.address.clone())
+        }
+        RenameTarget::Room { floor, wing, room } => {
+            let r: &Room = &building.floors[floor].wings[wing].rooms[room];
+            (r.id.clone(), r.name.clone(), r.address.clone())
+        }
+        RenameTarget::Equipment {
+            floor,
+            wing,
+            room,
+            equipment,
+        } => {
+            let e = &building.floors[floor].wings[wing].rooms[room].equipment[equipment];
+            (e.id.clone(), e.name.clone(), e.address.clone())
+        }
+    }
+}
+
+fn parent_path(path: &str) -> &str {
+    path.rfind('/').map(|i| &path[..i]).unwrap_or("")
+}
+
+fn apply_rewrites(value: &mut String, rewrites: &[(String, String)]) -> bool {
+    for (old, new) in rewrites {
+        if let Some(updated) = rewrite_prefix(value, old, new) {
+            *value = updated;
+            return true;
+        }
+    }
+    false
+}
+
+fn rewrite_props(
+    props: &mut HashMap<String, String>,
+    owner: &str,
+    rewrites: &[(String, String)],
+    changes: &mut Vec<String>,
+) {
+    for (k, v) in props.iter_mut() {
+        let before = v.clone();
+        if apply_rewrites(v, rewrites) {
+            changes.push(format!("{} property {}: {} → {}", owner, k, before, v));
+        }
+    }
+}
+
+fn rewrite_address(
+    addr: &mut Option<ArxAddress>,
+    owner: &str,
+    rewrites: &[(String, String)],
+    changes: &mut Vec<String>,
+) {
+    if let Some(a) = addr {
+        let before = a.path.clone();
+        if apply_rewrites(&mut a.path, rewrites) {
+            changes.push(format!("{} address: {} → {}", owner, before, a.path));
+        }
+    }
+}
+
+fn rewrite_equipment(eq: &mut Equipment, rewrites: &[(String, String)], changes: &mut Vec<String>) {
+    let owner = format!("equipment '{}'", eq.name);
+    rewrite_address(&mut eq.address, &owner, rewrites, changes);
+    let before = eq.path.clone();
+    if apply_rewrites(&mut eq.path, rewrites) {
+        changes.push(format!("{} path: {} → {}", owner, before, eq.path));
+    }
+    rewrite_props(&mut eq.properties, &owner, rewrites, changes);
+    for mapping in eq.sensor_mappings.iter_mut().flatten() {
+        let before = mapping.sensor_id.clone();
+        if apply_rewrites(&mut mapping.sensor_id, rewrites) {
+            changes.push(format!(
+                "{} sensor: {} → {}",
+                owner, before, mapping.sensor_id
+            ));
+        }
+    }
+}
+
+/// Rename the entity at `from` to the last segment of `to`, rewriting references.
+///
+/// Floors resolved by level number with a numeric new segment are renumbered
+/// instead of renamed.
+pub fn rename_path(building: &mut Building, from: &str, to: &str) -> Result<RenameReport, String> {
+    let from_segs = segments(from);
+    let to_segs = segments(to);
+    if from_segs.is_empty() || from_segs.len() != to_segs.len() {
+        return Err("--from and --to must have the same number of path segments".into());
+    }
+    if from_segs[..from_segs.len() - 1] != to_segs[..to_segs.len() - 1] {
+        return Err("only the last path segment may change (moves are not supported)".into());
+    }
+    let old_segment = from_segs[from_segs.len() - 1];
+    let new_name = to_segs[to_segs.len() - 1].to_string();
+    if old_segment == new_name {
+        return Err("--from and --to are identical".into());
+    }
+
+    let target = resolve_rename_target(building, from)
+        .ok_or_else(|| format!("No floor, wing, room, or equipment at '{}'", from))?;
+    let (entity_id, old_name, own_address) = target_state(building, &target);
+
+    // Sibling collision check
+    let collides = match target {
+        RenameTarget::Floor { floor } => building
+            .floors
+            .iter()
+            .enumerate()
+            .any(|(i, f)| i != floor && segment_matches(&f.name, &new_name)),
+        RenameTarget::Wing { floor, wing } => building.floors[floor]
+            .wings
+            .iter()
+            .enumerate()
+            .any(|(i, w)| i != wing && segment_matches(&w.name, &new_name)),
+        RenameTarget::Room { floor, wing, room } => building.floors[floor].wings[wing]
+            .rooms
+            .iter()
+            .enumerate()
+            .any(|(i, r)| i != room && r.matches_name(&new_name)),
+        RenameTarget::Equipment {
+            floor,
+            wing,
+            room,
+            equipment,
+        } => building.floors[floor].wings[wing].rooms[room]
+            .equipment
+            .iter()
+            .enumerate()
+            .any(|(i, e)| i != equipment && e.matches_name(&new_name)),
+    };
+    if collides {
+        return Err(format!(
+            "A sibling {} named '{}' already exists",
+            target.kind(),
+            new_name
+        ));
+    }
+
+    // Collect prefix rewrites: the hierarchy path itself, the entity's own
+    // address, and the equipment-address prefixes that embed its slug.
+    let new_slug = path_slug(&new_name);
+    let slugged = |segs: &[&str]| {
+        segs.iter()
+            .map(|s| path_slug(s))
+            .collect::<Vec<_>>()
+            .join("/")
+    };
+    let mut rewrites: Vec<(String, String)> = vec![
+        (
+            format!("/{}", from_segs.join("/")),
+            format!("/{}", to_segs.join("/")),
+        ),
+        // Sensor room paths are usually written in slug form
+        (
+            format!("/{}", slugged(&from_segs)),
+            format!("/{}", slugged(&to_segs)),
+        ),
+    ];
+    if let Some(addr) = &own_address {
+        let new_addr = format!("{}/{}", parent_path(&addr.path), new_slug);
+        rewrites.push((addr.path.clone(), new_addr));
+    }
+    let old_slug = path_slug(&old_name);
+    let idx = equipment_segment_index(&target);
+    for eq in equipment_under(building, &target) {
+        let Some(addr) = &eq.address else { continue };
+        let segs = segments(&addr.path);
+        if segs.len() > idx && segs[idx] == old_slug {
+            let old_prefix = format!("/{}", segs[..=idx].join("/"));
+            let new_prefix = format!("/{}/{}", segs[..idx].join("/"), new_slug);
+            if !rewrites.iter().any(|(o, _)| *o == old_prefix) {
+                rewrites.push((old_prefix, new_prefix));
+            }
+        }
+    }
+    rewrites.retain(|(o, n)| o != n);
+    // Longest prefix first so nested rewrites win
+    rewrites.sort_by_key(|r| std::cmp::Reverse(r.0.len()));
+
+    // Rename the entity itself
+    let mut changes = Vec::new();
+    match target {
+        RenameTarget::Floor { floor } => {
+            let f = &mut building.floors[floor];
+            match (old_segment.parse::<i32>(), new_name.parse::<i32>()) {
+                (Ok(old_level), Ok(new_level)) if f.level == old_level => {
+                    f.level = new_level;
+                    changes.push(format!("floor level: {} → {}", old_level, new_level));
+                }
+                _ => {
+                    f.name = new_name.clone();
+                    changes.push(format!("floor name: {} → {}", old_name, new_name));
+                }
+            }
+        }
+        RenameTarget::Wing { floor, wing } => {
+            building.floors[floor].wings[wing].name = new_name.clone();
+            changes.push(format!("wing name: {} → {}", old_name, new_name));
+        }
+        RenameTarget::Room { floor, wing, room } => {
+            let r = &mut building.floors[floor].wings[wing].rooms[room];
+            r.name = new_name.clone();
+            r.updated_at = Some(clock::now());
+            changes.push(format!("room name: {} → {}", old_name, new_name));
+        }
+        RenameTarget::Equipment {
+            floor,
+            wing,
+            room,
+            equipment,
+        } => {
+            building.floors[floor].wings[wing].rooms[room].equipment[equipment].name =
+                new_name.clone();
+            changes.push(format!("equipment name: {} → {}", old_name, new_name));
+        }
+    }
+
+    // Rewrite references building-wide
+    rewrite_address(&mut building.address, "building", &rewrites, &mut changes);
+    if let Some(meta) = building.metadata.as_mut() {
+        rewrite_props(&mut meta.properties, "building", &rewrites, &mut changes);
+    }
+    for anchor in building.get_all_anchors_mut() {
+        let owner = format!("anchor '{}'", anchor.name);
+        rewrite_address(&mut anchor.address, &owner, &rewrites, &mut changes);
+    }
+    for floor in &mut building.floors {
+        let owner = format!("floor '{}'", floor.name);
+        rewrite_address(&mut floor.address, &owner, &rewrites, &mut changes);
+        rewrite_props(&mut floor.properties, &owner, &rewrites, &mut changes);
+        for eq in &mut floor.equipment {
+            rewrite_equipment(eq, &rewrites, &mut changes);
+        }
+        for wing in &mut floor.wings {
+            let owner = format!("wing '{}'", wing.name);
+            rewrite_address(&mut wing.address, &owner, &rewrites, &mut changes);
+            rewrite_props(&mut wing.properties, &owner, &rewrites, &mut changes);
+            for eq in &mut wing.equipment {
+                rewrite_equipment(eq, &rewrites, &mut changes);
+            }
+            for room in &mut wing.rooms {
+                let owner = format!("room '{}'", room.name);
+                rewrite_address(&mut room.address, &owner, &rewrites, &mut changes);
+                rewrite_props(&mut room.properties, &owner, &rewrites, &mut changes);
+                for eq in &mut room.equipment {
+                    rewrite_equipment(eq, &rewrites, &mut changes);
+                }
+            }
+        }
+    }
+
+    Ok(RenameReport {
+        kind: target.kind(),
+        entity_id,
+        old_name,
+        new_name,
+        rewrites,
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::operations::backfill_equipment_addresses;
+    use crate::core::{EquipmentType, RoomType};
+
+    fn sample() -> Building {
+        let mut b = Building::new("B1".into(), "/b1".into());
+        let mut floor = Floor::new("3".into(), 3);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("Lab".into(), RoomType::Laboratory);
+        let mut eq = Equipment::new("Hood".into(), String::new(), EquipmentType::HVAC);
+        eq.sensor_mappings = Some(vec![crate::core::equipment::SensorMapping {
+            sensor_id: "/b1/3/a/lab/airflow".into(),
+            sensor_type: "airflow".into(),
+            thresholds: HashMap::new(),
+        }]);
+        room.add_equipment(eq);
+        room.properties
+            .insert("photo_door".into(), "/B1/3/A/Lab/door.jpg".into());
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        backfill_equipment_addresses(&mut b);
+        b
+    }
+
+    #[test]
+    fn rename_wing_rewrites_descendant_addresses_and_props() {
+        let mut b = sample();
+        let room_addr_before = b.floors[0].wings[0].rooms[0].address.clone().unwrap();
+        assert!(room_addr_before.path.ends_with("/3/a/lab"));
+
+        let report = rename_path(&mut b, "/B1/3/A", "/B1/3/B").unwrap();
+        assert_eq!(report.kind, "wing");
+        let wing = &b.floors[0].wings[0];
+        assert_eq!(wing.name, "B");
+        assert!(wing.address.as_ref().unwrap().path.ends_with("/3/b"));
+        let room = &wing.rooms[0];
+        assert!(room.address.as_ref().unwrap().path.ends_with("/3/b/lab"));
+        assert_eq!(room.properties["photo_door"], "/B1/3/B/Lab/door.jpg");
+        // Room equipment addresses do not include the wing
+        let eq = &room.equipment[0];
+        assert!(eq.address.as_ref().unwrap().path.ends_with("/3/lab/hood"));
+    }
+
+    #[test]
+    fn rename_room_rewrites_equipment_address_path_and_sensors() {
+        let mut b = sample();
+        let report = rename_path(&mut b, "/B1/3/A/Lab", "/B1/3/A/Wet Lab").unwrap();
+        assert_eq!(report.kind, "room");
+        let room = &b.floors[0].wings[0].rooms[0];
+        assert_eq!(room.name, "Wet Lab");
+        let eq = &room.equipment[0];
+        let addr = &eq.address.as_ref().unwrap().path;
+        assert!(addr.ends_with("/3/wet-lab/hood"), "{}", addr);
+        assert_eq!(&eq.path, addr);
+        assert_eq!(
+            eq.sensor_mappings.as_ref().unwrap()[0].sensor_id,
+            "/b1/3/a/wet-lab/airflow"
+        );
+    }
+
+    #[test]
+    fn rename_by_address_and_rejects_moves_and_collisions() {
+        let mut b = sample();
+        let floor_addr = b.floors[0].address.clone().unwrap().path;
+        let to = format!("{}/floor-03", parent_path(&floor_addr));
+        let report = rename_path(&mut b, &floor_addr, &to).unwrap();
+        assert_eq!(report.kind, "floor");
+        assert_eq!(b.floors[0].name, "floor-03");
+        let eq = &b.floors[0].wings[0].rooms[0].equipment[0];
+        assert!(eq.address.as_ref().unwrap().path.contains("/floor-03/lab/"));
+
+        assert!(rename_path(&mut b, "/B1/floor-03/A", "/B2/floor-03/A").is_err());
+        b.floors[0].wings.push(Wing::new("C".into()));
+        assert!(rename_path(&mut b, "/B1/floor-03/A", "/B1/floor-03/C").is_err());
+    }
+}
@@ -0,0 +1,381 @@
+//! Roll a room, a piece of equipment, or the whole building back to an earlier
+//! version (typically `building.yaml` as of a Git commit).
+//!
+//! An entity is located in the earlier building by path (see
+//! [`resolve_rename_target`]), id, or name, and matched to the current building
+//! by id: a renamed entity is restored in place, a deleted one is re-created
+//! in the wing or room it used to belong to.
+
+use super::rename::{resolve_rename_target, RenameTarget};
+use crate::core::{Building, Equipment, Room};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Outcome of a rollback.
+#[derive(Debug, Clone)]
+pub struct RollbackReport {
+    /// `room`, `equipment`, or `building`
+    pub kind: &'static str,
+    pub name: String,
+    /// The entity no longer existed and was re-created
+    pub recreated: bool,
+    /// Human-readable list of restored values
+    pub changes: Vec<String>,
+}
+
+/// Room or room equipment that `query` names in `building`.
+fn locate(building: &Building, query: &str) -> Result<Option<RenameTarget>, String> {
+    if let Some(target) = resolve_rename_target(building, query) {
+        return match target {
+            RenameTarget::Room { .. } | RenameTarget::Equipment { .. } => Ok(Some(target)),
+            _ => Err(format!(
+                "'{}' is a {}; only rooms and equipment can be rolled back on their own \
+                 (omit --entity to restore the whole building)",
+                query,
+                target.kind()
+            )),
+        };
+    }
+    for (fi, floor) in building.floors.iter().enumerate() {
+        for (wi, wing) in floor.wings.iter().enumerate() {
+            for (ri, room) in wing.rooms.iter().enumerate() {
+                if room.id.eq_ignore_ascii_case(query) || room.matches_name(query) {
+                    return Ok(Some(RenameTarget::Room {
+                        floor: fi,
+                        wing: wi,
+                        room: ri,
+                    }));
+                }
+                if let Some(ei) = room
+                    .equipment
+                    .iter()
+                    .position(|e| e.id.eq_ignore_ascii_case(query) || e.matches_name(query))
+                {
+                    return Ok(Some(RenameTarget::Equipment {
+                        floor: fi,
+                        wing: wi,
+                        room: ri,
+                        equipment: ei,
+                    }));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn room_mut<'a>(building: &'a mut Building, id: &str) -> Option<&'a mut Room> {
+    building
+        .floors
+        .iter_mut()
+        .flat_map(|f| f.wings.iter_mut())
+        .flat_map(|w| w.rooms.iter_mut())
+        .find(|r| r.id == id)
+}
+
+fn equipment_mut<'a>(building: &'a mut Building, id: &str) -> Option<&'a mut Equipment> {
+    building
+        .floors
+        .iter_mut()
+        .flat_map(|f| f.wings.iter_mut())
+        .flat_map(|w| w.rooms.iter_mut())
+        .flat_map(|r| r.equipment.iter_mut())
+        .find(|e| e.id == id)
+}
+
+fn short(value: &Value) -> String {
+    match value {
+        Value::Null => "(none)".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => format!("{} item(s)", items.len()),
+        Value::Object(_) => "{…}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Top-level fields that differ between `current` and `restored`, as
+/// `field: current → restored`. Provenance bookkeeping is left out.
+fn field_changes<T: Serialize>(current: &T, restored: &T) -> Vec<String> {
+    let as_map = |v: &T| match serde_json::to_value(v) {
+        Ok(Value::Object(map)) => map.into_iter().collect::<BTreeMap<_, _>>(),
+        _ => BTreeMap::new(),
+    };
+    let (before, after) = (as_map(current), as_map(restored));
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|k| k.as_str() != "provenance")
+        .filter_map(|k| {
+            let (old, new) = (
+                before.get(k).unwrap_or(&Value::Null),
+                after.get(k).unwrap_or(&Value::Null),
+            );
+            if old == new {
+                return None;
+            }
+            let (old_s, new_s) = (short(old), short(new));
+            Some(if old_s == new_s {
+                format!("{} changed", k)
+            } else {
+                format!("{}: {} → {}", k, old_s, new_s)
+            })
+        })
+        .collect()
+}
+
+/// Restore the room or equipment `query` names in `earlier` into `current`.
+pub fn rollback_entity(
+    current: &mut Building,
+    earlier: &Building,
+    query: &str,
+) -> Result<RollbackReport, String> {
+    let target = match locate(earlier, query)? {
+        Some(target) => target,
+        None if locate(current, query)?.is_some() => {
+            return Err(format!("'{}' did not exist in that version", query))
+        }
+        None => return Err(format!("No room or equipment matches '{}'", query)),
+    };
+
+    match target {
+        RenameTarget::Room { floor, wing, room } => {
+            let restored = earlier.floors[floor].wings[wing].rooms[room].clone();
+            let name = restored.name.clone();
+            if let Some(existing) = room_mut(current, &restored.id) {
+                let changes = field_changes(&*existing, &restored);
+                *existing = restored;
+                return Ok(RollbackReport {
+                    kind: "room",
+                    name,
+                    recreated: false,
+                    changes,
+                });
+            }
+            let (floor_id, wing) = (
+                &earlier.floors[floor].id,
+                &earlier.floors[floor].wings[wing],
+            );
+            let parent = current
+                .floors
+                .iter_mut()
+                .filter(|f| &f.id == floor_id)
+                .flat_map(|f| f.wings.iter_mut())
+                .find(|w| w.id == wing.id)
+                .ok_or_else(|| {
+                    format!(
+                        "Cannot re-create room '{}': wing '{}' no longer exists \
+                         (roll back the whole building instead)",
+                        name, wing.name
+                    )
+                })?;
+            let changes = vec![format!(
+                "re-created in wing '{}' with {} equipment",
+                parent.name,
+                restored.equipment.len()
+            )];
+            parent.rooms.push(restored);
+            Ok(RollbackReport {
+                kind: "room",
+                name,
+                recreated: true,
+                changes,
+            })
+        }
+        RenameTarget::Equipment {
+            floor,
+            wing,
+            room,
+            equipment,
+        } => {
+            let parent_room = &earlier.floors[floor].wings[wing].rooms[room];
+            let restored = parent_room.equipment[equipment].clone();
+            let name = restored.name.clone();
+            if let Some(existing) = equipment_mut(current, &restored.id) {
+                let changes = field_changes(&*existing, &restored);
+                *existing = restored;
+                return Ok(RollbackReport {
+                    kind: "equipment",
+                    name,
+                    recreated: false,
+                    changes,
+                });
+            }
+            let parent = room_mut(current, &parent_room.id).ok_or_else(|| {
+                format!(
+                    "Cannot re-create equipment '{}': room '{}' no longer exists \
+                     (roll back the room instead)",
+                    name, parent_room.name
+                )
+            })?;
+            let changes = vec![format!("re-created in room '{}'", parent.name)];
+            parent.equipment.push(restored);
+            Ok(RollbackReport {
+                kind: "equipment",
+                name,
+                recreated: true,
+                changes,
+            })
+        }
+        _ => unreachable!("locate only yields rooms and equipment"),
+    }
+}
+
+fn room_entries(rooms: &[Room]) -> Vec<(&str, &str, &Room)> {
+    rooms
+        .iter()
+        .map(|r| (r.id.as_str(), r.name.as_str(), r))
+        .collect()
+}
+
+fn equipment_entries(building: &Building) -> Vec<(&str, &str, &Equipment)> {
+    building
+        .get_all_equipment()
+        .into_iter()
+        .map(|e| (e.id.as_str(), e.name.as_str(), e))
+        .collect()
+}
+
+/// Summary of replacing `current` with `earlier` wholesale: rooms and
+/// equipment that come back, go away, or revert.
+pub fn rollback_building(current: &Building, earlier: &Building) -> RollbackReport {
+    fn compare<T: Serialize>(
+        kind: &str,
+        current: Vec<(&str, &str, &T)>,
+        earlier: Vec<(&str, &str, &T)>,
+        changes: &mut Vec<String>,
+    ) {
+        for (id, name, restored) in &earlier {
+            match current.iter().find(|(cid, _, _)| cid == id) {
+                None => changes.push(format!("{} '{}' restored", kind, name)),
+                Some((_, _, now)) => {
+                    let fields = field_changes(*now, *restored);
+                    if !fields.is_empty() {
+                        changes.push(format!(
+                            "{} '{}' reverted ({})",
+                            kind,
+                            name,
+                            fields.join("; ")
+                        ));
+                    }
+                }
+            }
+        }
+        for (id, name, _) in &current {
+            if !earlier.iter().any(|(eid, _, _)| eid == id) {
+                changes.push(format!("{} '{}' removed", kind, name));
+            }
+        }
+    }
+
+    // Rooms carry their equipment, which is compared on its own
+    let bare = |b: &Building| -> Vec<Room> {
+        b.floors
+            .iter()
+            .flat_map(|f| f.wings.iter())
+            .flat_map(|w| w.rooms.iter())
+            .map(|r| Room {
+                equipment: Vec::new(),
+                ..r.clone()
+            })
+            .collect()
+    };
+    let (current_rooms, earlier_rooms) = (bare(current), bare(earlier));
+    let mut changes = Vec::new();
+    compare(
+        "room",
+        room_entries(&current_rooms),
+        room_entries(&earlier_rooms),
+        &mut changes,
+    );
+    compare(
+        "equipment",
+        equipment_entries(current),
+        equipment_entries(earlier),
+        &mut changes,
+    );
+    if current.floors.len() != earlier.floors.len() {
+        changes.push(format!(
+            "floors: {} → {}",
+            current.floors.len(),
+            earlier.floors.len()
+        ));
+    }
+    RollbackReport {
+        kind: "building",
+        name: earlier.name.clone(),
+        recreated: false,
+        changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentStatus, EquipmentType, Floor, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        wing.add_room(Room::new("Office".into(), RoomType::Office));
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn test_rollback_entity_restores_and_recreates() {
+        let earlier = building();
+        let mut current = earlier.clone();
+        current.find_equipment_mut("AHU-1").unwrap().status = EquipmentStatus::Maintenance;
+        current.floors[0].wings[0]
+            .rooms
+            .retain(|r| r.name != "Office");
+
+        let report =
+            rollback_entity(&mut current, &earlier, "/HQ/Ground/East/Plant/AHU-1").unwrap();
+        assert_eq!((report.kind, report.recreated), ("equipment", false));
+        assert_eq!(report.changes, vec!["status: Maintenance → Active"]);
+        assert_eq!(
+            current.find_equipment("AHU-1").unwrap().status,
+            EquipmentStatus::Active
+        );
+
+        let report = rollback_entity(&mut current, &earlier, "Office").unwrap();
+        assert!(report.recreated);
+        assert_eq!(current.floors[0].wings[0].rooms.len(), 2);
+
+        assert!(rollback_entity(&mut current, &earlier, "/HQ/Ground").is_err());
+        assert!(rollback_entity(&mut current, &earlier, "Nowhere").is_err());
+    }
+
+    #[test]
+    fn test_rollback_building_summary() {
+        let earlier = building();
+        let mut current = earlier.clone();
+        current.floors[0].wings[0].rooms[0].equipment.clear();
+        current.floors[0].wings[0].rooms[1].name = "Open Office".into();
+        current.floors[0].wings[0]
+            .rooms
+            .push(Room::new("Store".into(), RoomType::Storage));
+
+        let report = rollback_building(&current, &earlier);
+        assert_eq!(
+            report.changes,
+            vec![
+                "room 'Office' reverted (name: Open Office → Office)",
+                "room 'Store' removed",
+                "equipment 'AHU-1' restored",
+            ]
+        );
+    }
+}
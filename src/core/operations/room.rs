@@ -85,8 +85,7 @@ pub fn get_room(
     for floor in &building.floors {
         for wing in &floor.wings {
             for room in &wing.rooms {
-                if room.name.eq_ignore_ascii_case(room_name)
-                    || room.id.eq_ignore_ascii_case(room_name)
+                if room.matches_name(room_name) || room.id.eq_ignore_ascii_case(room_name)
                 {
                     return Ok(room.clone());
                 }
@@ -0,0 +1,238 @@
+//! Sensor-to-model binding.
+//!
+//! Incoming sensor ids are bound either to existing equipment (a new
+//! `SensorMapping` on it) or to a room, in which case a sensor equipment entry
+//! named after the sensor is created in that room to carry the mapping.
+
+use crate::core::equipment::{SensorMapping, ThresholdConfig};
+use crate::core::{Building, Equipment, EquipmentType};
+use std::collections::{BTreeSet, HashMap};
+
+/// Where a sensor gets bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SensorBindTarget {
+    /// Equipment id
+    Equipment(String),
+    /// Room id
+    Room(String),
+}
+
+/// Every sensor id already mapped anywhere in the building.
+pub fn mapped_sensor_ids(building: &Building) -> BTreeSet<String> {
+    all_equipment(building)
+        .flat_map(|e| e.sensor_mappings.iter().flatten())
+        .map(|m| m.sensor_id.clone())
+        .collect()
+}
+
+fn all_equipment(building: &Building) -> impl Iterator<Item = &Equipment> {
+    building.floors.iter().flat_map(|f| {
+        f.equipment.iter().chain(f.wings.iter().flat_map(|w| {
+            w.equipment
+                .iter()
+                .chain(w.rooms.iter().flat_map(|r| r.equipment.iter()))
+        }))
+    })
+}
+
+fn all_equipment_mut(building: &mut Building) -> impl Iterator<Item = &mut Equipment> {
+    building.floors.iter_mut().flat_map(|f| {
+        f.equipment
+            .iter_mut()
+            .chain(f.wings.iter_mut().flat_map(|w| {
+                w.equipment
+                    .iter_mut()
+                    .chain(w.rooms.iter_mut().flat_map(|r| r.equipment.iter_mut()))
+            }))
+    })
+}
+
+/// Parse a threshold range: `min..max`, either side optional (`..30`, `10..`).
+pub fn parse_threshold_range(spec: &str) -> Result<ThresholdConfig, String> {
+    let (lo, hi) = spec
+        .trim()
+        .split_once("..")
+        .ok_or_else(|| format!("Threshold '{}' must look like min..max", spec))?;
+    let bound = |s: &str| -> Result<Option<f64>, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            Ok(None)
+        } else {
+            s.parse::<f64>()
+                .map(Some)
+                .map_err(|_| format!("'{}' is not a number", s))
+        }
+    };
+    let (min, max) = (bound(lo)?, bound(hi)?);
+    if let (Some(a), Some(b)) = (min, max) {
+        if a > b {
+            return Err(format!("Threshold min {} is above max {}", a, b));
+        }
+    }
+    Ok(ThresholdConfig {
+        min,
+        max,
+        warning_min: None,
+        warning_max: None,
+        critical_min: None,
+        critical_max: None,
+    })
+}
+
+/// Bind `sensor_id` to `target`, replacing any earlier binding of the same id.
+/// `threshold` is keyed by `sensor_type`. Returns the name of the equipment
+/// now carrying the mapping.
+pub fn bind_sensor(
+    building: &mut Building,
+    sensor_id: &str,
+    sensor_type: &str,
+    target: &SensorBindTarget,
+    threshold: Option<ThresholdConfig>,
+) -> Result<String, String> {
+    let sensor_id = sensor_id.trim();
+    if sensor_id.is_empty() {
+        return Err("Sensor id is empty".into());
+    }
+    let sensor_type = match sensor_type.trim() {
+        "" => "generic",
+        t => t,
+    };
+    let mut thresholds = HashMap::new();
+    if let Some(t) = threshold {
+        thresholds.insert(sensor_type.to_string(), t);
+    }
+    let mapping = SensorMapping {
+        sensor_id: sensor_id.to_string(),
+        sensor_type: sensor_type.to_string(),
+        thresholds,
+    };
+
+    // Resolve the target before touching anything so a bad target is a no-op
+    let exists = match target {
+        SensorBindTarget::Equipment(id) => all_equipment(building).any(|e| &e.id == id),
+        SensorBindTarget::Room(id) => building
+            .floors
+            .iter()
+            .flat_map(|f| f.wings.iter())
+            .flat_map(|w| w.rooms.iter())
+            .any(|r| &r.id == id),
+    };
+    if !exists {
+        return Err(format!("Bind target {:?} not found", target));
+    }
+
+    unbind_sensor(building, sensor_id);
+
+    match target {
+        SensorBindTarget::Equipment(id) => {
+            let eq = all_equipment_mut(building)
+                .find(|e| &e.id == id)
+                .expect("checked above");
+            eq.sensor_mappings
+                .get_or_insert_with(Vec::new)
+                .push(mapping);
+            Ok(eq.name.clone())
+        }
+        SensorBindTarget::Room(id) => {
+            let room = building
+                .floors
+                .iter_mut()
+                .flat_map(|f| f.wings.iter_mut())
+                .flat_map(|w| w.rooms.iter_mut())
+                .find(|r| &r.id == id)
+                .expect("checked above");
+            let mut eq = Equipment::new(
+                sensor_id.to_string(),
+                String::new(),
+                EquipmentType::Other("Sensor".to_string()),
+            );
+            eq.room_id = Some(room.id.clone());
+            eq.sensor_mappings = Some(vec![mapping]);
+            let name = eq.name.clone();
+            room.add_equipment(eq);
+            Ok(name)
+        }
+    }
+}
+
+/// Drop every mapping of `sensor_id`; returns how many were removed.
+pub fn unbind_sensor(building: &mut Building, sensor_id: &str) -> usize {
+    let mut removed = 0;
+    for eq in all_equipment_mut(building) {
+        if let Some(mappings) = eq.sensor_mappings.as_mut() {
+            let before = mappings.len();
+            mappings.retain(|m| m.sensor_id != sensor_id);
+            removed += before - mappings.len();
+            if mappings.is_empty() {
+                eq.sensor_mappings = None;
+            }
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, Room, RoomType, Wing};
+
+    fn sample() -> (Building, String, String) {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Lobby".into(), RoomType::Office);
+        let ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        let (room_id, eq_id) = (room.id.clone(), ahu.id.clone());
+        room.add_equipment(ahu);
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        (b, room_id, eq_id)
+    }
+
+    #[test]
+    fn binds_to_equipment_and_rebinds_to_room() {
+        let (mut b, room_id, eq_id) = sample();
+        let t = parse_threshold_range("18..26").unwrap();
+        let name = bind_sensor(
+            &mut b,
+            "zigbee-42",
+            "temperature",
+            &SensorBindTarget::Equipment(eq_id),
+            Some(t),
+        )
+        .unwrap();
+        assert_eq!(name, "AHU-1");
+        assert!(mapped_sensor_ids(&b).contains("zigbee-42"));
+
+        bind_sensor(
+            &mut b,
+            "zigbee-42",
+            "temperature",
+            &SensorBindTarget::Room(room_id),
+            None,
+        )
+        .unwrap();
+        let room = &b.floors[0].wings[0].rooms[0];
+        assert!(room.equipment[0].sensor_mappings.is_none());
+        assert_eq!(room.equipment[1].name, "zigbee-42");
+        assert_eq!(mapped_sensor_ids(&b).len(), 1);
+
+        assert!(bind_sensor(
+            &mut b,
+            "x",
+            "",
+            &SensorBindTarget::Room("missing".into()),
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parses_threshold_ranges() {
+        let t = parse_threshold_range("..30").unwrap();
+        assert_eq!((t.min, t.max), (None, Some(30.0)));
+        assert!(parse_threshold_range("30..10").is_err());
+        assert!(parse_threshold_range("30").is_err());
+    }
+}
@@ -3,8 +3,10 @@
 //! This module provides spatial query, transformation, and validation operations
 //! for building entities.
 
+use crate::core::spatial::transform::Transform;
+use crate::core::spatial::{BoundingBox3D, Mesh, Point3D};
 use crate::core::types::{Position, SpatialQueryResult};
-use crate::core::Building;
+use crate::core::{Building, Equipment};
 
 /// Result of spatial validation
 #[derive(Debug, Clone)]
@@ -440,6 +442,179 @@ pub fn transform_coordinates(
         from, to, entity_name, pos.x, pos.y, pos.z))
 }
 
+/// Outcome of [`apply_transform`].
+#[derive(Debug, Clone)]
+pub struct TransformReport {
+    /// Display name of the transformed entity
+    pub entity_name: String,
+    /// `room` or `equipment`
+    pub entity_type: String,
+    /// Normalized operations, as applied
+    pub operations: Vec<String>,
+    pub before: Position,
+    pub after: Position,
+    /// Equipment moved along with a room
+    pub equipment_moved: usize,
+}
+
+/// Apply transform `spec` (see [`crate::core::spatial::transform`]) to the
+/// stored geometry of room or equipment `entity`.
+///
+/// Rotation and scale pivot on the entity position. For a room, the bounding
+/// box, dimensions, mesh, `floor_polygon`, and contained equipment move with it;
+/// equipment keeps its position and mesh in step. Every stored copy of moved
+/// equipment (room and floor lists) is updated.
+pub fn apply_transform(
+    building: &mut Building,
+    entity: &str,
+    spec: &str,
+) -> Result<TransformReport, Box<dyn std::error::Error>> {
+    use crate::core::spatial::extrusion::{parse_polygon, FLOOR_POLYGON_PROPERTY};
+    use crate::core::spatial::transform::parse_ops;
+    use std::collections::HashSet;
+
+    let ops = parse_ops(spec)?;
+    let operations: Vec<String> = ops.iter().map(|op| op.to_string()).collect();
+
+    for floor in &mut building.floors {
+        let room = floor
+            .wings
+            .iter_mut()
+            .flat_map(|w| w.rooms.iter_mut())
+            .find(|r| r.name == entity || r.id == entity);
+        if let Some(room) = room {
+            let before = room.spatial_properties.position.clone();
+            let t = Transform::from_ops(&ops, &to_point(&before));
+            let props = &mut room.spatial_properties;
+            props.position = move_position(&t, &props.position);
+            let bbox = &props.bounding_box;
+            let mut corners = Vec::with_capacity(8);
+            for x in [bbox.min.x, bbox.max.x] {
+                for y in [bbox.min.y, bbox.max.y] {
+                    for z in [bbox.min.z, bbox.max.z] {
+                        corners.push(t.apply(&Point3D::new(x, y, z)));
+                    }
+                }
+            }
+            if let Some(aabb) = BoundingBox3D::from_points(&corners) {
+                props.bounding_box.min = with_point(&props.bounding_box.min, &aabb.min);
+                props.bounding_box.max = with_point(&props.bounding_box.max, &aabb.max);
+                props.dimensions.width = aabb.max.x - aabb.min.x;
+                props.dimensions.depth = aabb.max.y - aabb.min.y;
+                props.dimensions.height = aabb.max.z - aabb.min.z;
+            }
+            if let Some(mesh) = props.mesh.as_mut() {
+                move_mesh(&t, mesh);
+            }
+            if let Some(points) = room
+                .properties
+                .get(FLOOR_POLYGON_PROPERTY)
+                .and_then(|s| parse_polygon(s))
+            {
+                let moved: Vec<String> = points
+                    .iter()
+                    .map(|&(x, y)| {
+                        let p = t.apply(&Point3D::new(x, y, before.z));
+                        format!("{},{}", round(p.x), round(p.y))
+                    })
+                    .collect();
+                room.properties
+                    .insert(FLOOR_POLYGON_PROPERTY.to_string(), moved.join(";"));
+            }
+            let ids: HashSet<String> = room.equipment.iter().map(|e| e.id.clone()).collect();
+            for equipment in &mut room.equipment {
+                move_equipment(&t, equipment);
+            }
+            for equipment in floor.equipment.iter_mut().filter(|e| ids.contains(&e.id)) {
+                move_equipment(&t, equipment);
+            }
+            return Ok(TransformReport {
+                entity_name: room.name.clone(),
+                entity_type: "room".to_string(),
+                operations,
+                after: room.spatial_properties.position.clone(),
+                before,
+                equipment_moved: ids.len(),
+            });
+        }
+
+        let found = floor
+            .equipment
+            .iter()
+            .chain(
+                floor
+                    .wings
+                    .iter()
+                    .flat_map(|w| w.rooms.iter())
+                    .flat_map(|r| r.equipment.iter()),
+            )
+            .find(|e| e.name == entity || e.id == entity)
+            .map(|e| (e.id.clone(), e.name.clone(), e.position.clone()));
+        if let Some((id, name, before)) = found {
+            let t = Transform::from_ops(&ops, &to_point(&before));
+            let copies = floor.equipment.iter_mut().chain(
+                floor
+                    .wings
+                    .iter_mut()
+                    .flat_map(|w| w.rooms.iter_mut())
+                    .flat_map(|r| r.equipment.iter_mut()),
+            );
+            for equipment in copies.filter(|e| e.id == id) {
+                move_equipment(&t, equipment);
+            }
+            return Ok(TransformReport {
+                entity_name: name,
+                entity_type: "equipment".to_string(),
+                operations,
+                after: move_position(&t, &before),
+                before,
+                equipment_moved: 0,
+            });
+        }
+    }
+    Err(format!("Entity '{}' not found", entity).into())
+}
+
+fn to_point(p: &Position) -> Point3D {
+    Point3D::new(p.x, p.y, p.z)
+}
+
+fn with_point(p: &Position, point: &Point3D) -> Position {
+    Position {
+        x: round(point.x),
+        y: round(point.y),
+        z: round(point.z),
+        coordinate_system: p.coordinate_system.clone(),
+    }
+}
+
+fn move_position(t: &Transform, p: &Position) -> Position {
+    with_point(p, &t.apply(&to_point(p)))
+}
+
+fn move_mesh(t: &Transform, mesh: &mut Mesh) {
+    for vertex in &mut mesh.vertices {
+        *vertex = t.apply(vertex);
+    }
+}
+
+fn move_equipment(t: &Transform, equipment: &mut Equipment) {
+    equipment.position = move_position(t, &equipment.position);
+    if let Some(mesh) = equipment.mesh.as_mut() {
+        move_mesh(t, mesh);
+    }
+}
+
+/// Trim float noise from rotations (micrometre precision) so YAML stays readable.
+fn round(v: f64) -> f64 {
+    let r = (v * 1e6).round() / 1e6;
+    if r == 0.0 {
+        0.0
+    } else {
+        r
+    }
+}
+
 /// Validate spatial data for entities
 ///
 /// Performs comprehensive spatial validation including:
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::core::operations::spatial::{spatial_query, transform_coordinates};
+    use crate::core::operations::spatial::{apply_transform, spatial_query, transform_coordinates};
     use crate::core::spatial::Point3D;
     use crate::core::types::Position;
     use crate::core::CoordinateSystemInfo;
@@ -112,4 +112,37 @@ mod tests {
         assert!(result.contains("Room A"));
         assert!(result.contains("local_offset"));
     }
+
+    #[test]
+    fn test_apply_transform_moves_room_geometry_and_equipment() {
+        let mut building = create_test_building();
+        building.floors[0].wings[0].rooms[0].properties.insert(
+            "floor_polygon".to_string(),
+            "0,0;10,0;10,10;0,10".to_string(),
+        );
+
+        let report =
+            apply_transform(&mut building, "Room A", "translate(5, 0) rotate(90)").unwrap();
+        assert_eq!(report.entity_type, "room");
+        let room = &building.floors[0].wings[0].rooms[0];
+        let props = &room.spatial_properties;
+        assert_eq!((props.position.x, props.position.y), (5.0, 0.0));
+        assert_eq!(
+            (props.bounding_box.min.x, props.bounding_box.min.y),
+            (-5.0, 0.0)
+        );
+        assert_eq!(
+            (props.bounding_box.max.x, props.bounding_box.max.y),
+            (5.0, 10.0)
+        );
+        assert_eq!(props.dimensions.height, 3.0);
+        assert_eq!(room.properties["floor_polygon"], "5,0;5,10;-5,10;-5,0");
+
+        apply_transform(&mut building, "TestEq", "translate(1, 2)").unwrap();
+        let eq = &building.floors[0].equipment[0];
+        assert_eq!((eq.position.x, eq.position.y), (6.0, 7.0));
+
+        assert!(apply_transform(&mut building, "Nowhere", "scale(2)").is_err());
+        assert!(apply_transform(&mut building, "Room A", "twist(3)").is_err());
+    }
 }
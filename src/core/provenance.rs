@@ -0,0 +1,321 @@
+//! Field-level provenance: where a room or equipment value came from.
+//!
+//! Rooms and equipment carry a `provenance` map from field name (`name`,
+//! `position`, `properties.serial`, …) to [`FieldProvenance`]. Nobody edits it
+//! by hand: every save diffs the building against what is on disk and stamps
+//! each changed key field with the current source — [`ProvenanceSource::Manual`]
+//! unless an importer, sensor sync, or migration installed a
+//! [`ProvenanceGuard`] for the duration of its write. Code that knows better
+//! for a single field (sensor-driven runtime inside a manual save) calls
+//! [`stamp`] directly, and save-time stamping leaves that entry alone.
+//!
+//! The commit is not known while saving; `arx provenance` resolves it from
+//! history as the first commit that stored the entry.
+
+use super::{clock, Building, Equipment, Room};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Room fields whose changes are stamped (plus every `properties.<key>`).
+pub const ROOM_FIELDS: &[&str] = &["name", "room_type", "spatial_properties", "address"];
+
+/// Equipment fields whose changes are stamped (plus every `properties.<key>`).
+pub const EQUIPMENT_FIELDS: &[&str] = &[
+    "name",
+    "equipment_type",
+    "position",
+    "status",
+    "health_status",
+    "room_id",
+    "address",
+    "runtime",
+];
+
+/// Origin of a field value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProvenanceSource {
+    IfcImport,
+    ArScan,
+    Sensor,
+    #[default]
+    Manual,
+    Migration,
+}
+
+impl ProvenanceSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProvenanceSource::IfcImport => "ifc-import",
+            ProvenanceSource::ArScan => "ar-scan",
+            ProvenanceSource::Sensor => "sensor",
+            ProvenanceSource::Manual => "manual",
+            ProvenanceSource::Migration => "migration",
+        }
+    }
+}
+
+impl fmt::Display for ProvenanceSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Who set a field, how, and when.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldProvenance {
+    pub source: ProvenanceSource,
+    pub at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// Commit that recorded the value, when known at write time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// Provenance entries keyed by field name.
+pub type ProvenanceMap = BTreeMap<String, FieldProvenance>;
+
+thread_local! {
+    static CONTEXT: RefCell<Option<(ProvenanceSource, Option<String>)>> = const { RefCell::new(None) };
+}
+
+/// Sets the provenance source (and optionally actor) for saves on the current
+/// thread until dropped; guards nest and restore whatever was set before.
+pub struct ProvenanceGuard {
+    previous: Option<(ProvenanceSource, Option<String>)>,
+}
+
+impl ProvenanceGuard {
+    pub fn source(source: ProvenanceSource) -> Self {
+        Self::install(source, None)
+    }
+
+    pub fn install(source: ProvenanceSource, actor: Option<String>) -> Self {
+        Self {
+            previous: CONTEXT.with(|c| c.replace(Some((source, actor)))),
+        }
+    }
+}
+
+impl Drop for ProvenanceGuard {
+    fn drop(&mut self) {
+        CONTEXT.with(|c| *c.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Source for saves on this thread (manual unless a guard says otherwise).
+pub fn current_source() -> ProvenanceSource {
+    CONTEXT.with(|c| c.borrow().as_ref().map(|(s, _)| *s).unwrap_or_default())
+}
+
+/// Actor installed by the current guard, if any.
+pub fn current_actor() -> Option<String> {
+    CONTEXT.with(|c| c.borrow().as_ref().and_then(|(_, a)| a.clone()))
+}
+
+/// Record `source` for `field` now, regardless of the save-time source.
+pub fn stamp(map: &mut ProvenanceMap, field: &str, source: ProvenanceSource) {
+    map.insert(
+        field.to_string(),
+        FieldProvenance {
+            source,
+            at: clock::now(),
+            actor: current_actor(),
+            commit: None,
+        },
+    );
+}
+
+/// Key field values of an entity: listed fields plus `properties.<key>`.
+fn key_values<T: Serialize>(entity: &T, fields: &[&str]) -> BTreeMap<String, Value> {
+    let Ok(Value::Object(map)) = serde_json::to_value(entity) else {
+        return BTreeMap::new();
+    };
+    let mut values: BTreeMap<String, Value> = fields
+        .iter()
+        .filter_map(|f| {
+            map.get(*f)
+                .filter(|v| !v.is_null())
+                .map(|v| (f.to_string(), v.clone()))
+        })
+        .collect();
+    if let Some(Value::Object(props)) = map.get("properties") {
+        for (key, value) in props {
+            values.insert(format!("properties.{}", key), value.clone());
+        }
+    }
+    values
+}
+
+fn stamp_entity<T: Serialize>(
+    before: Option<(&T, &ProvenanceMap)>,
+    after: &T,
+    provenance: &mut ProvenanceMap,
+    fields: &[&str],
+    entry: &FieldProvenance,
+) -> usize {
+    let new_values = key_values(after, fields);
+    let (old_values, old_provenance) = match before {
+        Some((entity, map)) => (key_values(entity, fields), Some(map)),
+        None => (BTreeMap::new(), None),
+    };
+    // Dropped properties take their provenance with them
+    provenance
+        .retain(|field, _| !field.starts_with("properties.") || new_values.contains_key(field));
+
+    let mut stamped = 0;
+    for (field, value) in &new_values {
+        if old_values.get(field) == Some(value) {
+            // Entities rebuilt by merges keep what the stored copy knew
+            if let Some(kept) = old_provenance.and_then(|m| m.get(field)) {
+                provenance
+                    .entry(field.clone())
+                    .or_insert_with(|| kept.clone());
+            }
+            continue;
+        }
+        let explicit = provenance
+            .get(field)
+            .is_some_and(|p| Some(p) != old_provenance.and_then(|m| m.get(field)));
+        if explicit {
+            continue;
+        }
+        provenance.insert(field.clone(), entry.clone());
+        stamped += 1;
+    }
+    stamped
+}
+
+/// Stamp every key field that differs between `before` (what is stored) and
+/// `after` (what is about to be saved) with the current source, `actor`, and
+/// time; returns how many fields were stamped.
+pub fn stamp_changes(
+    before: Option<&Building>,
+    after: &mut Building,
+    actor: Option<&str>,
+) -> usize {
+    let entry = FieldProvenance {
+        source: current_source(),
+        at: clock::now(),
+        actor: current_actor().or_else(|| actor.map(str::to_string)),
+        commit: None,
+    };
+    let mut old_rooms: HashMap<&str, &Room> = HashMap::new();
+    let mut old_equipment: HashMap<&str, &Equipment> = HashMap::new();
+    if let Some(before) = before {
+        for room in before.get_all_rooms() {
+            old_rooms.entry(room.id.as_str()).or_insert(room);
+        }
+        for eq in before.get_all_equipment() {
+            old_equipment.entry(eq.id.as_str()).or_insert(eq);
+        }
+    }
+
+    let mut stamped = 0;
+    for room in after.get_all_rooms_mut() {
+        let old = old_rooms.get(room.id.as_str()).map(|r| (*r, &r.provenance));
+        let mut provenance = std::mem::take(&mut room.provenance);
+        stamped += stamp_entity(old, &*room, &mut provenance, ROOM_FIELDS, &entry);
+        room.provenance = provenance;
+    }
+    for eq in after.get_all_equipment_mut() {
+        let old = old_equipment
+            .get(eq.id.as_str())
+            .map(|e| (*e, &e.provenance));
+        let mut provenance = std::mem::take(&mut eq.provenance);
+        stamped += stamp_entity(old, &*eq, &mut provenance, EQUIPMENT_FIELDS, &entry);
+        eq.provenance = provenance;
+    }
+    stamped
+}
+
+/// Entry for `field`, accepting `type` for the entity's type field and a bare
+/// property key for `properties.<key>`; returns the canonical field name too.
+pub fn lookup<'a>(map: &'a ProvenanceMap, field: &str) -> Option<(&'a str, &'a FieldProvenance)> {
+    let candidates = [
+        field.to_string(),
+        format!("properties.{}", field),
+        if field == "type" {
+            "equipment_type".to_string()
+        } else {
+            String::new()
+        },
+        if field == "type" {
+            "room_type".to_string()
+        } else {
+            String::new()
+        },
+    ];
+    candidates
+        .iter()
+        .find_map(|c| map.get_key_value(c.as_str()))
+        .map(|(k, v)| (k.as_str(), v))
+}
+
+/// One-line `field=source` listing for detail views.
+pub fn summarize(map: &ProvenanceMap) -> String {
+    map.iter()
+        .map(|(field, p)| format!("{}={}", field, p.source))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentType, Floor, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn test_stamps_changed_fields_with_current_source() {
+        let mut imported = building();
+        {
+            let _guard = ProvenanceGuard::source(ProvenanceSource::IfcImport);
+            assert!(stamp_changes(None, &mut imported, Some("alice")) > 0);
+        }
+        let eq = &imported.get_all_equipment()[0];
+        assert_eq!(eq.provenance["name"].source, ProvenanceSource::IfcImport);
+        assert_eq!(eq.provenance["name"].actor.as_deref(), Some("alice"));
+
+        let mut edited = imported.clone();
+        {
+            let eq = edited.get_all_equipment_mut().remove(0);
+            eq.properties.insert("serial".into(), "SN-42".into());
+            stamp(&mut eq.provenance, "runtime", ProvenanceSource::Sensor);
+            eq.runtime = Some(Default::default());
+        }
+        assert_eq!(stamp_changes(Some(&imported), &mut edited, Some("bob")), 1);
+        let eq = &edited.get_all_equipment()[0];
+        let (field, serial) = lookup(&eq.provenance, "serial").unwrap();
+        assert_eq!(
+            (field, serial.source),
+            ("properties.serial", ProvenanceSource::Manual)
+        );
+        assert_eq!(eq.provenance["runtime"].source, ProvenanceSource::Sensor);
+        assert_eq!(
+            eq.provenance["name"],
+            imported.get_all_equipment()[0].provenance["name"]
+        );
+        assert_eq!(lookup(&eq.provenance, "type").unwrap().0, "equipment_type");
+    }
+}
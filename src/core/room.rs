@@ -1,11 +1,11 @@
 //! Room data structure and implementation
 
+use super::clock;
 use super::{Equipment, SpatialProperties, Anchor};
 use super::domain::ArxAddress;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 /// Represents a room in a building
 ///
@@ -16,6 +16,8 @@ pub struct Room {
     pub id: String,
     /// Human-readable room name
     pub name: String,
+    /// Alternate display names (e.g. official number vs. colloquial name); `id` stays the stable key
+    pub aliases: Vec<String>,
     /// Type categorization of the room
     pub room_type: RoomType,
     /// Collection of equipment physically located in the room.
@@ -41,6 +43,12 @@ pub struct Room {
     pub anchors: Vec<Anchor>,
     /// Temporary list of anchor IDs parsed during deserialization
     pub pending_anchor_ids: Vec<String>,
+    /// Where key field values came from (maintained on save)
+    pub provenance: super::provenance::ProvenanceMap,
+    /// Unmapped vendor IFC property sets, re-emitted on export
+    pub vendor_extensions: super::vendor::VendorExtensions,
+    /// Photos and documents attached to the room
+    pub attachments: Vec<super::Attachment>,
 }
 
 /// DTO for Room serialization to preserve YAML and Git layout
@@ -48,6 +56,8 @@ pub struct Room {
 struct RoomDto {
     id: String,
     name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
     room_type: RoomType,
     equipment: Vec<String>,
     spatial_properties: super::SpatialProperties,
@@ -65,6 +75,12 @@ struct RoomDto {
     address: Option<ArxAddress>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     anchors: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    provenance: super::provenance::ProvenanceMap,
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    vendor_extensions: super::vendor::VendorExtensions,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<super::Attachment>,
 }
 
 // Custom Serialize implementation for Room via RoomDto
@@ -78,6 +94,7 @@ impl serde::Serialize for Room {
         let dto = RoomDto {
             id: self.id.clone(),
             name: self.name.clone(),
+            aliases: self.aliases.clone(),
             room_type: self.room_type.clone(),
             equipment: equipment_ids,
             spatial_properties: self.spatial_properties.clone(),
@@ -88,6 +105,9 @@ impl serde::Serialize for Room {
             ifc_global_id: self.ifc_global_id.clone(),
             address: self.address.clone(),
             anchors: anchor_ids,
+            provenance: self.provenance.clone(),
+            vendor_extensions: self.vendor_extensions.clone(),
+            attachments: self.attachments.clone(),
         };
         dto.serialize(serializer)
     }
@@ -103,6 +123,7 @@ impl<'de> serde::Deserialize<'de> for Room {
         Ok(Room {
             id: dto.id,
             name: dto.name,
+            aliases: dto.aliases,
             room_type: dto.room_type,
             equipment: Vec::new(),
             pending_equipment_ids: dto.equipment,
@@ -115,6 +136,9 @@ impl<'de> serde::Deserialize<'de> for Room {
             address: dto.address,
             anchors: Vec::new(),
             pending_anchor_ids: dto.anchors,
+            provenance: dto.provenance,
+            vendor_extensions: dto.vendor_extensions,
+            attachments: dto.attachments,
         })
     }
 }
@@ -219,10 +243,11 @@ impl Room {
     /// assert_eq!(room.equipment.len(), 0);
     /// ```
     pub fn new(name: String, room_type: RoomType) -> Self {
-        let now = Some(Utc::now());
+        let now = Some(clock::now());
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name,
+            aliases: Vec::new(),
             room_type,
             equipment: Vec::new(),
             pending_equipment_ids: Vec::new(),
@@ -235,6 +260,9 @@ impl Room {
             address: None,
             anchors: Vec::new(),
             pending_anchor_ids: Vec::new(),
+            provenance: Default::default(),
+            vendor_extensions: Default::default(),
+            attachments: Vec::new(),
         }
     }
 
@@ -261,7 +289,29 @@ impl Room {
     /// ```
     pub fn add_equipment(&mut self, equipment: Equipment) {
         self.equipment.push(equipment);
-        self.updated_at = Some(Utc::now());
+        self.updated_at = Some(clock::now());
+    }
+
+    /// Add an alternate display name (ignored when blank or already known)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use arxos::core::{Room, RoomType};
+    /// let mut room = Room::new("R-2.113".to_string(), RoomType::Office);
+    /// room.add_alias("the fishbowl");
+    /// assert!(room.matches_name("The Fishbowl"));
+    /// assert!(room.matches_name("r-2.113"));
+    /// ```
+    pub fn add_alias(&mut self, alias: &str) {
+        if super::alias::push_alias(&self.name, &mut self.aliases, alias) {
+            self.updated_at = Some(clock::now());
+        }
+    }
+
+    /// Case-insensitive match against the room name or any alias
+    pub fn matches_name(&self, query: &str) -> bool {
+        super::alias::matches_name(&self.name, &self.aliases, query)
     }
 
     /// Find equipment by name
@@ -356,6 +406,6 @@ impl Room {
     /// ```
     pub fn update_spatial_properties(&mut self, spatial_properties: SpatialProperties) {
         self.spatial_properties = spatial_properties;
-        self.updated_at = Some(Utc::now());
+        self.updated_at = Some(clock::now());
     }
 }
@@ -0,0 +1,381 @@
+//! Equipment run hours and runtime-based maintenance.
+//!
+//! Each piece of equipment can carry a [`RuntimeCounter`] that accumulates
+//! time spent running. Two kinds of event move it:
+//!
+//! - status transitions: `Active` counts as running, every other status as
+//!   stopped ([`set_status`]);
+//! - on/off signals from mapped sensors whose type is one of
+//!   [`RUN_SIGNAL_TYPES`]; a value above [`RUN_SIGNAL_THRESHOLD`] means running
+//!   ([`apply_run_signals`]).
+//!
+//! Events older than the last one applied are ignored, so replaying the same
+//! readings log is idempotent. Tracking starts with the first event; time
+//! before that is unknown and not counted.
+//!
+//! Maintenance schedules in `.arx/maintenance.yaml` trigger on run hours
+//! since the last service instead of calendar time; inspections in the same
+//! file recur on the calendar:
+//!
+//! ```yaml
+//! schedules:
+//!   - name: filter-change
+//!     match: { equipment_type: HVAC }
+//!     every_runtime_hours: 500
+//!     assignee: hvac-team
+//! inspections:
+//!   - name: fire-extinguisher-check
+//!     match: { equipment_type: Safety }
+//!     every_days: 30
+//!     start: 2024-01-05
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Building, Equipment, EquipmentStatus};
+use crate::validation::EquipmentSelector;
+
+/// Per-repo maintenance schedules.
+pub const MAINTENANCE_FILE: &str = ".arx/maintenance.yaml";
+
+/// Sensor types treated as on/off run signals.
+pub const RUN_SIGNAL_TYPES: &[&str] = &["run_status", "on_off"];
+
+/// Signal values above this mean the equipment is running.
+pub const RUN_SIGNAL_THRESHOLD: f64 = 0.5;
+
+/// Accumulated run time of one piece of equipment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeCounter {
+    /// Hours in closed run intervals
+    pub hours: f64,
+    /// First event applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracking_since: Option<DateTime<Utc>>,
+    /// Start of the current run, if running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub running_since: Option<DateTime<Utc>>,
+    /// Time of the latest event applied; older events are ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event_at: Option<DateTime<Utc>>,
+    /// Run hours at the last service, by schedule name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub serviced_at_hours: BTreeMap<String, f64>,
+}
+
+impl RuntimeCounter {
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Run hours up to `now`, including the open run.
+    pub fn hours_at(&self, now: DateTime<Utc>) -> f64 {
+        self.hours
+            + self
+                .running_since
+                .map_or(0.0, |since| hours_between(since, now))
+    }
+
+    /// Apply an on/off event; returns whether it was applied (not stale).
+    pub fn record(&mut self, running: bool, at: DateTime<Utc>) -> bool {
+        if self.last_event_at.is_some_and(|last| at < last) {
+            return false;
+        }
+        match (self.running_since, running) {
+            (None, true) => self.running_since = Some(at),
+            (Some(since), false) => {
+                self.hours += hours_between(since, at);
+                self.running_since = None;
+            }
+            _ => {}
+        }
+        self.last_event_at = Some(at);
+        self.tracking_since.get_or_insert(at);
+        true
+    }
+
+    /// Average run hours per day since tracking started (`None` before a
+    /// full hour of history).
+    pub fn daily_rate(&self, now: DateTime<Utc>) -> Option<f64> {
+        let tracked = hours_between(self.tracking_since?, now);
+        (tracked >= 1.0).then(|| self.hours_at(now) / tracked * 24.0)
+    }
+}
+
+fn hours_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    ((to - from).num_seconds().max(0) as f64) / 3600.0
+}
+
+/// Whether a status counts as running.
+pub fn status_runs(status: EquipmentStatus) -> bool {
+    status == EquipmentStatus::Active
+}
+
+/// Change the status and record the transition in the runtime counter.
+pub fn set_status(eq: &mut Equipment, status: EquipmentStatus, at: DateTime<Utc>) {
+    if eq.status != status || eq.runtime.is_none() {
+        eq.runtime
+            .get_or_insert_with(RuntimeCounter::default)
+            .record(status_runs(status), at);
+    }
+    eq.status = status;
+}
+
+/// Sensor ids mapped to `eq` that carry run signals.
+pub fn run_signal_sensors(eq: &Equipment) -> Vec<&str> {
+    eq.sensor_mappings
+        .iter()
+        .flatten()
+        .filter(|m| {
+            RUN_SIGNAL_TYPES
+                .iter()
+                .any(|t| m.sensor_type.eq_ignore_ascii_case(t))
+        })
+        .map(|m| m.sensor_id.as_str())
+        .collect()
+}
+
+/// Apply `(at, value)` run signals in time order; returns how many were new.
+pub fn apply_run_signals(
+    eq: &mut Equipment,
+    signals: impl IntoIterator<Item = (DateTime<Utc>, f64)>,
+) -> usize {
+    let mut signals: Vec<_> = signals.into_iter().collect();
+    signals.sort_by_key(|(at, _)| *at);
+    let counter = eq.runtime.get_or_insert_with(RuntimeCounter::default);
+    let last = counter.last_event_at;
+    signals
+        .into_iter()
+        .filter(|(at, _)| last.is_none_or(|last| *at > last))
+        .filter(|(at, value)| counter.record(*value > RUN_SIGNAL_THRESHOLD, *at))
+        .count()
+}
+
+/// A maintenance task that falls due every `every_runtime_hours` of running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceSchedule {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "match", default)]
+    pub selector: EquipmentSelector,
+    pub every_runtime_hours: f64,
+    /// Who does the work (calendar feeds filter on it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+}
+
+/// A calendar inspection round every `every_days`, starting on `start`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionSchedule {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "match", default)]
+    pub selector: EquipmentSelector,
+    pub every_days: u32,
+    pub start: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+}
+
+/// Contents of [`MAINTENANCE_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceSchedules {
+    #[serde(default)]
+    pub schedules: Vec<MaintenanceSchedule>,
+    #[serde(default)]
+    pub inspections: Vec<InspectionSchedule>,
+}
+
+impl MaintenanceSchedules {
+    /// Load `{base}/.arx/maintenance.yaml`; an absent file yields no schedules.
+    pub fn load_at(base: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = base.join(MAINTENANCE_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let schedules: Self = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        if let Some(bad) = schedules
+            .schedules
+            .iter()
+            .find(|s| s.every_runtime_hours <= 0.0)
+        {
+            return Err(format!(
+                "Invalid {}: schedule '{}' needs every_runtime_hours > 0",
+                path.display(),
+                bad.name
+            )
+            .into());
+        }
+        if let Some(bad) = schedules.inspections.iter().find(|s| s.every_days == 0) {
+            return Err(format!(
+                "Invalid {}: inspection '{}' needs every_days > 0",
+                path.display(),
+                bad.name
+            )
+            .into());
+        }
+        Ok(schedules)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MaintenanceSchedule> {
+        self.schedules.iter().find(|s| s.name == name)
+    }
+}
+
+/// Where one piece of equipment stands against one schedule.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceStatus {
+    pub schedule: String,
+    pub equipment_id: String,
+    pub equipment: String,
+    pub runtime_hours: f64,
+    pub since_service_hours: f64,
+    pub every_runtime_hours: f64,
+}
+
+impl MaintenanceStatus {
+    /// Run hours until due; negative when overdue.
+    pub fn remaining_hours(&self) -> f64 {
+        self.every_runtime_hours - self.since_service_hours
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.remaining_hours() <= 0.0
+    }
+
+    /// Projected due time at the equipment's average daily run rate; `now`
+    /// when already due, `None` when it has not run long enough to tell.
+    pub fn forecast_due(
+        &self,
+        counter: &RuntimeCounter,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if self.is_due() {
+            return Some(now);
+        }
+        let rate = counter.daily_rate(now).filter(|r| *r > 0.0)?;
+        let days = self.remaining_hours() / rate;
+        Some(now + Duration::seconds((days * 86_400.0) as i64))
+    }
+}
+
+/// Schedule status for every tracked equipment a schedule matches, most
+/// overdue first.
+pub fn maintenance_status(
+    building: &Building,
+    schedules: &MaintenanceSchedules,
+    now: DateTime<Utc>,
+) -> Vec<MaintenanceStatus> {
+    let mut out = Vec::new();
+    for eq in building.get_all_equipment() {
+        let Some(counter) = &eq.runtime else {
+            continue;
+        };
+        let runtime_hours = counter.hours_at(now);
+        for schedule in schedules
+            .schedules
+            .iter()
+            .filter(|s| s.selector.matches(eq))
+        {
+            let serviced = counter
+                .serviced_at_hours
+                .get(&schedule.name)
+                .copied()
+                .unwrap_or(0.0);
+            out.push(MaintenanceStatus {
+                schedule: schedule.name.clone(),
+                equipment_id: eq.id.clone(),
+                equipment: eq.name.clone(),
+                runtime_hours,
+                since_service_hours: (runtime_hours - serviced).max(0.0),
+                every_runtime_hours: schedule.every_runtime_hours,
+            });
+        }
+    }
+    out.sort_by(|a, b| a.remaining_hours().total_cmp(&b.remaining_hours()));
+    out
+}
+
+/// Reset `schedule` on `eq` to the current run hours.
+pub fn record_service(eq: &mut Equipment, schedule: &str, now: DateTime<Utc>) {
+    let counter = eq.runtime.get_or_insert_with(RuntimeCounter::default);
+    let hours = counter.hours_at(now);
+    counter
+        .serviced_at_hours
+        .insert(schedule.to_string(), hours);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::equipment::SensorMapping;
+    use crate::core::{EquipmentType, Floor};
+    use chrono::{Duration, TimeZone};
+    use std::collections::HashMap;
+
+    fn t(hour: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::hours(hour)
+    }
+
+    #[test]
+    fn test_transitions_and_signals_accumulate_hours() {
+        let mut eq = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        eq.status = EquipmentStatus::Inactive;
+        eq.sensor_mappings = Some(vec![SensorMapping {
+            sensor_id: "ahu-1-run".into(),
+            sensor_type: "run_status".into(),
+            thresholds: HashMap::new(),
+        }]);
+        assert_eq!(run_signal_sensors(&eq), vec!["ahu-1-run"]);
+
+        set_status(&mut eq, EquipmentStatus::Active, t(0));
+        set_status(&mut eq, EquipmentStatus::Maintenance, t(10));
+        let signals = [(t(20), 1.0), (t(12), 1.0), (t(25), 0.0), (t(5), 0.0)];
+        // t(5) predates the last transition and is ignored
+        assert_eq!(apply_run_signals(&mut eq, signals), 3);
+        assert_eq!(apply_run_signals(&mut eq, signals), 0);
+
+        let counter = eq.runtime.as_ref().unwrap();
+        assert!(!counter.is_running());
+        assert_eq!(counter.hours_at(t(100)), 23.0);
+
+        set_status(&mut eq, EquipmentStatus::Active, t(30));
+        assert_eq!(eq.runtime.as_ref().unwrap().hours_at(t(32)), 25.0);
+    }
+
+    #[test]
+    fn test_schedules_trigger_on_runtime_since_service() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        set_status(&mut ahu, EquipmentStatus::Active, t(0));
+        let mut pump = Equipment::new("P-1".into(), String::new(), EquipmentType::Plumbing);
+        set_status(&mut pump, EquipmentStatus::Active, t(0));
+        floor.equipment.extend([ahu, pump]);
+        building.add_floor(floor);
+
+        let schedules: MaintenanceSchedules = serde_yaml::from_str(
+            "schedules:\n  - name: filter-change\n    match: { equipment_type: HVAC }\n    every_runtime_hours: 500\n",
+        )
+        .unwrap();
+        let status = maintenance_status(&building, &schedules, t(600));
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].equipment, "AHU-1");
+        assert!(status[0].is_due());
+        assert_eq!(status[0].remaining_hours(), -100.0);
+
+        let ahu = building.find_equipment_mut("AHU-1").unwrap();
+        record_service(ahu, "filter-change", t(600));
+        let status = maintenance_status(&building, &schedules, t(700));
+        assert_eq!(status[0].since_service_hours, 100.0);
+        assert!(!status[0].is_due());
+    }
+}
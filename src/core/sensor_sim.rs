@@ -0,0 +1,350 @@
+//! Synthetic sensor streams for development and demos.
+//!
+//! A [`SimulationProfile`] describes a set of sensors and the pattern each
+//! follows; [`SensorSimulator`] samples them at given instants, adding noise
+//! and occasional faults. The caller feeds the readings through normal sensor
+//! ingestion (`arx sensor simulate`), so dashboards and threshold alerts see
+//! them like hardware readings.
+//!
+//! ```yaml
+//! interval_secs: 300
+//! seed: 7
+//! sensors:
+//!   - id: temp-101
+//!     type: temperature
+//!     pattern: { kind: daily, base: 21.5, amplitude: 2.0, peak_hour: 15 }
+//!     noise: 0.2
+//!     fault_rate: 0.01
+//!   - id: occ-101
+//!     type: occupancy
+//!     pattern: { kind: occupancy, peak: 24, start_hour: 8, end_hour: 18 }
+//! ```
+
+use std::f64::consts::PI;
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Samples a stuck sensor repeats its last value for.
+const STUCK_SAMPLES: u32 = 5;
+
+/// Sensors to simulate and how often to sample them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationProfile {
+    /// Seconds between samples
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Fixed seed for reproducible streams; random when unset
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub sensors: Vec<SimulatedSensor>,
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+/// One simulated sensor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedSensor {
+    pub id: String,
+    /// Sensor type recorded with each reading (temperature, occupancy, ...)
+    #[serde(rename = "type")]
+    pub sensor_type: String,
+    pub pattern: Pattern,
+    /// Standard deviation of Gaussian-ish noise added to each sample
+    #[serde(default)]
+    pub noise: f64,
+    /// Chance per sample (0–1) that a fault starts
+    #[serde(default)]
+    pub fault_rate: f64,
+    /// Decimal places kept (whole numbers for occupancy)
+    #[serde(default)]
+    pub precision: Option<u32>,
+}
+
+/// Shape of a sensor's signal over the day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Pattern {
+    /// Cosine over 24 h peaking at `peak_hour` (UTC)
+    Daily {
+        base: f64,
+        amplitude: f64,
+        #[serde(default = "default_peak_hour")]
+        peak_hour: f64,
+    },
+    /// Zero outside working hours, rising to `peak` mid-day on weekdays
+    Occupancy {
+        peak: f64,
+        #[serde(default = "default_start_hour")]
+        start_hour: u32,
+        #[serde(default = "default_end_hour")]
+        end_hour: u32,
+        #[serde(default)]
+        weekends: bool,
+    },
+    Constant {
+        value: f64,
+    },
+}
+
+fn default_peak_hour() -> f64 {
+    15.0
+}
+
+fn default_start_hour() -> u32 {
+    8
+}
+
+fn default_end_hour() -> u32 {
+    18
+}
+
+/// Fault injected into a sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fault {
+    /// Implausible jump (3× the clean value plus an offset)
+    Spike,
+    /// Reported with no value
+    Dropout,
+    /// Repeats the last value for a few samples
+    Stuck,
+}
+
+/// One simulated reading.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimulatedReading {
+    pub sensor_id: String,
+    pub sensor_type: String,
+    pub at: DateTime<Utc>,
+    /// `None` for a dropout
+    pub value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fault: Option<Fault>,
+}
+
+impl SimulationProfile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let profile: Self = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        profile
+            .validate()
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        Ok(profile)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_secs == 0 {
+            return Err("interval_secs must be positive".to_string());
+        }
+        if self.sensors.is_empty() {
+            return Err("no sensors".to_string());
+        }
+        for sensor in &self.sensors {
+            if sensor.id.trim().is_empty() {
+                return Err("sensor without an id".to_string());
+            }
+            if !(0.0..=1.0).contains(&sensor.fault_rate) {
+                return Err(format!("{}: fault_rate must be within 0-1", sensor.id));
+            }
+            if sensor.noise < 0.0 {
+                return Err(format!("{}: noise must not be negative", sensor.id));
+            }
+            if let Pattern::Occupancy {
+                start_hour,
+                end_hour,
+                ..
+            } = sensor.pattern
+            {
+                if start_hour >= end_hour || end_hour > 24 {
+                    return Err(format!(
+                        "{}: working hours must satisfy start < end <= 24",
+                        sensor.id
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pattern {
+    /// Noise-free value at `at`.
+    pub fn value_at(&self, at: DateTime<Utc>) -> f64 {
+        let hour = at.hour() as f64 + at.minute() as f64 / 60.0;
+        match *self {
+            Pattern::Daily {
+                base,
+                amplitude,
+                peak_hour,
+            } => base + amplitude * (2.0 * PI * (hour - peak_hour) / 24.0).cos(),
+            Pattern::Occupancy {
+                peak,
+                start_hour,
+                end_hour,
+                weekends,
+            } => {
+                let weekend = matches!(at.weekday(), Weekday::Sat | Weekday::Sun);
+                let (start, end) = (start_hour as f64, end_hour as f64);
+                if (weekend && !weekends) || hour < start || hour >= end {
+                    0.0
+                } else {
+                    peak * (PI * (hour - start) / (end - start)).sin()
+                }
+            }
+            Pattern::Constant { value } => value,
+        }
+    }
+}
+
+/// Samples a profile's sensors over time.
+pub struct SensorSimulator {
+    profile: SimulationProfile,
+    rng: StdRng,
+    /// Per sensor: last reported value and remaining stuck samples
+    state: Vec<(Option<f64>, u32)>,
+}
+
+impl SensorSimulator {
+    pub fn new(profile: SimulationProfile) -> Self {
+        let rng = match profile.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let state = vec![(None, 0); profile.sensors.len()];
+        Self {
+            profile,
+            rng,
+            state,
+        }
+    }
+
+    pub fn profile(&self) -> &SimulationProfile {
+        &self.profile
+    }
+
+    /// One reading per sensor at `at`.
+    pub fn sample(&mut self, at: DateTime<Utc>) -> Vec<SimulatedReading> {
+        let mut readings = Vec::with_capacity(self.profile.sensors.len());
+        for (sensor, (last, stuck)) in self.profile.sensors.iter().zip(self.state.iter_mut()) {
+            let clean = sensor.pattern.value_at(at);
+            // Sum of uniforms: cheap, roughly normal, bounded
+            let noise = (0..3).map(|_| self.rng.gen_range(-1.0..1.0)).sum::<f64>() * sensor.noise;
+            let mut value = Some(clean + noise);
+            let mut fault = None;
+
+            if *stuck > 0 && last.is_some() {
+                *stuck -= 1;
+                value = *last;
+                fault = Some(Fault::Stuck);
+            } else if sensor.fault_rate > 0.0 && self.rng.gen_bool(sensor.fault_rate) {
+                let kind = match self.rng.gen_range(0..3) {
+                    0 => Fault::Spike,
+                    1 => Fault::Dropout,
+                    _ => Fault::Stuck,
+                };
+                match kind {
+                    Fault::Spike => value = Some(clean * 3.0 + 10.0),
+                    Fault::Dropout => value = None,
+                    Fault::Stuck => {
+                        *stuck = STUCK_SAMPLES - 1;
+                        value = last.or(value);
+                    }
+                }
+                fault = Some(kind);
+            }
+
+            let value = value.map(|v| {
+                let v = if matches!(sensor.pattern, Pattern::Occupancy { .. }) {
+                    v.max(0.0)
+                } else {
+                    v
+                };
+                let scale = 10f64.powi(sensor.precision.unwrap_or(match sensor.pattern {
+                    Pattern::Occupancy { .. } => 0,
+                    _ => 1,
+                }) as i32);
+                (v * scale).round() / scale
+            });
+            if value.is_some() {
+                *last = value;
+            }
+            readings.push(SimulatedReading {
+                sensor_id: sensor.id.clone(),
+                sensor_type: sensor.sensor_type.clone(),
+                at,
+                value,
+                fault,
+            });
+        }
+        readings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn profile(fault_rate: f64) -> SimulationProfile {
+        serde_yaml::from_str(&format!(
+            r#"
+seed: 3
+sensors:
+  - id: temp-1
+    type: temperature
+    pattern: {{ kind: daily, base: 21.0, amplitude: 2.0, peak_hour: 15 }}
+    fault_rate: {}
+  - id: occ-1
+    type: occupancy
+    pattern: {{ kind: occupancy, peak: 20 }}
+"#,
+            fault_rate
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_patterns_follow_the_day_and_seed_is_reproducible() {
+        let tuesday = |h| Utc.with_ymd_and_hms(2024, 3, 5, h, 0, 0).unwrap();
+        let p = profile(0.0);
+        assert!(p.validate().is_ok());
+        assert_eq!(p.interval_secs, 60);
+
+        let mut sim = SensorSimulator::new(p.clone());
+        let afternoon = sim.sample(tuesday(15));
+        assert_eq!(afternoon[0].value, Some(23.0));
+        assert_eq!(afternoon[1].value, Some(16.0));
+        let night = sim.sample(tuesday(3));
+        assert_eq!(night[0].value, Some(19.0));
+        assert_eq!(night[1].value, Some(0.0));
+        let saturday = Utc.with_ymd_and_hms(2024, 3, 9, 13, 0, 0).unwrap();
+        assert_eq!(sim.sample(saturday)[1].value, Some(0.0));
+
+        let run = || {
+            let mut sim = SensorSimulator::new(profile(0.3));
+            (0..50)
+                .flat_map(|h| sim.sample(tuesday(h % 24)))
+                .collect::<Vec<_>>()
+        };
+        let (a, b) = (run(), run());
+        assert_eq!(a, b);
+        assert!(a.iter().any(|r| r.fault.is_some()));
+        assert!(a
+            .iter()
+            .any(|r| r.fault == Some(Fault::Dropout) && r.value.is_none()));
+
+        let mut bad = profile(1.5);
+        assert!(bad.validate().is_err());
+        bad.sensors[0].fault_rate = 0.0;
+        bad.interval_secs = 0;
+        assert!(bad.validate().is_err());
+    }
+}
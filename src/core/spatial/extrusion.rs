@@ -0,0 +1,523 @@
+//! Derived 2.5D geometry for rooms without a 3D mesh.
+//!
+//! Most imported buildings only carry 2D room outlines (the IFC space
+//! `floor_polygon` property, or just a plan bounding box). [`room_meshes`]
+//! extrudes each outline between the floor elevation and the room / storey
+//! height, producing floor, ceiling, and wall triangles. Rooms that already
+//! have a mesh keep it; derived meshes are flagged so viewers can style them.
+//! Equipment without a mesh gets a small marker cube at its position, and
+//! each floor a thin slab spanning its rooms' outlines.
+
+use super::layout::{floor_footprints, FootprintSource, RoomFootprint};
+use super::mesh::Mesh;
+use super::types::Point3D;
+use crate::core::{Building, Equipment, Floor, Room};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Storey height (m) when neither the room nor the floors give one.
+pub const DEFAULT_STOREY_HEIGHT: f64 = 3.0;
+
+/// Room property holding the plan outline as `x,y;x,y;...` (set by IFC import).
+pub const FLOOR_POLYGON_PROPERTY: &str = "floor_polygon";
+
+/// Floor property overriding the storey height (m).
+pub const FLOOR_HEIGHT_PROPERTY: &str = "height";
+
+/// Edge length (m) of the marker cube for equipment without a mesh.
+pub const EQUIPMENT_MARKER_SIZE: f64 = 0.5;
+
+/// Thickness (m) of the slab drawn below each floor's rooms.
+pub const FLOOR_SLAB_THICKNESS: f64 = 0.2;
+
+/// Points closer than this (m) are merged when cleaning an outline.
+const EPSILON: f64 = 1e-9;
+
+/// Mesh for one room, explicit or derived.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomMesh {
+    pub room_id: String,
+    pub room_name: String,
+    /// Room type as displayed (material lookup key)
+    pub room_type: String,
+    pub floor_level: i32,
+    /// True when extruded from a 2D outline rather than taken from the model
+    pub derived: bool,
+    /// True when the outline itself was auto-laid out (no stored geometry)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub approximate: bool,
+    pub mesh: Mesh,
+}
+
+/// Mesh for one piece of equipment, explicit or a position marker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquipmentMesh {
+    pub equipment_id: String,
+    pub equipment_name: String,
+    /// Equipment type as displayed (material lookup key)
+    pub equipment_type: String,
+    pub floor_level: i32,
+    /// True for a marker cube rather than model geometry
+    pub derived: bool,
+    pub mesh: Mesh,
+    /// Operational status as displayed
+    #[serde(default)]
+    pub status: String,
+    /// Room the equipment sits in, when known
+    #[serde(default)]
+    pub room_id: Option<String>,
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+}
+
+/// One storey and the slab under its rooms.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloorMesh {
+    pub floor_id: String,
+    pub floor_name: String,
+    pub level: i32,
+    pub elevation: f64,
+    /// Box over the rooms' plan extent; `None` when no room has an outline
+    pub slab: Option<Mesh>,
+}
+
+/// Everything exporters draw for a building.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuildingMeshes {
+    #[serde(default)]
+    pub floors: Vec<FloorMesh>,
+    pub rooms: Vec<RoomMesh>,
+    pub equipment: Vec<EquipmentMesh>,
+}
+
+/// Plan outline of `room`: the `floor_polygon` property, else its bounding box.
+pub fn room_footprint(room: &Room) -> Option<Vec<(f64, f64)>> {
+    if let Some(points) = room
+        .properties
+        .get(FLOOR_POLYGON_PROPERTY)
+        .and_then(|s| parse_polygon(s))
+    {
+        return Some(points);
+    }
+    let bbox = &room.spatial_properties.bounding_box;
+    let (w, d) = (bbox.max.x - bbox.min.x, bbox.max.y - bbox.min.y);
+    (w > EPSILON && d > EPSILON).then(|| {
+        vec![
+            (bbox.min.x, bbox.min.y),
+            (bbox.max.x, bbox.min.y),
+            (bbox.max.x, bbox.max.y),
+            (bbox.min.x, bbox.max.y),
+        ]
+    })
+}
+
+/// Parse `x,y;x,y;...` into a cleaned outline (closing and duplicate points dropped).
+pub fn parse_polygon(s: &str) -> Option<Vec<(f64, f64)>> {
+    let mut points: Vec<(f64, f64)> = Vec::new();
+    for pair in s.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (x, y) = pair.split_once(',')?;
+        let point = (x.trim().parse().ok()?, y.trim().parse().ok()?);
+        if points.last().is_none_or(|last| !same_point(*last, point)) {
+            points.push(point);
+        }
+    }
+    if points.len() > 1 && same_point(points[0], points[points.len() - 1]) {
+        points.pop();
+    }
+    (points.len() >= 3 && signed_area(&points).abs() > EPSILON).then_some(points)
+}
+
+fn same_point(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() < EPSILON && (a.1 - b.1).abs() < EPSILON
+}
+
+/// Shoelace area; positive for counter-clockwise outlines.
+fn signed_area(points: &[(f64, f64)]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn inside_triangle(p: (f64, f64), a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> bool {
+    cross(a, b, p) >= -EPSILON && cross(b, c, p) >= -EPSILON && cross(c, a, p) >= -EPSILON
+}
+
+/// Ear-clipping triangulation of a counter-clockwise simple polygon.
+fn triangulate(points: &[(f64, f64)]) -> Vec<[u32; 3]> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (ia, ib, ic) = (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            );
+            let (a, b, c) = (points[ia], points[ib], points[ic]);
+            cross(a, b, c) > EPSILON
+                && remaining
+                    .iter()
+                    .filter(|&&j| j != ia && j != ib && j != ic)
+                    .all(|&j| !inside_triangle(points[j], a, b, c))
+        });
+        // Degenerate or self-intersecting outline: fall back to a fan over what is left
+        let Some(i) = ear else { break };
+        triangles.push([
+            remaining[(i + n - 1) % n] as u32,
+            remaining[i] as u32,
+            remaining[(i + 1) % n] as u32,
+        ]);
+        remaining.remove(i);
+    }
+    for k in 1..remaining.len().saturating_sub(1) {
+        triangles.push([
+            remaining[0] as u32,
+            remaining[k] as u32,
+            remaining[k + 1] as u32,
+        ]);
+    }
+    triangles
+}
+
+/// Closed prism over `footprint` from `base` to `base + height`.
+///
+/// Vertices `0..n` are the floor ring, `n..2n` the ceiling ring; the floor faces
+/// down, the ceiling up, and walls outward.
+pub fn extrude_footprint(footprint: &[(f64, f64)], base: f64, height: f64) -> Mesh {
+    let mut ring = footprint.to_vec();
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+    let n = ring.len() as u32;
+    let top = base + height;
+    let vertices: Vec<Point3D> = ring
+        .iter()
+        .map(|&(x, y)| Point3D::new(x, y, base))
+        .chain(ring.iter().map(|&(x, y)| Point3D::new(x, y, top)))
+        .collect();
+
+    let mut indices = Vec::new();
+    for [a, b, c] in triangulate(&ring) {
+        indices.extend([a, c, b]);
+        indices.extend([a + n, b + n, c + n]);
+    }
+    for i in 0..n {
+        let j = (i + 1) % n;
+        indices.extend([i, j, j + n, i, j + n, i + n]);
+    }
+    Mesh::new(vertices, indices)
+}
+
+/// Base elevation of `floor` (explicit, else `level × default storey height`).
+fn floor_elevation(floor: &Floor) -> f64 {
+    floor
+        .elevation
+        .unwrap_or(floor.level as f64 * DEFAULT_STOREY_HEIGHT)
+}
+
+/// Storey height of `floor`: its `height` property, else the gap to the next floor up.
+fn storey_height(building: &Building, floor: &Floor) -> f64 {
+    if let Some(h) = floor
+        .properties
+        .get(FLOOR_HEIGHT_PROPERTY)
+        .and_then(|h| h.parse::<f64>().ok())
+        .filter(|h| *h > 0.0)
+    {
+        return h;
+    }
+    let base = floor_elevation(floor);
+    building
+        .floors
+        .iter()
+        .map(floor_elevation)
+        .filter(|e| *e > base + EPSILON)
+        .fold(None, |min: Option<f64>, e| {
+            Some(min.map_or(e, |m| m.min(e)))
+        })
+        .map(|next| next - base)
+        .unwrap_or(DEFAULT_STOREY_HEIGHT)
+}
+
+/// Extruded mesh for a room lacking 3D geometry, if it has an outline.
+pub fn derive_room_mesh(room: &Room, base: f64, storey_height: f64) -> Option<Mesh> {
+    let footprint = room_footprint(room)?;
+    Some(extrude_room(room, &footprint, base, storey_height))
+}
+
+fn extrude_room(room: &Room, footprint: &[(f64, f64)], base: f64, storey_height: f64) -> Mesh {
+    let height = Some(room.spatial_properties.dimensions.height)
+        .filter(|h| *h > EPSILON)
+        .unwrap_or(storey_height);
+    extrude_footprint(footprint, base, height)
+}
+
+/// Meshes for every room: explicit meshes as-is, derived extrusions otherwise.
+///
+/// Rooms without geometry are extruded from their auto-layout rectangle
+/// ([`floor_footprints`]).
+pub fn room_meshes(building: &Building) -> Vec<RoomMesh> {
+    let mut out = Vec::new();
+    for floor in &building.floors {
+        let base = floor_elevation(floor);
+        let height = storey_height(building, floor);
+        let footprints: HashMap<&str, RoomFootprint> = floor_footprints(floor)
+            .into_iter()
+            .map(|f| (f.room.id.as_str(), f))
+            .collect();
+        for room in floor.wings.iter().flat_map(|w| w.rooms.iter()) {
+            let footprint = footprints.get(room.id.as_str());
+            let (mesh, derived) = match &room.spatial_properties.mesh {
+                Some(mesh) if mesh.validate() && !mesh.indices.is_empty() => (mesh.clone(), false),
+                _ => match footprint {
+                    Some(f) => (extrude_room(room, &f.outline, base, height), true),
+                    None => continue,
+                },
+            };
+            out.push(RoomMesh {
+                room_id: room.id.clone(),
+                room_name: room.name.clone(),
+                room_type: room.room_type.to_string(),
+                floor_level: floor.level,
+                derived,
+                approximate: derived
+                    && footprint.is_some_and(|f| f.source == FootprintSource::Layout),
+                mesh,
+            });
+        }
+    }
+    out
+}
+
+/// Marker cube centred on `equipment`'s plan position, resting on its elevation.
+///
+/// Equipment still at the origin is treated as unplaced and gets no marker.
+fn equipment_marker(equipment: &Equipment) -> Option<Mesh> {
+    let p = &equipment.position;
+    if p.x.abs() < EPSILON && p.y.abs() < EPSILON && p.z.abs() < EPSILON {
+        return None;
+    }
+    let h = EQUIPMENT_MARKER_SIZE / 2.0;
+    let square = [
+        (p.x - h, p.y - h),
+        (p.x + h, p.y - h),
+        (p.x + h, p.y + h),
+        (p.x - h, p.y + h),
+    ];
+    Some(extrude_footprint(&square, p.z, EQUIPMENT_MARKER_SIZE))
+}
+
+/// Meshes for every placed piece of equipment (floor, wing, and room lists;
+/// duplicates by id are drawn once).
+pub fn equipment_meshes(building: &Building) -> Vec<EquipmentMesh> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for floor in &building.floors {
+        let rooms_by_equipment: HashMap<&str, &str> = floor
+            .wings
+            .iter()
+            .flat_map(|w| w.rooms.iter())
+            .flat_map(|r| r.equipment.iter().map(|e| (e.id.as_str(), r.id.as_str())))
+            .collect();
+        let all = floor.equipment.iter().chain(floor.wings.iter().flat_map(|w| {
+            w.equipment
+                .iter()
+                .chain(w.rooms.iter().flat_map(|r| r.equipment.iter()))
+        }));
+        for equipment in all {
+            if !seen.insert(equipment.id.as_str()) {
+                continue;
+            }
+            let (mesh, derived) = match &equipment.mesh {
+                Some(mesh) if mesh.validate() && !mesh.indices.is_empty() => (mesh.clone(), false),
+                _ => match equipment_marker(equipment) {
+                    Some(mesh) => (mesh, true),
+                    None => continue,
+                },
+            };
+            out.push(EquipmentMesh {
+                equipment_id: equipment.id.clone(),
+                equipment_name: equipment.name.clone(),
+                equipment_type: equipment.equipment_type.to_string(),
+                floor_level: floor.level,
+                derived,
+                mesh,
+                status: equipment.status.to_string(),
+                room_id: equipment.room_id.clone().or_else(|| {
+                    rooms_by_equipment
+                        .get(equipment.id.as_str())
+                        .map(|id| id.to_string())
+                }),
+                properties: equipment
+                    .properties
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            });
+        }
+    }
+    out
+}
+
+/// Every floor with a slab spanning its rooms' outlines, just below the floor elevation.
+pub fn floor_meshes(building: &Building) -> Vec<FloorMesh> {
+    building
+        .floors
+        .iter()
+        .map(|floor| {
+            let points: Vec<(f64, f64)> = floor_footprints(floor)
+                .into_iter()
+                .flat_map(|f| f.outline)
+                .collect();
+            let elevation = floor_elevation(floor);
+            let slab = (!points.is_empty()).then(|| {
+                let (mut min_x, mut min_y) = (f64::MAX, f64::MAX);
+                let (mut max_x, mut max_y) = (f64::MIN, f64::MIN);
+                for (x, y) in points {
+                    min_x = min_x.min(x);
+                    min_y = min_y.min(y);
+                    max_x = max_x.max(x);
+                    max_y = max_y.max(y);
+                }
+                let rect = [
+                    (min_x, min_y),
+                    (max_x, min_y),
+                    (max_x, max_y),
+                    (min_x, max_y),
+                ];
+                extrude_footprint(
+                    &rect,
+                    elevation - FLOOR_SLAB_THICKNESS,
+                    FLOOR_SLAB_THICKNESS,
+                )
+            });
+            FloorMesh {
+                floor_id: floor.id.clone(),
+                floor_name: floor.name.clone(),
+                level: floor.level,
+                elevation,
+                slab,
+            }
+        })
+        .collect()
+}
+
+/// Floor, room, and equipment meshes for `building`.
+pub fn building_meshes(building: &Building) -> BuildingMeshes {
+    BuildingMeshes {
+        floors: floor_meshes(building),
+        rooms: room_meshes(building),
+        equipment: equipment_meshes(building),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{RoomType, Wing};
+
+    #[test]
+    fn extrudes_l_shaped_outline_into_closed_prism() {
+        // Clockwise L shape: exercises reorientation and a reflex vertex
+        let outline = parse_polygon("0,0;0,4;2,4;2,2;4,2;4,0;0,0").unwrap();
+        assert_eq!(outline.len(), 6);
+        let mesh = extrude_footprint(&outline, 3.0, 2.5);
+        assert!(mesh.validate());
+        assert_eq!(mesh.vertices.len(), 12);
+        // 4 floor + 4 ceiling + 12 wall triangles
+        assert_eq!(mesh.indices.len() / 3, 20);
+
+        // Floor triangle areas add up to the L's 12 m²
+        let area: f64 = mesh.indices[..12]
+            .chunks(3)
+            .map(|t| {
+                let p = |i: u32| {
+                    let v = mesh.vertices[i as usize];
+                    (v.x, v.y)
+                };
+                cross(p(t[0]), p(t[1]), p(t[2])).abs() / 2.0
+            })
+            .sum();
+        assert!((area - 12.0).abs() < 1e-9);
+        assert!(mesh.vertices.iter().all(|v| v.z == 3.0 || v.z == 5.5));
+        assert!(parse_polygon("0,0;1,1;2,2").is_none());
+    }
+
+    #[test]
+    fn derives_meshes_only_for_rooms_without_geometry() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut ground = Floor::new("Ground".into(), 0);
+        ground.elevation = Some(0.0);
+        let mut upper = Floor::new("First".into(), 1);
+        upper.elevation = Some(4.0);
+
+        let mut wing = Wing::new("East".into());
+        let mut outlined = Room::new("Lab".into(), RoomType::Laboratory);
+        outlined
+            .properties
+            .insert(FLOOR_POLYGON_PROPERTY.into(), "0,0;5,0;5,4;0,4".into());
+        outlined.spatial_properties.dimensions.height = 0.0;
+        let mut modelled = Room::new("Office".into(), RoomType::Office);
+        modelled.spatial_properties.mesh = Some(Mesh::tetrahedron(1.0));
+        wing.add_room(outlined);
+        wing.add_room(modelled);
+        ground.add_wing(wing);
+        building.add_floor(ground);
+        building.add_floor(upper);
+
+        let meshes = room_meshes(&building);
+        let lab = meshes.iter().find(|m| m.room_name == "Lab").unwrap();
+        assert!(lab.derived);
+        // No room height: extruded to the next floor's elevation
+        let top = lab.mesh.vertices.iter().map(|v| v.z).fold(0.0, f64::max);
+        assert_eq!(top, 4.0);
+        let office = meshes.iter().find(|m| m.room_name == "Office").unwrap();
+        assert!(!office.derived);
+        assert_eq!(office.mesh, Mesh::tetrahedron(1.0));
+        assert_eq!(office.room_type, "Office");
+    }
+
+    #[test]
+    fn places_marker_cubes_for_unmeshed_equipment_once() {
+        use crate::core::{EquipmentType, Position};
+
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut placed = Equipment::new("AHU-1".into(), "/ahu-1".into(), EquipmentType::HVAC);
+        placed.position = Position {
+            x: 2.0,
+            y: 3.0,
+            z: 1.0,
+            coordinate_system: "building_local".into(),
+        };
+        let unplaced = Equipment::new("Spare".into(), "/spare".into(), EquipmentType::HVAC);
+        floor.equipment.push(placed.clone());
+        floor.equipment.push(unplaced);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.equipment.push(placed);
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let meshes = equipment_meshes(&building);
+        assert_eq!(meshes.len(), 1);
+        let marker = &meshes[0];
+        assert!(marker.derived);
+        assert_eq!(marker.equipment_type, "HVAC");
+        assert_eq!(marker.mesh.vertices.len(), 8);
+        assert!(marker.mesh.vertices.iter().all(|v| (v.x - 2.0).abs() <= 0.25));
+        assert!(marker.mesh.vertices.iter().all(|v| v.z == 1.0 || v.z == 1.5));
+        // Listed on the floor first, but still attributed to its room
+        assert!(marker.room_id.is_some());
+    }
+}
@@ -0,0 +1,282 @@
+//! Approximate plan layout for rooms without geometry.
+//!
+//! Many imported buildings carry no room outlines: rooms have no extent, or
+//! all sit on the same placeholder box at the origin. [`floor_footprints`]
+//! keeps every stored outline and packs the remaining rooms into rectangles,
+//! wing by wing, beside the stored geometry, so plans and extrusions still
+//! show every room. A generated rectangle has the room's `area` hint (m²),
+//! else the area of its dimensions, else [`DEFAULT_ROOM_AREA`]. Rooms named in
+//! each other's `adjacent_to` hint are packed next to each other.
+
+use super::extrusion::{parse_polygon, FLOOR_POLYGON_PROPERTY};
+use crate::core::{Floor, Room};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Room property giving its floor area (m²) for auto-layout.
+pub const AREA_HINT_PROPERTY: &str = "area";
+
+/// Room property listing rooms (names or ids, comma-separated) it borders.
+pub const ADJACENT_HINT_PROPERTY: &str = "adjacent_to";
+
+/// Area (m²) of a laid-out room with no hint or dimensions.
+pub const DEFAULT_ROOM_AREA: f64 = 20.0;
+
+/// Corridor (m) left between laid-out wings and the stored geometry.
+pub const WING_GAP: f64 = 2.0;
+
+const EPSILON: f64 = 1e-9;
+
+/// Where a room's plan outline came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FootprintSource {
+    /// The `floor_polygon` property
+    Polygon,
+    /// The room's bounding box
+    BoundingBox,
+    /// Generated by auto-layout; position and shape are approximate
+    Layout,
+}
+
+/// Plan outline of one room.
+#[derive(Debug, Clone)]
+pub struct RoomFootprint<'a> {
+    pub room: &'a Room,
+    /// Closed polygon (last point not repeated)
+    pub outline: Vec<(f64, f64)>,
+    pub source: FootprintSource,
+}
+
+type BoxKey = [u64; 4];
+
+fn box_key(room: &Room) -> Option<BoxKey> {
+    let bbox = &room.spatial_properties.bounding_box;
+    let (w, d) = (bbox.max.x - bbox.min.x, bbox.max.y - bbox.min.y);
+    (w > EPSILON && d > EPSILON)
+        .then(|| [bbox.min.x, bbox.min.y, bbox.max.x, bbox.max.y].map(f64::to_bits))
+}
+
+fn rectangle(x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<(f64, f64)> {
+    vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+}
+
+/// The room's `area` hint, else its dimensions' area, else the default.
+pub fn layout_area(room: &Room) -> f64 {
+    let d = &room.spatial_properties.dimensions;
+    room.properties
+        .get(AREA_HINT_PROPERTY)
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|a| a.is_finite() && *a > EPSILON)
+        .or_else(|| Some(d.width * d.depth).filter(|a| *a > EPSILON))
+        .unwrap_or(DEFAULT_ROOM_AREA)
+}
+
+fn hints_at(a: &Room, b: &Room) -> bool {
+    a.properties
+        .get(ADJACENT_HINT_PROPERTY)
+        .is_some_and(|list| {
+            list.split(',')
+                .map(str::trim)
+                .any(|n| b.id == n || b.matches_name(n))
+        })
+}
+
+/// `rooms` reordered so each room follows one it is hinted to border, where
+/// there is one.
+fn adjacency_order<'a>(rooms: &[&'a Room]) -> Vec<&'a Room> {
+    let mut remaining: Vec<&Room> = rooms.to_vec();
+    let mut ordered: Vec<&Room> = Vec::with_capacity(rooms.len());
+    while !remaining.is_empty() {
+        let next = ordered
+            .last()
+            .and_then(|last| {
+                remaining
+                    .iter()
+                    .position(|r| hints_at(last, r) || hints_at(r, last))
+            })
+            .unwrap_or(0);
+        ordered.push(remaining.remove(next));
+    }
+    ordered
+}
+
+/// Pack `rooms` into rows of equal depth starting at `(x0, y0)`, roughly
+/// square overall. Returns the rectangles by room id and the block's depth.
+fn pack(rooms: &[&Room], x0: f64, y0: f64) -> (HashMap<String, Vec<(f64, f64)>>, f64) {
+    let areas: Vec<f64> = rooms.iter().map(|r| layout_area(r)).collect();
+    let total: f64 = areas.iter().sum();
+    let depth = (total / rooms.len() as f64).sqrt();
+    let width = total.sqrt().max(depth);
+
+    let mut out = HashMap::new();
+    let (mut x, mut y) = (x0, y0);
+    for (room, area) in rooms.iter().zip(&areas) {
+        let w = area / depth;
+        if x > x0 + EPSILON && x + w > x0 + width + EPSILON {
+            x = x0;
+            y += depth;
+        }
+        out.insert(room.id.clone(), rectangle(x, y, x + w, y + depth));
+        x += w;
+    }
+    (out, y + depth - y0)
+}
+
+/// Plan outline of every room on `floor`, in model order: stored outlines
+/// as-is, generated rectangles for rooms with no extent or sharing their
+/// bounding box with another room.
+pub fn floor_footprints(floor: &Floor) -> Vec<RoomFootprint<'_>> {
+    let rooms: Vec<&Room> = floor.wings.iter().flat_map(|w| w.rooms.iter()).collect();
+    let polygon = |room: &Room| {
+        room.properties
+            .get(FLOOR_POLYGON_PROPERTY)
+            .and_then(|s| parse_polygon(s))
+    };
+    let mut box_counts: HashMap<BoxKey, usize> = HashMap::new();
+    for room in rooms.iter().filter(|r| polygon(r).is_none()) {
+        if let Some(key) = box_key(room) {
+            *box_counts.entry(key).or_default() += 1;
+        }
+    }
+
+    let mut stored: HashMap<&str, RoomFootprint> = HashMap::new();
+    for room in &rooms {
+        let footprint = if let Some(outline) = polygon(room) {
+            Some((outline, FootprintSource::Polygon))
+        } else {
+            box_key(room)
+                .filter(|key| box_counts.get(key) == Some(&1))
+                .map(|_| {
+                    let b = &room.spatial_properties.bounding_box;
+                    let outline = rectangle(b.min.x, b.min.y, b.max.x, b.max.y);
+                    (outline, FootprintSource::BoundingBox)
+                })
+        };
+        if let Some((outline, source)) = footprint {
+            stored.insert(
+                room.id.as_str(),
+                RoomFootprint {
+                    room,
+                    outline,
+                    source,
+                },
+            );
+        }
+    }
+
+    // Laid-out wings go in a column next to (left-aligned, above) the stored
+    // geometry
+    let points = || stored.values().flat_map(|f| f.outline.iter());
+    let x0 = points()
+        .map(|p| p.0)
+        .fold(None, |m: Option<f64>, x| Some(m.map_or(x, |m| m.min(x))));
+    let mut y = points()
+        .map(|p| p.1)
+        .fold(None, |m: Option<f64>, y| Some(m.map_or(y, |m| m.max(y))))
+        .map_or(0.0, |top| top + WING_GAP);
+    let mut laid_out: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+    for wing in &floor.wings {
+        let pending: Vec<&Room> = wing
+            .rooms
+            .iter()
+            .filter(|r| !stored.contains_key(r.id.as_str()))
+            .collect();
+        if pending.is_empty() {
+            continue;
+        }
+        let (rects, depth) = pack(&adjacency_order(&pending), x0.unwrap_or(0.0), y);
+        laid_out.extend(rects);
+        y += depth + WING_GAP;
+    }
+
+    rooms
+        .into_iter()
+        .filter_map(|room| {
+            stored.remove(room.id.as_str()).or_else(|| {
+                Some(RoomFootprint {
+                    room,
+                    outline: laid_out.remove(&room.id)?,
+                    source: FootprintSource::Layout,
+                })
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Dimensions, Position, RoomType, SpatialProperties, Wing};
+
+    fn room(name: &str, area: Option<&str>) -> Room {
+        let mut room = Room::new(name.into(), RoomType::Office);
+        room.id = name.to_lowercase();
+        if let Some(area) = area {
+            room.properties
+                .insert(AREA_HINT_PROPERTY.into(), area.into());
+        }
+        room
+    }
+
+    fn area(outline: &[(f64, f64)]) -> f64 {
+        (outline[2].0 - outline[0].0) * (outline[2].1 - outline[0].1)
+    }
+
+    #[test]
+    fn packs_unplaced_rooms_beside_stored_geometry() {
+        let mut surveyed = room("Lobby", None);
+        surveyed.spatial_properties = SpatialProperties::new(
+            Position {
+                x: 5.0,
+                y: 5.0,
+                z: 0.0,
+                coordinate_system: "building_local".into(),
+            },
+            Dimensions {
+                width: 10.0,
+                height: 3.0,
+                depth: 10.0,
+            },
+            "building_local".into(),
+        );
+        let mut east = Wing::new("East".into());
+        east.rooms.push(surveyed);
+        // Both still on the placeholder box at the origin
+        let mut office = room("Office", Some("30"));
+        office
+            .properties
+            .insert(ADJACENT_HINT_PROPERTY.into(), "Store".into());
+        east.rooms.push(office);
+        east.rooms.push(room("Meeting", Some("12")));
+        east.rooms.push(room("Store", Some("6")));
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.wings.push(east);
+
+        let footprints = floor_footprints(&floor);
+        let names: Vec<&str> = footprints.iter().map(|f| f.room.name.as_str()).collect();
+        assert_eq!(names, ["Lobby", "Office", "Meeting", "Store"]);
+        assert_eq!(footprints[0].source, FootprintSource::BoundingBox);
+        assert!(footprints[1..]
+            .iter()
+            .all(|f| f.source == FootprintSource::Layout));
+
+        let by_name = |name: &str| {
+            &footprints
+                .iter()
+                .find(|f| f.room.name == name)
+                .unwrap()
+                .outline
+        };
+        assert!((area(by_name("Office")) - 30.0).abs() < 1e-9);
+        assert!((area(by_name("Store")) - 6.0).abs() < 1e-9);
+        // Above the lobby, past the corridor
+        assert!(footprints[1..]
+            .iter()
+            .all(|f| f.outline.iter().all(|p| p.1 >= 10.0 + WING_GAP - 1e-9)));
+        // The hinted neighbour is packed straight after the office, touching it
+        let (o, s) = (by_name("Office"), by_name("Store"));
+        assert!(s[0].0 <= o[2].0 + 1e-9 && o[0].0 <= s[2].0 + 1e-9);
+        assert!(s[0].1 <= o[2].1 + 1e-9 && o[0].1 <= s[2].1 + 1e-9);
+    }
+}
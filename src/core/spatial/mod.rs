@@ -1,9 +1,17 @@
 // Spatial data processing for ArxOS
 use nalgebra::Point3;
 
+pub mod extrusion;
 pub mod grid;
+pub mod layout;
 pub mod mesh;
+pub mod navigation;
+pub mod plan;
+pub mod transform;
 pub mod types;
+pub use extrusion::{
+    building_meshes, room_meshes, BuildingMeshes, EquipmentMesh, FloorMesh, RoomMesh,
+};
 pub use mesh::*;
 pub use types::*;
 
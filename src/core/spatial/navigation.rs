@@ -0,0 +1,161 @@
+//! Room navigation graph.
+//!
+//! Rooms on the same floor whose plan footprints touch (within a tolerance)
+//! are treated as connected; edges are weighted by centre-to-centre distance.
+//! This is a coarse walkable graph derived from room envelopes — there is no
+//! door model yet, so adjacency stands in for an opening.
+
+use crate::core::{Building, Room};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Default gap (m) under which two room footprints are considered adjacent.
+pub const DEFAULT_ADJACENCY_TOLERANCE: f64 = 0.5;
+
+/// Graph node: one room with its plan centre.
+#[derive(Debug, Clone)]
+pub struct NavNode {
+    pub room_id: String,
+    pub room_name: String,
+    pub floor_level: i32,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Undirected room adjacency graph for a building.
+#[derive(Debug, Clone, Default)]
+pub struct NavigationGraph {
+    pub nodes: Vec<NavNode>,
+    /// Adjacency list: node index → (neighbour index, distance m)
+    pub edges: Vec<Vec<(usize, f64)>>,
+}
+
+impl NavigationGraph {
+    /// Build the graph from room bounding boxes, floor by floor.
+    pub fn from_building(building: &Building, tolerance: f64) -> Self {
+        let mut graph = NavigationGraph::default();
+        for floor in &building.floors {
+            let start = graph.nodes.len();
+            let rooms: Vec<&Room> = floor.wings.iter().flat_map(|w| w.rooms.iter()).collect();
+            for room in &rooms {
+                let c = room.spatial_properties.bounding_box.center();
+                graph.nodes.push(NavNode {
+                    room_id: room.id.clone(),
+                    room_name: room.name.clone(),
+                    floor_level: floor.level,
+                    x: c.x,
+                    y: c.y,
+                });
+                graph.edges.push(Vec::new());
+            }
+            for i in 0..rooms.len() {
+                for j in (i + 1)..rooms.len() {
+                    if footprints_touch(rooms[i], rooms[j], tolerance) {
+                        let (a, b) = (start + i, start + j);
+                        let d = graph.plan_distance(a, b);
+                        graph.edges[a].push((b, d));
+                        graph.edges[b].push((a, d));
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// Index of the node for `room_id`.
+    pub fn index_of(&self, room_id: &str) -> Option<usize> {
+        self.nodes.iter().position(|n| n.room_id == room_id)
+    }
+
+    /// Plan (XY) distance between two node centres.
+    pub fn plan_distance(&self, a: usize, b: usize) -> f64 {
+        let (na, nb) = (&self.nodes[a], &self.nodes[b]);
+        ((na.x - nb.x).powi(2) + (na.y - nb.y).powi(2)).sqrt()
+    }
+
+    /// Multi-source Dijkstra: shortest distance from every node to the nearest
+    /// of `sources`, plus which source it reached. `None` = unreachable.
+    pub fn distances_from(&self, sources: &[usize]) -> Vec<Option<(f64, usize)>> {
+        let mut best: Vec<Option<(f64, usize)>> = vec![None; self.nodes.len()];
+        let mut heap = BinaryHeap::new();
+        for &s in sources {
+            best[s] = Some((0.0, s));
+            heap.push(State { cost: 0.0, node: s, origin: s });
+        }
+        while let Some(State { cost, node, origin }) = heap.pop() {
+            if let Some((known, _)) = best[node] {
+                if cost > known {
+                    continue;
+                }
+            }
+            for &(next, w) in &self.edges[node] {
+                let candidate = cost + w;
+                let better = match best[next] {
+                    Some((known, _)) => candidate < known,
+                    None => true,
+                };
+                if better {
+                    best[next] = Some((candidate, origin));
+                    heap.push(State {
+                        cost: candidate,
+                        node: next,
+                        origin,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    /// Room ids mapped to their node index.
+    pub fn index_map(&self) -> HashMap<&str, usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.room_id.as_str(), i))
+            .collect()
+    }
+}
+
+/// Whether two room footprints overlap or are separated by at most `tolerance`.
+pub fn footprints_touch(a: &Room, b: &Room, tolerance: f64) -> bool {
+    let (ba, bb) = (
+        &a.spatial_properties.bounding_box,
+        &b.spatial_properties.bounding_box,
+    );
+    let gap_x = (ba.min.x - bb.max.x).max(bb.min.x - ba.max.x);
+    let gap_y = (ba.min.y - bb.max.y).max(bb.min.y - ba.max.y);
+    gap_x <= tolerance && gap_y <= tolerance
+}
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    cost: f64,
+    node: usize,
+    origin: usize,
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost && self.node == other.node
+    }
+}
+
+impl Eq for State {}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reverse for a min-heap on cost
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,317 @@
+//! Plan-view geometry DTOs for viewers (PWA floor plans, building pickers).
+//!
+//! [`building_overview`] lists floors with counts; [`floor_plan`] gives one
+//! floor's room outlines (from [`floor_footprints`]) and equipment positions,
+//! in building-local metres. [`PlanIndex`] resolves a plan position back to
+//! the room or equipment under it.
+
+use super::layout::{floor_footprints, FootprintSource};
+use super::types::Point3D;
+use crate::core::{Building, Floor};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::{RTree, AABB};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// One floor in a [`BuildingOverview`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloorListing {
+    pub level: i32,
+    pub name: String,
+    pub room_count: usize,
+    pub equipment_count: usize,
+}
+
+/// Building header plus its floors, lowest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildingOverview {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub floors: Vec<FloorListing>,
+}
+
+/// Room outline on a [`FloorPlan`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanRoom {
+    pub id: String,
+    pub name: String,
+    pub room_type: String,
+    /// Closed polygon as `[x, y]` pairs (last point not repeated)
+    pub outline: Vec<[f64; 2]>,
+    /// True when the outline is the plan bounding box, not a stored polygon
+    pub derived: bool,
+    /// True when the room had no usable geometry and was auto-laid out
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub approximate: bool,
+}
+
+/// Equipment marker on a [`FloorPlan`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanEquipment {
+    pub id: String,
+    pub name: String,
+    pub equipment_type: String,
+    pub status: String,
+    pub position: Point3D,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<String>,
+}
+
+/// Plan geometry for one floor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloorPlan {
+    pub level: i32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation: Option<f64>,
+    pub rooms: Vec<PlanRoom>,
+    pub equipment: Vec<PlanEquipment>,
+}
+
+/// Floors of `building` with room and equipment counts.
+pub fn building_overview(building: &Building) -> BuildingOverview {
+    let mut floors: Vec<FloorListing> = building
+        .floors
+        .iter()
+        .map(|floor| FloorListing {
+            level: floor.level,
+            name: floor.name.clone(),
+            room_count: floor.wings.iter().map(|w| w.rooms.len()).sum(),
+            equipment_count: floor_equipment(floor).len(),
+        })
+        .collect();
+    floors.sort_by_key(|f| f.level);
+    BuildingOverview {
+        id: building.id.clone(),
+        name: building.name.clone(),
+        path: building.path.clone(),
+        floors,
+    }
+}
+
+/// Plan geometry for the floor at `level`; rooms without geometry are auto-laid out.
+pub fn floor_plan(building: &Building, level: i32) -> Option<FloorPlan> {
+    let floor = building.floors.iter().find(|f| f.level == level)?;
+    let rooms = floor_footprints(floor)
+        .into_iter()
+        .map(|footprint| PlanRoom {
+            id: footprint.room.id.clone(),
+            name: footprint.room.name.clone(),
+            room_type: footprint.room.room_type.to_string(),
+            outline: footprint.outline.into_iter().map(|(x, y)| [x, y]).collect(),
+            derived: footprint.source != FootprintSource::Polygon,
+            approximate: footprint.source == FootprintSource::Layout,
+        })
+        .collect();
+    let equipment = floor_equipment(floor)
+        .into_iter()
+        .map(|(equipment, room_id)| PlanEquipment {
+            id: equipment.id.clone(),
+            name: equipment.name.clone(),
+            equipment_type: equipment.equipment_type.to_string(),
+            status: equipment.status.to_string(),
+            position: Point3D::new(
+                equipment.position.x,
+                equipment.position.y,
+                equipment.position.z,
+            ),
+            room_id: equipment.room_id.clone().or(room_id),
+        })
+        .collect();
+    Some(FloorPlan {
+        level: floor.level,
+        name: floor.name.clone(),
+        elevation: floor.elevation,
+        rooms,
+        equipment,
+    })
+}
+
+/// Equipment on `floor` (room, wing, and floor lists; one entry per id), with
+/// the containing room's id when listed under a room.
+fn floor_equipment(floor: &Floor) -> Vec<(&crate::core::Equipment, Option<String>)> {
+    let mut seen = HashSet::new();
+    let in_rooms = floor
+        .wings
+        .iter()
+        .flat_map(|w| w.rooms.iter())
+        .flat_map(|r| r.equipment.iter().map(move |e| (e, Some(r.id.clone()))));
+    let loose = floor
+        .wings
+        .iter()
+        .flat_map(|w| w.equipment.iter())
+        .chain(floor.equipment.iter())
+        .map(|e| (e, None));
+    in_rooms
+        .chain(loose)
+        .filter(|(e, _)| seen.insert(e.id.as_str()))
+        .collect()
+}
+
+/// What a plan position resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanPick {
+    Equipment(String),
+    Room(String),
+}
+
+/// R-tree over one [`FloorPlan`] for picking by position.
+pub struct PlanIndex {
+    equipment: RTree<GeomWithData<[f64; 2], String>>,
+    /// Room outline bounding boxes; data indexes `outlines`
+    rooms: RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>,
+    /// (room id, outline, area)
+    outlines: Vec<(String, Vec<[f64; 2]>, f64)>,
+}
+
+impl PlanIndex {
+    pub fn new(plan: &FloorPlan) -> Self {
+        let equipment = plan
+            .equipment
+            .iter()
+            .map(|e| GeomWithData::new([e.position.x, e.position.y], e.id.clone()))
+            .collect();
+        let outlines: Vec<(String, Vec<[f64; 2]>, f64)> = plan
+            .rooms
+            .iter()
+            .filter(|r| r.outline.len() >= 3)
+            .map(|r| (r.id.clone(), r.outline.clone(), polygon_area(&r.outline)))
+            .collect();
+        let rooms = outlines
+            .iter()
+            .enumerate()
+            .map(|(i, (_, outline, _))| {
+                let bbox = AABB::from_points(outline.iter());
+                GeomWithData::new(Rectangle::from_aabb(bbox), i)
+            })
+            .collect();
+        Self {
+            equipment: RTree::bulk_load(equipment),
+            rooms: RTree::bulk_load(rooms),
+            outlines,
+        }
+    }
+
+    /// Equipment within `radius` of (`x`, `y`) (the nearest wins), else the
+    /// smallest room whose outline contains the point.
+    pub fn pick(&self, x: f64, y: f64, radius: f64) -> Option<PlanPick> {
+        if let Some((nearest, d2)) = self
+            .equipment
+            .nearest_neighbor_iter_with_distance_2(&[x, y])
+            .next()
+        {
+            if d2 <= radius * radius {
+                return Some(PlanPick::Equipment(nearest.data.clone()));
+            }
+        }
+        self.room_at(x, y).map(PlanPick::Room)
+    }
+
+    /// Smallest room whose outline contains (`x`, `y`).
+    pub fn room_at(&self, x: f64, y: f64) -> Option<String> {
+        self.rooms
+            .locate_all_at_point(&[x, y])
+            .map(|r| &self.outlines[r.data])
+            .filter(|(_, outline, _)| polygon_contains(outline, x, y))
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(id, _, _)| id.clone())
+    }
+}
+
+/// Even-odd rule; points on an edge may fall either way.
+fn polygon_contains(outline: &[[f64; 2]], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut j = outline.len() - 1;
+    for (i, a) in outline.iter().enumerate() {
+        let b = outline[j];
+        if (a[1] > y) != (b[1] > y) && x < (b[0] - a[0]) * (y - a[1]) / (b[1] - a[1]) + a[0] {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn polygon_area(outline: &[[f64; 2]]) -> f64 {
+    let n = outline.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (outline[i], outline[(i + 1) % n]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum::<f64>()
+        .abs()
+        / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::spatial::extrusion::FLOOR_POLYGON_PROPERTY;
+    use crate::core::{Equipment, EquipmentType, Room, RoomType, Wing};
+
+    #[test]
+    fn test_floor_plan_outlines_and_equipment() {
+        let mut room = Room::new("Lab".into(), RoomType::Laboratory);
+        room.properties
+            .insert(FLOOR_POLYGON_PROPERTY.into(), "0,0;4,0;4,3;0,3".into());
+        let mut pump = Equipment::new("P-1".into(), String::new(), EquipmentType::Plumbing);
+        pump.position.x = 2.0;
+        room.add_equipment(pump.clone());
+        let room_id = room.id.clone();
+        let mut wing = Wing::new("W".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        floor.equipment.push(pump);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building.add_floor(Floor::new("Roof".into(), -1));
+
+        let overview = building_overview(&building);
+        assert_eq!(overview.floors[0].level, -1);
+        assert_eq!(overview.floors[1].room_count, 1);
+        assert_eq!(overview.floors[1].equipment_count, 1);
+
+        let plan = floor_plan(&building, 0).unwrap();
+        assert_eq!(plan.rooms[0].outline.len(), 4);
+        assert!(!plan.rooms[0].derived);
+        assert_eq!(plan.equipment.len(), 1);
+        assert_eq!(plan.equipment[0].position.x, 2.0);
+        assert_eq!(plan.equipment[0].room_id.as_deref(), Some(room_id.as_str()));
+        assert!(floor_plan(&building, 7).is_none());
+    }
+
+    #[test]
+    fn test_plan_index_picks_equipment_then_smallest_room() {
+        let mut hall = Room::new("Hall".into(), RoomType::Other("Hall".into()));
+        hall.properties
+            .insert(FLOOR_POLYGON_PROPERTY.into(), "0,0;10,0;10,10;0,10".into());
+        let mut closet = Room::new("Closet".into(), RoomType::Storage);
+        closet
+            .properties
+            .insert(FLOOR_POLYGON_PROPERTY.into(), "1,1;3,1;3,3;1,3".into());
+        let mut panel = Equipment::new("EP-1".into(), String::new(), EquipmentType::Electrical);
+        panel.position.x = 8.0;
+        panel.position.y = 8.0;
+        let (hall_id, closet_id, panel_id) = (hall.id.clone(), closet.id.clone(), panel.id.clone());
+        hall.add_equipment(panel);
+        let mut wing = Wing::new("W".into());
+        wing.add_room(hall);
+        wing.add_room(closet);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let index = PlanIndex::new(&floor_plan(&building, 0).unwrap());
+        assert_eq!(
+            index.pick(8.2, 7.9, 0.5),
+            Some(PlanPick::Equipment(panel_id))
+        );
+        assert_eq!(index.pick(2.0, 2.0, 0.5), Some(PlanPick::Room(closet_id)));
+        assert_eq!(index.pick(5.0, 5.0, 0.5), Some(PlanPick::Room(hall_id)));
+        assert_eq!(index.pick(12.0, 5.0, 0.5), None);
+    }
+}
@@ -0,0 +1,225 @@
+//! Affine transforms for stored geometry.
+//!
+//! A transform spec is a sequence of operations applied left to right,
+//! separated by whitespace or `;`:
+//!
+//! - `translate(dx, dy)` / `translate(dx, dy, dz)`
+//! - `rotate(deg)` about the z axis, or `rotate(x|y|z, deg)`
+//! - `scale(s)` / `scale(sx, sy)` / `scale(sx, sy, sz)`
+//!
+//! Rotations and scales act about a pivot (usually the entity's position)
+//! that moves with earlier translations, so `translate(5, 0) rotate(90)`
+//! moves a room five metres east and then turns it in place.
+
+use super::types::Point3D;
+use nalgebra::{Matrix4, Rotation3, Vector3};
+use std::fmt;
+
+/// Rotation axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// One parsed operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformOp {
+    Translate(Vector3<f64>),
+    Rotate { axis: Axis, degrees: f64 },
+    Scale(Vector3<f64>),
+}
+
+impl fmt::Display for TransformOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformOp::Translate(d) => write!(f, "translate({}, {}, {})", d.x, d.y, d.z),
+            TransformOp::Rotate { axis, degrees } => {
+                let axis = match axis {
+                    Axis::X => "x",
+                    Axis::Y => "y",
+                    Axis::Z => "z",
+                };
+                write!(f, "rotate({}, {})", axis, degrees)
+            }
+            TransformOp::Scale(s) => write!(f, "scale({}, {}, {})", s.x, s.y, s.z),
+        }
+    }
+}
+
+/// Parse a transform spec into its operations.
+pub fn parse_ops(spec: &str) -> Result<Vec<TransformOp>, String> {
+    let mut ops = Vec::new();
+    let mut rest = spec.trim();
+    while !rest.is_empty() {
+        let open = rest
+            .find('(')
+            .ok_or_else(|| format!("Expected '(' after '{}'", rest))?;
+        let close = rest[open..]
+            .find(')')
+            .map(|i| open + i)
+            .ok_or_else(|| format!("Missing ')' in '{}'", rest))?;
+        let name = rest[..open].trim().to_ascii_lowercase();
+        let args: Vec<&str> = rest[open + 1..close]
+            .split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .collect();
+        ops.push(parse_op(&name, &args)?);
+        rest = rest[close + 1..].trim_start_matches([';', ' ', '\t', '\n']);
+    }
+    if ops.is_empty() {
+        return Err("Empty transform (expected e.g. 'translate(2, 0) rotate(90)')".to_string());
+    }
+    Ok(ops)
+}
+
+fn numbers(name: &str, args: &[&str]) -> Result<Vec<f64>, String> {
+    args.iter()
+        .map(|a| {
+            a.parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .ok_or_else(|| format!("{}: '{}' is not a number", name, a))
+        })
+        .collect()
+}
+
+fn parse_op(name: &str, args: &[&str]) -> Result<TransformOp, String> {
+    match name {
+        "translate" => match numbers(name, args)?.as_slice() {
+            [dx, dy] => Ok(TransformOp::Translate(Vector3::new(*dx, *dy, 0.0))),
+            [dx, dy, dz] => Ok(TransformOp::Translate(Vector3::new(*dx, *dy, *dz))),
+            _ => Err("translate takes (dx, dy) or (dx, dy, dz)".to_string()),
+        },
+        "rotate" => {
+            let (axis, degrees) = match args {
+                [deg] => (Axis::Z, *deg),
+                [axis, deg] => {
+                    let axis = match axis.to_ascii_lowercase().as_str() {
+                        "x" => Axis::X,
+                        "y" => Axis::Y,
+                        "z" => Axis::Z,
+                        other => return Err(format!("rotate: unknown axis '{}'", other)),
+                    };
+                    (axis, *deg)
+                }
+                _ => return Err("rotate takes (degrees) or (axis, degrees)".to_string()),
+            };
+            let degrees = numbers(name, &[degrees])?[0];
+            Ok(TransformOp::Rotate { axis, degrees })
+        }
+        "scale" => {
+            let factors = match numbers(name, args)?.as_slice() {
+                [s] => Vector3::new(*s, *s, *s),
+                [sx, sy] => Vector3::new(*sx, *sy, 1.0),
+                [sx, sy, sz] => Vector3::new(*sx, *sy, *sz),
+                _ => return Err("scale takes (s), (sx, sy), or (sx, sy, sz)".to_string()),
+            };
+            if factors.iter().any(|f| *f == 0.0) {
+                return Err("scale factors must be non-zero".to_string());
+            }
+            Ok(TransformOp::Scale(factors))
+        }
+        other => Err(format!(
+            "Unknown operation '{}' (use translate, rotate, scale)",
+            other
+        )),
+    }
+}
+
+/// Homogeneous 4×4 affine transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    pub matrix: Matrix4<f64>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            matrix: Matrix4::identity(),
+        }
+    }
+
+    /// Compose `ops` in order, rotating and scaling about `pivot`.
+    pub fn from_ops(ops: &[TransformOp], pivot: &Point3D) -> Self {
+        let mut pivot = Vector3::new(pivot.x, pivot.y, pivot.z);
+        let mut matrix = Matrix4::identity();
+        for op in ops {
+            let step = match op {
+                TransformOp::Translate(d) => {
+                    pivot += d;
+                    Matrix4::new_translation(d)
+                }
+                TransformOp::Rotate { axis, degrees } => {
+                    let axis = match axis {
+                        Axis::X => Vector3::x_axis(),
+                        Axis::Y => Vector3::y_axis(),
+                        Axis::Z => Vector3::z_axis(),
+                    };
+                    let rotation = Rotation3::from_axis_angle(&axis, degrees.to_radians());
+                    about(&pivot, rotation.to_homogeneous())
+                }
+                TransformOp::Scale(s) => about(&pivot, Matrix4::new_nonuniform_scaling(s)),
+            };
+            matrix = step * matrix;
+        }
+        Self { matrix }
+    }
+
+    /// `self` followed by `next`.
+    pub fn then(&self, next: &Transform) -> Self {
+        Self {
+            matrix: next.matrix * self.matrix,
+        }
+    }
+
+    pub fn apply(&self, p: &Point3D) -> Point3D {
+        let v = self
+            .matrix
+            .transform_point(&nalgebra::Point3::new(p.x, p.y, p.z));
+        Point3D::new(v.x, v.y, v.z)
+    }
+}
+
+/// `linear` applied about `pivot` instead of the origin.
+fn about(pivot: &Vector3<f64>, linear: Matrix4<f64>) -> Matrix4<f64> {
+    Matrix4::new_translation(pivot) * linear * Matrix4::new_translation(&-pivot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &Point3D, b: &Point3D) -> bool {
+        (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9 && (a.z - b.z).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_parse_and_compose_about_moving_pivot() {
+        let ops = parse_ops("translate(5, 0); rotate(90) scale(2, 1)").unwrap();
+        assert_eq!(ops.len(), 3);
+        let t = Transform::from_ops(&ops, &Point3D::new(1.0, 1.0, 0.0));
+        // Pivot ends at (6, 1); (2, 1) → (7, 1) → rotated to (6, 2) → scaled x about 6
+        assert!(close(
+            &t.apply(&Point3D::new(2.0, 1.0, 0.0)),
+            &Point3D::new(6.0, 2.0, 0.0)
+        ));
+        assert!(close(
+            &t.apply(&Point3D::new(1.0, 1.0, 3.0)),
+            &Point3D::new(6.0, 1.0, 3.0)
+        ));
+
+        assert!(parse_ops("shear(1)").is_err());
+        assert!(parse_ops("scale(0)").is_err());
+        assert!(parse_ops("rotate(w, 10)").is_err());
+        assert!(parse_ops("translate(1").is_err());
+    }
+}
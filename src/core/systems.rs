@@ -0,0 +1,315 @@
+//! Equipment systems: typed edges between equipment items.
+//!
+//! Each equipment item lists its outgoing [`Connection`]s in building.yaml,
+//! e.g. `AHU-1` carries `{relation: feeds, to: <VAV-3-01 id>}` and `Panel-B`
+//! carries `{relation: powers, to: <Pump-2 id>}`. Following edges forward
+//! answers "what loses service if this fails"
+//! ([`SystemGraph::downstream`]); following them backward answers "what does
+//! this depend on" ([`SystemGraph::upstream`]). Edges to
+//! equipment that no longer exists are ignored.
+
+use super::{Building, Equipment};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// How one piece of equipment depends on another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Relation {
+    /// Supplies air, water, or another medium (AHU → VAV)
+    Feeds,
+    /// Supplies electrical power (panel → pump)
+    Powers,
+    /// Sends control signals (controller → damper)
+    Controls,
+    /// Serves an area or terminal unit (pump → coil)
+    Serves,
+}
+
+impl Relation {
+    pub const ALL: [Relation; 4] = [
+        Relation::Feeds,
+        Relation::Powers,
+        Relation::Controls,
+        Relation::Serves,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Relation::Feeds => "feeds",
+            Relation::Powers => "powers",
+            Relation::Controls => "controls",
+            Relation::Serves => "serves",
+        }
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Relation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Relation::ALL
+            .into_iter()
+            .find(|r| r.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown relation '{}'. Use: feeds, powers, controls, serves",
+                    s
+                )
+            })
+    }
+}
+
+/// Outgoing edge from the equipment that carries it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Connection {
+    pub relation: Relation,
+    /// Id of the downstream equipment
+    pub to: String,
+}
+
+/// Equipment reached from the starting item during impact analysis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpactNode {
+    pub id: String,
+    pub name: String,
+    /// Hops from the starting item (1 = directly connected)
+    pub depth: usize,
+    /// Relation of the edge this item was reached through
+    pub relation: Relation,
+    /// Id of the item it was reached from
+    pub via: String,
+}
+
+/// Edge list of a building, indexed both ways.
+pub struct SystemGraph<'a> {
+    equipment: BTreeMap<String, &'a Equipment>,
+    outgoing: BTreeMap<String, Vec<(Relation, String)>>,
+    incoming: BTreeMap<String, Vec<(Relation, String)>>,
+}
+
+impl<'a> SystemGraph<'a> {
+    pub fn new(building: &'a Building) -> Self {
+        let equipment: BTreeMap<String, &Equipment> = building
+            .get_all_equipment()
+            .into_iter()
+            .map(|eq| (eq.id.clone(), eq))
+            .collect();
+        let mut outgoing: BTreeMap<String, Vec<(Relation, String)>> = BTreeMap::new();
+        let mut incoming: BTreeMap<String, Vec<(Relation, String)>> = BTreeMap::new();
+        for eq in equipment.values() {
+            for c in &eq.connections {
+                if !equipment.contains_key(&c.to) {
+                    continue;
+                }
+                outgoing
+                    .entry(eq.id.clone())
+                    .or_default()
+                    .push((c.relation, c.to.clone()));
+                incoming
+                    .entry(c.to.clone())
+                    .or_default()
+                    .push((c.relation, eq.id.clone()));
+            }
+        }
+        Self {
+            equipment,
+            outgoing,
+            incoming,
+        }
+    }
+
+    pub fn equipment(&self, id: &str) -> Option<&'a Equipment> {
+        self.equipment.get(id).copied()
+    }
+
+    /// Number of edges between existing equipment.
+    pub fn edge_count(&self) -> usize {
+        self.outgoing.values().map(Vec::len).sum()
+    }
+
+    /// All edges as `(from, relation, to)` ids, ordered by source id.
+    pub fn edges(&self) -> impl Iterator<Item = (&str, Relation, &str)> {
+        self.outgoing.iter().flat_map(|(from, edges)| {
+            edges
+                .iter()
+                .map(move |(relation, to)| (from.as_str(), *relation, to.as_str()))
+        })
+    }
+
+    /// Everything that depends on `id`, nearest first.
+    pub fn downstream(&self, id: &str) -> Vec<ImpactNode> {
+        self.walk(id, &self.outgoing)
+    }
+
+    /// Everything `id` depends on, nearest first.
+    pub fn upstream(&self, id: &str) -> Vec<ImpactNode> {
+        self.walk(id, &self.incoming)
+    }
+
+    fn walk(
+        &self,
+        start: &str,
+        edges: &BTreeMap<String, Vec<(Relation, String)>>,
+    ) -> Vec<ImpactNode> {
+        let mut seen: HashSet<&str> = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0usize)]);
+        let mut nodes = Vec::new();
+        while let Some((id, depth)) = queue.pop_front() {
+            for (relation, next) in edges.get(id).into_iter().flatten() {
+                if !seen.insert(next.as_str()) {
+                    continue;
+                }
+                let Some(eq) = self.equipment(next) else {
+                    continue;
+                };
+                nodes.push(ImpactNode {
+                    id: next.clone(),
+                    name: eq.name.clone(),
+                    depth: depth + 1,
+                    relation: *relation,
+                    via: id.to_string(),
+                });
+                queue.push_back((next.as_str(), depth + 1));
+            }
+        }
+        nodes.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.name.cmp(&b.name)));
+        nodes
+    }
+}
+
+/// Resolve an equipment query (id, name, or alias) to `(id, name)`.
+fn resolve(building: &Building, query: &str) -> Result<(String, String), String> {
+    building
+        .find_equipment(query)
+        .map(|eq| (eq.id.clone(), eq.name.clone()))
+        .ok_or_else(|| format!("Equipment '{}' not found", query))
+}
+
+/// Add the edge `from --relation--> to`. Returns the resolved names; adding an
+/// edge that already exists is a no-op.
+pub fn connect(
+    building: &mut Building,
+    from: &str,
+    relation: Relation,
+    to: &str,
+) -> Result<(String, String), String> {
+    let (from_id, from_name) = resolve(building, from)?;
+    let (to_id, to_name) = resolve(building, to)?;
+    if from_id == to_id {
+        return Err(format!("'{}' cannot be connected to itself", from_name));
+    }
+    let eq = building
+        .find_equipment_mut(&from_id)
+        .ok_or_else(|| format!("Equipment '{}' not found", from))?;
+    let connection = Connection {
+        relation,
+        to: to_id,
+    };
+    if !eq.connections.contains(&connection) {
+        eq.connections.push(connection);
+    }
+    Ok((from_name, to_name))
+}
+
+/// Remove every edge from `from` to `to`. Returns how many were removed.
+pub fn disconnect(building: &mut Building, from: &str, to: &str) -> Result<usize, String> {
+    let (from_id, _) = resolve(building, from)?;
+    // The target may already be gone; fall back to the raw query as an id
+    let to_id = resolve(building, to)
+        .map(|(id, _)| id)
+        .unwrap_or_else(|_| to.to_string());
+    let eq = building
+        .find_equipment_mut(&from_id)
+        .ok_or_else(|| format!("Equipment '{}' not found", from))?;
+    let before = eq.connections.len();
+    eq.connections.retain(|c| c.to != to_id);
+    Ok(before - eq.connections.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn plant() -> Building {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        for (name, kind) in [
+            ("Panel-B", EquipmentType::Electrical),
+            ("AHU-1", EquipmentType::HVAC),
+            ("VAV-3-01", EquipmentType::HVAC),
+            ("VAV-3-02", EquipmentType::HVAC),
+        ] {
+            room.add_equipment(Equipment::new(name.into(), String::new(), kind));
+        }
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn test_impact_follows_edges_both_ways() {
+        let mut building = plant();
+        connect(&mut building, "Panel-B", Relation::Powers, "AHU-1").unwrap();
+        connect(&mut building, "AHU-1", Relation::Feeds, "VAV-3-01").unwrap();
+        connect(&mut building, "AHU-1", Relation::Feeds, "VAV-3-02").unwrap();
+        // A cycle must not loop forever
+        connect(&mut building, "VAV-3-02", Relation::Controls, "AHU-1").unwrap();
+        assert!(connect(&mut building, "AHU-1", Relation::Feeds, "AHU-1").is_err());
+
+        let graph = SystemGraph::new(&building);
+        let panel = building.find_equipment("Panel-B").unwrap();
+        let down: Vec<(String, usize)> = graph
+            .downstream(&panel.id)
+            .into_iter()
+            .map(|n| (n.name, n.depth))
+            .collect();
+        assert_eq!(
+            down,
+            [
+                ("AHU-1".to_string(), 1),
+                ("VAV-3-01".to_string(), 2),
+                ("VAV-3-02".to_string(), 2)
+            ]
+        );
+
+        let vav = building.find_equipment("VAV-3-01").unwrap();
+        let up: Vec<String> = graph
+            .upstream(&vav.id)
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(up, ["AHU-1", "Panel-B", "VAV-3-02"]);
+    }
+
+    #[test]
+    fn test_connections_persist_and_disconnect() {
+        use crate::persistence::PersistenceManager;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistenceManager::at(dir.path());
+        let mut building = plant();
+        connect(&mut building, "AHU-1", Relation::Feeds, "VAV-3-01").unwrap();
+        connect(&mut building, "AHU-1", Relation::Feeds, "VAV-3-01").unwrap();
+        store.save_building_data(&building).unwrap();
+        let yaml = std::fs::read_to_string(dir.path().join("building.yaml")).unwrap();
+        assert!(yaml.contains("relation: feeds"));
+
+        let mut building = store.load_building_data().unwrap();
+        assert_eq!(SystemGraph::new(&building).edge_count(), 1);
+        assert_eq!(disconnect(&mut building, "AHU-1", "VAV-3-01").unwrap(), 1);
+        assert_eq!(SystemGraph::new(&building).edge_count(), 0);
+    }
+}
@@ -0,0 +1,413 @@
+//! Templates for standard room and equipment types.
+//!
+//! A template is a YAML file of defaults that `arx equipment add --template`
+//! and `arx room create --template` start from. Equipment templates give the
+//! type, properties, sensor mappings with thresholds, and maintenance /
+//! inspection schedules; room templates give the room type, dimensions,
+//! properties, and the equipment every such room gets:
+//!
+//! ```yaml
+//! # .arx/templates/ahu-standard.yaml
+//! kind: equipment
+//! equipment_type: HVAC
+//! properties:
+//!   filter_type: MERV-13
+//! sensors:
+//!   - sensor_type: temperature
+//!     thresholds:
+//!       temperature: { min: 10.0, max: 18.0 }
+//! maintenance:
+//!   - name: ahu-filter-change
+//!     every_runtime_hours: 2000
+//! ```
+//!
+//! The file stem is the template name. Projects override or add templates
+//! in `.arx/templates/`; `ahu-standard`, `electrical-panel`, and `classroom`
+//! are built in. Instantiated equipment records its template in the
+//! `template` property, which the template's schedules select on once they
+//! are merged into `.arx/maintenance.yaml`.
+
+use super::runtime::{InspectionSchedule, MaintenanceSchedule, MaintenanceSchedules};
+use super::equipment::{SensorMapping, ThresholdConfig};
+use super::{Equipment, EquipmentType, Room};
+use crate::validation::EquipmentSelector;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::path::Path;
+
+/// Project templates directory.
+pub const TEMPLATES_DIR: &str = ".arx/templates";
+
+/// Equipment property naming the template it was created from.
+pub const TEMPLATE_PROPERTY: &str = "template";
+
+/// Templates shipped with arx: `(name, yaml)`.
+pub const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "ahu-standard",
+        include_str!("../../templates/types/ahu-standard.yaml"),
+    ),
+    (
+        "classroom",
+        include_str!("../../templates/types/classroom.yaml"),
+    ),
+    (
+        "electrical-panel",
+        include_str!("../../templates/types/electrical-panel.yaml"),
+    ),
+];
+
+/// What a template instantiates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateKind {
+    Equipment,
+    Room,
+}
+
+/// A sensor mapping to create on instantiation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorTemplate {
+    pub sensor_type: String,
+    /// Sensor id; `{name}` is replaced by the equipment name. Default:
+    /// `<equipment name>-<sensor_type>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub thresholds: BTreeMap<String, ThresholdConfig>,
+}
+
+/// Equipment a room template places in every room made from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomEquipment {
+    /// Appended to the room name: `<room>-<name>`
+    pub name: String,
+    /// Equipment template to start from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_type: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sensors: Vec<SensorTemplate>,
+}
+
+/// One template file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    /// File stem
+    #[serde(skip)]
+    pub name: String,
+    pub kind: TemplateKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+    /// Equipment type as displayed (`HVAC`, custom names become `Other`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sensors: Vec<SensorTemplate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maintenance: Vec<MaintenanceSchedule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inspections: Vec<InspectionSchedule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_type: Option<String>,
+    /// `WIDTHxDEPTHxHEIGHT` in meters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equipment: Vec<RoomEquipment>,
+}
+
+impl Template {
+    pub fn parse(name: &str, yaml: &str) -> Result<Self, Box<dyn Error>> {
+        let mut template: Template = serde_yaml::from_str(yaml)
+            .map_err(|e| format!("Invalid template '{}': {}", name, e))?;
+        template.name = name.to_string();
+        match template.kind {
+            TemplateKind::Equipment if template.equipment_type.is_none() => {
+                return Err(format!("Template '{}' needs equipment_type", name).into());
+            }
+            TemplateKind::Room if template.room_type.is_none() => {
+                return Err(format!("Template '{}' needs room_type", name).into());
+            }
+            _ => {}
+        }
+        Ok(template)
+    }
+
+    /// Equipment named `name` with this template's type, properties, and
+    /// sensor mappings.
+    pub fn instantiate_equipment(&self, name: &str) -> Result<Equipment, Box<dyn Error>> {
+        if self.kind != TemplateKind::Equipment {
+            return Err(format!("'{}' is a room template", self.name).into());
+        }
+        let equipment_type: EquipmentType = self.equipment_type.as_deref().unwrap_or("").parse()?;
+        let mut equipment = Equipment::new(name.to_string(), "/".to_string(), equipment_type);
+        equipment.properties = to_map(&self.properties);
+        equipment
+            .properties
+            .insert(TEMPLATE_PROPERTY.to_string(), self.name.clone());
+        equipment.sensor_mappings = sensor_mappings(name, &self.sensors);
+        Ok(equipment)
+    }
+
+    /// Add this template's properties and equipment to `room`; entries that
+    /// name an equipment template are looked up in `templates`. The room
+    /// type and dimensions are the caller's (they can be overridden).
+    pub fn instantiate_room_equipment(
+        &self,
+        room: &mut Room,
+        templates: &[Template],
+    ) -> Result<(), Box<dyn Error>> {
+        if self.kind != TemplateKind::Room {
+            return Err(format!("'{}' is an equipment template", self.name).into());
+        }
+        for (key, value) in &self.properties {
+            room.properties.entry(key.clone()).or_insert(value.clone());
+        }
+        for entry in &self.equipment {
+            let name = format!("{}-{}", room.name, entry.name);
+            let mut equipment = match &entry.template {
+                Some(template) => find_in(templates, template)?.instantiate_equipment(&name)?,
+                None => Equipment::new(
+                    name.clone(),
+                    "/".to_string(),
+                    entry
+                        .equipment_type
+                        .as_deref()
+                        .ok_or_else(|| {
+                            format!(
+                                "Template '{}': equipment '{}' needs equipment_type or template",
+                                self.name, entry.name
+                            )
+                        })?
+                        .parse()?,
+                ),
+            };
+            equipment.properties.extend(to_map(&entry.properties));
+            if let Some(sensors) = sensor_mappings(&name, &entry.sensors) {
+                equipment
+                    .sensor_mappings
+                    .get_or_insert_with(Vec::new)
+                    .extend(sensors);
+            }
+            equipment.room_id = Some(room.id.clone());
+            room.add_equipment(equipment);
+        }
+        Ok(())
+    }
+
+    /// Equipment templates the room template's entries refer to.
+    pub fn equipment_templates<'a>(&self, templates: &'a [Template]) -> Vec<&'a Template> {
+        self.equipment
+            .iter()
+            .filter_map(|e| e.template.as_deref())
+            .filter_map(|name| find_in(templates, name).ok())
+            .collect()
+    }
+
+    /// Merge this template's schedules into `{base}/.arx/maintenance.yaml`,
+    /// selecting equipment made from it. Schedules whose name is already
+    /// there are left alone; returns the names added. In a dry run the
+    /// write is recorded instead of made.
+    pub fn register_schedules(&self, base: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+        if self.maintenance.is_empty() && self.inspections.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut schedules = MaintenanceSchedules::load_at(base)?;
+        let selector = EquipmentSelector {
+            template: Some(self.name.clone()),
+            ..Default::default()
+        };
+        let mut added = Vec::new();
+        for schedule in &self.maintenance {
+            if schedules.get(&schedule.name).is_none() {
+                schedules.schedules.push(MaintenanceSchedule {
+                    selector: selector.clone(),
+                    ..schedule.clone()
+                });
+                added.push(schedule.name.clone());
+            }
+        }
+        for inspection in &self.inspections {
+            if !schedules
+                .inspections
+                .iter()
+                .any(|i| i.name == inspection.name)
+            {
+                schedules.inspections.push(InspectionSchedule {
+                    selector: selector.clone(),
+                    ..inspection.clone()
+                });
+                added.push(inspection.name.clone());
+            }
+        }
+        if !added.is_empty() {
+            let path = base.join(super::runtime::MAINTENANCE_FILE);
+            if super::operations::dry_run::is_dry_run() {
+                super::operations::dry_run::record_file_write(
+                    super::runtime::MAINTENANCE_FILE,
+                    path.exists(),
+                );
+                return Ok(added);
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, crate::yaml::to_canonical_yaml(&schedules)?)?;
+        }
+        Ok(added)
+    }
+}
+
+fn to_map(properties: &BTreeMap<String, String>) -> HashMap<String, String> {
+    properties
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+fn sensor_mappings(name: &str, sensors: &[SensorTemplate]) -> Option<Vec<SensorMapping>> {
+    if sensors.is_empty() {
+        return None;
+    }
+    Some(
+        sensors
+            .iter()
+            .map(|s| SensorMapping {
+                sensor_id: match &s.id {
+                    Some(id) => id.replace("{name}", name),
+                    None => format!("{}-{}", name, s.sensor_type),
+                },
+                sensor_type: s.sensor_type.clone(),
+                thresholds: s
+                    .thresholds
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+fn find_in<'a>(templates: &'a [Template], name: &str) -> Result<&'a Template, Box<dyn Error>> {
+    templates
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+            format!("No template '{}' (available: {})", name, names.join(", ")).into()
+        })
+}
+
+/// Built-in templates overlaid with `{base}/.arx/templates/*.yaml`, by name.
+pub fn load_templates(base: &Path) -> Result<Vec<Template>, Box<dyn Error>> {
+    let mut templates: Vec<Template> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|(name, yaml)| Template::parse(name, yaml))
+        .collect::<Result<_, _>>()?;
+    let dir = base.join(TEMPLATES_DIR);
+    if dir.is_dir() {
+        let mut paths: Vec<_> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let template = Template::parse(&name, &std::fs::read_to_string(&path)?)?;
+            templates.retain(|t| !t.name.eq_ignore_ascii_case(&name));
+            templates.push(template);
+        }
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// Template `name` from [`load_templates`].
+pub fn find_template(base: &Path, name: &str) -> Result<(Template, Vec<Template>), Box<dyn Error>> {
+    let templates = load_templates(base)?;
+    let template = find_in(&templates, name)?.clone();
+    Ok((template, templates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::RoomType;
+
+    #[test]
+    fn equipment_template_instantiates_and_registers_schedules_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ahu, _) = find_template(dir.path(), "AHU-standard").unwrap();
+        let equipment = ahu.instantiate_equipment("AHU-1").unwrap();
+        assert_eq!(equipment.equipment_type, EquipmentType::HVAC);
+        assert_eq!(equipment.properties[TEMPLATE_PROPERTY], "ahu-standard");
+        let sensors = equipment.sensor_mappings.as_ref().unwrap();
+        assert_eq!(sensors[0].sensor_id, "AHU-1-temperature");
+        assert_eq!(sensors[0].thresholds["temperature"].max, Some(18.0));
+
+        assert_eq!(
+            ahu.register_schedules(dir.path()).unwrap(),
+            ["ahu-filter-change", "ahu-belt-inspection"]
+        );
+        assert!(ahu.register_schedules(dir.path()).unwrap().is_empty());
+        let schedules = MaintenanceSchedules::load_at(dir.path()).unwrap();
+        let filter = schedules.get("ahu-filter-change").unwrap();
+        assert!(filter.selector.matches(&equipment));
+        assert!(!filter.selector.matches(&Equipment::default()));
+    }
+
+    #[test]
+    fn dry_run_records_schedules_without_writing() {
+        use crate::core::operations::DryRunGuard;
+        use crate::core::runtime::MAINTENANCE_FILE;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (ahu, _) = find_template(dir.path(), "ahu-standard").unwrap();
+        let guard = DryRunGuard::begin();
+        assert_eq!(ahu.register_schedules(dir.path()).unwrap().len(), 2);
+        let changes = guard.finish();
+        assert!(!dir.path().join(MAINTENANCE_FILE).exists());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].target, format!("file {}", MAINTENANCE_FILE));
+    }
+
+    #[test]
+    fn project_templates_override_builtins_and_fill_rooms() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = dir.path().join(TEMPLATES_DIR);
+        std::fs::create_dir_all(&templates).unwrap();
+        std::fs::write(
+            templates.join("classroom.yaml"),
+            "kind: room\nroom_type: Classroom\nproperties: {capacity: \"24\"}\nequipment:\n  - name: ahu\n    template: ahu-standard\n  - name: projector\n    equipment_type: AV\n",
+        )
+        .unwrap();
+        std::fs::write(templates.join("broken.yaml"), "kind: equipment\n").unwrap();
+        assert!(load_templates(dir.path()).is_err());
+        std::fs::remove_file(templates.join("broken.yaml")).unwrap();
+
+        let (classroom, all) = find_template(dir.path(), "classroom").unwrap();
+        let mut room = Room::new("101".into(), RoomType::Classroom);
+        classroom
+            .instantiate_room_equipment(&mut room, &all)
+            .unwrap();
+        assert_eq!(room.properties["capacity"], "24");
+        let names: Vec<&str> = room.equipment.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["101-ahu", "101-projector"]);
+        assert_eq!(
+            room.equipment[0].properties[TEMPLATE_PROPERTY],
+            "ahu-standard"
+        );
+        assert_eq!(classroom.equipment_templates(&all)[0].name, "ahu-standard");
+        assert!(classroom.instantiate_equipment("x").is_err());
+    }
+}
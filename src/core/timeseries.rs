@@ -0,0 +1,107 @@
+//! Sensor time series: range selection and downsampling.
+//!
+//! Storage lives in `persistence::sensors`; this module only works on
+//! samples already loaded, oldest first.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One timestamped value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub at: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// Aggregate of the samples in one downsampling window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bucket {
+    pub start: DateTime<Utc>,
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Latest value in the window
+    pub last: f64,
+}
+
+/// Samples with `from <= at < to`; either bound may be open.
+pub fn in_range(
+    samples: &[Sample],
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Vec<Sample> {
+    samples
+        .iter()
+        .filter(|s| from.is_none_or(|f| s.at >= f) && to.is_none_or(|t| s.at < t))
+        .copied()
+        .collect()
+}
+
+/// Group sorted `samples` into windows of `width` aligned to the Unix epoch
+/// (so hourly buckets start on the hour). Empty windows are omitted.
+pub fn downsample(samples: &[Sample], width: Duration) -> Vec<Bucket> {
+    let width_secs = width.num_seconds().max(1);
+    let mut buckets: Vec<Bucket> = Vec::new();
+    for sample in samples {
+        let secs = sample.at.timestamp();
+        let start_secs = secs - secs.rem_euclid(width_secs);
+        let start = Utc
+            .timestamp_opt(start_secs, 0)
+            .single()
+            .unwrap_or(sample.at);
+        match buckets.last_mut() {
+            Some(bucket) if bucket.start == start => {
+                bucket.mean += (sample.value - bucket.mean) / (bucket.count + 1) as f64;
+                bucket.count += 1;
+                bucket.min = bucket.min.min(sample.value);
+                bucket.max = bucket.max.max(sample.value);
+                bucket.last = sample.value;
+            }
+            _ => buckets.push(Bucket {
+                start,
+                count: 1,
+                min: sample.value,
+                max: sample.value,
+                mean: sample.value,
+                last: sample.value,
+            }),
+        }
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap() + Duration::minutes(minute)
+    }
+
+    #[test]
+    fn test_range_and_hourly_buckets() {
+        let samples: Vec<Sample> = [(0, 20.0), (20, 22.0), (59, 24.0), (75, 30.0), (130, 18.0)]
+            .iter()
+            .map(|(m, v)| Sample {
+                at: at(*m),
+                value: *v,
+            })
+            .collect();
+
+        let window = in_range(&samples, Some(at(20)), Some(at(130)));
+        assert_eq!(window.len(), 3);
+        assert_eq!(in_range(&samples, None, None).len(), 5);
+
+        let hourly = downsample(&samples, Duration::hours(1));
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].start, at(0));
+        assert_eq!(hourly[0].count, 3);
+        assert_eq!(hourly[0].min, 20.0);
+        assert_eq!(hourly[0].max, 24.0);
+        assert!((hourly[0].mean - 22.0).abs() < 1e-9);
+        assert_eq!(hourly[0].last, 24.0);
+        assert_eq!(hourly[1].start, at(60));
+        assert_eq!(hourly[2].start, at(120));
+    }
+}
@@ -0,0 +1,389 @@
+//! Digital twin snapshots: the whole building state as one canonical,
+//! content-hashed document, and structural diffs between two of them.
+//!
+//! A [`TwinSnapshot`] flattens the building into entities keyed by id
+//! (building, floors, wings, rooms, equipment), each holding its own fields
+//! without child collections plus, for equipment, the latest value of every
+//! mapped sensor. Object keys are sorted before hashing, so equal states hash
+//! equally wherever they are captured. A client that holds the snapshot with
+//! hash `H` can be brought up to date with [`TwinSnapshot::diff`] against `H`
+//! instead of the full document, and checks the result with
+//! [`TwinSnapshot::apply`].
+
+use super::Building;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Version of the snapshot document layout.
+pub const TWIN_FORMAT: u32 = 1;
+
+/// Latest reading of one sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SensorValue {
+    pub value: f64,
+    pub at: DateTime<Utc>,
+}
+
+/// One entity of the building, without its children.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwinEntity {
+    /// `building`, `floor`, `wing`, `room`, or `equipment`
+    pub kind: String,
+    /// Id of the containing entity (none for the building)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// The entity's own fields, keys sorted
+    pub data: Value,
+    /// Mapped sensor id → latest reading (equipment only)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sensors: BTreeMap<String, SensorValue>,
+}
+
+/// Canonical state of a building at one moment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwinSnapshot {
+    pub format: u32,
+    /// Hex SHA-256 of the canonical `entities` document
+    pub hash: String,
+    pub entities: BTreeMap<String, TwinEntity>,
+}
+
+/// Field-level update of one entity present in both snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityPatch {
+    /// New parent, when the entity moved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Fields added or changed, with their new values
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub set: BTreeMap<String, Value>,
+    /// Fields no longer present
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unset: Vec<String>,
+    /// Full replacement sensor map, when any reading changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensors: Option<BTreeMap<String, SensorValue>>,
+}
+
+/// Structural difference between two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwinDiff {
+    /// Hash of the snapshot the diff applies to
+    pub from: String,
+    /// Hash of the snapshot it produces
+    pub to: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub added: BTreeMap<String, TwinEntity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub changed: BTreeMap<String, EntityPatch>,
+}
+
+impl TwinDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// `value` with every object's keys in sorted order.
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<String, Value> =
+                map.into_iter().map(|(k, v)| (k, canonical(v))).collect();
+            Value::Object(sorted.into_iter().collect::<Map<String, Value>>())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical).collect()),
+        other => other,
+    }
+}
+
+/// Own fields of `entity` as canonical JSON, minus the `children` keys.
+fn entity_data<T: Serialize>(entity: &T, children: &[&str]) -> Value {
+    let mut value = serde_json::to_value(entity).unwrap_or(Value::Null);
+    if let Value::Object(map) = &mut value {
+        for key in children {
+            map.remove(*key);
+        }
+    }
+    canonical(value)
+}
+
+fn hash_entities(entities: &BTreeMap<String, TwinEntity>) -> String {
+    let document = canonical(serde_json::to_value(entities).unwrap_or(Value::Null));
+    format!("{:x}", Sha256::digest(document.to_string().as_bytes()))
+}
+
+impl TwinSnapshot {
+    /// Snapshot `building`; `latest` maps sensor ids to their last reading
+    /// and is matched against each equipment item's sensor mappings.
+    pub fn capture(building: &Building, latest: &BTreeMap<String, SensorValue>) -> Self {
+        let mut entities = BTreeMap::new();
+        let mut add = |id: &str, kind: &str, parent: Option<&str>, data: Value, sensors| {
+            entities.insert(
+                id.to_string(),
+                TwinEntity {
+                    kind: kind.to_string(),
+                    parent: parent.map(str::to_string),
+                    data,
+                    sensors,
+                },
+            );
+        };
+
+        let none = BTreeMap::new;
+        add(
+            &building.id,
+            "building",
+            None,
+            entity_data(building, &["floors"]),
+            none(),
+        );
+        let mut equipment = Vec::new();
+        for floor in &building.floors {
+            let data = entity_data(floor, &["wings", "equipment"]);
+            add(&floor.id, "floor", Some(&building.id), data, none());
+            equipment.extend(floor.equipment.iter().map(|eq| (eq, &floor.id)));
+            for wing in &floor.wings {
+                let data = entity_data(wing, &["rooms", "equipment"]);
+                add(&wing.id, "wing", Some(&floor.id), data, none());
+                equipment.extend(wing.equipment.iter().map(|eq| (eq, &wing.id)));
+                for room in &wing.rooms {
+                    let data = entity_data(room, &["equipment"]);
+                    add(&room.id, "room", Some(&wing.id), data, none());
+                    equipment.extend(room.equipment.iter().map(|eq| (eq, &room.id)));
+                }
+            }
+        }
+        for (eq, parent) in equipment {
+            let sensors = eq
+                .sensor_mappings
+                .iter()
+                .flatten()
+                .filter_map(|m| Some((m.sensor_id.clone(), *latest.get(&m.sensor_id)?)))
+                .collect();
+            add(
+                &eq.id,
+                "equipment",
+                Some(parent),
+                entity_data(eq, &[]),
+                sensors,
+            );
+        }
+
+        Self {
+            format: TWIN_FORMAT,
+            hash: hash_entities(&entities),
+            entities,
+        }
+    }
+
+    /// Whether `hash` matches the entities (e.g. after transfer).
+    pub fn verify(&self) -> bool {
+        hash_entities(&self.entities) == self.hash
+    }
+
+    /// Changes that turn `self` into `newer`.
+    pub fn diff(&self, newer: &TwinSnapshot) -> TwinDiff {
+        let mut diff = TwinDiff {
+            from: self.hash.clone(),
+            to: newer.hash.clone(),
+            added: BTreeMap::new(),
+            removed: Vec::new(),
+            changed: BTreeMap::new(),
+        };
+        for (id, old) in &self.entities {
+            match newer.entities.get(id) {
+                Some(new) if new == old => {}
+                Some(new) if new.kind == old.kind => {
+                    diff.changed.insert(id.clone(), patch(old, new));
+                }
+                Some(new) => {
+                    diff.removed.push(id.clone());
+                    diff.added.insert(id.clone(), new.clone());
+                }
+                None => diff.removed.push(id.clone()),
+            }
+        }
+        for (id, new) in &newer.entities {
+            if !self.entities.contains_key(id) {
+                diff.added.insert(id.clone(), new.clone());
+            }
+        }
+        diff
+    }
+
+    /// Apply `diff` to this snapshot. Fails when the diff was made against a
+    /// different snapshot or does not reproduce its target hash.
+    pub fn apply(&self, diff: &TwinDiff) -> Result<TwinSnapshot, String> {
+        if diff.from != self.hash {
+            return Err(format!(
+                "Diff applies to snapshot {}, not {}",
+                short(&diff.from),
+                short(&self.hash)
+            ));
+        }
+        let mut entities = self.entities.clone();
+        for id in &diff.removed {
+            entities.remove(id);
+        }
+        for (id, patch) in &diff.changed {
+            let entity = entities
+                .get_mut(id)
+                .ok_or_else(|| format!("Diff changes unknown entity {}", id))?;
+            if let Some(parent) = &patch.parent {
+                entity.parent = Some(parent.clone());
+            }
+            if let Value::Object(map) = &mut entity.data {
+                for key in &patch.unset {
+                    map.remove(key);
+                }
+                for (key, value) in &patch.set {
+                    map.insert(key.clone(), value.clone());
+                }
+            }
+            entity.data = canonical(std::mem::take(&mut entity.data));
+            if let Some(sensors) = &patch.sensors {
+                entity.sensors = sensors.clone();
+            }
+        }
+        for (id, entity) in &diff.added {
+            entities.insert(id.clone(), entity.clone());
+        }
+
+        let hash = hash_entities(&entities);
+        if hash != diff.to {
+            return Err(format!(
+                "Patched snapshot hashes to {}, expected {}",
+                short(&hash),
+                short(&diff.to)
+            ));
+        }
+        Ok(Self {
+            format: TWIN_FORMAT,
+            hash,
+            entities,
+        })
+    }
+}
+
+fn short(hash: &str) -> &str {
+    hash.get(..12).unwrap_or(hash)
+}
+
+fn patch(old: &TwinEntity, new: &TwinEntity) -> EntityPatch {
+    let mut patch = EntityPatch {
+        parent: (old.parent != new.parent)
+            .then(|| new.parent.clone())
+            .flatten(),
+        sensors: (old.sensors != new.sensors).then(|| new.sensors.clone()),
+        ..Default::default()
+    };
+    match (&old.data, &new.data) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, value) in after {
+                if before.get(key) != Some(value) {
+                    patch.set.insert(key.clone(), value.clone());
+                }
+            }
+            patch.unset = before
+                .keys()
+                .filter(|k| !after.contains_key(*k))
+                .cloned()
+                .collect();
+        }
+        _ => {
+            patch.set.insert("data".into(), new.data.clone());
+        }
+    }
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::equipment::SensorMapping;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Lobby".into(), RoomType::Office);
+        let mut eq = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        eq.sensor_mappings = Some(vec![SensorMapping {
+            sensor_id: "t-1".into(),
+            sensor_type: "temperature".into(),
+            thresholds: Default::default(),
+        }]);
+        room.add_equipment(eq);
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        building
+    }
+
+    fn reading(value: f64) -> BTreeMap<String, SensorValue> {
+        let at = DateTime::parse_from_rfc3339("2026-10-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        BTreeMap::from([("t-1".to_string(), SensorValue { value, at })])
+    }
+
+    #[test]
+    fn test_snapshot_is_canonical_and_hashed() {
+        let building = building();
+        let snapshot = TwinSnapshot::capture(&building, &reading(21.5));
+        assert_eq!(snapshot.entities.len(), 5);
+        assert!(snapshot.verify());
+        assert_eq!(snapshot, TwinSnapshot::capture(&building, &reading(21.5)));
+
+        let eq = &snapshot.entities[&building.floors[0].wings[0].rooms[0].equipment[0].id];
+        assert_eq!(eq.kind, "equipment");
+        assert_eq!(eq.sensors["t-1"].value, 21.5);
+        let room = &snapshot.entities[&building.floors[0].wings[0].rooms[0].id];
+        assert!(room.data.get("equipment").is_none());
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let back: TwinSnapshot = serde_json::from_str(&json).unwrap();
+        assert!(back.verify());
+        assert_ne!(
+            snapshot.hash,
+            TwinSnapshot::capture(&building, &reading(22.0)).hash
+        );
+    }
+
+    #[test]
+    fn test_diff_applies_to_reproduce_newer_snapshot() {
+        let before = building();
+        let old = TwinSnapshot::capture(&before, &reading(21.5));
+
+        let mut after = before.clone();
+        let wing = &mut after.floors[0].wings[0];
+        wing.rooms[0].name = "Main Lobby".into();
+        wing.add_room(Room::new("Lab".into(), RoomType::Laboratory));
+        let removed = after.floors[0].wings[0].rooms[0].equipment.remove(0);
+        after.floors[0].equipment.push(removed);
+        let new = TwinSnapshot::capture(&after, &reading(23.0));
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added.len(), 1);
+        assert!(diff.removed.is_empty());
+        let room_patch = &diff.changed[&before.floors[0].wings[0].rooms[0].id];
+        assert_eq!(room_patch.set["name"], "Main Lobby");
+        let eq_patch = &diff.changed[&before.floors[0].wings[0].rooms[0].equipment[0].id];
+        assert_eq!(
+            eq_patch.parent.as_deref(),
+            Some(before.floors[0].id.as_str())
+        );
+        assert_eq!(eq_patch.sensors.as_ref().unwrap()["t-1"].value, 23.0);
+
+        assert_eq!(old.apply(&diff).unwrap(), new);
+        assert!(new.apply(&diff).is_err());
+        assert!(old.diff(&old).is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+//! Equipment status history and availability.
+//!
+//! Every save that changes an equipment status appends a [`StatusTransition`]
+//! to the status-history log (`persistence::status_history`), with the save's
+//! provenance source as the cause (manual edit, sensor sync, IFC import, …).
+//! Equipment first appears in the log with no `from` status.
+//!
+//! [`uptime_report`] replays one item's transitions over a window:
+//!
+//! - `Active` and `Inactive` count as up (available, running or not);
+//! - `Maintenance` is planned downtime, `OutOfOrder` unplanned downtime;
+//! - `Unknown`, and time before the first transition, are not tracked.
+//!
+//! Availability is up time over tracked time. A failure is a transition into
+//! `OutOfOrder`; MTBF is up hours per failure and MTTR out-of-order hours per
+//! outage in the window.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::provenance::ProvenanceSource;
+use super::{Building, EquipmentStatus};
+
+/// One equipment status change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub equipment_id: String,
+    /// Equipment name when the change was recorded
+    pub equipment: String,
+    /// Previous status; absent when the equipment was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<EquipmentStatus>,
+    pub to: EquipmentStatus,
+    pub at: DateTime<Utc>,
+    pub cause: ProvenanceSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+}
+
+/// Status changes between `before` (what is stored) and `after` (what is
+/// being saved), including equipment added with its first status.
+pub fn status_transitions(
+    before: Option<&Building>,
+    after: &Building,
+    at: DateTime<Utc>,
+    cause: ProvenanceSource,
+    actor: Option<&str>,
+) -> Vec<StatusTransition> {
+    let old: HashMap<&str, EquipmentStatus> = before
+        .map(|b| {
+            b.get_all_equipment()
+                .into_iter()
+                .map(|eq| (eq.id.as_str(), eq.status))
+                .collect()
+        })
+        .unwrap_or_default();
+    after
+        .get_all_equipment()
+        .into_iter()
+        .filter(|eq| old.get(eq.id.as_str()) != Some(&eq.status))
+        .map(|eq| StatusTransition {
+            equipment_id: eq.id.clone(),
+            equipment: eq.name.clone(),
+            from: old.get(eq.id.as_str()).copied(),
+            to: eq.status,
+            at,
+            cause,
+            actor: actor.map(str::to_string),
+        })
+        .collect()
+}
+
+/// Availability of one piece of equipment over a window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UptimeReport {
+    pub equipment_id: String,
+    pub equipment: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// Hours with a known status
+    pub tracked_hours: f64,
+    pub up_hours: f64,
+    /// Hours in `Maintenance`
+    pub planned_downtime_hours: f64,
+    /// Hours `OutOfOrder`
+    pub unplanned_downtime_hours: f64,
+    /// Up time as a percentage of tracked time
+    pub availability_percent: Option<f64>,
+    /// Transitions into `OutOfOrder` inside the window
+    pub failures: usize,
+    pub mtbf_hours: Option<f64>,
+    pub mttr_hours: Option<f64>,
+    /// Transitions inside the window, oldest first
+    pub transitions: Vec<StatusTransition>,
+}
+
+fn hours_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    ((to - from).num_milliseconds().max(0) as f64) / 3_600_000.0
+}
+
+/// Replay `history` (one item's transitions, any order) over `start..end`.
+pub fn uptime_report(
+    equipment_id: &str,
+    equipment: &str,
+    history: &[StatusTransition],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> UptimeReport {
+    let mut history: Vec<&StatusTransition> = history.iter().collect();
+    history.sort_by_key(|t| t.at);
+
+    let mut state = history
+        .iter()
+        .take_while(|t| t.at <= start)
+        .last()
+        .map(|t| t.to);
+    let mut report = UptimeReport {
+        equipment_id: equipment_id.to_string(),
+        equipment: equipment.to_string(),
+        window_start: start,
+        window_end: end,
+        tracked_hours: 0.0,
+        up_hours: 0.0,
+        planned_downtime_hours: 0.0,
+        unplanned_downtime_hours: 0.0,
+        availability_percent: None,
+        failures: 0,
+        mtbf_hours: None,
+        mttr_hours: None,
+        transitions: Vec::new(),
+    };
+    let mut outages = usize::from(state == Some(EquipmentStatus::OutOfOrder));
+
+    let add = |state: Option<EquipmentStatus>, hours: f64, report: &mut UptimeReport| {
+        let bucket = match state {
+            Some(EquipmentStatus::Active | EquipmentStatus::Inactive) => &mut report.up_hours,
+            Some(EquipmentStatus::Maintenance) => &mut report.planned_downtime_hours,
+            Some(EquipmentStatus::OutOfOrder) => &mut report.unplanned_downtime_hours,
+            Some(EquipmentStatus::Unknown) | None => return,
+        };
+        *bucket += hours;
+        report.tracked_hours += hours;
+    };
+    let mut cursor = start;
+    for transition in history.into_iter().filter(|t| t.at > start && t.at < end) {
+        add(state, hours_between(cursor, transition.at), &mut report);
+        let failed = transition.to == EquipmentStatus::OutOfOrder
+            && state != Some(EquipmentStatus::OutOfOrder);
+        if failed {
+            report.failures += 1;
+            outages += 1;
+        }
+        state = Some(transition.to);
+        cursor = transition.at;
+        report.transitions.push(transition.clone());
+    }
+    add(state, hours_between(cursor, end), &mut report);
+
+    if report.tracked_hours > 0.0 {
+        report.availability_percent = Some(report.up_hours / report.tracked_hours * 100.0);
+    }
+    if report.failures > 0 {
+        report.mtbf_hours = Some(report.up_hours / report.failures as f64);
+    }
+    if outages > 0 {
+        report.mttr_hours = Some(report.unplanned_downtime_hours / outages as f64);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor};
+    use chrono::{Duration, TimeZone};
+
+    fn t(hour: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::hours(hour)
+    }
+
+    fn change(from: Option<EquipmentStatus>, to: EquipmentStatus, at: i64) -> StatusTransition {
+        StatusTransition {
+            equipment_id: "eq-1".into(),
+            equipment: "AHU-1".into(),
+            from,
+            to,
+            at: t(at),
+            cause: ProvenanceSource::Manual,
+            actor: None,
+        }
+    }
+
+    #[test]
+    fn transitions_cover_added_and_changed_equipment() {
+        let mut before = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.equipment.push(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        before.add_floor(floor);
+
+        let mut after = before.clone();
+        after.floors[0].equipment[0].status = EquipmentStatus::OutOfOrder;
+        after.floors[0].equipment.push(Equipment::new(
+            "P-1".into(),
+            String::new(),
+            EquipmentType::Plumbing,
+        ));
+        let changes =
+            status_transitions(Some(&before), &after, t(0), ProvenanceSource::Sensor, None);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].equipment, "AHU-1");
+        assert_eq!(changes[0].to, EquipmentStatus::OutOfOrder);
+        assert_eq!(changes[0].cause, ProvenanceSource::Sensor);
+        assert_eq!(changes[1].from, None);
+        assert!(
+            status_transitions(Some(&after), &after, t(0), ProvenanceSource::Manual, None)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn report_splits_up_and_down_time_over_the_window() {
+        use EquipmentStatus::*;
+        let history = [
+            change(None, Active, 0),
+            change(Some(Active), OutOfOrder, 40),
+            change(Some(OutOfOrder), Active, 44),
+            change(Some(Active), Maintenance, 60),
+            change(Some(Maintenance), Inactive, 62),
+            change(Some(Inactive), OutOfOrder, 90),
+            change(Some(OutOfOrder), Active, 120),
+        ];
+        let report = uptime_report("eq-1", "AHU-1", &history, t(20), t(100));
+
+        assert_eq!(report.tracked_hours, 80.0);
+        assert_eq!(report.up_hours, 20.0 + 16.0 + 28.0);
+        assert_eq!(report.planned_downtime_hours, 2.0);
+        assert_eq!(report.unplanned_downtime_hours, 4.0 + 10.0);
+        assert_eq!(report.availability_percent, Some(80.0));
+        assert_eq!(report.failures, 2);
+        assert_eq!(report.mtbf_hours, Some(32.0));
+        assert_eq!(report.mttr_hours, Some(7.0));
+        assert_eq!(report.transitions.len(), 5);
+
+        // Before the first transition nothing is tracked
+        let early = uptime_report("eq-1", "AHU-1", &history[..1], t(-10), t(10));
+        assert_eq!(early.tracked_hours, 10.0);
+        assert_eq!(early.availability_percent, Some(100.0));
+        assert_eq!(early.mtbf_hours, None);
+    }
+}
@@ -0,0 +1,50 @@
+//! Vendor IFC property sets kept verbatim across import, edits, and export.
+//!
+//! Property sets the IFC mapping does not understand (anything outside the
+//! `Pset_Arx*` family) are stored per entity under `vendor_extensions`,
+//! grouped by property set, with the IFC value type they arrived with:
+//!
+//! ```yaml
+//! vendor_extensions:
+//!   Pset_AcmeAirHandler:
+//!     FanCurve: { value: C-12, type: IFCLABEL }
+//!     DesignAirflow: { value: "4.2", type: IFCVOLUMETRICFLOWRATEMEASURE }
+//! ```
+//!
+//! The section is a soft schema: values are plain strings and may be edited
+//! by hand; IFC export writes each property set back under its own name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One vendor property value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VendorProperty {
+    pub value: String,
+    /// IFC measure / value type (`IFCLABEL`, `IFCREAL`, …); label when absent
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub ifc_type: Option<String>,
+}
+
+/// Vendor properties keyed by property set, then property name.
+pub type VendorExtensions = BTreeMap<String, BTreeMap<String, VendorProperty>>;
+
+/// Value of `property` in vendor property set `pset`, if present.
+pub fn vendor_value<'a>(
+    extensions: &'a VendorExtensions,
+    pset: &str,
+    property: &str,
+) -> Option<&'a str> {
+    extensions
+        .get(pset)
+        .and_then(|props| props.get(property))
+        .map(|p| p.value.as_str())
+}
+
+/// Fill property sets missing from `target` with those in `other`; sets
+/// already present in `target` win whole.
+pub fn merge_missing(target: &mut VendorExtensions, other: &VendorExtensions) {
+    for (pset, props) in other {
+        target.entry(pset.clone()).or_insert_with(|| props.clone());
+    }
+}
@@ -1,10 +1,10 @@
 //! Wing data structure and implementation
 
+use super::clock;
 use super::{Equipment, Room, Anchor};
 use super::domain::ArxAddress;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 /// Represents a wing on a floor
 #[derive(Debug, Clone)]
@@ -104,7 +104,7 @@ impl Wing {
     /// ```
     pub fn new(name: String) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: clock::new_id(),
             name,
             rooms: Vec::new(),
             equipment: Vec::new(),
@@ -0,0 +1,566 @@
+//! Hardware-in-the-loop harness for the sensor ingestion path.
+//!
+//! Simulates a fleet of field devices (ESP32-class temperature, occupancy, CO2
+//! and humidity nodes) posting `sensor.report` payloads against a scratch
+//! workspace, then checks what landed on disk:
+//!
+//! - every well-formed report is accepted and every malformed one rejected,
+//! - the sensor inbox holds each device with the right reading count and type,
+//! - the trace log holds exactly the valued readings,
+//! - devices without a mapping show up as unmapped, mapped ones don't,
+//! - ingestion never touches `building.yaml` or Git history.
+//!
+//! Reports go through the real agent stack: [`Transport::Http`] serves
+//! [`agent_router`] on a loopback port and POSTs JSON-RPC to `/rpc`;
+//! [`Transport::Dispatcher`] skips the socket and calls the dispatcher
+//! directly. The agent has no MQTT, CoAP or mesh ingestion in this build, so
+//! there is nothing to drive for those yet; gateways for them should add a
+//! transport here when they land.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+
+use crate::agent::auth::{all_capabilities, generate_did_key, TokenState};
+use crate::agent::dispatcher::{dispatch, AgentState};
+use crate::agent::protocol::{JsonRpcRequest, JsonRpcResponse};
+use crate::agent::server::agent_router;
+use crate::core::operations::{bind_sensor, SensorBindTarget};
+use crate::core::{Building, Floor, Room, RoomType, Wing};
+use crate::persistence::sensors::{load_seen_sensors, load_sensor_readings, unmapped_sensors};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+
+/// How reports reach the agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// JSON-RPC over HTTP against the agent router on a loopback port
+    Http,
+    /// Straight into the dispatcher, no socket
+    Dispatcher,
+}
+
+impl std::str::FromStr for Transport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "http" => Ok(Self::Http),
+            "dispatcher" | "direct" => Ok(Self::Dispatcher),
+            "mqtt" | "coap" | "mesh" => Err(format!(
+                "no {} ingestion in this build; use http or dispatcher",
+                s
+            )),
+            _ => Err(format!("unknown transport '{}' (http, dispatcher)", s)),
+        }
+    }
+}
+
+/// Simulated device firmware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceProfile {
+    Temperature,
+    Occupancy,
+    Co2,
+    Humidity,
+}
+
+impl DeviceProfile {
+    const ALL: [DeviceProfile; 4] = [
+        DeviceProfile::Temperature,
+        DeviceProfile::Occupancy,
+        DeviceProfile::Co2,
+        DeviceProfile::Humidity,
+    ];
+
+    pub fn sensor_type(self) -> &'static str {
+        match self {
+            Self::Temperature => "temperature",
+            Self::Occupancy => "occupancy",
+            Self::Co2 => "co2",
+            Self::Humidity => "humidity",
+        }
+    }
+
+    fn initial(self, rng: &mut StdRng) -> f64 {
+        match self {
+            Self::Temperature => rng.gen_range(19.0..24.0),
+            Self::Occupancy => rng.gen_range(0..6) as f64,
+            Self::Co2 => rng.gen_range(420.0..700.0),
+            Self::Humidity => rng.gen_range(30.0..55.0),
+        }
+    }
+
+    /// Next reading as a bounded random walk, rounded like the firmware does.
+    fn step(self, previous: f64, rng: &mut StdRng) -> f64 {
+        match self {
+            Self::Temperature => {
+                ((previous + rng.gen_range(-0.3..0.3)).clamp(15.0, 30.0) * 10.0).round() / 10.0
+            }
+            Self::Occupancy => (previous + rng.gen_range(-2..=2) as f64).clamp(0.0, 40.0),
+            Self::Co2 => (previous + rng.gen_range(-25.0..40.0))
+                .clamp(400.0, 2500.0)
+                .round(),
+            Self::Humidity => (previous + rng.gen_range(-1.0..1.0))
+                .clamp(20.0, 80.0)
+                .round(),
+        }
+    }
+}
+
+/// Payload defects seen from real firmware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defect {
+    /// No `sensor_id` (must be rejected)
+    MissingId,
+    /// `sensor_id` longer than the inbox accepts (must be rejected)
+    OversizedId,
+    /// Value sent as a string: accepted into the inbox, kept out of the trace
+    StringValue,
+}
+
+impl Defect {
+    const ALL: [Defect; 3] = [Defect::MissingId, Defect::OversizedId, Defect::StringValue];
+
+    fn rejected(self) -> bool {
+        !matches!(self, Self::StringValue)
+    }
+}
+
+/// Fleet size and behaviour.
+#[derive(Debug, Clone)]
+pub struct HilConfig {
+    pub devices: usize,
+    pub readings_per_device: usize,
+    pub transport: Transport,
+    /// Every n-th report carries a [`Defect`] (0 disables)
+    pub defect_every: usize,
+    /// Reports in flight at once
+    pub concurrency: usize,
+    pub seed: u64,
+}
+
+impl Default for HilConfig {
+    fn default() -> Self {
+        Self {
+            devices: 12,
+            readings_per_device: 10,
+            transport: Transport::Http,
+            defect_every: 7,
+            concurrency: 8,
+            seed: 1,
+        }
+    }
+}
+
+/// One simulated device in the fleet.
+#[derive(Debug, Clone)]
+pub struct Device {
+    pub sensor_id: String,
+    pub profile: DeviceProfile,
+    /// Bound to a room in the fixture building
+    pub mapped: bool,
+}
+
+/// A payload plus what the harness expects from it.
+#[derive(Debug, Clone)]
+struct Report {
+    device: usize,
+    params: Value,
+    defect: Option<Defect>,
+    value: Option<f64>,
+}
+
+/// Outcome of [`run`].
+#[derive(Debug, Clone, Default)]
+pub struct HilReport {
+    pub workspace: PathBuf,
+    pub sent: usize,
+    pub accepted: usize,
+    pub rejected: usize,
+    pub elapsed: Duration,
+    /// Empty when every check passed
+    pub failures: Vec<String>,
+}
+
+impl HilReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// The simulated fleet: profiles rotate, every other device is mapped.
+pub fn fleet(config: &HilConfig) -> Vec<Device> {
+    (0..config.devices)
+        .map(|i| {
+            let profile = DeviceProfile::ALL[i % DeviceProfile::ALL.len()];
+            Device {
+                sensor_id: format!("esp32-{:06x}-{}", 0xa000 + i, profile.sensor_type()),
+                profile,
+                mapped: i % 2 == 0,
+            }
+        })
+        .collect()
+}
+
+fn reports(config: &HilConfig, devices: &[Device]) -> Vec<Report> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut last: Vec<f64> = devices
+        .iter()
+        .map(|d| d.profile.initial(&mut rng))
+        .collect();
+    let mut out = Vec::new();
+    for _ in 0..config.readings_per_device {
+        for (i, device) in devices.iter().enumerate() {
+            let value = device.profile.step(last[i], &mut rng);
+            last[i] = value;
+            let n = out.len() + 1;
+            let defect = (config.defect_every > 0 && n % config.defect_every == 0)
+                .then(|| Defect::ALL[(n / config.defect_every) % Defect::ALL.len()]);
+            let params = match defect {
+                None => json!({
+                    "sensor_id": device.sensor_id,
+                    "sensor_type": device.profile.sensor_type(),
+                    "value": value,
+                }),
+                Some(Defect::MissingId) => json!({
+                    "sensor_type": device.profile.sensor_type(),
+                    "value": value,
+                }),
+                Some(Defect::OversizedId) => json!({
+                    "sensor_id": format!("{}-{}", device.sensor_id, "x".repeat(200)),
+                    "value": value,
+                }),
+                Some(Defect::StringValue) => json!({
+                    "sensor_id": device.sensor_id,
+                    "sensor_type": device.profile.sensor_type(),
+                    "value": value.to_string(),
+                }),
+            };
+            out.push(Report {
+                device: i,
+                params,
+                defect,
+                value: (defect.is_none()).then_some(value),
+            });
+        }
+    }
+    out
+}
+
+fn git(base: &Path, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(base)
+        .args(args)
+        .output()
+        .context("running git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Git repository with a committed building that maps the fleet's even devices.
+pub fn prepare_workspace(base: &Path, devices: &[Device]) -> Result<()> {
+    git(base, &["init", "-q"])?;
+    git(base, &["config", "user.name", "arx hil"])?;
+    git(base, &["config", "user.email", "hil@arxos.invalid"])?;
+
+    let mut building = Building::new("HIL Test Site".into(), "/hil".into());
+    let mut floor = Floor::new("Ground".into(), 0);
+    let mut wing = Wing::new("East".into());
+    let rooms: Vec<Room> = ["Lab", "Office", "Lobby"]
+        .iter()
+        .map(|name| Room::new(name.to_string(), RoomType::Office))
+        .collect();
+    let room_ids: Vec<String> = rooms.iter().map(|r| r.id.clone()).collect();
+    for room in rooms {
+        wing.add_room(room);
+    }
+    floor.add_wing(wing);
+    building.add_floor(floor);
+    for (i, device) in devices.iter().filter(|d| d.mapped).enumerate() {
+        bind_sensor(
+            &mut building,
+            &device.sensor_id,
+            device.profile.sensor_type(),
+            &SensorBindTarget::Room(room_ids[i % room_ids.len()].clone()),
+            None,
+        )
+        .map_err(|e| anyhow!(e))?;
+    }
+    crate::ingest::persist_building_at(base, building, true, Some("HIL fixture"))
+        .map_err(|e| anyhow!("persisting fixture: {}", e))?;
+    Ok(())
+}
+
+fn agent_state(base: &Path, token: &str) -> Arc<AgentState> {
+    Arc::new(AgentState {
+        repo_root: base.to_path_buf(),
+        token: Arc::new(Mutex::new(TokenState::new(
+            token.to_string(),
+            all_capabilities(),
+        ))),
+        metrics: Arc::new(crate::agent::observability::AgentMetrics::new()),
+        reload_handle: None,
+        sessions: Default::default(),
+    })
+}
+
+fn request(seq: usize, params: Value) -> JsonRpcRequest {
+    JsonRpcRequest {
+        jsonrpc: "2.0".into(),
+        method: "sensor.report".into(),
+        params: Some(params),
+        id: Some(json!(seq)),
+    }
+}
+
+/// Send every report; returns whether each was accepted (in report order).
+async fn deliver(
+    config: &HilConfig,
+    state: Arc<AgentState>,
+    token: &str,
+    reports: &[Report],
+) -> Result<Vec<bool>> {
+    use futures_util::stream::{self, StreamExt};
+
+    let endpoint = match config.transport {
+        Transport::Http => {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+            let addr = listener.local_addr()?;
+            let app = agent_router(state.clone());
+            tokio::spawn(async move {
+                let _ = axum::serve(listener, app).await;
+            });
+            Some(format!("http://{}/rpc", addr))
+        }
+        Transport::Dispatcher => None,
+    };
+    let client = reqwest::Client::new();
+
+    let results = stream::iter(reports.iter().enumerate())
+        .map(|(seq, report)| {
+            let state = state.clone();
+            let client = client.clone();
+            let endpoint = endpoint.clone();
+            let req = request(seq, report.params.clone());
+            async move {
+                let response: JsonRpcResponse = match endpoint {
+                    Some(url) => {
+                        client
+                            .post(url)
+                            .bearer_auth(token)
+                            .json(&req)
+                            .send()
+                            .await?
+                            .error_for_status()?
+                            .json()
+                            .await?
+                    }
+                    None => dispatch(state, req).await,
+                };
+                Ok::<_, anyhow::Error>(response.error.is_none())
+            }
+        })
+        .buffered(config.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    results.into_iter().collect()
+}
+
+/// Run the fleet against a fresh workspace at `base` and check the results.
+pub async fn run(base: &Path, config: &HilConfig) -> Result<HilReport> {
+    let devices = fleet(config);
+    prepare_workspace(base, &devices)?;
+    let head_before = git(base, &["rev-parse", "HEAD"])?;
+    let yaml_before = std::fs::read(base.join(BUILDING_YAML))?;
+
+    let token = generate_did_key();
+    let state = agent_state(base, &token);
+    let reports = reports(config, &devices);
+    let started = Instant::now();
+    let accepted = deliver(config, state, &token, &reports).await?;
+
+    let mut report = HilReport {
+        workspace: base.to_path_buf(),
+        sent: reports.len(),
+        accepted: accepted.iter().filter(|a| **a).count(),
+        rejected: accepted.iter().filter(|a| !**a).count(),
+        elapsed: started.elapsed(),
+        failures: Vec::new(),
+    };
+    let failures = &mut report.failures;
+
+    // Acceptance matches each payload's defect class
+    for (seq, (r, ok)) in reports.iter().zip(&accepted).enumerate() {
+        let expect = !r.defect.is_some_and(Defect::rejected);
+        if *ok != expect {
+            failures.push(format!(
+                "report #{} ({}): expected {}, got {}",
+                seq,
+                r.defect
+                    .map_or("well-formed".to_string(), |d| format!("{:?}", d)),
+                if expect { "accepted" } else { "rejected" },
+                if *ok { "accepted" } else { "rejected" }
+            ));
+        }
+    }
+
+    // Inbox: one entry per device, reading count and type as sent
+    let mut expected_counts: BTreeMap<&str, u64> = BTreeMap::new();
+    for r in reports
+        .iter()
+        .filter(|r| !r.defect.is_some_and(Defect::rejected))
+    {
+        *expected_counts
+            .entry(devices[r.device].sensor_id.as_str())
+            .or_default() += 1;
+    }
+    let seen = load_seen_sensors(base)?;
+    if seen.len() != expected_counts.len() {
+        failures.push(format!(
+            "inbox has {} sensor(s), expected {}",
+            seen.len(),
+            expected_counts.len()
+        ));
+    }
+    for device in &devices {
+        let expected = expected_counts
+            .get(device.sensor_id.as_str())
+            .copied()
+            .unwrap_or(0);
+        match seen.iter().find(|s| s.sensor_id == device.sensor_id) {
+            Some(s) => {
+                if s.readings != expected {
+                    failures.push(format!(
+                        "{}: {} reading(s) in inbox, sent {}",
+                        device.sensor_id, s.readings, expected
+                    ));
+                }
+                if s.sensor_type.as_deref() != Some(device.profile.sensor_type()) {
+                    failures.push(format!(
+                        "{}: inbox type {:?}, expected {}",
+                        device.sensor_id,
+                        s.sensor_type,
+                        device.profile.sensor_type()
+                    ));
+                }
+            }
+            None if expected > 0 => {
+                failures.push(format!("{}: missing from inbox", device.sensor_id))
+            }
+            None => {}
+        }
+    }
+
+    // Trace log: exactly the valued readings, per device in send order
+    let trace = load_sensor_readings(base)?;
+    let valued = reports.iter().filter(|r| r.value.is_some()).count();
+    if trace.len() != valued {
+        failures.push(format!(
+            "trace log has {} reading(s), expected {}",
+            trace.len(),
+            valued
+        ));
+    }
+    let mut sent_values: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    for r in &reports {
+        if let Some(v) = r.value {
+            sent_values
+                .entry(devices[r.device].sensor_id.as_str())
+                .or_default()
+                .push(v);
+        }
+    }
+    for (sensor_id, mut values) in sent_values {
+        let mut logged: Vec<f64> = trace
+            .iter()
+            .filter(|t| t.sensor_id == sensor_id)
+            .map(|t| t.value)
+            .collect();
+        // Concurrent delivery may reorder a device's reports; compare as multisets
+        values.sort_by(f64::total_cmp);
+        logged.sort_by(f64::total_cmp);
+        if values != logged {
+            failures.push(format!("{}: trace values differ from sent", sensor_id));
+        }
+    }
+
+    // Mapping: only the unmapped half of the fleet is reported as unmapped
+    let building = load_building_at(base).map_err(|e| anyhow!("{}", e))?;
+    let unmapped: Vec<String> = unmapped_sensors(base, &building)?
+        .into_iter()
+        .map(|s| s.sensor_id)
+        .collect();
+    for device in &devices {
+        let listed = unmapped.contains(&device.sensor_id);
+        if device.mapped && listed {
+            failures.push(format!(
+                "{}: mapped but listed as unmapped",
+                device.sensor_id
+            ));
+        }
+        if !device.mapped && !listed && expected_counts.contains_key(device.sensor_id.as_str()) {
+            failures.push(format!("{}: unmapped but not listed", device.sensor_id));
+        }
+    }
+
+    // Ingestion is working-tree state only: no model edits, no commits
+    if std::fs::read(base.join(BUILDING_YAML))? != yaml_before {
+        failures.push(format!("{} changed during ingestion", BUILDING_YAML));
+    }
+    let head_after = git(base, &["rev-parse", "HEAD"])?;
+    if head_after != head_before {
+        failures.push(format!(
+            "HEAD moved during ingestion ({} -> {})",
+            head_before, head_after
+        ));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payloads_are_deterministic_and_realistic() {
+        let config = HilConfig {
+            devices: 8,
+            readings_per_device: 20,
+            ..HilConfig::default()
+        };
+        let devices = fleet(&config);
+        assert_eq!(devices.iter().filter(|d| d.mapped).count(), 4);
+        let a = reports(&config, &devices);
+        let b = reports(&config, &devices);
+        assert_eq!(a.len(), 160);
+        assert_eq!(
+            a.iter().map(|r| r.params.clone()).collect::<Vec<_>>(),
+            b.iter().map(|r| r.params.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(a.iter().filter(|r| r.defect.is_some()).count(), 160 / 7);
+        for r in a.iter().filter(|r| r.defect.is_none()) {
+            let v = r.value.unwrap();
+            match devices[r.device].profile {
+                DeviceProfile::Temperature => assert!((15.0..=30.0).contains(&v)),
+                DeviceProfile::Occupancy => assert_eq!(v.fract(), 0.0),
+                DeviceProfile::Co2 => assert!((400.0..=2500.0).contains(&v)),
+                DeviceProfile::Humidity => assert!((20.0..=80.0).contains(&v)),
+            }
+        }
+        assert!("mqtt".parse::<Transport>().is_err());
+        assert_eq!("HTTP".parse::<Transport>(), Ok(Transport::Http));
+    }
+}
@@ -0,0 +1,3 @@
+//! Developer tooling (`arx devtools`): harnesses for exercising the pipelines end to end.
+
+pub mod hil;
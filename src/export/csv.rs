@@ -0,0 +1,75 @@
+//! Equipment register as CSV, for spreadsheets and CMMS asset imports.
+
+use crate::core::domain::issue::csv_field;
+use crate::core::Building;
+
+/// One row per piece of equipment, floor by floor, with room and position.
+pub fn equipment_to_csv(building: &Building) -> String {
+    let mut out = String::from("id,name,type,status,health,floor,room_id,room,address,x,y,z\n");
+    for floor in &building.floors {
+        let in_rooms = floor
+            .wings
+            .iter()
+            .flat_map(|w| &w.rooms)
+            .flat_map(|r| r.equipment.iter().map(move |eq| (Some(r), eq)));
+        let common = floor.equipment.iter().map(|eq| (None, eq));
+        for (room, eq) in in_rooms.chain(common) {
+            let row = [
+                eq.id.clone(),
+                eq.name.clone(),
+                eq.equipment_type.to_string(),
+                eq.status.to_string(),
+                eq.health_status
+                    .map(|h| format!("{:?}", h))
+                    .unwrap_or_default(),
+                floor.level.to_string(),
+                room.map(|r| r.id.clone()).unwrap_or_default(),
+                room.map(|r| r.name.clone()).unwrap_or_default(),
+                eq.address
+                    .as_ref()
+                    .map(|a| a.to_string())
+                    .unwrap_or_default(),
+                eq.position.x.to_string(),
+                eq.position.y.to_string(),
+                eq.position.z.to_string(),
+            ];
+            let line: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&line.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn test_equipment_to_csv_lists_room_and_common_area_equipment() {
+        let mut room = Room::new("Plant, East".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        floor.equipment.push(Equipment::new(
+            "Lobby panel".into(),
+            String::new(),
+            EquipmentType::Electrical,
+        ));
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let csv = equipment_to_csv(&building);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains(",AHU-1,") && lines[1].contains(",\"Plant, East\","));
+        assert!(lines[2].contains(",Lobby panel,") && lines[2].contains(",0,,,"));
+    }
+}
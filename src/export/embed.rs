@@ -0,0 +1,290 @@
+//! Floor status widget data for intranet embeds.
+//!
+//! A [`FloorSummary`] is the minimal, public-safe view of one floor: each
+//! room's worst equipment state and alert count, plus floor totals. The agent
+//! serves it at `GET /api/embed/floors/:level?token=…` to read-only embed
+//! tokens listed in `.arx/embed.yaml`:
+//!
+//! ```yaml
+//! tokens:
+//!   - token: 7f3c9a-lobby-screen
+//!     label: Lobby screen
+//!     floors: [0, 1]     # omit for every floor
+//! ```
+//!
+//! The same summary can be computed in the browser from building JSON
+//! (`floor_summary_json` in the WASM bridge) and rendered with
+//! [`render_widget_html`].
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::site::escape_html;
+use crate::core::{Building, Equipment, EquipmentHealthStatus, EquipmentStatus, Room};
+
+/// Embed tokens and their floor scopes.
+pub const EMBED_CONFIG_FILE: &str = ".arx/embed.yaml";
+
+/// Room state shown by the widget, worst first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomState {
+    Critical,
+    Warning,
+    Maintenance,
+    Ok,
+}
+
+impl RoomState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoomState::Critical => "critical",
+            RoomState::Warning => "warning",
+            RoomState::Maintenance => "maintenance",
+            RoomState::Ok => "ok",
+        }
+    }
+
+    /// Marker colour shared by the widget and the SVG floor plan.
+    pub fn color(self) -> &'static str {
+        match self {
+            RoomState::Critical => "#d32f2f",
+            RoomState::Warning => "#f9a825",
+            RoomState::Maintenance => "#1976d2",
+            RoomState::Ok => "#388e3c",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomSummary {
+    pub id: String,
+    pub name: String,
+    pub state: RoomState,
+    /// Equipment warnings / criticals plus open issues in the room
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub alerts: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertCounts {
+    pub critical: usize,
+    pub warning: usize,
+    pub issues: usize,
+}
+
+/// Everything the widget shows for one floor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloorSummary {
+    pub building: String,
+    pub floor: String,
+    pub level: i32,
+    /// Last change to the building model (not request time, so responses cache)
+    pub updated_at: DateTime<Utc>,
+    pub rooms: Vec<RoomSummary>,
+    pub alerts: AlertCounts,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// State one piece of equipment contributes to its room.
+pub fn equipment_state(eq: &Equipment) -> RoomState {
+    match (eq.health_status, eq.status) {
+        (Some(EquipmentHealthStatus::Critical), _) | (_, EquipmentStatus::OutOfOrder) => {
+            RoomState::Critical
+        }
+        (Some(EquipmentHealthStatus::Warning), _) => RoomState::Warning,
+        (_, EquipmentStatus::Maintenance) => RoomState::Maintenance,
+        _ => RoomState::Ok,
+    }
+}
+
+fn room_summary(room: &Room, open_issues: usize, totals: &mut AlertCounts) -> RoomSummary {
+    let mut state = RoomState::Ok;
+    let mut alerts = open_issues;
+    for eq in &room.equipment {
+        let eq_state = equipment_state(eq);
+        match eq_state {
+            RoomState::Critical => totals.critical += 1,
+            RoomState::Warning => totals.warning += 1,
+            _ => {}
+        }
+        if eq_state <= RoomState::Warning {
+            alerts += 1;
+        }
+        state = state.min(eq_state);
+    }
+    totals.issues += open_issues;
+    RoomSummary {
+        id: room.id.clone(),
+        name: room.name.clone(),
+        state,
+        alerts,
+    }
+}
+
+/// Summary of the floor at `level`; `open_issues` counts open issues by room id.
+pub fn floor_summary(
+    building: &Building,
+    level: i32,
+    open_issues: &HashMap<String, usize>,
+) -> Option<FloorSummary> {
+    let floor = building.floors.iter().find(|f| f.level == level)?;
+    let mut alerts = AlertCounts::default();
+    let rooms = floor
+        .wings
+        .iter()
+        .flat_map(|w| &w.rooms)
+        .map(|room| {
+            let issues = open_issues.get(&room.id).copied().unwrap_or(0);
+            room_summary(room, issues, &mut alerts)
+        })
+        .collect();
+    Some(FloorSummary {
+        building: building.name.clone(),
+        floor: floor.name.clone(),
+        level,
+        updated_at: building.updated_at,
+        rooms,
+        alerts,
+    })
+}
+
+/// Strong validator for a serialized summary (hex SHA-256 prefix, quoted).
+pub fn etag(body: &str) -> String {
+    let digest = Sha256::digest(body.as_bytes());
+    let hex: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\"{}\"", hex)
+}
+
+/// Self-contained HTML fragment (inline styles) for the widget.
+pub fn render_widget_html(summary: &FloorSummary) -> String {
+    let mut html = format!(
+        "<div class=\"arx-floor\" style=\"font:13px sans-serif\"><strong>{} · {}</strong> \
+         <span>{} critical, {} warning, {} open issue(s)</span><ul style=\"list-style:none;padding:0\">",
+        escape_html(&summary.building),
+        escape_html(&summary.floor),
+        summary.alerts.critical,
+        summary.alerts.warning,
+        summary.alerts.issues
+    );
+    for room in &summary.rooms {
+        html.push_str(&format!(
+            "<li><span style=\"color:{}\">●</span> {} <small>{}</small></li>",
+            room.state.color(),
+            escape_html(&room.name),
+            if room.alerts > 0 {
+                format!("{} ({})", room.state.as_str(), room.alerts)
+            } else {
+                room.state.as_str().to_string()
+            }
+        ));
+    }
+    html.push_str("</ul></div>");
+    html
+}
+
+/// One read-only embed token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbedToken {
+    pub token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Floor levels this token may read; empty = all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub floors: Vec<i32>,
+}
+
+/// Contents of [`EMBED_CONFIG_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbedConfig {
+    #[serde(default)]
+    pub tokens: Vec<EmbedToken>,
+}
+
+impl EmbedConfig {
+    /// Tokens from `base/.arx/embed.yaml`; none when the file is absent.
+    pub fn load_at(base: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = base.join(EMBED_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let config: EmbedConfig = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        if let Some(short) = config.tokens.iter().find(|t| t.token.len() < 16) {
+            return Err(format!(
+                "Invalid {}: embed token '{}' must be at least 16 characters",
+                path.display(),
+                short.label.as_deref().unwrap_or("(unlabelled)")
+            )
+            .into());
+        }
+        Ok(config)
+    }
+
+    /// The token entry when `token` may read floor `level`.
+    pub fn authorize(&self, token: &str, level: i32) -> Option<&EmbedToken> {
+        self.tokens
+            .iter()
+            .find(|t| t.token == token)
+            .filter(|t| t.floors.is_empty() || t.floors.contains(&level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, RoomType, Wing};
+
+    #[test]
+    fn test_floor_summary_and_token_scope() {
+        let mut ok = Room::new("Lobby".into(), RoomType::Office);
+        ok.id = "lobby".into();
+        let mut hot = Room::new("Lab <1>".into(), RoomType::Laboratory);
+        hot.id = "lab".into();
+        let mut fume = Equipment::new("Hood".into(), String::new(), EquipmentType::HVAC);
+        fume.health_status = Some(EquipmentHealthStatus::Critical);
+        let mut fan = Equipment::new("Fan".into(), String::new(), EquipmentType::HVAC);
+        fan.status = EquipmentStatus::Maintenance;
+        hot.equipment.extend([fume, fan]);
+        let mut wing = Wing::new("East".into());
+        wing.rooms.extend([ok, hot]);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.wings.push(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let issues = HashMap::from([("lobby".to_string(), 2)]);
+        let summary = floor_summary(&building, 0, &issues).unwrap();
+        let states: Vec<(RoomState, usize)> =
+            summary.rooms.iter().map(|r| (r.state, r.alerts)).collect();
+        assert_eq!(states, vec![(RoomState::Ok, 2), (RoomState::Critical, 1)]);
+        assert_eq!(
+            summary.alerts,
+            AlertCounts {
+                critical: 1,
+                warning: 0,
+                issues: 2
+            }
+        );
+        assert!(floor_summary(&building, 3, &issues).is_none());
+
+        let body = serde_json::to_string(&summary).unwrap();
+        assert_eq!(etag(&body), etag(&body.clone()));
+        assert!(render_widget_html(&summary).contains("Lab &lt;1&gt;"));
+
+        let config: EmbedConfig =
+            serde_yaml::from_str("tokens:\n  - token: lobby-screen-0001\n    floors: [0]\n")
+                .unwrap();
+        assert!(config.authorize("lobby-screen-0001", 0).is_some());
+        assert!(config.authorize("lobby-screen-0001", 1).is_none());
+        assert!(config.authorize("other", 0).is_none());
+    }
+}
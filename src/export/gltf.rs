@@ -0,0 +1,360 @@
+//! glTF 2.0 export of floor, room, and equipment geometry.
+//!
+//! Writes a single self-contained `.gltf` (JSON with an embedded base64 buffer):
+//! one node per room and per piece of equipment, grouped under one node per
+//! floor that carries the storey slab. Rooms without a model mesh use derived
+//! extrusions (see [`crate::core::spatial::extrusion`]) and get a translucent
+//! variant of their material; equipment without one is a marker cube. Ids,
+//! types, status, and equipment properties go in node `extras`, which Blender
+//! shows as custom properties and three.js as `userData`. Materials come from
+//! the per-type palette in [`crate::core::materials`]. ArxOS is Z-up; glTF is
+//! Y-up, so positions are written as `(x, z, -y)`.
+
+use crate::core::materials::{Material, MaterialSet};
+use crate::core::spatial::{BuildingMeshes, Mesh};
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+/// Opacity multiplier for derived room extrusions.
+const DERIVED_OPACITY: f64 = 0.5;
+
+#[derive(Default)]
+struct Document {
+    buffer: Vec<u8>,
+    views: Vec<Value>,
+    accessors: Vec<Value>,
+    meshes: Vec<Value>,
+    nodes: Vec<Value>,
+    materials: Vec<Value>,
+    /// Material index by name, so each type / variant is emitted once
+    material_index: BTreeMap<String, usize>,
+}
+
+impl Document {
+    fn material(&mut self, name: String, material: &Material, derived: bool) -> usize {
+        if let Some(&i) = self.material_index.get(&name) {
+            return i;
+        }
+        let mut color = material.base_color_factor();
+        if derived {
+            color[3] *= DERIVED_OPACITY;
+        }
+        let mut value = json!({
+            "name": name,
+            "pbrMetallicRoughness": {
+                "baseColorFactor": color,
+                "metallicFactor": material.metallic,
+                "roughnessFactor": material.roughness
+            },
+            "doubleSided": true
+        });
+        if color[3] < 1.0 {
+            value["alphaMode"] = json!("BLEND");
+        }
+        let i = self.materials.len();
+        self.materials.push(value);
+        self.material_index.insert(name, i);
+        i
+    }
+
+    /// Append `mesh` to the buffer and return its glTF mesh index.
+    fn mesh(&mut self, name: &str, mesh: &Mesh, material: usize) -> usize {
+        let (mut min, mut max) = ([f32::MAX; 3], [f32::MIN; 3]);
+        let position_offset = self.buffer.len();
+        for v in &mesh.vertices {
+            let p = [v.x as f32, v.z as f32, -v.y as f32];
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+                self.buffer.extend_from_slice(&p[axis].to_le_bytes());
+            }
+        }
+        let index_offset = self.buffer.len();
+        for i in &mesh.indices {
+            self.buffer.extend_from_slice(&i.to_le_bytes());
+        }
+
+        let view = self.views.len();
+        self.views.push(json!({
+            "buffer": 0, "byteOffset": position_offset,
+            "byteLength": index_offset - position_offset, "target": ARRAY_BUFFER
+        }));
+        self.views.push(json!({
+            "buffer": 0, "byteOffset": index_offset,
+            "byteLength": self.buffer.len() - index_offset, "target": ELEMENT_ARRAY_BUFFER
+        }));
+        let accessor = self.accessors.len();
+        self.accessors.push(json!({
+            "bufferView": view, "componentType": FLOAT,
+            "count": mesh.vertices.len(), "type": "VEC3", "min": min, "max": max
+        }));
+        self.accessors.push(json!({
+            "bufferView": view + 1, "componentType": UNSIGNED_INT,
+            "count": mesh.indices.len(), "type": "SCALAR"
+        }));
+
+        self.meshes.push(json!({
+            "name": name,
+            "primitives": [{
+                "attributes": { "POSITION": accessor },
+                "indices": accessor + 1,
+                "material": material
+            }]
+        }));
+        self.meshes.len() - 1
+    }
+}
+
+/// glTF document for `meshes` (scene named after the building), coloured by `materials`.
+pub fn building_meshes_to_gltf(
+    building_name: &str,
+    meshes: &BuildingMeshes,
+    materials: &MaterialSet,
+) -> Value {
+    let mut doc = Document::default();
+    let mut floors: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
+
+    for room in &meshes.rooms {
+        let name = if room.derived {
+            format!("room/{} (derived)", room.room_type)
+        } else {
+            format!("room/{}", room.room_type)
+        };
+        let material = doc.material(
+            name,
+            materials.for_room_type_name(&room.room_type),
+            room.derived,
+        );
+        let mesh = doc.mesh(&room.room_name, &room.mesh, material);
+        floors.entry(room.floor_level).or_default().push(doc.nodes.len());
+        doc.nodes.push(json!({
+            "name": room.room_name,
+            "mesh": mesh,
+            "extras": {
+                "room_id": room.room_id, "room_type": room.room_type, "derived": room.derived
+            }
+        }));
+    }
+    for equipment in &meshes.equipment {
+        let material = doc.material(
+            format!("equipment/{}", equipment.equipment_type),
+            materials.for_equipment_type_name(&equipment.equipment_type),
+            false,
+        );
+        let mesh = doc.mesh(&equipment.equipment_name, &equipment.mesh, material);
+        floors
+            .entry(equipment.floor_level)
+            .or_default()
+            .push(doc.nodes.len());
+        doc.nodes.push(json!({
+            "name": equipment.equipment_name,
+            "mesh": mesh,
+            "extras": {
+                "equipment_id": equipment.equipment_id,
+                "equipment_type": equipment.equipment_type,
+                "status": equipment.status,
+                "room_id": equipment.room_id,
+                "properties": equipment.properties,
+                "derived": equipment.derived
+            }
+        }));
+    }
+    for floor in &meshes.floors {
+        floors.entry(floor.level).or_default();
+    }
+
+    let mut scene_nodes = Vec::new();
+    for (level, mut children) in floors {
+        let floor = meshes.floors.iter().find(|f| f.level == level);
+        let name = floor
+            .map(|f| f.floor_name.clone())
+            .unwrap_or_else(|| format!("Floor {}", level));
+        if let Some(slab) = floor.and_then(|f| f.slab.as_ref()) {
+            let material = doc.material("floor/slab".into(), &materials.floor_slab, false);
+            let mesh = doc.mesh(&format!("{} slab", name), slab, material);
+            children.insert(0, doc.nodes.len());
+            doc.nodes
+                .push(json!({ "name": format!("{} slab", name), "mesh": mesh }));
+        }
+        let mut node = json!({ "name": name, "children": children });
+        if let Some(f) = floor {
+            node["extras"] = json!({
+                "floor_id": f.floor_id, "level": f.level, "elevation": f.elevation
+            });
+        }
+        scene_nodes.push(doc.nodes.len());
+        doc.nodes.push(node);
+    }
+
+    let mut out = json!({
+        "asset": { "version": "2.0", "generator": "ArxOS" },
+        "scene": 0,
+        "scenes": [{ "name": building_name, "nodes": scene_nodes }],
+        "nodes": doc.nodes,
+        "meshes": doc.meshes,
+        "materials": doc.materials,
+        "accessors": doc.accessors,
+        "bufferViews": doc.views,
+    });
+    if !doc.buffer.is_empty() {
+        out["buffers"] = json!([{
+            "byteLength": doc.buffer.len(),
+            "uri": format!(
+                "data:application/octet-stream;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(&doc.buffer)
+            )
+        }]);
+    }
+    out
+}
+
+/// Write `meshes` to `path` as `.gltf`.
+pub fn export_gltf(
+    building_name: &str,
+    meshes: &BuildingMeshes,
+    materials: &MaterialSet,
+    path: &Path,
+) -> Result<()> {
+    let doc = building_meshes_to_gltf(building_name, meshes, materials);
+    std::fs::write(path, serde_json::to_string_pretty(&doc)?)
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::spatial::extrusion::extrude_footprint;
+    use crate::core::spatial::{EquipmentMesh, RoomMesh};
+
+    #[test]
+    fn gltf_document_references_consistent_buffers() {
+        let mesh = extrude_footprint(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)], 0.0, 3.0);
+        let meshes = BuildingMeshes {
+            rooms: vec![RoomMesh {
+                room_id: "r1".into(),
+                room_name: "Lab".into(),
+                room_type: "Laboratory".into(),
+                floor_level: 0,
+                derived: true,
+                approximate: false,
+                mesh,
+            }],
+            ..Default::default()
+        };
+        let doc = building_meshes_to_gltf("HQ", &meshes, &MaterialSet::default());
+
+        assert_eq!(doc["asset"]["version"], "2.0");
+        assert_eq!(doc["meshes"][0]["primitives"][0]["material"], 0);
+        assert_eq!(doc["accessors"][0]["count"], 8);
+        // Y-up: the 3 m ceiling lands on +Y
+        assert_eq!(doc["accessors"][0]["max"][1], 3.0);
+        let uri = doc["buffers"][0]["uri"].as_str().unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(uri.split_once(',').unwrap().1)
+            .unwrap();
+        // 8 vertices × 12 bytes + 36 indices × 4 bytes
+        assert_eq!(bytes.len(), 8 * 12 + 36 * 4);
+        assert_eq!(doc["buffers"][0]["byteLength"], bytes.len());
+        assert_eq!(doc["scenes"][0]["nodes"], json!([1]));
+    }
+
+    #[test]
+    fn materials_follow_type_palette_and_are_shared() {
+        let cube = || extrude_footprint(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)], 0.0, 1.0);
+        let room = |id: &str, derived| RoomMesh {
+            room_id: id.into(),
+            room_name: id.into(),
+            room_type: "Office".into(),
+            floor_level: 0,
+            derived,
+            approximate: false,
+            mesh: cube(),
+        };
+        let meshes = BuildingMeshes {
+            rooms: vec![room("a", false), room("b", false), room("c", true)],
+            equipment: vec![EquipmentMesh {
+                equipment_id: "e1".into(),
+                equipment_name: "AHU-1".into(),
+                equipment_type: "HVAC".into(),
+                floor_level: 1,
+                derived: true,
+                mesh: cube(),
+                status: "Active".into(),
+                room_id: None,
+                properties: BTreeMap::new(),
+            }],
+            ..Default::default()
+        };
+        let palette = MaterialSet::default();
+        let doc = building_meshes_to_gltf("HQ", &meshes, &palette);
+
+        let materials = doc["materials"].as_array().unwrap();
+        assert_eq!(materials.len(), 3);
+        let office = palette.for_room_type_name("Office").base_color_factor();
+        assert_eq!(materials[0]["pbrMetallicRoughness"]["baseColorFactor"], json!(office));
+        assert!(materials[0].get("alphaMode").is_none());
+        assert_eq!(materials[1]["alphaMode"], "BLEND");
+        assert_eq!(materials[2]["name"], "equipment/HVAC");
+        assert_eq!(doc["meshes"][1]["primitives"][0]["material"], 0);
+        assert_eq!(doc["meshes"][3]["primitives"][0]["material"], 2);
+        // Two floors, equipment grouped under level 1
+        assert_eq!(doc["scenes"][0]["nodes"], json!([4, 5]));
+        assert_eq!(doc["nodes"][5]["children"], json!([3]));
+        assert_eq!(doc["nodes"][3]["extras"]["status"], "Active");
+    }
+
+    #[test]
+    fn floors_carry_slab_and_storey_metadata() {
+        use crate::core::spatial::building_meshes;
+        use crate::core::{Building, Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.elevation = Some(0.0);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.properties.insert(
+            crate::core::spatial::extrusion::FLOOR_POLYGON_PROPERTY.into(),
+            "0,0;6,0;6,4;0,4".into(),
+        );
+        let mut ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        ahu.position.x = 2.0;
+        ahu.position.y = 2.0;
+        ahu.properties.insert("serial".into(), "X-100".into());
+        room.add_equipment(ahu);
+        let room_id = room.id.clone();
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let meshes = building_meshes(&building);
+        let doc = building_meshes_to_gltf("HQ", &meshes, &MaterialSet::default());
+        let nodes = doc["nodes"].as_array().unwrap();
+        let ground = &nodes[doc["scenes"][0]["nodes"][0].as_u64().unwrap() as usize];
+        assert_eq!(ground["name"], "Ground");
+        assert_eq!(ground["extras"]["level"], 0);
+        // Room, equipment, and the slab listed first
+        let children = ground["children"].as_array().unwrap();
+        assert_eq!(children.len(), 3);
+        let slab = &nodes[children[0].as_u64().unwrap() as usize];
+        assert_eq!(slab["name"], "Ground slab");
+        // Slab meshes come after the room and equipment: its positions are accessor 4
+        assert_eq!(slab["mesh"], 2);
+        let slab_positions = &doc["accessors"][4];
+        // Sits just under the floor (Y-up) and spans the room
+        assert_eq!(slab_positions["max"][1], 0.0);
+        assert_eq!(slab_positions["min"][1], -0.2f32 as f64);
+        assert_eq!(slab_positions["max"][0], 6.0);
+        let equipment = nodes.iter().find(|n| n["name"] == "AHU-1").unwrap();
+        assert_eq!(equipment["extras"]["room_id"], room_id.as_str());
+        assert_eq!(equipment["extras"]["properties"]["serial"], "X-100");
+    }
+}
@@ -0,0 +1,380 @@
+//! iCalendar (RFC 5545) feed of upcoming maintenance work.
+//!
+//! Three sources end up as all-day events:
+//! - runtime maintenance schedules, dated by forecasting the equipment's
+//!   average daily run rate forward (overdue work lands on today),
+//! - inspection rounds, one recurring event per schedule,
+//! - warranty expirations read from equipment properties.
+//!
+//! Events carry stable UIDs so calendar clients update rather than duplicate
+//! them when the feed is re-imported. Filtering by assignee uses the
+//! schedule's `assignee`, falling back to the equipment's `assignee` property.
+
+use crate::core::clock;
+use crate::core::runtime::{maintenance_status, MaintenanceSchedules};
+use crate::core::vendor::vendor_value;
+use crate::core::{Building, Equipment};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// Equipment properties read as the warranty end date (`YYYY-MM-DD`), in order,
+/// before the IFC `Pset_Warranty.WarrantyEndDate` vendor extension.
+pub const WARRANTY_PROPERTIES: &[&str] = &[
+    "warranty_expires",
+    "warranty_date",
+    "Pset_Warranty.WarrantyEndDate",
+];
+
+/// Equipment property naming who looks after it.
+pub const ASSIGNEE_PROPERTY: &str = "assignee";
+
+/// Which schedule produced an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventCategory {
+    Maintenance,
+    Inspection,
+    Warranty,
+}
+
+impl EventCategory {
+    fn as_str(self) -> &'static str {
+        match self {
+            EventCategory::Maintenance => "MAINTENANCE",
+            EventCategory::Inspection => "INSPECTION",
+            EventCategory::Warranty => "WARRANTY",
+        }
+    }
+}
+
+/// One all-day calendar entry.
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub date: NaiveDate,
+    /// Recurrence rule body, e.g. `FREQ=DAILY;INTERVAL=30`
+    pub rrule: Option<String>,
+    pub assignee: Option<String>,
+    pub category: EventCategory,
+}
+
+fn equipment_assignee(eq: &Equipment) -> Option<String> {
+    eq.properties.get(ASSIGNEE_PROPERTY).cloned()
+}
+
+fn assigned_to(assignee: &Option<String>, filter: Option<&str>) -> bool {
+    match filter {
+        None => true,
+        Some(f) => assignee
+            .as_deref()
+            .is_some_and(|a| a.eq_ignore_ascii_case(f)),
+    }
+}
+
+/// Events due between today and `horizon_days` out, sorted by date.
+/// Inspection rounds are dated at their next occurrence and repeat via RRULE.
+pub fn upcoming_events(
+    building: &Building,
+    schedules: &MaintenanceSchedules,
+    now: DateTime<Utc>,
+    horizon_days: i64,
+    assignee: Option<&str>,
+) -> Vec<CalendarEvent> {
+    let today = now.date_naive();
+    let horizon = today + Duration::days(horizon_days);
+    let mut events = Vec::new();
+
+    for status in maintenance_status(building, schedules, now) {
+        let Some(eq) = building.find_equipment(&status.equipment_id) else {
+            continue;
+        };
+        let Some(due) = eq
+            .runtime
+            .as_ref()
+            .and_then(|counter| status.forecast_due(counter, now))
+            .map(|at| at.date_naive())
+        else {
+            continue;
+        };
+        if due > horizon {
+            continue;
+        }
+        let who = schedules
+            .get(&status.schedule)
+            .and_then(|s| s.assignee.clone())
+            .or_else(|| equipment_assignee(eq));
+        if !assigned_to(&who, assignee) {
+            continue;
+        }
+        let state = if status.is_due() {
+            format!("Overdue by {:.1} run hours", -status.remaining_hours())
+        } else {
+            format!("{:.1} run hours remaining", status.remaining_hours())
+        };
+        events.push(CalendarEvent {
+            uid: format!(
+                "maintenance-{}-{}@arxos",
+                status.schedule, status.equipment_id
+            ),
+            summary: format!("{}: {}", status.schedule, status.equipment),
+            description: format!(
+                "{} (every {} run hours, {:.1} since service)",
+                state, status.every_runtime_hours, status.since_service_hours
+            ),
+            date: due,
+            rrule: None,
+            assignee: who,
+            category: EventCategory::Maintenance,
+        });
+    }
+
+    for inspection in &schedules.inspections {
+        if !assigned_to(&inspection.assignee, assignee) {
+            continue;
+        }
+        let interval = i64::from(inspection.every_days);
+        let elapsed = (today - inspection.start).num_days();
+        let next = if elapsed <= 0 {
+            inspection.start
+        } else {
+            let rounds = (elapsed + interval - 1) / interval;
+            inspection.start + Duration::days(rounds * interval)
+        };
+        if next > horizon {
+            continue;
+        }
+        let equipment: Vec<&str> = building
+            .get_all_equipment()
+            .into_iter()
+            .filter(|eq| inspection.selector.matches(eq))
+            .map(|eq| eq.name.as_str())
+            .collect();
+        let mut description = inspection.description.clone().unwrap_or_default();
+        if !equipment.is_empty() {
+            if !description.is_empty() {
+                description.push('\n');
+            }
+            description.push_str(&format!("Equipment: {}", equipment.join(", ")));
+        }
+        events.push(CalendarEvent {
+            uid: format!("inspection-{}@arxos", inspection.name),
+            summary: format!("Inspection: {}", inspection.name),
+            description,
+            date: next,
+            rrule: Some(format!("FREQ=DAILY;INTERVAL={}", inspection.every_days)),
+            assignee: inspection.assignee.clone(),
+            category: EventCategory::Inspection,
+        });
+    }
+
+    for eq in building.get_all_equipment() {
+        let Some(expires) = WARRANTY_PROPERTIES
+            .iter()
+            .find_map(|key| eq.properties.get(*key).map(String::as_str))
+            .or_else(|| vendor_value(&eq.vendor_extensions, "Pset_Warranty", "WarrantyEndDate"))
+            .and_then(|v| NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").ok())
+        else {
+            continue;
+        };
+        if expires < today || expires > horizon {
+            continue;
+        }
+        let who = equipment_assignee(eq);
+        if !assigned_to(&who, assignee) {
+            continue;
+        }
+        events.push(CalendarEvent {
+            uid: format!("warranty-{}@arxos", eq.id),
+            summary: format!("Warranty expires: {}", eq.name),
+            description: format!("{} ({})", eq.name, eq.equipment_type),
+            date: expires,
+            rrule: None,
+            assignee: who,
+            category: EventCategory::Warranty,
+        });
+    }
+
+    events.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.uid.cmp(&b.uid)));
+    events
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11).
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Append a content line, folded at 75 octets (RFC 5545 §3.1).
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Render `events` as a VCALENDAR document.
+pub fn to_ical(calendar_name: &str, events: &[CalendarEvent]) -> String {
+    let stamp = clock::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//ArxOS//Maintenance Schedule//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(
+        &mut out,
+        &format!("X-WR-CALNAME:{}", escape_text(calendar_name)),
+    );
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", event.uid));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(
+            &mut out,
+            &format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+        );
+        push_line(
+            &mut out,
+            &format!(
+                "DTEND;VALUE=DATE:{}",
+                (event.date + Duration::days(1)).format("%Y%m%d")
+            ),
+        );
+        if let Some(rrule) = &event.rrule {
+            push_line(&mut out, &format!("RRULE:{}", rrule));
+        }
+        push_line(
+            &mut out,
+            &format!("SUMMARY:{}", escape_text(&event.summary)),
+        );
+        if !event.description.is_empty() {
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape_text(&event.description)),
+            );
+        }
+        push_line(&mut out, &format!("CATEGORIES:{}", event.category.as_str()));
+        if let Some(assignee) = &event.assignee {
+            push_line(
+                &mut out,
+                &format!("X-ARXOS-ASSIGNEE:{}", escape_text(assignee)),
+            );
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runtime::{InspectionSchedule, MaintenanceSchedule, RuntimeCounter};
+    use crate::core::{EquipmentType, Floor};
+    use crate::validation::EquipmentSelector;
+    use chrono::TimeZone;
+
+    fn building(now: DateTime<Utc>) -> Building {
+        let mut ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        ahu.id = "ahu-1".into();
+        // 120 run hours over 10 tracked days: 12h/day
+        let mut counter = RuntimeCounter::default();
+        counter.record(true, now - Duration::days(10));
+        counter.record(false, now - Duration::days(5));
+        ahu.runtime = Some(counter);
+        let mut ext = Equipment::new("EXT-1".into(), String::new(), EquipmentType::Safety);
+        ext.id = "ext-1".into();
+        ext.properties
+            .insert("warranty_expires".into(), "2024-03-01".into());
+        ext.properties
+            .insert(ASSIGNEE_PROPERTY.into(), "safety".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.equipment.extend([ahu, ext]);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    fn schedules() -> MaintenanceSchedules {
+        MaintenanceSchedules {
+            schedules: vec![MaintenanceSchedule {
+                name: "filter-change".into(),
+                description: None,
+                selector: EquipmentSelector {
+                    equipment_type: Some("HVAC".into()),
+                    ..Default::default()
+                },
+                every_runtime_hours: 180.0,
+                assignee: Some("hvac".into()),
+            }],
+            inspections: vec![InspectionSchedule {
+                name: "extinguishers".into(),
+                description: Some("Monthly check".into()),
+                selector: EquipmentSelector {
+                    equipment_type: Some("Safety".into()),
+                    ..Default::default()
+                },
+                every_days: 30,
+                start: NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(),
+                assignee: Some("safety".into()),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_upcoming_events_forecast_and_filter() {
+        let now = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        let b = building(now);
+        let events = upcoming_events(&b, &schedules(), now, 60, None);
+        let dated: Vec<(&str, NaiveDate)> =
+            events.iter().map(|e| (e.uid.as_str(), e.date)).collect();
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        assert_eq!(
+            dated,
+            vec![
+                ("inspection-extinguishers@arxos", day(2, 4)),
+                // 60 run hours left at 12h/day
+                ("maintenance-filter-change-ahu-1@arxos", day(2, 6)),
+                ("warranty-ext-1@arxos", day(3, 1)),
+            ]
+        );
+
+        let safety = upcoming_events(&b, &schedules(), now, 60, Some("Safety"));
+        assert_eq!(safety.len(), 2);
+        assert!(safety
+            .iter()
+            .all(|e| e.category != EventCategory::Maintenance));
+    }
+
+    #[test]
+    fn test_ical_escapes_and_folds() {
+        let _guard = clock::ClockGuard::deterministic(
+            Utc.with_ymd_and_hms(2024, 2, 1, 8, 30, 0).unwrap(),
+            0,
+        );
+        let event = CalendarEvent {
+            uid: "x@arxos".into(),
+            summary: "Check; valves, pumps".into(),
+            description: "a".repeat(100),
+            date: NaiveDate::from_ymd_opt(2024, 2, 4).unwrap(),
+            rrule: Some("FREQ=DAILY;INTERVAL=30".into()),
+            assignee: None,
+            category: EventCategory::Inspection,
+        };
+        let ics = to_ical("HQ", &[event]);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("SUMMARY:Check\\; valves\\, pumps\r\n"));
+        assert!(ics.contains("DTSTAMP:20240201T083000Z\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20240204\r\nDTEND;VALUE=DATE:20240205\r\n"));
+        assert!(ics.lines().all(|l| l.len() <= 75));
+        assert!(ics.contains("\r\n a"));
+    }
+}
@@ -1,9 +1,11 @@
 use crate::core::spatial::mesh::Mesh;
+use crate::core::vendor::VendorExtensions;
 use crate::core::{Building, Equipment, Floor, Room};
 use crate::ifc::mapping::{
-    entity_kind, identity_property_map, lidar_enrichment_to_pset, properties_for_export,
-    resolve_product_global_id, PROP_ARX_WING, PSET_ARX_BUILDING, PSET_ARX_EQUIPMENT,
-    PSET_ARX_FLOOR, PSET_ARX_IDENTITY, PSET_ARX_LIDAR, PSET_ARX_ROOM,
+    entity_kind, identity_property_map, is_arx_pset, lidar_enrichment_to_pset,
+    properties_for_export, resolve_product_global_id, vendor_step_value, PROP_ARX_WING,
+    PSET_ARX_BUILDING, PSET_ARX_EQUIPMENT, PSET_ARX_FLOOR, PSET_ARX_IDENTITY, PSET_ARX_LIDAR,
+    PSET_ARX_ROOM,
 };
 use anyhow::Result;
 use chrono::Utc;
@@ -252,6 +254,12 @@ impl IFCExporter {
                         PSET_ARX_ROOM,
                         &room_props,
                     )?;
+                    self.create_vendor_property_sets(
+                        writer,
+                        owner_history_id,
+                        room_id,
+                        &room.vendor_extensions,
+                    )?;
 
                     // Process Equipment in Room
                     if !room.equipment.is_empty() {
@@ -289,6 +297,12 @@ impl IFCExporter {
                                 PSET_ARX_EQUIPMENT,
                                 &eq_props,
                             )?;
+                            self.create_vendor_property_sets(
+                                writer,
+                                owner_history_id,
+                                eq_id,
+                                &equipment.vendor_extensions,
+                            )?;
                         }
                         self.create_containment(
                             writer,
@@ -336,6 +350,12 @@ impl IFCExporter {
                             PSET_ARX_EQUIPMENT,
                             &eq_props,
                         )?;
+                        self.create_vendor_property_sets(
+                            writer,
+                            owner_history_id,
+                            eq_id,
+                            &equipment.vendor_extensions,
+                        )?;
                     }
                     self.create_containment(
                         writer,
@@ -403,6 +423,12 @@ impl IFCExporter {
                         PSET_ARX_EQUIPMENT,
                         &eq_props,
                     )?;
+                    self.create_vendor_property_sets(
+                        writer,
+                        owner_history_id,
+                        eq_id,
+                        &equipment.vendor_extensions,
+                    )?;
                 }
                 self.create_containment(
                     writer,
@@ -775,6 +801,49 @@ impl IFCExporter {
         Ok(Some(pset_id))
     }
 
+    /// Re-emit each vendor property set under its original name and types
+    fn create_vendor_property_sets<W: Write>(
+        &self,
+        writer: &mut StepWriter<W>,
+        owner_hist: usize,
+        related_id: usize,
+        extensions: &VendorExtensions,
+    ) -> Result<()> {
+        for (pset, properties) in extensions {
+            if properties.is_empty() || is_arx_pset(pset) {
+                continue;
+            }
+            let mut prop_ids = Vec::new();
+            for (name, property) in properties {
+                prop_ids.push(writer.write_entity(format!(
+                    "IFCPROPERTYSINGLEVALUE('{}',$,{},$)",
+                    name.replace('\'', "''"),
+                    vendor_step_value(property)
+                ))?);
+            }
+            let prop_refs = prop_ids
+                .iter()
+                .map(|id| format!("#{}", id))
+                .collect::<Vec<_>>()
+                .join(",");
+            let pset_id = writer.write_entity(format!(
+                "IFCPROPERTYSET('{}',#{},'{}',$,({}))",
+                self.generate_guid(),
+                owner_hist,
+                pset.replace('\'', "''"),
+                prop_refs
+            ))?;
+            writer.write_entity(format!(
+                "IFCRELDEFINESBYPROPERTIES('{}',#{},$,$,(#{}),#{})",
+                self.generate_guid(),
+                owner_hist,
+                related_id,
+                pset_id
+            ))?;
+        }
+        Ok(())
+    }
+
     // --- Geometry Helpers ---
 
     fn create_cartesian_point<W: Write>(
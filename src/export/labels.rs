@@ -0,0 +1,429 @@
+//! Equipment labels: QR codes that lead back to the equipment record.
+//!
+//! Each label encodes the equipment's ArxAddress path (the legacy `path`, or
+//! the id, when no address is set or the path is shared) next to its name and
+//! room.
+//! [`render_labels_pdf`] lays labels out on A4 sheets for printing and
+//! [`render_labels_png`] draws one image; [`lookup_equipment_by_code`] turns a
+//! scanned code back into the equipment.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use qrcode::{Color, QrCode};
+
+use super::png::{Canvas, Rgb, MAX_DIMENSION};
+use crate::core::{Building, Equipment};
+
+/// One printable label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquipmentLabel {
+    pub equipment_id: String,
+    pub name: String,
+    pub room: Option<String>,
+    /// Text encoded in the QR code
+    pub code: String,
+}
+
+/// Label sheet format, chosen by output extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelFormat {
+    Pdf,
+    Png,
+}
+
+impl LabelFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("pdf") => Ok(LabelFormat::Pdf),
+            Some("png") => Ok(LabelFormat::Png),
+            _ => bail!(
+                "Unsupported label format for {} (use .pdf or .png)",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// The code printed on `equipment`'s label.
+pub fn label_code(equipment: &Equipment) -> String {
+    match &equipment.address {
+        Some(address) => address.path.clone(),
+        None if !equipment.path.is_empty() => equipment.path.clone(),
+        None => equipment.id.clone(),
+    }
+}
+
+/// Equipment for a scanned label code: ArxAddress path first, then the legacy
+/// path, then the id.
+pub fn lookup_equipment_by_code<'a>(building: &'a Building, code: &str) -> Option<&'a Equipment> {
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+    let all = building.get_all_equipment();
+    all.iter()
+        .find(|eq| {
+            eq.address
+                .as_ref()
+                .is_some_and(|a| a.path.eq_ignore_ascii_case(code))
+        })
+        .or_else(|| all.iter().find(|eq| eq.path.eq_ignore_ascii_case(code)))
+        .or_else(|| all.iter().find(|eq| eq.id.eq_ignore_ascii_case(code)))
+        .copied()
+}
+
+/// Labels for all equipment, or for the equipment in `room` (id or name),
+/// sorted by room and name.
+pub fn equipment_labels(building: &Building, room: Option<&str>) -> Result<Vec<EquipmentLabel>> {
+    let rooms = building.get_all_rooms();
+    if let Some(query) = room {
+        if !rooms
+            .iter()
+            .any(|r| r.id == query || r.name.eq_ignore_ascii_case(query))
+        {
+            bail!("Room '{}' not found", query);
+        }
+    }
+    let room_of: HashMap<&str, &str> = rooms
+        .iter()
+        .flat_map(|r| {
+            r.equipment
+                .iter()
+                .map(|eq| (eq.id.as_str(), r.name.as_str()))
+        })
+        .collect();
+    let room_ids: Vec<&str> = rooms
+        .iter()
+        .filter(|r| room.is_some_and(|q| r.id == q || r.name.eq_ignore_ascii_case(q)))
+        .flat_map(|r| r.equipment.iter().map(|eq| eq.id.as_str()))
+        .collect();
+    let mut labels: Vec<EquipmentLabel> = building
+        .get_all_equipment()
+        .into_iter()
+        .filter(|eq| room.is_none() || room_ids.contains(&eq.id.as_str()))
+        .map(|eq| EquipmentLabel {
+            equipment_id: eq.id.clone(),
+            name: eq.name.clone(),
+            room: room_of.get(eq.id.as_str()).map(|name| name.to_string()),
+            code: label_code(eq),
+        })
+        .collect();
+    // A code shared by several items (e.g. a legacy path of `/`) cannot tell
+    // them apart; those labels carry the id instead
+    let mut uses: HashMap<String, usize> = HashMap::new();
+    for label in &labels {
+        *uses.entry(label.code.to_ascii_lowercase()).or_default() += 1;
+    }
+    for label in &mut labels {
+        if uses[&label.code.to_ascii_lowercase()] > 1 {
+            label.code = label.equipment_id.clone();
+        }
+    }
+    labels.sort_by(|a, b| (&a.room, &a.name).cmp(&(&b.room, &b.name)));
+    Ok(labels)
+}
+
+/// Dark modules of the QR code for `code`, row by row, and its width.
+fn qr_modules(code: &str) -> Result<(usize, Vec<bool>)> {
+    let qr = QrCode::new(code.as_bytes())
+        .map_err(|e| anyhow!("Cannot encode '{}' as a QR code: {}", code, e))?;
+    let dark = qr
+        .to_colors()
+        .into_iter()
+        .map(|c| c == Color::Dark)
+        .collect();
+    Ok((qr.width(), dark))
+}
+
+/// First `max` characters of `text`, ending in `...` when cut.
+fn fit(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max.saturating_sub(3)).collect();
+    out.push_str("...");
+    out
+}
+
+/// QR modules of quiet zone around each code.
+const QUIET: usize = 4;
+
+const PNG_COLUMNS: usize = 3;
+const PNG_CELL: (usize, usize) = (320, 180);
+const PNG_QR: usize = 140;
+const INK: Rgb = [0, 0, 0];
+const CUT_LINE: Rgb = [200, 200, 200];
+
+/// One PNG with the labels in a three-column grid.
+pub fn render_labels_png(labels: &[EquipmentLabel]) -> Result<Vec<u8>> {
+    if labels.is_empty() {
+        bail!("No equipment to label");
+    }
+    let rows = labels.len().div_ceil(PNG_COLUMNS);
+    let (width, height) = (PNG_COLUMNS * PNG_CELL.0, rows * PNG_CELL.1);
+    if height > MAX_DIMENSION as usize {
+        bail!(
+            "{} labels do not fit one PNG; write a PDF or narrow with --room",
+            labels.len()
+        );
+    }
+    let mut canvas = Canvas::blank(width, height);
+    for (i, label) in labels.iter().enumerate() {
+        let x = (i % PNG_COLUMNS * PNG_CELL.0) as i64;
+        let y = (i / PNG_COLUMNS * PNG_CELL.1) as i64;
+        let (w, h) = (PNG_CELL.0 as i64, PNG_CELL.1 as i64);
+        canvas.fill_rect(x, y, w, 1, CUT_LINE);
+        canvas.fill_rect(x, y, 1, h, CUT_LINE);
+
+        let (modules, dark) = qr_modules(&label.code)?;
+        let size = (PNG_QR / (modules + 2 * QUIET)).max(1) as i64;
+        let (qx, qy) = (x + 10 + QUIET as i64 * size, y + 10 + QUIET as i64 * size);
+        for (n, _) in dark.iter().enumerate().filter(|(_, d)| **d) {
+            let (row, col) = ((n / modules) as i64, (n % modules) as i64);
+            canvas.fill_rect(qx + col * size, qy + row * size, size, size, INK);
+        }
+
+        let tx = x + PNG_QR as i64 + 20;
+        canvas.text(tx, y + 24, &fit(&label.name, 18), 2, INK);
+        if let Some(room) = &label.room {
+            canvas.text(tx, y + 44, &fit(room, 18), 2, INK);
+        }
+        canvas.text(x + 10, y + h - 16, &fit(&label.code, 76), 1, INK);
+    }
+    canvas.encode()
+}
+
+/// A4 in points.
+const PAGE: (f64, f64) = (595.0, 842.0);
+const PDF_MARGIN: f64 = 20.0;
+const PDF_COLUMNS: usize = 3;
+const PDF_ROWS: usize = 7;
+const PDF_QR: f64 = 80.0;
+
+/// `text` as a PDF string literal body (WinAnsi; other characters become `?`).
+fn pdf_string(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+fn pdf_label(out: &mut String, label: &EquipmentLabel, x: f64, top: f64) -> Result<()> {
+    let (w, h) = (
+        (PAGE.0 - 2.0 * PDF_MARGIN) / PDF_COLUMNS as f64,
+        (PAGE.1 - 2.0 * PDF_MARGIN) / PDF_ROWS as f64,
+    );
+    let _ = writeln!(
+        out,
+        "0.75 G 0.5 w {:.2} {:.2} {:.2} {:.2} re S",
+        x,
+        top - h,
+        w,
+        h
+    );
+
+    let (modules, dark) = qr_modules(&label.code)?;
+    let size = PDF_QR / (modules + 2 * QUIET) as f64;
+    let (qx, qy) = (
+        x + 4.0 + QUIET as f64 * size,
+        top - 4.0 - QUIET as f64 * size,
+    );
+    out.push_str("0 g\n");
+    for (n, _) in dark.iter().enumerate().filter(|(_, d)| **d) {
+        let (row, col) = ((n / modules) as f64, (n % modules) as f64);
+        let _ = writeln!(
+            out,
+            "{:.3} {:.3} {:.3} {:.3} re",
+            qx + col * size,
+            qy - (row + 1.0) * size,
+            size,
+            size
+        );
+    }
+    out.push_str("f\n");
+
+    let tx = x + PDF_QR + 10.0;
+    let mut text = |font: &str, pt: f64, tx: f64, ty: f64, body: &str| {
+        let _ = writeln!(
+            out,
+            "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET",
+            font,
+            pt,
+            tx,
+            ty,
+            pdf_string(body)
+        );
+    };
+    text("F2", 10.0, tx, top - 20.0, &fit(&label.name, 16));
+    if let Some(room) = &label.room {
+        text("F1", 8.0, tx, top - 34.0, &fit(room, 20));
+    }
+    text("F1", 5.0, x + 6.0, top - h + 8.0, &fit(&label.code, 64));
+    Ok(())
+}
+
+/// A4 PDF with 21 labels per page (3 × 7).
+pub fn render_labels_pdf(labels: &[EquipmentLabel]) -> Result<Vec<u8>> {
+    if labels.is_empty() {
+        bail!("No equipment to label");
+    }
+    let per_page = PDF_COLUMNS * PDF_ROWS;
+    let pages: Vec<&[EquipmentLabel]> = labels.chunks(per_page).collect();
+    let (w, h) = (
+        (PAGE.0 - 2.0 * PDF_MARGIN) / PDF_COLUMNS as f64,
+        (PAGE.1 - 2.0 * PDF_MARGIN) / PDF_ROWS as f64,
+    );
+
+    // Objects 1-4: catalog, page tree, fonts; then a page and its content per sheet
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 5 + 2 * i))
+        .collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (i, page) in pages.iter().enumerate() {
+        let mut content = String::new();
+        for (n, label) in page.iter().enumerate() {
+            let x = PDF_MARGIN + (n % PDF_COLUMNS) as f64 * w;
+            let top = PAGE.1 - PDF_MARGIN - (n / PDF_COLUMNS) as f64 * h;
+            pdf_label(&mut content, label, x, top)?;
+        }
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE.0,
+            PAGE.1,
+            6 + 2 * i
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+    let xref = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+    Ok(pdf.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::ArxAddress;
+    use crate::core::{EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut room = Room::new("Plant (B1)".into(), RoomType::Mechanical);
+        let mut boiler = Equipment::new("Boiler-01".into(), String::new(), EquipmentType::HVAC);
+        boiler.address = Some(ArxAddress {
+            path: "/usa/ny/brooklyn/ps-118/floor-01/mech/boiler-01".into(),
+        });
+        room.add_equipment(boiler);
+        room.add_equipment(Equipment::new(
+            "Pump-02".into(),
+            "/legacy/pump-02".into(),
+            EquipmentType::Plumbing,
+        ));
+        for name in ["Valve-1", "Valve-2"] {
+            room.add_equipment(Equipment::new(
+                name.into(),
+                "/".into(),
+                EquipmentType::Plumbing,
+            ));
+        }
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn test_label_codes_round_trip_through_lookup() {
+        let building = building();
+        let labels = equipment_labels(&building, Some("plant (b1)")).unwrap();
+        assert_eq!(labels.len(), 4);
+        assert_eq!(
+            labels[0].code,
+            "/usa/ny/brooklyn/ps-118/floor-01/mech/boiler-01"
+        );
+        assert_eq!(labels[1].code, "/legacy/pump-02");
+        assert_eq!(labels[2].code, labels[2].equipment_id);
+        for label in &labels {
+            let found = lookup_equipment_by_code(&building, &label.code).unwrap();
+            assert_eq!(found.id, label.equipment_id);
+        }
+        let id = &labels[0].equipment_id;
+        assert_eq!(lookup_equipment_by_code(&building, id).unwrap().id, *id);
+        assert!(lookup_equipment_by_code(&building, "/nowhere").is_none());
+        assert!(equipment_labels(&building, Some("Lobby")).is_err());
+    }
+
+    #[test]
+    fn test_render_label_sheets() {
+        let labels = equipment_labels(&building(), None).unwrap();
+
+        let pdf = String::from_utf8(render_labels_pdf(&labels).unwrap()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("/Count 1"));
+        assert!(pdf.contains("(Plant \\(B1\\)) Tj"));
+        let xref: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with("xref"));
+
+        let png = render_labels_png(&labels).unwrap();
+        let decoder = ::png::Decoder::new(std::io::Cursor::new(png));
+        let reader = decoder.read_info().unwrap();
+        assert_eq!(reader.info().width as usize, PNG_COLUMNS * PNG_CELL.0);
+        assert_eq!(reader.info().height as usize, 2 * PNG_CELL.1);
+        assert!(render_labels_png(&[]).is_err());
+    }
+}
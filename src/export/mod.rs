@@ -1 +1,11 @@
+pub mod csv;
+pub mod embed;
+pub mod gltf;
+pub mod ical;
 pub mod ifc;
+pub mod labels;
+pub mod plugin;
+pub mod png;
+pub mod pwa_bundle;
+pub mod site;
+pub mod svg;
@@ -0,0 +1,297 @@
+//! WASM converter plugins for custom import / export formats.
+//!
+//! Plugins live in `.arx/plugins/formats/<name>.wasm`, optionally next to a
+//! `<name>.toml` manifest (`name`, `description`, `extension`). The format name
+//! used on the command line is the manifest `name` or the file stem.
+//!
+//! # ABI
+//!
+//! A plugin is a core WASM module (no WASI) exporting:
+//!
+//! - `memory` — linear memory used for all data exchange
+//! - `arx_alloc(len: i32) -> i32` — reserve `len` bytes for host input
+//! - `arx_export(ptr: i32, len: i32) -> i64` — building JSON in, file bytes out
+//! - `arx_import(ptr: i32, len: i32) -> i64` — file bytes in, building JSON out
+//! - `arx_error() -> i64` (optional) — message for the last failure
+//!
+//! Either converter may be omitted. Results are packed as `(ptr << 32) | len`;
+//! a negative result signals failure. Plugins run sandboxed with bounded fuel
+//! and memory, and building JSON is the same shape `arx export --format json`
+//! writes.
+
+use crate::core::Building;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Plugin directory under the project root.
+pub const PLUGIN_DIR: &str = ".arx/plugins/formats";
+
+/// Largest input or output exchanged with a plugin.
+pub const MAX_PLUGIN_IO_BYTES: usize = 64 * 1024 * 1024;
+
+/// Optional `<name>.toml` sidecar.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PluginManifest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Default output file extension (without dot)
+    pub extension: Option<String>,
+}
+
+/// A discovered converter plugin.
+#[derive(Debug, Clone)]
+pub struct FormatPlugin {
+    pub name: String,
+    pub wasm_path: PathBuf,
+    pub description: Option<String>,
+    pub extension: String,
+}
+
+impl FormatPlugin {
+    /// Default output file name for an export.
+    pub fn default_output(&self, building: &Building) -> String {
+        format!("{}.{}", building.name, self.extension)
+    }
+}
+
+/// All plugins under `base/.arx/plugins/formats`, sorted by name.
+pub fn discover_plugins(base: &Path) -> Result<Vec<FormatPlugin>> {
+    let dir = base.join(PLUGIN_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut plugins = Vec::new();
+    for entry in std::fs::read_dir(&dir).with_context(|| format!("reading {}", dir.display()))? {
+        let wasm_path = entry?.path();
+        if wasm_path.extension().and_then(|e| e.to_str()) != Some("wasm") {
+            continue;
+        }
+        let stem = wasm_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let manifest_path = wasm_path.with_extension("toml");
+        let manifest: PluginManifest = if manifest_path.exists() {
+            toml::from_str(&std::fs::read_to_string(&manifest_path)?)
+                .with_context(|| format!("invalid manifest {}", manifest_path.display()))?
+        } else {
+            PluginManifest::default()
+        };
+        plugins.push(FormatPlugin {
+            name: manifest.name.unwrap_or_else(|| stem.clone()),
+            description: manifest.description,
+            extension: manifest.extension.unwrap_or_else(|| stem.clone()),
+            wasm_path,
+        });
+    }
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(plugins)
+}
+
+/// Plugin registered under `name` (case-insensitive).
+pub fn find_plugin(base: &Path, name: &str) -> Result<Option<FormatPlugin>> {
+    Ok(discover_plugins(base)?
+        .into_iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name)))
+}
+
+/// Run the plugin's `arx_export` on `building`.
+pub fn export_with_plugin(plugin: &FormatPlugin, building: &Building) -> Result<Vec<u8>> {
+    let json = serde_json::to_vec(building)?;
+    run_converter(plugin, "arx_export", &json)
+}
+
+/// Run the plugin's `arx_import` on `input` and parse the returned building.
+pub fn import_with_plugin(plugin: &FormatPlugin, input: &[u8]) -> Result<Building> {
+    let json = run_converter(plugin, "arx_import", input)?;
+    serde_json::from_slice(&json)
+        .with_context(|| format!("plugin '{}' returned invalid building JSON", plugin.name))
+}
+
+#[cfg(not(feature = "plugins"))]
+fn run_converter(plugin: &FormatPlugin, _entry: &str, _input: &[u8]) -> Result<Vec<u8>> {
+    Err(anyhow::anyhow!(
+        "format plugin '{}' found, but this build lacks WASM support (rebuild with --features plugins)",
+        plugin.name
+    ))
+}
+
+#[cfg(feature = "plugins")]
+fn run_converter(plugin: &FormatPlugin, entry: &str, input: &[u8]) -> Result<Vec<u8>> {
+    let module_bytes = std::fs::read(&plugin.wasm_path)
+        .with_context(|| format!("reading {}", plugin.wasm_path.display()))?;
+    host::run(&plugin.name, &module_bytes, entry, input)
+}
+
+#[cfg(feature = "plugins")]
+mod host {
+    use super::MAX_PLUGIN_IO_BYTES;
+    use anyhow::{anyhow, bail, Context, Result};
+    use wasmtime::{
+        Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    };
+
+    /// Instruction budget per conversion (guards against runaway plugins).
+    const FUEL: u64 = 20_000_000_000;
+    /// Linear memory cap per plugin instance.
+    const MAX_MEMORY_BYTES: usize = 512 * 1024 * 1024;
+
+    fn unpack(packed: i64) -> (usize, usize) {
+        (
+            ((packed as u64) >> 32) as usize,
+            (packed as u64 & 0xffff_ffff) as usize,
+        )
+    }
+
+    fn read(memory: &Memory, store: &Store<StoreLimits>, packed: i64) -> Result<Vec<u8>> {
+        let (ptr, len) = unpack(packed);
+        if len > MAX_PLUGIN_IO_BYTES {
+            bail!("plugin output exceeds {} bytes", MAX_PLUGIN_IO_BYTES);
+        }
+        memory
+            .data(store)
+            .get(ptr..ptr + len)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| anyhow!("plugin returned out-of-bounds output"))
+    }
+
+    fn plugin_error(
+        instance: &Instance,
+        memory: &Memory,
+        store: &mut Store<StoreLimits>,
+    ) -> String {
+        let message = instance
+            .get_typed_func::<(), i64>(&mut *store, "arx_error")
+            .ok()
+            .and_then(|f| f.call(&mut *store, ()).ok())
+            .filter(|packed| *packed >= 0)
+            .and_then(|packed| read(memory, store, packed).ok())
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+        message.unwrap_or_else(|| "conversion failed".to_string())
+    }
+
+    pub(super) fn run(
+        name: &str,
+        module_bytes: &[u8],
+        entry: &str,
+        input: &[u8],
+    ) -> Result<Vec<u8>> {
+        if input.len() > MAX_PLUGIN_IO_BYTES {
+            bail!("input exceeds {} bytes", MAX_PLUGIN_IO_BYTES);
+        }
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::new(&engine, module_bytes)
+            .with_context(|| format!("plugin '{}' is not a valid WASM module", name))?;
+
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .build();
+        let mut store = Store::new(&engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL)?;
+
+        // No imports: plugins get no host access beyond their own memory
+        let instance = Instance::new(&mut store, &module, &[]).with_context(|| {
+            format!(
+                "plugin '{}' failed to instantiate (imports are not allowed)",
+                name
+            )
+        })?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow!("plugin '{}' does not export 'memory'", name))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "arx_alloc")
+            .with_context(|| format!("plugin '{}' does not export 'arx_alloc'", name))?;
+        let convert = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, entry)
+            .with_context(|| format!("plugin '{}' does not support {}", name, entry))?;
+
+        let len = i32::try_from(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(|_| anyhow!("plugin '{}' allocated out-of-bounds input", name))?;
+
+        let packed = convert
+            .call(&mut store, (ptr, len))
+            .with_context(|| format!("plugin '{}' trapped", name))?;
+        if packed < 0 {
+            let message = plugin_error(&instance, &memory, &mut store);
+            bail!("plugin '{}': {}", name, message);
+        }
+        read(&memory, &store, packed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_plugin(base: &Path, file: &str, bytes: &[u8]) {
+        let dir = base.join(PLUGIN_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(file), bytes).unwrap();
+    }
+
+    #[test]
+    fn discovers_plugins_with_optional_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        write_plugin(dir.path(), "maximo.wasm", b"\0asm");
+        write_plugin(
+            dir.path(),
+            "maximo.toml",
+            b"name = \"maximo-assets\"\nextension = \"csv\"\n",
+        );
+        write_plugin(dir.path(), "plain.wasm", b"\0asm");
+        write_plugin(dir.path(), "notes.txt", b"ignored");
+
+        let plugins = discover_plugins(dir.path()).unwrap();
+        let names: Vec<&str> = plugins.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["maximo-assets", "plain"]);
+        assert_eq!(plugins[0].extension, "csv");
+        assert!(find_plugin(dir.path(), "MAXIMO-ASSETS").unwrap().is_some());
+        assert!(find_plugin(dir.path(), "missing").unwrap().is_none());
+    }
+
+    /// Echo plugin: export returns its input unchanged; import always fails.
+    #[cfg(feature = "plugins")]
+    const ECHO_WAT: &str = r#"
+        (module
+          (memory (export "memory") 2)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 16) "unsupported input")
+          (func (export "arx_alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "arx_export") (param $ptr i32) (param $len i32) (result i64)
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len))))
+          (func (export "arx_import") (param i32 i32) (result i64) (i64.const -1))
+          (func (export "arx_error") (result i64)
+            (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 17))))
+    "#;
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn runs_export_and_surfaces_plugin_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        write_plugin(dir.path(), "echo.wasm", ECHO_WAT.as_bytes());
+        let plugin = find_plugin(dir.path(), "echo").unwrap().unwrap();
+
+        let building = Building::new("HQ".into(), "/hq".into());
+        let out = export_with_plugin(&plugin, &building).unwrap();
+        let round: Building = serde_json::from_slice(&out).unwrap();
+        assert_eq!(round.name, "HQ");
+
+        let err = import_with_plugin(&plugin, b"anything").unwrap_err();
+        assert!(err.to_string().contains("unsupported input"), "{}", err);
+    }
+}
@@ -0,0 +1,465 @@
+//! Static floor plan images.
+//!
+//! [`render_floor_png`] rasterizes one floor into a PNG: room outlines (the
+//! `floor_polygon` property, else the plan bounding box), room labels,
+//! equipment markers, and optionally the floor status overlay from
+//! [`super::embed`]. Everything is drawn in pure Rust with a built-in 3×5
+//! bitmap font, so the same call works in the CLI, the agent, and WASM.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::embed::{floor_summary, RoomState};
+use crate::core::spatial::layout::floor_footprints;
+use crate::core::{Building, Equipment, Floor, Room};
+
+/// Default image width in pixels.
+pub const DEFAULT_WIDTH: u32 = 1024;
+/// Largest width or height rendered.
+pub const MAX_DIMENSION: u32 = 8192;
+
+const MIN_WIDTH: u32 = 64;
+pub(super) const MARGIN: f64 = 16.0;
+
+pub(super) type Rgb = [u8; 3];
+
+pub(super) const BACKGROUND: Rgb = [255, 255, 255];
+pub(super) const ROOM_FILL: Rgb = [236, 239, 241];
+pub(super) const ROOM_EDGE: Rgb = [69, 90, 100];
+pub(super) const LABEL: Rgb = [33, 33, 33];
+const MARKER: Rgb = [21, 101, 192];
+
+/// What, if anything, to paint over room fills.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FloorOverlay {
+    #[default]
+    None,
+    /// Fill each room by its worst equipment state (as in the floor widget)
+    Status,
+}
+
+/// Rendering options; every field has a default so partial JSON works.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloorPngOptions {
+    /// Image width in pixels; height follows the plan's aspect ratio
+    pub width: u32,
+    pub labels: bool,
+    pub equipment: bool,
+    pub overlay: FloorOverlay,
+}
+
+impl Default for FloorPngOptions {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            labels: true,
+            equipment: true,
+            overlay: FloorOverlay::None,
+        }
+    }
+}
+
+pub(super) fn overlay_fill(state: RoomState) -> Rgb {
+    match state {
+        RoomState::Critical => [239, 154, 154],
+        RoomState::Warning => [255, 224, 130],
+        RoomState::Maintenance => [144, 202, 249],
+        RoomState::Ok => [165, 214, 167],
+    }
+}
+
+/// RGB raster with the plan-to-pixel transform.
+pub(super) struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    origin: (f64, f64),
+    scale: f64,
+}
+
+impl Canvas {
+    /// White raster with an identity plan transform (for non-plan drawings).
+    pub(super) fn blank(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: BACKGROUND.repeat(width * height),
+            origin: (0.0, 0.0),
+            scale: 1.0,
+        }
+    }
+
+    /// Pixel coordinates of plan point `p` (plan y points up, image y down).
+    fn project(&self, p: (f64, f64)) -> (f64, f64) {
+        (
+            MARGIN + (p.0 - self.origin.0) * self.scale,
+            self.height as f64 - MARGIN - (p.1 - self.origin.1) * self.scale,
+        )
+    }
+
+    fn put(&mut self, x: i64, y: i64, color: Rgb) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let i = (y as usize * self.width + x as usize) * 3;
+        self.pixels[i..i + 3].copy_from_slice(&color);
+    }
+
+    pub(super) fn fill_rect(&mut self, x: i64, y: i64, w: i64, h: i64, color: Rgb) {
+        for py in y..y + h {
+            for px in x..x + w {
+                self.put(px, py, color);
+            }
+        }
+    }
+
+    /// Even-odd scanline fill of a pixel-space polygon.
+    fn fill_polygon(&mut self, points: &[(f64, f64)], color: Rgb) {
+        let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+            (lo.min(p.1), hi.max(p.1))
+        });
+        let (start, end) = (min_y.floor().max(0.0) as i64, max_y.ceil() as i64);
+        for y in start..end.min(self.height as i64) {
+            let sy = y as f64 + 0.5;
+            let mut xs: Vec<f64> = Vec::new();
+            for i in 0..points.len() {
+                let (a, b) = (points[i], points[(i + 1) % points.len()]);
+                if (a.1 <= sy) != (b.1 <= sy) {
+                    xs.push(a.0 + (sy - a.1) / (b.1 - a.1) * (b.0 - a.0));
+                }
+            }
+            xs.sort_by(f64::total_cmp);
+            for pair in xs.chunks_exact(2) {
+                let (x0, x1) = (
+                    (pair[0] - 0.5).ceil() as i64,
+                    (pair[1] - 0.5).floor() as i64,
+                );
+                for x in x0..=x1 {
+                    self.put(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn line(&mut self, a: (f64, f64), b: (f64, f64), pen: i64, color: Rgb) {
+        let steps = (b.0 - a.0).abs().max((b.1 - a.1).abs()).ceil().max(1.0) as i64;
+        for i in 0..=steps {
+            let t = i as f64 / steps as f64;
+            let x = (a.0 + (b.0 - a.0) * t).round() as i64;
+            let y = (a.1 + (b.1 - a.1) * t).round() as i64;
+            self.fill_rect(x - pen / 2, y - pen / 2, pen, pen, color);
+        }
+    }
+
+    pub(super) fn text(&mut self, x: i64, y: i64, text: &str, size: i64, color: Rgb) {
+        for (i, c) in text.chars().enumerate() {
+            let cx = x + i as i64 * 4 * size;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        let (px, py) = (cx + col as i64 * size, y + row as i64 * size);
+                        self.fill_rect(px, py, size, size, color);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(super) fn encode(self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&self.pixels)?;
+        }
+        Ok(out)
+    }
+}
+
+/// 3×5 glyph rows, most significant of the three bits leftmost. Labels are
+/// drawn upper-case; characters without a glyph render as `?`.
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+        '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+        '6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '#' => [0b101, 0b111, 0b101, 0b111, 0b101],
+        '(' => [0b010, 0b100, 0b100, 0b100, 0b010],
+        ')' => [0b010, 0b001, 0b001, 0b001, 0b010],
+        ' ' => [0; 5],
+        _ => [0b110, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+/// One floor's rooms and equipment with the plan-to-image transform shared
+/// by the PNG and SVG floor plans.
+pub(super) struct PlanLayout<'a> {
+    pub floor: &'a Floor,
+    /// Rooms with a footprint, and their outline in plan metres
+    pub rooms: Vec<(&'a Room, Vec<(f64, f64)>)>,
+    pub equipment: Vec<&'a Equipment>,
+    pub width: f64,
+    pub height: f64,
+    origin: (f64, f64),
+    scale: f64,
+}
+
+impl PlanLayout<'_> {
+    /// Image coordinates of plan point `p` (plan y points up, image y down).
+    pub fn project(&self, p: (f64, f64)) -> (f64, f64) {
+        (
+            MARGIN + (p.0 - self.origin.0) * self.scale,
+            self.height - MARGIN - (p.1 - self.origin.1) * self.scale,
+        )
+    }
+
+    /// Line width for room edges at this image width.
+    pub fn pen(&self) -> i64 {
+        (self.width / 512.0).ceil().max(1.0) as i64
+    }
+}
+
+/// Frame the floor at `level` into an image `width` wide; the height follows
+/// the plan's aspect ratio. Fails when the floor is missing or has nothing to
+/// draw.
+pub(super) fn layout_floor(building: &Building, level: i32, width: u32) -> Result<PlanLayout<'_>> {
+    let floor = building
+        .floors
+        .iter()
+        .find(|f| f.level == level)
+        .ok_or_else(|| anyhow!("No floor at level {}", level))?;
+    let rooms: Vec<_> = floor_footprints(floor)
+        .into_iter()
+        .map(|f| (f.room, f.outline))
+        .collect();
+    let equipment: Vec<&Equipment> = floor
+        .wings
+        .iter()
+        .flat_map(|w| &w.rooms)
+        .flat_map(|r| &r.equipment)
+        .chain(&floor.equipment)
+        .collect();
+
+    // Rooms frame the plan; equipment only when there are no outlines
+    let framing: Vec<(f64, f64)> = if rooms.is_empty() {
+        equipment
+            .iter()
+            .map(|eq| (eq.position.x, eq.position.y))
+            .collect()
+    } else {
+        rooms.iter().flat_map(|(_, o)| o.iter().copied()).collect()
+    };
+    if framing.is_empty() {
+        return Err(anyhow!(
+            "Floor '{}' has no room outlines or equipment to draw",
+            floor.name
+        ));
+    }
+    let (min, max) = framing.iter().fold(
+        ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN)),
+        |(lo, hi), p| {
+            (
+                (lo.0.min(p.0), lo.1.min(p.1)),
+                (hi.0.max(p.0), hi.1.max(p.1)),
+            )
+        },
+    );
+    let (plan_w, plan_h) = ((max.0 - min.0).max(1.0), (max.1 - min.1).max(1.0));
+    let width = width.clamp(MIN_WIDTH, MAX_DIMENSION) as f64;
+    let scale =
+        ((width - 2.0 * MARGIN) / plan_w).min((MAX_DIMENSION as f64 - 2.0 * MARGIN) / plan_h);
+    let height = (plan_h * scale + 2.0 * MARGIN).ceil();
+
+    Ok(PlanLayout {
+        floor,
+        rooms,
+        equipment,
+        width,
+        height,
+        origin: min,
+        scale,
+    })
+}
+
+/// PNG of the floor at `level`: rooms, labels, equipment markers, and the
+/// chosen overlay. Fails when the floor is missing or has nothing to draw.
+pub fn render_floor_png(
+    building: &Building,
+    level: i32,
+    options: &FloorPngOptions,
+) -> Result<Vec<u8>> {
+    let layout = layout_floor(building, level, options.width)?;
+    let (w, h) = (layout.width as usize, layout.height as usize);
+    let mut canvas = Canvas {
+        width: w,
+        height: h,
+        pixels: BACKGROUND.repeat(w * h),
+        origin: layout.origin,
+        scale: layout.scale,
+    };
+    let rooms = &layout.rooms;
+    let markers: Vec<(f64, f64)> = layout
+        .equipment
+        .iter()
+        .map(|eq| (eq.position.x, eq.position.y))
+        .collect();
+
+    let states: HashMap<String, RoomState> = match options.overlay {
+        FloorOverlay::Status => floor_summary(building, level, &HashMap::new())
+            .map(|s| s.rooms.into_iter().map(|r| (r.id, r.state)).collect())
+            .unwrap_or_default(),
+        FloorOverlay::None => HashMap::new(),
+    };
+    let pen = layout.pen();
+    for (room, outline) in rooms {
+        let points: Vec<(f64, f64)> = outline.iter().map(|p| canvas.project(*p)).collect();
+        let fill = states.get(&room.id).map_or(ROOM_FILL, |s| overlay_fill(*s));
+        canvas.fill_polygon(&points, fill);
+        for i in 0..points.len() {
+            canvas.line(points[i], points[(i + 1) % points.len()], pen, ROOM_EDGE);
+        }
+    }
+
+    if options.equipment {
+        let r = 2 * pen + 1;
+        for p in &markers {
+            let (x, y) = canvas.project(*p);
+            let (x, y) = (x.round() as i64, y.round() as i64);
+            canvas.fill_rect(x - r, y - r, 2 * r + 1, 2 * r + 1, MARKER);
+        }
+    }
+
+    if options.labels {
+        let size = pen + 1;
+        for (room, outline) in rooms {
+            let points: Vec<(f64, f64)> = outline.iter().map(|p| canvas.project(*p)).collect();
+            let (lo, hi) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+                (lo.min(p.0), hi.max(p.0))
+            });
+            let n = points.len() as f64;
+            let center = (
+                points.iter().map(|p| p.0).sum::<f64>() / n,
+                points.iter().map(|p| p.1).sum::<f64>() / n,
+            );
+            // Truncate to the room's pixel width; skip rooms too narrow for a glyph
+            let fits = ((hi - lo - 4.0) / (4 * size) as f64).floor().max(0.0) as usize;
+            let label: String = room.name.chars().take(fits).collect();
+            if label.is_empty() {
+                continue;
+            }
+            let text_w = (label.chars().count() as i64 * 4 - 1) * size;
+            canvas.text(
+                center.0.round() as i64 - text_w / 2,
+                center.1.round() as i64 - 5 * size / 2,
+                &label,
+                size,
+                LABEL,
+            );
+        }
+    }
+
+    canvas.encode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentHealthStatus, Floor, Room, RoomType, Wing};
+
+    fn decode(bytes: &[u8]) -> (u32, u32, Vec<u8>) {
+        let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        (info.width, info.height, buf)
+    }
+
+    #[test]
+    fn test_render_floor_png_draws_rooms_and_status_overlay() {
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.properties
+            .insert("floor_polygon".into(), "0,0;20,0;20,10;0,10".into());
+        let mut eq = crate::core::Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            crate::core::EquipmentType::HVAC,
+        );
+        eq.health_status = Some(EquipmentHealthStatus::Critical);
+        eq.position.x = 18.0;
+        eq.position.y = 8.0;
+        room.add_equipment(eq);
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let options = FloorPngOptions {
+            width: 200,
+            labels: false,
+            ..Default::default()
+        };
+        let (w, h, pixels) = decode(&render_floor_png(&building, 0, &options).unwrap());
+        assert_eq!((w, h), (200, 116));
+        // Plan point (5, 5) sits well inside the room
+        let at = |pixels: &[u8], x: usize, y: usize| {
+            let i = (y * w as usize + x) * 3;
+            [pixels[i], pixels[i + 1], pixels[i + 2]]
+        };
+        assert_eq!(at(&pixels, 58, 58), ROOM_FILL);
+        assert_eq!(at(&pixels, 2, 2), BACKGROUND);
+
+        let overlay = FloorPngOptions {
+            overlay: FloorOverlay::Status,
+            ..options
+        };
+        let (_, _, pixels) = decode(&render_floor_png(&building, 0, &overlay).unwrap());
+        assert_eq!(at(&pixels, 58, 58), overlay_fill(RoomState::Critical));
+
+        assert!(render_floor_png(&building, 3, &overlay).is_err());
+    }
+}
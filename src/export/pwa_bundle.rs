@@ -0,0 +1,140 @@
+//! Offline data bundle for the PWA.
+//!
+//! One file carries everything the field app shows without a network: the
+//! building model, its meshes and floor plans (so the device need not derive
+//! them), the latest sensor readings, and recent `building.yaml` history.
+//! On disk it is [`BUNDLE_MAGIC`], a format byte, then gzip-compressed JSON;
+//! the WASM `load_bundle` binding reads it back with [`PwaBundle::from_bytes`].
+
+use crate::core::spatial::extrusion::BuildingMeshes;
+use crate::core::spatial::plan::{floor_plan, FloorPlan};
+use crate::core::twin::SensorValue;
+use crate::core::Building;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+/// First bytes of every bundle file.
+pub const BUNDLE_MAGIC: &[u8; 4] = b"ARXB";
+
+/// Version of the bundle layout.
+pub const BUNDLE_FORMAT: u8 = 1;
+
+/// Commits of `building.yaml` history kept in a bundle.
+pub const HISTORY_LIMIT: usize = 50;
+
+/// One commit of `building.yaml` history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleCommit {
+    pub id: String,
+    /// First line of the commit message
+    pub summary: String,
+    pub author: String,
+    pub time: DateTime<Utc>,
+}
+
+/// Everything the PWA needs to browse a building offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PwaBundle {
+    pub generated_at: DateTime<Utc>,
+    /// Commit the bundle was made from, when the project is a Git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub building: Building,
+    pub meshes: BuildingMeshes,
+    /// Plan geometry per floor, lowest level first
+    pub plans: Vec<FloorPlan>,
+    /// Sensor id → latest reading
+    #[serde(default)]
+    pub sensors: BTreeMap<String, SensorValue>,
+    /// Newest first
+    #[serde(default)]
+    pub history: Vec<BundleCommit>,
+}
+
+impl PwaBundle {
+    /// Bundle `building` with its meshes; plans are derived here.
+    pub fn new(building: Building, meshes: BuildingMeshes) -> Self {
+        let mut levels: Vec<i32> = building.floors.iter().map(|f| f.level).collect();
+        levels.sort_unstable();
+        levels.dedup();
+        let plans = levels
+            .into_iter()
+            .filter_map(|level| floor_plan(&building, level))
+            .collect();
+        Self {
+            generated_at: crate::core::clock::now(),
+            commit: None,
+            building,
+            meshes,
+            plans,
+            sensors: BTreeMap::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Encoded bundle file contents.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let json = serde_json::to_vec(self).map_err(|e| format!("Serialization error: {}", e))?;
+        let mut out = BUNDLE_MAGIC.to_vec();
+        out.push(BUNDLE_FORMAT);
+        let mut encoder = GzEncoder::new(out, Compression::best());
+        encoder
+            .write_all(&json)
+            .and_then(|_| encoder.finish())
+            .map_err(|e| format!("Compression error: {}", e))
+    }
+
+    /// Decode bundle file contents.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let body = bytes
+            .strip_prefix(BUNDLE_MAGIC.as_slice())
+            .ok_or("Not an ArxOS PWA bundle")?;
+        let (&format, compressed) = body.split_first().ok_or("Truncated PWA bundle")?;
+        if format != BUNDLE_FORMAT {
+            return Err(format!(
+                "Unsupported PWA bundle format {} (expected {})",
+                format, BUNDLE_FORMAT
+            ));
+        }
+        let mut json = Vec::new();
+        GzDecoder::new(compressed)
+            .read_to_end(&mut json)
+            .map_err(|e| format!("Corrupt PWA bundle: {}", e))?;
+        serde_json::from_slice(&json).map_err(|e| format!("Invalid PWA bundle: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn bundle_round_trips_through_bytes() {
+        let mut wing = Wing::new("East".into());
+        wing.add_room(Room::new("Lobby".into(), RoomType::Office));
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let meshes = crate::core::spatial::building_meshes(&building);
+        let mut bundle = PwaBundle::new(building, meshes);
+        bundle.commit = Some("abc123".into());
+        assert_eq!(bundle.plans.len(), 1);
+
+        let bytes = bundle.to_bytes().unwrap();
+        assert!(bytes.starts_with(BUNDLE_MAGIC));
+        let loaded = PwaBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.commit.as_deref(), Some("abc123"));
+        assert_eq!(loaded.plans, bundle.plans);
+        assert_eq!(loaded.meshes, bundle.meshes);
+        assert_eq!(loaded.to_bytes().unwrap(), bytes);
+        assert!(PwaBundle::from_bytes(b"{}").is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! Static site export: guest-facing room info pages with QR codes.
+//!
+//! One HTML page per room (current temperature, equipment list, report-a-problem
+//! link) plus an SVG QR code pointing at the page's public URL, so labels on the
+//! door can take occupants straight to the room's status. Page layout comes from
+//! `templates/room-page.html`; a project can override it with
+//! `.arx/templates/room-page.html` (and `room-index.html`).
+
+use crate::core::{Building, Floor, Room};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Default room page template (`{{placeholder}}` substitution).
+pub const DEFAULT_ROOM_PAGE_TEMPLATE: &str = include_str!("../../templates/room-page.html");
+/// Default index template listing every room with its QR code.
+pub const DEFAULT_ROOM_INDEX_TEMPLATE: &str = include_str!("../../templates/room-index.html");
+/// Project-local template overrides live here.
+pub const TEMPLATE_OVERRIDE_DIR: &str = ".arx/templates";
+
+/// Room / equipment properties read as the current temperature, in order.
+const TEMPERATURE_PROPERTIES: &[&str] = &["current_temperature", "temperature"];
+
+/// Options for [`write_room_pages`].
+#[derive(Debug, Clone)]
+pub struct RoomPageOptions {
+    /// Public URL the site will be served from (QR codes point here)
+    pub base_url: String,
+    /// Report-a-problem link; `{room_id}` / `{room_name}` are substituted.
+    /// Default: `<base_url>/issues/new?room={room_id}`
+    pub report_url: Option<String>,
+    /// Page template (default: [`DEFAULT_ROOM_PAGE_TEMPLATE`])
+    pub page_template: String,
+    /// Index template (default: [`DEFAULT_ROOM_INDEX_TEMPLATE`])
+    pub index_template: String,
+}
+
+impl RoomPageOptions {
+    /// Options with the built-in templates, or the project overrides under
+    /// `base/.arx/templates/` when present.
+    pub fn with_templates_from(base: &Path, base_url: impl Into<String>) -> Result<Self> {
+        let dir = base.join(TEMPLATE_OVERRIDE_DIR);
+        let load = |name: &str, default: &str| -> Result<String> {
+            let path = dir.join(name);
+            if path.is_file() {
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("reading template {}", path.display()))
+            } else {
+                Ok(default.to_string())
+            }
+        };
+        Ok(Self {
+            base_url: base_url.into(),
+            report_url: None,
+            page_template: load("room-page.html", DEFAULT_ROOM_PAGE_TEMPLATE)?,
+            index_template: load("room-index.html", DEFAULT_ROOM_INDEX_TEMPLATE)?,
+        })
+    }
+
+    fn report_link(&self, room: &Room) -> String {
+        let template = self.report_url.clone().unwrap_or_else(|| {
+            format!(
+                "{}/issues/new?room={{room_id}}",
+                self.base_url.trim_end_matches('/')
+            )
+        });
+        template
+            .replace("{room_id}", &url_encode(&room.id))
+            .replace("{room_name}", &url_encode(&room.name))
+    }
+
+    fn page_url(&self, slug: &str) -> String {
+        format!(
+            "{}/rooms/{}.html",
+            self.base_url.trim_end_matches('/'),
+            slug
+        )
+    }
+}
+
+/// One generated room page.
+#[derive(Debug, Clone)]
+pub struct RoomPage {
+    pub room_id: String,
+    pub slug: String,
+    pub url: String,
+    pub html_path: PathBuf,
+    pub qr_path: PathBuf,
+}
+
+/// Escape text for HTML element / attribute content.
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn url_encode(s: &str) -> String {
+    let mut out = String::new();
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            let _ = write!(out, "%{:02X}", b);
+        }
+    }
+    out
+}
+
+/// File-name slug for a room: `<floor level>-<room name>`.
+pub fn room_slug(floor: &Floor, room: &Room) -> String {
+    let mut slug = String::new();
+    for c in room.name.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    let slug = if slug.is_empty() { "room" } else { slug };
+    format!("{}-{}", floor.level, slug)
+}
+
+/// Current temperature from the room's properties, falling back to its equipment.
+pub fn current_temperature(room: &Room) -> Option<String> {
+    let from = |props: &std::collections::HashMap<String, String>| {
+        TEMPERATURE_PROPERTIES
+            .iter()
+            .find_map(|k| props.get(*k).filter(|v| !v.trim().is_empty()).cloned())
+    };
+    let raw = from(&room.properties)
+        .or_else(|| room.equipment.iter().find_map(|e| from(&e.properties)))?;
+    Some(match raw.trim().parse::<f64>() {
+        Ok(v) => format!("{:.1} °C", v),
+        Err(_) => raw,
+    })
+}
+
+/// Render one room page from `template`.
+pub fn render_room_page(
+    building: &Building,
+    floor: &Floor,
+    room: &Room,
+    options: &RoomPageOptions,
+    template: &str,
+) -> String {
+    let mut rows = String::new();
+    for eq in &room.equipment {
+        let _ = writeln!(
+            rows,
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&eq.name),
+            escape_html(&eq.equipment_type.to_string()),
+            escape_html(&eq.status.to_string())
+        );
+    }
+    if room.equipment.is_empty() {
+        rows.push_str("<tr><td colspan=\"3\">No equipment recorded</td></tr>\n");
+    }
+    template
+        .replace("{{building_name}}", &escape_html(&building.name))
+        .replace("{{floor_name}}", &escape_html(&floor.name))
+        .replace("{{room_name}}", &escape_html(&room.name))
+        .replace("{{room_type}}", &escape_html(&room.room_type.to_string()))
+        .replace(
+            "{{temperature}}",
+            &escape_html(&current_temperature(room).unwrap_or_else(|| "—".into())),
+        )
+        .replace("{{equipment_rows}}", rows.trim_end())
+        .replace("{{report_url}}", &escape_html(&options.report_link(room)))
+        .replace(
+            "{{generated_at}}",
+            &Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+        )
+}
+
+/// SVG QR code encoding `url`.
+pub fn qr_svg(url: &str) -> Result<String> {
+    let code = QrCode::new(url.as_bytes()).context("encoding QR code")?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .quiet_zone(true)
+        .build())
+}
+
+/// Write `rooms/<slug>.html`, `qr/<slug>.svg`, and `index.html` under `out_dir`.
+pub fn write_room_pages(
+    building: &Building,
+    out_dir: &Path,
+    options: &RoomPageOptions,
+) -> Result<Vec<RoomPage>> {
+    let rooms_dir = out_dir.join("rooms");
+    let qr_dir = out_dir.join("qr");
+    std::fs::create_dir_all(&rooms_dir)
+        .with_context(|| format!("creating {}", rooms_dir.display()))?;
+    std::fs::create_dir_all(&qr_dir).with_context(|| format!("creating {}", qr_dir.display()))?;
+
+    let mut floors: Vec<&Floor> = building.floors.iter().collect();
+    floors.sort_by_key(|f| f.level);
+
+    let mut seen = HashSet::new();
+    let mut pages = Vec::new();
+    let mut cards = String::new();
+    for floor in floors {
+        for room in floor.wings.iter().flat_map(|w| w.rooms.iter()) {
+            let mut slug = room_slug(floor, room);
+            if !seen.insert(slug.clone()) {
+                let short: String = room
+                    .id
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .take(8)
+                    .collect();
+                slug = format!("{}-{}", slug, short);
+                seen.insert(slug.clone());
+            }
+            let url = options.page_url(&slug);
+            let html_path = rooms_dir.join(format!("{}.html", slug));
+            let qr_path = qr_dir.join(format!("{}.svg", slug));
+
+            std::fs::write(
+                &html_path,
+                render_room_page(building, floor, room, options, &options.page_template),
+            )
+            .with_context(|| format!("writing {}", html_path.display()))?;
+            std::fs::write(&qr_path, qr_svg(&url)?)
+                .with_context(|| format!("writing {}", qr_path.display()))?;
+
+            let _ = writeln!(
+                cards,
+                "<div class=\"card\"><a href=\"rooms/{slug}.html\"><img src=\"qr/{slug}.svg\" alt=\"QR code\"><br>{name}</a><br><small>{floor}</small></div>",
+                slug = slug,
+                name = escape_html(&room.name),
+                floor = escape_html(&floor.name)
+            );
+            pages.push(RoomPage {
+                room_id: room.id.clone(),
+                slug,
+                url,
+                html_path,
+                qr_path,
+            });
+        }
+    }
+
+    let index = options
+        .index_template
+        .replace("{{building_name}}", &escape_html(&building.name))
+        .replace("{{room_cards}}", cards.trim_end());
+    let index_path = out_dir.join("index.html");
+    std::fs::write(&index_path, index)
+        .with_context(|| format!("writing {}", index_path.display()))?;
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, RoomType, Wing};
+
+    fn sample() -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("Conf <A>".into(), RoomType::Office);
+        room.properties
+            .insert("temperature".into(), "21.456".into());
+        room.add_equipment(Equipment::new(
+            "Projector".into(),
+            String::new(),
+            EquipmentType::AV,
+        ));
+        wing.add_room(room);
+        wing.add_room(Room::new("Conf A".into(), RoomType::Office));
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    #[test]
+    fn renders_page_with_escaped_fields_and_report_link() {
+        let b = sample();
+        let floor = &b.floors[0];
+        let room = &floor.wings[0].rooms[0];
+        let opts =
+            RoomPageOptions::with_templates_from(Path::new("/nonexistent"), "https://hq.example/")
+                .unwrap();
+        let html = render_room_page(&b, floor, room, &opts, &opts.page_template);
+        assert!(html.contains("<h1>Conf &lt;A&gt;</h1>"));
+        assert!(html.contains("21.5 °C"));
+        assert!(html.contains("<td>Projector</td>"));
+        assert!(html.contains(&format!(
+            "href=\"https://hq.example/issues/new?room={}\"",
+            room.id
+        )));
+    }
+
+    #[test]
+    fn writes_unique_pages_qr_codes_and_index() {
+        let b = sample();
+        let dir = tempfile::tempdir().unwrap();
+        let opts = RoomPageOptions::with_templates_from(dir.path(), "https://hq.example").unwrap();
+        let pages = write_room_pages(&b, &dir.path().join("site"), &opts).unwrap();
+
+        // "Conf <A>" and "Conf A" slug to the same name; the second gets an id suffix
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].slug, "0-conf-a");
+        assert_ne!(pages[0].slug, pages[1].slug);
+        assert_eq!(pages[0].url, "https://hq.example/rooms/0-conf-a.html");
+        assert!(std::fs::read_to_string(&pages[0].qr_path)
+            .unwrap()
+            .contains("<svg"));
+        let index = std::fs::read_to_string(dir.path().join("site/index.html")).unwrap();
+        assert!(index.contains("qr/0-conf-a.svg"));
+    }
+
+    #[test]
+    fn project_template_override_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let tpl_dir = dir.path().join(TEMPLATE_OVERRIDE_DIR);
+        std::fs::create_dir_all(&tpl_dir).unwrap();
+        std::fs::write(tpl_dir.join("room-page.html"), "ROOM {{room_name}}").unwrap();
+        let opts = RoomPageOptions::with_templates_from(dir.path(), "https://x").unwrap();
+        assert_eq!(opts.page_template, "ROOM {{room_name}}");
+        assert_eq!(opts.index_template, DEFAULT_ROOM_INDEX_TEMPLATE);
+    }
+}
@@ -0,0 +1,199 @@
+//! Vector floor plans.
+//!
+//! [`render_floor_svg`] draws the same plan as [`super::png`] (shared
+//! framing and scale via `layout_floor`) as an SVG document: room polygons
+//! with labels, and equipment markers coloured by their state. Rooms and
+//! markers carry `<title>` tooltips and `data-*` attributes so a browser or
+//! drawing tool can pick them out.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use anyhow::Result;
+
+use super::embed::{equipment_state, floor_summary, RoomState};
+use super::png::{
+    layout_floor, overlay_fill, FloorOverlay, FloorPngOptions, Rgb, BACKGROUND, LABEL, ROOM_EDGE,
+    ROOM_FILL,
+};
+use super::site::escape_html;
+use crate::core::Building;
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn points(outline: &[(f64, f64)]) -> String {
+    outline
+        .iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// SVG of the floor at `level` with the PNG export's options: rooms, labels,
+/// equipment markers coloured by state, and the chosen room overlay.
+pub fn render_floor_svg(
+    building: &Building,
+    level: i32,
+    options: &FloorPngOptions,
+) -> Result<String> {
+    let layout = layout_floor(building, level, options.width)?;
+    let (width, height) = (layout.width, layout.height);
+    let pen = layout.pen();
+    let states: HashMap<String, RoomState> = match options.overlay {
+        FloorOverlay::Status => floor_summary(building, level, &HashMap::new())
+            .map(|s| s.rooms.into_iter().map(|r| (r.id, r.state)).collect())
+            .unwrap_or_default(),
+        FloorOverlay::None => HashMap::new(),
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width,
+        h = height
+    );
+    let _ = writeln!(
+        svg,
+        "<title>{} · {}</title>",
+        escape_html(&building.name),
+        escape_html(&layout.floor.name)
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        hex(BACKGROUND)
+    );
+
+    let _ = writeln!(
+        svg,
+        "<g class=\"rooms\" stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"round\">",
+        hex(ROOM_EDGE),
+        pen
+    );
+    for (room, outline) in &layout.rooms {
+        let projected: Vec<(f64, f64)> = outline.iter().map(|p| layout.project(*p)).collect();
+        let fill = states.get(&room.id).map_or(ROOM_FILL, |s| overlay_fill(*s));
+        let state = states
+            .get(&room.id)
+            .map(|s| format!(" data-state=\"{}\"", s.as_str()))
+            .unwrap_or_default();
+        let _ = writeln!(
+            svg,
+            "<polygon data-room=\"{}\"{} points=\"{}\" fill=\"{}\"><title>{}</title></polygon>",
+            escape_html(&room.id),
+            state,
+            points(&projected),
+            hex(fill),
+            escape_html(&room.name)
+        );
+    }
+    svg.push_str("</g>\n");
+
+    if options.labels {
+        let size = 4 * (pen + 1);
+        let _ = writeln!(
+            svg,
+            "<g class=\"labels\" fill=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"middle\">",
+            hex(LABEL),
+            size
+        );
+        for (room, outline) in &layout.rooms {
+            let projected: Vec<(f64, f64)> = outline.iter().map(|p| layout.project(*p)).collect();
+            let n = projected.len() as f64;
+            let (cx, cy) = (
+                projected.iter().map(|p| p.0).sum::<f64>() / n,
+                projected.iter().map(|p| p.1).sum::<f64>() / n,
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+                cx,
+                cy,
+                escape_html(&room.name)
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    if options.equipment {
+        let r = 2 * pen + 1;
+        svg.push_str("<g class=\"equipment\" stroke=\"#ffffff\" stroke-width=\"1\">\n");
+        for eq in &layout.equipment {
+            let (x, y) = layout.project((eq.position.x, eq.position.y));
+            let state = equipment_state(eq);
+            let _ = writeln!(
+                svg,
+                "<circle data-equipment=\"{}\" data-state=\"{}\" cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\"><title>{} ({}, {})</title></circle>",
+                escape_html(&eq.id),
+                state.as_str(),
+                x,
+                y,
+                r,
+                state.color(),
+                escape_html(&eq.name),
+                escape_html(&eq.equipment_type.to_string()),
+                eq.status
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        Equipment, EquipmentHealthStatus, EquipmentType, Floor, Room, RoomType, Wing,
+    };
+
+    #[test]
+    fn test_render_floor_svg_rooms_labels_and_status_markers() {
+        let mut room = Room::new("Plant & Boiler".into(), RoomType::Mechanical);
+        room.properties
+            .insert("floor_polygon".into(), "0,0;20,0;20,10;0,10".into());
+        let mut ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        ahu.health_status = Some(EquipmentHealthStatus::Critical);
+        ahu.position.x = 18.0;
+        ahu.position.y = 8.0;
+        let pump = Equipment::new("P-1".into(), String::new(), EquipmentType::Plumbing);
+        room.add_equipment(ahu);
+        room.add_equipment(pump);
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Second".into(), 2);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let options = FloorPngOptions {
+            width: 200,
+            ..Default::default()
+        };
+        let svg = render_floor_svg(&building, 2, &options).unwrap();
+        // Same framing as the PNG: 200 wide, 16 px margins, 8.4 px per metre
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"116\""));
+        assert!(svg.contains("points=\"16.0,100.0 184.0,100.0 184.0,16.0 16.0,16.0\""));
+        assert!(svg.contains(">Plant &amp; Boiler</text>"));
+        assert!(svg.contains("data-state=\"critical\" cx=\"167.2\" cy=\"32.8\""));
+        assert!(svg.contains(&format!("fill=\"{}\"", RoomState::Ok.color())));
+        assert!(!svg.contains("data-state=\"critical\" points"));
+
+        let overlay = FloorPngOptions {
+            overlay: FloorOverlay::Status,
+            ..options
+        };
+        let svg = render_floor_svg(&building, 2, &overlay).unwrap();
+        assert!(svg.contains(&format!(
+            "data-state=\"critical\" points=\"16.0,100.0 184.0,100.0 184.0,16.0 16.0,16.0\" fill=\"{}\"",
+            hex(overlay_fill(RoomState::Critical))
+        )));
+        assert!(render_floor_svg(&building, 0, &overlay).is_err());
+    }
+}
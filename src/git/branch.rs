@@ -0,0 +1,324 @@
+//! Branches for "what-if" scenarios and building-aware merges between them.
+
+use super::diff::read_file_at;
+use super::manager::{simulated_git, DRY_RUN_COMMIT_ID};
+use super::merge::{merge_buildings, BuildingMerge, MergePreference};
+use super::{GitConfig, GitError};
+use crate::persistence::BUILDING_YAML;
+use crate::yaml::BuildingYamlSerializer;
+use git2::build::CheckoutBuilder;
+use git2::{BranchType, Commit, IndexEntry, IndexTime, Oid, Repository, Signature, StatusOptions};
+
+/// A local branch.
+#[derive(Debug, Clone)]
+pub struct BranchInfo {
+    pub name: String,
+    /// Checked out
+    pub current: bool,
+    pub commit: String,
+    /// First line of the tip commit message
+    pub summary: String,
+}
+
+/// How a merge was carried out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeKind {
+    /// The branch had nothing new
+    UpToDate,
+    FastForward,
+    /// A merge commit with both tips as parents
+    Merged,
+}
+
+/// Outcome of [`merge_branch`]. Nothing is written while [`has_conflicts`]
+/// holds.
+///
+/// [`has_conflicts`]: MergeReport::has_conflicts
+#[derive(Debug, Clone)]
+pub struct MergeReport {
+    pub kind: MergeKind,
+    pub commit: Option<String>,
+    /// Entity-level merge of building.yaml, when both branches changed it
+    pub building: Option<BuildingMerge>,
+    /// Other files Git could not merge
+    pub file_conflicts: Vec<String>,
+}
+
+impl MergeReport {
+    pub fn has_conflicts(&self) -> bool {
+        !self.file_conflicts.is_empty()
+            || self
+                .building
+                .as_ref()
+                .is_some_and(|m| !m.conflicts.is_empty())
+    }
+}
+
+fn branch_commit<'r>(repo: &'r Repository, name: &str) -> Result<Commit<'r>, GitError> {
+    let branch = repo
+        .find_branch(name, BranchType::Local)
+        .map_err(|_| GitError::Generic(format!("No branch named '{}'", name)))?;
+    Ok(branch.get().peel_to_commit()?)
+}
+
+fn head_commit(repo: &Repository) -> Result<Commit<'_>, GitError> {
+    repo.head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|_| GitError::Generic("No commits yet; commit building.yaml first".into()))
+}
+
+/// Refuse to move the working tree while tracked files have uncommitted edits.
+fn ensure_clean(repo: &Repository, operation: &str) -> Result<(), GitError> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let dirty: Vec<String> = repo
+        .statuses(Some(&mut options))?
+        .iter()
+        .filter(|s| !s.status().is_empty())
+        .filter_map(|s| s.path().map(str::to_string))
+        .collect();
+    if dirty.is_empty() {
+        return Ok(());
+    }
+    Err(GitError::OperationFailed {
+        operation: operation.to_string(),
+        reason: format!(
+            "uncommitted changes in {}; commit or discard them first",
+            dirty.join(", ")
+        ),
+    })
+}
+
+/// Local branches, sorted by name.
+pub fn list_branches(repo: &Repository) -> Result<Vec<BranchInfo>, GitError> {
+    let mut branches = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Some(name) = branch.name()?.map(str::to_string) else {
+            continue;
+        };
+        let commit = branch.get().peel_to_commit()?;
+        branches.push(BranchInfo {
+            name,
+            current: branch.is_head(),
+            commit: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+        });
+    }
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(branches)
+}
+
+/// Create `name` at `from` (any revision; default HEAD) and return its commit id.
+pub fn create_branch(
+    repo: &Repository,
+    name: &str,
+    from: Option<&str>,
+) -> Result<String, GitError> {
+    if !git2::Branch::name_is_valid(name)? {
+        return Err(GitError::Generic(format!(
+            "'{}' is not a valid branch name",
+            name
+        )));
+    }
+    let commit = match from {
+        Some(revision) => repo.revparse_single(revision)?.peel_to_commit()?,
+        None => head_commit(repo)?,
+    };
+    repo.branch(name, &commit, false)?;
+    Ok(commit.id().to_string())
+}
+
+/// Check out `name`, updating building.yaml and the other tracked files.
+pub fn switch_branch(repo: &Repository, name: &str) -> Result<(), GitError> {
+    let commit = branch_commit(repo, name)?;
+    ensure_clean(repo, &format!("switch to {}", name))?;
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+    repo.set_head(&format!("refs/heads/{}", name))?;
+    Ok(())
+}
+
+fn building_at(repo: &Repository, commit: Oid) -> Option<crate::core::Building> {
+    read_file_at(repo, &commit.to_string(), BUILDING_YAML)
+        .ok()
+        .flatten()
+        .and_then(|yaml| BuildingYamlSerializer::deserialize_building(&yaml).ok())
+}
+
+/// Merge branch `name` into the checked-out branch.
+///
+/// Files other than building.yaml merge as Git would. When both branches changed
+/// building.yaml it is merged entity by entity ([`merge_buildings`]); conflicts
+/// on the same entity stop the merge unless `prefer` picks a side.
+pub fn merge_branch(
+    repo: &Repository,
+    config: &GitConfig,
+    name: &str,
+    prefer: Option<MergePreference>,
+) -> Result<MergeReport, GitError> {
+    let theirs = branch_commit(repo, name)?;
+    let ours = head_commit(repo)?;
+    let mut report = MergeReport {
+        kind: MergeKind::UpToDate,
+        commit: None,
+        building: None,
+        file_conflicts: Vec::new(),
+    };
+    if ours.id() == theirs.id() || repo.graph_descendant_of(ours.id(), theirs.id())? {
+        return Ok(report);
+    }
+    ensure_clean(repo, &format!("merge {}", name))?;
+
+    let base = repo.merge_base(ours.id(), theirs.id())?;
+    if base == ours.id() {
+        report.kind = MergeKind::FastForward;
+        if simulated_git("merge --ff-only", Some(name)) {
+            report.commit = Some(DRY_RUN_COMMIT_ID.to_string());
+            return Ok(report);
+        }
+        repo.checkout_tree(theirs.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        repo.head()?
+            .set_target(theirs.id(), &format!("merge {}: fast-forward", name))?;
+        report.commit = Some(theirs.id().to_string());
+        return Ok(report);
+    }
+
+    let mut index = repo.merge_commits(&ours, &theirs, None)?;
+    let yaml = |id: Oid| {
+        read_file_at(repo, &id.to_string(), BUILDING_YAML)
+            .ok()
+            .flatten()
+    };
+    let (base_yaml, ours_yaml, theirs_yaml) = (yaml(base), yaml(ours.id()), yaml(theirs.id()));
+    if base_yaml != ours_yaml && base_yaml != theirs_yaml && ours_yaml != theirs_yaml {
+        if let (Some(b), Some(o), Some(t)) = (
+            building_at(repo, base),
+            building_at(repo, ours.id()),
+            building_at(repo, theirs.id()),
+        ) {
+            let merged = merge_buildings(&b, &o, &t, prefer);
+            let content = BuildingYamlSerializer::serialize_building(&merged.building)
+                .map_err(|e| GitError::SerializationError(e.to_string()))?;
+            // Only present when Git's line merge of the file conflicted
+            let _ = index.conflict_remove(std::path::Path::new(BUILDING_YAML));
+            let entry = IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: content.len() as u32,
+                id: repo.blob(content.as_bytes())?,
+                flags: 0,
+                flags_extended: 0,
+                path: BUILDING_YAML.as_bytes().to_vec(),
+            };
+            index.add(&entry)?;
+            report.building = Some(merged);
+        }
+    }
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            report
+                .file_conflicts
+                .push(String::from_utf8_lossy(&entry.path).into_owned());
+        }
+    }
+    if report.has_conflicts() {
+        return Ok(report);
+    }
+    report.kind = MergeKind::Merged;
+    if simulated_git("merge", Some(name)) {
+        report.commit = Some(DRY_RUN_COMMIT_ID.to_string());
+        return Ok(report);
+    }
+
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+    let signature = Signature::now(&config.author_name, &config.author_email)?;
+    let mut message = format!("Merge branch '{}'", name);
+    if let Some(merged) = &report.building {
+        let mut lines: Vec<String> = merged.changes.iter().map(|c| format!("- {}", c)).collect();
+        if let Some(side) = prefer {
+            lines.extend(
+                merged
+                    .resolved
+                    .iter()
+                    .map(|c| format!("- resolved ({}): {}", side, c)),
+            );
+        }
+        if !lines.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(&lines.join("\n"));
+        }
+    }
+    let commit =
+        super::signing::create_commit(repo, &signature, &message, &tree, &[&ours, &theirs])?;
+    repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+    report.commit = Some(commit.to_string());
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Floor, Room, RoomType, Wing};
+    use crate::git::GitConfigManager;
+    use crate::persistence::{load_building_at, PersistenceManager};
+
+    fn edit_room(base: &std::path::Path, name: &str, key: &str, message: &str) {
+        let mut building = load_building_at(base).unwrap();
+        building
+            .get_all_rooms_mut()
+            .into_iter()
+            .find(|r| r.name == name)
+            .unwrap()
+            .properties
+            .insert(key.into(), "yes".into());
+        PersistenceManager::at(base)
+            .save_and_commit(&building, Some(message))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_branch_switch_and_entity_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut wing = Wing::new("East".into());
+        wing.add_room(Room::new("Office".into(), RoomType::Office));
+        wing.add_room(Room::new("Plant".into(), RoomType::Mechanical));
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        PersistenceManager::at(dir.path())
+            .save_and_commit(&building, Some("Initial"))
+            .unwrap();
+        let main = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        create_branch(&repo, "reno", None).unwrap();
+        switch_branch(&repo, "reno").unwrap();
+        edit_room(dir.path(), "Office", "renovated", "Renovate office");
+        switch_branch(&repo, &main).unwrap();
+        edit_room(dir.path(), "Plant", "inspected", "Inspect plant");
+
+        let branches = list_branches(&repo).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert!(branches.iter().any(|b| b.name == main && b.current));
+
+        let config = GitConfigManager::default_config();
+        let report = merge_branch(&repo, &config, "reno", None).unwrap();
+        assert_eq!(report.kind, MergeKind::Merged, "{:?}", report);
+        assert!(!report.has_conflicts());
+        let merged = load_building_at(dir.path()).unwrap();
+        let rooms = merged.get_all_rooms();
+        assert!(rooms.iter().all(|r| r.properties.len() == 1));
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_count(), 2);
+
+        let report = merge_branch(&repo, &config, "reno", None).unwrap();
+        assert_eq!(report.kind, MergeKind::UpToDate);
+    }
+}
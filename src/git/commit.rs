@@ -1,5 +1,6 @@
 //! Commit operations for Git repository
 
+use super::signing::create_commit;
 use super::{CommitMetadata, GitConfig, GitError};
 use git2::{Repository, Signature};
 use std::path::Path;
@@ -54,18 +55,16 @@ pub fn commit_changes_with_metadata(
     // Build enhanced commit message with Git trailers
     let enhanced_message = build_commit_message(metadata);
 
-    // Create commit
-    let commit_id = repo
-        .commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            &enhanced_message,
-            &tree,
-            &parent_commit.iter().collect::<Vec<_>>(),
-        )
-        .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    // Create commit (GPG-signed when the signing policy asks for it)
+    let commit_id = create_commit(
+        repo,
+        &signature,
+        &enhanced_message,
+        &tree,
+        &parent_commit.iter().collect::<Vec<_>>(),
+    )?;
 
+    announce_commit(repo, config, metadata, &commit_id.to_string());
     Ok(commit_id.to_string())
 }
 
@@ -123,21 +122,36 @@ pub fn commit_staged_with_user(
     // Build enhanced commit message with Git trailers
     let enhanced_message = build_commit_message(metadata);
 
-    // Create commit
-    let commit_id = repo
-        .commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            &enhanced_message,
-            &tree,
-            &parent_commit.iter().collect::<Vec<_>>(),
-        )
-        .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    // Create commit (GPG-signed when the signing policy asks for it)
+    let commit_id = create_commit(
+        repo,
+        &signature,
+        &enhanced_message,
+        &tree,
+        &parent_commit.iter().collect::<Vec<_>>(),
+    )?;
 
+    announce_commit(repo, config, metadata, &commit_id.to_string());
     Ok(commit_id.to_string())
 }
 
+/// Tell configured webhooks about a new commit (best-effort).
+fn announce_commit(repo: &Repository, config: &GitConfig, metadata: &CommitMetadata, id: &str) {
+    if let Some(workdir) = repo.workdir() {
+        crate::notifications::notify(
+            workdir,
+            &[crate::notifications::Event::Commit {
+                commit: id.to_string(),
+                message: metadata.message.clone(),
+                author: metadata
+                    .user_id
+                    .clone()
+                    .unwrap_or_else(|| config.author_name.clone()),
+            }],
+        );
+    }
+}
+
 /// Build commit message with Git trailers (standard Git practice)
 pub fn build_commit_message(metadata: &CommitMetadata) -> String {
     let mut message = metadata.message.clone();
@@ -174,6 +174,76 @@ pub fn get_file_history(repo: &Repository, file_path: &str) -> Result<Vec<Commit
     Ok(commits)
 }
 
+/// Commits of building.yaml scanned by [`get_entity_history`]
+pub const ENTITY_HISTORY_SCAN: usize = 100;
+
+/// Commits (newest first, at most `limit`) that changed the room, equipment,
+/// or other entity with `entity_id` in building.yaml, looking back over the
+/// last [`ENTITY_HISTORY_SCAN`] commits of the file.
+pub fn get_entity_history(
+    repo: &Repository,
+    entity_id: &str,
+    limit: usize,
+) -> Result<Vec<CommitInfo>, GitError> {
+    use crate::core::twin::{TwinEntity, TwinSnapshot};
+    use crate::persistence::BUILDING_YAML;
+    use crate::yaml::BuildingYamlSerializer;
+
+    let state = |yaml: Option<&str>| {
+        yaml.and_then(|y| BuildingYamlSerializer::deserialize_building(y).ok())
+            .and_then(|b| {
+                TwinSnapshot::capture(&b, &Default::default())
+                    .entities
+                    .remove(entity_id)
+            })
+    };
+
+    let history = get_file_history(repo, BUILDING_YAML)?;
+    let complete = history.len() <= ENTITY_HISTORY_SCAN;
+    let mut changed = Vec::new();
+    let mut newer: Option<(CommitInfo, Option<String>, Option<TwinEntity>)> = None;
+    for commit in history.into_iter().take(ENTITY_HISTORY_SCAN) {
+        let yaml = read_file_at(repo, &commit.id, BUILDING_YAML)?;
+        let entity = match &newer {
+            Some((_, newer_yaml, newer_entity)) if *newer_yaml == yaml => newer_entity.clone(),
+            _ => state(yaml.as_deref()),
+        };
+        if let Some((newer_commit, _, newer_entity)) = newer.take() {
+            if newer_entity != entity {
+                changed.push(newer_commit);
+            }
+        }
+        newer = Some((commit, yaml, entity));
+    }
+    // The oldest commit of the whole history created the entity if it holds it
+    if let Some((oldest, _, Some(_))) = newer.filter(|_| complete) {
+        changed.push(oldest);
+    }
+    changed.truncate(limit);
+    Ok(changed)
+}
+
+/// Contents of `file_path` as of `commit_hash` (`None` when absent there)
+pub fn read_file_at(
+    repo: &Repository,
+    commit_hash: &str,
+    file_path: &str,
+) -> Result<Option<String>, GitError> {
+    let oid = git2::Oid::from_str(commit_hash)
+        .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    let tree = repo
+        .find_commit(oid)
+        .and_then(|c| c.tree())
+        .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    let Ok(entry) = tree.get_path(Path::new(file_path)) else {
+        return Ok(None);
+    };
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+}
+
 /// Get diff between commits
 pub fn get_diff(
     repo: &Repository,
@@ -353,3 +423,67 @@ pub fn get_diff_stats(repo: &Repository, commit_hash: Option<&str>) -> Result<Di
         deletions: diff_result.deletions,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Floor, Room, RoomType, Wing};
+    use crate::persistence::BUILDING_YAML;
+    use crate::yaml::BuildingYamlSerializer;
+    use git2::Signature;
+
+    fn commit_building(repo: &Repository, building: &Building, message: &str) -> String {
+        let yaml = BuildingYamlSerializer::serialize_building(building).unwrap();
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(BUILDING_YAML), yaml).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(BUILDING_YAML)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test User", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+        .to_string()
+    }
+
+    #[test]
+    fn test_entity_history_lists_commits_that_changed_the_entity() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("Main".into());
+        wing.add_room(Room::new("Lab".into(), RoomType::Laboratory));
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        let lab = building.floors[0].wings[0].rooms[0].id.clone();
+        let created = commit_building(&repo, &building, "Add lab");
+
+        building.floors[0].wings[0].add_room(Room::new("Office".into(), RoomType::Office));
+        let office = building.floors[0].wings[0].rooms[1].id.clone();
+        let added = commit_building(&repo, &building, "Add office");
+
+        building.floors[0].wings[0].rooms[0].name = "Wet Lab".into();
+        let renamed = commit_building(&repo, &building, "Rename lab");
+
+        let ids = |id: &str| -> Vec<String> {
+            get_entity_history(&repo, id, 10)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.id)
+                .collect()
+        };
+        assert_eq!(ids(&lab), [renamed, created]);
+        assert_eq!(ids(&office), [added]);
+        assert_eq!(get_entity_history(&repo, &lab, 1).unwrap().len(), 1);
+    }
+}
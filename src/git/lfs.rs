@@ -0,0 +1,340 @@
+//! Git LFS handling for large binary artifacts.
+//!
+//! Point clouds, photos, and IFC sources are committed as Git LFS pointers
+//! instead of blobs. `arx init` marks the configured patterns with
+//! `filter=lfs` in `.gitattributes` ([`install_lfs`]); when the `git-lfs`
+//! binary is present it also runs `git lfs install --local`, so plain `git`
+//! commands clean and smudge as usual.
+//!
+//! libgit2 does not run filters, so arx does the LFS part itself: staging an
+//! LFS path stores the content under `.git/lfs/objects` and adds the pointer
+//! ([`stage_lfs_file`]), and readers go through [`resolve_path`], which turns
+//! a pointer left in the working tree (a clone without `git-lfs`, or
+//! `GIT_LFS_SKIP_SMUDGE=1`) into the local object.
+//!
+//! Patterns are configured in `.arx/lfs.yaml`:
+//!
+//! ```yaml
+//! patterns: ["*.las", "*.laz", "scans/**/*.e57"]
+//! ```
+
+use super::GitError;
+use git2::{AttrCheckFlags, Index, IndexEntry, IndexTime, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Repo-relative LFS pattern configuration.
+pub const LFS_CONFIG_FILE: &str = ".arx/lfs.yaml";
+
+/// Tracked when `.arx/lfs.yaml` is absent: point clouds, photos, IFC sources.
+pub const DEFAULT_LFS_PATTERNS: &[&str] = &[
+    "*.las", "*.laz", "*.e57", "*.ply", "*.pcd", "*.ifc", "*.ifczip", "*.jpg", "*.jpeg", "*.png",
+    "*.heic",
+];
+
+/// First line of every LFS pointer (spec v1).
+pub const LFS_POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// Files at least this large are never treated as pointers.
+const MAX_POINTER_SIZE: u64 = 1024;
+
+/// `.arx/lfs.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LfsConfig {
+    /// `.gitattributes` patterns stored in LFS
+    pub patterns: Vec<String>,
+}
+
+impl LfsConfig {
+    /// `None` when the repository uses [`DEFAULT_LFS_PATTERNS`].
+    pub fn load(base: &Path) -> Result<Option<Self>, GitError> {
+        let path = base.join(LFS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        let config = serde_yaml::from_str(&content).map_err(|e| GitError::InvalidConfig {
+            reason: format!("{}: {}", path.display(), e),
+        })?;
+        Ok(Some(config))
+    }
+
+    /// Configured patterns, else the defaults.
+    pub fn patterns(base: &Path) -> Result<Vec<String>, GitError> {
+        Ok(match Self::load(base)? {
+            Some(config) => config.patterns,
+            None => DEFAULT_LFS_PATTERNS.iter().map(|p| p.to_string()).collect(),
+        })
+    }
+}
+
+/// What Git stores in place of an LFS file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsPointer {
+    /// SHA-256 of the content, lowercase hex
+    pub oid: String,
+    pub size: u64,
+}
+
+impl LfsPointer {
+    pub fn render(&self) -> String {
+        format!(
+            "{}\noid sha256:{}\nsize {}\n",
+            LFS_POINTER_VERSION, self.oid, self.size
+        )
+    }
+
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        if lines.next()?.trim() != LFS_POINTER_VERSION {
+            return None;
+        }
+        let (mut oid, mut size) = (None, None);
+        for line in lines {
+            match line.trim().split_once(' ') {
+                Some(("oid", v)) => oid = v.trim().strip_prefix("sha256:").map(str::to_lowercase),
+                Some(("size", v)) => size = v.trim().parse().ok(),
+                _ => {}
+            }
+        }
+        let oid = oid?;
+        if oid.len() != 64 || !oid.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(Self { oid, size: size? })
+    }
+
+    /// Pointer stored at `path`, or `None` for a regular file.
+    pub fn read(path: &Path) -> Result<Option<Self>, GitError> {
+        if fs::metadata(path)?.len() >= MAX_POINTER_SIZE {
+            return Ok(None);
+        }
+        let bytes = fs::read(path)?;
+        Ok(std::str::from_utf8(&bytes).ok().and_then(Self::parse))
+    }
+
+    /// Local object path under `git_dir`: `lfs/objects/aa/bb/<oid>`.
+    pub fn object_path(&self, git_dir: &Path) -> PathBuf {
+        git_dir
+            .join("lfs/objects")
+            .join(&self.oid[..2])
+            .join(&self.oid[2..4])
+            .join(&self.oid)
+    }
+}
+
+fn git_lfs_available() -> bool {
+    Command::new("git")
+        .args(["lfs", "version"])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Mark the configured patterns as LFS in `repo_root`'s `.gitattributes`
+/// and, when `git-lfs` is installed, register its filter for this
+/// repository. Returns the number of patterns added.
+pub fn install_lfs(repo_root: &Path) -> Result<usize, GitError> {
+    Repository::open(repo_root)?;
+    let attributes = repo_root.join(".gitattributes");
+    let mut content = fs::read_to_string(&attributes).unwrap_or_default();
+    let mut added = 0;
+    for pattern in LfsConfig::patterns(repo_root)? {
+        let line = format!("{} filter=lfs diff=lfs merge=lfs -text", pattern);
+        if content.lines().any(|l| l.trim() == line) {
+            continue;
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&line);
+        content.push('\n');
+        added += 1;
+    }
+    if added > 0 {
+        fs::write(&attributes, content)?;
+    }
+    if git_lfs_available() {
+        let output = Command::new("git")
+            .args(["lfs", "install", "--local"])
+            .current_dir(repo_root)
+            .output()?;
+        if !output.status.success() {
+            return Err(GitError::OperationFailed {
+                operation: "git lfs install".into(),
+                reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+    }
+    Ok(added)
+}
+
+/// Whether `.gitattributes` routes `file_path` through the LFS filter.
+pub fn is_lfs_path(repo: &Repository, file_path: &str) -> bool {
+    repo.get_attr(
+        Path::new(file_path),
+        "filter",
+        AttrCheckFlags::FILE_THEN_INDEX,
+    )
+    .ok()
+    .flatten()
+        == Some("lfs")
+}
+
+/// Store the working-tree content of `file_path` as an LFS object and return
+/// its pointer. A file that already is a pointer is returned as is.
+pub fn clean(repo: &Repository, file_path: &str) -> Result<LfsPointer, GitError> {
+    let workdir = repo.workdir().ok_or_else(|| GitError::OperationFailed {
+        operation: "lfs clean".into(),
+        reason: "bare repository".into(),
+    })?;
+    let path = workdir.join(file_path);
+    if let Some(pointer) = LfsPointer::read(&path)? {
+        return Ok(pointer);
+    }
+    let mut file = fs::File::open(&path)?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)?;
+    let pointer = LfsPointer {
+        oid: format!("{:x}", hasher.finalize()),
+        size,
+    };
+    let object = pointer.object_path(repo.path());
+    if !object.exists() {
+        if let Some(parent) = object.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let partial = object.with_extension("partial");
+        fs::copy(&path, &partial)?;
+        fs::rename(&partial, &object)?;
+    }
+    Ok(pointer)
+}
+
+/// Add the LFS pointer for `file_path` to `index` in place of its content.
+pub fn stage_lfs_file(
+    repo: &Repository,
+    index: &mut Index,
+    file_path: &str,
+) -> Result<LfsPointer, GitError> {
+    let pointer = clean(repo, file_path)?;
+    let entry = IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: 0o100644,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: Oid::zero(),
+        flags: 0,
+        flags_extended: 0,
+        path: file_path.as_bytes().to_vec(),
+    };
+    index.add_frombuffer(&entry, pointer.render().as_bytes())?;
+    Ok(pointer)
+}
+
+/// Readable path for `path`: itself, unless it is an LFS pointer, in which
+/// case the local object (fetched with `git lfs smudge` when missing).
+pub fn resolve_path(path: &Path) -> Result<PathBuf, GitError> {
+    if !path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    let Some(pointer) = LfsPointer::read(path)? else {
+        return Ok(path.to_path_buf());
+    };
+    let start = path.parent().unwrap_or(Path::new("."));
+    let repo = Repository::discover(start).map_err(|_| GitError::RepositoryNotFound {
+        path: path.display().to_string(),
+    })?;
+    let object = pointer.object_path(repo.path());
+    if object.exists() {
+        return Ok(object);
+    }
+    let workdir = repo.workdir().unwrap_or(start);
+    if !git_lfs_available() || smudge(workdir, &pointer, &object).is_err() {
+        return Err(GitError::OperationFailed {
+            operation: "lfs smudge".into(),
+            reason: format!(
+                "{} is an LFS pointer and its content is not available locally; run `git lfs pull`",
+                path.display()
+            ),
+        });
+    }
+    Ok(object)
+}
+
+/// Fetch `pointer`'s content with `git lfs smudge` into `object`.
+fn smudge(workdir: &Path, pointer: &LfsPointer, object: &Path) -> Result<(), GitError> {
+    let mut child = Command::new("git")
+        .args(["lfs", "smudge"])
+        .current_dir(workdir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(pointer.render().as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    let content = output.stdout;
+    if !output.status.success() || format!("{:x}", Sha256::digest(&content)) != pointer.oid {
+        return Err(GitError::Generic("git lfs smudge failed".into()));
+    }
+    if let Some(parent) = object.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(object, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointer_round_trips_spec_format() {
+        let pointer = LfsPointer {
+            oid: "a".repeat(64),
+            size: 12345,
+        };
+        let text = pointer.render();
+        assert!(text.starts_with(LFS_POINTER_VERSION));
+        assert_eq!(LfsPointer::parse(&text), Some(pointer));
+        assert_eq!(LfsPointer::parse("version 1\nsize 3\n"), None);
+    }
+
+    #[test]
+    fn staging_lfs_paths_commits_pointers_and_resolves_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut repo = Repository::init(dir.path()).unwrap();
+        assert!(install_lfs(dir.path()).unwrap() > 0);
+        assert_eq!(install_lfs(dir.path()).unwrap(), 0);
+        assert!(is_lfs_path(&repo, "scans/site.las"));
+        assert!(!is_lfs_path(&repo, "building.yaml"));
+
+        fs::create_dir_all(dir.path().join("scans")).unwrap();
+        let scan = dir.path().join("scans/site.las");
+        fs::write(&scan, b"LASF point data").unwrap();
+        super::super::staging::stage_file(&mut repo, "scans/site.las").unwrap();
+
+        let index = repo.index().unwrap();
+        let entry = index.get_path(Path::new("scans/site.las"), 0).unwrap();
+        let blob = repo.find_blob(entry.id).unwrap();
+        let pointer = LfsPointer::parse(std::str::from_utf8(blob.content()).unwrap()).unwrap();
+        assert_eq!(pointer.size, 15);
+
+        // A clone without git-lfs checks out the pointer
+        fs::write(&scan, pointer.render()).unwrap();
+        let resolved = resolve_path(&scan).unwrap();
+        assert_eq!(fs::read(resolved).unwrap(), b"LASF point data");
+        let yaml = dir.path().join("building.yaml");
+        fs::write(&yaml, "name: HQ\n").unwrap();
+        assert_eq!(resolve_path(&yaml).unwrap(), yaml);
+    }
+}
@@ -1,7 +1,14 @@
 // Real Git operations for ArxOS
+use super::branch::{
+    create_branch, list_branches, merge_branch, switch_branch, BranchInfo, MergeReport,
+};
 use super::commit::{commit_staged, commit_staged_with_user};
-use super::diff::{get_diff, get_diff_stats, get_file_history, get_status, list_commits};
+use super::diff::{
+    get_diff, get_diff_stats, get_entity_history, get_file_history, get_status, list_commits,
+    read_file_at,
+};
 use super::export::export_building;
+use super::merge::MergePreference;
 use super::repository::initialize_repository;
 use super::staging::{stage_all, stage_file, unstage_all, unstage_file};
 use crate::yaml::BuildingYamlSerializer;
@@ -95,6 +102,36 @@ impl BuildingGitManager {
         )
     }
 
+    /// Local branches (scenarios), sorted by name
+    pub fn list_branches(&self) -> Result<Vec<BranchInfo>, GitError> {
+        list_branches(&self.repo)
+    }
+
+    /// Create a branch at `from` (default HEAD) without switching to it
+    pub fn create_branch(&self, name: &str, from: Option<&str>) -> Result<String, GitError> {
+        if simulated_git("branch", Some(name)) {
+            return Ok(DRY_RUN_COMMIT_ID.to_string());
+        }
+        create_branch(&self.repo, name, from)
+    }
+
+    /// Check out another branch
+    pub fn switch_branch(&self, name: &str) -> Result<(), GitError> {
+        if simulated_git("switch", Some(name)) {
+            return Ok(());
+        }
+        switch_branch(&self.repo, name)
+    }
+
+    /// Merge a branch into the current one, building.yaml entity by entity
+    pub fn merge_branch(
+        &self,
+        name: &str,
+        prefer: Option<MergePreference>,
+    ) -> Result<MergeReport, GitError> {
+        merge_branch(&self.repo, &self.git_config, name, prefer)
+    }
+
     /// Get repository status
     pub fn get_status(&self) -> Result<super::GitStatus, GitError> {
         get_status(&self.repo, &self.git_config.branch)
@@ -110,6 +147,36 @@ impl BuildingGitManager {
         get_file_history(&self.repo, file_path)
     }
 
+    /// Recent commits that changed one entity in building.yaml
+    pub fn get_entity_history(
+        &self,
+        entity_id: &str,
+        limit: usize,
+    ) -> Result<Vec<super::CommitInfo>, GitError> {
+        get_entity_history(&self.repo, entity_id, limit)
+    }
+
+    /// The commit a revision (`HEAD~2`, short hash, tag, …) names
+    pub fn resolve_commit(&self, revision: &str) -> Result<super::CommitInfo, GitError> {
+        let commit = self.repo.revparse_single(revision)?.peel_to_commit()?;
+        let author = commit.author().name().unwrap_or("").to_string();
+        Ok(super::CommitInfo {
+            id: commit.id().to_string(),
+            message: commit.message().unwrap_or("").to_string(),
+            author,
+            time: commit.time().seconds(),
+        })
+    }
+
+    /// Contents of a file as of a commit
+    pub fn read_file_at(
+        &self,
+        commit_hash: &str,
+        file_path: &str,
+    ) -> Result<Option<String>, GitError> {
+        read_file_at(&self.repo, commit_hash, file_path)
+    }
+
     /// Get diff between commits
     pub fn get_diff(
         &self,
@@ -126,25 +193,40 @@ impl BuildingGitManager {
 
     /// Stage a single file for commit
     pub fn stage_file(&mut self, file_path: &str) -> Result<(), GitError> {
+        if simulated_git("stage", Some(file_path)) {
+            return Ok(());
+        }
         stage_file(&mut self.repo, file_path)
     }
     /// Stage all modified files
     pub fn stage_all(&mut self) -> Result<usize, GitError> {
+        if simulated_git("stage all modified files", None) {
+            return Ok(0);
+        }
         stage_all(&mut self.repo)
     }
 
     /// Unstage a single file
     pub fn unstage_file(&mut self, file_path: &str) -> Result<(), GitError> {
+        if simulated_git("unstage", Some(file_path)) {
+            return Ok(());
+        }
         unstage_file(&mut self.repo, file_path)
     }
 
     /// Unstage all files
     pub fn unstage_all(&mut self) -> Result<usize, GitError> {
+        if simulated_git("unstage all files", None) {
+            return Ok(0);
+        }
         unstage_all(&mut self.repo)
     }
 
     /// Commit staged changes
     pub fn commit_staged(&mut self, message: &str) -> Result<String, GitError> {
+        if simulated_git("commit", Some(message)) {
+            return Ok(DRY_RUN_COMMIT_ID.to_string());
+        }
         commit_staged(&mut self.repo, &self.git_config, message)
     }
 
@@ -158,10 +240,31 @@ impl BuildingGitManager {
         &mut self,
         metadata: &CommitMetadata,
     ) -> Result<String, GitError> {
+        if simulated_git("commit", Some(&metadata.message)) {
+            return Ok(DRY_RUN_COMMIT_ID.to_string());
+        }
         commit_staged_with_user(&mut self.repo, &self.git_config, metadata)
     }
 }
 
+/// Commit id reported for simulated commits.
+pub const DRY_RUN_COMMIT_ID: &str = "dry-run";
+
+/// In a dry run, record the Git effect and report that it was simulated.
+pub(super) fn simulated_git(action: &str, detail: Option<&str>) -> bool {
+    use crate::core::operations::dry_run::{is_dry_run, record, Change, ChangeKind};
+
+    if !is_dry_run() {
+        return false;
+    }
+    record(Change::new(
+        ChangeKind::Git,
+        format!("git {}", action),
+        detail.map(str::to_string),
+    ));
+    true
+}
+
 /// Git operation errors
 ///
 /// Note: `git2::Error` doesn't implement `Clone`, so we store error messages as `String`
@@ -0,0 +1,509 @@
+//! Building-aware three-way merge of `building.yaml`.
+//!
+//! Git merges the file line by line, so two branches that touch neighbouring
+//! YAML conflict even when they changed different rooms. Here both sides are
+//! compared with their merge base entity by entity — the building record,
+//! floors, wings, rooms, and equipment, matched by id — and a conflict only
+//! arises when both branches changed (or one changed and the other removed)
+//! the same entity.
+
+use crate::core::{Building, Equipment, Floor, Room, Wing};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// Side that wins when both branches changed the same entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePreference {
+    /// The checked-out branch
+    Ours,
+    /// The branch being merged in
+    Theirs,
+}
+
+impl FromStr for MergePreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ours" => Ok(MergePreference::Ours),
+            "theirs" => Ok(MergePreference::Theirs),
+            other => Err(format!("expected 'ours' or 'theirs', got '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for MergePreference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergePreference::Ours => write!(f, "ours"),
+            MergePreference::Theirs => write!(f, "theirs"),
+        }
+    }
+}
+
+/// An entity both branches changed incompatibly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityConflict {
+    /// `building`, `floor`, `wing`, `room`, or `equipment`
+    pub kind: &'static str,
+    pub id: String,
+    pub name: String,
+    pub reason: String,
+}
+
+impl fmt::Display for EntityConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} '{}': {}", self.kind, self.name, self.reason)
+    }
+}
+
+/// Result of [`merge_buildings`].
+#[derive(Debug, Clone)]
+pub struct BuildingMerge {
+    pub building: Building,
+    /// Changes taken from the other branch, e.g. `added room 'Lab 2'`
+    pub changes: Vec<String>,
+    /// Conflicts settled by the merge preference
+    pub resolved: Vec<EntityConflict>,
+    /// Conflicts left for the user; the merge must not be committed
+    pub conflicts: Vec<EntityConflict>,
+}
+
+#[derive(Debug, Clone)]
+enum Entity {
+    Building(Building),
+    Floor(Floor),
+    Wing(Wing),
+    Room(Room),
+    Equipment(Equipment),
+}
+
+impl Entity {
+    fn kind(&self) -> &'static str {
+        match self {
+            Entity::Building(_) => "building",
+            Entity::Floor(_) => "floor",
+            Entity::Wing(_) => "wing",
+            Entity::Room(_) => "room",
+            Entity::Equipment(_) => "equipment",
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            Entity::Building(b) => &b.id,
+            Entity::Floor(f) => &f.id,
+            Entity::Wing(w) => &w.id,
+            Entity::Room(r) => &r.id,
+            Entity::Equipment(e) => &e.id,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Entity::Building(b) => &b.name,
+            Entity::Floor(f) => &f.name,
+            Entity::Wing(w) => &w.name,
+            Entity::Room(r) => &r.name,
+            Entity::Equipment(e) => &e.name,
+        }
+    }
+}
+
+/// One entity without its children, keyed by `kind:id`.
+#[derive(Debug, Clone)]
+struct Node {
+    parent: Option<String>,
+    entity: Entity,
+    /// Serialized entity plus parent, for comparison
+    value: Value,
+}
+
+/// Comparable form of an entity: edit timestamps are bumped by any change to
+/// its children, so they would make every touched container conflict.
+fn comparable<T: Serialize>(bare: &T, parent: Option<&str>) -> Value {
+    let mut value = serde_json::to_value(bare).unwrap_or(Value::Null);
+    if let Value::Object(map) = &mut value {
+        map.remove("updated_at");
+        if let Some(parent) = parent {
+            map.insert("_parent".into(), Value::String(parent.to_string()));
+        }
+    }
+    value
+}
+
+fn key(kind: &str, id: &str) -> String {
+    format!("{}:{}", kind, id)
+}
+
+#[derive(Default)]
+struct Flat {
+    order: Vec<String>,
+    nodes: HashMap<String, Node>,
+}
+
+impl Flat {
+    fn push<T: Serialize>(&mut self, parent: Option<String>, bare: &T, entity: Entity) {
+        let value = comparable(bare, parent.as_deref());
+        let k = key(entity.kind(), entity.id());
+        self.order.push(k.clone());
+        self.nodes.insert(
+            k,
+            Node {
+                parent,
+                entity,
+                value,
+            },
+        );
+    }
+
+    fn equipment(&mut self, parent: &str, equipment: &[Equipment]) {
+        for eq in equipment {
+            self.push(Some(parent.to_string()), eq, Entity::Equipment(eq.clone()));
+        }
+    }
+}
+
+fn flatten(building: &Building) -> Flat {
+    let mut flat = Flat::default();
+    let bare = Building {
+        floors: Vec::new(),
+        ..building.clone()
+    };
+    // Every branch shares one building record, whatever its id
+    flat.order.push("building".into());
+    flat.nodes.insert(
+        "building".into(),
+        Node {
+            parent: None,
+            value: comparable(&bare, None),
+            entity: Entity::Building(bare),
+        },
+    );
+    for floor in &building.floors {
+        let bare = Floor {
+            wings: Vec::new(),
+            equipment: Vec::new(),
+            ..floor.clone()
+        };
+        flat.push(None, &bare, Entity::Floor(bare.clone()));
+        let floor_key = key("floor", &floor.id);
+        flat.equipment(&floor_key, &floor.equipment);
+        for wing in &floor.wings {
+            let bare = Wing {
+                rooms: Vec::new(),
+                equipment: Vec::new(),
+                ..wing.clone()
+            };
+            flat.push(Some(floor_key.clone()), &bare, Entity::Wing(bare.clone()));
+            let wing_key = key("wing", &wing.id);
+            flat.equipment(&wing_key, &wing.equipment);
+            for room in &wing.rooms {
+                let bare = Room {
+                    equipment: Vec::new(),
+                    ..room.clone()
+                };
+                flat.push(Some(wing_key.clone()), &bare, Entity::Room(bare.clone()));
+                flat.equipment(&key("room", &room.id), &room.equipment);
+            }
+        }
+    }
+    flat
+}
+
+/// YAML of one entity in `building`, without its children; `None` when absent.
+pub fn entity_yaml(building: &Building, kind: &str, id: &str) -> Option<String> {
+    let flat = flatten(building);
+    let node = match kind {
+        "building" => flat.nodes.get("building"),
+        _ => flat.nodes.get(&key(kind, id)),
+    }?;
+    match &node.entity {
+        Entity::Building(b) => serde_yaml::to_string(b),
+        Entity::Floor(f) => serde_yaml::to_string(f),
+        Entity::Wing(w) => serde_yaml::to_string(w),
+        Entity::Room(r) => serde_yaml::to_string(r),
+        Entity::Equipment(e) => serde_yaml::to_string(e),
+    }
+    .ok()
+}
+
+/// Attach `node` under its parent in `building`; false if the parent is gone.
+fn attach(building: &mut Building, node: Node) -> bool {
+    let parent = node.parent.as_deref().unwrap_or("");
+    let (parent_kind, parent_id) = parent.split_once(':').unwrap_or(("", ""));
+    match node.entity {
+        Entity::Building(_) => false,
+        Entity::Floor(floor) => {
+            building.floors.push(floor);
+            true
+        }
+        Entity::Wing(wing) => match building.floors.iter_mut().find(|f| f.id == parent_id) {
+            Some(floor) => {
+                floor.wings.push(wing);
+                true
+            }
+            None => false,
+        },
+        Entity::Room(room) => match building
+            .floors
+            .iter_mut()
+            .flat_map(|f| f.wings.iter_mut())
+            .find(|w| w.id == parent_id)
+        {
+            Some(wing) => {
+                wing.rooms.push(room);
+                true
+            }
+            None => false,
+        },
+        Entity::Equipment(eq) => {
+            let list = match parent_kind {
+                "floor" => building
+                    .floors
+                    .iter_mut()
+                    .find(|f| f.id == parent_id)
+                    .map(|f| &mut f.equipment),
+                "wing" => building
+                    .floors
+                    .iter_mut()
+                    .flat_map(|f| f.wings.iter_mut())
+                    .find(|w| w.id == parent_id)
+                    .map(|w| &mut w.equipment),
+                _ => building
+                    .floors
+                    .iter_mut()
+                    .flat_map(|f| f.wings.iter_mut())
+                    .flat_map(|w| w.rooms.iter_mut())
+                    .find(|r| r.id == parent_id)
+                    .map(|r| &mut r.equipment),
+            };
+            match list {
+                Some(list) => {
+                    list.push(eq);
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+}
+
+/// Merge `theirs` into `ours` relative to their common ancestor `base`.
+pub fn merge_buildings(
+    base: &Building,
+    ours: &Building,
+    theirs: &Building,
+    prefer: Option<MergePreference>,
+) -> BuildingMerge {
+    merge_buildings_with(base, ours, theirs, |_| prefer)
+}
+
+/// [`merge_buildings`] with the winning side chosen per conflict; `None`
+/// leaves that conflict for the user.
+pub fn merge_buildings_with(
+    base: &Building,
+    ours: &Building,
+    theirs: &Building,
+    choose: impl Fn(&EntityConflict) -> Option<MergePreference>,
+) -> BuildingMerge {
+    let (base, ours, theirs) = (flatten(base), flatten(ours), flatten(theirs));
+    let mut seen = HashSet::new();
+    let keys: Vec<&String> = ours
+        .order
+        .iter()
+        .chain(&theirs.order)
+        .chain(&base.order)
+        .filter(|k| seen.insert(k.as_str()))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut resolved = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut chosen: Vec<Node> = Vec::new();
+    for k in keys {
+        let (b, o, t) = (base.nodes.get(k), ours.nodes.get(k), theirs.nodes.get(k));
+        let (bv, ov, tv) = (
+            b.map(|n| &n.value),
+            o.map(|n| &n.value),
+            t.map(|n| &n.value),
+        );
+        let pick = if ov == tv || bv == tv {
+            o
+        } else if bv == ov {
+            let entity = &t.or(o).expect("one side has it").entity;
+            changes.push(format!(
+                "{} {} '{}'",
+                match (o, t) {
+                    (None, _) => "added",
+                    (_, None) => "removed",
+                    _ => "updated",
+                },
+                entity.kind(),
+                entity.name()
+            ));
+            t
+        } else {
+            let entity = &o.or(t).expect("one side has it").entity;
+            let reason = match (b, o, t) {
+                (None, _, _) => "added on both branches with different content",
+                (_, None, _) => "removed on this branch, changed on the other",
+                (_, _, None) => "changed on this branch, removed on the other",
+                _ => "changed on both branches",
+            };
+            let conflict = EntityConflict {
+                kind: entity.kind(),
+                id: entity.id().to_string(),
+                name: entity.name().to_string(),
+                reason: reason.to_string(),
+            };
+            match choose(&conflict) {
+                Some(side) => {
+                    resolved.push(conflict);
+                    if side == MergePreference::Ours {
+                        o
+                    } else {
+                        t
+                    }
+                }
+                None => {
+                    conflicts.push(conflict);
+                    o
+                }
+            }
+        };
+        if let Some(node) = pick {
+            chosen.push(node.clone());
+        }
+    }
+
+    // Every side has the building record, so one of them was picked
+    let mut building = match chosen.remove(0).entity {
+        Entity::Building(building) => building,
+        _ => unreachable!("the building record is merged first"),
+    };
+    // Parents before children: floors, wings, rooms, then equipment
+    for rank in ["floor", "wing", "room", "equipment"] {
+        for node in chosen.iter().filter(|n| n.entity.kind() == rank) {
+            if attach(&mut building, node.clone()) {
+                continue;
+            }
+            let mut conflict = EntityConflict {
+                kind: node.entity.kind(),
+                id: node.entity.id().to_string(),
+                name: node.entity.name().to_string(),
+                reason: "its parent was removed on the other branch".into(),
+            };
+            // With a preference the side that removed the parent wins
+            if choose(&conflict).is_some() {
+                conflict.reason = "dropped: its parent was removed".into();
+                resolved.push(conflict);
+            } else {
+                conflicts.push(conflict);
+            }
+        }
+    }
+
+    BuildingMerge {
+        building,
+        changes,
+        resolved,
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentType, RoomType};
+
+    fn base() -> Building {
+        let mut wing = Wing::new("East".into());
+        wing.add_room(Room::new("Office".into(), RoomType::Office));
+        wing.add_room(Room::new("Plant".into(), RoomType::Mechanical));
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    fn room<'a>(building: &'a mut Building, name: &str) -> &'a mut Room {
+        building
+            .get_all_rooms_mut()
+            .into_iter()
+            .find(|r| r.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_disjoint_entity_edits_merge_cleanly() {
+        let base = base();
+        let mut ours = base.clone();
+        room(&mut ours, "Office")
+            .properties
+            .insert("finish".into(), "carpet".into());
+        let mut theirs = base.clone();
+        room(&mut theirs, "Plant").add_equipment(Equipment::new(
+            "AHU-2".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        theirs.floors[0].wings[0]
+            .rooms
+            .push(Room::new("Lab".into(), RoomType::Laboratory));
+
+        let merged = merge_buildings(&base, &ours, &theirs, None);
+        assert!(merged.conflicts.is_empty(), "{:?}", merged.conflicts);
+        assert_eq!(
+            merged.changes,
+            vec!["added equipment 'AHU-2'", "added room 'Lab'"]
+        );
+        let mut building = merged.building;
+        assert_eq!(room(&mut building, "Office").properties["finish"], "carpet");
+        assert_eq!(room(&mut building, "Plant").equipment.len(), 1);
+        assert_eq!(building.get_all_rooms().len(), 3);
+    }
+
+    #[test]
+    fn test_same_entity_conflicts_unless_preferred() {
+        let base = base();
+        let mut ours = base.clone();
+        room(&mut ours, "Office").name = "Open Office".into();
+        let mut theirs = base.clone();
+        room(&mut theirs, "Office").name = "Quiet Office".into();
+        // Removed on one side while the other adds equipment to it
+        theirs.floors[0].wings[0]
+            .rooms
+            .retain(|r| r.name != "Plant");
+        room(&mut ours, "Plant").add_equipment(Equipment::new(
+            "Pump".into(),
+            String::new(),
+            EquipmentType::Plumbing,
+        ));
+
+        let merged = merge_buildings(&base, &ours, &theirs, None);
+        let reasons: Vec<String> = merged.conflicts.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                "room 'Open Office': changed on both branches",
+                "equipment 'Pump': its parent was removed on the other branch",
+            ],
+        );
+
+        let merged = merge_buildings(&base, &ours, &theirs, Some(MergePreference::Theirs));
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(merged.resolved.len(), 2);
+        let names: Vec<&str> = merged
+            .building
+            .get_all_rooms()
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Quiet Office"]);
+    }
+}
@@ -0,0 +1,356 @@
+//! Git merge driver for building YAML.
+//!
+//! `arx init` registers `arx merge-driver %O %A %B %P` as the `arx` merge driver
+//! and marks `building.yaml` with `merge=arx` in `.gitattributes`, so `git merge`
+//! and `git pull` merge the file entity by entity ([`merge_buildings`]) instead
+//! of line by line. Entities both sides changed are left as they are on the
+//! checked-out branch and recorded in `.arx/merge/conflicts.yaml`; `arx merge`
+//! shows them in the merge tool and settles each one with
+//! [`resolve_recorded_conflicts`].
+
+use super::merge::{entity_yaml, merge_buildings, merge_buildings_with, MergePreference};
+use super::GitError;
+use crate::core::Building;
+use crate::persistence::BUILDING_YAML;
+use crate::yaml::BuildingYamlSerializer;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Driver name used in `.git/config` and `.gitattributes`.
+pub const MERGE_DRIVER_NAME: &str = "arx";
+
+/// Conflicts the driver could not settle, for the merge tool.
+pub const MERGE_CONFLICTS_FILE: &str = ".arx/merge/conflicts.yaml";
+
+/// An entity both sides of a merge changed incompatibly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictRecord {
+    /// Repository-relative path of the merged file
+    pub path: String,
+    /// `building`, `floor`, `wing`, `room`, or `equipment`
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    pub reason: String,
+    /// The entity as YAML, without its children, on each side (absent when
+    /// that side does not have it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ours: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theirs: Option<String>,
+}
+
+impl ConflictRecord {
+    /// `kind:id`, the key passed to [`resolve_recorded_conflicts`].
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.kind, self.id)
+    }
+}
+
+fn read_building(path: &Path) -> Result<Building, GitError> {
+    let yaml = fs::read_to_string(path)?;
+    BuildingYamlSerializer::deserialize_building(&yaml).map_err(|e| {
+        GitError::SerializationError(format!("{} does not parse: {}", path.display(), e))
+    })
+}
+
+fn write_building(path: &Path, building: &Building) -> Result<(), GitError> {
+    let content = BuildingYamlSerializer::serialize_building(building)
+        .map_err(|e| GitError::SerializationError(e.to_string()))?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Register the driver in `repo_root`'s Git config and route `building.yaml`
+/// through it. Returns false when `.gitattributes` already did.
+pub fn install_merge_driver(repo_root: &Path) -> Result<bool, GitError> {
+    let repo = Repository::open(repo_root)?;
+    let mut config = repo.config()?;
+    let section = format!("merge.{}", MERGE_DRIVER_NAME);
+    config.set_str(&format!("{}.name", section), "ArxOS building merge")?;
+    config.set_str(
+        &format!("{}.driver", section),
+        "arx merge-driver %O %A %B %P",
+    )?;
+
+    let attributes = repo_root.join(".gitattributes");
+    let line = format!("{} merge={}", BUILDING_YAML, MERGE_DRIVER_NAME);
+    let mut content = fs::read_to_string(&attributes).unwrap_or_default();
+    if content.lines().any(|l| l.trim() == line) {
+        return Ok(false);
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&line);
+    content.push('\n');
+    fs::write(&attributes, content)?;
+    Ok(true)
+}
+
+/// Run as Git's merge driver: merge `theirs` into `ours` relative to `base`
+/// and write the result over `ours`. Returns the conflicts left for the user,
+/// which are also recorded under `repo_root` for `path`.
+pub fn run_merge_driver(
+    repo_root: &Path,
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+    path: &str,
+) -> Result<Vec<ConflictRecord>, GitError> {
+    let (b, o, t) = (
+        read_building(base)?,
+        read_building(ours)?,
+        read_building(theirs)?,
+    );
+    let merged = merge_buildings(&b, &o, &t, None);
+    write_building(ours, &merged.building)?;
+
+    let records: Vec<ConflictRecord> = merged
+        .conflicts
+        .iter()
+        .map(|c| ConflictRecord {
+            path: path.to_string(),
+            kind: c.kind.to_string(),
+            id: c.id.clone(),
+            name: c.name.clone(),
+            reason: c.reason.clone(),
+            base: entity_yaml(&b, c.kind, &c.id),
+            ours: entity_yaml(&o, c.kind, &c.id),
+            theirs: entity_yaml(&t, c.kind, &c.id),
+        })
+        .collect();
+    save_conflict_records(repo_root, path, &records)?;
+    Ok(records)
+}
+
+/// Recorded conflicts, oldest merge first.
+pub fn load_conflict_records(repo_root: &Path) -> Result<Vec<ConflictRecord>, GitError> {
+    let file = repo_root.join(MERGE_CONFLICTS_FILE);
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_yaml::from_str(&fs::read_to_string(file)?)?)
+}
+
+/// Replace the records for `path`; the file is removed once none are left.
+pub fn save_conflict_records(
+    repo_root: &Path,
+    path: &str,
+    records: &[ConflictRecord],
+) -> Result<(), GitError> {
+    let mut all = load_conflict_records(repo_root)?;
+    all.retain(|r| r.path != path);
+    all.extend(records.iter().cloned());
+
+    let file = repo_root.join(MERGE_CONFLICTS_FILE);
+    if all.is_empty() {
+        if file.exists() {
+            fs::remove_file(file)?;
+        }
+        return Ok(());
+    }
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(file, crate::yaml::to_canonical_yaml(&all)?)?;
+    Ok(())
+}
+
+/// Re-merge `path` from the base, ours, and theirs versions Git keeps in the
+/// index during a conflicted merge, settling each recorded conflict by
+/// `choices` (keyed by [`ConflictRecord::key`]). The file is written and
+/// staged once nothing is left unsettled; returns the conflicts still open.
+pub fn resolve_recorded_conflicts(
+    repo_root: &Path,
+    path: &str,
+    choices: &HashMap<String, MergePreference>,
+) -> Result<Vec<ConflictRecord>, GitError> {
+    let repo = Repository::open(repo_root)?;
+    let mut index = repo.index()?;
+    let stage = |n: i32| -> Result<Building, GitError> {
+        let entry =
+            index
+                .get_path(Path::new(path), n)
+                .ok_or_else(|| GitError::OperationFailed {
+                    operation: format!("resolve {}", path),
+                    reason: "no merge in progress for this file".into(),
+                })?;
+        let blob = repo.find_blob(entry.id)?;
+        BuildingYamlSerializer::deserialize_building(&String::from_utf8_lossy(blob.content()))
+            .map_err(|e| GitError::SerializationError(e.to_string()))
+    };
+    let (base, ours, theirs) = (stage(1)?, stage(2)?, stage(3)?);
+    let merged = merge_buildings_with(&base, &ours, &theirs, |c| {
+        choices.get(&format!("{}:{}", c.kind, c.id)).copied()
+    });
+
+    let open: Vec<ConflictRecord> = load_conflict_records(repo_root)?
+        .into_iter()
+        .filter(|r| r.path == path)
+        .filter(|r| {
+            merged
+                .conflicts
+                .iter()
+                .any(|c| c.kind == r.kind && c.id == r.id)
+        })
+        .collect();
+    write_building(&repo_root.join(path), &merged.building)?;
+    save_conflict_records(repo_root, path, &open)?;
+    if merged.conflicts.is_empty() {
+        index.add_path(Path::new(path))?;
+        index.write()?;
+    }
+    Ok(open)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentStatus, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn plant() -> Building {
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        for name in ["AHU-1", "AHU-2"] {
+            room.add_equipment(Equipment::new(
+                name.into(),
+                String::new(),
+                EquipmentType::HVAC,
+            ));
+        }
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    fn write(dir: &Path, file: &str, building: &Building) -> std::path::PathBuf {
+        let path = dir.join(file);
+        write_building(&path, building).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_driver_merges_different_equipment_and_records_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = plant();
+        let mut ours = base.clone();
+        ours.find_equipment_mut("AHU-1").unwrap().status = EquipmentStatus::Maintenance;
+        let mut theirs = base.clone();
+        theirs.find_equipment_mut("AHU-2").unwrap().status = EquipmentStatus::OutOfOrder;
+
+        let (b, o, t) = (
+            write(dir.path(), "base", &base),
+            write(dir.path(), "ours", &ours),
+            write(dir.path(), "theirs", &theirs),
+        );
+        let records = run_merge_driver(dir.path(), &b, &o, &t, BUILDING_YAML).unwrap();
+        assert!(records.is_empty());
+        let merged = read_building(&o).unwrap();
+        assert_eq!(
+            merged.find_equipment("AHU-1").unwrap().status,
+            EquipmentStatus::Maintenance
+        );
+        assert_eq!(
+            merged.find_equipment("AHU-2").unwrap().status,
+            EquipmentStatus::OutOfOrder
+        );
+        assert!(!dir.path().join(MERGE_CONFLICTS_FILE).exists());
+
+        theirs.find_equipment_mut("AHU-1").unwrap().status = EquipmentStatus::OutOfOrder;
+        let (o, t) = (
+            write(dir.path(), "ours", &ours),
+            write(dir.path(), "theirs", &theirs),
+        );
+        let records = run_merge_driver(dir.path(), &b, &o, &t, BUILDING_YAML).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].kind, "equipment");
+        assert_eq!(records[0].name, "AHU-1");
+        assert!(records[0].theirs.as_deref().unwrap().contains("OutOfOrder"));
+        assert_eq!(load_conflict_records(dir.path()).unwrap(), records);
+    }
+
+    #[test]
+    fn test_resolve_recorded_conflicts_from_index_stages() {
+        use crate::persistence::PersistenceManager;
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let pm = PersistenceManager::at(dir.path());
+        let mut building = plant();
+        pm.save_and_commit(&building, Some("Initial")).unwrap();
+        let main = repo.head().unwrap().shorthand().unwrap().to_string();
+        let base = building.clone();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("other", &head, false).unwrap();
+        building.find_equipment_mut("AHU-1").unwrap().status = EquipmentStatus::Maintenance;
+        pm.save_and_commit(&building, Some("Ours")).unwrap();
+        let ours = building.clone();
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        let mut theirs = base.clone();
+        theirs.find_equipment_mut("AHU-1").unwrap().status = EquipmentStatus::OutOfOrder;
+        pm.save_and_commit(&theirs, Some("Theirs")).unwrap();
+        repo.set_head(&format!("refs/heads/{}", main)).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+
+        // libgit2 merges line by line, leaving all three stages in the index
+        let other = repo
+            .find_branch("other", git2::BranchType::Local)
+            .unwrap()
+            .get()
+            .peel_to_commit()
+            .unwrap();
+        let annotated = repo.find_annotated_commit(other.id()).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+        assert!(repo.index().unwrap().has_conflicts());
+        let (b, o, t) = (
+            write(dir.path(), "base", &base),
+            write(dir.path(), "ours", &ours),
+            write(dir.path(), "theirs", &theirs),
+        );
+        let records = run_merge_driver(dir.path(), &b, &o, &t, BUILDING_YAML).unwrap();
+        assert_eq!(records.len(), 1);
+
+        let open = resolve_recorded_conflicts(dir.path(), BUILDING_YAML, &HashMap::new()).unwrap();
+        assert_eq!(open, records);
+        let choices = HashMap::from([(records[0].key(), MergePreference::Theirs)]);
+        let open = resolve_recorded_conflicts(dir.path(), BUILDING_YAML, &choices).unwrap();
+        assert!(open.is_empty());
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!(!index.has_conflicts());
+        assert!(load_conflict_records(dir.path()).unwrap().is_empty());
+        let merged = read_building(&dir.path().join(BUILDING_YAML)).unwrap();
+        assert_eq!(
+            merged.find_equipment("AHU-1").unwrap().status,
+            EquipmentStatus::OutOfOrder
+        );
+    }
+
+    #[test]
+    fn test_install_merge_driver_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        assert!(install_merge_driver(dir.path()).unwrap());
+        assert!(!install_merge_driver(dir.path()).unwrap());
+        let attributes = fs::read_to_string(dir.path().join(".gitattributes")).unwrap();
+        assert_eq!(attributes, "building.yaml merge=arx\n");
+        let driver = repo
+            .config()
+            .unwrap()
+            .get_string("merge.arx.driver")
+            .unwrap();
+        assert!(driver.starts_with("arx merge-driver"));
+    }
+}
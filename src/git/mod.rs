@@ -4,11 +4,16 @@
 //! The primary interface is `BuildingGitManager`, which provides comprehensive Git operations
 //! including commits, diffs, history, and branch management.
 
+pub mod branch;
 pub mod commit;
 pub mod diff;
 pub mod export;
+pub mod lfs;
 pub mod manager;
+pub mod merge;
+pub mod merge_driver;
 pub mod repository;
+pub mod signing;
 pub mod staging;
 
 // Re-export types and main manager
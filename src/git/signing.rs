@@ -0,0 +1,420 @@
+//! Signed-commit policy.
+//!
+//! `git.signed_paths` in the arx configuration (e.g. `arx.toml`) lists
+//! paths, as globs, whose commits must carry a GPG signature:
+//!
+//! ```toml
+//! [git]
+//! signed_paths = ["building.yaml", ".arx/approvals/*"]
+//! ```
+//!
+//! Commits made by arx are signed with GPG when they touch a protected path
+//! (or always, with `git.gpg_sign` or Git's `commit.gpgsign`); see
+//! [`create_commit`]. Commits made with plain `git` are held back by the
+//! pre-commit hook `arx init` installs, which runs `arx verify --staged`.
+//! `arx verify` checks history after the fact ([`compliance_report`]).
+
+use super::GitError;
+use git2::{Commit, Oid, Repository, Signature, Tree};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Which commits must be signed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SigningPolicy {
+    /// Sign every arx commit (`git.gpg_sign`)
+    pub sign_commits: bool,
+    /// Globs of paths whose changes need a signed commit
+    pub protected_paths: Vec<String>,
+}
+
+impl SigningPolicy {
+    /// Policy from the layered arx configuration of the project at `root`.
+    pub fn load(root: &Path) -> Result<Self, GitError> {
+        let manager = crate::config::ConfigManager::load_layered(root).map_err(|e| {
+            GitError::InvalidConfig {
+                reason: e.to_string(),
+            }
+        })?;
+        let git = &manager.get_config().git;
+        Ok(Self {
+            sign_commits: git.gpg_sign,
+            protected_paths: git.signed_paths.clone(),
+        })
+    }
+
+    /// Whether `path` matches a protected glob.
+    pub fn protects(&self, path: &str) -> bool {
+        self.protected_paths.iter().any(|pattern| {
+            glob::Pattern::new(pattern)
+                .map(|glob| glob.matches(path))
+                .unwrap_or(pattern == path)
+        })
+    }
+
+    /// The protected paths among `paths`.
+    pub fn protected_in(&self, paths: &[String]) -> Vec<String> {
+        paths.iter().filter(|p| self.protects(p)).cloned().collect()
+    }
+}
+
+/// Outcome of checking a commit's signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureState {
+    Unsigned,
+    /// Verified against a key in the local keyring
+    Good,
+    /// Does not verify, or made with an expired or revoked key
+    Bad,
+    /// Signed, but the key is not in the local keyring
+    UnknownKey,
+    /// Signed, but gpg could not be run
+    Unverified,
+}
+
+impl SignatureState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureState::Unsigned => "unsigned",
+            SignatureState::Good => "good",
+            SignatureState::Bad => "bad",
+            SignatureState::UnknownKey => "unknown-key",
+            SignatureState::Unverified => "unverified",
+        }
+    }
+}
+
+/// One commit in a [`ComplianceReport`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitCompliance {
+    pub commit: String,
+    pub summary: String,
+    pub author: String,
+    /// Commit time, RFC 3339
+    pub time: String,
+    /// Protected paths the commit changes
+    pub protected_changes: Vec<String>,
+    pub signature: SignatureState,
+    /// Signer as reported by gpg, for signed commits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    pub compliant: bool,
+}
+
+/// Result of checking commits against the policy.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceReport {
+    pub protected_paths: Vec<String>,
+    pub commits_checked: usize,
+    pub violations: usize,
+    pub compliant: bool,
+    pub commits: Vec<CommitCompliance>,
+}
+
+/// Paths `commit` changes relative to its first parent.
+fn changed_paths(repo: &Repository, commit: &Commit) -> Result<Vec<String>, GitError> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    tree_changes(repo, parent_tree.as_ref(), &tree)
+}
+
+fn tree_changes(
+    repo: &Repository,
+    old: Option<&Tree>,
+    new: &Tree,
+) -> Result<Vec<String>, GitError> {
+    let diff = repo.diff_tree_to_tree(old, Some(new), None)?;
+    Ok(diff
+        .deltas()
+        .filter_map(|d| d.new_file().path().or_else(|| d.old_file().path()))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Check `oid`'s GPG signature against the local keyring.
+pub fn verify_commit(repo: &Repository, oid: Oid) -> (SignatureState, Option<String>) {
+    let Ok((signature, signed_data)) = repo.extract_signature(&oid, None) else {
+        return (SignatureState::Unsigned, None);
+    };
+    let Ok(mut sig_file) = tempfile::NamedTempFile::new() else {
+        return (SignatureState::Unverified, None);
+    };
+    if sig_file.write_all(&signature).is_err() {
+        return (SignatureState::Unverified, None);
+    }
+    let child = Command::new("gpg")
+        .args(["--batch", "--status-fd", "1", "--verify"])
+        .arg(sig_file.path())
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return (SignatureState::Unverified, None);
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(&signed_data);
+    }
+    let Ok(output) = child.wait_with_output() else {
+        return (SignatureState::Unverified, None);
+    };
+    let status = String::from_utf8_lossy(&output.stdout);
+    let mut state = SignatureState::Unverified;
+    let mut signer = None;
+    for line in status.lines() {
+        let mut fields = line.trim_start_matches("[GNUPG:] ").splitn(3, ' ');
+        let (keyword, _key, uid) = (fields.next(), fields.next(), fields.next());
+        let found = match keyword {
+            Some("GOODSIG") => SignatureState::Good,
+            Some("BADSIG" | "EXPKEYSIG" | "REVKEYSIG") => SignatureState::Bad,
+            Some("NO_PUBKEY" | "ERRSIG") => SignatureState::UnknownKey,
+            _ => continue,
+        };
+        // A bad or unknown-key line outranks anything else gpg reported
+        if state != SignatureState::Bad && state != SignatureState::UnknownKey {
+            state = found;
+        }
+        if signer.is_none() {
+            signer = uid.map(str::to_string);
+        }
+    }
+    (state, signer)
+}
+
+/// Check HEAD (every commit reachable from it, with `all`) against `policy`.
+/// A commit complies when it changes no protected path or its signature
+/// verifies.
+pub fn compliance_report(
+    repo: &Repository,
+    policy: &SigningPolicy,
+    all: bool,
+) -> Result<ComplianceReport, GitError> {
+    let mut commits = Vec::new();
+    if repo.head().is_ok() {
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        walk.set_sorting(git2::Sort::TIME)?;
+        for oid in walk.take(if all { usize::MAX } else { 1 }) {
+            let commit = repo.find_commit(oid?)?;
+            let protected_changes = policy.protected_in(&changed_paths(repo, &commit)?);
+            let (signature, signer) = verify_commit(repo, commit.id());
+            let time = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default();
+            commits.push(CommitCompliance {
+                commit: commit.id().to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                time,
+                compliant: protected_changes.is_empty() || signature == SignatureState::Good,
+                protected_changes,
+                signature,
+                signer,
+            });
+        }
+    }
+    let violations = commits.iter().filter(|c| !c.compliant).count();
+    Ok(ComplianceReport {
+        protected_paths: policy.protected_paths.clone(),
+        commits_checked: commits.len(),
+        violations,
+        compliant: violations == 0,
+        commits,
+    })
+}
+
+/// Staged protected paths that the next `git commit` would record unsigned
+/// (Git's `commit.gpgsign` is off). Empty when the commit may proceed.
+pub fn unsigned_staged_changes(
+    repo: &Repository,
+    policy: &SigningPolicy,
+) -> Result<Vec<String>, GitError> {
+    if repo.config()?.get_bool("commit.gpgsign").unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+    let staged: Vec<String> = diff
+        .deltas()
+        .filter_map(|d| d.new_file().path().or_else(|| d.old_file().path()))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    Ok(policy.protected_in(&staged))
+}
+
+/// Signing key: Git's `user.signingkey`, else `ARX_GPG_KEY`, else GPG's
+/// default key.
+fn signing_key(repo: &Repository) -> Option<String> {
+    repo.config()
+        .ok()
+        .and_then(|c| c.get_string("user.signingkey").ok())
+        .or_else(|| std::env::var("ARX_GPG_KEY").ok())
+        .filter(|k| !k.is_empty())
+}
+
+/// ASCII-armored detached signature of a commit buffer.
+fn gpg_sign(repo: &Repository, content: &str) -> Result<String, GitError> {
+    let mut command = Command::new("gpg");
+    command.args(["--batch", "--armor", "--detach-sign"]);
+    if let Some(key) = signing_key(repo) {
+        command.args(["--local-user", &key]);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitError::OperationFailed {
+            operation: "gpg sign".into(),
+            reason: format!("cannot run gpg ({})", e),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(GitError::OperationFailed {
+            operation: "gpg sign".into(),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Commit `tree` on HEAD, GPG-signed when the policy of the working tree
+/// asks for it: always with `git.gpg_sign` or `commit.gpgsign`, otherwise
+/// when the commit changes a protected path. Fails rather than commit
+/// unsigned when signing is required and gpg cannot sign.
+pub fn create_commit(
+    repo: &Repository,
+    signature: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+) -> Result<Oid, GitError> {
+    let policy = match repo.workdir() {
+        Some(root) => SigningPolicy::load(root)?,
+        None => SigningPolicy::default(),
+    };
+    let git_sign = repo.config()?.get_bool("commit.gpgsign").unwrap_or(false);
+    let protected = if policy.protected_paths.is_empty() {
+        Vec::new()
+    } else {
+        let parent_tree = parents.first().map(|p| p.tree()).transpose()?;
+        policy.protected_in(&tree_changes(repo, parent_tree.as_ref(), tree)?)
+    };
+    if !policy.sign_commits && !git_sign && protected.is_empty() {
+        return Ok(repo.commit(Some("HEAD"), signature, signature, message, tree, parents)?);
+    }
+
+    let buffer = repo.commit_create_buffer(signature, signature, message, tree, parents)?;
+    let content = buffer
+        .as_str()
+        .ok_or_else(|| GitError::Generic("commit buffer is not UTF-8".into()))?;
+    let gpg_signature = gpg_sign(repo, content).map_err(|e| match protected.first() {
+        Some(path) => GitError::OperationFailed {
+            operation: "commit".into(),
+            reason: format!(
+                "{} requires a signed commit (git.signed_paths), but signing failed: {}",
+                path, e
+            ),
+        },
+        None => e,
+    })?;
+    let oid = repo.commit_signed(content, &gpg_signature, None)?;
+
+    let head = repo.find_reference("HEAD")?;
+    match head.symbolic_target() {
+        Some(branch) => {
+            repo.reference(branch, oid, true, &format!("commit: {}", message))?;
+        }
+        None => repo.set_head_detached(oid)?,
+    }
+    Ok(oid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn commit_file(repo: &Repository, dir: &Path, file: &str, message: &str) {
+        fs::write(dir.join(file), message).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            message,
+            &tree,
+            &parent.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn unsigned_commits_to_protected_paths_violate_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let policy = SigningPolicy {
+            sign_commits: false,
+            protected_paths: vec!["building.yaml".into(), ".arx/approvals/*".into()],
+        };
+        assert!(policy.protects(".arx/approvals/APR-1.yaml"));
+        assert!(!policy.protects("notes.txt"));
+
+        commit_file(&repo, dir.path(), "building.yaml", "initial model");
+        commit_file(&repo, dir.path(), "notes.txt", "notes");
+
+        let head = compliance_report(&repo, &policy, false).unwrap();
+        assert_eq!(head.commits_checked, 1);
+        assert!(head.compliant);
+
+        let all = compliance_report(&repo, &policy, true).unwrap();
+        assert_eq!(all.commits_checked, 2);
+        assert_eq!(all.violations, 1);
+        let violation = all.commits.iter().find(|c| !c.compliant).unwrap();
+        assert_eq!(violation.summary, "initial model");
+        assert_eq!(violation.protected_changes, ["building.yaml"]);
+        assert_eq!(violation.signature, SignatureState::Unsigned);
+    }
+
+    #[test]
+    fn staged_protected_changes_need_commit_gpgsign() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let policy = SigningPolicy {
+            sign_commits: false,
+            protected_paths: vec!["building.yaml".into()],
+        };
+        fs::write(dir.path().join("building.yaml"), "name: HQ\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("building.yaml")).unwrap();
+        index.write().unwrap();
+
+        assert_eq!(
+            unsigned_staged_changes(&repo, &policy).unwrap(),
+            ["building.yaml"]
+        );
+        repo.config()
+            .unwrap()
+            .set_bool("commit.gpgsign", true)
+            .unwrap();
+        assert!(unsigned_staged_changes(&repo, &policy).unwrap().is_empty());
+    }
+}
@@ -4,28 +4,54 @@ use super::GitError;
 use git2::{ErrorCode, ObjectType, Repository};
 use std::path::Path;
 
-/// Stage a single file for commit
+/// Stage a single file for commit (its removal, when it no longer exists)
 pub fn stage_file(repo: &mut Repository, file_path: &str) -> Result<(), GitError> {
+    let deleted = repo
+        .workdir()
+        .is_some_and(|dir| !dir.join(file_path).exists());
     let mut index = repo
         .index()
         .map_err(|e| GitError::GitError(e.message().to_string()))?;
-    index
-        .add_path(Path::new(file_path))
-        .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    if deleted {
+        index
+            .remove_path(Path::new(file_path))
+            .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    } else if super::lfs::is_lfs_path(repo, file_path) {
+        super::lfs::stage_lfs_file(repo, &mut index, file_path)?;
+    } else {
+        index
+            .add_path(Path::new(file_path))
+            .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    }
     index
         .write()
         .map_err(|e| GitError::GitError(e.message().to_string()))?;
     Ok(())
 }
 
-/// Stage all modified files
+/// Stage all modified files (LFS paths as pointers)
 pub fn stage_all(repo: &mut Repository) -> Result<usize, GitError> {
     let mut index = repo
         .index()
         .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    let workdir = repo.workdir().map(Path::to_path_buf);
+    let mut lfs_paths = Vec::new();
+    let mut route = |path: &Path, _: &[u8]| -> i32 {
+        let rel = path.to_string_lossy().to_string();
+        let exists = workdir.as_ref().is_some_and(|dir| dir.join(path).is_file());
+        if exists && super::lfs::is_lfs_path(repo, &rel) {
+            lfs_paths.push(rel);
+            1
+        } else {
+            0
+        }
+    };
     index
-        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, Some(&mut route))
         .map_err(|e| GitError::GitError(e.message().to_string()))?;
+    for path in &lfs_paths {
+        super::lfs::stage_lfs_file(repo, &mut index, path)?;
+    }
     let count = index.len();
     index
         .write()
@@ -0,0 +1,273 @@
+//! Protobuf messages for `proto/arxos/v1/arxos.proto`.
+//!
+//! Declared by hand (the build has no `protoc` step) with the same tags as
+//! the `.proto` file. Field names match the agent's JSON, so requests
+//! serialize straight into action params and results deserialize back.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListRequest {
+    #[prost(uint32, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Room {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub room_type: String,
+    #[prost(int32, tag = "4")]
+    pub floor_level: i32,
+    #[prost(string, tag = "5")]
+    pub wing_name: String,
+    #[prost(uint64, tag = "6")]
+    pub equipment: u64,
+    #[prost(string, optional, tag = "7")]
+    pub address: Option<String>,
+    #[prost(double, repeated, tag = "8")]
+    pub position: Vec<f64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListRoomsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub items: Vec<Room>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+    #[prost(uint32, tag = "3")]
+    pub page_size: u32,
+    #[prost(string, optional, tag = "4")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoomKey {
+    #[prost(string, tag = "1")]
+    pub room: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CreateRoomRequest {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(int32, tag = "2")]
+    pub floor_level: i32,
+    #[prost(string, optional, tag = "3")]
+    pub wing: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub room_type: Option<String>,
+    #[prost(double, repeated, tag = "5")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub position: Vec<f64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateRoomRequest {
+    #[prost(string, tag = "1")]
+    pub room: String,
+    #[prost(string, optional, tag = "2")]
+    pub name: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub room_type: Option<String>,
+    #[prost(double, repeated, tag = "4")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub position: Vec<f64>,
+    #[prost(map = "string, string", tag = "5")]
+    pub properties: HashMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Equipment {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub equipment_type: String,
+    #[prost(string, tag = "4")]
+    pub status: String,
+    #[prost(int32, tag = "5")]
+    pub floor_level: i32,
+    #[prost(string, optional, tag = "6")]
+    pub wing_name: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub room_name: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub address: Option<String>,
+    #[prost(double, optional, tag = "9")]
+    pub runtime_hours: Option<f64>,
+    #[prost(double, repeated, tag = "10")]
+    pub position: Vec<f64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListEquipmentResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub items: Vec<Equipment>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+    #[prost(uint32, tag = "3")]
+    pub page_size: u32,
+    #[prost(string, optional, tag = "4")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EquipmentKey {
+    #[prost(string, tag = "1")]
+    pub equipment: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CreateEquipmentRequest {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub equipment_type: String,
+    #[prost(string, optional, tag = "3")]
+    pub room: Option<String>,
+    #[prost(int32, optional, tag = "4")]
+    pub floor_level: Option<i32>,
+    #[prost(string, optional, tag = "5")]
+    pub status: Option<String>,
+    #[prost(double, repeated, tag = "6")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub position: Vec<f64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateEquipmentRequest {
+    #[prost(string, tag = "1")]
+    pub equipment: String,
+    #[prost(string, optional, tag = "2")]
+    pub name: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub status: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub health_status: Option<String>,
+    #[prost(double, repeated, tag = "5")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub position: Vec<f64>,
+    #[prost(map = "string, string", tag = "6")]
+    pub properties: HashMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpatialQueryRequest {
+    #[prost(string, tag = "1")]
+    pub query_type: String,
+    #[prost(string, tag = "2")]
+    pub entity: String,
+    #[prost(string, repeated, tag = "3")]
+    pub params: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpatialHit {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub entity_type: String,
+    #[prost(double, repeated, tag = "3")]
+    pub position: Vec<f64>,
+    #[prost(double, tag = "4")]
+    pub distance: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpatialQueryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub hits: Vec<SpatialHit>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorReport {
+    #[prost(string, tag = "1")]
+    pub sensor_id: String,
+    #[prost(string, optional, tag = "2")]
+    pub sensor_type: Option<String>,
+    #[prost(double, optional, tag = "3")]
+    pub value: Option<f64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorStatus {
+    #[prost(string, tag = "1")]
+    pub sensor_id: String,
+    #[prost(string, optional, tag = "2")]
+    pub sensor_type: Option<String>,
+    #[prost(string, tag = "3")]
+    pub first_seen: String,
+    #[prost(string, tag = "4")]
+    pub last_seen: String,
+    #[prost(uint64, tag = "5")]
+    pub readings: u64,
+    #[prost(double, optional, tag = "6")]
+    pub last_value: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+    use serde_json::json;
+
+    #[test]
+    fn test_agent_json_round_trips_through_protobuf() {
+        let row = json!({
+            "id": "r-1", "name": "Lab", "room_type": "Laboratory", "floor_level": 2,
+            "wing_name": "East", "equipment": 3, "address": null, "position": [1.0, 2.0, 0.0],
+        });
+        let room: Room = serde_json::from_value(row).unwrap();
+        let decoded = Room::decode(room.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, room);
+        assert_eq!(decoded.position, vec![1.0, 2.0, 0.0]);
+
+        // Unset optional fields and empty positions stay out of the action params
+        let request = CreateEquipmentRequest {
+            name: "AHU-1".into(),
+            equipment_type: "HVAC".into(),
+            room: Some("Lab".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "name": "AHU-1", "equipment_type": "HVAC", "room": "Lab",
+                "floor_level": null, "status": null,
+            })
+        );
+    }
+}
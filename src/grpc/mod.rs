@@ -0,0 +1,14 @@
+//! gRPC façade over the agent (`--features grpc`).
+//!
+//! `arx agent serve` also listens for gRPC (`ARX_GRPC_ADDR`, default
+//! `0.0.0.0:50051`) with the service in `proto/arxos/v1/arxos.proto`: room
+//! and equipment CRUD, spatial queries, and sensor reports. Each RPC is
+//! dispatched as the matching agent action (`room.create`, `sensor.report`,
+//! …) for the caller named by the `authorization: Bearer <secret>` metadata,
+//! so the agent token, API keys, capabilities, roles, approvals, and
+//! read-only replicas behave exactly as over WebSocket and `/rpc`.
+
+pub mod messages;
+pub mod service;
+
+pub use service::{serve, ArxosServer, DEFAULT_GRPC_ADDR, GRPC_ADDR_ENV};
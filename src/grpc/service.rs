@@ -0,0 +1,309 @@
+//! The `arxos.v1.Arxos` service: routing, authentication, and status codes.
+//!
+//! Routing is written out by hand in place of `tonic-build` output; every
+//! method is a unary call that turns its request into action params, runs it
+//! through the agent dispatcher, and decodes the JSON result.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{http, Body, BoxFuture, Service, StdError};
+use tonic::metadata::MetadataValue;
+use tonic::server::{Grpc, NamedService, UnaryService};
+use tonic::{Code, Request, Response, Status};
+
+use super::messages::*;
+use crate::agent::dispatcher::AgentState;
+use crate::agent::protocol::{
+    JsonRpcError, JsonRpcRequest, AUTH_ERROR, METHOD_NOT_FOUND, READ_ONLY_ERROR,
+};
+use crate::agent::recorder::dispatch_recorded;
+use crate::agent::server::authenticate;
+
+/// Overrides the listen address.
+pub const GRPC_ADDR_ENV: &str = "ARX_GRPC_ADDR";
+
+pub const DEFAULT_GRPC_ADDR: &str = "0.0.0.0:50051";
+
+/// Response metadata listing approval requests raised by an edit.
+pub const PENDING_APPROVALS_KEY: &str = "x-arx-pending-approvals";
+
+/// Serve the façade for `state` until the listener fails.
+pub async fn serve(
+    state: Arc<AgentState>,
+    addr: SocketAddr,
+) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(ArxosServer::new(state))
+        .serve(addr)
+        .await
+}
+
+/// `arxos.v1.Arxos`, backed by the agent dispatcher.
+#[derive(Clone)]
+pub struct ArxosServer {
+    state: Arc<AgentState>,
+}
+
+impl ArxosServer {
+    pub fn new(state: Arc<AgentState>) -> Self {
+        Self { state }
+    }
+}
+
+impl NamedService for ArxosServer {
+    const NAME: &'static str = "arxos.v1.Arxos";
+}
+
+impl<B> Service<http::Request<B>> for ArxosServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let state = self.state.clone();
+        let method = req.uri().path().strip_prefix("/arxos.v1.Arxos/");
+        match method {
+            Some("ListRooms") => {
+                unary::<_, ListRequest, ListRoomsResponse>(state, req, "room.list")
+            }
+            Some("GetRoom") => unary::<_, RoomKey, Room>(state, req, "room.get"),
+            Some("CreateRoom") => unary::<_, CreateRoomRequest, Room>(state, req, "room.create"),
+            Some("UpdateRoom") => unary::<_, UpdateRoomRequest, Room>(state, req, "room.update"),
+            Some("DeleteRoom") => unary::<_, RoomKey, Room>(state, req, "room.delete"),
+            Some("ListEquipment") => {
+                unary::<_, ListRequest, ListEquipmentResponse>(state, req, "equipment.list")
+            }
+            Some("GetEquipment") => {
+                unary::<_, EquipmentKey, Equipment>(state, req, "equipment.get")
+            }
+            Some("CreateEquipment") => {
+                unary::<_, CreateEquipmentRequest, Equipment>(state, req, "equipment.create")
+            }
+            Some("UpdateEquipment") => {
+                unary::<_, UpdateEquipmentRequest, Equipment>(state, req, "equipment.update")
+            }
+            Some("DeleteEquipment") => {
+                unary::<_, EquipmentKey, Equipment>(state, req, "equipment.delete")
+            }
+            Some("SpatialQuery") => {
+                unary::<_, SpatialQueryRequest, SpatialQueryResponse>(state, req, "spatial.query")
+            }
+            Some("ReportSensor") => {
+                unary::<_, SensorReport, SensorStatus>(state, req, "sensor.report")
+            }
+            _ => Box::pin(async { Ok(Status::unimplemented("Unknown method").into_http()) }),
+        }
+    }
+}
+
+/// Adapts a closure to tonic's unary handler.
+struct Unary<F>(F);
+
+impl<Req, Res, F, Fut> UnaryService<Req> for Unary<F>
+where
+    F: FnMut(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Res>, Status>>,
+{
+    type Response = Res;
+    type Future = Fut;
+
+    fn call(&mut self, request: Request<Req>) -> Fut {
+        (self.0)(request)
+    }
+}
+
+fn unary<B, Req, Res>(
+    state: Arc<AgentState>,
+    req: http::Request<B>,
+    action: &'static str,
+) -> BoxFuture<http::Response<BoxBody>, Infallible>
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+    Req: prost::Message + Default + Serialize + Send + 'static,
+    Res: prost::Message + DeserializeOwned + Send + 'static,
+{
+    Box::pin(async move {
+        let handler = Unary(move |request| call::<Req, Res>(state.clone(), action, request));
+        let mut grpc = Grpc::new(ProstCodec::<Res, Req>::default());
+        Ok(grpc.unary(handler, req).await)
+    })
+}
+
+/// Run `action` for the request's caller and decode its result.
+async fn call<Req: Serialize, Res: DeserializeOwned>(
+    state: Arc<AgentState>,
+    action: &'static str,
+    request: Request<Req>,
+) -> Result<Response<Res>, Status> {
+    let headers = request.metadata().clone().into_headers();
+    let caller = authenticate(&headers, None, &state)
+        .ok_or_else(|| Status::unauthenticated("Missing or invalid bearer token"))?;
+    let params = serde_json::to_value(request.get_ref())
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+    let request = JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: action.to_string(),
+        params: Some(params),
+        id: None,
+    };
+    let response = dispatch_recorded(state, request, "grpc", &caller).await;
+    if let Some(error) = response.error {
+        return Err(status(error));
+    }
+
+    let (result, pending) = split_pending(response.result.unwrap_or(Value::Null));
+    // Repeated results arrive bare; the response message wraps them
+    let result = match result {
+        Value::Array(hits) if action == "spatial.query" => serde_json::json!({ "hits": hits }),
+        other => other,
+    };
+    let message = serde_json::from_value(result)
+        .map_err(|e| Status::internal(format!("Unexpected {} result: {}", action, e)))?;
+    let mut response = Response::new(message);
+    if !pending.is_empty() {
+        if let Ok(value) = MetadataValue::try_from(pending.join(",")) {
+            response.metadata_mut().insert(PENDING_APPROVALS_KEY, value);
+        }
+    }
+    Ok(response)
+}
+
+/// Unwrap `{result, pending_approvals}` into the result and the request ids.
+fn split_pending(value: Value) -> (Value, Vec<String>) {
+    match value {
+        Value::Object(mut map) if map.len() == 2 && map.contains_key("pending_approvals") => {
+            let ids = map
+                .get("pending_approvals")
+                .and_then(|p| p.as_array())
+                .map(|p| {
+                    p.iter()
+                        .filter_map(|r| r.get("id").and_then(|id| id.as_str()))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            (map.remove("result").unwrap_or(Value::Null), ids)
+        }
+        other => (other, Vec::new()),
+    }
+}
+
+/// gRPC status for an agent error.
+fn status(error: JsonRpcError) -> Status {
+    let code = match error.code {
+        AUTH_ERROR => Code::PermissionDenied,
+        READ_ONLY_ERROR => Code::FailedPrecondition,
+        METHOD_NOT_FOUND => Code::Unimplemented,
+        _ if error.is_not_found() => Code::NotFound,
+        _ if error.is_invalid_params() => Code::InvalidArgument,
+        _ => Code::Internal,
+    };
+    Status::new(code, error.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::auth::{all_capabilities, TokenState};
+    use crate::agent::protocol::INTERNAL_ERROR;
+    use std::sync::Mutex;
+
+    fn state(root: &std::path::Path) -> Arc<AgentState> {
+        Arc::new(AgentState {
+            repo_root: root.to_path_buf(),
+            token: Arc::new(Mutex::new(TokenState::new(
+                "secret".to_string(),
+                all_capabilities(),
+            ))),
+            metrics: Arc::new(crate::agent::observability::AgentMetrics::new()),
+            reload_handle: None,
+            sessions: Default::default(),
+        })
+    }
+
+    fn authorized<T>(message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer secret".parse().unwrap());
+        request
+    }
+
+    #[tokio::test]
+    async fn test_sensor_report_runs_as_agent_action() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state(dir.path());
+        let report = SensorReport {
+            sensor_id: "t-101".into(),
+            sensor_type: Some("temperature".into()),
+            value: Some(21.5),
+        };
+
+        let denied =
+            call::<_, SensorStatus>(state.clone(), "sensor.report", Request::new(report.clone()))
+                .await
+                .unwrap_err();
+        assert_eq!(denied.code(), Code::Unauthenticated);
+
+        let seen = call::<_, SensorStatus>(state, "sensor.report", authorized(report))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(seen.sensor_id, "t-101");
+        assert_eq!((seen.readings, seen.last_value), (1, Some(21.5)));
+    }
+
+    fn error(code: i32, message: &str) -> JsonRpcError {
+        JsonRpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(
+            status(error(AUTH_ERROR, "Permission denied")).code(),
+            Code::PermissionDenied
+        );
+        assert_eq!(
+            status(error(READ_ONLY_ERROR, "replica")).code(),
+            Code::FailedPrecondition
+        );
+        assert_eq!(
+            status(error(INTERNAL_ERROR, "room 'X' not found")).code(),
+            Code::NotFound
+        );
+        assert_eq!(
+            status(error(INTERNAL_ERROR, "Missing 'room' parameter")).code(),
+            Code::InvalidArgument
+        );
+
+        let (result, pending) = split_pending(serde_json::json!({
+            "result": { "id": "e-1" },
+            "pending_approvals": [{ "id": "apr-1", "summary": "AHU-1 status" }],
+        }));
+        assert_eq!(
+            (result["id"].as_str(), pending),
+            (Some("e-1"), vec!["apr-1".to_string()])
+        );
+    }
+}
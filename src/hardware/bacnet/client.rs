@@ -0,0 +1,306 @@
+//! Blocking BACnet/IP client over UDP and the poll loop feeding ingestion.
+
+use super::codec::{
+    decode, encode_read_property, encode_who_is, Apdu, ObjectId, BACNET_PORT, PROP_PRESENT_VALUE,
+};
+use super::mapping::{BacnetConfig, BacnetDevice};
+use super::BacnetError;
+use crate::persistence::sensors::record_sensor_reading;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// A device that answered Who-Is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiscoveredDevice {
+    pub instance: u32,
+    pub address: SocketAddr,
+    pub vendor_id: u32,
+    pub max_apdu: u32,
+}
+
+/// Outcome of one pass over the mapped points.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PollReport {
+    pub recorded: usize,
+    /// `sensor id: reason` for points that could not be read
+    pub failures: Vec<String>,
+}
+
+pub struct BacnetClient {
+    socket: UdpSocket,
+    timeout: Duration,
+    invoke_id: u8,
+    /// Device addresses found by Who-Is, reused across polls
+    resolved: HashMap<u32, SocketAddr>,
+}
+
+/// `ip:port`, or a bare host with the default BACnet port.
+pub fn parse_address(address: &str) -> Result<SocketAddr, BacnetError> {
+    let address = address.trim();
+    let with_port = if address.contains(':') {
+        address.to_string()
+    } else {
+        format!("{}:{}", address, BACNET_PORT)
+    };
+    with_port
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| BacnetError::Config(format!("invalid address '{}'", address)))
+}
+
+impl BacnetClient {
+    /// Bind a UDP socket (`0.0.0.0:0` for any interface) that may broadcast.
+    pub fn bind(local: &str, timeout: Duration) -> Result<Self, BacnetError> {
+        let socket = UdpSocket::bind(local)?;
+        socket.set_broadcast(true)?;
+        Ok(Self {
+            socket,
+            timeout,
+            invoke_id: 0,
+            resolved: HashMap::new(),
+        })
+    }
+
+    /// Client configured from `.arx/bacnet.yaml`.
+    pub fn from_config(config: &BacnetConfig) -> Result<Self, BacnetError> {
+        Self::bind(
+            config.bind.as_deref().unwrap_or("0.0.0.0:0"),
+            Duration::from_millis(config.timeout_ms),
+        )
+    }
+
+    /// Next datagram before `deadline`, or `None` once it passes.
+    fn recv_until(&self, deadline: Instant) -> Result<Option<(Vec<u8>, SocketAddr)>, BacnetError> {
+        let mut buf = [0u8; 1500];
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        self.socket.set_read_timeout(Some(deadline - now))?;
+        match self.socket.recv_from(&mut buf) {
+            Ok((n, from)) => Ok(Some((buf[..n].to_vec(), from))),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Send Who-Is to `target` and collect I-Am replies for `wait`.
+    pub fn discover(
+        &self,
+        target: SocketAddr,
+        range: Option<(u32, u32)>,
+        wait: Duration,
+    ) -> Result<Vec<DiscoveredDevice>, BacnetError> {
+        self.socket.send_to(&encode_who_is(range), target)?;
+        let deadline = Instant::now() + wait;
+        let mut found: Vec<DiscoveredDevice> = Vec::new();
+        while let Some((packet, from)) = self.recv_until(deadline)? {
+            if let Ok(Apdu::IAm(i_am)) = decode(&packet) {
+                if found.iter().all(|d| d.instance != i_am.device_instance) {
+                    found.push(DiscoveredDevice {
+                        instance: i_am.device_instance,
+                        address: from,
+                        vendor_id: i_am.vendor_id,
+                        max_apdu: i_am.max_apdu,
+                    });
+                }
+                // A targeted Who-Is has one answer
+                if range.is_some_and(|(low, high)| low == high) {
+                    break;
+                }
+            }
+        }
+        found.sort_by_key(|d| d.instance);
+        Ok(found)
+    }
+
+    /// Read a numeric property (REAL, unsigned, signed, enumerated, boolean).
+    pub fn read_property(
+        &mut self,
+        device: SocketAddr,
+        object: ObjectId,
+        property: u32,
+    ) -> Result<f64, BacnetError> {
+        self.invoke_id = self.invoke_id.wrapping_add(1);
+        let invoke = self.invoke_id;
+        self.socket
+            .send_to(&encode_read_property(invoke, object, property), device)?;
+        let deadline = Instant::now() + self.timeout;
+        while let Some((packet, from)) = self.recv_until(deadline)? {
+            if from != device {
+                continue;
+            }
+            match decode(&packet) {
+                Ok(Apdu::ReadPropertyAck {
+                    invoke_id, value, ..
+                }) if invoke_id == invoke => return Ok(value),
+                Ok(Apdu::Failure { invoke_id, reason }) if invoke_id == invoke => {
+                    return Err(BacnetError::Remote(reason));
+                }
+                _ => {}
+            }
+        }
+        Err(BacnetError::Timeout(format!("{} ({})", device, object)))
+    }
+
+    pub fn read_present_value(
+        &mut self,
+        device: SocketAddr,
+        object: ObjectId,
+    ) -> Result<f64, BacnetError> {
+        self.read_property(device, object, PROP_PRESENT_VALUE)
+    }
+
+    /// Address of `device`: configured, cached, or found by a targeted Who-Is.
+    fn resolve(
+        &mut self,
+        device: &BacnetDevice,
+        broadcast: &str,
+    ) -> Result<SocketAddr, BacnetError> {
+        if let Some(address) = &device.address {
+            return parse_address(address);
+        }
+        if let Some(address) = self.resolved.get(&device.instance) {
+            return Ok(*address);
+        }
+        let target = parse_address(broadcast)?;
+        let found = self.discover(
+            target,
+            Some((device.instance, device.instance)),
+            self.timeout,
+        )?;
+        let address = found
+            .first()
+            .map(|d| d.address)
+            .ok_or_else(|| BacnetError::Timeout(format!("device {}", device.instance)))?;
+        self.resolved.insert(device.instance, address);
+        Ok(address)
+    }
+}
+
+/// Read every mapped point once and record the values as sensor readings.
+pub fn poll_once(
+    base: &Path,
+    config: &BacnetConfig,
+    client: &mut BacnetClient,
+) -> Result<PollReport, BacnetError> {
+    let mut report = PollReport::default();
+    for device in &config.devices {
+        let address = match client.resolve(device, &config.broadcast) {
+            Ok(address) => address,
+            Err(e) => {
+                for point in &device.points {
+                    report.failures.push(format!(
+                        "{}: {}",
+                        point.sensor_id_for(device.instance),
+                        e
+                    ));
+                }
+                continue;
+            }
+        };
+        for point in &device.points {
+            let sensor_id = point.sensor_id_for(device.instance);
+            let result = point
+                .object_id()
+                .map_err(BacnetError::Config)
+                .and_then(|object| client.read_present_value(address, object));
+            match result {
+                Ok(value) => {
+                    record_sensor_reading(
+                        base,
+                        &sensor_id,
+                        point.sensor_type.as_deref(),
+                        Some(value),
+                    )
+                    .map_err(|e| BacnetError::Config(e.to_string()))?;
+                    report.recorded += 1;
+                }
+                Err(e) => {
+                    // A device that dropped off the network is looked up again next time
+                    if matches!(e, BacnetError::Timeout(_)) {
+                        client.resolved.remove(&device.instance);
+                    }
+                    report.failures.push(format!("{}: {}", sensor_id, e));
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::codec::{decode_request, encode_i_am, encode_read_property_ack, Request};
+    use super::super::mapping::BacnetPoint;
+    use super::*;
+    use crate::persistence::sensors::load_sensor_readings;
+
+    /// A device on loopback answering Who-Is and ReadProperty (value = instance + 0.5).
+    fn simulated_device(instance: u32) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 1500];
+            while let Ok((n, from)) = socket.recv_from(&mut buf) {
+                let reply = match decode_request(&buf[..n]) {
+                    Ok(Request::WhoIs(_)) => encode_i_am(instance, 7),
+                    Ok(Request::ReadProperty {
+                        invoke_id,
+                        object,
+                        property,
+                    }) => encode_read_property_ack(
+                        invoke_id,
+                        object,
+                        property,
+                        object.instance as f32 + 0.5,
+                    ),
+                    _ => continue,
+                };
+                let _ = socket.send_to(&reply, from);
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn test_discover_and_poll_into_sensor_readings() {
+        let device = simulated_device(1001);
+        let dir = tempfile::tempdir().unwrap();
+        let config = BacnetConfig {
+            broadcast: device.to_string(),
+            timeout_ms: 2000,
+            devices: vec![BacnetDevice {
+                instance: 1001,
+                address: None,
+                name: None,
+                points: vec![BacnetPoint {
+                    object: "ai:3".into(),
+                    sensor_id: None,
+                    sensor_type: Some("temperature".into()),
+                    equipment: None,
+                }],
+            }],
+            ..Default::default()
+        };
+        let mut client = BacnetClient::bind("127.0.0.1:0", Duration::from_secs(2)).unwrap();
+        let found = client
+            .discover(device, None, Duration::from_millis(300))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].instance, 1001);
+        assert_eq!(found[0].vendor_id, 7);
+
+        let report = poll_once(dir.path(), &config, &mut client).unwrap();
+        assert_eq!(report.recorded, 1, "{:?}", report.failures);
+        let readings = load_sensor_readings(dir.path()).unwrap();
+        assert_eq!(readings[0].sensor_id, "bacnet:1001:analog-input:3");
+        assert_eq!(readings[0].value, 3.5);
+        assert_eq!(readings[0].sensor_type.as_deref(), Some("temperature"));
+    }
+}
@@ -0,0 +1,643 @@
+//! BACnet/IP wire format: just enough of BVLC, NPDU, and APDU for Who-Is /
+//! I-Am discovery and ReadProperty of a point's present value.
+
+use super::BacnetError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Default BACnet/IP UDP port (0xBAC0).
+pub const BACNET_PORT: u16 = 47808;
+
+/// `present-value` property identifier.
+pub const PROP_PRESENT_VALUE: u32 = 85;
+
+const BVLC_TYPE: u8 = 0x81;
+const BVLC_FORWARDED_NPDU: u8 = 0x04;
+const BVLC_ORIGINAL_UNICAST: u8 = 0x0A;
+const BVLC_ORIGINAL_BROADCAST: u8 = 0x0B;
+
+const NPDU_VERSION: u8 = 0x01;
+const NPDU_EXPECTING_REPLY: u8 = 0x04;
+
+const PDU_CONFIRMED_REQUEST: u8 = 0x0;
+const PDU_UNCONFIRMED_REQUEST: u8 = 0x1;
+const PDU_COMPLEX_ACK: u8 = 0x3;
+const PDU_ERROR: u8 = 0x5;
+const PDU_REJECT: u8 = 0x6;
+const PDU_ABORT: u8 = 0x7;
+
+const SERVICE_I_AM: u8 = 0x00;
+const SERVICE_WHO_IS: u8 = 0x08;
+const SERVICE_READ_PROPERTY: u8 = 0x0C;
+
+/// Max segments not specified, max APDU 1476 octets.
+const MAX_APDU_1476: u8 = 0x05;
+
+/// Object types the poller reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectType {
+    AnalogInput,
+    AnalogOutput,
+    AnalogValue,
+    BinaryInput,
+    BinaryOutput,
+    BinaryValue,
+    Device,
+    MultiStateInput,
+    MultiStateOutput,
+    MultiStateValue,
+}
+
+impl ObjectType {
+    pub fn code(&self) -> u16 {
+        match self {
+            ObjectType::AnalogInput => 0,
+            ObjectType::AnalogOutput => 1,
+            ObjectType::AnalogValue => 2,
+            ObjectType::BinaryInput => 3,
+            ObjectType::BinaryOutput => 4,
+            ObjectType::BinaryValue => 5,
+            ObjectType::Device => 8,
+            ObjectType::MultiStateInput => 13,
+            ObjectType::MultiStateOutput => 14,
+            ObjectType::MultiStateValue => 19,
+        }
+    }
+
+    pub fn from_code(code: u16) -> Option<Self> {
+        Some(match code {
+            0 => ObjectType::AnalogInput,
+            1 => ObjectType::AnalogOutput,
+            2 => ObjectType::AnalogValue,
+            3 => ObjectType::BinaryInput,
+            4 => ObjectType::BinaryOutput,
+            5 => ObjectType::BinaryValue,
+            8 => ObjectType::Device,
+            13 => ObjectType::MultiStateInput,
+            14 => ObjectType::MultiStateOutput,
+            19 => ObjectType::MultiStateValue,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ObjectType::AnalogInput => "analog-input",
+            ObjectType::AnalogOutput => "analog-output",
+            ObjectType::AnalogValue => "analog-value",
+            ObjectType::BinaryInput => "binary-input",
+            ObjectType::BinaryOutput => "binary-output",
+            ObjectType::BinaryValue => "binary-value",
+            ObjectType::Device => "device",
+            ObjectType::MultiStateInput => "multi-state-input",
+            ObjectType::MultiStateOutput => "multi-state-output",
+            ObjectType::MultiStateValue => "multi-state-value",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ObjectType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_ascii_lowercase().as_str() {
+            "analog-input" | "ai" => ObjectType::AnalogInput,
+            "analog-output" | "ao" => ObjectType::AnalogOutput,
+            "analog-value" | "av" => ObjectType::AnalogValue,
+            "binary-input" | "bi" => ObjectType::BinaryInput,
+            "binary-output" | "bo" => ObjectType::BinaryOutput,
+            "binary-value" | "bv" => ObjectType::BinaryValue,
+            "device" | "dev" => ObjectType::Device,
+            "multi-state-input" | "msi" => ObjectType::MultiStateInput,
+            "multi-state-output" | "mso" => ObjectType::MultiStateOutput,
+            "multi-state-value" | "msv" => ObjectType::MultiStateValue,
+            other => return Err(format!("unknown BACnet object type '{}'", other)),
+        })
+    }
+}
+
+/// Object type plus instance number (22 bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectId {
+    pub object_type: ObjectType,
+    pub instance: u32,
+}
+
+/// Largest object instance number.
+pub const MAX_INSTANCE: u32 = 0x3F_FFFF;
+
+impl ObjectId {
+    pub fn new(object_type: ObjectType, instance: u32) -> Self {
+        Self {
+            object_type,
+            instance,
+        }
+    }
+
+    fn encode(&self) -> [u8; 4] {
+        ((u32::from(self.object_type.code()) << 22) | (self.instance & MAX_INSTANCE)).to_be_bytes()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, BacnetError> {
+        let raw = u32::from_be_bytes(
+            bytes
+                .try_into()
+                .map_err(|_| protocol("object identifier must be 4 octets"))?,
+        );
+        let code = (raw >> 22) as u16;
+        let object_type = ObjectType::from_code(code)
+            .ok_or_else(|| protocol(format!("unsupported object type {}", code)))?;
+        Ok(Self::new(object_type, raw & MAX_INSTANCE))
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.object_type, self.instance)
+    }
+}
+
+impl FromStr for ObjectId {
+    type Err = String;
+
+    /// `analog-input:3` or `ai:3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, instance) = s
+            .rsplit_once(':')
+            .ok_or_else(|| format!("object '{}' must look like analog-input:3", s))?;
+        let instance: u32 = instance
+            .trim()
+            .parse()
+            .map_err(|_| format!("object '{}': instance is not a number", s))?;
+        if instance > MAX_INSTANCE {
+            return Err(format!("object '{}': instance above {}", s, MAX_INSTANCE));
+        }
+        Ok(Self::new(kind.parse()?, instance))
+    }
+}
+
+/// Decoded I-Am announcement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IAm {
+    pub device_instance: u32,
+    pub max_apdu: u32,
+    pub vendor_id: u32,
+}
+
+/// A decoded message the client cares about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Apdu {
+    IAm(IAm),
+    ReadPropertyAck {
+        invoke_id: u8,
+        object: ObjectId,
+        property: u32,
+        value: f64,
+    },
+    /// Error, Reject, or Abort for `invoke_id`
+    Failure {
+        invoke_id: u8,
+        reason: String,
+    },
+    /// Anything else (other services, network-layer messages)
+    Other,
+}
+
+fn protocol(msg: impl Into<String>) -> BacnetError {
+    BacnetError::Protocol(msg.into())
+}
+
+fn bvlc(function: u8, npdu_apdu: &[u8]) -> Vec<u8> {
+    let len = (4 + npdu_apdu.len()) as u16;
+    let mut out = vec![BVLC_TYPE, function];
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(npdu_apdu);
+    out
+}
+
+/// Context-tagged unsigned integer in the fewest octets.
+fn context_unsigned(tag: u8, value: u32) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = bytes.iter().take(3).take_while(|b| **b == 0).count();
+    let body = &bytes[skip..];
+    let mut out = vec![(tag << 4) | 0x08 | body.len() as u8];
+    out.extend_from_slice(body);
+    out
+}
+
+/// Broadcast Who-Is, optionally limited to one device instance range.
+pub fn encode_who_is(range: Option<(u32, u32)>) -> Vec<u8> {
+    let mut body = vec![
+        NPDU_VERSION,
+        0x00,
+        PDU_UNCONFIRMED_REQUEST << 4,
+        SERVICE_WHO_IS,
+    ];
+    if let Some((low, high)) = range {
+        body.extend(context_unsigned(0, low));
+        body.extend(context_unsigned(1, high));
+    }
+    bvlc(BVLC_ORIGINAL_BROADCAST, &body)
+}
+
+/// Unicast ReadProperty for `property` of `object`.
+pub fn encode_read_property(invoke_id: u8, object: ObjectId, property: u32) -> Vec<u8> {
+    let mut body = vec![
+        NPDU_VERSION,
+        NPDU_EXPECTING_REPLY,
+        PDU_CONFIRMED_REQUEST << 4,
+        MAX_APDU_1476,
+        invoke_id,
+        SERVICE_READ_PROPERTY,
+        0x0C,
+    ];
+    body.extend_from_slice(&object.encode());
+    body.extend(context_unsigned(1, property));
+    bvlc(BVLC_ORIGINAL_UNICAST, &body)
+}
+
+/// I-Am for `device` (used by tests and simulators).
+pub fn encode_i_am(device: u32, vendor_id: u32) -> Vec<u8> {
+    let mut body = vec![
+        NPDU_VERSION,
+        0x00,
+        PDU_UNCONFIRMED_REQUEST << 4,
+        SERVICE_I_AM,
+        0xC4,
+    ];
+    body.extend_from_slice(&ObjectId::new(ObjectType::Device, device).encode());
+    body.extend([0x22, 0x05, 0xC4]); // max APDU 1476
+    body.extend([0x91, 0x03]); // no segmentation
+    body.extend([0x21, vendor_id.min(255) as u8]);
+    bvlc(BVLC_ORIGINAL_UNICAST, &body)
+}
+
+/// ReadProperty-ACK carrying a REAL (used by tests and simulators).
+pub fn encode_read_property_ack(
+    invoke_id: u8,
+    object: ObjectId,
+    property: u32,
+    value: f32,
+) -> Vec<u8> {
+    let mut body = vec![
+        NPDU_VERSION,
+        0x00,
+        PDU_COMPLEX_ACK << 4,
+        invoke_id,
+        SERVICE_READ_PROPERTY,
+        0x0C,
+    ];
+    body.extend_from_slice(&object.encode());
+    body.extend(context_unsigned(1, property));
+    body.push(0x3E);
+    body.push(0x44);
+    body.extend_from_slice(&value.to_be_bytes());
+    body.push(0x3F);
+    bvlc(BVLC_ORIGINAL_UNICAST, &body)
+}
+
+/// One tag header: (tag number, context class, length / value field, header length).
+fn read_tag(data: &[u8]) -> Result<(u8, bool, usize, usize), BacnetError> {
+    let first = *data.first().ok_or_else(|| protocol("truncated tag"))?;
+    let mut number = first >> 4;
+    let context = first & 0x08 != 0;
+    let mut used = 1;
+    if number == 0x0F {
+        number = *data.get(used).ok_or_else(|| protocol("truncated tag"))?;
+        used += 1;
+    }
+    let lvt = (first & 0x07) as usize;
+    let len = if lvt == 5 {
+        let ext = *data.get(used).ok_or_else(|| protocol("truncated tag"))? as usize;
+        used += 1;
+        match ext {
+            254 => {
+                let b = data
+                    .get(used..used + 2)
+                    .ok_or_else(|| protocol("truncated tag"))?;
+                used += 2;
+                u16::from_be_bytes([b[0], b[1]]) as usize
+            }
+            255 => return Err(protocol("values over 64 KiB are not supported")),
+            n => n,
+        }
+    } else {
+        lvt
+    };
+    Ok((number, context, len, used))
+}
+
+fn unsigned(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0u32, |acc, b| (acc << 8) | u32::from(*b))
+}
+
+fn signed(bytes: &[u8]) -> i64 {
+    let mut value = unsigned(bytes) as i64;
+    if let Some(first) = bytes.first() {
+        if first & 0x80 != 0 {
+            value -= 1i64 << (8 * bytes.len());
+        }
+    }
+    value
+}
+
+/// Decode one application-tagged primitive as a number.
+fn application_value(data: &[u8]) -> Result<f64, BacnetError> {
+    let (tag, context, len, used) = read_tag(data)?;
+    if context {
+        return Err(protocol("expected an application-tagged value"));
+    }
+    if tag == 1 {
+        // Boolean: the value is the length field itself
+        return Ok(if len != 0 { 1.0 } else { 0.0 });
+    }
+    let body = data
+        .get(used..used + len)
+        .ok_or_else(|| protocol("truncated value"))?;
+    match tag {
+        2 | 9 if len <= 4 => Ok(f64::from(unsigned(body))),
+        3 if len <= 4 => Ok(signed(body) as f64),
+        4 if len == 4 => Ok(f64::from(f32::from_be_bytes([
+            body[0], body[1], body[2], body[3],
+        ]))),
+        5 if len == 8 => {
+            let mut b = [0u8; 8];
+            b.copy_from_slice(body);
+            Ok(f64::from_be_bytes(b))
+        }
+        _ => Err(protocol(format!(
+            "value with application tag {} is not numeric",
+            tag
+        ))),
+    }
+}
+
+/// Strip BVLC and NPDU; returns the APDU, or `None` for network-layer messages.
+fn apdu_of(packet: &[u8]) -> Result<Option<&[u8]>, BacnetError> {
+    if packet.len() < 4 || packet[0] != BVLC_TYPE {
+        return Err(protocol("not a BACnet/IP packet"));
+    }
+    let declared = u16::from_be_bytes([packet[2], packet[3]]) as usize;
+    if declared != packet.len() {
+        return Err(protocol("BVLC length does not match packet"));
+    }
+    let npdu = match packet[1] {
+        BVLC_ORIGINAL_UNICAST | BVLC_ORIGINAL_BROADCAST => &packet[4..],
+        BVLC_FORWARDED_NPDU => packet
+            .get(10..)
+            .ok_or_else(|| protocol("truncated forwarded NPDU"))?,
+        _ => return Ok(None),
+    };
+    if npdu.len() < 2 || npdu[0] != NPDU_VERSION {
+        return Err(protocol("unsupported NPDU version"));
+    }
+    let control = npdu[1];
+    if control & 0x80 != 0 {
+        return Ok(None);
+    }
+    let mut at = 2;
+    let skip_address = |at: &mut usize| -> Result<(), BacnetError> {
+        let len = *npdu
+            .get(*at + 2)
+            .ok_or_else(|| protocol("truncated NPDU address"))? as usize;
+        *at += 3 + len;
+        Ok(())
+    };
+    let has_destination = control & 0x20 != 0;
+    if has_destination {
+        skip_address(&mut at)?;
+    }
+    if control & 0x08 != 0 {
+        skip_address(&mut at)?;
+    }
+    if has_destination {
+        at += 1; // hop count
+    }
+    npdu.get(at..)
+        .map(Some)
+        .ok_or_else(|| protocol("truncated NPDU"))
+}
+
+/// Decode a received BACnet/IP packet.
+pub fn decode(packet: &[u8]) -> Result<Apdu, BacnetError> {
+    let Some(apdu) = apdu_of(packet)? else {
+        return Ok(Apdu::Other);
+    };
+    let first = *apdu.first().ok_or_else(|| protocol("empty APDU"))?;
+    match first >> 4 {
+        PDU_UNCONFIRMED_REQUEST if apdu.get(1) == Some(&SERVICE_I_AM) => {
+            let mut at = 2;
+            let mut fields = Vec::new();
+            for _ in 0..4 {
+                let (_, _, len, used) = read_tag(&apdu[at..])?;
+                let body = apdu
+                    .get(at + used..at + used + len)
+                    .ok_or_else(|| protocol("truncated I-Am"))?;
+                fields.push(body);
+                at += used + len;
+            }
+            let device = ObjectId::decode(fields[0])?;
+            Ok(Apdu::IAm(IAm {
+                device_instance: device.instance,
+                max_apdu: unsigned(fields[1]),
+                vendor_id: unsigned(fields[3]),
+            }))
+        }
+        PDU_COMPLEX_ACK => {
+            if first & 0x08 != 0 {
+                return Err(protocol("segmented responses are not supported"));
+            }
+            let invoke_id = *apdu.get(1).ok_or_else(|| protocol("truncated ACK"))?;
+            if apdu.get(2) != Some(&SERVICE_READ_PROPERTY) {
+                return Ok(Apdu::Other);
+            }
+            let mut at = 3;
+            let (_, _, len, used) = read_tag(&apdu[at..])?;
+            let object = ObjectId::decode(
+                apdu.get(at + used..at + used + len)
+                    .ok_or_else(|| protocol("truncated ACK"))?,
+            )?;
+            at += used + len;
+            let (_, _, len, used) = read_tag(&apdu[at..])?;
+            let property = unsigned(
+                apdu.get(at + used..at + used + len)
+                    .ok_or_else(|| protocol("truncated ACK"))?,
+            );
+            at += used + len;
+            // Optional array index (context tag 2)
+            let (tag, context, len, used) = read_tag(&apdu[at..])?;
+            if context && tag == 2 {
+                at += used + len;
+            }
+            if apdu.get(at) != Some(&0x3E) {
+                return Err(protocol("ReadProperty-ACK without a value"));
+            }
+            let value = application_value(&apdu[at + 1..])?;
+            Ok(Apdu::ReadPropertyAck {
+                invoke_id,
+                object,
+                property,
+                value,
+            })
+        }
+        PDU_ERROR | PDU_REJECT | PDU_ABORT => {
+            let invoke_id = *apdu.get(1).ok_or_else(|| protocol("truncated error"))?;
+            let reason = match first >> 4 {
+                PDU_ERROR => {
+                    // Error class and code, both enumerated
+                    let rest = apdu.get(3..).unwrap_or_default();
+                    let class = read_tag(rest)
+                        .ok()
+                        .and_then(|(_, _, len, used)| rest.get(used..used + len))
+                        .map(unsigned);
+                    let code = class.and_then(|_| {
+                        let (_, _, len, used) = read_tag(rest).ok()?;
+                        let rest = rest.get(used + len..)?;
+                        let (_, _, len2, used2) = read_tag(rest).ok()?;
+                        rest.get(used2..used2 + len2).map(unsigned)
+                    });
+                    format!(
+                        "error class {} code {}",
+                        class.map(|c| c.to_string()).unwrap_or_else(|| "?".into()),
+                        code.map(|c| c.to_string()).unwrap_or_else(|| "?".into())
+                    )
+                }
+                PDU_REJECT => format!("rejected (reason {})", apdu.get(2).copied().unwrap_or(0)),
+                _ => format!("aborted (reason {})", apdu.get(2).copied().unwrap_or(0)),
+            };
+            Ok(Apdu::Failure { invoke_id, reason })
+        }
+        _ => Ok(Apdu::Other),
+    }
+}
+
+/// Request carried by a packet, for simulators: `(invoke id, object, property)`
+/// of a ReadProperty, or the Who-Is range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    WhoIs(Option<(u32, u32)>),
+    ReadProperty {
+        invoke_id: u8,
+        object: ObjectId,
+        property: u32,
+    },
+    Other,
+}
+
+/// Decode a request packet (the device side of [`decode`]).
+pub fn decode_request(packet: &[u8]) -> Result<Request, BacnetError> {
+    let Some(apdu) = apdu_of(packet)? else {
+        return Ok(Request::Other);
+    };
+    let first = *apdu.first().ok_or_else(|| protocol("empty APDU"))?;
+    match first >> 4 {
+        PDU_UNCONFIRMED_REQUEST if apdu.get(1) == Some(&SERVICE_WHO_IS) => {
+            if apdu.len() == 2 {
+                return Ok(Request::WhoIs(None));
+            }
+            let (_, _, len, used) = read_tag(&apdu[2..])?;
+            let low = unsigned(
+                apdu.get(2 + used..2 + used + len)
+                    .ok_or_else(|| protocol("truncated Who-Is"))?,
+            );
+            let at = 2 + used + len;
+            let (_, _, len, used) = read_tag(&apdu[at..])?;
+            let high = unsigned(
+                apdu.get(at + used..at + used + len)
+                    .ok_or_else(|| protocol("truncated Who-Is"))?,
+            );
+            Ok(Request::WhoIs(Some((low, high))))
+        }
+        PDU_CONFIRMED_REQUEST if apdu.get(3) == Some(&SERVICE_READ_PROPERTY) => {
+            let invoke_id = apdu[2];
+            let object = ObjectId::decode(
+                apdu.get(5..9)
+                    .ok_or_else(|| protocol("truncated ReadProperty"))?,
+            )?;
+            let (_, _, len, used) = read_tag(&apdu[9..])?;
+            let property = unsigned(
+                apdu.get(9 + used..9 + used + len)
+                    .ok_or_else(|| protocol("truncated ReadProperty"))?,
+            );
+            Ok(Request::ReadProperty {
+                invoke_id,
+                object,
+                property,
+            })
+        }
+        _ => Ok(Request::Other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_discovery_and_read_property() {
+        assert_eq!(
+            decode_request(&encode_who_is(Some((1001, 1001)))).unwrap(),
+            Request::WhoIs(Some((1001, 1001)))
+        );
+        assert_eq!(
+            decode_request(&encode_who_is(None)).unwrap(),
+            Request::WhoIs(None)
+        );
+        match decode(&encode_i_am(1001, 42)).unwrap() {
+            Apdu::IAm(i_am) => {
+                assert_eq!(i_am.device_instance, 1001);
+                assert_eq!(i_am.max_apdu, 1476);
+                assert_eq!(i_am.vendor_id, 42);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let object: ObjectId = "ai:3".parse().unwrap();
+        assert_eq!(object.to_string(), "analog-input:3");
+        let request = encode_read_property(7, object, PROP_PRESENT_VALUE);
+        assert_eq!(
+            decode_request(&request).unwrap(),
+            Request::ReadProperty {
+                invoke_id: 7,
+                object,
+                property: PROP_PRESENT_VALUE,
+            }
+        );
+        let ack = encode_read_property_ack(7, object, PROP_PRESENT_VALUE, 21.5);
+        assert_eq!(
+            decode(&ack).unwrap(),
+            Apdu::ReadPropertyAck {
+                invoke_id: 7,
+                object,
+                property: PROP_PRESENT_VALUE,
+                value: 21.5,
+            }
+        );
+
+        // Enumerated present-value of a binary input, and an error PDU
+        let mut enumerated = ack.clone();
+        let len = enumerated.len();
+        enumerated.splice(len - 6..len - 1, [0x91, 0x01]);
+        enumerated[3] = enumerated.len() as u8;
+        assert!(matches!(
+            decode(&enumerated).unwrap(),
+            Apdu::ReadPropertyAck { value, .. } if value == 1.0
+        ));
+        let error = bvlc(
+            BVLC_ORIGINAL_UNICAST,
+            &[0x01, 0x00, 0x50, 7, 0x0C, 0x91, 0x02, 0x91, 0x20],
+        );
+        assert_eq!(
+            decode(&error).unwrap(),
+            Apdu::Failure {
+                invoke_id: 7,
+                reason: "error class 2 code 32".into()
+            }
+        );
+        assert!("pump:1".parse::<ObjectId>().is_err());
+        assert!(decode(&[0x81, 0x0A, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! `.arx/bacnet.yaml`: which BACnet points feed which sensor ids / equipment.
+
+use super::codec::{ObjectId, BACNET_PORT, MAX_INSTANCE};
+use super::BacnetError;
+use crate::core::operations::{bind_sensor, SensorBindTarget};
+use crate::core::Building;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// BACnet point mapping, relative to the project root.
+pub const BACNET_CONFIG: &str = ".arx/bacnet.yaml";
+
+fn default_broadcast() -> String {
+    format!("255.255.255.255:{}", BACNET_PORT)
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_timeout_ms() -> u64 {
+    3000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacnetConfig {
+    /// Where Who-Is goes (the subnet broadcast address)
+    #[serde(default = "default_broadcast")]
+    pub broadcast: String,
+    /// Local address to bind (default: any interface, ephemeral port)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind: Option<String>,
+    /// Seconds between polls in `arx bacnet poll` / the agent
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// How long to wait for each reply
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub devices: Vec<BacnetDevice>,
+}
+
+impl Default for BacnetConfig {
+    fn default() -> Self {
+        Self {
+            broadcast: default_broadcast(),
+            bind: None,
+            interval_secs: default_interval_secs(),
+            timeout_ms: default_timeout_ms(),
+            devices: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacnetDevice {
+    /// Device object instance number
+    pub instance: u32,
+    /// `ip:port`; discovered with Who-Is when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub points: Vec<BacnetPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacnetPoint {
+    /// `analog-input:1`, `bv:4`, …
+    pub object: String,
+    /// Sensor id readings are recorded under (default `bacnet:<device>:<object>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_type: Option<String>,
+    /// Equipment id, name, or alias the sensor belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment: Option<String>,
+}
+
+impl BacnetPoint {
+    pub fn object_id(&self) -> Result<ObjectId, String> {
+        self.object.parse()
+    }
+
+    /// Sensor id for this point on device `device`.
+    pub fn sensor_id_for(&self, device: u32) -> String {
+        match self.sensor_id.as_deref().map(str::trim) {
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => {
+                let object = self
+                    .object_id()
+                    .map(|o| o.to_string())
+                    .unwrap_or_else(|_| self.object.trim().to_string());
+                format!("bacnet:{}:{}", device, object)
+            }
+        }
+    }
+}
+
+impl BacnetConfig {
+    /// Load and check [`BACNET_CONFIG`] under `base`; empty when the file is absent.
+    pub fn load_at(base: &Path) -> Result<Self, BacnetError> {
+        let path = base.join(BACNET_CONFIG);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let config: Self = serde_yaml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| BacnetError::Config(format!("{}: {}", path.display(), e)))?;
+        config.validate().map_err(BacnetError::Config)?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_secs == 0 {
+            return Err("interval_secs must be at least 1".into());
+        }
+        if self.timeout_ms == 0 {
+            return Err("timeout_ms must be at least 1".into());
+        }
+        let mut sensor_ids = HashSet::new();
+        for device in &self.devices {
+            if device.instance > MAX_INSTANCE {
+                return Err(format!(
+                    "device instance {} is out of range",
+                    device.instance
+                ));
+            }
+            for point in &device.points {
+                point
+                    .object_id()
+                    .map_err(|e| format!("device {}: {}", device.instance, e))?;
+                let id = point.sensor_id_for(device.instance);
+                if !sensor_ids.insert(id.clone()) {
+                    return Err(format!("sensor id '{}' is mapped twice", id));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.devices.iter().map(|d| d.points.len()).sum()
+    }
+}
+
+/// Add a sensor mapping for every point that names its equipment. Points
+/// already mapped on that equipment are left alone. Returns
+/// `(sensor id, equipment name)` for each new binding.
+pub fn bind_points(
+    building: &mut Building,
+    config: &BacnetConfig,
+) -> Result<Vec<(String, String)>, String> {
+    let mut bound = Vec::new();
+    for device in &config.devices {
+        for point in &device.points {
+            let Some(needle) = point.equipment.as_deref().map(str::trim) else {
+                continue;
+            };
+            let sensor_id = point.sensor_id_for(device.instance);
+            let equipment = building
+                .get_all_equipment()
+                .into_iter()
+                .find(|e| e.id == needle || e.matches_name(needle))
+                .ok_or_else(|| format!("{}: equipment '{}' not found", sensor_id, needle))?;
+            let already = equipment
+                .sensor_mappings
+                .iter()
+                .flatten()
+                .any(|m| m.sensor_id == sensor_id);
+            if already {
+                continue;
+            }
+            let target = SensorBindTarget::Equipment(equipment.id.clone());
+            let name = bind_sensor(
+                building,
+                &sensor_id,
+                point.sensor_type.as_deref().unwrap_or(""),
+                &target,
+                None,
+            )?;
+            bound.push((sensor_id, name));
+        }
+    }
+    Ok(bound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn test_config_defaults_and_bind_points() {
+        let config: BacnetConfig = serde_yaml::from_str(
+            "devices:\n  - instance: 1001\n    points:\n      - object: ai:1\n        sensor_type: temperature\n        equipment: AHU-1\n      - object: bv:4\n        sensor_id: fan\n",
+        )
+        .unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.interval_secs, 60);
+        assert_eq!(config.point_count(), 2);
+        let point = &config.devices[0].points[0];
+        assert_eq!(point.sensor_id_for(1001), "bacnet:1001:analog-input:1");
+
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Roof".into(), 5);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let bound = bind_points(&mut building, &config).unwrap();
+        assert_eq!(
+            bound,
+            vec![(
+                "bacnet:1001:analog-input:1".to_string(),
+                "AHU-1".to_string()
+            )]
+        );
+        assert!(bind_points(&mut building, &config).unwrap().is_empty());
+
+        let mut twice = config.clone();
+        twice.devices[0].points[1].sensor_id = Some("bacnet:1001:analog-input:1".into());
+        assert!(twice.validate().is_err());
+    }
+}
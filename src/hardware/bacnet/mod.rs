@@ -0,0 +1,53 @@
+//! BACnet/IP: discover devices and poll mapped points into sensor ingestion.
+//!
+//! Points are listed in [`BACNET_CONFIG`] (`.arx/bacnet.yaml`):
+//!
+//! ```yaml
+//! broadcast: 192.168.1.255:47808
+//! interval_secs: 60
+//! devices:
+//!   - instance: 1001
+//!     address: 192.168.1.50:47808   # optional; found with Who-Is otherwise
+//!     points:
+//!       - object: analog-input:1
+//!         sensor_type: temperature
+//!         equipment: AHU-1
+//!       - object: bv:4
+//!         sensor_id: ahu-1-fan-status
+//!         sensor_type: run_status
+//!         equipment: AHU-1
+//! ```
+//!
+//! Each poll reads `present-value` of every point and records it through
+//! [`record_sensor_reading`](crate::persistence::sensors::record_sensor_reading),
+//! the same path HTTP `sensor.report` uses, so readings land in the inbox,
+//! the trace log, and per-sensor history. Polling never edits `building.yaml`;
+//! `arx bacnet bind` turns the `equipment` entries into sensor mappings.
+
+pub mod client;
+pub mod codec;
+pub mod mapping;
+
+pub use client::{poll_once, BacnetClient, DiscoveredDevice, PollReport};
+pub use codec::{ObjectId, ObjectType, BACNET_PORT, PROP_PRESENT_VALUE};
+pub use mapping::{bind_points, BacnetConfig, BacnetDevice, BacnetPoint, BACNET_CONFIG};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BacnetError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("BACnet protocol error: {0}")]
+    Protocol(String),
+
+    #[error("no reply from {0}")]
+    Timeout(String),
+
+    #[error("device replied: {0}")]
+    Remote(String),
+
+    #[error("configuration error: {0}")]
+    Config(String),
+}
@@ -0,0 +1,13 @@
+//! Field-bus drivers that feed building automation data into sensor ingestion.
+//!
+//! Drivers read points off the network and record them with
+//! `persistence::sensors::record_sensor_reading`, exactly like the agent's
+//! HTTP `sensor.report`; none of them edit `building.yaml` on their own.
+//! [`mqtt`] goes the other way, publishing state changes to a broker.
+
+#[cfg(feature = "bacnet")]
+pub mod bacnet;
+#[cfg(feature = "modbus")]
+pub mod modbus;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
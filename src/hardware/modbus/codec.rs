@@ -0,0 +1,248 @@
+//! Modbus application PDUs with TCP (MBAP) and RTU (CRC-16) framing, and
+//! conversion of register words into numbers.
+
+use super::ModbusError;
+use serde::{Deserialize, Serialize};
+
+/// Default Modbus TCP port.
+pub const MODBUS_TCP_PORT: u16 = 502;
+
+/// Which table a point lives in; decides the read function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterTable {
+    Coil,
+    DiscreteInput,
+    #[default]
+    Holding,
+    Input,
+}
+
+impl RegisterTable {
+    pub fn function_code(&self) -> u8 {
+        match self {
+            RegisterTable::Coil => 0x01,
+            RegisterTable::DiscreteInput => 0x02,
+            RegisterTable::Holding => 0x03,
+            RegisterTable::Input => 0x04,
+        }
+    }
+
+    /// Coils and discrete inputs are single bits.
+    pub fn is_bit(&self) -> bool {
+        matches!(self, RegisterTable::Coil | RegisterTable::DiscreteInput)
+    }
+}
+
+/// How the register words encode the value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataType {
+    #[default]
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+}
+
+impl DataType {
+    pub fn words(&self) -> u16 {
+        match self {
+            DataType::U16 | DataType::I16 => 1,
+            DataType::U32 | DataType::I32 | DataType::F32 => 2,
+        }
+    }
+}
+
+/// Order of the two words of a 32-bit value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOrder {
+    /// High word first (Modbus convention)
+    #[default]
+    Big,
+    /// Low word first (many meters)
+    Little,
+}
+
+/// Read request PDU: function, start address, quantity.
+pub fn encode_read(table: RegisterTable, start: u16, count: u16) -> Vec<u8> {
+    let mut pdu = vec![table.function_code()];
+    pdu.extend_from_slice(&start.to_be_bytes());
+    pdu.extend_from_slice(&count.to_be_bytes());
+    pdu
+}
+
+/// Wrap a PDU in an MBAP header.
+pub fn frame_tcp(transaction: u16, unit: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(7 + pdu.len());
+    out.extend_from_slice(&transaction.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&((pdu.len() + 1) as u16).to_be_bytes());
+    out.push(unit);
+    out.extend_from_slice(pdu);
+    out
+}
+
+/// Modbus CRC-16 (poly 0xA001, init 0xFFFF).
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in data {
+        crc ^= u16::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Unit id + PDU + CRC (low byte first).
+pub fn frame_rtu(unit: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(3 + pdu.len());
+    out.push(unit);
+    out.extend_from_slice(pdu);
+    let crc = crc16(&out);
+    out.extend_from_slice(&crc.to_le_bytes());
+    out
+}
+
+/// Check an RTU frame's CRC and return unit id and PDU.
+pub fn unframe_rtu(frame: &[u8]) -> Result<(u8, &[u8]), ModbusError> {
+    if frame.len() < 4 {
+        return Err(ModbusError::Protocol("RTU frame too short".into()));
+    }
+    let (body, crc) = frame.split_at(frame.len() - 2);
+    if crc16(body).to_le_bytes() != [crc[0], crc[1]] {
+        return Err(ModbusError::Protocol("RTU CRC mismatch".into()));
+    }
+    Ok((body[0], &body[1..]))
+}
+
+fn exception_text(code: u8) -> &'static str {
+    match code {
+        0x01 => "illegal function",
+        0x02 => "illegal data address",
+        0x03 => "illegal data value",
+        0x04 => "server device failure",
+        0x05 => "acknowledge",
+        0x06 => "server device busy",
+        0x0A => "gateway path unavailable",
+        0x0B => "gateway target failed to respond",
+        _ => "unknown exception",
+    }
+}
+
+/// Data bytes of a read response PDU to `function`.
+pub fn decode_read_response(function: u8, pdu: &[u8]) -> Result<Vec<u8>, ModbusError> {
+    let code = *pdu
+        .first()
+        .ok_or_else(|| ModbusError::Protocol("empty response".into()))?;
+    if code == function | 0x80 {
+        let exception = pdu.get(1).copied().unwrap_or(0);
+        return Err(ModbusError::Exception(format!(
+            "{} (0x{:02X})",
+            exception_text(exception),
+            exception
+        )));
+    }
+    if code != function {
+        return Err(ModbusError::Protocol(format!(
+            "expected function 0x{:02X}, got 0x{:02X}",
+            function, code
+        )));
+    }
+    let count = pdu
+        .get(1)
+        .copied()
+        .ok_or_else(|| ModbusError::Protocol("missing byte count".into()))?;
+    let count = usize::from(count);
+    pdu.get(2..2 + count)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| ModbusError::Protocol("truncated response".into()))
+}
+
+/// Number held in the response `data` for a point of `data_type`.
+pub fn decode_value(
+    table: RegisterTable,
+    data_type: DataType,
+    order: WordOrder,
+    data: &[u8],
+) -> Result<f64, ModbusError> {
+    if table.is_bit() {
+        let byte = data
+            .first()
+            .ok_or_else(|| ModbusError::Protocol("no coil data".into()))?;
+        return Ok(f64::from(byte & 1));
+    }
+    let words: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|w| u16::from_be_bytes([w[0], w[1]]))
+        .collect();
+    if words.len() < data_type.words() as usize {
+        return Err(ModbusError::Protocol(format!(
+            "{:?} needs {} register(s), got {}",
+            data_type,
+            data_type.words(),
+            words.len()
+        )));
+    }
+    let wide = || match order {
+        WordOrder::Big => (u32::from(words[0]) << 16) | u32::from(words[1]),
+        WordOrder::Little => (u32::from(words[1]) << 16) | u32::from(words[0]),
+    };
+    Ok(match data_type {
+        DataType::U16 => f64::from(words[0]),
+        DataType::I16 => f64::from(words[0] as i16),
+        DataType::U32 => f64::from(wide()),
+        DataType::I32 => f64::from(wide() as i32),
+        DataType::F32 => f64::from(f32::from_bits(wide())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_and_values() {
+        // Reference frame: read 10 holding registers from unit 1
+        let rtu = frame_rtu(1, &encode_read(RegisterTable::Holding, 0, 10));
+        assert_eq!(rtu, [0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD]);
+        let (unit, pdu) = unframe_rtu(&rtu).unwrap();
+        assert_eq!((unit, pdu.len()), (1, 5));
+        let mut corrupt = rtu.clone();
+        corrupt[3] ^= 1;
+        assert!(unframe_rtu(&corrupt).is_err());
+
+        let tcp = frame_tcp(9, 1, &encode_read(RegisterTable::Input, 100, 2));
+        assert_eq!(tcp, [0, 9, 0, 0, 0, 6, 1, 0x04, 0, 100, 0, 2]);
+
+        let data = decode_read_response(0x03, &[0x03, 4, 0x41, 0xAC, 0x00, 0x00]).unwrap();
+        let value = decode_value(RegisterTable::Holding, DataType::F32, WordOrder::Big, &data);
+        assert_eq!(value.unwrap(), 21.5);
+        let swapped = [0x00, 0x00, 0x41, 0xAC];
+        let value = decode_value(
+            RegisterTable::Holding,
+            DataType::F32,
+            WordOrder::Little,
+            &swapped,
+        );
+        assert_eq!(value.unwrap(), 21.5);
+        let value = decode_value(
+            RegisterTable::Input,
+            DataType::I16,
+            WordOrder::Big,
+            &[0xFF, 0xFE],
+        );
+        assert_eq!(value.unwrap(), -2.0);
+        assert!(matches!(
+            decode_read_response(0x03, &[0x83, 0x02]),
+            Err(ModbusError::Exception(_))
+        ));
+    }
+}
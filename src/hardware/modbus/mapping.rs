@@ -0,0 +1,223 @@
+//! `.arx/modbus.yaml`: which registers feed which sensor ids, and how raw
+//! register values are scaled.
+
+use super::codec::{DataType, RegisterTable, WordOrder, MODBUS_TCP_PORT};
+use super::ModbusError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Modbus point mapping, relative to the project root.
+pub const MODBUS_CONFIG: &str = ".arx/modbus.yaml";
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_unit_id() -> u8 {
+    1
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusConfig {
+    /// Seconds between polls in `arx sensors modbus` / the agent
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// How long to wait for each reply
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub devices: Vec<ModbusDevice>,
+}
+
+impl Default for ModbusConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_interval_secs(),
+            timeout_ms: default_timeout_ms(),
+            devices: Vec::new(),
+        }
+    }
+}
+
+/// How a device is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    /// Modbus TCP (MBAP header) to `host:port`
+    #[default]
+    Tcp,
+    /// RTU frames, to a serial device path or a `host:port` serial gateway
+    Rtu,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusDevice {
+    pub name: String,
+    #[serde(default)]
+    pub transport: Transport,
+    /// `host[:port]` for TCP or a serial gateway; device path (`/dev/ttyUSB0`) for RTU
+    pub address: String,
+    /// Unit (slave) id
+    #[serde(default = "default_unit_id")]
+    pub unit_id: u8,
+    #[serde(default)]
+    pub points: Vec<ModbusPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusPoint {
+    /// Zero-based register (or coil) address
+    pub register: u16,
+    #[serde(default)]
+    pub table: RegisterTable,
+    #[serde(default)]
+    pub data_type: DataType,
+    #[serde(default)]
+    pub word_order: WordOrder,
+    /// Reading = raw × scale + offset
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    #[serde(default)]
+    pub offset: f64,
+    /// Sensor id readings are recorded under (default `modbus:<device>:<register>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_type: Option<String>,
+}
+
+impl ModbusPoint {
+    /// Sensor id for this point on `device`.
+    pub fn sensor_id_for(&self, device: &str) -> String {
+        match self.sensor_id.as_deref().map(str::trim) {
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => format!("modbus:{}:{}", device, self.register),
+        }
+    }
+
+    /// Registers (or coils) one read of this point spans.
+    pub fn count(&self) -> u16 {
+        if self.table.is_bit() {
+            1
+        } else {
+            self.data_type.words()
+        }
+    }
+
+    /// Apply the configured scaling to a raw register value.
+    pub fn convert(&self, raw: f64) -> f64 {
+        raw * self.scale + self.offset
+    }
+}
+
+impl ModbusDevice {
+    /// `host:port` for TCP transports, adding the default port when missing.
+    pub fn socket_address(&self) -> String {
+        let address = self.address.trim();
+        if address.contains(':') {
+            address.to_string()
+        } else {
+            format!("{}:{}", address, MODBUS_TCP_PORT)
+        }
+    }
+}
+
+impl ModbusConfig {
+    /// Load and check [`MODBUS_CONFIG`] under `base`; empty when the file is absent.
+    pub fn load_at(base: &Path) -> Result<Self, ModbusError> {
+        let path = base.join(MODBUS_CONFIG);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load(&path)
+    }
+
+    /// Load and check a config file at `path`.
+    pub fn load(path: &Path) -> Result<Self, ModbusError> {
+        let config: Self = serde_yaml::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| ModbusError::Config(format!("{}: {}", path.display(), e)))?;
+        config.validate().map_err(ModbusError::Config)?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_secs == 0 {
+            return Err("interval_secs must be at least 1".into());
+        }
+        if self.timeout_ms == 0 {
+            return Err("timeout_ms must be at least 1".into());
+        }
+        let mut names = HashSet::new();
+        let mut sensor_ids = HashSet::new();
+        for device in &self.devices {
+            if device.name.trim().is_empty() {
+                return Err("device name cannot be empty".into());
+            }
+            if !names.insert(device.name.as_str()) {
+                return Err(format!("device '{}' is listed twice", device.name));
+            }
+            if device.address.trim().is_empty() {
+                return Err(format!("device '{}': address is required", device.name));
+            }
+            if device.unit_id > 247 {
+                return Err(format!(
+                    "device '{}': unit_id {} is out of range",
+                    device.name, device.unit_id
+                ));
+            }
+            for point in &device.points {
+                let id = point.sensor_id_for(&device.name);
+                if !point.scale.is_finite() || !point.offset.is_finite() {
+                    return Err(format!("{}: scale and offset must be finite", id));
+                }
+                if point.table.is_bit() && point.data_type != DataType::U16 {
+                    return Err(format!("{}: coils and discrete inputs are single bits", id));
+                }
+                if !sensor_ids.insert(id.clone()) {
+                    return Err(format!("sensor id '{}' is mapped twice", id));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.devices.iter().map(|d| d.points.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_and_scaling() {
+        let config: ModbusConfig = serde_yaml::from_str(
+            "devices:\n  - name: meter-1\n    address: 10.0.0.5\n    points:\n      - register: 100\n        data_type: i16\n        scale: 0.1\n        offset: -40\n        sensor_type: temperature\n      - register: 4\n        table: coil\n        sensor_id: pump\n",
+        )
+        .unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.point_count(), 2);
+        let device = &config.devices[0];
+        assert_eq!(device.transport, Transport::Tcp);
+        assert_eq!(device.unit_id, 1);
+        assert_eq!(device.socket_address(), "10.0.0.5:502");
+        let point = &device.points[0];
+        assert_eq!(point.sensor_id_for(&device.name), "modbus:meter-1:100");
+        assert!((point.convert(615.0) - 21.5).abs() < 1e-9);
+        assert_eq!(device.points[1].count(), 1);
+
+        let mut twice = config.clone();
+        twice.devices[0].points[1].sensor_id = Some("modbus:meter-1:100".into());
+        assert!(twice.validate().is_err());
+    }
+}
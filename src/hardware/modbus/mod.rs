@@ -0,0 +1,60 @@
+//! Modbus TCP/RTU: poll mapped registers into sensor ingestion.
+//!
+//! Points are listed in [`MODBUS_CONFIG`] (`.arx/modbus.yaml`), or a file
+//! passed with `arx sensors modbus --config`:
+//!
+//! ```yaml
+//! interval_secs: 30
+//! devices:
+//!   - name: plant-meter
+//!     address: 192.168.1.60          # port 502 unless given
+//!     unit_id: 1
+//!     points:
+//!       - register: 100              # zero-based
+//!         table: input               # holding (default) | input | coil | discrete_input
+//!         data_type: i16             # u16 (default) | i16 | u32 | i32 | f32
+//!         scale: 0.1
+//!         sensor_id: chw-supply-temp
+//!         sensor_type: temperature
+//!   - name: boiler
+//!     transport: rtu
+//!     address: /dev/ttyUSB0          # or host:port of a serial gateway
+//!     unit_id: 4
+//!     points:
+//!       - register: 2
+//!         data_type: f32
+//!         word_order: little
+//!         sensor_type: pressure
+//! ```
+//!
+//! Each reading is `raw × scale + offset`, recorded through
+//! [`record_sensor_reading`](crate::persistence::sensors::record_sensor_reading),
+//! the same path HTTP `sensor.report` and BACnet polling use.
+
+pub mod client;
+pub mod codec;
+pub mod mapping;
+
+pub use client::{poll_once, ModbusClient, ModbusPoller, PollReport};
+pub use codec::{DataType, RegisterTable, WordOrder, MODBUS_TCP_PORT};
+pub use mapping::{ModbusConfig, ModbusDevice, ModbusPoint, Transport, MODBUS_CONFIG};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ModbusError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Modbus protocol error: {0}")]
+    Protocol(String),
+
+    #[error("no reply: {0}")]
+    Timeout(String),
+
+    #[error("device exception: {0}")]
+    Exception(String),
+
+    #[error("configuration error: {0}")]
+    Config(String),
+}
@@ -0,0 +1,326 @@
+//! Turns building and Git state into outbound messages.
+//!
+//! [`Bridge::changes`] compares the building (and commit history) with what
+//! it saw on the previous call and returns the messages to publish; the
+//! caller decides when to look and delivers them with [`publish_queued`].
+
+use super::client::MqttClient;
+use super::config::{topic_segment, EventKind, MqttConfig};
+use super::MqttError;
+use crate::core::{clock, Building};
+use crate::export::embed::{equipment_state, RoomState};
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
+use crate::git::CommitInfo;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+
+/// Messages held while the broker is unreachable; the oldest are dropped.
+pub const MAX_QUEUED: usize = 10_000;
+
+/// Commits read per check.
+const COMMIT_WINDOW: usize = 50;
+
+/// One outbound message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub topic: String,
+    /// JSON, or empty to clear a retained topic
+    pub payload: String,
+    pub retain: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct EquipmentView {
+    name: String,
+    status: Value,
+    health: Value,
+    state: RoomState,
+    room: Option<String>,
+}
+
+fn is_alert(state: RoomState) -> bool {
+    state <= RoomState::Warning
+}
+
+/// Change tracker for one building.
+pub struct Bridge {
+    config: MqttConfig,
+    /// `None` until the first call
+    equipment: Option<BTreeMap<String, EquipmentView>>,
+    last_commit: Option<String>,
+}
+
+impl Bridge {
+    pub fn new(config: MqttConfig) -> Self {
+        Self {
+            config,
+            equipment: None,
+            last_commit: None,
+        }
+    }
+
+    fn view(building: &Building) -> BTreeMap<String, EquipmentView> {
+        building
+            .get_all_equipment()
+            .into_iter()
+            .map(|eq| {
+                let room = eq
+                    .room_id
+                    .as_deref()
+                    .and_then(|id| building.find_room(id))
+                    .map(|r| r.name.clone());
+                let view = EquipmentView {
+                    name: eq.name.clone(),
+                    status: serde_json::to_value(eq.status).unwrap_or(Value::Null),
+                    health: serde_json::to_value(eq.health_status).unwrap_or(Value::Null),
+                    state: equipment_state(eq),
+                    room,
+                };
+                (eq.id.clone(), view)
+            })
+            .collect()
+    }
+
+    /// Messages for what changed since the previous call; `commits` is
+    /// newest first. The first call publishes every equipment status (so
+    /// retained topics start complete) and only notes the current commit.
+    pub fn changes(&mut self, building: &Building, commits: &[CommitInfo]) -> Vec<Message> {
+        let prefix = self.config.prefix_for(&building.name);
+        let at = clock::now().to_rfc3339();
+        let mut messages = Vec::new();
+
+        let current = Self::view(building);
+        let previous = self.equipment.replace(current.clone());
+        if self.config.publishes(EventKind::Status) {
+            let status_topic =
+                |id: &str| format!("{}/equipment/{}/status", prefix, topic_segment(id));
+            for (id, view) in &current {
+                if previous
+                    .as_ref()
+                    .and_then(|p| p.get(id))
+                    .is_some_and(|old| {
+                        old.status == view.status
+                            && old.health == view.health
+                            && old.name == view.name
+                    })
+                {
+                    continue;
+                }
+                messages.push(Message {
+                    topic: status_topic(id),
+                    payload: json!({
+                        "id": id,
+                        "name": view.name,
+                        "status": view.status,
+                        "health": view.health,
+                        "state": view.state.as_str(),
+                        "room": view.room,
+                        "at": at,
+                    })
+                    .to_string(),
+                    retain: self.config.retain,
+                });
+            }
+            for id in previous.iter().flat_map(|p| p.keys()) {
+                if !current.contains_key(id) && self.config.retain {
+                    messages.push(Message {
+                        topic: status_topic(id),
+                        payload: String::new(),
+                        retain: true,
+                    });
+                }
+            }
+        }
+
+        if let (Some(previous), true) = (&previous, self.config.publishes(EventKind::Alerts)) {
+            for (id, view) in &current {
+                let before = previous.get(id).map(|v| v.state).unwrap_or(RoomState::Ok);
+                if before == view.state || !(is_alert(before) || is_alert(view.state)) {
+                    continue;
+                }
+                messages.push(Message {
+                    topic: format!("{}/alerts", prefix),
+                    payload: json!({
+                        "equipment_id": id,
+                        "name": view.name,
+                        "room": view.room,
+                        "state": view.state.as_str(),
+                        "previous": before.as_str(),
+                        "cleared": !is_alert(view.state),
+                        "at": at,
+                    })
+                    .to_string(),
+                    retain: false,
+                });
+            }
+        }
+
+        let newest = commits.first().map(|c| c.id.clone());
+        if let (Some(last), true) = (&self.last_commit, self.config.publishes(EventKind::Commits)) {
+            let new: Vec<&CommitInfo> = match commits.iter().position(|c| &c.id == last) {
+                Some(n) => commits[..n].iter().collect(),
+                // History rewritten or too far behind: announce the head only
+                None => commits.first().into_iter().collect(),
+            };
+            for commit in new.into_iter().rev() {
+                messages.push(Message {
+                    topic: format!("{}/commits", prefix),
+                    payload: json!({
+                        "commit": commit.id,
+                        "message": commit.message.trim_end(),
+                        "author": commit.author,
+                        "time": commit.time,
+                    })
+                    .to_string(),
+                    retain: false,
+                });
+            }
+        }
+        if newest.is_some() {
+            self.last_commit = newest;
+        }
+        messages
+    }
+}
+
+/// Recent commits under `base`, newest first; empty outside a Git repository.
+pub fn recent_commits(base: &Path) -> Result<Vec<CommitInfo>, MqttError> {
+    if !base.join(".git").exists() {
+        return Ok(Vec::new());
+    }
+    let path = base
+        .to_str()
+        .ok_or_else(|| MqttError::State("project path is not valid UTF-8".into()))?;
+    let git = BuildingGitManager::new(
+        path,
+        "building",
+        GitConfigManager::load_from_arx_config_or_env(),
+    )
+    .map_err(|e| MqttError::State(e.to_string()))?;
+    // A repository without commits has no history to announce yet
+    Ok(git.list_commits(COMMIT_WINDOW).unwrap_or_default())
+}
+
+/// Queue `messages`, dropping the oldest beyond [`MAX_QUEUED`].
+pub fn enqueue(queue: &mut VecDeque<Message>, messages: Vec<Message>) {
+    queue.extend(messages);
+    while queue.len() > MAX_QUEUED {
+        queue.pop_front();
+    }
+}
+
+/// Publish queued messages in order; on failure the rest stay queued.
+pub fn publish_queued(
+    client: &mut MqttClient,
+    queue: &mut VecDeque<Message>,
+) -> Result<usize, MqttError> {
+    let mut sent = 0;
+    while let Some(message) = queue.front() {
+        client.publish(&message.topic, message.payload.as_bytes(), message.retain)?;
+        queue.pop_front();
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::codec::read_packet;
+    use super::*;
+    use crate::core::{Equipment, EquipmentHealthStatus, EquipmentStatus, EquipmentType, Floor};
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    fn config(broker: &str) -> MqttConfig {
+        serde_yaml::from_str(&format!("broker: {}\nqos: 1\n", broker)).unwrap()
+    }
+
+    fn commit(id: &str) -> CommitInfo {
+        CommitInfo {
+            id: id.into(),
+            message: format!("commit {}\n", id),
+            author: "ops".into(),
+            time: 0,
+        }
+    }
+
+    #[test]
+    fn test_changes_and_publish_to_broker() {
+        let mut building = Building::new("HQ East".into(), String::new());
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.equipment.push(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        floor.equipment.push(Equipment::new(
+            "P-1".into(),
+            String::new(),
+            EquipmentType::Plumbing,
+        ));
+        building.add_floor(floor);
+        let ahu = building.floors[0].equipment[0].id.clone();
+
+        let mut bridge = Bridge::new(config("127.0.0.1"));
+        let first = bridge.changes(&building, &[commit("a")]);
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|m| m.retain));
+        assert_eq!(
+            first[0].topic.split('/').take(3).collect::<Vec<_>>(),
+            ["arxos", "hq-east", "equipment"]
+        );
+        assert!(bridge.changes(&building, &[commit("a")]).is_empty());
+
+        building.floors[0].equipment[0].health_status = Some(EquipmentHealthStatus::Critical);
+        building.floors[0].equipment[1].status = EquipmentStatus::Inactive;
+        let removed = building.floors[0].equipment.remove(1);
+        let second = bridge.changes(&building, &[commit("c"), commit("b"), commit("a")]);
+        let topics: Vec<&str> = second.iter().map(|m| m.topic.as_str()).collect();
+        assert_eq!(
+            topics,
+            [
+                format!("arxos/hq-east/equipment/{}/status", ahu).as_str(),
+                format!("arxos/hq-east/equipment/{}/status", removed.id).as_str(),
+                "arxos/hq-east/alerts",
+                "arxos/hq-east/commits",
+                "arxos/hq-east/commits",
+            ]
+        );
+        assert!(second[1].payload.is_empty());
+        assert!(second[2].payload.contains("\"state\":\"critical\""));
+        assert!(second[3].payload.contains("\"commit\":\"b\""));
+
+        // A broker on loopback that acknowledges everything
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            while let Ok((header, body)) = read_packet(&mut stream) {
+                match header & 0xF0 {
+                    0x10 => stream.write_all(&[0x20, 2, 0, 0]).unwrap(),
+                    0x30 => {
+                        let topic_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                        let topic = String::from_utf8(body[2..2 + topic_len].to_vec()).unwrap();
+                        let id = &body[2 + topic_len..4 + topic_len];
+                        stream.write_all(&[0x40, 2, id[0], id[1]]).unwrap();
+                        tx.send((topic, header & 0x01 == 1)).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        let mut client = MqttClient::connect(&config(&address)).unwrap();
+        let mut queue = VecDeque::new();
+        enqueue(&mut queue, second);
+        assert_eq!(publish_queued(&mut client, &mut queue).unwrap(), 5);
+        assert!(queue.is_empty());
+        client.disconnect();
+        let received: Vec<(String, bool)> = rx.iter().take(5).collect();
+        assert_eq!(received[2], ("arxos/hq-east/alerts".to_string(), false));
+        assert!(received[0].1);
+    }
+}
@@ -0,0 +1,116 @@
+//! Blocking MQTT publisher over TCP.
+
+use super::codec::{
+    decode_connack, decode_puback, encode_connect, encode_disconnect, encode_pingreq,
+    encode_publish, read_packet, ConnectOptions, CONNACK, PINGRESP, PUBACK,
+};
+use super::config::MqttConfig;
+use super::MqttError;
+use std::io::{ErrorKind, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+pub struct MqttClient {
+    stream: TcpStream,
+    broker: String,
+    qos: u8,
+    next_id: u16,
+    keep_alive: Duration,
+    last_sent: Instant,
+}
+
+fn timed_out(e: &std::io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+impl MqttClient {
+    /// Connect and wait for the broker to accept the session.
+    pub fn connect(config: &MqttConfig) -> Result<Self, MqttError> {
+        let broker = config.socket_address();
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let address = broker
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| MqttError::Config(format!("cannot resolve {}", broker)))?;
+        let mut stream = TcpStream::connect_timeout(&address, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.write_all(&encode_connect(&ConnectOptions {
+            client_id: config.client_id.clone(),
+            username: config.username.clone(),
+            password: config.password()?,
+            keep_alive_secs: config.keep_alive_secs,
+        })?)?;
+        let (header, body) = read_packet(&mut stream).map_err(|e| match e {
+            MqttError::Io(e) if timed_out(&e) => MqttError::Timeout(broker.clone()),
+            e => e,
+        })?;
+        if header & 0xF0 != CONNACK {
+            return Err(MqttError::Protocol(format!(
+                "expected CONNACK, got packet type {:#04x}",
+                header
+            )));
+        }
+        decode_connack(&body)?;
+        Ok(Self {
+            stream,
+            broker,
+            qos: config.qos,
+            next_id: 1,
+            keep_alive: Duration::from_secs(u64::from(config.keep_alive_secs)),
+            last_sent: Instant::now(),
+        })
+    }
+
+    pub fn broker(&self) -> &str {
+        &self.broker
+    }
+
+    /// Publish one message; at QoS 1, waits for the broker's PUBACK.
+    pub fn publish(&mut self, topic: &str, payload: &[u8], retain: bool) -> Result<(), MqttError> {
+        let packet_id = (self.qos > 0).then(|| {
+            let id = self.next_id;
+            self.next_id = self.next_id.checked_add(1).unwrap_or(1);
+            id
+        });
+        self.stream.write_all(&encode_publish(
+            topic, payload, self.qos, retain, packet_id,
+        )?)?;
+        self.last_sent = Instant::now();
+        let Some(id) = packet_id else {
+            return Ok(());
+        };
+        loop {
+            let (header, body) = read_packet(&mut self.stream).map_err(|e| match e {
+                MqttError::Io(e) if timed_out(&e) => {
+                    MqttError::Timeout(format!("PUBACK for {}", topic))
+                }
+                e => e,
+            })?;
+            if header & 0xF0 == PUBACK && decode_puback(&body)? == id {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Send PINGREQ when half the keep-alive has passed without traffic.
+    pub fn keep_alive(&mut self) -> Result<(), MqttError> {
+        if self.keep_alive.is_zero() || self.last_sent.elapsed() < self.keep_alive / 2 {
+            return Ok(());
+        }
+        self.stream.write_all(&encode_pingreq())?;
+        self.last_sent = Instant::now();
+        let (header, _) = read_packet(&mut self.stream)?;
+        if header & 0xF0 != PINGRESP {
+            return Err(MqttError::Protocol(format!(
+                "expected PINGRESP, got packet type {:#04x}",
+                header
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn disconnect(mut self) {
+        let _ = self.stream.write_all(&encode_disconnect());
+    }
+}
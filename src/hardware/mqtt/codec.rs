@@ -0,0 +1,211 @@
+//! MQTT 3.1.1 packets the publisher needs: CONNECT / CONNACK, PUBLISH /
+//! PUBACK, PINGREQ / PINGRESP, and DISCONNECT.
+
+use super::MqttError;
+use std::io::Read;
+
+/// Default broker port (plain TCP).
+pub const MQTT_PORT: u16 = 1883;
+
+pub const CONNACK: u8 = 0x20;
+pub const PUBACK: u8 = 0x40;
+pub const PINGRESP: u8 = 0xD0;
+
+/// Largest value the remaining-length field can hold.
+const MAX_REMAINING_LENGTH: usize = 268_435_455;
+
+/// CONNECT fields.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub keep_alive_secs: u16,
+}
+
+fn encode_remaining_length(mut len: usize, out: &mut Vec<u8>) {
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+fn encode_string(s: &str, out: &mut Vec<u8>) -> Result<(), MqttError> {
+    let len = u16::try_from(s.len())
+        .map_err(|_| MqttError::Protocol(format!("string of {} bytes is too long", s.len())))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn packet(header: u8, body: &[u8]) -> Result<Vec<u8>, MqttError> {
+    if body.len() > MAX_REMAINING_LENGTH {
+        return Err(MqttError::Protocol(format!(
+            "packet of {} bytes is too large",
+            body.len()
+        )));
+    }
+    let mut out = vec![header];
+    encode_remaining_length(body.len(), &mut out);
+    out.extend_from_slice(body);
+    Ok(out)
+}
+
+pub fn encode_connect(options: &ConnectOptions) -> Result<Vec<u8>, MqttError> {
+    let mut flags = 0x02; // clean session
+    if options.username.is_some() {
+        flags |= 0x80;
+    }
+    if options.password.is_some() {
+        flags |= 0x40;
+    }
+    let mut body = Vec::new();
+    encode_string("MQTT", &mut body)?;
+    body.push(4); // protocol level 3.1.1
+    body.push(flags);
+    body.extend_from_slice(&options.keep_alive_secs.to_be_bytes());
+    encode_string(&options.client_id, &mut body)?;
+    if let Some(username) = &options.username {
+        encode_string(username, &mut body)?;
+    }
+    if let Some(password) = &options.password {
+        encode_string(password, &mut body)?;
+    }
+    packet(0x10, &body)
+}
+
+/// Check a topic name for publishing: non-empty, no wildcards or NUL.
+pub fn validate_topic(topic: &str) -> Result<(), MqttError> {
+    if topic.is_empty() || topic.contains(['+', '#', '\0']) {
+        return Err(MqttError::Config(format!(
+            "'{}' is not a valid topic name",
+            topic
+        )));
+    }
+    Ok(())
+}
+
+/// PUBLISH; `packet_id` is required for QoS 1.
+pub fn encode_publish(
+    topic: &str,
+    payload: &[u8],
+    qos: u8,
+    retain: bool,
+    packet_id: Option<u16>,
+) -> Result<Vec<u8>, MqttError> {
+    validate_topic(topic)?;
+    let mut body = Vec::new();
+    encode_string(topic, &mut body)?;
+    match (qos, packet_id) {
+        (0, _) => {}
+        (1, Some(id)) if id != 0 => body.extend_from_slice(&id.to_be_bytes()),
+        (1, _) => return Err(MqttError::Protocol("QoS 1 needs a packet id".into())),
+        _ => return Err(MqttError::Config(format!("QoS {} is not supported", qos))),
+    }
+    body.extend_from_slice(payload);
+    packet(0x30 | (qos << 1) | u8::from(retain), &body)
+}
+
+pub fn encode_pingreq() -> Vec<u8> {
+    vec![0xC0, 0x00]
+}
+
+pub fn encode_disconnect() -> Vec<u8> {
+    vec![0xE0, 0x00]
+}
+
+/// Read one packet: `(first byte, body)`.
+pub fn read_packet(reader: &mut impl Read) -> Result<(u8, Vec<u8>), MqttError> {
+    let mut header = [0u8; 1];
+    reader.read_exact(&mut header)?;
+    let mut len = 0usize;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        len |= ((byte[0] & 0x7F) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+        if shift > 21 {
+            return Err(MqttError::Protocol("malformed remaining length".into()));
+        }
+    }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    Ok((header[0], body))
+}
+
+/// Check a CONNACK body; errors carry the broker's reason.
+pub fn decode_connack(body: &[u8]) -> Result<(), MqttError> {
+    let [_, code] = body else {
+        return Err(MqttError::Protocol("malformed CONNACK".into()));
+    };
+    match code {
+        0 => Ok(()),
+        1 => Err(MqttError::Refused("unacceptable protocol version".into())),
+        2 => Err(MqttError::Refused("client id rejected".into())),
+        3 => Err(MqttError::Refused("server unavailable".into())),
+        4 => Err(MqttError::Refused("bad user name or password".into())),
+        5 => Err(MqttError::Refused("not authorized".into())),
+        n => Err(MqttError::Refused(format!("return code {}", n))),
+    }
+}
+
+/// Packet id of a PUBACK body.
+pub fn decode_puback(body: &[u8]) -> Result<u16, MqttError> {
+    match body {
+        [hi, lo] => Ok(u16::from_be_bytes([*hi, *lo])),
+        _ => Err(MqttError::Protocol("malformed PUBACK".into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_connect_publish_and_read_back() {
+        let connect = encode_connect(&ConnectOptions {
+            client_id: "arx".into(),
+            username: Some("u".into()),
+            password: Some("p".into()),
+            keep_alive_secs: 60,
+        })
+        .unwrap();
+        assert_eq!(
+            connect,
+            [
+                0x10, 21, 0, 4, b'M', b'Q', b'T', b'T', 4, 0xC2, 0, 60, 0, 3, b'a', b'r', b'x', 0,
+                1, b'u', 0, 1, b'p'
+            ]
+        );
+
+        let publish = encode_publish("a/b", b"on", 1, true, Some(7)).unwrap();
+        assert_eq!(publish, [0x33, 9, 0, 3, b'a', b'/', b'b', 0, 7, b'o', b'n']);
+        assert!(encode_publish("a/#", b"", 0, false, None).is_err());
+        assert!(encode_publish("a", b"", 1, false, None).is_err());
+        assert!(encode_publish("a", b"", 2, false, Some(1)).is_err());
+
+        let big = encode_publish("t", &[0u8; 200], 0, false, None).unwrap();
+        assert_eq!(&big[..3], &[0x30, 0xCB, 0x01]);
+        let (header, body) = read_packet(&mut big.as_slice()).unwrap();
+        assert_eq!(header, 0x30);
+        assert_eq!(body.len(), 203);
+
+        assert!(decode_connack(&[0, 0]).is_ok());
+        assert!(matches!(
+            decode_connack(&[0, 5]),
+            Err(MqttError::Refused(_))
+        ));
+        assert_eq!(decode_puback(&[0, 7]).unwrap(), 7);
+    }
+}
@@ -0,0 +1,177 @@
+//! `.arx/mqtt.yaml`: broker, credentials, topic prefix, and which state
+//! changes are published.
+
+use super::codec::{validate_topic, MQTT_PORT};
+use super::MqttError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Publisher settings, relative to the project root.
+pub const MQTT_CONFIG: &str = ".arx/mqtt.yaml";
+
+/// Placeholder in `topic_prefix` replaced by the building name.
+pub const BUILDING_PLACEHOLDER: &str = "{building}";
+
+fn default_interval_secs() -> u64 {
+    5
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_keep_alive_secs() -> u16 {
+    60
+}
+
+fn default_client_id() -> String {
+    "arxos".to_string()
+}
+
+fn default_topic_prefix() -> String {
+    format!("arxos/{}", BUILDING_PLACEHOLDER)
+}
+
+fn default_retain() -> bool {
+    true
+}
+
+fn default_events() -> Vec<EventKind> {
+    vec![EventKind::Status, EventKind::Alerts, EventKind::Commits]
+}
+
+/// What the bridge publishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// `<prefix>/equipment/<id>/status` on every status or health change
+    Status,
+    /// `<prefix>/alerts` when equipment enters or leaves warning / critical
+    Alerts,
+    /// `<prefix>/commits` for each new commit
+    Commits,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConfig {
+    /// `host[:port]`; port 1883 unless given
+    pub broker: String,
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Environment variable holding the password (kept out of Git)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_env: Option<String>,
+    /// Topic root; `{building}` becomes the building name as a topic segment
+    #[serde(default = "default_topic_prefix")]
+    pub topic_prefix: String,
+    /// 0 (at most once) or 1 (at least once)
+    #[serde(default)]
+    pub qos: u8,
+    /// Retain status topics so new subscribers get the current state
+    #[serde(default = "default_retain")]
+    pub retain: bool,
+    #[serde(default = "default_events")]
+    pub events: Vec<EventKind>,
+    /// Seconds between checks for changes in `arx mqtt bridge`
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_keep_alive_secs")]
+    pub keep_alive_secs: u16,
+    /// Connect / acknowledgement timeout
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl MqttConfig {
+    /// Load and check [`MQTT_CONFIG`] under `base`.
+    pub fn load_at(base: &Path) -> Result<Self, MqttError> {
+        let path = base.join(MQTT_CONFIG);
+        if !path.exists() {
+            return Err(MqttError::Config(format!(
+                "{} not found; add a broker to publish state changes",
+                MQTT_CONFIG
+            )));
+        }
+        Self::load(&path)
+    }
+
+    /// Load and check a config file at `path`.
+    pub fn load(path: &Path) -> Result<Self, MqttError> {
+        let config: Self = serde_yaml::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| MqttError::Config(format!("{}: {}", path.display(), e)))?;
+        config.validate().map_err(MqttError::Config)?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.broker.trim().is_empty() {
+            return Err("broker is required".into());
+        }
+        if self.client_id.is_empty() || self.client_id.len() > 23 {
+            return Err("client_id must be 1-23 characters".into());
+        }
+        if self.qos > 1 {
+            return Err(format!("qos {} is not supported (0 or 1)", self.qos));
+        }
+        if self.interval_secs == 0 || self.timeout_ms == 0 {
+            return Err("interval_secs and timeout_ms must be at least 1".into());
+        }
+        let prefix = self.topic_prefix.replace(BUILDING_PLACEHOLDER, "b");
+        validate_topic(&prefix).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// `host:port`, adding the default port when missing.
+    pub fn socket_address(&self) -> String {
+        let broker = self.broker.trim();
+        if broker.contains(':') {
+            broker.to_string()
+        } else {
+            format!("{}:{}", broker, MQTT_PORT)
+        }
+    }
+
+    /// Password from `password_env`, if set.
+    pub fn password(&self) -> Result<Option<String>, MqttError> {
+        self.password_env
+            .as_deref()
+            .map(|var| {
+                std::env::var(var)
+                    .map_err(|_| MqttError::Config(format!("password_env: {} is not set", var)))
+            })
+            .transpose()
+    }
+
+    /// Topic prefix for `building`.
+    pub fn prefix_for(&self, building: &str) -> String {
+        self.topic_prefix
+            .replace(BUILDING_PLACEHOLDER, &topic_segment(building))
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    pub fn publishes(&self, kind: EventKind) -> bool {
+        self.events.contains(&kind)
+    }
+}
+
+/// `name` as one topic level: lowercase, with `/`, wildcards, and spaces
+/// turned into `-`.
+pub fn topic_segment(name: &str) -> String {
+    let segment: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '+' | '#' | '\0' => '-',
+            c if c.is_whitespace() => '-',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect();
+    if segment.is_empty() {
+        "-".to_string()
+    } else {
+        segment
+    }
+}
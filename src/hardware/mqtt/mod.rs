@@ -0,0 +1,60 @@
+//! MQTT: publish building state changes to a broker.
+//!
+//! Unlike the field-bus drivers this one is outbound only. `arx mqtt bridge`
+//! watches `building.yaml` and the Git history and publishes what changed,
+//! as configured in [`MQTT_CONFIG`] (`.arx/mqtt.yaml`):
+//!
+//! ```yaml
+//! broker: mqtt.local                 # port 1883 unless given
+//! client_id: arxos-hq
+//! username: arxos
+//! password_env: ARX_MQTT_PASSWORD    # read from the environment
+//! topic_prefix: arxos/{building}     # default
+//! qos: 1                             # 0 | 1
+//! retain: true                       # retain status topics (default)
+//! events: [status, alerts, commits]  # default: all
+//! interval_secs: 5
+//! ```
+//!
+//! Topics, under the prefix:
+//!
+//! - `equipment/<id>/status`: status, health, and state of one item,
+//!   retained so new subscribers see current state
+//! - `alerts`: equipment entering or leaving warning / critical
+//! - `commits`: one message per new commit
+//!
+//! Messages are queued while the broker is unreachable and sent in order
+//! once it is back.
+
+pub mod bridge;
+pub mod client;
+pub mod codec;
+pub mod config;
+
+pub use bridge::{Bridge, Message};
+pub use client::MqttClient;
+pub use codec::MQTT_PORT;
+pub use config::{EventKind, MqttConfig, MQTT_CONFIG};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MqttError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("MQTT protocol error: {0}")]
+    Protocol(String),
+
+    #[error("no reply: {0}")]
+    Timeout(String),
+
+    #[error("broker refused connection: {0}")]
+    Refused(String),
+
+    #[error("configuration error: {0}")]
+    Config(String),
+
+    #[error("cannot read building state: {0}")]
+    State(String),
+}
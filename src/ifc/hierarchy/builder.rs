@@ -217,6 +217,7 @@ impl HierarchyBuilder {
             let room = Room {
                 id: space.id.clone(),
                 name: extract_space_name(space)?,
+                aliases: Vec::new(),
                 room_type: extract_space_type(space)?,
                 equipment: Vec::new(),
                 pending_equipment_ids: Vec::new(),
@@ -258,6 +259,9 @@ impl HierarchyBuilder {
                 address: None,
                 anchors: Vec::new(),
                 pending_anchor_ids: Vec::new(),
+                provenance: Default::default(),
+                vendor_extensions: Default::default(),
+                attachments: Vec::new(),
             };
             rooms.push(room);
         }
@@ -472,6 +476,7 @@ impl HierarchyBuilder {
             let equipment = Equipment {
                 id: eq.id.clone(),
                 name: eq.name.clone(),
+                aliases: Vec::new(),
                 path: format!("/equipment/{}", eq.name.to_lowercase().replace(" ", "-")),
                 address: None,
                 equipment_type: extract_equipment_type(&eq.entity_type)?,
@@ -489,6 +494,12 @@ impl HierarchyBuilder {
                 mesh: None,
                 lidar_enrichment: None,
                 ifc_global_id: None,
+                commissioning: None,
+                runtime: None,
+                provenance: Default::default(),
+                vendor_extensions: Default::default(),
+                attachments: Vec::new(),
+                connections: Vec::new(),
             };
             equipment_list.push(equipment);
         }
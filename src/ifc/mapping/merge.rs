@@ -15,6 +15,7 @@
 
 use std::collections::{HashMap, HashSet};
 
+use crate::core::vendor::merge_missing;
 use crate::core::{Building, Equipment, EquipmentType, Floor, Position, Room};
 
 use super::prefer_existing_lidar;
@@ -90,9 +91,41 @@ pub fn merge_building_with_policy(
     incoming: Building,
     policy: &MergePolicy,
 ) -> MergeResult {
-    match policy.hierarchy {
+    let incoming_global_ids: HashMap<String, String> = incoming
+        .get_all_equipment()
+        .into_iter()
+        .filter_map(|eq| Some((eq.id.clone(), eq.ifc_global_id.clone()?)))
+        .collect();
+    let mut result = match policy.hierarchy {
         HierarchyBase::Incoming => merge_incoming_base(existing, incoming, policy),
         HierarchyBase::Existing => merge_existing_base(existing, incoming, policy),
+    };
+    repoint_connections(&mut result.building, &incoming_global_ids);
+    result
+}
+
+/// Re-point imported connections at the ids matched equipment kept, via the
+/// incoming equipment's IFC GlobalIds.
+fn repoint_connections(building: &mut Building, incoming_global_ids: &HashMap<String, String>) {
+    let merged_ids: HashMap<String, String> = building
+        .get_all_equipment()
+        .into_iter()
+        .filter_map(|eq| Some((eq.ifc_global_id.clone()?, eq.id.clone())))
+        .collect();
+    for eq in building.get_all_equipment_mut() {
+        let mut connections = Vec::with_capacity(eq.connections.len());
+        for mut connection in std::mem::take(&mut eq.connections) {
+            if let Some(id) = incoming_global_ids
+                .get(&connection.to)
+                .and_then(|gid| merged_ids.get(gid))
+            {
+                connection.to = id.clone();
+            }
+            if !connections.contains(&connection) {
+                connections.push(connection);
+            }
+        }
+        eq.connections = connections;
     }
 }
 
@@ -550,12 +583,16 @@ fn merge_room_fields(room: &mut Room, old: &Room, _policy: &MergePolicy) {
             .entry(k.clone())
             .or_insert_with(|| v.clone());
     }
+    merge_missing(&mut room.vendor_extensions, &old.vendor_extensions);
+    room.attachments = old.attachments.clone();
 }
 
 fn merge_equipment_fields(eq: &mut Equipment, old: &Equipment, _policy: &MergePolicy) {
     eq.id = old.id.clone();
     eq.status = old.status;
     eq.health_status = old.health_status;
+    eq.commissioning = old.commissioning.clone();
+    eq.runtime = old.runtime.clone();
     if eq.sensor_mappings.is_none() {
         eq.sensor_mappings = old.sensor_mappings.clone();
     }
@@ -575,6 +612,14 @@ fn merge_equipment_fields(eq: &mut Equipment, old: &Equipment, _policy: &MergePo
         merged.insert(k, v);
     }
     eq.properties = merged;
+    merge_missing(&mut eq.vendor_extensions, &old.vendor_extensions);
+    eq.attachments = old.attachments.clone();
+    // Imported system connections join the existing ones
+    for connection in &old.connections {
+        if !eq.connections.contains(connection) {
+            eq.connections.push(connection.clone());
+        }
+    }
 }
 
 fn finish_orphan_stats(
@@ -958,6 +1003,34 @@ mod tests {
         assert!((eq.position.x - 50.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn imported_connections_follow_matched_equipment_ids() {
+        use crate::core::systems::{Connection, Relation};
+
+        let existing = sample_building();
+        let old_eq_id = existing.floors[0].wings[0].rooms[0].equipment[0].id.clone();
+
+        let mut incoming = existing.clone();
+        let room = &mut incoming.floors[0].wings[0].rooms[0];
+        room.equipment[0].id = "incoming-eq1".into();
+        let mut ahu = Equipment::new("ahu".into(), "".into(), EquipmentType::HVAC);
+        ahu.ifc_global_id = Some("EqGid002".into());
+        ahu.connections.push(Connection {
+            relation: Relation::Feeds,
+            to: "incoming-eq1".into(),
+        });
+        room.add_equipment(ahu);
+
+        let result = merge_building(&existing, incoming);
+        let ahu = result
+            .building
+            .get_all_equipment()
+            .into_iter()
+            .find(|eq| eq.name == "ahu")
+            .unwrap();
+        assert_eq!(ahu.connections[0].to, old_eq_id);
+    }
+
     #[test]
     fn merge_path_fallback_when_no_global_id() {
         let mut existing = Building::new("HQ".into(), "/hq".into());
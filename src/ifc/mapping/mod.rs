@@ -31,8 +31,8 @@ pub use merge::{
     HierarchyBase, MergePolicy, MergeResult, MergeSource,
 };
 pub use properties::{
-    normalize_imported_properties, properties_for_export, wing_name_from_properties, PROP_ARX_WING,
-    PROP_WING,
+    is_arx_pset, normalize_imported_properties, properties_for_export, vendor_step_value,
+    wing_name_from_properties, PROP_ARX_WING, PROP_WING,
 };
 pub use report::{FidelityLevel, LossReport, MappingResult, MappingWarning, MergeStats};
 
@@ -6,6 +6,8 @@
 
 use std::collections::HashMap;
 
+use crate::core::vendor::VendorProperty;
+
 use super::{
     pset_prop_key, PROP_ARX_ID, PROP_ENTITY_KIND, PSET_ARX_BUILDING, PSET_ARX_EQUIPMENT,
     PSET_ARX_FLOOR, PSET_ARX_IDENTITY, PSET_ARX_LIDAR, PSET_ARX_ROOM,
@@ -89,6 +91,66 @@ pub fn properties_for_export(
     out
 }
 
+/// Whether `pset` belongs to the Arx family (free-form or consumed); anything
+/// else is a vendor / third-party set kept in `vendor_extensions`.
+pub fn is_arx_pset(pset: &str) -> bool {
+    ARX_FREEFORM_PSETS.contains(&pset) || ARX_CONSUMED_PSETS.contains(&pset)
+}
+
+/// IFC value types written as quoted strings.
+const STRING_VALUE_TYPES: &[&str] = &[
+    "IFCLABEL",
+    "IFCTEXT",
+    "IFCIDENTIFIER",
+    "IFCDATE",
+    "IFCDATETIME",
+    "IFCTIME",
+    "IFCDURATION",
+    "IFCURIREFERENCE",
+];
+
+/// IFC value types written as STEP integers.
+const INTEGER_VALUE_TYPES: &[&str] = &["IFCINTEGER", "IFCCOUNTMEASURE", "IFCTIMESTAMP"];
+
+/// STEP literal for a vendor property's `NominalValue`, e.g. `IFCREAL(4.2)`.
+///
+/// Values that no longer fit their recorded type (after a hand edit) fall
+/// back to `IFCLABEL` rather than producing an unreadable file.
+pub fn vendor_step_value(property: &VendorProperty) -> String {
+    let label = || format!("IFCLABEL('{}')", property.value.replace('\'', "''"));
+    let Some(ty) = property.ifc_type.as_deref().map(str::to_uppercase) else {
+        return label();
+    };
+    let value = property.value.trim();
+    if STRING_VALUE_TYPES.contains(&ty.as_str()) {
+        return format!("{}('{}')", ty, property.value.replace('\'', "''"));
+    }
+    if ty == "IFCBOOLEAN" || ty == "IFCLOGICAL" {
+        return match value.to_ascii_lowercase().as_str() {
+            "true" | ".t." => format!("{}(.T.)", ty),
+            "false" | ".f." => format!("{}(.F.)", ty),
+            "unknown" | ".u." if ty == "IFCLOGICAL" => format!("{}(.U.)", ty),
+            _ => label(),
+        };
+    }
+    if INTEGER_VALUE_TYPES.contains(&ty.as_str()) {
+        return match value.parse::<i64>() {
+            Ok(n) => format!("{}({})", ty, n),
+            Err(_) => label(),
+        };
+    }
+    match value.parse::<f64>() {
+        Ok(n) if n.is_finite() => {
+            let mut literal = value.to_string();
+            if !literal.contains(['.', 'e', 'E']) {
+                literal.push('.');
+            }
+            format!("{}({})", ty, literal)
+        }
+        _ => label(),
+    }
+}
+
 /// Look up wing name from a (possibly mixed) property bag.
 ///
 /// Accepts clean keys and legacy prefixed keys for robustness.
@@ -216,6 +278,26 @@ mod tests {
         assert!(!out.keys().any(|k| k.contains(PSET_ARX_IDENTITY)));
     }
 
+    #[test]
+    fn vendor_values_keep_their_ifc_type() {
+        let prop = |value: &str, ty: Option<&str>| VendorProperty {
+            value: value.to_string(),
+            ifc_type: ty.map(str::to_string),
+        };
+        assert_eq!(
+            vendor_step_value(&prop("4", Some("IFCVOLUMETRICFLOWRATEMEASURE"))),
+            "IFCVOLUMETRICFLOWRATEMEASURE(4.)"
+        );
+        assert_eq!(vendor_step_value(&prop("12", Some("IFCINTEGER"))), "IFCINTEGER(12)");
+        assert_eq!(vendor_step_value(&prop("true", Some("IFCBOOLEAN"))), "IFCBOOLEAN(.T.)");
+        assert_eq!(
+            vendor_step_value(&prop("O'Neil", Some("IFCTEXT"))),
+            "IFCTEXT('O''Neil')"
+        );
+        assert_eq!(vendor_step_value(&prop("n/a", Some("IFCREAL"))), "IFCLABEL('n/a')");
+        assert_eq!(vendor_step_value(&prop("C-12", None)), "IFCLABEL('C-12')");
+    }
+
     #[test]
     fn wing_lookup_accepts_clean_and_legacy() {
         let mut clean = HashMap::new();
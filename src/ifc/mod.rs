@@ -82,6 +82,7 @@ impl IFCProcessor {
         content: &str,
         validate_strict: bool,
     ) -> anyhow::Result<ParsingResult> {
+        let schema = parser::IfcSchema::detect(content);
         let lexer = parser::StepLexer::new(content);
         let mut registry = parser::EntityRegistry::new();
         registry.populate_from_lexer(lexer);
@@ -94,7 +95,7 @@ impl IFCProcessor {
             ));
         }
 
-        let mut resolver = parser::IfcResolver::new(&mut registry);
+        let mut resolver = parser::IfcResolver::new(&mut registry).with_schema(schema);
         let (building, report) = resolver.resolve_all()?;
         let warnings = report
             .warnings
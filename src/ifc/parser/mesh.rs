@@ -9,15 +9,29 @@ use super::registry::EntityRegistry;
 use crate::core::spatial::mesh::Mesh;
 use crate::core::spatial::types::Point3D;
 use nalgebra::Vector3;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 
 pub struct MeshResolver<'a> {
-    registry: &'a EntityRegistry,
-    geometry: &'a GeometryResolver<'a>,
+    pub(super) registry: &'a EntityRegistry,
+    pub(super) geometry: &'a GeometryResolver<'a>,
+    /// Representation item classes that yielded no mesh, with counts
+    skipped: RefCell<BTreeMap<String, usize>>,
 }
 
 impl<'a> MeshResolver<'a> {
     pub fn new(registry: &'a EntityRegistry, geometry: &'a GeometryResolver<'a>) -> Self {
-        Self { registry, geometry }
+        Self {
+            registry,
+            geometry,
+            skipped: RefCell::default(),
+        }
+    }
+
+    /// Item classes that could not be tessellated so far, with counts.
+    /// Products built only from these fall back to bounding boxes.
+    pub fn skipped_items(&self) -> BTreeMap<String, usize> {
+        self.skipped.borrow().clone()
     }
 
     /// Extract a Mesh from a product definition shape.
@@ -123,7 +137,7 @@ impl<'a> MeshResolver<'a> {
         Some((width, depth, height))
     }
 
-    fn extract_mesh_from_representation(
+    pub(super) fn extract_mesh_from_representation(
         &self,
         rep_id: u64,
         transform: &Transform3D,
@@ -133,23 +147,39 @@ impl<'a> MeshResolver<'a> {
             return None;
         }
 
-        // Param 3: Items (List of geometric items)
+        // Param 3: Items (List of geometric items). The extended backend
+        // merges every item, as IfcOpenShell does; otherwise the first wins.
+        let mut merged: Option<Mesh> = None;
         if let Some(Param::List(items)) = rep_entity.params.get(3) {
             for item_id_param in items {
                 if let Param::Reference(item_id) = item_id_param {
                     if let Some(mesh) = self.resolve_mesh_item(*item_id, transform) {
-                        return Some(mesh);
+                        if !cfg!(feature = "ifc-geometry") {
+                            return Some(mesh);
+                        }
+                        merged = Some(match merged {
+                            Some(mut all) => {
+                                let offset = all.vertices.len() as u32;
+                                all.vertices.extend(mesh.vertices);
+                                all.indices.extend(mesh.indices.iter().map(|i| i + offset));
+                                all
+                            }
+                            None => mesh,
+                        });
                     }
                 }
             }
         }
 
-        None
+        merged
     }
 
     pub fn resolve_mesh_item(&self, item_id: u64, transform: &Transform3D) -> Option<Mesh> {
         let item_entity = self.registry.get_raw(item_id)?;
-        match item_entity.class.as_str() {
+        if let Some(mesh) = self.resolve_extended_item(item_entity, transform) {
+            return Some(mesh);
+        }
+        let mesh = match item_entity.class.as_str() {
             "IFCTRIANGULATEDFACESET" => self.resolve_triangulated_face_set(item_entity, transform),
             "IFCEXTRUDEDAREASOLID" => self.resolve_extruded_area_solid(item_entity, transform),
             "IFCSHELLBASEDSURFACEMODEL" => {
@@ -159,7 +189,21 @@ impl<'a> MeshResolver<'a> {
             "IFCCLOSEDSHELL" | "IFCOPENSHELL" => self.resolve_shell(item_id, transform),
             "IFCBOOLEANRESULT" => self.resolve_boolean_result(item_entity, transform),
             _ => None,
+        };
+        if mesh.is_none() {
+            *self
+                .skipped
+                .borrow_mut()
+                .entry(item_entity.class.clone())
+                .or_default() += 1;
         }
+        mesh
+    }
+
+    /// Items only the `ifc-geometry` backend tessellates (see `tessellate`).
+    #[cfg(not(feature = "ifc-geometry"))]
+    fn resolve_extended_item(&self, _entity: &RawEntity, _transform: &Transform3D) -> Option<Mesh> {
+        None
     }
 
     fn resolve_boolean_result(&self, entity: &RawEntity, transform: &Transform3D) -> Option<Mesh> {
@@ -323,7 +367,11 @@ impl<'a> MeshResolver<'a> {
         Some(Mesh { vertices, indices })
     }
 
-    fn resolve_point_list_3d(&self, list_id: u64, transform: &Transform3D) -> Option<Vec<Point3D>> {
+    pub(super) fn resolve_point_list_3d(
+        &self,
+        list_id: u64,
+        transform: &Transform3D,
+    ) -> Option<Vec<Point3D>> {
         let list_entity = self.registry.get_raw(list_id)?;
         if list_entity.class != "IFCCARTESIANPOINTLIST3D" {
             return None;
@@ -8,7 +8,12 @@ pub mod lexer;
 pub mod mesh;
 pub mod registry;
 pub mod resolver;
+pub mod schema;
+#[cfg(feature = "ifc-geometry")]
+pub mod tessellate;
+pub mod topology;
 
 pub use lexer::StepLexer;
 pub use registry::EntityRegistry;
 pub use resolver::IfcResolver;
+pub use schema::IfcSchema;
@@ -7,13 +7,16 @@ use super::geometry::GeometryResolver;
 use super::lexer::{Param, RawEntity};
 use super::mesh::MeshResolver;
 use super::registry::EntityRegistry;
+use super::schema::{self, ClassKind, IfcSchema, SPATIAL_ZONE_CLASS};
+use super::topology::{self, SYSTEM_PROPERTY};
 use crate::core::domain::ArxAddress;
+use crate::core::vendor::{VendorExtensions, VendorProperty};
 use crate::core::{
     Building, Dimensions, Equipment, EquipmentType, Floor, Position, Room, RoomType, Wing,
 };
 use crate::ifc::mapping::{
-    apply_identity_on_import, apply_lidar_on_import, dimensions_from_mesh_aabb, mesh_to_local,
-    normalize_imported_properties, position_from_origin, spatial_from_position_dims,
+    apply_identity_on_import, apply_lidar_on_import, dimensions_from_mesh_aabb, is_arx_pset,
+    mesh_to_local, normalize_imported_properties, position_from_origin, spatial_from_position_dims,
     wing_name_from_properties, FidelityLevel, LossReport, MappingWarning, COORD_BUILDING_LOCAL,
     PROP_ARX_WING,
 };
@@ -29,6 +32,10 @@ pub struct IfcResolver<'a> {
     city: String,
     resolved_rooms: std::collections::HashSet<u64>,
     warnings: Vec<MappingWarning>,
+    // Schema from the file header, when known
+    schema: Option<IfcSchema>,
+    // Representation items no mesh came from, with counts
+    skipped_geometry: std::collections::BTreeMap<String, usize>,
 }
 
 impl<'a> IfcResolver<'a> {
@@ -41,9 +48,17 @@ impl<'a> IfcResolver<'a> {
             city: "Main".to_string(),
             resolved_rooms: std::collections::HashSet::new(),
             warnings: Vec::new(),
+            schema: None,
+            skipped_geometry: Default::default(),
         }
     }
 
+    /// Set the schema named in the file header.
+    pub fn with_schema(mut self, schema: Option<IfcSchema>) -> Self {
+        self.schema = schema;
+        self
+    }
+
     /// Set building metadata for ArxAddress generation.
     pub fn with_metadata(mut self, country: &str, state: &str, city: &str) -> Self {
         self.country = country.to_string();
@@ -57,6 +72,16 @@ impl<'a> IfcResolver<'a> {
         let mut building = Building::default();
         let mut equipment_list = Vec::new();
 
+        if let Some(schema) = self.schema.as_ref().filter(|s| !s.is_supported()) {
+            self.warnings.push(MappingWarning::new(
+                "unsupported_schema",
+                format!(
+                    "IFC schema {} is not IFC2X3, IFC4, or IFC4X3; entities are read by name and may map incompletely",
+                    schema
+                ),
+            ));
+        }
+
         // 1. Find the root Project entity
         let project_id = self
             .find_root_entity("IFCPROJECT")
@@ -180,6 +205,7 @@ impl<'a> IfcResolver<'a> {
 
         // Honesty: product classes present in STEP but never mapped into Building domain
         self.append_unmapped_product_warnings();
+        self.append_geometry_fallback_warning();
 
         let mut report = LossReport::new(FidelityLevel::L2);
         report.warnings.append(&mut self.warnings);
@@ -191,98 +217,90 @@ impl<'a> IfcResolver<'a> {
     /// Pilot honesty (R2 / LossReport P0): do not claim "Warnings: none" when walls,
     /// slabs, doors, etc. are silently dropped. Spaces and MEP equipment classes
     /// that `resolve_equipment_under` already walks are **not** listed here.
+    /// Classes outside the schema catalog are counted under `unknown_entities`.
     fn append_unmapped_product_warnings(&mut self) {
         let stats = self.registry.get_stats();
-        let mut parts: Vec<String> = Vec::new();
-        let mut total = 0usize;
+        let mut products: (usize, Vec<String>) = (0, Vec::new());
+        let mut unknown: (usize, Vec<String>) = (0, Vec::new());
 
         // Sort keys to maintain deterministic warnings formatting
         let mut classes: Vec<&String> = stats.class_counts.keys().collect();
         classes.sort();
 
         for class in classes {
-            if Self::is_ignored_class(class) {
-                continue;
-            }
+            let bucket = match schema::classify(class) {
+                ClassKind::Product => &mut products,
+                ClassKind::Unknown => &mut unknown,
+                ClassKind::Imported | ClassKind::Resource => continue,
+            };
             let n = stats.class_counts.get(class).copied().unwrap_or(0);
             if n > 0 {
-                total += n;
-                parts.push(format!("{}×{}", class, n));
+                bucket.0 += n;
+                bucket.1.push(format!("{}×{}", class, n));
             }
         }
 
-        if total > 0 {
+        if products.0 > 0 {
             self.warnings.push(MappingWarning::new(
                 "unmapped_products",
                 format!(
                     "{} product entity(ies) present in IFC but not imported into Arx domain ({})",
-                    total,
-                    parts.join(", ")
+                    products.0,
+                    products.1.join(", ")
+                ),
+            ));
+        }
+        if unknown.0 > 0 {
+            let schema = self
+                .schema
+                .as_ref()
+                .map(|s| format!(" {}", s))
+                .unwrap_or_default();
+            self.warnings.push(MappingWarning::new(
+                "unknown_entities",
+                format!(
+                    "{} entity(ies) of types unknown to the{} reader were skipped ({})",
+                    unknown.0,
+                    schema,
+                    unknown.1.join(", ")
                 ),
             ));
         }
     }
 
-fn is_ignored_class(class: &str) -> bool {
-    if class.starts_with("IFCREL") {
-        return true;
-    }
-    if class.starts_with("IFCMATERIAL") {
-        return true;
-    }
-    if class.starts_with("IFCUNIT") {
-        return true;
-    }
-    if class.starts_with("IFCCARTESIAN") {
-        return true;
-    }
-    if class.starts_with("IFCMAPPED") {
-        return true;
-    }
-    if class.ends_with("TYPE") {
-        return true;
-    }
-    if class.contains("REPRESENTATION") {
-        return true;
-    }
-    if class.contains("PLACEMENT") {
-        return true;
-    }
-    if class.contains("PROPERTY") {
-        return true;
-    }
-    if class.contains("GEOMETRIC") {
-        return true;
+    fn note_skipped_geometry(&mut self, skipped: std::collections::BTreeMap<String, usize>) {
+        for (class, n) in skipped {
+            *self.skipped_geometry.entry(class).or_default() += n;
+        }
     }
 
-    const IGNORED: &[&str] = &[
-        // Mapped spatial
-        "IFCPROJECT", "IFCSITE", "IFCBUILDING", "IFCBUILDINGSTOREY", "IFCSPACE", "IFCZONE", "IFCGROUP",
-        // Mapped equipment
-        "IFCFLOWTERMINAL", "IFCFLOWCONTROLLER", "IFCSENSOR", "IFCAIRTERMINAL", "IFCLIGHTFIXTURE",
-        "IFCBOILER", "IFCCHILLER", "IFCFAN", "IFCPUMP", "IFCVALVE", "IFCLAMP", "IFCOUTLET",
-        "IFCSWITCHINGDEVICE", "IFCFIREALARM", "IFCFIRESUPRESSION", "IFCAUDIOVISUALAPPLIANCE",
-        "IFCFURNITURE", "IFCDISTRIBUTIONELEMENT", "IFCCOMMUNICATIONSAPPLIANCE",
-        // Geometric representation helpers
-        "IFCCARTESIANPOINT", "IFCDIRECTION", "IFCVECTOR", "IFCAXIS2PLACEMENT2D", "IFCAXIS2PLACEMENT3D",
-        "IFCPRODUCTDEFINITIONSHAPE", "IFCTRIANGULATEDFACESET", "IFCCARTESIANPOINTLIST3D",
-        "IFCPOLYLOOP", "IFCFACEOUTERBOUND", "IFCFACE", "IFCPOLYGONALBOUNDEDHALFSPACE",
-        "IFCSHELLBASEDSURFACEMODEL", "IFCCLOSEDSHELL", "IFCOPTIONAL", "IFCCOLOURRGB",
-        "IFCSURFACESTYLE", "IFCSURFACESTYLESHADING", "IFCPRESENTATIONSTYLEASSIGNMENT",
-        "IFCSTYLEDITEM", "IFCMAPCONVERSION", "IFCPROJECTEDCRS", "IFCEXTRUDEDAREASOLID",
-        "IFCARBITRARYCLOSEDPROFILEDEF", "IFCPLANE", "IFCPOLYLINE", "IFCTRANSFORMATION",
-        "IFCGRID", "IFCGRIDAXIS", "IFCGRIDCONVERGENCE", "IFCDIMENSIONALEXPONENTS",
-        // Property, Unit, and Metadata helpers
-        "IFCSIUNIT", "IFCPERSON", "IFCORGANIZATION", "IFCPERSONANDORGANIZATION", "IFCAPPLICATION",
-        "IFCOWNERHISTORY", "IFCPOSTALADDRESS", "IFCTELECOMADDRESS", "IFCCOMPLEXPROPERTY",
-        "IFCREFERENCE", "IFCLABEL", "IFCIDENTIFIER", "IFCMEASUREWITHUNIT", "IFCMONETARYUNIT",
-        "IFCVALUETYPE", "IFCCURRENCYMEASURE", "IFCTYPEPRODUCT", "IFCTYPEOBJECT", "IFCSYSTEM",
-        "IFCPRESENTATIONLAYERASSIGNMENT", "IFCSHAPEASPECT", "IFCCONVERSIONBASEDUNIT",
-        "IFCDERIVEDUNIT", "IFCDERIVEDUNITELEMENT", "IFCPRODUCTREPRESENTATION"
-    ];
-
-    IGNORED.contains(&class)
-}
+    /// Report representation items that produced no mesh; those rooms and
+    /// equipment fall back to bounding boxes.
+    fn append_geometry_fallback_warning(&mut self) {
+        let total: usize = self.skipped_geometry.values().sum();
+        if total == 0 {
+            return;
+        }
+        let parts: Vec<String> = self
+            .skipped_geometry
+            .iter()
+            .map(|(class, n)| format!("{}×{}", class, n))
+            .collect();
+        let hint = if cfg!(feature = "ifc-geometry") {
+            ""
+        } else {
+            "; build with --features ifc-geometry to tessellate mapped items, polygonal face sets, and swept solids"
+        };
+        self.warnings.push(MappingWarning::new(
+            "geometry_fallback",
+            format!(
+                "{} representation item(s) could not be tessellated ({}); bounding boxes used instead{}",
+                total,
+                parts.join(", "),
+                hint
+            ),
+        ));
+    }
 
     // --- Traversal Helpers ---
 
@@ -471,7 +489,11 @@ fn is_ignored_class(class: &str) -> bool {
         let raw = self.registry.get_raw(id).unwrap();
 
         // Resolve Properties
-        self.resolve_properties(id, &mut room.properties);
+        self.resolve_properties_with_vendor(
+            id,
+            &mut room.properties,
+            Some(&mut room.vendor_extensions),
+        );
         apply_identity_on_import(
             &mut room.id,
             &mut room.ifc_global_id,
@@ -525,6 +547,9 @@ fn is_ignored_class(class: &str) -> bool {
             }
         }
 
+        let skipped = mesh_resolver.skipped_items();
+        self.note_skipped_geometry(skipped);
+
         let dimensions = dims_opt.unwrap_or_else(|| room.spatial_properties.dimensions.clone());
         let mut spatial = spatial_from_position_dims(position, dimensions);
         spatial.mesh = mesh_local;
@@ -631,32 +656,13 @@ fn is_ignored_class(class: &str) -> bool {
     fn resolve_equipment_under(&mut self, _building_id: u64) -> Result<Vec<Equipment>> {
         let mut equipment_list = Vec::new();
 
-        let classes = [
-            "IFCFLOWTERMINAL",
-            "IFCFLOWCONTROLLER",
-            "IFCSENSOR",
-            "IFCAIRTERMINAL",
-            "IFCLIGHTFIXTURE",
-            "IFCBOILER",
-            "IFCCHILLER",
-            "IFCFAN",
-            "IFCPUMP",
-            "IFCVALVE",
-            "IFCLAMP",
-            "IFCOUTLET",
-            "IFCSWITCHINGDEVICE",
-            "IFCFIREALARM",
-            "IFCFIRESUPRESSION",
-            "IFCAUDIOVISUALAPPLIANCE",
-            "IFCFURNITURE",
-            "IFCDISTRIBUTIONELEMENT",
-            "IFCCOMMUNICATIONSAPPLIANCE",
-        ];
+        let mut steps = Vec::new();
+        let systems = topology::system_names(self.registry);
 
         let geom_resolver = GeometryResolver::new(self.registry);
         let mesh_resolver = MeshResolver::new(self.registry, &geom_resolver);
 
-        for &class in &classes {
+        for &class in schema::EQUIPMENT_CLASSES {
             for &id in self.registry.get_by_class(class) {
                 let eq_data = if let Some(raw) = self.registry.get_raw(id) {
                     let name = self
@@ -688,7 +694,11 @@ fn is_ignored_class(class: &str) -> bool {
                     }
 
                     // Resolve Properties + identity
-                    self.resolve_properties(id, &mut eq.properties);
+                    self.resolve_properties_with_vendor(
+                        id,
+                        &mut eq.properties,
+                        Some(&mut eq.vendor_extensions),
+                    );
                     let global_id = self
                         .registry
                         .get_raw(id)
@@ -701,6 +711,10 @@ fn is_ignored_class(class: &str) -> bool {
                     );
                     apply_lidar_on_import(&mut eq.lidar_enrichment, &mut eq.properties);
                     normalize_imported_properties(&mut eq.properties);
+                    if let Some(names) = systems.get(&id).filter(|n| !n.is_empty()) {
+                        eq.properties
+                            .insert(SYSTEM_PROPERTY.to_string(), names.join(", "));
+                    }
 
                     // Placement + optional body (L2)
                     if let Some(raw) = self.registry.get_raw(id) {
@@ -732,10 +746,26 @@ fn is_ignored_class(class: &str) -> bool {
                         }
                     }
 
+                    steps.push(id);
                     equipment_list.push(eq);
                 }
             }
         }
+        let skipped = mesh_resolver.skipped_items();
+        self.note_skipped_geometry(skipped);
+
+        // Distribution system topology between the imported equipment
+        let ids: HashMap<u64, String> = steps
+            .iter()
+            .zip(&equipment_list)
+            .map(|(step, eq)| (*step, eq.id.clone()))
+            .collect();
+        let mut connections = topology::equipment_connections(self.registry, &ids);
+        for (step, eq) in steps.iter().zip(equipment_list.iter_mut()) {
+            if let Some(list) = connections.remove(step) {
+                eq.connections = list;
+            }
+        }
 
         Ok(equipment_list)
     }
@@ -749,19 +779,51 @@ fn is_ignored_class(class: &str) -> bool {
             | "IFCBOILER"
             | "IFCCHILLER"
             | "IFCFAN"
-            | "IFCPUMP" => EquipmentType::HVAC,
+            | "IFCPUMP"
+            | "IFCAIRTERMINAL"
+            | "IFCAIRTERMINALBOX"
+            | "IFCAIRTOAIRHEATRECOVERY"
+            | "IFCBURNER"
+            | "IFCCOIL"
+            | "IFCCOMPRESSOR"
+            | "IFCCONDENSER"
+            | "IFCCOOLEDBEAM"
+            | "IFCCOOLINGTOWER"
+            | "IFCDAMPER"
+            | "IFCDUCTSILENCER"
+            | "IFCEVAPORATIVECOOLER"
+            | "IFCEVAPORATOR"
+            | "IFCFILTER"
+            | "IFCHEATEXCHANGER"
+            | "IFCHUMIDIFIER"
+            | "IFCSPACEHEATER"
+            | "IFCUNITARYEQUIPMENT" => EquipmentType::HVAC,
             "IFCCABLESEGMENT"
             | "IFCCABLEFITTING"
             | "IFCSWITCHINGDEVICE"
             | "IFCPROTECTIVEDEVICE"
-            | "IFCOUTLET" => EquipmentType::Electrical,
+            | "IFCOUTLET"
+            | "IFCELECTRICAPPLIANCE"
+            | "IFCELECTRICDISTRIBUTIONBOARD"
+            | "IFCELECTRICFLOWSTORAGEDEVICE"
+            | "IFCELECTRICGENERATOR"
+            | "IFCELECTRICMOTOR"
+            | "IFCELECTRICTIMECONTROL"
+            | "IFCSOLARDEVICE"
+            | "IFCTRANSFORMER" => EquipmentType::Electrical,
             "IFCLAMP" | "IFCLIGHTFIXTURE" => EquipmentType::Other("Lighting".to_string()),
-            "IFCVALVE" | "IFCPIPESEGMENT" | "IFCPIPEFITTING" | "IFCSANITARYTERMINAL" => {
-                EquipmentType::Plumbing
-            }
+            "IFCVALVE"
+            | "IFCPIPESEGMENT"
+            | "IFCPIPEFITTING"
+            | "IFCSANITARYTERMINAL"
+            | "IFCFLOWMETER"
+            | "IFCINTERCEPTOR"
+            | "IFCTANK" => EquipmentType::Plumbing,
             "IFCAUDIOVISUALAPPLIANCE" => EquipmentType::AV,
             "IFCFURNITURE" => EquipmentType::Furniture,
-            "IFCFIREALARM" | "IFCFIRESUPRESSION" | "IFCALARM" => EquipmentType::Safety,
+            "IFCFIREALARM" | "IFCFIRESUPRESSION" | "IFCFIRESUPPRESSIONTERMINAL" | "IFCALARM" => {
+                EquipmentType::Safety
+            }
             "IFCCOMMUNICATIONSAPPLIANCE" => EquipmentType::Network,
             _ => EquipmentType::Other(class.to_string()),
         }
@@ -773,6 +835,17 @@ fn is_ignored_class(class: &str) -> bool {
         &self,
         entity_id: u64,
         out_props: &mut std::collections::HashMap<String, String>,
+    ) {
+        self.resolve_properties_with_vendor(entity_id, out_props, None);
+    }
+
+    /// Like `resolve_properties`, but property sets outside the Arx family go
+    /// to `vendor` (typed, grouped by set) instead of prefixed property keys.
+    fn resolve_properties_with_vendor(
+        &self,
+        entity_id: u64,
+        out_props: &mut std::collections::HashMap<String, String>,
+        mut vendor: Option<&mut VendorExtensions>,
     ) {
         // Properties are linked via IFCRELDEFINESBYPROPERTIES
         // RelatedObjects: Param 4 (List of references)
@@ -787,7 +860,7 @@ fn is_ignored_class(class: &str) -> bool {
 
                     if is_related {
                         if let Some(Param::Reference(pset_id)) = rel_raw.params.get(5) {
-                            self.extract_property_set(*pset_id, out_props);
+                            self.extract_property_set(*pset_id, out_props, vendor.as_deref_mut());
                         }
                     }
                 }
@@ -799,6 +872,7 @@ fn is_ignored_class(class: &str) -> bool {
         &self,
         pset_id: u64,
         out_props: &mut std::collections::HashMap<String, String>,
+        vendor: Option<&mut VendorExtensions>,
     ) {
         if let Some(pset_raw) = self.registry.get_raw(pset_id) {
             if pset_raw.class == "IFCPROPERTYSET" {
@@ -806,6 +880,24 @@ fn is_ignored_class(class: &str) -> bool {
                     .extract_string_param(pset_raw, 2)
                     .unwrap_or_else(|| "Pset_Unknown".to_string());
 
+                if let Some(vendor) = vendor.filter(|_| !is_arx_pset(&pset_name)) {
+                    let mut set = std::collections::BTreeMap::new();
+                    if let Some(Param::List(properties)) = pset_raw.params.get(4) {
+                        for prop_param in properties {
+                            if let Param::Reference(prop_id) = prop_param {
+                                if let Some((name, value)) = self.extract_vendor_property(*prop_id)
+                                {
+                                    set.insert(name, value);
+                                }
+                            }
+                        }
+                    }
+                    if !set.is_empty() {
+                        vendor.entry(pset_name).or_default().extend(set);
+                    }
+                    return;
+                }
+
                 // HasProperties: Param 4 (List of references)
                 if let Some(Param::List(properties)) = pset_raw.params.get(4) {
                     for prop_param in properties {
@@ -838,6 +930,20 @@ fn is_ignored_class(class: &str) -> bool {
         }
     }
 
+    fn extract_vendor_property(&self, prop_id: u64) -> Option<(String, VendorProperty)> {
+        let prop_raw = self.registry.get_raw(prop_id)?;
+        if prop_raw.class != "IFCPROPERTYSINGLEVALUE" {
+            return None;
+        }
+        let name = self.extract_string_param(prop_raw, 0)?;
+        let ifc_type = match prop_raw.params.get(2) {
+            Some(Param::Typed(type_name, _)) => Some(type_name.to_uppercase()),
+            _ => None,
+        };
+        let value = self.extract_value_param(&prop_raw.params, 2)?;
+        Some((name, VendorProperty { value, ifc_type }))
+    }
+
     fn extract_value_param(&self, params: &[Param], index: usize) -> Option<String> {
         match params.get(index)? {
             Param::String(s) => Some(s.clone()),
@@ -1008,6 +1114,45 @@ fn is_ignored_class(class: &str) -> bool {
                 }
             }
         }
+        self.find_spatial_zone_of(entity_id)
+    }
+
+    /// Name of the IFC4 `IfcSpatialZone` referencing or aggregating `entity_id`.
+    fn find_spatial_zone_of(&self, entity_id: u64) -> Option<String> {
+        // IFCRELREFERENCEDINSPATIALSTRUCTURE: RelatedElements @4, RelatingStructure @5
+        // IFCRELAGGREGATES: RelatingObject @4, RelatedObjects @5
+        let links = [
+            ("IFCRELREFERENCEDINSPATIALSTRUCTURE", 4, 5),
+            ("IFCRELAGGREGATES", 5, 4),
+        ];
+        for (class, members_at, zone_at) in links {
+            for &rel_id in self.registry.get_by_class(class) {
+                let Some(rel) = self.registry.get_raw(rel_id) else {
+                    continue;
+                };
+                let Some(Param::Reference(zone_id)) = rel.params.get(zone_at) else {
+                    continue;
+                };
+                let contains_entity = match rel.params.get(members_at) {
+                    Some(Param::List(members)) => members
+                        .iter()
+                        .any(|item| matches!(item, Param::Reference(id) if *id == entity_id)),
+                    _ => false,
+                };
+                if !contains_entity {
+                    continue;
+                }
+                if let Some(zone) = self.registry.get_raw(*zone_id) {
+                    if zone.class == SPATIAL_ZONE_CLASS {
+                        if let Some(name) = self.extract_entity_name(zone) {
+                            if !name.trim().is_empty() {
+                                return Some(name);
+                            }
+                        }
+                    }
+                }
+            }
+        }
         None
     }
 
@@ -1085,4 +1230,107 @@ mod tests {
             resolver.warnings
         );
     }
+
+    #[test]
+    fn distribution_system_becomes_equipment_connections() {
+        use crate::core::systems::{Connection, Relation};
+
+        let s = |v: &str| Param::String(v.to_string());
+        let r = Param::Reference;
+        let e = |v: &str| Param::Enum(v.to_string());
+        let list = |ids: &[u64]| Param::List(ids.iter().map(|id| Param::Reference(*id)).collect());
+        let port = |direction: &str| {
+            let mut params = vec![Param::Null; 7];
+            params.push(e(direction));
+            params
+        };
+        let rel = |a: Param, b: Param| vec![s("rel"), Param::Null, Param::Null, Param::Null, a, b];
+        let mut registry = EntityRegistry::new();
+        for (id, class, params) in [
+            (
+                10u64,
+                "IFCUNITARYEQUIPMENT",
+                vec![s("g10"), Param::Null, s("AHU-1")],
+            ),
+            (11, "IFCDUCTSEGMENT", vec![s("g11"), Param::Null, s("Duct")]),
+            (
+                12,
+                "IFCAIRTERMINALBOX",
+                vec![s("g12"), Param::Null, s("VAV-1")],
+            ),
+            (20, "IFCDISTRIBUTIONPORT", port("SOURCE")),
+            (21, "IFCDISTRIBUTIONPORT", port("SINK")),
+            (22, "IFCDISTRIBUTIONPORT", port("SOURCE")),
+            (23, "IFCDISTRIBUTIONPORT", port("SINK")),
+            (30, "IFCRELNESTS", rel(r(10), list(&[20]))),
+            (31, "IFCRELNESTS", rel(r(11), list(&[21, 22]))),
+            (32, "IFCRELNESTS", rel(r(12), list(&[23]))),
+            (33, "IFCRELCONNECTSPORTS", rel(r(20), r(21))),
+            // Written sink-first; the port directions decide the edge
+            (34, "IFCRELCONNECTSPORTS", rel(r(23), r(22))),
+            (
+                40,
+                "IFCDISTRIBUTIONSYSTEM",
+                vec![
+                    s("g40"),
+                    Param::Null,
+                    s("Supply Air"),
+                    Param::Null,
+                    Param::Null,
+                    Param::Null,
+                    e("VENTILATION"),
+                ],
+            ),
+            (
+                41,
+                "IFCRELASSIGNSTOGROUP",
+                vec![
+                    s("rel"),
+                    Param::Null,
+                    Param::Null,
+                    Param::Null,
+                    list(&[10, 12]),
+                    Param::Null,
+                    r(40),
+                ],
+            ),
+            (50, "IFCWIDGET", vec![]),
+        ] {
+            registry.register(RawEntity {
+                id,
+                class: class.to_string(),
+                params,
+            });
+        }
+
+        let mut resolver = IfcResolver::new(&mut registry);
+        let equipment = resolver.resolve_equipment_under(0).unwrap();
+        let find = |name: &str| equipment.iter().find(|eq| eq.name == name).unwrap();
+        let (ahu, vav) = (find("AHU-1"), find("VAV-1"));
+        assert_eq!(
+            ahu.connections,
+            [Connection {
+                relation: Relation::Feeds,
+                to: vav.id.clone(),
+            }]
+        );
+        assert!(vav.connections.is_empty());
+        assert_eq!(
+            vav.properties.get(SYSTEM_PROPERTY).map(String::as_str),
+            Some("Supply Air")
+        );
+
+        resolver.append_unmapped_product_warnings();
+        let message = |code: &str| {
+            resolver
+                .warnings
+                .iter()
+                .find(|w| w.code == code)
+                .map(|w| w.message.clone())
+                .unwrap_or_default()
+        };
+        assert!(message("unknown_entities").contains("IFCWIDGET×1"));
+        assert!(message("unmapped_products").contains("IFCDUCTSEGMENT×1"));
+        assert!(!message("unmapped_products").contains("PORT"));
+    }
 }
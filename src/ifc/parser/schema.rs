@@ -0,0 +1,414 @@
+//! IFC schema versions and the entity classes the resolver knows.
+//!
+//! The resolver reads IFC2X3, IFC4, and IFC4X3 files. Every class falls in
+//! one of four groups: classes it imports (spatial structure, equipment,
+//! systems), products it recognizes but does not import (walls, ducts, ...),
+//! resource entities (geometry, units, relationships), and unknown classes,
+//! which the import reports with counts.
+
+use std::fmt;
+
+/// Schema named in the file's `FILE_SCHEMA` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfcSchema {
+    Ifc2x3,
+    Ifc4,
+    Ifc4x3,
+    /// Any other schema name, as written
+    Other(String),
+}
+
+impl IfcSchema {
+    /// The schema in `FILE_SCHEMA(('…'))`, if the header names one.
+    pub fn detect(content: &str) -> Option<Self> {
+        let header = &content[..content.find("DATA;").unwrap_or(content.len())];
+        let rest = &header[header.find("FILE_SCHEMA")?..];
+        let open = rest.find('\'')? + 1;
+        let close = open + rest[open..].find('\'')?;
+        Some(Self::from_name(&rest[open..close]))
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        let upper = name.trim().to_ascii_uppercase();
+        if upper.starts_with("IFC4X3") {
+            IfcSchema::Ifc4x3
+        } else if upper.starts_with("IFC4") {
+            IfcSchema::Ifc4
+        } else if upper.starts_with("IFC2X3") {
+            IfcSchema::Ifc2x3
+        } else {
+            IfcSchema::Other(name.trim().to_string())
+        }
+    }
+
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, IfcSchema::Other(_))
+    }
+}
+
+impl fmt::Display for IfcSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IfcSchema::Ifc2x3 => f.write_str("IFC2X3"),
+            IfcSchema::Ifc4 => f.write_str("IFC4"),
+            IfcSchema::Ifc4x3 => f.write_str("IFC4X3"),
+            IfcSchema::Other(name) => f.write_str(name),
+        }
+    }
+}
+
+/// How the resolver treats an entity class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassKind {
+    /// Mapped into the Arx domain
+    Imported,
+    /// A product the resolver recognizes but does not import
+    Product,
+    /// Geometry, units, relationships, and other resource entities
+    Resource,
+    Unknown,
+}
+
+/// Spatial structure and groupings mapped to buildings, floors, rooms, and wings.
+pub const SPATIAL_CLASSES: &[&str] = &[
+    "IFCPROJECT",
+    "IFCSITE",
+    "IFCBUILDING",
+    "IFCBUILDINGSTOREY",
+    "IFCSPACE",
+    "IFCZONE",
+    "IFCGROUP",
+    SPATIAL_ZONE_CLASS,
+];
+
+/// IFC4 overlay zone (thermal, fire, security, ...) grouping spaces.
+pub const SPATIAL_ZONE_CLASS: &str = "IFCSPATIALZONE";
+
+/// Classes imported as equipment (IFC2X3 generic classes and IFC4
+/// distribution element subtypes).
+pub const EQUIPMENT_CLASSES: &[&str] = &[
+    "IFCFLOWTERMINAL",
+    "IFCFLOWCONTROLLER",
+    "IFCSENSOR",
+    "IFCAIRTERMINAL",
+    "IFCLIGHTFIXTURE",
+    "IFCBOILER",
+    "IFCCHILLER",
+    "IFCFAN",
+    "IFCPUMP",
+    "IFCVALVE",
+    "IFCLAMP",
+    "IFCOUTLET",
+    "IFCSWITCHINGDEVICE",
+    "IFCFIREALARM",
+    "IFCFIRESUPRESSION",
+    "IFCAUDIOVISUALAPPLIANCE",
+    "IFCFURNITURE",
+    "IFCDISTRIBUTIONELEMENT",
+    "IFCCOMMUNICATIONSAPPLIANCE",
+    // IFC4 energy conversion, flow, and storage devices
+    "IFCFLOWMOVINGDEVICE",
+    "IFCAIRTERMINALBOX",
+    "IFCAIRTOAIRHEATRECOVERY",
+    "IFCBURNER",
+    "IFCCOIL",
+    "IFCCOMPRESSOR",
+    "IFCCONDENSER",
+    "IFCCOOLEDBEAM",
+    "IFCCOOLINGTOWER",
+    "IFCDAMPER",
+    "IFCDUCTSILENCER",
+    "IFCEVAPORATIVECOOLER",
+    "IFCEVAPORATOR",
+    "IFCFILTER",
+    "IFCHEATEXCHANGER",
+    "IFCHUMIDIFIER",
+    "IFCSPACEHEATER",
+    "IFCUNITARYEQUIPMENT",
+    "IFCELECTRICAPPLIANCE",
+    "IFCELECTRICDISTRIBUTIONBOARD",
+    "IFCELECTRICFLOWSTORAGEDEVICE",
+    "IFCELECTRICGENERATOR",
+    "IFCELECTRICMOTOR",
+    "IFCELECTRICTIMECONTROL",
+    "IFCPROTECTIVEDEVICE",
+    "IFCSOLARDEVICE",
+    "IFCTRANSFORMER",
+    "IFCFLOWMETER",
+    "IFCINTERCEPTOR",
+    "IFCTANK",
+    "IFCFIRESUPPRESSIONTERMINAL",
+    "IFCMEDICALDEVICE",
+    // IFC4 control elements
+    "IFCACTUATOR",
+    "IFCALARM",
+    "IFCCONTROLLER",
+    "IFCFLOWINSTRUMENT",
+    "IFCUNITARYCONTROLELEMENT",
+];
+
+/// Groups whose members become equipment topology.
+pub const SYSTEM_CLASSES: &[&str] = &[
+    "IFCSYSTEM",
+    "IFCDISTRIBUTIONSYSTEM",
+    "IFCDISTRIBUTIONCIRCUIT",
+    "IFCBUILDINGSYSTEM",
+    "IFCBUILTSYSTEM",
+];
+
+/// Products recognized but not imported: built elements (`IfcBuildingElement`
+/// before IFC4X3, `IfcBuiltElement` since), distribution runs and fixtures,
+/// features and assemblies, and IFC4X3 infrastructure facilities.
+pub const PRODUCT_CLASSES: &[&str] = &[
+    "IFCBUILTELEMENT",
+    "IFCBUILDINGELEMENT",
+    "IFCBUILDINGELEMENTPROXY",
+    "IFCBEAM",
+    "IFCBEAMSTANDARDCASE",
+    "IFCBEARING",
+    "IFCCHIMNEY",
+    "IFCCOLUMN",
+    "IFCCOLUMNSTANDARDCASE",
+    "IFCCOURSE",
+    "IFCCOVERING",
+    "IFCCURTAINWALL",
+    "IFCDEEPFOUNDATION",
+    "IFCCAISSONFOUNDATION",
+    "IFCPILE",
+    "IFCDOOR",
+    "IFCDOORSTANDARDCASE",
+    "IFCEARTHWORKSELEMENT",
+    "IFCEARTHWORKSFILL",
+    "IFCREINFORCEDSOIL",
+    "IFCFOOTING",
+    "IFCKERB",
+    "IFCMEMBER",
+    "IFCMEMBERSTANDARDCASE",
+    "IFCMOORINGDEVICE",
+    "IFCNAVIGATIONELEMENT",
+    "IFCPAVEMENT",
+    "IFCPLATE",
+    "IFCPLATESTANDARDCASE",
+    "IFCRAIL",
+    "IFCRAILING",
+    "IFCRAMP",
+    "IFCRAMPFLIGHT",
+    "IFCROOF",
+    "IFCSHADINGDEVICE",
+    "IFCSLAB",
+    "IFCSLABSTANDARDCASE",
+    "IFCSLABELEMENTEDCASE",
+    "IFCSTAIR",
+    "IFCSTAIRFLIGHT",
+    "IFCTRACKELEMENT",
+    "IFCWALL",
+    "IFCWALLSTANDARDCASE",
+    "IFCWALLELEMENTEDCASE",
+    "IFCWINDOW",
+    "IFCWINDOWSTANDARDCASE",
+    // Distribution runs, fittings, and plumbing fixtures
+    "IFCFLOWSEGMENT",
+    "IFCFLOWFITTING",
+    "IFCCABLECARRIERFITTING",
+    "IFCCABLECARRIERSEGMENT",
+    "IFCCABLEFITTING",
+    "IFCCABLESEGMENT",
+    "IFCDUCTFITTING",
+    "IFCDUCTSEGMENT",
+    "IFCPIPEFITTING",
+    "IFCPIPESEGMENT",
+    "IFCJUNCTIONBOX",
+    "IFCDISTRIBUTIONCHAMBERELEMENT",
+    "IFCSANITARYTERMINAL",
+    "IFCSTACKTERMINAL",
+    "IFCWASTETERMINAL",
+    // Features, assemblies, and other products
+    "IFCOPENINGELEMENT",
+    "IFCOPENINGSTANDARDCASE",
+    "IFCVOIDINGFEATURE",
+    "IFCSURFACEFEATURE",
+    "IFCPROJECTIONELEMENT",
+    "IFCELEMENTASSEMBLY",
+    "IFCBUILDINGELEMENTPART",
+    "IFCDISCRETEACCESSORY",
+    "IFCFASTENER",
+    "IFCMECHANICALFASTENER",
+    "IFCREINFORCINGBAR",
+    "IFCREINFORCINGMESH",
+    "IFCTENDON",
+    "IFCTENDONANCHOR",
+    "IFCTRANSPORTELEMENT",
+    "IFCVIRTUALELEMENT",
+    "IFCANNOTATION",
+    "IFCGEOGRAPHICELEMENT",
+    "IFCCIVILELEMENT",
+    "IFCSIGN",
+    "IFCSIGNAL",
+    "IFCIMPACTPROTECTIONDEVICE",
+    // IFC4X3 facilities outside the building structure
+    "IFCFACILITY",
+    "IFCFACILITYPART",
+    "IFCBRIDGE",
+    "IFCBRIDGEPART",
+    "IFCROAD",
+    "IFCROADPART",
+    "IFCRAILWAY",
+    "IFCRAILWAYPART",
+    "IFCMARINEFACILITY",
+    "IFCMARINEPART",
+    "IFCALIGNMENT",
+    "IFCEXTERNALSPATIALELEMENT",
+];
+
+/// Resource entities matched by exact name (see [`classify`] for the
+/// prefix and substring rules).
+const RESOURCE_CLASSES: &[&str] = &[
+    // Ports are read for system topology
+    "IFCDISTRIBUTIONPORT",
+    "IFCPORT",
+    // Geometric representation helpers
+    "IFCCARTESIANPOINT",
+    "IFCDIRECTION",
+    "IFCVECTOR",
+    "IFCAXIS2PLACEMENT2D",
+    "IFCAXIS2PLACEMENT3D",
+    "IFCPRODUCTDEFINITIONSHAPE",
+    "IFCTRIANGULATEDFACESET",
+    "IFCPOLYGONALFACESET",
+    "IFCINDEXEDPOLYGONALFACE",
+    "IFCINDEXEDPOLYGONALFACEWITHVOIDS",
+    "IFCCARTESIANPOINTLIST2D",
+    "IFCCARTESIANPOINTLIST3D",
+    "IFCPOLYLOOP",
+    "IFCFACEOUTERBOUND",
+    "IFCFACEBOUND",
+    "IFCFACE",
+    "IFCFACETEDBREP",
+    "IFCCONNECTEDFACESET",
+    "IFCPOLYGONALBOUNDEDHALFSPACE",
+    "IFCHALFSPACESOLID",
+    "IFCBOOLEANRESULT",
+    "IFCBOOLEANCLIPPINGRESULT",
+    "IFCSHELLBASEDSURFACEMODEL",
+    "IFCCLOSEDSHELL",
+    "IFCOPENSHELL",
+    "IFCOPTIONAL",
+    "IFCCOLOURRGB",
+    "IFCCOLOURRGBLIST",
+    "IFCINDEXEDCOLOURMAP",
+    "IFCSURFACESTYLE",
+    "IFCSURFACESTYLESHADING",
+    "IFCSURFACESTYLERENDERING",
+    "IFCPRESENTATIONSTYLEASSIGNMENT",
+    "IFCSTYLEDITEM",
+    "IFCMAPCONVERSION",
+    "IFCPROJECTEDCRS",
+    "IFCEXTRUDEDAREASOLID",
+    "IFCSWEPTDISKSOLID",
+    "IFCARBITRARYCLOSEDPROFILEDEF",
+    "IFCARBITRARYPROFILEDEFWITHVOIDS",
+    "IFCRECTANGLEPROFILEDEF",
+    "IFCCIRCLEPROFILEDEF",
+    "IFCPLANE",
+    "IFCLINE",
+    "IFCCIRCLE",
+    "IFCPOLYLINE",
+    "IFCINDEXEDPOLYCURVE",
+    "IFCTRIMMEDCURVE",
+    "IFCCOMPOSITECURVE",
+    "IFCCOMPOSITECURVESEGMENT",
+    "IFCTRANSFORMATION",
+    "IFCGRID",
+    "IFCGRIDAXIS",
+    "IFCGRIDCONVERGENCE",
+    "IFCDIMENSIONALEXPONENTS",
+    // Property, unit, and metadata helpers
+    "IFCSIUNIT",
+    "IFCPERSON",
+    "IFCORGANIZATION",
+    "IFCPERSONANDORGANIZATION",
+    "IFCACTORROLE",
+    "IFCAPPLICATION",
+    "IFCOWNERHISTORY",
+    "IFCPOSTALADDRESS",
+    "IFCTELECOMADDRESS",
+    "IFCCOMPLEXPROPERTY",
+    "IFCELEMENTQUANTITY",
+    "IFCREFERENCE",
+    "IFCLABEL",
+    "IFCIDENTIFIER",
+    "IFCMEASUREWITHUNIT",
+    "IFCMONETARYUNIT",
+    "IFCVALUETYPE",
+    "IFCCURRENCYMEASURE",
+    "IFCTYPEPRODUCT",
+    "IFCTYPEOBJECT",
+    "IFCPROJECTLIBRARY",
+    "IFCCLASSIFICATION",
+    "IFCCLASSIFICATIONREFERENCE",
+    "IFCDOCUMENTINFORMATION",
+    "IFCDOCUMENTREFERENCE",
+    "IFCPRESENTATIONLAYERASSIGNMENT",
+    "IFCPRESENTATIONLAYERWITHSTYLE",
+    "IFCSHAPEASPECT",
+    "IFCCONVERSIONBASEDUNIT",
+    "IFCDERIVEDUNIT",
+    "IFCDERIVEDUNITELEMENT",
+    "IFCPRODUCTREPRESENTATION",
+];
+
+fn is_resource_class(class: &str) -> bool {
+    const PREFIXES: &[&str] = &[
+        "IFCREL",
+        "IFCMATERIAL",
+        "IFCUNIT",
+        "IFCCARTESIAN",
+        "IFCMAPPED",
+        "IFCQUANTITY",
+    ];
+    const INFIXES: &[&str] = &["REPRESENTATION", "PLACEMENT", "PROPERTY", "GEOMETRIC"];
+
+    PREFIXES.iter().any(|p| class.starts_with(p))
+        || class.ends_with("TYPE")
+        || INFIXES.iter().any(|s| class.contains(s))
+        || RESOURCE_CLASSES.contains(&class)
+}
+
+/// How the resolver treats `class` (upper-case STEP name).
+pub fn classify(class: &str) -> ClassKind {
+    if SPATIAL_CLASSES.contains(&class)
+        || EQUIPMENT_CLASSES.contains(&class)
+        || SYSTEM_CLASSES.contains(&class)
+    {
+        ClassKind::Imported
+    } else if PRODUCT_CLASSES.contains(&class) {
+        ClassKind::Product
+    } else if is_resource_class(class) {
+        ClassKind::Resource
+    } else {
+        ClassKind::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_schema_and_classifies_ifc4x3_classes() {
+        let header = "ISO-10303-21;\nHEADER;\nFILE_SCHEMA(('IFC4X3_ADD2'));\nENDSEC;\nDATA;\n";
+        assert_eq!(IfcSchema::detect(header), Some(IfcSchema::Ifc4x3));
+        assert_eq!(
+            IfcSchema::detect("HEADER;FILE_SCHEMA(('IFC2X3'));"),
+            Some(IfcSchema::Ifc2x3)
+        );
+        assert!(!IfcSchema::from_name("CIS2").is_supported());
+        assert_eq!(IfcSchema::detect("DATA;\n#1=IFCWALL('FILE_SCHEMA');"), None);
+
+        assert_eq!(classify("IFCDISTRIBUTIONSYSTEM"), ClassKind::Imported);
+        assert_eq!(classify("IFCSPATIALZONE"), ClassKind::Imported);
+        assert_eq!(classify("IFCKERB"), ClassKind::Product);
+        assert_eq!(classify("IFCDUCTSEGMENTTYPE"), ClassKind::Resource);
+        assert_eq!(classify("IFCWIDGET"), ClassKind::Unknown);
+    }
+}
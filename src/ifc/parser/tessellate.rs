@@ -0,0 +1,487 @@
+//! Extended tessellation backend (feature `ifc-geometry`).
+//!
+//! The built-in [`MeshResolver`] covers triangulated face sets, B-reps, and
+//! extrusions of rectangle and polyline profiles (unplaced, along local Z,
+//! with fan-triangulated caps). This backend adds the body items IfcOpenShell
+//! also turns into triangles for typical building models: mapped items (type
+//! geometry placed per instance), polygonal face sets, swept disk solids
+//! (pipes, cables), clipping results, and extrusions of placed rectangle,
+//! circle, hollow-circle, and voided profiles along their extrusion direction
+//! with concave-safe caps. As with IfcOpenShell's default settings, all items
+//! of a representation are merged into one mesh in the product's frame.
+//! Without the feature, products built from these items keep their bounding
+//! boxes.
+
+use super::geometry::Transform3D;
+use super::lexer::{Param, RawEntity};
+use super::mesh::MeshResolver;
+use crate::core::spatial::mesh::Mesh;
+use crate::core::spatial::types::Point3D;
+use nalgebra::{Matrix3, Vector3};
+
+/// Segments used for circles and swept disks.
+pub const CIRCLE_SEGMENTS: usize = 24;
+
+fn number(param: Option<&Param>) -> Option<f64> {
+    match param? {
+        Param::Float(f) => Some(*f),
+        Param::Integer(i) => Some(*i as f64),
+        Param::Typed(_, inner) => number(Some(inner)),
+        _ => None,
+    }
+}
+
+fn reference(param: Option<&Param>) -> Option<u64> {
+    match param? {
+        Param::Reference(id) => Some(*id),
+        _ => None,
+    }
+}
+
+fn indices(param: Option<&Param>) -> Vec<usize> {
+    match param {
+        Some(Param::List(items)) => items
+            .iter()
+            .filter_map(|p| match p {
+                Param::Integer(i) if *i > 0 => Some(*i as usize - 1),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn point(v: Vector3<f64>) -> Point3D {
+    Point3D::new(v.x, v.y, v.z)
+}
+
+fn append(mesh: &mut Mesh, other: Mesh) {
+    let offset = mesh.vertices.len() as u32;
+    mesh.vertices.extend(other.vertices);
+    mesh.indices
+        .extend(other.indices.into_iter().map(|i| i + offset));
+}
+
+/// Triangulate a planar polygon (outer loop then holes) by ear clipping in
+/// its dominant plane. Returns indices into `loops` flattened.
+fn triangulate(loops: &[Vec<Vector3<f64>>]) -> Vec<u32> {
+    let outer = match loops.first() {
+        Some(outer) if outer.len() >= 3 => outer,
+        _ => return Vec::new(),
+    };
+    // Newell normal of the outer loop
+    let mut normal = Vector3::zeros();
+    for (i, a) in outer.iter().enumerate() {
+        let b = outer[(i + 1) % outer.len()];
+        normal += Vector3::new(
+            (a.y - b.y) * (a.z + b.z),
+            (a.z - b.z) * (a.x + b.x),
+            (a.x - b.x) * (a.y + b.y),
+        );
+    }
+    let (u, v) = if normal.z.abs() >= normal.x.abs() && normal.z.abs() >= normal.y.abs() {
+        (0, 1)
+    } else if normal.x.abs() >= normal.y.abs() {
+        (1, 2)
+    } else {
+        (0, 2)
+    };
+
+    let mut flat = Vec::new();
+    let mut holes = Vec::new();
+    for (i, ring) in loops.iter().enumerate() {
+        if i > 0 {
+            holes.push(flat.len() / 2);
+        }
+        for p in ring {
+            flat.push(p[u]);
+            flat.push(p[v]);
+        }
+    }
+    earcutr::earcut(&flat, &holes, 2)
+        .map(|tris| tris.into_iter().map(|i| i as u32).collect())
+        .unwrap_or_default()
+}
+
+impl MeshResolver<'_> {
+    /// Mesh for the items this backend handles; `None` defers to the
+    /// built-in resolver.
+    pub(super) fn resolve_extended_item(
+        &self,
+        entity: &RawEntity,
+        transform: &Transform3D,
+    ) -> Option<Mesh> {
+        match entity.class.as_str() {
+            "IFCMAPPEDITEM" => self.resolve_mapped_item(entity, transform),
+            "IFCPOLYGONALFACESET" => self.resolve_polygonal_face_set(entity, transform),
+            "IFCSWEPTDISKSOLID" => self.resolve_swept_disk(entity, transform),
+            "IFCEXTRUDEDAREASOLID" => self.resolve_extrusion(entity, transform),
+            // Without CSG the clipped body is approximated by the first operand
+            "IFCBOOLEANCLIPPINGRESULT" => {
+                self.resolve_mesh_item(reference(entity.params.get(1))?, transform)
+            }
+            _ => None,
+        }
+    }
+
+    fn resolve_mapped_item(&self, entity: &RawEntity, transform: &Transform3D) -> Option<Mesh> {
+        // MappingSource @0 (IfcRepresentationMap: MappingOrigin @0,
+        // MappedRepresentation @1), MappingTarget @1
+        let map = self.registry.get_raw(reference(entity.params.first())?)?;
+        let origin = reference(map.params.first())
+            .map(|id| self.geometry.resolve_placement(id))
+            .unwrap_or_else(Transform3D::identity);
+        let target = reference(entity.params.get(1))
+            .map(|id| self.resolve_transformation_operator(id))
+            .unwrap_or_else(Transform3D::identity);
+        let placed = transform.compose(&target).compose(&origin);
+        self.extract_mesh_from_representation(reference(map.params.get(1))?, &placed)
+    }
+
+    /// `IfcCartesianTransformationOperator3D` (and its non-uniform subtype).
+    fn resolve_transformation_operator(&self, id: u64) -> Transform3D {
+        let Some(op) = self.registry.get_raw(id) else {
+            return Transform3D::identity();
+        };
+        // Axis1 @0, Axis2 @1, LocalOrigin @2, Scale @3, Axis3 @4, Scale2 @5, Scale3 @6
+        let axis =
+            |i: usize| reference(op.params.get(i)).and_then(|d| self.geometry.resolve_direction(d));
+        let z = axis(4).unwrap_or(Vector3::z());
+        let x_raw = axis(0).unwrap_or(Vector3::x());
+        let x = (x_raw - x_raw.dot(&z) * z)
+            .try_normalize(1e-12)
+            .unwrap_or(Vector3::x());
+        let y = z.cross(&x);
+        let scale = number(op.params.get(3)).unwrap_or(1.0);
+        let scale2 = number(op.params.get(5)).unwrap_or(scale);
+        let scale3 = number(op.params.get(6)).unwrap_or(scale);
+        let rotation = Matrix3::from_columns(&[x * scale, y * scale2, z * scale3]);
+        let origin = reference(op.params.get(2))
+            .map(|p| self.geometry.resolve_cartesian_point(p))
+            .unwrap_or_else(Vector3::zeros);
+        Transform3D::from_translation_rotation(origin, rotation)
+    }
+
+    fn resolve_polygonal_face_set(
+        &self,
+        entity: &RawEntity,
+        transform: &Transform3D,
+    ) -> Option<Mesh> {
+        // Coordinates @0, Closed @1, Faces @2, PnIndex @3
+        let points = self.resolve_point_list_3d(reference(entity.params.first())?, transform)?;
+        let pn_index = indices(entity.params.get(3));
+        let vertex = |i: usize| {
+            let i = if pn_index.is_empty() {
+                Some(i)
+            } else {
+                pn_index.get(i).copied()
+            };
+            i.and_then(|i| points.get(i))
+                .map(|p| Vector3::new(p.x, p.y, p.z))
+        };
+
+        let mut mesh = Mesh::new(Vec::new(), Vec::new());
+        let Some(Param::List(faces)) = entity.params.get(2) else {
+            return None;
+        };
+        for face_id in faces.iter().filter_map(|f| reference(Some(f))) {
+            let Some(face) = self.registry.get_raw(face_id) else {
+                continue;
+            };
+            // IfcIndexedPolygonalFace: CoordIndex @0;
+            // ...WithVoids: InnerCoordIndices @1
+            let mut loops = vec![indices(face.params.first())];
+            if let Some(Param::List(inner)) = face.params.get(1) {
+                loops.extend(inner.iter().map(|l| indices(Some(l))));
+            }
+            let rings: Option<Vec<Vec<Vector3<f64>>>> = loops
+                .iter()
+                .map(|l| l.iter().map(|&i| vertex(i)).collect())
+                .collect();
+            let Some(rings) = rings else {
+                continue;
+            };
+            let triangles = triangulate(&rings);
+            let vertices = rings.into_iter().flatten().map(point).collect();
+            append(&mut mesh, Mesh::new(vertices, triangles));
+        }
+        (!mesh.indices.is_empty()).then_some(mesh)
+    }
+
+    /// Points of a directrix or profile curve (polyline or indexed poly curve).
+    fn resolve_curve_points(&self, curve_id: u64) -> Vec<Vector3<f64>> {
+        let Some(curve) = self.registry.get_raw(curve_id) else {
+            return Vec::new();
+        };
+        match curve.class.as_str() {
+            "IFCPOLYLINE" => self.geometry.resolve_polyline(curve_id),
+            "IFCINDEXEDPOLYCURVE" => {
+                // Points @0 (IfcCartesianPointList2D/3D); segments ignored
+                let Some(list) =
+                    reference(curve.params.first()).and_then(|id| self.registry.get_raw(id))
+                else {
+                    return Vec::new();
+                };
+                match list.params.first() {
+                    Some(Param::List(coords)) => coords
+                        .iter()
+                        .filter_map(|c| match c {
+                            Param::List(xyz) => Some(Vector3::new(
+                                number(xyz.first()).unwrap_or(0.0),
+                                number(xyz.get(1)).unwrap_or(0.0),
+                                number(xyz.get(2)).unwrap_or(0.0),
+                            )),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn resolve_swept_disk(&self, entity: &RawEntity, transform: &Transform3D) -> Option<Mesh> {
+        // Directrix @0, Radius @1
+        let path = self.resolve_curve_points(reference(entity.params.first())?);
+        let radius = number(entity.params.get(1))?;
+        if path.len() < 2 || radius <= 0.0 {
+            return None;
+        }
+
+        let mut mesh = Mesh::new(Vec::new(), Vec::new());
+        for segment in path.windows(2) {
+            let Some(dir) = (segment[1] - segment[0]).try_normalize(1e-12) else {
+                continue;
+            };
+            let helper = if dir.z.abs() < 0.9 {
+                Vector3::z()
+            } else {
+                Vector3::x()
+            };
+            let u = dir.cross(&helper).normalize();
+            let v = dir.cross(&u);
+            let ring = |center: Vector3<f64>| {
+                (0..CIRCLE_SEGMENTS).map(move |k| {
+                    let a = std::f64::consts::TAU * k as f64 / CIRCLE_SEGMENTS as f64;
+                    center + (u * a.cos() + v * a.sin()) * radius
+                })
+            };
+            let vertices = ring(segment[0])
+                .chain(ring(segment[1]))
+                .map(|p| point(transform.transform_point(&p)))
+                .collect();
+            let n = CIRCLE_SEGMENTS as u32;
+            let mut triangles = Vec::with_capacity(CIRCLE_SEGMENTS * 6);
+            for k in 0..n {
+                let next = (k + 1) % n;
+                triangles.extend([k, next, k + n, next, next + n, k + n]);
+            }
+            append(&mut mesh, Mesh::new(vertices, triangles));
+        }
+        (!mesh.indices.is_empty()).then_some(mesh)
+    }
+
+    /// Outer loop and holes of a profile, in its 2D placement.
+    fn resolve_profile_loops(&self, profile_id: u64) -> Vec<Vec<Vector3<f64>>> {
+        let Some(profile) = self.registry.get_raw(profile_id) else {
+            return Vec::new();
+        };
+        let circle = |r: f64| -> Vec<Vector3<f64>> {
+            (0..CIRCLE_SEGMENTS)
+                .map(|k| {
+                    let a = std::f64::consts::TAU * k as f64 / CIRCLE_SEGMENTS as f64;
+                    Vector3::new(r * a.cos(), r * a.sin(), 0.0)
+                })
+                .collect()
+        };
+        // Parameterized profiles: Position @2, then their dimensions
+        let loops = match profile.class.as_str() {
+            "IFCRECTANGLEPROFILEDEF" => {
+                let dx = number(profile.params.get(3)).unwrap_or(1.0) / 2.0;
+                let dy = number(profile.params.get(4)).unwrap_or(1.0) / 2.0;
+                vec![vec![
+                    Vector3::new(-dx, -dy, 0.0),
+                    Vector3::new(dx, -dy, 0.0),
+                    Vector3::new(dx, dy, 0.0),
+                    Vector3::new(-dx, dy, 0.0),
+                ]]
+            }
+            "IFCCIRCLEPROFILEDEF" => vec![circle(number(profile.params.get(3)).unwrap_or(0.0))],
+            "IFCCIRCLEHOLLOWPROFILEDEF" => {
+                let r = number(profile.params.get(3)).unwrap_or(0.0);
+                let wall = number(profile.params.get(4)).unwrap_or(0.0);
+                let mut inner = circle(r - wall);
+                inner.reverse();
+                vec![circle(r), inner]
+            }
+            "IFCARBITRARYCLOSEDPROFILEDEF" | "IFCARBITRARYPROFILEDEFWITHVOIDS" => {
+                // OuterCurve @2, InnerCurves @3
+                let mut loops: Vec<Vec<Vector3<f64>>> =
+                    vec![self.resolve_curve_points(reference(profile.params.get(2)).unwrap_or(0))];
+                if let Some(Param::List(inner)) = profile.params.get(3) {
+                    loops.extend(
+                        inner
+                            .iter()
+                            .filter_map(|c| reference(Some(c)))
+                            .map(|c| self.resolve_curve_points(c)),
+                    );
+                }
+                return loops
+                    .into_iter()
+                    .map(|mut l| {
+                        // Closed polylines repeat their first point
+                        if l.len() > 1 && (l[0] - l[l.len() - 1]).norm() < 1e-9 {
+                            l.pop();
+                        }
+                        l
+                    })
+                    .collect();
+            }
+            _ => return vec![self.geometry.resolve_profile_points(profile_id)],
+        };
+
+        // IfcAxis2Placement2D: Location @0, RefDirection @1
+        let Some(position) =
+            reference(profile.params.get(2)).and_then(|id| self.registry.get_raw(id))
+        else {
+            return loops;
+        };
+        let origin = reference(position.params.first())
+            .map(|p| self.geometry.resolve_cartesian_point(p))
+            .unwrap_or_else(Vector3::zeros);
+        let x = reference(position.params.get(1))
+            .and_then(|d| self.geometry.resolve_direction(d))
+            .unwrap_or(Vector3::x());
+        let y = Vector3::z().cross(&x);
+        loops
+            .into_iter()
+            .map(|l| l.into_iter().map(|p| origin + x * p.x + y * p.y).collect())
+            .collect()
+    }
+
+    fn resolve_extrusion(&self, entity: &RawEntity, transform: &Transform3D) -> Option<Mesh> {
+        // SweptArea @0, Position @1, ExtrudedDirection @2, Depth @3
+        let loops = self.resolve_profile_loops(reference(entity.params.first())?);
+        if loops.first().is_none_or(|outer| outer.len() < 3) {
+            return None;
+        }
+        let depth = number(entity.params.get(3)).unwrap_or(1.0);
+        let direction = reference(entity.params.get(2))
+            .and_then(|d| self.geometry.resolve_direction(d))
+            .unwrap_or(Vector3::z());
+        let frame = match reference(entity.params.get(1)) {
+            Some(position) => transform.compose(&self.geometry.resolve_placement(position)),
+            None => transform.clone(),
+        };
+        let offset = direction * depth;
+
+        let cap = triangulate(&loops);
+        let count: usize = loops.iter().map(Vec::len).sum();
+        let n = count as u32;
+        let profile: Vec<Vector3<f64>> = loops.iter().flatten().copied().collect();
+        let mut vertices: Vec<Point3D> = profile
+            .iter()
+            .map(|p| point(frame.transform_point(p)))
+            .collect();
+        vertices.extend(
+            profile
+                .iter()
+                .map(|p| point(frame.transform_point(&(p + offset)))),
+        );
+
+        let mut triangles = Vec::with_capacity(cap.len() * 2 + count * 6);
+        for tri in cap.chunks(3) {
+            // Bottom faces down, top faces up
+            triangles.extend([tri[0], tri[2], tri[1]]);
+            triangles.extend([tri[0] + n, tri[1] + n, tri[2] + n]);
+        }
+        let mut start = 0u32;
+        for ring in &loops {
+            let len = ring.len() as u32;
+            for k in 0..len {
+                let (a, b) = (start + k, start + (k + 1) % len);
+                triangles.extend([a, b, a + n, b, b + n, a + n]);
+            }
+            start += len;
+        }
+        Some(Mesh::new(vertices, triangles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ifc::parser::geometry::GeometryResolver;
+    use crate::ifc::parser::registry::EntityRegistry;
+    use crate::ifc::parser::StepLexer;
+
+    fn registry(step: &str) -> EntityRegistry {
+        let mut registry = EntityRegistry::new();
+        registry.populate_from_lexer(StepLexer::new(step));
+        registry
+    }
+
+    #[test]
+    fn mapped_polygonal_face_set_is_placed_per_instance() {
+        // A unit quad in type geometry, mapped twice its size at x = 10
+        let registry = registry(
+            "#1=IFCCARTESIANPOINTLIST3D(((0.,0.,0.),(1.,0.,0.),(1.,1.,0.),(0.,1.,0.)));
+             #2=IFCINDEXEDPOLYGONALFACE((1,2,3,4));
+             #3=IFCPOLYGONALFACESET(#1,.T.,(#2),$);
+             #4=IFCSHAPEREPRESENTATION($,'Body','Tessellation',(#3));
+             #5=IFCCARTESIANPOINT((0.,0.,0.));
+             #6=IFCAXIS2PLACEMENT3D(#5,$,$);
+             #7=IFCREPRESENTATIONMAP(#6,#4);
+             #8=IFCCARTESIANPOINT((10.,0.,0.));
+             #9=IFCCARTESIANTRANSFORMATIONOPERATOR3D($,$,#8,2.,$);
+             #10=IFCMAPPEDITEM(#7,#9);",
+        );
+        let geometry = GeometryResolver::new(&registry);
+        let resolver = MeshResolver::new(&registry, &geometry);
+
+        let mesh = resolver
+            .resolve_mesh_item(10, &Transform3D::identity())
+            .unwrap();
+        assert_eq!(mesh.indices.len(), 6);
+        let max_x = mesh.vertices.iter().map(|v| v.x).fold(f64::MIN, f64::max);
+        assert!((max_x - 12.0).abs() < 1e-9);
+        assert!(resolver.skipped_items().is_empty());
+    }
+
+    #[test]
+    fn swept_disks_and_circle_extrusions_tessellate() {
+        let registry = registry(
+            "#1=IFCCARTESIANPOINT((0.,0.,0.));
+             #2=IFCCARTESIANPOINT((0.,0.,3.));
+             #3=IFCPOLYLINE((#1,#2));
+             #4=IFCSWEPTDISKSOLID(#3,0.05,$,$,$);
+             #5=IFCCIRCLEPROFILEDEF(.AREA.,$,$,0.5);
+             #6=IFCAXIS2PLACEMENT3D(#1,$,$);
+             #7=IFCDIRECTION((0.,0.,1.));
+             #8=IFCEXTRUDEDAREASOLID(#5,#6,#7,2.);",
+        );
+        let geometry = GeometryResolver::new(&registry);
+        let resolver = MeshResolver::new(&registry, &geometry);
+
+        let pipe = resolver
+            .resolve_mesh_item(4, &Transform3D::identity())
+            .unwrap();
+        assert_eq!(pipe.vertices.len(), CIRCLE_SEGMENTS * 2);
+        assert!(pipe
+            .vertices
+            .iter()
+            .all(|v| ((v.x * v.x + v.y * v.y).sqrt() - 0.05).abs() < 1e-9));
+
+        let column = resolver
+            .resolve_mesh_item(8, &Transform3D::identity())
+            .unwrap();
+        let top = column.vertices.iter().map(|v| v.z).fold(f64::MIN, f64::max);
+        assert!((top - 2.0).abs() < 1e-9);
+        // Two caps of (segments - 2) triangles plus two per side
+        assert_eq!(
+            column.indices.len(),
+            3 * (2 * (CIRCLE_SEGMENTS - 2) + 2 * CIRCLE_SEGMENTS)
+        );
+    }
+}
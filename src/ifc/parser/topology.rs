@@ -0,0 +1,213 @@
+//! Equipment topology from IFC distribution systems.
+//!
+//! Members of an `IfcSystem` / `IfcDistributionSystem` (via
+//! `IfcRelAssignsToGroup`) are tagged with the system's name. Ports joined by
+//! `IfcRelConnectsPorts` link their owning elements (ports are nested under
+//! elements in IFC4, attached by `IfcRelConnectsPortToElement` in IFC2X3);
+//! edges run from the `SOURCE` port to the `SINK` port, or relating to
+//! related when neither says. Equipment reached through non-equipment runs
+//! (ducts, pipes, fittings) is connected directly, since Arx does not import
+//! the runs. `IfcRelFlowControlElements` adds `controls` edges.
+
+use super::lexer::Param;
+use super::registry::EntityRegistry;
+use super::schema::SYSTEM_CLASSES;
+use crate::core::systems::{Connection, Relation};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Equipment property naming the systems (comma-separated) it belongs to.
+pub const SYSTEM_PROPERTY: &str = "system";
+
+fn references(param: Option<&Param>) -> Vec<u64> {
+    match param {
+        Some(Param::Reference(id)) => vec![*id],
+        Some(Param::List(items)) => items
+            .iter()
+            .filter_map(|p| match p {
+                Param::Reference(id) => Some(*id),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn enum_param(registry: &EntityRegistry, id: u64, index: usize) -> Option<&str> {
+    match registry.get_raw(id)?.params.get(index)? {
+        Param::Enum(value) => Some(value.as_str()),
+        _ => None,
+    }
+}
+
+/// Relation implied by a system's `PredefinedType` (IFC4; IFC2X3 systems
+/// have none and feed).
+fn system_relation(registry: &EntityRegistry, system: u64) -> Relation {
+    match enum_param(registry, system, 6).unwrap_or("") {
+        "ELECTRICAL" | "LIGHTING" | "POWERGENERATION" | "EARTHING" | "LIGHTNINGPROTECTION" => {
+            Relation::Powers
+        }
+        "CONTROL" | "SIGNAL" => Relation::Controls,
+        _ => Relation::Feeds,
+    }
+}
+
+/// System entity ids each element is assigned to.
+fn system_groups(registry: &EntityRegistry) -> HashMap<u64, Vec<u64>> {
+    let mut groups: HashMap<u64, Vec<u64>> = HashMap::new();
+    for &rel_id in registry.get_by_class("IFCRELASSIGNSTOGROUP") {
+        let Some(rel) = registry.get_raw(rel_id) else {
+            continue;
+        };
+        // RelatedObjects @4, RelatingGroup @6
+        let Some(Param::Reference(group)) = rel.params.get(6) else {
+            continue;
+        };
+        let is_system = registry
+            .get_raw(*group)
+            .is_some_and(|g| SYSTEM_CLASSES.contains(&g.class.as_str()));
+        if !is_system {
+            continue;
+        }
+        for member in references(rel.params.get(4)) {
+            groups.entry(member).or_default().push(*group);
+        }
+    }
+    groups
+}
+
+/// Names of the systems each element belongs to.
+pub fn system_names(registry: &EntityRegistry) -> HashMap<u64, Vec<String>> {
+    system_groups(registry)
+        .into_iter()
+        .map(|(member, groups)| {
+            let names = groups
+                .iter()
+                .filter_map(|g| match registry.get_raw(*g)?.params.get(2)? {
+                    Param::String(name) if !name.trim().is_empty() => Some(name.clone()),
+                    _ => None,
+                })
+                .collect();
+            (member, names)
+        })
+        .collect()
+}
+
+/// Element owning each port.
+fn port_owners(registry: &EntityRegistry) -> HashMap<u64, u64> {
+    let mut owners = HashMap::new();
+    for &rel_id in registry.get_by_class("IFCRELNESTS") {
+        if let Some(rel) = registry.get_raw(rel_id) {
+            // RelatingObject @4, RelatedObjects @5
+            if let Some(Param::Reference(owner)) = rel.params.get(4) {
+                for port in references(rel.params.get(5)) {
+                    let is_port = registry
+                        .get_raw(port)
+                        .is_some_and(|p| p.class.ends_with("PORT"));
+                    if is_port {
+                        owners.insert(port, *owner);
+                    }
+                }
+            }
+        }
+    }
+    for &rel_id in registry.get_by_class("IFCRELCONNECTSPORTTOELEMENT") {
+        if let Some(rel) = registry.get_raw(rel_id) {
+            // RelatingPort @4, RelatedElement @5
+            if let (Some(Param::Reference(port)), Some(Param::Reference(owner))) =
+                (rel.params.get(4), rel.params.get(5))
+            {
+                owners.insert(*port, *owner);
+            }
+        }
+    }
+    owners
+}
+
+/// Downstream elements of each element, from port connections.
+fn flow_graph(registry: &EntityRegistry) -> HashMap<u64, Vec<u64>> {
+    let owners = port_owners(registry);
+    let mut graph: HashMap<u64, Vec<u64>> = HashMap::new();
+    for &rel_id in registry.get_by_class("IFCRELCONNECTSPORTS") {
+        let Some(rel) = registry.get_raw(rel_id) else {
+            continue;
+        };
+        // RelatingPort @4, RelatedPort @5
+        let (Some(Param::Reference(a)), Some(Param::Reference(b))) =
+            (rel.params.get(4), rel.params.get(5))
+        else {
+            continue;
+        };
+        let (Some(&from), Some(&to)) = (owners.get(a), owners.get(b)) else {
+            continue;
+        };
+        // FlowDirection @7
+        let reversed = enum_param(registry, *a, 7) == Some("SINK")
+            || enum_param(registry, *b, 7) == Some("SOURCE");
+        let (from, to) = if reversed { (to, from) } else { (from, to) };
+        if from != to {
+            graph.entry(from).or_default().push(to);
+        }
+    }
+    graph
+}
+
+/// Outgoing connections of each equipment element, keyed by STEP id.
+/// `equipment` maps equipment STEP ids to their Arx ids.
+pub fn equipment_connections(
+    registry: &EntityRegistry,
+    equipment: &HashMap<u64, String>,
+) -> HashMap<u64, Vec<Connection>> {
+    let groups = system_groups(registry);
+    let graph = flow_graph(registry);
+    let relation = |from: u64, to: u64| {
+        let from_groups = groups.get(&from).map(Vec::as_slice).unwrap_or_default();
+        let to_groups = groups.get(&to).map(Vec::as_slice).unwrap_or_default();
+        from_groups
+            .iter()
+            .find(|g| to_groups.contains(g))
+            .or(from_groups.first())
+            .map_or(Relation::Feeds, |g| system_relation(registry, *g))
+    };
+
+    let mut out: HashMap<u64, Vec<Connection>> = HashMap::new();
+    let mut push = |from: u64, to: u64, relation: Relation| {
+        let (Some(_), Some(to_id)) = (equipment.get(&from), equipment.get(&to)) else {
+            return;
+        };
+        let connection = Connection {
+            relation,
+            to: to_id.clone(),
+        };
+        let list = out.entry(from).or_default();
+        if from != to && !list.contains(&connection) {
+            list.push(connection);
+        }
+    };
+
+    for &start in equipment.keys() {
+        let mut seen = HashSet::from([start]);
+        let mut queue: VecDeque<u64> = graph.get(&start).cloned().unwrap_or_default().into();
+        while let Some(next) = queue.pop_front() {
+            if !seen.insert(next) {
+                continue;
+            }
+            if equipment.contains_key(&next) {
+                push(start, next, relation(start, next));
+            } else if let Some(downstream) = graph.get(&next) {
+                queue.extend(downstream);
+            }
+        }
+    }
+
+    for &rel_id in registry.get_by_class("IFCRELFLOWCONTROLELEMENTS") {
+        if let Some(rel) = registry.get_raw(rel_id) {
+            // RelatedControlElements @4, RelatingFlowElement @5
+            if let Some(Param::Reference(flow)) = rel.params.get(5) {
+                for control in references(rel.params.get(4)) {
+                    push(control, *flow, Relation::Controls);
+                }
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,444 @@
+//! AR scans → pending equipment for human review.
+//!
+//! The field app extracts equipment from an AR session as an [`ArScan`];
+//! [`pending_from_scan`] turns it into [`PendingEquipment`] records, which the
+//! agent keeps in its review queue (`ar.pending.*`). A record reaches the
+//! model when a reviewer approves it and [`place_pending`] adds it to the
+//! building with `review_status=accepted`. The [`ArReviewPolicy`] can merge
+//! confident detections of equipment types the room already has without
+//! review, and flags detections next to existing equipment as probable
+//! matches rather than new items.
+//!
+//! ```json
+//! {"scan_id": "scan-42", "floor_level": 1, "room": "Mech 101",
+//!  "equipment": [{"name": "AHU-3", "equipment_type": "hvac",
+//!                 "position": [4.2, 1.0, 2.5], "confidence": 0.87}]}
+//! ```
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::equipment_csv::resolve_room;
+use super::text::parse_eq_type;
+use crate::core::review::{ReviewStatus, PROP_REVIEW_STATUS};
+use crate::core::{Building, Equipment, EquipmentType};
+
+/// Equipment property recording the scan a record came from.
+pub const PROP_AR_SCAN_ID: &str = "ar_scan_id";
+
+/// Equipment property with the detector's confidence (0–1).
+pub const PROP_AR_CONFIDENCE: &str = "ar_confidence";
+
+/// Auto-accept and duplicate rules for submitted scans (see [`ArReviewPolicy`]).
+pub const AR_POLICY_FILE: &str = ".arx/ar-policy.yaml";
+
+/// Equipment extracted from one AR session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArScan {
+    pub scan_id: String,
+    /// Floor the scan was taken on, for items without a room
+    #[serde(default)]
+    pub floor_level: Option<i32>,
+    /// Room the scan was taken in (id, name, or alias)
+    #[serde(default)]
+    pub room: Option<String>,
+    #[serde(default)]
+    pub equipment: Vec<ScannedEquipment>,
+}
+
+/// One detected item.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScannedEquipment {
+    pub name: String,
+    #[serde(default)]
+    pub equipment_type: Option<String>,
+    /// Overrides the scan's room
+    #[serde(default)]
+    pub room: Option<String>,
+    /// `[x, y, z]` in building coordinates (m)
+    #[serde(default)]
+    pub position: Option<[f64; 3]>,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+}
+
+/// Review state of a pending record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingState {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Scanned equipment awaiting review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingEquipment {
+    /// `<scan_id>-<n>`, so resubmitting a scan does not duplicate it
+    pub id: String,
+    pub scan_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub equipment_type: Option<String>,
+    #[serde(default)]
+    pub floor_level: Option<i32>,
+    #[serde(default)]
+    pub room: Option<String>,
+    #[serde(default)]
+    pub position: Option<[f64; 3]>,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    #[serde(default)]
+    pub state: PendingState,
+    /// Set by the agent when the record is queued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// Reviewer's note (e.g. why it was rejected)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Id of the equipment created on approval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_id: Option<String>,
+    /// Existing equipment this detection probably is (see [`ArReviewPolicy`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probable_match: Option<String>,
+}
+
+/// Pending records for every named item in `scan`.
+pub fn pending_from_scan(scan: &ArScan) -> Result<Vec<PendingEquipment>, String> {
+    let scan_id = scan.scan_id.trim();
+    if scan_id.is_empty() {
+        return Err("scan_id is required".to_string());
+    }
+    let mut pending = Vec::new();
+    for (index, item) in scan.equipment.iter().enumerate() {
+        let name = item.name.trim();
+        if name.is_empty() {
+            return Err(format!("item {} has no name", index + 1));
+        }
+        if let Some(c) = item.confidence.filter(|c| !(0.0..=1.0).contains(c)) {
+            return Err(format!("{}: confidence {} is outside 0-1", name, c));
+        }
+        pending.push(PendingEquipment {
+            id: format!("{}-{}", scan_id, index + 1),
+            scan_id: scan_id.to_string(),
+            name: name.to_string(),
+            equipment_type: item.equipment_type.clone(),
+            floor_level: scan.floor_level,
+            room: item.room.clone().or_else(|| scan.room.clone()),
+            position: item.position,
+            confidence: item.confidence,
+            state: PendingState::Pending,
+            submitted_at: None,
+            reviewed_by: None,
+            reviewed_at: None,
+            note: None,
+            equipment_id: None,
+            probable_match: None,
+        });
+    }
+    Ok(pending)
+}
+
+/// Floor level and room id a record lands in.
+fn locate(
+    building: &Building,
+    pending: &PendingEquipment,
+) -> Result<(i32, Option<String>), String> {
+    match &pending.room {
+        Some(room) => {
+            let (level, room_id, _) = resolve_room(building, room, pending.floor_level)?;
+            Ok((level, Some(room_id)))
+        }
+        None => {
+            let level = pending
+                .floor_level
+                .ok_or_else(|| format!("{}: scan has no room or floor", pending.name))?;
+            if !building.floors.iter().any(|f| f.level == level) {
+                return Err(format!("floor {} not found", level));
+            }
+            Ok((level, None))
+        }
+    }
+}
+
+fn pending_type(pending: &PendingEquipment) -> Result<EquipmentType, String> {
+    match pending.equipment_type.as_deref().map(str::trim) {
+        None | Some("") => Ok(EquipmentType::Other("Unknown".into())),
+        Some(s) => parse_eq_type(s).map_err(|e| e.to_string()),
+    }
+}
+
+/// Add an approved record to `building` as accepted equipment; returns the
+/// new equipment id.
+pub fn place_pending(
+    building: &mut Building,
+    pending: &PendingEquipment,
+) -> Result<String, String> {
+    let (level, room_id) = locate(building, pending)?;
+    let mut equipment = Equipment::new(pending.name.clone(), String::new(), pending_type(pending)?);
+    if let Some([x, y, z]) = pending.position {
+        equipment.position.x = x;
+        equipment.position.y = y;
+        equipment.position.z = z;
+    }
+    equipment.properties.insert(
+        PROP_REVIEW_STATUS.to_string(),
+        ReviewStatus::Accepted.as_str().to_string(),
+    );
+    equipment
+        .properties
+        .insert(PROP_AR_SCAN_ID.to_string(), pending.scan_id.clone());
+    if let Some(c) = pending.confidence {
+        equipment
+            .properties
+            .insert(PROP_AR_CONFIDENCE.to_string(), format!("{:.2}", c));
+    }
+    let id = equipment.id.clone();
+
+    match room_id {
+        Some(room_id) => {
+            equipment.room_id = Some(room_id.clone());
+            building
+                .find_room_mut(&room_id)
+                .ok_or_else(|| format!("room '{}' not found", room_id))?
+                .add_equipment(equipment);
+        }
+        None => building
+            .floors
+            .iter_mut()
+            .find(|f| f.level == level)
+            .ok_or_else(|| format!("floor {} not found", level))?
+            .equipment
+            .push(equipment),
+    }
+    Ok(id)
+}
+
+/// How submitted records are triaged, from [`AR_POLICY_FILE`].
+///
+/// ```yaml
+/// auto_accept_confidence: 0.9
+/// duplicate_radius_cm: 50
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArReviewPolicy {
+    /// Detections at or above this confidence whose type is already in the
+    /// room are merged without review; unset keeps everything pending
+    #[serde(default)]
+    pub auto_accept_confidence: Option<f64>,
+    /// Detections this close to existing equipment on the floor are flagged
+    /// as probable matches instead of new items
+    #[serde(default = "default_duplicate_radius_cm")]
+    pub duplicate_radius_cm: f64,
+}
+
+fn default_duplicate_radius_cm() -> f64 {
+    50.0
+}
+
+impl Default for ArReviewPolicy {
+    fn default() -> Self {
+        Self {
+            auto_accept_confidence: None,
+            duplicate_radius_cm: default_duplicate_radius_cm(),
+        }
+    }
+}
+
+/// What the policy decided for one record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Triage {
+    /// Merge into the model without review
+    AutoAccept,
+    /// Leave for a reviewer
+    Pending,
+    /// Within the duplicate radius of this equipment id; left for a reviewer
+    ProbableMatch(String),
+}
+
+impl ArReviewPolicy {
+    /// [`AR_POLICY_FILE`] under `base`, or the defaults when absent.
+    pub fn load_at(base: &Path) -> Result<Self, String> {
+        let path = base.join(AR_POLICY_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let policy: Self = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        if policy
+            .auto_accept_confidence
+            .is_some_and(|c| !(0.0..=1.0).contains(&c))
+        {
+            return Err(format!(
+                "Invalid {}: auto_accept_confidence must be within 0-1",
+                path.display()
+            ));
+        }
+        Ok(policy)
+    }
+
+    /// Decide `pending` against the current `building`. Records that cannot
+    /// be placed stay pending so a reviewer sees why.
+    pub fn triage(&self, building: &Building, pending: &PendingEquipment) -> Triage {
+        let Ok((level, room_id)) = locate(building, pending) else {
+            return Triage::Pending;
+        };
+        let Some(floor) = building.floors.iter().find(|f| f.level == level) else {
+            return Triage::Pending;
+        };
+        let on_floor = || {
+            floor
+                .equipment
+                .iter()
+                .chain(floor.wings.iter().flat_map(|w| {
+                    w.equipment
+                        .iter()
+                        .chain(w.rooms.iter().flat_map(|r| r.equipment.iter()))
+                }))
+        };
+
+        if let Some([x, y, z]) = pending.position {
+            let radius = self.duplicate_radius_cm / 100.0;
+            let nearest = on_floor()
+                .map(|e| {
+                    let p = &e.position;
+                    let d = ((p.x - x).powi(2) + (p.y - y).powi(2) + (p.z - z).powi(2)).sqrt();
+                    (d, e)
+                })
+                .filter(|(d, _)| *d <= radius)
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((_, existing)) = nearest {
+                return Triage::ProbableMatch(existing.id.clone());
+            }
+        }
+
+        let confident = matches!(
+            (self.auto_accept_confidence, pending.confidence),
+            (Some(threshold), Some(c)) if c >= threshold
+        );
+        let (Some(room_id), Ok(equipment_type)) = (room_id, pending_type(pending)) else {
+            return Triage::Pending;
+        };
+        let type_in_room = building.find_room(&room_id).is_some_and(|r| {
+            r.equipment
+                .iter()
+                .any(|e| e.equipment_type == equipment_type)
+        });
+        if confident && type_in_room {
+            Triage::AutoAccept
+        } else {
+            Triage::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn test_scan_to_pending_and_place() {
+        let scan: ArScan = serde_json::from_str(
+            r#"{"scan_id": "scan-42", "floor_level": 1, "room": "Mech 101",
+                "equipment": [{"name": "AHU-3", "equipment_type": "hvac",
+                               "position": [4.2, 1.0, 2.5], "confidence": 0.87},
+                              {"name": "Panel", "room": "Lobby"}]}"#,
+        )
+        .unwrap();
+        let pending = pending_from_scan(&scan).unwrap();
+        assert_eq!(pending[0].id, "scan-42-1");
+        assert_eq!(pending[0].room.as_deref(), Some("Mech 101"));
+        assert_eq!(pending[1].room.as_deref(), Some("Lobby"));
+        assert_eq!(pending[1].state, PendingState::Pending);
+
+        let mut bad = scan.clone();
+        bad.equipment[0].confidence = Some(1.5);
+        assert!(pending_from_scan(&bad).is_err());
+
+        let mut building = Building::new("HQ".into(), String::new());
+        let mut floor = Floor::new("First".into(), 1);
+        let mut wing = Wing::new("Main".into());
+        wing.add_room(Room::new("Mech 101".into(), RoomType::Mechanical));
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let id = place_pending(&mut building, &pending[0]).unwrap();
+        let placed = building
+            .get_all_equipment()
+            .into_iter()
+            .find(|e| e.id == id)
+            .unwrap();
+        assert_eq!(placed.position.x, 4.2);
+        assert_eq!(placed.properties[PROP_REVIEW_STATUS], "accepted");
+        assert_eq!(placed.properties[PROP_AR_SCAN_ID], "scan-42");
+        assert!(placed.room_id.is_some());
+        assert!(place_pending(&mut building, &pending[1]).is_err());
+    }
+
+    #[test]
+    fn test_policy_auto_accepts_flags_matches_and_keeps_the_rest() {
+        let mut building = Building::new("HQ".into(), String::new());
+        let mut floor = Floor::new("First".into(), 1);
+        let mut wing = Wing::new("Main".into());
+        let mut room = Room::new("Mech 101".into(), RoomType::Mechanical);
+        let ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        let ahu_id = ahu.id.clone();
+        room.add_equipment(ahu);
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let detection = |kind: &str, confidence: f64, x: f64| PendingEquipment {
+            equipment_type: Some(kind.into()),
+            confidence: Some(confidence),
+            position: Some([x, 0.0, 0.0]),
+            ..pending_from_scan(&ArScan {
+                scan_id: "s".into(),
+                room: Some("Mech 101".into()),
+                equipment: vec![ScannedEquipment {
+                    name: "Unit".into(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })
+            .unwrap()
+            .remove(0)
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            ArReviewPolicy::load_at(dir.path()).unwrap(),
+            ArReviewPolicy::default()
+        );
+        std::fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        std::fs::write(
+            dir.path().join(AR_POLICY_FILE),
+            "auto_accept_confidence: 0.9\nduplicate_radius_cm: 40\n",
+        )
+        .unwrap();
+        let policy = ArReviewPolicy::load_at(dir.path()).unwrap();
+
+        let triage =
+            |kind, confidence, x| policy.triage(&building, &detection(kind, confidence, x));
+        assert_eq!(triage("hvac", 0.95, 5.0), Triage::AutoAccept);
+        assert_eq!(triage("hvac", 0.95, 0.3), Triage::ProbableMatch(ahu_id));
+        assert_eq!(triage("hvac", 0.95, 0.5), Triage::AutoAccept);
+        assert_eq!(triage("electrical", 0.99, 5.0), Triage::Pending);
+        assert_eq!(triage("hvac", 0.5, 5.0), Triage::Pending);
+        assert_eq!(
+            ArReviewPolicy::default().triage(&building, &detection("hvac", 0.95, 5.0)),
+            Triage::Pending
+        );
+    }
+}
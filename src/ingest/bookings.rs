@@ -0,0 +1,555 @@
+//! Room booking import from calendar systems.
+//!
+//! Each calendar is a [`BookingSource`]; `.arx/bookings.yaml` lists the ones a
+//! project pulls from:
+//!
+//! ```yaml
+//! sources:
+//!   - kind: ics                 # exported calendar file
+//!     path: exports/rooms.ics
+//!   - kind: caldav              # room resource calendar collection
+//!     url: https://cal.example.com/dav/rooms/conf-a/
+//!     room: Conference A
+//!     username: arx
+//!     password_env: CALDAV_PASSWORD
+//!   - kind: graph               # Microsoft 365 room mailbox
+//!     mailbox: conf-a@example.com
+//!     room: Conference A
+//!     token_env: GRAPH_TOKEN
+//! ```
+//!
+//! `room` names the building room every event in that calendar books; without
+//! it the event's LOCATION is used. CalDAV and Graph are asked for UTC times
+//! with recurring meetings expanded; `.ics` files are read as-is (times
+//! without `Z` are taken as UTC and only the first occurrence of a recurring
+//! event is imported). The network adapters need the `agent` feature.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::domain::booking::RoomBooking;
+
+/// Calendar sources for `arx booking import`.
+pub const BOOKINGS_CONFIG_FILE: &str = ".arx/bookings.yaml";
+
+/// A calendar that can list room bookings.
+pub trait BookingSource {
+    /// Adapter name stored with each booking (`ics`, `caldav`, `graph`).
+    fn kind(&self) -> &'static str;
+
+    /// Bookings overlapping `[from, to)`.
+    fn fetch(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RoomBooking>>;
+}
+
+/// One configured calendar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BookingSourceConfig {
+    Ics {
+        path: PathBuf,
+        #[serde(default)]
+        room: Option<String>,
+    },
+    Caldav {
+        url: String,
+        #[serde(default)]
+        room: Option<String>,
+        #[serde(default)]
+        username: Option<String>,
+        /// Environment variable holding the password
+        #[serde(default)]
+        password_env: Option<String>,
+    },
+    Graph {
+        mailbox: String,
+        #[serde(default)]
+        room: Option<String>,
+        /// Environment variable holding an OAuth access token
+        token_env: String,
+    },
+}
+
+/// Contents of [`BOOKINGS_CONFIG_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookingSources {
+    #[serde(default)]
+    pub sources: Vec<BookingSourceConfig>,
+}
+
+impl BookingSources {
+    /// Sources from `base/.arx/bookings.yaml`; none when the file is absent.
+    pub fn load_at(base: &Path) -> Result<Self> {
+        let path = base.join(BOOKINGS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("parsing {}", path.display()))
+    }
+}
+
+/// Build the adapter for `config`; relative `.ics` paths resolve under `base`.
+pub fn build_source(base: &Path, config: &BookingSourceConfig) -> Result<Box<dyn BookingSource>> {
+    match config {
+        BookingSourceConfig::Ics { path, room } => Ok(Box::new(IcsFileSource {
+            path: base.join(path),
+            room: room.clone(),
+        })),
+        #[cfg(feature = "agent")]
+        BookingSourceConfig::Caldav {
+            url,
+            room,
+            username,
+            password_env,
+        } => Ok(Box::new(CalDavSource {
+            url: url.clone(),
+            room: room.clone(),
+            username: username.clone(),
+            password: password_env.as_deref().map(env_secret).transpose()?,
+        })),
+        #[cfg(feature = "agent")]
+        BookingSourceConfig::Graph {
+            mailbox,
+            room,
+            token_env,
+        } => Ok(Box::new(GraphSource {
+            mailbox: mailbox.clone(),
+            room: room.clone(),
+            token: env_secret(token_env)?,
+        })),
+        #[cfg(not(feature = "agent"))]
+        BookingSourceConfig::Caldav { .. } | BookingSourceConfig::Graph { .. } => {
+            bail!("CalDAV / Microsoft Graph calendars need a build with --features agent")
+        }
+    }
+}
+
+#[cfg(feature = "agent")]
+fn env_secret(var: &str) -> Result<String> {
+    std::env::var(var).map_err(|_| anyhow::anyhow!("environment variable {} is not set", var))
+}
+
+/// Calendar exported to an `.ics` file.
+pub struct IcsFileSource {
+    pub path: PathBuf,
+    pub room: Option<String>,
+}
+
+impl BookingSource for IcsFileSource {
+    fn kind(&self) -> &'static str {
+        "ics"
+    }
+
+    fn fetch(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RoomBooking>> {
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("reading {}", self.path.display()))?;
+        Ok(parse_ics(&content, self.kind(), self.room.as_deref())
+            .into_iter()
+            .filter(|b| b.start < to && b.end > from)
+            .collect())
+    }
+}
+
+/// Unfold continuation lines (RFC 5545 §3.1).
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// `20240304T090000Z`, floating `20240304T090000` (taken as UTC), or an
+/// all-day `20240304`.
+fn parse_ics_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let naive = NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })?;
+    Some(Utc.from_utc_datetime(&naive))
+}
+
+#[derive(Default)]
+struct PendingEvent {
+    uid: Option<String>,
+    recurrence_id: Option<String>,
+    summary: Option<String>,
+    location: Option<String>,
+    organizer: Option<String>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    cancelled: bool,
+}
+
+/// Bookings in an iCalendar document. Events without a room (no `room`
+/// override and no LOCATION), times, or UID are skipped, as are cancelled ones.
+pub fn parse_ics(content: &str, source: &str, room: Option<&str>) -> Vec<RoomBooking> {
+    let mut out = Vec::new();
+    let mut event: Option<PendingEvent> = None;
+    for line in unfold(content) {
+        if line == "BEGIN:VEVENT" {
+            event = Some(PendingEvent::default());
+            continue;
+        }
+        let Some(current) = event.as_mut() else {
+            continue;
+        };
+        if line == "END:VEVENT" {
+            let e = event.take().unwrap_or_default();
+            let room = room.map(str::to_string).or(e.location);
+            if let (Some(uid), Some(room), Some(start), false) = (e.uid, room, e.start, e.cancelled)
+            {
+                out.push(RoomBooking {
+                    id: match e.recurrence_id {
+                        Some(rid) => format!("{}/{}", uid, rid),
+                        None => uid,
+                    },
+                    source: source.to_string(),
+                    room,
+                    room_id: None,
+                    start,
+                    end: e.end.unwrap_or(start),
+                    title: e.summary,
+                    organizer: e.organizer,
+                });
+            }
+            continue;
+        }
+        let Some((name_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name_params.split(';').next().unwrap_or_default();
+        match name.to_ascii_uppercase().as_str() {
+            "UID" => current.uid = Some(value.to_string()),
+            "RECURRENCE-ID" => current.recurrence_id = Some(value.to_string()),
+            "SUMMARY" => current.summary = Some(unescape_text(value)),
+            "LOCATION" => {
+                current.location = Some(unescape_text(value)).filter(|l| !l.trim().is_empty())
+            }
+            "ORGANIZER" => {
+                let addr = value.strip_prefix("mailto:").unwrap_or(value);
+                current.organizer = Some(addr.to_string());
+            }
+            "DTSTART" => current.start = parse_ics_time(value),
+            "DTEND" => current.end = parse_ics_time(value),
+            "STATUS" => current.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Calendar objects in a CalDAV multistatus response, XML entities decoded.
+pub fn caldav_calendar_data(xml: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("BEGIN:VCALENDAR") {
+        let Some(len) = rest[start..].find("END:VCALENDAR") else {
+            break;
+        };
+        let end = start + len + "END:VCALENDAR".len();
+        out.push(
+            rest[start..end]
+                .replace("&#13;", "")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        );
+        rest = &rest[end..];
+    }
+    out
+}
+
+/// Microsoft Graph `calendarView` page.
+#[derive(Debug, Deserialize)]
+struct GraphPage {
+    #[serde(default)]
+    value: Vec<GraphEvent>,
+    #[serde(rename = "@odata.nextLink", default)]
+    next_link: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphEvent {
+    id: String,
+    #[serde(default)]
+    subject: Option<String>,
+    start: GraphTime,
+    end: GraphTime,
+    #[serde(default)]
+    location: Option<GraphLocation>,
+    #[serde(default)]
+    organizer: Option<GraphRecipient>,
+    #[serde(default)]
+    is_cancelled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphTime {
+    date_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphLocation {
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphRecipient {
+    email_address: GraphEmail,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphEmail {
+    #[serde(default)]
+    address: Option<String>,
+}
+
+/// Bookings and next-page link from one Graph `calendarView` response
+/// (requested with `Prefer: outlook.timezone="UTC"`).
+pub fn parse_graph_page(
+    json: &str,
+    room: Option<&str>,
+) -> Result<(Vec<RoomBooking>, Option<String>)> {
+    let page: GraphPage = serde_json::from_str(json).context("parsing Graph calendarView")?;
+    let time = |t: &GraphTime| -> Result<DateTime<Utc>> {
+        let naive = NaiveDateTime::parse_from_str(&t.date_time, "%Y-%m-%dT%H:%M:%S%.f")
+            .with_context(|| format!("bad Graph dateTime '{}'", t.date_time))?;
+        Ok(Utc.from_utc_datetime(&naive))
+    };
+    let mut out = Vec::new();
+    for event in page.value.into_iter().filter(|e| !e.is_cancelled) {
+        let Some(room) = room
+            .map(str::to_string)
+            .or_else(|| event.location.and_then(|l| l.display_name))
+            .filter(|r| !r.trim().is_empty())
+        else {
+            continue;
+        };
+        out.push(RoomBooking {
+            start: time(&event.start)?,
+            end: time(&event.end)?,
+            id: event.id,
+            source: "graph".to_string(),
+            room,
+            room_id: None,
+            title: event.subject,
+            organizer: event.organizer.and_then(|o| o.email_address.address),
+        });
+    }
+    Ok((out, page.next_link))
+}
+
+/// Drive an async HTTP request from the synchronous CLI.
+#[cfg(feature = "agent")]
+fn block_on<T>(future: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(future)
+}
+
+/// CalDAV calendar collection (RFC 4791 `calendar-query` REPORT).
+#[cfg(feature = "agent")]
+pub struct CalDavSource {
+    pub url: String,
+    pub room: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[cfg(feature = "agent")]
+impl BookingSource for CalDavSource {
+    fn kind(&self) -> &'static str {
+        "caldav"
+    }
+
+    fn fetch(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RoomBooking>> {
+        let range = format!(
+            r#"start="{}" end="{}""#,
+            from.format("%Y%m%dT%H%M%SZ"),
+            to.format("%Y%m%dT%H%M%SZ")
+        );
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><C:calendar-data><C:expand {range}/></C:calendar-data></D:prop>
+  <C:filter><C:comp-filter name="VCALENDAR"><C:comp-filter name="VEVENT">
+    <C:time-range {range}/>
+  </C:comp-filter></C:comp-filter></C:filter>
+</C:calendar-query>"#
+        );
+        let xml = block_on(async {
+            let mut request = reqwest::Client::new()
+                .request(reqwest::Method::from_bytes(b"REPORT")?, &self.url)
+                .header("Depth", "1")
+                .header("Content-Type", "application/xml; charset=utf-8")
+                .body(body);
+            if let Some(user) = &self.username {
+                request = request.basic_auth(user, self.password.as_deref());
+            }
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                bail!("CalDAV {} returned {}", self.url, response.status());
+            }
+            Ok(response.text().await?)
+        })?;
+        Ok(caldav_calendar_data(&xml)
+            .iter()
+            .flat_map(|ics| parse_ics(ics, self.kind(), self.room.as_deref()))
+            .filter(|b| b.start < to && b.end > from)
+            .collect())
+    }
+}
+
+/// Microsoft 365 room mailbox via Graph `calendarView`.
+#[cfg(feature = "agent")]
+pub struct GraphSource {
+    pub mailbox: String,
+    pub room: Option<String>,
+    pub token: String,
+}
+
+#[cfg(feature = "agent")]
+impl BookingSource for GraphSource {
+    fn kind(&self) -> &'static str {
+        "graph"
+    }
+
+    fn fetch(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RoomBooking>> {
+        let first = format!(
+            "https://graph.microsoft.com/v1.0/users/{}/calendarView?startDateTime={}&endDateTime={}&$top=100",
+            self.mailbox,
+            from.format("%Y-%m-%dT%H:%M:%SZ"),
+            to.format("%Y-%m-%dT%H:%M:%SZ")
+        );
+        block_on(async {
+            let client = reqwest::Client::new();
+            let mut out = Vec::new();
+            let mut next = Some(first);
+            while let Some(url) = next.take() {
+                let response = client
+                    .get(&url)
+                    .bearer_auth(&self.token)
+                    .header("Prefer", "outlook.timezone=\"UTC\"")
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    bail!(
+                        "Graph calendarView for {} returned {}",
+                        self.mailbox,
+                        response.status()
+                    );
+                }
+                let (bookings, link) =
+                    parse_graph_page(&response.text().await?, self.room.as_deref())?;
+                out.extend(bookings);
+                next = link;
+            }
+            Ok(out)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ics_events() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:evt-1\r\n\
+SUMMARY:Design review\\, weekly\r\n\
+LOCATION:Conference A\r\n\
+ORGANIZER;CN=Sam:mailto:sam@example.com\r\n\
+DTSTART:20240304T090000Z\r\n\
+DTEND:20240304T1000\r\n 00Z\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:evt-2\r\n\
+LOCATION:Conference A\r\n\
+DTSTART:20240305T090000Z\r\n\
+DTEND:20240305T100000Z\r\n\
+STATUS:CANCELLED\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:evt-3\r\n\
+DTSTART;VALUE=DATE:20240306\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        let bookings = parse_ics(ics, "ics", None);
+        assert_eq!(bookings.len(), 1);
+        let b = &bookings[0];
+        assert_eq!(b.title.as_deref(), Some("Design review, weekly"));
+        assert_eq!(b.organizer.as_deref(), Some("sam@example.com"));
+        assert_eq!((b.room.as_str(), b.hours()), ("Conference A", 1.0));
+
+        // A room override books every event in the calendar, LOCATION or not
+        assert_eq!(parse_ics(ics, "ics", Some("Atrium")).len(), 2);
+
+        let xml = format!(
+            "<D:multistatus><C:calendar-data>{}</C:calendar-data></D:multistatus>",
+            ics.replace("\r\n", "&#13;\n")
+        );
+        assert_eq!(caldav_calendar_data(&xml).len(), 1);
+    }
+
+    #[test]
+    fn test_parse_graph_page() {
+        let json = r#"{
+            "value": [
+                {"id": "AAMk1", "subject": "1:1",
+                 "start": {"dateTime": "2024-03-04T09:00:00.0000000", "timeZone": "UTC"},
+                 "end": {"dateTime": "2024-03-04T09:30:00.0000000", "timeZone": "UTC"},
+                 "location": {"displayName": "Conference A"},
+                 "organizer": {"emailAddress": {"address": "kim@example.com"}}},
+                {"id": "AAMk2", "isCancelled": true,
+                 "start": {"dateTime": "2024-03-04T10:00:00.0000000", "timeZone": "UTC"},
+                 "end": {"dateTime": "2024-03-04T11:00:00.0000000", "timeZone": "UTC"}}
+            ],
+            "@odata.nextLink": "https://graph.microsoft.com/next"
+        }"#;
+        let (bookings, next) = parse_graph_page(json, None).unwrap();
+        assert_eq!(next.as_deref(), Some("https://graph.microsoft.com/next"));
+        assert_eq!(bookings.len(), 1);
+        assert_eq!(bookings[0].hours(), 0.5);
+        assert_eq!(bookings[0].organizer.as_deref(), Some("kim@example.com"));
+    }
+}
@@ -0,0 +1,552 @@
+//! Bulk equipment import from spreadsheet CSV exports.
+//!
+//! Columns are found by header name. The defaults match `arx export --format
+//! csv` (`id,name,type,status,floor,room_id,room,x,y,z`), so a register can
+//! round-trip; other layouts map their headers in [`EQUIPMENT_CSV_MAPPING`]:
+//!
+//! ```yaml
+//! columns:
+//!   name: Asset Name
+//!   type: Category
+//!   room: Location
+//!   floor: Level
+//! properties:
+//!   serial_number: Serial No
+//!   manufacturer: Make
+//! ```
+//!
+//! [`plan_equipment_import`] checks every row before anything is written:
+//! rows without a name, with a room that does not resolve, repeated in the
+//! file, or already in the model are reported and skipped.
+
+use super::text::{parse_eq_type, parse_status};
+use crate::core::{Building, Equipment};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// Column mapping for `arx import csv`, relative to the project root.
+pub const EQUIPMENT_CSV_MAPPING: &str = ".arx/import/equipment-csv.yaml";
+
+/// Header for each equipment field; unset fields fall back to the export's names.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EquipmentColumns {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub equipment_type: Option<String>,
+    pub status: Option<String>,
+    /// Room id, name, or alias
+    pub room: Option<String>,
+    /// Floor level; narrows the room lookup, or places equipment on the floor itself
+    pub floor: Option<String>,
+    pub x: Option<String>,
+    pub y: Option<String>,
+    pub z: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EquipmentCsvMapping {
+    #[serde(default)]
+    pub columns: EquipmentColumns,
+    /// Equipment property key → header
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+}
+
+impl EquipmentCsvMapping {
+    /// [`EQUIPMENT_CSV_MAPPING`] under `base`, or the defaults when absent.
+    pub fn load_at(base: &Path) -> Result<Self, String> {
+        let path = base.join(EQUIPMENT_CSV_MAPPING);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load(&path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_yaml::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+    }
+}
+
+/// Why a row was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowIssueKind {
+    /// Missing name, unreadable number or status, no location
+    Invalid,
+    UnknownRoom,
+    /// Same name and location earlier in the file
+    Duplicate,
+    /// Same id, or same name in the same room, already in the model
+    Exists,
+}
+
+impl fmt::Display for RowIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowIssueKind::Invalid => write!(f, "invalid"),
+            RowIssueKind::UnknownRoom => write!(f, "unknown room"),
+            RowIssueKind::Duplicate => write!(f, "duplicate"),
+            RowIssueKind::Exists => write!(f, "already exists"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RowIssue {
+    /// 1-based line in the file (the header is line 1)
+    pub line: usize,
+    pub kind: RowIssueKind,
+    pub message: String,
+}
+
+/// A row that passed validation.
+#[derive(Debug, Clone)]
+pub struct PlannedEquipment {
+    pub line: usize,
+    pub floor_level: i32,
+    /// Target room; `None` puts the equipment on the floor
+    pub room_id: Option<String>,
+    pub room_name: Option<String>,
+    pub equipment: Equipment,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EquipmentImportPlan {
+    pub create: Vec<PlannedEquipment>,
+    pub issues: Vec<RowIssue>,
+}
+
+impl EquipmentImportPlan {
+    pub fn count(&self, kind: RowIssueKind) -> usize {
+        self.issues.iter().filter(|i| i.kind == kind).count()
+    }
+}
+
+/// Split CSV text into records (quoted fields, `""` escapes, CRLF, BOM).
+/// Blank lines are dropped; each record keeps its 1-based starting line.
+pub fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let (mut line, mut start) = (1, 1);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                let done = std::mem::take(&mut record);
+                if done.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((start, done));
+                }
+                line += 1;
+                start = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!(
+            "unterminated quoted field starting on line {}",
+            start
+        ));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push((start, record));
+    }
+    Ok(records)
+}
+
+/// Resolved column indexes for one file.
+struct Layout {
+    id: Option<usize>,
+    name: usize,
+    equipment_type: Option<usize>,
+    status: Option<usize>,
+    room: Option<usize>,
+    floor: Option<usize>,
+    xyz: [Option<usize>; 3],
+    properties: Vec<(String, usize)>,
+}
+
+impl Layout {
+    fn new(header: &[String], mapping: &EquipmentCsvMapping) -> Result<Self, String> {
+        let find = |name: &str| {
+            header
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+        };
+        // A mapped header must exist; defaults are tried in order
+        let column =
+            |mapped: &Option<String>, defaults: &[&str]| -> Result<Option<usize>, String> {
+                match mapped {
+                    Some(name) => find(name)
+                        .map(Some)
+                        .ok_or_else(|| format!("column '{}' not found in header", name)),
+                    None => Ok(defaults.iter().find_map(|d| find(d))),
+                }
+            };
+        let c = &mapping.columns;
+        let name = column(&c.name, &["name", "equipment", "equipment name"])?
+            .ok_or("no name column (map one with columns.name)")?;
+        let properties = mapping
+            .properties
+            .iter()
+            .map(|(key, header)| {
+                find(header)
+                    .map(|i| (key.clone(), i))
+                    .ok_or_else(|| format!("column '{}' not found in header", header))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            id: column(&c.id, &["id"])?,
+            name,
+            equipment_type: column(&c.equipment_type, &["type", "equipment_type"])?,
+            status: column(&c.status, &["status"])?,
+            room: column(&c.room, &["room_id", "room"])?,
+            floor: column(&c.floor, &["floor", "level"])?,
+            xyz: [
+                column(&c.x, &["x"])?,
+                column(&c.y, &["y"])?,
+                column(&c.z, &["z"])?,
+            ],
+            properties,
+        })
+    }
+}
+
+fn cell(record: &[String], index: Option<usize>) -> &str {
+    index
+        .and_then(|i| record.get(i))
+        .map(|s| s.trim())
+        .unwrap_or("")
+}
+
+/// Room `(floor level, id, name)` by id, then name or alias, optionally on one floor.
+pub(crate) fn resolve_room(
+    building: &Building,
+    needle: &str,
+    floor: Option<i32>,
+) -> Result<(i32, String, String), String> {
+    let rooms: Vec<_> = building
+        .floors
+        .iter()
+        .filter(|f| floor.is_none_or(|level| f.level == level))
+        .flat_map(|f| {
+            f.wings
+                .iter()
+                .flat_map(|w| w.rooms.iter())
+                .map(move |r| (f.level, r))
+        })
+        .collect();
+    if let Some((level, room)) = rooms.iter().find(|(_, r)| r.id == needle) {
+        return Ok((*level, room.id.clone(), room.name.clone()));
+    }
+    let matches: Vec<_> = rooms
+        .iter()
+        .filter(|(_, r)| r.matches_name(needle))
+        .collect();
+    match matches.as_slice() {
+        [(level, room)] => Ok((*level, room.id.clone(), room.name.clone())),
+        [] => Err(format!("room '{}' not found", needle)),
+        _ => Err(format!(
+            "room '{}' is on {} floors; add a floor column",
+            needle,
+            matches.len()
+        )),
+    }
+}
+
+/// Validate `text` against `building` and build the equipment each good row creates.
+pub fn plan_equipment_import(
+    building: &Building,
+    text: &str,
+    mapping: &EquipmentCsvMapping,
+) -> Result<EquipmentImportPlan, String> {
+    let mut records = parse_csv(text)?.into_iter();
+    let (_, header) = records.next().ok_or("CSV file is empty")?;
+    let layout = Layout::new(&header, mapping)?;
+
+    let existing_ids: HashSet<&str> = building
+        .get_all_equipment()
+        .into_iter()
+        .map(|e| e.id.as_str())
+        .collect();
+    let mut plan = EquipmentImportPlan::default();
+    // (lowercased name, room id or floor) → first line
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+
+    for (line, record) in records {
+        let mut issue = |kind, message: String| {
+            plan.issues.push(RowIssue {
+                line,
+                kind,
+                message,
+            })
+        };
+        let name = cell(&record, Some(layout.name));
+        if name.is_empty() {
+            issue(RowIssueKind::Invalid, "name is empty".into());
+            continue;
+        }
+
+        let floor_cell = cell(&record, layout.floor);
+        let floor = match floor_cell {
+            "" => None,
+            s => match s.parse::<i32>() {
+                Ok(level) if building.floors.iter().any(|f| f.level == level) => Some(level),
+                Ok(level) => {
+                    issue(
+                        RowIssueKind::UnknownRoom,
+                        format!("{}: no floor {}", name, level),
+                    );
+                    continue;
+                }
+                Err(_) => {
+                    issue(
+                        RowIssueKind::Invalid,
+                        format!("{}: floor '{}' is not a level", name, s),
+                    );
+                    continue;
+                }
+            },
+        };
+        let room_cell = cell(&record, layout.room);
+        let (floor_level, room) = match (room_cell, floor) {
+            ("", Some(level)) => (level, None),
+            ("", None) => {
+                issue(RowIssueKind::Invalid, format!("{}: no room or floor", name));
+                continue;
+            }
+            (needle, floor) => match resolve_room(building, needle, floor) {
+                Ok((level, id, room_name)) => (level, Some((id, room_name))),
+                Err(e) => {
+                    issue(RowIssueKind::UnknownRoom, format!("{}: {}", name, e));
+                    continue;
+                }
+            },
+        };
+        let location = room
+            .as_ref()
+            .map(|(id, _)| id.clone())
+            .unwrap_or_else(|| format!("floor {}", floor_level));
+
+        let key = (name.to_lowercase(), location.clone());
+        if let Some(first) = seen.get(&key) {
+            issue(
+                RowIssueKind::Duplicate,
+                format!("{}: same name and location as line {}", name, first),
+            );
+            continue;
+        }
+        seen.insert(key, line);
+
+        let id = cell(&record, layout.id);
+        let in_model = if !id.is_empty() && existing_ids.contains(id) {
+            Some(format!("{}: id {} is already in the model", name, id))
+        } else {
+            let existing = match &room {
+                Some((room_id, _)) => building
+                    .floors
+                    .iter()
+                    .flat_map(|f| f.wings.iter().flat_map(|w| w.rooms.iter()))
+                    .find(|r| &r.id == room_id)
+                    .map(|r| {
+                        r.equipment
+                            .iter()
+                            .any(|e| e.name.eq_ignore_ascii_case(name))
+                    }),
+                None => building
+                    .floors
+                    .iter()
+                    .find(|f| f.level == floor_level)
+                    .map(|f| {
+                        f.equipment
+                            .iter()
+                            .any(|e| e.name.eq_ignore_ascii_case(name))
+                    }),
+            };
+            existing
+                .unwrap_or(false)
+                .then(|| format!("{}: already in {}", name, location))
+        };
+        if let Some(message) = in_model {
+            issue(RowIssueKind::Exists, message);
+            continue;
+        }
+
+        let equipment_type = match cell(&record, layout.equipment_type) {
+            "" => crate::core::EquipmentType::Other("Unknown".into()),
+            s => parse_eq_type(s).map_err(|e| e.to_string())?,
+        };
+        let mut equipment = Equipment::new(name.to_string(), String::new(), equipment_type);
+        if !id.is_empty() {
+            equipment.id = id.to_string();
+        }
+        match cell(&record, layout.status) {
+            "" => {}
+            s => match parse_status(&s.replace(' ', "")) {
+                Ok(status) => equipment.status = status,
+                Err(e) => {
+                    issue(RowIssueKind::Invalid, format!("{}: {}", name, e));
+                    continue;
+                }
+            },
+        }
+        let mut bad_number = None;
+        for (axis, index) in layout.xyz.iter().enumerate() {
+            let value = cell(&record, *index);
+            if value.is_empty() {
+                continue;
+            }
+            match value.parse::<f64>() {
+                Ok(v) if v.is_finite() => match axis {
+                    0 => equipment.position.x = v,
+                    1 => equipment.position.y = v,
+                    _ => equipment.position.z = v,
+                },
+                _ => bad_number = Some(value.to_string()),
+            }
+        }
+        if let Some(value) = bad_number {
+            issue(
+                RowIssueKind::Invalid,
+                format!("{}: '{}' is not a coordinate", name, value),
+            );
+            continue;
+        }
+        for (key, index) in &layout.properties {
+            let value = cell(&record, Some(*index));
+            if !value.is_empty() {
+                equipment.properties.insert(key.clone(), value.to_string());
+            }
+        }
+        equipment.room_id = room.as_ref().map(|(id, _)| id.clone());
+
+        plan.create.push(PlannedEquipment {
+            line,
+            floor_level,
+            room_name: room.as_ref().map(|(_, n)| n.clone()),
+            room_id: room.map(|(id, _)| id),
+            equipment,
+        });
+    }
+    Ok(plan)
+}
+
+/// Add the planned equipment to `building`; returns how many were placed.
+pub fn apply_equipment_import(building: &mut Building, plan: &EquipmentImportPlan) -> usize {
+    let mut added = 0;
+    for row in &plan.create {
+        let placed = match &row.room_id {
+            Some(room_id) => building.find_room_mut(room_id).map(|room| {
+                room.add_equipment(row.equipment.clone());
+            }),
+            None => building
+                .floors
+                .iter_mut()
+                .find(|f| f.level == row.floor_level)
+                .map(|floor| floor.equipment.push(row.equipment.clone())),
+        };
+        if placed.is_some() {
+            added += 1;
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentStatus, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        for level in [0, 1] {
+            let mut floor = Floor::new(format!("Level {}", level), level);
+            let mut wing = Wing::new("A".into());
+            let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+            if level == 0 {
+                room.add_equipment(Equipment::new(
+                    "AHU-1".into(),
+                    String::new(),
+                    EquipmentType::HVAC,
+                ));
+            }
+            wing.add_room(room);
+            wing.add_room(Room::new(format!("Office {}", level), RoomType::Office));
+            floor.add_wing(wing);
+            building.add_floor(floor);
+        }
+        building
+    }
+
+    #[test]
+    fn test_parse_csv_handles_quotes_and_blank_lines() {
+        let records = parse_csv("\u{feff}a,b\r\n\"x, \"\"y\"\"\",\"two\nlines\"\n\n3,4").unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].1, vec!["x, \"y\"", "two\nlines"]);
+        assert_eq!(records[2], (5, vec!["3".to_string(), "4".to_string()]));
+        assert!(parse_csv("a\n\"open").is_err());
+    }
+
+    #[test]
+    fn test_plan_reports_problems_and_apply_places_rows() {
+        let csv = "Asset,Category,Location,Level,Condition,Serial\n\
+                   Pump 1,Plumbing,Office 0,,Out of Order,S-1\n\
+                   Pump 1,Plumbing,office 0,,,S-2\n\
+                   AHU-1,HVAC,Plant,0,,\n\
+                   Panel,Electrical,Plant,,,\n\
+                   Fan,HVAC,Roof,,,\n\
+                   ,HVAC,Plant,0,,\n\
+                   Extinguisher,Safety,,1,,\n";
+        let mapping: EquipmentCsvMapping = serde_yaml::from_str(
+            "columns:\n  name: Asset\n  type: Category\n  room: Location\n  floor: Level\n  status: Condition\nproperties:\n  serial_number: Serial\n",
+        )
+        .unwrap();
+        let mut building = building();
+        let plan = plan_equipment_import(&building, csv, &mapping).unwrap();
+
+        assert_eq!(plan.create.len(), 2);
+        assert_eq!(plan.count(RowIssueKind::Duplicate), 1);
+        assert_eq!(plan.count(RowIssueKind::Exists), 1);
+        // "Plant" is on both floors without a level; "Roof" does not exist
+        assert_eq!(plan.count(RowIssueKind::UnknownRoom), 2);
+        assert_eq!(plan.count(RowIssueKind::Invalid), 1);
+        assert_eq!(plan.issues[0].line, 3);
+
+        let pump = &plan.create[0].equipment;
+        assert_eq!(pump.status, EquipmentStatus::OutOfOrder);
+        assert_eq!(pump.properties["serial_number"], "S-1");
+        assert_eq!(plan.create[1].room_id, None);
+
+        assert_eq!(apply_equipment_import(&mut building, &plan), 2);
+        assert_eq!(building.get_all_equipment().len(), 3);
+        assert!(building.floors[1].equipment[0].name == "Extinguisher");
+    }
+}
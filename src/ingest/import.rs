@@ -7,6 +7,7 @@ use anyhow::{anyhow, Context, Result};
 use crate::core::{Building, BuildingMetadata};
 use crate::ifc::mapping::{merge_building_with_policy, FidelityLevel, LossReport, MergePolicy};
 use crate::ifc::IFCProcessor;
+use crate::spatial::lidar::downsampler::Decimation;
 use crate::spatial::lidar::LidarPipeline;
 use crate::validation::{validate_building, BuildingValidationReport};
 use crate::yaml::BuildingYamlSerializer;
@@ -127,6 +128,17 @@ pub fn import_ifc_path(
     existing_yaml: Option<&Path>,
     strict: bool,
     validate: bool,
+) -> Result<IngestResult> {
+    let existing = load_existing_yaml(existing_yaml)?;
+    import_ifc_onto(path, existing, strict, validate)
+}
+
+/// Parse IFC at `path`, optionally merge onto `existing`, validate.
+pub fn import_ifc_onto(
+    path: &Path,
+    existing: Option<crate::core::Building>,
+    strict: bool,
+    validate: bool,
 ) -> Result<IngestResult> {
     crate::resource_limits::check_file_size(
         path,
@@ -156,8 +168,6 @@ pub fn import_ifc_path(
         properties: Default::default(),
     });
 
-    let existing = load_existing_yaml(existing_yaml)?;
-
     let mut result = finalize_ingest(
         building,
         IngestSource::Ifc,
@@ -185,6 +195,7 @@ pub fn import_lidar_path(
     existing_yaml: Option<&Path>,
     voxel_size: f64,
     light_mode: bool,
+    decimation: Decimation,
     validate: bool,
 ) -> Result<IngestResult> {
     crate::resource_limits::check_file_size(
@@ -193,7 +204,7 @@ pub fn import_lidar_path(
         "LiDAR",
     )?;
 
-    let pipeline = LidarPipeline::new(voxel_size, light_mode);
+    let pipeline = LidarPipeline::new(voxel_size, light_mode).with_decimation(decimation);
     let building = pipeline
         .process(path)
         .with_context(|| format!("LiDAR pipeline failed for {}", path.display()))?;
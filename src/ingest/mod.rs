@@ -3,13 +3,18 @@
 //! All adapters (IFC, LiDAR, text/AR) should finish through this module
 //! so merge policy and validation stay consistent.
 
+pub mod ar_scan;
+pub mod bookings;
+pub mod equipment_csv;
 mod import;
+pub mod queue;
 mod sync;
 pub mod text;
 
 pub use import::{
-    finalize_ingest, import_ifc_path, import_lidar_path, IngestOptions, IngestResult, IngestSource,
+    finalize_ingest, import_ifc_onto, import_ifc_path, import_lidar_path, IngestOptions, IngestResult, IngestSource,
 };
+pub use queue::{FlushReport, QueuedEdit, SyncConflict, SyncQueue, STORAGE_KEY_SYNC_QUEUE};
 pub use sync::{
     apply_text_to_sync_json, building_to_envelope, merge_sync_json, BuildingSyncEnvelope,
     SyncSource, STORAGE_KEY_ACTIVE_BUILDING, STORAGE_KEY_LEGACY_BUILDING, SYNC_SCHEMA_VERSION,
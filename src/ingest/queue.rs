@@ -0,0 +1,385 @@
+//! Offline edit queue for field devices.
+//!
+//! While offline, a device applies text-DSL edits ([`super::text`]) to its
+//! local copy and also records them in a [`SyncQueue`] together with the
+//! values the edited fields had at the time. When connectivity returns,
+//! [`SyncQueue::flush`] replays the queue in order against the current shared
+//! building:
+//!
+//! - edits whose fields are unchanged upstream (or already hold the queued
+//!   value) are applied and leave the queue;
+//! - edits whose fields were changed upstream to something else, whose target
+//!   is gone, or that no longer apply are kept and reported as conflicts.
+//!
+//! Conflicts are settled with [`SyncQueue::resolve`] (keep mine = force on the
+//! next flush, keep theirs = drop the edit). The queue serializes to JSON so
+//! the PWA can keep it in localStorage next to the building envelope.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::clock;
+use crate::core::{Building, Equipment, Room, PROP_REVIEW_STATUS};
+use crate::ingest::text::{apply_text_edits, parse_text_line, TextEdit};
+
+/// localStorage key for the pending offline edit queue.
+pub const STORAGE_KEY_SYNC_QUEUE: &str = "arxos_sync_queue_v1";
+
+/// Why a queued edit was not applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyncConflict {
+    /// The room / equipment the edit targets no longer exists
+    Missing { target: String },
+    /// Someone else changed the same field while the device was offline
+    ChangedUpstream {
+        field: String,
+        base: Option<String>,
+        upstream: Option<String>,
+    },
+    /// The edit fails against the current building
+    Rejected { reason: String },
+}
+
+impl std::fmt::Display for SyncConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "(unset)".to_string());
+        match self {
+            SyncConflict::Missing { target } => write!(f, "{} no longer exists", target),
+            SyncConflict::ChangedUpstream {
+                field,
+                base,
+                upstream,
+            } => write!(
+                f,
+                "{} changed upstream ({} → {})",
+                field,
+                show(base),
+                show(upstream)
+            ),
+            SyncConflict::Rejected { reason } => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// One pending edit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedEdit {
+    pub id: String,
+    /// One text-DSL command
+    pub command: String,
+    pub queued_at: DateTime<Utc>,
+    /// Field values the edit was made against (`room:Lab.finish` → value)
+    #[serde(default)]
+    pub base: BTreeMap<String, Option<String>>,
+    /// Apply even if upstream changed (set by resolving "keep mine")
+    #[serde(default)]
+    pub force: bool,
+    /// Outcome of the last flush, if it was not applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<SyncConflict>,
+}
+
+/// Result of one [`SyncQueue::flush`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlushReport {
+    /// Ids of edits applied (and removed from the queue)
+    pub applied: Vec<String>,
+    /// Edits still queued with their conflict
+    pub conflicts: Vec<QueuedEdit>,
+}
+
+/// Ordered queue of offline edits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncQueue {
+    #[serde(default)]
+    pub edits: Vec<QueuedEdit>,
+}
+
+impl SyncQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Queue every command in `script` (one per line, `#` comments skipped),
+    /// recording the current values from `local` — the device's copy before
+    /// the edit. Returns the new edit ids.
+    pub fn enqueue(&mut self, local: &Building, script: &str) -> Result<Vec<String>, String> {
+        let mut parsed = Vec::new();
+        for line in script.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let edit = parse_text_line(line).map_err(|e| format!("'{}': {}", line, e))?;
+            parsed.push((line.to_string(), edit));
+        }
+        let mut ids = Vec::new();
+        for (command, edit) in parsed {
+            let id = clock::new_id();
+            self.edits.push(QueuedEdit {
+                id: id.clone(),
+                command,
+                queued_at: clock::now(),
+                base: observed_fields(local, &edit).unwrap_or_default(),
+                force: false,
+                conflict: None,
+            });
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Replay queued edits, in order, onto `building`. Applied edits leave the
+    /// queue; the rest stay with their conflict recorded.
+    pub fn flush(&mut self, building: &mut Building) -> FlushReport {
+        let mut report = FlushReport::default();
+        let mut pending = Vec::new();
+        for mut queued in std::mem::take(&mut self.edits) {
+            match replay(building, &queued) {
+                Ok(()) => report.applied.push(queued.id),
+                Err(conflict) => {
+                    queued.conflict = Some(conflict);
+                    report.conflicts.push(queued.clone());
+                    pending.push(queued);
+                }
+            }
+        }
+        self.edits = pending;
+        report
+    }
+
+    /// Edits that failed their last flush.
+    pub fn conflict_report(&self) -> Vec<&QueuedEdit> {
+        self.edits.iter().filter(|e| e.conflict.is_some()).collect()
+    }
+
+    /// Settle a conflict: `keep_mine` forces the edit on the next flush,
+    /// otherwise it is dropped. Returns false for an unknown id.
+    pub fn resolve(&mut self, id: &str, keep_mine: bool) -> bool {
+        let Some(i) = self.edits.iter().position(|e| e.id == id) else {
+            return false;
+        };
+        if keep_mine {
+            self.edits[i].force = true;
+            self.edits[i].conflict = None;
+        } else {
+            self.edits.remove(i);
+        }
+        true
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        if json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(json).map_err(|e| format!("invalid sync queue JSON: {}", e))
+    }
+}
+
+fn replay(building: &mut Building, queued: &QueuedEdit) -> Result<(), SyncConflict> {
+    let edit = parse_text_line(&queued.command).map_err(|e| SyncConflict::Rejected {
+        reason: e.to_string(),
+    })?;
+    let upstream = match observed_fields(building, &edit) {
+        Ok(fields) => fields,
+        Err(target) if !queued.force => return Err(SyncConflict::Missing { target }),
+        Err(_) => BTreeMap::new(),
+    };
+    let mut trial = building.clone();
+    apply_text_edits(&mut trial, std::slice::from_ref(&edit)).map_err(|e| {
+        SyncConflict::Rejected {
+            reason: e.to_string(),
+        }
+    })?;
+    if !queued.force {
+        let ours = observed_fields(&trial, &edit).unwrap_or_default();
+        for (field, base) in &queued.base {
+            let now = upstream.get(field).cloned().flatten();
+            let mine = ours.get(field).cloned().flatten();
+            if now != *base && now != mine {
+                return Err(SyncConflict::ChangedUpstream {
+                    field: field.clone(),
+                    base: base.clone(),
+                    upstream: now,
+                });
+            }
+        }
+    }
+    *building = trial;
+    Ok(())
+}
+
+/// Exact name first, then case-insensitive name / alias (as the text DSL does).
+fn find_room<'a>(building: &'a Building, name: &str) -> Option<&'a Room> {
+    let rooms = building.get_all_rooms();
+    rooms
+        .iter()
+        .find(|r| r.name == name)
+        .or_else(|| rooms.iter().find(|r| r.matches_name(name)))
+        .copied()
+}
+
+fn find_equipment<'a>(building: &'a Building, name: &str) -> Option<&'a Equipment> {
+    let all = building.get_all_equipment();
+    all.iter()
+        .find(|e| e.name == name)
+        .or_else(|| all.iter().find(|e| e.matches_name(name)))
+        .copied()
+}
+
+fn json_value<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_string(value).ok()
+}
+
+/// Current values of the fields `edit` writes, keyed `room:<name>.<field>` /
+/// `equipment:<name>.<field>`. `Err(target)` when the target is missing.
+/// Adds have no base (duplicates surface as rejected edits); aliases are
+/// additive and never conflict.
+fn observed_fields(
+    building: &Building,
+    edit: &TextEdit,
+) -> Result<BTreeMap<String, Option<String>>, String> {
+    let mut out = BTreeMap::new();
+    match edit {
+        TextEdit::AddRoom { .. } | TextEdit::AddEquipment { .. } => {}
+        TextEdit::SetRoomProps { name, props } => {
+            let target = format!("room '{}'", name);
+            let room = find_room(building, name).ok_or(target)?;
+            for key in props.keys() {
+                let value = match key.as_str() {
+                    "alias" => continue,
+                    "type" => Some(room.room_type.to_string()),
+                    "pos" | "position" | "dims" | "dimensions" => {
+                        json_value(&room.spatial_properties)
+                    }
+                    "review_status" | "review" => room.properties.get(PROP_REVIEW_STATUS).cloned(),
+                    _ => room.properties.get(key).cloned(),
+                };
+                out.insert(format!("room:{}.{}", name, key), value);
+            }
+        }
+        TextEdit::SetEquipment {
+            name,
+            status,
+            props,
+        } => {
+            let target = format!("equipment '{}'", name);
+            let eq = find_equipment(building, name).ok_or(target)?;
+            if status.is_some() {
+                out.insert(
+                    format!("equipment:{}.status", name),
+                    Some(eq.status.to_string()),
+                );
+            }
+            for key in props.keys() {
+                let value = match key.as_str() {
+                    "alias" => continue,
+                    "status" => Some(eq.status.to_string()),
+                    "type" => Some(eq.equipment_type.to_string()),
+                    "pos" | "position" => json_value(&eq.position),
+                    "review_status" | "review" => eq.properties.get(PROP_REVIEW_STATUS).cloned(),
+                    _ => eq.properties.get(key).cloned(),
+                };
+                out.insert(format!("equipment:{}.{}", name, key), value);
+            }
+        }
+        TextEdit::RenameRoom { old_name, .. } => {
+            find_room(building, old_name).ok_or(format!("room '{}'", old_name))?;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::text::apply_text_script;
+
+    fn building() -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        apply_text_script(
+            &mut b,
+            "add room Lab floor=0\nadd room Office floor=0\nadd equipment AHU-1 room=Lab type=hvac",
+        )
+        .unwrap();
+        b
+    }
+
+    #[test]
+    fn test_flush_applies_clean_edits_and_reports_conflicts() {
+        let server = building();
+        let mut device = server.clone();
+        let mut queue = SyncQueue::new();
+
+        // Offline on the device
+        let script = "set room Lab finish=epoxy\nset room Office finish=carpet\n\
+                      set equipment AHU-1 status=maintenance\nrename room Gone Nowhere";
+        assert!(queue.enqueue(&device, "bogus command").is_err());
+        queue.enqueue(&device, script).unwrap();
+        apply_text_script(&mut device, "set room Lab finish=epoxy").unwrap();
+        assert_eq!(queue.len(), 4);
+
+        // Meanwhile upstream: same value for Lab, a different one for Office
+        let mut upstream = server;
+        apply_text_script(
+            &mut upstream,
+            "set room Lab finish=epoxy\nset room Office finish=tile",
+        )
+        .unwrap();
+
+        let restored = SyncQueue::from_json(&queue.to_json().unwrap()).unwrap();
+        let mut queue = restored;
+        let report = queue.flush(&mut upstream);
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(report.conflicts.len(), 2);
+        let eq = upstream
+            .get_all_equipment()
+            .into_iter()
+            .find(|e| e.name == "AHU-1")
+            .unwrap();
+        assert_eq!(eq.status.to_string(), "Maintenance");
+        assert_eq!(
+            queue.conflict_report()[0].conflict,
+            Some(SyncConflict::ChangedUpstream {
+                field: "room:Office.finish".into(),
+                base: None,
+                upstream: Some("tile".into()),
+            })
+        );
+        assert!(matches!(
+            queue.conflict_report()[1].conflict,
+            Some(SyncConflict::Missing { .. })
+        ));
+
+        // Keep mine for Office, drop the rename
+        let office = queue.edits[0].id.clone();
+        let rename = queue.edits[1].id.clone();
+        assert!(queue.resolve(&office, true));
+        assert!(queue.resolve(&rename, false));
+        let report = queue.flush(&mut upstream);
+        assert_eq!((report.applied.len(), queue.len()), (1, 0));
+        let office = upstream
+            .get_all_rooms()
+            .into_iter()
+            .find(|r| r.name == "Office")
+            .unwrap();
+        assert_eq!(
+            office.properties.get("finish").map(String::as_str),
+            Some("carpet")
+        );
+    }
+}
@@ -40,6 +40,8 @@ pub enum SyncSource {
     Text,
     Merge,
     Wasm,
+    /// `building.yaml` from a project repo (agent download or file pick)
+    Yaml,
 }
 
 impl From<IngestSource> for SyncSource {
@@ -102,6 +104,14 @@ impl BuildingSyncEnvelope {
             vec!["legacy bare Building JSON".into()],
         ))
     }
+
+    /// Envelope for a project's `building.yaml` text (same reader as the CLI,
+    /// so floor equipment is rehydrated into rooms).
+    pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+        let building = crate::yaml::BuildingYamlSerializer::deserialize_building(yaml)
+            .map_err(|e| format!("invalid building YAML: {}", e))?;
+        Ok(building_to_envelope(building, SyncSource::Yaml))
+    }
 }
 
 /// Wrap a building after validation into a sync envelope.
@@ -201,4 +211,20 @@ mod tests {
         assert_eq!(env.building.name, "Legacy");
         assert_eq!(env.schema_version, SYNC_SCHEMA_VERSION);
     }
+
+    #[test]
+    fn envelope_from_building_yaml() {
+        let mut b = Building::new("Yard".into(), "/yard".into());
+        let mut floor = Floor::new("F1".into(), 1);
+        let mut wing = Wing::new("North".into());
+        wing.add_room(Room::new("Shop".into(), RoomType::Storage));
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        let yaml = crate::yaml::BuildingYamlSerializer::serialize_building(&b).unwrap();
+
+        let env = BuildingSyncEnvelope::from_yaml(&yaml).unwrap();
+        assert_eq!(env.source, SyncSource::Yaml);
+        assert_eq!(env.building.floors[0].wings[0].rooms[0].name, "Shop");
+        assert!(BuildingSyncEnvelope::from_yaml("building: [").is_err());
+    }
 }
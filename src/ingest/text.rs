@@ -11,6 +11,9 @@
 //! set room <name> <key>=<value> [...]
 //! set equipment <name> status=<status> | <key>=<value>
 //! rename room <old_name> <new_name>
+//! # Aliases: extra display names; names above match the primary name or any alias
+//! set room <name> alias="<alias>"
+//! set equipment <name> alias="<alias>"
 //! # Review (Track C1): review_status=proposed|accepted|rejected
 //! set room <name> review_status=accepted
 //! set equipment <name> review_status=rejected
@@ -21,6 +24,8 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, bail, Context, Result};
 
+use crate::core::clock;
+use crate::core::runtime::set_status;
 use crate::core::{
     Building, Dimensions, Equipment, EquipmentStatus, EquipmentType, Floor, Position, Room,
     RoomType, Wing,
@@ -188,6 +193,7 @@ fn apply_one(building: &mut Building, edit: &TextEdit, report: &mut TextEditRepo
                     "type" => {
                         room.room_type = parse_room_type(v)?;
                     }
+                    "alias" => room.add_alias(v),
                     "pos" | "position" => {
                         room.spatial_properties.position = parse_position(v, COORD_BUILDING_LOCAL)?;
                     }
@@ -223,15 +229,16 @@ fn apply_one(building: &mut Building, edit: &TextEdit, report: &mut TextEditRepo
             let eq = find_equipment_mut(building, name)
                 .ok_or_else(|| anyhow!("equipment '{}' not found", name))?;
             if let Some(s) = status {
-                eq.status = *s;
+                set_status(eq, *s, clock::now());
             }
             for (k, v) in props {
                 match k.as_str() {
-                    "status" => eq.status = parse_status(v)?,
+                    "status" => set_status(eq, parse_status(v)?, clock::now()),
                     "pos" | "position" => {
                         eq.position = parse_position(v, COORD_BUILDING_LOCAL)?;
                     }
                     "type" => eq.equipment_type = parse_eq_type(v)?,
+                    "alias" => eq.add_alias(v),
                     "review_status" | "review" => {
                         let status = crate::core::ReviewStatus::parse(v).ok_or_else(|| {
                             anyhow!(
@@ -290,34 +297,34 @@ fn ensure_floor<'a>(building: &'a mut Building, floor_key: &str) -> Result<&'a m
     Ok(building.floors.last_mut().unwrap())
 }
 
+/// Exact name first, then case-insensitive name / alias.
 fn find_room_mut<'a>(building: &'a mut Building, name: &str) -> Option<&'a mut Room> {
-    for floor in &mut building.floors {
-        for wing in &mut floor.wings {
-            if let Some(r) = wing.rooms.iter_mut().find(|r| r.name == name) {
-                return Some(r);
-            }
-        }
-    }
-    None
+    let rooms = || {
+        building
+            .floors
+            .iter()
+            .flat_map(|f| f.wings.iter().flat_map(|w| w.rooms.iter()))
+    };
+    let id = rooms()
+        .find(|r| r.name == name)
+        .or_else(|| rooms().find(|r| r.matches_name(name)))
+        .map(|r| r.id.clone())?;
+    building.find_room_mut(&id)
 }
 
+/// Exact name first, then case-insensitive name / alias.
 fn find_equipment_mut<'a>(building: &'a mut Building, name: &str) -> Option<&'a mut Equipment> {
-    for floor in &mut building.floors {
-        if let Some(e) = floor.equipment.iter_mut().find(|e| e.name == name) {
-            return Some(e);
-        }
-        for wing in &mut floor.wings {
-            if let Some(e) = wing.equipment.iter_mut().find(|e| e.name == name) {
-                return Some(e);
-            }
-            for room in &mut wing.rooms {
-                if let Some(e) = room.equipment.iter_mut().find(|e| e.name == name) {
-                    return Some(e);
-                }
-            }
-        }
-    }
-    None
+    let id = {
+        let all = building.get_all_equipment();
+        all.iter()
+            .find(|e| e.name == name)
+            .or_else(|| all.iter().find(|e| e.matches_name(name)))
+            .map(|e| e.id.clone())?
+    };
+    building
+        .get_all_equipment_mut()
+        .into_iter()
+        .find(|e| e.id == id)
 }
 
 // --- parsing helpers ---
@@ -500,7 +507,7 @@ fn parse_room_type(s: &str) -> Result<RoomType> {
     })
 }
 
-fn parse_eq_type(s: &str) -> Result<EquipmentType> {
+pub(crate) fn parse_eq_type(s: &str) -> Result<EquipmentType> {
     Ok(match s.trim().to_ascii_lowercase().as_str() {
         "hvac" => EquipmentType::HVAC,
         "electrical" => EquipmentType::Electrical,
@@ -513,7 +520,7 @@ fn parse_eq_type(s: &str) -> Result<EquipmentType> {
     })
 }
 
-fn parse_status(s: &str) -> Result<EquipmentStatus> {
+pub(crate) fn parse_status(s: &str) -> Result<EquipmentStatus> {
     Ok(match s.trim().to_ascii_lowercase().as_str() {
         "active" => EquipmentStatus::Active,
         "inactive" => EquipmentStatus::Inactive,
@@ -568,4 +575,31 @@ mod tests {
         apply_text_script(&mut b, "rename room Old New").unwrap();
         assert_eq!(b.floors[0].wings[0].rooms[0].name, "New");
     }
+
+    #[test]
+    fn aliases_resolve_rooms_and_equipment() {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        apply_text_script(
+            &mut b,
+            r#"
+            add room R-2.113 floor=2 type=office
+            set room R-2.113 alias="the fishbowl"
+            add equipment vav-7 room="The Fishbowl" type=hvac
+            set equipment vav-7 alias=VAV-2-07
+            set equipment vav-2-07 status=maintenance
+            "#,
+        )
+        .unwrap();
+        let room = &b.floors[0].wings[0].rooms[0];
+        assert_eq!(room.name, "R-2.113");
+        assert_eq!(room.aliases, vec!["the fishbowl"]);
+        assert_eq!(room.equipment[0].aliases, vec!["VAV-2-07"]);
+        assert_eq!(room.equipment[0].status, EquipmentStatus::Maintenance);
+
+        // Aliases survive the YAML round trip
+        let yaml = serde_yaml::to_string(room).unwrap();
+        assert!(yaml.contains("aliases:"));
+        let back: Room = serde_yaml::from_str(&yaml).unwrap();
+        assert!(back.matches_name("THE FISHBOWL"));
+    }
 }
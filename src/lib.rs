@@ -18,6 +18,7 @@ pub mod export;
 pub mod git;
 pub mod ifc;
 pub mod ingest;
+pub mod notifications;
 pub mod persistence;
 pub mod resource_limits;
 pub mod spatial;
@@ -35,6 +36,15 @@ pub mod tui;
 #[cfg(feature = "agent")]
 pub mod agent;
 
+#[cfg(feature = "agent")]
+pub mod devtools;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
+#[cfg(any(feature = "bacnet", feature = "modbus", feature = "mqtt"))]
+pub mod hardware;
+
 #[cfg(feature = "blockchain")]
 pub mod blockchain;
 
@@ -3,15 +3,23 @@
 //! A unified command-line tool for building data management, 3D visualization,
 //! and collaborative workflows using Git as the foundation.
 
-use arxos::cli::Cli;
-use clap::Parser;
+use arxos::cli::{audit_invocation, command_path, Cli};
+use clap::{CommandFactory, FromArgMatches};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let raw_output = cli.raw_output();
+    let action = command_path(&matches);
 
-    match cli.execute() {
+    let result = cli.execute();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    audit_invocation(&action, &args, &result);
+    match result {
         Ok(()) => {
-            println!("✅ Command completed successfully");
+            if !raw_output {
+                println!("✅ Command completed successfully");
+            }
             Ok(())
         }
         Err(e) => {
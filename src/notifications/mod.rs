@@ -0,0 +1,410 @@
+//! Webhook notifications for repository and alert events.
+//!
+//! Webhooks are listed in [`NOTIFICATIONS_FILE`] (`.arx/notifications.yaml`);
+//! each receives the events it subscribes to, formatted for Slack, Microsoft
+//! Teams, or as plain JSON:
+//!
+//! ```yaml
+//! webhooks:
+//!   - name: ops
+//!     format: slack                      # json (default) | slack | teams
+//!     url_env: ARX_SLACK_WEBHOOK         # or url: https://...
+//!     events: [alert, validation_failed] # default: all
+//!   - name: audit
+//!     url: https://hooks.example.com/arx
+//! retry:
+//!   attempts: 3
+//!   backoff_ms: 500                      # doubles after each failure
+//!   timeout_secs: 10
+//! ```
+//!
+//! Events are raised where the change happens: commits in `git::commit`,
+//! saves refused by validation and failing `arx validate`, equipment entering
+//! warning or critical when `building.yaml` is saved, and AR detections
+//! queued for review. Delivery is best-effort: failures are logged and never
+//! fail the operation that raised the event. Nothing is sent during a dry
+//! run; `arx notify test` checks the configuration.
+
+pub mod webhook;
+
+pub use webhook::{Delivery, WebhookFormat};
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::operations::is_dry_run;
+use crate::core::{clock, Building};
+use crate::export::embed::{equipment_state, RoomState};
+
+/// Repo-relative webhook configuration.
+pub const NOTIFICATIONS_FILE: &str = ".arx/notifications.yaml";
+
+/// Events a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Commit,
+    ValidationFailed,
+    Alert,
+    ArPending,
+}
+
+fn all_events() -> Vec<EventKind> {
+    vec![
+        EventKind::Commit,
+        EventKind::ValidationFailed,
+        EventKind::Alert,
+        EventKind::ArPending,
+    ]
+}
+
+/// Something worth telling people about.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    Commit {
+        commit: String,
+        message: String,
+        author: String,
+    },
+    ValidationFailed {
+        /// What was validated (`save`, `arx validate`)
+        source: String,
+        errors: Vec<String>,
+    },
+    /// Equipment entered warning or critical
+    Alert {
+        equipment_id: String,
+        name: String,
+        room: Option<String>,
+        state: String,
+        previous: String,
+    },
+    /// AR detections queued for review
+    ArPending { count: usize, items: Vec<String> },
+    /// Sent by `arx notify test` to every webhook
+    Test,
+}
+
+impl Event {
+    /// Subscription the event belongs to; `None` reaches every webhook.
+    pub fn kind(&self) -> Option<EventKind> {
+        match self {
+            Event::Commit { .. } => Some(EventKind::Commit),
+            Event::ValidationFailed { .. } => Some(EventKind::ValidationFailed),
+            Event::Alert { .. } => Some(EventKind::Alert),
+            Event::ArPending { .. } => Some(EventKind::ArPending),
+            Event::Test => None,
+        }
+    }
+
+    /// One line for chat webhooks.
+    pub fn summary(&self, project: &str) -> String {
+        let text = match self {
+            Event::Commit {
+                commit,
+                message,
+                author,
+            } => format!(
+                "{} committed {}: {}",
+                author,
+                &commit[..commit.len().min(8)],
+                message.lines().next().unwrap_or("")
+            ),
+            Event::ValidationFailed { source, errors } => format!(
+                "Validation failed ({}, {} error(s)): {}",
+                source,
+                errors.len(),
+                errors.first().map(String::as_str).unwrap_or("")
+            ),
+            Event::Alert {
+                name,
+                room,
+                state,
+                previous,
+                ..
+            } => format!(
+                "{}{} is {} (was {})",
+                name,
+                room.as_ref()
+                    .map(|r| format!(" in {}", r))
+                    .unwrap_or_default(),
+                state,
+                previous
+            ),
+            Event::ArPending { count, items } => format!(
+                "{} AR detection(s) awaiting review: {}",
+                count,
+                items.join(", ")
+            ),
+            Event::Test => "Test notification from arx".to_string(),
+        };
+        format!("[{}] {}", project, text)
+    }
+}
+
+/// One webhook endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Environment variable holding the URL (keeps secrets out of Git)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_env: Option<String>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default = "all_events")]
+    pub events: Vec<EventKind>,
+}
+
+impl Webhook {
+    pub fn resolve_url(&self) -> Result<String, String> {
+        match (&self.url, &self.url_env) {
+            (Some(url), _) => Ok(url.clone()),
+            (None, Some(var)) => {
+                std::env::var(var).map_err(|_| format!("{}: {} is not set", self.name, var))
+            }
+            (None, None) => Err(format!("{}: url or url_env is required", self.name)),
+        }
+    }
+
+    pub fn wants(&self, event: &Event) -> bool {
+        event.kind().is_none_or(|kind| self.events.contains(&kind))
+    }
+}
+
+/// Delivery attempts per event and webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+    /// Pause after the first failure; doubles each retry
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+    /// Per-request timeout
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_attempts() -> u32 {
+    3
+}
+
+fn default_backoff_ms() -> u64 {
+    500
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: default_attempts(),
+            backoff_ms: default_backoff_ms(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+/// Contents of `.arx/notifications.yaml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+impl NotificationConfig {
+    /// The config under `base`, or `None` when notifications are not set up.
+    pub fn load_at(base: &Path) -> Result<Option<Self>, String> {
+        let path = base.join(NOTIFICATIONS_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", NOTIFICATIONS_FILE, e))?;
+        let config: Self = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", NOTIFICATIONS_FILE, e))?;
+        config
+            .validate()
+            .map_err(|e| format!("Invalid {}: {}", NOTIFICATIONS_FILE, e))?;
+        Ok(Some(config))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names = std::collections::HashSet::new();
+        for webhook in &self.webhooks {
+            if webhook.name.trim().is_empty() {
+                return Err("webhook without a name".to_string());
+            }
+            if !names.insert(webhook.name.as_str()) {
+                return Err(format!("duplicate webhook '{}'", webhook.name));
+            }
+            if webhook.url.is_none() && webhook.url_env.is_none() {
+                return Err(format!("{}: url or url_env is required", webhook.name));
+            }
+        }
+        if self.retry.attempts == 0 || self.retry.timeout_secs == 0 {
+            return Err("retry attempts and timeout_secs must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn project_name(base: &Path) -> String {
+    base.canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "building".to_string())
+}
+
+/// Send `event` to the webhooks that want it (or only `only`), returning
+/// one [`Delivery`] per webhook tried.
+pub fn send(
+    base: &Path,
+    config: &NotificationConfig,
+    event: &Event,
+    only: Option<&str>,
+) -> Vec<Delivery> {
+    let project = project_name(base);
+    let at = clock::now().to_rfc3339();
+    config
+        .webhooks
+        .iter()
+        .filter(|w| only.map_or(w.wants(event), |name| w.name == name))
+        .map(|webhook| {
+            let body = webhook::payload(webhook.format, &project, &at, event).to_string();
+            let (attempts, error) = match webhook.resolve_url() {
+                Ok(url) => webhook::with_retries(&config.retry, || {
+                    webhook::post(&url, &body, config.retry.timeout_secs)
+                }),
+                Err(e) => (0, Some(e)),
+            };
+            Delivery {
+                webhook: webhook.name.clone(),
+                attempts,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Best-effort delivery of `events`; failures are logged, not returned.
+pub fn notify(base: &Path, events: &[Event]) {
+    if events.is_empty() || is_dry_run() {
+        return;
+    }
+    let config = match NotificationConfig::load_at(base) {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("Notifications skipped: {}", e);
+            return;
+        }
+    };
+    for event in events {
+        for delivery in send(base, &config, event, None) {
+            if let Some(error) = delivery.error {
+                log::warn!(
+                    "Webhook {} failed after {} attempt(s): {}",
+                    delivery.webhook,
+                    delivery.attempts,
+                    error
+                );
+            }
+        }
+    }
+}
+
+fn is_alert(state: RoomState) -> bool {
+    state <= RoomState::Warning
+}
+
+/// Equipment that entered (or worsened within) warning / critical between
+/// two versions of a building. Nothing is reported without a previous
+/// version, so first imports do not page anyone.
+pub fn alerts_between(before: Option<&Building>, after: &Building) -> Vec<Event> {
+    let Some(before) = before else {
+        return Vec::new();
+    };
+    let previous: std::collections::HashMap<&str, RoomState> = before
+        .get_all_equipment()
+        .into_iter()
+        .map(|eq| (eq.id.as_str(), equipment_state(eq)))
+        .collect();
+    after
+        .get_all_equipment()
+        .into_iter()
+        .filter_map(|eq| {
+            let state = equipment_state(eq);
+            let was = previous
+                .get(eq.id.as_str())
+                .copied()
+                .unwrap_or(RoomState::Ok);
+            (is_alert(state) && state < was).then(|| Event::Alert {
+                equipment_id: eq.id.clone(),
+                name: eq.name.clone(),
+                room: eq
+                    .room_id
+                    .as_deref()
+                    .and_then(|id| after.find_room(id))
+                    .map(|r| r.name.clone()),
+                state: state.as_str().to_string(),
+                previous: was.as_str().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentHealthStatus, EquipmentType, Floor};
+
+    #[test]
+    fn test_alerts_between_reports_new_warnings_only() {
+        let mut before = Building::new("HQ".into(), String::new());
+        let mut floor = Floor::new("Ground".into(), 0);
+        for name in ["AHU-1", "AHU-2", "AHU-3"] {
+            floor.equipment.push(Equipment::new(
+                name.into(),
+                String::new(),
+                EquipmentType::HVAC,
+            ));
+        }
+        floor.equipment[2].health_status = Some(EquipmentHealthStatus::Warning);
+        before.add_floor(floor);
+
+        let mut after = before.clone();
+        after.floors[0].equipment[0].health_status = Some(EquipmentHealthStatus::Critical);
+        after.floors[0].equipment[2].health_status = Some(EquipmentHealthStatus::Warning);
+        assert!(alerts_between(None, &after).is_empty());
+        let alerts = alerts_between(Some(&before), &after);
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(
+            &alerts[0],
+            Event::Alert { name, state, previous, .. }
+                if name == "AHU-1" && state == "critical" && previous == "ok"
+        ));
+
+        let config: NotificationConfig = serde_yaml::from_str(
+            "webhooks:\n  - name: ops\n    url: http://x\n    events: [alert]\n",
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert!(config.webhooks[0].wants(&alerts[0]));
+        assert!(config.webhooks[0].wants(&Event::Test));
+        assert!(!config.webhooks[0].wants(&Event::ArPending {
+            count: 1,
+            items: vec![]
+        }));
+        let missing: NotificationConfig =
+            serde_yaml::from_str("webhooks:\n  - name: ops\n").unwrap();
+        assert!(missing.validate().is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Webhook payloads and delivery.
+//!
+//! Requests go through `curl` like `persistence::s3`; the URL (which for
+//! Slack and Teams is itself the credential) and the body are passed on
+//! stdin rather than the command line.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use super::{Event, RetryPolicy};
+
+/// How a webhook expects its body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// The event's fields plus `project`, `at`, and `summary`
+    #[default]
+    Json,
+    /// Slack incoming webhook (`text`)
+    Slack,
+    /// Microsoft Teams incoming webhook (MessageCard)
+    Teams,
+}
+
+/// Outcome of sending one event to one webhook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delivery {
+    pub webhook: String,
+    pub attempts: u32,
+    /// Last failure when every attempt failed
+    pub error: Option<String>,
+}
+
+/// Request body for `event` in `format`.
+pub fn payload(format: WebhookFormat, project: &str, at: &str, event: &Event) -> Value {
+    let summary = event.summary(project);
+    match format {
+        WebhookFormat::Json => {
+            let mut body = serde_json::to_value(event).unwrap_or_else(|_| json!({}));
+            if let Value::Object(map) = &mut body {
+                map.insert("project".into(), json!(project));
+                map.insert("at".into(), json!(at));
+                map.insert("summary".into(), json!(summary));
+            }
+            body
+        }
+        WebhookFormat::Slack => json!({ "text": summary }),
+        WebhookFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": summary,
+            "title": format!("ArxOS: {}", project),
+            "text": summary,
+        }),
+    }
+}
+
+/// Call `send` until it succeeds or the policy's attempts run out, doubling
+/// the pause after each failure. Returns the attempts made and the last error.
+pub fn with_retries(
+    policy: &RetryPolicy,
+    mut send: impl FnMut() -> Result<(), String>,
+) -> (u32, Option<String>) {
+    let mut backoff = Duration::from_millis(policy.backoff_ms);
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match send() {
+            Ok(()) => return (attempts, None),
+            Err(e) if attempts >= policy.attempts.max(1) => return (attempts, Some(e)),
+            Err(_) => {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+        }
+    }
+}
+
+/// Quote `s` for a curl config file.
+pub(crate) fn config_string(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("\"{}\"", escaped)
+}
+
+/// POST `body` as JSON to `url`; non-2xx responses are errors.
+pub fn post(url: &str, body: &str, timeout_secs: u64) -> Result<(), String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--config", "-"])
+        .args(["--max-time", &timeout_secs.to_string()])
+        .args(["--header", "Content-Type: application/json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("running curl (required for webhooks): {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "url = {}", config_string(url))
+            .and_then(|_| writeln!(stdin, "data-binary = {}", config_string(body)))
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_formats() {
+        let event = Event::ArPending {
+            count: 2,
+            items: vec!["AHU-1".into(), "VAV-2".into()],
+        };
+        let json = payload(WebhookFormat::Json, "hq", "2026-01-01T00:00:00Z", &event);
+        assert_eq!(json["event"], "ar_pending");
+        assert_eq!(json["count"], 2);
+        assert_eq!(json["project"], "hq");
+        let slack = payload(WebhookFormat::Slack, "hq", "", &event);
+        assert_eq!(
+            slack["text"],
+            "[hq] 2 AR detection(s) awaiting review: AHU-1, VAV-2"
+        );
+        let teams = payload(WebhookFormat::Teams, "hq", "", &event);
+        assert_eq!(teams["@type"], "MessageCard");
+        assert_eq!(teams["text"], slack["text"]);
+
+        assert_eq!(config_string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+    }
+
+    #[test]
+    fn test_retries_until_success_or_attempts_run_out() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff_ms: 1,
+            timeout_secs: 1,
+        };
+        let mut calls = 0;
+        let result = with_retries(&policy, || {
+            calls += 1;
+            if calls < 2 {
+                Err("503".into())
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, (2, None));
+        assert_eq!(
+            with_retries(&policy, || Err("refused".into())),
+            (3, Some("refused".to_string()))
+        );
+    }
+}
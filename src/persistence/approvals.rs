@@ -0,0 +1,687 @@
+//! Sign-off for critical equipment changes.
+//!
+//! With [`APPROVALS_CONFIG_FILE`] present, a save that creates, updates, or
+//! deletes critical equipment does not write those changes: they are held as
+//! a pending record under [`APPROVALS_DIR`] (the rest of the save goes through)
+//! until a second user runs `arx approve <id>`, which applies them, signs the
+//! record with GPG, and commits both. Records stay in Git as the sign-off
+//! history.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::access::{AccessPolicy, Role};
+use crate::core::{clock, Building, Equipment};
+
+use super::schema::{self, APPROVAL};
+use super::{PersistenceError, PersistenceManager, PersistenceResult, BUILDING_YAML};
+
+/// One YAML file per approval request.
+pub const APPROVALS_DIR: &str = ".arx/approvals";
+
+/// Which equipment is critical; without it nothing is held.
+pub const APPROVALS_CONFIG_FILE: &str = ".arx/approvals.yaml";
+
+/// Equipment property that marks a single item critical (`critical: "true"`).
+pub const PROP_CRITICAL: &str = "critical";
+
+/// Contents of [`APPROVALS_CONFIG_FILE`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalPolicy {
+    /// Equipment types whose changes need sign-off (e.g. `HVAC`, `Electrical`)
+    #[serde(default)]
+    pub equipment_types: Vec<String>,
+    /// Individual equipment ids or names
+    #[serde(default)]
+    pub equipment: Vec<String>,
+}
+
+impl ApprovalPolicy {
+    pub fn load(base: &Path) -> PersistenceResult<Option<Self>> {
+        let path = base.join(APPROVALS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_yaml::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    pub fn is_critical(&self, eq: &Equipment) -> bool {
+        let kind = eq.equipment_type.to_string();
+        eq.properties
+            .get(PROP_CRITICAL)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+            || self.equipment_types.iter().any(|t| {
+                t.eq_ignore_ascii_case(&kind)
+                    || t.eq_ignore_ascii_case(&eq.equipment_type.to_system_type())
+            })
+            || self
+                .equipment
+                .iter()
+                .any(|e| *e == eq.id || e.eq_ignore_ascii_case(&eq.name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeldAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// One held equipment change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeldChange {
+    pub action: HeldAction,
+    pub equipment_id: String,
+    pub name: String,
+    /// Id of the floor, wing, or room holding the equipment
+    pub parent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<Equipment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Equipment>,
+}
+
+impl std::fmt::Display for HeldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self.action {
+            HeldAction::Create => "create",
+            HeldAction::Update => "update",
+            HeldAction::Delete => "delete",
+        };
+        write!(f, "{} equipment '{}'", action, self.name)
+    }
+}
+
+/// A set of held changes and its sign-off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    pub id: String,
+    pub status: ApprovalStatus,
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+    pub changes: Vec<HeldChange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// ASCII-armored GPG signature of the record without this field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl ApprovalRecord {
+    /// Bytes covered by [`signature`](Self::signature).
+    pub fn signed_content(&self) -> PersistenceResult<String> {
+        let unsigned = Self {
+            signature: None,
+            ..self.clone()
+        };
+        Ok(serde_yaml::to_string(&unsigned)?)
+    }
+
+    /// One-line summary of the held changes.
+    pub fn summary(&self) -> String {
+        self.changes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+thread_local! {
+    static APPLYING: Cell<bool> = const { Cell::new(false) };
+    static HELD: RefCell<Vec<ApprovalRecord>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records created by saves on this thread since the last call.
+pub fn take_held() -> Vec<ApprovalRecord> {
+    HELD.with(|h| std::mem::take(&mut *h.borrow_mut()))
+}
+
+/// Number of records held on this thread and not yet taken.
+pub(crate) fn held_count() -> usize {
+    HELD.with(|h| h.borrow().len())
+}
+
+/// Ids of held records from the `skip`-th on, as repo-relative files.
+pub(crate) fn held_files_since(skip: usize) -> Vec<String> {
+    HELD.with(|h| {
+        h.borrow()
+            .iter()
+            .skip(skip)
+            .map(|r| record_file(&r.id))
+            .collect()
+    })
+}
+
+/// Lets approved changes through the hold while alive.
+struct ApplyingGuard {
+    previous: bool,
+}
+
+impl ApplyingGuard {
+    fn begin() -> Self {
+        Self {
+            previous: APPLYING.with(|a| a.replace(true)),
+        }
+    }
+}
+
+impl Drop for ApplyingGuard {
+    fn drop(&mut self) {
+        APPLYING.with(|a| a.set(self.previous));
+    }
+}
+
+pub fn approvals_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join(APPROVALS_DIR)
+}
+
+/// Repo-relative path of a record.
+pub fn record_file(id: &str) -> String {
+    format!("{}/{}.yaml", APPROVALS_DIR, id)
+}
+
+fn new_approval_id() -> String {
+    let short: String = clock::new_uuid()
+        .simple()
+        .to_string()
+        .chars()
+        .take(8)
+        .collect();
+    format!("APR-{}-{}", clock::now().format("%Y%m%d"), short)
+}
+
+pub fn save_record(base_dir: &Path, record: &ApprovalRecord) -> PersistenceResult<()> {
+    let path = base_dir.join(record_file(&record.id));
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(
+            &record_file(&record.id),
+            path.exists(),
+        );
+        return Ok(());
+    }
+    fs::create_dir_all(approvals_dir(base_dir))?;
+    schema::ensure_writable(&APPROVAL, &path)?;
+    fs::write(path, schema::to_yaml(&APPROVAL, record)?)?;
+    Ok(())
+}
+
+pub fn load_record(base_dir: &Path, id: &str) -> PersistenceResult<ApprovalRecord> {
+    let path = base_dir.join(record_file(id));
+    if !path.exists() {
+        return Err(PersistenceError::ValidationError(format!(
+            "No approval request '{}'",
+            id
+        )));
+    }
+    let content = fs::read_to_string(&path)?;
+    schema::from_yaml(&APPROVAL, &path.display().to_string(), &content)
+}
+
+/// All records, newest first.
+pub fn load_records(base_dir: &Path) -> PersistenceResult<Vec<ApprovalRecord>> {
+    let dir = approvals_dir(base_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut records = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "yaml") {
+            records.push(schema::from_yaml::<ApprovalRecord>(
+                &APPROVAL,
+                &path.display().to_string(),
+                &fs::read_to_string(&path)?,
+            )?);
+        }
+    }
+    records.sort_by_key(|r| std::cmp::Reverse(r.requested_at));
+    Ok(records)
+}
+
+/// Equipment by id with the id of its floor, wing, or room.
+fn equipment_index(building: &Building) -> BTreeMap<String, (String, &Equipment)> {
+    let mut index = BTreeMap::new();
+    for floor in &building.floors {
+        for eq in &floor.equipment {
+            index.insert(eq.id.clone(), (floor.id.clone(), eq));
+        }
+        for wing in &floor.wings {
+            for eq in &wing.equipment {
+                index.insert(eq.id.clone(), (wing.id.clone(), eq));
+            }
+            for room in &wing.rooms {
+                for eq in &room.equipment {
+                    index.insert(eq.id.clone(), (room.id.clone(), eq));
+                }
+            }
+        }
+    }
+    index
+}
+
+fn take_equipment(building: &mut Building, id: &str) -> Option<Equipment> {
+    fn take(list: &mut Vec<Equipment>, id: &str) -> Option<Equipment> {
+        let at = list.iter().position(|e| e.id == id)?;
+        Some(list.remove(at))
+    }
+    for floor in &mut building.floors {
+        if let Some(eq) = take(&mut floor.equipment, id) {
+            return Some(eq);
+        }
+        for wing in &mut floor.wings {
+            if let Some(eq) = take(&mut wing.equipment, id) {
+                return Some(eq);
+            }
+            for room in &mut wing.rooms {
+                if let Some(eq) = take(&mut room.equipment, id) {
+                    return Some(eq);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn insert_equipment(building: &mut Building, parent: &str, eq: Equipment) -> bool {
+    for floor in &mut building.floors {
+        if floor.id == parent {
+            floor.equipment.push(eq);
+            return true;
+        }
+        for wing in &mut floor.wings {
+            if wing.id == parent {
+                wing.equipment.push(eq);
+                return true;
+            }
+            if let Some(room) = wing.rooms.iter_mut().find(|r| r.id == parent) {
+                room.equipment.push(eq);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Equipment as compared for changes: provenance is restamped on every save.
+fn comparable(eq: &Equipment) -> serde_json::Value {
+    let mut value = serde_json::to_value(eq).unwrap_or_default();
+    if let Some(map) = value.as_object_mut() {
+        map.remove("provenance");
+    }
+    value
+}
+
+/// Pull critical equipment changes out of `building` (reverting them to
+/// `existing`) and store them as a pending record. Called by every save;
+/// returns the record when something was held.
+pub(crate) fn hold_critical_changes(
+    base_dir: &Path,
+    existing: Option<&Building>,
+    building: &mut Building,
+    requested_by: &str,
+) -> PersistenceResult<Option<ApprovalRecord>> {
+    if APPLYING.with(|a| a.get()) {
+        return Ok(None);
+    }
+    let (Some(existing), Some(policy)) = (existing, ApprovalPolicy::load(base_dir)?) else {
+        return Ok(None);
+    };
+    let before = equipment_index(existing);
+    let after = equipment_index(building);
+    let mut changes = Vec::new();
+    for id in before
+        .keys()
+        .chain(after.keys().filter(|k| !before.contains_key(*k)))
+    {
+        let (old, new) = (before.get(id), after.get(id));
+        let action = match (old, new) {
+            (Some((p0, e0)), Some((p1, e1))) => {
+                if p0 == p1 && comparable(e0) == comparable(e1) {
+                    continue;
+                }
+                HeldAction::Update
+            }
+            (None, Some(_)) => HeldAction::Create,
+            (Some(_), None) => HeldAction::Delete,
+            (None, None) => continue,
+        };
+        if !old
+            .into_iter()
+            .chain(new)
+            .any(|(_, eq)| policy.is_critical(eq))
+        {
+            continue;
+        }
+        let (parent, named) = new.or(old).map(|(p, e)| (p.clone(), *e)).unwrap();
+        changes.push(HeldChange {
+            action,
+            equipment_id: id.clone(),
+            name: named.name.clone(),
+            parent,
+            before: old.map(|(_, e)| (*e).clone()),
+            after: new.map(|(_, e)| (*e).clone()),
+        });
+    }
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    for change in &changes {
+        take_equipment(building, &change.equipment_id);
+        if let (Some(eq), Some((parent, _))) = (&change.before, before.get(&change.equipment_id)) {
+            if !insert_equipment(building, parent, eq.clone()) {
+                return Err(PersistenceError::ValidationError(format!(
+                    "Equipment '{}' needs approval, but its location is being removed in the same change",
+                    change.name
+                )));
+            }
+        }
+    }
+    let record = ApprovalRecord {
+        id: new_approval_id(),
+        status: ApprovalStatus::Pending,
+        requested_by: requested_by.to_string(),
+        requested_at: clock::now(),
+        changes,
+        decided_by: None,
+        decided_at: None,
+        note: None,
+        signature: None,
+    };
+    save_record(base_dir, &record)?;
+    HELD.with(|h| h.borrow_mut().push(record.clone()));
+    Ok(Some(record))
+}
+
+/// Apply the held changes of `record` to `building`; fails when the equipment
+/// changed since the request.
+pub fn apply_held_changes(
+    record: &ApprovalRecord,
+    building: &mut Building,
+) -> PersistenceResult<()> {
+    for change in &record.changes {
+        let current = equipment_index(building)
+            .get(&change.equipment_id)
+            .map(|(_, eq)| comparable(eq));
+        if current != change.before.as_ref().map(comparable) {
+            return Err(PersistenceError::ValidationError(format!(
+                "Equipment '{}' changed since {} was requested; reject it and request again",
+                change.name, record.id
+            )));
+        }
+        take_equipment(building, &change.equipment_id);
+        if let Some(eq) = &change.after {
+            if !insert_equipment(building, &change.parent, eq.clone()) {
+                return Err(PersistenceError::ValidationError(format!(
+                    "Location of equipment '{}' no longer exists",
+                    change.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Approve or reject pending request `id` as `approver`.
+///
+/// The approver must differ from the requester and, with roles configured,
+/// be a manager or admin. Approving applies the changes to building.yaml;
+/// with `sign` the record carries the approver's GPG signature. The building
+/// and record are committed together when `base_dir` is a Git repository.
+pub fn decide(
+    base_dir: &Path,
+    id: &str,
+    approver: &str,
+    approve: bool,
+    note: Option<String>,
+    sign: bool,
+) -> PersistenceResult<ApprovalRecord> {
+    let mut record = load_record(base_dir, id)?;
+    if record.status != ApprovalStatus::Pending {
+        return Err(PersistenceError::ValidationError(format!(
+            "{} is already {:?}",
+            id, record.status
+        )));
+    }
+    if record.requested_by == approver {
+        return Err(PersistenceError::ValidationError(format!(
+            "{} was requested by {}; a second user must sign off",
+            id, approver
+        )));
+    }
+    if let Some(policy) = AccessPolicy::load(base_dir)
+        .map_err(|e| PersistenceError::ValidationError(e.to_string()))?
+    {
+        let role = policy.role_of(approver);
+        if role < Role::Manager {
+            return Err(PersistenceError::ValidationError(format!(
+                "Permission denied: {} ({}) may not sign off changes",
+                approver, role
+            )));
+        }
+    }
+
+    record.status = if approve {
+        ApprovalStatus::Approved
+    } else {
+        ApprovalStatus::Rejected
+    };
+    record.decided_by = Some(approver.to_string());
+    record.decided_at = Some(clock::now());
+    record.note = note;
+    if sign {
+        record.signature = Some(gpg_sign(&record.signed_content()?)?);
+    }
+
+    let store = PersistenceManager::at(base_dir);
+    let mut files = vec![record_file(id)];
+    if approve {
+        let mut building = store.load_building_data()?;
+        apply_held_changes(&record, &mut building)?;
+        let _applying = ApplyingGuard::begin();
+        store.save_building_validated(&building)?;
+        files.push(BUILDING_YAML.to_string());
+    }
+    save_record(base_dir, &record)?;
+
+    let verb = if approve { "Approve" } else { "Reject" };
+    commit_files(
+        base_dir,
+        &files,
+        &format!("{} {}: {}", verb, id, record.summary()),
+    )?;
+    Ok(record)
+}
+
+fn commit_files(base_dir: &Path, files: &[String], message: &str) -> PersistenceResult<()> {
+    if !base_dir.join(".git").exists() {
+        return Ok(());
+    }
+
+    use crate::git::manager::{BuildingGitManager, GitConfigManager};
+
+    let base = base_dir.to_str().ok_or_else(|| {
+        PersistenceError::SerializationError("base path is not valid UTF-8".into())
+    })?;
+    let mut git = BuildingGitManager::new(
+        base,
+        "building",
+        GitConfigManager::load_from_arx_config_or_env(),
+    )
+    .map_err(|e| PersistenceError::SerializationError(format!("Git open failed: {}", e)))?;
+    for file in files {
+        git.stage_file(file).map_err(|e| {
+            PersistenceError::SerializationError(format!("Git stage failed: {}", e))
+        })?;
+    }
+    git.commit_staged(message)
+        .map_err(|e| PersistenceError::SerializationError(format!("Git commit failed: {}", e)))?;
+    Ok(())
+}
+
+/// Signing key: `ARX_GPG_KEY`, else GPG's default key.
+fn gpg_key() -> Option<String> {
+    std::env::var("ARX_GPG_KEY").ok().filter(|k| !k.is_empty())
+}
+
+/// ASCII-armored detached signature of `content`.
+fn gpg_sign(content: &str) -> PersistenceResult<String> {
+    let mut command = Command::new("gpg");
+    command.args(["--batch", "--armor", "--detach-sign"]);
+    if let Some(key) = gpg_key() {
+        command.args(["--local-user", &key]);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            PersistenceError::ValidationError(format!("Cannot run gpg ({}); use --no-sign", e))
+        })?;
+    child
+        .stdin
+        .take()
+        .expect("piped stdin")
+        .write_all(content.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(PersistenceError::ValidationError(format!(
+            "gpg signing failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Check the record's signature; returns gpg's report (e.g. `Good signature
+/// from "…"`), or `None` when the record is unsigned.
+pub fn verify_signature(record: &ApprovalRecord) -> PersistenceResult<Option<String>> {
+    let Some(signature) = &record.signature else {
+        return Ok(None);
+    };
+    let mut sig_file = tempfile::NamedTempFile::new()?;
+    sig_file.write_all(signature.as_bytes())?;
+    let mut child = Command::new("gpg")
+        .args(["--batch", "--verify"])
+        .arg(sig_file.path())
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("piped stdin")
+        .write_all(record.signed_content()?.as_bytes())?;
+    let output = child.wait_with_output()?;
+    let report = String::from_utf8_lossy(&output.stderr);
+    let line = report
+        .lines()
+        .find(|l| l.contains("signature"))
+        .unwrap_or(report.trim())
+        .trim_start_matches("gpg: ")
+        .to_string();
+    if output.status.success() {
+        Ok(Some(line))
+    } else {
+        Err(PersistenceError::ValidationError(format!(
+            "Signature of {} does not verify: {}",
+            record.id, line
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::access::ActingUserGuard;
+    use crate::core::{EquipmentStatus, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "Boiler 1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        room.add_equipment(Equipment::new(
+            "Light 1".into(),
+            String::new(),
+            EquipmentType::Electrical,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    fn set_status(building: &mut Building, name: &str) {
+        building.find_equipment_mut(name).unwrap().status = EquipmentStatus::OutOfOrder;
+    }
+
+    #[test]
+    fn test_critical_change_held_until_second_user_approves() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistenceManager::at(dir.path());
+        let mut building = building();
+        store.save_building_data(&building).unwrap();
+        fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        fs::write(
+            dir.path().join(APPROVALS_CONFIG_FILE),
+            "equipment_types: [HVAC]\n",
+        )
+        .unwrap();
+
+        let _dana = ActingUserGuard::act_as("dana");
+        set_status(&mut building, "Boiler 1");
+        set_status(&mut building, "Light 1");
+        store.save_building_data(&building).unwrap();
+        let held = take_held();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].summary(), "update equipment 'Boiler 1'");
+
+        let saved = store.load_building_data().unwrap();
+        let status = |b: &Building, name: &str| b.find_equipment(name).unwrap().status;
+        assert_eq!(status(&saved, "Boiler 1"), EquipmentStatus::Active);
+        assert_eq!(status(&saved, "Light 1"), EquipmentStatus::OutOfOrder);
+
+        let id = &held[0].id;
+        assert!(decide(dir.path(), id, "dana", true, None, false).is_err());
+        let record = decide(dir.path(), id, "lee", true, Some("ok".into()), false).unwrap();
+        assert_eq!(record.status, ApprovalStatus::Approved);
+        assert!(take_held().is_empty());
+        let saved = store.load_building_data().unwrap();
+        assert_eq!(status(&saved, "Boiler 1"), EquipmentStatus::OutOfOrder);
+        let records = load_records(dir.path()).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].decided_by.as_deref(), Some("lee"));
+        assert!(decide(dir.path(), id, "lee", true, None, false).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ingest::ar_scan::{PendingEquipment, PendingState};
+
+use super::schema::{self, AR_PENDING};
+use super::{PersistenceError, PersistenceResult};
+
+/// One YAML file per scanned item awaiting (or past) review.
+pub const AR_PENDING_DIR: &str = ".arx/ar-pending";
+
+pub fn save_pending(base_dir: &Path, pending: &PendingEquipment) -> PersistenceResult<()> {
+    validate_id(&pending.id)?;
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(
+            &format!("{}/{}.yaml", AR_PENDING_DIR, pending.id),
+            pending_path(base_dir, &pending.id).exists(),
+        );
+        return Ok(());
+    }
+    fs::create_dir_all(base_dir.join(AR_PENDING_DIR))?;
+    let path = pending_path(base_dir, &pending.id);
+    schema::ensure_writable(&AR_PENDING, &path)?;
+    fs::write(path, schema::to_yaml(&AR_PENDING, pending)?)?;
+    Ok(())
+}
+
+pub fn load_pending(base_dir: &Path, id: &str) -> PersistenceResult<Option<PendingEquipment>> {
+    validate_id(id)?;
+    let path = pending_path(base_dir, id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(Some(schema::from_yaml(
+        &AR_PENDING,
+        &path.display().to_string(),
+        &content,
+    )?))
+}
+
+/// Stored records (only those still awaiting review unless `all`), oldest first.
+pub fn list_pending(base_dir: &Path, all: bool) -> PersistenceResult<Vec<PendingEquipment>> {
+    let dir = base_dir.join(AR_PENDING_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut records = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+            continue;
+        }
+        let record: PendingEquipment = schema::from_yaml(
+            &AR_PENDING,
+            &path.display().to_string(),
+            &fs::read_to_string(&path)?,
+        )?;
+        if all || record.state == PendingState::Pending {
+            records.push(record);
+        }
+    }
+    records.sort_by(|a, b| a.submitted_at.cmp(&b.submitted_at).then(a.id.cmp(&b.id)));
+    Ok(records)
+}
+
+/// Ids become file names; keep them to one path segment.
+fn validate_id(id: &str) -> PersistenceResult<()> {
+    if id.is_empty() || id.starts_with('.') || id.contains(['/', '\\']) {
+        return Err(PersistenceError::ValidationError(format!(
+            "invalid pending equipment id '{}'",
+            id
+        )));
+    }
+    Ok(())
+}
+
+fn pending_path(base_dir: &Path, id: &str) -> PathBuf {
+    base_dir.join(AR_PENDING_DIR).join(format!("{}.yaml", id))
+}
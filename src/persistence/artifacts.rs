@@ -0,0 +1,405 @@
+//! Managed artifact directories with size caps and LRU eviction.
+//!
+//! Exports, renders, recorded sessions, and the attachment cache are
+//! regenerable, but they grow with every run. Each category has a
+//! directory and optional limits; [`clean`] evicts least-recently-used files
+//! (last access or modification, whichever is later) until the category fits
+//! its size cap, and drops files older than `max_age_days`. The newest
+//! `keep_latest` files and anything tracked by Git are never removed.
+//!
+//! Writers call [`enforce`] after adding a file so limits hold without a
+//! manual `arx clean`. Limits come from `.arx/artifacts.yaml`; an entry there
+//! replaces the built-in limits for that category and may add new ones.
+//! Uploaded IFC originals under `imports/` cannot be regenerated, so they are
+//! not a built-in category; listing `imports` opts them in to `arx clean`:
+//!
+//! ```yaml
+//! categories:
+//!   exports:
+//!     max_size_mb: 200
+//!     keep_latest: 3
+//!   renders:
+//!     path: out/renders
+//!     max_age_days: 14
+//!   imports:
+//!     max_age_days: 90
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::core::clock;
+use crate::core::operations::dry_run::{is_dry_run, record, Change, ChangeKind};
+use crate::persistence::attachments::{contained, AttachmentConfig, DEFAULT_CACHE_DIR};
+
+/// Repo-relative retention config.
+pub const ARTIFACTS_CONFIG_FILE: &str = ".arx/artifacts.yaml";
+
+const MIB: u64 = 1024 * 1024;
+
+/// Limits for one category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryLimits {
+    /// Directory (repo-relative); defaults to the built-in path or the category name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+    /// Newest files that are never evicted
+    #[serde(default = "default_keep_latest")]
+    pub keep_latest: usize,
+}
+
+fn default_keep_latest() -> usize {
+    1
+}
+
+/// Contents of `.arx/artifacts.yaml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArtifactsConfig {
+    #[serde(default)]
+    pub categories: BTreeMap<String, CategoryLimits>,
+}
+
+/// A category resolved against the repository.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactCategory {
+    pub name: String,
+    /// Repo-relative directory
+    pub dir: PathBuf,
+    pub max_bytes: Option<u64>,
+    pub max_age: Option<Duration>,
+    pub keep_latest: usize,
+}
+
+fn builtin(
+    name: &str,
+    path: &str,
+    max_size_mb: u64,
+    max_age_days: Option<u64>,
+) -> ArtifactCategory {
+    ArtifactCategory {
+        name: name.to_string(),
+        dir: PathBuf::from(path),
+        max_bytes: Some(max_size_mb * MIB),
+        max_age: max_age_days.map(days),
+        keep_latest: default_keep_latest(),
+    }
+}
+
+fn days(d: u64) -> Duration {
+    Duration::from_secs(d * 24 * 60 * 60)
+}
+
+impl ArtifactsConfig {
+    pub fn load(base: &Path) -> Result<Self> {
+        let path = base.join(ARTIFACTS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Built-in categories overlaid with the configured ones, sorted by name.
+    pub fn categories(&self, base: &Path) -> Result<Vec<ArtifactCategory>> {
+        let cache_dir = AttachmentConfig::load(base)?
+            .map(|c| c.cache_dir)
+            .unwrap_or_else(|| DEFAULT_CACHE_DIR.to_string());
+        let mut out: BTreeMap<String, ArtifactCategory> = [
+            builtin("attachment-cache", &cache_dir, 2048, None),
+            builtin("exports", "exports", 1024, None),
+            builtin("output", "output", 512, None),
+            builtin("renders", "renders", 512, None),
+            builtin("sessions", "sessions", 256, Some(30)),
+        ]
+        .into_iter()
+        .map(|c| (c.name.clone(), c))
+        .collect();
+
+        for (name, limits) in &self.categories {
+            let dir = match (&limits.path, out.get(name)) {
+                (Some(p), _) => PathBuf::from(p),
+                (None, Some(existing)) => existing.dir.clone(),
+                (None, None) => PathBuf::from(name),
+            };
+            if !contained(&dir) {
+                bail!(
+                    "{}: category '{}' path {} must be inside the repository",
+                    ARTIFACTS_CONFIG_FILE,
+                    name,
+                    dir.display()
+                );
+            }
+            out.insert(
+                name.clone(),
+                ArtifactCategory {
+                    name: name.clone(),
+                    dir,
+                    max_bytes: limits.max_size_mb.map(|mb| mb * MIB),
+                    max_age: limits.max_age_days.map(days),
+                    keep_latest: limits.keep_latest,
+                },
+            );
+        }
+        Ok(out.into_values().collect())
+    }
+}
+
+/// One evicted (or would-be evicted) file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Eviction {
+    /// Repo-relative path
+    pub path: PathBuf,
+    pub size: u64,
+    /// `size cap`, `age`, or `purge`
+    pub reason: &'static str,
+}
+
+/// Outcome of cleaning one category.
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanReport {
+    pub category: String,
+    pub dir: PathBuf,
+    pub files: usize,
+    pub bytes_before: u64,
+    pub max_bytes: Option<u64>,
+    pub evicted: Vec<Eviction>,
+    /// Files kept because Git tracks them
+    pub tracked: usize,
+}
+
+impl CleanReport {
+    pub fn bytes_freed(&self) -> u64 {
+        self.evicted.iter().map(|e| e.size).sum()
+    }
+
+    pub fn bytes_after(&self) -> u64 {
+        self.bytes_before - self.bytes_freed()
+    }
+}
+
+struct Artifact {
+    rel: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+fn walk(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let ty = entry.file_type()?;
+        if ty.is_dir() {
+            walk(&entry.path(), out)?;
+        } else if ty.is_file() {
+            out.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Repo-relative paths in the Git index (empty outside a repository).
+fn tracked_paths(base: &Path) -> BTreeSet<PathBuf> {
+    let Ok(repo) = git2::Repository::open(base) else {
+        return BTreeSet::new();
+    };
+    let Ok(index) = repo.index() else {
+        return BTreeSet::new();
+    };
+    index
+        .iter()
+        .filter_map(|e| String::from_utf8(e.path).ok())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Evict from `category` down to its limits (or everything untracked with `purge`).
+pub fn clean(
+    base: &Path,
+    category: &ArtifactCategory,
+    now: SystemTime,
+    purge: bool,
+) -> Result<CleanReport> {
+    let root = base.join(&category.dir);
+    let mut report = CleanReport {
+        category: category.name.clone(),
+        dir: category.dir.clone(),
+        files: 0,
+        bytes_before: 0,
+        max_bytes: category.max_bytes,
+        evicted: Vec::new(),
+        tracked: 0,
+    };
+    if !root.is_dir() {
+        return Ok(report);
+    }
+
+    let mut paths = Vec::new();
+    walk(&root, &mut paths)?;
+    let tracked = tracked_paths(base);
+    let mut artifacts = Vec::new();
+    for path in paths {
+        let meta = std::fs::metadata(&path)?;
+        let modified = meta.modified()?;
+        let last_used = meta.accessed().map_or(modified, |a| a.max(modified));
+        let rel = path.strip_prefix(base).unwrap_or(&path).to_path_buf();
+        report.files += 1;
+        report.bytes_before += meta.len();
+        if tracked.contains(&rel) {
+            report.tracked += 1;
+            continue;
+        }
+        artifacts.push(Artifact {
+            rel,
+            size: meta.len(),
+            last_used,
+        });
+    }
+
+    // Newest first; the first `keep_latest` are protected
+    artifacts.sort_by(|a, b| b.last_used.cmp(&a.last_used).then(a.rel.cmp(&b.rel)));
+    let keep = if purge { 0 } else { category.keep_latest };
+    let mut total = report.bytes_before;
+    for artifact in artifacts.iter().skip(keep).rev() {
+        let age = now.duration_since(artifact.last_used).unwrap_or_default();
+        let reason = if purge {
+            "purge"
+        } else if category.max_age.is_some_and(|max| age > max) {
+            "age"
+        } else if category.max_bytes.is_some_and(|max| total > max) {
+            "size cap"
+        } else {
+            continue;
+        };
+        if is_dry_run() {
+            record(Change::new(
+                ChangeKind::Remove,
+                format!("file {}", artifact.rel.display()),
+                Some(reason.to_string()),
+            ));
+        } else {
+            std::fs::remove_file(base.join(&artifact.rel))
+                .with_context(|| format!("removing {}", artifact.rel.display()))?;
+        }
+        total -= artifact.size;
+        report.evicted.push(Eviction {
+            path: artifact.rel.clone(),
+            size: artifact.size,
+            reason,
+        });
+    }
+    Ok(report)
+}
+
+/// Apply the limits of category `name` after a write; no-op for unknown names.
+pub fn enforce(base: &Path, name: &str) -> Result<Option<CleanReport>> {
+    let categories = ArtifactsConfig::load(base)?.categories(base)?;
+    match categories.into_iter().find(|c| c.name == name) {
+        Some(category) => clean(base, &category, SystemTime::from(clock::now()), false).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Mark `path` as just used so LRU eviction keeps it.
+pub fn touch(path: &Path) -> Result<()> {
+    std::fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::from(clock::now()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(base: &Path, rel: &str, kib: usize, age_days: u64, now: SystemTime) {
+        let path = base.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, vec![0u8; kib * 1024]).unwrap();
+        let at = now - days(age_days);
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_times(std::fs::FileTimes::new().set_accessed(at).set_modified(at))
+            .unwrap();
+    }
+
+    #[test]
+    fn evicts_least_recently_used_down_to_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        let now = SystemTime::now();
+        write(base, "renders/a.png", 400, 5, now);
+        write(base, "renders/b.png", 400, 3, now);
+        write(base, "renders/deep/c.png", 400, 1, now);
+        write(base, "renders/d.png", 400, 0, now);
+        let category = ArtifactCategory {
+            name: "renders".into(),
+            dir: "renders".into(),
+            max_bytes: Some(1024 * 1024),
+            max_age: None,
+            keep_latest: 1,
+        };
+
+        let (report, changes) =
+            crate::core::operations::simulate(|| clean(base, &category, now, false).unwrap());
+        assert_eq!(changes.len(), 2);
+        assert!(base.join("renders/a.png").exists());
+
+        let report2 = clean(base, &category, now, false).unwrap();
+        assert_eq!(report.evicted.len(), report2.evicted.len());
+        let evicted: Vec<_> = report2.evicted.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            evicted,
+            vec![
+                PathBuf::from("renders/a.png"),
+                PathBuf::from("renders/b.png")
+            ]
+        );
+        assert!(report2.bytes_after() <= 1024 * 1024);
+        assert!(base.join("renders/deep/c.png").exists());
+
+        let purged = clean(base, &category, now, true).unwrap();
+        assert_eq!(purged.evicted.len(), 2);
+        assert!(!base.join("renders/d.png").exists());
+    }
+
+    #[test]
+    fn config_overrides_builtin_limits_and_rejects_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        std::fs::create_dir_all(base.join(".arx")).unwrap();
+        std::fs::write(
+            base.join(ARTIFACTS_CONFIG_FILE),
+            "categories:\n  exports:\n    max_age_days: 7\n    keep_latest: 3\n  scans:\n    max_size_mb: 10\n",
+        )
+        .unwrap();
+        let categories = ArtifactsConfig::load(base)
+            .unwrap()
+            .categories(base)
+            .unwrap();
+        let exports = categories.iter().find(|c| c.name == "exports").unwrap();
+        assert_eq!(exports.dir, PathBuf::from("exports"));
+        assert_eq!((exports.max_bytes, exports.keep_latest), (None, 3));
+        assert_eq!(exports.max_age, Some(days(7)));
+        let scans = categories.iter().find(|c| c.name == "scans").unwrap();
+        assert_eq!(scans.max_bytes, Some(10 * MIB));
+        assert!(categories.iter().any(|c| c.name == "attachment-cache"));
+        assert!(!categories.iter().any(|c| c.name == "imports"));
+
+        std::fs::write(
+            base.join(ARTIFACTS_CONFIG_FILE),
+            "categories:\n  logs:\n    path: ../logs\n",
+        )
+        .unwrap();
+        assert!(ArtifactsConfig::load(base)
+            .unwrap()
+            .categories(base)
+            .is_err());
+    }
+}
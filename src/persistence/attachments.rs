@@ -0,0 +1,539 @@
+//! Attachment storage outside Git.
+//!
+//! Attachments are files referenced by `photo*` / `attachment*` properties
+//! (see [`PHOTO_PROPERTY_PREFIXES`]), entity attachments (see
+//! [`super::entity_attachments`]), and commissioning sign-off photos. Large ones can live in an object store
+//! instead of the repository: [`AttachmentStore::migrate`] uploads each blob
+//! under a content-addressed key (`sha256/<aa>/<digest>`) and replaces the
+//! file in the working tree with a small pointer, so property values keep
+//! working unchanged. [`resolve`] turns a reference into a readable local path,
+//! fetching pointed-to blobs into the local cache on first access.
+//!
+//! Configured in `.arx/attachments.yaml`:
+//!
+//! ```yaml
+//! store: s3://arx-attachments/site-a   # or a directory
+//! s3:
+//!   endpoint: https://minio.example.org:9000
+//!   region: us-east-1
+//! migrate_min_kb: 512
+//! ```
+//!
+//! Pointer files look like:
+//!
+//! ```text
+//! arx-attachment v1
+//! sha256 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+//! size 4
+//! ```
+
+use crate::core::commissioning::Evidence;
+use crate::core::completeness::PHOTO_PROPERTY_PREFIXES;
+use crate::core::Building;
+use crate::persistence::artifacts::{enforce, touch};
+use crate::persistence::s3::{S3Client, S3Settings};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Repo-relative attachment store configuration.
+pub const ATTACHMENTS_CONFIG_FILE: &str = ".arx/attachments.yaml";
+
+/// Default repo-relative cache of fetched blobs (kept out of Git).
+pub const DEFAULT_CACHE_DIR: &str = ".arx/cache/attachments";
+
+/// First line of every pointer file.
+pub const POINTER_HEADER: &str = "arx-attachment v1";
+
+/// Files at least this large are never treated as pointers.
+const MAX_POINTER_SIZE: u64 = 512;
+
+/// `.arx/attachments.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentConfig {
+    /// Directory path or `s3://bucket/prefix`
+    pub store: String,
+    #[serde(default)]
+    pub s3: S3Settings,
+    /// Local cache of fetched blobs (repo-relative or absolute)
+    #[serde(default = "default_cache_dir")]
+    pub cache_dir: String,
+    /// Attachments smaller than this stay in Git when migrating
+    #[serde(default = "default_migrate_min_kb")]
+    pub migrate_min_kb: u64,
+}
+
+fn default_cache_dir() -> String {
+    DEFAULT_CACHE_DIR.to_string()
+}
+
+fn default_migrate_min_kb() -> u64 {
+    512
+}
+
+impl AttachmentConfig {
+    /// `None` when the repository has no attachment store configured.
+    pub fn load(base: &Path) -> Result<Option<Self>> {
+        let path = base.join(ATTACHMENTS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let config = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid {}", path.display()))?;
+        Ok(Some(config))
+    }
+}
+
+/// Content-addressed reference left in the working tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pointer {
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl Pointer {
+    /// Object key: `sha256/<first two hex digits>/<digest>`.
+    pub fn key(&self) -> String {
+        format!("sha256/{}/{}", &self.sha256[..2], self.sha256)
+    }
+
+    pub fn render(&self) -> String {
+        format!(
+            "{}\nsha256 {}\nsize {}\n",
+            POINTER_HEADER, self.sha256, self.size
+        )
+    }
+
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        if lines.next()?.trim() != POINTER_HEADER {
+            return None;
+        }
+        let (mut sha256, mut size) = (None, None);
+        for line in lines {
+            match line.trim().split_once(' ') {
+                Some(("sha256", v)) => sha256 = Some(v.trim().to_lowercase()),
+                Some(("size", v)) => size = v.trim().parse().ok(),
+                _ => {}
+            }
+        }
+        let sha256 = sha256?;
+        if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(Self {
+            sha256,
+            size: size?,
+        })
+    }
+
+    /// Pointer stored at `path`, or `None` for a regular file.
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        if std::fs::metadata(path)?.len() >= MAX_POINTER_SIZE {
+            return Ok(None);
+        }
+        let bytes = std::fs::read(path)?;
+        Ok(std::str::from_utf8(&bytes).ok().and_then(Self::parse))
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<(u64, String)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)?;
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// Relative path that stays inside the project (no `..`, not absolute).
+pub(crate) fn contained(rel: &Path) -> bool {
+    !rel.as_os_str().is_empty() && rel.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Attachment paths referenced by room / equipment properties that exist on disk.
+pub fn referenced_paths(base: &Path, building: &Building) -> BTreeSet<PathBuf> {
+    let properties = building
+        .get_all_rooms()
+        .into_iter()
+        .map(|r| &r.properties)
+        .chain(
+            building
+                .get_all_equipment()
+                .into_iter()
+                .map(|e| &e.properties),
+        );
+    let mut out = BTreeSet::new();
+    for props in properties {
+        for (key, value) in props {
+            let key = key.to_lowercase();
+            if !PHOTO_PROPERTY_PREFIXES.iter().any(|p| key.starts_with(p)) {
+                continue;
+            }
+            let rel = PathBuf::from(value.trim());
+            if contained(&rel) && base.join(&rel).is_file() {
+                out.insert(rel);
+            }
+        }
+    }
+    // Files attached with `arx attachment add`
+    let attached = building
+        .get_all_rooms()
+        .into_iter()
+        .flat_map(|r| &r.attachments)
+        .chain(
+            building
+                .get_all_equipment()
+                .into_iter()
+                .flat_map(|e| &e.attachments),
+        );
+    for attachment in attached {
+        let rel = PathBuf::from(&attachment.path);
+        if contained(&rel) && base.join(&rel).is_file() {
+            out.insert(rel);
+        }
+    }
+    // Commissioning sign-off photos
+    for eq in building.get_all_equipment() {
+        for sign_off in eq.commissioning.iter().flat_map(|c| &c.history) {
+            for evidence in &sign_off.evidence {
+                if let Evidence::Photo { path } = evidence {
+                    let rel = PathBuf::from(path);
+                    if contained(&rel) && base.join(&rel).is_file() {
+                        out.insert(rel);
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Where blobs live.
+pub trait ObjectStore {
+    fn describe(&self) -> String;
+    fn put(&self, key: &str, source: &Path) -> Result<()>;
+    fn get(&self, key: &str, dest: &Path) -> Result<()>;
+}
+
+/// Blobs in a directory (local disk, NAS mount, ...).
+pub struct LocalObjectStore {
+    pub root: PathBuf,
+}
+
+impl ObjectStore for LocalObjectStore {
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn put(&self, key: &str, source: &Path) -> Result<()> {
+        let dest = self.root.join(key);
+        if dest.exists() {
+            // Content-addressed: an existing object is already this blob
+            return Ok(());
+        }
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(source, &dest).with_context(|| format!("writing {}", dest.display()))?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str, dest: &Path) -> Result<()> {
+        let source = self.root.join(key);
+        std::fs::copy(&source, dest).with_context(|| format!("reading {}", source.display()))?;
+        Ok(())
+    }
+}
+
+impl ObjectStore for S3Client {
+    fn describe(&self) -> String {
+        S3Client::describe(self)
+    }
+
+    fn put(&self, key: &str, source: &Path) -> Result<()> {
+        S3Client::put(self, &self.key(key), source)
+    }
+
+    fn get(&self, key: &str, dest: &Path) -> Result<()> {
+        S3Client::get(self, &self.key(key), dest)
+    }
+}
+
+/// Outcome of [`AttachmentStore::migrate`].
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    /// Attachments replaced by pointers (repo-relative path, size)
+    pub moved: Vec<(PathBuf, u64)>,
+    pub already_migrated: usize,
+    pub below_threshold: usize,
+}
+
+impl MigrationReport {
+    pub fn bytes_moved(&self) -> u64 {
+        self.moved.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// Configured object store plus local cache for one repository.
+pub struct AttachmentStore {
+    pub base: PathBuf,
+    pub config: AttachmentConfig,
+    backend: Box<dyn ObjectStore>,
+}
+
+impl AttachmentStore {
+    /// Store for `base`, or `None` without `.arx/attachments.yaml`.
+    pub fn open(base: &Path) -> Result<Option<Self>> {
+        let Some(config) = AttachmentConfig::load(base)? else {
+            return Ok(None);
+        };
+        let backend: Box<dyn ObjectStore> = if config.store.starts_with("s3://") {
+            Box::new(S3Client::parse(&config.store, config.s3.clone())?)
+        } else {
+            Box::new(LocalObjectStore {
+                root: base.join(&config.store),
+            })
+        };
+        Ok(Some(Self {
+            base: base.to_path_buf(),
+            config,
+            backend,
+        }))
+    }
+
+    pub fn describe(&self) -> String {
+        self.backend.describe()
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.base.join(&self.config.cache_dir)
+    }
+
+    pub fn cached_path(&self, pointer: &Pointer) -> PathBuf {
+        self.cache_dir().join(pointer.key())
+    }
+
+    /// Local copy of the blob, downloading and verifying it on a cache miss.
+    pub fn fetch(&self, pointer: &Pointer) -> Result<PathBuf> {
+        let cached = self.cached_path(pointer);
+        if cached.is_file() {
+            // Best effort: a read-only cache still serves the blob
+            let _ = touch(&cached);
+            return Ok(cached);
+        }
+        let parent = cached
+            .parent()
+            .ok_or_else(|| anyhow!("invalid cache path {}", cached.display()))?;
+        std::fs::create_dir_all(parent)?;
+        let partial = tempfile::NamedTempFile::new_in(parent)?;
+        self.backend
+            .get(&pointer.key(), partial.path())
+            .with_context(|| format!("fetching {} from {}", pointer.key(), self.describe()))?;
+        let (size, sha256) = sha256_file(partial.path())?;
+        if size != pointer.size || sha256 != pointer.sha256 {
+            bail!(
+                "{} from {} does not match its pointer (got {} bytes, sha256 {})",
+                pointer.key(),
+                self.describe(),
+                size,
+                sha256
+            );
+        }
+        partial.persist(&cached)?;
+        enforce(&self.base, "attachment-cache")?;
+        Ok(cached)
+    }
+
+    /// Upload `source` and keep a copy in the cache; returns its pointer.
+    pub fn upload(&self, source: &Path) -> Result<Pointer> {
+        let (size, sha256) = sha256_file(source)?;
+        let pointer = Pointer { sha256, size };
+        self.backend.put(&pointer.key(), source)?;
+        let cached = self.cached_path(&pointer);
+        if !cached.exists() {
+            if let Some(parent) = cached.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(source, &cached)?;
+        }
+        Ok(pointer)
+    }
+
+    /// Move referenced attachments of at least `min_size` bytes into the store,
+    /// leaving pointers behind. With `dry_run`, only reports what would move.
+    pub fn migrate(
+        &self,
+        building: &Building,
+        min_size: u64,
+        dry_run: bool,
+    ) -> Result<MigrationReport> {
+        let mut report = MigrationReport::default();
+        for rel in referenced_paths(&self.base, building) {
+            let path = self.base.join(&rel);
+            if Pointer::read(&path)?.is_some() {
+                report.already_migrated += 1;
+                continue;
+            }
+            let size = std::fs::metadata(&path)?.len();
+            if size < min_size {
+                report.below_threshold += 1;
+                continue;
+            }
+            if !dry_run {
+                let pointer = self.upload(&path)?;
+                std::fs::write(&path, pointer.render())?;
+            }
+            report.moved.push((rel, size));
+        }
+        if !dry_run && !report.moved.is_empty() {
+            self.ignore_cache()?;
+        }
+        Ok(report)
+    }
+
+    /// Make sure the cache is listed in `.gitignore` so blobs don't re-enter Git.
+    pub(crate) fn ignore_cache(&self) -> Result<()> {
+        let Ok(rel) = self
+            .cache_dir()
+            .strip_prefix(&self.base)
+            .map(Path::to_path_buf)
+        else {
+            return Ok(());
+        };
+        let entry = format!("{}/", rel.to_string_lossy().replace('\\', "/"));
+        let path = self.base.join(".gitignore");
+        let mut content = std::fs::read_to_string(&path).unwrap_or_default();
+        if content.lines().any(|l| l.trim() == entry) {
+            return Ok(());
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!(
+            "\n# Attachment cache (blobs live in the object store)\n{}\n",
+            entry
+        ));
+        std::fs::write(&path, content)?;
+        Ok(())
+    }
+}
+
+/// Readable local path for an attachment reference.
+///
+/// Regular files resolve to themselves; pointers resolve to the cached blob,
+/// fetched from the configured store on first access.
+pub fn resolve(base: &Path, reference: &str) -> Result<PathBuf> {
+    let rel = Path::new(reference.trim());
+    if !contained(rel) {
+        bail!("attachment reference {} leaves the project", reference);
+    }
+    let path = base.join(rel);
+    let Some(pointer) = Pointer::read(&path).with_context(|| format!("reading {}", reference))?
+    else {
+        return Ok(crate::git::lfs::resolve_path(&path)?);
+    };
+    let store = AttachmentStore::open(base)?.ok_or_else(|| {
+        anyhow!(
+            "{} is stored externally but {} is missing",
+            reference,
+            ATTACHMENTS_CONFIG_FILE
+        )
+    })?;
+    store.fetch(&pointer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, Room, RoomType, Wing};
+
+    fn building_with_photos(photos: &[(&str, &str)]) -> Building {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Lab".into(), RoomType::Laboratory);
+        for (key, value) in photos {
+            room.properties.insert(key.to_string(), value.to_string());
+        }
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn pointer_round_trips_and_rejects_regular_files() {
+        let pointer = Pointer {
+            sha256: "ab".repeat(32),
+            size: 42,
+        };
+        assert_eq!(Pointer::parse(&pointer.render()), Some(pointer.clone()));
+        assert_eq!(pointer.key(), format!("sha256/ab/{}", "ab".repeat(32)));
+        assert_eq!(
+            Pointer::parse("arx-attachment v1\nsha256 xyz\nsize 1\n"),
+            None
+        );
+        assert_eq!(Pointer::parse("just a note\n"), None);
+    }
+
+    #[test]
+    fn migrate_moves_large_blobs_and_resolve_fetches_them() {
+        let project = tempfile::tempdir().unwrap();
+        let base = project.path();
+        std::fs::create_dir_all(base.join(".arx")).unwrap();
+        std::fs::write(
+            base.join(ATTACHMENTS_CONFIG_FILE),
+            "store: objects\nmigrate_min_kb: 1\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(base.join("photos")).unwrap();
+        let big = vec![7u8; 4096];
+        std::fs::write(base.join("photos/scan.jpg"), &big).unwrap();
+        std::fs::write(base.join("photos/thumb.jpg"), b"tiny").unwrap();
+        let building = building_with_photos(&[
+            ("photo_scan", "photos/scan.jpg"),
+            ("photo_thumb", "photos/thumb.jpg"),
+            ("attachment_escape", "../outside.pdf"),
+        ]);
+
+        let store = AttachmentStore::open(base).unwrap().unwrap();
+        let min = store.config.migrate_min_kb * 1024;
+        let dry = store.migrate(&building, min, true).unwrap();
+        assert_eq!(dry.moved.len(), 1);
+        assert_eq!(std::fs::read(base.join("photos/scan.jpg")).unwrap(), big);
+
+        let report = store.migrate(&building, min, false).unwrap();
+        assert_eq!(report.moved, vec![(PathBuf::from("photos/scan.jpg"), 4096)]);
+        assert_eq!(report.below_threshold, 1);
+        let pointer = Pointer::read(&base.join("photos/scan.jpg"))
+            .unwrap()
+            .unwrap();
+        assert!(base.join("objects").join(pointer.key()).is_file());
+        assert!(std::fs::read_to_string(base.join(".gitignore"))
+            .unwrap()
+            .contains(".arx/cache/attachments/"));
+
+        // Second run is a no-op
+        let again = store.migrate(&building, min, false).unwrap();
+        assert!(again.moved.is_empty());
+        assert_eq!(again.already_migrated, 1);
+
+        // Cold cache: resolve downloads and verifies the blob
+        std::fs::remove_dir_all(store.cache_dir()).unwrap();
+        let path = resolve(base, "photos/scan.jpg").unwrap();
+        assert_eq!(path, store.cached_path(&pointer));
+        assert_eq!(std::fs::read(&path).unwrap(), big);
+        assert_eq!(
+            resolve(base, "photos/thumb.jpg").unwrap(),
+            base.join("photos/thumb.jpg")
+        );
+
+        // A corrupted object is refused rather than cached
+        std::fs::remove_dir_all(store.cache_dir()).unwrap();
+        std::fs::write(base.join("objects").join(pointer.key()), b"garbage").unwrap();
+        assert!(resolve(base, "photos/scan.jpg").is_err());
+        assert!(!store.cached_path(&pointer).exists());
+    }
+}
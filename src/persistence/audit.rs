@@ -0,0 +1,340 @@
+//! Audit log: who did what, including actions that never become a commit.
+//!
+//! Views, exports, failed or denied requests, and config changes leave no
+//! trace in Git history. Every CLI invocation and agent request (including
+//! those the field app relays) appends one [`AuditEntry`] to
+//! `.arx/audit/audit.jsonl`. The file is append-only, kept out of Git, and
+//! rotated to `audit.jsonl.1` … `audit.jsonl.<keep>` once it outgrows
+//! `max_bytes`. `.arx/audit.yaml` tunes rotation or turns the log off:
+//!
+//! ```yaml
+//! enabled: true
+//! max_bytes: 10485760
+//! keep: 5
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::clock;
+
+use super::{PersistenceError, PersistenceResult};
+
+/// Current log, one JSON object per line.
+pub const AUDIT_LOG: &str = ".arx/audit/audit.jsonl";
+/// Rotation settings.
+pub const AUDIT_CONFIG_FILE: &str = ".arx/audit.yaml";
+
+/// How an audited action ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+    /// Refused by authentication, capabilities, or role policy
+    Denied,
+}
+
+impl AuditOutcome {
+    /// Outcome of a failed action from its error message.
+    pub fn from_error(message: &str) -> Self {
+        if message.contains("Permission denied") || message.contains("Unauthorized") {
+            AuditOutcome::Denied
+        } else {
+            AuditOutcome::Failure
+        }
+    }
+}
+
+impl fmt::Display for AuditOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+            AuditOutcome::Denied => "denied",
+        })
+    }
+}
+
+/// One audited action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    pub actor: String,
+    /// `cli.<command>[.<subcommand>]` or `agent.<method>`
+    pub action: String,
+    /// Entity, file, or arguments the action was applied to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub outcome: AuditOutcome,
+    /// Error message of failed and denied actions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl AuditEntry {
+    /// Entry stamped now, by the acting user (see
+    /// [`super::PersistenceManager::acting_user`]).
+    pub fn new(action: impl Into<String>, target: Option<String>, outcome: AuditOutcome) -> Self {
+        Self {
+            at: clock::now(),
+            actor: crate::core::provenance::current_actor()
+                .unwrap_or_else(super::PersistenceManager::acting_user),
+            action: action.into(),
+            target,
+            outcome,
+            detail: None,
+        }
+    }
+
+    /// Entry for an action that ended with `result`.
+    pub fn for_result<T, E: fmt::Display>(
+        action: impl Into<String>,
+        target: Option<String>,
+        result: &Result<T, E>,
+    ) -> Self {
+        match result {
+            Ok(_) => Self::new(action, target, AuditOutcome::Success),
+            Err(e) => {
+                let message = e.to_string();
+                Self {
+                    detail: Some(message.clone()),
+                    ..Self::new(action, target, AuditOutcome::from_error(&message))
+                }
+            }
+        }
+    }
+
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = actor.into();
+        self
+    }
+}
+
+/// Contents of [`AUDIT_CONFIG_FILE`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Rotate once the current log would grow past this size
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: u64,
+    /// Rotated files kept; older ones are deleted
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_max_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_keep() -> usize {
+    5
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            max_bytes: default_max_bytes(),
+            keep: default_keep(),
+        }
+    }
+}
+
+impl AuditConfig {
+    /// Load `{base}/.arx/audit.yaml`; absent means the defaults.
+    pub fn load_at(base: &Path) -> PersistenceResult<Self> {
+        let path = base.join(AUDIT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&fs::read_to_string(&path)?).map_err(|e| {
+            PersistenceError::SerializationError(format!("Invalid {}: {}", AUDIT_CONFIG_FILE, e))
+        })
+    }
+}
+
+/// `audit.jsonl.<n>`; `n == 0` is the current log.
+fn log_path(base: &Path, n: usize) -> PathBuf {
+    let path = base.join(AUDIT_LOG);
+    if n == 0 {
+        path
+    } else {
+        path.with_extension(format!("jsonl.{}", n))
+    }
+}
+
+/// Shift `audit.jsonl` → `.1` → `.2` …, dropping what falls past `keep`.
+fn rotate(base: &Path, keep: usize) -> PersistenceResult<()> {
+    if keep == 0 {
+        fs::remove_file(log_path(base, 0))?;
+        return Ok(());
+    }
+    let oldest = log_path(base, keep);
+    if oldest.exists() {
+        fs::remove_file(oldest)?;
+    }
+    for n in (0..keep).rev() {
+        let from = log_path(base, n);
+        if from.exists() {
+            fs::rename(from, log_path(base, n + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// Append `entry` to the log under `base`, rotating first when it is full.
+pub fn record(base: &Path, entry: &AuditEntry) -> PersistenceResult<()> {
+    let config = AuditConfig::load_at(base)?;
+    if !config.enabled {
+        return Ok(());
+    }
+    let mut line = serde_json::to_string(entry)
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+    line.push('\n');
+
+    let path = log_path(base, 0);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    if size > 0 && size + line.len() as u64 > config.max_bytes {
+        rotate(base, config.keep)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// [`record`], logging instead of failing: auditing never blocks the action.
+pub fn record_quietly(base: &Path, entry: &AuditEntry) {
+    if let Err(e) = record(base, entry) {
+        log::warn!("Failed to write audit entry for {}: {}", entry.action, e);
+    }
+}
+
+/// Which entries `arx audit search` returns.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Exact actor, case-insensitive
+    pub actor: Option<String>,
+    /// Action prefix (`agent.`, `cli.export`)
+    pub action: Option<String>,
+    /// Substring of the target
+    pub target: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.actor
+            .as_deref()
+            .is_none_or(|a| entry.actor.eq_ignore_ascii_case(a))
+            && self
+                .action
+                .as_deref()
+                .is_none_or(|a| entry.action.starts_with(a))
+            && self.target.as_deref().is_none_or(|t| {
+                entry
+                    .target
+                    .as_deref()
+                    .is_some_and(|target| target.to_lowercase().contains(&t.to_lowercase()))
+            })
+            && self.outcome.is_none_or(|o| entry.outcome == o)
+            && self.since.is_none_or(|since| entry.at >= since)
+    }
+}
+
+/// Every entry under `base`, rotated files included, oldest first.
+/// Unparseable lines are skipped.
+pub fn load_entries(base: &Path) -> PersistenceResult<Vec<AuditEntry>> {
+    let mut files = Vec::new();
+    let mut n = 0;
+    while log_path(base, n).exists() || n == 0 {
+        files.push(log_path(base, n));
+        n += 1;
+    }
+    let mut entries = Vec::new();
+    for path in files.iter().rev().filter(|p| p.exists()) {
+        entries.extend(
+            fs::read_to_string(path)?
+                .lines()
+                .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok()),
+        );
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_rotates_and_reads_back_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        fs::write(
+            dir.path().join(AUDIT_CONFIG_FILE),
+            "max_bytes: 400\nkeep: 2\n",
+        )
+        .unwrap();
+        for i in 0..12 {
+            let result: Result<(), String> = if i % 4 == 3 {
+                Err("Permission denied: dana (viewer) may not update equipment 'AHU-1'".into())
+            } else {
+                Ok(())
+            };
+            let entry = AuditEntry::for_result("cli.export", Some(format!("AHU-{}", i)), &result)
+                .with_actor("dana");
+            record(dir.path(), &entry).unwrap();
+        }
+        assert!(log_path(dir.path(), 1).exists());
+        assert!(log_path(dir.path(), 2).exists());
+        assert!(!log_path(dir.path(), 3).exists());
+
+        let entries = load_entries(dir.path()).unwrap();
+        assert!(entries.len() < 12, "oldest rotated file is dropped");
+        assert_eq!(entries.last().unwrap().target.as_deref(), Some("AHU-11"));
+        assert!(entries.windows(2).all(|w| w[0].at <= w[1].at));
+
+        let denied = AuditFilter {
+            actor: Some("DANA".into()),
+            outcome: Some(AuditOutcome::Denied),
+            ..Default::default()
+        };
+        let denied: Vec<_> = entries.iter().filter(|e| denied.matches(e)).collect();
+        assert!(!denied.is_empty());
+        assert!(denied.iter().all(|e| e
+            .detail
+            .as_deref()
+            .unwrap()
+            .starts_with("Permission denied")));
+    }
+
+    #[test]
+    fn disabled_config_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        fs::write(dir.path().join(AUDIT_CONFIG_FILE), "enabled: false\n").unwrap();
+        record(
+            dir.path(),
+            &AuditEntry::new("cli.status", None, AuditOutcome::Success),
+        )
+        .unwrap();
+        assert!(load_entries(dir.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,577 @@
+//! Scheduled backups with restore verification.
+//!
+//! A backup is a Git bundle of the whole repository plus the working files Git
+//! does not carry (`.arx/` / `.arxos/` state such as issues, sensor inboxes and
+//! caches, and attachments referenced by `photo*` / `attachment*` properties),
+//! each recorded with its SHA-256 in a manifest. Backups go to the target in
+//! `.arx/backup.yaml`; [`verify_backup`] downloads one, checks every digest,
+//! restores it into a scratch directory, and re-runs validation. Attachments
+//! moved to an object store (see [`crate::persistence::attachments`]) are
+//! captured as their pointer files; the blobs stay in that store.
+//!
+//! ```yaml
+//! target: /mnt/nas/arx-backups     # or s3://bucket/prefix
+//! s3:
+//!   endpoint: https://minio.example.org:9000
+//!   region: us-east-1
+//! schedule:
+//!   interval_hours: 24
+//! retention:
+//!   keep_last: 7
+//!   keep_days: 30
+//! ```
+
+pub mod target;
+
+use crate::core::clock;
+use crate::core::Building;
+use crate::persistence::attachments::{contained, referenced_paths, AttachmentConfig};
+use crate::persistence::{load_building_at, BUILDING_YAML};
+use crate::validation::{validate_building, ValidationSeverity};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub use crate::persistence::s3::S3Settings;
+pub use target::{open_target, BackupTarget, LocalTarget, S3Target};
+
+/// Repo-relative backup configuration.
+pub const BACKUP_CONFIG_FILE: &str = ".arx/backup.yaml";
+
+/// Manifest object written last in every backup.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Git bundle object name.
+pub const BUNDLE_FILE: &str = "repo.bundle";
+
+/// Prefix for working files stored next to the bundle.
+const FILES_PREFIX: &str = "files/";
+
+/// Working-tree state directories captured alongside the bundle.
+const STATE_DIRS: &[&str] = &[".arx", ".arxos"];
+
+const MANIFEST_VERSION: u32 = 1;
+
+/// `.arx/backup.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Directory path or `s3://bucket/prefix`
+    pub target: String,
+    #[serde(default)]
+    pub s3: S3Settings,
+    #[serde(default)]
+    pub schedule: BackupSchedule,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupSchedule {
+    /// Minimum hours between scheduled backups (unset: manual only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_hours: Option<u64>,
+}
+
+/// A backup is kept if it is among the newest `keep_last` or younger than `keep_days`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    #[serde(default = "default_keep_last")]
+    pub keep_last: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_days: Option<u64>,
+}
+
+fn default_keep_last() -> usize {
+    7
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: default_keep_last(),
+            keep_days: None,
+        }
+    }
+}
+
+impl BackupConfig {
+    pub fn load(base: &Path) -> Result<Self> {
+        let path = base.join(BACKUP_CONFIG_FILE);
+        let content = std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "no backup configuration: create {} with a `target`",
+                BACKUP_CONFIG_FILE
+            )
+        })?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn open_target(&self, base: &Path) -> Result<Box<dyn BackupTarget>> {
+        open_target(base, &self.target, &self.s3)
+    }
+
+    /// Local target directory inside the project, excluded from captured files.
+    fn local_root_within(&self, base: &Path) -> Option<PathBuf> {
+        if self.target.starts_with("s3://") {
+            return None;
+        }
+        let root = base.join(&self.target);
+        root.strip_prefix(base).ok().map(Path::to_path_buf)
+    }
+}
+
+/// One stored object and its digest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Contents of `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub version: u32,
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub building: Option<String>,
+    /// HEAD commit captured by the bundle
+    pub head: String,
+    /// Validation errors in the captured building (restores must match)
+    pub validation_errors: usize,
+    /// True when `building.yaml` had uncommitted edits (not in the bundle)
+    #[serde(default)]
+    pub uncommitted_changes: bool,
+    pub files: Vec<BackupFile>,
+}
+
+impl BackupManifest {
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+/// Backup id for a creation time (sorts chronologically).
+pub fn backup_id(at: DateTime<Utc>) -> String {
+    format!("arx-backup-{}", at.format("%Y%m%dT%H%M%SZ"))
+}
+
+fn sha256_file(path: &Path) -> Result<(u64, String)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)?;
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+fn git(base: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(base)
+        .args(args)
+        .output()
+        .context("running git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn collect_dir(
+    base: &Path,
+    rel: &Path,
+    skip: &[PathBuf],
+    out: &mut BTreeSet<PathBuf>,
+) -> Result<()> {
+    if skip.iter().any(|s| rel.starts_with(s)) {
+        return Ok(());
+    }
+    let dir = base.join(rel);
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        let child = rel.join(entry.file_name());
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            collect_dir(base, &child, skip, out)?;
+        } else if kind.is_file() && !skip.iter().any(|s| child.starts_with(s)) {
+            out.insert(child);
+        }
+    }
+    Ok(())
+}
+
+/// Working files captured next to the bundle (repo-relative).
+pub fn working_files(base: &Path, config: &BackupConfig) -> Result<BTreeSet<PathBuf>> {
+    let mut skip: Vec<PathBuf> = config.local_root_within(base).into_iter().collect();
+    // Externally stored attachments are captured as pointers; their cached blobs stay out
+    if let Ok(Some(attachments)) = AttachmentConfig::load(base) {
+        skip.push(PathBuf::from(attachments.cache_dir));
+    }
+    let mut files = BTreeSet::new();
+    for dir in STATE_DIRS {
+        collect_dir(base, Path::new(dir), &skip, &mut files)?;
+    }
+    if let Ok(building) = load_building_at(base) {
+        files.extend(referenced_paths(base, &building));
+    }
+    Ok(files)
+}
+
+/// Create a backup of `base` and upload it to the configured target.
+pub fn create_backup(base: &Path, config: &BackupConfig) -> Result<BackupManifest> {
+    let target = config.open_target(base)?;
+    let created_at = clock::now();
+    let id = backup_id(created_at);
+    let staging = tempfile::tempdir()?;
+
+    let head = git(base, &["rev-parse", "HEAD"])
+        .map_err(|_| anyhow!("nothing to back up: {} has no commits", base.display()))?;
+    let bundle = staging.path().join(BUNDLE_FILE);
+    git(
+        base,
+        &["bundle", "create", &bundle.to_string_lossy(), "--all"],
+    )?;
+    let uncommitted_changes =
+        !git(base, &["status", "--porcelain", "--", BUILDING_YAML])?.is_empty();
+    let building = load_building_at(base).ok();
+    let validation_errors = building.as_ref().map_or(0, error_count);
+
+    let mut files = Vec::new();
+    let (size, sha256) = sha256_file(&bundle)?;
+    target.put(&id, BUNDLE_FILE, &bundle)?;
+    files.push(BackupFile {
+        name: BUNDLE_FILE.to_string(),
+        size,
+        sha256,
+    });
+    for rel in working_files(base, config)? {
+        let name = format!(
+            "{}{}",
+            FILES_PREFIX,
+            rel.to_string_lossy().replace('\\', "/")
+        );
+        let source = base.join(&rel);
+        let (size, sha256) = sha256_file(&source)?;
+        target.put(&id, &name, &source)?;
+        files.push(BackupFile { name, size, sha256 });
+    }
+
+    let manifest = BackupManifest {
+        version: MANIFEST_VERSION,
+        id: id.clone(),
+        created_at,
+        building: building.map(|b| b.name),
+        head,
+        validation_errors,
+        uncommitted_changes,
+        files,
+    };
+    let manifest_path = staging.path().join(MANIFEST_FILE);
+    std::fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+    // Written last: a backup without a manifest is incomplete and never listed
+    target.put(&id, MANIFEST_FILE, &manifest_path)?;
+    Ok(manifest)
+}
+
+fn error_count(building: &Building) -> usize {
+    let report = validate_building(building);
+    report
+        .results
+        .iter()
+        .filter(|r| r.severity == ValidationSeverity::Error)
+        .count()
+}
+
+/// Manifest of backup `id`.
+pub fn read_manifest(target: &dyn BackupTarget, id: &str) -> Result<BackupManifest> {
+    let scratch = tempfile::tempdir()?;
+    let path = scratch.path().join(MANIFEST_FILE);
+    target.get(id, MANIFEST_FILE, &path)?;
+    let manifest: BackupManifest = serde_json::from_slice(&std::fs::read(&path)?)
+        .with_context(|| format!("invalid manifest in backup {}", id))?;
+    if manifest.version > MANIFEST_VERSION {
+        bail!(
+            "backup {} uses manifest v{}, newer than this build",
+            id,
+            manifest.version
+        );
+    }
+    Ok(manifest)
+}
+
+/// Manifests of every complete backup, oldest first.
+pub fn list_backups(target: &dyn BackupTarget) -> Result<Vec<BackupManifest>> {
+    target
+        .list()?
+        .iter()
+        .map(|id| read_manifest(target, id))
+        .collect()
+}
+
+/// Whether a scheduled backup is due given the newest existing one.
+pub fn is_due(config: &BackupConfig, latest: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    match (config.schedule.interval_hours, latest) {
+        (Some(hours), Some(latest)) => now - latest >= Duration::hours(hours as i64),
+        _ => true,
+    }
+}
+
+/// Ids the retention policy drops from `backups` (oldest first).
+pub fn retention_candidates(
+    policy: &RetentionPolicy,
+    backups: &[BackupManifest],
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut newest_first: Vec<&BackupManifest> = backups.iter().collect();
+    newest_first.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+    let mut drop: Vec<String> = newest_first
+        .iter()
+        .enumerate()
+        .filter(|(i, m)| {
+            let recent = policy
+                .keep_days
+                .is_some_and(|days| now - m.created_at < Duration::days(days as i64));
+            *i >= policy.keep_last.max(1) && !recent
+        })
+        .map(|(_, m)| m.id.clone())
+        .collect();
+    drop.reverse();
+    drop
+}
+
+/// Apply the retention policy; returns the removed ids.
+pub fn prune_backups(config: &BackupConfig, target: &dyn BackupTarget) -> Result<Vec<String>> {
+    let backups = list_backups(target)?;
+    let drop = retention_candidates(&config.retention, &backups, clock::now());
+    for id in &drop {
+        target.delete(id)?;
+    }
+    Ok(drop)
+}
+
+/// Outcome of [`verify_backup`].
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    pub id: String,
+    pub files_checked: usize,
+    pub bytes_checked: u64,
+    pub restored_head: Option<String>,
+    pub validation_errors: Option<usize>,
+    /// Empty when the backup is restorable and matches its manifest
+    pub problems: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Download backup `id`, check digests, restore into a scratch directory, and
+/// re-run validation against what the manifest recorded.
+pub fn verify_backup(target: &dyn BackupTarget, id: &str) -> Result<VerifyReport> {
+    let manifest = read_manifest(target, id)?;
+    let scratch = tempfile::tempdir()?;
+    let download = scratch.path().join("download");
+    let restore = scratch.path().join("restore");
+    std::fs::create_dir_all(&download)?;
+
+    let mut report = VerifyReport {
+        id: id.to_string(),
+        files_checked: 0,
+        bytes_checked: 0,
+        restored_head: None,
+        validation_errors: None,
+        problems: Vec::new(),
+    };
+
+    for file in &manifest.files {
+        let rel = Path::new(&file.name);
+        if !contained(rel) {
+            report
+                .problems
+                .push(format!("{}: unsafe path in manifest", file.name));
+            continue;
+        }
+        let local = download.join(rel);
+        if let Some(parent) = local.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if let Err(e) = target.get(id, &file.name, &local) {
+            report.problems.push(format!("{}: {:#}", file.name, e));
+            continue;
+        }
+        let (size, sha256) = sha256_file(&local)?;
+        report.files_checked += 1;
+        report.bytes_checked += size;
+        if size != file.size || sha256 != file.sha256 {
+            report
+                .problems
+                .push(format!("{}: checksum mismatch", file.name));
+        }
+    }
+    if !report.problems.is_empty() {
+        return Ok(report);
+    }
+
+    let bundle = download.join(BUNDLE_FILE);
+    let clone = Command::new("git")
+        .args(["clone", "--quiet"])
+        .arg(&bundle)
+        .arg(&restore)
+        .output()
+        .context("running git clone")?;
+    if !clone.status.success() {
+        report.problems.push(format!(
+            "bundle does not restore: {}",
+            String::from_utf8_lossy(&clone.stderr).trim()
+        ));
+        return Ok(report);
+    }
+    let head = git(&restore, &["rev-parse", "HEAD"])?;
+    if head != manifest.head {
+        report.problems.push(format!(
+            "restored HEAD {} does not match recorded {}",
+            head, manifest.head
+        ));
+    }
+    report.restored_head = Some(head);
+
+    let files_root = download.join(FILES_PREFIX.trim_end_matches('/'));
+    if files_root.is_dir() {
+        let mut files = BTreeSet::new();
+        collect_dir(&files_root, Path::new(""), &[], &mut files)?;
+        for rel in files {
+            let dest = restore.join(&rel);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(files_root.join(&rel), dest)?;
+        }
+    }
+
+    if manifest.building.is_some() {
+        match load_building_at(&restore) {
+            Ok(building) => {
+                let errors = error_count(&building);
+                // Uncommitted edits were not bundled, so the counts may legitimately differ
+                if errors != manifest.validation_errors && !manifest.uncommitted_changes {
+                    report.problems.push(format!(
+                        "restored building has {} validation error(s), {} recorded",
+                        errors, manifest.validation_errors
+                    ));
+                }
+                report.validation_errors = Some(errors);
+            }
+            Err(e) => report
+                .problems
+                .push(format!("restored {} does not load: {}", BUILDING_YAML, e)),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(id: &str, created_at: DateTime<Utc>) -> BackupManifest {
+        BackupManifest {
+            version: MANIFEST_VERSION,
+            id: id.to_string(),
+            created_at,
+            building: None,
+            head: String::new(),
+            validation_errors: 0,
+            uncommitted_changes: false,
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn retention_keeps_newest_and_recent_backups() {
+        let now = Utc::now();
+        let backups: Vec<_> = (0..5)
+            .map(|d| manifest(&format!("b{}", d), now - Duration::days(d * 10)))
+            .collect();
+        let policy = RetentionPolicy {
+            keep_last: 2,
+            keep_days: Some(25),
+        };
+        // b0, b1 newest; b2 is 20 days old; b3 / b4 go
+        assert_eq!(
+            retention_candidates(&policy, &backups, now),
+            vec!["b4", "b3"]
+        );
+
+        let config: BackupConfig =
+            serde_yaml::from_str("target: backups\nschedule: { interval_hours: 24 }\n").unwrap();
+        assert!(!is_due(&config, Some(now - Duration::hours(3)), now));
+        assert!(is_due(&config, Some(now - Duration::hours(25)), now));
+        assert!(is_due(&config, None, now));
+    }
+
+    #[test]
+    fn backup_round_trips_and_detects_tampering() {
+        let project = tempfile::tempdir().unwrap();
+        let base = project.path();
+        git(base, &["init", "-q"]).unwrap();
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = crate::core::Floor::new("Ground".into(), 0);
+        let mut wing = crate::core::Wing::new("East".into());
+        let mut room = crate::core::Room::new("Lab".into(), crate::core::RoomType::Laboratory);
+        room.properties
+            .insert("photo_main".into(), "photos/lab.jpg".into());
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        crate::ingest::persist_building_at(base, building, true, Some("Initial")).unwrap();
+        std::fs::create_dir_all(base.join("photos")).unwrap();
+        std::fs::write(base.join("photos/lab.jpg"), b"jpeg").unwrap();
+        std::fs::create_dir_all(base.join(".arx/issues")).unwrap();
+        std::fs::write(base.join(".arx/issues/1.yaml"), "id: 1\n").unwrap();
+
+        // Local target inside the project is not captured into itself
+        let config: BackupConfig = serde_yaml::from_str("target: .arx/backups\n").unwrap();
+        let manifest = create_backup(base, &config).unwrap();
+        let names: Vec<&str> = manifest.files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                BUNDLE_FILE,
+                "files/.arx/issues/1.yaml",
+                "files/photos/lab.jpg"
+            ]
+        );
+
+        let target = config.open_target(base).unwrap();
+        let report = verify_backup(target.as_ref(), &manifest.id).unwrap();
+        assert!(report.is_ok(), "{:?}", report.problems);
+        assert_eq!(report.files_checked, 3);
+        assert_eq!(report.validation_errors, Some(manifest.validation_errors));
+
+        std::fs::write(
+            base.join(".arx/backups")
+                .join(&manifest.id)
+                .join("files/photos/lab.jpg"),
+            b"corrupt",
+        )
+        .unwrap();
+        let report = verify_backup(target.as_ref(), &manifest.id).unwrap();
+        assert_eq!(
+            report.problems,
+            vec!["files/photos/lab.jpg: checksum mismatch"]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Backup storage targets: a local / mounted directory or an S3-compatible bucket.
+//!
+//! Each backup is a flat set of named objects under `<target>/<backup id>/`.
+//! The manifest is written last, so only backups with a manifest are listed.
+
+use super::MANIFEST_FILE;
+use crate::persistence::s3::{S3Client, S3Settings};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Where backups are stored.
+pub trait BackupTarget {
+    /// Human-readable location for messages.
+    fn describe(&self) -> String;
+    /// Store `source` as `name` in backup `id`.
+    fn put(&self, id: &str, name: &str, source: &Path) -> Result<()>;
+    /// Fetch `name` of backup `id` into `dest`.
+    fn get(&self, id: &str, name: &str, dest: &Path) -> Result<()>;
+    /// Ids of complete backups (those with a manifest).
+    fn list(&self) -> Result<Vec<String>>;
+    /// Remove backup `id` and everything in it.
+    fn delete(&self, id: &str) -> Result<()>;
+}
+
+/// Backups in a directory (local disk, NAS mount, ...).
+pub struct LocalTarget {
+    pub root: PathBuf,
+}
+
+impl BackupTarget for LocalTarget {
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn put(&self, id: &str, name: &str, source: &Path) -> Result<()> {
+        let dest = self.root.join(id).join(name);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(source, &dest).with_context(|| format!("writing {}", dest.display()))?;
+        Ok(())
+    }
+
+    fn get(&self, id: &str, name: &str, dest: &Path) -> Result<()> {
+        let source = self.root.join(id).join(name);
+        std::fs::copy(&source, dest).with_context(|| format!("reading {}", source.display()))?;
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        if !self.root.exists() {
+            return Ok(Vec::new());
+        }
+        let mut ids = Vec::new();
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if path.join(MANIFEST_FILE).is_file() {
+                if let Some(id) = path.file_name().and_then(|n| n.to_str()) {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn delete(&self, id: &str) -> Result<()> {
+        let dir = self.root.join(id);
+        std::fs::remove_dir_all(&dir).with_context(|| format!("removing {}", dir.display()))
+    }
+}
+
+/// Backups in an S3-compatible bucket, one key prefix per backup id.
+pub struct S3Target {
+    pub client: S3Client,
+}
+
+impl S3Target {
+    /// Parse `s3://bucket[/prefix]`.
+    pub fn parse(url: &str, settings: S3Settings) -> Result<Self> {
+        Ok(Self {
+            client: S3Client::parse(url, settings)?,
+        })
+    }
+
+    fn key(&self, id: &str, name: &str) -> String {
+        self.client.key(&format!("{}/{}", id, name))
+    }
+}
+
+impl BackupTarget for S3Target {
+    fn describe(&self) -> String {
+        self.client.describe()
+    }
+
+    fn put(&self, id: &str, name: &str, source: &Path) -> Result<()> {
+        self.client.put(&self.key(id, name), source)
+    }
+
+    fn get(&self, id: &str, name: &str, dest: &Path) -> Result<()> {
+        self.client.get(&self.key(id, name), dest)
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        let base = self.client.key_prefix();
+        let suffix = format!("/{}", MANIFEST_FILE);
+        let mut ids: Vec<String> = self
+            .client
+            .list_keys(&base)?
+            .iter()
+            .filter_map(|k| k.strip_prefix(&base)?.strip_suffix(&suffix))
+            .filter(|id| !id.contains('/'))
+            .map(str::to_string)
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn delete(&self, id: &str) -> Result<()> {
+        for key in self.client.list_keys(&self.client.key(&format!("{}/", id)))? {
+            self.client.delete(&key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Target for a configured location: `s3://bucket/prefix` or a directory path
+/// (relative paths resolve against the project root).
+pub fn open_target(base: &Path, location: &str, s3: &S3Settings) -> Result<Box<dyn BackupTarget>> {
+    if location.starts_with("s3://") {
+        return Ok(Box::new(S3Target::parse(location, s3.clone())?));
+    }
+    let path = Path::new(location);
+    let root = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    };
+    Ok(Box::new(LocalTarget { root }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn s3_urls_are_path_style_and_encoded() {
+        let target = S3Target::parse(
+            "s3://arx-backups/site-a/",
+            S3Settings {
+                endpoint: "http://minio.local:9000/".into(),
+                region: "us-east-1".into(),
+            },
+        )
+        .unwrap();
+        assert_eq!(target.client.prefix, "site-a");
+        assert_eq!(
+            target
+                .client
+                .object_url(&target.key("b1", "files/.arx/my notes.yaml")),
+            "http://minio.local:9000/arx-backups/site-a/b1/files/.arx/my%20notes.yaml"
+        );
+        assert!(S3Target::parse("s3:///x", S3Settings::default()).is_err());
+    }
+}
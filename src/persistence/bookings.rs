@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::core::clock;
+use crate::core::domain::booking::{
+    booking_conflicts, OccupancySample, RoomBooking, LOCAL_BOOKING_SOURCE, OCCUPANCY_SENSOR_TYPES,
+};
+use crate::core::Building;
+
+use super::schema::{self, RESERVATION};
+use super::sensors::load_sensor_readings;
+use super::{PersistenceError, PersistenceResult};
+
+/// Imported room bookings, one JSON object per line, kept next to the
+/// occupancy readings they are compared with.
+pub const BOOKINGS_LOG: &str = ".arx/sensors/bookings.jsonl";
+
+/// Reservations made with `arx room book`, one YAML file each.
+pub const RESERVATIONS_DIR: &str = ".arx/reservations";
+
+/// Stored bookings, earliest first. Unparseable lines are skipped.
+pub fn load_bookings(base_dir: &Path) -> PersistenceResult<Vec<RoomBooking>> {
+    let path = base_dir.join(BOOKINGS_LOG);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut bookings: Vec<RoomBooking> = fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    bookings.sort_by(|a, b| a.start.cmp(&b.start).then(a.id.cmp(&b.id)));
+    Ok(bookings)
+}
+
+/// Merge `incoming` into the store, replacing earlier copies of the same
+/// event (same source and id); returns how many were new or changed.
+pub fn store_bookings(base_dir: &Path, incoming: Vec<RoomBooking>) -> PersistenceResult<usize> {
+    let mut stored: HashMap<(String, String), RoomBooking> = load_bookings(base_dir)?
+        .into_iter()
+        .map(|b| ((b.source.clone(), b.id.clone()), b))
+        .collect();
+    let mut changed = 0;
+    for booking in incoming {
+        let key = (booking.source.clone(), booking.id.clone());
+        if stored.get(&key) != Some(&booking) {
+            stored.insert(key, booking);
+            changed += 1;
+        }
+    }
+
+    let path = base_dir.join(BOOKINGS_LOG);
+    if crate::core::operations::is_dry_run() {
+        if changed > 0 {
+            crate::core::operations::dry_run::record_file_write(BOOKINGS_LOG, path.exists());
+        }
+        return Ok(changed);
+    }
+    if changed == 0 {
+        return Ok(0);
+    }
+    let mut bookings: Vec<RoomBooking> = stored.into_values().collect();
+    bookings.sort_by(|a, b| a.start.cmp(&b.start).then(a.id.cmp(&b.id)));
+    let mut content = String::new();
+    for booking in &bookings {
+        content.push_str(
+            &serde_json::to_string(booking)
+                .map_err(|e| PersistenceError::SerializationError(e.to_string()))?,
+        );
+        content.push('\n');
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(changed)
+}
+
+/// Reservations made in ArxOS, earliest first.
+pub fn load_reservations(base_dir: &Path) -> PersistenceResult<Vec<RoomBooking>> {
+    let dir = base_dir.join(RESERVATIONS_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reservations = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        reservations.push(schema::from_yaml::<RoomBooking>(
+            &RESERVATION,
+            &path.display().to_string(),
+            &content,
+        )?);
+    }
+    reservations.sort_by(|a, b| a.start.cmp(&b.start).then(a.id.cmp(&b.id)));
+    Ok(reservations)
+}
+
+/// Imported bookings and local reservations together, earliest first.
+pub fn all_bookings(base_dir: &Path) -> PersistenceResult<Vec<RoomBooking>> {
+    let mut bookings = load_bookings(base_dir)?;
+    bookings.extend(load_reservations(base_dir)?);
+    bookings.sort_by(|a, b| a.start.cmp(&b.start).then(a.id.cmp(&b.id)));
+    Ok(bookings)
+}
+
+/// Store a new reservation of a resolved room, refusing to double-book it.
+/// The id and source are assigned here.
+pub fn reserve(base_dir: &Path, mut booking: RoomBooking) -> PersistenceResult<RoomBooking> {
+    if booking.room_id.is_none() {
+        return Err(PersistenceError::ValidationError(format!(
+            "Room '{}' not found",
+            booking.room
+        )));
+    }
+    if booking.end <= booking.start {
+        return Err(PersistenceError::ValidationError(
+            "A reservation must end after it starts".to_string(),
+        ));
+    }
+    booking.id = new_reservation_id();
+    booking.source = LOCAL_BOOKING_SOURCE.to_string();
+
+    let existing = all_bookings(base_dir)?;
+    if let Some(other) = booking_conflicts(&booking, &existing).first() {
+        return Err(PersistenceError::ValidationError(format!(
+            "{} is already booked {} – {} ({}{})",
+            booking.room,
+            other.start.format("%Y-%m-%d %H:%M"),
+            other.end.format("%H:%M"),
+            other.title.as_deref().unwrap_or("untitled"),
+            other
+                .organizer
+                .as_deref()
+                .map(|o| format!(", {}", o))
+                .unwrap_or_default()
+        )));
+    }
+
+    let rel = format!("{}/{}.yaml", RESERVATIONS_DIR, booking.id);
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(&rel, false);
+        return Ok(booking);
+    }
+    fs::create_dir_all(base_dir.join(RESERVATIONS_DIR))?;
+    fs::write(base_dir.join(rel), schema::to_yaml(&RESERVATION, &booking)?)?;
+    Ok(booking)
+}
+
+fn new_reservation_id() -> String {
+    let short: String = clock::new_uuid()
+        .simple()
+        .to_string()
+        .chars()
+        .take(8)
+        .collect();
+    format!("RES-{}-{}", clock::now().format("%Y%m%d"), short)
+}
+
+/// Occupancy readings from sensors mapped to equipment in a room, attributed
+/// to that room.
+pub fn room_occupancy_samples(
+    base_dir: &Path,
+    building: &Building,
+) -> PersistenceResult<Vec<OccupancySample>> {
+    let mut sensor_rooms: HashMap<&str, &str> = HashMap::new();
+    for room in building.get_all_rooms() {
+        for eq in &room.equipment {
+            for m in eq.sensor_mappings.iter().flatten() {
+                if OCCUPANCY_SENSOR_TYPES.contains(&m.sensor_type.as_str()) {
+                    sensor_rooms.insert(m.sensor_id.as_str(), room.id.as_str());
+                }
+            }
+        }
+    }
+    Ok(load_sensor_readings(base_dir)?
+        .into_iter()
+        .filter_map(|r| {
+            sensor_rooms
+                .get(r.sensor_id.as_str())
+                .map(|room_id| OccupancySample {
+                    room_id: room_id.to_string(),
+                    at: r.at,
+                    value: r.value,
+                })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn store_replaces_same_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let booking = RoomBooking {
+            id: "evt-1".into(),
+            source: "ics".into(),
+            room: "Conference A".into(),
+            room_id: None,
+            start,
+            end: start + chrono::Duration::hours(1),
+            title: Some("Standup".into()),
+            organizer: None,
+        };
+        assert_eq!(
+            store_bookings(dir.path(), vec![booking.clone()]).unwrap(),
+            1
+        );
+        assert_eq!(
+            store_bookings(dir.path(), vec![booking.clone()]).unwrap(),
+            0
+        );
+
+        let mut moved = booking.clone();
+        moved.end = start + chrono::Duration::hours(2);
+        let mut other = booking;
+        other.source = "graph".into();
+        assert_eq!(store_bookings(dir.path(), vec![moved, other]).unwrap(), 2);
+        let stored = load_bookings(dir.path()).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(stored
+            .iter()
+            .all(|b| b.source == "graph" || b.hours() == 2.0));
+    }
+    #[test]
+    fn reserve_rejects_double_booking() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let imported = RoomBooking {
+            id: "evt-1".into(),
+            source: "ics".into(),
+            room: "Huddle".into(),
+            room_id: Some("huddle".into()),
+            start,
+            end: start + chrono::Duration::hours(1),
+            title: Some("Standup".into()),
+            organizer: None,
+        };
+        store_bookings(dir.path(), vec![imported.clone()]).unwrap();
+
+        let clash = RoomBooking {
+            start: start + chrono::Duration::minutes(30),
+            end: start + chrono::Duration::minutes(90),
+            ..imported.clone()
+        };
+        assert!(reserve(dir.path(), clash).is_err());
+
+        let after = RoomBooking {
+            start: start + chrono::Duration::hours(1),
+            end: start + chrono::Duration::hours(2),
+            ..imported
+        };
+        let saved = reserve(dir.path(), after).unwrap();
+        assert!(saved.id.starts_with("RES-"));
+        assert_eq!(load_reservations(dir.path()).unwrap(), [saved]);
+        assert_eq!(all_bookings(dir.path()).unwrap().len(), 2);
+    }
+}
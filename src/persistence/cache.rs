@@ -0,0 +1,134 @@
+//! Process-wide cache of parsed `building.yaml` files.
+//!
+//! Parsing a 10k-equipment model takes far longer than the agent and TUI
+//! calls that read it, so each file is parsed once and reused until it
+//! changes. An entry is trusted while the file's mtime and size match; a file
+//! modified within [`RACY_WINDOW`] of the check (Git's "racily clean" case,
+//! where a second write can land in the same mtime tick) is also compared by
+//! its Git blob id before reuse. Saves through the persistence layer
+//! invalidate their entry.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use git2::{ObjectType, Oid};
+
+use super::{PersistenceError, PersistenceResult};
+use crate::core::Building;
+
+/// Files modified this recently are verified by content before reuse.
+pub const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Distinct files kept; beyond this the cache starts over.
+const MAX_ENTRIES: usize = 16;
+
+struct Entry {
+    modified: SystemTime,
+    len: u64,
+    oid: Oid,
+    building: Arc<Building>,
+}
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, Entry>> {
+    static ENTRIES: OnceLock<Mutex<HashMap<PathBuf, Entry>>> = OnceLock::new();
+    ENTRIES.get_or_init(Default::default)
+}
+
+fn key(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The building parsed from `path`, reusing the cached copy while the file
+/// is unchanged. `parse` runs only on a miss.
+pub fn load(
+    path: &Path,
+    parse: impl FnOnce(&str) -> PersistenceResult<Building>,
+) -> PersistenceResult<Arc<Building>> {
+    let key = key(path);
+    let meta = fs::metadata(&key)?;
+    let (modified, len) = (meta.modified()?, meta.len());
+    let racy = SystemTime::now()
+        .duration_since(modified)
+        .map_or(true, |age| age < RACY_WINDOW);
+
+    let cached_oid = {
+        let entries = cache().lock().unwrap();
+        match entries.get(&key) {
+            Some(e) if e.modified == modified && e.len == len && !racy => {
+                return Ok(e.building.clone());
+            }
+            Some(e) => Some(e.oid),
+            None => None,
+        }
+    };
+
+    let content = fs::read(&key)?;
+    let oid = Oid::hash_object(ObjectType::Blob, &content)
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+    let mut entries = cache().lock().unwrap();
+    if cached_oid == Some(oid) {
+        if let Some(entry) = entries.get_mut(&key) {
+            entry.modified = modified;
+            entry.len = len;
+            return Ok(entry.building.clone());
+        }
+    }
+    drop(entries);
+
+    let text = String::from_utf8(content)
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+    let building = Arc::new(parse(&text)?);
+    let mut entries = cache().lock().unwrap();
+    if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+        entries.clear();
+    }
+    entries.insert(
+        key,
+        Entry {
+            modified,
+            len,
+            oid,
+            building: building.clone(),
+        },
+    );
+    Ok(building)
+}
+
+/// Drop the cached copy of `path` (after writing it).
+pub fn invalidate(path: &Path) {
+    cache().lock().unwrap().remove(&key(path));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn reparses_only_when_the_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("building.yaml");
+        fs::write(&path, "A").unwrap();
+        let parses = Cell::new(0);
+        let parse = |text: &str| {
+            parses.set(parses.get() + 1);
+            Ok(Building::new(text.to_string(), "/x".into()))
+        };
+
+        assert_eq!(load(&path, parse).unwrap().name, "A");
+        assert_eq!(load(&path, parse).unwrap().name, "A");
+        assert_eq!(parses.get(), 1);
+
+        // Same size, same (racy) mtime tick: caught by the blob id
+        fs::write(&path, "B").unwrap();
+        assert_eq!(load(&path, parse).unwrap().name, "B");
+        assert_eq!(parses.get(), 2);
+
+        invalidate(&path);
+        load(&path, parse).unwrap();
+        assert_eq!(parses.get(), 3);
+    }
+}
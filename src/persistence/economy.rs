@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 use crate::core::domain::economy::{ContributionRecord, EconomySnapshot};
 
+use super::schema::{self, ECONOMY_SNAPSHOT};
 use super::PersistenceResult;
 
 const ECONOMY_DIR: &str = ".arxos/economy";
@@ -19,9 +20,8 @@ pub fn load_snapshot(base_dir: &Path) -> PersistenceResult<EconomySnapshot> {
         });
     }
 
-    let content = fs::read_to_string(path)?;
-    let snapshot = serde_yaml::from_str(&content)?;
-    Ok(snapshot)
+    let content = fs::read_to_string(&path)?;
+    schema::from_yaml(&ECONOMY_SNAPSHOT, &path.display().to_string(), &content)
 }
 
 pub fn save_snapshot(base_dir: &Path, snapshot: &EconomySnapshot) -> PersistenceResult<()> {
@@ -29,8 +29,8 @@ pub fn save_snapshot(base_dir: &Path, snapshot: &EconomySnapshot) -> Persistence
     fs::create_dir_all(&dir)?;
 
     let path = economy_path(base_dir, SNAPSHOT_FILE);
-    let content = serde_yaml::to_string(snapshot)?;
-    fs::write(path, content)?;
+    schema::ensure_writable(&ECONOMY_SNAPSHOT, &path)?;
+    fs::write(path, schema::to_yaml(&ECONOMY_SNAPSHOT, snapshot)?)?;
     Ok(())
 }
 
@@ -39,7 +39,7 @@ pub fn append_contribution(base_dir: &Path, record: ContributionRecord) -> Persi
     fs::create_dir_all(&dir)?;
 
     let path = economy_path(base_dir, CONTRIBUTIONS_FILE);
-    let content = serde_yaml::to_string(&record)?;
+    let content = crate::yaml::to_canonical_yaml(&record)?;
 
     // Append to file
     if path.exists() {
@@ -0,0 +1,399 @@
+//! Energy meter storage: the meter list and per-meter consumption intervals.
+//!
+//! Meters live in [`METERS_FILE`]; each meter's intervals are appended to an
+//! `start,end,value` CSV under [`ENERGY_DATA_DIR`], like sensor history.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::core::clock;
+use crate::core::energy::{rollup, Interval, Meter, Period, Rollup, Utility};
+use crate::core::Building;
+
+use super::sensors::file_stem;
+use super::{PersistenceError, PersistenceResult};
+
+/// Registered meters.
+pub const METERS_FILE: &str = ".arx/energy/meters.yaml";
+
+/// Per-meter interval history: one append-only `start,end,value` CSV per meter.
+pub const ENERGY_DATA_DIR: &str = ".arx/energy-data";
+
+/// Interval assumed for a meter's first reading when no start is given.
+const DEFAULT_INTERVAL_MINUTES: i64 = 60;
+
+/// Interval history file for `meter_id`, relative to the project root.
+pub fn meter_data_file(meter_id: &str) -> String {
+    format!("{}/{}.csv", ENERGY_DATA_DIR, file_stem(meter_id))
+}
+
+/// Registered meters, by id.
+pub fn load_meters(base_dir: &Path) -> PersistenceResult<Vec<Meter>> {
+    let path = base_dir.join(METERS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut meters: Vec<Meter> = serde_yaml::from_str(&fs::read_to_string(path)?)?;
+    meters.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(meters)
+}
+
+fn save_meters(base_dir: &Path, meters: &[Meter]) -> PersistenceResult<()> {
+    let path = base_dir.join(METERS_FILE);
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(METERS_FILE, path.exists());
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, crate::yaml::to_canonical_yaml(meters)?)?;
+    Ok(())
+}
+
+fn load_meter(base_dir: &Path, meter_id: &str) -> PersistenceResult<Meter> {
+    load_meters(base_dir)?
+        .into_iter()
+        .find(|m| m.id == meter_id)
+        .ok_or_else(|| {
+            PersistenceError::ValidationError(format!(
+                "Meter '{}' is not registered (arx energy meter add)",
+                meter_id
+            ))
+        })
+}
+
+/// Display name of what `meter` measures.
+pub fn meter_target(building: &Building, meter: &Meter) -> String {
+    if let Some(id) = &meter.equipment {
+        return building
+            .find_equipment(id)
+            .map(|eq| eq.name.clone())
+            .unwrap_or_else(|| format!("{} (missing)", id));
+    }
+    if let Some(id) = &meter.floor {
+        return building
+            .floors
+            .iter()
+            .find(|f| &f.id == id)
+            .map(|f| format!("floor {}", f.name))
+            .unwrap_or_else(|| format!("floor {} (missing)", id));
+    }
+    "building".to_string()
+}
+
+/// Register meter `id` on the equipment or floor matching `equipment` /
+/// `floor` (id, name, alias, or floor level).
+pub fn add_meter(
+    base_dir: &Path,
+    building: &Building,
+    id: &str,
+    utility: Utility,
+    equipment: Option<&str>,
+    floor: Option<&str>,
+) -> PersistenceResult<Meter> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err(PersistenceError::ValidationError(
+            "Meter id is empty".into(),
+        ));
+    }
+    let invalid = |msg: String| PersistenceError::ValidationError(msg);
+    let meter = match (equipment, floor) {
+        (Some(query), None) => Meter {
+            id: id.to_string(),
+            utility,
+            equipment: Some(
+                building
+                    .find_equipment(query)
+                    .ok_or_else(|| invalid(format!("Equipment '{}' not found", query)))?
+                    .id
+                    .clone(),
+            ),
+            floor: None,
+        },
+        (None, Some(query)) => Meter {
+            id: id.to_string(),
+            utility,
+            equipment: None,
+            floor: Some(
+                building
+                    .floors
+                    .iter()
+                    .find(|f| {
+                        f.id == query
+                            || f.name.eq_ignore_ascii_case(query)
+                            || query.parse() == Ok(f.level)
+                    })
+                    .ok_or_else(|| invalid(format!("Floor '{}' not found", query)))?
+                    .id
+                    .clone(),
+            ),
+        },
+        _ => {
+            return Err(invalid(
+                "A meter is tied to exactly one of --equipment or --floor".into(),
+            ))
+        }
+    };
+
+    let mut meters = load_meters(base_dir)?;
+    if meters.iter().any(|m| m.id == meter.id) {
+        return Err(invalid(format!("Meter '{}' already exists", meter.id)));
+    }
+    meters.push(meter.clone());
+    save_meters(base_dir, &meters)?;
+    Ok(meter)
+}
+
+fn parse_interval(line: &str) -> Option<Interval> {
+    let mut fields = line.split(',').map(str::trim);
+    let start = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+    let end = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+    Some(Interval {
+        start: start.with_timezone(&Utc),
+        end: end.with_timezone(&Utc),
+        value: fields.next()?.parse().ok()?,
+    })
+}
+
+fn check_interval(interval: &Interval) -> Result<(), String> {
+    if interval.end <= interval.start {
+        return Err("interval end must be after its start".into());
+    }
+    if !interval.value.is_finite() || interval.value < 0.0 {
+        return Err(format!("consumption must be ≥ 0 (got {})", interval.value));
+    }
+    Ok(())
+}
+
+fn append_intervals(
+    base_dir: &Path,
+    meter_id: &str,
+    intervals: &[Interval],
+) -> PersistenceResult<()> {
+    let data = meter_data_file(meter_id);
+    let path = base_dir.join(&data);
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(&data, path.exists());
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    for i in intervals {
+        writeln!(
+            file,
+            "{},{},{}",
+            i.start.to_rfc3339(),
+            i.end.to_rfc3339(),
+            i.value
+        )?;
+    }
+    Ok(())
+}
+
+/// Record `value` consumed by `meter_id` between `start` and `end`. `end`
+/// defaults to now and `start` to the end of the meter's previous interval
+/// (or one hour before `end` for its first reading).
+pub fn record_interval(
+    base_dir: &Path,
+    meter_id: &str,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    value: f64,
+) -> PersistenceResult<Interval> {
+    load_meter(base_dir, meter_id)?;
+    let end = end.unwrap_or_else(clock::now);
+    let start = match start {
+        Some(start) => start,
+        None => meter_intervals(base_dir, meter_id, None, None)?
+            .iter()
+            .map(|i| i.end)
+            .filter(|e| *e < end)
+            .max()
+            .unwrap_or(end - Duration::minutes(DEFAULT_INTERVAL_MINUTES)),
+    };
+    let interval = Interval { start, end, value };
+    check_interval(&interval).map_err(PersistenceError::ValidationError)?;
+    append_intervals(base_dir, meter_id, &[interval])?;
+    Ok(interval)
+}
+
+/// Append `start,end,value` rows (e.g. a utility's interval export) to
+/// `meter_id`. A header row is skipped; any other bad row rejects the file.
+pub fn import_intervals(base_dir: &Path, meter_id: &str, csv: &str) -> PersistenceResult<usize> {
+    load_meter(base_dir, meter_id)?;
+    let mut intervals = Vec::new();
+    for (n, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (n == 0 && line.to_ascii_lowercase().starts_with("start")) {
+            continue;
+        }
+        let interval = parse_interval(line)
+            .ok_or_else(|| "expected start,end,value with RFC 3339 times".to_string())
+            .and_then(|i| check_interval(&i).map(|_| i))
+            .map_err(|e| PersistenceError::ValidationError(format!("line {}: {}", n + 1, e)))?;
+        intervals.push(interval);
+    }
+    append_intervals(base_dir, meter_id, &intervals)?;
+    Ok(intervals.len())
+}
+
+/// Intervals of `meter_id` starting in `from <= start < to`, oldest first.
+pub fn meter_intervals(
+    base_dir: &Path,
+    meter_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> PersistenceResult<Vec<Interval>> {
+    let path = base_dir.join(meter_data_file(meter_id));
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut intervals: Vec<Interval> = fs::read_to_string(path)?
+        .lines()
+        .filter_map(parse_interval)
+        .filter(|i| from.is_none_or(|f| i.start >= f) && to.is_none_or(|t| i.start < t))
+        .collect();
+    intervals.sort_by_key(|i| i.start);
+    Ok(intervals)
+}
+
+/// Consumption of one meter in the latest period of its utility's rollup.
+#[derive(Debug, Clone, Serialize)]
+pub struct MeterUsage {
+    pub meter_id: String,
+    /// Equipment or floor the meter is tied to
+    pub target: String,
+    pub total: f64,
+}
+
+/// Rollups of every meter of one utility.
+#[derive(Debug, Clone, Serialize)]
+pub struct UtilityReport {
+    pub utility: Utility,
+    pub unit: &'static str,
+    pub rollups: Vec<Rollup>,
+    /// Per-meter consumption in the latest period, largest first
+    pub meters: Vec<MeterUsage>,
+}
+
+/// Rollups per utility of all meters with data since `from`.
+pub fn energy_report(
+    base_dir: &Path,
+    building: &Building,
+    period: Period,
+    from: Option<DateTime<Utc>>,
+) -> PersistenceResult<Vec<UtilityReport>> {
+    let meters = load_meters(base_dir)?;
+    let mut reports = Vec::new();
+    for utility in Utility::ALL {
+        let mut all = Vec::new();
+        let mut by_meter = Vec::new();
+        for meter in meters.iter().filter(|m| m.utility == utility) {
+            let intervals = meter_intervals(base_dir, &meter.id, from, None)?;
+            all.extend_from_slice(&intervals);
+            by_meter.push((meter, intervals));
+        }
+        let rollups = rollup(&all, period);
+        let Some(latest) = rollups.last() else {
+            continue;
+        };
+        let mut usage: Vec<MeterUsage> = by_meter
+            .into_iter()
+            .map(|(meter, intervals)| MeterUsage {
+                meter_id: meter.id.clone(),
+                target: meter_target(building, meter),
+                total: intervals
+                    .iter()
+                    .filter(|i| period.start_of(i.start) == latest.start)
+                    .map(|i| i.value)
+                    .sum(),
+            })
+            .filter(|u| u.total > 0.0)
+            .collect();
+        usage.sort_by(|a, b| b.total.total_cmp(&a.total));
+        reports.push(UtilityReport {
+            utility,
+            unit: utility.unit(),
+            rollups,
+            meters: usage,
+        });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_meters_record_import_and_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "Chiller-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        add_meter(
+            dir.path(),
+            &building,
+            "M-1",
+            Utility::Electric,
+            Some("Chiller-1"),
+            None,
+        )
+        .unwrap();
+        add_meter(
+            dir.path(),
+            &building,
+            "M-2",
+            Utility::Electric,
+            None,
+            Some("0"),
+        )
+        .unwrap();
+        assert!(add_meter(dir.path(), &building, "M-1", Utility::Gas, None, Some("0")).is_err());
+        assert!(add_meter(dir.path(), &building, "M-3", Utility::Gas, None, None).is_err());
+
+        let at = |day, hour| Utc.with_ymd_and_hms(2026, 2, day, hour, 0, 0).unwrap();
+        let csv = "start,end,kwh\n\
+                   2026-01-05T00:00:00Z,2026-01-06T00:00:00Z,100\n\
+                   2026-02-01T00:00:00Z,2026-02-02T00:00:00Z,150\n";
+        assert_eq!(import_intervals(dir.path(), "M-1", csv).unwrap(), 2);
+        assert!(import_intervals(dir.path(), "M-1", "2026-01-01T00:00:00Z,oops,1").is_err());
+        let next = record_interval(dir.path(), "M-1", None, Some(at(3, 0)), 30.0).unwrap();
+        assert_eq!(next.start, at(2, 0));
+        record_interval(dir.path(), "M-2", Some(at(4, 8)), Some(at(4, 9)), 20.0).unwrap();
+        assert!(record_interval(dir.path(), "M-9", None, None, 1.0).is_err());
+
+        let report = energy_report(dir.path(), &building, Period::Month, None).unwrap();
+        assert_eq!(report.len(), 1);
+        let electric = &report[0];
+        let totals: Vec<f64> = electric.rollups.iter().map(|r| r.total).collect();
+        assert_eq!(totals, [100.0, 200.0]);
+        assert_eq!(electric.rollups[1].change_vs_baseline(), Some(100.0));
+        assert_eq!(electric.meters[0].target, "Chiller-1");
+        assert_eq!(electric.meters[0].total, 180.0);
+        assert_eq!(electric.meters[1].target, "floor Ground");
+    }
+}
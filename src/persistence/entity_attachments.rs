@@ -0,0 +1,388 @@
+//! Add, list, and remove photos and documents on rooms and equipment.
+//!
+//! Files are copied into the entity's directory
+//! (`attachments/<rooms|equipment>/<id>/`, see [`attachment_dir`]) and recorded
+//! on the entity with uploader, time, and caption. When an attachment store is
+//! configured (`.arx/attachments.yaml`), files of at least `migrate_min_kb` are
+//! uploaded right away and only their pointer is kept in the repository.
+
+use super::attachments::AttachmentStore;
+use crate::core::attachment::{attachment_dir, Attachment};
+use crate::core::operations::rename::{resolve_rename_target, RenameTarget};
+use crate::core::{clock, Building};
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::Path;
+
+/// The room or equipment item attachments belong to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentOwner {
+    /// `room` or `equipment`
+    pub kind: &'static str,
+    pub id: String,
+    pub name: String,
+}
+
+/// Result of [`add_attachment`].
+#[derive(Debug, Clone)]
+pub struct AddedAttachment {
+    pub owner: AttachmentOwner,
+    pub attachment: Attachment,
+    /// Uploaded to the attachment store; the repository holds a pointer
+    pub external: bool,
+}
+
+/// Room or equipment for `query`: a hierarchy path or address, an id, or a name.
+pub fn resolve_owner(building: &Building, query: &str) -> Result<AttachmentOwner> {
+    let owner = |kind, id: &str, name: &str| AttachmentOwner {
+        kind,
+        id: id.to_string(),
+        name: name.to_string(),
+    };
+    match resolve_rename_target(building, query) {
+        Some(RenameTarget::Room { floor, wing, room }) => {
+            let r = &building.floors[floor].wings[wing].rooms[room];
+            return Ok(owner("room", &r.id, &r.name));
+        }
+        Some(RenameTarget::Equipment {
+            floor,
+            wing,
+            room,
+            equipment,
+        }) => {
+            let e = &building.floors[floor].wings[wing].rooms[room].equipment[equipment];
+            return Ok(owner("equipment", &e.id, &e.name));
+        }
+        Some(other) => bail!(
+            "'{}' is a {}; attachments belong to rooms and equipment",
+            query,
+            other.kind()
+        ),
+        None => {}
+    }
+    if let Some(eq) = building.find_equipment(query) {
+        return Ok(owner("equipment", &eq.id, &eq.name));
+    }
+    building
+        .get_all_rooms()
+        .into_iter()
+        .find(|r| r.id.eq_ignore_ascii_case(query) || r.matches_name(query))
+        .map(|r| owner("room", &r.id, &r.name))
+        .ok_or_else(|| anyhow!("No room or equipment matches '{}'", query))
+}
+
+/// Attachments recorded on `owner`.
+pub fn attachments_of<'a>(building: &'a Building, owner: &AttachmentOwner) -> &'a [Attachment] {
+    if owner.kind == "room" {
+        building
+            .get_all_rooms()
+            .into_iter()
+            .find(|r| r.id == owner.id)
+            .map(|r| r.attachments.as_slice())
+            .unwrap_or_default()
+    } else {
+        building
+            .get_all_equipment()
+            .into_iter()
+            .find(|e| e.id == owner.id)
+            .map(|e| e.attachments.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+fn attachments_mut<'a>(
+    building: &'a mut Building,
+    owner: &AttachmentOwner,
+) -> Option<&'a mut Vec<Attachment>> {
+    if owner.kind == "room" {
+        let room = building
+            .get_all_rooms_mut()
+            .into_iter()
+            .find(|r| r.id == owner.id)?;
+        room.updated_at = Some(clock::now());
+        Some(&mut room.attachments)
+    } else {
+        building
+            .find_equipment_mut(&owner.id)
+            .map(|e| &mut e.attachments)
+    }
+}
+
+/// `name` reduced to letters, digits, `-`, and `_`, keeping the extension.
+fn safe_file_name(name: &str) -> String {
+    let clean = |part: &str| {
+        part.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    };
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) => (clean(stem), clean(ext)),
+        None => (clean(name), String::new()),
+    };
+    let stem = if stem.is_empty() {
+        "attachment".to_string()
+    } else {
+        stem
+    };
+    if ext.is_empty() {
+        stem
+    } else {
+        format!("{}.{}", stem, ext.to_ascii_lowercase())
+    }
+}
+
+/// `dir/name`, with `-2`, `-3`, ... before the extension while `taken`.
+fn unused_path(dir: &str, name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    let mut candidate = format!("{}/{}", dir, name);
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{}/{}-{}{}", dir, stem, n, ext);
+        n += 1;
+    }
+    candidate
+}
+
+/// Record an attachment named `file_name` on the room or equipment matching
+/// `query` without touching the filesystem; the file is expected at the
+/// returned path. `exists` reports paths already present on disk.
+fn record(
+    building: &mut Building,
+    query: &str,
+    file_name: &str,
+    size: u64,
+    caption: Option<String>,
+    uploaded_by: &str,
+    exists: impl Fn(&str) -> bool,
+) -> Result<(AttachmentOwner, Attachment)> {
+    let owner = resolve_owner(building, query)?;
+    let dir = attachment_dir(owner.kind, &owner.id);
+    let recorded = attachments_of(building, &owner);
+    let path = unused_path(&dir, &safe_file_name(file_name), |p| {
+        exists(p) || recorded.iter().any(|a| a.path == p)
+    });
+    let attachment = Attachment {
+        path,
+        caption: caption.filter(|c| !c.trim().is_empty()),
+        uploaded_by: uploaded_by.to_string(),
+        uploaded_at: clock::now(),
+        size,
+    };
+    attachments_mut(building, &owner)
+        .expect("owner resolved above")
+        .push(attachment.clone());
+    Ok((owner, attachment))
+}
+
+/// Record an attachment the caller stores itself (e.g. a photo uploaded from
+/// the field app); nothing is written to disk.
+pub fn record_attachment(
+    building: &mut Building,
+    query: &str,
+    file_name: &str,
+    size: u64,
+    caption: Option<String>,
+    uploaded_by: &str,
+) -> Result<Attachment> {
+    record(
+        building,
+        query,
+        file_name,
+        size,
+        caption,
+        uploaded_by,
+        |_| false,
+    )
+    .map(|(_, attachment)| attachment)
+}
+
+/// Copy `source` into the directory of the room or equipment matching `query`
+/// and record it there. Under dry run nothing is written.
+pub fn add_attachment(
+    base: &Path,
+    building: &mut Building,
+    query: &str,
+    source: &Path,
+    caption: Option<String>,
+    uploaded_by: &str,
+) -> Result<AddedAttachment> {
+    let size = std::fs::metadata(source)
+        .with_context(|| format!("Cannot read {}", source.display()))?
+        .len();
+    if !source.is_file() {
+        bail!("{} is not a file", source.display());
+    }
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (owner, attachment) = record(
+        building,
+        query,
+        &file_name,
+        size,
+        caption,
+        uploaded_by,
+        |p| base.join(p).exists(),
+    )?;
+
+    let mut external = false;
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(&attachment.path, false);
+    } else {
+        let dest = base.join(&attachment.path);
+        std::fs::create_dir_all(base.join(attachment_dir(owner.kind, &owner.id)))?;
+        std::fs::copy(source, &dest)
+            .with_context(|| format!("Copying {} to {}", source.display(), attachment.path))?;
+        if let Some(store) = AttachmentStore::open(base)? {
+            if size >= store.config.migrate_min_kb * 1024 {
+                let pointer = store.upload(&dest)?;
+                std::fs::write(&dest, pointer.render())?;
+                store.ignore_cache()?;
+                external = true;
+            }
+        }
+    }
+    Ok(AddedAttachment {
+        owner,
+        attachment,
+        external,
+    })
+}
+
+/// Drop the attachment named `file` (file name or path) from the room or
+/// equipment matching `query`, leaving the file alone.
+pub fn forget_attachment(
+    building: &mut Building,
+    query: &str,
+    file: &str,
+) -> Result<(AttachmentOwner, Attachment)> {
+    let owner = resolve_owner(building, query)?;
+    let attachments = attachments_mut(building, &owner).expect("owner resolved above");
+    let index = attachments
+        .iter()
+        .position(|a| a.path == file || a.file_name() == file)
+        .ok_or_else(|| {
+            anyhow!(
+                "{} '{}' has no attachment '{}'",
+                owner.kind,
+                owner.name,
+                file
+            )
+        })?;
+    let attachment = attachments.remove(index);
+    Ok((owner, attachment))
+}
+
+/// Like [`forget_attachment`], also deleting the file. Under dry run nothing
+/// is deleted.
+pub fn remove_attachment(
+    base: &Path,
+    building: &mut Building,
+    query: &str,
+    file: &str,
+) -> Result<(AttachmentOwner, Attachment)> {
+    let (owner, attachment) = forget_attachment(building, query, file)?;
+    let path = base.join(&attachment.path);
+    if crate::core::operations::is_dry_run() {
+        if path.exists() {
+            crate::core::operations::dry_run::record_file_write(&attachment.path, true);
+        }
+    } else if path.exists() {
+        std::fs::remove_file(&path)?;
+        // Drop the entity directory with its last file
+        let dir = base.join(attachment_dir(owner.kind, &owner.id));
+        if std::fs::read_dir(&dir).is_ok_and(|mut d| d.next().is_none()) {
+            std::fs::remove_dir(&dir)?;
+        }
+    }
+    Ok((owner, attachment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        room.add_equipment(Equipment::new(
+            "Boiler 1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut wing = Wing::new("East".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        building
+    }
+
+    #[test]
+    fn test_add_list_remove_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("Boiler manual (v2).pdf");
+        std::fs::write(&source, b"%PDF-1.4 manual").unwrap();
+        let mut building = building();
+
+        let first = add_attachment(
+            dir.path(),
+            &mut building,
+            "Boiler 1",
+            &source,
+            Some("Installation manual".into()),
+            "Dana",
+        )
+        .unwrap();
+        let second =
+            add_attachment(dir.path(), &mut building, "Boiler 1", &source, None, "Dana").unwrap();
+        assert_eq!(first.owner.kind, "equipment");
+        assert!(!first.external);
+        let id = &first.owner.id;
+        assert_eq!(
+            first.attachment.path,
+            format!("attachments/equipment/{}/Boiler-manual-v2.pdf", id)
+        );
+        assert_eq!(
+            second.attachment.path,
+            format!("attachments/equipment/{}/Boiler-manual-v2-2.pdf", id)
+        );
+        assert!(dir.path().join(&first.attachment.path).is_file());
+        let listed = attachments_of(&building, &first.owner);
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].caption.as_deref(), Some("Installation manual"));
+        assert_eq!(listed[0].size, 15);
+
+        let room =
+            add_attachment(dir.path(), &mut building, "Plant", &source, None, "Dana").unwrap();
+        assert_eq!(room.owner.kind, "room");
+        assert_eq!(building.get_all_rooms()[0].attachments.len(), 1);
+
+        for file in ["Boiler-manual-v2.pdf", "Boiler-manual-v2-2.pdf"] {
+            remove_attachment(dir.path(), &mut building, "Boiler 1", file).unwrap();
+        }
+        assert!(attachments_of(&building, &first.owner).is_empty());
+        assert!(!dir.path().join(attachment_dir("equipment", id)).exists());
+        assert!(remove_attachment(dir.path(), &mut building, "Boiler 1", "nope.pdf").is_err());
+        assert!(resolve_owner(&building, "Lobby").is_err());
+    }
+
+    #[test]
+    fn test_record_attachment_without_files() {
+        let mut building = building();
+        let photo =
+            record_attachment(&mut building, "Plant", "IMG 0042.JPG", 2048, None, "Sam").unwrap();
+        let again =
+            record_attachment(&mut building, "Plant", "IMG 0042.JPG", 2048, None, "Sam").unwrap();
+        assert!(photo.path.ends_with("/IMG-0042.jpg"));
+        assert!(again.path.ends_with("/IMG-0042-2.jpg"));
+        assert!(photo.is_photo());
+        forget_attachment(&mut building, "Plant", "IMG-0042.jpg").unwrap();
+        assert_eq!(building.get_all_rooms()[0].attachments, vec![again]);
+    }
+}
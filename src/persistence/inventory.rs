@@ -0,0 +1,49 @@
+//! Spare parts storage: the part list in [`INVENTORY_FILE`].
+//!
+//! See [`crate::core::inventory`] for stock rules; work orders record the
+//! parts they consumed (`arx workorder close --part`).
+
+use std::fs;
+use std::path::Path;
+
+use crate::core::inventory::Inventory;
+
+use super::schema::{self, INVENTORY};
+use super::{PersistenceError, PersistenceResult};
+
+/// Stocked parts, by part number.
+pub const INVENTORY_FILE: &str = ".arx/inventory.yaml";
+
+/// The inventory under `base_dir`; empty when none is kept yet.
+pub fn load_inventory(base_dir: &Path) -> PersistenceResult<Inventory> {
+    let path = base_dir.join(INVENTORY_FILE);
+    if !path.exists() {
+        return Ok(Inventory::default());
+    }
+    schema::from_yaml(&INVENTORY, INVENTORY_FILE, &fs::read_to_string(path)?)
+}
+
+pub fn save_inventory(base_dir: &Path, inventory: &Inventory) -> PersistenceResult<()> {
+    let path = base_dir.join(INVENTORY_FILE);
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(INVENTORY_FILE, path.exists());
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    schema::ensure_writable(&INVENTORY, &path)?;
+    fs::write(path, schema::to_yaml(&INVENTORY, inventory)?)?;
+    Ok(())
+}
+
+/// Load, change with `update`, and save the inventory.
+pub fn update_inventory<T>(
+    base_dir: &Path,
+    update: impl FnOnce(&mut Inventory) -> Result<T, String>,
+) -> PersistenceResult<T> {
+    let mut inventory = load_inventory(base_dir)?;
+    let value = update(&mut inventory).map_err(PersistenceError::ValidationError)?;
+    save_inventory(base_dir, &inventory)?;
+    Ok(value)
+}
@@ -0,0 +1,124 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::clock;
+use crate::core::domain::issue::{triage_report, validate_report, Issue, IssueReport};
+use crate::core::Building;
+
+use super::schema::{self, ISSUE};
+use super::{PersistenceError, PersistenceResult};
+
+/// One YAML file per issue, so concurrent intake never conflicts in Git.
+pub const ISSUES_DIR: &str = ".arx/issues";
+
+/// Validate, triage, and store an incoming report.
+pub fn submit_issue(
+    base_dir: &Path,
+    building: &Building,
+    report: &IssueReport,
+) -> PersistenceResult<Issue> {
+    validate_report(report).map_err(PersistenceError::ValidationError)?;
+    let issue = triage_report(building, report, new_issue_id());
+    save_issue(base_dir, &issue)?;
+    Ok(issue)
+}
+
+pub fn save_issue(base_dir: &Path, issue: &Issue) -> PersistenceResult<()> {
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(
+            &format!("{}/{}.yaml", ISSUES_DIR, issue.id),
+            issue_path(base_dir, &issue.id).exists(),
+        );
+        return Ok(());
+    }
+    let dir = issues_dir(base_dir);
+    fs::create_dir_all(&dir)?;
+    let path = issue_path(base_dir, &issue.id);
+    schema::ensure_writable(&ISSUE, &path)?;
+    fs::write(path, schema::to_yaml(&ISSUE, issue)?)?;
+    Ok(())
+}
+
+/// All stored issues, newest first.
+pub fn load_issues(base_dir: &Path) -> PersistenceResult<Vec<Issue>> {
+    let dir = issues_dir(base_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut issues = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        issues.push(schema::from_yaml::<Issue>(
+            &ISSUE,
+            &path.display().to_string(),
+            &content,
+        )?);
+    }
+    issues.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+    Ok(issues)
+}
+
+/// Open issues, most urgent first.
+pub fn open_issues(base_dir: &Path) -> PersistenceResult<Vec<Issue>> {
+    let mut issues: Vec<Issue> = load_issues(base_dir)?
+        .into_iter()
+        .filter(|i| i.status.is_open())
+        .collect();
+    issues.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then(b.created_at.cmp(&a.created_at))
+    });
+    Ok(issues)
+}
+
+fn new_issue_id() -> String {
+    let short: String = clock::new_uuid()
+        .simple()
+        .to_string()
+        .chars()
+        .take(8)
+        .collect();
+    format!("ISS-{}-{}", clock::now().format("%Y%m%d"), short)
+}
+
+fn issues_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join(ISSUES_DIR)
+}
+
+fn issue_path(base_dir: &Path, id: &str) -> PathBuf {
+    issues_dir(base_dir).join(format!("{}.yaml", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::issue::{IssuePriority, IssueStatus};
+
+    #[test]
+    fn submit_then_list_open_issues_by_priority() {
+        let dir = tempfile::tempdir().unwrap();
+        let building = Building::new("HQ".into(), "/hq".into());
+        let report = |d: &str| IssueReport {
+            room: Some("Lobby".into()),
+            description: d.into(),
+            ..Default::default()
+        };
+        submit_issue(dir.path(), &building, &report("Door squeaks")).unwrap();
+        let mut urgent = submit_issue(dir.path(), &building, &report("Smoke smell")).unwrap();
+        assert!(submit_issue(dir.path(), &building, &report("")).is_err());
+
+        let open = open_issues(dir.path()).unwrap();
+        assert_eq!(open.len(), 2);
+        assert_eq!(open[0].priority, IssuePriority::Urgent);
+
+        urgent.status = IssueStatus::Resolved;
+        save_issue(dir.path(), &urgent).unwrap();
+        assert_eq!(open_issues(dir.path()).unwrap().len(), 1);
+        assert_eq!(load_issues(dir.path()).unwrap().len(), 2);
+    }
+}
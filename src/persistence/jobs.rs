@@ -0,0 +1,397 @@
+//! Background jobs for long-running operations (IFC import, exports, reports).
+//!
+//! A job is a YAML record under [`JOBS_DIR`] that the worker rewrites as it
+//! goes, so a client that reconnects, or a later `arx jobs status`, sees the
+//! same id, state, and progress. Work reports progress with [`report`], a
+//! no-op on threads not bound to a job. Cancellation is cooperative:
+//! [`cancel`] leaves a `<id>.cancel` marker and the worker's next [`report`]
+//! returns [`Cancelled`]. A record still `running` whose process has exited
+//! reads back as [`JobState::Interrupted`].
+//!
+//! Jobs start two ways:
+//! - `arx --async <command>` runs the command in a detached `arx` process bound
+//!   to a new job through [`JOB_ID_ENV`]; its output goes to `<id>.log`;
+//! - [`spawn`] runs a closure on a worker thread (agent calls with `async`).
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::access::{acting_user, ActingUserGuard};
+use crate::core::clock;
+
+use super::{PersistenceError, PersistenceResult};
+
+/// One `<id>.yaml` record per job, plus `<id>.cancel` / `<id>.log` while relevant.
+pub const JOBS_DIR: &str = ".arx/jobs";
+
+/// Finished jobs are pruned after this many days.
+pub const JOB_TTL_DAYS: i64 = 7;
+
+/// Set on processes started by `arx --async` to the job they run.
+pub const JOB_ID_ENV: &str = "ARX_JOB_ID";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+    /// The process running it exited without finishing it
+    Interrupted,
+}
+
+impl JobState {
+    pub fn is_finished(self) -> bool {
+        self != JobState::Running
+    }
+}
+
+impl std::fmt::Display for JobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            JobState::Running => "running",
+            JobState::Done => "done",
+            JobState::Failed => "failed",
+            JobState::Cancelled => "cancelled",
+            JobState::Interrupted => "interrupted",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Persisted state of one job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: String,
+    /// What runs, e.g. `arx import ifc model.ifc` or `ifc.import`
+    pub description: String,
+    pub state: JobState,
+    /// 0–100
+    pub progress: u8,
+    pub stage: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Process running the job
+    pub pid: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Result of `spawn`ed work once done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Output of an `arx --async` run, relative to the repository root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+}
+
+/// Returned by [`report`] once the current job has been asked to stop.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("job {0} was cancelled")]
+pub struct Cancelled(pub String);
+
+thread_local! {
+    static CURRENT: RefCell<Option<(PathBuf, String)>> = const { RefCell::new(None) };
+}
+
+fn record_path(base: &Path, id: &str) -> PathBuf {
+    base.join(JOBS_DIR).join(format!("{}.yaml", id))
+}
+
+fn cancel_path(base: &Path, id: &str) -> PathBuf {
+    base.join(JOBS_DIR).join(format!("{}.cancel", id))
+}
+
+/// Repo-relative log file for an `arx --async` run.
+pub fn log_path(id: &str) -> String {
+    format!("{}/{}.log", JOBS_DIR, id)
+}
+
+fn read(base: &Path, id: &str) -> PersistenceResult<Option<JobRecord>> {
+    let path = record_path(base, id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_yaml::from_str(&fs::read_to_string(path)?)?))
+}
+
+/// Replace the record in one rename so readers never see half of it.
+fn write(base: &Path, record: &JobRecord) -> PersistenceResult<()> {
+    let path = record_path(base, &record.id);
+    fs::create_dir_all(base.join(JOBS_DIR))?;
+    let tmp = path.with_extension("yaml.tmp");
+    fs::write(&tmp, serde_yaml::to_string(record)?)?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+fn update(
+    base: &Path,
+    id: &str,
+    apply: impl FnOnce(&mut JobRecord),
+) -> PersistenceResult<JobRecord> {
+    let mut record = read(base, id)?
+        .ok_or_else(|| PersistenceError::ValidationError(format!("Unknown job '{}'", id)))?;
+    apply(&mut record);
+    record.updated_at = clock::now();
+    write(base, &record)?;
+    Ok(record)
+}
+
+/// Whether `pid` is still running; assumed so where it cannot be checked.
+fn process_alive(pid: u32) -> bool {
+    let proc_dir = Path::new("/proc");
+    pid == std::process::id() || !proc_dir.is_dir() || proc_dir.join(pid.to_string()).exists()
+}
+
+fn with_liveness(mut record: JobRecord) -> JobRecord {
+    if record.state == JobState::Running && !process_alive(record.pid) {
+        record.state = JobState::Interrupted;
+    }
+    record
+}
+
+/// Drop finished jobs older than [`JOB_TTL_DAYS`] with their markers and logs.
+fn prune(base: &Path) {
+    let cutoff = clock::now() - chrono::Duration::days(JOB_TTL_DAYS);
+    for record in list(base).unwrap_or_default() {
+        if record.state.is_finished() && record.updated_at < cutoff {
+            let _ = fs::remove_file(record_path(base, &record.id));
+            let _ = fs::remove_file(cancel_path(base, &record.id));
+            let _ = fs::remove_file(base.join(log_path(&record.id)));
+        }
+    }
+}
+
+/// Record a new running job owned by this process.
+pub fn create(base: &Path, description: &str) -> PersistenceResult<JobRecord> {
+    prune(base);
+    let now = clock::now();
+    let record = JobRecord {
+        id: clock::new_id(),
+        description: description.to_string(),
+        state: JobState::Running,
+        progress: 0,
+        stage: "queued".to_string(),
+        created_at: now,
+        updated_at: now,
+        pid: std::process::id(),
+        error: None,
+        result: None,
+        log: None,
+    };
+    write(base, &record)?;
+    Ok(record)
+}
+
+/// Record where the output of job `id` goes (an `arx --async` run's log).
+pub fn set_log(base: &Path, id: &str, log: &str) -> PersistenceResult<JobRecord> {
+    update(base, id, |record| record.log = Some(log.to_string()))
+}
+
+pub fn load(base: &Path, id: &str) -> PersistenceResult<Option<JobRecord>> {
+    Ok(read(base, id)?.map(with_liveness))
+}
+
+/// Every job, newest first.
+pub fn list(base: &Path) -> PersistenceResult<Vec<JobRecord>> {
+    let dir = base.join(JOBS_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut records = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "yaml") {
+            let record: JobRecord = serde_yaml::from_str(&fs::read_to_string(&path)?)?;
+            records.push(with_liveness(record));
+        }
+    }
+    records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+    Ok(records)
+}
+
+/// Ask a running job to stop at its next progress report.
+pub fn cancel(base: &Path, id: &str) -> PersistenceResult<JobRecord> {
+    let record = load(base, id)?
+        .ok_or_else(|| PersistenceError::ValidationError(format!("Unknown job '{}'", id)))?;
+    if record.state.is_finished() {
+        return Err(PersistenceError::ValidationError(format!(
+            "Job '{}' already {}",
+            id, record.state
+        )));
+    }
+    fs::write(cancel_path(base, id), "")?;
+    Ok(record)
+}
+
+pub fn is_cancel_requested(base: &Path, id: &str) -> bool {
+    cancel_path(base, id).exists()
+}
+
+/// Binds the current thread to a job until dropped, so [`report`] updates it.
+pub struct JobGuard {
+    base: PathBuf,
+    id: String,
+    previous: Option<(PathBuf, String)>,
+}
+
+impl JobGuard {
+    /// Take over job `id` in this process (the `arx --async` child).
+    pub fn attach(base: &Path, id: &str) -> PersistenceResult<Self> {
+        update(base, id, |record| {
+            record.pid = std::process::id();
+            record.stage = "running".to_string();
+        })?;
+        Ok(Self::bind(base, id))
+    }
+
+    fn bind(base: &Path, id: &str) -> Self {
+        let previous = CURRENT.with(|c| c.replace(Some((base.to_path_buf(), id.to_string()))));
+        Self {
+            base: base.to_path_buf(),
+            id: id.to_string(),
+            previous,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Record the outcome; an error after a cancel request counts as cancelled.
+    pub fn finish(self, outcome: Result<Option<Value>, String>) -> PersistenceResult<JobRecord> {
+        let cancelled = is_cancel_requested(&self.base, &self.id);
+        let record = update(&self.base, &self.id, |record| match outcome {
+            Ok(result) => {
+                record.state = JobState::Done;
+                record.progress = 100;
+                record.stage = "done".to_string();
+                record.result = result;
+            }
+            Err(e) => {
+                record.state = if cancelled {
+                    JobState::Cancelled
+                } else {
+                    JobState::Failed
+                };
+                record.stage = record.state.to_string();
+                record.error = Some(e);
+            }
+        })?;
+        let _ = fs::remove_file(cancel_path(&self.base, &self.id));
+        Ok(record)
+    }
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|c| *c.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Id of the job bound to this thread, if any.
+pub fn current() -> Option<String> {
+    CURRENT.with(|c| c.borrow().as_ref().map(|(_, id)| id.clone()))
+}
+
+/// Record progress for the job bound to this thread (a no-op outside one)
+/// and pass it on to any [`crate::utils::progress`] listener, such as an
+/// agent client waiting on the request. Returns [`Cancelled`] once the job
+/// has been asked to stop, so the work can bail out at a safe point.
+pub fn report(progress: u8, stage: &str) -> Result<(), Cancelled> {
+    crate::utils::progress::report(f64::from(progress.min(100)) / 100.0, stage);
+    let Some((base, id)) = CURRENT.with(|c| c.borrow().clone()) else {
+        return Ok(());
+    };
+    if is_cancel_requested(&base, &id) {
+        return Err(Cancelled(id));
+    }
+    let written = update(&base, &id, |record| {
+        record.progress = progress.min(100);
+        record.stage = stage.to_string();
+    });
+    if let Err(e) = written {
+        log::warn!("Failed to record progress for job {}: {}", id, e);
+    }
+    Ok(())
+}
+
+/// Run `work` on a worker thread as a new job, acting for the same user as
+/// the caller. Returns the job straight away.
+pub fn spawn<F>(base: &Path, description: &str, work: F) -> PersistenceResult<JobRecord>
+where
+    F: FnOnce() -> Result<Value, String> + Send + 'static,
+{
+    let record = create(base, description)?;
+    let (base, id, user) = (base.to_path_buf(), record.id.clone(), acting_user());
+    std::thread::spawn(move || {
+        let _acting = user.map(ActingUserGuard::act_as);
+        let guard = JobGuard::bind(&base, &id);
+        let outcome = match report(0, "running") {
+            Ok(()) => work().map(Some),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = guard.finish(outcome) {
+            log::warn!("Failed to record outcome of job {}: {}", id, e);
+        }
+    });
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait_for(base: &Path, id: &str) -> JobRecord {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let record = load(base, id).unwrap().unwrap();
+            if record.state.is_finished() || Instant::now() > deadline {
+                return record;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_spawned_job_reports_progress_and_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = spawn(dir.path(), "count", || {
+            report(50, "halfway").map_err(|e| e.to_string())?;
+            Ok(serde_json::json!({ "rooms": 3 }))
+        })
+        .unwrap();
+        assert_eq!(job.state, JobState::Running);
+
+        let done = wait_for(dir.path(), &job.id);
+        assert_eq!((done.state, done.progress), (JobState::Done, 100));
+        assert_eq!(done.result, Some(serde_json::json!({ "rooms": 3 })));
+        assert_eq!(list(dir.path()).unwrap().len(), 1);
+        assert!(cancel(dir.path(), &job.id).is_err());
+        // Outside a job, reports are no-ops
+        assert!(report(10, "idle").is_ok());
+    }
+
+    #[test]
+    fn test_cancel_stops_work_at_next_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = create(dir.path(), "import").unwrap();
+        let guard = JobGuard::attach(dir.path(), &record.id).unwrap();
+        assert!(report(20, "parsing").is_ok());
+        cancel(dir.path(), &record.id).unwrap();
+        let stopped = report(40, "mapping").unwrap_err();
+
+        let finished = guard.finish(Err(stopped.to_string())).unwrap();
+        assert_eq!(
+            (finished.state, finished.progress),
+            (JobState::Cancelled, 20)
+        );
+        assert!(!is_cancel_requested(dir.path(), &record.id));
+    }
+}
@@ -1,15 +1,23 @@
 //! Persistence for the canonical `core::Building` durable store.
 //!
-//! Single layout: `{base_path}/building.yaml` via `BuildingYamlSerializer`.
+//! Single layout: `building.yaml` via `BuildingYamlSerializer`, stored in
+//! `{base_path}` unless `.arx/storage.yaml` selects another backend (see
+//! [`super::storage`]).
 
+use super::storage::{open_storage, Storage};
 use super::{PersistenceError, PersistenceResult};
 use crate::core::Building;
 use crate::yaml::BuildingYamlSerializer;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Canonical durable filename for a building model.
 pub const BUILDING_YAML: &str = "building.yaml";
 
+fn storage_error(e: anyhow::Error) -> PersistenceError {
+    PersistenceError::IoError(std::io::Error::other(format!("{:#}", e)))
+}
+
 /// Manager for loading and saving the Building SSOT on disk.
 ///
 /// Does not own Git versioning — use `BuildingGitManager` for commits.
@@ -81,18 +89,121 @@ impl PersistenceManager {
     /// (e.g. `persist_building` / import already checked `has_errors()`).
     /// Do not call from CLI/agent entry points for untrusted models.
     pub fn save_building_unchecked(&self, building: &Building) -> PersistenceResult<()> {
-        use std::fs;
-
-        if !self.base_path.exists() {
-            fs::create_dir_all(&self.base_path)?;
+        super::schema::ensure_writable(&super::schema::BUILDING, &self.building_yaml_path())?;
+        let existing = self.load_building_data().ok();
+        self.authorize_save(existing.as_ref(), building)?;
+        let mut building = building.clone();
+        super::approvals::hold_critical_changes(
+            &self.base_path,
+            existing.as_ref(),
+            &mut building,
+            &Self::acting_user(),
+        )?;
+        let building = &building;
+        if crate::core::operations::is_dry_run() {
+            return self.record_dry_run_save(existing.as_ref(), building);
         }
 
+        let building = &self.with_provenance(existing.as_ref(), building);
         let yaml_content = BuildingYamlSerializer::serialize_building(building)
             .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
 
-        let file_path = self.building_yaml_path();
-        fs::write(&file_path, yaml_content)?;
+        let storage = self.storage()?;
+        storage
+            .write(BUILDING_YAML, yaml_content.as_bytes())
+            .map_err(storage_error)?;
+        if let Some(file_path) = storage.local_path(BUILDING_YAML) {
+            super::cache::invalidate(&file_path);
+        }
+        self.record_status_changes(existing.as_ref(), building)?;
+
+        crate::notifications::notify(
+            &self.base_path,
+            &crate::notifications::alerts_between(existing.as_ref(), building),
+        );
+        Ok(())
+    }
+
+    /// User edits are attributed to: the acting user, else the Git author.
+    pub fn acting_user() -> String {
+        use crate::git::manager::GitConfigManager;
+
+        crate::core::access::acting_user()
+            .unwrap_or_else(|| GitConfigManager::load_from_arx_config_or_env().author_name)
+    }
+
+    /// With roles configured (`.arx/roles.yaml`), refuse saves whose entity
+    /// changes the acting user's role does not permit.
+    fn authorize_save(
+        &self,
+        existing: Option<&Building>,
+        building: &Building,
+    ) -> PersistenceResult<()> {
+        use crate::core::access::AccessPolicy;
+        use crate::core::operations::dry_run::diff_buildings;
+
+        let Some(policy) = AccessPolicy::load(&self.base_path)
+            .map_err(|e| PersistenceError::ValidationError(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        let changes = diff_buildings(existing, building);
+        Ok(policy.authorize(&Self::acting_user(), &changes)?)
+    }
+
+    /// `building` with field provenance stamped against the stored copy.
+    fn with_provenance(&self, existing: Option<&Building>, building: &Building) -> Building {
+        let mut stamped = building.clone();
+        let actor = crate::core::provenance::current_actor().unwrap_or_else(Self::acting_user);
+        crate::core::provenance::stamp_changes(existing, &mut stamped, Some(&actor));
+        stamped
+    }
+
+    /// Append the equipment status transitions of this save to the status
+    /// history, caused by the current provenance source.
+    fn record_status_changes(
+        &self,
+        existing: Option<&Building>,
+        building: &Building,
+    ) -> PersistenceResult<()> {
+        use crate::core::provenance::{current_actor, current_source};
+        use crate::core::uptime::status_transitions;
+
+        let actor = current_actor().unwrap_or_else(Self::acting_user);
+        let transitions = status_transitions(
+            existing,
+            building,
+            crate::core::clock::now(),
+            current_source(),
+            Some(&actor),
+        );
+        super::status_history::append_transitions(&self.base_path, &transitions)
+    }
+
+    /// Dry run: record the entity-level change set instead of writing.
+    fn record_dry_run_save(
+        &self,
+        existing: Option<&Building>,
+        building: &Building,
+    ) -> PersistenceResult<()> {
+        use crate::core::operations::dry_run;
 
+        for change in dry_run::diff_buildings(existing, building) {
+            dry_run::record(change);
+        }
+        let stored = self
+            .storage()?
+            .exists(BUILDING_YAML)
+            .map_err(storage_error)?;
+        dry_run::record_file_write(BUILDING_YAML, stored);
+        let now = crate::core::clock::now();
+        let source = crate::core::provenance::current_source();
+        if !crate::core::uptime::status_transitions(existing, building, now, source, None)
+            .is_empty()
+        {
+            let log = super::status_history::STATUS_HISTORY_LOG;
+            dry_run::record_file_write(log, self.base_path.join(log).exists());
+        }
         Ok(())
     }
 
@@ -107,6 +218,13 @@ impl PersistenceManager {
                     None => e.message.clone(),
                 })
                 .collect();
+            crate::notifications::notify(
+                &self.base_path,
+                &[crate::notifications::Event::ValidationFailed {
+                    source: "save".to_string(),
+                    errors: details.clone(),
+                }],
+            );
             return Err(PersistenceError::ValidationError(format!(
                 "Building validation failed ({} error(s)): {}",
                 details.len(),
@@ -118,19 +236,66 @@ impl PersistenceManager {
 
     /// Load `Building` from `{base}/building.yaml` only (no multi-file discovery).
     pub fn load_building_data(&self) -> PersistenceResult<Building> {
-        use std::fs;
+        Ok((*self.load_building_shared()?).clone())
+    }
 
-        let file_path = self.building_yaml_path();
-        if !file_path.exists() {
-            return Err(PersistenceError::ValidationError(format!(
-                "No building SSOT found at {}",
-                file_path.display()
-            )));
+    /// [`Self::load_building_data`] without copying: the cached parse,
+    /// shared until `building.yaml` changes. For read-only callers.
+    pub fn load_building_shared(&self) -> PersistenceResult<Arc<Building>> {
+        let parse = |yaml_content: &str| {
+            BuildingYamlSerializer::deserialize_building(yaml_content)
+                .map_err(|e| PersistenceError::SerializationError(e.to_string()))
+        };
+        let storage = self.storage()?;
+        if let Some(file_path) = storage.local_path(BUILDING_YAML) {
+            if !file_path.exists() {
+                return Err(PersistenceError::ValidationError(format!(
+                    "No building SSOT found at {}",
+                    file_path.display()
+                )));
+            }
+            return super::cache::load(&file_path, parse);
         }
 
-        let yaml_content = fs::read_to_string(&file_path)?;
-        BuildingYamlSerializer::deserialize_building(&yaml_content)
-            .map_err(|e| PersistenceError::SerializationError(e.to_string()))
+        let data = storage
+            .read(BUILDING_YAML)
+            .map_err(storage_error)?
+            .ok_or_else(|| {
+                PersistenceError::ValidationError(format!(
+                    "No building SSOT found in {}",
+                    storage.describe()
+                ))
+            })?;
+        let yaml_content = String::from_utf8(data).map_err(|e| {
+            PersistenceError::SerializationError(format!("{} is not UTF-8: {}", BUILDING_YAML, e))
+        })?;
+        Ok(Arc::new(parse(&yaml_content)?))
+    }
+
+    /// The stored building, or `None` when nothing has been saved yet.
+    pub fn load_existing(&self) -> PersistenceResult<Option<Building>> {
+        let stored = self
+            .storage()?
+            .exists(BUILDING_YAML)
+            .map_err(storage_error)?;
+        stored.then(|| self.load_building_data()).transpose()
+    }
+
+    /// Stored `building.yaml` text from the active backend, or `None` when
+    /// nothing has been saved yet. For callers that need the file, not the model.
+    pub fn read_building_yaml(&self) -> PersistenceResult<Option<String>> {
+        let Some(data) = self.storage()?.read(BUILDING_YAML).map_err(storage_error)? else {
+            return Ok(None);
+        };
+        String::from_utf8(data).map(Some).map_err(|e| {
+            PersistenceError::SerializationError(format!("{} is not UTF-8: {}", BUILDING_YAML, e))
+        })
+    }
+
+    /// Backend holding `building.yaml`: the project directory unless
+    /// `.arx/storage.yaml` selects another.
+    pub fn storage(&self) -> PersistenceResult<Box<dyn Storage>> {
+        open_storage(&self.base_path).map_err(storage_error)
     }
 
     /// Save Building, then commit with `BuildingGitManager` when a repo exists.
@@ -140,11 +305,21 @@ impl PersistenceManager {
         message: Option<&str>,
     ) -> PersistenceResult<()> {
         // Caller (persist_building) already validated; avoid double work but keep gate if used alone.
+        let held_before = super::approvals::held_count();
         self.save_building_validated(building)?;
 
         if !self.has_git_repo() {
             return Ok(());
         }
+        let storage = self.storage()?;
+        if storage.local_path(BUILDING_YAML) != Some(self.building_yaml_path()) {
+            return Err(PersistenceError::ValidationError(format!(
+                "Saved to {}, but Git commits need {} in the working tree (remove {} to use the default storage)",
+                storage.describe(),
+                BUILDING_YAML,
+                super::storage::STORAGE_CONFIG_FILE
+            )));
+        }
 
         use crate::git::manager::{BuildingGitManager, GitConfigManager};
 
@@ -156,9 +331,13 @@ impl PersistenceManager {
         let mut git = BuildingGitManager::new(base, "building", config)
             .map_err(|e| PersistenceError::SerializationError(format!("Git open failed: {}", e)))?;
 
-        git.stage_file(BUILDING_YAML).map_err(|e| {
-            PersistenceError::SerializationError(format!("Git stage failed: {}", e))
-        })?;
+        // Approval requests held by this save go into the same commit
+        let held = super::approvals::held_files_since(held_before);
+        for file in std::iter::once(BUILDING_YAML.to_string()).chain(held) {
+            git.stage_file(&file).map_err(|e| {
+                PersistenceError::SerializationError(format!("Git stage failed: {}", e))
+            })?;
+        }
 
         let msg = message.unwrap_or("Update building data");
         git.commit_staged(msg).map_err(|e| {
@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::spatial::{building_meshes, BuildingMeshes};
+use crate::core::Building;
+
+use super::{PersistenceError, PersistenceResult, BUILDING_YAML};
+
+/// Derived floor, room, and equipment meshes, one file per commit (`<commit>.json`).
+pub const MESH_CACHE_DIR: &str = ".arx/cache/meshes";
+
+/// Bump when extrusion output changes so stale caches are rebuilt.
+const MESH_CACHE_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct MeshCacheFile {
+    version: u32,
+    commit: String,
+    meshes: BuildingMeshes,
+}
+
+/// HEAD commit id when `building.yaml` matches it (no uncommitted edits).
+fn clean_head_commit(base_dir: &Path) -> Option<String> {
+    let repo = git2::Repository::open(base_dir).ok()?;
+    let head = repo.head().ok()?.peel_to_commit().ok()?;
+    let status = repo.status_file(Path::new(BUILDING_YAML)).ok()?;
+    status.is_empty().then(|| head.id().to_string())
+}
+
+fn cache_path(base_dir: &Path, commit: &str) -> PathBuf {
+    base_dir
+        .join(MESH_CACHE_DIR)
+        .join(format!("{}.json", commit))
+}
+
+/// Room and equipment meshes for `building` (loaded from `base_dir`), cached per commit.
+///
+/// The cache is only consulted when `building.yaml` is committed and unmodified;
+/// otherwise meshes are derived fresh and not stored.
+pub fn load_building_meshes(
+    base_dir: &Path,
+    building: &Building,
+) -> PersistenceResult<BuildingMeshes> {
+    let Some(commit) = clean_head_commit(base_dir) else {
+        return Ok(building_meshes(building));
+    };
+    let path = cache_path(base_dir, &commit);
+    if let Some(cached) = fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<MeshCacheFile>(&s).ok())
+        .filter(|c| c.version == MESH_CACHE_VERSION && c.commit == commit)
+    {
+        return Ok(cached.meshes);
+    }
+
+    let meshes = building_meshes(building);
+    // Derived data, but still a write: skip it while simulating
+    if !crate::core::operations::is_dry_run() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = MeshCacheFile {
+            version: MESH_CACHE_VERSION,
+            commit,
+            meshes,
+        };
+        let json = serde_json::to_string(&file)
+            .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+        fs::write(&path, json)?;
+        return Ok(file.meshes);
+    }
+    Ok(meshes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::spatial::extrusion::FLOOR_POLYGON_PROPERTY;
+    use crate::core::{Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn caches_meshes_for_clean_commits_only() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Lab".into(), RoomType::Laboratory);
+        room.properties
+            .insert(FLOOR_POLYGON_PROPERTY.into(), "0,0;4,0;4,3;0,3".into());
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        // Uncommitted: derived but not cached
+        crate::persistence::save_building_at(dir.path(), &building).unwrap();
+        assert!(!load_building_meshes(dir.path(), &building)
+            .unwrap()
+            .rooms
+            .is_empty());
+        assert!(!dir.path().join(MESH_CACHE_DIR).exists());
+
+        crate::ingest::persist_building_at(dir.path(), building.clone(), true, Some("Add lab"))
+            .unwrap();
+        let commit = clean_head_commit(dir.path()).unwrap();
+        let meshes = load_building_meshes(dir.path(), &building).unwrap();
+        assert!(cache_path(dir.path(), &commit).exists());
+        assert_eq!(load_building_meshes(dir.path(), &building).unwrap(), meshes);
+    }
+}
@@ -2,8 +2,29 @@
 //!
 //! Durable Building SSOT: `{dir}/building.yaml` via `BuildingYamlSerializer`.
 
+pub mod artifacts;
+pub mod approvals;
+pub mod ar_pending;
+pub mod attachments;
+pub mod audit;
+pub mod backup;
+pub mod bookings;
+pub mod cache;
 pub mod economy;
+pub mod energy;
+pub mod entity_attachments;
+pub mod inventory;
+pub mod issues;
+pub mod jobs;
 pub mod manager;
+pub mod meshes;
+pub mod s3;
+pub mod schema;
+pub mod sensors;
+pub mod status_history;
+pub mod storage;
+pub mod workorders;
+pub mod workspace;
 
 use thiserror::Error;
 
@@ -18,6 +39,17 @@ pub enum PersistenceError {
 
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    #[error(transparent)]
+    PermissionDenied(#[from] crate::core::access::AccessDenied),
+
+    #[error("{path} is {document} schema v{found}, newer than this arx supports (v{supported}); upgrade arx before writing it")]
+    SchemaTooNew {
+        path: String,
+        document: &'static str,
+        found: u32,
+        supported: u32,
+    },
 }
 
 impl From<serde_yaml::Error> for PersistenceError {
@@ -44,6 +76,14 @@ pub fn load_building_at(
     Ok(pm.load_building_data()?)
 }
 
+/// [`load_building_at`] for read-only callers: the cached parse, not a copy.
+pub fn load_building_shared_at(
+    base: impl AsRef<std::path::Path>,
+) -> Result<std::sync::Arc<crate::core::Building>, Box<dyn std::error::Error>> {
+    let pm = PersistenceManager::at(base.as_ref());
+    Ok(pm.load_building_shared()?)
+}
+
 /// Save Building to `{base}/building.yaml` with validation hard-gate.
 pub fn save_building_at(
     base: impl AsRef<std::path::Path>,
@@ -0,0 +1,210 @@
+//! Minimal S3-compatible object client shared by backups and attachments.
+//!
+//! Requests go through `curl --aws-sigv4` (curl 7.75+) with credentials from
+//! `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY`, passed on stdin rather than
+//! the command line. Buckets are addressed path-style so MinIO, Ceph and
+//! Wasabi work without DNS setup.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Endpoint settings for `s3://` locations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3Settings {
+    /// Base URL of the S3-compatible service (MinIO, Ceph, Wasabi, AWS)
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+}
+
+fn default_endpoint() -> String {
+    "https://s3.amazonaws.com".to_string()
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+impl Default for S3Settings {
+    fn default() -> Self {
+        Self {
+            endpoint: default_endpoint(),
+            region: default_region(),
+        }
+    }
+}
+
+/// A bucket plus key prefix, e.g. `s3://arx-backups/site-a`.
+#[derive(Debug, Clone)]
+pub struct S3Client {
+    pub settings: S3Settings,
+    pub bucket: String,
+    pub prefix: String,
+}
+
+impl S3Client {
+    /// Parse `s3://bucket[/prefix]`.
+    pub fn parse(url: &str, settings: S3Settings) -> Result<Self> {
+        let rest = url
+            .strip_prefix("s3://")
+            .ok_or_else(|| anyhow!("not an s3:// URL: {}", url))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            bail!("missing bucket in {}", url);
+        }
+        Ok(Self {
+            settings,
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.prefix)
+    }
+
+    /// Full object key for `key` under the configured prefix.
+    pub fn key(&self, key: &str) -> String {
+        format!("{}{}", self.key_prefix(), key)
+    }
+
+    /// `prefix/`, or empty without a prefix.
+    pub fn key_prefix(&self) -> String {
+        if self.prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", self.prefix)
+        }
+    }
+
+    pub fn object_url(&self, full_key: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.settings.endpoint.trim_end_matches('/'),
+            self.bucket,
+            full_key
+                .split('/')
+                .map(uri_encode)
+                .collect::<Vec<_>>()
+                .join("/")
+        )
+    }
+
+    fn curl(&self, args: &[&str]) -> Result<Vec<u8>> {
+        let key_id = std::env::var("AWS_ACCESS_KEY_ID")
+            .map_err(|_| anyhow!("AWS_ACCESS_KEY_ID is not set"))?;
+        let secret = std::env::var("AWS_SECRET_ACCESS_KEY")
+            .map_err(|_| anyhow!("AWS_SECRET_ACCESS_KEY is not set"))?;
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--config", "-"])
+            .arg("--aws-sigv4")
+            .arg(format!("aws:amz:{}:s3", self.settings.region))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("running curl (required for s3:// locations)")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "user = \"{}:{}\"", key_id, secret)?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "S3 request failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+
+    /// Upload `source` as `full_key`.
+    pub fn put(&self, full_key: &str, source: &Path) -> Result<()> {
+        let source = source.to_string_lossy();
+        self.curl(&["--upload-file", &source, &self.object_url(full_key)])?;
+        Ok(())
+    }
+
+    /// Download `full_key` into `dest`.
+    pub fn get(&self, full_key: &str, dest: &Path) -> Result<()> {
+        let dest = dest.to_string_lossy();
+        self.curl(&["--output", &dest, &self.object_url(full_key)])?;
+        Ok(())
+    }
+
+    pub fn delete(&self, full_key: &str) -> Result<()> {
+        self.curl(&["--request", "DELETE", &self.object_url(full_key)])?;
+        Ok(())
+    }
+
+    /// Every key starting with `prefix`, following continuation tokens.
+    pub fn list_keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let key_re = regex::Regex::new(r"<Key>([^<]*)</Key>")?;
+        let token_re =
+            regex::Regex::new(r"<NextContinuationToken>([^<]*)</NextContinuationToken>")?;
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut url = format!(
+                "{}/{}?list-type=2&prefix={}",
+                self.settings.endpoint.trim_end_matches('/'),
+                self.bucket,
+                uri_encode(prefix)
+            );
+            if let Some(t) = &token {
+                url.push_str(&format!("&continuation-token={}", uri_encode(t)));
+            }
+            let body = String::from_utf8_lossy(&self.curl(&[&url])?).into_owned();
+            keys.extend(key_re.captures_iter(&body).map(|c| xml_unescape(&c[1])));
+            match token_re.captures(&body) {
+                Some(c) => token = Some(xml_unescape(&c[1])),
+                None => break,
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// RFC 3986 percent-encoding (everything but unreserved characters).
+fn uri_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_respect_optional_prefix() {
+        let bare = S3Client::parse("s3://objects", S3Settings::default()).unwrap();
+        assert_eq!(bare.key("sha256/ab/abcd"), "sha256/ab/abcd");
+        assert_eq!(
+            bare.object_url(&bare.key("a+b/c d")),
+            "https://s3.amazonaws.com/objects/a%2Bb/c%20d"
+        );
+        let nested = S3Client::parse("s3://objects/site/a/", S3Settings::default()).unwrap();
+        assert_eq!(nested.key_prefix(), "site/a/");
+        assert_eq!(xml_unescape("a&amp;lt;b&gt;"), "a&lt;b>");
+    }
+}
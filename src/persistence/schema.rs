@@ -0,0 +1,341 @@
+//! Versioned on-disk documents and the migrations between versions.
+//!
+//! Every persisted YAML document carries a top-level `schema_version`
+//! (absent = 1). Loading runs the document's migrations in memory, so a
+//! current binary reads old repos; `arx migrate schema` rewrites them on
+//! disk. A binary refuses to overwrite a document written by a newer one,
+//! since serde would silently drop the fields it does not know.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use super::{PersistenceError, PersistenceResult};
+use crate::yaml::BUILDING_YAML_SCHEMA_VERSION;
+
+/// Top-level key holding a document's schema version.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Upgrades a document in place by one version.
+pub type Migration = fn(&mut Mapping) -> Result<(), String>;
+
+/// One kind of persisted document.
+pub struct DocumentSchema {
+    pub name: &'static str,
+    /// Repo-relative glob of the files holding this kind
+    pub pattern: &'static str,
+    pub current: u32,
+    /// `migrations[i]` upgrades version `i + 1` to `i + 2`
+    pub migrations: &'static [Migration],
+}
+
+pub const BUILDING: DocumentSchema = DocumentSchema {
+    name: "building",
+    pattern: "building.yaml",
+    current: BUILDING_YAML_SCHEMA_VERSION,
+    migrations: &[],
+};
+
+pub const ISSUE: DocumentSchema = DocumentSchema {
+    name: "issue",
+    pattern: ".arx/issues/*.yaml",
+    current: 1,
+    migrations: &[],
+};
+
+pub const WORK_ORDER: DocumentSchema = DocumentSchema {
+    name: "work order",
+    pattern: ".arx/workorders/*.yaml",
+    current: 1,
+    migrations: &[],
+};
+
+pub const APPROVAL: DocumentSchema = DocumentSchema {
+    name: "approval",
+    pattern: ".arx/approvals/*.yaml",
+    current: 1,
+    migrations: &[],
+};
+
+pub const AR_PENDING: DocumentSchema = DocumentSchema {
+    name: "AR pending item",
+    pattern: ".arx/ar-pending/*.yaml",
+    current: 1,
+    migrations: &[],
+};
+
+pub const RESERVATION: DocumentSchema = DocumentSchema {
+    name: "room reservation",
+    pattern: ".arx/reservations/*.yaml",
+    current: 1,
+    migrations: &[],
+};
+
+pub const INVENTORY: DocumentSchema = DocumentSchema {
+    name: "inventory",
+    pattern: ".arx/inventory.yaml",
+    current: 1,
+    migrations: &[],
+};
+
+pub const ECONOMY_SNAPSHOT: DocumentSchema = DocumentSchema {
+    name: "economy snapshot",
+    pattern: ".arxos/economy/snapshot.yaml",
+    current: 1,
+    migrations: &[],
+};
+
+/// Every versioned document kind, in migration order.
+pub const DOCUMENTS: &[&DocumentSchema] = &[
+    &BUILDING,
+    &ISSUE,
+    &WORK_ORDER,
+    &APPROVAL,
+    &AR_PENDING,
+    &RESERVATION,
+    &INVENTORY,
+    &ECONOMY_SNAPSHOT,
+];
+
+/// A document brought to its current version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upgrade {
+    /// Stored version; `None` when the file had no `schema_version`
+    pub from: Option<u32>,
+    pub to: u32,
+}
+
+impl Upgrade {
+    pub fn is_needed(&self) -> bool {
+        self.from != Some(self.to)
+    }
+}
+
+/// A file `arx migrate schema` rewrote (or would rewrite).
+#[derive(Debug, Clone)]
+pub struct MigratedFile {
+    pub path: String,
+    pub document: &'static str,
+    pub upgrade: Upgrade,
+}
+
+fn stored_version(doc: &Mapping) -> Option<u32> {
+    doc.get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+}
+
+fn too_new(schema: &DocumentSchema, path: &str, found: u32) -> PersistenceError {
+    PersistenceError::SchemaTooNew {
+        path: path.to_string(),
+        document: schema.name,
+        found,
+        supported: schema.current,
+    }
+}
+
+/// Run `schema`'s migrations on `doc` and stamp the current version first.
+pub fn upgrade(
+    schema: &DocumentSchema,
+    path: &str,
+    doc: &mut Mapping,
+) -> PersistenceResult<Upgrade> {
+    let from = stored_version(doc);
+    let mut version = from.unwrap_or(1);
+    if version > schema.current {
+        return Err(too_new(schema, path, version));
+    }
+    while version < schema.current {
+        let step = schema.migrations.get(version as usize - 1).ok_or_else(|| {
+            PersistenceError::SerializationError(format!(
+                "No migration for {} schema v{} -> v{}",
+                schema.name,
+                version,
+                version + 1
+            ))
+        })?;
+        step(doc).map_err(|e| {
+            PersistenceError::SerializationError(format!(
+                "Migrating {} {} from v{}: {}",
+                schema.name, path, version, e
+            ))
+        })?;
+        version += 1;
+    }
+    stamp(schema, doc);
+    Ok(Upgrade {
+        from,
+        to: schema.current,
+    })
+}
+
+/// Set `schema_version` to the current version, as the first key.
+fn stamp(schema: &DocumentSchema, doc: &mut Mapping) {
+    let mut stamped = Mapping::new();
+    stamped.insert(SCHEMA_VERSION_KEY.into(), schema.current.into());
+    for (key, value) in std::mem::take(doc) {
+        if key.as_str() != Some(SCHEMA_VERSION_KEY) {
+            stamped.insert(key, value);
+        }
+    }
+    *doc = stamped;
+}
+
+fn parse_mapping(path: &str, content: &str) -> PersistenceResult<Mapping> {
+    match serde_yaml::from_str(content)? {
+        Value::Mapping(doc) => Ok(doc),
+        _ => Err(PersistenceError::SerializationError(format!(
+            "{} is not a YAML mapping",
+            path
+        ))),
+    }
+}
+
+/// Parse a stored document, migrating it to the current version first.
+pub fn from_yaml<T: DeserializeOwned>(
+    schema: &DocumentSchema,
+    path: &str,
+    content: &str,
+) -> PersistenceResult<T> {
+    let mut doc = parse_mapping(path, content)?;
+    upgrade(schema, path, &mut doc)?;
+    Ok(serde_yaml::from_value(Value::Mapping(doc))?)
+}
+
+/// Serialize `value` with the current `schema_version` as its first key.
+pub fn to_yaml<T: Serialize>(schema: &DocumentSchema, value: &T) -> PersistenceResult<String> {
+    let Value::Mapping(mut doc) = serde_yaml::to_value(value)? else {
+        return Err(PersistenceError::SerializationError(format!(
+            "A {} must serialize to a YAML mapping",
+            schema.name
+        )));
+    };
+    stamp(schema, &mut doc);
+    Ok(crate::yaml::to_canonical_yaml(&doc)?)
+}
+
+/// Refuse to overwrite `path` when a newer binary wrote it.
+pub fn ensure_writable(schema: &DocumentSchema, path: &Path) -> PersistenceResult<()> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+    let found = serde_yaml::from_str::<Mapping>(&content)
+        .ok()
+        .and_then(|doc| stored_version(&doc));
+    match found {
+        Some(found) if found > schema.current => {
+            Err(too_new(schema, &path.display().to_string(), found))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn schema_files(base: &Path, schema: &DocumentSchema) -> PersistenceResult<Vec<PathBuf>> {
+    let pattern = base.join(schema.pattern);
+    let paths = glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| PersistenceError::ValidationError(e.to_string()))?;
+    let mut files: Vec<PathBuf> = paths.filter_map(Result::ok).collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Bring every versioned document under `base` to the current schema.
+/// With `dry_run`, only report what would change.
+pub fn migrate_repo(base: &Path, dry_run: bool) -> PersistenceResult<Vec<MigratedFile>> {
+    let mut migrated = Vec::new();
+    for schema in DOCUMENTS {
+        for file in schema_files(base, schema)? {
+            let rel = file
+                .strip_prefix(base)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .into_owned();
+            let mut doc = parse_mapping(&rel, &fs::read_to_string(&file)?)?;
+            let upgrade = upgrade(schema, &rel, &mut doc)?;
+            if !upgrade.is_needed() {
+                continue;
+            }
+            if dry_run {
+                crate::core::operations::dry_run::record_file_write(&rel, true);
+            } else {
+                fs::write(&file, crate::yaml::to_canonical_yaml(&doc)?)?;
+            }
+            migrated.push(MigratedFile {
+                path: rel,
+                document: schema.name,
+                upgrade,
+            });
+        }
+    }
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename_title(doc: &mut Mapping) -> Result<(), String> {
+        let title = doc.remove("title").ok_or("missing title")?;
+        doc.insert("summary".into(), title);
+        Ok(())
+    }
+
+    const NOTE_V2: DocumentSchema = DocumentSchema {
+        name: "note",
+        pattern: ".arx/notes/*.yaml",
+        current: 2,
+        migrations: &[rename_title],
+    };
+
+    #[derive(Debug, serde::Deserialize, Serialize)]
+    struct Note {
+        summary: String,
+    }
+
+    #[test]
+    fn loads_old_documents_through_migrations() {
+        let note: Note = from_yaml(&NOTE_V2, "n.yaml", "title: Leak\n").unwrap();
+        assert_eq!(note.summary, "Leak");
+
+        let yaml = to_yaml(&NOTE_V2, &note).unwrap();
+        assert_eq!(yaml, "schema_version: 2\nsummary: Leak\n");
+
+        let newer = from_yaml::<Note>(&NOTE_V2, "n.yaml", "schema_version: 3\nsummary: x\n");
+        assert!(matches!(
+            newer,
+            Err(PersistenceError::SchemaTooNew { found: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn migrate_repo_stamps_unversioned_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".arx/issues")).unwrap();
+        let issue = dir.path().join(".arx/issues/ISS-1.yaml");
+        fs::write(&issue, "id: ISS-1\n").unwrap();
+        let inventory = dir.path().join(".arx/inventory.yaml");
+        fs::write(&inventory, "parts: []\n").unwrap();
+
+        let planned = migrate_repo(dir.path(), true).unwrap();
+        assert_eq!(planned.len(), 2);
+        assert_eq!(fs::read_to_string(&issue).unwrap(), "id: ISS-1\n");
+
+        let migrated = migrate_repo(dir.path(), false).unwrap();
+        assert_eq!(migrated[0].upgrade, Upgrade { from: None, to: 1 });
+        assert_eq!(
+            fs::read_to_string(&issue).unwrap(),
+            "schema_version: 1\nid: ISS-1\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&inventory).unwrap(),
+            "schema_version: 1\nparts: []\n"
+        );
+        assert!(migrate_repo(dir.path(), false).unwrap().is_empty());
+
+        fs::write(&issue, "schema_version: 9\nid: ISS-1\n").unwrap();
+        assert!(ensure_writable(&ISSUE, &issue).is_err());
+    }
+}
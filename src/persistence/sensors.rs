@@ -0,0 +1,349 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::clock;
+use crate::core::operations::mapped_sensor_ids;
+use crate::core::provenance::{stamp, ProvenanceSource};
+use crate::core::runtime::{apply_run_signals, run_signal_sensors};
+use crate::core::timeseries::{in_range, Sample};
+use crate::core::Building;
+
+use super::{PersistenceError, PersistenceResult};
+
+/// Sensor ids seen by ingestion (agent `sensor.report`), mapped or not.
+pub const SENSOR_INBOX: &str = ".arx/sensors/seen.yaml";
+
+/// Append-only trace of valued readings, one JSON object per line.
+pub const SENSOR_READINGS_LOG: &str = ".arx/sensors/readings.jsonl";
+
+/// Per-sensor history: one append-only `at,value` CSV per sensor id.
+pub const SENSOR_DATA_DIR: &str = ".arx/sensor-data";
+
+/// Longest sensor id accepted into the inbox.
+const MAX_SENSOR_ID_LEN: usize = 128;
+
+/// One incoming sensor as last seen by ingestion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeenSensor {
+    pub sensor_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_type: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    #[serde(default)]
+    pub readings: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_value: Option<f64>,
+}
+
+/// One valued reading from the trace log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorReading {
+    pub sensor_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_type: Option<String>,
+    pub at: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// Record a reading from `sensor_id` in the inbox (and the trace log when it has a value).
+pub fn record_sensor_reading(
+    base_dir: &Path,
+    sensor_id: &str,
+    sensor_type: Option<&str>,
+    value: Option<f64>,
+) -> PersistenceResult<SeenSensor> {
+    let sensor_id = sensor_id.trim();
+    if sensor_id.is_empty() || sensor_id.len() > MAX_SENSOR_ID_LEN {
+        return Err(PersistenceError::ValidationError(format!(
+            "Sensor id must be 1-{} characters",
+            MAX_SENSOR_ID_LEN
+        )));
+    }
+    let now = clock::now();
+    let mut seen = load_seen_sensors(base_dir)?;
+    let idx = match seen.iter().position(|s| s.sensor_id == sensor_id) {
+        Some(i) => i,
+        None => {
+            seen.push(SeenSensor {
+                sensor_id: sensor_id.to_string(),
+                sensor_type: None,
+                first_seen: now,
+                last_seen: now,
+                readings: 0,
+                last_value: None,
+            });
+            seen.len() - 1
+        }
+    };
+    let entry = &mut seen[idx];
+    entry.last_seen = now;
+    entry.readings += 1;
+    if let Some(t) = sensor_type.filter(|t| !t.trim().is_empty()) {
+        entry.sensor_type = Some(t.trim().to_string());
+    }
+    if value.is_some() {
+        entry.last_value = value;
+    }
+    let recorded = entry.clone();
+
+    let path = inbox_path(base_dir);
+    let log = base_dir.join(SENSOR_READINGS_LOG);
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(SENSOR_INBOX, path.exists());
+        if value.is_some() {
+            crate::core::operations::dry_run::record_file_write(SENSOR_READINGS_LOG, log.exists());
+            let data = sensor_data_file(&recorded.sensor_id);
+            crate::core::operations::dry_run::record_file_write(
+                &data,
+                base_dir.join(&data).exists(),
+            );
+        }
+        return Ok(recorded);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, crate::yaml::to_canonical_yaml(&seen)?)?;
+    if let Some(value) = value {
+        let reading = SensorReading {
+            sensor_id: recorded.sensor_id.clone(),
+            sensor_type: recorded.sensor_type.clone(),
+            at: now,
+            value,
+        };
+        let line = serde_json::to_string(&reading)
+            .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(log)?;
+        writeln!(file, "{}", line)?;
+        append_sensor_data(base_dir, &reading)?;
+    }
+    Ok(recorded)
+}
+
+fn append_sensor_data(base_dir: &Path, reading: &SensorReading) -> PersistenceResult<()> {
+    let path = base_dir.join(sensor_data_file(&reading.sensor_id));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{},{}", reading.at.to_rfc3339(), reading.value)?;
+    Ok(())
+}
+
+/// History file for `sensor_id`, relative to the project root. Characters
+/// outside `[A-Za-z0-9._-]` are percent-encoded so any id maps to one file.
+pub fn sensor_data_file(sensor_id: &str) -> String {
+    format!("{}/{}.csv", SENSOR_DATA_DIR, file_stem(sensor_id))
+}
+
+/// `id` as a file name: bytes outside `[A-Za-z0-9._-]` and a leading dot are
+/// percent-encoded.
+pub(crate) fn file_stem(id: &str) -> String {
+    let mut name = String::new();
+    for b in id.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-') {
+            name.push(b as char);
+        } else {
+            name.push_str(&format!("%{:02X}", b));
+        }
+    }
+    if name.starts_with('.') {
+        name.replace_range(..1, "%2E");
+    }
+    name
+}
+
+/// Readings of `sensor_id` with `from <= at < to`, oldest first.
+///
+/// Sensors recorded before per-sensor files existed are read from the
+/// trace log instead.
+pub fn sensor_history(
+    base_dir: &Path,
+    sensor_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> PersistenceResult<Vec<Sample>> {
+    let path = base_dir.join(sensor_data_file(sensor_id));
+    let mut samples: Vec<Sample> = if path.exists() {
+        fs::read_to_string(path)?
+            .lines()
+            .filter_map(|line| {
+                let (at, value) = line.split_once(',')?;
+                Some(Sample {
+                    at: DateTime::parse_from_rfc3339(at).ok()?.with_timezone(&Utc),
+                    value: value.trim().parse().ok()?,
+                })
+            })
+            .collect()
+    } else {
+        load_sensor_readings(base_dir)?
+            .into_iter()
+            .filter(|r| r.sensor_id == sensor_id)
+            .map(|r| Sample {
+                at: r.at,
+                value: r.value,
+            })
+            .collect()
+    };
+    samples.sort_by_key(|s| s.at);
+    Ok(in_range(&samples, from, to))
+}
+
+/// Every valued reading in the trace log, oldest first. Unparseable lines
+/// (e.g. a torn final write) are skipped.
+pub fn load_sensor_readings(base_dir: &Path) -> PersistenceResult<Vec<SensorReading>> {
+    let path = base_dir.join(SENSOR_READINGS_LOG);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut readings: Vec<SensorReading> = fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    readings.sort_by_key(|r| r.at);
+    Ok(readings)
+}
+
+/// All sensors in the inbox, most recently seen first.
+pub fn load_seen_sensors(base_dir: &Path) -> PersistenceResult<Vec<SeenSensor>> {
+    let path = inbox_path(base_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut seen: Vec<SeenSensor> = serde_yaml::from_str(&fs::read_to_string(path)?)?;
+    seen.sort_by(|a, b| {
+        b.last_seen
+            .cmp(&a.last_seen)
+            .then(a.sensor_id.cmp(&b.sensor_id))
+    });
+    Ok(seen)
+}
+
+/// Last valued reading of every inbox sensor, for twin snapshots.
+pub fn latest_sensor_values(
+    base_dir: &Path,
+) -> PersistenceResult<std::collections::BTreeMap<String, crate::core::twin::SensorValue>> {
+    Ok(load_seen_sensors(base_dir)?
+        .into_iter()
+        .filter_map(|s| {
+            let value = s.last_value?;
+            Some((
+                s.sensor_id,
+                crate::core::twin::SensorValue {
+                    value,
+                    at: s.last_seen,
+                },
+            ))
+        })
+        .collect())
+}
+
+/// Inbox sensors with no `SensorMapping` in `building`.
+pub fn unmapped_sensors(
+    base_dir: &Path,
+    building: &Building,
+) -> PersistenceResult<Vec<SeenSensor>> {
+    let mapped = mapped_sensor_ids(building);
+    Ok(load_seen_sensors(base_dir)?
+        .into_iter()
+        .filter(|s| !mapped.contains(&s.sensor_id))
+        .collect())
+}
+
+/// Feed logged readings from run-signal sensors into equipment runtime
+/// counters; returns how many equipment counters moved.
+pub fn sync_runtime(base_dir: &Path, building: &mut Building) -> PersistenceResult<usize> {
+    let readings = load_sensor_readings(base_dir)?;
+    let mut updated = 0;
+    for eq in building.get_all_equipment_mut() {
+        let sensors = run_signal_sensors(eq);
+        if sensors.is_empty() {
+            continue;
+        }
+        let signals: Vec<_> = readings
+            .iter()
+            .filter(|r| sensors.contains(&r.sensor_id.as_str()))
+            .map(|r| (r.at, r.value))
+            .collect();
+        if apply_run_signals(eq, signals) > 0 {
+            stamp(&mut eq.provenance, "runtime", ProvenanceSource::Sensor);
+            updated += 1;
+        }
+    }
+    Ok(updated)
+}
+
+fn inbox_path(base_dir: &Path) -> PathBuf {
+    base_dir.join(SENSOR_INBOX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::operations::{bind_sensor, SensorBindTarget};
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn records_readings_and_filters_mapped() {
+        let dir = tempfile::tempdir().unwrap();
+        record_sensor_reading(dir.path(), "zigbee-1", Some("temperature"), Some(21.5)).unwrap();
+        let again = record_sensor_reading(dir.path(), "zigbee-1", None, None).unwrap();
+        assert_eq!(again.readings, 2);
+        let trace = load_sensor_readings(dir.path()).unwrap();
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].value, 21.5);
+        assert_eq!(trace[0].sensor_type.as_deref(), Some("temperature"));
+        assert_eq!(again.sensor_type.as_deref(), Some("temperature"));
+        assert_eq!(again.last_value, Some(21.5));
+        let history = sensor_history(dir.path(), "zigbee-1", None, None).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].value, 21.5);
+        assert!(sensor_history(
+            dir.path(),
+            "zigbee-1",
+            Some(history[0].at + chrono::Duration::seconds(1)),
+            None
+        )
+        .unwrap()
+        .is_empty());
+        assert_eq!(
+            sensor_data_file("bus/3:temp"),
+            ".arx/sensor-data/bus%2F3%3Atemp.csv"
+        );
+        assert_eq!(sensor_data_file(".."), ".arx/sensor-data/%2E..csv");
+        record_sensor_reading(dir.path(), "zigbee-2", None, None).unwrap();
+        assert!(record_sensor_reading(dir.path(), " ", None, None).is_err());
+
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Lobby".into(), RoomType::Office);
+        let eq = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        let eq_id = eq.id.clone();
+        room.add_equipment(eq);
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        assert_eq!(unmapped_sensors(dir.path(), &building).unwrap().len(), 2);
+
+        bind_sensor(
+            &mut building,
+            "zigbee-1",
+            "temperature",
+            &SensorBindTarget::Equipment(eq_id),
+            None,
+        )
+        .unwrap();
+        let unmapped = unmapped_sensors(dir.path(), &building).unwrap();
+        assert_eq!(unmapped.len(), 1);
+        assert_eq!(unmapped[0].sensor_id, "zigbee-2");
+    }
+}
@@ -0,0 +1,101 @@
+//! Equipment status history: every status transition saved to building.yaml.
+//!
+//! The manager appends to the log after each save; see [`crate::core::uptime`]
+//! for what is recorded and how `arx equipment uptime` reads it back.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::core::uptime::StatusTransition;
+
+use super::{PersistenceError, PersistenceResult};
+
+/// Append-only status transitions, one JSON object per line.
+pub const STATUS_HISTORY_LOG: &str = ".arx/equipment/status-history.jsonl";
+
+/// Append `transitions` to the log.
+pub fn append_transitions(
+    base_dir: &Path,
+    transitions: &[StatusTransition],
+) -> PersistenceResult<()> {
+    if transitions.is_empty() {
+        return Ok(());
+    }
+    let path = base_dir.join(STATUS_HISTORY_LOG);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for transition in transitions {
+        content.push_str(
+            &serde_json::to_string(transition)
+                .map_err(|e| PersistenceError::SerializationError(e.to_string()))?,
+        );
+        content.push('\n');
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Transitions of `equipment_id` (every equipment when `None`), oldest
+/// first. Unparseable lines are skipped.
+pub fn load_status_history(
+    base_dir: &Path,
+    equipment_id: Option<&str>,
+) -> PersistenceResult<Vec<StatusTransition>> {
+    let path = base_dir.join(STATUS_HISTORY_LOG);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut history: Vec<StatusTransition> = fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str::<StatusTransition>(line).ok())
+        .filter(|t| equipment_id.is_none_or(|id| t.equipment_id == id))
+        .collect();
+    history.sort_by_key(|t| t.at);
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Equipment, EquipmentStatus, EquipmentType, Floor};
+    use crate::persistence::PersistenceManager;
+
+    #[test]
+    fn saves_append_status_transitions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.equipment.push(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+        let pm = PersistenceManager::at(dir.path());
+        pm.save_building_unchecked(&building).unwrap();
+
+        building.floors[0].equipment[0].status = EquipmentStatus::OutOfOrder;
+        pm.save_building_unchecked(&building).unwrap();
+        // Unchanged status: nothing new
+        building.floors[0].equipment[0].name = "AHU-1A".into();
+        pm.save_building_unchecked(&building).unwrap();
+
+        let id = &building.floors[0].equipment[0].id;
+        let history = load_status_history(dir.path(), Some(id)).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].from, None);
+        assert_eq!(history[1].from, Some(history[0].to));
+        assert_eq!(history[1].to, EquipmentStatus::OutOfOrder);
+        assert_eq!(history[1].cause.as_str(), "manual");
+        assert!(load_status_history(dir.path(), Some("other"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Storage backends for building data.
+//!
+//! [`PersistenceManager`](super::PersistenceManager) reads and writes
+//! `building.yaml` through a [`Storage`], so callers never see where the
+//! model lives. The default is the project directory itself (the layout Git
+//! versions); larger or cloud-hosted deployments can select another backend
+//! in `.arx/storage.yaml`:
+//!
+//! ```yaml
+//! store: sqlite:.arx/building.db      # or s3://bucket/prefix, or a directory
+//! s3:
+//!   endpoint: https://minio.example.org:9000
+//!   region: us-east-1
+//! ```
+//!
+//! Objects are addressed by `/`-separated keys relative to the project root
+//! (`building.yaml`). Git commits need `building.yaml` in the working tree,
+//! so only the default filesystem backend can commit.
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use crate::persistence::s3::{S3Client, S3Settings};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Repo-relative storage backend configuration.
+pub const STORAGE_CONFIG_FILE: &str = ".arx/storage.yaml";
+
+/// Where building data is stored.
+pub trait Storage {
+    /// Human-readable location for messages.
+    fn describe(&self) -> String;
+    /// Contents of `key`, or `None` when it does not exist.
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    /// Create or replace `key`.
+    fn write(&self, key: &str, data: &[u8]) -> Result<()>;
+    /// Remove `key`; absent keys are not an error.
+    fn delete(&self, key: &str) -> Result<()>;
+    /// Keys starting with `prefix`, sorted.
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+    /// Local file holding `key`, for backends that keep one (Git and the
+    /// parse cache work on it).
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+    fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.read(key)?.is_some())
+    }
+}
+
+/// `.arx/storage.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    /// Directory path, `sqlite:<file>`, or `s3://bucket/prefix`
+    pub store: String,
+    #[serde(default)]
+    pub s3: S3Settings,
+}
+
+impl StorageConfig {
+    /// `None` when the repository uses the default filesystem layout.
+    pub fn load(base: &Path) -> Result<Option<Self>> {
+        let path = base.join(STORAGE_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let config = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid {}", path.display()))?;
+        Ok(Some(config))
+    }
+}
+
+/// Objects as files under a directory; the default backend is the project
+/// root itself.
+pub struct FilesystemStorage {
+    pub root: PathBuf,
+}
+
+impl FilesystemStorage {
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl Storage for FilesystemStorage {
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+        Ok(Some(data))
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, data).with_context(|| format!("writing {}", path.display()))
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        let path = self.path(key);
+        if path.exists() {
+            std::fs::remove_file(&path).with_context(|| format!("removing {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        fn walk(dir: &Path, root: &Path, out: &mut Vec<String>) -> Result<()> {
+            for entry in std::fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    walk(&path, root, out)?;
+                } else if let Ok(rel) = path.strip_prefix(root) {
+                    let parts: Vec<_> = rel
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect();
+                    out.push(parts.join("/"));
+                }
+            }
+            Ok(())
+        }
+        let mut keys = Vec::new();
+        if self.root.is_dir() {
+            walk(&self.root, &self.root, &mut keys)?;
+        }
+        keys.retain(|k| k.starts_with(prefix));
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.path(key))
+    }
+
+    fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.path(key).exists())
+    }
+}
+
+/// Objects in an S3-compatible bucket under a key prefix.
+pub struct S3Storage {
+    pub client: S3Client,
+}
+
+impl Storage for S3Storage {
+    fn describe(&self) -> String {
+        self.client.describe()
+    }
+
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        if !self.exists(key)? {
+            return Ok(None);
+        }
+        let download = tempfile::NamedTempFile::new()?;
+        self.client.get(&self.client.key(key), download.path())?;
+        Ok(Some(std::fs::read(download.path())?))
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+        let upload = tempfile::NamedTempFile::new()?;
+        std::fs::write(upload.path(), data)?;
+        self.client.put(&self.client.key(key), upload.path())
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        if self.exists(key)? {
+            self.client.delete(&self.client.key(key))?;
+        }
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let base = self.client.key_prefix();
+        let mut keys: Vec<String> = self
+            .client
+            .list_keys(&self.client.key(prefix))?
+            .iter()
+            .filter_map(|k| k.strip_prefix(&base))
+            .map(str::to_string)
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn exists(&self, key: &str) -> Result<bool> {
+        let full = self.client.key(key);
+        Ok(self.client.list_keys(&full)?.contains(&full))
+    }
+}
+
+/// Backend for a configured location: `s3://bucket/prefix`, `sqlite:<file>`,
+/// or a directory path (relative paths resolve against the project root).
+pub fn open_store(base: &Path, location: &str, s3: &S3Settings) -> Result<Box<dyn Storage>> {
+    if location.starts_with("s3://") {
+        return Ok(Box::new(S3Storage {
+            client: S3Client::parse(location, s3.clone())?,
+        }));
+    }
+    let resolve = |path: &str| {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            base.join(path)
+        }
+    };
+    if let Some(file) = location.strip_prefix("sqlite:") {
+        #[cfg(feature = "sqlite")]
+        return Ok(Box::new(sqlite::SqliteStorage::open(&resolve(file))?));
+        #[cfg(not(feature = "sqlite"))]
+        anyhow::bail!(
+            "{} selects SQLite ({}), but this arx was built without the `sqlite` feature",
+            STORAGE_CONFIG_FILE,
+            file
+        );
+    }
+    Ok(Box::new(FilesystemStorage {
+        root: resolve(location),
+    }))
+}
+
+/// The backend configured for the project at `base`; the project directory
+/// when `.arx/storage.yaml` is absent.
+pub fn open_storage(base: &Path) -> Result<Box<dyn Storage>> {
+    match StorageConfig::load(base)? {
+        Some(config) => open_store(base, &config.store, &config.s3),
+        None => Ok(Box::new(FilesystemStorage {
+            root: base.to_path_buf(),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filesystem_storage_reads_writes_and_lists_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = open_storage(dir.path()).unwrap();
+        assert_eq!(store.read("building.yaml").unwrap(), None);
+
+        store.write("building.yaml", b"name: HQ\n").unwrap();
+        store.write("floors/1.yaml", b"level: 1\n").unwrap();
+        assert_eq!(store.read("building.yaml").unwrap().unwrap(), b"name: HQ\n");
+        assert_eq!(store.list("floors/").unwrap(), ["floors/1.yaml"]);
+        assert_eq!(
+            store.local_path("building.yaml"),
+            Some(dir.path().join("building.yaml"))
+        );
+
+        store.delete("floors/1.yaml").unwrap();
+        store.delete("floors/1.yaml").unwrap();
+        assert!(!store.exists("floors/1.yaml").unwrap());
+
+        std::fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        std::fs::write(
+            dir.path().join(STORAGE_CONFIG_FILE),
+            "store: sqlite:.arx/building.db\n",
+        )
+        .unwrap();
+        let configured = open_storage(dir.path());
+        assert_eq!(configured.is_ok(), cfg!(feature = "sqlite"));
+    }
+}
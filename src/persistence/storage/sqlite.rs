@@ -0,0 +1,124 @@
+//! SQLite storage backend: every object is a row in one database file.
+
+use super::Storage;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Objects in the `objects` table of a SQLite database.
+pub struct SqliteStorage {
+    path: PathBuf,
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Open (creating if needed) the database at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path).with_context(|| format!("opening {}", path.display()))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS objects (
+                 key TEXT PRIMARY KEY,
+                 data BLOB NOT NULL,
+                 updated_at TEXT NOT NULL
+             );",
+        )?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn describe(&self) -> String {
+        format!("sqlite:{}", self.path.display())
+    }
+
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT data FROM objects WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO objects (key, data, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET data = excluded.data, updated_at = excluded.updated_at",
+            params![key, data, crate::core::clock::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        self.conn()
+            .execute("DELETE FROM objects WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT key FROM objects ORDER BY key")?;
+        let keys = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(keys.into_iter().filter(|k| k.starts_with(prefix)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqlite_storage_round_trips_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStorage::open(&dir.path().join("store/building.db")).unwrap();
+        assert_eq!(store.read("building.yaml").unwrap(), None);
+        store.write("building.yaml", b"name: HQ\n").unwrap();
+        store.write("building.yaml", b"name: HQ2\n").unwrap();
+        store.write("floors/1.yaml", b"level: 1\n").unwrap();
+        assert_eq!(
+            store.read("building.yaml").unwrap().unwrap(),
+            b"name: HQ2\n"
+        );
+        assert_eq!(store.list("floors/").unwrap(), ["floors/1.yaml"]);
+        store.delete("floors/1.yaml").unwrap();
+        assert!(!store.exists("floors/1.yaml").unwrap());
+        assert_eq!(store.local_path("building.yaml"), None);
+    }
+
+    #[test]
+    fn manager_saves_through_configured_sqlite_store() {
+        use crate::core::Building;
+        use crate::persistence::{PersistenceManager, BUILDING_YAML};
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".arx")).unwrap();
+        std::fs::write(
+            dir.path().join(super::super::STORAGE_CONFIG_FILE),
+            "store: sqlite:.arx/building.db\n",
+        )
+        .unwrap();
+        let pm = PersistenceManager::at(dir.path());
+        pm.save_building_unchecked(&Building::new("HQ".into(), "/hq".into()))
+            .unwrap();
+
+        assert!(!dir.path().join(BUILDING_YAML).exists());
+        assert!(dir.path().join(".arx/building.db").exists());
+        assert_eq!(pm.load_building_data().unwrap().name, "HQ");
+    }
+}
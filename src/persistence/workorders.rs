@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::clock;
+use crate::core::domain::workorder::{create_work_order, WorkOrder, WorkOrderDraft};
+use crate::core::Building;
+
+use super::schema::{self, WORK_ORDER};
+use super::{PersistenceError, PersistenceResult};
+
+/// One YAML file per work order; every change is its own Git commit.
+pub const WORKORDERS_DIR: &str = ".arx/workorders";
+
+/// Resolve `draft` against the building and store the new work order.
+pub fn submit_work_order(
+    base_dir: &Path,
+    building: &Building,
+    draft: &WorkOrderDraft,
+) -> PersistenceResult<WorkOrder> {
+    let order = create_work_order(building, draft, new_work_order_id())
+        .map_err(PersistenceError::ValidationError)?;
+    save_work_order(base_dir, &order)?;
+    Ok(order)
+}
+
+pub fn save_work_order(base_dir: &Path, order: &WorkOrder) -> PersistenceResult<()> {
+    if crate::core::operations::is_dry_run() {
+        crate::core::operations::dry_run::record_file_write(
+            &work_order_file(&order.id),
+            work_order_path(base_dir, &order.id).exists(),
+        );
+        return Ok(());
+    }
+    fs::create_dir_all(workorders_dir(base_dir))?;
+    let path = work_order_path(base_dir, &order.id);
+    schema::ensure_writable(&WORK_ORDER, &path)?;
+    fs::write(path, schema::to_yaml(&WORK_ORDER, order)?)?;
+    Ok(())
+}
+
+/// Commit the work order's file when `base_dir` is a Git repository.
+pub fn commit_work_order(
+    base_dir: &Path,
+    order: &WorkOrder,
+    message: &str,
+) -> PersistenceResult<()> {
+    commit_work_order_with(base_dir, order, &[], message)
+}
+
+/// Commit the work order's file together with `also` (project-relative
+/// files the same change wrote, e.g. the inventory).
+pub fn commit_work_order_with(
+    base_dir: &Path,
+    order: &WorkOrder,
+    also: &[&str],
+    message: &str,
+) -> PersistenceResult<()> {
+    if !base_dir.join(".git").exists() {
+        return Ok(());
+    }
+
+    use crate::git::manager::{BuildingGitManager, GitConfigManager};
+
+    let base = base_dir.to_str().ok_or_else(|| {
+        PersistenceError::SerializationError("base path is not valid UTF-8".into())
+    })?;
+    let mut git = BuildingGitManager::new(
+        base,
+        "building",
+        GitConfigManager::load_from_arx_config_or_env(),
+    )
+    .map_err(|e| PersistenceError::SerializationError(format!("Git open failed: {}", e)))?;
+    for file in std::iter::once(work_order_file(&order.id).as_str()).chain(also.iter().copied()) {
+        git.stage_file(file).map_err(|e| {
+            PersistenceError::SerializationError(format!("Git stage failed: {}", e))
+        })?;
+    }
+    git.commit_staged(message)
+        .map_err(|e| PersistenceError::SerializationError(format!("Git commit failed: {}", e)))?;
+    Ok(())
+}
+
+/// All stored work orders, newest first.
+pub fn load_work_orders(base_dir: &Path) -> PersistenceResult<Vec<WorkOrder>> {
+    let dir = workorders_dir(base_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut orders = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        orders.push(schema::from_yaml::<WorkOrder>(
+            &WORK_ORDER,
+            &path.display().to_string(),
+            &content,
+        )?);
+    }
+    orders.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+    Ok(orders)
+}
+
+/// The work order with `id`.
+pub fn load_work_order(base_dir: &Path, id: &str) -> PersistenceResult<WorkOrder> {
+    let path = work_order_path(base_dir, id);
+    if !is_valid_id(id) || !path.exists() {
+        return Err(PersistenceError::ValidationError(format!(
+            "work order '{}' not found",
+            id
+        )));
+    }
+    let content = fs::read_to_string(&path)?;
+    schema::from_yaml(&WORK_ORDER, &path.display().to_string(), &content)
+}
+
+/// Path of a work order's file relative to the project root.
+pub fn work_order_file(id: &str) -> String {
+    format!("{}/{}.yaml", WORKORDERS_DIR, id)
+}
+
+fn new_work_order_id() -> String {
+    let short: String = clock::new_uuid()
+        .simple()
+        .to_string()
+        .chars()
+        .take(8)
+        .collect();
+    format!("WO-{}-{}", clock::now().format("%Y%m%d"), short)
+}
+
+/// Ids come from the command line; keep them to a single file name.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn workorders_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join(WORKORDERS_DIR)
+}
+
+fn work_order_path(base_dir: &Path, id: &str) -> PathBuf {
+    workorders_dir(base_dir).join(format!("{}.yaml", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::workorder::WorkOrderStatus;
+    use crate::core::{Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn submit_close_and_reload_work_orders() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("A".into());
+        wing.add_room(Room::new("Lobby".into(), RoomType::Hallway));
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let draft = WorkOrderDraft {
+            title: "Fix door closer".into(),
+            room: Some("Lobby".into()),
+            ..Default::default()
+        };
+        let mut order = submit_work_order(dir.path(), &building, &draft).unwrap();
+        assert!(order.id.starts_with("WO-"));
+        order.close(Some("Adjusted")).unwrap();
+        save_work_order(dir.path(), &order).unwrap();
+        commit_work_order(dir.path(), &order, "no repo, no commit").unwrap();
+
+        let loaded = load_work_order(dir.path(), &order.id).unwrap();
+        assert_eq!(loaded.status, WorkOrderStatus::Closed);
+        assert_eq!(loaded.resolution.as_deref(), Some("Adjusted"));
+        assert_eq!(load_work_orders(dir.path()).unwrap().len(), 1);
+        assert!(load_work_order(dir.path(), "../building").is_err());
+        assert!(load_work_order(dir.path(), "WO-missing").is_err());
+    }
+}
@@ -0,0 +1,259 @@
+//! Workspace: a registry of building repositories.
+//!
+//! Each building still lives in its own project directory (its
+//! `building.yaml`, `.arx/`, and Git history). The registry, kept per user in
+//! `~/.arxos/workspaces.yaml`, names those directories so one session can
+//! reach any of them through a [`BuildingHandle`]:
+//!
+//! ```yaml
+//! active: hq
+//! buildings:
+//!   - name: hq
+//!     root: /srv/buildings/hq
+//!   - name: annex
+//!     root: /srv/buildings/annex
+//! ```
+//!
+//! `arx workspace switch` sets `active`; commands run outside a building
+//! project then work on the active building, and `arx --in <name>` picks one
+//! for a single command.
+
+use super::{PersistenceManager, PersistenceResult, BUILDING_YAML};
+use crate::core::Building;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Registry file under the user's arx directory (`~/.arxos`).
+pub const WORKSPACE_REGISTRY_FILE: &str = "workspaces.yaml";
+
+/// Overrides the registry location (tests, shared portfolio setups).
+pub const WORKSPACE_REGISTRY_ENV: &str = "ARX_WORKSPACE_REGISTRY";
+
+/// A registered building: its name in the workspace and project root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildingHandle {
+    pub name: String,
+    pub root: PathBuf,
+}
+
+impl BuildingHandle {
+    /// Persistence for this building's project root.
+    pub fn persistence(&self) -> PersistenceManager {
+        PersistenceManager::at(&self.root)
+    }
+
+    pub fn load(&self) -> PersistenceResult<Building> {
+        self.persistence().load_building_data()
+    }
+
+    /// [`Self::load`] for read-only callers: the cached parse.
+    pub fn load_shared(&self) -> PersistenceResult<Arc<Building>> {
+        self.persistence().load_building_shared()
+    }
+
+    /// Validate, then save.
+    pub fn save(&self, building: &Building) -> PersistenceResult<()> {
+        self.persistence().save_building_validated(building)
+    }
+
+    /// Whether the root holds a building (in whichever storage it uses).
+    pub fn exists(&self) -> bool {
+        self.persistence()
+            .storage()
+            .ok()
+            .and_then(|s| s.exists(BUILDING_YAML).ok())
+            .unwrap_or(false)
+    }
+}
+
+/// `~/.arxos/workspaces.yaml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceRegistry {
+    /// Name of the building commands use outside a project directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub buildings: Vec<BuildingHandle>,
+}
+
+impl WorkspaceRegistry {
+    /// `$ARX_WORKSPACE_REGISTRY`, else `~/.arxos/workspaces.yaml`.
+    pub fn default_path() -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os(WORKSPACE_REGISTRY_ENV).filter(|p| !p.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        let home = dirs::home_dir().context("Could not find home directory")?;
+        Ok(home.join(".arxos").join(WORKSPACE_REGISTRY_FILE))
+    }
+
+    /// The user's registry; empty when none was saved yet.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::default_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, crate::yaml::to_canonical_yaml(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Building registered as `name` (case-insensitive).
+    pub fn get(&self, name: &str) -> Option<&BuildingHandle> {
+        self.buildings
+            .iter()
+            .find(|b| b.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Like [`Self::get`], with an error listing the registered names.
+    pub fn handle(&self, name: &str) -> Result<&BuildingHandle> {
+        self.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.buildings.iter().map(|b| b.name.as_str()).collect();
+            anyhow!(
+                "No building '{}' in the workspace (registered: {})",
+                name,
+                if names.is_empty() {
+                    "none; see `arx workspace add`".to_string()
+                } else {
+                    names.join(", ")
+                }
+            )
+        })
+    }
+
+    pub fn active(&self) -> Option<&BuildingHandle> {
+        self.active.as_deref().and_then(|name| self.get(name))
+    }
+
+    /// Register the building project at `root` as `name`; the building's
+    /// own name (else the directory name) when `name` is `None`.
+    pub fn add(&mut self, root: &Path, name: Option<&str>) -> Result<&BuildingHandle> {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("{} does not exist", root.display()))?;
+        let mut handle = BuildingHandle {
+            name: String::new(),
+            root,
+        };
+        if !handle.exists() {
+            bail!(
+                "{} has no {} (run `arx init` there first)",
+                handle.root.display(),
+                BUILDING_YAML
+            );
+        }
+        handle.name = match name {
+            Some(name) => name.to_string(),
+            None => handle
+                .load_shared()
+                .ok()
+                .map(|b| b.name.clone())
+                .filter(|n| !n.is_empty())
+                .or_else(|| {
+                    handle
+                        .root
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                })
+                .unwrap_or_else(|| "building".to_string()),
+        };
+        if let Some(existing) = self.buildings.iter().find(|b| b.root == handle.root) {
+            bail!(
+                "{} is already registered as '{}'",
+                handle.root.display(),
+                existing.name
+            );
+        }
+        if self.get(&handle.name).is_some() {
+            bail!(
+                "A building named '{}' is already registered (use --name)",
+                handle.name
+            );
+        }
+        self.buildings.push(handle);
+        Ok(self.buildings.last().expect("just pushed"))
+    }
+
+    /// Unregister `name` (the project itself is left alone).
+    pub fn remove(&mut self, name: &str) -> Result<BuildingHandle> {
+        let index = self
+            .buildings
+            .iter()
+            .position(|b| b.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("No building '{}' in the workspace", name))?;
+        let handle = self.buildings.remove(index);
+        if self
+            .active
+            .as_deref()
+            .is_some_and(|a| a.eq_ignore_ascii_case(&handle.name))
+        {
+            self.active = None;
+        }
+        Ok(handle)
+    }
+
+    /// Make `name` the active building.
+    pub fn switch(&mut self, name: &str) -> Result<&BuildingHandle> {
+        let name = self.handle(name)?.name.clone();
+        self.active = Some(name);
+        Ok(self.active().expect("just set"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(dir: &Path, name: &str) -> PathBuf {
+        let root = dir.join(name.to_lowercase());
+        std::fs::create_dir_all(&root).unwrap();
+        PersistenceManager::at(&root)
+            .save_building_unchecked(&Building::new(name.into(), format!("/{}", name)))
+            .unwrap();
+        root
+    }
+
+    #[test]
+    fn registry_adds_switches_and_removes_buildings() {
+        let dir = tempfile::tempdir().unwrap();
+        let (hq, annex) = (project(dir.path(), "HQ"), project(dir.path(), "Annex"));
+        let path = dir.path().join("workspaces.yaml");
+
+        let mut registry = WorkspaceRegistry::load_from(&path).unwrap();
+        assert_eq!(registry.add(&hq, None).unwrap().name, "HQ");
+        registry.add(&annex, Some("east")).unwrap();
+        assert!(registry.add(&hq, Some("again")).is_err());
+        assert!(registry.add(&dir.path().join("missing"), None).is_err());
+        registry.switch("hq").unwrap();
+        registry.save_to(&path).unwrap();
+
+        let mut registry = WorkspaceRegistry::load_from(&path).unwrap();
+        let active = registry.active().unwrap();
+        assert_eq!(active.root, hq.canonicalize().unwrap());
+        assert_eq!(active.load().unwrap().name, "HQ");
+        assert_eq!(
+            registry.handle("EAST").unwrap().load().unwrap().name,
+            "Annex"
+        );
+        assert!(registry.handle("west").is_err());
+
+        registry.remove("HQ").unwrap();
+        assert_eq!(registry.active, None);
+        assert_eq!(registry.buildings.len(), 1);
+    }
+}
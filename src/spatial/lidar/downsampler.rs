@@ -11,6 +11,38 @@ pub struct IngestionStats {
 pub struct VoxelGridFilter {
     voxel_size: f64,
     light_mode: bool,
+    decimation: Decimation,
+}
+
+/// Point-density thinning around the voxel filter, for dense laser scans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimation {
+    /// Keep every Nth input point (1 keeps all)
+    pub every: usize,
+    /// Thin the downsampled cloud uniformly to at most this many points
+    pub max_points: Option<usize>,
+}
+
+impl Default for Decimation {
+    fn default() -> Self {
+        Self {
+            every: 1,
+            max_points: None,
+        }
+    }
+}
+
+impl Decimation {
+    /// Evenly spaced subset of `points` no larger than `max_points`.
+    fn thin(&self, points: Vec<Point3D>) -> Vec<Point3D> {
+        match self.max_points {
+            Some(max) if points.len() > max => {
+                let len = points.len();
+                (0..max).map(|k| points[k * len / max]).collect()
+            }
+            _ => points,
+        }
+    }
 }
 
 struct VoxelAccumulator {
@@ -25,9 +57,15 @@ impl VoxelGridFilter {
         Self {
             voxel_size,
             light_mode,
+            decimation: Decimation::default(),
         }
     }
 
+    pub fn with_decimation(mut self, decimation: Decimation) -> Self {
+        self.decimation = decimation;
+        self
+    }
+
     pub fn filter(
         &self,
         points: impl Iterator<Item = Result<Point3D>>,
@@ -45,16 +83,22 @@ impl VoxelGridFilter {
             HashMap::with_capacity(max_capacity);
         let mut filtered_points = Vec::new();
         let mut total_points = 0;
+        let mut kept_points = 0;
 
         let max_input = max_lidar_input_points();
+        let every = self.decimation.every.max(1);
 
         for point_result in points {
             let p = point_result?;
             total_points += 1;
-            if total_points > max_input {
+            if (total_points - 1) % every != 0 {
+                continue;
+            }
+            kept_points += 1;
+            if kept_points > max_input {
                 bail!(
                     "LiDAR input exceeded pilot point limit ({} points). \
-                     Use --light, increase --voxel-size, decimate the scan offline, \
+                     Use --light, increase --voxel-size, pass --decimate, \
                      or set ARX_MAX_LIDAR_INPUT_POINTS. See docs/resource-limits.md.",
                     max_input
                 );
@@ -86,6 +130,7 @@ impl VoxelGridFilter {
 
         // Final flush
         self.flush_voxels(&mut voxel_map, &mut filtered_points);
+        let filtered_points = self.decimation.thin(filtered_points);
 
         let stats = IngestionStats {
             total_points,
@@ -113,3 +158,30 @@ impl VoxelGridFilter {
         voxel_map.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimation_keeps_every_nth_point_and_caps_output() {
+        // 100 points a metre apart: each lands in its own voxel
+        let points = || (0..100).map(|i| Ok(Point3D::new(i as f64, 0.0, 0.0)));
+
+        let filter = VoxelGridFilter::new(0.5, false).with_decimation(Decimation {
+            every: 10,
+            max_points: None,
+        });
+        let (kept, stats) = filter.filter(points()).unwrap();
+        assert_eq!(stats.total_points, 100);
+        assert_eq!(kept.len(), 10);
+
+        let filter = VoxelGridFilter::new(0.5, false).with_decimation(Decimation {
+            every: 1,
+            max_points: Some(25),
+        });
+        let (kept, stats) = filter.filter(points()).unwrap();
+        assert_eq!(kept.len(), 25);
+        assert_eq!(stats.downsampled_points, 25);
+    }
+}
@@ -0,0 +1,721 @@
+//! Streaming reader for ASTM E57 laser-scan files.
+//!
+//! An E57 file is a paged binary container: every page ends in a 4-byte
+//! checksum, an XML section describes the scans, and each scan's points live
+//! in a "compressed vector" binary section of packets, one bytestream per
+//! prototype field. Only the coordinate fields are decoded: cartesian or
+//! spherical coordinates stored as floats or bit-packed (scaled) integers,
+//! with each scan's pose applied. Page checksums are not verified.
+
+use crate::core::spatial::Point3D;
+use anyhow::{anyhow, bail, Context, Result};
+use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// File signature at offset 0.
+pub const E57_SIGNATURE: &[u8; 8] = b"ASTM-E57";
+
+const HEADER_LEN: usize = 48;
+const PAGE_CHECKSUM_LEN: u64 = 4;
+const SECTION_HEADER_LEN: usize = 32;
+const DATA_PACKET: u8 = 1;
+
+/// Fields decoded per record; everything else in the prototype is skipped.
+const COORDINATE_FIELDS: [&str; 7] = [
+    "cartesianX",
+    "cartesianY",
+    "cartesianZ",
+    "sphericalRange",
+    "sphericalAzimuth",
+    "sphericalElevation",
+    "cartesianInvalidState",
+];
+
+/// Stream every scan's points, in file order.
+pub fn stream_e57(path: &Path) -> Result<impl Iterator<Item = Result<Point3D>>> {
+    let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut paged = PagedFile::open(file)?;
+    let xml = paged.read_xml()?;
+    let root = parse_xml(&xml)?;
+    let scans: Vec<Scan> = root
+        .child("data3D")
+        .map(|data3d| data3d.children.iter().map(Scan::from_xml).collect())
+        .transpose()?
+        .unwrap_or_default();
+    Ok(E57Iterator {
+        file: paged,
+        scans: scans.into(),
+        current: None,
+        ready: VecDeque::new(),
+    })
+}
+
+/// Logical reads over the physical page layout.
+struct PagedFile {
+    file: File,
+    page_size: u64,
+    xml_offset: u64,
+    xml_length: u64,
+}
+
+impl PagedFile {
+    fn open(mut file: File) -> Result<Self> {
+        let mut header = [0u8; HEADER_LEN];
+        file.read_exact(&mut header)
+            .map_err(|_| anyhow!("E57 file is shorter than its header"))?;
+        if &header[..8] != E57_SIGNATURE {
+            bail!("Not an E57 file (missing ASTM-E57 signature)");
+        }
+        let page_size = u64_at(&header, 40);
+        if page_size <= PAGE_CHECKSUM_LEN {
+            bail!("E57 header has an invalid page size ({})", page_size);
+        }
+        Ok(Self {
+            file,
+            page_size,
+            xml_offset: u64_at(&header, 24),
+            xml_length: u64_at(&header, 32),
+        })
+    }
+
+    fn payload(&self) -> u64 {
+        self.page_size - PAGE_CHECKSUM_LEN
+    }
+
+    /// Fill `buf` with logical bytes starting at a physical offset; returns
+    /// the physical offset just past the last byte read.
+    fn read_at(&mut self, mut physical: u64, buf: &mut [u8]) -> Result<u64> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            let within = physical % self.page_size;
+            if within >= self.payload() {
+                physical += self.page_size - within;
+                continue;
+            }
+            let take = ((self.payload() - within) as usize).min(buf.len() - filled);
+            self.file.seek(SeekFrom::Start(physical))?;
+            self.file
+                .read_exact(&mut buf[filled..filled + take])
+                .map_err(|_| anyhow!("E57 file truncated at offset {}", physical))?;
+            filled += take;
+            physical += take as u64;
+        }
+        Ok(physical)
+    }
+
+    fn read_xml(&mut self) -> Result<String> {
+        let mut xml = vec![0u8; self.xml_length as usize];
+        self.read_at(self.xml_offset, &mut xml)?;
+        String::from_utf8(xml).map_err(|_| anyhow!("E57 XML section is not UTF-8"))
+    }
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(word)
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> usize {
+    usize::from(u16::from_le_bytes([bytes[at], bytes[at + 1]]))
+}
+
+/// An XML element, with just what the E57 schema needs.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attrs: HashMap<String, String>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).map(String::as_str)
+    }
+
+    fn number(&self, name: &str) -> Result<Option<f64>> {
+        self.attr(name)
+            .map(|v| {
+                v.trim()
+                    .parse::<f64>()
+                    .map_err(|_| anyhow!("E57 <{}> has non-numeric {}='{}'", self.name, name, v))
+            })
+            .transpose()
+    }
+
+    fn value(&self) -> f64 {
+        self.text.trim().parse().unwrap_or(0.0)
+    }
+}
+
+/// Minimal XML parser: elements, attributes and text; no entities or DTDs.
+fn parse_xml(xml: &str) -> Result<Element> {
+    let mut stack = vec![Element::default()];
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        stack
+            .last_mut()
+            .ok_or_else(|| anyhow!("E57 XML is malformed"))?
+            .text
+            .push_str(&rest[..open]);
+        rest = &rest[open..];
+        let skip_to = |rest: &str, end: &str| {
+            rest.find(end)
+                .map(|i| i + end.len())
+                .ok_or_else(|| anyhow!("E57 XML ends inside a tag"))
+        };
+        if rest.starts_with("<?") {
+            rest = &rest[skip_to(rest, "?>")?..];
+        } else if rest.starts_with("<!--") {
+            rest = &rest[skip_to(rest, "-->")?..];
+        } else if rest.starts_with("<!") {
+            rest = &rest[skip_to(rest, ">")?..];
+        } else if rest.starts_with("</") {
+            rest = &rest[skip_to(rest, ">")?..];
+            let done = stack.pop().ok_or_else(|| anyhow!("E57 XML is malformed"))?;
+            stack
+                .last_mut()
+                .ok_or_else(|| anyhow!("E57 XML has an unmatched </{}>", done.name))?
+                .children
+                .push(done);
+        } else {
+            let end = skip_to(rest, ">")?;
+            let tag = &rest[1..end - 1];
+            rest = &rest[end..];
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let element = Element {
+                name: name.to_string(),
+                attrs: parse_attrs(attrs),
+                ..Element::default()
+            };
+            if self_closing {
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(element);
+                }
+            } else {
+                stack.push(element);
+            }
+        }
+    }
+    let document = stack.pop().filter(|_| stack.is_empty());
+    document
+        .and_then(|d| d.children.into_iter().next())
+        .ok_or_else(|| anyhow!("E57 XML section has no root element"))
+}
+
+fn parse_attrs(mut s: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    while let Some(eq) = s.find('=') {
+        let name = s[..eq].trim();
+        let after = s[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(len) = after[1..].find(quote) else {
+            break;
+        };
+        attrs.insert(name.to_string(), after[1..1 + len].to_string());
+        s = &after[len + 2..];
+    }
+    attrs
+}
+
+/// How one prototype field's values are stored in its bytestream.
+#[derive(Debug, Clone, Copy)]
+enum Encoding {
+    Float32,
+    Float64,
+    /// Bit-packed integer: `(raw + minimum) * scale + offset`
+    Packed {
+        bits: u32,
+        minimum: i64,
+        scale: f64,
+        offset: f64,
+    },
+}
+
+impl Encoding {
+    fn from_xml(field: &Element) -> Result<Self> {
+        match field.attr("type") {
+            Some("Float") => Ok(match field.attr("precision") {
+                Some("single") => Encoding::Float32,
+                _ => Encoding::Float64,
+            }),
+            Some(kind @ ("Integer" | "ScaledInteger")) => {
+                let minimum = field.number("minimum")?.unwrap_or(i64::MIN as f64) as i64;
+                let maximum = field.number("maximum")?.unwrap_or(i64::MAX as f64) as i64;
+                if maximum < minimum {
+                    bail!("E57 field {} has maximum below minimum", field.name);
+                }
+                let range = maximum.abs_diff(minimum);
+                let bits = if range == 0 {
+                    0
+                } else {
+                    u64::BITS - range.leading_zeros()
+                };
+                let scaled = kind == "ScaledInteger";
+                Ok(Encoding::Packed {
+                    bits,
+                    minimum,
+                    scale: if scaled {
+                        field.number("scale")?.unwrap_or(1.0)
+                    } else {
+                        1.0
+                    },
+                    offset: if scaled {
+                        field.number("offset")?.unwrap_or(0.0)
+                    } else {
+                        0.0
+                    },
+                })
+            }
+            other => bail!(
+                "E57 field {} has unsupported type {}",
+                field.name,
+                other.unwrap_or("(none)")
+            ),
+        }
+    }
+}
+
+/// Pending bytes of one field's bytestream, consumed bit by bit.
+#[derive(Debug)]
+struct Bytestream {
+    encoding: Encoding,
+    bytes: Vec<u8>,
+    bit: usize,
+}
+
+impl Bytestream {
+    fn available_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.bit
+    }
+
+    fn next_value(&mut self) -> Option<f64> {
+        let value = match self.encoding {
+            Encoding::Float32 | Encoding::Float64 => {
+                let width = if matches!(self.encoding, Encoding::Float32) {
+                    4
+                } else {
+                    8
+                };
+                let start = self.bit / 8;
+                if self.bytes.len() < start + width {
+                    return None;
+                }
+                let raw = &self.bytes[start..start + width];
+                self.bit += width * 8;
+                if width == 4 {
+                    f64::from(f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]))
+                } else {
+                    f64::from_le_bytes(raw.try_into().ok()?)
+                }
+            }
+            Encoding::Packed {
+                bits,
+                minimum,
+                scale,
+                offset,
+            } => {
+                let bits = bits as usize;
+                if self.available_bits() < bits {
+                    return None;
+                }
+                let mut raw: u64 = 0;
+                for i in 0..bits {
+                    let at = self.bit + i;
+                    if self.bytes[at / 8] >> (at % 8) & 1 == 1 {
+                        raw |= 1 << i;
+                    }
+                }
+                self.bit += bits;
+                (minimum.wrapping_add(raw as i64)) as f64 * scale + offset
+            }
+        };
+        // Drop whole consumed bytes so buffers stay packet-sized
+        let consumed = self.bit / 8;
+        if consumed > 0 {
+            self.bytes.drain(..consumed);
+            self.bit -= consumed * 8;
+        }
+        Some(value)
+    }
+}
+
+/// One `data3D` scan: where its points are and how to place them.
+#[derive(Debug)]
+struct Scan {
+    section_offset: u64,
+    records: u64,
+    /// Prototype field position and encoding, by name
+    fields: HashMap<String, (usize, Encoding)>,
+    field_count: usize,
+    rotation: UnitQuaternion<f64>,
+    translation: Vector3<f64>,
+}
+
+impl Scan {
+    fn from_xml(scan: &Element) -> Result<Self> {
+        let points = scan
+            .child("points")
+            .ok_or_else(|| anyhow!("E57 scan has no <points>"))?;
+        let prototype = points
+            .child("prototype")
+            .ok_or_else(|| anyhow!("E57 <points> has no <prototype>"))?;
+        let mut fields = HashMap::new();
+        for (i, field) in prototype.children.iter().enumerate() {
+            if COORDINATE_FIELDS.contains(&field.name.as_str()) {
+                fields.insert(field.name.clone(), (i, Encoding::from_xml(field)?));
+            }
+        }
+        let cartesian = ["cartesianX", "cartesianY", "cartesianZ"];
+        let spherical = ["sphericalRange", "sphericalAzimuth", "sphericalElevation"];
+        if !cartesian.iter().all(|f| fields.contains_key(*f))
+            && !spherical.iter().all(|f| fields.contains_key(*f))
+        {
+            bail!("E57 scan has neither cartesian nor spherical coordinates");
+        }
+
+        let pose = scan.child("pose");
+        let component = |part: &str, axis: &str, default: f64| {
+            pose.and_then(|p| p.child(part))
+                .and_then(|p| p.child(axis))
+                .map_or(default, Element::value)
+        };
+        let rotation = UnitQuaternion::from_quaternion(Quaternion::new(
+            component("rotation", "w", 1.0),
+            component("rotation", "x", 0.0),
+            component("rotation", "y", 0.0),
+            component("rotation", "z", 0.0),
+        ));
+        let translation = Vector3::new(
+            component("translation", "x", 0.0),
+            component("translation", "y", 0.0),
+            component("translation", "z", 0.0),
+        );
+
+        Ok(Self {
+            section_offset: points
+                .number("fileOffset")?
+                .ok_or_else(|| anyhow!("E57 <points> has no fileOffset"))?
+                as u64,
+            records: points.number("recordCount")?.unwrap_or(0.0) as u64,
+            fields,
+            field_count: prototype.children.len(),
+            rotation,
+            translation,
+        })
+    }
+}
+
+/// Packet-by-packet decode state for the scan being read.
+struct ScanReader {
+    scan: Scan,
+    streams: HashMap<String, Bytestream>,
+    next_packet: u64,
+    end: u64,
+    remaining: u64,
+}
+
+struct E57Iterator {
+    file: PagedFile,
+    scans: VecDeque<Scan>,
+    current: Option<ScanReader>,
+    ready: VecDeque<Point3D>,
+}
+
+impl E57Iterator {
+    fn start_scan(&mut self, scan: Scan) -> Result<ScanReader> {
+        let mut header = [0u8; SECTION_HEADER_LEN];
+        self.file.read_at(scan.section_offset, &mut header)?;
+        if header[0] != 1 {
+            bail!(
+                "E57 section at offset {} is not a compressed vector",
+                scan.section_offset
+            );
+        }
+        let next_packet = u64_at(&header, 16);
+        let logical_len = u64_at(&header, 8);
+        let payload = self.file.payload();
+        // Physical end of the section: its logical length spread over pages
+        let start_logical = (scan.section_offset / self.file.page_size) * payload
+            + scan.section_offset % self.file.page_size;
+        let end_logical = start_logical + logical_len;
+        let end = (end_logical / payload) * self.file.page_size + end_logical % payload;
+        let streams = scan
+            .fields
+            .iter()
+            .map(|(name, (_, encoding))| {
+                (
+                    name.clone(),
+                    Bytestream {
+                        encoding: *encoding,
+                        bytes: Vec::new(),
+                        bit: 0,
+                    },
+                )
+            })
+            .collect();
+        Ok(ScanReader {
+            remaining: scan.records,
+            scan,
+            streams,
+            next_packet,
+            end,
+        })
+    }
+
+    /// Read one packet of the current scan; false once the scan is exhausted.
+    fn read_packet(&mut self) -> Result<bool> {
+        let Some(reader) = self.current.as_mut() else {
+            return Ok(false);
+        };
+        if reader.remaining == 0 || reader.next_packet >= reader.end {
+            return Ok(false);
+        }
+        let mut head = [0u8; 4];
+        self.file.read_at(reader.next_packet, &mut head)?;
+        let length = u16_at(&head, 2) + 1;
+        let mut packet = vec![0u8; length];
+        reader.next_packet = self.file.read_at(reader.next_packet, &mut packet)?;
+        if packet[0] != DATA_PACKET {
+            // Index and empty packets carry no point data
+            return Ok(true);
+        }
+        let count = u16_at(&packet, 4);
+        if count != reader.scan.field_count {
+            bail!(
+                "E57 data packet has {} bytestreams; prototype has {} fields",
+                count,
+                reader.scan.field_count
+            );
+        }
+        let mut at = 6 + 2 * count;
+        let mut buffers = Vec::with_capacity(count);
+        for i in 0..count {
+            let len = u16_at(&packet, 6 + 2 * i);
+            let buffer = packet
+                .get(at..at + len)
+                .ok_or_else(|| anyhow!("E57 data packet is shorter than its bytestreams"))?;
+            buffers.push(buffer);
+            at += len;
+        }
+        for (name, (index, _)) in &reader.scan.fields {
+            if let Some(stream) = reader.streams.get_mut(name) {
+                stream.bytes.extend_from_slice(buffers[*index]);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Move every complete record of the current scan into `ready`.
+    fn decode_records(&mut self) {
+        let Some(reader) = self.current.as_mut() else {
+            return;
+        };
+        while reader.remaining > 0 {
+            let mut record = HashMap::with_capacity(reader.streams.len());
+            // A record is complete only when every field has a value buffered
+            let complete = reader.streams.iter().all(|(_, s)| match s.encoding {
+                Encoding::Float32 => s.available_bits() >= 32,
+                Encoding::Float64 => s.available_bits() >= 64,
+                Encoding::Packed { bits, .. } => s.available_bits() >= bits as usize,
+            });
+            if !complete {
+                break;
+            }
+            for (name, stream) in reader.streams.iter_mut() {
+                if let Some(value) = stream.next_value() {
+                    record.insert(name.as_str(), value);
+                }
+            }
+            reader.remaining -= 1;
+            if record.get("cartesianInvalidState").copied().unwrap_or(0.0) != 0.0 {
+                continue;
+            }
+            let local = match (
+                record.get("cartesianX"),
+                record.get("cartesianY"),
+                record.get("cartesianZ"),
+            ) {
+                (Some(x), Some(y), Some(z)) => Vector3::new(*x, *y, *z),
+                _ => {
+                    let range = record.get("sphericalRange").copied().unwrap_or(0.0);
+                    let azimuth = record.get("sphericalAzimuth").copied().unwrap_or(0.0);
+                    let elevation = record.get("sphericalElevation").copied().unwrap_or(0.0);
+                    Vector3::new(
+                        range * elevation.cos() * azimuth.cos(),
+                        range * elevation.cos() * azimuth.sin(),
+                        range * elevation.sin(),
+                    )
+                }
+            };
+            let p = reader.scan.rotation * local + reader.scan.translation;
+            self.ready.push_back(Point3D::new(p.x, p.y, p.z));
+        }
+    }
+
+    fn fill(&mut self) -> Result<()> {
+        while self.ready.is_empty() {
+            if self.current.is_none() {
+                let Some(scan) = self.scans.pop_front() else {
+                    return Ok(());
+                };
+                self.current = Some(self.start_scan(scan)?);
+            }
+            if !self.read_packet()? {
+                self.current = None;
+                continue;
+            }
+            self.decode_records();
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for E57Iterator {
+    type Item = Result<Point3D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.fill() {
+            // Stop after the first error instead of retrying the same packet
+            self.scans.clear();
+            self.current = None;
+            return Some(Err(e));
+        }
+        self.ready.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: usize = 1024;
+    const PAYLOAD: usize = PAGE - 4;
+
+    fn physical(logical: usize) -> u64 {
+        ((logical / PAYLOAD) * PAGE + logical % PAYLOAD) as u64
+    }
+
+    /// Spread logical bytes over 1 KiB pages with (zeroed) checksums.
+    fn paginate(logical: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for chunk in logical.chunks(PAYLOAD) {
+            out.extend_from_slice(chunk);
+            out.resize(out.len() + PAYLOAD - chunk.len() + 4, 0);
+        }
+        out
+    }
+
+    fn pack_bits(values: &[u64], bits: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; (values.len() * bits).div_ceil(8)];
+        for (n, value) in values.iter().enumerate() {
+            for i in 0..bits {
+                if value >> i & 1 == 1 {
+                    let at = n * bits + i;
+                    bytes[at / 8] |= 1 << (at % 8);
+                }
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_reads_scaled_integer_and_float_fields_with_pose() {
+        // x: ScaledInteger 0..1000 (10 bits) * 0.01; y: double; z: single;
+        // the third record is flagged invalid and must be dropped
+        let xs = pack_bits(&[100, 250, 999], 10);
+        let ys: Vec<u8> = [1.5f64, 2.0, 9.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let zs: Vec<u8> = [0.5f32, 3.0, 9.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let invalid = pack_bits(&[0, 0, 2], 2);
+        let streams = [xs, ys, zs, invalid, vec![0xAA; 3]];
+
+        let mut packet = vec![DATA_PACKET, 0, 0, 0];
+        packet.extend_from_slice(&(streams.len() as u16).to_le_bytes());
+        for s in &streams {
+            packet.extend_from_slice(&(s.len() as u16).to_le_bytes());
+        }
+        for s in &streams {
+            packet.extend_from_slice(s);
+        }
+        packet.resize(packet.len().next_multiple_of(4), 0);
+        let len = (packet.len() - 1) as u16;
+        packet[2..4].copy_from_slice(&len.to_le_bytes());
+
+        let section_at = HEADER_LEN;
+        let data_at = section_at + SECTION_HEADER_LEN;
+        let mut logical = vec![0u8; HEADER_LEN];
+        let mut section = vec![1u8; 1];
+        section.resize(8, 0);
+        section.extend_from_slice(&((SECTION_HEADER_LEN + packet.len()) as u64).to_le_bytes());
+        section.extend_from_slice(&physical(data_at).to_le_bytes());
+        section.extend_from_slice(&0u64.to_le_bytes());
+        logical.extend_from_slice(&section);
+        logical.extend_from_slice(&packet);
+
+        let xml = format!(
+            r#"<?xml version="1.0"?>
+<e57Root type="Structure" xmlns="http://www.astm.org/COMMIT/E57/2010-e57-v1.0">
+  <data3D type="Vector" allowHeterogeneousChildren="1">
+    <vectorChild type="Structure">
+      <pose type="Structure">
+        <translation type="Structure"><x type="Float">10</x><y type="Float">0</y><z type="Float">0</z></translation>
+      </pose>
+      <points type="CompressedVector" fileOffset="{}" recordCount="3">
+        <prototype type="Structure">
+          <cartesianX type="ScaledInteger" minimum="0" maximum="1000" scale="0.01" offset="0"/>
+          <cartesianY type="Float"/>
+          <cartesianZ type="Float" precision="single"/>
+          <cartesianInvalidState type="Integer" minimum="0" maximum="2"/>
+          <intensity type="Integer" minimum="0" maximum="255"/>
+        </prototype>
+      </points>
+    </vectorChild>
+  </data3D>
+</e57Root>"#,
+            physical(section_at)
+        );
+        // Start the XML near the end of the first page so reading it has to
+        // skip a page checksum
+        logical.resize(PAYLOAD - 100, 0);
+        let xml_at = logical.len();
+        logical.extend_from_slice(xml.as_bytes());
+
+        logical[..8].copy_from_slice(E57_SIGNATURE);
+        logical[8..12].copy_from_slice(&1u32.to_le_bytes());
+        let file = paginate(&logical);
+        logical[16..24].copy_from_slice(&(file.len() as u64).to_le_bytes());
+        logical[24..32].copy_from_slice(&physical(xml_at).to_le_bytes());
+        logical[32..40].copy_from_slice(&(xml.len() as u64).to_le_bytes());
+        logical[40..48].copy_from_slice(&(PAGE as u64).to_le_bytes());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.e57");
+        std::fs::write(&path, paginate(&logical)).unwrap();
+
+        let points: Vec<Point3D> = stream_e57(&path).unwrap().collect::<Result<_>>().unwrap();
+        assert_eq!(points.len(), 2);
+        assert!((points[0].x - 11.0).abs() < 1e-9);
+        assert!((points[0].y - 1.5).abs() < 1e-9);
+        assert!((points[0].z - 0.5).abs() < 1e-9);
+        assert!((points[1].x - 12.5).abs() < 1e-9);
+        assert!((points[1].z - 3.0).abs() < 1e-9);
+    }
+}
@@ -5,11 +5,13 @@ use std::path::Path;
 
 pub mod detector;
 pub mod downsampler;
+pub mod e57;
 pub mod parser;
 
 pub struct LidarPipeline {
     pub voxel_size: f64,
     pub light_mode: bool,
+    pub decimation: downsampler::Decimation,
 }
 
 impl LidarPipeline {
@@ -17,16 +19,23 @@ impl LidarPipeline {
         Self {
             voxel_size,
             light_mode,
+            decimation: downsampler::Decimation::default(),
         }
     }
 
+    pub fn with_decimation(mut self, decimation: downsampler::Decimation) -> Self {
+        self.decimation = decimation;
+        self
+    }
+
     pub fn process<P: AsRef<Path>>(&self, path: P) -> Result<Building> {
         let path = path.as_ref();
         println!("🚀 Reading points from {}...", path.display());
         let points = parser::stream_points(path)?;
 
         println!("🧹 Filtering point cloud via voxel downsampler...");
-        let downsampler = downsampler::VoxelGridFilter::new(self.voxel_size, self.light_mode);
+        let downsampler = downsampler::VoxelGridFilter::new(self.voxel_size, self.light_mode)
+            .with_decimation(self.decimation);
         let (downsampled_points, stats) = downsampler.filter(points)?;
 
         println!("🏢 Reconstructing building structure...");
@@ -5,7 +5,7 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
-/// Stream points from a point cloud file (CSV, XYZ, PLY, or LAS)
+/// Stream points from a point cloud file (CSV, XYZ, PLY, LAS, or E57)
 pub fn stream_points(path: &Path) -> Result<Box<dyn Iterator<Item = Result<Point3D>>>> {
     // 1. Try to detect by reading the first few bytes
     if let Ok(mut file) = File::open(path) {
@@ -18,6 +18,9 @@ pub fn stream_points(path: &Path) -> Result<Box<dyn Iterator<Item = Result<Point
             if &magic[..3] == b"ply" {
                 return Ok(Box::new(stream_ply(path)?));
             }
+            if magic == super::e57::E57_SIGNATURE[..4] {
+                return Ok(Box::new(super::e57::stream_e57(path)?));
+            }
         }
     }
 
@@ -31,6 +34,7 @@ pub fn stream_points(path: &Path) -> Result<Box<dyn Iterator<Item = Result<Point
         Some("csv") | Some("xyz") | Some("txt") => Ok(Box::new(stream_xyz_csv(path)?)),
         Some("ply") => Ok(Box::new(stream_ply(path)?)),
         Some("las") | Some("laz") => Ok(Box::new(stream_las(path)?)),
+        Some("e57") => Ok(Box::new(super::e57::stream_e57(path)?)),
         _ => {
             // Default fallback to text-based parsing
             Ok(Box::new(stream_xyz_csv(path)?))
@@ -186,6 +190,13 @@ impl<'a> Iterator for LasIterator<'a> {
 
 /// Streaming reader for LAS binary point clouds using `las` crate
 fn stream_las(path: &Path) -> Result<impl Iterator<Item = Result<Point3D>> + 'static> {
-    let reader = las::Reader::from_path(path)?;
+    let reader = las::Reader::from_path(path).map_err(|e| match e {
+        las::Error::Laszip => anyhow!(
+            "{} is LASzip-compressed (LAZ), which this build cannot decode; \
+             decompress it first (e.g. `laszip -i scan.laz -o scan.las`)",
+            path.display()
+        ),
+        e => e.into(),
+    })?;
     Ok(LasIterator { reader })
 }
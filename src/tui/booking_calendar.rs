@@ -0,0 +1,300 @@
+//! Room booking calendar for ArxOS TUI
+//!
+//! Lists the building's rooms and shows, for the selected room, a week of
+//! bookings (imported calendars and `arx room book` reservations) with its
+//! capacity and current occupancy. Double-bookings are highlighted.
+
+use crate::core::domain::booking::{room_schedule, OccupancySample, RoomBooking, RoomSchedule};
+use crate::core::Building;
+use chrono::{DateTime, Duration, Utc, Weekday};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+/// Booking calendar view state
+pub struct CalendarView {
+    building: Building,
+    /// (id, name) of every room, by name
+    rooms: Vec<(String, String)>,
+    bookings: Vec<RoomBooking>,
+    samples: Vec<OccupancySample>,
+    now: DateTime<Utc>,
+    /// Monday 00:00 UTC of the week shown
+    week_start: DateTime<Utc>,
+    state: ListState,
+}
+
+fn week_of(at: DateTime<Utc>) -> DateTime<Utc> {
+    let monday = at.date_naive().week(Weekday::Mon).first_day();
+    monday.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+impl CalendarView {
+    /// `bookings` should already be resolved to room ids.
+    pub fn new(
+        building: Building,
+        bookings: Vec<RoomBooking>,
+        samples: Vec<OccupancySample>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut rooms: Vec<(String, String)> = building
+            .get_all_rooms()
+            .into_iter()
+            .map(|r| (r.id.clone(), r.name.clone()))
+            .collect();
+        rooms.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let mut state = ListState::default();
+        if !rooms.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            building,
+            rooms,
+            bookings,
+            samples,
+            now,
+            week_start: week_of(now),
+            state,
+        }
+    }
+
+    /// Rooms shown in the list
+    pub fn room_count(&self) -> usize {
+        self.rooms.len()
+    }
+
+    /// Select the room with this id
+    pub fn select_room(&mut self, room_id: &str) {
+        if let Some(i) = self.rooms.iter().position(|(id, _)| id == room_id) {
+            self.state.select(Some(i));
+        }
+    }
+
+    /// Selected room's schedule for the week shown
+    pub fn schedule(&self) -> Option<RoomSchedule> {
+        let (id, _) = self.state.selected().and_then(|i| self.rooms.get(i))?;
+        let room = self.building.find_room(id)?;
+        Some(room_schedule(
+            room,
+            &self.bookings,
+            &self.samples,
+            self.week_start,
+            self.week_start + Duration::days(7),
+            self.now,
+        ))
+    }
+
+    /// Handle keyboard input
+    pub fn handle_key(&mut self, key: KeyEvent) -> CalendarAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return CalendarAction::Exit,
+            KeyCode::Left | KeyCode::Char('h') => self.week_start -= Duration::days(7),
+            KeyCode::Right | KeyCode::Char('l') => self.week_start += Duration::days(7),
+            KeyCode::Char('t') => self.week_start = week_of(self.now),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            _ => {}
+        }
+        CalendarAction::Continue
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.rooms.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.rooms.len() as isize - 1);
+        self.state.select(Some(next as usize));
+    }
+
+    /// Render the view
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(3)])
+            .split(area);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
+            .split(rows[0]);
+
+        let items: Vec<ListItem> = self
+            .rooms
+            .iter()
+            .map(|(_, name)| ListItem::new(format!("🚪 {}", name)))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Rooms ({})", self.rooms.len())),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▸ ");
+        frame.render_stateful_widget(list, columns[0], &mut self.state);
+
+        let week_end = self.week_start + Duration::days(6);
+        let Some(schedule) = self.schedule() else {
+            frame.render_widget(
+                Paragraph::new("(no rooms)").block(Block::default().borders(Borders::ALL)),
+                columns[1],
+            );
+            return;
+        };
+        let calendar = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .split(columns[1]);
+
+        let capacity = schedule
+            .capacity
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let occupancy = schedule
+            .occupancy
+            .map(|o| format!("{:.0}", o))
+            .unwrap_or_else(|| "no recent reading".to_string());
+        let mut summary = vec![Span::raw(format!(
+            "Capacity {}  │  Occupancy now {}",
+            capacity, occupancy
+        ))];
+        if schedule.conflicts() > 0 {
+            summary.push(Span::styled(
+                format!("  │  ⚠ {} double-booked", schedule.conflicts()),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(summary)).block(
+                Block::default().borders(Borders::ALL).title(format!(
+                    "📅 {} — week of {} to {}",
+                    schedule.room,
+                    self.week_start.format("%Y-%m-%d"),
+                    week_end.format("%Y-%m-%d")
+                )),
+            ),
+            calendar[0],
+        );
+
+        let days = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 7); 7])
+            .split(calendar[1]);
+        for (i, column) in days.iter().enumerate() {
+            let day = self.week_start + Duration::days(i as i64);
+            let lines: Vec<Line> = schedule
+                .bookings
+                .iter()
+                .filter(|b| b.booking.overlaps(day, day + Duration::days(1)))
+                .map(|b| {
+                    let style = if b.conflicts.is_empty() {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::Red)
+                    };
+                    Line::from(Span::styled(
+                        format!(
+                            "{}–{} {}",
+                            b.booking.start.format("%H:%M"),
+                            b.booking.end.format("%H:%M"),
+                            b.booking.title.as_deref().unwrap_or("(untitled)")
+                        ),
+                        style,
+                    ))
+                })
+                .collect();
+            let title_style = if day.date_naive() == self.now.date_naive() {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            frame.render_widget(
+                Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(Span::styled(day.format("%a %d").to_string(), title_style)),
+                ),
+                *column,
+            );
+        }
+
+        let help = Paragraph::new("↑↓ Room │ ←→ Week │ t: This week │ q/Esc: Quit")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::ALL).title("Help"));
+        frame.render_widget(help, rows[1]);
+    }
+}
+
+/// Actions returned by the calendar view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarAction {
+    Continue,
+    Exit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Floor, Room, RoomType, Wing};
+    use chrono::TimeZone;
+
+    #[test]
+    fn arrows_page_through_weeks() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Huddle".into(), RoomType::Office);
+        room.id = "huddle".into();
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2024, 3, 6, 10, 0, 0).unwrap();
+        let booking = |id: &str, days: i64| RoomBooking {
+            id: id.into(),
+            source: "arx".into(),
+            room: "Huddle".into(),
+            room_id: Some("huddle".into()),
+            start: now + Duration::days(days),
+            end: now + Duration::days(days) + Duration::hours(1),
+            title: None,
+            organizer: None,
+        };
+        let mut view = CalendarView::new(
+            building,
+            vec![booking("this", 1), booking("next", 7)],
+            Vec::new(),
+            now,
+        );
+        assert_eq!(view.room_count(), 1);
+        let ids = |view: &CalendarView| -> Vec<String> {
+            view.schedule()
+                .unwrap()
+                .bookings
+                .into_iter()
+                .map(|b| b.booking.id)
+                .collect()
+        };
+        assert_eq!(ids(&view), ["this"]);
+        view.handle_key(KeyEvent::from(KeyCode::Right));
+        assert_eq!(ids(&view), ["next"]);
+        view.handle_key(KeyEvent::from(KeyCode::Char('t')));
+        assert_eq!(ids(&view), ["this"]);
+        assert_eq!(
+            view.handle_key(KeyEvent::from(KeyCode::Char('q'))),
+            CalendarAction::Exit
+        );
+    }
+}
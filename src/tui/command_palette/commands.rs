@@ -78,6 +78,30 @@ pub fn load_commands() -> Vec<CommandEntry> {
             "Query equipment by durable ArxAddress",
             CommandCategory::Search,
         ),
+        entry(
+            "workorder create",
+            "arx workorder create --title 'Replace filter' --equipment VAV-7",
+            "Open a work order on a room or equipment",
+            CommandCategory::Equipment,
+        ),
+        entry(
+            "workorder list",
+            "arx workorder list",
+            "List open work orders",
+            CommandCategory::Equipment,
+        ),
+        entry(
+            "workorder show",
+            "arx workorder show WO-",
+            "Show a work order and its comments",
+            CommandCategory::Equipment,
+        ),
+        entry(
+            "workorder close",
+            "arx workorder close WO- --resolution 'Done'",
+            "Close a work order (committed to Git)",
+            CommandCategory::Equipment,
+        ),
         entry(
             "search",
             "arx search boiler",
@@ -125,6 +149,7 @@ mod tests {
         assert!(!commands.is_empty());
         assert!(commands.iter().any(|c| c.name == "query"));
         assert!(commands.iter().any(|c| c.name == "migrate"));
+        assert!(commands.iter().any(|c| c.name == "workorder create"));
         // Compiler surface is intentionally smaller than the old kitchen-sink palette
         assert!(commands.len() >= 10);
         assert!(commands.len() < 30);
@@ -1,10 +1,13 @@
 //! Dashboard module - requires both tui and agent features.
 //!
 //! Shows agent repo context (no hardware/sensor polling — drivers deferred).
+//! A [`RepoWatcher`] keeps it live: each change to `building.yaml` or the
+//! `.arx/` records is listed under "Live changes" and refreshes the status.
 
 #![cfg(feature = "agent")]
 
 use crate::agent::dispatcher::AgentState;
+use crate::agent::watcher::{FileChange, RepoWatcher};
 use anyhow::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
@@ -19,6 +22,8 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame, Terminal,
 };
+use std::collections::VecDeque;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -27,6 +32,8 @@ pub struct App {
     pub title: String,
     pub should_quit: bool,
     pub lines: Vec<String>,
+    /// Most recent file changes, newest first
+    pub changes: VecDeque<String>,
 }
 
 impl App {
@@ -35,10 +42,28 @@ impl App {
             title: title.to_string(),
             should_quit: false,
             lines,
+            changes: VecDeque::new(),
         }
     }
+
+    /// Record a batch of file changes, keeping the newest [`CHANGES_SHOWN`].
+    pub fn push_changes(&mut self, changes: &[FileChange]) {
+        for change in changes {
+            self.changes.push_front(format!(
+                "{} {:<8} {} ({})",
+                change.at.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                change.kind.to_string(),
+                change.path,
+                change.area
+            ));
+        }
+        self.changes.truncate(CHANGES_SHOWN);
+    }
 }
 
+/// Number of file changes kept on screen.
+const CHANGES_SHOWN: usize = 8;
+
 pub async fn run_dashboard(state: Arc<AgentState>) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
@@ -46,15 +71,13 @@ pub async fn run_dashboard(state: Arc<AgentState>) -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let lines = vec![
-        format!("Repo: {}", state.repo_root.display()),
-        "Mode: agent edge bridge (git + IFC)".to_string(),
-        "Hardware sensors: not in this build".to_string(),
-        "Keys: q / Esc quit".to_string(),
-    ];
-    let mut app = App::new("ArxOS Agent Dashboard", lines);
+    let mut app = App::new("ArxOS Agent Dashboard", dashboard_lines(&state.repo_root));
 
-    let res = run_app(&mut terminal, &mut app).await;
+    // Without a watcher the dashboard still works, it just isn't live
+    let watcher = RepoWatcher::new(&state.repo_root)
+        .map_err(|e| tracing::warn!(error = %e, "Dashboard live updates disabled"))
+        .ok();
+    let res = run_app(&mut terminal, &mut app, &state.repo_root, watcher.as_ref()).await;
 
     disable_raw_mode()?;
     execute!(
@@ -71,10 +94,122 @@ pub async fn run_dashboard(state: Arc<AgentState>) -> Result<()> {
     Ok(())
 }
 
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+/// Status lines shown for the repo at `repo_root`.
+fn dashboard_lines(repo_root: &std::path::Path) -> Vec<String> {
+    let mut lines = vec![
+        format!("Repo: {}", repo_root.display()),
+        "Mode: agent edge bridge (git + IFC)".to_string(),
+        "Hardware sensors: not in this build".to_string(),
+    ];
+    lines.extend(capacity_lines(repo_root));
+    lines.extend(completeness_lines(repo_root));
+    lines.extend(issue_lines(repo_root));
+    lines.extend(sensor_lines(repo_root));
+    lines.extend(energy_lines(repo_root));
+    lines.push("Keys: q / Esc quit".to_string());
+    lines
+}
+
+/// Per-floor capacity constraint violations from `.arx/constraints.yaml`.
+fn capacity_lines(repo_root: &std::path::Path) -> Vec<String> {
+    use crate::validation::{capacity_summary_lines, evaluate_constraints, ConstraintSet};
+
+    let constraints = match ConstraintSet::load_at(repo_root) {
+        Ok(set) if !set.is_empty() => set,
+        Ok(_) => return Vec::new(),
+        Err(e) => return vec![format!("Capacity constraints: {}", e)],
+    };
+    match crate::persistence::load_building_shared_at(repo_root) {
+        Ok(building) => capacity_summary_lines(&evaluate_constraints(&building, &constraints)),
+        Err(e) => vec![format!("Capacity constraints: building not loaded ({})", e)],
+    }
+}
+
+/// Building / per-floor completeness score and LOD.
+fn completeness_lines(repo_root: &std::path::Path) -> Vec<String> {
+    match crate::persistence::load_building_shared_at(repo_root) {
+        Ok(building) => crate::core::assess_completeness(&building).summary_lines(),
+        Err(e) => vec![format!("Completeness: building not loaded ({})", e)],
+    }
+}
+
+/// Open occupant issues (most urgent first) as notifications.
+fn issue_lines(repo_root: &std::path::Path) -> Vec<String> {
+    const SHOWN: usize = 5;
+    match crate::persistence::issues::open_issues(repo_root) {
+        Ok(issues) if issues.is_empty() => Vec::new(),
+        Ok(issues) => {
+            let mut lines = vec![format!("🔔 Open issues: {}", issues.len())];
+            lines.extend(issues.iter().take(SHOWN).map(|i| format!("  {}", i.summary())));
+            if issues.len() > SHOWN {
+                lines.push(format!("  … {} more (arx issue list)", issues.len() - SHOWN));
+            }
+            lines
+        }
+        Err(e) => vec![format!("Issues: {}", e)],
+    }
+}
+
+/// Sensors reported via `sensor.report` that still need a mapping.
+fn sensor_lines(repo_root: &std::path::Path) -> Vec<String> {
+    let Ok(building) = crate::persistence::load_building_shared_at(repo_root) else {
+        return Vec::new();
+    };
+    match crate::persistence::sensors::unmapped_sensors(repo_root, &building) {
+        Ok(unmapped) if unmapped.is_empty() => Vec::new(),
+        Ok(unmapped) => vec![format!(
+            "📡 Unmapped sensors: {} (arx sensor map)",
+            unmapped.len()
+        )],
+        Err(e) => vec![format!("Sensors: {}", e)],
+    }
+}
+
+/// Latest month of consumption per utility against its baseline.
+fn energy_lines(repo_root: &std::path::Path) -> Vec<String> {
+    use crate::core::energy::Period;
+
+    let Ok(building) = crate::persistence::load_building_shared_at(repo_root) else {
+        return Vec::new();
+    };
+    match crate::persistence::energy::energy_report(repo_root, &building, Period::Month, None) {
+        Ok(reports) => reports
+            .iter()
+            .filter_map(|report| {
+                let latest = report.rollups.last()?;
+                let delta = latest
+                    .change_vs_baseline()
+                    .map(|c| format!(" ({:+.0}% vs baseline)", c))
+                    .unwrap_or_default();
+                Some(format!(
+                    "⚡ {} {}: {:.1} {}{}",
+                    report.utility,
+                    latest.start.format("%Y-%m"),
+                    latest.total,
+                    report.unit,
+                    delta
+                ))
+            })
+            .collect(),
+        Err(e) => vec![format!("Energy: {}", e)],
+    }
+}
+
+async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    repo_root: &Path,
+    watcher: Option<&RepoWatcher>,
+) -> Result<()> {
     let tick_rate = Duration::from_millis(250);
 
     loop {
+        let changes = watcher.map(RepoWatcher::drain).unwrap_or_default();
+        if !changes.is_empty() {
+            app.push_changes(&changes);
+            app.lines = dashboard_lines(repo_root);
+        }
+
         terminal.draw(|f| ui(f, app))?;
 
         if crossterm::event::poll(tick_rate)? {
@@ -93,10 +228,14 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
 }
 
 fn ui(f: &mut Frame, app: &mut App) {
+    let mut constraints = vec![Constraint::Length(3), Constraint::Min(5)];
+    if !app.changes.is_empty() {
+        constraints.push(Constraint::Length(app.changes.len() as u16 + 2));
+    }
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
-        .constraints([Constraint::Length(3), Constraint::Min(5)].as_ref())
+        .constraints(constraints)
         .split(f.size());
 
     let title = Paragraph::new(Line::from(vec![Span::styled(
@@ -119,4 +258,74 @@ fn ui(f: &mut Frame, app: &mut App) {
             .title("Status (no hardware drivers)"),
     );
     f.render_widget(list, chunks[1]);
+
+    if !app.changes.is_empty() {
+        let items: Vec<ListItem> = app
+            .changes
+            .iter()
+            .map(|s| ListItem::new(s.as_str()))
+            .collect();
+        let list =
+            List::new(items).block(Block::default().borders(Borders::ALL).title("Live changes"));
+        f.render_widget(list, chunks[2]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::snapshot::{assert_snapshot, fixture_building, render_to_string};
+
+    #[test]
+    fn test_dashboard_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::persistence::save_building_unchecked_at(dir.path(), &fixture_building()).unwrap();
+        let mut lines = dashboard_lines(dir.path());
+        // The temp path differs per run
+        lines[0] = "Repo: <fixture>".to_string();
+        let mut app = App::new("ArxOS Agent Dashboard", lines);
+        let screen = render_to_string(80, 24, |f| ui(f, &mut app));
+        assert_snapshot("dashboard", &screen);
+    }
+
+    #[test]
+    fn test_dashboard_shows_latest_energy_month() {
+        use crate::core::energy::Utility;
+        use crate::persistence::energy::{add_meter, import_intervals};
+
+        let dir = tempfile::tempdir().unwrap();
+        let building = fixture_building();
+        crate::persistence::save_building_unchecked_at(dir.path(), &building).unwrap();
+        assert!(energy_lines(dir.path()).is_empty());
+
+        let floor = building.floors[0].id.clone();
+        add_meter(dir.path(), &building, "M-1", Utility::Electric, None, Some(&floor)).unwrap();
+        let csv = "2026-01-05T00:00:00Z,2026-01-06T00:00:00Z,100\n\
+                   2026-02-01T00:00:00Z,2026-02-02T00:00:00Z,125\n";
+        import_intervals(dir.path(), "M-1", csv).unwrap();
+        assert_eq!(
+            energy_lines(dir.path()),
+            ["⚡ electric 2026-02: 125.0 kWh (+25% vs baseline)"]
+        );
+    }
+
+    #[test]
+    fn test_dashboard_lists_live_changes() {
+        use crate::agent::watcher::{ChangeArea, ChangeKind};
+
+        let mut app = App::new("ArxOS Agent Dashboard", vec!["Repo: <fixture>".into()]);
+        let change = |n: usize| FileChange {
+            path: format!(".arx/sensor-data/s{}.jsonl", n),
+            area: ChangeArea::SensorData,
+            kind: ChangeKind::Modified,
+            at: chrono::Utc::now(),
+        };
+        app.push_changes(&(0..10).map(change).collect::<Vec<_>>());
+        assert_eq!(app.changes.len(), CHANGES_SHOWN);
+        assert!(app.changes[0].contains("s9.jsonl (sensor data)"));
+
+        let screen = render_to_string(80, 24, |f| ui(f, &mut app));
+        assert!(screen.contains("Live changes"));
+        assert!(screen.contains("modified"));
+    }
 }
@@ -0,0 +1,466 @@
+//! Interactive floor-plan renderer with entity picking for ArxOS TUI
+//!
+//! Draws one floor's room outlines and equipment markers as characters.
+//! Moving the cursor (arrow keys, or the mouse) resolves the cell under it
+//! through the floor's [`PlanIndex`] and shows that room or equipment in an
+//! info panel: properties, status, and the recent commits that changed it.
+
+use crate::core::review::{equipment_review_status, room_review_status};
+use crate::core::spatial::plan::{floor_plan, FloorPlan, PlanIndex, PlanPick};
+use crate::core::Building;
+use crate::git::manager::{BuildingGitManager, GitConfigManager};
+use crate::tui::mouse::{is_point_in_rect, MouseAction};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Commits listed in the info panel
+const RECENT_COMMITS: usize = 5;
+
+/// Result of handling input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectorAction {
+    Continue,
+    Exit,
+}
+
+/// Mapping from canvas cells to plan metres (terminal cells are ~2:1 tall)
+#[derive(Debug, Clone, Copy)]
+struct Viewport {
+    min_x: f64,
+    max_y: f64,
+    cell_width: f64,
+}
+
+impl Viewport {
+    fn world_at(&self, col: u16, row: u16) -> (f64, f64) {
+        (
+            self.min_x + (f64::from(col) + 0.5) * self.cell_width,
+            self.max_y - (f64::from(row) + 0.5) * self.cell_width * 2.0,
+        )
+    }
+
+    /// Equipment within this distance of a cell centre is drawn / picked
+    /// there; it covers the nearest centre, at most √1.25 cell widths away
+    fn pick_radius(&self) -> f64 {
+        self.cell_width * 1.2
+    }
+}
+
+/// Floor-plan inspector state
+pub struct PlanInspector {
+    building: Building,
+    /// Floor levels, lowest first
+    levels: Vec<i32>,
+    floor: usize,
+    plan: Option<FloorPlan>,
+    index: Option<PlanIndex>,
+    /// Plan extent as (min_x, min_y, max_x, max_y)
+    bounds: (f64, f64, f64, f64),
+    /// Plan area inside the border, from the last layout
+    canvas: Rect,
+    /// Cursor cell relative to `canvas`
+    cursor: (u16, u16),
+    focus: Option<PlanPick>,
+    /// Repository for commit history (none: history not shown)
+    repo: Option<PathBuf>,
+    history: HashMap<String, Vec<String>>,
+}
+
+impl PlanInspector {
+    /// Inspector opened on `level` (default: lowest floor); `repo` is the Git
+    /// working tree commit history is read from.
+    pub fn new(building: Building, level: Option<i32>, repo: Option<PathBuf>) -> Self {
+        let mut levels: Vec<i32> = building.floors.iter().map(|f| f.level).collect();
+        levels.sort_unstable();
+        levels.dedup();
+        let floor = level
+            .and_then(|l| levels.iter().position(|&x| x == l))
+            .unwrap_or(0);
+        let mut inspector = Self {
+            building,
+            levels,
+            floor,
+            plan: None,
+            index: None,
+            bounds: (0.0, 0.0, 10.0, 10.0),
+            canvas: Rect::default(),
+            cursor: (0, 0),
+            focus: None,
+            repo: repo.filter(|r| r.join(".git").exists()),
+            history: HashMap::new(),
+        };
+        inspector.load_floor();
+        inspector
+    }
+
+    fn load_floor(&mut self) {
+        self.plan = self
+            .levels
+            .get(self.floor)
+            .and_then(|&level| floor_plan(&self.building, level));
+        self.index = self.plan.as_ref().map(PlanIndex::new);
+        let points = self.plan.iter().flat_map(|plan| {
+            plan.rooms
+                .iter()
+                .flat_map(|r| r.outline.iter().map(|p| (p[0], p[1])))
+                .chain(plan.equipment.iter().map(|e| (e.position.x, e.position.y)))
+        });
+        let mut bounds = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for (x, y) in points {
+            bounds = (
+                bounds.0.min(x),
+                bounds.1.min(y),
+                bounds.2.max(x),
+                bounds.3.max(y),
+            );
+        }
+        self.bounds = if bounds.0 > bounds.2 {
+            (0.0, 0.0, 10.0, 10.0)
+        } else {
+            // A metre of margin so edge equipment is not clipped
+            (
+                bounds.0 - 1.0,
+                bounds.1 - 1.0,
+                bounds.2 + 1.0,
+                bounds.3 + 1.0,
+            )
+        };
+        self.pick();
+    }
+
+    fn viewport(&self) -> Viewport {
+        let (min_x, min_y, max_x, max_y) = self.bounds;
+        let cols = f64::from(self.canvas.width.max(1));
+        let rows = f64::from(self.canvas.height.max(1));
+        Viewport {
+            min_x,
+            max_y,
+            cell_width: ((max_x - min_x) / cols).max((max_y - min_y) / (rows * 2.0)),
+        }
+    }
+
+    /// Split `area` into plan, info, and help panes; remembers the plan canvas.
+    fn layout(&mut self, area: Rect) -> (Rect, Rect, Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(3)])
+            .split(area);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[0]);
+        let canvas = Block::default().borders(Borders::ALL).inner(columns[0]);
+        if canvas != self.canvas {
+            self.canvas = canvas;
+            self.cursor = (
+                self.cursor.0.min(canvas.width.saturating_sub(1)),
+                self.cursor.1.min(canvas.height.saturating_sub(1)),
+            );
+            self.pick();
+        }
+        (columns[0], columns[1], rows[1])
+    }
+
+    /// Room or equipment under the cursor
+    pub fn focus(&self) -> Option<&PlanPick> {
+        self.focus.as_ref()
+    }
+
+    /// Resolve the cursor cell and load the focused entity's history.
+    fn pick(&mut self) {
+        let viewport = self.viewport();
+        let (x, y) = viewport.world_at(self.cursor.0, self.cursor.1);
+        self.focus = self
+            .index
+            .as_ref()
+            .and_then(|index| index.pick(x, y, viewport.pick_radius()));
+        let Some(id) = self.focus.as_ref().map(|pick| match pick {
+            PlanPick::Equipment(id) | PlanPick::Room(id) => id.clone(),
+        }) else {
+            return;
+        };
+        if self.history.contains_key(&id) {
+            return;
+        }
+        let commits = self.repo.as_ref().and_then(|repo| {
+            BuildingGitManager::new(
+                repo.to_str()?,
+                "building",
+                GitConfigManager::load_from_arx_config_or_env(),
+            )
+            .ok()?
+            .get_entity_history(&id, RECENT_COMMITS)
+            .ok()
+        });
+        let lines = commits
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| {
+                let when = chrono::DateTime::from_timestamp(c.time, 0)
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                format!(
+                    "{} {} {} ({})",
+                    &c.id[..c.id.len().min(8)],
+                    when,
+                    c.message.lines().next().unwrap_or(""),
+                    c.author
+                )
+            })
+            .collect();
+        self.history.insert(id, lines);
+    }
+
+    /// Move the cursor to a canvas cell
+    pub fn move_to(&mut self, col: u16, row: u16) {
+        self.cursor = (
+            col.min(self.canvas.width.saturating_sub(1)),
+            row.min(self.canvas.height.saturating_sub(1)),
+        );
+        self.pick();
+    }
+
+    /// Handle keyboard input
+    pub fn handle_key(&mut self, key: KeyEvent) -> InspectorAction {
+        let (col, row) = self.cursor;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return InspectorAction::Exit,
+            KeyCode::Left | KeyCode::Char('h') => self.move_to(col.saturating_sub(1), row),
+            KeyCode::Right | KeyCode::Char('l') => self.move_to(col.saturating_add(1), row),
+            KeyCode::Up | KeyCode::Char('k') => self.move_to(col, row.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.move_to(col, row.saturating_add(1)),
+            KeyCode::Char('[') | KeyCode::PageDown if self.floor > 0 => {
+                self.floor -= 1;
+                self.load_floor();
+            }
+            KeyCode::Char(']') | KeyCode::PageUp if self.floor + 1 < self.levels.len() => {
+                self.floor += 1;
+                self.load_floor();
+            }
+            _ => {}
+        }
+        InspectorAction::Continue
+    }
+
+    /// Hovering or clicking on the plan moves the cursor there
+    pub fn handle_mouse(&mut self, action: MouseAction) {
+        if let MouseAction::Move { x, y } | MouseAction::LeftClick { x, y } = action {
+            if is_point_in_rect(x, y, self.canvas) {
+                self.move_to(x - self.canvas.x, y - self.canvas.y);
+            }
+        }
+    }
+
+    /// Info panel text for the focused entity
+    pub fn info_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let properties = match &self.focus {
+            None => {
+                lines.push("Move the cursor over a room or equipment".to_string());
+                return lines;
+            }
+            Some(PlanPick::Room(id)) => {
+                let Some(room) = self.building.find_room(id) else {
+                    return lines;
+                };
+                lines.push(format!("🚪 {}", room.name));
+                lines.push(format!("id: {}", room.id));
+                lines.push(format!("type: {}", room.room_type));
+                if let Some(review) = room_review_status(room) {
+                    lines.push(format!("review: {}", review.as_str()));
+                }
+                lines.push(format!("equipment: {}", room.equipment.len()));
+                &room.properties
+            }
+            Some(PlanPick::Equipment(id)) => {
+                let Some(eq) = self.building.find_equipment(id) else {
+                    return lines;
+                };
+                lines.push(format!("⚙️  {}", eq.name));
+                lines.push(format!("id: {}", eq.id));
+                lines.push(format!("type: {}", eq.equipment_type));
+                lines.push(format!("status: {}", eq.status));
+                if let Some(health) = &eq.health_status {
+                    lines.push(format!("health: {:?}", health));
+                }
+                if let Some(review) = equipment_review_status(eq) {
+                    lines.push(format!("review: {}", review.as_str()));
+                }
+                lines.push(format!(
+                    "position: ({:.2}, {:.2}, {:.2})",
+                    eq.position.x, eq.position.y, eq.position.z
+                ));
+                if let Some(room) = eq
+                    .room_id
+                    .as_deref()
+                    .and_then(|r| self.building.find_room(r))
+                {
+                    lines.push(format!("room: {}", room.name));
+                }
+                &eq.properties
+            }
+        };
+
+        let mut keys: Vec<&String> = properties.keys().collect();
+        keys.sort();
+        if !keys.is_empty() {
+            lines.push(String::new());
+            lines.push("Properties".to_string());
+            for key in keys {
+                lines.push(format!("  {} = {}", key, properties[key]));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push("Recent commits".to_string());
+        let history = self.focus.as_ref().and_then(|pick| match pick {
+            PlanPick::Equipment(id) | PlanPick::Room(id) => self.history.get(id),
+        });
+        match history {
+            _ if self.repo.is_none() => lines.push("  (not a Git repository)".to_string()),
+            Some(commits) if !commits.is_empty() => {
+                lines.extend(commits.iter().map(|c| format!("  {}", c)))
+            }
+            _ => lines.push("  (none)".to_string()),
+        }
+        lines
+    }
+
+    fn plan_lines(&self) -> Vec<Line<'static>> {
+        let Some(index) = &self.index else {
+            return vec![Line::from("(no rooms or equipment with plan geometry)")];
+        };
+        let viewport = self.viewport();
+        let radius = viewport.pick_radius();
+        let room_at = |col: u16, row: u16| {
+            let (x, y) = viewport.world_at(col, row);
+            index.room_at(x, y)
+        };
+
+        (0..self.canvas.height)
+            .map(|row| {
+                let spans: Vec<Span> = (0..self.canvas.width)
+                    .map(|col| {
+                        let (x, y) = viewport.world_at(col, row);
+                        let pick = index.pick(x, y, radius);
+                        let glyph = match &pick {
+                            Some(PlanPick::Equipment(_)) => "◆",
+                            Some(PlanPick::Room(id)) => {
+                                let right = room_at(col + 1, row);
+                                let below = room_at(col, row + 1);
+                                if right.as_ref() != Some(id) || below.as_ref() != Some(id) {
+                                    "░"
+                                } else {
+                                    "·"
+                                }
+                            }
+                            None => " ",
+                        };
+                        let mut style = match &pick {
+                            Some(p) if Some(p) == self.focus.as_ref() => Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                            Some(PlanPick::Equipment(_)) => Style::default().fg(Color::Cyan),
+                            _ => Style::default().fg(Color::DarkGray),
+                        };
+                        if (col, row) == self.cursor {
+                            style = style.add_modifier(Modifier::REVERSED);
+                        }
+                        Span::styled(glyph, style)
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Render the view
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let (plan_area, info_area, help_area) = self.layout(area);
+
+        let title = match (self.levels.get(self.floor), &self.plan) {
+            (Some(level), Some(plan)) => format!(
+                "🗺️  {} (level {}) — floor {}/{}",
+                plan.name,
+                level,
+                self.floor + 1,
+                self.levels.len()
+            ),
+            _ => "🗺️  No floors".to_string(),
+        };
+        let plan = Paragraph::new(self.plan_lines())
+            .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(plan, plan_area);
+
+        let info: Vec<Line> = self.info_lines().into_iter().map(Line::from).collect();
+        let info = Paragraph::new(info)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("🔎 Inspect"));
+        frame.render_widget(info, info_area);
+
+        let help = Paragraph::new(Line::from(vec![
+            Span::styled("←↑↓→/hjkl", Style::default().fg(Color::Yellow)),
+            Span::raw(" move  "),
+            Span::styled("mouse", Style::default().fg(Color::Yellow)),
+            Span::raw(" hover/click to pick  "),
+            Span::styled("[ ]", Style::default().fg(Color::Yellow)),
+            Span::raw(" floor  "),
+            Span::styled("q", Style::default().fg(Color::Yellow)),
+            Span::raw(" quit"),
+        ]))
+        .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(help, help_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::spatial::extrusion::FLOOR_POLYGON_PROPERTY;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn test_cursor_and_mouse_pick_room_and_equipment() {
+        let mut room = Room::new("Mech".into(), RoomType::Mechanical);
+        room.properties
+            .insert(FLOOR_POLYGON_PROPERTY.into(), "0,0;20,0;20,20;0,20".into());
+        let mut ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        ahu.position.x = 15.0;
+        ahu.position.y = 15.0;
+        ahu.properties.insert("cfm".into(), "4000".into());
+        let (room_id, ahu_id) = (room.id.clone(), ahu.id.clone());
+        room.add_equipment(ahu);
+        let mut wing = Wing::new("W".into());
+        wing.add_room(room);
+        let mut floor = Floor::new("Ground".into(), 0);
+        floor.add_wing(wing);
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        building.add_floor(floor);
+
+        let mut inspector = PlanInspector::new(building, None, None);
+        // 44 x 22 canvas over a 22 m square: half-metre cells, one-metre rows
+        inspector.canvas = Rect::new(1, 1, 44, 22);
+
+        inspector.move_to(10, 10);
+        assert_eq!(inspector.focus(), Some(&PlanPick::Room(room_id)));
+        assert!(inspector
+            .info_lines()
+            .iter()
+            .any(|l| l == "type: Mechanical"));
+
+        // (15, 15) m is column 32, row 6; the mouse is offset by the border
+        inspector.handle_mouse(MouseAction::Move { x: 33, y: 7 });
+        assert_eq!(inspector.focus(), Some(&PlanPick::Equipment(ahu_id)));
+        let info = inspector.info_lines();
+        assert!(info.contains(&"  cfm = 4000".to_string()));
+        assert!(info.contains(&"  (not a Git repository)".to_string()));
+    }
+}
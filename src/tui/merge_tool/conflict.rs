@@ -3,6 +3,7 @@
 //! Parses Git conflict markers and git merge-tree output to extract
 //! base, ours, and theirs versions for three-way merge.
 
+use crate::git::merge_driver::ConflictRecord;
 use std::fs;
 use std::path::Path;
 
@@ -21,6 +22,34 @@ pub struct Conflict {
     pub context_after: Vec<String>,
 }
 
+impl Conflict {
+    /// An entity conflict recorded by the building merge driver, shown as the
+    /// entity's YAML on each side.
+    pub fn from_record(record: &ConflictRecord) -> Self {
+        let section = |yaml: &Option<String>, label: &str| ConflictSection {
+            lines: match yaml {
+                Some(yaml) => yaml.lines().map(String::from).collect(),
+                None => vec![format!("# {} removed", record.kind)],
+            },
+            label: label.to_string(),
+        };
+        Self {
+            start_line: 0,
+            end_line: 0,
+            sections: ConflictSections {
+                ours: section(&record.ours, "ours"),
+                base: Some(section(&record.base, "base")),
+                theirs: section(&record.theirs, "theirs"),
+            },
+            context_before: vec![format!(
+                "# {} '{}': {}",
+                record.kind, record.name, record.reason
+            )],
+            context_after: Vec::new(),
+        }
+    }
+}
+
 /// The three versions in a conflict
 #[derive(Debug, Clone)]
 pub struct ConflictSections {
@@ -240,6 +269,24 @@ more normal lines
         assert_eq!(conflicts[0].sections.theirs.lines, vec!["their changes"]);
     }
 
+    #[test]
+    fn test_conflict_from_record() {
+        let record = ConflictRecord {
+            path: "building.yaml".into(),
+            kind: "equipment".into(),
+            id: "eq-1".into(),
+            name: "AHU-1".into(),
+            reason: "changed on both branches".into(),
+            base: Some("status: Active\n".into()),
+            ours: Some("status: Maintenance\n".into()),
+            theirs: None,
+        };
+        let conflict = Conflict::from_record(&record);
+        assert_eq!(conflict.sections.ours.lines, vec!["status: Maintenance"]);
+        assert_eq!(conflict.sections.theirs.lines, vec!["# equipment removed"]);
+        assert!(conflict.context_before[0].contains("AHU-1"));
+    }
+
     #[test]
     fn test_parse_conflict_with_base() {
         let content = r#"
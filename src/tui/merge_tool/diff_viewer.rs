@@ -476,3 +476,38 @@ enum KeyAction {
     SaveAndExit,
     CancelAndExit,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::merge_tool::conflict::ConflictParser;
+    use crate::tui::snapshot::{assert_snapshot, render_to_string};
+
+    const CONFLICTED_BUILDING: &str = r#"equipment:
+- id: eq-ahu-1
+  name: AHU-1
+<<<<<<< HEAD
+  status: Active
+  health_status: Warning
+=======
+  status: Maintenance
+>>>>>>> field-tablet
+- id: eq-panel-a
+<<<<<<< HEAD
+  name: Panel A
+=======
+  name: Panel A (East)
+>>>>>>> field-tablet
+"#;
+
+    #[test]
+    fn test_merge_screen_snapshot() {
+        let conflicts = ConflictParser::new()
+            .parse_content(CONFLICTED_BUILDING)
+            .unwrap();
+        let mut viewer = MergeViewer::new(&conflicts, Path::new("building.yaml")).unwrap();
+        viewer.choose_theirs();
+        let screen = render_to_string(100, 20, |f| viewer.render(f));
+        assert_snapshot("merge_screen", &screen);
+    }
+}
@@ -11,28 +11,51 @@ pub use conflict::{Conflict, ConflictParser};
 pub use diff_viewer::MergeViewer;
 pub use resolver::ResolutionEngine;
 
-use std::path::PathBuf;
+use crate::git::merge::MergePreference;
+use crate::git::merge_driver::{load_conflict_records, resolve_recorded_conflicts, ConflictRecord};
+use resolver::ResolutionChoice;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Main entry point for merge conflict resolution
 pub struct MergeTool {
     file_path: PathBuf,
     conflicts: Vec<Conflict>,
     resolver: ResolutionEngine,
+    /// Entity conflicts the building merge driver recorded for this file
+    records: Vec<ConflictRecord>,
 }
 
 impl MergeTool {
     /// Create a new merge tool for a file with conflicts
     pub fn new(file_path: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
-        let parser = ConflictParser::new();
-        let conflicts = parser.parse_file(&file_path)?;
+        let records = Self::recorded_conflicts(&file_path)?;
+        let conflicts = if records.is_empty() {
+            ConflictParser::new().parse_file(&file_path)?
+        } else {
+            records.iter().map(Conflict::from_record).collect()
+        };
 
         Ok(Self {
             file_path,
             conflicts: conflicts.clone(),
             resolver: ResolutionEngine::new(conflicts),
+            records,
         })
     }
 
+    /// Entity conflicts recorded by the merge driver for `file_path`
+    /// (relative to the repository root, the working directory).
+    pub fn recorded_conflicts(
+        file_path: &Path,
+    ) -> Result<Vec<ConflictRecord>, Box<dyn std::error::Error>> {
+        let path = file_path.strip_prefix(".").unwrap_or(file_path);
+        Ok(load_conflict_records(Path::new("."))?
+            .into_iter()
+            .filter(|r| Path::new(&r.path) == path)
+            .collect())
+    }
+
     /// Launch interactive merge viewer
     pub fn run_interactive(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let mut viewer = MergeViewer::new(&self.conflicts, &self.file_path)?;
@@ -40,6 +63,10 @@ impl MergeTool {
         // Run the viewer and get resolutions
         let resolutions = viewer.run()?;
 
+        if !self.records.is_empty() {
+            return self.resolve_records(&resolutions);
+        }
+
         // Apply resolutions
         self.resolver.apply_resolutions(&resolutions);
 
@@ -49,6 +76,39 @@ impl MergeTool {
         Ok(())
     }
 
+    /// Re-merge the file keeping the chosen side of each entity conflict.
+    /// Only "ours" and "theirs" apply to whole entities; other choices leave
+    /// the conflict open.
+    fn resolve_records(
+        &self,
+        resolutions: &[resolver::Resolution],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut choices = HashMap::new();
+        for resolution in resolutions {
+            let side = match resolution.choice {
+                ResolutionChoice::Ours => MergePreference::Ours,
+                ResolutionChoice::Theirs => MergePreference::Theirs,
+                _ => continue,
+            };
+            if let Some(record) = self.records.get(resolution.conflict_index) {
+                choices.insert(record.key(), side);
+            }
+        }
+
+        let path = self.records[0].path.clone();
+        let open = resolve_recorded_conflicts(Path::new("."), &path, &choices)?;
+        if open.is_empty() {
+            println!("All entity conflicts settled; {} is staged", path);
+        } else {
+            println!(
+                "{} entity conflict(s) in {} still need \"ours\" or \"theirs\"",
+                open.len(),
+                path
+            );
+        }
+        Ok(())
+    }
+
     /// Preview merged result and save if confirmed
     fn preview_and_save(&self) -> Result<(), Box<dyn std::error::Error>> {
         let merged_content = self.resolver.build_merged_content();
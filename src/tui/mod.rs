@@ -3,6 +3,8 @@
 //! Provides reusable Ratatui components and patterns for interactive terminal experiences.
 //! Designed for non-technical building management professionals.
 
+#[cfg(feature = "tui")]
+pub mod booking_calendar;
 pub mod command_palette;
 #[cfg(feature = "agent")]
 pub mod dashboard;
@@ -11,11 +13,21 @@ pub mod error_integration;
 pub mod error_modal;
 pub mod export;
 pub mod help;
+#[cfg(feature = "tui")]
+pub mod inspector;
 pub mod layouts;
 pub mod merge_tool;
 pub mod mouse;
 #[cfg(feature = "tui")]
+pub mod portfolio;
+#[cfg(feature = "tui")]
 pub mod search;
+#[cfg(feature = "tui")]
+pub mod sensor_mapping;
+#[cfg(feature = "tui")]
+pub mod topology;
+#[cfg(test)]
+pub(crate) mod snapshot;
 pub mod spreadsheet;
 pub mod terminal;
 pub mod theme;
@@ -69,3 +81,134 @@ pub fn render_building(building_name: &str) -> Result<(), Box<dyn std::error::Er
 
     Ok(())
 }
+
+/// Open the interactive floor-plan inspector on the cwd building.
+#[cfg(feature = "tui")]
+pub fn inspect_building(
+    building_name: &str,
+    level: Option<i32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crossterm::event::{Event, KeyEventKind};
+    use inspector::{InspectorAction, PlanInspector};
+    use mouse::{parse_mouse_event, MouseConfig};
+    use std::time::Duration;
+
+    let building = crate::persistence::load_building_data_from_dir()?;
+    if building.name != building_name && building.id != building_name {
+        println!(
+            "⚠️  Warning: Loaded building '{}' does not match requested '{}'",
+            building.name, building_name
+        );
+    }
+
+    let mut view = PlanInspector::new(building, level, Some(std::path::PathBuf::from(".")));
+    let mouse_config = MouseConfig::new();
+    let mut terminal_manager = TerminalManager::new()?;
+    loop {
+        terminal_manager.terminal().draw(|frame| {
+            view.render(frame, frame.size());
+        })?;
+        let Some(event) = terminal_manager.poll_event(Duration::from_millis(100))? else {
+            continue;
+        };
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if view.handle_key(key) == InspectorAction::Exit {
+                    return Ok(());
+                }
+            }
+            event => {
+                if let Some(action) = parse_mouse_event(&event, &mouse_config) {
+                    view.handle_mouse(action);
+                }
+            }
+        }
+    }
+}
+
+/// Edit the cwd building's equipment or rooms in the spreadsheet, writing
+/// reviewed changes back to building.yaml (and to Git when `commit`).
+#[cfg(feature = "tui")]
+pub fn edit_spreadsheet(target: &str, commit: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use crossterm::event::{Event, KeyEventKind};
+    use spreadsheet::app::{SpreadsheetAction, SpreadsheetApp};
+    use spreadsheet::data_source::equipment_source::EquipmentDataSource;
+    use spreadsheet::data_source::room_source::RoomDataSource;
+    use spreadsheet::data_source::SpreadsheetDataSource;
+    use spreadsheet::workflow::FileLock;
+    use std::time::Duration;
+
+    let building = crate::persistence::load_building_data_from_dir()?;
+    let name = building.name.clone();
+    let source: Box<dyn SpreadsheetDataSource> = match target {
+        "equipment" => Box::new(EquipmentDataSource::new(building, name)),
+        "rooms" => Box::new(RoomDataSource::new(building, name)),
+        other => {
+            return Err(format!("Unknown spreadsheet '{}'. Use: equipment, rooms", other).into())
+        }
+    };
+    let _lock = FileLock::acquire(std::path::Path::new(crate::persistence::BUILDING_YAML))?;
+
+    let mut app = SpreadsheetApp::new(source, commit);
+    let mut terminal_manager = TerminalManager::new()?;
+    loop {
+        terminal_manager.terminal().draw(|frame| {
+            app.render(frame, frame.size());
+        })?;
+        let Some(Event::Key(key)) = terminal_manager.poll_event(Duration::from_millis(100))? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Press && app.handle_key(key) == SpreadsheetAction::Exit {
+            return Ok(());
+        }
+    }
+}
+
+/// Portfolio dashboard over the workspace registry. Drilling into a building
+/// runs the inspector or spreadsheet from that building's project root, then
+/// returns here with the rows reloaded.
+#[cfg(feature = "tui")]
+pub fn portfolio_dashboard() -> Result<(), Box<dyn std::error::Error>> {
+    use crossterm::event::{Event, KeyEventKind};
+    use portfolio::{PortfolioAction, PortfolioView};
+    use std::time::Duration;
+
+    let registry = crate::persistence::workspace::WorkspaceRegistry::load()?;
+    let mut view = PortfolioView::from_registry(&registry);
+    let cwd = std::env::current_dir()?;
+    loop {
+        let action = {
+            let mut terminal_manager = TerminalManager::new()?;
+            loop {
+                terminal_manager.terminal().draw(|frame| {
+                    view.render(frame, frame.size());
+                })?;
+                let Some(Event::Key(key)) =
+                    terminal_manager.poll_event(Duration::from_millis(100))?
+                else {
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match view.handle_key(key) {
+                    PortfolioAction::Continue => {}
+                    action => break action,
+                }
+            }
+        };
+        let result = match &action {
+            PortfolioAction::Exit => return Ok(()),
+            PortfolioAction::Inspect(handle) => std::env::set_current_dir(&handle.root)
+                .map_err(Into::into)
+                .and_then(|_| inspect_building(&handle.name, None)),
+            PortfolioAction::Spreadsheet(handle) => std::env::set_current_dir(&handle.root)
+                .map_err(Into::into)
+                .and_then(|_| edit_spreadsheet("equipment", false)),
+            PortfolioAction::Continue => Ok(()),
+        };
+        std::env::set_current_dir(&cwd)?;
+        result?;
+        view.refresh();
+    }
+}
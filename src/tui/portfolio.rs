@@ -0,0 +1,431 @@
+//! Portfolio view for ArxOS TUI
+//!
+//! One row per building in the workspace registry (`arx workspace add`):
+//! equipment count, open alerts (equipment in warning or critical), the last
+//! commit, and validation status. `s` cycles the sort column; Enter opens
+//! the selected building's floor-plan inspector and `e` its equipment
+//! spreadsheet.
+
+use crate::export::embed::{equipment_state, RoomState};
+use crate::git::CommitInfo;
+use crate::persistence::workspace::{BuildingHandle, WorkspaceRegistry};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+/// Validation outcome of one building
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationStatus {
+    Valid,
+    Warnings(usize),
+    Errors(usize),
+    /// building.yaml could not be loaded
+    Unavailable(String),
+}
+
+impl ValidationStatus {
+    /// Higher is worse
+    fn severity(&self) -> (u8, usize) {
+        match self {
+            ValidationStatus::Valid => (0, 0),
+            ValidationStatus::Warnings(n) => (1, *n),
+            ValidationStatus::Errors(n) => (2, *n),
+            ValidationStatus::Unavailable(_) => (3, 0),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            ValidationStatus::Valid => "✅ valid".to_string(),
+            ValidationStatus::Warnings(n) => format!("⚠️  {} warning(s)", n),
+            ValidationStatus::Errors(n) => format!("❌ {} error(s)", n),
+            ValidationStatus::Unavailable(_) => "❌ unreadable".to_string(),
+        }
+    }
+}
+
+/// Summary of one registered building
+#[derive(Debug)]
+pub struct PortfolioRow {
+    pub handle: BuildingHandle,
+    pub equipment: usize,
+    /// Equipment in warning or critical state
+    pub alerts: usize,
+    pub last_commit: Option<CommitInfo>,
+    pub validation: ValidationStatus,
+}
+
+impl PortfolioRow {
+    pub fn load(handle: &BuildingHandle) -> Self {
+        let last_commit = git2::Repository::open(&handle.root)
+            .ok()
+            .and_then(|repo| crate::git::diff::list_commits(&repo, 1).ok())
+            .and_then(|commits| commits.into_iter().next());
+        let mut row = Self {
+            handle: handle.clone(),
+            equipment: 0,
+            alerts: 0,
+            last_commit,
+            validation: ValidationStatus::Valid,
+        };
+        match handle.load_shared() {
+            Ok(building) => {
+                let equipment = building.get_all_equipment();
+                row.equipment = equipment.len();
+                row.alerts = equipment
+                    .iter()
+                    .filter(|eq| equipment_state(eq) <= RoomState::Warning)
+                    .count();
+                let report = crate::validation::validate_building(&building);
+                let (errors, warnings) = (report.errors().count(), report.warnings().count());
+                row.validation = if errors > 0 {
+                    ValidationStatus::Errors(errors)
+                } else if warnings > 0 {
+                    ValidationStatus::Warnings(warnings)
+                } else {
+                    ValidationStatus::Valid
+                };
+            }
+            Err(e) => row.validation = ValidationStatus::Unavailable(e.to_string()),
+        }
+        row
+    }
+}
+
+/// Column the rows are sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortfolioSort {
+    Name,
+    Equipment,
+    Alerts,
+    LastCommit,
+    Validation,
+}
+
+impl PortfolioSort {
+    fn next(self) -> Self {
+        match self {
+            PortfolioSort::Name => PortfolioSort::Equipment,
+            PortfolioSort::Equipment => PortfolioSort::Alerts,
+            PortfolioSort::Alerts => PortfolioSort::LastCommit,
+            PortfolioSort::LastCommit => PortfolioSort::Validation,
+            PortfolioSort::Validation => PortfolioSort::Name,
+        }
+    }
+
+    fn column(self) -> usize {
+        self as usize
+    }
+}
+
+/// Actions returned by the portfolio view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortfolioAction {
+    Continue,
+    Exit,
+    /// Open the floor-plan inspector of this building
+    Inspect(BuildingHandle),
+    /// Open the equipment spreadsheet of this building
+    Spreadsheet(BuildingHandle),
+}
+
+/// Portfolio view state
+pub struct PortfolioView {
+    rows: Vec<PortfolioRow>,
+    sort: PortfolioSort,
+    state: TableState,
+}
+
+impl PortfolioView {
+    pub fn new(rows: Vec<PortfolioRow>) -> Self {
+        let mut view = Self {
+            rows,
+            sort: PortfolioSort::Name,
+            state: TableState::default(),
+        };
+        view.sort_rows();
+        if !view.rows.is_empty() {
+            view.state.select(Some(0));
+        }
+        view
+    }
+
+    /// Rows for every building in `registry`
+    pub fn from_registry(registry: &WorkspaceRegistry) -> Self {
+        Self::new(registry.buildings.iter().map(PortfolioRow::load).collect())
+    }
+
+    pub fn rows(&self) -> &[PortfolioRow] {
+        &self.rows
+    }
+
+    pub fn sort(&self) -> PortfolioSort {
+        self.sort
+    }
+
+    /// Reload every row, keeping the selected building selected
+    pub fn refresh(&mut self) {
+        let selected = self.selected().map(|r| r.handle.name.clone());
+        self.rows = self
+            .rows
+            .iter()
+            .map(|r| PortfolioRow::load(&r.handle))
+            .collect();
+        self.sort_rows();
+        self.select_named(selected.as_deref());
+    }
+
+    fn selected(&self) -> Option<&PortfolioRow> {
+        self.state.selected().and_then(|i| self.rows.get(i))
+    }
+
+    fn select_named(&mut self, name: Option<&str>) {
+        let index = name
+            .and_then(|n| self.rows.iter().position(|r| r.handle.name == n))
+            .or((!self.rows.is_empty()).then_some(0));
+        self.state.select(index);
+    }
+
+    /// Name ascending; counts, recency, and validation worst first
+    fn sort_rows(&mut self) {
+        let name = |r: &PortfolioRow| r.handle.name.to_lowercase();
+        match self.sort {
+            PortfolioSort::Name => self.rows.sort_by_key(name),
+            PortfolioSort::Equipment => self
+                .rows
+                .sort_by(|a, b| b.equipment.cmp(&a.equipment).then(name(a).cmp(&name(b)))),
+            PortfolioSort::Alerts => self
+                .rows
+                .sort_by(|a, b| b.alerts.cmp(&a.alerts).then(name(a).cmp(&name(b)))),
+            PortfolioSort::LastCommit => self.rows.sort_by(|a, b| {
+                let time = |r: &PortfolioRow| r.last_commit.as_ref().map(|c| c.time);
+                time(b).cmp(&time(a)).then(name(a).cmp(&name(b)))
+            }),
+            PortfolioSort::Validation => self.rows.sort_by(|a, b| {
+                b.validation
+                    .severity()
+                    .cmp(&a.validation.severity())
+                    .then(name(a).cmp(&name(b)))
+            }),
+        }
+    }
+
+    /// Handle keyboard input
+    pub fn handle_key(&mut self, key: KeyEvent) -> PortfolioAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return PortfolioAction::Exit,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Char('s') => {
+                let selected = self.selected().map(|r| r.handle.name.clone());
+                self.sort = self.sort.next();
+                self.sort_rows();
+                self.select_named(selected.as_deref());
+            }
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Enter => {
+                if let Some(row) = self.selected() {
+                    return PortfolioAction::Inspect(row.handle.clone());
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Some(row) = self.selected() {
+                    return PortfolioAction::Spreadsheet(row.handle.clone());
+                }
+            }
+            _ => {}
+        }
+        PortfolioAction::Continue
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.rows.len() as isize - 1);
+        self.state.select(Some(next as usize));
+    }
+
+    /// Render the view
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ])
+            .split(area);
+
+        let titles = [
+            "Building",
+            "Equipment",
+            "Alerts",
+            "Last commit",
+            "Validation",
+        ];
+        let header = Row::new(titles.iter().enumerate().map(|(i, title)| {
+            let style = Style::default().add_modifier(Modifier::BOLD);
+            if i == self.sort.column() {
+                Cell::from(format!("{} ▼", title)).style(style.fg(Color::Cyan))
+            } else {
+                Cell::from(*title).style(style)
+            }
+        }));
+        let body: Vec<Row> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let last_commit = row
+                    .last_commit
+                    .as_ref()
+                    .map(|c| {
+                        let when = chrono::DateTime::from_timestamp(c.time, 0)
+                            .map(|t| t.format("%Y-%m-%d").to_string())
+                            .unwrap_or_default();
+                        format!("{} {}", when, c.message.lines().next().unwrap_or(""))
+                    })
+                    .unwrap_or_else(|| "—".to_string());
+                let alert_style = if row.alerts > 0 {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                let validation_color = match row.validation {
+                    ValidationStatus::Valid => Color::Green,
+                    ValidationStatus::Warnings(_) => Color::Yellow,
+                    _ => Color::Red,
+                };
+                Row::new(vec![
+                    Cell::from(row.handle.name.clone()),
+                    Cell::from(row.equipment.to_string()),
+                    Cell::from(row.alerts.to_string()).style(alert_style),
+                    Cell::from(last_commit),
+                    Cell::from(row.validation.label()).style(Style::default().fg(validation_color)),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Percentage(22),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Percentage(40),
+            Constraint::Length(18),
+        ];
+        let table = Table::new(body)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("🏙️  Portfolio ({} buildings)", self.rows.len())),
+            )
+            .widths(&widths)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▸ ");
+        frame.render_stateful_widget(table, rows[0], &mut self.state);
+
+        let detail = match self.selected() {
+            Some(row) => match &row.validation {
+                ValidationStatus::Unavailable(error) => error.clone(),
+                _ => row.handle.root.display().to_string(),
+            },
+            None => "No buildings registered. Add one with `arx workspace add <path>`".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(detail).block(Block::default().borders(Borders::ALL)),
+            rows[1],
+        );
+
+        let help = Paragraph::new(
+            "↑↓ Select │ Enter: Floor plan │ e: Equipment sheet │ s: Sort │ r: Refresh │ q/Esc: Quit",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::ALL).title("Help"));
+        frame.render_widget(help, rows[2]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Building, Equipment, EquipmentStatus, EquipmentType, Floor};
+    use crate::persistence::PersistenceManager;
+    use std::path::Path;
+
+    fn register(dir: &Path, name: &str, equipment: &[EquipmentStatus]) -> BuildingHandle {
+        let root = dir.join(name);
+        std::fs::create_dir_all(&root).unwrap();
+        let mut floor = Floor::new("Ground".into(), 0);
+        for (i, status) in equipment.iter().enumerate() {
+            let mut eq = Equipment::new(format!("EQ-{}", i), String::new(), EquipmentType::HVAC);
+            eq.status = *status;
+            floor.equipment.push(eq);
+        }
+        let mut building = Building::new(name.into(), format!("/{}", name));
+        building.add_floor(floor);
+        PersistenceManager::at(&root)
+            .save_building_unchecked(&building)
+            .unwrap();
+        BuildingHandle {
+            name: name.into(),
+            root,
+        }
+    }
+
+    #[test]
+    fn rows_sort_by_column_and_drill_into_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = WorkspaceRegistry {
+            active: None,
+            buildings: vec![
+                register(dir.path(), "annex", &[EquipmentStatus::Active]),
+                register(
+                    dir.path(),
+                    "hq",
+                    &[EquipmentStatus::OutOfOrder, EquipmentStatus::Active],
+                ),
+                BuildingHandle {
+                    name: "gone".into(),
+                    root: dir.path().join("gone"),
+                },
+            ],
+        };
+        let mut view = PortfolioView::from_registry(&registry);
+        let names = |view: &PortfolioView| -> Vec<String> {
+            view.rows().iter().map(|r| r.handle.name.clone()).collect()
+        };
+        assert_eq!(names(&view), ["annex", "gone", "hq"]);
+        assert!(matches!(
+            view.rows()[1].validation,
+            ValidationStatus::Unavailable(_)
+        ));
+
+        view.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert_eq!(view.sort(), PortfolioSort::Equipment);
+        view.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert_eq!(view.sort(), PortfolioSort::Alerts);
+        assert_eq!(names(&view), ["hq", "annex", "gone"]);
+        assert_eq!(view.rows()[0].alerts, 1);
+
+        // The selection follows "annex" across the re-sort
+        assert!(matches!(
+            view.handle_key(KeyEvent::from(KeyCode::Enter)),
+            PortfolioAction::Inspect(handle) if handle.name == "annex"
+        ));
+        view.handle_key(KeyEvent::from(KeyCode::Up));
+        assert!(matches!(
+            view.handle_key(KeyEvent::from(KeyCode::Char('e'))),
+            PortfolioAction::Spreadsheet(handle) if handle.name == "hq"
+        ));
+    }
+}
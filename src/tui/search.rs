@@ -432,12 +432,23 @@ impl SearchBrowser {
     }
 
     fn match_room_with_indices(&self, room: &Room) -> Option<(i64, Vec<usize>)> {
-        let searchable = format!("{} {:?}", room.name, room.room_type);
+        // Aliases trail the name so fuzzy hits on colloquial names still surface the room
+        let searchable = format!(
+            "{} {:?} {}",
+            room.name,
+            room.room_type,
+            room.aliases.join(" ")
+        );
         self.matcher.fuzzy_indices(&searchable, &self.query)
     }
 
     fn match_equipment_with_indices(&self, equipment: &Equipment) -> Option<(i64, Vec<usize>)> {
-        let searchable = format!("{} {:?}", equipment.name, equipment.equipment_type);
+        let searchable = format!(
+            "{} {:?} {}",
+            equipment.name,
+            equipment.equipment_type,
+            equipment.aliases.join(" ")
+        );
         self.matcher.fuzzy_indices(&searchable, &self.query)
     }
 
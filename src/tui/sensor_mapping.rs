@@ -0,0 +1,539 @@
+//! Sensor mapping editor for ArxOS TUI
+//!
+//! Lists sensor ids seen by ingestion that have no `SensorMapping` yet and
+//! binds them to equipment or rooms:
+//! - Target field autocompletes over room / equipment names and aliases
+//! - Sensor type and a `min..max` threshold are set per binding
+//! - Ctrl+S hands the updated building back for commit
+
+use crate::core::operations::sensor::{bind_sensor, parse_threshold_range, SensorBindTarget};
+use crate::core::Building;
+use crate::persistence::sensors::SeenSensor;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Maximum autocomplete suggestions shown
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Something a sensor can be bound to
+#[derive(Debug, Clone)]
+pub struct BindCandidate {
+    pub label: String,
+    pub detail: String,
+    /// Name plus aliases, matched against the query
+    pub match_text: String,
+    pub target: SensorBindTarget,
+}
+
+/// Rooms and equipment in `building`, rooms first.
+pub fn bind_candidates(building: &Building) -> Vec<BindCandidate> {
+    let mut rooms = Vec::new();
+    let mut equipment = Vec::new();
+    for floor in &building.floors {
+        for wing in &floor.wings {
+            for room in &wing.rooms {
+                rooms.push(BindCandidate {
+                    label: format!("🚪 {}", room.name),
+                    detail: format!("{} / {}", floor.name, wing.name),
+                    match_text: std::iter::once(room.name.as_str())
+                        .chain(room.aliases.iter().map(String::as_str))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    target: SensorBindTarget::Room(room.id.clone()),
+                });
+                for eq in &room.equipment {
+                    equipment.push(BindCandidate {
+                        label: format!("⚙️  {}", eq.name),
+                        detail: format!("{} / {}", floor.name, room.name),
+                        match_text: std::iter::once(eq.name.as_str())
+                            .chain(eq.aliases.iter().map(String::as_str))
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                        target: SensorBindTarget::Equipment(eq.id.clone()),
+                    });
+                }
+            }
+        }
+    }
+    rooms.extend(equipment);
+    rooms
+}
+
+/// Editor field with keyboard focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingField {
+    Sensors,
+    Target,
+    SensorType,
+    Threshold,
+}
+
+impl MappingField {
+    fn next(self) -> Self {
+        match self {
+            MappingField::Sensors => MappingField::Target,
+            MappingField::Target => MappingField::SensorType,
+            MappingField::SensorType => MappingField::Threshold,
+            MappingField::Threshold => MappingField::Sensors,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            MappingField::Sensors => MappingField::Threshold,
+            MappingField::Target => MappingField::Sensors,
+            MappingField::SensorType => MappingField::Target,
+            MappingField::Threshold => MappingField::SensorType,
+        }
+    }
+}
+
+/// Sensor mapping editor state
+pub struct SensorMappingEditor {
+    building: Building,
+    sensors: Vec<SeenSensor>,
+    sensor_state: ListState,
+    field: MappingField,
+    candidates: Vec<BindCandidate>,
+    matcher: SkimMatcherV2,
+    target_query: String,
+    suggestions: Vec<usize>,
+    suggestion: usize,
+    sensor_type: String,
+    threshold: String,
+    /// (sensor id, bound-to label) for this session
+    bound: Vec<(String, String)>,
+    status: Option<String>,
+}
+
+impl SensorMappingEditor {
+    pub fn new(building: Building, unmapped: Vec<SeenSensor>) -> Self {
+        let candidates = bind_candidates(&building);
+        let mut editor = Self {
+            building,
+            sensors: unmapped,
+            sensor_state: ListState::default(),
+            field: MappingField::Sensors,
+            candidates,
+            matcher: SkimMatcherV2::default(),
+            target_query: String::new(),
+            suggestions: Vec::new(),
+            suggestion: 0,
+            sensor_type: String::new(),
+            threshold: String::new(),
+            bound: Vec::new(),
+            status: None,
+        };
+        editor.select_sensor(0);
+        editor.update_suggestions();
+        editor
+    }
+
+    /// Bindings made this session
+    pub fn bound(&self) -> &[(String, String)] {
+        &self.bound
+    }
+
+    /// Updated building (with new sensor mappings)
+    pub fn into_building(self) -> Building {
+        self.building
+    }
+
+    /// Handle keyboard input
+    pub fn handle_key(&mut self, key: KeyEvent) -> SensorMappingAction {
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => return SensorMappingAction::Exit,
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+                if self.bound.is_empty() {
+                    self.status = Some("Nothing to commit yet".to_string());
+                    return SensorMappingAction::Continue;
+                }
+                return SensorMappingAction::Commit;
+            }
+            (KeyCode::Tab, _) => self.field = self.field.next(),
+            (KeyCode::BackTab, _) => self.field = self.field.previous(),
+            (KeyCode::Enter, _) => match self.field {
+                MappingField::Sensors => self.field = MappingField::Target,
+                _ => self.bind_selected(),
+            },
+            (KeyCode::Down, _) => self.move_selection(1),
+            (KeyCode::Up, _) => self.move_selection(-1),
+            (KeyCode::Backspace, _) => {
+                if let Some(buf) = self.input_mut() {
+                    buf.pop();
+                }
+                if self.field == MappingField::Target {
+                    self.update_suggestions();
+                }
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT) => {
+                match self.field {
+                    MappingField::Sensors if c == 'q' => return SensorMappingAction::Exit,
+                    MappingField::Sensors if c == 'j' => self.move_selection(1),
+                    MappingField::Sensors if c == 'k' => self.move_selection(-1),
+                    _ => {
+                        if let Some(buf) = self.input_mut() {
+                            buf.push(c);
+                        }
+                        if self.field == MappingField::Target {
+                            self.update_suggestions();
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        SensorMappingAction::Continue
+    }
+
+    fn input_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            MappingField::Sensors => None,
+            MappingField::Target => Some(&mut self.target_query),
+            MappingField::SensorType => Some(&mut self.sensor_type),
+            MappingField::Threshold => Some(&mut self.threshold),
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        match self.field {
+            MappingField::Target => {
+                if !self.suggestions.is_empty() {
+                    let len = self.suggestions.len() as isize;
+                    self.suggestion = (self.suggestion as isize + delta).rem_euclid(len) as usize;
+                }
+            }
+            _ => {
+                if !self.sensors.is_empty() {
+                    let len = self.sensors.len() as isize;
+                    let current = self.sensor_state.selected().unwrap_or(0) as isize;
+                    self.select_sensor((current + delta).rem_euclid(len) as usize);
+                }
+            }
+        }
+    }
+
+    fn select_sensor(&mut self, idx: usize) {
+        if self.sensors.is_empty() {
+            self.sensor_state.select(None);
+            return;
+        }
+        let idx = idx.min(self.sensors.len() - 1);
+        self.sensor_state.select(Some(idx));
+        self.sensor_type = self.sensors[idx].sensor_type.clone().unwrap_or_default();
+    }
+
+    fn update_suggestions(&mut self) {
+        let query = self.target_query.trim();
+        let mut scored: Vec<(i64, usize)> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| {
+                if query.is_empty() {
+                    Some((0, i))
+                } else {
+                    self.matcher
+                        .fuzzy_match(&c.match_text, query)
+                        .map(|score| (score, i))
+                }
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        self.suggestions = scored
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, i)| i)
+            .collect();
+        self.suggestion = 0;
+    }
+
+    fn bind_selected(&mut self) {
+        let Some(idx) = self.sensor_state.selected() else {
+            self.status = Some("No unmapped sensors".to_string());
+            return;
+        };
+        let Some(&candidate_idx) = self.suggestions.get(self.suggestion) else {
+            self.status = Some("Pick a room or equipment in Target".to_string());
+            return;
+        };
+        let threshold = if self.threshold.trim().is_empty() {
+            None
+        } else {
+            match parse_threshold_range(&self.threshold) {
+                Ok(t) => Some(t),
+                Err(e) => {
+                    self.status = Some(e);
+                    return;
+                }
+            }
+        };
+        let sensor_id = self.sensors[idx].sensor_id.clone();
+        let candidate = &self.candidates[candidate_idx];
+        match bind_sensor(
+            &mut self.building,
+            &sensor_id,
+            &self.sensor_type,
+            &candidate.target,
+            threshold,
+        ) {
+            Ok(carrier) => {
+                let label = format!("{} ({})", candidate.label, candidate.detail);
+                self.status = Some(format!("Bound {} → {}", sensor_id, carrier));
+                self.bound.push((sensor_id, label));
+                self.sensors.remove(idx);
+                self.target_query.clear();
+                self.threshold.clear();
+                // Room bindings add a sensor equipment entry; refresh candidates
+                self.candidates = bind_candidates(&self.building);
+                self.update_suggestions();
+                self.select_sensor(idx);
+                self.field = MappingField::Sensors;
+            }
+            Err(e) => self.status = Some(e),
+        }
+    }
+
+    fn field_style(&self, field: MappingField) -> Style {
+        if self.field == field {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        }
+    }
+
+    /// Render the editor
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(4)])
+            .split(area);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(rows[0]);
+
+        // Unmapped sensors
+        let items: Vec<ListItem> = self
+            .sensors
+            .iter()
+            .map(|s| {
+                let mut spans = vec![Span::raw(s.sensor_id.clone())];
+                if let Some(ref t) = s.sensor_type {
+                    spans.push(Span::styled(
+                        format!("  {}", t),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                if let Some(v) = s.last_value {
+                    spans.push(Span::styled(
+                        format!("  = {}", v),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                spans.push(Span::styled(
+                    format!("  {}", s.last_seen.format("%Y-%m-%d %H:%M")),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let sensors = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("📡 Unmapped sensors ({})", self.sensors.len()))
+                    .border_style(self.field_style(MappingField::Sensors)),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▸ ");
+        frame.render_stateful_widget(sensors, columns[0], &mut self.sensor_state);
+
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),                          // Target input
+                Constraint::Length(MAX_SUGGESTIONS as u16 + 2), // Suggestions
+                Constraint::Length(3),                          // Type
+                Constraint::Length(3),                          // Threshold
+                Constraint::Min(1),                             // Bound this session
+            ])
+            .split(columns[1]);
+
+        let input = |text: &str, title: &str, field: MappingField| {
+            Paragraph::new(text.to_string())
+                .style(Style::default().fg(Color::Yellow))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(title.to_string())
+                        .border_style(self.field_style(field)),
+                )
+        };
+        frame.render_widget(
+            input(
+                &self.target_query,
+                "Bind to (room / equipment)",
+                MappingField::Target,
+            ),
+            right[0],
+        );
+
+        let suggestions: Vec<ListItem> = self
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let candidate = &self.candidates[c];
+                let style = if i == self.suggestion {
+                    Style::default().fg(Color::Black).bg(Color::Cyan)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(candidate.label.clone(), style),
+                    Span::styled(
+                        format!("  {}", candidate.detail),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect();
+        frame.render_widget(
+            List::new(suggestions).block(Block::default().borders(Borders::ALL).title("Matches")),
+            right[1],
+        );
+        frame.render_widget(
+            input(&self.sensor_type, "Sensor type", MappingField::SensorType),
+            right[2],
+        );
+        frame.render_widget(
+            input(
+                &self.threshold,
+                "Threshold (min..max)",
+                MappingField::Threshold,
+            ),
+            right[3],
+        );
+
+        let bound: Vec<ListItem> = self
+            .bound
+            .iter()
+            .map(|(id, label)| ListItem::new(format!("✅ {} → {}", id, label)))
+            .collect();
+        frame.render_widget(
+            List::new(bound).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Bound ({})", self.bound.len())),
+            ),
+            right[4],
+        );
+
+        let mut help_text = vec![Line::from(
+            "Tab: Next field │ ↑↓ Select │ Enter: Bind │ Ctrl+S: Commit │ Esc: Quit",
+        )];
+        if let Some(ref status) = self.status {
+            help_text.push(Line::from(Span::styled(
+                status.clone(),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        let help = Paragraph::new(help_text)
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::ALL).title("Help"));
+        frame.render_widget(help, rows[1]);
+    }
+}
+
+/// Actions returned by the sensor mapping editor
+#[derive(Debug, PartialEq, Eq)]
+pub enum SensorMappingAction {
+    Continue,
+    Commit,
+    Exit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    fn seen(id: &str) -> SeenSensor {
+        let now = chrono::Utc::now();
+        SeenSensor {
+            sensor_id: id.to_string(),
+            sensor_type: Some("temperature".to_string()),
+            first_seen: now,
+            last_seen: now,
+            readings: 1,
+            last_value: None,
+        }
+    }
+
+    fn type_str(editor: &mut SensorMappingEditor, s: &str) {
+        for c in s.chars() {
+            editor.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn autocomplete_bind_then_commit() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Lobby".into(), RoomType::Office);
+        room.add_equipment(Equipment::new(
+            "AHU-1".into(),
+            String::new(),
+            EquipmentType::HVAC,
+        ));
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+
+        let mut editor = SensorMappingEditor::new(building, vec![seen("zb-1"), seen("zb-2")]);
+        assert_eq!(
+            editor.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+            SensorMappingAction::Continue
+        );
+
+        editor.handle_key(KeyEvent::from(KeyCode::Enter)); // focus Target
+        type_str(&mut editor, "ahu");
+        assert!(matches!(
+            editor.candidates[editor.suggestions[0]].target,
+            SensorBindTarget::Equipment(_)
+        ));
+        editor.handle_key(KeyEvent::from(KeyCode::Tab));
+        editor.handle_key(KeyEvent::from(KeyCode::Tab));
+        type_str(&mut editor, "18..26");
+        editor.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        assert_eq!(editor.bound().len(), 1);
+        assert_eq!(editor.sensors.len(), 1);
+        assert_eq!(
+            editor.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+            SensorMappingAction::Commit
+        );
+        let building = editor.into_building();
+        let mapping = &building.floors[0].wings[0].rooms[0].equipment[0]
+            .sensor_mappings
+            .as_ref()
+            .unwrap()[0];
+        assert_eq!(mapping.sensor_id, "zb-1");
+        assert_eq!(mapping.thresholds["temperature"].max, Some(26.0));
+    }
+}
@@ -0,0 +1,144 @@
+//! Snapshot harness for TUI screens
+//!
+//! Renders a screen into ratatui's `TestBackend` and compares the text grid
+//! against a reviewed file in `src/tui/snapshots/`. Layout changes show up as
+//! snapshot diffs in review instead of slipping through unnoticed.
+//!
+//! On mismatch the rendered screen is written next to the snapshot as
+//! `<name>.snap.new` and the test fails with a line diff. Accept intended
+//! changes with `UPDATE_SNAPSHOTS=1 cargo test` and commit the `.snap` files.
+
+use crate::core::{Building, Equipment, EquipmentStatus, EquipmentType, Floor, Room, Wing};
+use crate::core::{EquipmentHealthStatus, RoomType};
+use chrono::{TimeZone, Utc};
+use ratatui::{backend::TestBackend, Frame, Terminal};
+use std::path::PathBuf;
+
+/// Environment variable that rewrites snapshots instead of comparing.
+pub const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tui/snapshots")
+        .join(format!("{}.snap", name))
+}
+
+/// Render `draw` on a `width` x `height` test terminal and return the
+/// screen as text, one line per row with trailing blanks trimmed.
+pub fn render_to_string(width: u16, height: u16, draw: impl FnOnce(&mut Frame)) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("test terminal");
+    terminal.draw(draw).expect("draw");
+    let buffer = terminal.backend().buffer();
+    let mut out = String::new();
+    for y in 0..height {
+        let line: String = (0..width)
+            .map(|x| buffer.get(x, y).symbol.as_str())
+            .collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Compare `rendered` against `src/tui/snapshots/<name>.snap`.
+pub fn assert_snapshot(name: &str, rendered: &str) {
+    let path = snapshot_path(name);
+    let pending = path.with_extension("snap.new");
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        std::fs::create_dir_all(path.parent().expect("snapshot dir")).expect("snapshot dir");
+        std::fs::write(&path, rendered).expect("write snapshot");
+        let _ = std::fs::remove_file(&pending);
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_default();
+    if expected == rendered {
+        let _ = std::fs::remove_file(&pending);
+        return;
+    }
+    std::fs::write(&pending, rendered).expect("write pending snapshot");
+    if expected.is_empty() {
+        panic!(
+            "no snapshot for '{}'; review {} and rerun with {}=1 to accept",
+            name,
+            pending.display(),
+            UPDATE_ENV
+        );
+    }
+    panic!(
+        "snapshot '{}' changed (rerun with {}=1 to accept):\n{}",
+        name,
+        UPDATE_ENV,
+        line_diff(&expected, rendered)
+    );
+}
+
+/// Row-by-row diff; screens have a fixed height so rows line up.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut out = String::new();
+    for row in 0..expected.len().max(actual.len()) {
+        let (e, a) = (expected.get(row), actual.get(row));
+        if e == a {
+            continue;
+        }
+        if let Some(e) = e {
+            out.push_str(&format!("{:>3} - {}\n", row + 1, e));
+        }
+        if let Some(a) = a {
+            out.push_str(&format!("{:>3} + {}\n", row + 1, a));
+        }
+    }
+    out
+}
+
+fn fixture_equipment(id: &str, name: &str, equipment_type: EquipmentType) -> Equipment {
+    let mut eq = Equipment::new(name.to_string(), String::new(), equipment_type);
+    eq.id = id.to_string();
+    eq
+}
+
+/// Small deterministic building (fixed ids and timestamps) for screens.
+pub fn fixture_building() -> Building {
+    let at = Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+    let mut building = Building::new("Snapshot Hall".to_string(), "/snapshot-hall".to_string());
+    building.id = "bldg-snapshot".to_string();
+    building.created_at = at;
+    building.updated_at = at;
+
+    let mut ahu = fixture_equipment("eq-ahu-1", "AHU-1", EquipmentType::HVAC);
+    ahu.health_status = Some(EquipmentHealthStatus::Warning);
+    let mut panel = fixture_equipment("eq-panel-a", "Panel A", EquipmentType::Electrical);
+    panel.status = EquipmentStatus::Maintenance;
+    let projector = fixture_equipment("eq-proj-101", "Projector 101", EquipmentType::AV);
+
+    let mut room = Room::new("Room 101".to_string(), RoomType::Classroom);
+    room.id = "room-101".to_string();
+    room.created_at = Some(at);
+    room.updated_at = Some(at);
+    room.equipment.push(projector);
+
+    let mut wing = Wing::new("East".to_string());
+    wing.id = "wing-east".to_string();
+    wing.equipment.push(panel);
+    wing.rooms.push(room);
+
+    let mut floor = Floor::new("Ground".to_string(), 0);
+    floor.id = "floor-0".to_string();
+    floor.equipment.push(ahu);
+    floor.wings.push(wing);
+    building.floors.push(floor);
+    building
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff_reports_changed_rows_only() {
+        let diff = line_diff("a\nb\nc\n", "a\nB\nc\n");
+        assert_eq!(diff, "  2 - b\n  2 + B\n");
+    }
+}
@@ -0,0 +1,304 @@
+//! Interactive spreadsheet editing session
+//!
+//! Ties the grid, cell editor, and [`Changeset`] together: edits are staged
+//! (and validated) cell by cell, undone / redone within the session, reviewed
+//! as a diff, and written back to the building YAML in one save.
+
+use super::changeset::Changeset;
+use super::data_source::SpreadsheetDataSource;
+use super::editor::{CellEditor, EditorAction};
+use super::navigation::handle_navigation;
+use super::render::render_spreadsheet_with_editor_and_save;
+use super::save_state::SaveState;
+use super::types::Grid;
+use super::workflow::WorkflowStatus;
+use crate::tui::Theme;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Result of handling a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadsheetAction {
+    Continue,
+    Exit,
+}
+
+/// Spreadsheet session over one data source
+pub struct SpreadsheetApp {
+    source: Box<dyn SpreadsheetDataSource>,
+    grid: Grid,
+    editor: Option<CellEditor>,
+    changes: Changeset,
+    /// Diff preview is open
+    reviewing: bool,
+    /// Commit to Git when writing
+    commit: bool,
+    save_state: SaveState,
+    workflow: WorkflowStatus,
+    theme: Theme,
+    message: Option<String>,
+    quit_armed: bool,
+}
+
+impl SpreadsheetApp {
+    /// Open a session; `commit` is the initial auto-commit setting
+    pub fn new(source: Box<dyn SpreadsheetDataSource>, commit: bool) -> Self {
+        let mut grid = Grid::new(source.columns(), source.row_count());
+        for (row, cells) in grid.rows.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                if let Ok(value) = source.get_cell(row, col) {
+                    cell.value = value;
+                }
+            }
+        }
+        Self {
+            source,
+            grid,
+            editor: None,
+            changes: Changeset::new(),
+            reviewing: false,
+            commit,
+            save_state: SaveState::Clean,
+            workflow: WorkflowStatus::detect(),
+            theme: Theme::default(),
+            message: None,
+            quit_armed: false,
+        }
+    }
+
+    /// Staged edits
+    pub fn changes(&self) -> &Changeset {
+        &self.changes
+    }
+
+    fn selected(&self) -> (usize, usize) {
+        let row = self
+            .grid
+            .get_original_row(self.grid.selected_row)
+            .unwrap_or(self.grid.selected_row);
+        (row, self.grid.selected_col)
+    }
+
+    /// Show the staged value of a cell and whether it differs from disk
+    fn refresh_cell(&mut self, row: usize, col: usize) {
+        let value = self.changes.value(self.source.as_ref(), row, col);
+        let modified = self
+            .changes
+            .changes()
+            .iter()
+            .any(|e| e.row == row && e.col == col);
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.value = value;
+            cell.modified = modified;
+            cell.error = None;
+        }
+        self.save_state = if self.changes.is_empty() {
+            SaveState::Clean
+        } else {
+            SaveState::Modified
+        };
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, key: KeyEvent) -> SpreadsheetAction {
+        if self.editor.is_some() {
+            self.handle_editor_key(key);
+        } else if self.reviewing {
+            self.handle_review_key(key);
+        } else {
+            return self.handle_grid_key(key);
+        }
+        SpreadsheetAction::Continue
+    }
+
+    fn handle_grid_key(&mut self, key: KeyEvent) -> SpreadsheetAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let quit_armed = std::mem::take(&mut self.quit_armed);
+        self.message = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if self.changes.is_empty() || quit_armed {
+                    return SpreadsheetAction::Exit;
+                }
+                self.quit_armed = true;
+                self.message = Some(format!(
+                    "{} unsaved change(s) — press q again to discard",
+                    self.changes.changes().len()
+                ));
+            }
+            KeyCode::Enter | KeyCode::F(2) => {
+                let (row, col) = self.selected();
+                match self.grid.columns.get(col) {
+                    Some(column) if column.editable => {
+                        let value = self.changes.value(self.source.as_ref(), row, col);
+                        let mut editor = CellEditor::new(column.clone(), value);
+                        editor.reset_cursor();
+                        self.editor = Some(editor);
+                        self.grid.editing_cell = Some((row, col));
+                    }
+                    Some(column) => {
+                        self.message = Some(format!("{} is read-only", column.label));
+                    }
+                    None => {}
+                }
+            }
+            KeyCode::Char('z') if ctrl => match self.changes.undo().cloned() {
+                Some(edit) => {
+                    self.refresh_cell(edit.row, edit.col);
+                    self.message = Some(format!("Undid row {} edit", edit.row + 1));
+                }
+                None => self.message = Some("Nothing to undo".to_string()),
+            },
+            KeyCode::Char('y') if ctrl => match self.changes.redo().cloned() {
+                Some(edit) => {
+                    self.refresh_cell(edit.row, edit.col);
+                    self.message = Some(format!("Redid row {} edit", edit.row + 1));
+                }
+                None => self.message = Some("Nothing to redo".to_string()),
+            },
+            KeyCode::Char('s') if ctrl => {
+                if self.changes.is_empty() {
+                    self.message = Some("No changes to write".to_string());
+                } else {
+                    self.reviewing = true;
+                }
+            }
+            _ => {
+                handle_navigation(key, &mut self.grid);
+            }
+        }
+        SpreadsheetAction::Continue
+    }
+
+    fn handle_editor_key(&mut self, key: KeyEvent) {
+        let Some(editor) = self.editor.as_mut() else {
+            return;
+        };
+        match editor.handle_key(key) {
+            EditorAction::Continue => {}
+            EditorAction::Cancel => {
+                self.editor = None;
+                self.grid.editing_cell = None;
+            }
+            EditorAction::ValidateAndApply => {
+                let Some((row, col)) = self.grid.editing_cell else {
+                    return;
+                };
+                let input = editor.get_current_value().to_string();
+                match self.changes.stage(self.source.as_ref(), row, col, &input) {
+                    Ok(_) => {
+                        self.editor = None;
+                        self.grid.editing_cell = None;
+                        self.refresh_cell(row, col);
+                    }
+                    Err(e) => {
+                        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+                            cell.error = Some(e.message);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_review_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.reviewing = false,
+            KeyCode::Char('c') => self.commit = !self.commit,
+            KeyCode::Enter | KeyCode::Char('y') => {
+                self.reviewing = false;
+                self.save_state = SaveState::Saving;
+                match self.changes.apply(self.source.as_mut(), self.commit) {
+                    Ok(count) => {
+                        for cell in self.grid.rows.iter_mut().flatten() {
+                            cell.modified = false;
+                        }
+                        self.save_state = SaveState::Saved;
+                        self.message = Some(format!(
+                            "Wrote {} cell(s){}",
+                            count,
+                            if self.commit { " and committed" } else { "" }
+                        ));
+                    }
+                    Err(e) => self.save_state = SaveState::Error(e.to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Diff preview lines, styled
+    fn review_lines(&self) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line> = self
+            .changes
+            .preview(self.source.as_ref())
+            .into_iter()
+            .map(|line| {
+                let color = match line.chars().next() {
+                    Some('-') => Color::Red,
+                    Some('+') => Color::Green,
+                    _ => Color::Cyan,
+                };
+                Line::styled(line, Style::default().fg(color))
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            format!(
+                "Enter: write   c: commit to Git [{}]   Esc: back",
+                if self.commit { "on" } else { "off" }
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+        lines
+    }
+
+    /// Render the session
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(15), Constraint::Length(1)])
+            .split(area);
+
+        render_spreadsheet_with_editor_and_save(
+            frame,
+            layout[0],
+            &self.grid,
+            &self.theme,
+            &self.workflow,
+            self.editor.as_ref(),
+            Some(&self.save_state),
+        );
+
+        let hint = self.message.clone().unwrap_or_else(|| {
+            "Enter: edit | Ctrl+Z: undo | Ctrl+Y: redo | Ctrl+S: review & write | q: quit"
+                .to_string()
+        });
+        frame.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(Color::Yellow)),
+            layout[1],
+        );
+
+        if self.reviewing {
+            let popup = Rect {
+                x: area.x + area.width / 8,
+                y: area.y + area.height / 8,
+                width: area.width - area.width / 4,
+                height: area.height - area.height / 4,
+            };
+            frame.render_widget(Clear, popup);
+            let title = format!("Review {} change(s)", self.changes.changes().len());
+            frame.render_widget(
+                Paragraph::new(self.review_lines())
+                    .block(Block::default().borders(Borders::ALL).title(title)),
+                popup,
+            );
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Staged cell edits for the spreadsheet
+//!
+//! Edits are validated against the column schema when staged, kept as a
+//! session history (undo / redo), previewed as a per-cell diff, and written
+//! back through the data source in one save.
+
+use super::data_source::SpreadsheetDataSource;
+use super::types::CellValue;
+use super::validation::{validate_cell, ValidationError};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// One staged cell change
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellEdit {
+    pub row: usize,
+    pub col: usize,
+    pub old_value: CellValue,
+    pub new_value: CellValue,
+}
+
+/// Pending edits against a data source
+#[derive(Debug, Default)]
+pub struct Changeset {
+    history: Vec<CellEdit>,
+    undone: Vec<CellEdit>,
+}
+
+impl Changeset {
+    /// Create an empty changeset
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate `input` for the cell and stage it
+    ///
+    /// Returns the staged edit, or `None` when the value is unchanged.
+    pub fn stage(
+        &mut self,
+        source: &dyn SpreadsheetDataSource,
+        row: usize,
+        col: usize,
+        input: &str,
+    ) -> Result<Option<&CellEdit>, ValidationError> {
+        let columns = source.columns();
+        let column = columns.get(col).ok_or_else(|| ValidationError {
+            message: format!("Column {} out of bounds", col),
+        })?;
+        if !column.editable {
+            return Err(ValidationError {
+                message: format!("{} is read-only", column.label),
+            });
+        }
+        if row >= source.row_count() {
+            return Err(ValidationError {
+                message: format!("Row {} out of bounds", row),
+            });
+        }
+
+        let new_value = validate_cell(input, column)?;
+        let old_value = self.value(source, row, col);
+        if new_value == old_value {
+            return Ok(None);
+        }
+        self.undone.clear();
+        self.history.push(CellEdit {
+            row,
+            col,
+            old_value,
+            new_value,
+        });
+        Ok(self.history.last())
+    }
+
+    /// Cell value with staged edits applied
+    pub fn value(&self, source: &dyn SpreadsheetDataSource, row: usize, col: usize) -> CellValue {
+        self.history
+            .iter()
+            .rev()
+            .find(|e| e.row == row && e.col == col)
+            .map(|e| e.new_value.clone())
+            .unwrap_or_else(|| source.get_cell(row, col).unwrap_or(CellValue::Empty))
+    }
+
+    /// Drop the most recent edit; the returned edit's `old_value` is the
+    /// value the cell goes back to
+    pub fn undo(&mut self) -> Option<&CellEdit> {
+        let edit = self.history.pop()?;
+        self.undone.push(edit);
+        self.undone.last()
+    }
+
+    /// Restage the most recently undone edit
+    pub fn redo(&mut self) -> Option<&CellEdit> {
+        let edit = self.undone.pop()?;
+        self.history.push(edit);
+        self.history.last()
+    }
+
+    /// Net change per cell, in row / column order (edits that were changed
+    /// back to the original value drop out)
+    pub fn changes(&self) -> Vec<CellEdit> {
+        let mut net: BTreeMap<(usize, usize), CellEdit> = BTreeMap::new();
+        for edit in &self.history {
+            net.entry((edit.row, edit.col))
+                .and_modify(|e| e.new_value = edit.new_value.clone())
+                .or_insert_with(|| edit.clone());
+        }
+        net.into_values()
+            .filter(|e| e.old_value != e.new_value)
+            .collect()
+    }
+
+    /// True when nothing would be written
+    pub fn is_empty(&self) -> bool {
+        self.changes().is_empty()
+    }
+
+    /// Diff lines for the confirmation view
+    pub fn preview(&self, source: &dyn SpreadsheetDataSource) -> Vec<String> {
+        let columns = source.columns();
+        let key_col = columns
+            .iter()
+            .position(|c| c.id.ends_with(".name"))
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        let mut last_row = None;
+        for edit in self.changes() {
+            if last_row != Some(edit.row) {
+                let key = source
+                    .get_cell(edit.row, key_col)
+                    .unwrap_or(CellValue::Empty);
+                lines.push(format!("@@ row {} · {}", edit.row + 1, key));
+                last_row = Some(edit.row);
+            }
+            let label = columns
+                .get(edit.col)
+                .map(|c| c.label.as_str())
+                .unwrap_or("?");
+            lines.push(format!("- {}: {}", label, edit.old_value));
+            lines.push(format!("+ {}: {}", label, edit.new_value));
+        }
+        lines
+    }
+
+    /// Write the net changes through `source` and save (committing when
+    /// `commit`); returns the number of cells written
+    ///
+    /// On failure the source is reloaded so no partial edit lingers in it,
+    /// and the changeset is kept for another attempt.
+    pub fn apply(
+        &mut self,
+        source: &mut dyn SpreadsheetDataSource,
+        commit: bool,
+    ) -> Result<usize, Box<dyn Error>> {
+        let changes = self.changes();
+        let result = changes
+            .iter()
+            .try_for_each(|e| source.set_cell(e.row, e.col, e.new_value.clone()))
+            .and_then(|_| source.save(commit));
+        if let Err(e) = result {
+            let _ = source.reload();
+            return Err(e);
+        }
+        self.history.clear();
+        self.undone.clear();
+        Ok(changes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::spreadsheet::types::{CellType, ColumnDefinition, ValidationRule};
+
+    struct MockDataSource {
+        names: Vec<String>,
+        saved: Option<bool>,
+    }
+
+    impl SpreadsheetDataSource for MockDataSource {
+        fn columns(&self) -> Vec<ColumnDefinition> {
+            vec![
+                ColumnDefinition {
+                    id: "equipment.id".to_string(),
+                    label: "ID".to_string(),
+                    data_type: CellType::Text,
+                    editable: false,
+                    width: None,
+                    validation: None,
+                    enum_values: None,
+                },
+                ColumnDefinition {
+                    id: "equipment.name".to_string(),
+                    label: "Name".to_string(),
+                    data_type: CellType::Text,
+                    editable: true,
+                    width: None,
+                    validation: Some(ValidationRule::Required),
+                    enum_values: None,
+                },
+            ]
+        }
+
+        fn row_count(&self) -> usize {
+            self.names.len()
+        }
+
+        fn get_cell(&self, row: usize, col: usize) -> Result<CellValue, Box<dyn Error>> {
+            match col {
+                0 => Ok(CellValue::Text(format!("eq-{}", row))),
+                _ => Ok(CellValue::Text(self.names[row].clone())),
+            }
+        }
+
+        fn set_cell(
+            &mut self,
+            row: usize,
+            _col: usize,
+            value: CellValue,
+        ) -> Result<(), Box<dyn Error>> {
+            self.names[row] = value.to_string();
+            Ok(())
+        }
+
+        fn save(&mut self, commit: bool) -> Result<(), Box<dyn Error>> {
+            self.saved = Some(commit);
+            Ok(())
+        }
+
+        fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stage_validates_undo_and_apply() {
+        let mut source = MockDataSource {
+            names: vec!["AHU-1".to_string(), "P-1".to_string()],
+            saved: None,
+        };
+        let mut changes = Changeset::new();
+
+        assert!(changes.stage(&source, 0, 0, "x").is_err());
+        assert!(changes.stage(&source, 0, 1, " ").is_err());
+        assert!(changes.stage(&source, 0, 1, "AHU-1").unwrap().is_none());
+
+        changes.stage(&source, 1, 1, "Pump 1").unwrap();
+        changes.stage(&source, 0, 1, "AHU-2").unwrap();
+        changes.stage(&source, 0, 1, "AHU-3").unwrap();
+        assert_eq!(
+            changes.value(&source, 0, 1),
+            CellValue::Text("AHU-3".into())
+        );
+        assert_eq!(
+            changes.preview(&source),
+            vec![
+                "@@ row 1 · AHU-1",
+                "- Name: AHU-1",
+                "+ Name: AHU-3",
+                "@@ row 2 · P-1",
+                "- Name: P-1",
+                "+ Name: Pump 1",
+            ]
+        );
+
+        let undone = changes.undo().unwrap();
+        assert_eq!(undone.old_value, CellValue::Text("AHU-2".into()));
+        changes.undo();
+        changes.redo();
+        assert_eq!(changes.changes().len(), 2);
+        changes.stage(&source, 0, 1, "AHU-1").unwrap();
+        assert!(changes.redo().is_none());
+        assert_eq!(changes.changes().len(), 1);
+
+        assert_eq!(changes.apply(&mut source, true).unwrap(), 1);
+        assert_eq!(source.names, vec!["AHU-1", "Pump 1"]);
+        assert_eq!(source.saved, Some(true));
+        assert!(changes.is_empty());
+    }
+}
@@ -5,6 +5,8 @@
 
 use super::super::types::{CellType, CellValue, ColumnDefinition, ValidationRule};
 use super::trait_def::SpreadsheetDataSource;
+use crate::core::clock;
+use crate::core::runtime::set_status;
 use crate::core::{Building, Equipment, EquipmentHealthStatus, EquipmentStatus, EquipmentType};
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
@@ -266,7 +268,7 @@ impl SpreadsheetDataSource for EquipmentDataSource {
                                 "OutOfOrder" => EquipmentStatus::OutOfOrder,
                                 _ => unreachable!(),
                             };
-                            equipment.status = status;
+                            set_status(equipment, status, clock::now());
                         }
                         _ => return Err(format!("Invalid status: {}", status_str).into()),
                     };
@@ -2,6 +2,8 @@
 //!
 //! Provides Excel-like spreadsheet interface for viewing and editing building data.
 
+pub mod app;
+pub mod changeset;
 pub mod clipboard;
 pub mod data_source;
 pub mod editor;
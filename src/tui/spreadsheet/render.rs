@@ -7,6 +7,7 @@ use super::save_state::SaveState;
 use super::search::SearchState;
 use super::types::Grid;
 use super::workflow::WorkflowStatus;
+use crate::core::materials::MaterialSet;
 use crate::tui::Theme;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
@@ -26,6 +27,8 @@ pub struct SpreadsheetRenderContext<'a> {
     pub save_state: Option<&'a SaveState>,
     pub search_state: Option<&'a SearchState>,
     pub ar_scan_count: Option<usize>,
+    /// Palette for type columns (`None`: built-in palette)
+    pub materials: Option<&'a MaterialSet>,
 }
 
 impl<'a> SpreadsheetRenderContext<'a> {
@@ -44,6 +47,7 @@ impl<'a> SpreadsheetRenderContext<'a> {
             save_state: None,
             search_state: None,
             ar_scan_count: None,
+            materials: None,
         }
     }
 }
@@ -136,6 +140,7 @@ pub fn render_spreadsheet_with_editor_save_search_ar(
         context.theme,
         context.editor,
         context.search_state,
+        context.materials,
     );
 
     // Render address modal if active
@@ -166,7 +171,11 @@ fn render_grid_body(
     theme: &Theme,
     editor: Option<&CellEditor>,
     search_state: Option<&SearchState>,
+    materials: Option<&MaterialSet>,
 ) {
+    static BUILTIN_MATERIALS: std::sync::OnceLock<MaterialSet> = std::sync::OnceLock::new();
+    let materials = materials.unwrap_or_else(|| BUILTIN_MATERIALS.get_or_init(MaterialSet::default));
+
     // Calculate visible area (accounting for borders)
     let margin = Margin::new(1, 1);
     let inner_area = area.inner(&margin);
@@ -298,6 +307,22 @@ fn render_grid_body(
                     }
                 }
 
+                // Mirror export materials on type columns
+                if !is_editing && !is_selected && !is_search_match {
+                    let column = grid.columns[col_idx].id.as_str();
+                    if let Some(c) = cell.filter(|c| c.error.is_none()) {
+                        let type_name = c.value.to_string();
+                        let material = match column {
+                            "room.type" => Some(materials.for_room_type_name(&type_name)),
+                            "equipment.type" => Some(materials.for_equipment_type_name(&type_name)),
+                            _ => None,
+                        };
+                        if let Some(material) = material {
+                            base_style = base_style.fg(Theme::material_color(material));
+                        }
+                    }
+                }
+
                 let style = base_style;
 
                 Some(TableCell::from(text).style(style))
@@ -563,3 +588,58 @@ fn render_address_modal(frame: &mut Frame, area: Rect, address_path: &str, theme
 
     frame.render_widget(paragraph, modal_area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::snapshot::{assert_snapshot, fixture_building, render_to_string};
+    use crate::tui::spreadsheet::data_source::equipment_source::EquipmentDataSource;
+    use crate::tui::spreadsheet::data_source::SpreadsheetDataSource;
+
+    fn equipment_grid() -> Grid {
+        let source = EquipmentDataSource::new(fixture_building(), "Snapshot Hall".to_string());
+        let mut grid = Grid::new(source.columns(), source.row_count());
+        for (row, cells) in grid.rows.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                cell.value = source.get_cell(row, col).unwrap();
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn test_equipment_browser_snapshot() {
+        let grid = equipment_grid();
+        let theme = Theme::default();
+        let status = WorkflowStatus::default();
+        let screen = render_to_string(120, 16, |f| {
+            render_spreadsheet(f, f.size(), &grid, &theme, &status)
+        });
+        assert_snapshot("equipment_browser", &screen);
+    }
+
+    #[test]
+    fn test_watch_view_snapshot() {
+        let mut grid = equipment_grid();
+        grid.selected_row = 1;
+        grid.selected_col = 2;
+        let theme = Theme::default();
+        let status = WorkflowStatus {
+            watch_mode_active: true,
+            sync_active: false,
+            sensors_active: true,
+        };
+        let mut search = SearchState::new("panel".to_string(), false);
+        search.is_active = true;
+        let save = SaveState::Saved;
+        let mut context =
+            SpreadsheetRenderContext::new(Rect::new(0, 0, 120, 16), &grid, &theme, &status);
+        context.save_state = Some(&save);
+        context.search_state = Some(&search);
+        context.ar_scan_count = Some(2);
+        let screen = render_to_string(120, 16, |f| {
+            render_spreadsheet_with_editor_save_search_ar(f, context)
+        });
+        assert_snapshot("watch_view", &screen);
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Provides consistent color schemes and styling for building management context.
 
+use crate::core::materials::Material;
 use crate::tui::theme_manager::ThemeManager;
 use ratatui::style::Color;
 
@@ -124,6 +125,12 @@ impl Theme {
         }
     }
 
+    /// Terminal colour for a display material (matches its glTF base colour)
+    pub fn material_color(material: &Material) -> Color {
+        let (r, g, b) = material.rgb();
+        Color::Rgb(r, g, b)
+    }
+
     /// Detect system theme preference
     /// Returns true for dark mode, false for light mode
     fn detect_system_theme() -> bool {
@@ -157,7 +164,7 @@ impl Theme {
             // Alternative: Check gsettings (GNOME)
             use std::process::Command;
             if let Ok(output) = Command::new("gsettings")
-                .args(&["get", "org.gnome.desktop.interface", "gtk-theme"])
+                .args(["get", "org.gnome.desktop.interface", "gtk-theme"])
                 .output()
             {
                 if let Ok(theme) = String::from_utf8(output.stdout) {
@@ -261,6 +268,13 @@ impl Theme {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_material_color_matches_export_palette() {
+        let palette = crate::core::materials::MaterialSet::default();
+        let material = palette.for_room_type_name("laboratory");
+        assert_eq!(Theme::material_color(material), Color::Rgb(0x6f, 0xb3, 0xd2));
+    }
+
     #[test]
     fn test_detect_terminal_theme() {
         // Test that from_terminal returns a valid theme
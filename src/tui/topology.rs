@@ -0,0 +1,220 @@
+//! System topology view for ArxOS TUI
+//!
+//! Lists equipment that takes part in a system (has any `feeds` / `powers` /
+//! `controls` / `serves` edge) and shows, for the selected item, the tree of
+//! equipment downstream of it or, after Tab, upstream of it.
+
+use crate::core::systems::{ImpactNode, SystemGraph};
+use crate::core::Building;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Direction the impact tree is followed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactDirection {
+    Downstream,
+    Upstream,
+}
+
+/// System topology view state
+pub struct TopologyView {
+    building: Building,
+    /// (id, name) of connected equipment, by name
+    nodes: Vec<(String, String)>,
+    state: ListState,
+    direction: ImpactDirection,
+}
+
+impl TopologyView {
+    pub fn new(building: Building) -> Self {
+        let graph = SystemGraph::new(&building);
+        let mut ids: Vec<&str> = graph.edges().flat_map(|(from, _, to)| [from, to]).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let mut nodes: Vec<(String, String)> = ids
+            .into_iter()
+            .filter_map(|id| graph.equipment(id))
+            .map(|eq| (eq.id.clone(), eq.name.clone()))
+            .collect();
+        nodes.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let mut state = ListState::default();
+        if !nodes.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            building,
+            nodes,
+            state,
+            direction: ImpactDirection::Downstream,
+        }
+    }
+
+    /// Equipment shown in the list
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Impact tree of the selected equipment in the current direction
+    pub fn impact(&self) -> Vec<ImpactNode> {
+        let Some((id, _)) = self.state.selected().and_then(|i| self.nodes.get(i)) else {
+            return Vec::new();
+        };
+        let graph = SystemGraph::new(&self.building);
+        match self.direction {
+            ImpactDirection::Downstream => graph.downstream(id),
+            ImpactDirection::Upstream => graph.upstream(id),
+        }
+    }
+
+    /// Handle keyboard input
+    pub fn handle_key(&mut self, key: KeyEvent) -> TopologyAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return TopologyAction::Exit,
+            KeyCode::Tab => {
+                self.direction = match self.direction {
+                    ImpactDirection::Downstream => ImpactDirection::Upstream,
+                    ImpactDirection::Upstream => ImpactDirection::Downstream,
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            _ => {}
+        }
+        TopologyAction::Continue
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.nodes.len() as isize - 1);
+        self.state.select(Some(next as usize));
+    }
+
+    /// Render the view
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(3)])
+            .split(area);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(rows[0]);
+
+        let items: Vec<ListItem> = self
+            .nodes
+            .iter()
+            .map(|(_, name)| ListItem::new(format!("⚙️  {}", name)))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("🔗 Connected equipment ({})", self.nodes.len())),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▸ ");
+        frame.render_stateful_widget(list, columns[0], &mut self.state);
+
+        let (title, arrow) = match self.direction {
+            ImpactDirection::Downstream => ("Downstream impact", "→"),
+            ImpactDirection::Upstream => ("Upstream dependencies", "←"),
+        };
+        let impact = self.impact();
+        let lines: Vec<Line> = if impact.is_empty() {
+            vec![Line::from(Span::styled(
+                "(none)",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            impact
+                .iter()
+                .map(|node| {
+                    Line::from(vec![
+                        Span::raw("  ".repeat(node.depth - 1)),
+                        Span::styled(
+                            format!("{} {} ", arrow, node.relation),
+                            Style::default().fg(Color::Yellow),
+                        ),
+                        Span::raw(node.name.clone()),
+                    ])
+                })
+                .collect()
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
+                "{} ({})",
+                title,
+                impact.len()
+            ))),
+            columns[1],
+        );
+
+        let help = Paragraph::new("↑↓ Select │ Tab: Downstream/Upstream │ q/Esc: Quit")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::ALL).title("Help"));
+        frame.render_widget(help, rows[1]);
+    }
+}
+
+/// Actions returned by the topology view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopologyAction {
+    Continue,
+    Exit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::systems::{connect, Relation};
+    use crate::core::{Equipment, EquipmentType, Floor, Room, RoomType, Wing};
+
+    #[test]
+    fn tab_switches_impact_direction() {
+        let mut building = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("Ground".into(), 0);
+        let mut wing = Wing::new("East".into());
+        let mut room = Room::new("Plant".into(), RoomType::Mechanical);
+        for name in ["AHU-1", "VAV-3-01", "Lamp"] {
+            room.add_equipment(Equipment::new(
+                name.into(),
+                String::new(),
+                EquipmentType::HVAC,
+            ));
+        }
+        wing.add_room(room);
+        floor.add_wing(wing);
+        building.add_floor(floor);
+        connect(&mut building, "AHU-1", Relation::Feeds, "VAV-3-01").unwrap();
+
+        let mut view = TopologyView::new(building);
+        assert_eq!(view.node_count(), 2);
+        let names = |view: &TopologyView| -> Vec<String> {
+            view.impact().into_iter().map(|n| n.name).collect()
+        };
+        assert_eq!(names(&view), ["VAV-3-01"]);
+        view.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert!(names(&view).is_empty());
+        view.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(names(&view), ["AHU-1"]);
+        assert_eq!(
+            view.handle_key(KeyEvent::from(KeyCode::Char('q'))),
+            TopologyAction::Exit
+        );
+    }
+}
@@ -6,15 +6,19 @@ use super::types::Workspace;
 use crate::utils::loading;
 use std::path::{Path, PathBuf};
 
-/// Discover available workspaces by scanning for building.yaml files
+/// Discover available workspaces: buildings registered with `arx workspace
+/// add`, then building.yaml files found by scanning
 pub fn discover_workspaces() -> Result<Vec<Workspace>, Box<dyn std::error::Error>> {
-    let mut workspaces = Vec::new();
+    let mut workspaces = registered_workspaces();
 
     // Try to find YAML files using existing utility
     let yaml_files = loading::find_yaml_files()?;
 
     for yaml_file_str in yaml_files {
         let yaml_path = PathBuf::from(&yaml_file_str);
+        if workspaces.iter().any(|w| same_file(&w.path, &yaml_path)) {
+            continue;
+        }
 
         // Extract building name from path or filename
         let name = yaml_path
@@ -66,6 +70,39 @@ pub fn discover_workspaces() -> Result<Vec<Workspace>, Box<dyn std::error::Error
     Ok(workspaces)
 }
 
+/// Buildings in the user's workspace registry
+fn registered_workspaces() -> Vec<Workspace> {
+    use crate::persistence::workspace::WorkspaceRegistry;
+
+    let Ok(registry) = WorkspaceRegistry::load() else {
+        return Vec::new();
+    };
+    registry
+        .buildings
+        .iter()
+        .map(|handle| {
+            let yaml_path = handle.root.join(crate::persistence::BUILDING_YAML);
+            Workspace {
+                name: handle.name.clone(),
+                description: load_building_description(&yaml_path).ok(),
+                git_repo: handle
+                    .root
+                    .join(".git")
+                    .exists()
+                    .then(|| handle.root.clone()),
+                path: yaml_path,
+            }
+        })
+        .collect()
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 /// Load building description from YAML file
 pub fn load_building_description(yaml_path: &Path) -> Result<String, Box<dyn std::error::Error>> {
     use crate::utils::path_safety::PathSafety;
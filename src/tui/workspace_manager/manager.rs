@@ -31,16 +31,25 @@ impl WorkspaceManager {
         let workspaces = discover_workspaces()?;
         let filtered_workspaces: Vec<usize> = (0..workspaces.len()).collect();
 
-        // Detect current active workspace (from current directory)
-        let active_workspace = std::env::current_dir().ok().and_then(|dir| {
-            // Check if current directory has a building.yaml
-            let yaml_path = dir.join("building.yaml");
-            if yaml_path.exists() {
-                Some(yaml_path)
-            } else {
-                None
-            }
-        });
+        // Detect current active workspace (from current directory, else the
+        // registry's active building)
+        let active_workspace = std::env::current_dir()
+            .ok()
+            .and_then(|dir| {
+                // Check if current directory has a building.yaml
+                let yaml_path = dir.join("building.yaml");
+                if yaml_path.exists() {
+                    Some(yaml_path)
+                } else {
+                    None
+                }
+            })
+            .or_else(|| {
+                crate::persistence::workspace::WorkspaceRegistry::load()
+                    .ok()?
+                    .active()
+                    .map(|h| h.root.join(crate::persistence::BUILDING_YAML))
+            });
 
         let mut list_state = ListState::default();
         if !workspaces.is_empty() {
@@ -6,6 +6,12 @@ use std::collections::HashMap;
 
 /// Progress tracking utilities
 pub mod progress {
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
     /// Simple progress tracker
     pub struct ProgressTracker {
         current: usize,
@@ -66,6 +72,65 @@ pub mod progress {
             eprintln!("{}: ERROR - {}", self.description, error_message);
         }
     }
+
+    /// Receives `(fraction, stage)` progress updates, `fraction` in `0.0..=1.0`.
+    pub type ProgressListener = Arc<dyn Fn(f64, &str) + Send + Sync>;
+
+    thread_local! {
+        static LISTENER: RefCell<Option<ProgressListener>> = const { RefCell::new(None) };
+    }
+
+    /// Routes [`report`] calls on this thread to a listener until dropped;
+    /// the previous listener (if any) is restored afterwards.
+    pub struct ProgressReporter {
+        previous: Option<ProgressListener>,
+    }
+
+    impl ProgressReporter {
+        pub fn install(listener: ProgressListener) -> Self {
+            let previous = LISTENER.with(|l| l.borrow_mut().replace(listener));
+            Self { previous }
+        }
+    }
+
+    impl Drop for ProgressReporter {
+        fn drop(&mut self) {
+            let previous = self.previous.take();
+            LISTENER.with(|l| *l.borrow_mut() = previous);
+        }
+    }
+
+    /// Report progress to the listener installed on this thread, if any.
+    pub fn report(fraction: f64, stage: &str) {
+        let listener = LISTENER.with(|l| l.borrow().clone());
+        if let Some(listener) = listener {
+            listener(fraction.clamp(0.0, 1.0), stage);
+        }
+    }
+
+    /// Future that installs a listener around every poll of the inner future,
+    /// so reports reach it whichever worker thread runs the poll.
+    pub struct Reported<F> {
+        listener: ProgressListener,
+        inner: Pin<Box<F>>,
+    }
+
+    impl<F: Future> Future for Reported<F> {
+        type Output = F::Output;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+            let _reporter = ProgressReporter::install(self.listener.clone());
+            self.inner.as_mut().poll(cx)
+        }
+    }
+
+    /// Run `future` with `listener` receiving its progress reports.
+    pub fn reported<F: Future>(listener: ProgressListener, future: F) -> Reported<F> {
+        Reported {
+            listener,
+            inner: Box::pin(future),
+        }
+    }
 }
 
 /// Path safety utilities
@@ -365,19 +430,51 @@ pub mod sorted_map {
     use std::collections::{BTreeMap, HashMap};
 
     /// Serialize a `HashMap` by collecting its keys into a sorted `BTreeMap` first.
-    pub fn serialize<S>(map: &HashMap<String, String>, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<V, S>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
     where
+        V: Serialize,
         S: Serializer,
     {
-        let sorted: BTreeMap<&str, &str> = map.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        let sorted: BTreeMap<&str, &V> = map.iter().map(|(k, v)| (k.as_str(), v)).collect();
         sorted.serialize(serializer)
     }
 
     /// Deserialize a `HashMap` using the default `HashMap` deserializer.
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+    pub fn deserialize<'de, V, D>(deserializer: D) -> Result<HashMap<String, V>, D::Error>
     where
+        V: Deserialize<'de>,
         D: Deserializer<'de>,
     {
         HashMap::deserialize(deserializer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::progress::{report, ProgressReporter};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_progress_reporter_nests_and_restores() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let (outer, inner) = (seen.clone(), seen.clone());
+        report(0.1, "unheard");
+        {
+            let _outer = ProgressReporter::install(Arc::new(move |p, s: &str| {
+                outer.lock().unwrap().push(format!("outer {} {}", p, s))
+            }));
+            {
+                let _inner = ProgressReporter::install(Arc::new(move |p, s: &str| {
+                    inner.lock().unwrap().push(format!("inner {} {}", p, s))
+                }));
+                report(1.5, "parsing");
+            }
+            report(0.5, "writing");
+        }
+        report(0.9, "unheard");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec!["inner 1 parsing".to_string(), "outer 0.5 writing".to_string()]
+        );
+    }
+}
@@ -3,13 +3,14 @@
 use crate::core::Building;
 use crate::ifc::mapping::COORD_BUILDING_LOCAL;
 use super::rules::{ValidationResult, ValidationSeverity};
+use serde::Serialize;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Global flag controlling whether address validation checks reserved system prefixes strictly (as errors) or leniently (as warnings).
 pub static STRICT_ADDRESSES: AtomicBool = AtomicBool::new(false);
 
 /// Aggregated validation outcome for a building after ingest.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct BuildingValidationReport {
     pub results: Vec<ValidationResult>,
 }
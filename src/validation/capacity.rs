@@ -0,0 +1,432 @@
+//! Equipment count and capacity constraints.
+//!
+//! Engineering standards are usually phrased as counts ("max 12 VAV boxes per
+//! AHU") or densities ("one smoke detector per 90 m²"). Teams declare them in
+//! `.arx/constraints.yaml`; `arx validate` evaluates them against the Building
+//! model and reports violations per floor / zone.
+//!
+//! ```yaml
+//! constraints:
+//!   - id: vav-per-ahu
+//!     description: Max 12 VAV boxes per AHU
+//!     match: { name: "VAV*" }
+//!     kind: max_per_parent
+//!     parent: { name: "AHU*" }
+//!     link_property: served_by
+//!     max: 12
+//!   - id: smoke-density
+//!     match: { equipment_type: Safety, name: "*smoke*" }
+//!     kind: area_density
+//!     area_per_unit_m2: 90
+//!     scope: room
+//! ```
+
+use super::rules::{ValidationResult, ValidationSeverity};
+use crate::core::{Building, Equipment, Room};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Repo-relative location of the declared constraint set.
+pub const CONSTRAINTS_FILE: &str = ".arx/constraints.yaml";
+
+/// Rule id prefix used for every capacity violation (`capacity.<constraint id>`).
+pub const CAPACITY_RULE_PREFIX: &str = "capacity.";
+
+/// Declared constraint set (`.arx/constraints.yaml`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConstraintSet {
+    #[serde(default)]
+    pub constraints: Vec<CapacityConstraint>,
+}
+
+/// Selects equipment by type, case-insensitive name glob, and / or the
+/// template it was created from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EquipmentSelector {
+    /// Equipment type as displayed (`HVAC`, `Safety`, custom `Other` names)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_type: Option<String>,
+    /// Name glob, e.g. `VAV*`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Template name recorded on instantiation (`template` property)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl EquipmentSelector {
+    pub fn matches(&self, eq: &Equipment) -> bool {
+        if let Some(ref t) = self.equipment_type {
+            if !eq.equipment_type.to_string().eq_ignore_ascii_case(t) {
+                return false;
+            }
+        }
+        if let Some(ref pattern) = self.name {
+            let options = glob::MatchOptions {
+                case_sensitive: false,
+                ..Default::default()
+            };
+            match glob::Pattern::new(pattern) {
+                Ok(p) if p.matches_with(&eq.name, options) => {}
+                _ => return false,
+            }
+        }
+        if let Some(ref template) = self.template {
+            let recorded = eq.properties.get(crate::core::templates::TEMPLATE_PROPERTY);
+            if !recorded.is_some_and(|t| t.eq_ignore_ascii_case(template)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Spatial scope a count or density is evaluated over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintScope {
+    #[default]
+    Room,
+    Floor,
+}
+
+/// What a constraint checks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConstraintKind {
+    /// At most `max` matching equipment per scope.
+    MaxCount {
+        max: usize,
+        #[serde(default)]
+        scope: ConstraintScope,
+    },
+    /// At least one matching item per `area_per_unit_m2` of floor area in scope.
+    AreaDensity {
+        area_per_unit_m2: f64,
+        #[serde(default)]
+        scope: ConstraintScope,
+    },
+    /// At most `max` matching items linked to each parent via `link_property`
+    /// (value = parent id or name).
+    MaxPerParent {
+        parent: EquipmentSelector,
+        link_property: String,
+        max: usize,
+    },
+}
+
+/// One declared engineering constraint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityConstraint {
+    pub id: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_severity")]
+    pub severity: ValidationSeverity,
+    #[serde(rename = "match", default)]
+    pub selector: EquipmentSelector,
+    #[serde(flatten)]
+    pub kind: ConstraintKind,
+}
+
+fn default_severity() -> ValidationSeverity {
+    ValidationSeverity::Warning
+}
+
+impl ConstraintSet {
+    /// Load `{base}/.arx/constraints.yaml`; an absent file yields an empty set.
+    pub fn load_at(base: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = base.join(CONSTRAINTS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e).into())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+}
+
+/// Room footprint in m² (width × depth of the spatial envelope).
+pub fn room_area_m2(room: &Room) -> f64 {
+    let d = &room.spatial_properties.dimensions;
+    (d.width * d.depth).max(0.0)
+}
+
+/// Evaluate every declared constraint; each violation carries the zone in `field`.
+pub fn evaluate_constraints(building: &Building, set: &ConstraintSet) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    for constraint in &set.constraints {
+        match &constraint.kind {
+            ConstraintKind::MaxCount { max, scope } => {
+                for zone in zones(building, *scope) {
+                    let count = zone.count(&constraint.selector);
+                    if count > *max {
+                        results.push(violation(
+                            constraint,
+                            &zone.label,
+                            format!("{} matching item(s) in {} (max {})", count, zone.label, max),
+                        ));
+                    }
+                }
+            }
+            ConstraintKind::AreaDensity {
+                area_per_unit_m2,
+                scope,
+            } => {
+                if *area_per_unit_m2 <= 0.0 {
+                    results.push(violation(
+                        constraint,
+                        CONSTRAINTS_FILE,
+                        "area_per_unit_m2 must be positive".to_string(),
+                    ));
+                    continue;
+                }
+                for zone in zones(building, *scope) {
+                    if zone.area_m2 <= 0.0 {
+                        continue;
+                    }
+                    let required = (zone.area_m2 / area_per_unit_m2).ceil() as usize;
+                    let count = zone.count(&constraint.selector);
+                    if count < required {
+                        results.push(violation(
+                            constraint,
+                            &zone.label,
+                            format!(
+                                "{} matching item(s) for {:.1} m² in {} (need {}, one per {} m²)",
+                                count, zone.area_m2, zone.label, required, area_per_unit_m2
+                            ),
+                        ));
+                    }
+                }
+            }
+            ConstraintKind::MaxPerParent {
+                parent,
+                link_property,
+                max,
+            } => {
+                for (floor_label, eq) in located_equipment(building) {
+                    if !parent.matches(eq) {
+                        continue;
+                    }
+                    let children = building
+                        .get_all_equipment()
+                        .into_iter()
+                        .filter(|c| constraint.selector.matches(c))
+                        .filter(|c| {
+                            c.properties
+                                .get(link_property)
+                                .map(|v| v == &eq.id || v.eq_ignore_ascii_case(&eq.name))
+                                .unwrap_or(false)
+                        })
+                        .count();
+                    if children > *max {
+                        let zone = format!("{}/{}", floor_label, eq.name);
+                        results.push(violation(
+                            constraint,
+                            &zone,
+                            format!("'{}' serves {} item(s) (max {})", eq.name, children, max),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    results
+}
+
+/// Group capacity violations by floor for human-readable reports.
+pub fn capacity_summary_lines(results: &[ValidationResult]) -> Vec<String> {
+    let mut by_floor: BTreeMap<String, Vec<&ValidationResult>> = BTreeMap::new();
+    for r in results
+        .iter()
+        .filter(|r| r.rule_id.starts_with(CAPACITY_RULE_PREFIX))
+    {
+        let zone = r.field.as_deref().unwrap_or("building");
+        let floor = zone.split('/').next().unwrap_or(zone).to_string();
+        by_floor.entry(floor).or_default().push(r);
+    }
+    if by_floor.is_empty() {
+        return vec!["Capacity constraints: ok".to_string()];
+    }
+    let total: usize = by_floor.values().map(|v| v.len()).sum();
+    let mut lines = vec![format!("Capacity constraints: {} violation(s)", total)];
+    for (floor, items) in by_floor {
+        lines.push(format!("  {} ({} violation(s))", floor, items.len()));
+        for r in items {
+            lines.push(format!("    - [{}] {}", r.rule_id, r.message));
+        }
+    }
+    lines
+}
+
+fn violation(constraint: &CapacityConstraint, zone: &str, detail: String) -> ValidationResult {
+    let message = match constraint.description {
+        Some(ref d) => format!("{}: {}", d, detail),
+        None => detail,
+    };
+    ValidationResult {
+        rule_id: format!("{}{}", CAPACITY_RULE_PREFIX, constraint.id),
+        message,
+        severity: constraint.severity,
+        field: Some(zone.to_string()),
+    }
+}
+
+/// Evaluation zone: a room or a whole floor, with the equipment it holds.
+struct Zone<'a> {
+    label: String,
+    area_m2: f64,
+    equipment: Vec<&'a Equipment>,
+}
+
+impl Zone<'_> {
+    fn count(&self, selector: &EquipmentSelector) -> usize {
+        self.equipment.iter().filter(|e| selector.matches(e)).count()
+    }
+}
+
+fn zones(building: &Building, scope: ConstraintScope) -> Vec<Zone<'_>> {
+    let mut out = Vec::new();
+    for floor in &building.floors {
+        match scope {
+            ConstraintScope::Room => {
+                for wing in &floor.wings {
+                    for room in &wing.rooms {
+                        out.push(Zone {
+                            label: format!("{}/{}/{}", floor.name, wing.name, room.name),
+                            area_m2: room_area_m2(room),
+                            equipment: room.equipment.iter().collect(),
+                        });
+                    }
+                }
+            }
+            ConstraintScope::Floor => {
+                let mut equipment: Vec<&Equipment> = floor.equipment.iter().collect();
+                let mut area_m2 = 0.0;
+                for wing in &floor.wings {
+                    equipment.extend(wing.equipment.iter());
+                    for room in &wing.rooms {
+                        area_m2 += room_area_m2(room);
+                        equipment.extend(room.equipment.iter());
+                    }
+                }
+                out.push(Zone {
+                    label: floor.name.clone(),
+                    area_m2,
+                    equipment,
+                });
+            }
+        }
+    }
+    out
+}
+
+fn located_equipment(building: &Building) -> Vec<(&str, &Equipment)> {
+    let mut out = Vec::new();
+    for floor in &building.floors {
+        let label = floor.name.as_str();
+        out.extend(floor.equipment.iter().map(|e| (label, e)));
+        for wing in &floor.wings {
+            out.extend(wing.equipment.iter().map(|e| (label, e)));
+            for room in &wing.rooms {
+                out.extend(room.equipment.iter().map(|e| (label, e)));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Dimensions, EquipmentType, Floor, Position, RoomType, SpatialProperties, Wing};
+
+    fn building_with_room(area_side: f64, equipment: Vec<Equipment>) -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("F1".into(), 0);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("Open Office".into(), RoomType::Office);
+        room.spatial_properties = SpatialProperties::new(
+            Position {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                coordinate_system: "building_local".into(),
+            },
+            Dimensions {
+                width: area_side,
+                height: 3.0,
+                depth: area_side,
+            },
+            "building_local".into(),
+        );
+        for eq in equipment {
+            room.add_equipment(eq);
+        }
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    fn parse(yaml: &str) -> ConstraintSet {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn area_density_flags_under_provisioned_room() {
+        // 20 × 20 = 400 m² → needs 5 detectors at one per 90 m²
+        let detectors = (0..3)
+            .map(|i| Equipment::new(format!("Smoke-{}", i), String::new(), EquipmentType::Safety))
+            .collect();
+        let b = building_with_room(20.0, detectors);
+        let set = parse(
+            "constraints:\n  - id: smoke\n    match: { equipment_type: Safety, name: \"smoke*\" }\n    kind: area_density\n    area_per_unit_m2: 90\n",
+        );
+        let results = evaluate_constraints(&b, &set);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].rule_id, "capacity.smoke");
+        assert_eq!(results[0].severity, ValidationSeverity::Warning);
+        assert_eq!(results[0].field.as_deref(), Some("F1/A/Open Office"));
+        assert!(results[0].message.contains("need 5"));
+    }
+
+    #[test]
+    fn max_per_parent_counts_linked_children() {
+        let ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        let mut items = vec![ahu];
+        for i in 0..3 {
+            let mut vav = Equipment::new(format!("VAV-{}", i), String::new(), EquipmentType::HVAC);
+            vav.add_property("served_by".into(), "AHU-1".into());
+            items.push(vav);
+        }
+        let b = building_with_room(10.0, items);
+        let set = parse(
+            "constraints:\n  - id: vav-per-ahu\n    severity: Error\n    match: { name: \"VAV*\" }\n    kind: max_per_parent\n    parent: { name: \"AHU*\" }\n    link_property: served_by\n    max: 2\n",
+        );
+        let results = evaluate_constraints(&b, &set);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].severity, ValidationSeverity::Error);
+        assert_eq!(results[0].field.as_deref(), Some("F1/AHU-1"));
+
+        let lines = capacity_summary_lines(&results);
+        assert_eq!(lines[0], "Capacity constraints: 1 violation(s)");
+        assert!(lines[1].starts_with("  F1"));
+    }
+
+    #[test]
+    fn max_count_per_floor_within_limit_is_clean() {
+        let items = vec![Equipment::new("Panel".into(), String::new(), EquipmentType::Electrical)];
+        let b = building_with_room(10.0, items);
+        let set = parse(
+            "constraints:\n  - id: panels\n    match: { equipment_type: electrical }\n    kind: max_count\n    max: 1\n    scope: floor\n",
+        );
+        assert!(evaluate_constraints(&b, &set).is_empty());
+        assert_eq!(capacity_summary_lines(&[]), vec!["Capacity constraints: ok"]);
+    }
+}
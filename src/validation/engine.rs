@@ -0,0 +1,468 @@
+//! Pluggable building rules, configured per repository.
+//!
+//! `validate_building` guards every write with structural invariants; the
+//! rules here are the engineering checks a team tunes for its own data.
+//! Each rule implements [`BuildingRule`], and `.arx/validation.yaml` turns
+//! rules off, overrides their severity, and declares property requirements:
+//!
+//! ```yaml
+//! rules:
+//!   room.area.positive: { severity: Error }
+//!   equipment.position.in_floor: { enabled: false }
+//! required_properties:
+//!   - id: hvac-nameplate
+//!     match: { equipment_type: HVAC }
+//!     properties: [manufacturer, model]
+//!     severity: Error
+//! ```
+
+use super::building::BuildingValidationReport;
+use super::capacity::{room_area_m2, EquipmentSelector};
+use super::rules::{ValidationResult, ValidationSeverity};
+use crate::core::{Building, Equipment, Floor};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Repo-relative location of the rule configuration.
+pub const VALIDATION_RULES_FILE: &str = ".arx/validation.yaml";
+
+/// Rule id prefix for declared property requirements (`properties.<id>`).
+pub const REQUIRED_PROPERTIES_PREFIX: &str = "properties.";
+
+/// Per-rule switch and severity override.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<ValidationSeverity>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Properties every matching piece of equipment must carry (non-empty).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequiredProperties {
+    pub id: String,
+    #[serde(rename = "match", default)]
+    pub selector: EquipmentSelector,
+    pub properties: Vec<String>,
+    #[serde(default = "default_required_severity")]
+    pub severity: ValidationSeverity,
+}
+
+fn default_required_severity() -> ValidationSeverity {
+    ValidationSeverity::Warning
+}
+
+/// Declared rule configuration (`.arx/validation.yaml`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleConfig {
+    #[serde(default)]
+    pub rules: BTreeMap<String, RuleSettings>,
+    #[serde(default)]
+    pub required_properties: Vec<RequiredProperties>,
+}
+
+impl RuleConfig {
+    /// Load `{base}/.arx/validation.yaml`; an absent file yields the defaults.
+    pub fn load_at(base: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = base.join(VALIDATION_RULES_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e).into())
+    }
+}
+
+/// A finding before the configured severity is applied.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub message: String,
+    pub field: Option<String>,
+    /// Reports under this id instead of the rule's own
+    pub rule_id: Option<String>,
+    /// Takes precedence over the rule's configured severity
+    pub severity: Option<ValidationSeverity>,
+}
+
+impl Finding {
+    pub fn new(message: String, field: impl Into<String>) -> Self {
+        Self {
+            message,
+            field: Some(field.into()),
+            rule_id: None,
+            severity: None,
+        }
+    }
+}
+
+/// One check over the whole building.
+pub trait BuildingRule: Send + Sync {
+    /// Stable id, used in reports and in `.arx/validation.yaml`.
+    fn id(&self) -> &str;
+    fn description(&self) -> &str;
+    fn default_severity(&self) -> ValidationSeverity;
+    fn check(&self, building: &Building, config: &RuleConfig) -> Vec<Finding>;
+}
+
+/// Ordered set of rules run against a building.
+pub struct RuleEngine {
+    rules: Vec<Box<dyn BuildingRule>>,
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// The built-in rules.
+    pub fn with_builtin() -> Self {
+        let mut engine = Self::new();
+        engine.register(Box::new(RoomAreaPositive));
+        engine.register(Box::new(EquipmentRoomExists));
+        engine.register(Box::new(AddressesUnique));
+        engine.register(Box::new(PositionInFloor));
+        engine.register(Box::new(EquipmentRequiredProperties));
+        engine
+    }
+
+    pub fn register(&mut self, rule: Box<dyn BuildingRule>) {
+        self.rules.push(rule);
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn BuildingRule> {
+        self.rules.iter().map(|r| r.as_ref())
+    }
+
+    /// Run every enabled rule, applying configured severities.
+    pub fn run(&self, building: &Building, config: &RuleConfig) -> BuildingValidationReport {
+        let mut report = BuildingValidationReport::default();
+        for rule in &self.rules {
+            let settings = config.rules.get(rule.id());
+            if settings.is_some_and(|s| !s.enabled) {
+                continue;
+            }
+            let severity = settings
+                .and_then(|s| s.severity)
+                .unwrap_or_else(|| rule.default_severity());
+            for finding in rule.check(building, config) {
+                report.results.push(ValidationResult {
+                    rule_id: finding.rule_id.unwrap_or_else(|| rule.id().to_string()),
+                    message: finding.message,
+                    severity: finding.severity.unwrap_or(severity),
+                    field: finding.field,
+                });
+            }
+        }
+        report
+    }
+}
+
+impl Default for RuleEngine {
+    fn default() -> Self {
+        Self::with_builtin()
+    }
+}
+
+/// Rooms need a footprint (width × depth > 0).
+struct RoomAreaPositive;
+
+impl BuildingRule for RoomAreaPositive {
+    fn id(&self) -> &str {
+        "room.area.positive"
+    }
+
+    fn description(&self) -> &str {
+        "Room area (width × depth) is greater than zero"
+    }
+
+    fn default_severity(&self) -> ValidationSeverity {
+        ValidationSeverity::Warning
+    }
+
+    fn check(&self, building: &Building, _config: &RuleConfig) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for floor in &building.floors {
+            for wing in &floor.wings {
+                for room in &wing.rooms {
+                    if room_area_m2(room) <= 0.0 {
+                        findings.push(Finding::new(
+                            format!("Room '{}' has no floor area", room.name),
+                            format!("{}/{}/{}", floor.name, wing.name, room.name),
+                        ));
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// `room_id` on equipment names a room (by id or name) that exists.
+struct EquipmentRoomExists;
+
+impl BuildingRule for EquipmentRoomExists {
+    fn id(&self) -> &str {
+        "equipment.room.exists"
+    }
+
+    fn description(&self) -> &str {
+        "Equipment room references point at an existing room"
+    }
+
+    fn default_severity(&self) -> ValidationSeverity {
+        ValidationSeverity::Error
+    }
+
+    fn check(&self, building: &Building, _config: &RuleConfig) -> Vec<Finding> {
+        let mut rooms = HashSet::new();
+        for floor in &building.floors {
+            for wing in &floor.wings {
+                for room in &wing.rooms {
+                    rooms.insert(room.id.clone());
+                    rooms.insert(room.name.to_lowercase());
+                }
+            }
+        }
+        located_equipment(building)
+            .into_iter()
+            .filter_map(|(floor, eq)| {
+                let room = eq.room_id.as_deref()?.trim();
+                if room.is_empty() || rooms.contains(room) || rooms.contains(&room.to_lowercase()) {
+                    return None;
+                }
+                Some(Finding::new(
+                    format!("Equipment '{}' references unknown room '{}'", eq.name, room),
+                    format!("{}/{}", floor.name, eq.name),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// No two entities share an address path.
+struct AddressesUnique;
+
+impl BuildingRule for AddressesUnique {
+    fn id(&self) -> &str {
+        "address.unique"
+    }
+
+    fn description(&self) -> &str {
+        "Every address path is used by one entity"
+    }
+
+    fn default_severity(&self) -> ValidationSeverity {
+        ValidationSeverity::Error
+    }
+
+    fn check(&self, building: &Building, _config: &RuleConfig) -> Vec<Finding> {
+        let mut owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut add = |address: &Option<crate::core::domain::ArxAddress>, owner: String| {
+            if let Some(addr) = address {
+                owners.entry(addr.path.clone()).or_default().push(owner);
+            }
+        };
+        add(&building.address, format!("building '{}'", building.name));
+        for floor in &building.floors {
+            add(&floor.address, format!("floor '{}'", floor.name));
+            for wing in &floor.wings {
+                add(&wing.address, format!("wing '{}'", wing.name));
+                for room in &wing.rooms {
+                    add(&room.address, format!("room '{}'", room.name));
+                }
+            }
+        }
+        for (_, eq) in located_equipment(building) {
+            add(&eq.address, format!("equipment '{}'", eq.name));
+        }
+        owners
+            .into_iter()
+            .filter(|(_, owners)| owners.len() > 1)
+            .map(|(path, owners)| {
+                Finding::new(
+                    format!("Address {} is shared by {}", path, owners.join(", ")),
+                    path,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Equipment sits within its floor's bounding box (plan view), when the floor has one.
+struct PositionInFloor;
+
+impl BuildingRule for PositionInFloor {
+    fn id(&self) -> &str {
+        "equipment.position.in_floor"
+    }
+
+    fn description(&self) -> &str {
+        "Equipment positions fall inside their floor's bounds"
+    }
+
+    fn default_severity(&self) -> ValidationSeverity {
+        ValidationSeverity::Warning
+    }
+
+    fn check(&self, building: &Building, _config: &RuleConfig) -> Vec<Finding> {
+        located_equipment(building)
+            .into_iter()
+            .filter_map(|(floor, eq)| {
+                let bounds = floor.bounding_box.as_ref()?;
+                let (x, y) = (eq.position.x, eq.position.y);
+                let inside = x >= bounds.min.x
+                    && x <= bounds.max.x
+                    && y >= bounds.min.y
+                    && y <= bounds.max.y;
+                if inside {
+                    return None;
+                }
+                Some(Finding::new(
+                    format!(
+                        "Equipment '{}' at ({:.2}, {:.2}) is outside floor '{}'",
+                        eq.name, x, y, floor.name
+                    ),
+                    format!("{}/{}", floor.name, eq.name),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Declared `required_properties`; each entry reports under `properties.<id>`
+/// with its own severity, and this rule switches them on or off as a group.
+struct EquipmentRequiredProperties;
+
+impl BuildingRule for EquipmentRequiredProperties {
+    fn id(&self) -> &str {
+        "equipment.properties.required"
+    }
+
+    fn description(&self) -> &str {
+        "Equipment carries the properties required for its type"
+    }
+
+    fn default_severity(&self) -> ValidationSeverity {
+        ValidationSeverity::Warning
+    }
+
+    fn check(&self, building: &Building, config: &RuleConfig) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for required in &config.required_properties {
+            for (floor, eq) in located_equipment(building) {
+                if !required.selector.matches(eq) {
+                    continue;
+                }
+                let missing: Vec<&str> = required
+                    .properties
+                    .iter()
+                    .filter(|p| !has_property(&eq.properties, p))
+                    .map(String::as_str)
+                    .collect();
+                if !missing.is_empty() {
+                    findings.push(Finding {
+                        rule_id: Some(format!("{}{}", REQUIRED_PROPERTIES_PREFIX, required.id)),
+                        severity: Some(required.severity),
+                        ..Finding::new(
+                            format!("Equipment '{}' is missing {}", eq.name, missing.join(", ")),
+                            format!("{}/{}", floor.name, eq.name),
+                        )
+                    });
+                }
+            }
+        }
+        findings
+    }
+}
+
+fn has_property(properties: &HashMap<String, String>, name: &str) -> bool {
+    properties
+        .iter()
+        .any(|(k, v)| k.eq_ignore_ascii_case(name) && !v.trim().is_empty())
+}
+
+/// Run the built-in rules with the configured overrides.
+pub fn evaluate_rules(building: &Building, config: &RuleConfig) -> BuildingValidationReport {
+    RuleEngine::with_builtin().run(building, config)
+}
+
+fn located_equipment(building: &Building) -> Vec<(&Floor, &Equipment)> {
+    let mut out = Vec::new();
+    for floor in &building.floors {
+        out.extend(floor.equipment.iter().map(|e| (floor, e)));
+        for wing in &floor.wings {
+            out.extend(wing.equipment.iter().map(|e| (floor, e)));
+            for room in &wing.rooms {
+                out.extend(room.equipment.iter().map(|e| (floor, e)));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{EquipmentType, Room, RoomType, Wing};
+
+    fn building() -> Building {
+        let mut b = Building::new("HQ".into(), "/hq".into());
+        let mut floor = Floor::new("F1".into(), 0);
+        let mut wing = Wing::new("A".into());
+        let mut room = Room::new("Lab".into(), RoomType::Office);
+        room.spatial_properties.dimensions.width = 4.0;
+        room.spatial_properties.dimensions.depth = 5.0;
+        let mut ahu = Equipment::new("AHU-1".into(), String::new(), EquipmentType::HVAC);
+        ahu.room_id = Some("Boiler Room".into());
+        room.add_equipment(ahu);
+        wing.add_room(room);
+        floor.add_wing(wing);
+        b.add_floor(floor);
+        b
+    }
+
+    #[test]
+    fn builtin_rules_report_dangling_rooms_and_missing_properties() {
+        let config: RuleConfig = serde_yaml::from_str(
+            "required_properties:\n  - id: nameplate\n    match: { equipment_type: HVAC }\n    properties: [manufacturer]\n    severity: Error\n",
+        )
+        .unwrap();
+        let report = evaluate_rules(&building(), &config);
+        let ids: Vec<(&str, ValidationSeverity)> = report
+            .results
+            .iter()
+            .map(|r| (r.rule_id.as_str(), r.severity))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("equipment.room.exists", ValidationSeverity::Error),
+                ("properties.nameplate", ValidationSeverity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn config_disables_and_overrides_rules() {
+        let config: RuleConfig = serde_yaml::from_str(
+            "rules:\n  equipment.room.exists: { enabled: false }\n  room.area.positive: { severity: Error }\n",
+        )
+        .unwrap();
+        let mut b = building();
+        b.floors[0].wings[0].rooms[0]
+            .spatial_properties
+            .dimensions
+            .width = 0.0;
+        let report = evaluate_rules(&b, &config);
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].rule_id, "room.area.positive");
+        assert!(report.has_errors());
+    }
+}
@@ -1,7 +1,16 @@
 //! Validation rules and constraints engine
 
 pub mod building;
+pub mod capacity;
+pub mod engine;
 pub mod rules;
 
 pub use building::{validate_building, BuildingValidationReport, STRICT_ADDRESSES};
+pub use capacity::{
+    capacity_summary_lines, evaluate_constraints, CapacityConstraint, ConstraintKind,
+    ConstraintScope, ConstraintSet, EquipmentSelector, CONSTRAINTS_FILE,
+};
+pub use engine::{
+    evaluate_rules, BuildingRule, Finding, RuleConfig, RuleEngine, VALIDATION_RULES_FILE,
+};
 pub use rules::{ValidationResult, ValidationRule, ValidationRuleType, ValidationSeverity};
@@ -118,10 +118,10 @@ pub fn ClaimReview() -> impl IntoView {
             // B. Load Pending Contributions
             match crate::web::ws_client::send_rpc(
                 "claim.list_pending",
-                serde_json::json!({}),
+                serde_json::json!({ "page_size": 500 }),
             ).await {
                 Ok(val) => {
-                    if let Some(arr) = val.as_array() {
+                    if let Some(arr) = val.get("items").and_then(|v| v.as_array()) {
                         let parsed: Vec<PendingContributionDto> = arr.iter().map(|item| {
                             PendingContributionDto {
                                 index: item.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
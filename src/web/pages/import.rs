@@ -32,7 +32,13 @@ pub fn Import() -> impl IntoView {
                     let onload = Closure::wrap(Box::new(move |_event: Event| {
                         let text = file_reader_clone.result().unwrap().as_string().unwrap();
 
-                        match crate::web::wasm_bridge::parse_ifc_data_with_report(&text) {
+                        let lower = file_name_clone.to_ascii_lowercase();
+                        let parsed = if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+                            crate::web::wasm_bridge::building_envelope_from_yaml(&text)
+                        } else {
+                            crate::web::wasm_bridge::parse_ifc_data_with_report(&text)
+                        };
+                        match parsed {
                             Ok(envelope_json) => {
                                 if let Err(e) =
                                     crate::web::wasm_bridge::store_active_building(&envelope_json)
@@ -74,7 +80,10 @@ pub fn Import() -> impl IntoView {
                                 }
                             }
                             Err(e) => {
-                                set_result.set(Some(format!("Error parsing IFC: {:?}", e)));
+                                set_result.set(Some(format!(
+                                    "Error parsing {}: {:?}",
+                                    file_name_clone, e
+                                )));
                             }
                         }
                         set_parsing.set(false);
@@ -91,7 +100,7 @@ pub fn Import() -> impl IntoView {
     view! {
         <div class="page import-page">
             <h1>"Import IFC File"</h1>
-            <p>"Upload an Industry Foundation Classes (IFC) file — uses the same native pipeline as the CLI."</p>
+            <p>"Upload an Industry Foundation Classes (IFC) file, or a project's building.yaml — uses the same native pipeline as the CLI."</p>
 
             <div class="upload-section">
                 <label for="ifc-upload" class="upload-label">
@@ -100,7 +109,7 @@ pub fn Import() -> impl IntoView {
                 <input
                     type="file"
                     id="ifc-upload"
-                    accept=".ifc"
+                    accept=".ifc,.yaml,.yml"
                     on:change=on_file_change
                     class="file-input"
                 />
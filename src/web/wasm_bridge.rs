@@ -3,16 +3,37 @@
 //! Exposes core ArxOS functionality to the Leptos/WASM frontend:
 //!
 //! - [`init_panic_hook`]: Sets up browser-friendly Rust panic messages.
+//! - [`set_acting_user`]: user and role that envelope edits below are checked
+//!   against (same rules as `.arx/roles.yaml` on the CLI and agent).
 //! - [`parse_ifc_data`]: Parse raw IFC text → JSON Building model.
 //! - [`render_building_ascii`]: Render a Building (JSON) → bordered ASCII-art string
 //!   suitable for display in a `<pre>` element or Xterm.js terminal pane.
 //! - [`render_building_ascii_simple`]: As above but without borders/legend.
+//! - [`generate_room_meshes`]: Building (JSON) → room meshes, extruding 2D outlines.
+//! - [`sync_queue_enqueue`] / [`sync_queue_flush`] / [`sync_queue_conflicts`] /
+//!   [`sync_queue_resolve`]: offline edit queue replayed when back online.
+//! - [`floor_summary_json`] / [`render_floor_widget`]: floor status widget data and
+//!   its HTML fragment (same JSON as the agent's `/api/embed/floors/:level`).
+//! - [`render_floor_png`]: Building (JSON) → PNG bytes of one floor plan.
+//! - [`lookup_equipment_by_code`]: scanned equipment label code → Equipment JSON.
+//! - [`list_attachments_json`] / [`add_attachment_record`] /
+//!   [`remove_attachment_record`]: photos and documents on rooms and equipment.
+//! - [`building_envelope_from_yaml`]: project `building.yaml` → envelope JSON;
+//!   [`building_overview_json`] / [`floor_plan_json`]: floors and plan geometry.
+//! - [`ar_scan_to_pending_json`] / [`ar_pending_submit_params`]: AR scan →
+//!   pending equipment records for the agent's `ar.pending.*` review queue.
+//! - [`load_bundle`]: `arx export --format pwa-bundle` file → bundle JSON for
+//!   offline browsing.
+//! - [`search_commands`]: fuzzy command palette search with each command's
+//!   argument schema, for prompting.
 
-use crate::core::BuildingMetadata;
+use crate::core::access::{authorize_role, Role};
+use crate::core::operations::dry_run::diff_buildings;
+use crate::core::{Building, BuildingMetadata};
 use crate::ifc::IFCProcessor;
 use crate::ingest::{
     apply_text_to_sync_json, finalize_ingest, merge_sync_json, BuildingSyncEnvelope, IngestOptions,
-    IngestSource, SyncSource, STORAGE_KEY_ACTIVE_BUILDING, STORAGE_KEY_LEGACY_BUILDING,
+    IngestSource, SyncQueue, SyncSource, STORAGE_KEY_ACTIVE_BUILDING, STORAGE_KEY_LEGACY_BUILDING,
 };
 use wasm_bindgen::prelude::*;
 
@@ -27,6 +48,37 @@ pub fn storage_key_active_building() -> String {
     STORAGE_KEY_ACTIVE_BUILDING.to_string()
 }
 
+thread_local! {
+    static ACTING_USER: std::cell::RefCell<Option<(String, Role)>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Make later edits on behalf of `user` with `role` (viewer, technician,
+/// manager, admin; as the agent reports it). Edits the role does not permit
+/// fail before touching the envelope. An empty `user` stops checking.
+#[wasm_bindgen]
+pub fn set_acting_user(user: &str, role: &str) -> Result<(), JsValue> {
+    let acting = if user.is_empty() {
+        None
+    } else {
+        Some((
+            user.to_string(),
+            role.parse::<Role>().map_err(|e| JsValue::from_str(&e))?,
+        ))
+    };
+    ACTING_USER.with(|a| *a.borrow_mut() = acting);
+    Ok(())
+}
+
+/// Check the edit `before` → `after` against the acting user's role.
+fn authorize_edit(before: &Building, after: &Building) -> Result<(), JsValue> {
+    ACTING_USER.with(|a| match &*a.borrow() {
+        Some((user, role)) => authorize_role(user, *role, &diff_buildings(Some(before), after))
+            .map_err(|e| JsValue::from_str(&e.to_string())),
+        None => Ok(()),
+    })
+}
+
 fn ifc_content_to_envelope(content: &str) -> Result<BuildingSyncEnvelope, JsValue> {
     let processor = IFCProcessor::new();
     let parsed = processor
@@ -91,7 +143,76 @@ pub fn merge_building_sync_json(
 /// Apply a text/AR command script to a sync envelope JSON; returns updated envelope.
 #[wasm_bindgen]
 pub fn apply_text_script_json(envelope_json: &str, script: &str) -> Result<String, JsValue> {
-    apply_text_to_sync_json(envelope_json, script).map_err(|e| JsValue::from_str(&e))
+    let updated =
+        apply_text_to_sync_json(envelope_json, script).map_err(|e| JsValue::from_str(&e))?;
+    let before =
+        BuildingSyncEnvelope::from_json(envelope_json).map_err(|e| JsValue::from_str(&e))?;
+    let after = BuildingSyncEnvelope::from_json(&updated).map_err(|e| JsValue::from_str(&e))?;
+    authorize_edit(&before.building, &after.building)?;
+    Ok(updated)
+}
+
+/// Queue a text/AR script made offline against `envelope_json` (the device's
+/// copy before the edit); returns the updated queue JSON. Empty queue JSON
+/// starts a new queue.
+#[wasm_bindgen]
+pub fn sync_queue_enqueue(
+    queue_json: &str,
+    envelope_json: &str,
+    script: &str,
+) -> Result<String, JsValue> {
+    let mut queue = SyncQueue::from_json(queue_json).map_err(|e| JsValue::from_str(&e))?;
+    let env = BuildingSyncEnvelope::from_json(envelope_json).map_err(|e| JsValue::from_str(&e))?;
+    queue
+        .enqueue(&env.building, script)
+        .map_err(|e| JsValue::from_str(&e))?;
+    queue
+        .to_json()
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Replay the queue onto the freshly pulled shared envelope. Returns
+/// `{queue, envelope, report}` JSON; conflicting edits stay in `queue`.
+#[wasm_bindgen]
+pub fn sync_queue_flush(queue_json: &str, envelope_json: &str) -> Result<String, JsValue> {
+    let mut queue = SyncQueue::from_json(queue_json).map_err(|e| JsValue::from_str(&e))?;
+    let env = BuildingSyncEnvelope::from_json(envelope_json).map_err(|e| JsValue::from_str(&e))?;
+    let mut building = env.building.clone();
+    let report = queue.flush(&mut building);
+    authorize_edit(&env.building, &building)?;
+    let lines = vec![format!(
+        "offline queue: {} applied, {} conflict(s)",
+        report.applied.len(),
+        report.conflicts.len()
+    )];
+    let envelope = BuildingSyncEnvelope::new(building, SyncSource::Wasm, lines);
+    serde_json::to_string(&serde_json::json!({
+        "queue": queue,
+        "envelope": envelope,
+        "report": report,
+    }))
+    .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Queued edits whose last flush hit a conflict, as JSON.
+#[wasm_bindgen]
+pub fn sync_queue_conflicts(queue_json: &str) -> Result<String, JsValue> {
+    let queue = SyncQueue::from_json(queue_json).map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&queue.conflict_report())
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Settle a conflict (`keep_mine` forces it on the next flush, otherwise it is
+/// dropped); returns the updated queue JSON.
+#[wasm_bindgen]
+pub fn sync_queue_resolve(queue_json: &str, edit_id: &str, keep_mine: bool) -> Result<String, JsValue> {
+    let mut queue = SyncQueue::from_json(queue_json).map_err(|e| JsValue::from_str(&e))?;
+    if !queue.resolve(edit_id, keep_mine) {
+        return Err(JsValue::from_str(&format!("No queued edit '{}'", edit_id)));
+    }
+    queue
+        .to_json()
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
 }
 
 /// Extract Building JSON from an envelope (or pass through bare Building JSON).
@@ -102,6 +223,216 @@ pub fn building_json_from_envelope(json: &str) -> Result<String, JsValue> {
         .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
 }
 
+/// Sync envelope JSON for `building.yaml` text (a file the user picked, or the
+/// agent's copy of the project). The result feeds every other binding here.
+#[wasm_bindgen]
+pub fn building_envelope_from_yaml(yaml: &str) -> Result<String, JsValue> {
+    let env = BuildingSyncEnvelope::from_yaml(yaml).map_err(|e| JsValue::from_str(&e))?;
+    env.to_json()
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Building header and floor list (`{id, name, path, floors: [{level, name,
+/// room_count, equipment_count}]}`) for Building or envelope JSON.
+#[wasm_bindgen]
+pub fn building_overview_json(building_json: &str) -> Result<String, JsValue> {
+    let env = BuildingSyncEnvelope::from_json(building_json).map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&crate::core::spatial::plan::building_overview(&env.building))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Plan geometry for floor `level` (`{level, name, rooms: [{id, name, outline,
+/// derived, ..}], equipment: [{id, name, position, status, ..}]}`).
+#[wasm_bindgen]
+pub fn floor_plan_json(building_json: &str, level: i32) -> Result<String, JsValue> {
+    let env = BuildingSyncEnvelope::from_json(building_json).map_err(|e| JsValue::from_str(&e))?;
+    let plan = crate::core::spatial::plan::floor_plan(&env.building, level)
+        .ok_or_else(|| JsValue::from_str(&format!("No floor at level {}", level)))?;
+    serde_json::to_string(&plan)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Room meshes for a Building or envelope JSON, as JSON (`[{room_id, room_name,
+/// room_type, floor_level, derived, mesh: {vertices, indices}}]`). Rooms without 3D
+/// geometry are extruded from their 2D outlines, matching `arx export --format gltf`.
+#[wasm_bindgen]
+pub fn generate_room_meshes(building_json: &str) -> Result<String, JsValue> {
+    let env = BuildingSyncEnvelope::from_json(building_json).map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&crate::core::spatial::room_meshes(&env.building))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Floor status widget summary for `level` from Building or envelope JSON. Open
+/// issues are not part of the building model, so issue counts are zero here.
+#[wasm_bindgen]
+pub fn floor_summary_json(building_json: &str, level: i32) -> Result<String, JsValue> {
+    let env = BuildingSyncEnvelope::from_json(building_json).map_err(|e| JsValue::from_str(&e))?;
+    let summary =
+        crate::export::embed::floor_summary(&env.building, level, &Default::default())
+            .ok_or_else(|| JsValue::from_str(&format!("No floor at level {}", level)))?;
+    serde_json::to_string(&summary)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// HTML fragment for widget summary JSON (from the agent endpoint or
+/// [`floor_summary_json`]).
+#[wasm_bindgen]
+pub fn render_floor_widget(summary_json: &str) -> Result<String, JsValue> {
+    let summary: crate::export::embed::FloorSummary = serde_json::from_str(summary_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid widget JSON: {}", e)))?;
+    Ok(crate::export::embed::render_widget_html(&summary))
+}
+
+/// PNG bytes of the floor plan at `level` for Building or envelope JSON.
+/// `options_json` is a partial `FloorPngOptions` (`{"width":800,"overlay":"status"}`);
+/// pass an empty string for defaults. Same image as `arx export --format png`.
+#[wasm_bindgen]
+pub fn render_floor_png(
+    building_json: &str,
+    level: i32,
+    options_json: &str,
+) -> Result<Vec<u8>, JsValue> {
+    let env = BuildingSyncEnvelope::from_json(building_json).map_err(|e| JsValue::from_str(&e))?;
+    let options: crate::export::png::FloorPngOptions = if options_json.trim().is_empty() {
+        Default::default()
+    } else {
+        serde_json::from_str(options_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid render options: {}", e)))?
+    };
+    crate::export::png::render_floor_png(&env.building, level, &options)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Equipment JSON for a scanned label code (ArxAddress path, legacy path, or
+/// id, as printed by `arx equipment labels`), so the field app can open the
+/// record. Errors when no equipment matches.
+#[wasm_bindgen]
+pub fn lookup_equipment_by_code(building_json: &str, code: &str) -> Result<String, JsValue> {
+    let env = BuildingSyncEnvelope::from_json(building_json).map_err(|e| JsValue::from_str(&e))?;
+    let equipment = crate::export::labels::lookup_equipment_by_code(&env.building, code)
+        .ok_or_else(|| JsValue::from_str(&format!("No equipment for code '{}'", code)))?;
+    serde_json::to_string(equipment)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Pending equipment records (JSON array) for the items in an AR scan (JSON,
+/// see [`crate::ingest::ar_scan::ArScan`]), for review before they reach the
+/// model.
+#[wasm_bindgen]
+pub fn ar_scan_to_pending_json(scan_json: &str) -> Result<String, JsValue> {
+    use crate::ingest::ar_scan::{pending_from_scan, ArScan};
+    let scan: ArScan = serde_json::from_str(scan_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid scan: {}", e)))?;
+    let pending = pending_from_scan(&scan).map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&pending)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// `ar.pending.submit` params for pending records (JSON array from
+/// [`ar_scan_to_pending_json`]), so the app can queue them on the agent.
+#[wasm_bindgen]
+pub fn ar_pending_submit_params(pending_json: &str) -> Result<String, JsValue> {
+    use crate::ingest::ar_scan::PendingEquipment;
+    let pending: Vec<PendingEquipment> = serde_json::from_str(pending_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid pending equipment: {}", e)))?;
+    serde_json::to_string(&serde_json::json!({ "equipment": pending }))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Decode an offline bundle (`arx export --format pwa-bundle`) and return its
+/// JSON: `{generated_at, commit, building, meshes, plans, sensors, history}`.
+/// `building` feeds every binding here that takes Building JSON.
+#[wasm_bindgen]
+pub fn load_bundle(bytes: &[u8]) -> Result<String, JsValue> {
+    let bundle = crate::export::pwa_bundle::PwaBundle::from_bytes(bytes)
+        .map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&bundle)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Catalog commands matching `query`, best first, as JSON (`[{score,
+/// command, about, args: [{name, long, required, takes_value, help,
+/// value_type, choices, default, completion}]}]`). The palette prompts for
+/// each argument from its schema; an empty query lists every command.
+#[wasm_bindgen]
+pub fn search_commands(query: &str, limit: u32) -> Result<String, JsValue> {
+    let matches = crate::cli::catalog::search_catalog(query, limit as usize);
+    serde_json::to_string(&matches)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Apply a `twin.snapshot` diff to the snapshot JSON the app holds; returns
+/// the updated snapshot JSON, or an error when the diff does not fit it.
+#[wasm_bindgen]
+pub fn apply_twin_diff_json(snapshot_json: &str, diff_json: &str) -> Result<String, JsValue> {
+    use crate::core::twin::{TwinDiff, TwinSnapshot};
+    let snapshot: TwinSnapshot = serde_json::from_str(snapshot_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid snapshot: {}", e)))?;
+    let diff: TwinDiff = serde_json::from_str(diff_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid diff: {}", e)))?;
+    let updated = snapshot.apply(&diff).map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&updated)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Attachments (JSON array) of the room or equipment matching `entity` (path,
+/// id, or name) in Building or envelope JSON.
+#[wasm_bindgen]
+pub fn list_attachments_json(building_json: &str, entity: &str) -> Result<String, JsValue> {
+    use crate::persistence::entity_attachments::{attachments_of, resolve_owner};
+    let env = BuildingSyncEnvelope::from_json(building_json).map_err(|e| JsValue::from_str(&e))?;
+    let owner =
+        resolve_owner(&env.building, entity).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    serde_json::to_string(attachments_of(&env.building, &owner))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Record an attachment uploaded by the field app on the room or equipment
+/// matching `entity`; returns the updated envelope JSON. The file itself is
+/// expected at the recorded `path` (see `arx attachment add` for the CLI).
+#[wasm_bindgen]
+pub fn add_attachment_record(
+    envelope_json: &str,
+    entity: &str,
+    file_name: &str,
+    size: f64,
+    caption: &str,
+    uploaded_by: &str,
+) -> Result<String, JsValue> {
+    let mut env =
+        BuildingSyncEnvelope::from_json(envelope_json).map_err(|e| JsValue::from_str(&e))?;
+    let caption = (!caption.trim().is_empty()).then(|| caption.to_string());
+    let before = env.building.clone();
+    crate::persistence::entity_attachments::record_attachment(
+        &mut env.building,
+        entity,
+        file_name,
+        size as u64,
+        caption,
+        uploaded_by,
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    authorize_edit(&before, &env.building)?;
+    env.to_json().map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Drop attachment `file` (file name or path) from the room or equipment
+/// matching `entity`; returns the updated envelope JSON.
+#[wasm_bindgen]
+pub fn remove_attachment_record(
+    envelope_json: &str,
+    entity: &str,
+    file: &str,
+) -> Result<String, JsValue> {
+    let mut env =
+        BuildingSyncEnvelope::from_json(envelope_json).map_err(|e| JsValue::from_str(&e))?;
+    let before = env.building.clone();
+    crate::persistence::entity_attachments::forget_attachment(&mut env.building, entity, file)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    authorize_edit(&before, &env.building)?;
+    env.to_json().map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 /// Persist envelope JSON to localStorage under the canonical key (and legacy key).
 #[wasm_bindgen]
 pub fn store_active_building(envelope_json: &str) -> Result<(), JsValue> {
@@ -22,15 +22,32 @@ impl BuildingYamlSerializer {
     pub fn serialize(data: &BuildingData) -> Result<String, Box<dyn std::error::Error>> {
         let mut sorted_data = BuildingData::from_building(&data.building);
         sorted_data.sort_deterministically();
-        Ok(serde_yaml::to_string(&sorted_data)?)
+        Ok(to_canonical_yaml(&sorted_data)?)
     }
 
     /// Deserialize YAML into `BuildingData` and rehydrate room equipment.
     ///
-    /// After raw deserialization, calls [`BuildingData::rehydrate_room_equipment`] to
-    /// populate each room's `equipment` list from the global equipment index.
+    /// Older documents are migrated to [`BUILDING_YAML_SCHEMA_VERSION`] first
+    /// (see `persistence::schema`); newer ones are read as-is, and saving them
+    /// is refused. After deserialization, calls
+    /// [`BuildingData::rehydrate_room_equipment`] to populate each room's
+    /// `equipment` list from the global equipment index.
     pub fn deserialize(yaml: &str) -> Result<BuildingData, Box<dyn std::error::Error>> {
-        let mut data: BuildingData = serde_yaml::from_str(yaml)?;
+        use crate::persistence::{schema, PersistenceError, BUILDING_YAML};
+
+        let mut data: BuildingData =
+            match schema::from_yaml(&schema::BUILDING, BUILDING_YAML, yaml) {
+                Err(PersistenceError::SchemaTooNew { found, .. }) => {
+                    log::warn!(
+                        "{} uses schema v{} (this arx supports v{}); reading it read-only",
+                        BUILDING_YAML,
+                        found,
+                        BUILDING_YAML_SCHEMA_VERSION
+                    );
+                    serde_yaml::from_str(yaml)?
+                }
+                parsed => parsed?,
+            };
         data.rehydrate_room_equipment();
         Ok(data)
     }
@@ -99,56 +116,57 @@ impl BuildingData {
         }
     }
     /// Sorts all hierarchical collections deterministically to ensure zero-diff Git output.
+    ///
+    /// Names order what people read; ids break ties, so the order never
+    /// depends on insertion or iteration order.
     pub fn sort_deterministically(&mut self) {
         // 1. Sort Floors by level (numerical)
-        self.building.floors.sort_by_key(|f| f.level);
+        self.building.floors.sort_by(|a, b| (a.level, &a.id).cmp(&(b.level, &b.id)));
 
         // Sort Building-level anchors
-        self.building.anchors.sort_by(|a, b| a.name.cmp(&b.name));
+        self.building.anchors.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
         for floor in &mut self.building.floors {
             // 2. Sort Wings by name (alphabetical)
-            floor.wings.sort_by(|a, b| a.name.cmp(&b.name));
+            floor.wings.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
             // 3. Sort Floor-level (common area) equipment
-            floor.equipment.sort_by(|a, b| a.name.cmp(&b.name));
+            floor.equipment.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
             // Sort Floor-level anchors
-            floor.anchors.sort_by(|a, b| a.name.cmp(&b.name));
+            floor.anchors.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
             for wing in &mut floor.wings {
                 // 4. Sort Rooms by name
-                wing.rooms.sort_by(|a, b| a.name.cmp(&b.name));
+                wing.rooms.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
                 // 5. Sort Wing-level equipment
-                wing.equipment.sort_by(|a, b| a.name.cmp(&b.name));
+                wing.equipment.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
                 // Sort Wing-level anchors
-                wing.anchors.sort_by(|a, b| a.name.cmp(&b.name));
+                wing.anchors.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
                 for room in &mut wing.rooms {
                     // 6. Sort Room equipment
-                    room.equipment.sort_by(|a, b| a.name.cmp(&b.name));
+                    room.equipment.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
 
                     // Sort Room anchors
-                    room.anchors.sort_by(|a, b| a.name.cmp(&b.name));
+                    room.anchors.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
                 }
             }
         }
 
-        // 7. Sort Global equipment list by ArxAddress path (or name fallback)
-        self.equipment
-            .sort_by(|a, b| match (&a.address, &b.address) {
-                (Some(addr_a), Some(addr_b)) => addr_a.path.cmp(&addr_b.path),
-                _ => a.name.cmp(&b.name),
-            });
-
-        // 8. Sort Global anchor list by ArxAddress path (or name fallback)
-        self.anchors
-            .sort_by(|a, b| match (&a.address, &b.address) {
-                (Some(addr_a), Some(addr_b)) => addr_a.path.cmp(&addr_b.path),
-                _ => a.name.cmp(&b.name),
-            });
+        // 7. Sort Global equipment list by ArxAddress path (unaddressed first), then name and id
+        self.equipment.sort_by(|a, b| {
+            let key_a = (a.address.as_ref().map(|x| &x.path), &a.name, &a.id);
+            key_a.cmp(&(b.address.as_ref().map(|x| &x.path), &b.name, &b.id))
+        });
+
+        // 8. Sort Global anchor list by ArxAddress path (unaddressed first), then name and id
+        self.anchors.sort_by(|a, b| {
+            let key_a = (a.address.as_ref().map(|x| &x.path), &a.name, &a.id);
+            key_a.cmp(&(b.address.as_ref().map(|x| &x.path), &b.name, &b.id))
+        });
     }
 
     /// Rehydrate room equipment lists after YAML deserialization.
@@ -291,6 +309,42 @@ pub fn to_yaml<T: Serialize>(data: &T) -> Result<String, serde_yaml::Error> {
     serde_yaml::to_string(data)
 }
 
+/// Decimal places floats are rounded to in persisted YAML (nanometres for
+/// coordinates in metres), so arithmetic noise such as `0.30000000000000004`
+/// never reaches a commit.
+pub const CANONICAL_FLOAT_DECIMALS: i32 = 9;
+
+/// Serialize for persistence: fields in declaration order, maps sorted by
+/// key (`utils::sorted_map`), and floats rounded to
+/// [`CANONICAL_FLOAT_DECIMALS`]. Writing an unchanged document yields the
+/// same bytes, so commits only show real changes.
+pub fn to_canonical_yaml<T: Serialize + ?Sized>(data: &T) -> Result<String, serde_yaml::Error> {
+    let mut value = serde_yaml::to_value(data)?;
+    canonicalize_floats(&mut value);
+    serde_yaml::to_string(&value)
+}
+
+fn canonicalize_floats(value: &mut serde_yaml::Value) {
+    use serde_yaml::Value;
+
+    match value {
+        Value::Number(n) if n.is_f64() => {
+            let x = n.as_f64().unwrap_or_default();
+            let scale = 10f64.powi(CANONICAL_FLOAT_DECIMALS);
+            // Past 1e6 the scaled value nears f64's integer precision
+            if x.is_finite() && x.abs() < 1e6 {
+                let rounded = (x * scale).round() / scale;
+                // Avoid `-0.0`
+                *value = Value::from(if rounded == 0.0 { 0.0 } else { rounded });
+            }
+        }
+        Value::Sequence(items) => items.iter_mut().for_each(canonicalize_floats),
+        Value::Mapping(map) => map.iter_mut().for_each(|(_, v)| canonicalize_floats(v)),
+        Value::Tagged(tagged) => canonicalize_floats(&mut tagged.value),
+        _ => {}
+    }
+}
+
 /// Deserialize YAML string to any deserializable type
 pub fn from_yaml<T: for<'a> Deserialize<'a>>(yaml: &str) -> Result<T, serde_yaml::Error> {
     serde_yaml::from_str(yaml)
@@ -505,4 +559,33 @@ equipment: []
         let restored = BuildingYamlSerializer::deserialize_building(&yaml).expect("deserialize");
         assert_eq!(restored.claim_grace_period_days, Some(30));
     }
+
+    #[test]
+    fn test_canonical_yaml_is_stable() {
+        let mut building = Building::new("HQ".to_string(), "/hq".to_string());
+        let mut floor = Floor::new("F1".to_string(), 0);
+        floor.elevation = Some(0.1 + 0.2);
+        for key in ["zeta", "alpha", "mid"] {
+            floor.properties.insert(key.to_string(), "x".to_string());
+        }
+        for id in ["b", "a"] {
+            let mut eq = Equipment::new("VAV".to_string(), String::new(), EquipmentType::HVAC);
+            eq.id = id.to_string();
+            floor.equipment.push(eq);
+        }
+        building.add_floor(floor);
+
+        let yaml = BuildingYamlSerializer::serialize_building(&building).unwrap();
+        assert!(yaml.contains("elevation: 0.3\n"), "{}", yaml);
+        let keys: Vec<usize> = ["alpha:", "mid:", "zeta:"]
+            .iter()
+            .map(|k| yaml.find(k).unwrap())
+            .collect();
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+
+        let reloaded = BuildingYamlSerializer::deserialize_building(&yaml).unwrap();
+        let again = BuildingYamlSerializer::serialize_building(&reloaded).unwrap();
+        assert_eq!(yaml, again);
+        assert_eq!(reloaded.floors[0].equipment[0].id, "a");
+    }
 }
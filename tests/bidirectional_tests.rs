@@ -2,6 +2,7 @@ use arxos::core::{
     Building, Dimensions, Equipment, EquipmentType, Floor, LidarEnrichment, Position, Room,
     RoomType, Wing,
 };
+use arxos::core::vendor::VendorProperty;
 use arxos::export::ifc::IFCExporter;
 use arxos::ifc::mapping::{
     approx_eq, dimensions_approx_eq, merge_building, positions_approx_eq,
@@ -624,6 +625,84 @@ fn test_property_keys_stay_clean_across_double_ifc_pass() -> anyhow::Result<()>
     Ok(())
 }
 
+/// Unmapped vendor Psets survive Model → IFC → Model → YAML → Model with
+/// their set names and value types, and are not flattened into `properties`.
+#[test]
+fn test_vendor_extensions_roundtrip_via_ifc_and_yaml() -> anyhow::Result<()> {
+    let prop = |value: &str, ty: &str| VendorProperty {
+        value: value.to_string(),
+        ifc_type: Some(ty.to_string()),
+    };
+    let mut building = Building::new("Vendor HQ".to_string(), "/vendor".to_string());
+    let mut floor = Floor::new("Plant".to_string(), 0);
+    let mut wing = Wing::new("Core".to_string());
+    let mut room = Room::new("Plant Room".to_string(), RoomType::Mechanical);
+    room.vendor_extensions.insert(
+        "Pset_AcmeSpace".to_string(),
+        [("ZoneCode".to_string(), prop("Z-07", "IFCIDENTIFIER"))].into(),
+    );
+    let mut ahu = Equipment::new("AHU-1".to_string(), "".to_string(), EquipmentType::HVAC);
+    ahu.vendor_extensions.insert(
+        "Pset_AcmeAirHandler".to_string(),
+        [
+            ("FanCurve".to_string(), prop("C-12", "IFCLABEL")),
+            (
+                "DesignAirflow".to_string(),
+                prop("4.2", "IFCVOLUMETRICFLOWRATEMEASURE"),
+            ),
+            ("Serviceable".to_string(), prop("true", "IFCBOOLEAN")),
+        ]
+        .into(),
+    );
+    room.add_equipment(ahu);
+    wing.add_room(room);
+    floor.add_wing(wing);
+    building.add_floor(floor);
+
+    let temp_ifc = NamedTempFile::new()?;
+    IFCExporter::new(building.clone()).export(temp_ifc.path())?;
+    let ifc_text = fs::read_to_string(temp_ifc.path())?;
+    assert!(ifc_text.contains("'Pset_AcmeAirHandler'"));
+    assert!(ifc_text.contains("IFCVOLUMETRICFLOWRATEMEASURE(4.2)"));
+
+    let imported = IFCProcessor::new()
+        .parse_native(temp_ifc.path().to_str().unwrap(), false)?
+        .building;
+    let imp_room = imported
+        .floors
+        .iter()
+        .flat_map(|f| f.wings.iter())
+        .flat_map(|w| w.rooms.iter())
+        .find(|r| r.name == "Plant Room")
+        .expect("Plant Room after IFC import");
+    let imp_ahu = imp_room
+        .equipment
+        .iter()
+        .find(|e| e.name == "AHU-1")
+        .expect("AHU-1 after IFC import");
+    let original = &building.floors[0].wings[0].rooms[0];
+    assert_eq!(imp_room.vendor_extensions, original.vendor_extensions);
+    assert_eq!(
+        imp_ahu.vendor_extensions,
+        original.equipment[0].vendor_extensions
+    );
+    assert!(
+        !imp_ahu.properties.keys().any(|k| k.starts_with("Pset_Acme")),
+        "vendor psets must not be flattened into properties: {:?}",
+        imp_ahu.properties.keys().collect::<Vec<_>>()
+    );
+
+    let yaml = BuildingYamlSerializer::serialize_building(&imported)
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    assert!(yaml.contains("vendor_extensions:"));
+    let reloaded = BuildingYamlSerializer::deserialize_building(&yaml)
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    let reloaded_ahu = reloaded.find_equipment("AHU-1").expect("AHU-1 from YAML");
+    assert_eq!(reloaded_ahu.vendor_extensions, imp_ahu.vendor_extensions);
+
+    Ok(())
+}
+
 /// Phase 4 / L2: room position + extruded dimensions and equipment position
 /// round-trip through IFC within geometry epsilon.
 #[test]
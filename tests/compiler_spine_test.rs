@@ -101,7 +101,9 @@ fn compiler_spine_seed_mutate_query_export() {
 
     // Round-trip address type API
     let addr = ArxAddress::from_path(&path).expect("parse path");
-    assert!(addr.matches_glob("/local/local/local/*/*/*"));
+    assert!(addr.matches_glob("/local/local/local/*/*/*/*"));
+    assert!(addr.matches_glob("/local/local/local/**"));
+    assert!(!addr.matches_glob("/local/local/local/*/*/*"));
 
     env::set_current_dir(original).ok();
 }
@@ -0,0 +1,31 @@
+//! Hardware-in-the-loop ingestion: simulated device fleets against a scratch workspace.
+
+#[cfg(feature = "agent")]
+mod hil_tests {
+    use arxos::devtools::hil::{run, HilConfig, Transport};
+
+    fn run_fleet(transport: Transport) {
+        let dir = tempfile::tempdir().unwrap();
+        let config = HilConfig {
+            devices: 8,
+            readings_per_device: 6,
+            transport,
+            ..HilConfig::default()
+        };
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let report = rt.block_on(run(dir.path(), &config)).unwrap();
+        assert!(report.passed(), "{:#?}", report.failures);
+        assert_eq!(report.sent, 48);
+        assert!(report.rejected > 0);
+    }
+
+    #[test]
+    fn fleet_over_http_lands_in_inbox_and_trace() {
+        run_fleet(Transport::Http);
+    }
+
+    #[test]
+    fn fleet_through_dispatcher_lands_in_inbox_and_trace() {
+        run_fleet(Transport::Dispatcher);
+    }
+}